@@ -14,6 +14,8 @@ use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
 use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::Op;
+use codex_protocol::protocol::Submission;
 use schemars::JsonSchema;
 use schemars::schema_for;
 use serde::Serialize;
@@ -31,6 +33,9 @@ use std::process::Command;
 use ts_rs::TS;
 
 const HEADER: &str = "// GENERATED CODE! DO NOT MODIFY BY HAND!\n\n";
+/// Version of the Codex build the schemas are generated from. Stamped into
+/// exported schemas so clients can tell which protocol revision they target.
+pub const PROTOCOL_VERSION: &str = env!("CARGO_PKG_VERSION");
 const IGNORED_DEFINITIONS: &[&str] = &["Option<()>"];
 
 #[derive(Clone)]
@@ -56,6 +61,32 @@ impl GeneratedSchema {
 }
 
 type JsonSchemaEmitter = fn(&Path) -> Result<GeneratedSchema>;
+
+/// Output formats supported by [`export_schema`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaFormat {
+    JsonSchema,
+    Ts,
+}
+
+/// Exports the complete protocol (submissions/ops, events, and app-server
+/// requests and notifications) to `out_dir`, stamped with
+/// [`PROTOCOL_VERSION`].
+pub fn export_schema(out_dir: &Path, format: SchemaFormat, prettier: Option<&Path>) -> Result<()> {
+    match format {
+        SchemaFormat::JsonSchema => generate_json(out_dir),
+        SchemaFormat::Ts => {
+            generate_ts(out_dir, prettier)?;
+            let version_path = out_dir.join("version.ts");
+            fs::write(
+                &version_path,
+                format!("{HEADER}export const PROTOCOL_VERSION = \"{PROTOCOL_VERSION}\";\n"),
+            )
+            .with_context(|| format!("Failed to write {}", version_path.display()))
+        }
+    }
+}
+
 pub fn generate_types(out_dir: &Path, prettier: Option<&Path>) -> Result<()> {
     generate_ts(out_dir, prettier)?;
     generate_json(out_dir)?;
@@ -100,6 +131,8 @@ pub fn generate_ts_with_options(
     export_server_responses(out_dir)?;
     ServerNotification::export_all_to(out_dir)?;
 
+    Submission::export_all_to(out_dir)?;
+
     if options.generate_indices {
         generate_index_ts(out_dir)?;
         generate_index_ts(&v2_out_dir)?;
@@ -154,6 +187,8 @@ pub fn generate_json(out_dir: &Path) -> Result<()> {
         |d| write_json_schema_with_return::<crate::ClientNotification>(d, "ClientNotification"),
         |d| write_json_schema_with_return::<crate::ServerNotification>(d, "ServerNotification"),
         |d| write_json_schema_with_return::<EventMsg>(d, "EventMsg"),
+        |d| write_json_schema_with_return::<Submission>(d, "Submission"),
+        |d| write_json_schema_with_return::<Op>(d, "Op"),
     ];
 
     let mut schemas: Vec<GeneratedSchema> = Vec::new();
@@ -182,6 +217,7 @@ fn build_schema_bundle(schemas: Vec<GeneratedSchema>) -> Result<Value> {
         "ClientNotification",
         "ClientRequest",
         "EventMsg",
+        "Op",
         "ServerNotification",
         "ServerRequest",
     ];
@@ -260,6 +296,10 @@ fn build_schema_bundle(schemas: Vec<GeneratedSchema>) -> Result<Value> {
         "title".to_string(),
         Value::String("CodexAppServerProtocol".into()),
     );
+    root.insert(
+        "version".to_string(),
+        Value::String(PROTOCOL_VERSION.into()),
+    );
     root.insert("type".to_string(), Value::String("object".into()));
     root.insert("definitions".to_string(), Value::Object(definitions));
 
@@ -943,4 +983,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn exported_json_schema_includes_ops_and_version() -> Result<()> {
+        let output_dir =
+            std::env::temp_dir().join(format!("codex_schema_export_{}", Uuid::now_v7()));
+        fs::create_dir(&output_dir)?;
+
+        struct TempDirGuard(PathBuf);
+
+        impl Drop for TempDirGuard {
+            fn drop(&mut self) {
+                let _ = fs::remove_dir_all(&self.0);
+            }
+        }
+
+        let _guard = TempDirGuard(output_dir.clone());
+
+        export_schema(&output_dir, SchemaFormat::JsonSchema, None)?;
+
+        let bundle: Value = serde_json::from_str(&fs::read_to_string(
+            output_dir.join("codex_app_server_protocol.schemas.json"),
+        )?)?;
+        assert_eq!(bundle["version"], Value::String(PROTOCOL_VERSION.into()));
+        let definitions = bundle["definitions"]
+            .as_object()
+            .ok_or_else(|| anyhow!("definitions missing"))?;
+        for name in ["Submission", "Op", "EventMsg", "ClientRequest"] {
+            assert!(definitions.contains_key(name), "missing {name}");
+        }
+        Ok(())
+    }
 }
@@ -2,6 +2,9 @@ mod export;
 mod jsonrpc_lite;
 mod protocol;
 
+pub use export::PROTOCOL_VERSION;
+pub use export::SchemaFormat;
+pub use export::export_schema;
 pub use export::generate_json;
 pub use export::generate_ts;
 pub use export::generate_types;
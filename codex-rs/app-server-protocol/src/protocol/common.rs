@@ -34,6 +34,25 @@ pub enum AuthMode {
 /// client can send to the server. Each variant has associated `params` and
 /// `response` types. Also generates a `export_client_responses()` function to
 /// export all response types to TypeScript.
+/// JSON-RPC method name of a generated variant: its `=> "wire"` name when
+/// given, otherwise the camelCase variant name (matching serde's renaming).
+macro_rules! wire_method {
+    ($variant:ident, $wire:literal) => {
+        $wire.to_string()
+    };
+    ($variant:ident) => {
+        camel_case_variant(stringify!($variant))
+    };
+}
+
+fn camel_case_variant(variant: &str) -> String {
+    let mut chars = variant.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 macro_rules! client_request_definitions {
     (
         $(
@@ -60,6 +79,11 @@ macro_rules! client_request_definitions {
             )*
         }
 
+        /// Methods of every request a client may send, in declaration order.
+        pub fn client_request_methods() -> Vec<String> {
+            vec![$(wire_method!($variant $(, $wire)?)),*]
+        }
+
         pub fn export_client_responses(
             out_dir: &::std::path::Path,
         ) -> ::std::result::Result<(), ::ts_rs::ExportError> {
@@ -117,6 +141,18 @@ client_request_definitions! {
         params: v2::ThreadArchiveParams,
         response: v2::ThreadArchiveResponse,
     },
+    ThreadDelete => "thread/delete" {
+        params: v2::ThreadDeleteParams,
+        response: v2::ThreadDeleteResponse,
+    },
+    ThreadNameSet => "thread/name/set" {
+        params: v2::ThreadNameSetParams,
+        response: v2::ThreadNameSetResponse,
+    },
+    ThreadTagsSet => "thread/tags/set" {
+        params: v2::ThreadTagsSetParams,
+        response: v2::ThreadTagsSetResponse,
+    },
     ThreadRollback => "thread/rollback" {
         params: v2::ThreadRollbackParams,
         response: v2::ThreadRollbackResponse,
@@ -125,10 +161,89 @@ client_request_definitions! {
         params: v2::ThreadCompactParams,
         response: v2::ThreadCompactResponse,
     },
+    ThreadRestoreFile => "thread/restoreFile" {
+        params: v2::ThreadRestoreFileParams,
+        response: v2::ThreadRestoreFileResponse,
+    },
+    ThreadRevertHunks => "thread/revertHunks" {
+        params: v2::ThreadRevertHunksParams,
+        response: v2::ThreadRevertHunksResponse,
+    },
+    ThreadQueueAdd => "thread/queue/add" {
+        params: v2::ThreadQueueAddParams,
+        response: v2::ThreadQueueAddResponse,
+    },
+    ThreadQueueList => "thread/queue/list" {
+        params: v2::ThreadQueueListParams,
+        response: v2::ThreadQueueListResponse,
+    },
+    ThreadQueueClear => "thread/queue/clear" {
+        params: v2::ThreadQueueClearParams,
+        response: v2::ThreadQueueClearResponse,
+    },
+    ThreadMcpServerAdd => "thread/mcpServer/add" {
+        params: v2::ThreadMcpServerAddParams,
+        response: v2::ThreadMcpServerAddResponse,
+    },
+    ThreadMcpServerRemove => "thread/mcpServer/remove" {
+        params: v2::ThreadMcpServerRemoveParams,
+        response: v2::ThreadMcpServerRemoveResponse,
+    },
+    ThreadPinAdd => "thread/pin/add" {
+        params: v2::ThreadPinAddParams,
+        response: v2::ThreadPinAddResponse,
+    },
+    ThreadPinRemove => "thread/pin/remove" {
+        params: v2::ThreadPinRemoveParams,
+        response: v2::ThreadPinRemoveResponse,
+    },
+    ThreadPinList => "thread/pin/list" {
+        params: v2::ThreadPinListParams,
+        response: v2::ThreadPinListResponse,
+    },
+    ThreadContextInspect => "thread/context/inspect" {
+        params: v2::ThreadContextInspectParams,
+        response: v2::ThreadContextInspectResponse,
+    },
+    ThreadContextDrop => "thread/context/drop" {
+        params: v2::ThreadContextDropParams,
+        response: v2::ThreadContextDropResponse,
+    },
+    ThreadContextCompact => "thread/context/compact" {
+        params: v2::ThreadContextCompactParams,
+        response: v2::ThreadContextCompactResponse,
+    },
+    ThreadPlanModeSet => "thread/planMode/set" {
+        params: v2::ThreadPlanModeSetParams,
+        response: v2::ThreadPlanModeSetResponse,
+    },
+    /// CODEZ - Switch the model / reasoning effort of a thread between turns.
+    ThreadSettingsUpdate => "thread/settings/update" {
+        params: v2::ThreadSettingsUpdateParams,
+        response: v2::ThreadSettingsUpdateResponse,
+    },
+    /// CODEZ - Ask for an inline suggestion at the editor's cursor; the
+    /// answer arrives as `thread/nextEdit/suggested`.
+    ThreadNextEditSuggest => "thread/nextEdit/suggest" {
+        params: v2::ThreadNextEditSuggestParams,
+        response: v2::ThreadNextEditSuggestResponse,
+    },
+    ThreadTerminalWrite => "thread/terminal/write" {
+        params: v2::ThreadTerminalWriteParams,
+        response: v2::ThreadTerminalWriteResponse,
+    },
+    ThreadTerminalResize => "thread/terminal/resize" {
+        params: v2::ThreadTerminalResizeParams,
+        response: v2::ThreadTerminalResizeResponse,
+    },
     ThreadList => "thread/list" {
         params: v2::ThreadListParams,
         response: v2::ThreadListResponse,
     },
+    ThreadSearch => "thread/search" {
+        params: v2::ThreadSearchParams,
+        response: v2::ThreadSearchResponse,
+    },
     ThreadLoadedList => "thread/loaded/list" {
         params: v2::ThreadLoadedListParams,
         response: v2::ThreadLoadedListResponse,
@@ -137,6 +252,18 @@ client_request_definitions! {
         params: v2::ThreadReadParams,
         response: v2::ThreadReadResponse,
     },
+    ThreadExport => "thread/export" {
+        params: v2::ThreadExportParams,
+        response: v2::ThreadExportResponse,
+    },
+    ThreadAttach => "thread/attach" {
+        params: v2::ThreadAttachParams,
+        response: v2::ThreadAttachResponse,
+    },
+    ThreadDetach => "thread/detach" {
+        params: v2::ThreadDetachParams,
+        response: v2::ThreadDetachResponse,
+    },
     SkillsList => "skills/list" {
         params: v2::SkillsListParams,
         response: v2::SkillsListResponse,
@@ -145,6 +272,14 @@ client_request_definitions! {
         params: v2::SkillsConfigWriteParams,
         response: v2::SkillsConfigWriteResponse,
     },
+    SubAgentsList => "subagents/list" {
+        params: v2::SubAgentsListParams,
+        response: v2::SubAgentsListResponse,
+    },
+    SubAgentsRun => "subagents/run" {
+        params: v2::SubAgentsRunParams,
+        response: v2::SubAgentsRunResponse,
+    },
     TurnStart => "turn/start" {
         params: v2::TurnStartParams,
         response: v2::TurnStartResponse,
@@ -183,6 +318,12 @@ client_request_definitions! {
         response: v2::ListMcpServerStatusResponse,
     },
 
+    /// CODEZ - Report which optional subsystems are active and why others are not.
+    CapabilitiesRead => "capabilities/read" {
+        params: v2::CapabilitiesReadParams,
+        response: v2::CapabilitiesReadResponse,
+    },
+
     LoginAccount => "account/login/start" {
         params: v2::LoginAccountParams,
         response: v2::LoginAccountResponse,
@@ -244,6 +385,19 @@ client_request_definitions! {
         response: v2::ConfigRequirementsReadResponse,
     },
 
+    /// CODEZ - JSON Schema of `config.toml`, for clients that edit it.
+    ConfigSchemaRead => "config/schema/read" {
+        params: #[ts(type = "undefined")] #[serde(skip_serializing_if = "Option::is_none")] Option<()>,
+        response: v2::ConfigSchemaReadResponse,
+    },
+
+    /// CODEZ - Unknown keys, type mismatches, and replaced options in the
+    /// config files, with their positions.
+    ConfigValidate => "config/validate" {
+        params: v2::ConfigValidateParams,
+        response: v2::ConfigValidateResponse,
+    },
+
     GetAccount => "account/read" {
         params: v2::GetAccountParams,
         response: v2::GetAccountResponse,
@@ -379,6 +533,11 @@ macro_rules! server_request_definitions {
             )*
         }
 
+        /// Methods of every request the server may send, in declaration order.
+        pub fn server_request_methods() -> Vec<String> {
+            vec![$(wire_method!($variant $(, $wire)?)),*]
+        }
+
         #[derive(Debug, Clone, PartialEq, JsonSchema)]
         pub enum ServerRequestPayload {
             $( $variant($params), )*
@@ -452,6 +611,12 @@ macro_rules! server_notification_definitions {
             )*
         }
 
+        /// Methods of every notification the server may send, in declaration
+        /// order.
+        pub fn server_notification_methods() -> Vec<String> {
+            vec![$(wire_method!($variant $(, $wire)?)),*]
+        }
+
         impl ServerNotification {
             pub fn to_params(self) -> Result<serde_json::Value, serde_json::Error> {
                 match self {
@@ -530,6 +695,20 @@ server_request_definitions! {
         response: v2::FileChangeRequestApprovalResponse,
     },
 
+    /// Sent when the agent submits a plan in plan mode. Approving it unlocks
+    /// commands and file changes for the rest of the turn.
+    PlanRequestApproval => "item/plan/requestApproval" {
+        params: v2::PlanRequestApprovalParams,
+        response: v2::PlanRequestApprovalResponse,
+    },
+
+    /// Sent when an MCP server's `tool_approvals` config asks before running
+    /// one of its tools.
+    McpToolCallRequestApproval => "item/mcpToolCall/requestApproval" {
+        params: v2::McpToolCallRequestApprovalParams,
+        response: v2::McpToolCallRequestApprovalResponse,
+    },
+
     /// CODEZ - Request structured input from the user (legacy compatibility).
     AskUserQuestion => "user/askQuestion" {
         params: v2::AskUserQuestionParams,
@@ -646,10 +825,20 @@ server_notification_definitions! {
     Error => "error" (v2::ErrorNotification),
     ThreadStarted => "thread/started" (v2::ThreadStartedNotification),
     ThreadTokenUsageUpdated => "thread/tokenUsage/updated" (v2::ThreadTokenUsageUpdatedNotification),
+    ThreadCostUpdated => "thread/cost/updated" (v2::ThreadCostUpdatedNotification),
+    ThreadRateLimitUpdated => "thread/rateLimit/updated" (v2::ThreadRateLimitUpdatedNotification),
+    ThreadQueueUpdated => "thread/queue/updated" (v2::ThreadQueueUpdatedNotification),
+    ThreadPinnedUpdated => "thread/pinned/updated" (v2::ThreadPinnedUpdatedNotification),
+    ThreadMcpToolsChanged => "thread/mcpTools/changed" (v2::ThreadMcpToolsChangedNotification),
+    ThreadContextInspected => "thread/context/inspected" (v2::ThreadContextInspectedNotification),
+    ThreadPlanModeUpdated => "thread/planMode/updated" (v2::ThreadPlanModeUpdatedNotification),
+    ThreadProcessesUpdated => "thread/processes/updated" (v2::ThreadProcessesUpdatedNotification),
+    ThreadNextEditSuggested => "thread/nextEdit/suggested" (v2::ThreadNextEditSuggestedNotification),
     TurnStarted => "turn/started" (v2::TurnStartedNotification),
     TurnCompleted => "turn/completed" (v2::TurnCompletedNotification),
     TurnDiffUpdated => "turn/diff/updated" (v2::TurnDiffUpdatedNotification),
     TurnPlanUpdated => "turn/plan/updated" (v2::TurnPlanUpdatedNotification),
+    TurnFollowUpsSuggested => "turn/followUps/suggested" (v2::TurnFollowUpsSuggestedNotification),
     ItemStarted => "item/started" (v2::ItemStartedNotification),
     ItemCompleted => "item/completed" (v2::ItemCompletedNotification),
     /// This event is internal-only. Used by Codex Cloud.
@@ -666,16 +855,17 @@ server_notification_definitions! {
     ReasoningSummaryPartAdded => "item/reasoning/summaryPartAdded" (v2::ReasoningSummaryPartAddedNotification),
     ReasoningTextDelta => "item/reasoning/textDelta" (v2::ReasoningTextDeltaNotification),
     ContextCompacted => "thread/compacted" (v2::ContextCompactedNotification),
+    FileRestored => "thread/fileRestored" (v2::FileRestoredNotification),
     DeprecationNotice => "deprecationNotice" (v2::DeprecationNoticeNotification),
     ConfigWarning => "configWarning" (v2::ConfigWarningNotification),
+    ConfigReloaded => "config/reloaded" (v2::ConfigReloadedNotification),
+    SubAgentsUpdated => "subagents/updated" (v2::SubAgentsUpdatedNotification),
+    SubAgentPreview => "subagents/preview" (v2::SubAgentPreviewNotification),
 
     /// Notifies the user of world-writable directories on Windows, which cannot be protected by the sandbox.
     WindowsWorldWritableWarning => "windows/worldWritableWarning" (v2::WindowsWorldWritableWarningNotification),
 
-    #[serde(rename = "account/login/completed")]
-    #[ts(rename = "account/login/completed")]
-    #[strum(serialize = "account/login/completed")]
-    AccountLoginCompleted(v2::AccountLoginCompletedNotification),
+    AccountLoginCompleted => "account/login/completed" (v2::AccountLoginCompletedNotification),
 
     /// DEPRECATED NOTIFICATIONS below
     AuthStatusChange(v1::AuthStatusChangeNotification),
@@ -819,6 +1009,40 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn method_lists_use_wire_names() -> Result<()> {
+        let request = ClientRequest::GetAccountRateLimits {
+            request_id: RequestId::Integer(1),
+            params: None,
+        };
+        let method = serde_json::to_value(&request)?["method"].clone();
+        assert_eq!(method, json!("account/rateLimits/read"));
+
+        let requests = client_request_methods();
+        assert!(requests.contains(&"account/rateLimits/read".to_string()));
+        assert!(requests.contains(&"initialize".to_string()));
+        assert!(requests.contains(&"newConversation".to_string()));
+
+        let notifications = server_notification_methods();
+        assert!(notifications.contains(&"account/login/completed".to_string()));
+        assert!(notifications.contains(&"authStatusChange".to_string()));
+        assert!(server_request_methods().contains(&"execCommandApproval".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn negotiates_the_highest_shared_protocol_version() {
+        assert_eq!(
+            v1::negotiate_protocol_version(None),
+            Ok(v1::MIN_APP_SERVER_PROTOCOL_VERSION)
+        );
+        assert_eq!(
+            v1::negotiate_protocol_version(Some(v1::APP_SERVER_PROTOCOL_VERSION + 3)),
+            Ok(v1::APP_SERVER_PROTOCOL_VERSION)
+        );
+        assert!(v1::negotiate_protocol_version(Some(0)).is_err());
+    }
+
     #[test]
     fn serialize_get_account_rate_limits() -> Result<()> {
         let request = ClientRequest::GetAccountRateLimits {
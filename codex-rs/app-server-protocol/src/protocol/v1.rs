@@ -33,6 +33,107 @@ use crate::protocol::common::GitSha;
 #[serde(rename_all = "camelCase")]
 pub struct InitializeParams {
     pub client_info: ClientInfo,
+    /// Highest app-server protocol revision the client speaks. Clients that
+    /// predate negotiation omit it and are treated as revision 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub protocol_version: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub capabilities: Option<ClientCapabilities>,
+}
+
+/// Revision of the app-server JSON-RPC protocol. Bumped whenever a change
+/// could break a client written against the previous revision, so the server
+/// can keep the old behavior for clients that negotiate a lower one.
+pub const APP_SERVER_PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest revision the server still speaks.
+pub const MIN_APP_SERVER_PROTOCOL_VERSION: u32 = 1;
+
+/// Picks the revision used for a connection: the client's highest revision,
+/// capped at the server's. Fails when the client only speaks revisions the
+/// server no longer supports.
+pub fn negotiate_protocol_version(client_version: Option<u32>) -> Result<u32, String> {
+    let client_version = client_version.unwrap_or(MIN_APP_SERVER_PROTOCOL_VERSION);
+    if client_version < MIN_APP_SERVER_PROTOCOL_VERSION {
+        return Err(format!(
+            "Unsupported protocolVersion {client_version}; this server speaks {MIN_APP_SERVER_PROTOCOL_VERSION} through {APP_SERVER_PROTOCOL_VERSION}."
+        ));
+    }
+    Ok(client_version.min(APP_SERVER_PROTOCOL_VERSION))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientCapabilities {
+    /// Notification methods the client handles. When set, the server sends
+    /// no other notifications on this connection; when omitted, it sends all.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub notifications: Option<Vec<String>>,
+    /// Codex feature flags (`[features]` keys) the client wants to rely on.
+    /// The server answers with the ones that are enabled.
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// High-volume notification groups the client does not want. Matching
+    /// notifications are dropped by the server before they are serialized.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_notification_categories: Vec<NotificationCategory>,
+}
+
+/// Groups of streaming notifications a client can opt out of at `initialize`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationCategory {
+    /// Reasoning summary and raw reasoning text deltas.
+    ReasoningDeltas,
+    /// Streaming agent message text.
+    AgentMessageDeltas,
+    /// Command output chunks, terminal interactions and patch output.
+    CommandOutputDeltas,
+    /// Token usage and rate limit updates.
+    TokenUsage,
+    /// Every legacy `codex/event/*` notification.
+    LegacyEvents,
+}
+
+impl NotificationCategory {
+    /// Whether the notification `method` (v2 or legacy `codex/event/*`)
+    /// belongs to this category.
+    pub fn includes(self, method: &str) -> bool {
+        let methods: &[&str] = match self {
+            Self::ReasoningDeltas => &[
+                "item/reasoning/summaryTextDelta",
+                "item/reasoning/summaryPartAdded",
+                "item/reasoning/textDelta",
+                "codex/event/agent_reasoning_delta",
+                "codex/event/agent_reasoning_raw_content_delta",
+                "codex/event/agent_reasoning_section_break",
+                "codex/event/reasoning_content_delta",
+                "codex/event/reasoning_raw_content_delta",
+            ],
+            Self::AgentMessageDeltas => &[
+                "item/agentMessage/delta",
+                "codex/event/agent_message_delta",
+                "codex/event/agent_message_content_delta",
+            ],
+            Self::CommandOutputDeltas => &[
+                "item/commandExecution/outputDelta",
+                "item/commandExecution/terminalInteraction",
+                "item/fileChange/outputDelta",
+                "codex/event/exec_command_output_delta",
+                "codex/event/terminal_interaction",
+            ],
+            Self::TokenUsage => &[
+                "thread/tokenUsage/updated",
+                "account/rateLimits/updated",
+                "codex/event/token_count",
+            ],
+            Self::LegacyEvents => return method.starts_with("codex/event/"),
+        };
+        methods.contains(&method)
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
@@ -47,6 +148,33 @@ pub struct ClientInfo {
 #[serde(rename_all = "camelCase")]
 pub struct InitializeResponse {
     pub user_agent: String,
+    /// Revision used for the rest of the connection. Older servers omit it.
+    #[serde(default)]
+    pub protocol_version: u32,
+    #[serde(default)]
+    pub capabilities: ServerCapabilities,
+}
+
+/// Machine-readable description of what this server supports.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerCapabilities {
+    /// Codex version the server was built from.
+    pub version: String,
+    /// Request methods the server accepts.
+    pub requests: Vec<String>,
+    /// Notification methods the server may send.
+    pub notifications: Vec<String>,
+    /// Request methods the server may send to the client.
+    pub server_requests: Vec<String>,
+    /// Transports this server build can listen on (`stdio`, `websocket`, `http`).
+    pub transports: Vec<String>,
+    /// Built-in tools a new thread would offer the model (MCP tools excluded).
+    pub tools: Vec<String>,
+    /// Enabled Codex feature flags.
+    pub features: Vec<String>,
+    /// Features from `capabilities.features` in the request that are enabled.
+    pub accepted_features: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
@@ -134,6 +262,9 @@ pub struct ConversationSummary {
     pub cli_version: String,
     pub source: SessionSource,
     pub git_info: Option<ConversationGitInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub forked_from_id: Option<ThreadId>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
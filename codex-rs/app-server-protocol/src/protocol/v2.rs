@@ -17,12 +17,27 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::openai_models::ReasoningEffort;
 use codex_protocol::parse_command::ParsedCommand as CoreParsedCommand;
 use codex_protocol::plan_tool::PlanItemArg as CorePlanItemArg;
+use codex_protocol::plan_tool::ProposedPlan as CoreProposedPlan;
 use codex_protocol::plan_tool::StepStatus as CorePlanStepStatus;
 use codex_protocol::protocol::AgentStatus as CoreAgentStatus;
 use codex_protocol::protocol::AskForApproval as CoreAskForApproval;
+use codex_protocol::protocol::BackgroundProcessInfo as CoreBackgroundProcessInfo;
+use codex_protocol::protocol::BackgroundProcessStatus as CoreBackgroundProcessStatus;
 use codex_protocol::protocol::CodexErrorInfo as CoreCodexErrorInfo;
+use codex_protocol::protocol::ContextInspectionItem as CoreContextInspectionItem;
 use codex_protocol::protocol::CreditsSnapshot as CoreCreditsSnapshot;
+use codex_protocol::protocol::DiffHunk as CoreDiffHunk;
+use codex_protocol::protocol::FileDiff as CoreFileDiff;
+use codex_protocol::protocol::FollowUpSuggestion as CoreFollowUpSuggestion;
+use codex_protocol::protocol::McpServerChange as CoreMcpServerChange;
 use codex_protocol::protocol::NetworkAccess as CoreNetworkAccess;
+use codex_protocol::protocol::PatchHunkResult as CorePatchHunkResult;
+use codex_protocol::protocol::PatchPreview as CorePatchPreview;
+use codex_protocol::protocol::PatchPreviewFile as CorePatchPreviewFile;
+use codex_protocol::protocol::PinnedContent as CorePinnedContent;
+use codex_protocol::protocol::PinnedContextItem as CorePinnedContextItem;
+use codex_protocol::protocol::ProviderQuota as CoreProviderQuota;
+use codex_protocol::protocol::QueuedUserInput as CoreQueuedUserInput;
 use codex_protocol::protocol::RateLimitSnapshot as CoreRateLimitSnapshot;
 use codex_protocol::protocol::RateLimitWindow as CoreRateLimitWindow;
 use codex_protocol::protocol::SessionSource as CoreSessionSource;
@@ -30,13 +45,20 @@ use codex_protocol::protocol::SkillErrorInfo as CoreSkillErrorInfo;
 use codex_protocol::protocol::SkillInterface as CoreSkillInterface;
 use codex_protocol::protocol::SkillMetadata as CoreSkillMetadata;
 use codex_protocol::protocol::SkillScope as CoreSkillScope;
+use codex_protocol::protocol::SubAgentTokenUsage as CoreSubAgentTokenUsage;
 use codex_protocol::protocol::TokenUsage as CoreTokenUsage;
 use codex_protocol::protocol::TokenUsageInfo as CoreTokenUsageInfo;
+use codex_protocol::protocol::ToolTokenUsage as CoreToolTokenUsage;
+use codex_protocol::protocol::TurnMetadata as CoreTurnMetadata;
+use codex_protocol::subagents::SubAgentInfo as CoreSubAgentInfo;
+use codex_protocol::subagents::SubAgentLoadError as CoreSubAgentLoadError;
+use codex_protocol::subagents::SubAgentPreview as CoreSubAgentPreview;
 use codex_protocol::user_input::ByteRange as CoreByteRange;
 use codex_protocol::user_input::TextElement as CoreTextElement;
 use codex_protocol::user_input::UserInput as CoreUserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use mcp_types::ContentBlock as McpContentBlock;
+use mcp_types::Prompt as McpPrompt;
 use mcp_types::Resource as McpResource;
 use mcp_types::ResourceTemplate as McpResourceTemplate;
 use mcp_types::Tool as McpTool;
@@ -97,6 +119,8 @@ pub enum CodexErrorInfo {
     Unauthorized,
     BadRequest,
     ThreadRollbackFailed,
+    /// A `[budget]` spending limit was reached.
+    BudgetExceeded,
     SandboxError,
     /// The response SSE stream disconnected in the middle of a turn before completion.
     ResponseStreamDisconnected {
@@ -128,6 +152,7 @@ impl From<CoreCodexErrorInfo> for CodexErrorInfo {
             CoreCodexErrorInfo::Unauthorized => CodexErrorInfo::Unauthorized,
             CoreCodexErrorInfo::BadRequest => CodexErrorInfo::BadRequest,
             CoreCodexErrorInfo::ThreadRollbackFailed => CodexErrorInfo::ThreadRollbackFailed,
+            CoreCodexErrorInfo::BudgetExceeded => CodexErrorInfo::BudgetExceeded,
             CoreCodexErrorInfo::SandboxError => CodexErrorInfo::SandboxError,
             CoreCodexErrorInfo::ResponseStreamDisconnected { http_status_code } => {
                 CodexErrorInfo::ResponseStreamDisconnected { http_status_code }
@@ -471,6 +496,13 @@ pub struct ConfigReadResponse {
 pub struct ConfigRequirements {
     pub allowed_approval_policies: Option<Vec<AskForApproval>>,
     pub allowed_sandbox_modes: Option<Vec<SandboxMode>>,
+    /// CODEZ - Ids of the model providers that may be selected.
+    pub allowed_model_providers: Option<Vec<String>>,
+    /// CODEZ - Names of the MCP servers that may run; other configured
+    /// servers are disabled.
+    pub allowed_mcp_servers: Option<Vec<String>>,
+    /// CODEZ - When true, the `[audit]` log is always on.
+    pub require_audit_log: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -481,6 +513,64 @@ pub struct ConfigRequirementsReadResponse {
     pub requirements: Option<ConfigRequirements>,
 }
 
+/// CODEZ - The draft-07 JSON Schema of `config.toml` (the same document as
+/// `codex-rs/core/config.schema.json`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigSchemaReadResponse {
+    pub schema: JsonValue,
+}
+
+/// CODEZ - Checks the config layers against the `config.toml` schema.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigValidateParams {
+    /// Optional working directory to resolve project config layers, as in
+    /// `config/read`.
+    pub cwd: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigValidateResponse {
+    /// Highest-precedence layer first; empty when nothing is wrong.
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ConfigDiagnosticKind {
+    /// A key the schema does not know; it has no effect.
+    UnknownKey,
+    /// A value of the wrong type; the configuration does not load.
+    TypeMismatch,
+    /// An option that still works (or is ignored) but has been replaced.
+    Deprecated,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigDiagnostic {
+    pub kind: ConfigDiagnosticKind,
+    pub layer: ConfigLayerSource,
+    /// The file behind `layer`, for layers read from one.
+    pub file_path: Option<String>,
+    /// Dotted path of the key, e.g. `profiles.fast.model`. Array elements
+    /// appear as `[0]`.
+    pub key_path: String,
+    /// 1-based position of the key in `filePath`.
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+    /// The key or value to use instead.
+    pub suggestion: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1008,6 +1098,7 @@ pub struct McpServerStatus {
     pub tools: std::collections::HashMap<String, McpTool>,
     pub resources: Vec<McpResource>,
     pub resource_templates: Vec<McpResourceTemplate>,
+    pub prompts: Vec<McpPrompt>,
     pub auth_status: McpAuthStatus,
 }
 
@@ -1021,6 +1112,47 @@ pub struct ListMcpServerStatusResponse {
     pub next_cursor: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CapabilitiesReadParams {
+    /// Skip starting the configured MCP servers; they are then reported
+    /// from config alone.
+    #[serde(default)]
+    pub skip_mcp: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum CapabilityStatus {
+    /// The subsystem is in use.
+    Active,
+    /// Turned off by configuration.
+    Disabled,
+    /// Wanted, but this machine or provider cannot provide it.
+    Unavailable,
+}
+
+/// One optional subsystem and whether it is usable in this environment.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct Capability {
+    /// Stable id, e.g. `sandbox`, `keyring`, `vision`, or `mcp:<server>`.
+    pub id: String,
+    pub status: CapabilityStatus,
+    /// What backs the subsystem when active, or why it is not.
+    pub detail: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CapabilitiesReadResponse {
+    pub capabilities: Vec<Capability>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1197,6 +1329,10 @@ pub struct ThreadForkParams {
     pub config: Option<HashMap<String, serde_json::Value>>,
     pub base_instructions: Option<String>,
     pub developer_instructions: Option<String>,
+
+    /// Keep only the first `numTurns` turns of the source thread. When null,
+    /// the whole history is copied.
+    pub num_turns: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -1224,6 +1360,52 @@ pub struct ThreadArchiveParams {
 #[ts(export_to = "v2/")]
 pub struct ThreadArchiveResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadDeleteParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadDeleteResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadNameSetParams {
+    pub thread_id: String,
+    /// New name; `null` or blank removes the name.
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadNameSetResponse {
+    /// The name as stored, with whitespace collapsed.
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTagsSetParams {
+    pub thread_id: String,
+    /// Replaces the thread's tags; an empty list removes them.
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTagsSetResponse {
+    /// The tags as stored: lowercased, deduplicated, and sorted.
+    pub tags: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1263,199 +1445,832 @@ pub struct ThreadCompactResponse {}
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct ThreadListParams {
-    /// Opaque pagination cursor returned by a previous call.
-    pub cursor: Option<String>,
-    /// Optional page size; defaults to a reasonable server-side value.
-    pub limit: Option<u32>,
-    /// Optional sort key; defaults to created_at.
-    pub sort_key: Option<ThreadSortKey>,
-    /// Optional provider filter; when set, only sessions recorded under these
-    /// providers are returned. When present but empty, includes all providers.
-    pub model_providers: Option<Vec<String>>,
-    /// Optional archived filter; when set to true, only archived threads are returned.
-    /// If false or null, only non-archived threads are returned.
-    pub archived: Option<bool>,
+pub struct ThreadRestoreFileParams {
+    pub thread_id: String,
+    /// Turn whose starting snapshot to restore from. Defaults to the most
+    /// recent turn that captured one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+    /// File to revert, absolute or relative to the thread's cwd.
+    pub path: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema, TS)]
-#[serde(rename_all = "snake_case")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub enum ThreadSortKey {
-    CreatedAt,
-    UpdatedAt,
-}
+pub struct ThreadRestoreFileResponse {}
 
+/// CODEZ - Revert some hunks of a turn's changes to one file.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct ThreadListResponse {
-    pub data: Vec<Thread>,
-    /// Opaque cursor to pass to the next call to continue after the last item.
-    /// if None, there are no more items to return.
-    pub next_cursor: Option<String>,
+pub struct ThreadRevertHunksParams {
+    pub thread_id: String,
+    /// Turn whose diff reported `hunks`. Defaults to the most recent turn
+    /// that captured a snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+    /// File to revert, absolute or relative to the thread's cwd.
+    pub path: String,
+    /// Hunks from that turn's `turn/diff/updated` to undo; the others stay.
+    pub hunks: Vec<TurnDiffHunk>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct ThreadLoadedListParams {
-    /// Opaque pagination cursor returned by a previous call.
-    pub cursor: Option<String>,
-    /// Optional page size; defaults to no limit.
-    pub limit: Option<u32>,
-}
+pub struct ThreadRevertHunksResponse {}
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct ThreadLoadedListResponse {
-    /// Thread ids for sessions currently loaded in memory.
-    pub data: Vec<String>,
-    /// Opaque cursor to pass to the next call to continue after the last item.
-    /// if None, there are no more items to return.
-    pub next_cursor: Option<String>,
+pub struct ThreadQueueAddParams {
+    pub thread_id: String,
+    pub input: Vec<UserInput>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct ThreadReadParams {
-    pub thread_id: String,
-    /// When true, include turns and their items from rollout history.
-    #[serde(default)]
-    pub include_turns: bool,
-}
+pub struct ThreadQueueAddResponse {}
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct ThreadReadResponse {
-    pub thread: Thread,
+pub struct ThreadQueueListParams {
+    pub thread_id: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillsListParams {
-    /// When empty, defaults to the current session working directory.
-    #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub cwds: Vec<PathBuf>,
+pub struct ThreadQueueListResponse {}
 
-    /// When true, bypass the skills cache and re-scan skills from disk.
-    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
-    pub force_reload: bool,
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadQueueClearParams {
+    pub thread_id: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillsListResponse {
-    pub data: Vec<SkillsListEntry>,
-}
+pub struct ThreadQueueClearResponse {}
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
-#[serde(rename_all = "snake_case")]
-#[ts(rename_all = "snake_case")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub enum SkillScope {
-    User,
-    Repo,
-    System,
-    Admin,
+pub struct ThreadPlanModeSetParams {
+    pub thread_id: String,
+    pub enabled: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillMetadata {
-    pub name: String,
-    pub description: String,
+pub struct ThreadPlanModeSetResponse {}
+
+/// CODEZ - Changes the model and/or reasoning effort used from the next turn
+/// on, without starting a turn. Fields left out keep their current value.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSettingsUpdateParams {
+    pub thread_id: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
-    /// Legacy short_description from SKILL.md. Prefer SKILL.toml interface.short_description.
-    pub short_description: Option<String>,
+    pub model: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
-    pub interface: Option<SkillInterface>,
-    pub path: PathBuf,
-    pub scope: SkillScope,
-    pub enabled: bool,
+    pub effort: Option<ReasoningEffort>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillInterface {
-    #[ts(optional)]
-    pub display_name: Option<String>,
-    #[ts(optional)]
-    pub short_description: Option<String>,
-    #[ts(optional)]
-    pub icon_small: Option<PathBuf>,
-    #[ts(optional)]
-    pub icon_large: Option<PathBuf>,
+pub struct ThreadSettingsUpdateResponse {}
+
+/// CODEZ - Inline "next edit" suggestion at the cursor of `path`, based on
+/// the thread's recent messages. The thread's history is not changed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadNextEditSuggestParams {
+    pub thread_id: String,
+    /// Chosen by the client; echoed in `thread/nextEdit/suggested`.
+    pub request_id: String,
+    pub path: PathBuf,
+    /// File contents before the cursor.
+    pub prefix: String,
+    /// File contents after the cursor.
+    pub suffix: String,
+    /// Model to ask instead of the thread's model, typically a fast one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
-    pub brand_color: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadNextEditSuggestResponse {}
+
+/// CODEZ - Answer to `thread/nextEdit/suggest`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadNextEditSuggestedNotification {
+    pub thread_id: String,
+    pub request_id: String,
+    /// Text to insert at the cursor; absent when there is no suggestion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
-    pub default_prompt: Option<String>,
+    pub text: Option<String>,
 }
 
+/// Writes user input to a command the thread started with `tty: true`.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillErrorInfo {
-    pub path: PathBuf,
-    pub message: String,
+pub struct ThreadTerminalWriteParams {
+    pub thread_id: String,
+    /// The `processId` reported on the command's `commandExecution` item.
+    pub process_id: String,
+    /// Raw bytes to write; include a trailing `\n` to submit a line.
+    pub input: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillsListEntry {
-    pub cwd: PathBuf,
-    pub skills: Vec<SkillMetadata>,
-    pub errors: Vec<SkillErrorInfo>,
+pub struct ThreadTerminalWriteResponse {}
+
+/// Sets the terminal size used for the thread's PTY-backed commands.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTerminalResizeParams {
+    pub thread_id: String,
+    pub rows: u16,
+    pub cols: u16,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillsConfigWriteParams {
-    pub path: PathBuf,
+pub struct ThreadTerminalResizeResponse {}
+
+/// Sent when plan mode is turned on or off for a thread.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPlanModeUpdatedNotification {
+    pub thread_id: String,
     pub enabled: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct SkillsConfigWriteResponse {
-    pub effective_enabled: bool,
+pub struct QueuedInput {
+    /// Turn id the input will run under once it starts.
+    pub id: String,
+    pub input: Vec<UserInput>,
 }
 
-impl From<CoreSkillMetadata> for SkillMetadata {
-    fn from(value: CoreSkillMetadata) -> Self {
+impl From<CoreQueuedUserInput> for QueuedInput {
+    fn from(value: CoreQueuedUserInput) -> Self {
         Self {
-            name: value.name,
-            description: value.description,
-            short_description: value.short_description,
-            interface: value.interface.map(SkillInterface::from),
-            path: value.path,
-            scope: value.scope.into(),
-            enabled: true,
+            id: value.id,
+            input: value.items.into_iter().map(Into::into).collect(),
         }
     }
 }
 
-impl From<CoreSkillInterface> for SkillInterface {
-    fn from(value: CoreSkillInterface) -> Self {
-        Self {
-            display_name: value.display_name,
-            short_description: value.short_description,
-            brand_color: value.brand_color,
-            default_prompt: value.default_prompt,
-            icon_small: value.icon_small,
-            icon_large: value.icon_large,
+/// Sent whenever a thread's input queue changes and in reply to
+/// `thread/queue/list`. Entries are listed next-to-run first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadQueueUpdatedNotification {
+    pub thread_id: String,
+    pub inputs: Vec<QueuedInput>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum BackgroundProcessStatus {
+    Running,
+    Exited,
+}
+
+/// A long-running process the agent started with its `background_process` tool.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct BackgroundProcess {
+    pub process_id: String,
+    pub command: String,
+    pub cwd: PathBuf,
+    pub status: BackgroundProcessStatus,
+    /// Set once the process has exited.
+    pub exit_code: Option<i32>,
+}
+
+impl From<CoreBackgroundProcessInfo> for BackgroundProcess {
+    fn from(value: CoreBackgroundProcessInfo) -> Self {
+        let (status, exit_code) = match value.status {
+            CoreBackgroundProcessStatus::Running => (BackgroundProcessStatus::Running, None),
+            CoreBackgroundProcessStatus::Exited { exit_code } => {
+                (BackgroundProcessStatus::Exited, Some(exit_code))
+            }
+        };
+        Self {
+            process_id: value.process_id,
+            command: value.command,
+            cwd: value.cwd,
+            status,
+            exit_code,
+        }
+    }
+}
+
+/// Sent whenever a background process of the thread starts, exits or is stopped.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadProcessesUpdatedNotification {
+    pub thread_id: String,
+    pub processes: Vec<BackgroundProcess>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum PinnedContent {
+    /// A reply or other message text kept verbatim.
+    Message { text: String },
+    /// A file re-read before every request; relative paths resolve against
+    /// the thread's cwd.
+    File { path: String },
+    /// A free-form note from the user.
+    Note { text: String },
+}
+
+impl PinnedContent {
+    pub fn into_core(self) -> CorePinnedContent {
+        match self {
+            PinnedContent::Message { text } => CorePinnedContent::Message { text },
+            PinnedContent::File { path } => CorePinnedContent::File {
+                path: PathBuf::from(path),
+            },
+            PinnedContent::Note { text } => CorePinnedContent::Note { text },
+        }
+    }
+}
+
+impl From<CorePinnedContent> for PinnedContent {
+    fn from(value: CorePinnedContent) -> Self {
+        match value {
+            CorePinnedContent::Message { text } => PinnedContent::Message { text },
+            CorePinnedContent::File { path } => PinnedContent::File {
+                path: path.to_string_lossy().into_owned(),
+            },
+            CorePinnedContent::Note { text } => PinnedContent::Note { text },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct PinnedContextItem {
+    /// Id to pass to `thread/pin/remove`.
+    pub id: String,
+    pub content: PinnedContent,
+}
+
+impl From<CorePinnedContextItem> for PinnedContextItem {
+    fn from(value: CorePinnedContextItem) -> Self {
+        Self {
+            id: value.id,
+            content: value.content.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMcpServerAddParams {
+    pub thread_id: String,
+    pub name: String,
+    /// Server config in the `[mcp_servers.<name>]` shape from config.toml,
+    /// e.g. `{ "command": "npx", "args": ["-y", "my-server"] }` or
+    /// `{ "url": "https://example.com/mcp" }`.
+    pub config: JsonValue,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMcpServerAddResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMcpServerRemoveParams {
+    pub thread_id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMcpServerRemoveResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum McpServerChange {
+    /// The server started; `tools` are the fully qualified names it added.
+    Added { tools: Vec<String> },
+    /// The server was stopped and its tools removed.
+    Removed,
+    /// The server could not be added, or there was no such server to remove.
+    Failed { error: String },
+}
+
+impl From<CoreMcpServerChange> for McpServerChange {
+    fn from(value: CoreMcpServerChange) -> Self {
+        match value {
+            CoreMcpServerChange::Added { tools } => McpServerChange::Added { tools },
+            CoreMcpServerChange::Removed => McpServerChange::Removed,
+            CoreMcpServerChange::Failed { error } => McpServerChange::Failed { error },
+        }
+    }
+}
+
+/// Sent when `thread/mcpServer/add` or `thread/mcpServer/remove` completes.
+/// The model sees the new tool list from its next request on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMcpToolsChangedNotification {
+    pub thread_id: String,
+    pub server: String,
+    pub change: McpServerChange,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinAddParams {
+    pub thread_id: String,
+    pub content: PinnedContent,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinAddResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinRemoveParams {
+    pub thread_id: String,
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinRemoveResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinListParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinListResponse {}
+
+/// Sent whenever a thread's pinned context changes and in reply to
+/// `thread/pin/list`. Items are listed in pin order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadPinnedUpdatedNotification {
+    pub thread_id: String,
+    pub items: Vec<PinnedContextItem>,
+}
+
+v2_enum_from_core!(
+    pub enum ContextItemKind from codex_protocol::protocol::ContextItemKind {
+        BaseInstructions,
+        DeveloperInstructions,
+        UserInstructions,
+        SkillInstructions,
+        EnvironmentContext,
+        Pinned,
+        Turn,
+        ToolOutput,
+        Other
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ContextInspectionItem {
+    /// Id to pass to `thread/context/drop`; `null` for items that cannot be
+    /// dropped.
+    pub id: Option<String>,
+    pub kind: ContextItemKind,
+    pub label: String,
+    /// Estimated tokens.
+    pub tokens: i64,
+    /// 1-based turn of `turn` and `toolOutput` items; pass it to
+    /// `thread/context/compact` to summarize the turn.
+    pub turn: Option<u32>,
+}
+
+impl From<CoreContextInspectionItem> for ContextInspectionItem {
+    fn from(value: CoreContextInspectionItem) -> Self {
+        Self {
+            id: value.id,
+            kind: value.kind.into(),
+            label: value.label,
+            tokens: value.tokens,
+            turn: value.turn,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextInspectParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextInspectResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextDropParams {
+    pub thread_id: String,
+    /// An item id from `thread/context/inspected`.
+    pub item_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextDropResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextCompactParams {
+    pub thread_id: String,
+    /// 1-based turn to replace with a summary.
+    pub turn: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextCompactResponse {}
+
+/// Sent in reply to `thread/context/inspect` and `thread/context/drop`.
+/// Items are listed in the order the next request carries them; `toolOutput`
+/// items are also counted in their turn and left out of `totalTokens`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextInspectedNotification {
+    pub thread_id: String,
+    pub items: Vec<ContextInspectionItem>,
+    pub total_tokens: i64,
+    pub context_window: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadListParams {
+    /// Opaque pagination cursor returned by a previous call.
+    pub cursor: Option<String>,
+    /// Optional page size; defaults to a reasonable server-side value.
+    pub limit: Option<u32>,
+    /// Optional sort key; defaults to created_at.
+    pub sort_key: Option<ThreadSortKey>,
+    /// Optional provider filter; when set, only sessions recorded under these
+    /// providers are returned. When present but empty, includes all providers.
+    pub model_providers: Option<Vec<String>>,
+    /// Optional archived filter; when set to true, only archived threads are returned.
+    /// If false or null, only non-archived threads are returned.
+    pub archived: Option<bool>,
+    /// Optional branch filter; when set, only threads forked from this thread
+    /// id (its branches) are returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub forked_from_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export_to = "v2/")]
+pub enum ThreadSortKey {
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadListResponse {
+    pub data: Vec<Thread>,
+    /// Opaque cursor to pass to the next call to continue after the last item.
+    /// if None, there are no more items to return.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchParams {
+    /// Words that must all appear in the thread's messages, commands,
+    /// patched file paths, name, or tags (case-insensitive). May be empty when `tags` is set.
+    pub query: String,
+    /// Only threads carrying all of these tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub tags: Option<Vec<String>>,
+    /// Opaque pagination cursor returned by a previous call.
+    pub cursor: Option<String>,
+    /// Optional maximum number of results; defaults to a reasonable server-side value.
+    pub limit: Option<u32>,
+    /// When true, searches archived threads instead.
+    pub archived: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResponse {
+    /// Matching threads, most recently updated first.
+    pub data: Vec<ThreadSearchResult>,
+    /// Opaque cursor to pass to the next call to continue the search. A page
+    /// may hold fewer results than `limit` even when this is set, since each
+    /// call scans a bounded number of threads.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResult {
+    pub thread: Thread,
+    /// Excerpt of the first message mentioning the query, if any.
+    pub snippet: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadLoadedListParams {
+    /// Opaque pagination cursor returned by a previous call.
+    pub cursor: Option<String>,
+    /// Optional page size; defaults to no limit.
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadLoadedListResponse {
+    /// Thread ids for sessions currently loaded in memory.
+    pub data: Vec<String>,
+    /// Opaque cursor to pass to the next call to continue after the last item.
+    /// if None, there are no more items to return.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadReadParams {
+    pub thread_id: String,
+    /// When true, include turns and their items from rollout history.
+    #[serde(default)]
+    pub include_turns: bool,
+    /// CODEZ - When true, fill `model` and `diffStat` from the whole rollout.
+    #[serde(default)]
+    pub include_stats: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadReadResponse {
+    pub thread: Thread,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export_to = "v2/")]
+pub enum ThreadExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadExportParams {
+    pub thread_id: String,
+    pub format: ThreadExportFormat,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadExportResponse {
+    /// The full transcript (messages, commands, diffs, subagent messages)
+    /// rendered in the requested format, with secrets redacted.
+    pub contents: String,
+    /// Number of values that looked like secrets and were redacted.
+    pub redactions: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadAttachParams {
+    /// A thread that is already running in this server, typically started by
+    /// another client.
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadAttachResponse {
+    /// The thread with its turns so far, read from the rollout. Events after
+    /// this point arrive as notifications.
+    pub thread: Thread,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadDetachParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadDetachResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillsListParams {
+    /// When empty, defaults to the current session working directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cwds: Vec<PathBuf>,
+
+    /// When true, bypass the skills cache and re-scan skills from disk.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub force_reload: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillsListResponse {
+    pub data: Vec<SkillsListEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+#[ts(export_to = "v2/")]
+pub enum SkillScope {
+    User,
+    Repo,
+    System,
+    Admin,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillMetadata {
+    pub name: String,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    /// Legacy short_description from SKILL.md. Prefer SKILL.toml interface.short_description.
+    pub short_description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub interface: Option<SkillInterface>,
+    pub path: PathBuf,
+    pub scope: SkillScope,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillInterface {
+    #[ts(optional)]
+    pub display_name: Option<String>,
+    #[ts(optional)]
+    pub short_description: Option<String>,
+    #[ts(optional)]
+    pub icon_small: Option<PathBuf>,
+    #[ts(optional)]
+    pub icon_large: Option<PathBuf>,
+    #[ts(optional)]
+    pub brand_color: Option<String>,
+    #[ts(optional)]
+    pub default_prompt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillErrorInfo {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillsListEntry {
+    pub cwd: PathBuf,
+    pub skills: Vec<SkillMetadata>,
+    pub errors: Vec<SkillErrorInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillsConfigWriteParams {
+    pub path: PathBuf,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SkillsConfigWriteResponse {
+    pub effective_enabled: bool,
+}
+
+impl From<CoreSkillMetadata> for SkillMetadata {
+    fn from(value: CoreSkillMetadata) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            short_description: value.short_description,
+            interface: value.interface.map(SkillInterface::from),
+            path: value.path,
+            scope: value.scope.into(),
+            enabled: true,
+        }
+    }
+}
+
+impl From<CoreSkillInterface> for SkillInterface {
+    fn from(value: CoreSkillInterface) -> Self {
+        Self {
+            display_name: value.display_name,
+            short_description: value.short_description,
+            brand_color: value.brand_color,
+            default_prompt: value.default_prompt,
+            icon_small: value.icon_small,
+            icon_large: value.icon_large,
         }
     }
 }
@@ -1480,6 +2295,171 @@ impl From<CoreSkillErrorInfo> for SkillErrorInfo {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentsListParams {
+    /// When empty, defaults to the current session working directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cwds: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentsListResponse {
+    pub data: Vec<SubAgentsListEntry>,
+}
+
+v2_enum_from_core!(
+    pub enum SubAgentSource from codex_protocol::subagents::SubAgentSource {
+        Repo, User, Extra
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentMetadata {
+    pub name: String,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub color: Option<String>,
+    /// Sandbox mode the subagent runs under; absent when it inherits the thread's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sandbox_mode: Option<SandboxMode>,
+    /// Model override; absent when it inherits the thread's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub model: Option<String>,
+    /// Path to the `<name>.md` definition file.
+    pub path: PathBuf,
+    pub source: SubAgentSource,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentErrorInfo {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentsListEntry {
+    pub cwd: PathBuf,
+    /// Search roots scanned for this `cwd`, highest priority first. Roots that
+    /// do not exist yet are included so clients can offer to create them.
+    pub roots: Vec<SubAgentRoot>,
+    pub subagents: Vec<SubAgentMetadata>,
+    pub errors: Vec<SubAgentErrorInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentRoot {
+    pub path: PathBuf,
+    pub source: SubAgentSource,
+}
+
+/// Sent when subagent definitions under the thread's search roots change on disk.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentsUpdatedNotification {
+    pub thread_id: String,
+    pub subagents: Vec<SubAgentMetadata>,
+    pub errors: Vec<SubAgentErrorInfo>,
+}
+
+impl From<CoreSubAgentInfo> for SubAgentMetadata {
+    fn from(value: CoreSubAgentInfo) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            color: value.color,
+            sandbox_mode: value.sandbox_mode.map(SandboxMode::from),
+            model: value.model,
+            path: value.path,
+            source: value.source.into(),
+        }
+    }
+}
+
+impl From<CoreSubAgentLoadError> for SubAgentErrorInfo {
+    fn from(value: CoreSubAgentLoadError) -> Self {
+        Self {
+            path: value.path,
+            message: value.message,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentsRunParams {
+    pub thread_id: String,
+    pub name: String,
+    pub prompt: String,
+    /// Resolve the invocation without spawning the child thread; the result is
+    /// delivered via `subagents/preview`.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentsRunResponse {}
+
+/// Sent in reply to `subagents/run` with `dryRun: true`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentPreviewNotification {
+    pub thread_id: String,
+    pub name: String,
+    /// Path to the `<name>.md` definition file.
+    pub path: PathBuf,
+    /// Model the child thread would use.
+    pub model: String,
+    pub approval_policy: AskForApproval,
+    /// Effective sandbox policy after the definition's `sandboxMode`.
+    pub sandbox_policy: SandboxPolicy,
+    /// Built-in tools exposed to the child thread.
+    pub tools: Vec<String>,
+    /// Enabled MCP servers whose tools the child thread would also receive.
+    pub mcp_servers: Vec<String>,
+    /// Thread developer instructions with the definition body appended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub developer_instructions: Option<String>,
+    pub prompt: String,
+}
+
+impl SubAgentPreviewNotification {
+    pub fn new(thread_id: String, preview: CoreSubAgentPreview) -> Self {
+        Self {
+            thread_id,
+            name: preview.name,
+            path: preview.path,
+            model: preview.model,
+            approval_policy: preview.approval_policy.into(),
+            sandbox_policy: preview.sandbox_policy.into(),
+            tools: preview.tools,
+            mcp_servers: preview.mcp_servers,
+            developer_instructions: preview.developer_instructions,
+            prompt: preview.prompt,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1505,6 +2485,26 @@ pub struct Thread {
     pub source: SessionSource,
     /// Optional Git metadata captured when the thread was created.
     pub git_info: Option<GitInfo>,
+    /// Id of the thread this one was forked from, if it is a branch.
+    pub forked_from_id: Option<String>,
+    /// Name given with `thread/name/set`. Like `tags`, only populated on
+    /// `thread/list`, `thread/search`, and `thread/read` responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub name: Option<String>,
+    /// Tags given with `thread/tags/set`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// CODEZ - Model of the latest turn. Only populated on `thread/read`
+    /// responses when `includeStats` is true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub model: Option<String>,
+    /// CODEZ - Lines changed by the thread's `apply_patch` calls. Only
+    /// populated on `thread/read` responses when `includeStats` is true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub diff_stat: Option<ThreadDiffStat>,
     /// Only populated on `thread/resume`, `thread/rollback`, `thread/fork`, and `thread/read`
     /// (when `includeTurns` is true) responses.
     /// For all other responses and notifications returning a Thread,
@@ -1512,6 +2512,15 @@ pub struct Thread {
     pub turns: Vec<Turn>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadDiffStat {
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1531,6 +2540,61 @@ pub struct ThreadTokenUsageUpdatedNotification {
     pub token_usage: ThreadTokenUsage,
 }
 
+/// Estimated spending in USD, priced with the `[budget.prices]` table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadCostUpdatedNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub turn_usd: f64,
+    pub session_usd: f64,
+    pub max_usd_per_turn: Option<f64>,
+    pub max_usd_per_session: Option<f64>,
+}
+
+/// Provider quota from the `x-ratelimit-*` headers of the latest response,
+/// and while a turn waits out a 429, how long until it retries.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadRateLimitUpdatedNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub quota: Option<ProviderQuota>,
+    #[ts(type = "number | null")]
+    pub resume_in_seconds: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ProviderQuota {
+    #[ts(type = "number | null")]
+    pub limit_requests: Option<i64>,
+    #[ts(type = "number | null")]
+    pub remaining_requests: Option<i64>,
+    #[ts(type = "number | null")]
+    pub limit_tokens: Option<i64>,
+    #[ts(type = "number | null")]
+    pub remaining_tokens: Option<i64>,
+    pub requests_reset_seconds: Option<f64>,
+    pub tokens_reset_seconds: Option<f64>,
+}
+
+impl From<CoreProviderQuota> for ProviderQuota {
+    fn from(value: CoreProviderQuota) -> Self {
+        Self {
+            limit_requests: value.limit_requests,
+            remaining_requests: value.remaining_requests,
+            limit_tokens: value.limit_tokens,
+            remaining_tokens: value.remaining_tokens,
+            requests_reset_seconds: value.requests_reset_seconds,
+            tokens_reset_seconds: value.tokens_reset_seconds,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1540,6 +2604,12 @@ pub struct ThreadTokenUsage {
     // TODO(aibrahim): make this not optional
     #[ts(type = "number | null")]
     pub model_context_window: Option<i64>,
+    /// Session totals per tool, largest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_usage: Vec<ToolTokenUsage>,
+    /// Session totals per subagent definition.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subagent_usage: Vec<SubAgentTokenUsage>,
 }
 
 impl From<CoreTokenUsageInfo> for ThreadTokenUsage {
@@ -1548,6 +2618,54 @@ impl From<CoreTokenUsageInfo> for ThreadTokenUsage {
             total: value.total_token_usage.into(),
             last: value.last_token_usage.into(),
             model_context_window: value.model_context_window,
+            tool_usage: Vec::new(),
+            subagent_usage: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ToolTokenUsage {
+    pub tool_name: String,
+    #[ts(type = "number")]
+    pub calls: u64,
+    /// Estimated prompt tokens the tool's outputs added to the context.
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    /// Completion tokens of the responses that followed the outputs.
+    #[ts(type = "number")]
+    pub follow_up_tokens: i64,
+}
+
+impl From<CoreToolTokenUsage> for ToolTokenUsage {
+    fn from(value: CoreToolTokenUsage) -> Self {
+        Self {
+            tool_name: value.tool_name,
+            calls: value.calls,
+            output_tokens: value.output_tokens,
+            follow_up_tokens: value.follow_up_tokens,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SubAgentTokenUsage {
+    pub name: String,
+    #[ts(type = "number")]
+    pub runs: u64,
+    pub token_usage: TokenUsageBreakdown,
+}
+
+impl From<CoreSubAgentTokenUsage> for SubAgentTokenUsage {
+    fn from(value: CoreSubAgentTokenUsage) -> Self {
+        Self {
+            name: value.name,
+            runs: value.runs,
+            token_usage: value.token_usage.into(),
         }
     }
 }
@@ -1700,6 +2818,17 @@ pub enum ReviewTarget {
         title: Option<String>,
     },
 
+    /// CODEZ - Review the changes in a revision range as understood by
+    /// `git diff`, such as `main..HEAD`.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    Range { range: String },
+
+    /// CODEZ - Review a GitHub pull request given its URL.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    PullRequest { url: String },
+
     /// Arbitrary instructions, equivalent to the old free-form prompt.
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -1816,6 +2945,37 @@ pub enum UserInput {
         name: String,
         path: PathBuf,
     },
+    /// CODEZ - Editor content attached as context: the selected lines, or the
+    /// whole file when no range is set. Recorded after the prompt as an
+    /// `<editor_context>` message rather than as prompt text.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    EditorContext {
+        path: PathBuf,
+        /// 1-based.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        start_line: Option<u32>,
+        /// 1-based, inclusive.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        end_line: Option<u32>,
+        text: String,
+    },
+    /// CODEZ - Output captured from a client terminal, attached as context
+    /// in the same `<editor_context>` message.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    TerminalOutput {
+        terminal: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        command: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        exit_code: Option<i32>,
+        text: String,
+    },
 }
 
 impl UserInput {
@@ -1831,6 +2991,28 @@ impl UserInput {
             UserInput::Image { url } => CoreUserInput::Image { image_url: url },
             UserInput::LocalImage { path } => CoreUserInput::LocalImage { path },
             UserInput::Skill { name, path } => CoreUserInput::Skill { name, path },
+            UserInput::EditorContext {
+                path,
+                start_line,
+                end_line,
+                text,
+            } => CoreUserInput::EditorContext {
+                path,
+                start_line,
+                end_line,
+                text,
+            },
+            UserInput::TerminalOutput {
+                terminal,
+                command,
+                exit_code,
+                text,
+            } => CoreUserInput::TerminalOutput {
+                terminal,
+                command,
+                exit_code,
+                text,
+            },
         }
     }
 }
@@ -1848,6 +3030,28 @@ impl From<CoreUserInput> for UserInput {
             CoreUserInput::Image { image_url } => UserInput::Image { url: image_url },
             CoreUserInput::LocalImage { path } => UserInput::LocalImage { path },
             CoreUserInput::Skill { name, path } => UserInput::Skill { name, path },
+            CoreUserInput::EditorContext {
+                path,
+                start_line,
+                end_line,
+                text,
+            } => UserInput::EditorContext {
+                path,
+                start_line,
+                end_line,
+                text,
+            },
+            CoreUserInput::TerminalOutput {
+                terminal,
+                command,
+                exit_code,
+                text,
+            } => UserInput::TerminalOutput {
+                terminal,
+                command,
+                exit_code,
+                text,
+            },
             _ => unreachable!("unsupported user input variant"),
         }
     }
@@ -2145,6 +3349,37 @@ pub struct Usage {
 pub struct TurnCompletedNotification {
     pub thread_id: String,
     pub turn: Turn,
+    /// CODEZ - Model, timing, retry and token stats of the turn. Null for
+    /// interrupted turns.
+    #[serde(default)]
+    pub metadata: Option<TurnMetadata>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnMetadata {
+    pub model: String,
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Wall-clock time from the start of the turn until it completed.
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+    /// Model requests retried after a stream error or a rate limit.
+    pub retries: u32,
+    /// Tokens used by this turn's model requests only.
+    pub token_usage: TokenUsageBreakdown,
+}
+
+impl From<CoreTurnMetadata> for TurnMetadata {
+    fn from(value: CoreTurnMetadata) -> Self {
+        Self {
+            model: value.model,
+            reasoning_effort: value.reasoning_effort,
+            duration_ms: value.duration_ms,
+            retries: value.retries,
+            token_usage: value.token_usage.into(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -2156,8 +3391,146 @@ pub struct TurnDiffUpdatedNotification {
     pub thread_id: String,
     pub turn_id: String,
     pub diff: String,
+    /// CODEZ - Structured view of `diff`, one entry per changed file.
+    #[serde(default)]
+    pub files: Vec<TurnDiffFile>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnDiffFile {
+    pub path: PathBuf,
+    /// Set only for renames.
+    pub old_path: Option<PathBuf>,
+    pub status: TurnDiffFileStatus,
+    /// Git blob id before the turn; null for added files.
+    pub old_hash: Option<String>,
+    /// Git blob id after the turn; null for deleted files.
+    pub new_hash: Option<String>,
+    pub binary: bool,
+    pub hunks: Vec<TurnDiffHunk>,
+}
+
+impl From<CoreFileDiff> for TurnDiffFile {
+    fn from(value: CoreFileDiff) -> Self {
+        Self {
+            path: value.path,
+            old_path: value.old_path,
+            status: value.status.into(),
+            old_hash: value.old_hash,
+            new_hash: value.new_hash,
+            binary: value.binary,
+            hunks: value.hunks.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+v2_enum_from_core!(
+    pub enum TurnDiffFileStatus from codex_protocol::protocol::FileDiffStatus {
+        Added, Deleted, Modified, Renamed
+    }
+);
+
+/// 1-based line ranges of one `@@` hunk; a zero-length range starts at the
+/// line before the change.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnDiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+}
+
+impl From<CoreDiffHunk> for TurnDiffHunk {
+    fn from(value: CoreDiffHunk) -> Self {
+        Self {
+            old_start: value.old_start,
+            old_lines: value.old_lines,
+            new_start: value.new_start,
+            new_lines: value.new_lines,
+        }
+    }
+}
+
+impl From<TurnDiffHunk> for CoreDiffHunk {
+    fn from(value: TurnDiffHunk) -> Self {
+        Self {
+            old_start: value.old_start,
+            old_lines: value.old_lines,
+            new_start: value.new_start,
+            new_lines: value.new_lines,
+        }
+    }
+}
+
+/// CODEZ - Dry run of a patch against the working tree.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct PatchPreview {
+    /// One entry per file the patch touches, sorted by path.
+    pub files: Vec<PatchPreviewFile>,
+}
+
+impl From<CorePatchPreview> for PatchPreview {
+    fn from(value: CorePatchPreview) -> Self {
+        Self {
+            files: value.files.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct PatchPreviewFile {
+    pub path: PathBuf,
+    /// Destination of a rename.
+    pub move_path: Option<PathBuf>,
+    pub status: TurnDiffFileStatus,
+    /// Placement of each `@@` chunk when some chunk did not match the file as
+    /// written; empty when all of them did.
+    pub hunks: Vec<PatchHunkResult>,
+}
+
+impl From<CorePatchPreviewFile> for PatchPreviewFile {
+    fn from(value: CorePatchPreviewFile) -> Self {
+        Self {
+            path: value.path,
+            move_path: value.move_path,
+            status: value.status.into(),
+            hunks: value.hunks.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct PatchHunkResult {
+    /// 1-based line in the file before the patch where the chunk starts.
+    pub line: usize,
+    pub status: PatchHunkStatus,
+}
+
+impl From<CorePatchHunkResult> for PatchHunkResult {
+    fn from(value: CorePatchHunkResult) -> Self {
+        Self {
+            line: value.line,
+            status: value.status.into(),
+        }
+    }
 }
 
+v2_enum_from_core!(
+    pub enum PatchHunkStatus from codex_protocol::protocol::PatchHunkStatus {
+        Applied, Fuzzy, Merged, AlreadyApplied, Conflict
+    }
+);
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2168,6 +3541,33 @@ pub struct TurnPlanUpdatedNotification {
     pub plan: Vec<TurnPlanStep>,
 }
 
+/// Next steps the agent proposed at the end of a turn.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnFollowUpsSuggestedNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub suggestions: Vec<FollowUpSuggestion>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct FollowUpSuggestion {
+    pub title: String,
+    pub prompt: String,
+}
+
+impl From<CoreFollowUpSuggestion> for FollowUpSuggestion {
+    fn from(value: CoreFollowUpSuggestion) -> Self {
+        Self {
+            title: value.title,
+            prompt: value.prompt,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2346,6 +3746,15 @@ pub struct ContextCompactedNotification {
     pub turn_id: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct FileRestoredNotification {
+    pub thread_id: String,
+    pub success: bool,
+    pub message: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2369,6 +3778,93 @@ pub struct CommandExecutionRequestApprovalParams {
     pub command_actions: Option<Vec<CommandAction>>,
     /// Optional proposed execpolicy amendment to allow similar commands without prompting.
     pub proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
+    /// One-line plain-language description of what the command does, when
+    /// the `approval_explanations` feature is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub explanation: Option<String>,
+    /// CODEZ - Sandbox policy the command would run under if approved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sandbox_policy: Option<SandboxPolicy>,
+    /// CODEZ - Coarse risk classification of the command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub risk: Option<ApprovalRisk>,
+    /// CODEZ - Most restrictive `[[command_rules]]` entry matching the command.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub matched_rule: Option<CommandRuleMatch>,
+    /// CODEZ - What the platform sandbox enforces on this machine. Absent when
+    /// the command would not run sandboxed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sandbox: Option<SandboxCapabilities>,
+}
+
+v2_enum_from_core!(
+    pub enum ApprovalRisk from codex_protocol::protocol::ApprovalRisk {
+        Low, Medium, High
+    }
+);
+
+v2_enum_from_core!(
+    pub enum CommandRuleDecision from codex_protocol::protocol::CommandRuleDecision {
+        AutoApprove, AlwaysAsk, AutoDeny
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandRuleMatch {
+    /// The rule's `name`, or its program and argument patterns when unnamed.
+    pub rule: String,
+    pub decision: CommandRuleDecision,
+    /// File that defines the rule.
+    pub source: PathBuf,
+    pub reason: Option<String>,
+}
+
+impl From<codex_protocol::protocol::CommandRuleMatch> for CommandRuleMatch {
+    fn from(value: codex_protocol::protocol::CommandRuleMatch) -> Self {
+        Self {
+            rule: value.rule,
+            decision: value.decision.into(),
+            source: value.source,
+            reason: value.reason,
+        }
+    }
+}
+
+v2_enum_from_core!(
+    pub enum SandboxBackend from codex_protocol::protocol::SandboxBackend {
+        None, MacosSeatbelt, LinuxLandlock, WindowsRestrictedToken, WindowsElevated, Container
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SandboxCapabilities {
+    pub backend: SandboxBackend,
+    /// Writes outside the policy's writable roots are blocked.
+    pub enforces_writable_roots: bool,
+    /// Outbound network is blocked when the policy disables it.
+    pub enforces_network: bool,
+    /// Gaps in enforcement and how to close them, for display.
+    pub warnings: Vec<String>,
+}
+
+impl From<codex_protocol::protocol::SandboxCapabilities> for SandboxCapabilities {
+    fn from(value: codex_protocol::protocol::SandboxCapabilities) -> Self {
+        Self {
+            backend: value.backend.into(),
+            enforces_writable_roots: value.enforces_writable_roots,
+            enforces_network: value.enforces_network,
+            warnings: value.warnings,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -2390,6 +3886,23 @@ pub struct FileChangeRequestApprovalParams {
     /// [UNSTABLE] When set, the agent is asking the user to allow writes under this root
     /// for the remainder of the session (unclear if this is honored today).
     pub grant_root: Option<PathBuf>,
+    /// CODEZ - Structured view of the proposed changes, one entry per file.
+    /// `newHash` is null for updates since the result is not known yet.
+    #[serde(default)]
+    pub files: Vec<TurnDiffFile>,
+    /// CODEZ - Sandbox policy the patch would be applied under if approved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sandbox_policy: Option<SandboxPolicy>,
+    /// CODEZ - Coarse risk classification of the patch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub risk: Option<ApprovalRisk>,
+    /// CODEZ - Dry run of the patch against the working tree, when the
+    /// `patch_preview` feature is on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub preview: Option<PatchPreview>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -2398,6 +3911,97 @@ pub struct FileChangeRequestApprovalResponse {
     pub decision: FileChangeApprovalDecision,
 }
 
+v2_enum_from_core!(
+    pub enum PlanRisk from codex_protocol::plan_tool::PlanRisk {
+        Low, Medium, High
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ProposedPlan {
+    pub summary: String,
+    pub steps: Vec<String>,
+    pub affected_files: Vec<String>,
+    pub risk: PlanRisk,
+    pub risk_notes: Option<String>,
+}
+
+impl From<CoreProposedPlan> for ProposedPlan {
+    fn from(value: CoreProposedPlan) -> Self {
+        Self {
+            summary: value.summary,
+            steps: value.steps,
+            affected_files: value.affected_files,
+            risk: value.risk.into(),
+            risk_notes: value.risk_notes,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct PlanRequestApprovalParams {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// The `submit_plan` call id.
+    pub item_id: String,
+    pub plan: ProposedPlan,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum PlanApprovalDecision {
+    /// User approved the plan; commands and file changes are unlocked for the turn.
+    Accept,
+    /// User declined the plan. The agent will continue the turn without write access.
+    Decline,
+    /// User declined the plan. The turn will also be immediately interrupted.
+    Cancel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[ts(export_to = "v2/")]
+pub struct PlanRequestApprovalResponse {
+    pub decision: PlanApprovalDecision,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpToolCallRequestApprovalParams {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// The MCP tool call item id.
+    pub item_id: String,
+    pub server: String,
+    pub tool: String,
+    pub arguments: JsonValue,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum McpToolCallApprovalDecision {
+    /// User approved the tool call.
+    Accept,
+    /// User approved the tool call and future calls to the same tool should run without prompting.
+    AcceptForSession,
+    /// User denied the tool call. The agent will continue the turn.
+    Decline,
+    /// User denied the tool call. The turn will also be immediately interrupted.
+    Cancel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[ts(export_to = "v2/")]
+pub struct McpToolCallRequestApprovalResponse {
+    pub decision: McpToolCallApprovalDecision,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2562,6 +4166,19 @@ pub struct ConfigWarningNotification {
     pub details: Option<String>,
 }
 
+/// Sent when the thread's checked-out git branch changed and the
+/// `[branches]` config overrides were re-resolved for it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigReloadedNotification {
+    pub thread_id: String,
+    /// Branch now checked out, or null on a detached HEAD.
+    pub branch: Option<String>,
+    pub approval_policy: AskForApproval,
+    pub sandbox_policy: SandboxPolicy,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2713,6 +4330,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn subagent_metadata_serializes_in_camel_case() {
+        let metadata = SubAgentMetadata::from(CoreSubAgentInfo {
+            name: "reviewer".to_string(),
+            description: "Reviews diffs".to_string(),
+            color: None,
+            sandbox_mode: Some(CoreSandboxMode::ReadOnly),
+            model: None,
+            path: PathBuf::from("/repo/.codex/agents/reviewer.md"),
+            source: codex_protocol::subagents::SubAgentSource::Repo,
+        });
+
+        assert_eq!(
+            serde_json::to_value(metadata).unwrap(),
+            json!({
+                "name": "reviewer",
+                "description": "Reviews diffs",
+                "sandboxMode": "read-only",
+                "path": "/repo/.codex/agents/reviewer.md",
+                "source": "repo",
+            }),
+        );
+    }
+
     #[test]
     fn codex_error_info_serializes_http_status_code_in_camel_case() {
         let value = CodexErrorInfo::ResponseTooManyFailedAttempts {
@@ -16,6 +16,7 @@ use anyhow::bail;
 use clap::ArgAction;
 use clap::Parser;
 use clap::Subcommand;
+use codex_app_server_protocol::APP_SERVER_PROTOCOL_VERSION;
 use codex_app_server_protocol::AddConversationListenerParams;
 use codex_app_server_protocol::AddConversationSubscriptionResponse;
 use codex_app_server_protocol::AskForApproval;
@@ -419,6 +420,8 @@ impl CodexClient {
                     title: Some("Codex Toy App Server".to_string()),
                     version: env!("CARGO_PKG_VERSION").to_string(),
                 },
+                protocol_version: Some(APP_SERVER_PROTOCOL_VERSION),
+                capabilities: None,
             },
         };
 
@@ -846,6 +849,11 @@ impl CodexClient {
             cwd,
             command_actions,
             proposed_execpolicy_amendment,
+            explanation,
+            sandbox_policy,
+            risk,
+            matched_rule,
+            sandbox,
         } = params;
 
         println!(
@@ -857,6 +865,9 @@ impl CodexClient {
         if let Some(command) = command.as_deref() {
             println!("< command: {command}");
         }
+        if let Some(explanation) = explanation.as_deref() {
+            println!("< explanation: {explanation}");
+        }
         if let Some(cwd) = cwd.as_ref() {
             println!("< cwd: {}", cwd.display());
         }
@@ -868,6 +879,18 @@ impl CodexClient {
         if let Some(execpolicy_amendment) = proposed_execpolicy_amendment.as_ref() {
             println!("< proposed execpolicy amendment: {execpolicy_amendment:?}");
         }
+        if let Some(sandbox_policy) = sandbox_policy.as_ref() {
+            println!("< sandbox policy: {sandbox_policy:?}");
+        }
+        if let Some(risk) = risk {
+            println!("< risk: {risk:?}");
+        }
+        if let Some(matched_rule) = matched_rule.as_ref() {
+            println!("< matched rule: {matched_rule:?}");
+        }
+        if let Some(sandbox) = sandbox.as_ref() {
+            println!("< sandbox: {sandbox:?}");
+        }
 
         let response = CommandExecutionRequestApprovalResponse {
             decision: CommandExecutionApprovalDecision::Accept,
@@ -888,6 +911,10 @@ impl CodexClient {
             item_id,
             reason,
             grant_root,
+            files,
+            sandbox_policy,
+            risk,
+            preview,
         } = params;
 
         println!(
@@ -899,6 +926,30 @@ impl CodexClient {
         if let Some(grant_root) = grant_root.as_deref() {
             println!("< grant root: {}", grant_root.display());
         }
+        for file in &files {
+            println!(
+                "< {:?} {} ({} hunks)",
+                file.status,
+                file.path.display(),
+                file.hunks.len()
+            );
+        }
+        if let Some(sandbox_policy) = sandbox_policy.as_ref() {
+            println!("< sandbox policy: {sandbox_policy:?}");
+        }
+        if let Some(risk) = risk {
+            println!("< risk: {risk:?}");
+        }
+        if let Some(preview) = preview {
+            for file in &preview.files {
+                println!(
+                    "< preview: {:?} {} ({} chunks reported)",
+                    file.status,
+                    file.path.display(),
+                    file.hunks.len()
+                );
+            }
+        }
 
         let response = FileChangeRequestApprovalResponse {
             decision: FileChangeApprovalDecision::Accept,
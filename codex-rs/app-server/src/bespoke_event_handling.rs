@@ -13,6 +13,7 @@ use codex_app_server_protocol::AccountRateLimitsUpdatedNotification;
 use codex_app_server_protocol::AgentMessageDeltaNotification;
 use codex_app_server_protocol::ApplyPatchApprovalParams;
 use codex_app_server_protocol::ApplyPatchApprovalResponse;
+use codex_app_server_protocol::ApprovalRisk as V2ApprovalRisk;
 use codex_app_server_protocol::AskUserQuestion;
 use codex_app_server_protocol::AskUserQuestionOption;
 use codex_app_server_protocol::AskUserQuestionParams;
@@ -29,6 +30,8 @@ use codex_app_server_protocol::CommandExecutionOutputDeltaNotification;
 use codex_app_server_protocol::CommandExecutionRequestApprovalParams;
 use codex_app_server_protocol::CommandExecutionRequestApprovalResponse;
 use codex_app_server_protocol::CommandExecutionStatus;
+use codex_app_server_protocol::CommandRuleMatch as V2CommandRuleMatch;
+use codex_app_server_protocol::ConfigReloadedNotification;
 use codex_app_server_protocol::ContextCompactedNotification;
 use codex_app_server_protocol::DeprecationNoticeNotification;
 use codex_app_server_protocol::ErrorNotification;
@@ -39,32 +42,56 @@ use codex_app_server_protocol::FileChangeApprovalDecision;
 use codex_app_server_protocol::FileChangeOutputDeltaNotification;
 use codex_app_server_protocol::FileChangeRequestApprovalParams;
 use codex_app_server_protocol::FileChangeRequestApprovalResponse;
+use codex_app_server_protocol::FileRestoredNotification;
 use codex_app_server_protocol::FileUpdateChange;
 use codex_app_server_protocol::InterruptConversationResponse;
 use codex_app_server_protocol::ItemCompletedNotification;
 use codex_app_server_protocol::ItemStartedNotification;
 use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::McpToolCallApprovalDecision;
 use codex_app_server_protocol::McpToolCallError;
+use codex_app_server_protocol::McpToolCallRequestApprovalParams;
+use codex_app_server_protocol::McpToolCallRequestApprovalResponse;
 use codex_app_server_protocol::McpToolCallResult;
 use codex_app_server_protocol::McpToolCallStatus;
 use codex_app_server_protocol::PatchApplyStatus;
 use codex_app_server_protocol::PatchChangeKind as V2PatchChangeKind;
+use codex_app_server_protocol::PatchPreview as V2PatchPreview;
+use codex_app_server_protocol::PlanApprovalDecision;
+use codex_app_server_protocol::PlanRequestApprovalParams;
+use codex_app_server_protocol::PlanRequestApprovalResponse;
 use codex_app_server_protocol::RawResponseItemCompletedNotification;
 use codex_app_server_protocol::ReasoningSummaryPartAddedNotification;
 use codex_app_server_protocol::ReasoningSummaryTextDeltaNotification;
 use codex_app_server_protocol::ReasoningTextDeltaNotification;
+use codex_app_server_protocol::SandboxCapabilities as V2SandboxCapabilities;
+use codex_app_server_protocol::SandboxPolicy as V2SandboxPolicy;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::ServerRequestPayload;
+use codex_app_server_protocol::SubAgentPreviewNotification;
+use codex_app_server_protocol::SubAgentsUpdatedNotification;
 use codex_app_server_protocol::TerminalInteractionNotification;
+use codex_app_server_protocol::ThreadContextInspectedNotification;
+use codex_app_server_protocol::ThreadCostUpdatedNotification;
 use codex_app_server_protocol::ThreadItem;
+use codex_app_server_protocol::ThreadMcpToolsChangedNotification;
+use codex_app_server_protocol::ThreadNextEditSuggestedNotification;
+use codex_app_server_protocol::ThreadPinnedUpdatedNotification;
+use codex_app_server_protocol::ThreadPlanModeUpdatedNotification;
+use codex_app_server_protocol::ThreadProcessesUpdatedNotification;
+use codex_app_server_protocol::ThreadQueueUpdatedNotification;
+use codex_app_server_protocol::ThreadRateLimitUpdatedNotification;
 use codex_app_server_protocol::ThreadRollbackResponse;
 use codex_app_server_protocol::ThreadTokenUsage;
 use codex_app_server_protocol::ThreadTokenUsageUpdatedNotification;
 use codex_app_server_protocol::Turn;
 use codex_app_server_protocol::TurnCompletedNotification;
+use codex_app_server_protocol::TurnDiffFile;
 use codex_app_server_protocol::TurnDiffUpdatedNotification;
 use codex_app_server_protocol::TurnError;
+use codex_app_server_protocol::TurnFollowUpsSuggestedNotification;
 use codex_app_server_protocol::TurnInterruptResponse;
+use codex_app_server_protocol::TurnMetadata as V2TurnMetadata;
 use codex_app_server_protocol::TurnPlanStep;
 use codex_app_server_protocol::TurnPlanUpdatedNotification;
 use codex_app_server_protocol::TurnStatus;
@@ -78,12 +105,18 @@ use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::FileChange as CoreFileChange;
+use codex_core::protocol::ListPinnedContextResponseEvent;
+use codex_core::protocol::McpToolCallApprovalRequestEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
+use codex_core::protocol::PinnedContextUpdatedEvent;
+use codex_core::protocol::PlanApprovalRequestEvent;
 use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::TokenCountEvent;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::TurnMetadata as CoreTurnMetadata;
+use codex_core::protocol::UndoKind;
 use codex_core::review_format::format_review_findings_block;
 use codex_core::review_prompts;
 use codex_protocol::ThreadId;
@@ -117,10 +150,11 @@ pub(crate) async fn apply_bespoke_event_handling(
         msg,
     } = event;
     match msg {
-        EventMsg::TurnComplete(_ev) => {
+        EventMsg::TurnComplete(ev) => {
             handle_turn_complete(
                 conversation_id,
                 event_turn_id,
+                ev.metadata,
                 &outgoing,
                 &turn_summary_store,
             )
@@ -132,6 +166,10 @@ pub(crate) async fn apply_bespoke_event_handling(
             changes,
             reason,
             grant_root,
+            files,
+            sandbox_policy,
+            risk,
+            preview,
         }) => match api_version {
             ApiVersion::V1 => {
                 let params = ApplyPatchApprovalParams {
@@ -181,6 +219,10 @@ pub(crate) async fn apply_bespoke_event_handling(
                     item_id: item_id.clone(),
                     reason,
                     grant_root,
+                    files: files.into_iter().map(TurnDiffFile::from).collect(),
+                    sandbox_policy: sandbox_policy.map(V2SandboxPolicy::from),
+                    risk: risk.map(V2ApprovalRisk::from),
+                    preview: preview.map(V2PatchPreview::from),
                 };
                 let rx = outgoing
                     .send_request(ServerRequestPayload::FileChangeRequestApproval(params))
@@ -200,6 +242,73 @@ pub(crate) async fn apply_bespoke_event_handling(
                 });
             }
         },
+        EventMsg::PlanApprovalRequest(PlanApprovalRequestEvent {
+            call_id,
+            turn_id,
+            plan,
+        }) => match api_version {
+            ApiVersion::V1 => {
+                error!("plan approval is not supported on the v1 API; declining");
+                if let Err(err) = conversation
+                    .submit(Op::PlanApproval {
+                        id: event_turn_id,
+                        decision: ReviewDecision::Denied,
+                    })
+                    .await
+                {
+                    error!("failed to submit PlanApproval: {err}");
+                }
+            }
+            ApiVersion::V2 => {
+                let params = PlanRequestApprovalParams {
+                    thread_id: conversation_id.to_string(),
+                    turn_id,
+                    item_id: call_id,
+                    plan: plan.into(),
+                };
+                let rx = outgoing
+                    .send_request(ServerRequestPayload::PlanRequestApproval(params))
+                    .await;
+                tokio::spawn(async move {
+                    on_plan_request_approval_response(event_turn_id, rx, conversation).await;
+                });
+            }
+        },
+        EventMsg::McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent {
+            call_id,
+            turn_id,
+            invocation,
+        }) => match api_version {
+            ApiVersion::V1 => {
+                error!("MCP tool approval is not supported on the v1 API; declining");
+                if let Err(err) = conversation
+                    .submit(Op::McpToolCallApproval {
+                        id: event_turn_id,
+                        decision: ReviewDecision::Denied,
+                    })
+                    .await
+                {
+                    error!("failed to submit McpToolCallApproval: {err}");
+                }
+            }
+            ApiVersion::V2 => {
+                let params = McpToolCallRequestApprovalParams {
+                    thread_id: conversation_id.to_string(),
+                    turn_id,
+                    item_id: call_id,
+                    server: invocation.server,
+                    tool: invocation.tool,
+                    arguments: invocation.arguments.unwrap_or(JsonValue::Null),
+                };
+                let rx = outgoing
+                    .send_request(ServerRequestPayload::McpToolCallRequestApproval(params))
+                    .await;
+                tokio::spawn(async move {
+                    on_mcp_tool_call_request_approval_response(event_turn_id, rx, conversation)
+                        .await;
+                });
+            }
+        },
         EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
             call_id,
             turn_id,
@@ -208,6 +317,11 @@ pub(crate) async fn apply_bespoke_event_handling(
             reason,
             proposed_execpolicy_amendment,
             parsed_cmd,
+            explanation,
+            sandbox_policy,
+            risk,
+            matched_rule,
+            sandbox,
         }) => match api_version {
             ApiVersion::V1 => {
                 let params = ExecCommandApprovalParams {
@@ -247,6 +361,11 @@ pub(crate) async fn apply_bespoke_event_handling(
                     cwd: Some(cwd.clone()),
                     command_actions: Some(command_actions.clone()),
                     proposed_execpolicy_amendment: proposed_execpolicy_amendment_v2,
+                    explanation,
+                    sandbox_policy: sandbox_policy.map(V2SandboxPolicy::from),
+                    risk: risk.map(V2ApprovalRisk::from),
+                    matched_rule: matched_rule.map(V2CommandRuleMatch::from),
+                    sandbox: sandbox.map(V2SandboxCapabilities::from),
                 };
                 let rx = outgoing
                     .send_request(ServerRequestPayload::CommandExecutionRequestApproval(
@@ -602,6 +721,18 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .send_server_notification(ServerNotification::ContextCompacted(notification))
                 .await;
         }
+        EventMsg::UndoCompleted(event)
+            if matches!(event.kind, UndoKind::RestoreFile | UndoKind::RevertHunks) =>
+        {
+            let notification = FileRestoredNotification {
+                thread_id: conversation_id.to_string(),
+                success: event.success,
+                message: event.message,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::FileRestored(notification))
+                .await;
+        }
         EventMsg::DeprecationNotice(event) => {
             let notification = DeprecationNoticeNotification {
                 summary: event.summary,
@@ -611,6 +742,102 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .send_server_notification(ServerNotification::DeprecationNotice(notification))
                 .await;
         }
+        EventMsg::ConfigReloaded(event) => {
+            let notification = ConfigReloadedNotification {
+                thread_id: conversation_id.to_string(),
+                branch: event.branch,
+                approval_policy: event.approval_policy.into(),
+                sandbox_policy: event.sandbox_policy.into(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ConfigReloaded(notification))
+                .await;
+        }
+        EventMsg::SubAgentPreview(preview) => {
+            let notification =
+                SubAgentPreviewNotification::new(conversation_id.to_string(), preview);
+            outgoing
+                .send_server_notification(ServerNotification::SubAgentPreview(notification))
+                .await;
+        }
+        EventMsg::PlanModeUpdated(event) => {
+            let notification = ThreadPlanModeUpdatedNotification {
+                thread_id: conversation_id.to_string(),
+                enabled: event.enabled,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadPlanModeUpdated(notification))
+                .await;
+        }
+        EventMsg::BackgroundProcessesUpdated(event) => {
+            let notification = ThreadProcessesUpdatedNotification {
+                thread_id: conversation_id.to_string(),
+                processes: event.processes.into_iter().map(Into::into).collect(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadProcessesUpdated(notification))
+                .await;
+        }
+        EventMsg::QueuedUserInputs(event) => {
+            let notification = ThreadQueueUpdatedNotification {
+                thread_id: conversation_id.to_string(),
+                inputs: event.inputs.into_iter().map(Into::into).collect(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadQueueUpdated(notification))
+                .await;
+        }
+        EventMsg::PinnedContextUpdated(PinnedContextUpdatedEvent { items })
+        | EventMsg::ListPinnedContextResponse(ListPinnedContextResponseEvent { items }) => {
+            let notification = ThreadPinnedUpdatedNotification {
+                thread_id: conversation_id.to_string(),
+                items: items.into_iter().map(Into::into).collect(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadPinnedUpdated(notification))
+                .await;
+        }
+        EventMsg::McpToolsChanged(event) => {
+            let notification = ThreadMcpToolsChangedNotification {
+                thread_id: conversation_id.to_string(),
+                server: event.server,
+                change: event.change.into(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadMcpToolsChanged(notification))
+                .await;
+        }
+        EventMsg::ContextInspection(event) => {
+            let notification = ThreadContextInspectedNotification {
+                thread_id: conversation_id.to_string(),
+                items: event.items.into_iter().map(Into::into).collect(),
+                total_tokens: event.total_tokens,
+                context_window: event.context_window,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadContextInspected(notification))
+                .await;
+        }
+        EventMsg::NextEditSuggestion(event) => {
+            let notification = ThreadNextEditSuggestedNotification {
+                thread_id: conversation_id.to_string(),
+                request_id: event.request_id,
+                text: event.text,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadNextEditSuggested(notification))
+                .await;
+        }
+        EventMsg::SubAgentsUpdated(event) => {
+            let notification = SubAgentsUpdatedNotification {
+                thread_id: conversation_id.to_string(),
+                subagents: event.subagents.into_iter().map(Into::into).collect(),
+                errors: event.errors.into_iter().map(Into::into).collect(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::SubAgentsUpdated(notification))
+                .await;
+        }
         EventMsg::ReasoningContentDelta(event) => {
             let notification = ReasoningSummaryTextDeltaNotification {
                 thread_id: conversation_id.to_string(),
@@ -654,6 +881,30 @@ pub(crate) async fn apply_bespoke_event_handling(
             handle_token_count_event(conversation_id, event_turn_id, token_count_event, &outgoing)
                 .await;
         }
+        EventMsg::CostUpdate(event) => {
+            let notification = ThreadCostUpdatedNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_turn_id,
+                turn_usd: event.turn_usd,
+                session_usd: event.session_usd,
+                max_usd_per_turn: event.max_usd_per_turn,
+                max_usd_per_session: event.max_usd_per_session,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadCostUpdated(notification))
+                .await;
+        }
+        EventMsg::ProviderRateLimit(event) => {
+            let notification = ThreadRateLimitUpdatedNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_turn_id,
+                quota: event.quota.map(Into::into),
+                resume_in_seconds: event.resume_in_seconds,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadRateLimitUpdated(notification))
+                .await;
+        }
         EventMsg::Error(ev) => {
             let message = ev.message.clone();
             let codex_error_info = ev.codex_error_info.clone();
@@ -1100,6 +1351,20 @@ pub(crate) async fn apply_bespoke_event_handling(
             )
             .await;
         }
+        EventMsg::FollowUpSuggestions(event) => {
+            if let ApiVersion::V2 = api_version {
+                let notification = TurnFollowUpsSuggestedNotification {
+                    thread_id: conversation_id.to_string(),
+                    turn_id: event_turn_id,
+                    suggestions: event.suggestions.into_iter().map(Into::into).collect(),
+                };
+                outgoing
+                    .send_server_notification(ServerNotification::TurnFollowUpsSuggested(
+                        notification,
+                    ))
+                    .await;
+            }
+        }
 
         _ => {}
     }
@@ -1117,6 +1382,11 @@ async fn handle_turn_diff(
             thread_id: conversation_id.to_string(),
             turn_id: event_turn_id.to_string(),
             diff: turn_diff_event.unified_diff,
+            files: turn_diff_event
+                .files
+                .into_iter()
+                .map(TurnDiffFile::from)
+                .collect(),
         };
         outgoing
             .send_server_notification(ServerNotification::TurnDiffUpdated(notification))
@@ -1153,6 +1423,7 @@ async fn emit_turn_completed_with_status(
     event_turn_id: String,
     status: TurnStatus,
     error: Option<TurnError>,
+    metadata: Option<CoreTurnMetadata>,
     outgoing: &OutgoingMessageSender,
 ) {
     let notification = TurnCompletedNotification {
@@ -1163,6 +1434,7 @@ async fn emit_turn_completed_with_status(
             error,
             status,
         },
+        metadata: metadata.map(V2TurnMetadata::from),
     };
     outgoing
         .send_server_notification(ServerNotification::TurnCompleted(notification))
@@ -1265,6 +1537,7 @@ async fn find_and_remove_turn_summary(
 async fn handle_turn_complete(
     conversation_id: ThreadId,
     event_turn_id: String,
+    metadata: Option<CoreTurnMetadata>,
     outgoing: &OutgoingMessageSender,
     turn_summary_store: &TurnSummaryStore,
 ) {
@@ -1275,7 +1548,15 @@ async fn handle_turn_complete(
         None => (TurnStatus::Completed, None),
     };
 
-    emit_turn_completed_with_status(conversation_id, event_turn_id, status, error, outgoing).await;
+    emit_turn_completed_with_status(
+        conversation_id,
+        event_turn_id,
+        status,
+        error,
+        metadata,
+        outgoing,
+    )
+    .await;
 }
 
 async fn handle_turn_interrupted(
@@ -1291,6 +1572,7 @@ async fn handle_turn_interrupted(
         event_turn_id,
         TurnStatus::Interrupted,
         None,
+        None,
         outgoing,
     )
     .await;
@@ -1327,8 +1609,15 @@ async fn handle_token_count_event(
     token_count_event: TokenCountEvent,
     outgoing: &OutgoingMessageSender,
 ) {
-    let TokenCountEvent { info, rate_limits } = token_count_event;
-    if let Some(token_usage) = info.map(ThreadTokenUsage::from) {
+    let TokenCountEvent {
+        info,
+        rate_limits,
+        tool_usage,
+        subagent_usage,
+    } = token_count_event;
+    if let Some(mut token_usage) = info.map(ThreadTokenUsage::from) {
+        token_usage.tool_usage = tool_usage.into_iter().map(Into::into).collect();
+        token_usage.subagent_usage = subagent_usage.into_iter().map(Into::into).collect();
         let notification = ThreadTokenUsageUpdatedNotification {
             thread_id: conversation_id.to_string(),
             turn_id,
@@ -1651,6 +1940,81 @@ async fn on_file_change_request_approval_response(
     }
 }
 
+async fn on_plan_request_approval_response(
+    event_turn_id: String,
+    receiver: oneshot::Receiver<JsonValue>,
+    codex: Arc<CodexThread>,
+) {
+    let decision = match receiver.await {
+        Ok(value) => {
+            let response = serde_json::from_value::<PlanRequestApprovalResponse>(value)
+                .unwrap_or_else(|err| {
+                    error!("failed to deserialize PlanRequestApprovalResponse: {err}");
+                    PlanRequestApprovalResponse {
+                        decision: PlanApprovalDecision::Decline,
+                    }
+                });
+            match response.decision {
+                PlanApprovalDecision::Accept => ReviewDecision::Approved,
+                PlanApprovalDecision::Decline => ReviewDecision::Denied,
+                PlanApprovalDecision::Cancel => ReviewDecision::Abort,
+            }
+        }
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Denied
+        }
+    };
+
+    if let Err(err) = codex
+        .submit(Op::PlanApproval {
+            id: event_turn_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit PlanApproval: {err}");
+    }
+}
+
+async fn on_mcp_tool_call_request_approval_response(
+    event_turn_id: String,
+    receiver: oneshot::Receiver<JsonValue>,
+    codex: Arc<CodexThread>,
+) {
+    let decision = match receiver.await {
+        Ok(value) => {
+            let response = serde_json::from_value::<McpToolCallRequestApprovalResponse>(value)
+                .unwrap_or_else(|err| {
+                    error!("failed to deserialize McpToolCallRequestApprovalResponse: {err}");
+                    McpToolCallRequestApprovalResponse {
+                        decision: McpToolCallApprovalDecision::Decline,
+                    }
+                });
+            match response.decision {
+                McpToolCallApprovalDecision::Accept => ReviewDecision::Approved,
+                McpToolCallApprovalDecision::AcceptForSession => ReviewDecision::ApprovedForSession,
+                McpToolCallApprovalDecision::Decline => ReviewDecision::Denied,
+                McpToolCallApprovalDecision::Cancel => ReviewDecision::Abort,
+            }
+        }
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Denied
+        }
+    };
+
+    if let Err(err) = codex
+        .submit(Op::McpToolCallApproval {
+            id: event_turn_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit McpToolCallApproval: {err}");
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn on_command_execution_request_approval_response(
     event_turn_id: String,
@@ -1810,8 +2174,13 @@ mod tests {
     use anyhow::Result;
     use anyhow::anyhow;
     use anyhow::bail;
+    use codex_app_server_protocol::TurnDiffFileStatus;
+    use codex_app_server_protocol::TurnDiffHunk;
     use codex_app_server_protocol::TurnPlanStepStatus;
     use codex_core::protocol::CreditsSnapshot;
+    use codex_core::protocol::DiffHunk;
+    use codex_core::protocol::FileDiff;
+    use codex_core::protocol::FileDiffStatus;
     use codex_core::protocol::McpInvocation;
     use codex_core::protocol::RateLimitSnapshot;
     use codex_core::protocol::RateLimitWindow;
@@ -1877,9 +2246,23 @@ mod tests {
         let outgoing = Arc::new(OutgoingMessageSender::new(tx));
         let turn_summary_store = new_turn_summary_store();
 
+        let metadata = CoreTurnMetadata {
+            model: "gpt-5".to_string(),
+            reasoning_effort: None,
+            duration_ms: 1_500,
+            retries: 1,
+            token_usage: TokenUsage {
+                input_tokens: 100,
+                cached_input_tokens: 0,
+                output_tokens: 20,
+                reasoning_output_tokens: 0,
+                total_tokens: 120,
+            },
+        };
         handle_turn_complete(
             conversation_id,
             event_turn_id.clone(),
+            Some(metadata.clone()),
             &outgoing,
             &turn_summary_store,
         )
@@ -1894,6 +2277,7 @@ mod tests {
                 assert_eq!(n.turn.id, event_turn_id);
                 assert_eq!(n.turn.status, TurnStatus::Completed);
                 assert_eq!(n.turn.error, None);
+                assert_eq!(n.metadata, Some(V2TurnMetadata::from(metadata)));
             }
             other => bail!("unexpected message: {other:?}"),
         }
@@ -1964,6 +2348,7 @@ mod tests {
         handle_turn_complete(
             conversation_id,
             event_turn_id.clone(),
+            None,
             &outgoing,
             &turn_summary_store,
         )
@@ -2087,6 +2472,8 @@ mod tests {
             TokenCountEvent {
                 info: Some(info),
                 rate_limits: Some(rate_limits),
+                tool_usage: Vec::new(),
+                subagent_usage: Vec::new(),
             },
             &outgoing,
         )
@@ -2140,6 +2527,8 @@ mod tests {
             TokenCountEvent {
                 info: None,
                 rate_limits: None,
+                tool_usage: Vec::new(),
+                subagent_usage: Vec::new(),
             },
             &outgoing,
         )
@@ -2215,6 +2604,7 @@ mod tests {
         handle_turn_complete(
             conversation_a,
             a_turn1.clone(),
+            None,
             &outgoing,
             &turn_summary_store,
         )
@@ -2235,6 +2625,7 @@ mod tests {
         handle_turn_complete(
             conversation_b,
             b_turn1.clone(),
+            None,
             &outgoing,
             &turn_summary_store,
         )
@@ -2245,6 +2636,7 @@ mod tests {
         handle_turn_complete(
             conversation_a,
             a_turn2.clone(),
+            None,
             &outgoing,
             &turn_summary_store,
         )
@@ -2456,6 +2848,20 @@ mod tests {
             "turn-1",
             TurnDiffEvent {
                 unified_diff: unified_diff.clone(),
+                files: vec![FileDiff {
+                    path: PathBuf::from("a"),
+                    old_path: None,
+                    status: FileDiffStatus::Modified,
+                    old_hash: Some("1".repeat(40)),
+                    new_hash: Some("2".repeat(40)),
+                    binary: false,
+                    hunks: vec![DiffHunk {
+                        old_start: 1,
+                        old_lines: 1,
+                        new_start: 1,
+                        new_lines: 2,
+                    }],
+                }],
             },
             ApiVersion::V2,
             &outgoing,
@@ -2473,6 +2879,23 @@ mod tests {
                 assert_eq!(notification.thread_id, conversation_id.to_string());
                 assert_eq!(notification.turn_id, "turn-1");
                 assert_eq!(notification.diff, unified_diff);
+                assert_eq!(
+                    notification.files,
+                    vec![TurnDiffFile {
+                        path: PathBuf::from("a"),
+                        old_path: None,
+                        status: TurnDiffFileStatus::Modified,
+                        old_hash: Some("1".repeat(40)),
+                        new_hash: Some("2".repeat(40)),
+                        binary: false,
+                        hunks: vec![TurnDiffHunk {
+                            old_start: 1,
+                            old_lines: 1,
+                            new_start: 1,
+                            new_lines: 2,
+                        }],
+                    }]
+                );
             }
             other => bail!("unexpected message: {other:?}"),
         }
@@ -2491,6 +2914,7 @@ mod tests {
             "turn-1",
             TurnDiffEvent {
                 unified_diff: "diff".to_string(),
+                files: Vec::new(),
             },
             ApiVersion::V1,
             &outgoing,
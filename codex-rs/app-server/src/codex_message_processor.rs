@@ -5,6 +5,7 @@ use crate::fuzzy_file_search::run_fuzzy_file_search;
 use crate::models::supported_models;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotification;
+use crate::thread_events::ThreadEventHub;
 use chrono::DateTime;
 use chrono::SecondsFormat;
 use chrono::Utc;
@@ -24,6 +25,8 @@ use codex_app_server_protocol::CancelLoginAccountParams;
 use codex_app_server_protocol::CancelLoginAccountResponse;
 use codex_app_server_protocol::CancelLoginAccountStatus;
 use codex_app_server_protocol::CancelLoginChatGptResponse;
+use codex_app_server_protocol::CapabilitiesReadParams;
+use codex_app_server_protocol::CapabilitiesReadResponse;
 use codex_app_server_protocol::ClientRequest;
 use codex_app_server_protocol::CollaborationModeListParams;
 use codex_app_server_protocol::CollaborationModeListResponse;
@@ -94,13 +97,33 @@ use codex_app_server_protocol::SkillsConfigWriteParams;
 use codex_app_server_protocol::SkillsConfigWriteResponse;
 use codex_app_server_protocol::SkillsListParams;
 use codex_app_server_protocol::SkillsListResponse;
+use codex_app_server_protocol::SubAgentsListParams;
+use codex_app_server_protocol::SubAgentsListResponse;
+use codex_app_server_protocol::SubAgentsRunParams;
+use codex_app_server_protocol::SubAgentsRunResponse;
 use codex_app_server_protocol::SwitchAccountParams;
 use codex_app_server_protocol::SwitchAccountResponse;
 use codex_app_server_protocol::Thread;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadArchiveResponse;
+use codex_app_server_protocol::ThreadAttachParams;
+use codex_app_server_protocol::ThreadAttachResponse;
 use codex_app_server_protocol::ThreadCompactParams;
 use codex_app_server_protocol::ThreadCompactResponse;
+use codex_app_server_protocol::ThreadContextCompactParams;
+use codex_app_server_protocol::ThreadContextCompactResponse;
+use codex_app_server_protocol::ThreadContextDropParams;
+use codex_app_server_protocol::ThreadContextDropResponse;
+use codex_app_server_protocol::ThreadContextInspectParams;
+use codex_app_server_protocol::ThreadContextInspectResponse;
+use codex_app_server_protocol::ThreadDeleteParams;
+use codex_app_server_protocol::ThreadDeleteResponse;
+use codex_app_server_protocol::ThreadDetachParams;
+use codex_app_server_protocol::ThreadDetachResponse;
+use codex_app_server_protocol::ThreadDiffStat;
+use codex_app_server_protocol::ThreadExportFormat;
+use codex_app_server_protocol::ThreadExportParams;
+use codex_app_server_protocol::ThreadExportResponse;
 use codex_app_server_protocol::ThreadForkParams;
 use codex_app_server_protocol::ThreadForkResponse;
 use codex_app_server_protocol::ThreadItem;
@@ -108,15 +131,52 @@ use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadListResponse;
 use codex_app_server_protocol::ThreadLoadedListParams;
 use codex_app_server_protocol::ThreadLoadedListResponse;
+use codex_app_server_protocol::ThreadMcpServerAddParams;
+use codex_app_server_protocol::ThreadMcpServerAddResponse;
+use codex_app_server_protocol::ThreadMcpServerRemoveParams;
+use codex_app_server_protocol::ThreadMcpServerRemoveResponse;
+use codex_app_server_protocol::ThreadNameSetParams;
+use codex_app_server_protocol::ThreadNameSetResponse;
+use codex_app_server_protocol::ThreadNextEditSuggestParams;
+use codex_app_server_protocol::ThreadNextEditSuggestResponse;
+use codex_app_server_protocol::ThreadPinAddParams;
+use codex_app_server_protocol::ThreadPinAddResponse;
+use codex_app_server_protocol::ThreadPinListParams;
+use codex_app_server_protocol::ThreadPinListResponse;
+use codex_app_server_protocol::ThreadPinRemoveParams;
+use codex_app_server_protocol::ThreadPinRemoveResponse;
+use codex_app_server_protocol::ThreadPlanModeSetParams;
+use codex_app_server_protocol::ThreadPlanModeSetResponse;
+use codex_app_server_protocol::ThreadQueueAddParams;
+use codex_app_server_protocol::ThreadQueueAddResponse;
+use codex_app_server_protocol::ThreadQueueClearParams;
+use codex_app_server_protocol::ThreadQueueClearResponse;
+use codex_app_server_protocol::ThreadQueueListParams;
+use codex_app_server_protocol::ThreadQueueListResponse;
 use codex_app_server_protocol::ThreadReadParams;
 use codex_app_server_protocol::ThreadReadResponse;
+use codex_app_server_protocol::ThreadRestoreFileParams;
+use codex_app_server_protocol::ThreadRestoreFileResponse;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadResumeResponse;
+use codex_app_server_protocol::ThreadRevertHunksParams;
+use codex_app_server_protocol::ThreadRevertHunksResponse;
 use codex_app_server_protocol::ThreadRollbackParams;
+use codex_app_server_protocol::ThreadSearchParams;
+use codex_app_server_protocol::ThreadSearchResponse;
+use codex_app_server_protocol::ThreadSearchResult;
+use codex_app_server_protocol::ThreadSettingsUpdateParams;
+use codex_app_server_protocol::ThreadSettingsUpdateResponse;
 use codex_app_server_protocol::ThreadSortKey;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
+use codex_app_server_protocol::ThreadTagsSetParams;
+use codex_app_server_protocol::ThreadTagsSetResponse;
+use codex_app_server_protocol::ThreadTerminalResizeParams;
+use codex_app_server_protocol::ThreadTerminalResizeResponse;
+use codex_app_server_protocol::ThreadTerminalWriteParams;
+use codex_app_server_protocol::ThreadTerminalWriteResponse;
 use codex_app_server_protocol::Turn;
 use codex_app_server_protocol::TurnError;
 use codex_app_server_protocol::TurnInterruptParams;
@@ -145,8 +205,10 @@ use codex_core::accounts::load_accounts as load_accounts_core;
 use codex_core::accounts::resolve_active_account as resolve_active_account_core;
 use codex_core::accounts::switch_account as switch_account_core;
 use codex_core::accounts::update_account_meta;
+use codex_core::archive_rollout;
 use codex_core::auth::CLIENT_ID;
 use codex_core::auth::login_with_api_key;
+use codex_core::capabilities::collect_capabilities;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigService;
@@ -158,6 +220,7 @@ use codex_core::error::CodexErr;
 use codex_core::exec::ExecParams;
 use codex_core::exec_env::create_env;
 use codex_core::features::Feature;
+use codex_core::find_archived_thread_path_by_id_str;
 use codex_core::find_thread_path_by_id_str;
 use codex_core::git_info::git_diff_to_remote;
 use codex_core::mcp::collect_mcp_snapshot;
@@ -172,6 +235,18 @@ use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::read_head_for_summary;
 use codex_core::read_session_meta_line;
 use codex_core::sandboxing::SandboxPermissions;
+use codex_core::session_export::ExportFormat;
+use codex_core::session_export::export_transcript;
+use codex_core::subagents::load_subagents;
+use codex_core::subagents::subagent_search_roots;
+use codex_core::thread_labels::ThreadLabels;
+use codex_core::thread_labels::normalize_tags;
+use codex_core::thread_labels::read_thread_labels;
+use codex_core::thread_labels::remove_thread_labels;
+use codex_core::thread_labels::set_thread_name;
+use codex_core::thread_labels::set_thread_tags;
+use codex_core::thread_search::SearchQuery;
+use codex_core::thread_stats::thread_stats;
 use codex_feedback::CodexFeedback;
 use codex_login::ServerOptions as LoginServerOptions;
 use codex_login::ShutdownHandle;
@@ -190,6 +265,7 @@ use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 use codex_protocol::user_input::UserInput as CoreInputItem;
 use codex_rmcp_client::perform_oauth_login_return_url;
 use codex_utils_json_to_toml::json_to_toml;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::OsStr;
@@ -226,6 +302,9 @@ pub(crate) type TurnSummaryStore = Arc<Mutex<HashMap<ThreadId, TurnSummary>>>;
 
 const THREAD_LIST_DEFAULT_LIMIT: usize = 25;
 const THREAD_LIST_MAX_LIMIT: usize = 100;
+/// Threads `thread/search` reads per call before returning a cursor, so a
+/// query with few matches does not scan every rollout in one request.
+const THREAD_SEARCH_MAX_SCANNED: usize = 500;
 
 // Duration before a ChatGPT login attempt is abandoned.
 const LOGIN_CHATGPT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
@@ -249,6 +328,7 @@ impl Drop for ActiveLogin {
 pub(crate) struct CodexMessageProcessor {
     auth_manager: Arc<AuthManager>,
     thread_manager: Arc<ThreadManager>,
+    thread_events: Arc<ThreadEventHub>,
     outgoing: Arc<OutgoingMessageSender>,
     codex_linux_sandbox_exe: Option<PathBuf>,
     config: Arc<Config>,
@@ -295,9 +375,11 @@ impl CodexMessageProcessor {
 
         Ok((thread_id, thread))
     }
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         auth_manager: Arc<AuthManager>,
         thread_manager: Arc<ThreadManager>,
+        thread_events: Arc<ThreadEventHub>,
         outgoing: Arc<OutgoingMessageSender>,
         codex_linux_sandbox_exe: Option<PathBuf>,
         config: Arc<Config>,
@@ -307,6 +389,7 @@ impl CodexMessageProcessor {
         Self {
             auth_manager,
             thread_manager,
+            thread_events,
             outgoing,
             codex_linux_sandbox_exe,
             config,
@@ -362,6 +445,20 @@ impl CodexMessageProcessor {
                     .filter(|t| !t.is_empty());
                 ApiReviewTarget::Commit { sha, title }
             }
+            ApiReviewTarget::Range { range } => {
+                let range = range.trim().to_string();
+                if range.is_empty() {
+                    return Err(invalid_request("range must not be empty".to_string()));
+                }
+                ApiReviewTarget::Range { range }
+            }
+            ApiReviewTarget::PullRequest { url } => {
+                let url = url.trim().to_string();
+                if let Err(err) = codex_core::review_prompts::PullRequestRef::parse(&url) {
+                    return Err(invalid_request(err.to_string()));
+                }
+                ApiReviewTarget::PullRequest { url }
+            }
             ApiReviewTarget::Custom { instructions } => {
                 let trimmed = instructions.trim().to_string();
                 if trimmed.is_empty() {
@@ -379,6 +476,8 @@ impl CodexMessageProcessor {
             ApiReviewTarget::UncommittedChanges => CoreReviewTarget::UncommittedChanges,
             ApiReviewTarget::BaseBranch { branch } => CoreReviewTarget::BaseBranch { branch },
             ApiReviewTarget::Commit { sha, title } => CoreReviewTarget::Commit { sha, title },
+            ApiReviewTarget::Range { range } => CoreReviewTarget::Range { range },
+            ApiReviewTarget::PullRequest { url } => CoreReviewTarget::PullRequest { url },
             ApiReviewTarget::Custom { instructions } => CoreReviewTarget::Custom { instructions },
         };
 
@@ -409,27 +508,108 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadArchive { request_id, params } => {
                 self.thread_archive(request_id, params).await;
             }
+            ClientRequest::ThreadDelete { request_id, params } => {
+                self.thread_delete(request_id, params).await;
+            }
+            ClientRequest::ThreadNameSet { request_id, params } => {
+                self.thread_name_set(request_id, params).await;
+            }
+            ClientRequest::ThreadTagsSet { request_id, params } => {
+                self.thread_tags_set(request_id, params).await;
+            }
             ClientRequest::ThreadRollback { request_id, params } => {
                 self.thread_rollback(request_id, params).await;
             }
             ClientRequest::ThreadCompact { request_id, params } => {
                 self.thread_compact(request_id, params).await;
             }
+            ClientRequest::ThreadRestoreFile { request_id, params } => {
+                self.thread_restore_file(request_id, params).await;
+            }
+            ClientRequest::ThreadRevertHunks { request_id, params } => {
+                self.thread_revert_hunks(request_id, params).await;
+            }
+            ClientRequest::ThreadQueueAdd { request_id, params } => {
+                self.thread_queue_add(request_id, params).await;
+            }
+            ClientRequest::ThreadQueueList { request_id, params } => {
+                self.thread_queue_list(request_id, params).await;
+            }
+            ClientRequest::ThreadQueueClear { request_id, params } => {
+                self.thread_queue_clear(request_id, params).await;
+            }
+            ClientRequest::ThreadMcpServerAdd { request_id, params } => {
+                self.thread_mcp_server_add(request_id, params).await;
+            }
+            ClientRequest::ThreadMcpServerRemove { request_id, params } => {
+                self.thread_mcp_server_remove(request_id, params).await;
+            }
+            ClientRequest::ThreadPinAdd { request_id, params } => {
+                self.thread_pin_add(request_id, params).await;
+            }
+            ClientRequest::ThreadPinRemove { request_id, params } => {
+                self.thread_pin_remove(request_id, params).await;
+            }
+            ClientRequest::ThreadPinList { request_id, params } => {
+                self.thread_pin_list(request_id, params).await;
+            }
+            ClientRequest::ThreadContextInspect { request_id, params } => {
+                self.thread_context_inspect(request_id, params).await;
+            }
+            ClientRequest::ThreadContextDrop { request_id, params } => {
+                self.thread_context_drop(request_id, params).await;
+            }
+            ClientRequest::ThreadContextCompact { request_id, params } => {
+                self.thread_context_compact(request_id, params).await;
+            }
+            ClientRequest::ThreadPlanModeSet { request_id, params } => {
+                self.thread_plan_mode_set(request_id, params).await;
+            }
+            ClientRequest::ThreadSettingsUpdate { request_id, params } => {
+                self.thread_settings_update(request_id, params).await;
+            }
+            ClientRequest::ThreadNextEditSuggest { request_id, params } => {
+                self.thread_next_edit_suggest(request_id, params).await;
+            }
+            ClientRequest::ThreadTerminalWrite { request_id, params } => {
+                self.thread_terminal_write(request_id, params).await;
+            }
+            ClientRequest::ThreadTerminalResize { request_id, params } => {
+                self.thread_terminal_resize(request_id, params).await;
+            }
             ClientRequest::ThreadList { request_id, params } => {
                 self.thread_list(request_id, params).await;
             }
+            ClientRequest::ThreadSearch { request_id, params } => {
+                self.thread_search(request_id, params).await;
+            }
             ClientRequest::ThreadLoadedList { request_id, params } => {
                 self.thread_loaded_list(request_id, params).await;
             }
             ClientRequest::ThreadRead { request_id, params } => {
                 self.thread_read(request_id, params).await;
             }
+            ClientRequest::ThreadExport { request_id, params } => {
+                self.thread_export(request_id, params).await;
+            }
+            ClientRequest::ThreadAttach { request_id, params } => {
+                self.thread_attach(request_id, params).await;
+            }
+            ClientRequest::ThreadDetach { request_id, params } => {
+                self.thread_detach(request_id, params).await;
+            }
             ClientRequest::SkillsList { request_id, params } => {
                 self.skills_list(request_id, params).await;
             }
             ClientRequest::SkillsConfigWrite { request_id, params } => {
                 self.skills_config_write(request_id, params).await;
             }
+            ClientRequest::SubAgentsList { request_id, params } => {
+                self.subagents_list(request_id, params).await;
+            }
+            ClientRequest::SubAgentsRun { request_id, params } => {
+                self.subagents_run(request_id, params).await;
+            }
             ClientRequest::TurnStart { request_id, params } => {
                 self.turn_start(request_id, params).await;
             }
@@ -478,6 +658,9 @@ impl CodexMessageProcessor {
             ClientRequest::McpServerStatusList { request_id, params } => {
                 self.list_mcp_server_status(request_id, params).await;
             }
+            ClientRequest::CapabilitiesRead { request_id, params } => {
+                self.read_capabilities(request_id, params).await;
+            }
             ClientRequest::LoginAccount { request_id, params } => {
                 self.login_v2(request_id, params).await;
             }
@@ -588,6 +771,12 @@ impl CodexMessageProcessor {
             ClientRequest::ConfigRequirementsRead { .. } => {
                 warn!("ConfigRequirementsRead request reached CodexMessageProcessor unexpectedly");
             }
+            ClientRequest::ConfigSchemaRead { .. } => {
+                warn!("ConfigSchemaRead request reached CodexMessageProcessor unexpectedly");
+            }
+            ClientRequest::ConfigValidate { .. } => {
+                warn!("ConfigValidate request reached CodexMessageProcessor unexpectedly");
+            }
             ClientRequest::GetAccountRateLimits {
                 request_id,
                 params: _,
@@ -1759,6 +1948,150 @@ impl CodexMessageProcessor {
         }
     }
 
+    /// Deletes a thread's rollout, archived or not, and its labels. A loaded
+    /// thread is shut down first.
+    async fn thread_delete(&mut self, request_id: RequestId, params: ThreadDeleteParams) {
+        let thread_id = match ThreadId::from_string(&params.thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
+                    .await;
+                return;
+            }
+        };
+
+        let codex_home = self.config.codex_home.clone();
+        let rollout_path =
+            match find_thread_path_by_id_str(&codex_home, &thread_id.to_string()).await {
+                Ok(None) => {
+                    find_archived_thread_path_by_id_str(&codex_home, &thread_id.to_string()).await
+                }
+                other => other,
+            };
+        let rollout_path = match rollout_path {
+            Ok(Some(path)) => path,
+            Ok(None) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("no rollout found for thread id {thread_id}"),
+                )
+                .await;
+                return;
+            }
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to locate thread id {thread_id}: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+
+        self.shutdown_loaded_thread(thread_id).await;
+        if let Err(err) = tokio::fs::remove_file(&rollout_path).await {
+            self.send_internal_error(
+                request_id,
+                format!("failed to delete `{}`: {err}", rollout_path.display()),
+            )
+            .await;
+            return;
+        }
+        if let Err(err) = remove_thread_labels(&codex_home, thread_id).await {
+            warn!("failed to remove labels of deleted thread {thread_id}: {err}");
+        }
+        self.outgoing
+            .send_response(request_id, ThreadDeleteResponse {})
+            .await;
+    }
+
+    async fn thread_name_set(&self, request_id: RequestId, params: ThreadNameSetParams) {
+        let ThreadNameSetParams { thread_id, name } = params;
+        let thread_id = match self.existing_thread(&thread_id).await {
+            Ok((id, _)) => id,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+        match set_thread_name(&self.config.codex_home, thread_id, name).await {
+            Ok(labels) => {
+                let response = ThreadNameSetResponse { name: labels.name };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => self.send_label_error(request_id, err).await,
+        }
+    }
+
+    async fn thread_tags_set(&self, request_id: RequestId, params: ThreadTagsSetParams) {
+        let ThreadTagsSetParams { thread_id, tags } = params;
+        let thread_id = match self.existing_thread(&thread_id).await {
+            Ok((id, _)) => id,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+        match set_thread_tags(&self.config.codex_home, thread_id, &tags).await {
+            Ok(labels) => {
+                let response = ThreadTagsSetResponse { tags: labels.tags };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => self.send_label_error(request_id, err).await,
+        }
+    }
+
+    /// Parses `thread_id` and finds the thread's rollout, archived or not.
+    async fn existing_thread(
+        &self,
+        thread_id: &str,
+    ) -> Result<(ThreadId, PathBuf), JSONRPCErrorError> {
+        let invalid_request = |message: String| JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message,
+            data: None,
+        };
+        let thread_id = ThreadId::from_string(thread_id)
+            .map_err(|err| invalid_request(format!("invalid thread id: {err}")))?;
+        let codex_home = &self.config.codex_home;
+        let found = match find_thread_path_by_id_str(codex_home, &thread_id.to_string()).await {
+            Ok(None) => {
+                find_archived_thread_path_by_id_str(codex_home, &thread_id.to_string()).await
+            }
+            other => other,
+        };
+        match found {
+            Ok(Some(path)) => Ok((thread_id, path)),
+            Ok(None) => Err(invalid_request(format!(
+                "no rollout found for thread id {thread_id}"
+            ))),
+            Err(err) => Err(invalid_request(format!(
+                "failed to locate thread id {thread_id}: {err}"
+            ))),
+        }
+    }
+
+    async fn send_label_error(&self, request_id: RequestId, err: IoError) {
+        if err.kind() == std::io::ErrorKind::InvalidInput {
+            self.send_invalid_request_error(request_id, err.to_string())
+                .await;
+        } else {
+            self.send_internal_error(request_id, format!("failed to update thread labels: {err}"))
+                .await;
+        }
+    }
+
+    /// Labels of every labelled thread; empty (with a warning) when the labels
+    /// file cannot be read, so listings still work.
+    async fn thread_labels(&self) -> BTreeMap<String, ThreadLabels> {
+        read_thread_labels(&self.config.codex_home)
+            .await
+            .unwrap_or_else(|err| {
+                warn!("failed to read thread labels: {err}");
+                BTreeMap::new()
+            })
+    }
+
     async fn thread_rollback(&mut self, request_id: RequestId, params: ThreadRollbackParams) {
         let ThreadRollbackParams {
             thread_id,
@@ -1826,146 +2159,917 @@ impl CodexMessageProcessor {
             .await;
     }
 
-    async fn thread_list(&self, request_id: RequestId, params: ThreadListParams) {
-        let ThreadListParams {
-            cursor,
-            limit,
-            sort_key,
-            model_providers,
-            archived,
+    async fn thread_restore_file(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadRestoreFileParams,
+    ) {
+        let ThreadRestoreFileParams {
+            thread_id,
+            turn_id,
+            path,
         } = params;
 
-        let requested_page_size = limit
-            .map(|value| value as usize)
-            .unwrap_or(THREAD_LIST_DEFAULT_LIMIT)
-            .clamp(1, THREAD_LIST_MAX_LIMIT);
-        let core_sort_key = match sort_key.unwrap_or(ThreadSortKey::CreatedAt) {
-            ThreadSortKey::CreatedAt => CoreThreadSortKey::CreatedAt,
-            ThreadSortKey::UpdatedAt => CoreThreadSortKey::UpdatedAt,
-        };
-        let (summaries, next_cursor) = match self
-            .list_threads_common(
-                requested_page_size,
-                cursor,
-                model_providers,
-                core_sort_key,
-                archived.unwrap_or(false),
-            )
-            .await
-        {
-            Ok(r) => r,
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
             Err(error) => {
                 self.outgoing.send_error(request_id, error).await;
                 return;
             }
         };
 
-        let data = summaries.into_iter().map(summary_to_thread).collect();
-        let response = ThreadListResponse { data, next_cursor };
-        self.outgoing.send_response(request_id, response).await;
+        let op = Op::RestoreFile {
+            turn_id,
+            path: PathBuf::from(path),
+        };
+        if let Err(err) = thread.submit(op).await {
+            self.send_internal_error(request_id, format!("failed to restore file: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadRestoreFileResponse {})
+            .await;
     }
 
-    async fn thread_loaded_list(&self, request_id: RequestId, params: ThreadLoadedListParams) {
-        let ThreadLoadedListParams { cursor, limit } = params;
-        let mut data = self
-            .thread_manager
-            .list_thread_ids()
-            .await
-            .into_iter()
-            .map(|thread_id| thread_id.to_string())
-            .collect::<Vec<_>>();
+    async fn thread_revert_hunks(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadRevertHunksParams,
+    ) {
+        let ThreadRevertHunksParams {
+            thread_id,
+            turn_id,
+            path,
+            hunks,
+        } = params;
 
-        if data.is_empty() {
-            let response = ThreadLoadedListResponse {
-                data,
-                next_cursor: None,
-            };
-            self.outgoing.send_response(request_id, response).await;
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let op = Op::RevertHunks {
+            turn_id,
+            path: PathBuf::from(path),
+            hunks: hunks.into_iter().map(Into::into).collect(),
+        };
+        if let Err(err) = thread.submit(op).await {
+            self.send_internal_error(request_id, format!("failed to revert hunks: {err}"))
+                .await;
             return;
         }
 
-        data.sort();
-        let total = data.len();
-        let start = match cursor {
-            Some(cursor) => {
-                let cursor = match ThreadId::from_string(&cursor) {
-                    Ok(id) => id.to_string(),
-                    Err(_) => {
-                        let error = JSONRPCErrorError {
-                            code: INVALID_REQUEST_ERROR_CODE,
-                            message: format!("invalid cursor: {cursor}"),
-                            data: None,
-                        };
-                        self.outgoing.send_error(request_id, error).await;
-                        return;
-                    }
-                };
-                match data.binary_search(&cursor) {
-                    Ok(idx) => idx + 1,
-                    Err(idx) => idx,
-                }
+        self.outgoing
+            .send_response(request_id, ThreadRevertHunksResponse {})
+            .await;
+    }
+
+    async fn thread_queue_add(&mut self, request_id: RequestId, params: ThreadQueueAddParams) {
+        let ThreadQueueAddParams { thread_id, input } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
             }
-            None => 0,
         };
 
-        let effective_limit = limit.unwrap_or(total as u32).max(1) as usize;
-        let end = start.saturating_add(effective_limit).min(total);
-        let page = data[start..end].to_vec();
-        let next_cursor = page.last().filter(|_| end < total).cloned();
+        let items = input.into_iter().map(V2UserInput::into_core).collect();
+        if let Err(err) = thread.submit(Op::QueueUserInput { items }).await {
+            self.send_internal_error(request_id, format!("failed to queue input: {err}"))
+                .await;
+            return;
+        }
 
-        let response = ThreadLoadedListResponse {
-            data: page,
-            next_cursor,
+        self.outgoing
+            .send_response(request_id, ThreadQueueAddResponse {})
+            .await;
+    }
+
+    async fn thread_queue_list(&mut self, request_id: RequestId, params: ThreadQueueListParams) {
+        let ThreadQueueListParams { thread_id } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
         };
-        self.outgoing.send_response(request_id, response).await;
+
+        if let Err(err) = thread.submit(Op::ListQueuedUserInputs).await {
+            self.send_internal_error(request_id, format!("failed to list queued input: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadQueueListResponse {})
+            .await;
     }
 
-    async fn thread_read(&self, request_id: RequestId, params: ThreadReadParams) {
-        let ThreadReadParams {
-            thread_id,
-            include_turns,
-        } = params;
+    async fn thread_queue_clear(&mut self, request_id: RequestId, params: ThreadQueueClearParams) {
+        let ThreadQueueClearParams { thread_id } = params;
 
-        let thread_uuid = match ThreadId::from_string(&thread_id) {
-            Ok(id) => id,
-            Err(err) => {
-                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
-                    .await;
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::ClearQueuedUserInputs).await {
+            self.send_internal_error(request_id, format!("failed to clear queued input: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadQueueClearResponse {})
+            .await;
+    }
+
+    async fn thread_mcp_server_add(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadMcpServerAddParams,
+    ) {
+        let ThreadMcpServerAddParams {
+            thread_id,
+            name,
+            config,
+        } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::AddMcpServer { name, config }).await {
+            self.send_internal_error(request_id, format!("failed to add MCP server: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadMcpServerAddResponse {})
+            .await;
+    }
+
+    async fn thread_mcp_server_remove(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadMcpServerRemoveParams,
+    ) {
+        let ThreadMcpServerRemoveParams { thread_id, name } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::RemoveMcpServer { name }).await {
+            self.send_internal_error(request_id, format!("failed to remove MCP server: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadMcpServerRemoveResponse {})
+            .await;
+    }
+
+    async fn thread_pin_add(&mut self, request_id: RequestId, params: ThreadPinAddParams) {
+        let ThreadPinAddParams { thread_id, content } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let op = Op::PinContext {
+            content: content.into_core(),
+        };
+        if let Err(err) = thread.submit(op).await {
+            self.send_internal_error(request_id, format!("failed to pin context: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadPinAddResponse {})
+            .await;
+    }
+
+    async fn thread_pin_remove(&mut self, request_id: RequestId, params: ThreadPinRemoveParams) {
+        let ThreadPinRemoveParams { thread_id, id } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::UnpinContext { id }).await {
+            self.send_internal_error(request_id, format!("failed to unpin context: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadPinRemoveResponse {})
+            .await;
+    }
+
+    async fn thread_pin_list(&mut self, request_id: RequestId, params: ThreadPinListParams) {
+        let ThreadPinListParams { thread_id } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::ListPinnedContext).await {
+            self.send_internal_error(request_id, format!("failed to list pinned context: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadPinListResponse {})
+            .await;
+    }
+
+    async fn thread_context_inspect(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadContextInspectParams,
+    ) {
+        let ThreadContextInspectParams { thread_id } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::InspectContext).await {
+            self.send_internal_error(request_id, format!("failed to inspect context: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadContextInspectResponse {})
+            .await;
+    }
+
+    async fn thread_context_drop(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadContextDropParams,
+    ) {
+        let ThreadContextDropParams { thread_id, item_id } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::DropContextItem { id: item_id }).await {
+            self.send_internal_error(request_id, format!("failed to drop context item: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadContextDropResponse {})
+            .await;
+    }
+
+    async fn thread_context_compact(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadContextCompactParams,
+    ) {
+        let ThreadContextCompactParams { thread_id, turn } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let op = Op::CompactRange {
+            from_turn: turn,
+            to_turn: turn,
+        };
+        if let Err(err) = thread.submit(op).await {
+            self.send_internal_error(request_id, format!("failed to compact turn: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadContextCompactResponse {})
+            .await;
+    }
+
+    async fn thread_plan_mode_set(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadPlanModeSetParams,
+    ) {
+        let ThreadPlanModeSetParams { thread_id, enabled } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::SetPlanMode { enabled }).await {
+            self.send_internal_error(request_id, format!("failed to set plan mode: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadPlanModeSetResponse {})
+            .await;
+    }
+
+    async fn thread_settings_update(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadSettingsUpdateParams,
+    ) {
+        let ThreadSettingsUpdateParams {
+            thread_id,
+            model,
+            effort,
+        } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if model.is_some() || effort.is_some() {
+            let op = Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: None,
+                sandbox_policy: None,
+                model,
+                effort: effort.map(Some),
+                summary: None,
+                collaboration_mode: None,
+                personality: None,
+            };
+            if let Err(err) = thread.submit(op).await {
+                self.send_internal_error(
+                    request_id,
+                    format!("failed to update thread settings: {err}"),
+                )
+                .await;
+                return;
+            }
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadSettingsUpdateResponse {})
+            .await;
+    }
+
+    async fn thread_next_edit_suggest(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadNextEditSuggestParams,
+    ) {
+        let ThreadNextEditSuggestParams {
+            thread_id,
+            request_id: suggestion_id,
+            path,
+            prefix,
+            suffix,
+            model,
+        } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let op = Op::SuggestNextEdit {
+            request_id: suggestion_id,
+            path,
+            prefix,
+            suffix,
+            model,
+        };
+        if let Err(err) = thread.submit(op).await {
+            self.send_internal_error(request_id, format!("failed to suggest next edit: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadNextEditSuggestResponse {})
+            .await;
+    }
+
+    async fn thread_terminal_write(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadTerminalWriteParams,
+    ) {
+        let ThreadTerminalWriteParams {
+            thread_id,
+            process_id,
+            input,
+        } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::TerminalInput { process_id, input }).await {
+            self.send_internal_error(request_id, format!("failed to write to terminal: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadTerminalWriteResponse {})
+            .await;
+    }
+
+    async fn thread_terminal_resize(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadTerminalResizeParams,
+    ) {
+        let ThreadTerminalResizeParams {
+            thread_id,
+            rows,
+            cols,
+        } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread.submit(Op::SetTerminalSize { rows, cols }).await {
+            self.send_internal_error(request_id, format!("failed to resize terminal: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, ThreadTerminalResizeResponse {})
+            .await;
+    }
+
+    async fn thread_list(&self, request_id: RequestId, params: ThreadListParams) {
+        let ThreadListParams {
+            cursor,
+            limit,
+            sort_key,
+            model_providers,
+            archived,
+            forked_from_id,
+        } = params;
+
+        let requested_page_size = limit
+            .map(|value| value as usize)
+            .unwrap_or(THREAD_LIST_DEFAULT_LIMIT)
+            .clamp(1, THREAD_LIST_MAX_LIMIT);
+        let core_sort_key = match sort_key.unwrap_or(ThreadSortKey::CreatedAt) {
+            ThreadSortKey::CreatedAt => CoreThreadSortKey::CreatedAt,
+            ThreadSortKey::UpdatedAt => CoreThreadSortKey::UpdatedAt,
+        };
+        let forked_from = match forked_from_id.as_deref().map(ThreadId::from_string) {
+            Some(Ok(id)) => Some(id),
+            Some(Err(err)) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid forkedFromId: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+            None => None,
+        };
+        let (summaries, next_cursor) = match self
+            .list_threads_common(
+                requested_page_size,
+                cursor,
+                model_providers,
+                core_sort_key,
+                archived.unwrap_or(false),
+                forked_from,
+            )
+            .await
+        {
+            Ok(r) => r,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let labels = self.thread_labels().await;
+        let data = summaries
+            .into_iter()
+            .map(|summary| {
+                let mut thread = summary_to_thread(summary);
+                apply_thread_labels(&mut thread, &labels);
+                thread
+            })
+            .collect();
+        let response = ThreadListResponse { data, next_cursor };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
+    async fn thread_search(&self, request_id: RequestId, params: ThreadSearchParams) {
+        let ThreadSearchParams {
+            query,
+            tags,
+            cursor,
+            limit,
+            archived,
+        } = params;
+
+        let query = SearchQuery::new(&query);
+        let tags = match normalize_tags(&tags.unwrap_or_default()) {
+            Ok(tags) => tags,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, err.to_string())
+                    .await;
+                return;
+            }
+        };
+        if query.is_empty() && tags.is_empty() {
+            self.send_invalid_request_error(
+                request_id,
+                "thread/search needs a query or tags".to_string(),
+            )
+            .await;
+            return;
+        }
+        let requested = limit
+            .map(|value| value as usize)
+            .unwrap_or(THREAD_LIST_DEFAULT_LIMIT)
+            .clamp(1, THREAD_LIST_MAX_LIMIT);
+
+        let labels = self.thread_labels().await;
+        let mut data = Vec::new();
+        let mut cursor = cursor;
+        let mut scanned = 0;
+        loop {
+            // Never list more threads than results still wanted, so the
+            // cursor always points just past the last thread looked at.
+            let page_size = (requested - data.len()).min(THREAD_SEARCH_MAX_SCANNED - scanned);
+            let (summaries, next_cursor) = match self
+                .list_threads_common(
+                    page_size,
+                    cursor,
+                    None,
+                    CoreThreadSortKey::UpdatedAt,
+                    archived.unwrap_or(false),
+                    None,
+                )
+                .await
+            {
+                Ok(page) => page,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
+            scanned += summaries.len();
+            for summary in summaries {
+                let thread_labels = labels.get(&summary.conversation_id.to_string());
+                if !tags.is_empty() && !thread_labels.is_some_and(|found| found.has_tags(&tags)) {
+                    continue;
+                }
+                let snippet = if query.is_empty() {
+                    None
+                } else {
+                    match query.search_rollout(&summary.path, thread_labels).await {
+                        Ok(Some(found)) => found.snippet,
+                        Ok(None) => continue,
+                        Err(err) => {
+                            warn!("failed to search `{}`: {err}", summary.path.display());
+                            continue;
+                        }
+                    }
+                };
+                let mut thread = summary_to_thread(summary);
+                apply_thread_labels(&mut thread, &labels);
+                data.push(ThreadSearchResult { thread, snippet });
+            }
+            cursor = next_cursor;
+            if cursor.is_none() || data.len() >= requested || scanned >= THREAD_SEARCH_MAX_SCANNED {
+                break;
+            }
+        }
+
+        let response = ThreadSearchResponse {
+            data,
+            next_cursor: cursor,
+        };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
+    async fn thread_loaded_list(&self, request_id: RequestId, params: ThreadLoadedListParams) {
+        let ThreadLoadedListParams { cursor, limit } = params;
+        let mut data = self
+            .thread_manager
+            .list_thread_ids()
+            .await
+            .into_iter()
+            .map(|thread_id| thread_id.to_string())
+            .collect::<Vec<_>>();
+
+        if data.is_empty() {
+            let response = ThreadLoadedListResponse {
+                data,
+                next_cursor: None,
+            };
+            self.outgoing.send_response(request_id, response).await;
+            return;
+        }
+
+        data.sort();
+        let total = data.len();
+        let start = match cursor {
+            Some(cursor) => {
+                let cursor = match ThreadId::from_string(&cursor) {
+                    Ok(id) => id.to_string(),
+                    Err(_) => {
+                        let error = JSONRPCErrorError {
+                            code: INVALID_REQUEST_ERROR_CODE,
+                            message: format!("invalid cursor: {cursor}"),
+                            data: None,
+                        };
+                        self.outgoing.send_error(request_id, error).await;
+                        return;
+                    }
+                };
+                match data.binary_search(&cursor) {
+                    Ok(idx) => idx + 1,
+                    Err(idx) => idx,
+                }
+            }
+            None => 0,
+        };
+
+        let effective_limit = limit.unwrap_or(total as u32).max(1) as usize;
+        let end = start.saturating_add(effective_limit).min(total);
+        let page = data[start..end].to_vec();
+        let next_cursor = page.last().filter(|_| end < total).cloned();
+
+        let response = ThreadLoadedListResponse {
+            data: page,
+            next_cursor,
+        };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
+    async fn thread_read(&self, request_id: RequestId, params: ThreadReadParams) {
+        let ThreadReadParams {
+            thread_id,
+            include_turns,
+            include_stats,
+        } = params;
+
+        let thread_uuid = match ThreadId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
+                    .await;
+                return;
+            }
+        };
+
+        let rollout_path =
+            match find_thread_path_by_id_str(&self.config.codex_home, &thread_uuid.to_string())
+                .await
+            {
+                Ok(Some(path)) => path,
+                Ok(None) => {
+                    self.send_invalid_request_error(
+                        request_id,
+                        format!("no rollout found for thread id {thread_uuid}"),
+                    )
+                    .await;
+                    return;
+                }
+                Err(err) => {
+                    self.send_invalid_request_error(
+                        request_id,
+                        format!("failed to locate thread id {thread_uuid}: {err}"),
+                    )
+                    .await;
+                    return;
+                }
+            };
+
+        let fallback_provider = self.config.model_provider_id.as_str();
+        let mut thread = match read_summary_from_rollout(&rollout_path, fallback_provider).await {
+            Ok(summary) => summary_to_thread(summary),
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!(
+                        "failed to load rollout `{}` for thread {thread_uuid}: {err}",
+                        rollout_path.display()
+                    ),
+                )
+                .await;
+                return;
+            }
+        };
+
+        if include_turns || include_stats {
+            match read_rollout_items(&rollout_path).await {
+                Ok(items) => {
+                    if include_stats {
+                        let stats = thread_stats(&items);
+                        thread.model = stats.model;
+                        thread.diff_stat = Some(ThreadDiffStat {
+                            files_changed: stats.files_changed as u32,
+                            insertions: stats.insertions as u32,
+                            deletions: stats.deletions as u32,
+                        });
+                    }
+                    if include_turns {
+                        let events: Vec<_> = items
+                            .into_iter()
+                            .filter_map(|item| match item {
+                                RolloutItem::EventMsg(event) => Some(event),
+                                _ => None,
+                            })
+                            .collect();
+                        thread.turns = build_turns_from_event_msgs(&events);
+                    }
+                }
+                Err(err) => {
+                    self.send_internal_error(
+                        request_id,
+                        format!(
+                            "failed to load rollout `{}` for thread {thread_uuid}: {err}",
+                            rollout_path.display()
+                        ),
+                    )
+                    .await;
+                    return;
+                }
+            }
+        }
+
+        apply_thread_labels(&mut thread, &self.thread_labels().await);
+        let response = ThreadReadResponse { thread };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
+    async fn thread_export(&self, request_id: RequestId, params: ThreadExportParams) {
+        let ThreadExportParams { thread_id, format } = params;
+        let (thread_id, rollout_path) = match self.existing_thread(&thread_id).await {
+            Ok(found) => found,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+        let history = match RolloutRecorder::get_rollout_history(&rollout_path).await {
+            Ok(history) => history,
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!(
+                        "failed to load rollout `{}` for thread {thread_id}: {err}",
+                        rollout_path.display()
+                    ),
+                )
+                .await;
+                return;
+            }
+        };
+        let format = match format {
+            ThreadExportFormat::Markdown => ExportFormat::Markdown,
+            ThreadExportFormat::Html => ExportFormat::Html,
+            ThreadExportFormat::Json => ExportFormat::Json,
+        };
+        match export_transcript(&history.get_rollout_items(), format) {
+            Ok(exported) => {
+                let response = ThreadExportResponse {
+                    contents: exported.contents,
+                    redactions: u32::try_from(exported.redactions).unwrap_or(u32::MAX),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to export thread {thread_id}: {err}"),
+                )
+                .await;
+            }
+        }
+    }
+
+    /// Streams a thread that is already running (usually started by another
+    /// client) to this client, and returns its turns so far.
+    async fn thread_attach(&mut self, request_id: RequestId, params: ThreadAttachParams) {
+        let ThreadAttachParams { thread_id } = params;
+        let (thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
                 return;
             }
         };
 
-        let rollout_path =
-            match find_thread_path_by_id_str(&self.config.codex_home, &thread_uuid.to_string())
+        // Listen before reading the rollout so that no event falls between
+        // the backlog and the live stream.
+        let already_attached = self
+            .listener_thread_ids_by_subscription
+            .values()
+            .any(|entry| *entry == thread_id);
+        if !already_attached
+            && let Err(error) = self
+                .attach_conversation_listener(thread_id, false, ApiVersion::V2)
                 .await
-            {
-                Ok(Some(path)) => path,
-                Ok(None) => {
-                    self.send_invalid_request_error(
-                        request_id,
-                        format!("no rollout found for thread id {thread_uuid}"),
-                    )
-                    .await;
-                    return;
-                }
-                Err(err) => {
-                    self.send_invalid_request_error(
-                        request_id,
-                        format!("failed to locate thread id {thread_uuid}: {err}"),
-                    )
-                    .await;
-                    return;
-                }
-            };
+        {
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        }
 
+        let rollout_path = thread.rollout_path();
         let fallback_provider = self.config.model_provider_id.as_str();
-        let mut thread = match read_summary_from_rollout(&rollout_path, fallback_provider).await {
-            Ok(summary) => summary_to_thread(summary),
+        let backlog = async {
+            let summary = read_summary_from_rollout(&rollout_path, fallback_provider).await?;
+            let events = read_event_msgs_from_rollout(&rollout_path).await?;
+            std::io::Result::Ok((summary, events))
+        };
+        let thread = match backlog.await {
+            Ok((summary, events)) => {
+                let mut thread = summary_to_thread(summary);
+                thread.turns = build_turns_from_event_msgs(&events);
+                thread
+            }
             Err(err) => {
                 self.send_internal_error(
                     request_id,
                     format!(
-                        "failed to load rollout `{}` for thread {thread_uuid}: {err}",
+                        "failed to load rollout `{}` for thread {thread_id}: {err}",
                         rollout_path.display()
                     ),
                 )
@@ -1973,28 +3077,36 @@ impl CodexMessageProcessor {
                 return;
             }
         };
+        let response = ThreadAttachResponse { thread };
+        self.outgoing.send_response(request_id, response).await;
+    }
 
-        if include_turns {
-            match read_event_msgs_from_rollout(&rollout_path).await {
-                Ok(events) => {
-                    thread.turns = build_turns_from_event_msgs(&events);
-                }
-                Err(err) => {
-                    self.send_internal_error(
-                        request_id,
-                        format!(
-                            "failed to load rollout `{}` for thread {thread_uuid}: {err}",
-                            rollout_path.display()
-                        ),
-                    )
+    /// Stops streaming a thread to this client. The thread keeps running.
+    async fn thread_detach(&mut self, request_id: RequestId, params: ThreadDetachParams) {
+        let thread_id = match ThreadId::from_string(&params.thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
                     .await;
-                    return;
-                }
+                return;
+            }
+        };
+        let subscriptions: Vec<Uuid> = self
+            .listener_thread_ids_by_subscription
+            .iter()
+            .filter(|(_, entry)| **entry == thread_id)
+            .map(|(subscription_id, _)| *subscription_id)
+            .collect();
+        for subscription_id in subscriptions {
+            self.listener_thread_ids_by_subscription
+                .remove(&subscription_id);
+            if let Some(cancel) = self.conversation_listeners.remove(&subscription_id) {
+                let _ = cancel.send(());
             }
         }
-
-        let response = ThreadReadResponse { thread };
-        self.outgoing.send_response(request_id, response).await;
+        self.outgoing
+            .send_response(request_id, ThreadDetachResponse {})
+            .await;
     }
 
     pub(crate) fn thread_created_receiver(&self) -> broadcast::Receiver<ThreadId> {
@@ -2073,12 +3185,23 @@ impl CodexMessageProcessor {
                 }
             };
 
+            // Archived threads are restored to the sessions directory when resumed.
             let path = match find_thread_path_by_id_str(
                 &self.config.codex_home,
                 &existing_thread_id.to_string(),
             )
             .await
             {
+                Ok(None) => {
+                    find_archived_thread_path_by_id_str(
+                        &self.config.codex_home,
+                        &existing_thread_id.to_string(),
+                    )
+                    .await
+                }
+                other => other,
+            };
+            let path = match path {
                 Ok(Some(p)) => p,
                 Ok(None) => {
                     self.send_invalid_request_error(
@@ -2230,6 +3353,7 @@ impl CodexMessageProcessor {
             config: cli_overrides,
             base_instructions,
             developer_instructions,
+            num_turns,
         } = params;
 
         let rollout_path = if let Some(path) = path {
@@ -2334,7 +3458,11 @@ impl CodexMessageProcessor {
             ..
         } = match self
             .thread_manager
-            .fork_thread(usize::MAX, config, rollout_path.clone())
+            .fork_thread(
+                num_turns.map_or(usize::MAX, |n| n as usize),
+                config,
+                rollout_path.clone(),
+            )
             .await
         {
             Ok(thread) => thread,
@@ -2494,6 +3622,7 @@ impl CodexMessageProcessor {
                 model_providers,
                 CoreThreadSortKey::UpdatedAt,
                 false,
+                None,
             )
             .await
         {
@@ -2514,6 +3643,7 @@ impl CodexMessageProcessor {
         model_providers: Option<Vec<String>>,
         sort_key: CoreThreadSortKey,
         archived: bool,
+        forked_from: Option<ThreadId>,
     ) -> Result<(Vec<ConversationSummary>, Option<String>), JSONRPCErrorError> {
         let mut cursor_obj: Option<RolloutCursor> = match cursor.as_ref() {
             Some(cursor_str) => {
@@ -2586,6 +3716,10 @@ impl CodexMessageProcessor {
                     let session_meta_line = it.head.first().and_then(|first| {
                         serde_json::from_value::<SessionMetaLine>(first.clone()).ok()
                     })?;
+                    if forked_from.is_some() && session_meta_line.meta.forked_from_id != forked_from
+                    {
+                        return None;
+                    }
                     extract_conversation_summary(
                         it.path,
                         &it.head,
@@ -2871,6 +4005,28 @@ impl CodexMessageProcessor {
         });
     }
 
+    async fn read_capabilities(&self, request_id: RequestId, params: CapabilitiesReadParams) {
+        let mut config = match self.load_latest_config().await {
+            Ok(config) => config,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+        // Reloaded configs do not know the helper this server was started with.
+        if config.codex_linux_sandbox_exe.is_none() {
+            config.codex_linux_sandbox_exe = self.codex_linux_sandbox_exe.clone();
+        }
+
+        let outgoing = Arc::clone(&self.outgoing);
+        tokio::spawn(async move {
+            let capabilities = collect_capabilities(&config, params.skip_mcp).await;
+            outgoing
+                .send_response(request_id, CapabilitiesReadResponse { capabilities })
+                .await;
+        });
+    }
+
     async fn list_mcp_server_status_task(
         outgoing: Arc<OutgoingMessageSender>,
         request_id: RequestId,
@@ -2888,6 +4044,7 @@ impl CodexMessageProcessor {
             .chain(snapshot.auth_statuses.keys().cloned())
             .chain(snapshot.resources.keys().cloned())
             .chain(snapshot.resource_templates.keys().cloned())
+            .chain(snapshot.prompts.keys().cloned())
             .collect();
         server_names.sort();
         server_names.dedup();
@@ -2934,6 +4091,7 @@ impl CodexMessageProcessor {
                     .get(name)
                     .cloned()
                     .unwrap_or_default(),
+                prompts: snapshot.prompts.get(name).cloned().unwrap_or_default(),
                 auth_status: snapshot
                     .auth_statuses
                     .get(name)
@@ -3377,6 +4535,63 @@ impl CodexMessageProcessor {
         }
     }
 
+    /// If the thread is loaded, removes it from the thread manager, requests
+    /// shutdown, and waits briefly for it to finish.
+    async fn shutdown_loaded_thread(&self, thread_id: ThreadId) {
+        if let Some(conversation) = self.thread_manager.remove_thread(&thread_id).await {
+            info!("thread {thread_id} was active; shutting down");
+            let mut events = self.thread_events.subscribe(thread_id, &conversation);
+            let notify = Arc::new(tokio::sync::Notify::new());
+            let notify_clone = notify.clone();
+
+            // Establish the listener for ShutdownComplete before submitting
+            // Shutdown so it is not missed.
+            let is_shutdown = tokio::spawn(async move {
+                // Create the notified future outside the loop to avoid losing notifications.
+                let notified = notify_clone.notified();
+                tokio::pin!(notified);
+                loop {
+                    select! {
+                        _ = &mut notified => { break; }
+                        event = events.recv() => {
+                            match event {
+                                Ok(event) => {
+                                    if matches!(event.msg, EventMsg::ShutdownComplete) { break; }
+                                }
+                                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                                // Break when the thread is gone to avoid tight loops.
+                                Err(broadcast::error::RecvError::Closed) => { break; }
+                            }
+                        }
+                    }
+                }
+            });
+            // Request shutdown.
+            match conversation.submit(Op::Shutdown).await {
+                Ok(_) => {
+                    // Successfully submitted Shutdown; wait before proceeding.
+                    select! {
+                        _ = is_shutdown => {
+                            // Normal shutdown.
+                        }
+                        _ = tokio::time::sleep(Duration::from_secs(10)) => {
+                            warn!("thread {thread_id} shutdown timed out; proceeding anyway");
+                            // Wake any waiter; use notify_waiters to avoid missing the signal.
+                            notify.notify_waiters();
+                            // Perhaps we lost a shutdown race, so let's continue to
+                            // clean up the .jsonl file.
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!("failed to submit Shutdown to thread {thread_id}: {err}");
+                    notify.notify_waiters();
+                }
+            }
+            self.thread_events.forget(thread_id);
+        }
+    }
+
     async fn archive_thread_common(
         &mut self,
         thread_id: ThreadId,
@@ -3439,75 +4654,17 @@ impl CodexMessageProcessor {
             });
         }
 
-        // If the thread is active, request shutdown and wait briefly.
-        if let Some(conversation) = self.thread_manager.remove_thread(&thread_id).await {
-            info!("thread {thread_id} was active; shutting down");
-            let conversation_clone = conversation.clone();
-            let notify = Arc::new(tokio::sync::Notify::new());
-            let notify_clone = notify.clone();
-
-            // Establish the listener for ShutdownComplete before submitting
-            // Shutdown so it is not missed.
-            let is_shutdown = tokio::spawn(async move {
-                // Create the notified future outside the loop to avoid losing notifications.
-                let notified = notify_clone.notified();
-                tokio::pin!(notified);
-                loop {
-                    select! {
-                        _ = &mut notified => { break; }
-                        event = conversation_clone.next_event() => {
-                            match event {
-                                Ok(event) => {
-                                    if matches!(event.msg, EventMsg::ShutdownComplete) { break; }
-                                }
-                                // Break on errors to avoid tight loops when the agent loop has exited.
-                                Err(_) => { break; }
-                            }
-                        }
-                    }
-                }
-            });
-            // Request shutdown.
-            match conversation.submit(Op::Shutdown).await {
-                Ok(_) => {
-                    // Successfully submitted Shutdown; wait before proceeding.
-                    select! {
-                        _ = is_shutdown => {
-                            // Normal shutdown: proceed with archive.
-                        }
-                        _ = tokio::time::sleep(Duration::from_secs(10)) => {
-                            warn!("thread {thread_id} shutdown timed out; proceeding with archive");
-                            // Wake any waiter; use notify_waiters to avoid missing the signal.
-                            notify.notify_waiters();
-                            // Perhaps we lost a shutdown race, so let's continue to
-                            // clean up the .jsonl file.
-                        }
-                    }
-                }
-                Err(err) => {
-                    error!("failed to submit Shutdown to thread {thread_id}: {err}");
-                    notify.notify_waiters();
-                }
-            }
-        }
-
-        // Move the rollout file to archived.
-        let result: std::io::Result<()> = async {
-            let archive_folder = self
-                .config
-                .codex_home
-                .join(codex_core::ARCHIVED_SESSIONS_SUBDIR);
-            tokio::fs::create_dir_all(&archive_folder).await?;
-            tokio::fs::rename(&canonical_rollout_path, &archive_folder.join(&file_name)).await?;
-            Ok(())
-        }
-        .await;
+        self.shutdown_loaded_thread(thread_id).await;
 
-        result.map_err(|err| JSONRPCErrorError {
-            code: INTERNAL_ERROR_CODE,
-            message: format!("failed to archive thread: {err}"),
-            data: None,
-        })
+        // Compress the rollout into cold storage.
+        archive_rollout(&self.config.codex_home, &canonical_rollout_path)
+            .await
+            .map(|_| ())
+            .map_err(|err| JSONRPCErrorError {
+                code: INTERNAL_ERROR_CODE,
+                message: format!("failed to archive thread: {err}"),
+                data: None,
+            })
     }
 
     async fn send_user_message(&self, request_id: RequestId, params: SendUserMessageParams) {
@@ -3637,6 +4794,78 @@ impl CodexMessageProcessor {
             .await;
     }
 
+    async fn subagents_list(&self, request_id: RequestId, params: SubAgentsListParams) {
+        let SubAgentsListParams { cwds } = params;
+        let cwds = if cwds.is_empty() {
+            vec![self.config.cwd.clone()]
+        } else {
+            cwds
+        };
+
+        let codex_home = &self.config.codex_home;
+        let extra_dirs = &self.config.subagent_extra_dirs;
+        let data = cwds
+            .into_iter()
+            .map(|cwd| {
+                let roots = subagent_search_roots(&cwd, codex_home, extra_dirs)
+                    .into_iter()
+                    .map(|root| codex_app_server_protocol::SubAgentRoot {
+                        path: root.path,
+                        source: root.source.into(),
+                    })
+                    .collect();
+                let outcome = load_subagents(&cwd, codex_home, extra_dirs);
+                codex_app_server_protocol::SubAgentsListEntry {
+                    cwd,
+                    roots,
+                    subagents: outcome
+                        .subagents
+                        .iter()
+                        .map(|subagent| subagent.to_info().into())
+                        .collect(),
+                    errors: outcome.errors.into_iter().map(Into::into).collect(),
+                }
+            })
+            .collect();
+        self.outgoing
+            .send_response(request_id, SubAgentsListResponse { data })
+            .await;
+    }
+
+    async fn subagents_run(&mut self, request_id: RequestId, params: SubAgentsRunParams) {
+        let SubAgentsRunParams {
+            thread_id,
+            name,
+            prompt,
+            dry_run,
+        } = params;
+
+        let (_thread_id, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        if let Err(err) = thread
+            .submit(Op::RunSubAgent {
+                name,
+                prompt,
+                dry_run,
+            })
+            .await
+        {
+            self.send_internal_error(request_id, format!("failed to run subagent: {err}"))
+                .await;
+            return;
+        }
+
+        self.outgoing
+            .send_response(request_id, SubAgentsRunResponse {})
+            .await;
+    }
+
     async fn skills_config_write(&self, request_id: RequestId, params: SkillsConfigWriteParams) {
         let SkillsConfigWriteParams { path, enabled } = params;
         let edits = vec![ConfigEdit::SetSkillConfig { path, enabled }];
@@ -4087,6 +5316,7 @@ impl CodexMessageProcessor {
             }
         };
 
+        let mut events = self.thread_events.subscribe(conversation_id, &conversation);
         let subscription_id = Uuid::new_v4();
         let (cancel_tx, mut cancel_rx) = oneshot::channel();
         self.conversation_listeners
@@ -4107,13 +5337,16 @@ impl CodexMessageProcessor {
                         // User has unsubscribed, so exit this task.
                         break;
                     }
-                    event = conversation.next_event() => {
+                    event = events.recv() => {
                         let event = match event {
                             Ok(event) => event,
-                            Err(err) => {
-                                tracing::warn!("thread.next_event() failed with: {err}");
-                                break;
+                            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                                tracing::warn!(
+                                    "listener for thread {conversation_id} fell behind; skipped {skipped} events"
+                                );
+                                continue;
                             }
+                            Err(broadcast::error::RecvError::Closed) => break,
                         };
 
                         if let EventMsg::RawResponseItem(_) = &event.msg
@@ -4130,28 +5363,31 @@ impl CodexMessageProcessor {
                             EventMsg::TurnComplete(_) => "task_complete",
                             _ => &event.msg.to_string(),
                         };
-                        let mut params = match serde_json::to_value(event.clone()) {
-                            Ok(serde_json::Value::Object(map)) => map,
-                            Ok(_) => {
-                                error!("event did not serialize to an object");
-                                continue;
-                            }
-                            Err(err) => {
-                                error!("failed to serialize event: {err}");
-                                continue;
-                            }
-                        };
-                        params.insert(
-                            "conversationId".to_string(),
-                            conversation_id.to_string().into(),
-                        );
-
-                        outgoing_for_task
-                            .send_notification(OutgoingNotification {
-                                method: format!("codex/event/{event_formatted}"),
-                                params: Some(params.into()),
-                            })
-                            .await;
+                        let method = format!("codex/event/{event_formatted}");
+                        if !outgoing_for_task.is_notification_excluded(&method) {
+                            let mut params = match serde_json::to_value(event.clone()) {
+                                Ok(serde_json::Value::Object(map)) => map,
+                                Ok(_) => {
+                                    error!("event did not serialize to an object");
+                                    continue;
+                                }
+                                Err(err) => {
+                                    error!("failed to serialize event: {err}");
+                                    continue;
+                                }
+                            };
+                            params.insert(
+                                "conversationId".to_string(),
+                                conversation_id.to_string().into(),
+                            );
+
+                            outgoing_for_task
+                                .send_notification(OutgoingNotification {
+                                    method,
+                                    params: Some(params.into()),
+                                })
+                                .await;
+                        }
 
                         apply_bespoke_event_handling(
                             event.clone(),
@@ -4491,19 +5727,23 @@ pub(crate) async fn read_summary_from_rollout(
         cli_version: session_meta.cli_version,
         source: session_meta.source,
         git_info,
+        forked_from_id: session_meta.forked_from_id,
     })
 }
 
-pub(crate) async fn read_event_msgs_from_rollout(
-    path: &Path,
-) -> std::io::Result<Vec<codex_protocol::protocol::EventMsg>> {
-    let items = match RolloutRecorder::get_rollout_history(path).await? {
+async fn read_rollout_items(path: &Path) -> std::io::Result<Vec<RolloutItem>> {
+    Ok(match RolloutRecorder::get_rollout_history(path).await? {
         InitialHistory::New => Vec::new(),
         InitialHistory::Forked(items) => items,
         InitialHistory::Resumed(resumed) => resumed.history,
-    };
+    })
+}
 
-    Ok(items
+pub(crate) async fn read_event_msgs_from_rollout(
+    path: &Path,
+) -> std::io::Result<Vec<codex_protocol::protocol::EventMsg>> {
+    Ok(read_rollout_items(path)
+        .await?
         .into_iter()
         .filter_map(|item| match item {
             RolloutItem::EventMsg(event) => Some(event),
@@ -4557,6 +5797,7 @@ fn extract_conversation_summary(
         cli_version: session_meta.cli_version.clone(),
         source: session_meta.source.clone(),
         git_info,
+        forked_from_id: session_meta.forked_from_id,
     })
 }
 
@@ -4600,6 +5841,7 @@ pub(crate) fn summary_to_thread(summary: ConversationSummary) -> Thread {
         cli_version,
         source,
         git_info,
+        forked_from_id,
     } = summary;
 
     let created_at = parse_datetime(timestamp.as_deref());
@@ -4621,10 +5863,22 @@ pub(crate) fn summary_to_thread(summary: ConversationSummary) -> Thread {
         cli_version,
         source: source.into(),
         git_info,
+        forked_from_id: forked_from_id.map(|id| id.to_string()),
+        name: None,
+        tags: Vec::new(),
+        model: None,
+        diff_stat: None,
         turns: Vec::new(),
     }
 }
 
+fn apply_thread_labels(thread: &mut Thread, labels: &BTreeMap<String, ThreadLabels>) {
+    if let Some(labels) = labels.get(&thread.id) {
+        thread.name = labels.name.clone();
+        thread.tags = labels.tags.clone();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4690,6 +5944,7 @@ mod tests {
             cli_version: "0.0.0".to_string(),
             source: SessionSource::VSCode,
             git_info: None,
+            forked_from_id: None,
         };
 
         assert_eq!(summary, expected);
@@ -4746,6 +6001,7 @@ mod tests {
             cli_version: String::new(),
             source: SessionSource::VSCode,
             git_info: None,
+            forked_from_id: None,
         };
 
         assert_eq!(summary, expected);
@@ -1,10 +1,15 @@
 use crate::error_code::INTERNAL_ERROR_CODE;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use codex_app_server_protocol::ConfigBatchWriteParams;
+use codex_app_server_protocol::ConfigDiagnostic;
+use codex_app_server_protocol::ConfigDiagnosticKind;
 use codex_app_server_protocol::ConfigReadParams;
 use codex_app_server_protocol::ConfigReadResponse;
 use codex_app_server_protocol::ConfigRequirements;
 use codex_app_server_protocol::ConfigRequirementsReadResponse;
+use codex_app_server_protocol::ConfigSchemaReadResponse;
+use codex_app_server_protocol::ConfigValidateParams;
+use codex_app_server_protocol::ConfigValidateResponse;
 use codex_app_server_protocol::ConfigValueWriteParams;
 use codex_app_server_protocol::ConfigWriteErrorCode;
 use codex_app_server_protocol::ConfigWriteResponse;
@@ -12,10 +17,14 @@ use codex_app_server_protocol::JSONRPCErrorError;
 use codex_app_server_protocol::SandboxMode;
 use codex_core::config::ConfigService;
 use codex_core::config::ConfigServiceError;
+use codex_core::config::schema::config_schema;
+use codex_core::config::validate::ConfigDiagnostic as CoreConfigDiagnostic;
+use codex_core::config::validate::ConfigDiagnosticKind as CoreConfigDiagnosticKind;
 use codex_core::config_loader::ConfigRequirementsToml;
 use codex_core::config_loader::LoaderOverrides;
 use codex_core::config_loader::SandboxModeRequirement as CoreSandboxModeRequirement;
 use serde_json::json;
+use std::path::Path;
 use std::path::PathBuf;
 use toml::Value as TomlValue;
 
@@ -55,6 +64,29 @@ impl ConfigApi {
         Ok(ConfigRequirementsReadResponse { requirements })
     }
 
+    pub(crate) fn schema_read(&self) -> Result<ConfigSchemaReadResponse, JSONRPCErrorError> {
+        let schema = serde_json::to_value(config_schema()).map_err(|err| JSONRPCErrorError {
+            code: INTERNAL_ERROR_CODE,
+            message: format!("failed to serialize the config schema: {err}"),
+            data: None,
+        })?;
+        Ok(ConfigSchemaReadResponse { schema })
+    }
+
+    pub(crate) async fn validate(
+        &self,
+        params: ConfigValidateParams,
+    ) -> Result<ConfigValidateResponse, JSONRPCErrorError> {
+        let diagnostics = self
+            .service
+            .validate(params.cwd.as_deref().map(Path::new))
+            .await
+            .map_err(map_error)?;
+        Ok(ConfigValidateResponse {
+            diagnostics: diagnostics.into_iter().map(map_diagnostic).collect(),
+        })
+    }
+
     pub(crate) async fn write_value(
         &self,
         params: ConfigValueWriteParams,
@@ -84,6 +116,32 @@ fn map_requirements_toml_to_api(requirements: ConfigRequirementsToml) -> ConfigR
                 .filter_map(map_sandbox_mode_requirement_to_api)
                 .collect()
         }),
+        allowed_model_providers: requirements.allowed_model_providers,
+        allowed_mcp_servers: requirements
+            .mcp_servers
+            .map(|servers| servers.into_keys().collect()),
+        require_audit_log: requirements.require_audit_log,
+    }
+}
+
+fn map_diagnostic(diagnostic: CoreConfigDiagnostic) -> ConfigDiagnostic {
+    ConfigDiagnostic {
+        kind: match diagnostic.kind {
+            CoreConfigDiagnosticKind::UnknownKey => ConfigDiagnosticKind::UnknownKey,
+            CoreConfigDiagnosticKind::TypeMismatch => ConfigDiagnosticKind::TypeMismatch,
+            CoreConfigDiagnosticKind::Deprecated => ConfigDiagnosticKind::Deprecated,
+        },
+        layer: diagnostic.source,
+        file_path: diagnostic
+            .file
+            .map(|file| file.to_string_lossy().into_owned()),
+        key_path: diagnostic.key_path,
+        line: diagnostic.line.and_then(|line| u32::try_from(line).ok()),
+        column: diagnostic
+            .column
+            .and_then(|column| u32::try_from(column).ok()),
+        message: diagnostic.message,
+        suggestion: diagnostic.suggestion,
     }
 }
 
@@ -136,6 +194,8 @@ mod tests {
                 CoreSandboxModeRequirement::ExternalSandbox,
             ]),
             mcp_servers: None,
+            allowed_model_providers: Some(vec!["openai".to_string()]),
+            require_audit_log: Some(true),
         };
 
         let mapped = map_requirements_toml_to_api(requirements);
@@ -151,5 +211,11 @@ mod tests {
             mapped.allowed_sandbox_modes,
             Some(vec![SandboxMode::ReadOnly]),
         );
+        assert_eq!(
+            mapped.allowed_model_providers,
+            Some(vec!["openai".to_string()])
+        );
+        assert_eq!(mapped.allowed_mcp_servers, None);
+        assert_eq!(mapped.require_audit_log, Some(true));
     }
 }
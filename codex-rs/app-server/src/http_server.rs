@@ -0,0 +1,648 @@
+//! HTTP facade (`--listen http://HOST:PORT`) for CI jobs and dashboards that
+//! would rather not speak JSON-RPC. It drives a single processor as a
+//! JSON-RPC client, shared by every HTTP caller:
+//!
+//! - `POST /v1/threads`: `thread/start`; the body is its params (may be empty).
+//! - `POST /v1/threads/{thread_id}/turns`: `turn/start`; the body is its
+//!   params without `threadId`. `{"text": "..."}` is shorthand for a single
+//!   text input.
+//! - `POST /v1/threads/{thread_id}/turns/{turn_id}/interrupt`: `turn/interrupt`.
+//! - `POST /v1/requests/{request_id}`: answers a server request (such as an
+//!   approval) with the body as its result.
+//! - `GET /v1/events[?threadId=...]`: server-sent events. Each notification is
+//!   an event named after its method with its params as data; server requests
+//!   arrive as `serverRequest` events carrying the whole request. Server
+//!   requests still awaiting an answer are replayed to each new subscriber
+//!   until they are answered or their turn completes.
+//!
+//! Every request needs a token (one is generated when `CODEX_APP_SERVER_TOKEN`
+//! is unset), and `POST` bodies must be `application/json` so that browsers
+//! cannot send them as simple cross-site requests.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::ready;
+use std::io::Result as IoResult;
+use std::sync::Arc;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+
+use axum::Router;
+use axum::extract::Path;
+use axum::extract::Request;
+use axum::extract::State;
+use axum::http::Method;
+use axum::http::StatusCode;
+use axum::http::header::CONTENT_TYPE;
+use axum::middleware;
+use axum::middleware::Next;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::response::sse::Event;
+use axum::response::sse::KeepAlive;
+use axum::response::sse::Sse;
+use axum::routing::get;
+use axum::routing::post;
+use codex_app_server_protocol::APP_SERVER_PROTOCOL_VERSION;
+use codex_app_server_protocol::ClientInfo;
+use codex_app_server_protocol::InitializeParams;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::JSONRPCMessage;
+use codex_app_server_protocol::JSONRPCRequest;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use futures::Stream;
+use futures::StreamExt;
+use rand::Rng;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::Mutex;
+use tokio::sync::broadcast;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use tokio::sync::watch;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use crate::CHANNEL_CAPACITY;
+use crate::ProcessorContext;
+use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::listen;
+use crate::outgoing_message::OutgoingMessage;
+use crate::serialize_outgoing;
+use crate::spawn_processor;
+
+/// `clientInfo.name` the facade initializes the processor with.
+const CLIENT_NAME: &str = "codex_app_server_http";
+
+/// Events buffered per SSE subscriber before it starts missing some.
+const EVENT_CAPACITY: usize = 1024;
+
+/// Once a turn completes, its server requests can no longer be answered.
+const TURN_COMPLETED_METHOD: &str = "turn/completed";
+
+type PendingCalls = Arc<Mutex<HashMap<RequestId, oneshot::Sender<RpcResult>>>>;
+type RpcResult = Result<Value, JSONRPCErrorError>;
+
+/// Server requests still awaiting an answer, in the order they were sent.
+type PendingServerRequests = Arc<Mutex<Vec<(RequestId, ServerEvent)>>>;
+
+pub(crate) async fn serve(address: &str, processor_context: ProcessorContext) -> IoResult<()> {
+    let (listener, auth_token) = listen::bind(address, "http").await?;
+    // Without a configured token the server is bound to loopback (`bind`
+    // refuses anything else), and the generated token keeps other local
+    // users and browser pages out.
+    let require_loopback = auth_token.is_none();
+    let auth_token = auth_token.unwrap_or_else(|| {
+        let token = generate_token();
+        eprintln!("codex app-server http token: {token}");
+        token
+    });
+
+    let (incoming_tx, incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
+    let (outgoing_tx, outgoing_rx) = mpsc::channel::<OutgoingMessage>(CHANNEL_CAPACITY);
+    let processor_handle = spawn_processor(processor_context, incoming_rx, outgoing_tx);
+
+    let pending = PendingCalls::default();
+    let server_requests = PendingServerRequests::default();
+    let (events, _) = broadcast::channel(EVENT_CAPACITY);
+    let dispatcher_handle = tokio::spawn(dispatch_outgoing(
+        outgoing_rx,
+        pending.clone(),
+        server_requests.clone(),
+        events.clone(),
+    ));
+    let client = Arc::new(RpcClient {
+        incoming_tx,
+        next_id: AtomicI64::new(0),
+        pending,
+        server_requests,
+        events,
+    });
+    client.initialize().await?;
+
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let state = HttpState {
+        client: client.clone(),
+        auth_token,
+        require_loopback,
+        shutdown_rx,
+    };
+    let router = Router::new()
+        .route("/v1/threads", post(start_thread))
+        .route("/v1/threads/{thread_id}/turns", post(start_turn))
+        .route(
+            "/v1/threads/{thread_id}/turns/{turn_id}/interrupt",
+            post(interrupt_turn),
+        )
+        .route("/v1/requests/{request_id}", post(answer_request))
+        .route("/v1/events", get(events))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state);
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async move {
+            let _ = tokio::signal::ctrl_c().await;
+            info!("received Ctrl-C; closing event streams");
+            // Event streams never end on their own, so end them before the
+            // server waits for open connections.
+            let _ = shutdown_tx.send(true);
+        })
+        .await?;
+
+    // The router is gone; dropping the last client closes the processor's
+    // input. Thread listeners may still hold the outgoing channel, so stop
+    // the dispatcher explicitly.
+    drop(client);
+    let _ = processor_handle.await;
+    dispatcher_handle.abort();
+    Ok(())
+}
+
+#[derive(Clone)]
+struct HttpState {
+    client: Arc<RpcClient>,
+    auth_token: String,
+    /// Also reject requests whose `Host` or `Origin` is not loopback.
+    require_loopback: bool,
+    shutdown_rx: watch::Receiver<bool>,
+}
+
+/// One server-to-client message, ready to be sent as an SSE event.
+#[derive(Debug, Clone)]
+struct ServerEvent {
+    name: String,
+    thread_id: Option<String>,
+    data: String,
+}
+
+struct RpcClient {
+    incoming_tx: mpsc::Sender<JSONRPCMessage>,
+    next_id: AtomicI64,
+    pending: PendingCalls,
+    server_requests: PendingServerRequests,
+    events: broadcast::Sender<ServerEvent>,
+}
+
+impl RpcClient {
+    async fn initialize(&self) -> IoResult<()> {
+        let params = InitializeParams {
+            client_info: ClientInfo {
+                name: CLIENT_NAME.to_string(),
+                title: None,
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            protocol_version: Some(APP_SERVER_PROTOCOL_VERSION),
+            capabilities: None,
+        };
+        let params = serde_json::to_value(params).map_err(std::io::Error::other)?;
+        self.call("initialize", params)
+            .await
+            .map(|_| ())
+            .map_err(|err| std::io::Error::other(format!("initialize failed: {}", err.message)))
+    }
+
+    async fn call(&self, method: &str, params: Value) -> RpcResult {
+        let id = RequestId::Integer(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id.clone(), tx);
+        let request = JSONRPCMessage::Request(JSONRPCRequest {
+            id: id.clone(),
+            method: method.to_string(),
+            params: Some(params),
+        });
+        if self.incoming_tx.send(request).await.is_err() {
+            self.pending.lock().await.remove(&id);
+            return Err(shutting_down());
+        }
+        rx.await.unwrap_or_else(|_| Err(shutting_down()))
+    }
+
+    async fn respond(&self, id: RequestId, result: Value) -> Result<(), JSONRPCErrorError> {
+        self.server_requests
+            .lock()
+            .await
+            .retain(|(pending_id, _)| *pending_id != id);
+        let response = JSONRPCMessage::Response(JSONRPCResponse { id, result });
+        self.incoming_tx
+            .send(response)
+            .await
+            .map_err(|_| shutting_down())
+    }
+
+    /// Subscribes to events, returning the server requests that are still
+    /// awaiting an answer. The dispatcher records and broadcasts a request
+    /// under the same lock, so each one arrives exactly once: either in the
+    /// returned list or through the receiver.
+    async fn subscribe(&self) -> (Vec<ServerEvent>, broadcast::Receiver<ServerEvent>) {
+        let server_requests = self.server_requests.lock().await;
+        let pending = server_requests
+            .iter()
+            .map(|(_, event)| event.clone())
+            .collect();
+        (pending, self.events.subscribe())
+    }
+}
+
+fn shutting_down() -> JSONRPCErrorError {
+    JSONRPCErrorError {
+        code: INTERNAL_ERROR_CODE,
+        message: "app server is shutting down".to_string(),
+        data: None,
+    }
+}
+
+/// Routes the processor's output: responses complete pending calls, and
+/// everything else is broadcast to SSE subscribers. Server requests are also
+/// kept until answered or until their turn completes, so that subscribers
+/// that connect later see them.
+async fn dispatch_outgoing(
+    mut outgoing_rx: mpsc::Receiver<OutgoingMessage>,
+    pending: PendingCalls,
+    server_requests: PendingServerRequests,
+    events: broadcast::Sender<ServerEvent>,
+) {
+    while let Some(outgoing_message) = outgoing_rx.recv().await {
+        // Round-trip through the wire format so HTTP callers see exactly what
+        // stdio clients see.
+        let Some(json) = serialize_outgoing(outgoing_message) else {
+            continue;
+        };
+        let message = match serde_json::from_str::<JSONRPCMessage>(&json) {
+            Ok(message) => message,
+            Err(e) => {
+                error!("Failed to deserialize JSONRPCMessage: {e}");
+                continue;
+            }
+        };
+        dispatch_message(message, json, &pending, &server_requests, &events).await;
+    }
+}
+
+async fn dispatch_message(
+    message: JSONRPCMessage,
+    json: String,
+    pending: &PendingCalls,
+    server_requests: &PendingServerRequests,
+    events: &broadcast::Sender<ServerEvent>,
+) {
+    let event = match message {
+        JSONRPCMessage::Response(JSONRPCResponse { id, result }) => {
+            complete(pending, id, Ok(result)).await;
+            return;
+        }
+        JSONRPCMessage::Error(JSONRPCError { id, error }) => {
+            complete(pending, id, Err(error)).await;
+            return;
+        }
+        JSONRPCMessage::Notification(notification) => {
+            let thread_id = thread_id_of(notification.params.as_ref());
+            if notification.method == TURN_COMPLETED_METHOD
+                && let Some(thread_id) = &thread_id
+            {
+                // Whether the request was answered, timed out or the turn was
+                // interrupted, later subscribers must not see it again.
+                server_requests
+                    .lock()
+                    .await
+                    .retain(|(_, event)| event.thread_id.as_ref() != Some(thread_id));
+            }
+            ServerEvent {
+                thread_id,
+                data: notification
+                    .params
+                    .map_or_else(|| "{}".to_string(), |params| params.to_string()),
+                name: notification.method,
+            }
+        }
+        JSONRPCMessage::Request(request) => {
+            let event = ServerEvent {
+                name: "serverRequest".to_string(),
+                thread_id: thread_id_of(request.params.as_ref()),
+                data: json,
+            };
+            let mut server_requests = server_requests.lock().await;
+            server_requests.push((request.id, event.clone()));
+            let _ = events.send(event);
+            return;
+        }
+    };
+    // No subscribers is fine; notifications are simply dropped.
+    let _ = events.send(event);
+}
+
+async fn complete(pending: &PendingCalls, id: RequestId, result: RpcResult) {
+    match pending.lock().await.remove(&id) {
+        Some(tx) => {
+            let _ = tx.send(result);
+        }
+        None => warn!("response for unknown request {id:?}"),
+    }
+}
+
+fn thread_id_of(params: Option<&Value>) -> Option<String> {
+    let params = params?;
+    params
+        .get("threadId")
+        .or_else(|| params.get("thread").and_then(|thread| thread.get("id")))
+        .and_then(Value::as_str)
+        .map(ToString::to_string)
+}
+
+async fn require_token(State(state): State<HttpState>, request: Request, next: Next) -> Response {
+    if state.require_loopback && !listen::is_loopback_request(&request) {
+        return error_response(StatusCode::FORBIDDEN, "forbidden");
+    }
+    if !listen::is_authorized(&request, Some(&state.auth_token)) {
+        return error_response(StatusCode::UNAUTHORIZED, "unauthorized");
+    }
+    if request.method() == Method::POST && !is_json_content_type(&request) {
+        return error_response(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Content-Type must be application/json",
+        );
+    }
+    next.run(request).await
+}
+
+fn is_json_content_type(request: &Request) -> bool {
+    request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"))
+}
+
+/// 128 random bits, hex-encoded.
+fn generate_token() -> String {
+    let bytes: [u8; 16] = rand::rng().random();
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+async fn start_thread(State(state): State<HttpState>, body: String) -> Response {
+    let params = match parse_params(&body) {
+        Ok(params) => params,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, &message),
+    };
+    rpc_response(state.client.call("thread/start", params).await)
+}
+
+async fn start_turn(
+    State(state): State<HttpState>,
+    Path(thread_id): Path<String>,
+    body: String,
+) -> Response {
+    let params = match parse_params(&body) {
+        Ok(params) => turn_start_params(thread_id, params),
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, &message),
+    };
+    rpc_response(state.client.call("turn/start", params).await)
+}
+
+async fn interrupt_turn(
+    State(state): State<HttpState>,
+    Path((thread_id, turn_id)): Path<(String, String)>,
+) -> Response {
+    let params = json!({ "threadId": thread_id, "turnId": turn_id });
+    rpc_response(state.client.call("turn/interrupt", params).await)
+}
+
+async fn answer_request(
+    State(state): State<HttpState>,
+    Path(request_id): Path<String>,
+    body: String,
+) -> Response {
+    let result = match parse_params(&body) {
+        Ok(result) => result,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, &message),
+    };
+    let id = match request_id.parse::<i64>() {
+        Ok(id) => RequestId::Integer(id),
+        Err(_) => RequestId::String(request_id),
+    };
+    match state.client.respond(id, result).await {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(err) => rpc_response(Err(err)),
+    }
+}
+
+async fn events(
+    State(state): State<HttpState>,
+    request: Request,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let thread_filter = listen::query_param(&request, "threadId").map(ToString::to_string);
+    let (pending_requests, receiver) = state.client.subscribe().await;
+    let live = futures::stream::unfold(
+        (receiver, state.shutdown_rx),
+        |(mut receiver, mut shutdown_rx)| async move {
+            let event = tokio::select! {
+                _ = shutdown_rx.changed() => return None,
+                event = receiver.recv() => event,
+            };
+            let event = match event {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => ServerEvent {
+                    name: "lagged".to_string(),
+                    thread_id: None,
+                    data: json!({ "skipped": skipped }).to_string(),
+                },
+                Err(broadcast::error::RecvError::Closed) => return None,
+            };
+            Some((event, (receiver, shutdown_rx)))
+        },
+    );
+    let stream = futures::stream::iter(pending_requests)
+        .chain(live)
+        .filter(move |event| {
+            ready(match (&thread_filter, &event.thread_id) {
+                (Some(filter), Some(thread_id)) => filter == thread_id,
+                _ => true,
+            })
+        })
+        .map(|event| Ok(Event::default().event(event.name).data(event.data)));
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// Parses a request body as a JSON object; an empty body is `{}`.
+fn parse_params(body: &str) -> Result<Value, String> {
+    if body.trim().is_empty() {
+        return Ok(json!({}));
+    }
+    match serde_json::from_str::<Value>(body) {
+        Ok(value) if value.is_object() => Ok(value),
+        Ok(_) => Err("request body must be a JSON object".to_string()),
+        Err(err) => Err(format!("invalid JSON body: {err}")),
+    }
+}
+
+/// Builds `turn/start` params from a request body, expanding the `text`
+/// shorthand into a single text input.
+fn turn_start_params(thread_id: String, mut params: Value) -> Value {
+    if let Some(object) = params.as_object_mut() {
+        if !object.contains_key("input")
+            && let Some(Value::String(text)) = object.remove("text")
+        {
+            object.insert(
+                "input".to_string(),
+                json!([{ "type": "text", "text": text }]),
+            );
+        }
+        object.insert("threadId".to_string(), Value::String(thread_id));
+    }
+    params
+}
+
+fn rpc_response(result: RpcResult) -> Response {
+    match result {
+        Ok(result) => json_response(StatusCode::OK, &result),
+        Err(error) => {
+            let status = if error.code == INVALID_REQUEST_ERROR_CODE {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            json_response(status, &json!({ "error": error }))
+        }
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    json_response(status, &json!({ "error": { "message": message } }))
+}
+
+fn json_response(status: StatusCode, body: &Value) -> Response {
+    (
+        status,
+        [(CONTENT_TYPE, "application/json")],
+        body.to_string(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn expands_text_shorthand_into_turn_input() {
+        let params = turn_start_params(
+            "thr_1".to_string(),
+            json!({ "text": "fix the build", "model": "gpt-5" }),
+        );
+        assert_eq!(
+            params,
+            json!({
+                "threadId": "thr_1",
+                "input": [{ "type": "text", "text": "fix the build" }],
+                "model": "gpt-5",
+            })
+        );
+
+        let input = json!([{ "type": "image", "url": "https://example.com/a.png" }]);
+        let params = turn_start_params("thr_1".to_string(), json!({ "input": input.clone() }));
+        assert_eq!(params, json!({ "threadId": "thr_1", "input": input }));
+    }
+
+    #[test]
+    fn finds_thread_ids_in_notification_params() {
+        assert_eq!(
+            thread_id_of(Some(&json!({ "threadId": "thr_1", "turn": {} }))),
+            Some("thr_1".to_string())
+        );
+        assert_eq!(
+            thread_id_of(Some(&json!({ "thread": { "id": "thr_2" } }))),
+            Some("thr_2".to_string())
+        );
+        assert_eq!(thread_id_of(Some(&json!({ "summary": "x" }))), None);
+        assert_eq!(thread_id_of(None), None);
+    }
+
+    #[tokio::test]
+    async fn completed_turns_drop_their_pending_server_requests() {
+        let (incoming_tx, _incoming_rx) = mpsc::channel(1);
+        let client = &RpcClient {
+            incoming_tx,
+            next_id: AtomicI64::new(0),
+            pending: PendingCalls::default(),
+            server_requests: PendingServerRequests::default(),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        };
+        let dispatch = |message: JSONRPCMessage| {
+            let json = serde_json::to_string(&message).expect("serialize message");
+            dispatch_message(
+                message,
+                json,
+                &client.pending,
+                &client.server_requests,
+                &client.events,
+            )
+        };
+        let request = |id: i64, thread_id: &str| {
+            JSONRPCMessage::Request(JSONRPCRequest {
+                id: RequestId::Integer(id),
+                method: "item/commandExecution/requestApproval".to_string(),
+                params: Some(json!({ "threadId": thread_id, "turnId": "turn_1" })),
+            })
+        };
+        let pending_threads = || async move {
+            let (pending, _receiver) = client.subscribe().await;
+            pending
+                .into_iter()
+                .map(|event| event.thread_id)
+                .collect::<Vec<_>>()
+        };
+
+        dispatch(request(1, "thr_1")).await;
+        dispatch(request(2, "thr_2")).await;
+        dispatch(request(3, "thr_1")).await;
+        assert_eq!(
+            pending_threads().await,
+            vec![
+                Some("thr_1".to_string()),
+                Some("thr_2".to_string()),
+                Some("thr_1".to_string()),
+            ]
+        );
+
+        dispatch(JSONRPCMessage::Notification(
+            codex_app_server_protocol::JSONRPCNotification {
+                method: TURN_COMPLETED_METHOD.to_string(),
+                params: Some(json!({ "threadId": "thr_1", "turn": { "id": "turn_1" } })),
+            },
+        ))
+        .await;
+        assert_eq!(pending_threads().await, vec![Some("thr_2".to_string())]);
+
+        client
+            .respond(RequestId::Integer(2), json!({ "decision": "accept" }))
+            .await
+            .expect("respond");
+        assert_eq!(pending_threads().await, Vec::<Option<String>>::new());
+    }
+
+    #[test]
+    fn post_bodies_must_be_json() {
+        let with_content_type = |content_type: Option<&str>| {
+            let mut builder = Request::builder().method(Method::POST).uri("/v1/threads");
+            if let Some(content_type) = content_type {
+                builder = builder.header(CONTENT_TYPE, content_type);
+            }
+            builder.body(axum::body::Body::empty()).expect("request")
+        };
+        assert!(is_json_content_type(&with_content_type(Some(
+            "application/json"
+        ))));
+        assert!(is_json_content_type(&with_content_type(Some(
+            "Application/JSON; charset=utf-8"
+        ))));
+        assert!(!is_json_content_type(&with_content_type(Some(
+            "text/plain"
+        ))));
+        assert!(!is_json_content_type(&with_content_type(None)));
+    }
+}
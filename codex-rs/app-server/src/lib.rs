@@ -11,10 +11,14 @@ use std::path::PathBuf;
 use crate::message_processor::MessageProcessor;
 use crate::outgoing_message::OutgoingMessage;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::thread_events::ThreadEventHub;
 use codex_app_server_protocol::ConfigWarningNotification;
 use codex_app_server_protocol::JSONRPCMessage;
+use codex_core::AuthManager;
+use codex_core::ThreadManager;
 use codex_core::check_execpolicy_for_warnings;
 use codex_feedback::CodexFeedback;
+use codex_protocol::protocol::SessionSource;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
@@ -35,48 +39,89 @@ mod codex_message_processor;
 mod config_api;
 mod error_code;
 mod fuzzy_file_search;
+mod http_server;
+mod listen;
 mod message_processor;
 mod models;
 mod outgoing_message;
+mod thread_events;
+mod websocket;
 
 /// Size of the bounded channels used to communicate between tasks. The value
 /// is a balance between throughput and memory usage – 128 messages should be
 /// plenty for an interactive CLI.
 const CHANNEL_CAPACITY: usize = 128;
 
+/// How clients reach the app server.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AppServerTransport {
+    /// JSON-RPC over stdin/stdout, one message per line.
+    #[default]
+    Stdio,
+    /// JSON-RPC over WebSocket text frames on `address` (`HOST:PORT`). Each
+    /// connection gets its own processor; threads are shared, so a connection
+    /// can attach to a thread another one started.
+    WebSocket { address: String },
+    /// A REST + server-sent events facade on `address` (`HOST:PORT`) for
+    /// clients that cannot speak JSON-RPC. All HTTP clients share one
+    /// processor.
+    Http { address: String },
+}
+
+/// Names of the [`AppServerTransport`]s, as advertised to clients in the
+/// `initialize` response.
+pub(crate) const TRANSPORT_NAMES: &[&str] = &["stdio", "websocket", "http"];
+
+impl AppServerTransport {
+    /// Parses a `--listen` value such as `ws://127.0.0.1:4500` or `stdio://`.
+    pub fn from_listen_url(url: &str) -> Result<Self, String> {
+        if url == "stdio://" {
+            return Ok(Self::Stdio);
+        }
+        let (address, transport): (&str, fn(String) -> Self) = if let Some(address) =
+            url.strip_prefix("ws://")
+        {
+            (address, |address| Self::WebSocket { address })
+        } else if let Some(address) = url.strip_prefix("http://") {
+            (address, |address| Self::Http { address })
+        } else {
+            return Err(format!(
+                "unsupported listen URL `{url}`: expected ws://HOST:PORT, http://HOST:PORT or stdio://"
+            ));
+        };
+        let address = address.strip_suffix('/').unwrap_or(address);
+        if address.is_empty() || address.contains('/') || !address.contains(':') {
+            return Err(format!(
+                "unsupported listen URL `{url}`: expected a HOST:PORT address"
+            ));
+        }
+        Ok(transport(address.to_string()))
+    }
+}
+
 pub async fn run_main(
     codex_linux_sandbox_exe: Option<PathBuf>,
     cli_config_overrides: CliConfigOverrides,
     loader_overrides: LoaderOverrides,
     default_analytics_enabled: bool,
 ) -> IoResult<()> {
-    // Set up channels.
-    let (incoming_tx, mut incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
-    let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<OutgoingMessage>(CHANNEL_CAPACITY);
-
-    // Task: read from stdin, push to `incoming_tx`.
-    let stdin_reader_handle = tokio::spawn({
-        async move {
-            let stdin = io::stdin();
-            let reader = BufReader::new(stdin);
-            let mut lines = reader.lines();
-
-            while let Some(line) = lines.next_line().await.unwrap_or_default() {
-                match serde_json::from_str::<JSONRPCMessage>(&line) {
-                    Ok(msg) => {
-                        if incoming_tx.send(msg).await.is_err() {
-                            // Receiver gone – nothing left to do.
-                            break;
-                        }
-                    }
-                    Err(e) => error!("Failed to deserialize JSONRPCMessage: {e}"),
-                }
-            }
-
-            debug!("stdin reader finished (EOF)");
-        }
-    });
+    run_main_with_transport(
+        codex_linux_sandbox_exe,
+        cli_config_overrides,
+        loader_overrides,
+        default_analytics_enabled,
+        AppServerTransport::Stdio,
+    )
+    .await
+}
 
+pub async fn run_main_with_transport(
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    cli_config_overrides: CliConfigOverrides,
+    loader_overrides: LoaderOverrides,
+    default_analytics_enabled: bool,
+    transport: AppServerTransport,
+) -> IoResult<()> {
     // Parse CLI overrides once and derive the base Config eagerly so later
     // components do not need to work with raw TOML values.
     let cli_kv_overrides = cli_config_overrides.parse_overrides().map_err(|e| {
@@ -162,77 +207,96 @@ pub async fn run_main(
         }
     }
 
-    // Task: process incoming messages.
-    let processor_handle = tokio::spawn({
-        let outgoing_message_sender = OutgoingMessageSender::new(outgoing_tx);
-        let cli_overrides: Vec<(String, TomlValue)> = cli_kv_overrides.clone();
-        let loader_overrides = loader_overrides_for_config_api;
-        let mut processor = MessageProcessor::new(
-            outgoing_message_sender,
-            codex_linux_sandbox_exe,
-            std::sync::Arc::new(config),
-            cli_overrides,
-            loader_overrides,
-            feedback.clone(),
-            config_warnings,
-        );
-        let mut thread_created_rx = processor.thread_created_receiver();
+    let auth_manager = AuthManager::shared(
+        config.codex_home.clone(),
+        false,
+        config.cli_auth_credentials_store_mode,
+    );
+    let thread_manager = std::sync::Arc::new(ThreadManager::new(
+        config.codex_home.clone(),
+        auth_manager.clone(),
+        SessionSource::VSCode,
+    ));
+    let processor_context = ProcessorContext {
+        codex_linux_sandbox_exe,
+        config: std::sync::Arc::new(config),
+        cli_overrides: cli_kv_overrides,
+        loader_overrides: loader_overrides_for_config_api,
+        feedback,
+        config_warnings,
+        auth_manager,
+        thread_manager,
+        thread_events: std::sync::Arc::new(ThreadEventHub::default()),
+    };
+    match transport {
+        AppServerTransport::Stdio => run_stdio(processor_context).await,
+        AppServerTransport::WebSocket { address } => {
+            websocket::serve(&address, processor_context).await
+        }
+        AppServerTransport::Http { address } => {
+            http_server::serve(&address, processor_context).await
+        }
+    }
+}
+
+/// Everything needed to start a [`MessageProcessor`] for one client.
+#[derive(Clone)]
+pub(crate) struct ProcessorContext {
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    config: std::sync::Arc<Config>,
+    cli_overrides: Vec<(String, TomlValue)>,
+    loader_overrides: LoaderOverrides,
+    feedback: CodexFeedback,
+    config_warnings: Vec<ConfigWarningNotification>,
+    /// Shared by every client, so that clients can attach to each other's
+    /// threads.
+    auth_manager: std::sync::Arc<AuthManager>,
+    thread_manager: std::sync::Arc<ThreadManager>,
+    thread_events: std::sync::Arc<ThreadEventHub>,
+}
+
+async fn run_stdio(processor_context: ProcessorContext) -> IoResult<()> {
+    // Set up channels.
+    let (incoming_tx, incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
+    let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<OutgoingMessage>(CHANNEL_CAPACITY);
+
+    // Task: read from stdin, push to `incoming_tx`.
+    let stdin_reader_handle = tokio::spawn({
         async move {
-            let mut listen_for_threads = true;
-            loop {
-                tokio::select! {
-                    msg = incoming_rx.recv() => {
-                        let Some(msg) = msg else {
+            let stdin = io::stdin();
+            let reader = BufReader::new(stdin);
+            let mut lines = reader.lines();
+
+            while let Some(line) = lines.next_line().await.unwrap_or_default() {
+                match serde_json::from_str::<JSONRPCMessage>(&line) {
+                    Ok(msg) => {
+                        if incoming_tx.send(msg).await.is_err() {
+                            // Receiver gone – nothing left to do.
                             break;
-                        };
-                        match msg {
-                            JSONRPCMessage::Request(r) => processor.process_request(r).await,
-                            JSONRPCMessage::Response(r) => processor.process_response(r).await,
-                            JSONRPCMessage::Notification(n) => processor.process_notification(n).await,
-                            JSONRPCMessage::Error(e) => processor.process_error(e),
-                        }
-                    }
-                    created = thread_created_rx.recv(), if listen_for_threads => {
-                        match created {
-                            Ok(thread_id) => {
-                                processor.try_attach_thread_listener(thread_id).await;
-                            }
-                            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
-                                // TODO(jif) handle lag.
-                                // Assumes thread creation volume is low enough that lag never happens.
-                                // If it does, we log and continue without resyncing to avoid attaching
-                                // listeners for threads that should remain unsubscribed.
-                                warn!("thread_created receiver lagged; skipping resync");
-                            }
-                            Err(tokio::sync::broadcast::error::RecvError::Closed) => {
-                                listen_for_threads = false;
-                            }
                         }
                     }
+                    Err(e) => error!("Failed to deserialize JSONRPCMessage: {e}"),
                 }
             }
 
-            info!("processor task exited (channel closed)");
+            debug!("stdin reader finished (EOF)");
         }
     });
 
+    // Task: process incoming messages.
+    let processor_handle = spawn_processor(processor_context, incoming_rx, outgoing_tx);
+
     // Task: write outgoing messages to stdout.
     let stdout_writer_handle = tokio::spawn(async move {
         let mut stdout = io::stdout();
         while let Some(outgoing_message) = outgoing_rx.recv().await {
-            let Ok(value) = serde_json::to_value(outgoing_message) else {
-                error!("Failed to convert OutgoingMessage to JSON value");
+            let Some(mut json) = serialize_outgoing(outgoing_message) else {
                 continue;
             };
-            match serde_json::to_string(&value) {
-                Ok(mut json) => {
-                    json.push('\n');
-                    if let Err(e) = stdout.write_all(json.as_bytes()).await {
-                        error!("Failed to write to stdout: {e}");
-                        break;
-                    }
-                }
-                Err(e) => error!("Failed to serialize JSONRPCMessage: {e}"),
+            json.push('\n');
+            if let Err(e) = stdout.write_all(json.as_bytes()).await {
+                error!("Failed to write to stdout: {e}");
+                break;
             }
         }
 
@@ -246,3 +310,88 @@ pub async fn run_main(
 
     Ok(())
 }
+
+/// Runs a [`MessageProcessor`] until `incoming_rx` closes.
+pub(crate) fn spawn_processor(
+    processor_context: ProcessorContext,
+    mut incoming_rx: mpsc::Receiver<JSONRPCMessage>,
+    outgoing_tx: mpsc::Sender<OutgoingMessage>,
+) -> tokio::task::JoinHandle<()> {
+    let ProcessorContext {
+        codex_linux_sandbox_exe,
+        config,
+        cli_overrides,
+        loader_overrides,
+        feedback,
+        config_warnings,
+        auth_manager,
+        thread_manager,
+        thread_events,
+    } = processor_context;
+    let outgoing_message_sender = OutgoingMessageSender::new(outgoing_tx);
+    let mut processor = MessageProcessor::new(
+        outgoing_message_sender,
+        codex_linux_sandbox_exe,
+        config,
+        cli_overrides,
+        loader_overrides,
+        feedback,
+        config_warnings,
+        auth_manager,
+        thread_manager,
+        thread_events,
+    );
+    let mut thread_created_rx = processor.thread_created_receiver();
+    tokio::spawn(async move {
+        let mut listen_for_threads = true;
+        loop {
+            tokio::select! {
+                msg = incoming_rx.recv() => {
+                    let Some(msg) = msg else {
+                        break;
+                    };
+                    match msg {
+                        JSONRPCMessage::Request(r) => processor.process_request(r).await,
+                        JSONRPCMessage::Response(r) => processor.process_response(r).await,
+                        JSONRPCMessage::Notification(n) => processor.process_notification(n).await,
+                        JSONRPCMessage::Error(e) => processor.process_error(e),
+                    }
+                }
+                created = thread_created_rx.recv(), if listen_for_threads => {
+                    match created {
+                        Ok(thread_id) => {
+                            processor.try_attach_thread_listener(thread_id).await;
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                            // TODO(jif) handle lag.
+                            // Assumes thread creation volume is low enough that lag never happens.
+                            // If it does, we log and continue without resyncing to avoid attaching
+                            // listeners for threads that should remain unsubscribed.
+                            warn!("thread_created receiver lagged; skipping resync");
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                            listen_for_threads = false;
+                        }
+                    }
+                }
+            }
+        }
+
+        info!("processor task exited (channel closed)");
+    })
+}
+
+/// Serializes one outgoing message as a single line of JSON.
+pub(crate) fn serialize_outgoing(outgoing_message: OutgoingMessage) -> Option<String> {
+    let Ok(value) = serde_json::to_value(outgoing_message) else {
+        error!("Failed to convert OutgoingMessage to JSON value");
+        return None;
+    };
+    match serde_json::to_string(&value) {
+        Ok(json) => Some(json),
+        Err(e) => {
+            error!("Failed to serialize JSONRPCMessage: {e}");
+            None
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Binding and authentication shared by the network transports
+//! (`--listen ws://...` and `--listen http://...`).
+
+use std::io::ErrorKind;
+use std::io::Result as IoResult;
+use std::net::IpAddr;
+
+use http::Request;
+use http::header::AUTHORIZATION;
+use http::header::HOST;
+use http::header::ORIGIN;
+use tokio::net::TcpListener;
+
+/// Clients must present this token (as `Authorization: Bearer <token>` or a
+/// `?token=<token>` query parameter) when it is set.
+pub(crate) const AUTH_TOKEN_ENV_VAR: &str = "CODEX_APP_SERVER_TOKEN";
+
+/// Binds `address` and returns the token clients must present, if any.
+/// Listening beyond loopback without a token is refused.
+pub(crate) async fn bind(address: &str, scheme: &str) -> IoResult<(TcpListener, Option<String>)> {
+    let auth_token = std::env::var(AUTH_TOKEN_ENV_VAR)
+        .ok()
+        .filter(|token| !token.is_empty());
+    let listener = TcpListener::bind(address).await?;
+    let local_addr = listener.local_addr()?;
+    if auth_token.is_none() && !local_addr.ip().is_loopback() {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "refusing to listen on non-loopback address {local_addr} without {AUTH_TOKEN_ENV_VAR}"
+            ),
+        ));
+    }
+    // Printed unconditionally so callers binding port 0 can discover the port.
+    #[allow(clippy::print_stderr)]
+    {
+        eprintln!("codex app-server listening on {scheme}://{local_addr}");
+    }
+    Ok((listener, auth_token))
+}
+
+/// Checks a request against the configured token. Browsers cannot set
+/// headers on WebSocket or `EventSource` requests, so the token is also
+/// accepted as a query parameter. Without a token, only requests that come
+/// from the local machine are accepted (see [`is_loopback_request`]).
+pub(crate) fn is_authorized<B>(request: &Request<B>, auth_token: Option<&str>) -> bool {
+    let Some(expected) = auth_token else {
+        return is_loopback_request(request);
+    };
+    let bearer = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let query = query_param(request, "token");
+    bearer
+        .into_iter()
+        .chain(query)
+        .any(|candidate| constant_time_eq(candidate.as_bytes(), expected.as_bytes()))
+}
+
+/// Whether the `Host` and, for browsers, the `Origin` of a request name a
+/// loopback address. This keeps web pages open in the user's browser from
+/// reaching a loopback server, whether directly (their `Origin` is their own
+/// site) or through DNS rebinding (the `Host` is their domain).
+pub(crate) fn is_loopback_request<B>(request: &Request<B>) -> bool {
+    let header = |name| {
+        request
+            .headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap_or_default())
+    };
+    let host_ok = header(HOST).is_none_or(is_loopback_authority);
+    let origin_ok = header(ORIGIN).is_none_or(|origin| {
+        origin.split_once("://").is_some_and(|(scheme, authority)| {
+            matches!(scheme, "http" | "https") && is_loopback_authority(authority)
+        })
+    });
+    host_ok && origin_ok
+}
+
+/// Whether `host[:port]` names `localhost` or a loopback IP.
+fn is_loopback_authority(authority: &str) -> bool {
+    let host = match authority.strip_prefix('[') {
+        Some(rest) => rest.split_once(']').map(|(host, _)| host),
+        None => Some(
+            authority
+                .rsplit_once(':')
+                .map_or(authority, |(host, _)| host),
+        ),
+    };
+    host.is_some_and(|host| {
+        host.eq_ignore_ascii_case("localhost")
+            || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+    })
+}
+
+/// Returns the raw (not percent-decoded) value of a query parameter.
+pub(crate) fn query_param<'a, B>(request: &'a Request<B>, name: &str) -> Option<&'a str> {
+    request
+        .uri()
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|pair| {
+            pair.strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('='))
+        })
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AppServerTransport;
+    use pretty_assertions::assert_eq;
+
+    fn request(uri: &str, authorization: Option<&str>) -> Request<()> {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(authorization) = authorization {
+            builder = builder.header(AUTHORIZATION, authorization);
+        }
+        builder.body(()).expect("request")
+    }
+
+    #[test]
+    fn parses_listen_urls() {
+        assert_eq!(
+            AppServerTransport::from_listen_url("ws://127.0.0.1:4500/"),
+            Ok(AppServerTransport::WebSocket {
+                address: "127.0.0.1:4500".to_string(),
+            })
+        );
+        assert_eq!(
+            AppServerTransport::from_listen_url("http://localhost:8080"),
+            Ok(AppServerTransport::Http {
+                address: "localhost:8080".to_string(),
+            })
+        );
+        assert_eq!(
+            AppServerTransport::from_listen_url("stdio://"),
+            Ok(AppServerTransport::Stdio)
+        );
+        assert!(AppServerTransport::from_listen_url("wss://127.0.0.1:4500").is_err());
+        assert!(AppServerTransport::from_listen_url("ws://127.0.0.1").is_err());
+        assert!(AppServerTransport::from_listen_url("http://127.0.0.1:4500/rpc").is_err());
+    }
+
+    #[test]
+    fn accepts_token_from_header_or_query() {
+        let token = Some("s3cret");
+        assert!(is_authorized(&request("/", None), None));
+        assert!(is_authorized(&request("/", Some("Bearer s3cret")), token));
+        assert!(is_authorized(&request("/?v=2&token=s3cret", None), token));
+        assert!(!is_authorized(&request("/", None), token));
+        assert!(!is_authorized(&request("/", Some("Bearer s3cre")), token));
+        assert!(!is_authorized(&request("/?token=wrong", None), token));
+        assert!(!is_authorized(&request("/?tokenx=s3cret", None), token));
+    }
+
+    #[test]
+    fn without_token_only_loopback_hosts_and_origins_are_accepted() {
+        let with_headers = |host: &str, origin: Option<&str>| {
+            let mut builder = Request::builder().uri("/").header(HOST, host);
+            if let Some(origin) = origin {
+                builder = builder.header(ORIGIN, origin);
+            }
+            builder.body(()).expect("request")
+        };
+        assert!(is_authorized(&with_headers("127.0.0.1:4500", None), None));
+        assert!(is_authorized(&with_headers("[::1]:4500", None), None));
+        assert!(is_authorized(
+            &with_headers("localhost:4500", Some("http://localhost:3000")),
+            None
+        ));
+        assert!(!is_authorized(
+            &with_headers("127.0.0.1:4500", Some("https://evil.example")),
+            None
+        ));
+        assert!(!is_authorized(
+            &with_headers("127.0.0.1:4500", Some("null")),
+            None
+        ));
+        // DNS rebinding: the page's own domain resolves to 127.0.0.1.
+        assert!(!is_authorized(
+            &with_headers("evil.example:4500", None),
+            None
+        ));
+        // A token holder may connect from anywhere.
+        assert!(is_authorized(
+            &Request::builder()
+                .uri("/?token=s3cret")
+                .header(HOST, "evil.example")
+                .header(ORIGIN, "https://evil.example")
+                .body(())
+                .expect("request"),
+            Some("s3cret")
+        ));
+    }
+}
@@ -1,16 +1,20 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::TRANSPORT_NAMES;
 use crate::codex_message_processor::CodexMessageProcessor;
 use crate::config_api::ConfigApi;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::thread_events::ThreadEventHub;
 use codex_app_server_protocol::ClientInfo;
 use codex_app_server_protocol::ClientRequest;
 use codex_app_server_protocol::ConfigBatchWriteParams;
 use codex_app_server_protocol::ConfigReadParams;
+use codex_app_server_protocol::ConfigValidateParams;
 use codex_app_server_protocol::ConfigValueWriteParams;
 use codex_app_server_protocol::ConfigWarningNotification;
+use codex_app_server_protocol::InitializeParams;
 use codex_app_server_protocol::InitializeResponse;
 use codex_app_server_protocol::JSONRPCError;
 use codex_app_server_protocol::JSONRPCErrorError;
@@ -18,18 +22,24 @@ use codex_app_server_protocol::JSONRPCNotification;
 use codex_app_server_protocol::JSONRPCRequest;
 use codex_app_server_protocol::JSONRPCResponse;
 use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ServerCapabilities;
 use codex_app_server_protocol::ServerNotification;
+use codex_app_server_protocol::client_request_methods;
+use codex_app_server_protocol::negotiate_protocol_version;
+use codex_app_server_protocol::server_notification_methods;
+use codex_app_server_protocol::server_request_methods;
 use codex_core::AuthManager;
 use codex_core::ThreadManager;
+use codex_core::capabilities::builtin_tool_names;
 use codex_core::config::Config;
 use codex_core::config_loader::LoaderOverrides;
 use codex_core::default_client::SetOriginatorError;
 use codex_core::default_client::USER_AGENT_SUFFIX;
 use codex_core::default_client::get_codex_user_agent;
 use codex_core::default_client::set_default_originator;
+use codex_core::features::FEATURES;
 use codex_feedback::CodexFeedback;
 use codex_protocol::ThreadId;
-use codex_protocol::protocol::SessionSource;
 use tokio::sync::broadcast;
 use toml::Value as TomlValue;
 
@@ -37,6 +47,7 @@ pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     codex_message_processor: CodexMessageProcessor,
     config_api: ConfigApi,
+    config: Arc<Config>,
     initialized: bool,
     config_warnings: Vec<ConfigWarningNotification>,
 }
@@ -44,6 +55,7 @@ pub(crate) struct MessageProcessor {
 impl MessageProcessor {
     /// Create a new `MessageProcessor`, retaining a handle to the outgoing
     /// `Sender` so handlers can enqueue messages to be written to stdout.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         outgoing: OutgoingMessageSender,
         codex_linux_sandbox_exe: Option<PathBuf>,
@@ -52,21 +64,15 @@ impl MessageProcessor {
         loader_overrides: LoaderOverrides,
         feedback: CodexFeedback,
         config_warnings: Vec<ConfigWarningNotification>,
+        auth_manager: Arc<AuthManager>,
+        thread_manager: Arc<ThreadManager>,
+        thread_events: Arc<ThreadEventHub>,
     ) -> Self {
         let outgoing = Arc::new(outgoing);
-        let auth_manager = AuthManager::shared(
-            config.codex_home.clone(),
-            false,
-            config.cli_auth_credentials_store_mode,
-        );
-        let thread_manager = Arc::new(ThreadManager::new(
-            config.codex_home.clone(),
-            auth_manager.clone(),
-            SessionSource::VSCode,
-        ));
         let codex_message_processor = CodexMessageProcessor::new(
             auth_manager,
             thread_manager,
+            thread_events,
             outgoing.clone(),
             codex_linux_sandbox_exe,
             Arc::clone(&config),
@@ -79,6 +85,7 @@ impl MessageProcessor {
             outgoing,
             codex_message_processor,
             config_api,
+            config,
             initialized: false,
             config_warnings,
         }
@@ -125,11 +132,28 @@ impl MessageProcessor {
                     self.outgoing.send_error(request_id, error).await;
                     return;
                 } else {
+                    let InitializeParams {
+                        client_info,
+                        protocol_version,
+                        capabilities,
+                    } = params;
+                    let protocol_version = match negotiate_protocol_version(protocol_version) {
+                        Ok(protocol_version) => protocol_version,
+                        Err(message) => {
+                            let error = JSONRPCErrorError {
+                                code: INVALID_REQUEST_ERROR_CODE,
+                                message,
+                                data: None,
+                            };
+                            self.outgoing.send_error(request_id, error).await;
+                            return;
+                        }
+                    };
                     let ClientInfo {
                         name,
                         title: _title,
                         version,
-                    } = params.client_info;
+                    } = client_info;
                     if let Err(error) = set_default_originator(name.clone()) {
                         match error {
                             SetOriginatorError::InvalidHeaderValue => {
@@ -156,8 +180,22 @@ impl MessageProcessor {
                         *suffix = Some(user_agent_suffix);
                     }
 
+                    let capabilities = capabilities.unwrap_or_default();
+                    if capabilities.notifications.is_some()
+                        || !capabilities.exclude_notification_categories.is_empty()
+                    {
+                        self.outgoing.restrict_notifications(
+                            capabilities.notifications,
+                            capabilities.exclude_notification_categories,
+                        );
+                    }
+
                     let user_agent = get_codex_user_agent();
-                    let response = InitializeResponse { user_agent };
+                    let response = InitializeResponse {
+                        user_agent,
+                        protocol_version,
+                        capabilities: self.server_capabilities(&capabilities.features),
+                    };
                     self.outgoing.send_response(request_id, response).await;
 
                     self.initialized = true;
@@ -203,12 +241,44 @@ impl MessageProcessor {
             } => {
                 self.handle_config_requirements_read(request_id).await;
             }
+            ClientRequest::ConfigSchemaRead {
+                request_id,
+                params: _,
+            } => {
+                self.handle_config_schema_read(request_id).await;
+            }
+            ClientRequest::ConfigValidate { request_id, params } => {
+                self.handle_config_validate(request_id, params).await;
+            }
             other => {
                 self.codex_message_processor.process_request(other).await;
             }
         }
     }
 
+    fn server_capabilities(&self, requested_features: &[String]) -> ServerCapabilities {
+        let features: Vec<String> = FEATURES
+            .iter()
+            .filter(|spec| self.config.features.enabled(spec.id))
+            .map(|spec| spec.key.to_string())
+            .collect();
+        let accepted_features = requested_features
+            .iter()
+            .filter(|feature| features.contains(feature))
+            .cloned()
+            .collect();
+        ServerCapabilities {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            requests: client_request_methods(),
+            notifications: server_notification_methods(),
+            server_requests: server_request_methods(),
+            transports: TRANSPORT_NAMES.iter().map(ToString::to_string).collect(),
+            tools: builtin_tool_names(&self.config),
+            features,
+            accepted_features,
+        }
+    }
+
     pub(crate) async fn process_notification(&self, notification: JSONRPCNotification) {
         // Currently, we do not expect to receive any notifications from the
         // client, so we just log them.
@@ -275,4 +345,18 @@ impl MessageProcessor {
             Err(error) => self.outgoing.send_error(request_id, error).await,
         }
     }
+
+    async fn handle_config_schema_read(&self, request_id: RequestId) {
+        match self.config_api.schema_read() {
+            Ok(response) => self.outgoing.send_response(request_id, response).await,
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
+
+    async fn handle_config_validate(&self, request_id: RequestId, params: ConfigValidateParams) {
+        match self.config_api.validate(params).await {
+            Ok(response) => self.outgoing.send_response(request_id, response).await,
+            Err(error) => self.outgoing.send_error(request_id, error).await,
+        }
+    }
 }
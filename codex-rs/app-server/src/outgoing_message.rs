@@ -1,8 +1,11 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::OnceLock;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::Ordering;
 
 use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::NotificationCategory;
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::Result;
 use codex_app_server_protocol::ServerNotification;
@@ -12,6 +15,7 @@ use serde::Serialize;
 use tokio::sync::Mutex;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
+use tracing::debug;
 use tracing::warn;
 
 use crate::error_code::INTERNAL_ERROR_CODE;
@@ -24,6 +28,15 @@ pub(crate) struct OutgoingMessageSender {
     next_request_id: AtomicI64,
     sender: mpsc::Sender<OutgoingMessage>,
     request_id_to_callback: Mutex<HashMap<RequestId, oneshot::Sender<Result>>>,
+    /// Notification preferences the client declared at `initialize`; unset
+    /// means the client takes every notification.
+    notification_filter: OnceLock<NotificationFilter>,
+}
+
+struct NotificationFilter {
+    /// Server notification methods the client handles; `None` means all.
+    allowed: Option<HashSet<String>>,
+    excluded: Vec<NotificationCategory>,
 }
 
 impl OutgoingMessageSender {
@@ -32,9 +45,36 @@ impl OutgoingMessageSender {
             next_request_id: AtomicI64::new(0),
             sender,
             request_id_to_callback: Mutex::new(HashMap::new()),
+            notification_filter: OnceLock::new(),
         }
     }
 
+    /// Limits [`Self::send_server_notification`] to `allowed` (when set) and
+    /// drops every notification in an `excluded` category for the rest of the
+    /// connection. Only the first call has an effect.
+    pub(crate) fn restrict_notifications(
+        &self,
+        allowed: Option<Vec<String>>,
+        excluded: Vec<NotificationCategory>,
+    ) {
+        let _ = self.notification_filter.set(NotificationFilter {
+            allowed: allowed.map(|methods| methods.into_iter().collect()),
+            excluded,
+        });
+    }
+
+    /// Whether the client opted out of `method` through an excluded
+    /// notification category. Callers can check this before building an
+    /// expensive payload.
+    pub(crate) fn is_notification_excluded(&self, method: &str) -> bool {
+        self.notification_filter.get().is_some_and(|filter| {
+            filter
+                .excluded
+                .iter()
+                .any(|category| category.includes(method))
+        })
+    }
+
     pub(crate) async fn send_request(
         &self,
         request: ServerRequestPayload,
@@ -98,6 +138,21 @@ impl OutgoingMessageSender {
     }
 
     pub(crate) async fn send_server_notification(&self, notification: ServerNotification) {
+        if let Some(filter) = self.notification_filter.get() {
+            let method = notification.to_string();
+            if filter
+                .allowed
+                .as_ref()
+                .is_some_and(|allowed| !allowed.contains(&method))
+            {
+                debug!("client did not declare {method}; not sending it");
+                return;
+            }
+            if self.is_notification_excluded(&method) {
+                debug!("client excluded {method}; not sending it");
+                return;
+            }
+        }
         if let Err(err) = self
             .sender
             .send(OutgoingMessage::AppServerNotification(notification))
@@ -110,6 +165,9 @@ impl OutgoingMessageSender {
     /// All notifications should be migrated to [`ServerNotification`] and
     /// [`OutgoingMessage::Notification`] should be removed.
     pub(crate) async fn send_notification(&self, notification: OutgoingNotification) {
+        if self.is_notification_excluded(&notification.method) {
+            return;
+        }
         let outgoing_message = OutgoingMessage::Notification(notification);
         if let Err(err) = self.sender.send(outgoing_message).await {
             warn!("failed to send notification to client: {err:?}");
@@ -166,6 +224,7 @@ mod tests {
     use codex_app_server_protocol::LoginChatGptCompleteNotification;
     use codex_app_server_protocol::RateLimitSnapshot;
     use codex_app_server_protocol::RateLimitWindow;
+    use codex_app_server_protocol::ReasoningTextDeltaNotification;
     use pretty_assertions::assert_eq;
     use serde_json::json;
     use uuid::Uuid;
@@ -303,4 +362,89 @@ mod tests {
             "ensure the notification serializes correctly"
         );
     }
+
+    #[tokio::test]
+    async fn restricted_connections_only_get_declared_notifications() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let outgoing = OutgoingMessageSender::new(tx);
+        outgoing.restrict_notifications(Some(vec!["configWarning".to_string()]), Vec::new());
+
+        outgoing
+            .send_server_notification(ServerNotification::AccountUpdated(
+                AccountUpdatedNotification {
+                    auth_mode: None,
+                    active_account: None,
+                },
+            ))
+            .await;
+        outgoing
+            .send_server_notification(ServerNotification::ConfigWarning(
+                ConfigWarningNotification {
+                    summary: "warning".to_string(),
+                    details: None,
+                },
+            ))
+            .await;
+        drop(outgoing);
+
+        let mut methods = Vec::new();
+        while let Some(OutgoingMessage::AppServerNotification(notification)) = rx.recv().await {
+            methods.push(notification.to_string());
+        }
+        assert_eq!(methods, vec!["configWarning".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn excluded_categories_are_not_sent() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let outgoing = OutgoingMessageSender::new(tx);
+        outgoing.restrict_notifications(
+            None,
+            vec![
+                NotificationCategory::ReasoningDeltas,
+                NotificationCategory::LegacyEvents,
+            ],
+        );
+
+        outgoing
+            .send_server_notification(ServerNotification::ReasoningTextDelta(
+                ReasoningTextDeltaNotification {
+                    thread_id: "thread".to_string(),
+                    turn_id: "turn".to_string(),
+                    item_id: "item".to_string(),
+                    delta: "thinking".to_string(),
+                    content_index: 0,
+                },
+            ))
+            .await;
+        outgoing
+            .send_notification(OutgoingNotification {
+                method: "codex/event/agent_message_delta".to_string(),
+                params: None,
+            })
+            .await;
+        outgoing
+            .send_server_notification(ServerNotification::ConfigWarning(
+                ConfigWarningNotification {
+                    summary: "warning".to_string(),
+                    details: None,
+                },
+            ))
+            .await;
+        assert!(outgoing.is_notification_excluded("codex/event/token_count"));
+        assert!(!outgoing.is_notification_excluded("item/agentMessage/delta"));
+        drop(outgoing);
+
+        let mut methods = Vec::new();
+        while let Some(message) = rx.recv().await {
+            match message {
+                OutgoingMessage::AppServerNotification(notification) => {
+                    methods.push(notification.to_string());
+                }
+                OutgoingMessage::Notification(notification) => methods.push(notification.method),
+                _ => {}
+            }
+        }
+        assert_eq!(methods, vec!["configWarning".to_string()]);
+    }
 }
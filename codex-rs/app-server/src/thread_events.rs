@@ -0,0 +1,152 @@
+//! Fans each thread's events out to every listener.
+//!
+//! A thread has a single event queue, so listeners must not read it
+//! directly: with two clients attached to the same thread each would only
+//! see part of the events. The first subscription starts a task that drains
+//! the queue into a broadcast channel, and later subscribers join it.
+//!
+//! The task stops draining once the last listener is gone, so events (such as
+//! an approval request) wait in the thread's queue for the next subscriber
+//! instead of being broadcast to nobody. Such an idle entry only holds the
+//! thread weakly and is dropped once the thread is shut down or removed.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Weak;
+
+use codex_core::CodexThread;
+use codex_core::protocol::Event;
+use codex_protocol::ThreadId;
+use tokio::sync::broadcast;
+
+/// Events a slow listener may fall behind by before it starts missing them.
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
+
+#[derive(Default)]
+pub(crate) struct ThreadEventHub {
+    channels: Mutex<HashMap<ThreadId, ThreadChannel>>,
+}
+
+struct ThreadChannel {
+    /// Kept alive by the draining task while there is one.
+    thread: Weak<CodexThread>,
+    state: ChannelState,
+}
+
+impl ThreadChannel {
+    fn is_for(&self, thread: &Arc<CodexThread>) -> bool {
+        std::ptr::eq(self.thread.as_ptr(), Arc::as_ptr(thread))
+    }
+}
+
+enum ChannelState {
+    /// A task is draining the thread's queue into `events`.
+    Draining(broadcast::Sender<Event>),
+    /// The last listener left. `pending` holds the event that was read from
+    /// the queue but had nobody to go to.
+    Idle { pending: Option<Event> },
+}
+
+impl ThreadEventHub {
+    /// Returns a receiver for the events `thread` emits from now on, starting
+    /// with any it emitted while nobody was listening. The receiver closes
+    /// when the thread shuts down.
+    pub(crate) fn subscribe(
+        self: &Arc<Self>,
+        thread_id: ThreadId,
+        thread: &Arc<CodexThread>,
+    ) -> broadcast::Receiver<Event> {
+        let mut channels = self.lock();
+        channels.retain(|_, channel| channel.thread.strong_count() > 0);
+        // A thread that was shut down and resumed keeps its id but is a new
+        // `CodexThread` with its own queue.
+        let pending = match channels.get_mut(&thread_id) {
+            Some(channel) if channel.is_for(thread) => match &mut channel.state {
+                ChannelState::Draining(events) => return events.subscribe(),
+                ChannelState::Idle { pending } => std::mem::take(pending),
+            },
+            _ => None,
+        };
+
+        let (events, receiver) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        if let Some(event) = pending {
+            let _ = events.send(event);
+        }
+        channels.insert(
+            thread_id,
+            ThreadChannel {
+                thread: Arc::downgrade(thread),
+                state: ChannelState::Draining(events.clone()),
+            },
+        );
+        drop(channels);
+
+        let hub = Arc::clone(self);
+        let thread = Arc::clone(thread);
+        tokio::spawn(async move {
+            while let Ok(event) = thread.next_event().await {
+                if let Err(broadcast::error::SendError(event)) = events.send(event)
+                    && !hub.retry_or_park(thread_id, &thread, &events, event)
+                {
+                    return;
+                }
+            }
+            hub.remove(thread_id, &thread);
+        });
+        receiver
+    }
+
+    /// Called when an event had no receivers. Under the lock `subscribe`
+    /// joins channels with, either delivers it to a listener that joined in
+    /// the meantime (returning `true` to keep draining) or parks it for the
+    /// next subscriber.
+    fn retry_or_park(
+        &self,
+        thread_id: ThreadId,
+        thread: &Arc<CodexThread>,
+        events: &broadcast::Sender<Event>,
+        event: Event,
+    ) -> bool {
+        let mut channels = self.lock();
+        if events.receiver_count() > 0 {
+            return events.send(event).is_ok();
+        }
+        if let Some(channel) = channels.get_mut(&thread_id)
+            && channel.is_for(thread)
+        {
+            channel.state = ChannelState::Idle {
+                pending: Some(event),
+            };
+        }
+        false
+    }
+
+    fn remove(&self, thread_id: ThreadId, thread: &Arc<CodexThread>) {
+        let mut channels = self.lock();
+        if channels
+            .get(&thread_id)
+            .is_some_and(|channel| channel.is_for(thread))
+        {
+            channels.remove(&thread_id);
+        }
+    }
+
+    /// Drops what the hub holds for a thread that was shut down, including
+    /// an event parked for a subscriber that will never come.
+    pub(crate) fn forget(&self, thread_id: ThreadId) {
+        let mut channels = self.lock();
+        if channels
+            .get(&thread_id)
+            .is_some_and(|channel| matches!(channel.state, ChannelState::Idle { .. }))
+        {
+            channels.remove(&thread_id);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<ThreadId, ThreadChannel>> {
+        self.channels
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
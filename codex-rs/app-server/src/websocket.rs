@@ -0,0 +1,145 @@
+//! WebSocket transport (`--listen ws://HOST:PORT`). Every connection speaks
+//! the same JSON-RPC protocol as stdio, one message per text frame, and gets
+//! its own [`crate::message_processor::MessageProcessor`].
+
+use std::io::Result as IoResult;
+use std::net::SocketAddr;
+
+use codex_app_server_protocol::JSONRPCMessage;
+use futures::SinkExt;
+use futures::StreamExt;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+use tokio_tungstenite::accept_hdr_async;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::handshake::server::ErrorResponse;
+use tokio_tungstenite::tungstenite::handshake::server::Request;
+use tokio_tungstenite::tungstenite::handshake::server::Response;
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tracing::debug;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use crate::CHANNEL_CAPACITY;
+use crate::ProcessorContext;
+use crate::listen;
+use crate::outgoing_message::OutgoingMessage;
+use crate::serialize_outgoing;
+use crate::spawn_processor;
+
+/// Accepts connections on `address` until Ctrl-C, then closes every open
+/// connection and waits for its processor to finish.
+pub(crate) async fn serve(address: &str, processor_context: ProcessorContext) -> IoResult<()> {
+    let (listener, auth_token) = listen::bind(address, "ws").await?;
+
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let mut connections = JoinSet::new();
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        tokio::select! {
+            _ = &mut ctrl_c => {
+                info!("received Ctrl-C; closing {} connection(s)", connections.len());
+                break;
+            }
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => {
+                    connections.spawn(handle_connection(
+                        stream,
+                        peer,
+                        processor_context.clone(),
+                        auth_token.clone(),
+                        shutdown_rx.clone(),
+                    ));
+                }
+                Err(err) => warn!("failed to accept connection: {err}"),
+            },
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+
+    let _ = shutdown_tx.send(true);
+    while connections.join_next().await.is_some() {}
+    Ok(())
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    peer: SocketAddr,
+    processor_context: ProcessorContext,
+    auth_token: Option<String>,
+    mut shutdown_rx: watch::Receiver<bool>,
+) {
+    let authorize = |request: &Request, response: Response| {
+        if listen::is_authorized(request, auth_token.as_deref()) {
+            Ok(response)
+        } else {
+            let mut rejection = ErrorResponse::new(Some("unauthorized".to_string()));
+            *rejection.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(rejection)
+        }
+    };
+    let mut websocket = match accept_hdr_async(stream, authorize).await {
+        Ok(websocket) => websocket,
+        Err(err) => {
+            warn!("rejected WebSocket connection from {peer}: {err}");
+            return;
+        }
+    };
+    info!("WebSocket client connected: {peer}");
+
+    let (incoming_tx, incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
+    let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<OutgoingMessage>(CHANNEL_CAPACITY);
+    let processor_handle = spawn_processor(processor_context, incoming_rx, outgoing_tx);
+
+    loop {
+        tokio::select! {
+            frame = websocket.next() => match frame {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<JSONRPCMessage>(&text) {
+                        Ok(msg) => {
+                            if incoming_tx.send(msg).await.is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) => error!("Failed to deserialize JSONRPCMessage: {e}"),
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                // Pings are answered by tungstenite; binary frames are not
+                // part of the protocol.
+                Some(Ok(_)) => {}
+                Some(Err(err)) => {
+                    warn!("WebSocket error from {peer}: {err}");
+                    break;
+                }
+            },
+            outgoing = outgoing_rx.recv() => {
+                let Some(outgoing_message) = outgoing else {
+                    break;
+                };
+                let Some(json) = serialize_outgoing(outgoing_message) else {
+                    continue;
+                };
+                if let Err(err) = websocket.send(Message::Text(json)).await {
+                    warn!("failed to send to {peer}: {err}");
+                    break;
+                }
+            }
+            _ = shutdown_rx.changed() => {
+                let _ = websocket.close(None).await;
+                break;
+            }
+        }
+    }
+
+    // Closing both channels lets the processor exit even if it is blocked
+    // sending to a client that is gone.
+    drop(incoming_tx);
+    drop(outgoing_rx);
+    let _ = processor_handle.await;
+    debug!("WebSocket client disconnected: {peer}");
+}
@@ -62,10 +62,11 @@ async fn archive_conversation_moves_rollout_into_archived_directory() -> Result<
         to_response::<ArchiveConversationResponse>(archive_response)?;
 
     let archived_directory = codex_home.path().join(ARCHIVED_SESSIONS_SUBDIR);
+    let file_name = rollout_path
+        .file_name()
+        .unwrap_or_else(|| panic!("rollout path {} missing file name", rollout_path.display()));
     let archived_rollout_path =
-        archived_directory.join(rollout_path.file_name().unwrap_or_else(|| {
-            panic!("rollout path {} missing file name", rollout_path.display())
-        }));
+        archived_directory.join(format!("{}.zst", file_name.to_string_lossy()));
 
     assert!(
         !rollout_path.exists(),
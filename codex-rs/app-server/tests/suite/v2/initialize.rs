@@ -2,7 +2,10 @@ use anyhow::Result;
 use app_test_support::McpProcess;
 use app_test_support::create_mock_responses_server_sequence_unchecked;
 use app_test_support::to_response;
+use codex_app_server_protocol::APP_SERVER_PROTOCOL_VERSION;
+use codex_app_server_protocol::ClientCapabilities;
 use codex_app_server_protocol::ClientInfo;
+use codex_app_server_protocol::InitializeParams;
 use codex_app_server_protocol::InitializeResponse;
 use codex_app_server_protocol::JSONRPCMessage;
 use pretty_assertions::assert_eq;
@@ -33,7 +36,7 @@ async fn initialize_uses_client_info_name_as_originator() -> Result<()> {
     let JSONRPCMessage::Response(response) = message else {
         anyhow::bail!("expected initialize response, got {message:?}");
     };
-    let InitializeResponse { user_agent } = to_response::<InitializeResponse>(response)?;
+    let InitializeResponse { user_agent, .. } = to_response::<InitializeResponse>(response)?;
 
     assert!(user_agent.starts_with("codex_vscode/"));
     Ok(())
@@ -67,7 +70,7 @@ async fn initialize_respects_originator_override_env_var() -> Result<()> {
     let JSONRPCMessage::Response(response) = message else {
         anyhow::bail!("expected initialize response, got {message:?}");
     };
-    let InitializeResponse { user_agent } = to_response::<InitializeResponse>(response)?;
+    let InitializeResponse { user_agent, .. } = to_response::<InitializeResponse>(response)?;
 
     assert!(user_agent.starts_with("codex_originator_via_env_var/"));
     Ok(())
@@ -108,6 +111,93 @@ async fn initialize_rejects_invalid_client_name() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn initialize_negotiates_protocol_version_and_reports_capabilities() -> Result<()> {
+    let responses = Vec::new();
+    let server = create_mock_responses_server_sequence_unchecked(responses).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri(), "never")?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+
+    let message = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.initialize_with_params(InitializeParams {
+            client_info: test_client_info(),
+            protocol_version: Some(APP_SERVER_PROTOCOL_VERSION + 1),
+            capabilities: Some(ClientCapabilities {
+                notifications: None,
+                features: vec!["shell_tool".to_string(), "no_such_feature".to_string()],
+                exclude_notification_categories: Vec::new(),
+            }),
+        }),
+    )
+    .await??;
+
+    let JSONRPCMessage::Response(response) = message else {
+        anyhow::bail!("expected initialize response, got {message:?}");
+    };
+    let InitializeResponse {
+        protocol_version,
+        capabilities,
+        ..
+    } = to_response::<InitializeResponse>(response)?;
+
+    assert_eq!(protocol_version, APP_SERVER_PROTOCOL_VERSION);
+    assert_eq!(
+        capabilities.accepted_features,
+        vec!["shell_tool".to_string()]
+    );
+    assert!(capabilities.requests.contains(&"thread/start".to_string()));
+    assert!(capabilities.requests.contains(&"initialize".to_string()));
+    assert!(
+        capabilities
+            .notifications
+            .contains(&"turn/completed".to_string())
+    );
+    assert!(capabilities.transports.contains(&"stdio".to_string()));
+    assert!(capabilities.tools.contains(&"update_plan".to_string()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn initialize_rejects_unsupported_protocol_version() -> Result<()> {
+    let responses = Vec::new();
+    let server = create_mock_responses_server_sequence_unchecked(responses).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri(), "never")?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+
+    let message = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.initialize_with_params(InitializeParams {
+            client_info: test_client_info(),
+            protocol_version: Some(0),
+            capabilities: None,
+        }),
+    )
+    .await??;
+
+    let JSONRPCMessage::Error(error) = message else {
+        anyhow::bail!("expected initialize error, got {message:?}");
+    };
+    assert_eq!(error.error.code, -32600);
+    assert!(
+        error
+            .error
+            .message
+            .starts_with("Unsupported protocolVersion 0")
+    );
+    Ok(())
+}
+
+fn test_client_info() -> ClientInfo {
+    ClientInfo {
+        name: "codex_vscode".to_string(),
+        title: None,
+        version: "0.1.0".to_string(),
+    }
+}
+
 // Helper to create a config.toml pointing at the mock model server.
 fn create_config_toml(
     codex_home: &Path,
@@ -9,12 +9,15 @@ mod rate_limits;
 mod request_user_input;
 mod review;
 mod thread_archive;
+mod thread_attach;
+mod thread_export;
 mod thread_fork;
 mod thread_list;
 mod thread_loaded_list;
 mod thread_read;
 mod thread_resume;
 mod thread_rollback;
+mod thread_search;
 mod thread_start;
 mod turn_interrupt;
 mod turn_start;
@@ -63,9 +63,14 @@ async fn thread_archive_moves_rollout_into_archived_directory() -> Result<()> {
 
     // Verify file moved.
     let archived_directory = codex_home.path().join(ARCHIVED_SESSIONS_SUBDIR);
-    // The archived file keeps the original filename (rollout-...-<id>.jsonl).
-    let archived_rollout_path =
-        archived_directory.join(rollout_path.file_name().expect("rollout file name"));
+    // The archive keeps the original filename plus a `.zst` suffix.
+    let archived_rollout_path = archived_directory.join(format!(
+        "{}.zst",
+        rollout_path
+            .file_name()
+            .expect("rollout file name")
+            .to_string_lossy()
+    ));
     assert!(
         !rollout_path.exists(),
         "expected rollout path {} to be moved",
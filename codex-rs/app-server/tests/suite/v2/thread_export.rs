@@ -0,0 +1,125 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadExportFormat;
+use codex_app_server_protocol::ThreadExportParams;
+use codex_app_server_protocol::ThreadExportResponse;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_export_renders_markdown_and_json() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let thread_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-05T12-00-00",
+        "2025-01-05T12:00:00Z",
+        "Summarize the incident",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let ThreadExportResponse {
+        contents,
+        redactions,
+    } = export(&mut mcp, &thread_id, ThreadExportFormat::Markdown).await?;
+    assert_eq!(
+        contents,
+        format!(
+            "# Summarize the incident\n\n\
+             _Session {thread_id} · 2025-01-05T12:00:00Z · / · 1 turns_\n\n\
+             ## Turn 1\n\n\
+             **User**\n\nSummarize the incident\n\n"
+        )
+    );
+    assert_eq!(redactions, 0);
+
+    let ThreadExportResponse { contents, .. } =
+        export(&mut mcp, &thread_id, ThreadExportFormat::Json).await?;
+    let value: serde_json::Value = serde_json::from_str(&contents)?;
+    assert_eq!(value["session"]["id"], thread_id.as_str());
+    assert_eq!(
+        value["turns"],
+        serde_json::json!([{
+            "turn": 1,
+            "entries": [{"type": "user", "text": "Summarize the incident"}],
+        }])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn thread_export_of_unknown_thread_is_an_error() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_thread_export_request(ThreadExportParams {
+            thread_id: "67e55044-10b1-426f-9247-bb680e5fe0c8".to_string(),
+            format: ThreadExportFormat::Html,
+        })
+        .await?;
+    let error: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    assert!(error.error.message.contains("no rollout found"));
+
+    Ok(())
+}
+
+async fn export(
+    mcp: &mut McpProcess,
+    thread_id: &str,
+    format: ThreadExportFormat,
+) -> Result<ThreadExportResponse> {
+    let request_id = mcp
+        .send_thread_export_request(ThreadExportParams {
+            thread_id: thread_id.to_string(),
+            format,
+        })
+        .await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    to_response::<ThreadExportResponse>(resp)
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "http://127.0.0.1:0/v1"
+wire_api = "responses"
+request_max_retries = 0
+stream_max_retries = 0
+"#,
+    )
+}
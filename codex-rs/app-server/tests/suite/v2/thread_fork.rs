@@ -81,6 +81,7 @@ async fn thread_fork_creates_new_thread_and_emits_started() -> Result<()> {
     assert_ne!(thread.path, original_path);
     assert!(thread.cwd.is_absolute());
     assert_eq!(thread.source, SessionSource::VsCode);
+    assert_eq!(thread.forked_from_id, Some(conversation_id.clone()));
 
     assert_eq!(
         thread.turns.len(),
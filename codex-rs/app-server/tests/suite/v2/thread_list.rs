@@ -13,10 +13,10 @@ use codex_app_server_protocol::SessionSource;
 use codex_app_server_protocol::ThreadListResponse;
 use codex_app_server_protocol::ThreadSortKey;
 use codex_core::ARCHIVED_SESSIONS_SUBDIR;
+use codex_core::archive_rollout;
 use codex_protocol::protocol::GitInfo as CoreGitInfo;
 use pretty_assertions::assert_eq;
 use std::cmp::Reverse;
-use std::fs;
 use std::fs::FileTimes;
 use std::fs::OpenOptions;
 use std::path::Path;
@@ -58,6 +58,7 @@ async fn list_threads_with_sort(
             sort_key,
             model_providers: providers,
             archived,
+            forked_from_id: None,
         })
         .await?;
     let resp: JSONRPCResponse = timeout(
@@ -828,15 +829,12 @@ async fn thread_list_archived_filter() -> Result<()> {
         None,
     )?;
 
-    let archived_dir = codex_home.path().join(ARCHIVED_SESSIONS_SUBDIR);
-    fs::create_dir_all(&archived_dir)?;
     let archived_source = rollout_path(codex_home.path(), "2025-03-01T09-00-00", &archived_id);
-    let archived_dest = archived_dir.join(
-        archived_source
-            .file_name()
-            .expect("archived rollout should have a file name"),
+    let archived_dest = archive_rollout(codex_home.path(), &archived_source).await?;
+    assert_eq!(
+        archived_dest.parent(),
+        Some(codex_home.path().join(ARCHIVED_SESSIONS_SUBDIR).as_path())
     );
-    fs::rename(&archived_source, &archived_dest)?;
 
     let mut mcp = init_mcp(codex_home.path()).await?;
 
@@ -879,6 +877,7 @@ async fn thread_list_invalid_cursor_returns_error() -> Result<()> {
             sort_key: None,
             model_providers: Some(vec!["mock_provider".to_string()]),
             archived: None,
+            forked_from_id: None,
         })
         .await?;
     let error: JSONRPCError = timeout(
@@ -6,6 +6,7 @@ use app_test_support::to_response;
 use codex_app_server_protocol::JSONRPCResponse;
 use codex_app_server_protocol::RequestId;
 use codex_app_server_protocol::SessionSource;
+use codex_app_server_protocol::ThreadDiffStat;
 use codex_app_server_protocol::ThreadItem;
 use codex_app_server_protocol::ThreadReadParams;
 use codex_app_server_protocol::ThreadReadResponse;
@@ -52,6 +53,7 @@ async fn thread_read_returns_summary_without_turns() -> Result<()> {
         .send_thread_read_request(ThreadReadParams {
             thread_id: conversation_id.clone(),
             include_turns: false,
+            include_stats: false,
         })
         .await?;
     let read_resp: JSONRPCResponse = timeout(
@@ -69,6 +71,8 @@ async fn thread_read_returns_summary_without_turns() -> Result<()> {
     assert_eq!(thread.cli_version, "0.0.0");
     assert_eq!(thread.source, SessionSource::Cli);
     assert_eq!(thread.git_info, None);
+    assert_eq!(thread.model, None);
+    assert_eq!(thread.diff_stat, None);
     assert_eq!(thread.turns.len(), 0);
 
     Ok(())
@@ -105,6 +109,7 @@ async fn thread_read_can_include_turns() -> Result<()> {
         .send_thread_read_request(ThreadReadParams {
             thread_id: conversation_id.clone(),
             include_turns: true,
+            include_stats: true,
         })
         .await?;
     let read_resp: JSONRPCResponse = timeout(
@@ -114,6 +119,14 @@ async fn thread_read_can_include_turns() -> Result<()> {
     .await??;
     let ThreadReadResponse { thread } = to_response::<ThreadReadResponse>(read_resp)?;
 
+    assert_eq!(
+        thread.diff_stat,
+        Some(ThreadDiffStat {
+            files_changed: 0,
+            insertions: 0,
+            deletions: 0,
+        })
+    );
     assert_eq!(thread.turns.len(), 1);
     let turn = &thread.turns[0];
     assert_eq!(turn.status, TurnStatus::Completed);
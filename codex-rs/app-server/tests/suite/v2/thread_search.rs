@@ -0,0 +1,207 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadDeleteParams;
+use codex_app_server_protocol::ThreadDeleteResponse;
+use codex_app_server_protocol::ThreadListParams;
+use codex_app_server_protocol::ThreadListResponse;
+use codex_app_server_protocol::ThreadNameSetParams;
+use codex_app_server_protocol::ThreadNameSetResponse;
+use codex_app_server_protocol::ThreadReadParams;
+use codex_app_server_protocol::ThreadSearchParams;
+use codex_app_server_protocol::ThreadSearchResponse;
+use codex_app_server_protocol::ThreadTagsSetParams;
+use codex_app_server_protocol::ThreadTagsSetResponse;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_search_matches_messages_and_labels() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let login = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-05T12-00-00",
+        "2025-01-05T12:00:00Z",
+        "Why does the login page return 500?",
+        Some("mock_provider"),
+        None,
+    )?;
+    let docs = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-06T12-00-00",
+        "2025-01-06T12:00:00Z",
+        "Update the README",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let ThreadSearchResponse { data, next_cursor } = search(&mut mcp, "LOGIN 500", None).await?;
+    assert_eq!(
+        data.iter()
+            .map(|result| (result.thread.id.as_str(), result.snippet.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![(login.as_str(), Some("Why does the login page return 500?"))]
+    );
+    assert_eq!(next_cursor, None);
+
+    let name_id = mcp
+        .send_thread_name_set_request(ThreadNameSetParams {
+            thread_id: docs.clone(),
+            name: Some("  Docs   refresh ".to_string()),
+        })
+        .await?;
+    let name_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(name_id)),
+    )
+    .await??;
+    let ThreadNameSetResponse { name } = to_response::<ThreadNameSetResponse>(name_resp)?;
+    assert_eq!(name.as_deref(), Some("Docs refresh"));
+
+    let tags_id = mcp
+        .send_thread_tags_set_request(ThreadTagsSetParams {
+            thread_id: docs.clone(),
+            tags: vec!["Docs".to_string(), "release".to_string()],
+        })
+        .await?;
+    let tags_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(tags_id)),
+    )
+    .await??;
+    let ThreadTagsSetResponse { tags } = to_response::<ThreadTagsSetResponse>(tags_resp)?;
+    assert_eq!(tags, vec!["docs".to_string(), "release".to_string()]);
+
+    // A name matches without a snippet; tags filter without a query.
+    let ThreadSearchResponse { data, .. } = search(&mut mcp, "refresh", None).await?;
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].thread.id, docs);
+    assert_eq!(data[0].snippet, None);
+    let ThreadSearchResponse { data, .. } =
+        search(&mut mcp, "", Some(vec!["release".to_string()])).await?;
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].thread.name.as_deref(), Some("Docs refresh"));
+    assert_eq!(data[0].thread.tags, tags);
+
+    let list_id = mcp
+        .send_thread_list_request(ThreadListParams {
+            cursor: None,
+            limit: None,
+            sort_key: None,
+            model_providers: None,
+            archived: None,
+            forked_from_id: None,
+        })
+        .await?;
+    let list_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await??;
+    let ThreadListResponse { data, .. } = to_response::<ThreadListResponse>(list_resp)?;
+    let listed = data
+        .iter()
+        .find(|thread| thread.id == docs)
+        .expect("docs thread listed");
+    assert_eq!(listed.tags, tags);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn thread_delete_removes_the_rollout() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let thread_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-05T12-00-00",
+        "2025-01-05T12:00:00Z",
+        "Saved user message",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let delete_id = mcp
+        .send_thread_delete_request(ThreadDeleteParams {
+            thread_id: thread_id.clone(),
+        })
+        .await?;
+    let delete_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(delete_id)),
+    )
+    .await??;
+    let ThreadDeleteResponse {} = to_response::<ThreadDeleteResponse>(delete_resp)?;
+
+    let read_id = mcp
+        .send_thread_read_request(ThreadReadParams {
+            thread_id,
+            include_turns: false,
+            include_stats: false,
+        })
+        .await?;
+    let _: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(read_id)),
+    )
+    .await??;
+
+    Ok(())
+}
+
+async fn search(
+    mcp: &mut McpProcess,
+    query: &str,
+    tags: Option<Vec<String>>,
+) -> Result<ThreadSearchResponse> {
+    let request_id = mcp
+        .send_thread_search_request(ThreadSearchParams {
+            query: query.to_string(),
+            tags,
+            cursor: None,
+            limit: None,
+            archived: None,
+        })
+        .await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    to_response::<ThreadSearchResponse>(resp)
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "http://127.0.0.1:0/v1"
+wire_api = "responses"
+request_max_retries = 0
+stream_max_retries = 0
+"#,
+    )
+}
@@ -13,12 +13,17 @@ use crate::ApplyPatchArgs;
 use crate::ApplyPatchError;
 use crate::ApplyPatchFileChange;
 use crate::ApplyPatchFileUpdate;
+use crate::ChunkOutcome;
 use crate::IoError;
 use crate::MaybeApplyPatchVerified;
+use crate::VerifiedFileUpdate;
 use crate::parser::Hunk;
 use crate::parser::ParseError;
+use crate::parser::UpdateFileChunk;
+use crate::parser::format_patch;
 use crate::parser::parse_patch;
-use crate::unified_diff_from_chunks;
+use crate::split_lines;
+use crate::verified_file_update;
 use std::str::Utf8Error;
 use tree_sitter::LanguageError;
 
@@ -130,6 +135,21 @@ pub fn maybe_parse_apply_patch(argv: &[String]) -> MaybeApplyPatch {
 /// cwd must be an absolute path so that we can resolve relative paths in the
 /// patch.
 pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApplyPatchVerified {
+    maybe_parse_apply_patch_verified_with_base(argv, cwd, &|_| None)
+}
+
+/// Like [`maybe_parse_apply_patch_verified`], with a three-way fallback for
+/// updates that no longer match their file: `base` returns an older copy of
+/// a file (the one the patch was most likely written against), the chunks are
+/// placed on it and carried over to the current contents, and chunks that
+/// collide with later edits are left out and reported as conflicts. The
+/// returned action's `patch` is rewritten so that applying it reproduces the
+/// merged contents.
+pub fn maybe_parse_apply_patch_verified_with_base(
+    argv: &[String],
+    cwd: &Path,
+    base: &dyn Fn(&Path) -> Option<String>,
+) -> MaybeApplyPatchVerified {
     // Detect a raw patch body passed directly as the command or as the body of a shell
     // script. In these cases, report an explicit error rather than applying the patch.
     if let [body] = argv
@@ -161,11 +181,18 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                 })
                 .unwrap_or_else(|| cwd.to_path_buf());
             let mut changes = HashMap::new();
+            let mut chunk_reports = HashMap::new();
+            // Hunks to apply, with merged updates replaced by a rewrite of the
+            // whole file; only used when some update needed the base.
+            let mut applied_hunks = Vec::with_capacity(hunks.len());
+            let mut merged = false;
             for hunk in hunks {
                 let path = hunk.resolve_path(&effective_cwd);
+                let original_hunk = hunk.clone();
                 match hunk {
                     Hunk::AddFile { contents, .. } => {
                         changes.insert(path, ApplyPatchFileChange::Add { content: contents });
+                        applied_hunks.push(original_hunk);
                     }
                     Hunk::DeleteFile { .. } => {
                         let content = match std::fs::read_to_string(&path) {
@@ -180,19 +207,53 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                             }
                         };
                         changes.insert(path, ApplyPatchFileChange::Delete { content });
+                        applied_hunks.push(original_hunk);
                     }
                     Hunk::UpdateFile {
-                        move_path, chunks, ..
+                        path: relative_path,
+                        move_path,
+                        chunks,
                     } => {
-                        let ApplyPatchFileUpdate {
-                            unified_diff,
-                            content: contents,
-                        } = match unified_diff_from_chunks(&path, &chunks) {
-                            Ok(diff) => diff,
+                        let VerifiedFileUpdate {
+                            update:
+                                ApplyPatchFileUpdate {
+                                    unified_diff,
+                                    content: contents,
+                                },
+                            original_contents,
+                            chunk_reports: reports,
+                        } = match verified_file_update(&path, &chunks, base) {
+                            Ok(update) => update,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(e);
                             }
                         };
+                        let file_merged = reports.iter().any(|report| report.outcome.is_merge());
+                        if reports
+                            .iter()
+                            .any(|report| report.outcome != ChunkOutcome::Applied)
+                        {
+                            chunk_reports.insert(path.clone(), reports);
+                        }
+                        if file_merged {
+                            merged = true;
+                            // Every chunk conflicted or was already applied.
+                            if contents == original_contents && move_path.is_none() {
+                                continue;
+                            }
+                            applied_hunks.push(Hunk::UpdateFile {
+                                path: relative_path,
+                                move_path: move_path.clone(),
+                                chunks: vec![UpdateFileChunk {
+                                    change_context: None,
+                                    old_lines: split_lines(&original_contents),
+                                    new_lines: split_lines(&contents),
+                                    is_end_of_file: false,
+                                }],
+                            });
+                        } else {
+                            applied_hunks.push(original_hunk);
+                        }
                         changes.insert(
                             path,
                             ApplyPatchFileChange::Update {
@@ -204,8 +265,14 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                     }
                 }
             }
+            let patch = if merged {
+                format_patch(&applied_hunks)
+            } else {
+                patch
+            };
             MaybeApplyPatchVerified::Body(ApplyPatchAction {
                 changes,
+                chunk_reports,
                 patch,
                 cwd: effective_cwd,
             })
@@ -396,6 +463,8 @@ fn extract_apply_patch_from_bash(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ChunkReport;
+    use crate::unified_diff_from_chunks;
     use assert_matches::assert_matches;
     use pretty_assertions::assert_eq;
     use std::fs;
@@ -783,6 +852,7 @@ PATCH"#,
                         new_content: "updated session directory content\n".to_string(),
                     },
                 )]),
+                chunk_reports: HashMap::new(),
                 patch: argv[1].clone(),
                 cwd: session_dir.path().to_path_buf(),
             })
@@ -835,4 +905,57 @@ PATCH"#,
             other => panic!("expected update change, got {other:?}"),
         }
     }
+
+    #[test]
+    fn test_verified_with_base_merges_and_reports_conflicts() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        let base = "one\ntwo\nthree\nfour\n".to_string();
+        fs::write(&path, "zero\none\ntwo\nthree\nFOUR\n").unwrap();
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+-two
++TWO
+@@
+-four
++4"#,
+            path.display()
+        ));
+        let argv = vec!["apply_patch".to_string(), patch.clone()];
+
+        assert_matches!(
+            maybe_parse_apply_patch_verified(&argv, dir.path()),
+            MaybeApplyPatchVerified::CorrectnessError(ApplyPatchError::ComputeReplacements(_))
+        );
+
+        let result =
+            maybe_parse_apply_patch_verified_with_base(&argv, dir.path(), &|_| Some(base.clone()));
+        let action = match result {
+            MaybeApplyPatchVerified::Body(action) => action,
+            other => panic!("expected verified body, got {other:?}"),
+        };
+        assert_eq!(
+            action.chunk_reports().get(&path),
+            Some(&vec![
+                ChunkReport {
+                    line: 3,
+                    outcome: ChunkOutcome::Merged,
+                },
+                ChunkReport {
+                    line: 5,
+                    outcome: ChunkOutcome::Conflict,
+                },
+            ])
+        );
+        assert_ne!(action.patch, patch);
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        crate::apply_patch(&action.patch, &mut stdout, &mut stderr).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "zero\none\nTWO\nthree\nFOUR\n"
+        );
+    }
 }
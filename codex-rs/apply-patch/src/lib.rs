@@ -1,4 +1,5 @@
 mod invocation;
+mod merge;
 mod parser;
 mod seek_sequence;
 mod standalone_executable;
@@ -18,6 +19,7 @@ use similar::TextDiff;
 use thiserror::Error;
 
 pub use invocation::maybe_parse_apply_patch_verified;
+pub use invocation::maybe_parse_apply_patch_verified_with_base;
 pub use standalone_executable::main;
 
 use crate::invocation::ExtractHeredocError;
@@ -25,6 +27,10 @@ use crate::invocation::ExtractHeredocError;
 /// Detailed instructions for gpt-4.1 on how to use the `apply_patch` tool.
 pub const APPLY_PATCH_TOOL_INSTRUCTIONS: &str = include_str!("../apply_patch_tool_instructions.md");
 
+/// How many context lines at either end of a chunk may be ignored when the
+/// chunk does not match the file as written.
+const MAX_FUZZ: usize = 2;
+
 #[derive(Debug, Error, PartialEq)]
 pub enum ApplyPatchError {
     #[error(transparent)]
@@ -113,12 +119,52 @@ pub enum MaybeApplyPatchVerified {
     NotApplyPatch,
 }
 
+/// How one `@@` chunk of an `Update File` hunk was placed in its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkOutcome {
+    /// The chunk matched the file as written.
+    Applied,
+    /// The chunk matched after ignoring `fuzz` context lines at its ends.
+    Fuzzy { fuzz: usize },
+    /// The chunk matched an older copy of the file and was carried over to
+    /// the current contents.
+    Merged,
+    /// The current file already contains the chunk's result.
+    AlreadyApplied,
+    /// The file changed in the lines the chunk touches since the older copy,
+    /// so the chunk was left out.
+    Conflict,
+}
+
+impl ChunkOutcome {
+    /// Whether the chunk was placed using an older copy of the file.
+    pub fn is_merge(self) -> bool {
+        matches!(
+            self,
+            ChunkOutcome::Merged | ChunkOutcome::AlreadyApplied | ChunkOutcome::Conflict
+        )
+    }
+}
+
+/// Where one chunk landed and how it was placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkReport {
+    /// 1-based line in the file as it was before the patch where the chunk
+    /// starts (or would have started, for a conflict).
+    pub line: usize,
+    pub outcome: ChunkOutcome,
+}
+
 /// ApplyPatchAction is the result of parsing an `apply_patch` command. By
 /// construction, all paths should be absolute paths.
 #[derive(Debug, PartialEq)]
 pub struct ApplyPatchAction {
     changes: HashMap<PathBuf, ApplyPatchFileChange>,
 
+    /// Per-chunk placement for updated files where some chunk did not match
+    /// as written, in chunk order. Files whose chunks all matched are absent.
+    chunk_reports: HashMap<PathBuf, Vec<ChunkReport>>,
+
     /// The raw patch argument that can be used with `apply_patch` as an exec
     /// call. i.e., if the original arg was parsed in "lenient" mode with a
     /// heredoc, this should be the value without the heredoc wrapper.
@@ -138,6 +184,12 @@ impl ApplyPatchAction {
         &self.changes
     }
 
+    /// Returns how the chunks were placed in files that did not match the
+    /// patch exactly.
+    pub fn chunk_reports(&self) -> &HashMap<PathBuf, Vec<ChunkReport>> {
+        &self.chunk_reports
+    }
+
     /// Should be used exclusively for testing. (Not worth the overhead of
     /// creating a feature flag for this.)
     pub fn new_add_for_test(path: &Path, content: String) -> Self {
@@ -161,6 +213,7 @@ impl ApplyPatchAction {
         #[expect(clippy::expect_used)]
         Self {
             changes,
+            chunk_reports: HashMap::new(),
             cwd: path
                 .parent()
                 .expect("path should have parent")
@@ -332,6 +385,7 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
 struct AppliedPatch {
     original_contents: String,
     new_contents: String,
+    chunk_reports: Vec<ChunkReport>,
 }
 
 /// Return *only* the new file contents (joined into a single `String`) after
@@ -339,6 +393,18 @@ struct AppliedPatch {
 fn derive_new_contents_from_chunks(
     path: &Path,
     chunks: &[UpdateFileChunk],
+) -> std::result::Result<AppliedPatch, ApplyPatchError> {
+    derive_new_contents_with_base(path, chunks, &|_| None)
+}
+
+/// Like [`derive_new_contents_from_chunks`], but when the chunks no longer
+/// match the file, `base` is asked for an older copy of it (the one the patch
+/// was most likely written against). The chunks are then placed on that copy
+/// and carried over to the current contents wherever the two still agree.
+fn derive_new_contents_with_base(
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+    base: &dyn Fn(&Path) -> Option<String>,
 ) -> std::result::Result<AppliedPatch, ApplyPatchError> {
     let original_contents = match std::fs::read_to_string(path) {
         Ok(contents) => contents,
@@ -350,17 +416,40 @@ fn derive_new_contents_from_chunks(
         }
     };
 
-    let mut original_lines: Vec<String> = original_contents.split('\n').map(String::from).collect();
-
-    // Drop the trailing empty element that results from the final newline so
-    // that line counts match the behaviour of standard `diff`.
-    if original_lines.last().is_some_and(String::is_empty) {
-        original_lines.pop();
-    }
+    let original_lines = split_lines(&original_contents);
+    let mut placements = match place_chunks(&original_lines, path, chunks) {
+        Ok(placements) => placements,
+        Err(err) => {
+            let Some(base_contents) = base(path) else {
+                return Err(err);
+            };
+            let base_lines = split_lines(&base_contents);
+            if base_lines == original_lines {
+                return Err(err);
+            }
+            // The base cannot explain the patch either, so report the error
+            // against the file the caller knows about.
+            let Ok(base_placements) = place_chunks(&base_lines, path, chunks) else {
+                return Err(err);
+            };
+            merge::merge_placements(&base_lines, &original_lines, base_placements)
+        }
+    };
 
-    let replacements = compute_replacements(&original_lines, path, chunks)?;
-    let new_lines = apply_replacements(original_lines, &replacements);
-    let mut new_lines = new_lines;
+    let chunk_reports = placements
+        .iter()
+        .map(|placement| ChunkReport {
+            line: placement.start + 1,
+            outcome: placement.outcome,
+        })
+        .collect();
+    placements.sort_by_key(|placement| placement.start);
+    let replacements: Vec<(usize, usize, Vec<String>)> = placements
+        .into_iter()
+        .map(|placement| (placement.start, placement.old_len, placement.new_lines))
+        .collect();
+
+    let mut new_lines = apply_replacements(original_lines, &replacements);
     if !new_lines.last().is_some_and(String::is_empty) {
         new_lines.push(String::new());
     }
@@ -368,18 +457,39 @@ fn derive_new_contents_from_chunks(
     Ok(AppliedPatch {
         original_contents,
         new_contents,
+        chunk_reports,
     })
 }
 
-/// Compute a list of replacements needed to transform `original_lines` into the
-/// new lines, given the patch `chunks`. Each replacement is returned as
-/// `(start_index, old_len, new_lines)`.
-fn compute_replacements(
+/// Splits file contents into lines, dropping the trailing empty element that
+/// results from the final newline so that line counts match the behaviour of
+/// standard `diff`.
+fn split_lines(contents: &str) -> Vec<String> {
+    let mut lines: Vec<String> = contents.split('\n').map(String::from).collect();
+    if lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Where one chunk landed: `old_len` lines starting at `start` are replaced
+/// with `new_lines`. A chunk that was not applied replaces nothing.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Placement {
+    pub(crate) start: usize,
+    pub(crate) old_len: usize,
+    pub(crate) new_lines: Vec<String>,
+    pub(crate) outcome: ChunkOutcome,
+}
+
+/// Locate every chunk in `original_lines`, in order. Each placement says
+/// which lines the chunk replaces and how it was found.
+fn place_chunks(
     original_lines: &[String],
     path: &Path,
     chunks: &[UpdateFileChunk],
-) -> std::result::Result<Vec<(usize, usize, Vec<String>)>, ApplyPatchError> {
-    let mut replacements: Vec<(usize, usize, Vec<String>)> = Vec::new();
+) -> std::result::Result<Vec<Placement>, ApplyPatchError> {
+    let mut placements: Vec<Placement> = Vec::new();
     let mut line_index: usize = 0;
 
     for chunk in chunks {
@@ -410,7 +520,12 @@ fn compute_replacements(
             } else {
                 original_lines.len()
             };
-            replacements.push((insertion_idx, 0, chunk.new_lines.clone()));
+            placements.push(Placement {
+                start: insertion_idx,
+                old_len: 0,
+                new_lines: chunk.new_lines.clone(),
+                outcome: ChunkOutcome::Applied,
+            });
             continue;
         }
 
@@ -448,8 +563,16 @@ fn compute_replacements(
         }
 
         if let Some(start_idx) = found {
-            replacements.push((start_idx, pattern.len(), new_slice.to_vec()));
+            placements.push(Placement {
+                start: start_idx,
+                old_len: pattern.len(),
+                new_lines: new_slice.to_vec(),
+                outcome: ChunkOutcome::Applied,
+            });
             line_index = start_idx + pattern.len();
+        } else if let Some(placement) = seek_with_fuzz(original_lines, chunk, line_index) {
+            line_index = placement.start + placement.old_len;
+            placements.push(placement);
         } else {
             return Err(ApplyPatchError::ComputeReplacements(format!(
                 "Failed to find expected lines in {}:\n{}",
@@ -459,9 +582,59 @@ fn compute_replacements(
         }
     }
 
-    replacements.sort_by(|(lhs_idx, _, _), (rhs_idx, _, _)| lhs_idx.cmp(rhs_idx));
+    Ok(placements)
+}
+
+/// Retry a chunk that did not match while ignoring up to [`MAX_FUZZ`] of its
+/// leading and trailing context lines, like `patch --fuzz`. Removed lines must
+/// still match, and the shortened chunk must match in exactly one place so a
+/// generic remainder (a lone `}`) is never applied at a guess.
+fn seek_with_fuzz(lines: &[String], chunk: &UpdateFileChunk, start: usize) -> Option<Placement> {
+    let old_lines = &chunk.old_lines;
+    let new_lines = &chunk.new_lines;
+    let leading = old_lines
+        .iter()
+        .zip(new_lines)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let trailing = old_lines[leading..]
+        .iter()
+        .rev()
+        .zip(new_lines[leading..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    // Try the smallest amount of fuzz first, trimming one end before both.
+    let mut skips: Vec<(usize, usize)> = (0..=leading.min(MAX_FUZZ))
+        .flat_map(|skip_start| {
+            (0..=trailing.min(MAX_FUZZ)).map(move |skip_end| (skip_start, skip_end))
+        })
+        .filter(|&skip| skip != (0, 0))
+        .collect();
+    skips.sort_by_key(|&(skip_start, skip_end)| (skip_start.max(skip_end), skip_start + skip_end));
 
-    Ok(replacements)
+    for (skip_start, skip_end) in skips {
+        let pattern = &old_lines[skip_start..old_lines.len() - skip_end];
+        if pattern.iter().all(|line| line.trim().is_empty()) {
+            continue;
+        }
+        let eof = chunk.is_end_of_file && skip_end == 0;
+        let Some(found) = seek_sequence::seek_sequence(lines, pattern, start, eof) else {
+            continue;
+        };
+        if seek_sequence::seek_sequence(lines, pattern, found + 1, false).is_some() {
+            continue;
+        }
+        return Some(Placement {
+            start: found,
+            old_len: pattern.len(),
+            new_lines: new_lines[skip_start..new_lines.len() - skip_end].to_vec(),
+            outcome: ChunkOutcome::Fuzzy {
+                fuzz: skip_start.max(skip_end),
+            },
+        });
+    }
+    None
 }
 
 /// Apply the `(start_index, old_len, new_lines)` replacements to `original_lines`,
@@ -514,6 +687,7 @@ pub fn unified_diff_from_chunks_with_context(
     let AppliedPatch {
         original_contents,
         new_contents,
+        ..
     } = derive_new_contents_from_chunks(path, chunks)?;
     let text_diff = TextDiff::from_lines(&original_contents, &new_contents);
     let unified_diff = text_diff.unified_diff().context_radius(context).to_string();
@@ -523,6 +697,36 @@ pub fn unified_diff_from_chunks_with_context(
     })
 }
 
+/// A verified file update together with the contents it was computed from
+/// and how each chunk was placed.
+struct VerifiedFileUpdate {
+    update: ApplyPatchFileUpdate,
+    original_contents: String,
+    chunk_reports: Vec<ChunkReport>,
+}
+
+fn verified_file_update(
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+    base: &dyn Fn(&Path) -> Option<String>,
+) -> std::result::Result<VerifiedFileUpdate, ApplyPatchError> {
+    let AppliedPatch {
+        original_contents,
+        new_contents,
+        chunk_reports,
+    } = derive_new_contents_with_base(path, chunks, base)?;
+    let text_diff = TextDiff::from_lines(&original_contents, &new_contents);
+    let unified_diff = text_diff.unified_diff().context_radius(1).to_string();
+    Ok(VerifiedFileUpdate {
+        update: ApplyPatchFileUpdate {
+            unified_diff,
+            content: new_contents,
+        },
+        original_contents,
+        chunk_reports,
+    })
+}
+
 /// Print the summary of changes in git-style format.
 /// Write a summary of changes to the given writer.
 pub fn print_summary(
@@ -1062,4 +1266,39 @@ g
         let result = apply_patch(&patch, &mut stdout, &mut stderr);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_fuzzy_chunk_ignores_drifted_context() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fuzzy.txt");
+        fs::write(&path, "header v2\nold\ntail\nold\n").unwrap();
+        let chunks = vec![UpdateFileChunk {
+            change_context: None,
+            old_lines: vec!["header".to_string(), "old".to_string(), "tail".to_string()],
+            new_lines: vec!["header".to_string(), "new".to_string(), "tail".to_string()],
+            is_end_of_file: false,
+        }];
+
+        let applied = derive_new_contents_from_chunks(&path, &chunks).unwrap();
+        assert_eq!(applied.new_contents, "header v2\nnew\ntail\nold\n");
+        assert_eq!(
+            applied.chunk_reports,
+            vec![ChunkReport {
+                line: 2,
+                outcome: ChunkOutcome::Fuzzy { fuzz: 1 },
+            }]
+        );
+
+        // Without the trailing context the remaining `old` is ambiguous.
+        let ambiguous = vec![UpdateFileChunk {
+            change_context: None,
+            old_lines: vec!["header".to_string(), "old".to_string()],
+            new_lines: vec!["header".to_string(), "new".to_string()],
+            is_end_of_file: false,
+        }];
+        assert_matches::assert_matches!(
+            derive_new_contents_from_chunks(&path, &ambiguous).err(),
+            Some(ApplyPatchError::ComputeReplacements(_))
+        );
+    }
 }
@@ -0,0 +1,192 @@
+//! Three-way placement of patch chunks.
+//!
+//! When a patch no longer matches a file, its chunks are placed on an older
+//! copy of the file (the base) instead. Each chunk is then carried over to the
+//! current contents if the lines it replaces are unchanged between the two
+//! copies, and reported as a conflict otherwise.
+
+use similar::Algorithm;
+use similar::DiffOp;
+use similar::capture_diff_slices;
+
+use crate::ChunkOutcome;
+use crate::Placement;
+
+/// A region that differs between the base and the current contents:
+/// `base_len` base lines at `base_start` became `current_len` lines.
+struct Change {
+    base_start: usize,
+    base_len: usize,
+    current_len: usize,
+}
+
+impl Change {
+    fn base_end(&self) -> usize {
+        self.base_start + self.base_len
+    }
+
+    /// Whether this change touches the base lines `start..end`. An insertion
+    /// only counts when it lands strictly inside the range, and an empty
+    /// range (a pure addition) only when it falls inside a replaced region.
+    fn overlaps(&self, start: usize, end: usize) -> bool {
+        if start == end {
+            self.base_start < start && start < self.base_end()
+        } else if self.base_len == 0 {
+            start < self.base_start && self.base_start < end
+        } else {
+            self.base_start < end && start < self.base_end()
+        }
+    }
+
+    fn delta(&self) -> isize {
+        self.current_len as isize - self.base_len as isize
+    }
+}
+
+/// Moves `placements` computed against `base` onto `current`.
+pub(crate) fn merge_placements(
+    base: &[String],
+    current: &[String],
+    placements: Vec<Placement>,
+) -> Vec<Placement> {
+    let changes: Vec<Change> = capture_diff_slices(Algorithm::Myers, base, current)
+        .into_iter()
+        .filter_map(|op| match op {
+            DiffOp::Equal { .. } => None,
+            DiffOp::Delete {
+                old_index, old_len, ..
+            } => Some(Change {
+                base_start: old_index,
+                base_len: old_len,
+                current_len: 0,
+            }),
+            DiffOp::Insert {
+                old_index, new_len, ..
+            } => Some(Change {
+                base_start: old_index,
+                base_len: 0,
+                current_len: new_len,
+            }),
+            DiffOp::Replace {
+                old_index,
+                old_len,
+                new_len,
+                ..
+            } => Some(Change {
+                base_start: old_index,
+                base_len: old_len,
+                current_len: new_len,
+            }),
+        })
+        .collect();
+
+    placements
+        .into_iter()
+        .map(|placement| merge_placement(&changes, current, placement))
+        .collect()
+}
+
+fn merge_placement(changes: &[Change], current: &[String], placement: Placement) -> Placement {
+    let start = placement.start;
+    let end = start + placement.old_len;
+
+    // Changes wholly before the chunk shift it; changes it overlaps make it
+    // a conflict but still widen the region it maps to.
+    let mut shift_before: isize = 0;
+    let mut shift_inside: isize = 0;
+    let mut conflict = false;
+    for change in changes {
+        if change.overlaps(start, end) {
+            conflict = true;
+            shift_inside += change.delta();
+        } else if change.base_end() <= start {
+            shift_before += change.delta();
+        }
+    }
+
+    let current_start = start.saturating_add_signed(shift_before).min(current.len());
+    if !conflict {
+        return Placement {
+            start: current_start,
+            outcome: ChunkOutcome::Merged,
+            ..placement
+        };
+    }
+
+    let current_end = end
+        .saturating_add_signed(shift_before + shift_inside)
+        .clamp(current_start, current.len());
+    let outcome = if current[current_start..current_end] == placement.new_lines[..] {
+        ChunkOutcome::AlreadyApplied
+    } else {
+        ChunkOutcome::Conflict
+    };
+    Placement {
+        start: current_start,
+        old_len: 0,
+        new_lines: Vec::new(),
+        outcome,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn placement(start: usize, old_len: usize, new_lines: &str) -> Placement {
+        Placement {
+            start,
+            old_len,
+            new_lines: lines(new_lines),
+            outcome: ChunkOutcome::Applied,
+        }
+    }
+
+    #[test]
+    fn carries_chunks_past_unrelated_edits() {
+        let base = lines("a\nb\nc\nd\ne");
+        let current = lines("intro\na\nb\nc\nd\nE");
+
+        let merged = merge_placements(&base, &current, vec![placement(1, 2, "B\nC")]);
+
+        assert_eq!(
+            merged,
+            vec![Placement {
+                start: 2,
+                old_len: 2,
+                new_lines: lines("B\nC"),
+                outcome: ChunkOutcome::Merged,
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_conflicts_and_already_applied_chunks() {
+        let base = lines("a\nb\nc\nd\ne");
+        let current = lines("a\nB\nc\nd\nx\ne");
+
+        let merged = merge_placements(
+            &base,
+            &current,
+            vec![placement(1, 1, "B"), placement(3, 2, "d\ny\ne")],
+        );
+
+        let outcomes: Vec<(usize, ChunkOutcome)> = merged
+            .iter()
+            .map(|placement| (placement.start, placement.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                (1, ChunkOutcome::AlreadyApplied),
+                (3, ChunkOutcome::Conflict)
+            ]
+        );
+        assert!(merged.iter().all(|placement| placement.old_len == 0));
+    }
+}
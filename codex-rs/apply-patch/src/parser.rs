@@ -112,6 +112,50 @@ pub fn parse_patch(patch: &str) -> Result<ApplyPatchArgs, ParseError> {
     parse_patch_text(patch, mode)
 }
 
+/// Renders `hunks` back into patch text that [`parse_patch`] turns into the
+/// same hunks. Context lines are written as a removal plus an addition, which
+/// parses to the same chunk.
+pub(crate) fn format_patch(hunks: &[Hunk]) -> String {
+    let mut lines = vec![BEGIN_PATCH_MARKER.to_string()];
+    for hunk in hunks {
+        match hunk {
+            AddFile { path, contents } => {
+                lines.push(format!("{ADD_FILE_MARKER}{}", path.display()));
+                if !contents.is_empty() {
+                    let body = contents.strip_suffix('\n').unwrap_or(contents);
+                    lines.extend(body.split('\n').map(|line| format!("+{line}")));
+                }
+            }
+            DeleteFile { path } => {
+                lines.push(format!("{DELETE_FILE_MARKER}{}", path.display()));
+            }
+            UpdateFile {
+                path,
+                move_path,
+                chunks,
+            } => {
+                lines.push(format!("{UPDATE_FILE_MARKER}{}", path.display()));
+                if let Some(move_path) = move_path {
+                    lines.push(format!("{MOVE_TO_MARKER}{}", move_path.display()));
+                }
+                for chunk in chunks {
+                    lines.push(match &chunk.change_context {
+                        Some(context) => format!("{CHANGE_CONTEXT_MARKER}{context}"),
+                        None => EMPTY_CHANGE_CONTEXT_MARKER.to_string(),
+                    });
+                    lines.extend(chunk.old_lines.iter().map(|line| format!("-{line}")));
+                    lines.extend(chunk.new_lines.iter().map(|line| format!("+{line}")));
+                    if chunk.is_end_of_file {
+                        lines.push(EOF_MARKER.to_string());
+                    }
+                }
+            }
+        }
+    }
+    lines.push(END_PATCH_MARKER.to_string());
+    lines.join("\n")
+}
+
 enum ParseMode {
     /// Parse the patch text argument as is.
     Strict,
@@ -761,3 +805,38 @@ fn test_update_file_chunk() {
         ))
     );
 }
+
+#[test]
+fn test_format_patch_round_trips() {
+    let hunks = vec![
+        AddFile {
+            path: PathBuf::from("new.txt"),
+            contents: "first\n\nthird\n".to_string(),
+        },
+        DeleteFile {
+            path: PathBuf::from("old.txt"),
+        },
+        UpdateFile {
+            path: PathBuf::from("src/lib.rs"),
+            move_path: Some(PathBuf::from("src/main.rs")),
+            chunks: vec![
+                UpdateFileChunk {
+                    change_context: Some("fn main() {".to_string()),
+                    old_lines: vec!["    a();".to_string(), "".to_string()],
+                    new_lines: vec!["    b();".to_string(), "".to_string()],
+                    is_end_of_file: false,
+                },
+                UpdateFileChunk {
+                    change_context: None,
+                    old_lines: vec![],
+                    new_lines: vec!["// end".to_string()],
+                    is_end_of_file: true,
+                },
+            ],
+        },
+    ];
+
+    let parsed = parse_patch(&format_patch(&hunks)).map(|args| args.hunks);
+
+    assert_eq!(parsed, Ok(hunks));
+}
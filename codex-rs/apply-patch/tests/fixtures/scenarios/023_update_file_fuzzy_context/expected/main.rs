@@ -0,0 +1,5 @@
+fn main() {
+    // entry point, edited
+    let x = 2;
+    println!("{x}");
+}
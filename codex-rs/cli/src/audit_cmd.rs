@@ -0,0 +1,119 @@
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::audit::AuditRecord;
+use codex_core::audit::read_audit_log;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+
+/// Subcommands:
+/// - `show` — print recorded agent actions
+#[derive(Debug, clap::Parser)]
+pub struct AuditCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: AuditSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum AuditSubcommand {
+    /// Print the most recent records of the audit log (`[audit]`).
+    Show(ShowArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ShowArgs {
+    /// Only show records of this session.
+    #[arg(long = "session", value_name = "SESSION_ID")]
+    pub session_id: Option<String>,
+
+    /// Only show this kind of action: `exec`, `patch_apply`, `file_write`,
+    /// `approval`, `subagent_start`, or `subagent_end`.
+    #[arg(long = "action", value_name = "ACTION")]
+    pub action: Option<String>,
+
+    /// Number of records to show, newest last. `0` shows all of them.
+    #[arg(long = "limit", short = 'n', default_value_t = 50)]
+    pub limit: usize,
+
+    /// Print the records as JSON lines, as stored.
+    #[arg(long = "json", default_value_t = false)]
+    pub json: bool,
+}
+
+impl AuditCli {
+    pub async fn run(self) -> Result<()> {
+        let AuditCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        match subcommand {
+            AuditSubcommand::Show(args) => run_show(&config_overrides, args).await,
+        }
+    }
+}
+
+async fn run_show(config_overrides: &CliConfigOverrides, args: ShowArgs) -> Result<()> {
+    let ShowArgs {
+        session_id,
+        action,
+        limit,
+        json,
+    } = args;
+
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides::default(),
+    )
+    .await
+    .context("failed to load configuration")?;
+
+    let records: Vec<AuditRecord> = read_audit_log(&config.codex_home)
+        .context("failed to read the audit log")?
+        .into_iter()
+        .filter(|record| {
+            session_id
+                .as_deref()
+                .is_none_or(|session_id| record.session_id == session_id)
+        })
+        .filter(|record| {
+            action
+                .as_deref()
+                .is_none_or(|action| record.action.name() == action)
+        })
+        .collect();
+    if records.is_empty() {
+        if config.audit.is_none() {
+            println!("No audit records. Enable the log with an `[audit]` section in config.toml.");
+        } else {
+            println!("No audit records.");
+        }
+        return Ok(());
+    }
+
+    let skip = match limit {
+        0 => 0,
+        limit => records.len().saturating_sub(limit),
+    };
+    for record in &records[skip..] {
+        if json {
+            println!("{}", serde_json::to_string(record)?);
+        } else {
+            println!(
+                "{}  {}  {:<14}  {:<8}  {}",
+                record.ts,
+                record.session_id,
+                record.action.name(),
+                record.outcome.as_str(),
+                record.action.summary()
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_app_server_protocol::CapabilitiesReadResponse;
+use codex_app_server_protocol::CapabilityStatus;
+use codex_common::CliConfigOverrides;
+use codex_core::capabilities::collect_capabilities;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+
+/// Reports which optional subsystems (sandbox, keyring, image input, MCP
+/// servers, ...) are active here, and why the others are not.
+#[derive(Debug, clap::Parser)]
+pub struct CapabilitiesCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Do not start the configured MCP servers; report them from config only.
+    #[arg(long = "skip-mcp", default_value_t = false)]
+    pub skip_mcp: bool,
+
+    /// Print the report as JSON (the `capabilities/read` response shape).
+    #[arg(long = "json", default_value_t = false)]
+    pub json: bool,
+}
+
+impl CapabilitiesCli {
+    pub async fn run(self, codex_linux_sandbox_exe: Option<PathBuf>) -> Result<()> {
+        let overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            overrides,
+            ConfigOverrides {
+                codex_linux_sandbox_exe,
+                ..Default::default()
+            },
+        )
+        .await
+        .context("failed to load configuration")?;
+
+        let capabilities = collect_capabilities(&config, self.skip_mcp).await;
+        if self.json {
+            let response = CapabilitiesReadResponse { capabilities };
+            println!("{}", serde_json::to_string_pretty(&response)?);
+            return Ok(());
+        }
+
+        let id_width = capabilities
+            .iter()
+            .map(|capability| capability.id.len())
+            .max()
+            .unwrap_or(0);
+        for capability in &capabilities {
+            let status = match capability.status {
+                CapabilityStatus::Active => "active",
+                CapabilityStatus::Disabled => "disabled",
+                CapabilityStatus::Unavailable => "unavailable",
+            };
+            println!(
+                "{:<id_width$}  {status:<11}  {}",
+                capability.id, capability.detail
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::ThreadManager;
+use codex_core::ci::CiState;
+use codex_core::ci::FailedCheck;
+use codex_core::ci::GitHubClient;
+use codex_core::ci::GitHubRepo;
+use codex_core::ci::ci_state;
+use codex_core::ci::fix_prompt;
+use codex_core::ci::run_fix_turn;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::git_info::collect_git_info;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::SessionSource;
+use tokio::time::Instant;
+
+/// Subcommands:
+/// - `watch` — wait for CI on the pushed HEAD commit and run fix turns for
+///   failing checks
+#[derive(Debug, clap::Parser)]
+pub struct CiCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: CiSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum CiSubcommand {
+    /// Wait for the GitHub checks of HEAD. When some fail, start a headless
+    /// fix turn from their logs, push its commit, and watch again.
+    Watch(WatchArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct WatchArgs {
+    /// Repository to watch (defaults to the current directory).
+    #[arg(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Fix turns to run before giving up; 0 only reports the result.
+    #[arg(long, default_value_t = 2, value_name = "N")]
+    pub max_fix_turns: usize,
+
+    /// Minutes to wait for the checks of one commit to finish.
+    #[arg(long, default_value_t = 60, value_name = "MINUTES")]
+    pub timeout: u64,
+
+    /// Seconds between polls of the checks API.
+    #[arg(long, default_value_t = 30, value_name = "SECONDS")]
+    pub poll_interval: u64,
+}
+
+impl CiCli {
+    pub async fn run(self) -> Result<()> {
+        let CiCli {
+            config_overrides,
+            subcommand,
+        } = self;
+        match subcommand {
+            CiSubcommand::Watch(args) => run_watch(&config_overrides, args).await,
+        }
+    }
+}
+
+async fn run_watch(config_overrides: &CliConfigOverrides, args: WatchArgs) -> Result<()> {
+    let WatchArgs {
+        cwd,
+        max_fix_turns,
+        timeout,
+        poll_interval,
+    } = args;
+    let cwd = match cwd {
+        Some(cwd) => cwd,
+        None => std::env::current_dir()?,
+    };
+    let client = GitHubClient::from_env();
+    let timeout = Duration::from_secs(timeout * 60);
+    let poll_interval = Duration::from_secs(poll_interval.max(1));
+
+    let mut fix_turns = 0;
+    // Loaded on the first fix turn only: watching needs no model access.
+    let mut fixer: Option<(ThreadManager, Config)> = None;
+    loop {
+        let head = HeadCommit::read(&cwd).await?;
+        println!(
+            "Watching CI for {}/{} `{}` at {}...",
+            head.repo.owner,
+            head.repo.name,
+            head.branch,
+            short_sha(&head.sha)
+        );
+
+        let failed = match wait_for_checks(&client, &head, timeout, poll_interval).await? {
+            CiState::Passed => {
+                println!("All checks passed.");
+                return Ok(());
+            }
+            CiState::NoChecks => bail!(
+                "no checks were reported for {} within the timeout; was it pushed?",
+                short_sha(&head.sha)
+            ),
+            CiState::Pending { completed, total } => bail!(
+                "timed out with {completed} of {total} checks completed for {}",
+                short_sha(&head.sha)
+            ),
+            CiState::Failed(failed) => failed,
+        };
+        for run in &failed {
+            println!(
+                "  failed: {} ({})",
+                run.name,
+                run.conclusion.as_deref().unwrap_or_default()
+            );
+        }
+        if fix_turns >= max_fix_turns {
+            bail!(
+                "{} check(s) failing after {fix_turns} fix turn(s)",
+                failed.len()
+            );
+        }
+
+        let mut checks = Vec::with_capacity(failed.len());
+        for run in &failed {
+            let log = if run.is_github_actions() {
+                match client.job_log(&head.repo, run.id).await {
+                    Ok(log) => Some(log),
+                    Err(err) => {
+                        eprintln!("  could not fetch the log of `{}`: {err}", run.name);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            checks.push(FailedCheck::new(run, log.as_deref()));
+        }
+
+        fix_turns += 1;
+        println!("Starting fix turn {fix_turns} of {max_fix_turns}...");
+        let (thread_manager, config) = match fixer.take() {
+            Some(fixer) => fixer,
+            None => load_fixer(config_overrides, &cwd).await?,
+        };
+        let outcome = run_fix_turn(
+            &thread_manager,
+            config.clone(),
+            fix_prompt(&head.branch, &head.sha, &checks),
+        )
+        .await;
+        fixer = Some((thread_manager, config));
+        let outcome = outcome?;
+        if let Some(message) = &outcome.last_agent_message {
+            println!("{message}");
+        }
+        if let Some(error) = outcome.error {
+            bail!("fix turn failed (thread {}): {error}", outcome.thread_id);
+        }
+
+        let new_head = HeadCommit::read(&cwd).await?;
+        if new_head.sha == head.sha {
+            bail!(
+                "fix turn made no commit (thread {}); stopping",
+                outcome.thread_id
+            );
+        }
+        git_push(&cwd).await?;
+        println!(
+            "Pushed {} from fix turn (thread {}).",
+            short_sha(&new_head.sha),
+            outcome.thread_id
+        );
+    }
+}
+
+struct HeadCommit {
+    repo: GitHubRepo,
+    branch: String,
+    sha: String,
+}
+
+impl HeadCommit {
+    async fn read(cwd: &Path) -> Result<Self> {
+        let Some(info) = collect_git_info(cwd).await else {
+            bail!("{} is not a git repository", cwd.display());
+        };
+        let Some(sha) = info.commit_hash else {
+            bail!("the repository has no commits");
+        };
+        let Some(branch) = info.branch else {
+            bail!("HEAD is detached; check out the pushed branch");
+        };
+        let repo = info
+            .repository_url
+            .as_deref()
+            .and_then(GitHubRepo::from_remote_url)
+            .context("the `origin` remote is not a GitHub repository")?;
+        Ok(Self { repo, branch, sha })
+    }
+}
+
+/// Polls until every check of `head` completed or `timeout` passed, and
+/// returns the last state seen.
+async fn wait_for_checks(
+    client: &GitHubClient,
+    head: &HeadCommit,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<CiState> {
+    let deadline = Instant::now() + timeout;
+    let mut last_progress = None;
+    loop {
+        let runs = client.check_runs(&head.repo, &head.sha).await?;
+        let state = ci_state(&runs);
+        match &state {
+            CiState::Passed | CiState::Failed(_) => return Ok(state),
+            CiState::Pending { completed, total } => {
+                if last_progress != Some((*completed, *total)) {
+                    println!("  {completed} of {total} checks completed");
+                    last_progress = Some((*completed, *total));
+                }
+            }
+            CiState::NoChecks => {}
+        }
+        if Instant::now() + poll_interval > deadline {
+            return Ok(state);
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(poll_interval) => {}
+            _ = tokio::signal::ctrl_c() => bail!("interrupted"),
+        }
+    }
+}
+
+async fn load_fixer(
+    config_overrides: &CliConfigOverrides,
+    cwd: &Path,
+) -> Result<(ThreadManager, Config)> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides {
+            cwd: Some(cwd.to_path_buf()),
+            // Headless: there is nobody to answer approval prompts.
+            approval_policy: Some(AskForApproval::Never),
+            ..Default::default()
+        },
+    )
+    .await
+    .context("failed to load configuration")?;
+    let auth_manager = AuthManager::shared(
+        config.codex_home.clone(),
+        true,
+        config.cli_auth_credentials_store_mode,
+    );
+    let thread_manager =
+        ThreadManager::new(config.codex_home.clone(), auth_manager, SessionSource::Exec);
+    Ok((thread_manager, config))
+}
+
+async fn git_push(cwd: &Path) -> Result<()> {
+    let status = tokio::process::Command::new("git")
+        .arg("push")
+        .current_dir(cwd)
+        .status()
+        .await
+        .context("failed to run `git push`")?;
+    if !status.success() {
+        bail!("`git push` failed ({status})");
+    }
+    Ok(())
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
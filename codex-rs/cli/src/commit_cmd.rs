@@ -0,0 +1,229 @@
+use std::io::BufRead;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::commit_plan::CommitPlan;
+use codex_core::commit_plan::change_units;
+use codex_core::commit_plan::commit_plan_prompt;
+use codex_core::commit_plan::commit_plan_schema;
+use codex_core::commit_plan::commit_staged;
+use codex_core::commit_plan::stage_units;
+use codex_core::commit_plan::unstage_all;
+use codex_core::commit_plan::working_tree_diff;
+use codex_core::config::Config;
+use codex_core::git_info::get_git_repo_root;
+use codex_core::pre_commit::CommitMessage;
+use codex_core::pre_commit::commit_message_prompt;
+use codex_core::pre_commit::commit_message_schema;
+use codex_core::pre_commit::staged_diff;
+
+use crate::hooks_cmd::load_config;
+use crate::hooks_cmd::review_staged_diff;
+use crate::hooks_cmd::run_structured_exec;
+
+/// Commit the staged changes with a message written by Codex, or only print
+/// the suggestion (`--suggest`) or review the changes (`--review`).
+///
+/// With `--all` or `--split`, or when nothing is staged, every change in the
+/// working tree is grouped into one or more commits instead, which are
+/// created after confirmation.
+///
+/// All runs are read-only headless `codex exec` runs; the model, timeout, and
+/// message convention come from `[pre_commit]`.
+#[derive(Debug, clap::Parser)]
+pub struct CommitCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Print the suggested message (or commit plan) instead of committing.
+    #[arg(long, default_value_t = false, conflicts_with = "review")]
+    pub suggest: bool,
+
+    /// Review the staged changes and print the findings instead of
+    /// committing. Exits with status 1 when a finding should block the commit.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["all", "split"])]
+    pub review: bool,
+
+    /// Group all changes in the working tree (staged, unstaged, and
+    /// untracked) by file into one or more commits.
+    #[arg(long, default_value_t = false)]
+    pub all: bool,
+
+    /// Like `--all`, but group individual hunks, so one file's changes can
+    /// land in different commits.
+    #[arg(long, default_value_t = false)]
+    pub split: bool,
+
+    /// Create the planned commits without asking for confirmation.
+    #[arg(long, short = 'y', default_value_t = false)]
+    pub yes: bool,
+
+    /// With `--review`, print the findings as JSON.
+    #[arg(long, default_value_t = false, requires = "review")]
+    pub json: bool,
+}
+
+impl CommitCli {
+    pub async fn run(self) -> Result<()> {
+        let cwd = std::env::current_dir().context("failed to read the current directory")?;
+        let config = load_config(&self.config_overrides).await?;
+        let diff = staged_diff(&cwd)?;
+        let nothing_staged = diff.trim().is_empty();
+        if self.all || self.split || (nothing_staged && !self.suggest && !self.review) {
+            return self.run_plan(&config, &cwd).await;
+        }
+        if nothing_staged {
+            anyhow::bail!("no staged changes; stage them with `git add` first");
+        }
+
+        if self.review {
+            let verdict = review_staged_diff(&config, &cwd, &diff, &self.config_overrides).await?;
+            if self.json {
+                println!("{}", serde_json::to_string_pretty(&verdict)?);
+            } else if verdict.findings.is_empty() {
+                println!("No problems found in the staged changes.");
+            } else {
+                for finding in &verdict.findings {
+                    let location = match finding.line {
+                        Some(line) => format!("{}:{line}", finding.path),
+                        None => finding.path.clone(),
+                    };
+                    println!("{location}: {}", finding.message);
+                }
+            }
+            if verdict.block {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+
+        let settings = &config.pre_commit;
+        let answer = run_structured_exec(
+            &cwd,
+            &commit_message_prompt(
+                &diff,
+                settings.commit_convention,
+                settings.commit_instructions.as_deref(),
+            ),
+            &commit_message_schema(),
+            settings.review_model.as_deref(),
+            settings.timeout,
+            &self.config_overrides,
+        )
+        .await?;
+        let message = serde_json::from_str::<CommitMessage>(&answer)
+            .context("unexpected commit message answer")?
+            .render(settings.commit_convention);
+        if self.suggest {
+            println!("{message}");
+            return Ok(());
+        }
+
+        // Let the user edit the suggestion before it is committed.
+        let status = Command::new("git")
+            .args(["commit", "--edit", "--message", &message])
+            .current_dir(&cwd)
+            .status()
+            .context("failed to run git commit")?;
+        if !status.success() {
+            std::process::exit(status.code().unwrap_or(1));
+        }
+        Ok(())
+    }
+
+    /// Groups the working tree changes into commits, shows the plan, and
+    /// creates the commits once confirmed.
+    async fn run_plan(&self, config: &Config, cwd: &Path) -> Result<()> {
+        let root = get_git_repo_root(cwd).context("not inside a git repository")?;
+        let units = change_units(&working_tree_diff(&root)?, self.split);
+        if units.is_empty() {
+            anyhow::bail!("no changes to commit");
+        }
+
+        let settings = &config.pre_commit;
+        let answer = run_structured_exec(
+            &root,
+            &commit_plan_prompt(
+                &units,
+                settings.commit_convention,
+                settings.commit_instructions.as_deref(),
+            ),
+            &commit_plan_schema(),
+            settings.review_model.as_deref(),
+            settings.timeout,
+            &self.config_overrides,
+        )
+        .await?;
+        let mut plan =
+            serde_json::from_str::<CommitPlan>(&answer).context("unexpected commit plan answer")?;
+        let left_out = plan.normalize(&units);
+        if plan.commits.is_empty() {
+            anyhow::bail!("codex did not propose any commits");
+        }
+
+        let messages: Vec<String> = plan
+            .commits
+            .iter()
+            .map(|commit| commit.message.render(settings.commit_convention))
+            .collect();
+        for (n, (commit, message)) in plan.commits.iter().zip(&messages).enumerate() {
+            println!("{}. {}", n + 1, message.replace('\n', "\n   "));
+            for unit in units
+                .iter()
+                .filter(|unit| commit.changes.contains(&unit.id))
+            {
+                match unit.hunk_header().filter(|_| self.split) {
+                    Some(hunk) => println!("   - {} {hunk}", unit.path),
+                    None => println!("   - {}", unit.path),
+                }
+            }
+            println!();
+        }
+        let left_out: Vec<&str> = units
+            .iter()
+            .filter(|unit| left_out.contains(&unit.id))
+            .map(|unit| unit.path.as_str())
+            .collect();
+        if !left_out.is_empty() {
+            println!("Not in any commit: {}", left_out.join(", "));
+        }
+        if self.suggest || !self.confirm(messages.len())? {
+            return Ok(());
+        }
+
+        // Every commit is staged on its own, starting from an empty index.
+        unstage_all(&root)?;
+        for (n, (planned, message)) in plan.commits.iter().zip(&messages).enumerate() {
+            let commit = stage_units(&root, &units, &planned.changes)
+                .and_then(|()| commit_staged(&root, message))
+                .with_context(|| format!("stopped after {n} of {} commits", messages.len()))?;
+            let subject = message.lines().next().unwrap_or_default();
+            println!("Created {commit} {subject}");
+        }
+        Ok(())
+    }
+
+    fn confirm(&self, commits: usize) -> Result<bool> {
+        if self.yes {
+            return Ok(true);
+        }
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!("rerun with --yes to create the commits without a terminal");
+        }
+        let noun = if commits == 1 { "commit" } else { "commits" };
+        print!("Create {commits} {noun}? [y/N] ");
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        let confirmed = matches!(answer.trim(), "y" | "Y" | "yes");
+        if !confirmed {
+            println!("Nothing committed.");
+        }
+        Ok(confirmed)
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::Context;
+use anyhow::Result;
+use codex_app_server_protocol::ConfigLayerSource;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::ConfigService;
+use codex_core::config::ConfigToml;
+use codex_core::config::find_codex_home;
+use codex_core::config::profile::resolve_profile;
+use codex_core::config::profile::resolved_config_toml;
+use codex_core::config::validate::ConfigDiagnosticKind;
+use codex_core::config_loader::LoaderOverrides;
+
+/// Subcommands:
+/// - `show` — print the config layers, or the configuration they resolve to
+/// - `check` — report unknown keys, type mismatches, and replaced options
+#[derive(Debug, clap::Parser)]
+pub struct ConfigCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ConfigSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ConfigSubcommand {
+    /// Print each config file and `-c` layer that applies in the current
+    /// directory, highest precedence first.
+    Show(ShowArgs),
+
+    /// Check the config files that apply in the current directory against the
+    /// `config.toml` schema. Exits with status 1 when a key is unknown or a
+    /// value has the wrong type.
+    Check,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ShowArgs {
+    /// Print the merged configuration instead, with the active profile (and
+    /// the profiles it extends) applied.
+    #[arg(long, default_value_t = false)]
+    pub resolved: bool,
+}
+
+impl ConfigCli {
+    /// `config_profile` is the root `--profile` flag, which takes precedence
+    /// over the `profile` key of the config files.
+    pub async fn run(self, config_profile: Option<String>) -> Result<()> {
+        let ConfigCli {
+            config_overrides,
+            subcommand,
+        } = self;
+        match subcommand {
+            ConfigSubcommand::Show(args) => run_show(&config_overrides, config_profile, args).await,
+            ConfigSubcommand::Check => run_check(&config_overrides).await,
+        }
+    }
+}
+
+async fn run_show(
+    config_overrides: &CliConfigOverrides,
+    config_profile: Option<String>,
+    args: ShowArgs,
+) -> Result<()> {
+    let ShowArgs { resolved } = args;
+
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides {
+            config_profile,
+            ..Default::default()
+        },
+    )
+    .await
+    .context("failed to load configuration")?;
+    let layers = &config.config_layer_stack;
+
+    if !resolved {
+        for layer in layers.layers_high_to_low() {
+            println!("# {}", describe_layer(&layer.name));
+            let contents = toml::to_string_pretty(&layer.config)?;
+            if contents.trim().is_empty() {
+                println!("# (empty)\n");
+            } else {
+                println!("{}", contents.trim_end());
+                println!();
+            }
+        }
+        return Ok(());
+    }
+
+    let effective = layers.effective_config();
+    let config_toml: ConfigToml = effective
+        .clone()
+        .try_into()
+        .context("failed to parse the merged configuration")?;
+    let active = match config.active_profile.as_deref() {
+        Some(name) => Some((name, resolve_profile(&config_toml.profiles, name)?)),
+        None => None,
+    };
+    let resolved = resolved_config_toml(
+        &effective,
+        active.as_ref().map(|(name, profile)| (*name, profile)),
+    )?;
+
+    println!("# Configuration for {}", config.cwd.display());
+    match &active {
+        Some((name, profile)) => match &profile.extends {
+            Some(base) => println!("# Profile `{name}` (extends `{base}`) applied."),
+            None => println!("# Profile `{name}` applied."),
+        },
+        None => println!("# No profile selected."),
+    }
+    println!();
+    print!("{}", toml::to_string_pretty(&resolved)?);
+    Ok(())
+}
+
+async fn run_check(config_overrides: &CliConfigOverrides) -> Result<()> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let service = ConfigService::new(find_codex_home()?, overrides, LoaderOverrides::default());
+    let cwd = std::env::current_dir()?;
+    let diagnostics = service
+        .validate(Some(&cwd))
+        .await
+        .context("failed to read the config files")?;
+
+    if diagnostics.is_empty() {
+        println!("No problems found.");
+        return Ok(());
+    }
+    for diagnostic in &diagnostics {
+        let label = match diagnostic.kind {
+            ConfigDiagnosticKind::UnknownKey => "unknown",
+            ConfigDiagnosticKind::TypeMismatch => "invalid",
+            ConfigDiagnosticKind::Deprecated => "deprecated",
+        };
+        println!("{label:<10}  {diagnostic}");
+    }
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.kind != ConfigDiagnosticKind::Deprecated)
+    {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn describe_layer(source: &ConfigLayerSource) -> String {
+    match source {
+        ConfigLayerSource::Mdm { domain, key } => format!("managed preferences {domain} {key}"),
+        ConfigLayerSource::System { file } => format!("system: {}", file.display()),
+        ConfigLayerSource::User { file } => format!("user: {}", file.display()),
+        ConfigLayerSource::Project { dot_codex_folder } => {
+            format!(
+                "project: {}",
+                dot_codex_folder.as_path().join("config.toml").display()
+            )
+        }
+        ConfigLayerSource::SessionFlags => "-c / --config flags".to_string(),
+        ConfigLayerSource::LegacyManagedConfigTomlFromFile { file } => {
+            format!("managed config: {}", file.display())
+        }
+        ConfigLayerSource::LegacyManagedConfigTomlFromMdm => {
+            "managed config (managed preferences)".to_string()
+        }
+    }
+}
@@ -10,12 +10,15 @@ use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::exec_env::create_env;
 use codex_core::landlock::spawn_command_under_linux_sandbox;
+use codex_core::sandbox_capabilities::detect_sandbox_capabilities;
+use codex_core::sandbox_capabilities::policy_uses_sandbox;
 #[cfg(target_os = "macos")]
 use codex_core::seatbelt::spawn_command_under_seatbelt;
 use codex_core::spawn::StdioPolicy;
 use codex_protocol::config_types::SandboxMode;
 
 use crate::LandlockCommand;
+use crate::SandboxStatusCommand;
 use crate::SeatbeltCommand;
 use crate::WindowsCommand;
 use crate::exit_status::handle_exit_status;
@@ -93,6 +96,66 @@ pub async fn run_command_under_windows(
     .await
 }
 
+/// Prints the sandbox backend commands would run under with the current
+/// config and what it enforces on this machine.
+pub async fn print_sandbox_status(
+    command: SandboxStatusCommand,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let SandboxStatusCommand {
+        json,
+        config_overrides,
+    } = command;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?,
+        ConfigOverrides {
+            codex_linux_sandbox_exe,
+            ..Default::default()
+        },
+    )
+    .await?;
+    let policy = config.sandbox_policy.get();
+    let capabilities =
+        detect_sandbox_capabilities(&config.codex_home, config.codex_linux_sandbox_exe.as_ref());
+
+    if json {
+        let report = serde_json::json!({
+            "sandbox_policy": policy,
+            "network_allowlist": config.network_allowlist,
+            "capabilities": capabilities,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let yes_no = |enforced: bool| if enforced { "yes" } else { "no" };
+    println!("sandbox policy:          {policy}");
+    if !policy_uses_sandbox(policy) {
+        println!("commands run without a sandbox under this policy");
+    }
+    println!("backend:                 {}", capabilities.backend);
+    println!(
+        "enforces writable roots: {}",
+        yes_no(capabilities.enforces_writable_roots)
+    );
+    println!(
+        "enforces network:        {}",
+        yes_no(capabilities.enforces_network)
+    );
+    if !config.network_allowlist.is_empty() {
+        println!(
+            "network allowlist:       {}",
+            config.network_allowlist.join(", ")
+        );
+    }
+    for warning in &capabilities.warnings {
+        println!("warning: {warning}");
+    }
+    Ok(())
+}
+
 enum SandboxType {
     #[cfg(target_os = "macos")]
     Seatbelt,
@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::find_codex_home;
+use codex_core::doctor::DoctorOptions;
+use codex_core::doctor::DoctorStatus;
+use codex_core::doctor::run_doctor;
+
+/// Checks the login, model provider, sandbox, MCP servers, config,
+/// subagent definitions, and session storage, and says how to fix what is
+/// broken. Exits with status 1 when a check fails.
+#[derive(Debug, clap::Parser)]
+pub struct DoctorCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Do not contact the model provider.
+    #[arg(long, default_value_t = false)]
+    pub offline: bool,
+
+    /// Do not start the configured MCP servers; report them from config only.
+    #[arg(long = "skip-mcp", default_value_t = false)]
+    pub skip_mcp: bool,
+
+    /// Print the report as JSON, e.g. to attach to a bug report.
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
+
+impl DoctorCli {
+    pub async fn run(self, codex_linux_sandbox_exe: Option<PathBuf>) -> Result<()> {
+        let overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let codex_home = find_codex_home().context("failed to locate CODEX_HOME")?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            overrides,
+            ConfigOverrides {
+                codex_linux_sandbox_exe,
+                ..Default::default()
+            },
+        )
+        .await;
+        let report = run_doctor(
+            &codex_home,
+            config,
+            DoctorOptions {
+                network: !self.offline,
+                mcp: !self.skip_mcp,
+            },
+        )
+        .await;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!(
+                "codex {} ({} {}), CODEX_HOME {}",
+                report.version,
+                report.os,
+                report.arch,
+                report.codex_home.display()
+            );
+            let id_width = report
+                .checks
+                .iter()
+                .map(|check| check.id.len())
+                .max()
+                .unwrap_or(0);
+            for check in &report.checks {
+                let status = match check.status {
+                    DoctorStatus::Ok => "ok",
+                    DoctorStatus::Warning => "warning",
+                    DoctorStatus::Error => "error",
+                    DoctorStatus::Skipped => "skipped",
+                };
+                println!("{status:<7}  {:<id_width$}  {}", check.id, check.detail);
+                if let Some(fix) = &check.fix {
+                    println!("{:<7}  {:<id_width$}  fix: {fix}", "", "");
+                }
+            }
+        }
+        if report.has_errors() {
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::RolloutRecorder;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::find_archived_thread_path_by_id_str;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::session_export::ExportFormat;
+use codex_core::session_export::export_transcript;
+
+/// Renders the full transcript of a thread (messages, commands, diffs,
+/// subagent messages) as Markdown, HTML, or JSON, with secrets redacted.
+#[derive(Debug, clap::Parser)]
+pub struct ExportCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Thread id (as shown by `codex resume`), archived or not, or path to a rollout file.
+    pub thread: String,
+
+    /// Output format: `md`, `html`, or `json`.
+    #[arg(long, short = 'f', default_value = "md", value_name = "FORMAT")]
+    pub format: ExportFormat,
+
+    /// Output file; the transcript is written to stdout when omitted.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+impl ExportCli {
+    pub async fn run(self) -> Result<()> {
+        let ExportCli {
+            config_overrides,
+            thread,
+            format,
+            output,
+        } = self;
+
+        let path = resolve_thread_path(&config_overrides, &thread).await?;
+        let history = RolloutRecorder::get_rollout_history(&path)
+            .await
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let exported = export_transcript(&history.get_rollout_items(), format)?;
+
+        let Some(output) = output else {
+            std::io::stdout().write_all(exported.contents.as_bytes())?;
+            return Ok(());
+        };
+        std::fs::write(&output, &exported.contents)
+            .with_context(|| format!("failed to write {}", output.display()))?;
+        println!(
+            "Wrote {} turn(s) as {format} to {} ({} secret(s) redacted).",
+            exported.total_turns,
+            output.display(),
+            exported.redactions
+        );
+        Ok(())
+    }
+}
+
+/// `thread` as a rollout path: either a path to a rollout file or the id of
+/// a thread, archived or not.
+pub(crate) async fn resolve_thread_path(
+    config_overrides: &CliConfigOverrides,
+    thread: &str,
+) -> Result<PathBuf> {
+    let path = PathBuf::from(thread);
+    if path.is_file() {
+        return Ok(path);
+    }
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides::default(),
+    )
+    .await
+    .context("failed to load configuration")?;
+    let found = match find_thread_path_by_id_str(&config.codex_home, thread).await? {
+        Some(path) => Some(path),
+        None => find_archived_thread_path_by_id_str(&config.codex_home, thread).await?,
+    };
+    match found {
+        Some(path) => Ok(path),
+        None => bail!("No thread found with id {thread}."),
+    }
+}
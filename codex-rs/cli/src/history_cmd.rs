@@ -0,0 +1,346 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::NaiveDate;
+use codex_common::CliConfigOverrides;
+use codex_core::INTERACTIVE_SESSION_SOURCES;
+use codex_core::RolloutRecorder;
+use codex_core::ThreadSortKey;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::history_import::HistoryImportError;
+use codex_core::history_import::ImportedConversation;
+use codex_core::history_import::SessionConverter;
+use codex_core::history_import::converter;
+use codex_core::history_import::detect_converter;
+use codex_core::history_import::parse_export;
+use codex_core::history_import::write_imported_rollout;
+use codex_core::session_index::SessionIndex;
+use codex_core::thread_labels::ThreadLabels;
+use codex_core::thread_labels::read_thread_labels;
+use codex_core::thread_search::SearchFilters;
+use codex_core::thread_search::SearchQuery;
+use codex_core::thread_search::ThreadHit;
+
+/// Threads listed per page while searching.
+const SEARCH_PAGE_SIZE: usize = 100;
+
+/// Subcommands:
+/// - `import` — convert conversations exported from other tools into sessions
+/// - `search` — find threads across all local sessions
+#[derive(Debug, clap::Parser)]
+pub struct HistoryCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: HistorySubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum HistorySubcommand {
+    /// Import session logs of other agent tools as resumable sessions.
+    Import(ImportArgs),
+
+    /// Find threads whose messages, commands, or patched files mention every
+    /// word of a query, newest first.
+    Search(SearchArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ImportArgs {
+    /// Session log or export to import, or a directory of them: a Claude Code
+    /// `<session>.jsonl`, a ChatGPT `conversations.json`, or an aider
+    /// `.aider.chat.history.md`.
+    pub path: PathBuf,
+
+    /// Tool that wrote the log: `claude-code`, `chatgpt`, or `aider`.
+    /// Detected from the contents when omitted.
+    #[arg(long, alias = "format", value_name = "TOOL")]
+    pub from: Option<String>,
+
+    /// Working directory to record for conversations that do not carry one
+    /// (defaults to the current directory).
+    #[arg(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SearchArgs {
+    /// Words that must all appear in a thread (case-insensitive). Leave it
+    /// empty to list every thread that passes the filters.
+    #[arg(default_value = "")]
+    pub query: String,
+
+    /// Only threads started on or after this day (YYYY-MM-DD).
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<NaiveDate>,
+
+    /// Only threads started on or before this day (YYYY-MM-DD).
+    #[arg(long, value_name = "DATE")]
+    pub until: Option<NaiveDate>,
+
+    /// Only threads whose working directory or repository URL contains this.
+    #[arg(long, value_name = "REPO")]
+    pub repo: Option<String>,
+
+    /// Only threads in which some turn used a model whose name contains this.
+    #[arg(long, value_name = "MODEL")]
+    pub model: Option<String>,
+
+    /// Only threads that patched a file whose path contains this.
+    #[arg(long, value_name = "PATH")]
+    pub file: Option<String>,
+
+    /// Stop after this many threads.
+    #[arg(long, short = 'n', default_value_t = 20)]
+    pub limit: usize,
+
+    /// Print only thread ids, one per line, e.g. for
+    /// `codex resume $(codex history search migration --ids -n 1)`.
+    #[arg(long, default_value_t = false)]
+    pub ids: bool,
+}
+
+/// Same as `history import`, as a top-level command.
+#[derive(Debug, clap::Parser)]
+pub struct ImportCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[clap(flatten)]
+    pub args: ImportArgs,
+}
+
+impl ImportCli {
+    pub async fn run(self) -> Result<()> {
+        run_import(&self.config_overrides, self.args).await
+    }
+}
+
+impl HistoryCli {
+    pub async fn run(self) -> Result<()> {
+        let HistoryCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        match subcommand {
+            HistorySubcommand::Import(args) => run_import(&config_overrides, args).await,
+            HistorySubcommand::Search(args) => run_search(&config_overrides, args).await,
+        }
+    }
+}
+
+async fn run_import(config_overrides: &CliConfigOverrides, args: ImportArgs) -> Result<()> {
+    let ImportArgs { path, from, cwd } = args;
+    let forced = from.as_deref().map(converter).transpose()?;
+
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides {
+            cwd,
+            ..Default::default()
+        },
+    )
+    .await
+    .context("failed to load configuration")?;
+
+    let conversations = if path.is_dir() {
+        read_directory(&path, forced)?
+    } else {
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let converter = match forced {
+            Some(converter) => converter,
+            None => detect_converter(&contents).ok_or(HistoryImportError::UnknownFormat)?,
+        };
+        parse_export(&contents, converter)?
+    };
+    if conversations.is_empty() {
+        bail!(
+            "No conversations with user or assistant messages found in {}.",
+            path.display()
+        );
+    }
+
+    for conversation in &conversations {
+        let (thread_id, rollout_path) = write_imported_rollout(&config, conversation)
+            .with_context(|| format!("failed to write session for {}", path.display()))?;
+        let title = conversation.title.as_deref().unwrap_or("(untitled)");
+        println!(
+            "Imported \"{title}\" ({} messages, {}) as {thread_id}: {}",
+            conversation.messages.len(),
+            conversation.source,
+            rollout_path.display()
+        );
+    }
+    println!("Resume with `codex resume <id>`.");
+
+    Ok(())
+}
+
+async fn run_search(config_overrides: &CliConfigOverrides, args: SearchArgs) -> Result<()> {
+    let SearchArgs {
+        query,
+        since,
+        until,
+        repo,
+        model,
+        file,
+        limit,
+        ids,
+    } = args;
+    let query = SearchQuery::new(&query);
+    let filters = SearchFilters {
+        since,
+        until,
+        repo,
+        model,
+        file,
+    };
+    if query.is_empty() && filters == SearchFilters::default() {
+        bail!("Give a query or at least one of --since, --until, --repo, --model, --file.");
+    }
+
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides::default(),
+    )
+    .await
+    .context("failed to load configuration")?;
+    let labels = read_thread_labels(&config.codex_home).await?;
+
+    let hits = match SessionIndex::open(&config.codex_home)? {
+        Some(mut index) => {
+            index.sync()?;
+            index.search(
+                &query,
+                &labels,
+                &filters,
+                INTERACTIVE_SESSION_SOURCES,
+                limit,
+            )?
+        }
+        None => scan_threads(&config, &query, &labels, &filters, limit).await?,
+    };
+
+    if ids {
+        for hit in &hits {
+            println!("{}", hit.thread_id);
+        }
+        return Ok(());
+    }
+    if hits.is_empty() {
+        println!("No matching threads.");
+        return Ok(());
+    }
+    for hit in &hits {
+        let started_at = DateTime::parse_from_rfc3339(&hit.started_at)
+            .map(|started_at| {
+                started_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| hit.started_at.clone());
+        let model = hit.model.as_deref().unwrap_or("-");
+        println!(
+            "{}  {started_at}  {model}  {}",
+            hit.thread_id,
+            hit.cwd.display()
+        );
+        if let Some(name) = &hit.name {
+            println!("    {name}");
+        }
+        if let Some(snippet) = &hit.snippet {
+            println!("    {snippet}");
+        }
+    }
+    println!("Resume with `codex resume <id>`.");
+    Ok(())
+}
+
+/// Searches the rollouts one by one, for homes without a session index.
+async fn scan_threads(
+    config: &Config,
+    query: &SearchQuery,
+    labels: &BTreeMap<String, ThreadLabels>,
+    filters: &SearchFilters,
+    limit: usize,
+) -> Result<Vec<ThreadHit>> {
+    let mut hits = Vec::new();
+    let mut cursor = None;
+    'pages: loop {
+        let page = RolloutRecorder::list_threads(
+            &config.codex_home,
+            SEARCH_PAGE_SIZE,
+            cursor.as_ref(),
+            ThreadSortKey::UpdatedAt,
+            INTERACTIVE_SESSION_SOURCES,
+            None,
+            &config.model_provider_id,
+        )
+        .await?;
+        for item in page.items {
+            if hits.len() >= limit {
+                break 'pages;
+            }
+            match query.search_thread(&item.path, labels, filters).await {
+                Ok(Some(hit)) => hits.push(hit),
+                Ok(None) => {}
+                Err(err) => eprintln!("Skipped {}: {err}", item.path.display()),
+            }
+        }
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(hits)
+}
+
+/// Parses every file directly under `dir` that the converter recognizes;
+/// files it does not recognize are skipped.
+fn read_directory(
+    dir: &Path,
+    forced: Option<&'static dyn SessionConverter>,
+) -> Result<Vec<ImportedConversation>> {
+    let mut files = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut conversations = Vec::new();
+    for file in files {
+        let Ok(contents) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let converter = match forced {
+            Some(converter) if converter.detect(&contents) => converter,
+            Some(_) => continue,
+            None => match detect_converter(&contents) {
+                Some(converter) => converter,
+                None => continue,
+            },
+        };
+        let parsed = parse_export(&contents, converter)
+            .with_context(|| format!("failed to import {}", file.display()))?;
+        conversations.extend(parsed);
+    }
+    Ok(conversations)
+}
@@ -0,0 +1,233 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::pre_commit::ReviewVerdict;
+use codex_core::pre_commit::install_hook;
+use codex_core::pre_commit::review_output_schema;
+use codex_core::pre_commit::review_prompt;
+use codex_core::pre_commit::scan_for_secrets;
+use codex_core::pre_commit::staged_diff;
+use codex_core::pre_commit::uninstall_hook;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Subcommands:
+/// - `install`   — add a pre-commit hook that runs the `[pre_commit]` checks
+/// - `uninstall` — remove that hook
+/// - `run`       — run the checks for a hook (called by the hook itself)
+#[derive(Debug, clap::Parser)]
+pub struct HooksCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: HooksSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum HooksSubcommand {
+    /// Install a pre-commit hook in the current repository.
+    Install(InstallArgs),
+
+    /// Remove the pre-commit hook installed by `codex hooks install`.
+    Uninstall,
+
+    /// Run the checks for a git hook against the staged changes.
+    Run(RunArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct InstallArgs {
+    /// Replace an existing pre-commit hook (it is kept as `pre-commit.backup`).
+    #[arg(long, default_value_t = false)]
+    pub force: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RunArgs {
+    /// Hook to run.
+    #[arg(value_enum)]
+    pub hook: HookKind,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum HookKind {
+    PreCommit,
+}
+
+impl HooksCli {
+    pub async fn run(self) -> Result<()> {
+        let cwd = std::env::current_dir().context("failed to read the current directory")?;
+        match self.subcommand {
+            HooksSubcommand::Install(InstallArgs { force }) => {
+                let exe = std::env::current_exe()
+                    .ok()
+                    .and_then(|exe| exe.to_str().map(str::to_string))
+                    .unwrap_or_else(|| "codex".to_string());
+                let installed = install_hook(&cwd, &exe, force)?;
+                if let Some(backup) = installed.backup {
+                    println!("Moved the previous hook to {}.", backup.display());
+                }
+                println!(
+                    "Installed {}. Configure the checks under [pre_commit] in config.toml.",
+                    installed.path.display()
+                );
+            }
+            HooksSubcommand::Uninstall => match uninstall_hook(&cwd)? {
+                Some(path) => println!("Removed {}.", path.display()),
+                None => println!("No pre-commit hook installed by codex."),
+            },
+            HooksSubcommand::Run(RunArgs {
+                hook: HookKind::PreCommit,
+            }) => {
+                let config = load_config(&self.config_overrides).await?;
+                if !run_pre_commit(&config, &cwd, &self.config_overrides).await? {
+                    eprintln!("Commit blocked by codex. Use `git commit --no-verify` to skip.");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) async fn load_config(config_overrides: &CliConfigOverrides) -> Result<Config> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    Config::load_with_cli_overrides_and_harness_overrides(overrides, ConfigOverrides::default())
+        .await
+        .context("failed to load configuration")
+}
+
+/// Runs the enabled checks; `false` means the commit should be blocked.
+async fn run_pre_commit(
+    config: &Config,
+    cwd: &Path,
+    config_overrides: &CliConfigOverrides,
+) -> Result<bool> {
+    let settings = &config.pre_commit;
+    let diff = staged_diff(cwd)?;
+    if diff.trim().is_empty() {
+        return Ok(true);
+    }
+
+    if settings.secrets_scan {
+        let findings = scan_for_secrets(&diff);
+        if !findings.is_empty() {
+            eprintln!("codex: staged changes look like they contain secrets:");
+            for finding in findings {
+                eprintln!("  {}:{}", finding.path, finding.line);
+            }
+            return Ok(false);
+        }
+    }
+
+    if settings.review {
+        match review_staged_diff(config, cwd, &diff, config_overrides).await {
+            Ok(verdict) => {
+                for finding in &verdict.findings {
+                    let location = match finding.line {
+                        Some(line) => format!("{}:{line}", finding.path),
+                        None => finding.path.clone(),
+                    };
+                    eprintln!("codex review: {location}: {}", finding.message);
+                }
+                if verdict.block {
+                    return Ok(false);
+                }
+            }
+            // A review that cannot finish must not get in the way of committing.
+            Err(err) => eprintln!("codex review skipped: {err:#}"),
+        }
+    }
+    Ok(true)
+}
+
+/// Reviews `diff` with a read-only `codex exec` run limited to
+/// `[pre_commit].timeout_seconds`.
+pub(crate) async fn review_staged_diff(
+    config: &Config,
+    cwd: &Path,
+    diff: &str,
+    config_overrides: &CliConfigOverrides,
+) -> Result<ReviewVerdict> {
+    let settings = &config.pre_commit;
+    let verdict = run_structured_exec(
+        cwd,
+        &review_prompt(diff, settings.review_instructions.as_deref()),
+        &review_output_schema(),
+        settings.review_model.as_deref(),
+        settings.timeout,
+        config_overrides,
+    )
+    .await?;
+    serde_json::from_str::<ReviewVerdict>(&verdict).context("unexpected review verdict")
+}
+
+/// Runs `prompt` through a read-only `codex exec` run whose final message is
+/// shaped by `schema`, and returns that message.
+pub(crate) async fn run_structured_exec(
+    cwd: &Path,
+    prompt: &str,
+    schema: &serde_json::Value,
+    model: Option<&str>,
+    timeout: Duration,
+    config_overrides: &CliConfigOverrides,
+) -> Result<String> {
+    let scratch =
+        std::env::temp_dir().join(format!("codex-structured-exec-{}", std::process::id()));
+    std::fs::create_dir_all(&scratch)?;
+    let schema_path = scratch.join("schema.json");
+    let output_path = scratch.join("output.json");
+    std::fs::write(&schema_path, schema.to_string())?;
+
+    let exe = std::env::current_exe().context("failed to locate the codex executable")?;
+    let mut command = Command::new(exe);
+    command
+        .arg("exec")
+        .args(["--sandbox", "read-only", "--color", "never"])
+        .arg("--output-schema")
+        .arg(&schema_path)
+        .arg("--output-last-message")
+        .arg(&output_path);
+    if let Some(model) = model {
+        command.args(["--model", model]);
+    }
+    for raw in &config_overrides.raw_overrides {
+        command.args(["--config", raw]);
+    }
+    command
+        .arg("-")
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let result = async {
+        let mut child = command.spawn().context("failed to start codex exec")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(prompt.as_bytes()).await?;
+        }
+        let output = tokio::time::timeout(timeout, child.wait_with_output())
+            .await
+            .map_err(|_| anyhow::anyhow!("timed out after {}s", timeout.as_secs()))??;
+        if !output.status.success() {
+            anyhow::bail!(
+                "codex exec failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        std::fs::read_to_string(&output_path).context("codex exec did not produce an answer")
+    }
+    .await;
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::semantic_index::build_index;
+use codex_core::semantic_index::index_root;
+use codex_core::semantic_index::index_status;
+
+/// Subcommands:
+/// - `build`  — index the project for the `semantic_search` tool
+/// - `status` — show how far the index is behind the files on disk
+#[derive(Debug, clap::Parser)]
+pub struct IndexCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: IndexSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum IndexSubcommand {
+    /// Create or update the index in `.codex/index/` at the project root.
+    Build(BuildArgs),
+
+    /// Show the size of the index and how many files changed since it was built.
+    Status(StatusArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct BuildArgs {
+    /// Re-embed every file instead of only new and changed ones.
+    #[arg(long, default_value_t = false)]
+    pub rebuild: bool,
+
+    /// Directory inside the project to index (defaults to the current directory).
+    #[arg(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct StatusArgs {
+    /// Output the status as JSON.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Directory inside the project to inspect (defaults to the current directory).
+    #[arg(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+}
+
+impl IndexCli {
+    pub async fn run(self) -> Result<()> {
+        let IndexCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        match subcommand {
+            IndexSubcommand::Build(args) => run_build(&config_overrides, args).await,
+            IndexSubcommand::Status(args) => run_status(&config_overrides, args).await,
+        }
+    }
+}
+
+/// Loads the configuration and returns the project root with it.
+async fn resolve_root(
+    config_overrides: &CliConfigOverrides,
+    cwd: Option<PathBuf>,
+) -> Result<(PathBuf, Config)> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides {
+            cwd,
+            ..Default::default()
+        },
+    )
+    .await
+    .context("failed to load configuration")?;
+    Ok((index_root(&config.cwd), config))
+}
+
+async fn run_build(config_overrides: &CliConfigOverrides, args: BuildArgs) -> Result<()> {
+    let BuildArgs { rebuild, cwd } = args;
+    let (root, config) = resolve_root(config_overrides, cwd).await?;
+
+    let build_root = root.clone();
+    let update = tokio::task::spawn_blocking(move || {
+        build_index(&build_root, rebuild, &config.semantic_index)
+    })
+    .await?
+    .with_context(|| format!("failed to index {}", root.display()))?;
+
+    println!(
+        "Indexed {} ({} files, {} chunks): {} re-embedded, {} unchanged, {} removed.",
+        update.root.display(),
+        update.total_files,
+        update.total_chunks,
+        update.indexed_files,
+        update.unchanged_files,
+        update.removed_files
+    );
+    if update.truncated {
+        println!("The project has more files than the index limit; some files were skipped.");
+    }
+
+    Ok(())
+}
+
+async fn run_status(config_overrides: &CliConfigOverrides, args: StatusArgs) -> Result<()> {
+    let StatusArgs { json, cwd } = args;
+    let (root, config) = resolve_root(config_overrides, cwd).await?;
+
+    let status_root = root.clone();
+    let status =
+        tokio::task::spawn_blocking(move || index_status(&status_root, &config.semantic_index))
+            .await?
+            .with_context(|| format!("failed to read the index of {}", root.display()))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&status)?);
+        return Ok(());
+    }
+
+    if !status.exists {
+        println!(
+            "No index for {}. Run `codex index build` to create it.",
+            status.root.display()
+        );
+        return Ok(());
+    }
+    println!("Index:    {}", status.index_path.display());
+    println!("Files:    {} ({} chunks)", status.files, status.chunks);
+    if let Some(updated_at) = &status.updated_at {
+        println!("Updated:  {updated_at}");
+    }
+    println!(
+        "Pending:  {} changed, {} new, {} deleted",
+        status.changed_files, status.new_files, status.deleted_files
+    );
+
+    Ok(())
+}
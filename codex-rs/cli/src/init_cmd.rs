@@ -0,0 +1,376 @@
+use std::fs;
+use std::io::BufRead;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_app_server_protocol::ConfigBatchWriteParams;
+use codex_app_server_protocol::ConfigEdit;
+use codex_app_server_protocol::MergeStrategy;
+use codex_cli::login::login_with_chatgpt;
+use codex_common::CliConfigOverrides;
+use codex_common::approval_presets::builtin_approval_presets;
+use codex_core::AuthManager;
+use codex_core::CodexAuth;
+use codex_core::auth::login_with_api_key;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::service::ConfigService;
+use codex_core::config_loader::LoaderOverrides;
+use codex_core::git_info::get_git_repo_root;
+use codex_core::models_manager::manager::ModelsManager;
+use codex_core::models_manager::manager::RefreshStrategy;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ForcedLoginMethod;
+use codex_protocol::config_types::SandboxMode;
+use serde_json::json;
+
+const AGENTS_MD_TEMPLATE: &str = "\
+# AGENTS.md
+
+Instructions for coding agents working in this repository.
+
+## Project overview
+
+<!-- What this repository is and how it is laid out. -->
+
+## Build and test
+
+<!-- Commands to build, lint, and test, e.g. `make test`. -->
+
+## Conventions
+
+<!-- Code style, naming, commit messages, and what reviewers insist on. -->
+";
+
+/// Walks through sign-in, the default model and reasoning effort, and the
+/// approval and sandbox policy, and writes the answers to `config.toml`.
+///
+/// Inside a git repository it also offers to create `AGENTS.md` and the
+/// `.codex/agents/` (subagents) and `.codex/commands/` (slash commands)
+/// directories.
+#[derive(Debug, clap::Parser)]
+pub struct InitCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Take the default answer to every question without asking; sign-in is
+    /// skipped.
+    #[arg(long, short = 'y', default_value_t = false)]
+    pub yes: bool,
+
+    /// Set up `.codex/` in the current repository without asking.
+    #[arg(long, default_value_t = false)]
+    pub scaffold: bool,
+}
+
+impl InitCli {
+    pub async fn run(self) -> Result<()> {
+        if !self.yes && !std::io::stdin().is_terminal() {
+            anyhow::bail!("codex init asks questions; run it in a terminal or pass --yes");
+        }
+        let prompt = Prompt { yes: self.yes };
+        let overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            overrides.clone(),
+            ConfigOverrides::default(),
+        )
+        .await
+        .context("config.toml does not load; fix it first (`codex doctor` shows where)")?;
+        println!(
+            "This sets up {}.\n",
+            config.codex_home.join("config.toml").display()
+        );
+
+        println!("Step 1 of 4: sign in");
+        sign_in(&config, &prompt).await?;
+
+        println!("\nStep 2 of 4: default model");
+        let mut edits = choose_model(&config, &prompt).await?;
+
+        println!("\nStep 3 of 4: approvals and sandbox");
+        edits.extend(choose_approval_preset(&config, &prompt)?);
+
+        ConfigService::new(
+            config.codex_home.clone(),
+            overrides,
+            LoaderOverrides::default(),
+        )
+        .batch_write(ConfigBatchWriteParams {
+            edits,
+            file_path: None,
+            expected_version: None,
+        })
+        .await
+        .map_err(|err| anyhow::anyhow!("failed to write config.toml: {err}"))?;
+        println!("Saved {}.", config.codex_home.join("config.toml").display());
+
+        println!("\nStep 4 of 4: project files");
+        match get_git_repo_root(&config.cwd) {
+            Some(root) => {
+                let question = format!(
+                    "Create AGENTS.md, .codex/agents/, and .codex/commands/ in {}?",
+                    root.display()
+                );
+                if self.scaffold || prompt.confirm(&question, false)? {
+                    let created = scaffold_project(&root)?;
+                    if created.is_empty() {
+                        println!("Everything already exists.");
+                    }
+                    for path in created {
+                        println!("Created {}", path.display());
+                    }
+                }
+            }
+            None => println!("Not inside a git repository; skipped."),
+        }
+
+        println!("\nDone. Run `codex` to start, or `codex doctor` to check the setup.");
+        Ok(())
+    }
+}
+
+async fn sign_in(config: &Config, prompt: &Prompt) -> Result<()> {
+    if !config.model_provider.requires_openai_auth {
+        println!(
+            "Provider `{}` uses its own credentials; nothing to do.",
+            config.model_provider_id
+        );
+        return Ok(());
+    }
+    if let Some(auth) =
+        CodexAuth::from_auth_storage(&config.codex_home, config.cli_auth_credentials_store_mode)?
+    {
+        let account = auth
+            .get_account_email()
+            .map(|email| format!(" as {email}"))
+            .unwrap_or_default();
+        println!("Already signed in{account}.");
+        return Ok(());
+    }
+    if prompt.yes {
+        println!("Skipped; run `codex login` later.");
+        return Ok(());
+    }
+
+    let mut methods = Vec::new();
+    if config.forced_login_method != Some(ForcedLoginMethod::Api) {
+        methods.push("Sign in with ChatGPT");
+    }
+    if config.forced_login_method != Some(ForcedLoginMethod::Chatgpt) {
+        methods.push("Use an API key");
+    }
+    methods.push("Skip for now");
+    let labels: Vec<String> = methods.iter().map(ToString::to_string).collect();
+    match methods[prompt.choose("How do you want to sign in?", &labels, 0)?] {
+        "Sign in with ChatGPT" => {
+            login_with_chatgpt(
+                config.codex_home.clone(),
+                config.forced_chatgpt_workspace_id.clone(),
+                config.cli_auth_credentials_store_mode,
+            )
+            .await
+            .context("ChatGPT sign-in failed")?;
+            println!("Signed in.");
+        }
+        "Use an API key" => {
+            let key = prompt.input("API key")?;
+            if key.is_empty() {
+                println!("No key entered; run `codex login --with-api-key` later.");
+            } else {
+                login_with_api_key(
+                    &config.codex_home,
+                    &key,
+                    config.cli_auth_credentials_store_mode,
+                )?;
+                println!("Saved the API key.");
+            }
+        }
+        _ => println!("Skipped; run `codex login` later."),
+    }
+    Ok(())
+}
+
+/// Edits for `model` and `model_reasoning_effort`, or none when no model
+/// list is available offline.
+async fn choose_model(config: &Config, prompt: &Prompt) -> Result<Vec<ConfigEdit>> {
+    let auth_manager = AuthManager::shared(
+        config.codex_home.clone(),
+        false,
+        config.cli_auth_credentials_store_mode,
+    );
+    let presets: Vec<_> = ModelsManager::new(config.codex_home.clone(), auth_manager)
+        .list_models(config, RefreshStrategy::Offline)
+        .await
+        .into_iter()
+        .filter(|preset| preset.show_in_picker)
+        .collect();
+    if presets.is_empty() {
+        println!("No model list available; keeping the current model.");
+        return Ok(Vec::new());
+    }
+
+    let default = presets
+        .iter()
+        .position(|preset| Some(&preset.model) == config.model.as_ref())
+        .or_else(|| presets.iter().position(|preset| preset.is_default))
+        .unwrap_or(0);
+    let labels: Vec<String> = presets
+        .iter()
+        .map(|preset| format!("{} — {}", preset.display_name, preset.description))
+        .collect();
+    let preset =
+        &presets[prompt.choose("Which model should new sessions use?", &labels, default)?];
+
+    let efforts = &preset.supported_reasoning_efforts;
+    let effort = if efforts.is_empty() {
+        preset.default_reasoning_effort
+    } else {
+        let wanted = config
+            .model_reasoning_effort
+            .unwrap_or(preset.default_reasoning_effort);
+        let default = efforts
+            .iter()
+            .position(|option| option.effort == wanted)
+            .unwrap_or(0);
+        let labels: Vec<String> = efforts
+            .iter()
+            .map(|option| format!("{} — {}", option.effort, option.description))
+            .collect();
+        efforts[prompt.choose("Reasoning effort?", &labels, default)?].effort
+    };
+
+    Ok(vec![
+        replace("model", json!(preset.model)),
+        replace("model_reasoning_effort", json!(effort)),
+    ])
+}
+
+/// Edits for `approval_policy` and `sandbox_mode` from one of the built-in
+/// presets. The `auto` preset is suggested even when another one is in
+/// effect, since an unconfigured install falls back to read-only.
+fn choose_approval_preset(config: &Config, prompt: &Prompt) -> Result<Vec<ConfigEdit>> {
+    let presets = builtin_approval_presets();
+    let suggested = presets
+        .iter()
+        .position(|preset| preset.id == "auto")
+        .unwrap_or(0);
+    let labels: Vec<String> = presets
+        .iter()
+        .map(|preset| {
+            let current = &preset.approval == config.approval_policy.get()
+                && &preset.sandbox == config.sandbox_policy.get();
+            let current = if current { " (current)" } else { "" };
+            format!("{}{current} — {}", preset.label, preset.description)
+        })
+        .collect();
+    let mut preset =
+        &presets[prompt.choose("What may Codex do without asking?", &labels, suggested)?];
+    if preset.sandbox == SandboxPolicy::DangerFullAccess
+        && !prompt.confirm(
+            "Full Access turns the sandbox off. Use it only in a disposable environment. Continue?",
+            false,
+        )?
+    {
+        preset = &presets[suggested];
+    }
+
+    let sandbox_mode = match preset.sandbox {
+        SandboxPolicy::ReadOnly => SandboxMode::ReadOnly,
+        SandboxPolicy::DangerFullAccess => SandboxMode::DangerFullAccess,
+        _ => SandboxMode::WorkspaceWrite,
+    };
+    Ok(vec![
+        replace("approval_policy", json!(preset.approval)),
+        replace("sandbox_mode", json!(sandbox_mode)),
+    ])
+}
+
+fn replace(key_path: &str, value: serde_json::Value) -> ConfigEdit {
+    ConfigEdit {
+        key_path: key_path.to_string(),
+        value,
+        merge_strategy: MergeStrategy::Replace,
+    }
+}
+
+/// Creates `AGENTS.md` and the `.codex/agents/` and `.codex/commands/`
+/// directories under `root`, leaving existing ones alone, and returns what
+/// was created.
+fn scaffold_project(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut created = Vec::new();
+    let agents_md = root.join("AGENTS.md");
+    if !agents_md.exists() {
+        fs::write(&agents_md, AGENTS_MD_TEMPLATE)
+            .with_context(|| format!("failed to write {}", agents_md.display()))?;
+        created.push(agents_md);
+    }
+    for dir in ["agents", "commands"] {
+        let dir = root.join(".codex").join(dir);
+        if dir.exists() {
+            continue;
+        }
+        fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        // Keeps the empty directory in git; only `*.md` files are loaded.
+        fs::write(dir.join(".gitkeep"), "")?;
+        created.push(dir);
+    }
+    Ok(created)
+}
+
+/// Line-based questions on stdin. With `yes`, every question takes its
+/// default answer without being shown.
+struct Prompt {
+    yes: bool,
+}
+
+impl Prompt {
+    fn choose(&self, question: &str, options: &[String], default: usize) -> Result<usize> {
+        if self.yes {
+            return Ok(default);
+        }
+        println!("{question}");
+        for (n, option) in options.iter().enumerate() {
+            let marker = if n == default { "*" } else { " " };
+            println!(" {marker}{}. {option}", n + 1);
+        }
+        loop {
+            let answer = self.input(&format!("Choose 1-{} [{}]", options.len(), default + 1))?;
+            if answer.is_empty() {
+                return Ok(default);
+            }
+            match answer.parse::<usize>() {
+                Ok(n) if (1..=options.len()).contains(&n) => return Ok(n - 1),
+                _ => println!("Enter a number between 1 and {}.", options.len()),
+            }
+        }
+    }
+
+    fn confirm(&self, question: &str, default: bool) -> Result<bool> {
+        if self.yes {
+            return Ok(default);
+        }
+        let hint = if default { "Y/n" } else { "y/N" };
+        let answer = self.input(&format!("{question} [{hint}]"))?;
+        Ok(match answer.to_ascii_lowercase().as_str() {
+            "" => default,
+            answer => answer == "y" || answer == "yes",
+        })
+    }
+
+    fn input(&self, question: &str) -> Result<String> {
+        print!("{question}: ");
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        if std::io::stdin().lock().read_line(&mut answer)? == 0 {
+            anyhow::bail!("input ended before setup finished");
+        }
+        Ok(answer.trim().to_string())
+    }
+}
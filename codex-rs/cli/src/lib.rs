@@ -37,6 +37,16 @@ pub struct LandlockCommand {
     pub command: Vec<String>,
 }
 
+#[derive(Debug, Parser)]
+pub struct SandboxStatusCommand {
+    /// Print the report as JSON.
+    #[arg(long = "json", default_value_t = false)]
+    pub json: bool,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
+
 #[derive(Debug, Parser)]
 pub struct WindowsCommand {
     /// Convenience alias for low-friction sandboxed automatic execution (network-disabled sandbox that can write to cwd and TMPDIR)
@@ -3,8 +3,10 @@ use codex_common::CliConfigOverrides;
 use codex_core::CodexAuth;
 use codex_core::auth::AuthCredentialsStoreMode;
 use codex_core::auth::CLIENT_ID;
+use codex_core::auth::entra;
 use codex_core::auth::login_with_api_key;
 use codex_core::auth::logout;
+use codex_core::auth::migrate_auth_to_keyring;
 use codex_core::config::Config;
 use codex_login::ServerOptions;
 use codex_login::run_device_code_login;
@@ -19,6 +21,17 @@ const CHATGPT_LOGIN_DISABLED_MESSAGE: &str =
 const API_KEY_LOGIN_DISABLED_MESSAGE: &str =
     "API key login is disabled. Use ChatGPT login instead.";
 const LOGIN_SUCCESS_MESSAGE: &str = "Successfully logged in";
+const CHATGPT_AUTH_URL: &str = "https://auth.openai.com";
+
+/// ChatGPT login needs the OpenAI auth server, which offline mode rules out.
+fn exit_if_offline(config: &Config) {
+    if let Some(offline) = &config.offline
+        && let Err(err) = offline.check_url("ChatGPT login", CHATGPT_AUTH_URL)
+    {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
 
 fn print_login_server_start(actual_port: u16, auth_url: &str) {
     eprintln!(
@@ -51,6 +64,7 @@ pub async fn run_login_with_chatgpt(cli_config_overrides: CliConfigOverrides) ->
         eprintln!("{CHATGPT_LOGIN_DISABLED_MESSAGE}");
         std::process::exit(1);
     }
+    exit_if_offline(&config);
 
     let forced_chatgpt_workspace_id = config.forced_chatgpt_workspace_id.clone();
 
@@ -137,6 +151,7 @@ pub async fn run_login_with_device_code(
         eprintln!("{CHATGPT_LOGIN_DISABLED_MESSAGE}");
         std::process::exit(1);
     }
+    exit_if_offline(&config);
     let forced_chatgpt_workspace_id = config.forced_chatgpt_workspace_id.clone();
     let mut opts = ServerOptions::new(
         config.codex_home,
@@ -173,6 +188,7 @@ pub async fn run_login_with_device_code_fallback_to_browser(
         eprintln!("{CHATGPT_LOGIN_DISABLED_MESSAGE}");
         std::process::exit(1);
     }
+    exit_if_offline(&config);
 
     let forced_chatgpt_workspace_id = config.forced_chatgpt_workspace_id.clone();
     let mut opts = ServerOptions::new(
@@ -252,6 +268,62 @@ pub async fn run_login_status(cli_config_overrides: CliConfigOverrides) -> ! {
     }
 }
 
+pub async fn run_migrate_to_keyring(cli_config_overrides: CliConfigOverrides) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    match migrate_auth_to_keyring(&config.codex_home, config.cli_auth_credentials_store_mode) {
+        Ok(0) => {
+            eprintln!("No plaintext credentials to move");
+            std::process::exit(0);
+        }
+        Ok(moved) => {
+            eprintln!("Moved {moved} set(s) of credentials into the OS keyring");
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error moving credentials into the keyring: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_login_with_entra_id(
+    cli_config_overrides: CliConfigOverrides,
+    provider_id: Option<String>,
+) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+    let provider_id = provider_id.unwrap_or_else(|| config.model_provider_id.clone());
+    let Some(provider) = config.model_providers.get(&provider_id) else {
+        eprintln!("Unknown model provider `{provider_id}`");
+        std::process::exit(1);
+    };
+    let Some(entra_id) = &provider.entra_id else {
+        eprintln!("Model provider `{provider_id}` has no `entra_id` section");
+        std::process::exit(1);
+    };
+    if entra_id.client_secret_env.is_some() {
+        eprintln!(
+            "Model provider `{provider_id}` uses a client secret; no interactive sign-in is needed"
+        );
+        std::process::exit(0);
+    }
+
+    match entra::device_code_login(entra_id, &config.codex_home, |prompt| {
+        eprintln!("{}", prompt.message);
+    })
+    .await
+    {
+        Ok(()) => {
+            eprintln!("{LOGIN_SUCCESS_MESSAGE}");
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error logging in to Entra ID: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
 pub async fn run_logout(cli_config_overrides: CliConfigOverrides) -> ! {
     let config = load_config_or_exit(cli_config_overrides).await;
 
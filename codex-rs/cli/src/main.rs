@@ -7,6 +7,7 @@ use codex_arg0::arg0_dispatch_or_else;
 use codex_chatgpt::apply_command::ApplyCommand;
 use codex_chatgpt::apply_command::run_apply_command;
 use codex_cli::LandlockCommand;
+use codex_cli::SandboxStatusCommand;
 use codex_cli::SeatbeltCommand;
 use codex_cli::WindowsCommand;
 use codex_cli::login::read_api_key_from_stdin;
@@ -14,7 +15,9 @@ use codex_cli::login::run_login_status;
 use codex_cli::login::run_login_with_api_key;
 use codex_cli::login::run_login_with_chatgpt;
 use codex_cli::login::run_login_with_device_code;
+use codex_cli::login::run_login_with_entra_id;
 use codex_cli::login::run_logout;
+use codex_cli::login::run_migrate_to_keyring;
 use codex_cloud_tasks::Cli as CloudTasksCli;
 use codex_common::CliConfigOverrides;
 use codex_exec::Cli as ExecCli;
@@ -31,11 +34,42 @@ use std::io::IsTerminal;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod audit_cmd;
+mod capabilities_cmd;
+mod ci_cmd;
+mod commit_cmd;
+mod config_cmd;
+mod doctor_cmd;
+mod export_cmd;
+mod history_cmd;
+mod hooks_cmd;
+mod index_cmd;
+mod init_cmd;
 mod mcp_cmd;
+mod schedule_cmd;
+mod sessions_cmd;
+mod share_cmd;
+mod subagents_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::audit_cmd::AuditCli;
+use crate::capabilities_cmd::CapabilitiesCli;
+use crate::ci_cmd::CiCli;
+use crate::commit_cmd::CommitCli;
+use crate::config_cmd::ConfigCli;
+use crate::doctor_cmd::DoctorCli;
+use crate::export_cmd::ExportCli;
+use crate::history_cmd::HistoryCli;
+use crate::history_cmd::ImportCli;
+use crate::hooks_cmd::HooksCli;
+use crate::index_cmd::IndexCli;
+use crate::init_cmd::InitCli;
 use crate::mcp_cmd::McpCli;
+use crate::schedule_cmd::ScheduleCli;
+use crate::sessions_cmd::SessionsCli;
+use crate::share_cmd::ShareCli;
+use crate::subagents_cmd::SubAgentsCli;
 
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
@@ -116,6 +150,43 @@ enum Subcommand {
     /// Fork a previous interactive session (picker by default; use --last to fork the most recent).
     Fork(ForkCommand),
 
+    /// Import session logs of other agent tools (e.g. `--from claude-code`) as sessions.
+    Import(ImportCli),
+
+    /// Import conversations from other tools (Claude Code, ChatGPT, aider) as sessions.
+    History(HistoryCli),
+
+    /// Install a git pre-commit hook that runs Codex checks on staged changes.
+    Hooks(HooksCli),
+
+    /// Commit staged changes with a suggested message, review them, or split
+    /// the working tree into several commits.
+    Commit(CommitCli),
+
+    /// Build or inspect the local workspace index used by `semantic_search`.
+    Index(IndexCli),
+
+    /// Run prompts or subagents headlessly on a cron-like schedule.
+    Schedule(ScheduleCli),
+
+    /// Export turns of a session as a self-contained HTML page for review.
+    Share(ShareCli),
+
+    /// Export the full transcript of a thread as Markdown, HTML, or JSON.
+    Export(ExportCli),
+
+    /// Move threads between machines: export one to a file, or import an exported one.
+    Sessions(SessionsCli),
+
+    /// Watch GitHub checks for the pushed branch and run fix turns for failures.
+    Ci(CiCli),
+
+    /// Inspect subagent definitions (e.g. `run <name> --dry-run`).
+    Subagents(SubAgentsCli),
+
+    /// Show the audit log of agent actions (`[audit]`).
+    Audit(AuditCli),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -130,6 +201,21 @@ enum Subcommand {
 
     /// Inspect feature flags.
     Features(FeaturesCli),
+
+    /// Report which optional subsystems are active and why the others are not.
+    Capabilities(CapabilitiesCli),
+
+    /// Diagnose the login, provider, sandbox, MCP servers, and config.
+    Doctor(DoctorCli),
+
+    /// Set up config.toml and the current repository step by step.
+    Init(InitCli),
+
+    /// Show the config layers that apply here, or check them for unknown keys and type errors.
+    Config(ConfigCli),
+
+    /// Export the protocol schema for client developers.
+    Schema(SchemaCli),
 }
 
 #[derive(Debug, Parser)]
@@ -154,6 +240,11 @@ struct ResumeCommand {
     #[arg(long = "all", default_value_t = false)]
     all: bool,
 
+    /// Pick from archived sessions. The chosen session is restored from the
+    /// archive before it resumes.
+    #[arg(long = "archived", default_value_t = false, conflicts_with = "last")]
+    archived: bool,
+
     #[clap(flatten)]
     config_overrides: TuiCli,
 }
@@ -195,6 +286,9 @@ enum SandboxCommand {
 
     /// Run a command under Windows restricted token (Windows only).
     Windows(WindowsCommand),
+
+    /// Show which sandbox backend commands run under and what it enforces.
+    Status(SandboxStatusCommand),
 }
 
 #[derive(Debug, Parser)]
@@ -249,6 +343,17 @@ struct LoginCommand {
 enum LoginSubcommand {
     /// Show login status.
     Status,
+
+    /// Move credentials from plaintext auth.json files into the OS keyring.
+    MigrateToKeyring,
+
+    /// Sign in to Microsoft Entra ID for an Azure OpenAI provider.
+    Azure {
+        /// Provider key under `model_providers`; defaults to the active
+        /// provider.
+        #[arg(long)]
+        provider: Option<String>,
+    },
 }
 
 #[derive(Debug, Parser)]
@@ -280,6 +385,21 @@ struct AppServerCommand {
     /// See https://developers.openai.com/codex/config-advanced/#metrics for more details.
     #[arg(long = "analytics-default-enabled")]
     analytics_default_enabled: bool,
+
+    /// Where clients connect: `stdio://` (default), `ws://HOST:PORT`, or
+    /// `http://HOST:PORT`.
+    ///
+    /// Over WebSocket each connection gets its own session; the HTTP mode
+    /// exposes a REST + server-sent events facade over one shared session.
+    /// Set `CODEX_APP_SERVER_TOKEN` to require clients to present that
+    /// token; it is mandatory when listening on a non-loopback address.
+    #[arg(
+        long = "listen",
+        value_name = "URL",
+        default_value = "stdio://",
+        value_parser = codex_app_server::AppServerTransport::from_listen_url
+    )]
+    listen: codex_app_server::AppServerTransport,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -451,6 +571,49 @@ enum FeaturesSubcommand {
     List,
 }
 
+#[derive(Debug, Parser)]
+struct SchemaCli {
+    #[command(subcommand)]
+    sub: SchemaSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum SchemaSubcommand {
+    /// Write the versioned protocol schema (ops, events, app-server v1/v2
+    /// requests and notifications) generated from the Rust types.
+    Export(SchemaExportCommand),
+}
+
+#[derive(Debug, Args)]
+struct SchemaExportCommand {
+    /// Output format.
+    #[arg(long = "format", value_enum, default_value_t = SchemaExportFormat::JsonSchema)]
+    format: SchemaExportFormat,
+
+    /// Output directory where the schema files will be written.
+    #[arg(short = 'o', long = "out", value_name = "DIR")]
+    out_dir: PathBuf,
+
+    /// Optional path to the Prettier executable to format generated TypeScript files.
+    #[arg(short = 'p', long = "prettier", value_name = "PRETTIER_BIN")]
+    prettier: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum SchemaExportFormat {
+    JsonSchema,
+    Ts,
+}
+
+impl From<SchemaExportFormat> for codex_app_server_protocol::SchemaFormat {
+    fn from(format: SchemaExportFormat) -> Self {
+        match format {
+            SchemaExportFormat::JsonSchema => Self::JsonSchema,
+            SchemaExportFormat::Ts => Self::Ts,
+        }
+    }
+}
+
 fn stage_str(stage: codex_core::features::Stage) -> &'static str {
     use codex_core::features::Stage;
     match stage {
@@ -514,13 +677,95 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
             mcp_cli.run().await?;
         }
+        Some(Subcommand::Import(mut import_cli)) => {
+            prepend_config_flags(
+                &mut import_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            import_cli.run().await?;
+        }
+        Some(Subcommand::History(mut history_cli)) => {
+            prepend_config_flags(
+                &mut history_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            history_cli.run().await?;
+        }
+        Some(Subcommand::Hooks(mut hooks_cli)) => {
+            prepend_config_flags(
+                &mut hooks_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            hooks_cli.run().await?;
+        }
+        Some(Subcommand::Commit(mut commit_cli)) => {
+            prepend_config_flags(
+                &mut commit_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            commit_cli.run().await?;
+        }
+        Some(Subcommand::Index(mut index_cli)) => {
+            prepend_config_flags(
+                &mut index_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            index_cli.run().await?;
+        }
+        Some(Subcommand::Schedule(mut schedule_cli)) => {
+            prepend_config_flags(
+                &mut schedule_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            schedule_cli.run().await?;
+        }
+        Some(Subcommand::Share(mut share_cli)) => {
+            prepend_config_flags(
+                &mut share_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            share_cli.run().await?;
+        }
+        Some(Subcommand::Export(mut export_cli)) => {
+            prepend_config_flags(
+                &mut export_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            export_cli.run().await?;
+        }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            sessions_cli.run().await?;
+        }
+        Some(Subcommand::Ci(mut ci_cli)) => {
+            prepend_config_flags(&mut ci_cli.config_overrides, root_config_overrides.clone());
+            ci_cli.run().await?;
+        }
+        Some(Subcommand::Subagents(mut subagents_cli)) => {
+            prepend_config_flags(
+                &mut subagents_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            subagents_cli.run().await?;
+        }
+        Some(Subcommand::Audit(mut audit_cli)) => {
+            prepend_config_flags(
+                &mut audit_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            audit_cli.run().await?;
+        }
         Some(Subcommand::AppServer(app_server_cli)) => match app_server_cli.subcommand {
             None => {
-                codex_app_server::run_main(
+                codex_app_server::run_main_with_transport(
                     codex_linux_sandbox_exe,
                     root_config_overrides,
                     codex_core::config_loader::LoaderOverrides::default(),
                     app_server_cli.analytics_default_enabled,
+                    app_server_cli.listen,
                 )
                 .await?;
             }
@@ -538,6 +783,7 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             session_id,
             last,
             all,
+            archived,
             config_overrides,
         })) => {
             interactive = finalize_resume_interactive(
@@ -546,6 +792,7 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 session_id,
                 last,
                 all,
+                archived,
                 config_overrides,
             );
             let exit_info = run_interactive_tui(interactive, codex_linux_sandbox_exe).await?;
@@ -577,6 +824,12 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 Some(LoginSubcommand::Status) => {
                     run_login_status(login_cli.config_overrides).await;
                 }
+                Some(LoginSubcommand::MigrateToKeyring) => {
+                    run_migrate_to_keyring(login_cli.config_overrides).await;
+                }
+                Some(LoginSubcommand::Azure { provider }) => {
+                    run_login_with_entra_id(login_cli.config_overrides, provider).await;
+                }
                 None => {
                     if login_cli.use_device_code {
                         run_login_with_device_code(
@@ -650,6 +903,14 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 )
                 .await?;
             }
+            SandboxCommand::Status(mut status_cli) => {
+                prepend_config_flags(
+                    &mut status_cli.config_overrides,
+                    root_config_overrides.clone(),
+                );
+                codex_cli::debug_sandbox::print_sandbox_status(status_cli, codex_linux_sandbox_exe)
+                    .await?;
+            }
         },
         Some(Subcommand::Execpolicy(ExecpolicyCommand { sub })) => match sub {
             ExecpolicySubcommand::Check(cmd) => run_execpolicycheck(cmd)?,
@@ -670,6 +931,34 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             tokio::task::spawn_blocking(move || codex_stdio_to_uds::run(socket_path.as_path()))
                 .await??;
         }
+        Some(Subcommand::Capabilities(mut capabilities_cli)) => {
+            prepend_config_flags(
+                &mut capabilities_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            capabilities_cli.run(codex_linux_sandbox_exe).await?;
+        }
+        Some(Subcommand::Doctor(mut doctor_cli)) => {
+            prepend_config_flags(
+                &mut doctor_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            doctor_cli.run(codex_linux_sandbox_exe).await?;
+        }
+        Some(Subcommand::Init(mut init_cli)) => {
+            prepend_config_flags(
+                &mut init_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            init_cli.run().await?;
+        }
+        Some(Subcommand::Config(mut config_cli)) => {
+            prepend_config_flags(
+                &mut config_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            config_cli.run(interactive.config_profile.clone()).await?;
+        }
         Some(Subcommand::Features(FeaturesCli { sub })) => match sub {
             FeaturesSubcommand::List => {
                 // Respect root-level `-c` overrides plus top-level flags like `--profile`.
@@ -704,6 +993,20 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 }
             }
         },
+        Some(Subcommand::Schema(SchemaCli { sub })) => match sub {
+            SchemaSubcommand::Export(export_cli) => {
+                codex_app_server_protocol::export_schema(
+                    &export_cli.out_dir,
+                    export_cli.format.into(),
+                    export_cli.prettier.as_deref(),
+                )?;
+                println!(
+                    "Exported protocol schema v{} to {}",
+                    codex_app_server_protocol::PROTOCOL_VERSION,
+                    export_cli.out_dir.display()
+                );
+            }
+        },
     }
 
     Ok(())
@@ -778,6 +1081,7 @@ fn finalize_resume_interactive(
     session_id: Option<String>,
     last: bool,
     show_all: bool,
+    archived: bool,
     resume_cli: TuiCli,
 ) -> TuiCli {
     // Start with the parsed interactive CLI so resume shares the same
@@ -787,6 +1091,7 @@ fn finalize_resume_interactive(
     interactive.resume_last = last;
     interactive.resume_session_id = resume_session_id;
     interactive.resume_show_all = show_all;
+    interactive.resume_archived = archived;
 
     // Merge resume-scoped flags and overrides with highest precedence.
     merge_interactive_cli_flags(&mut interactive, resume_cli);
@@ -898,6 +1203,7 @@ mod tests {
             session_id,
             last,
             all,
+            archived,
             config_overrides: resume_cli,
         }) = subcommand.expect("resume present")
         else {
@@ -910,6 +1216,7 @@ mod tests {
             session_id,
             last,
             all,
+            archived,
             resume_cli,
         )
     }
@@ -1037,6 +1344,16 @@ mod tests {
         assert!(interactive.resume_show_all);
     }
 
+    #[test]
+    fn resume_archived_flag_sets_resume_archived() {
+        let interactive = finalize_resume_from_args(["codex", "resume", "--archived"].as_ref());
+        assert!(interactive.resume_picker);
+        assert!(interactive.resume_archived);
+
+        let interactive = finalize_resume_from_args(["codex", "resume"].as_ref());
+        assert!(!interactive.resume_archived);
+    }
+
     #[test]
     fn resume_merges_option_flags_and_full_auto() {
         let interactive = finalize_resume_from_args(
@@ -1157,6 +1474,35 @@ mod tests {
         assert!(app_server.analytics_default_enabled);
     }
 
+    #[test]
+    fn app_server_listen_defaults_to_stdio() {
+        let app_server = app_server_from_args(["codex", "app-server"].as_ref());
+        assert_eq!(
+            app_server.listen,
+            codex_app_server::AppServerTransport::Stdio
+        );
+
+        let app_server = app_server_from_args(
+            ["codex", "app-server", "--listen", "ws://127.0.0.1:4500"].as_ref(),
+        );
+        assert_eq!(
+            app_server.listen,
+            codex_app_server::AppServerTransport::WebSocket {
+                address: "127.0.0.1:4500".to_string(),
+            }
+        );
+
+        let app_server = app_server_from_args(
+            ["codex", "app-server", "--listen", "http://127.0.0.1:8080"].as_ref(),
+        );
+        assert_eq!(
+            app_server.listen,
+            codex_app_server::AppServerTransport::Http {
+                address: "127.0.0.1:8080".to_string(),
+            }
+        );
+    }
+
     #[test]
     fn feature_toggles_known_features_generate_overrides() {
         let toggles = FeatureToggles {
@@ -247,6 +247,8 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         tool_timeout_sec: None,
         enabled_tools: None,
         disabled_tools: None,
+        default_tool_approval: None,
+        tool_approvals: None,
     };
 
     servers.insert(name.clone(), new_entry);
@@ -0,0 +1,268 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Utc;
+use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::ThreadManager;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::find_codex_home;
+use codex_core::schedules::Schedule;
+use codex_core::schedules::add_schedule;
+use codex_core::schedules::load_schedule;
+use codex_core::schedules::load_schedules;
+use codex_core::schedules::remove_schedule;
+use codex_core::schedules::run_schedule;
+use codex_core::schedules::save_schedule;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::SessionSource;
+
+/// How often `codex schedule run` re-reads schedules and checks for due runs.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Subcommands:
+/// - `add`  — register a prompt or subagent to run on a cron schedule
+/// - `list` — show registered schedules and their next run
+/// - `rm`   — delete a schedule
+/// - `run`  — run due schedules (foreground), or one schedule immediately
+#[derive(Debug, clap::Parser)]
+pub struct ScheduleCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ScheduleSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ScheduleSubcommand {
+    /// Register a schedule under `$CODEX_HOME/schedules`.
+    Add(AddArgs),
+
+    /// List schedules with their next and last run.
+    List,
+
+    /// Delete a schedule.
+    #[clap(visible_alias = "remove")]
+    Rm(RmArgs),
+
+    /// Run schedules headlessly as they come due. Keeps running until
+    /// interrupted unless `--once` or an ID is given.
+    Run(RunArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct AddArgs {
+    /// Identifier for the schedule (letters, digits, `-`, `_`).
+    pub id: String,
+
+    /// Cron expression in local time, e.g. `"0 3 * * *"` or `@nightly`.
+    #[arg(long)]
+    pub cron: String,
+
+    /// Run this subagent definition with the prompt instead of a plain turn.
+    #[arg(long = "agent", value_name = "NAME")]
+    pub subagent: Option<String>,
+
+    /// Working directory for the runs (defaults to the current directory).
+    #[arg(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Prompt to run.
+    pub prompt: String,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RmArgs {
+    pub id: String,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RunArgs {
+    /// Run this schedule now, regardless of its cron expression, and exit.
+    pub id: Option<String>,
+
+    /// Run whatever is due right now and exit (for driving from system cron).
+    #[arg(long, conflicts_with = "id")]
+    pub once: bool,
+}
+
+impl ScheduleCli {
+    pub async fn run(self) -> Result<()> {
+        let ScheduleCli {
+            config_overrides,
+            subcommand,
+        } = self;
+        let codex_home = find_codex_home().context("failed to locate CODEX_HOME")?;
+
+        match subcommand {
+            ScheduleSubcommand::Add(args) => run_add(&codex_home, args),
+            ScheduleSubcommand::List => run_list(&codex_home),
+            ScheduleSubcommand::Rm(RmArgs { id }) => {
+                remove_schedule(&codex_home, &id)?;
+                println!("Removed schedule `{id}`.");
+                Ok(())
+            }
+            ScheduleSubcommand::Run(args) => run_run(&config_overrides, &codex_home, args).await,
+        }
+    }
+}
+
+fn run_add(codex_home: &Path, args: AddArgs) -> Result<()> {
+    let AddArgs {
+        id,
+        cron,
+        subagent,
+        cwd,
+        prompt,
+    } = args;
+    let cwd = match cwd {
+        Some(cwd) => cwd,
+        None => std::env::current_dir()?,
+    };
+    let cwd = cwd
+        .canonicalize()
+        .with_context(|| format!("failed to resolve {}", cwd.display()))?;
+    let schedule = Schedule {
+        id,
+        cron,
+        prompt,
+        subagent,
+        cwd,
+        created_at: Utc::now(),
+        last_run_at: None,
+        last_thread_id: None,
+    };
+    let path = add_schedule(codex_home, &schedule)?;
+    println!("Added schedule `{}`: {}", schedule.id, path.display());
+    if let Some(next) = schedule.next_run_at() {
+        println!("Next run: {}", format_time(next));
+    }
+    println!("Runs happen while `codex schedule run` is running.");
+    Ok(())
+}
+
+fn run_list(codex_home: &Path) -> Result<()> {
+    let schedules = load_schedules(codex_home)?;
+    if schedules.is_empty() {
+        println!("No schedules. Add one with `codex schedule add`.");
+        return Ok(());
+    }
+    for schedule in schedules {
+        let target = match &schedule.subagent {
+            Some(name) => format!("@{name} {}", schedule.prompt),
+            None => schedule.prompt.clone(),
+        };
+        println!("{}  [{}]  {target}", schedule.id, schedule.cron);
+        println!("  cwd:      {}", schedule.cwd.display());
+        let next = schedule
+            .next_run_at()
+            .map(format_time)
+            .unwrap_or_else(|| "never".to_string());
+        println!("  next run: {next}");
+        if let Some(last) = schedule.last_run_at {
+            let thread = schedule.last_thread_id.as_deref().unwrap_or("-");
+            println!(
+                "  last run: {} (thread {thread})",
+                format_time(last.with_timezone(&Local))
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn run_run(
+    config_overrides: &CliConfigOverrides,
+    codex_home: &Path,
+    args: RunArgs,
+) -> Result<()> {
+    let RunArgs { id, once } = args;
+    if let Some(id) = id {
+        let schedule = load_schedule(codex_home, &id)?;
+        return run_and_record(config_overrides, codex_home, schedule).await;
+    }
+
+    loop {
+        let now = Local::now();
+        for schedule in load_schedules(codex_home)? {
+            if !schedule.is_due(now) {
+                continue;
+            }
+            let id = schedule.id.clone();
+            if let Err(err) = run_and_record(config_overrides, codex_home, schedule).await {
+                eprintln!("schedule `{id}` failed: {err:#}");
+            }
+        }
+        if once {
+            return Ok(());
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+async fn run_and_record(
+    config_overrides: &CliConfigOverrides,
+    codex_home: &Path,
+    mut schedule: Schedule,
+) -> Result<()> {
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides {
+            cwd: Some(schedule.cwd.clone()),
+            // Headless: there is nobody to answer approval prompts.
+            approval_policy: Some(AskForApproval::Never),
+            ..Default::default()
+        },
+    )
+    .await
+    .context("failed to load configuration")?;
+
+    // Record the attempt up front so a crash mid-run does not re-trigger it
+    // in a loop.
+    schedule.last_run_at = Some(Utc::now());
+    save_schedule(codex_home, &schedule)?;
+    println!("Running schedule `{}`...", schedule.id);
+
+    let auth_manager = AuthManager::shared(
+        config.codex_home.clone(),
+        true,
+        config.cli_auth_credentials_store_mode,
+    );
+    let thread_manager =
+        ThreadManager::new(config.codex_home.clone(), auth_manager, SessionSource::Exec);
+    let outcome = run_schedule(&thread_manager, config, &schedule).await?;
+
+    schedule.last_thread_id = Some(outcome.thread_id.to_string());
+    save_schedule(codex_home, &schedule)?;
+
+    match outcome.error {
+        Some(error) => println!(
+            "Schedule `{}` finished with an error (thread {}): {error}",
+            schedule.id, outcome.thread_id
+        ),
+        None => println!(
+            "Schedule `{}` finished (thread {}).",
+            schedule.id, outcome.thread_id
+        ),
+    }
+    if let Some(message) = outcome.last_agent_message {
+        println!("{message}");
+    }
+    Ok(())
+}
+
+fn format_time(time: DateTime<Local>) -> String {
+    time.format("%Y-%m-%d %H:%M").to_string()
+}
@@ -0,0 +1,179 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::session_index::SESSION_INDEX_FILE;
+use codex_core::session_index::SessionIndex;
+use codex_core::session_transfer::export_portable_rollout;
+use codex_core::session_transfer::import_portable_rollout;
+
+use crate::export_cmd::resolve_thread_path;
+
+/// Subcommands:
+/// - `export` — write a thread as a file that can be imported elsewhere
+/// - `import` — add an exported thread to the local sessions
+/// - `migrate` — build the index that speeds up listing and search
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// Write a thread as a portable `.jsonl` file, without account data.
+    Export(SessionsExportArgs),
+
+    /// Add a thread exported with `codex sessions export` to the local
+    /// sessions so it can be resumed.
+    Import(SessionsImportArgs),
+
+    /// Index the local sessions in a SQLite database so that `codex resume`
+    /// listings and `codex history search` no longer read every rollout.
+    /// Rollout files are kept; the index is updated as sessions change.
+    Migrate(SessionsMigrateArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SessionsExportArgs {
+    /// Thread id (as shown by `codex resume`), archived or not, or path to a rollout file.
+    pub thread: String,
+
+    /// Replace API keys, tokens, and other values that look like secrets
+    /// with `[REDACTED]`.
+    #[arg(long, default_value_t = false)]
+    pub redact_secrets: bool,
+
+    /// Output file; the thread is written to stdout when omitted.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SessionsImportArgs {
+    /// File written by `codex sessions export`.
+    pub file: PathBuf,
+
+    /// Working directory to record for the thread (defaults to the one it
+    /// was recorded in).
+    #[arg(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SessionsMigrateArgs {
+    /// Discard the existing index and read every rollout again.
+    #[arg(long, default_value_t = false)]
+    pub rebuild: bool,
+}
+
+impl SessionsCli {
+    pub async fn run(self) -> Result<()> {
+        let SessionsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        match subcommand {
+            SessionsSubcommand::Export(args) => run_export(&config_overrides, args).await,
+            SessionsSubcommand::Import(args) => run_import(&config_overrides, args).await,
+            SessionsSubcommand::Migrate(args) => run_migrate(&config_overrides, args).await,
+        }
+    }
+}
+
+async fn run_export(config_overrides: &CliConfigOverrides, args: SessionsExportArgs) -> Result<()> {
+    let SessionsExportArgs {
+        thread,
+        redact_secrets,
+        output,
+    } = args;
+
+    let path = resolve_thread_path(config_overrides, &thread).await?;
+    let exported = export_portable_rollout(&path, redact_secrets)
+        .await
+        .with_context(|| format!("failed to export {}", path.display()))?;
+
+    let Some(output) = output else {
+        std::io::stdout().write_all(exported.contents.as_bytes())?;
+        return Ok(());
+    };
+    std::fs::write(&output, &exported.contents)
+        .with_context(|| format!("failed to write {}", output.display()))?;
+    let redacted = if redact_secrets {
+        format!(", {} secret(s) redacted", exported.redactions)
+    } else {
+        String::new()
+    };
+    println!(
+        "Wrote {} line(s) to {}{redacted}.",
+        exported.lines,
+        output.display()
+    );
+    Ok(())
+}
+
+async fn run_import(config_overrides: &CliConfigOverrides, args: SessionsImportArgs) -> Result<()> {
+    let SessionsImportArgs { file, cwd } = args;
+
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides::default(),
+    )
+    .await
+    .context("failed to load configuration")?;
+    let contents = std::fs::read_to_string(&file)
+        .with_context(|| format!("failed to read {}", file.display()))?;
+    let (thread_id, path) = import_portable_rollout(&config.codex_home, &contents, cwd)
+        .await
+        .with_context(|| format!("failed to import {}", file.display()))?;
+
+    println!("Imported {thread_id}: {}", path.display());
+    println!("Resume with `codex resume {thread_id}`.");
+    Ok(())
+}
+
+async fn run_migrate(
+    config_overrides: &CliConfigOverrides,
+    args: SessionsMigrateArgs,
+) -> Result<()> {
+    let SessionsMigrateArgs { rebuild } = args;
+
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides::default(),
+    )
+    .await
+    .context("failed to load configuration")?;
+    let codex_home = config.codex_home;
+    let stats = tokio::task::spawn_blocking(move || {
+        let mut index = if rebuild {
+            SessionIndex::rebuild(&codex_home)?
+        } else {
+            SessionIndex::create(&codex_home)?
+        };
+        index.sync()
+    })
+    .await?
+    .with_context(|| format!("failed to build {SESSION_INDEX_FILE}"))?;
+
+    println!(
+        "Indexed {} session(s); {} unchanged, {} removed.",
+        stats.indexed, stats.unchanged, stats.removed
+    );
+    println!("Listings and `codex history search` now use the index; rollout files are unchanged.");
+    Ok(())
+}
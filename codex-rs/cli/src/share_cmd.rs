@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::RolloutRecorder;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::session_share::ShareOptions;
+use codex_core::session_share::TurnRange;
+use codex_core::session_share::render_share_html;
+
+/// Writes turns of a recorded session to a single HTML file (transcript,
+/// diffs, collapsed tool output) with secrets redacted.
+#[derive(Debug, clap::Parser)]
+pub struct ShareCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Session id (as shown by `codex resume`) or path to a rollout `.jsonl` file.
+    pub session: String,
+
+    /// Turns to include: `N`, `A..B` (inclusive), `A..` or `..B`. All turns when omitted.
+    #[arg(long, value_name = "RANGE")]
+    pub turns: Option<TurnRange>,
+
+    /// Output file (defaults to `codex-share-<session>.html` in the current directory).
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Page title (defaults to the first user message of the selected turns).
+    #[arg(long)]
+    pub title: Option<String>,
+}
+
+impl ShareCli {
+    pub async fn run(self) -> Result<()> {
+        let ShareCli {
+            config_overrides,
+            session,
+            turns,
+            output,
+            title,
+        } = self;
+
+        let path = PathBuf::from(&session);
+        let path = if path.is_file() {
+            path
+        } else {
+            let overrides = config_overrides
+                .parse_overrides()
+                .map_err(anyhow::Error::msg)?;
+            let config = Config::load_with_cli_overrides_and_harness_overrides(
+                overrides,
+                ConfigOverrides::default(),
+            )
+            .await
+            .context("failed to load configuration")?;
+            match find_thread_path_by_id_str(&config.codex_home, &session).await? {
+                Some(path) => path,
+                None => bail!("No session found with id {session}."),
+            }
+        };
+
+        let history = RolloutRecorder::get_rollout_history(&path)
+            .await
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let shared =
+            render_share_html(&history.get_rollout_items(), &ShareOptions { turns, title })?;
+
+        let output = output.unwrap_or_else(|| {
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.rsplit('-').next())
+                .unwrap_or("session");
+            PathBuf::from(format!("codex-share-{stem}.html"))
+        });
+        std::fs::write(&output, &shared.html)
+            .with_context(|| format!("failed to write {}", output.display()))?;
+
+        println!(
+            "Wrote turns {}..{} of {} to {} ({} secret(s) redacted).",
+            shared.first_turn,
+            shared.last_turn,
+            shared.total_turns,
+            output.display(),
+            shared.redactions
+        );
+        Ok(())
+    }
+}
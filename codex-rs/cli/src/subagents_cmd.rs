@@ -0,0 +1,155 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::models_manager::manager::ModelsManager;
+use codex_core::subagents::load_subagents;
+use codex_core::subagents::preview_subagent;
+use codex_protocol::subagents::SubAgentPreview;
+
+/// Subcommands:
+/// - `run` — resolve a subagent invocation (currently `--dry-run` only)
+#[derive(Debug, clap::Parser)]
+pub struct SubAgentsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SubAgentsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SubAgentsSubcommand {
+    /// Resolve a subagent invocation against the current config.
+    Run(RunArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RunArgs {
+    /// Name of the subagent definition (`<name>.md`).
+    pub name: String,
+
+    /// Prompt to send to the subagent.
+    #[arg(default_value = "")]
+    pub prompt: String,
+
+    /// Print the effective model, policies, tools, and instructions instead
+    /// of starting the subagent.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Print the preview as JSON.
+    #[arg(long, requires = "dry_run")]
+    pub json: bool,
+
+    /// Working directory whose definitions and config are used.
+    #[arg(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+}
+
+impl SubAgentsCli {
+    pub async fn run(self) -> Result<()> {
+        let SubAgentsCli {
+            config_overrides,
+            subcommand,
+        } = self;
+
+        match subcommand {
+            SubAgentsSubcommand::Run(args) => run_run(&config_overrides, args).await,
+        }
+    }
+}
+
+async fn run_run(config_overrides: &CliConfigOverrides, args: RunArgs) -> Result<()> {
+    let RunArgs {
+        name,
+        prompt,
+        dry_run,
+        json,
+        cwd,
+    } = args;
+    if !dry_run {
+        bail!(
+            "`codex subagents run` only supports --dry-run; start subagents from a session with /agents or @{name}."
+        );
+    }
+
+    let overrides = config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        overrides,
+        ConfigOverrides {
+            cwd,
+            ..Default::default()
+        },
+    )
+    .await
+    .context("failed to load configuration")?;
+
+    let outcome = load_subagents(&config.cwd, &config.codex_home, &config.subagent_extra_dirs);
+    for error in &outcome.errors {
+        eprintln!(
+            "warning: failed to load subagent {}: {}",
+            error.path.display(),
+            error.message
+        );
+    }
+    let definition = outcome
+        .find(&name)
+        .ok_or_else(|| anyhow!("unknown subagent `{name}`"))?;
+
+    let auth_manager = AuthManager::shared(
+        config.codex_home.clone(),
+        false,
+        config.cli_auth_credentials_store_mode,
+    );
+    let models_manager = ModelsManager::new(config.codex_home.clone(), auth_manager);
+    let preview = preview_subagent(config, definition, prompt, &models_manager)
+        .await
+        .map_err(anyhow::Error::msg)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&preview)?);
+    } else {
+        print_preview(&preview);
+    }
+    Ok(())
+}
+
+fn print_preview(preview: &SubAgentPreview) {
+    let SubAgentPreview {
+        name,
+        path,
+        model,
+        approval_policy,
+        sandbox_policy,
+        tools,
+        mcp_servers,
+        developer_instructions,
+        prompt,
+    } = preview;
+    println!("Subagent @{name} (dry run, not started)");
+    println!("  definition: {}", path.display());
+    println!("  model:      {model}");
+    println!("  approval:   {approval_policy}");
+    println!("  sandbox:    {sandbox_policy}");
+    println!("  tools:      {}", tools.join(", "));
+    if !mcp_servers.is_empty() {
+        println!("  mcp:        {}", mcp_servers.join(", "));
+    }
+    if !prompt.is_empty() {
+        println!("  prompt:     {prompt}");
+    }
+    if let Some(instructions) = developer_instructions {
+        println!();
+        println!("Developer instructions:");
+        println!("{instructions}");
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use anyhow::Result;
+use predicates::str::contains;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::new(codex_utils_cargo_bin::cargo_bin("codex")?);
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+#[test]
+fn config_check_points_at_unknown_keys() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let cwd = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        "modle = \"gpt-5.1-codex\"\nexperimental_use_unified_exec_tool = true\n",
+    )?;
+
+    codex_command(codex_home.path())?
+        .args(["config", "check"])
+        .current_dir(cwd.path())
+        .assert()
+        .failure()
+        .stdout(contains(
+            "config.toml:1:1: unknown key `modle`; did you mean `model`?",
+        ))
+        .stdout(contains("use `features.unified_exec` instead"));
+
+    Ok(())
+}
+
+#[test]
+fn config_check_passes_a_valid_config() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let cwd = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        "model = \"gpt-5.1-codex\"\n",
+    )?;
+
+    codex_command(codex_home.path())?
+        .args(["config", "check"])
+        .current_dir(cwd.path())
+        .assert()
+        .success()
+        .stdout(contains("No problems found."));
+
+    Ok(())
+}
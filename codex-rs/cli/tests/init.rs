@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use anyhow::Result;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+fn codex_command(codex_home: &Path) -> Result<assert_cmd::Command> {
+    let mut cmd = assert_cmd::Command::new(codex_utils_cargo_bin::cargo_bin("codex")?);
+    cmd.env("CODEX_HOME", codex_home);
+    Ok(cmd)
+}
+
+#[test]
+fn init_yes_writes_config_and_scaffolds_repo() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let repo = TempDir::new()?;
+    std::process::Command::new("git")
+        .args(["init", "--quiet"])
+        .current_dir(repo.path())
+        .status()?;
+
+    codex_command(codex_home.path())?
+        .args(["init", "--yes", "--scaffold"])
+        .current_dir(repo.path())
+        .assert()
+        .success();
+
+    let config = std::fs::read_to_string(codex_home.path().join("config.toml"))?;
+    assert!(
+        config.contains("approval_policy = \"on-request\""),
+        "{config}"
+    );
+    assert!(
+        config.contains("sandbox_mode = \"workspace-write\""),
+        "{config}"
+    );
+    assert!(repo.path().join("AGENTS.md").is_file());
+    assert!(repo.path().join(".codex/agents/.gitkeep").is_file());
+    assert!(repo.path().join(".codex/commands/.gitkeep").is_file());
+
+    Ok(())
+}
+
+#[test]
+fn init_keeps_an_existing_agents_md() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let repo = TempDir::new()?;
+    std::process::Command::new("git")
+        .args(["init", "--quiet"])
+        .current_dir(repo.path())
+        .status()?;
+    std::fs::write(repo.path().join("AGENTS.md"), "Run `just test`.\n")?;
+
+    codex_command(codex_home.path())?
+        .args(["init", "--yes", "--scaffold"])
+        .current_dir(repo.path())
+        .assert()
+        .success();
+
+    assert_eq!(
+        std::fs::read_to_string(repo.path().join("AGENTS.md"))?,
+        "Run `just test`.\n"
+    );
+
+    Ok(())
+}
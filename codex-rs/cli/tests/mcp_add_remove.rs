@@ -133,6 +133,7 @@ async fn add_streamable_http_without_manual_token() -> Result<()> {
     assert!(github.enabled);
 
     assert!(!codex_home.path().join(".credentials.json").exists());
+    assert!(!codex_home.path().join("mcp_auth").exists());
     assert!(!codex_home.path().join(".env").exists());
 
     Ok(())
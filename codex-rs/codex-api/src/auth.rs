@@ -25,3 +25,18 @@ pub(crate) fn add_auth_headers<A: AuthProvider>(auth: &A, mut req: Request) -> R
     }
     req
 }
+
+/// Sends the key in a provider-specific header (`x-api-key` for Anthropic,
+/// `x-goog-api-key` for Gemini) rather than as a bearer token.
+pub(crate) fn add_api_key_header<A: AuthProvider>(
+    auth: &A,
+    header_name: &'static str,
+    mut req: Request,
+) -> Request {
+    if let Some(token) = auth.bearer_token()
+        && let Ok(header) = token.parse()
+    {
+        let _ = req.headers.insert(header_name, header);
+    }
+    req
+}
@@ -0,0 +1,64 @@
+use crate::auth::AuthProvider;
+use crate::common::ResponseStream;
+use crate::endpoint::streaming::StreamingClient;
+use crate::error::ApiError;
+use crate::provider::Provider;
+use crate::requests::GeminiRequest;
+use crate::sse::gemini::spawn_gemini_stream;
+use crate::telemetry::SseTelemetry;
+use codex_client::HttpTransport;
+use codex_client::RequestCompression;
+use codex_client::RequestTelemetry;
+use http::HeaderMap;
+use serde_json::Value;
+use std::sync::Arc;
+
+pub struct GeminiClient<T: HttpTransport, A: AuthProvider> {
+    streaming: StreamingClient<T, A>,
+}
+
+impl<T: HttpTransport, A: AuthProvider> GeminiClient<T, A> {
+    pub fn new(transport: T, provider: Provider, auth: A) -> Self {
+        Self {
+            streaming: StreamingClient::new(transport, provider, auth),
+        }
+    }
+
+    pub fn with_telemetry(
+        self,
+        request: Option<Arc<dyn RequestTelemetry>>,
+        sse: Option<Arc<dyn SseTelemetry>>,
+    ) -> Self {
+        Self {
+            streaming: self.streaming.with_telemetry(request, sse),
+        }
+    }
+
+    pub async fn stream_request(
+        &self,
+        model: &str,
+        request: GeminiRequest,
+    ) -> Result<ResponseStream, ApiError> {
+        self.stream(model, request.body, request.headers).await
+    }
+
+    /// The model is part of the path rather than the body.
+    pub async fn stream(
+        &self,
+        model: &str,
+        body: Value,
+        extra_headers: HeaderMap,
+    ) -> Result<ResponseStream, ApiError> {
+        let path = format!("models/{model}:streamGenerateContent?alt=sse");
+        self.streaming
+            .stream(
+                &path,
+                body,
+                extra_headers,
+                RequestCompression::None,
+                spawn_gemini_stream,
+                None,
+            )
+            .await
+    }
+}
@@ -110,8 +110,9 @@ impl<T: HttpTransport, A: AuthProvider> ResponsesClient<T, A> {
 
     fn path(&self) -> &'static str {
         match self.streaming.provider().wire {
-            WireApi::Responses | WireApi::Compact => "responses",
+            WireApi::Responses | WireApi::Compact | WireApi::Gemini => "responses",
             WireApi::Chat => "chat/completions",
+            WireApi::Anthropic => "messages",
         }
     }
 
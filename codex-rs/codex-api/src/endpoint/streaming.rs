@@ -1,8 +1,10 @@
 use crate::auth::AuthProvider;
+use crate::auth::add_api_key_header;
 use crate::auth::add_auth_headers;
 use crate::common::ResponseStream;
 use crate::error::ApiError;
 use crate::provider::Provider;
+use crate::provider::WireApi;
 use crate::telemetry::SseTelemetry;
 use crate::telemetry::run_with_request_telemetry;
 use codex_client::HttpTransport;
@@ -74,7 +76,11 @@ impl<T: HttpTransport, A: AuthProvider> StreamingClient<T, A> {
             );
             req.body = Some(body.clone());
             req.compression = compression;
-            add_auth_headers(&self.auth, req)
+            match self.provider.wire {
+                WireApi::Anthropic => add_api_key_header(&self.auth, "x-api-key", req),
+                WireApi::Gemini => add_api_key_header(&self.auth, "x-goog-api-key", req),
+                _ => add_auth_headers(&self.auth, req),
+            }
         };
 
         let stream_response = run_with_request_telemetry(
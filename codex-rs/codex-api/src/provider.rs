@@ -14,6 +14,10 @@ pub enum WireApi {
     Responses,
     Chat,
     Compact,
+    /// Anthropic Messages API (`/v1/messages`).
+    Anthropic,
+    /// Gemini API (`models/<model>:streamGenerateContent`).
+    Gemini,
 }
 
 /// High-level retry configuration for a provider.
@@ -76,7 +80,7 @@ impl Provider {
                 .map(|(k, v)| format!("{k}={v}"))
                 .collect::<Vec<_>>()
                 .join("&");
-            url.push('?');
+            url.push(if url.contains('?') { '&' } else { '?' });
             url.push_str(&qs);
         }
 
@@ -1,8 +1,10 @@
 use codex_protocol::protocol::CreditsSnapshot;
+use codex_protocol::protocol::ProviderQuota;
 use codex_protocol::protocol::RateLimitSnapshot;
 use codex_protocol::protocol::RateLimitWindow;
 use http::HeaderMap;
 use std::fmt::Display;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct RateLimitError {
@@ -65,6 +67,83 @@ fn parse_rate_limit_window(
     })
 }
 
+/// Parses the standard `x-ratelimit-*` headers sent by OpenAI-compatible
+/// providers. Returns `None` when none of them are present.
+pub fn parse_provider_quota(headers: &HeaderMap) -> Option<ProviderQuota> {
+    let quota = ProviderQuota {
+        limit_requests: parse_header_i64(headers, "x-ratelimit-limit-requests"),
+        remaining_requests: parse_header_i64(headers, "x-ratelimit-remaining-requests"),
+        limit_tokens: parse_header_i64(headers, "x-ratelimit-limit-tokens"),
+        remaining_tokens: parse_header_i64(headers, "x-ratelimit-remaining-tokens"),
+        requests_reset_seconds: parse_header_str(headers, "x-ratelimit-reset-requests")
+            .and_then(parse_reset_duration)
+            .map(|reset| reset.as_secs_f64()),
+        tokens_reset_seconds: parse_header_str(headers, "x-ratelimit-reset-tokens")
+            .and_then(parse_reset_duration)
+            .map(|reset| reset.as_secs_f64()),
+    };
+    (quota != ProviderQuota::default()).then_some(quota)
+}
+
+/// How long a rate-limited (429) response asks the client to wait:
+/// `retry-after-ms`, then `retry-after` (in seconds), then the reset time of
+/// the exhausted `x-ratelimit-*` quota.
+pub fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    if let Some(millis) = parse_header_f64(headers, "retry-after-ms").filter(|ms| *ms >= 0.0) {
+        return Some(Duration::from_secs_f64(millis / 1000.0));
+    }
+    if let Some(seconds) = parse_header_f64(headers, "retry-after").filter(|s| *s >= 0.0) {
+        return Some(Duration::from_secs_f64(seconds));
+    }
+    let quota = parse_provider_quota(headers)?;
+    let requests = quota.requests_reset_seconds.filter(|_| {
+        quota
+            .remaining_requests
+            .is_none_or(|remaining| remaining <= 0)
+    });
+    let tokens = quota.tokens_reset_seconds.filter(|_| {
+        quota
+            .remaining_tokens
+            .is_none_or(|remaining| remaining <= 0)
+    });
+    requests
+        .into_iter()
+        .chain(tokens)
+        .max_by(f64::total_cmp)
+        .map(Duration::from_secs_f64)
+}
+
+/// Parses reset times such as `1s`, `6m0s`, `1h2m3.5s`, `20ms`, or a bare
+/// number of seconds.
+fn parse_reset_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<f64>() {
+        return (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds));
+    }
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+        total += number * scale;
+    }
+    (!value.is_empty() && total.is_finite()).then(|| Duration::from_secs_f64(total))
+}
+
 fn parse_credits_snapshot(headers: &HeaderMap) -> Option<CreditsSnapshot> {
     let has_credits = parse_header_bool(headers, "x-codex-credits-has-credits")?;
     let unlimited = parse_header_bool(headers, "x-codex-credits-unlimited")?;
@@ -104,3 +183,84 @@ fn parse_header_bool(headers: &HeaderMap, name: &str) -> Option<bool> {
 fn parse_header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
     headers.get(name)?.to_str().ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+    use pretty_assertions::assert_eq;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn parses_reset_durations() {
+        assert_eq!(parse_reset_duration("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_reset_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(
+            parse_reset_duration("1h2m3.5s"),
+            Some(Duration::from_secs_f64(3723.5))
+        );
+        assert_eq!(
+            parse_reset_duration("20ms"),
+            Some(Duration::from_millis(20))
+        );
+        assert_eq!(parse_reset_duration("42"), Some(Duration::from_secs(42)));
+        assert_eq!(parse_reset_duration("soon"), None);
+        assert_eq!(parse_reset_duration(""), None);
+    }
+
+    #[test]
+    fn parses_provider_quota() {
+        assert_eq!(parse_provider_quota(&headers(&[])), None);
+        assert_eq!(
+            parse_provider_quota(&headers(&[
+                ("x-ratelimit-limit-requests", "500"),
+                ("x-ratelimit-remaining-requests", "499"),
+                ("x-ratelimit-remaining-tokens", "0"),
+                ("x-ratelimit-reset-tokens", "1m30s"),
+            ])),
+            Some(ProviderQuota {
+                limit_requests: Some(500),
+                remaining_requests: Some(499),
+                limit_tokens: None,
+                remaining_tokens: Some(0),
+                requests_reset_seconds: None,
+                tokens_reset_seconds: Some(90.0),
+            })
+        );
+    }
+
+    #[test]
+    fn retry_after_prefers_explicit_headers_then_exhausted_quota() {
+        assert_eq!(
+            parse_retry_after(&headers(&[
+                ("retry-after-ms", "1500"),
+                ("retry-after", "9")
+            ])),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            parse_retry_after(&headers(&[("retry-after", "42")])),
+            Some(Duration::from_secs(42))
+        );
+        assert_eq!(
+            parse_retry_after(&headers(&[
+                ("x-ratelimit-remaining-requests", "10"),
+                ("x-ratelimit-reset-requests", "2s"),
+                ("x-ratelimit-remaining-tokens", "0"),
+                ("x-ratelimit-reset-tokens", "12s"),
+            ])),
+            Some(Duration::from_secs(12))
+        );
+        assert_eq!(
+            parse_retry_after(&headers(&[("x-ratelimit-remaining-requests", "10")])),
+            None
+        );
+    }
+}
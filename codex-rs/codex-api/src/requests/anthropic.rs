@@ -0,0 +1,428 @@
+use crate::error::ApiError;
+use crate::provider::Provider;
+use crate::requests::headers::build_conversation_headers;
+use crate::requests::headers::insert_header;
+use crate::requests::headers::subagent_header;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::SessionSource;
+use http::HeaderMap;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Output budget used when the caller does not set `max_tokens`; the
+/// Messages API requires one on every request.
+const DEFAULT_MAX_TOKENS: u64 = 16_384;
+
+/// Assembled request body plus headers for Anthropic Messages streaming calls.
+pub struct AnthropicRequest {
+    pub body: Value,
+    pub headers: HeaderMap,
+}
+
+pub struct AnthropicRequestBuilder<'a> {
+    model: &'a str,
+    instructions: &'a str,
+    input: &'a [ResponseItem],
+    tools: &'a [Value],
+    parallel_tool_calls: bool,
+    max_tokens: Option<u64>,
+    thinking_budget: Option<u64>,
+    conversation_id: Option<String>,
+    session_source: Option<SessionSource>,
+}
+
+impl<'a> AnthropicRequestBuilder<'a> {
+    pub fn new(
+        model: &'a str,
+        instructions: &'a str,
+        input: &'a [ResponseItem],
+        tools: &'a [Value],
+    ) -> Self {
+        Self {
+            model,
+            instructions,
+            input,
+            tools,
+            parallel_tool_calls: true,
+            max_tokens: None,
+            thinking_budget: None,
+            conversation_id: None,
+            session_source: None,
+        }
+    }
+
+    pub fn parallel_tool_calls(mut self, enabled: bool) -> Self {
+        self.parallel_tool_calls = enabled;
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: Option<u64>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Enables extended thinking with the given token budget.
+    pub fn thinking_budget(mut self, budget: Option<u64>) -> Self {
+        self.thinking_budget = budget;
+        self
+    }
+
+    pub fn conversation_id(mut self, id: Option<String>) -> Self {
+        self.conversation_id = id;
+        self
+    }
+
+    pub fn session_source(mut self, source: Option<SessionSource>) -> Self {
+        self.session_source = source;
+        self
+    }
+
+    pub fn build(self, provider: &Provider) -> Result<AnthropicRequest, ApiError> {
+        let mut messages = build_messages(self.input);
+        if messages.is_empty() {
+            return Err(ApiError::InvalidRequest {
+                message: "Anthropic requests need at least one message".to_string(),
+            });
+        }
+        // Cache the conversation prefix so each turn only pays for new input.
+        if let Some(block) = messages
+            .last_mut()
+            .and_then(|message| message.get_mut("content"))
+            .and_then(Value::as_array_mut)
+            .and_then(|blocks| blocks.last_mut())
+            .and_then(Value::as_object_mut)
+        {
+            block.insert("cache_control".to_string(), json!({"type": "ephemeral"}));
+        }
+
+        let mut max_tokens = self.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+        let mut payload = Map::new();
+        payload.insert("model".to_string(), json!(self.model));
+        payload.insert(
+            "system".to_string(),
+            json!([{
+                "type": "text",
+                "text": self.instructions,
+                "cache_control": {"type": "ephemeral"},
+            }]),
+        );
+        payload.insert("messages".to_string(), json!(messages));
+        payload.insert("stream".to_string(), Value::Bool(true));
+        if !self.tools.is_empty() {
+            payload.insert("tools".to_string(), json!(self.tools));
+            payload.insert(
+                "tool_choice".to_string(),
+                json!({
+                    "type": "auto",
+                    "disable_parallel_tool_use": !self.parallel_tool_calls,
+                }),
+            );
+        }
+        if let Some(budget) = self.thinking_budget {
+            // The thinking budget counts against `max_tokens`, so leave the
+            // usual room for the visible answer on top of it.
+            max_tokens += budget;
+            payload.insert(
+                "thinking".to_string(),
+                json!({"type": "enabled", "budget_tokens": budget}),
+            );
+        }
+        payload.insert("max_tokens".to_string(), json!(max_tokens));
+
+        let mut headers = build_conversation_headers(self.conversation_id);
+        if let Some(subagent) = subagent_header(&self.session_source) {
+            insert_header(&mut headers, "x-openai-subagent", &subagent);
+        }
+        if !provider.headers.contains_key("anthropic-version") {
+            insert_header(&mut headers, "anthropic-version", ANTHROPIC_VERSION);
+        }
+
+        Ok(AnthropicRequest {
+            body: Value::Object(payload),
+            headers,
+        })
+    }
+}
+
+/// Maps the conversation onto alternating `user`/`assistant` messages.
+/// Tool calls become `tool_use` blocks on the assistant side and their
+/// outputs `tool_result` blocks on the user side; consecutive items with the
+/// same role share one message, as the API requires.
+fn build_messages(input: &[ResponseItem]) -> Vec<Value> {
+    let mut messages: Vec<(&'static str, Vec<Value>)> = Vec::new();
+    let mut push = |role: &'static str, block: Value| match messages.last_mut() {
+        Some((last_role, blocks)) if *last_role == role => blocks.push(block),
+        _ => messages.push((role, vec![block])),
+    };
+
+    for item in input {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                let role = if role == "assistant" {
+                    "assistant"
+                } else {
+                    "user"
+                };
+                for content_item in content {
+                    match content_item {
+                        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                            if !text.trim().is_empty() {
+                                push(role, json!({"type": "text", "text": text}));
+                            }
+                        }
+                        ContentItem::InputImage { image_url } => {
+                            push(role, image_block(image_url));
+                        }
+                    }
+                }
+            }
+            ResponseItem::Reasoning {
+                content: Some(content),
+                encrypted_content: Some(signature),
+                ..
+            } => {
+                // Only thinking blocks produced by Anthropic carry a signature,
+                // and the API rejects replayed thinking without one.
+                let thinking: String = content
+                    .iter()
+                    .map(|entry| match entry {
+                        ReasoningItemContent::ReasoningText { text }
+                        | ReasoningItemContent::Text { text } => text.as_str(),
+                    })
+                    .collect();
+                push(
+                    "assistant",
+                    json!({"type": "thinking", "thinking": thinking, "signature": signature}),
+                );
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                let input = serde_json::from_str::<Value>(arguments)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({}));
+                push(
+                    "assistant",
+                    json!({"type": "tool_use", "id": call_id, "name": name, "input": input}),
+                );
+            }
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => {
+                push(
+                    "assistant",
+                    json!({"type": "tool_use", "id": call_id, "name": name, "input": {"input": input}}),
+                );
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let content = match &output.content_items {
+                    Some(items) => json!(
+                        items
+                            .iter()
+                            .map(|item| match item {
+                                FunctionCallOutputContentItem::InputText { text } => {
+                                    json!({"type": "text", "text": text})
+                                }
+                                FunctionCallOutputContentItem::InputImage { image_url } => {
+                                    image_block(image_url)
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    ),
+                    None => json!(output.content),
+                };
+                let mut block =
+                    json!({"type": "tool_result", "tool_use_id": call_id, "content": content});
+                if output.success == Some(false)
+                    && let Some(obj) = block.as_object_mut()
+                {
+                    obj.insert("is_error".to_string(), Value::Bool(true));
+                }
+                push("user", block);
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                push(
+                    "user",
+                    json!({"type": "tool_result", "tool_use_id": call_id, "content": output}),
+                );
+            }
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::LocalShellCall { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::CodeInterpreterCall { .. }
+            | ResponseItem::FileSearchCall { .. }
+            | ResponseItem::GhostSnapshot { .. }
+            | ResponseItem::Compaction { .. }
+            | ResponseItem::Other => {}
+        }
+    }
+
+    messages
+        .into_iter()
+        .map(|(role, content)| json!({"role": role, "content": content}))
+        .collect()
+}
+
+/// Data URLs are sent inline as base64; anything else is fetched by URL.
+fn image_block(image_url: &str) -> Value {
+    if let Some(rest) = image_url.strip_prefix("data:")
+        && let Some((media_type, data)) = rest.split_once(";base64,")
+    {
+        return json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": media_type, "data": data},
+        });
+    }
+    json!({"type": "image", "source": {"type": "url", "url": image_url}})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::RetryConfig;
+    use crate::provider::WireApi;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use http::HeaderValue;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn provider() -> Provider {
+        Provider {
+            name: "Anthropic".to_string(),
+            base_url: "https://api.anthropic.com/v1".to_string(),
+            query_params: None,
+            wire: WireApi::Anthropic,
+            headers: HeaderMap::new(),
+            retry: RetryConfig {
+                max_attempts: 1,
+                base_delay: Duration::from_millis(10),
+                retry_429: false,
+                retry_5xx: true,
+                retry_transport: true,
+            },
+            stream_idle_timeout: Duration::from_secs(1),
+        }
+    }
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        }
+    }
+
+    #[test]
+    fn maps_tool_calls_to_tool_use_and_tool_result_blocks() {
+        let input = vec![
+            message("developer", "be brief"),
+            message("user", "list files"),
+            ResponseItem::Reasoning {
+                id: String::new(),
+                summary: Vec::new(),
+                content: Some(vec![ReasoningItemContent::ReasoningText {
+                    text: "use ls".to_string(),
+                }]),
+                encrypted_content: Some("sig".to_string()),
+            },
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "toolu_1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "toolu_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "denied".to_string(),
+                    content_items: None,
+                    success: Some(false),
+                },
+            },
+        ];
+
+        let req = AnthropicRequestBuilder::new("claude-test", "inst", &input, &[])
+            .build(&provider())
+            .expect("request");
+
+        assert_eq!(
+            req.body["messages"],
+            json!([
+                {"role": "user", "content": [
+                    {"type": "text", "text": "be brief"},
+                    {"type": "text", "text": "list files"},
+                ]},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "use ls", "signature": "sig"},
+                    {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {"command": ["ls"]}},
+                ]},
+                {"role": "user", "content": [
+                    {
+                        "type": "tool_result",
+                        "tool_use_id": "toolu_1",
+                        "content": "denied",
+                        "is_error": true,
+                        "cache_control": {"type": "ephemeral"},
+                    },
+                ]},
+            ])
+        );
+        assert_eq!(req.body["system"][0]["text"], "inst");
+        assert_eq!(req.body.get("tools"), None);
+        assert_eq!(
+            req.headers.get("anthropic-version"),
+            Some(&HeaderValue::from_static(ANTHROPIC_VERSION))
+        );
+    }
+
+    #[test]
+    fn thinking_budget_is_added_on_top_of_max_tokens() {
+        let input = vec![message("user", "hi")];
+        let tools = vec![json!({"name": "shell", "input_schema": {"type": "object"}})];
+
+        let req = AnthropicRequestBuilder::new("claude-test", "inst", &input, &tools)
+            .parallel_tool_calls(false)
+            .max_tokens(Some(1_000))
+            .thinking_budget(Some(4_000))
+            .build(&provider())
+            .expect("request");
+
+        assert_eq!(req.body["max_tokens"], 5_000);
+        assert_eq!(
+            req.body["thinking"],
+            json!({"type": "enabled", "budget_tokens": 4_000})
+        );
+        assert_eq!(req.body["tool_choice"]["disable_parallel_tool_use"], true);
+    }
+
+    #[test]
+    fn data_urls_become_base64_image_sources() {
+        assert_eq!(
+            image_block("data:image/png;base64,AAAA"),
+            json!({
+                "type": "image",
+                "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"},
+            })
+        );
+        assert_eq!(
+            image_block("https://example.com/a.png")["source"]["type"],
+            "url"
+        );
+    }
+}
@@ -72,7 +72,9 @@ impl<'a> ChatRequestBuilder<'a> {
                 ResponseItem::Reasoning { .. } | ResponseItem::Other => {}
                 ResponseItem::CustomToolCall { .. } => {}
                 ResponseItem::CustomToolCallOutput { .. } => {}
-                ResponseItem::WebSearchCall { .. } => {}
+                ResponseItem::WebSearchCall { .. }
+                | ResponseItem::CodeInterpreterCall { .. }
+                | ResponseItem::FileSearchCall { .. } => {}
                 ResponseItem::GhostSnapshot { .. } => {}
                 ResponseItem::Compaction { .. } => {}
             }
@@ -284,6 +286,8 @@ impl<'a> ChatRequestBuilder<'a> {
                 }
                 ResponseItem::Reasoning { .. }
                 | ResponseItem::WebSearchCall { .. }
+                | ResponseItem::CodeInterpreterCall { .. }
+                | ResponseItem::FileSearchCall { .. }
                 | ResponseItem::Other
                 | ResponseItem::Compaction { .. } => {
                     continue;
@@ -0,0 +1,314 @@
+use crate::error::ApiError;
+use crate::requests::headers::build_conversation_headers;
+use crate::requests::headers::insert_header;
+use crate::requests::headers::subagent_header;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::SessionSource;
+use http::HeaderMap;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// Assembled request body plus headers for Gemini `streamGenerateContent` calls.
+pub struct GeminiRequest {
+    pub body: Value,
+    pub headers: HeaderMap,
+}
+
+pub struct GeminiRequestBuilder<'a> {
+    instructions: &'a str,
+    input: &'a [ResponseItem],
+    function_declarations: &'a [Value],
+    safety_settings: Vec<Value>,
+    conversation_id: Option<String>,
+    session_source: Option<SessionSource>,
+}
+
+impl<'a> GeminiRequestBuilder<'a> {
+    pub fn new(
+        instructions: &'a str,
+        input: &'a [ResponseItem],
+        function_declarations: &'a [Value],
+    ) -> Self {
+        Self {
+            instructions,
+            input,
+            function_declarations,
+            safety_settings: Vec::new(),
+            conversation_id: None,
+            session_source: None,
+        }
+    }
+
+    /// `{category, threshold}` entries sent as-is in `safetySettings`.
+    pub fn safety_settings(mut self, settings: Vec<Value>) -> Self {
+        self.safety_settings = settings;
+        self
+    }
+
+    pub fn conversation_id(mut self, id: Option<String>) -> Self {
+        self.conversation_id = id;
+        self
+    }
+
+    pub fn session_source(mut self, source: Option<SessionSource>) -> Self {
+        self.session_source = source;
+        self
+    }
+
+    pub fn build(self) -> Result<GeminiRequest, ApiError> {
+        let contents = build_contents(self.input);
+        if contents.is_empty() {
+            return Err(ApiError::InvalidRequest {
+                message: "Gemini requests need at least one message".to_string(),
+            });
+        }
+
+        let mut payload = Map::new();
+        payload.insert(
+            "systemInstruction".to_string(),
+            json!({"parts": [{"text": self.instructions}]}),
+        );
+        payload.insert("contents".to_string(), json!(contents));
+        if !self.function_declarations.is_empty() {
+            payload.insert(
+                "tools".to_string(),
+                json!([{"functionDeclarations": self.function_declarations}]),
+            );
+        }
+        if !self.safety_settings.is_empty() {
+            payload.insert(
+                "safetySettings".to_string(),
+                Value::Array(self.safety_settings),
+            );
+        }
+
+        let mut headers = build_conversation_headers(self.conversation_id);
+        if let Some(subagent) = subagent_header(&self.session_source) {
+            insert_header(&mut headers, "x-openai-subagent", &subagent);
+        }
+
+        Ok(GeminiRequest {
+            body: Value::Object(payload),
+            headers,
+        })
+    }
+}
+
+/// Maps the conversation onto `user`/`model` contents. A `functionResponse`
+/// is matched to its call by function name rather than id, so outputs look
+/// up the name of the call they answer; consecutive items with the same role
+/// share one content entry.
+fn build_contents(input: &[ResponseItem]) -> Vec<Value> {
+    let mut contents: Vec<(&'static str, Vec<Value>)> = Vec::new();
+    let mut push = |role: &'static str, part: Value| match contents.last_mut() {
+        Some((last_role, parts)) if *last_role == role => parts.push(part),
+        _ => contents.push((role, vec![part])),
+    };
+    let mut call_names: HashMap<&str, &str> = HashMap::new();
+
+    for item in input {
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                let role = if role == "assistant" { "model" } else { "user" };
+                for content_item in content {
+                    match content_item {
+                        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                            if !text.trim().is_empty() {
+                                push(role, json!({"text": text}));
+                            }
+                        }
+                        ContentItem::InputImage { image_url } => {
+                            push(role, image_part(image_url));
+                        }
+                    }
+                }
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                call_names.insert(call_id, name);
+                let args = serde_json::from_str::<Value>(arguments)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({}));
+                push(
+                    "model",
+                    json!({"functionCall": {"name": name, "args": args}}),
+                );
+            }
+            ResponseItem::CustomToolCall {
+                call_id,
+                name,
+                input,
+                ..
+            } => {
+                call_names.insert(call_id, name);
+                push(
+                    "model",
+                    json!({"functionCall": {"name": name, "args": {"input": input}}}),
+                );
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let name = call_names.get(call_id.as_str()).copied().unwrap_or(call_id);
+                let mut text = output.content.clone();
+                let mut images = Vec::new();
+                if let Some(items) = &output.content_items {
+                    text.clear();
+                    for item in items {
+                        match item {
+                            FunctionCallOutputContentItem::InputText { text: segment } => {
+                                text.push_str(segment);
+                            }
+                            FunctionCallOutputContentItem::InputImage { image_url } => {
+                                images.push(image_part(image_url));
+                            }
+                        }
+                    }
+                }
+                let key = if output.success == Some(false) {
+                    "error"
+                } else {
+                    "output"
+                };
+                push(
+                    "user",
+                    json!({"functionResponse": {"name": name, "response": {key: text}}}),
+                );
+                for image in images {
+                    push("user", image);
+                }
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                let name = call_names.get(call_id.as_str()).copied().unwrap_or(call_id);
+                push(
+                    "user",
+                    json!({"functionResponse": {"name": name, "response": {"output": output}}}),
+                );
+            }
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::LocalShellCall { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::CodeInterpreterCall { .. }
+            | ResponseItem::FileSearchCall { .. }
+            | ResponseItem::GhostSnapshot { .. }
+            | ResponseItem::Compaction { .. }
+            | ResponseItem::Other => {}
+        }
+    }
+
+    contents
+        .into_iter()
+        .map(|(role, parts)| json!({"role": role, "parts": parts}))
+        .collect()
+}
+
+/// Data URLs are sent inline; anything else is referenced by URI.
+fn image_part(image_url: &str) -> Value {
+    if let Some(rest) = image_url.strip_prefix("data:")
+        && let Some((mime_type, data)) = rest.split_once(";base64,")
+    {
+        return json!({"inlineData": {"mimeType": mime_type, "data": data}});
+    }
+    json!({"fileData": {"fileUri": image_url}})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        }
+    }
+
+    #[test]
+    fn function_responses_are_named_after_their_call() {
+        let input = vec![
+            message("user", "list files"),
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "call_1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "a.rs".to_string(),
+                    content_items: None,
+                    success: Some(true),
+                },
+            },
+            message("assistant", "Found a.rs"),
+        ];
+        let declarations = vec![json!({"name": "shell"})];
+        let settings = vec![json!({
+            "category": "HARM_CATEGORY_DANGEROUS_CONTENT",
+            "threshold": "BLOCK_ONLY_HIGH",
+        })];
+
+        let req = GeminiRequestBuilder::new("inst", &input, &declarations)
+            .safety_settings(settings.clone())
+            .build()
+            .expect("request");
+
+        assert_eq!(
+            req.body,
+            json!({
+                "systemInstruction": {"parts": [{"text": "inst"}]},
+                "contents": [
+                    {"role": "user", "parts": [{"text": "list files"}]},
+                    {"role": "model", "parts": [
+                        {"functionCall": {"name": "shell", "args": {"command": ["ls"]}}},
+                    ]},
+                    {"role": "user", "parts": [
+                        {"functionResponse": {"name": "shell", "response": {"output": "a.rs"}}},
+                    ]},
+                    {"role": "model", "parts": [{"text": "Found a.rs"}]},
+                ],
+                "tools": [{"functionDeclarations": declarations}],
+                "safetySettings": settings,
+            })
+        );
+    }
+
+    #[test]
+    fn failed_outputs_are_reported_as_errors() {
+        let input = vec![
+            message("user", "run it"),
+            ResponseItem::FunctionCallOutput {
+                call_id: "call_9".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "denied".to_string(),
+                    content_items: None,
+                    success: Some(false),
+                },
+            },
+        ];
+
+        let req = GeminiRequestBuilder::new("inst", &input, &[])
+            .build()
+            .expect("request");
+
+        assert_eq!(
+            req.body["contents"][0]["parts"][1],
+            json!({"functionResponse": {"name": "call_9", "response": {"error": "denied"}}})
+        );
+        assert_eq!(req.body.get("tools"), None);
+    }
+}
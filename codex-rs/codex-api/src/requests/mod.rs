@@ -1,8 +1,14 @@
+pub mod anthropic;
 pub mod chat;
+pub mod gemini;
 pub(crate) mod headers;
 pub mod responses;
 
+pub use anthropic::AnthropicRequest;
+pub use anthropic::AnthropicRequestBuilder;
 pub use chat::ChatRequest;
 pub use chat::ChatRequestBuilder;
+pub use gemini::GeminiRequest;
+pub use gemini::GeminiRequestBuilder;
 pub use responses::ResponsesRequest;
 pub use responses::ResponsesRequestBuilder;
@@ -172,6 +172,8 @@ fn attach_item_ids(payload_json: &mut Value, original_items: &[ResponseItem]) {
         if let ResponseItem::Reasoning { id, .. }
         | ResponseItem::Message { id: Some(id), .. }
         | ResponseItem::WebSearchCall { id: Some(id), .. }
+        | ResponseItem::CodeInterpreterCall { id: Some(id), .. }
+        | ResponseItem::FileSearchCall { id: Some(id), .. }
         | ResponseItem::FunctionCall { id: Some(id), .. }
         | ResponseItem::LocalShellCall { id: Some(id), .. }
         | ResponseItem::CustomToolCall { id: Some(id), .. } = item
@@ -0,0 +1,485 @@
+use crate::common::ResponseEvent;
+use crate::common::ResponseStream;
+use crate::error::ApiError;
+use crate::telemetry::SseTelemetry;
+use codex_client::StreamResponse;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::TokenUsage;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+pub(crate) fn spawn_anthropic_stream(
+    stream_response: StreamResponse,
+    idle_timeout: Duration,
+    telemetry: Option<Arc<dyn SseTelemetry>>,
+    _turn_state: Option<Arc<OnceLock<String>>>,
+) -> ResponseStream {
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
+    tokio::spawn(async move {
+        process_anthropic_sse(stream_response.bytes, tx_event, idle_timeout, telemetry).await;
+    });
+    ResponseStream { rx_event }
+}
+
+/// A content block that is still streaming, keyed by its `index`.
+enum Block {
+    Text(String),
+    Thinking {
+        text: String,
+        signature: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        json: String,
+    },
+}
+
+/// Token counts reported across `message_start` and `message_delta`.
+#[derive(Default)]
+struct Usage {
+    input: i64,
+    cache_creation: i64,
+    cache_read: i64,
+    output: i64,
+}
+
+impl Usage {
+    fn update(&mut self, usage: &Value) {
+        let field = |name: &str| usage.get(name).and_then(Value::as_i64);
+        if let Some(value) = field("input_tokens") {
+            self.input = value;
+        }
+        if let Some(value) = field("cache_creation_input_tokens") {
+            self.cache_creation = value;
+        }
+        if let Some(value) = field("cache_read_input_tokens") {
+            self.cache_read = value;
+        }
+        if let Some(value) = field("output_tokens") {
+            self.output = value;
+        }
+    }
+
+    /// Anthropic reports cached prompt tokens separately from
+    /// `input_tokens`; fold them back in so the totals match the other wires.
+    fn to_token_usage(&self) -> TokenUsage {
+        let input_tokens = self.input + self.cache_creation + self.cache_read;
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens: self.cache_read,
+            output_tokens: self.output,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + self.output,
+        }
+    }
+}
+
+/// Processes Server-Sent Events from the Anthropic Messages streaming API.
+///
+/// Text and thinking blocks are surfaced as deltas while they stream and as
+/// complete items on `content_block_stop`; `tool_use` blocks become
+/// `FunctionCall` items once their JSON input has fully arrived. Thinking
+/// signatures ride along in `encrypted_content` so the blocks can be replayed
+/// on the next request.
+pub async fn process_anthropic_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    idle_timeout: Duration,
+    telemetry: Option<Arc<dyn SseTelemetry>>,
+) where
+    S: Stream<Item = Result<bytes::Bytes, codex_client::TransportError>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+    let mut blocks: HashMap<u64, Block> = HashMap::new();
+    let mut usage = Usage::default();
+    let mut response_id = String::new();
+    let mut stop_reason: Option<String> = None;
+
+    loop {
+        let start = Instant::now();
+        let response = timeout(idle_timeout, stream.next()).await;
+        if let Some(t) = telemetry.as_ref() {
+            t.on_sse_poll(&response, start.elapsed());
+        }
+        let sse = match response {
+            Ok(Some(Ok(sse))) => sse,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event.send(Err(ApiError::Stream(e.to_string()))).await;
+                return;
+            }
+            Ok(None) => {
+                let _ = tx_event
+                    .send(Err(ApiError::Stream(
+                        "stream closed before message_stop".into(),
+                    )))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(ApiError::Stream("idle timeout waiting for SSE".into())))
+                    .await;
+                return;
+            }
+        };
+
+        trace!("SSE event: {}", sse.data);
+
+        let event: Value = match serde_json::from_str(&sse.data) {
+            Ok(event) => event,
+            Err(err) => {
+                debug!(
+                    "Failed to parse Anthropic SSE event: {err}, data: {}",
+                    sse.data
+                );
+                continue;
+            }
+        };
+        let index = event.get("index").and_then(Value::as_u64).unwrap_or(0);
+
+        match event
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+        {
+            "message_start" => {
+                if let Some(message) = event.get("message") {
+                    if let Some(id) = message.get("id").and_then(Value::as_str) {
+                        response_id = id.to_string();
+                    }
+                    if let Some(value) = message.get("usage") {
+                        usage.update(value);
+                    }
+                }
+                let _ = tx_event.send(Ok(ResponseEvent::Created)).await;
+            }
+            "content_block_start" => {
+                let Some(block) = event.get("content_block") else {
+                    continue;
+                };
+                let str_field = |name: &str| {
+                    block
+                        .get(name)
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                match block.get("type").and_then(Value::as_str) {
+                    Some("text") => {
+                        let item = assistant_message(String::new());
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                            .await;
+                        blocks.insert(index, Block::Text(str_field("text")));
+                    }
+                    Some("thinking") => {
+                        let item = reasoning_item(String::new(), None);
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                            .await;
+                        blocks.insert(
+                            index,
+                            Block::Thinking {
+                                text: str_field("thinking"),
+                                signature: str_field("signature"),
+                            },
+                        );
+                    }
+                    Some("tool_use") => {
+                        blocks.insert(
+                            index,
+                            Block::ToolUse {
+                                id: str_field("id"),
+                                name: str_field("name"),
+                                json: String::new(),
+                            },
+                        );
+                    }
+                    other => debug!("Ignoring Anthropic content block of type {other:?}"),
+                }
+            }
+            "content_block_delta" => {
+                let Some(delta) = event.get("delta") else {
+                    continue;
+                };
+                let str_field = |name: &str| delta.get(name).and_then(Value::as_str);
+                match (blocks.get_mut(&index), str_field("type")) {
+                    (Some(Block::Text(text)), Some("text_delta")) => {
+                        let chunk = str_field("text").unwrap_or_default();
+                        text.push_str(chunk);
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::OutputTextDelta(chunk.to_string())))
+                            .await;
+                    }
+                    (Some(Block::Thinking { text, .. }), Some("thinking_delta")) => {
+                        let chunk = str_field("thinking").unwrap_or_default();
+                        text.push_str(chunk);
+                        let _ = tx_event
+                            .send(Ok(ResponseEvent::ReasoningContentDelta {
+                                delta: chunk.to_string(),
+                                content_index: 0,
+                            }))
+                            .await;
+                    }
+                    (Some(Block::Thinking { signature, .. }), Some("signature_delta")) => {
+                        signature.push_str(str_field("signature").unwrap_or_default());
+                    }
+                    (Some(Block::ToolUse { json, .. }), Some("input_json_delta")) => {
+                        json.push_str(str_field("partial_json").unwrap_or_default());
+                    }
+                    _ => {}
+                }
+            }
+            "content_block_stop" => {
+                let item = match blocks.remove(&index) {
+                    Some(Block::Text(text)) => assistant_message(text),
+                    Some(Block::Thinking { text, signature }) => {
+                        reasoning_item(text, Some(signature).filter(|s| !s.is_empty()))
+                    }
+                    Some(Block::ToolUse { id, name, json }) => ResponseItem::FunctionCall {
+                        id: None,
+                        name,
+                        arguments: if json.trim().is_empty() {
+                            "{}".to_string()
+                        } else {
+                            json
+                        },
+                        call_id: id,
+                    },
+                    None => continue,
+                };
+                let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+            }
+            "message_delta" => {
+                if let Some(reason) = event
+                    .get("delta")
+                    .and_then(|delta| delta.get("stop_reason"))
+                    .and_then(Value::as_str)
+                {
+                    stop_reason = Some(reason.to_string());
+                }
+                if let Some(value) = event.get("usage") {
+                    usage.update(value);
+                }
+            }
+            "message_stop" => {
+                if stop_reason.as_deref() == Some("model_context_window_exceeded") {
+                    let _ = tx_event.send(Err(ApiError::ContextWindowExceeded)).await;
+                    return;
+                }
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage: Some(usage.to_token_usage()),
+                    }))
+                    .await;
+                return;
+            }
+            "error" => {
+                let _ = tx_event.send(Err(stream_error(&event))).await;
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Maps an in-stream `error` event; overloads are worth retrying.
+fn stream_error(event: &Value) -> ApiError {
+    let error = event.get("error");
+    let kind = error
+        .and_then(|error| error.get("type"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let message = error
+        .and_then(|error| error.get("message"))
+        .and_then(Value::as_str)
+        .unwrap_or("unknown error")
+        .to_string();
+    match kind {
+        "overloaded_error" | "api_error" => ApiError::Retryable {
+            message,
+            delay: None,
+        },
+        "invalid_request_error" if message.contains("prompt is too long") => {
+            ApiError::ContextWindowExceeded
+        }
+        _ => ApiError::Stream(message),
+    }
+}
+
+fn assistant_message(text: String) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "assistant".to_string(),
+        content: if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![ContentItem::OutputText { text }]
+        },
+        end_turn: None,
+    }
+}
+
+fn reasoning_item(text: String, signature: Option<String>) -> ResponseItem {
+    ResponseItem::Reasoning {
+        id: String::new(),
+        summary: Vec::new(),
+        content: Some(if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![ReasoningItemContent::ReasoningText { text }]
+        }),
+        encrypted_content: signature,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tokio_util::io::ReaderStream;
+
+    fn build_body(events: &[Value]) -> String {
+        let mut body = String::new();
+        for e in events {
+            let kind = e["type"].as_str().unwrap_or_default();
+            body.push_str(&format!("event: {kind}\ndata: {e}\n\n"));
+        }
+        body
+    }
+
+    async fn collect_events(body: &str) -> Vec<Result<ResponseEvent, ApiError>> {
+        let reader = ReaderStream::new(std::io::Cursor::new(body.to_string()))
+            .map_err(|err| codex_client::TransportError::Network(err.to_string()));
+        let (tx, mut rx) = mpsc::channel::<Result<ResponseEvent, ApiError>>(16);
+        tokio::spawn(process_anthropic_sse(
+            reader,
+            tx,
+            Duration::from_millis(1000),
+            None,
+        ));
+
+        let mut out = Vec::new();
+        while let Some(ev) = rx.recv().await {
+            out.push(ev);
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn streams_thinking_text_and_tool_use() {
+        let body = build_body(&[
+            json!({"type": "message_start", "message": {"id": "msg_1", "usage": {
+                "input_tokens": 10, "cache_read_input_tokens": 90, "output_tokens": 1,
+            }}}),
+            json!({"type": "content_block_start", "index": 0,
+                "content_block": {"type": "thinking", "thinking": ""}}),
+            json!({"type": "content_block_delta", "index": 0,
+                "delta": {"type": "thinking_delta", "thinking": "plan"}}),
+            json!({"type": "content_block_delta", "index": 0,
+                "delta": {"type": "signature_delta", "signature": "sig"}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "content_block_start", "index": 1,
+                "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 1,
+                "delta": {"type": "text_delta", "text": "Listing"}}),
+            json!({"type": "content_block_stop", "index": 1}),
+            json!({"type": "ping"}),
+            json!({"type": "content_block_start", "index": 2,
+                "content_block": {"type": "tool_use", "id": "toolu_1", "name": "shell", "input": {}}}),
+            json!({"type": "content_block_delta", "index": 2,
+                "delta": {"type": "input_json_delta", "partial_json": "{\"command\":"}}),
+            json!({"type": "content_block_delta", "index": 2,
+                "delta": {"type": "input_json_delta", "partial_json": "[\"ls\"]}"}}),
+            json!({"type": "content_block_stop", "index": 2}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"},
+                "usage": {"output_tokens": 20}}),
+            json!({"type": "message_stop"}),
+        ]);
+
+        let events: Vec<ResponseEvent> = collect_events(&body)
+            .await
+            .into_iter()
+            .map(|ev| ev.expect("stream error"))
+            .collect();
+
+        assert_matches!(
+            &events[..],
+            [
+                ResponseEvent::Created,
+                ResponseEvent::OutputItemAdded(ResponseItem::Reasoning { .. }),
+                ResponseEvent::ReasoningContentDelta { .. },
+                ResponseEvent::OutputItemDone(ResponseItem::Reasoning { encrypted_content: Some(signature), .. }),
+                ResponseEvent::OutputItemAdded(ResponseItem::Message { .. }),
+                ResponseEvent::OutputTextDelta(delta),
+                ResponseEvent::OutputItemDone(ResponseItem::Message { .. }),
+                ResponseEvent::OutputItemDone(ResponseItem::FunctionCall { call_id, name, arguments, .. }),
+                ResponseEvent::Completed { .. },
+            ] if signature == "sig"
+                && delta == "Listing"
+                && call_id == "toolu_1"
+                && name == "shell"
+                && arguments == "{\"command\":[\"ls\"]}"
+        );
+        let Some(ResponseEvent::Completed {
+            response_id,
+            token_usage,
+        }) = events.last()
+        else {
+            panic!("expected completion");
+        };
+        assert_eq!(response_id, "msg_1");
+        assert_eq!(
+            token_usage,
+            &Some(TokenUsage {
+                input_tokens: 100,
+                cached_input_tokens: 90,
+                output_tokens: 20,
+                reasoning_output_tokens: 0,
+                total_tokens: 120,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn overloaded_error_is_retryable() {
+        let body = build_body(&[json!({"type": "error", "error": {
+            "type": "overloaded_error", "message": "Overloaded",
+        }})]);
+
+        let events = collect_events(&body).await;
+        assert_matches!(
+            &events[..],
+            [Err(ApiError::Retryable { message, .. })] if message == "Overloaded"
+        );
+    }
+
+    #[tokio::test]
+    async fn truncated_stream_is_an_error() {
+        let body = build_body(&[json!({"type": "message_start", "message": {"id": "msg_1"}})]);
+
+        let events = collect_events(&body).await;
+        assert_matches!(
+            &events[..],
+            [Ok(ResponseEvent::Created), Err(ApiError::Stream(_))]
+        );
+    }
+}
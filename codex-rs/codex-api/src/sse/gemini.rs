@@ -0,0 +1,374 @@
+use crate::common::ResponseEvent;
+use crate::common::ResponseStream;
+use crate::error::ApiError;
+use crate::telemetry::SseTelemetry;
+use codex_client::StreamResponse;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::TokenUsage;
+use eventsource_stream::Eventsource;
+use futures::Stream;
+use futures::StreamExt;
+use serde_json::Value;
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio::time::timeout;
+use tracing::debug;
+use tracing::trace;
+
+pub(crate) fn spawn_gemini_stream(
+    stream_response: StreamResponse,
+    idle_timeout: Duration,
+    telemetry: Option<Arc<dyn SseTelemetry>>,
+    _turn_state: Option<Arc<OnceLock<String>>>,
+) -> ResponseStream {
+    let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
+    tokio::spawn(async move {
+        process_gemini_sse(stream_response.bytes, tx_event, idle_timeout, telemetry).await;
+    });
+    ResponseStream { rx_event }
+}
+
+/// Processes Server-Sent Events from Gemini's `streamGenerateContent?alt=sse`.
+///
+/// Each event is a partial `GenerateContentResponse`. Text parts stream as
+/// deltas of one assistant message; `functionCall` parts always arrive whole
+/// and become `FunctionCall` items. Gemini does not id its calls, so call ids
+/// are minted from the response id. There is no terminal event: the stream
+/// completes when the connection closes, unless a candidate finished for a
+/// safety (or similar) reason, which is surfaced as an error.
+pub async fn process_gemini_sse<S>(
+    stream: S,
+    tx_event: mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    idle_timeout: Duration,
+    telemetry: Option<Arc<dyn SseTelemetry>>,
+) where
+    S: Stream<Item = Result<bytes::Bytes, codex_client::TransportError>> + Unpin,
+{
+    let mut stream = stream.eventsource();
+    let mut assistant_text: Option<String> = None;
+    let mut response_id = String::new();
+    let mut call_count = 0usize;
+    let mut token_usage: Option<TokenUsage> = None;
+
+    loop {
+        let start = Instant::now();
+        let response = timeout(idle_timeout, stream.next()).await;
+        if let Some(t) = telemetry.as_ref() {
+            t.on_sse_poll(&response, start.elapsed());
+        }
+        let sse = match response {
+            Ok(Some(Ok(sse))) => sse,
+            Ok(Some(Err(e))) => {
+                let _ = tx_event.send(Err(ApiError::Stream(e.to_string()))).await;
+                return;
+            }
+            Ok(None) => {
+                flush_assistant(&tx_event, &mut assistant_text).await;
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::Completed {
+                        response_id,
+                        token_usage,
+                    }))
+                    .await;
+                return;
+            }
+            Err(_) => {
+                let _ = tx_event
+                    .send(Err(ApiError::Stream("idle timeout waiting for SSE".into())))
+                    .await;
+                return;
+            }
+        };
+
+        trace!("SSE event: {}", sse.data);
+
+        let chunk: Value = match serde_json::from_str(&sse.data) {
+            Ok(chunk) => chunk,
+            Err(err) => {
+                debug!(
+                    "Failed to parse Gemini SSE event: {err}, data: {}",
+                    sse.data
+                );
+                continue;
+            }
+        };
+
+        if let Some(error) = chunk.get("error") {
+            let _ = tx_event.send(Err(stream_error(error))).await;
+            return;
+        }
+        if let Some(reason) = chunk
+            .get("promptFeedback")
+            .and_then(|feedback| feedback.get("blockReason"))
+            .and_then(Value::as_str)
+        {
+            let _ = tx_event
+                .send(Err(ApiError::InvalidRequest {
+                    message: format!("Gemini blocked the prompt: {reason}"),
+                }))
+                .await;
+            return;
+        }
+        if response_id.is_empty() {
+            response_id = chunk
+                .get("responseId")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| {
+                    let nanos = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|elapsed| elapsed.as_nanos())
+                        .unwrap_or_default();
+                    format!("gemini-{nanos}")
+                });
+        }
+        if let Some(usage) = chunk.get("usageMetadata") {
+            token_usage = Some(to_token_usage(usage));
+        }
+
+        let Some(candidate) = chunk
+            .get("candidates")
+            .and_then(Value::as_array)
+            .and_then(|candidates| candidates.first())
+        else {
+            continue;
+        };
+        let parts = candidate
+            .get("content")
+            .and_then(|content| content.get("parts"))
+            .and_then(Value::as_array);
+        for part in parts.into_iter().flatten() {
+            // Thought summaries are only sent when asked for; skip them.
+            if part.get("thought").and_then(Value::as_bool) == Some(true) {
+                continue;
+            }
+            if let Some(text) = part.get("text").and_then(Value::as_str) {
+                if assistant_text.is_none() {
+                    let item = assistant_message(String::new());
+                    let _ = tx_event
+                        .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                        .await;
+                }
+                assistant_text.get_or_insert_default().push_str(text);
+                let _ = tx_event
+                    .send(Ok(ResponseEvent::OutputTextDelta(text.to_string())))
+                    .await;
+            }
+            if let Some(call) = part.get("functionCall") {
+                flush_assistant(&tx_event, &mut assistant_text).await;
+                let call_id = call
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("{response_id}-{call_count}"));
+                call_count += 1;
+                let item = ResponseItem::FunctionCall {
+                    id: None,
+                    name: call
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string(),
+                    arguments: call
+                        .get("args")
+                        .map(Value::to_string)
+                        .unwrap_or_else(|| "{}".to_string()),
+                    call_id,
+                };
+                let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
+            }
+        }
+
+        match candidate.get("finishReason").and_then(Value::as_str) {
+            None | Some("STOP") | Some("MAX_TOKENS") | Some("FINISH_REASON_UNSPECIFIED") => {}
+            Some("MALFORMED_FUNCTION_CALL") => {
+                let _ = tx_event
+                    .send(Err(ApiError::Retryable {
+                        message: "Gemini produced a malformed function call".to_string(),
+                        delay: None,
+                    }))
+                    .await;
+                return;
+            }
+            Some(reason) => {
+                let _ = tx_event
+                    .send(Err(ApiError::InvalidRequest {
+                        message: format!("Gemini stopped the response: {reason}"),
+                    }))
+                    .await;
+                return;
+            }
+        }
+    }
+}
+
+async fn flush_assistant(
+    tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    assistant_text: &mut Option<String>,
+) {
+    if let Some(text) = assistant_text.take() {
+        let _ = tx_event
+            .send(Ok(ResponseEvent::OutputItemDone(assistant_message(text))))
+            .await;
+    }
+}
+
+/// Maps an in-stream `error` object; quota and availability errors are worth
+/// retrying.
+fn stream_error(error: &Value) -> ApiError {
+    let message = error
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown error")
+        .to_string();
+    match error.get("status").and_then(Value::as_str) {
+        Some("RESOURCE_EXHAUSTED" | "UNAVAILABLE" | "INTERNAL") => ApiError::Retryable {
+            message,
+            delay: None,
+        },
+        _ => ApiError::Stream(message),
+    }
+}
+
+/// Gemini counts thinking separately from candidate tokens; fold it into the
+/// output like the Responses API does.
+fn to_token_usage(usage: &Value) -> TokenUsage {
+    let field = |name: &str| usage.get(name).and_then(Value::as_i64).unwrap_or(0);
+    let input_tokens = field("promptTokenCount");
+    let reasoning_output_tokens = field("thoughtsTokenCount");
+    let output_tokens = field("candidatesTokenCount") + reasoning_output_tokens;
+    TokenUsage {
+        input_tokens,
+        cached_input_tokens: field("cachedContentTokenCount"),
+        output_tokens,
+        reasoning_output_tokens,
+        total_tokens: input_tokens + output_tokens,
+    }
+}
+
+fn assistant_message(text: String) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "assistant".to_string(),
+        content: if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![ContentItem::OutputText { text }]
+        },
+        end_turn: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use futures::TryStreamExt;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tokio_util::io::ReaderStream;
+
+    fn build_body(chunks: &[Value]) -> String {
+        let mut body = String::new();
+        for chunk in chunks {
+            body.push_str(&format!("data: {chunk}\n\n"));
+        }
+        body
+    }
+
+    async fn collect_events(body: &str) -> Vec<Result<ResponseEvent, ApiError>> {
+        let reader = ReaderStream::new(std::io::Cursor::new(body.to_string()))
+            .map_err(|err| codex_client::TransportError::Network(err.to_string()));
+        let (tx, mut rx) = mpsc::channel::<Result<ResponseEvent, ApiError>>(16);
+        tokio::spawn(process_gemini_sse(
+            reader,
+            tx,
+            Duration::from_millis(1000),
+            None,
+        ));
+
+        let mut out = Vec::new();
+        while let Some(ev) = rx.recv().await {
+            out.push(ev);
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn streams_text_then_function_call() {
+        let body = build_body(&[
+            json!({"responseId": "r1", "candidates": [{"content": {"role": "model", "parts": [
+                {"text": "Listing"},
+            ]}}]}),
+            json!({"responseId": "r1", "candidates": [{"content": {"role": "model", "parts": [
+                {"text": " files"},
+                {"functionCall": {"name": "shell", "args": {"command": ["ls"]}}},
+            ]}, "finishReason": "STOP"}], "usageMetadata": {
+                "promptTokenCount": 100,
+                "cachedContentTokenCount": 40,
+                "candidatesTokenCount": 8,
+                "thoughtsTokenCount": 2,
+            }}),
+        ]);
+
+        let events: Vec<ResponseEvent> = collect_events(&body)
+            .await
+            .into_iter()
+            .map(|ev| ev.expect("stream error"))
+            .collect();
+
+        assert_matches!(
+            &events[..],
+            [
+                ResponseEvent::OutputItemAdded(ResponseItem::Message { .. }),
+                ResponseEvent::OutputTextDelta(_),
+                ResponseEvent::OutputTextDelta(_),
+                ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. }),
+                ResponseEvent::OutputItemDone(ResponseItem::FunctionCall { call_id, name, arguments, .. }),
+                ResponseEvent::Completed { .. },
+            ] if content == &vec![ContentItem::OutputText { text: "Listing files".to_string() }]
+                && call_id == "r1-0"
+                && name == "shell"
+                && arguments == r#"{"command":["ls"]}"#
+        );
+        let Some(ResponseEvent::Completed {
+            response_id,
+            token_usage,
+        }) = events.last()
+        else {
+            panic!("expected completion");
+        };
+        assert_eq!(response_id, "r1");
+        assert_eq!(
+            token_usage,
+            &Some(TokenUsage {
+                input_tokens: 100,
+                cached_input_tokens: 40,
+                output_tokens: 10,
+                reasoning_output_tokens: 2,
+                total_tokens: 110,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn safety_stop_is_an_error() {
+        let body = build_body(&[json!({"responseId": "r1", "candidates": [{
+            "content": {"role": "model", "parts": []},
+            "finishReason": "SAFETY",
+        }]})]);
+
+        let events = collect_events(&body).await;
+        assert_matches!(
+            &events[..],
+            [Err(ApiError::InvalidRequest { message })]
+                if message == "Gemini stopped the response: SAFETY"
+        );
+    }
+}
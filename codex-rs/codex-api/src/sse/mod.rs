@@ -1,4 +1,6 @@
+pub mod anthropic;
 pub mod chat;
+pub mod gemini;
 pub mod responses;
 
 pub use responses::process_sse;
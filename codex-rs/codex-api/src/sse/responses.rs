@@ -1,6 +1,7 @@
 use crate::common::ResponseEvent;
 use crate::common::ResponseStream;
 use crate::error::ApiError;
+use crate::rate_limits::parse_provider_quota;
 use crate::rate_limits::parse_rate_limit;
 use crate::telemetry::SseTelemetry;
 use codex_client::ByteStream;
@@ -55,6 +56,7 @@ pub fn spawn_response_stream(
     turn_state: Option<Arc<OnceLock<String>>>,
 ) -> ResponseStream {
     let rate_limits = parse_rate_limit(&stream_response.headers);
+    let provider_quota = parse_provider_quota(&stream_response.headers);
     let models_etag = stream_response
         .headers
         .get("X-Models-Etag")
@@ -77,6 +79,9 @@ pub fn spawn_response_stream(
         if let Some(snapshot) = rate_limits {
             let _ = tx_event.send(Ok(ResponseEvent::RateLimits(snapshot))).await;
         }
+        if let Some(quota) = provider_quota {
+            let _ = tx_event.send(Ok(ResponseEvent::ProviderQuota(quota))).await;
+        }
         if let Some(etag) = models_etag {
             let _ = tx_event.send(Ok(ResponseEvent::ModelsEtag(etag))).await;
         }
@@ -1,5 +1,6 @@
 mod default_client;
 mod error;
+mod network;
 mod request;
 mod retry;
 mod sse;
@@ -10,6 +11,12 @@ pub use crate::default_client::CodexHttpClient;
 pub use crate::default_client::CodexRequestBuilder;
 pub use crate::error::StreamError;
 pub use crate::error::TransportError;
+pub use crate::network::NetworkSettings;
+pub use crate::network::NetworkSettingsError;
+pub use crate::network::ProxyBasicAuth;
+pub use crate::network::ProxySettings;
+pub use crate::network::apply_network_settings;
+pub use crate::network::set_network_settings;
 pub use crate::request::Request;
 pub use crate::request::RequestCompression;
 pub use crate::request::Response;
@@ -0,0 +1,204 @@
+//! Process-wide proxy and CA settings for every HTTP client Codex builds.
+//!
+//! Codex talks to the model API, MCP servers and the login endpoints through
+//! separately constructed `reqwest` clients. Behind a corporate proxy or a
+//! TLS-intercepting gateway all of them need the same proxy and extra root
+//! certificates, so the settings are installed once at startup with
+//! [`set_network_settings`] and applied by [`apply_network_settings`] wherever
+//! a client builder is created.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::RwLock;
+
+use reqwest::Certificate;
+use reqwest::ClientBuilder;
+use reqwest::NoProxy;
+use reqwest::Proxy;
+use thiserror::Error;
+
+/// Proxy and CA configuration; the default leaves `reqwest`'s behavior
+/// (including `HTTPS_PROXY` and friends) untouched.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkSettings {
+    pub proxy: Option<ProxySettings>,
+    /// PEM files whose certificates are trusted in addition to the system
+    /// roots.
+    pub ca_certificates: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxySettings {
+    /// Used for both HTTP and HTTPS requests.
+    pub url: String,
+    /// Hosts that bypass the proxy, in `NO_PROXY` syntax.
+    pub no_proxy: Vec<String>,
+    /// Basic authentication; credentials in the URL work as well.
+    pub basic_auth: Option<ProxyBasicAuth>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProxyBasicAuth {
+    pub username: String,
+    pub password: String,
+}
+
+impl std::fmt::Debug for ProxyBasicAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyBasicAuth")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum NetworkSettingsError {
+    #[error("invalid proxy URL `{url}`: {source}")]
+    InvalidProxy { url: String, source: reqwest::Error },
+    #[error("failed to read CA certificate {}: {source}", path.display())]
+    ReadCertificate {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid CA certificate {}: {reason}", path.display())]
+    InvalidCertificate { path: PathBuf, reason: String },
+}
+
+#[derive(Clone, Default)]
+struct LoadedNetworkSettings {
+    proxy: Option<Proxy>,
+    certificates: Vec<Certificate>,
+}
+
+static NETWORK_SETTINGS: LazyLock<RwLock<LoadedNetworkSettings>> =
+    LazyLock::new(|| RwLock::new(LoadedNetworkSettings::default()));
+
+/// Validates `settings` (proxy URL, readable PEM files) and installs them for
+/// clients built from now on.
+pub fn set_network_settings(settings: &NetworkSettings) -> Result<(), NetworkSettingsError> {
+    let loaded = load(settings)?;
+    if let Ok(mut guard) = NETWORK_SETTINGS.write() {
+        *guard = loaded;
+    }
+    Ok(())
+}
+
+/// Applies the installed proxy and CA certificates to `builder`. An explicit
+/// proxy replaces the environment and system proxies, even when `builder`
+/// had them turned off with `no_proxy()`.
+pub fn apply_network_settings(mut builder: ClientBuilder) -> ClientBuilder {
+    let settings = match NETWORK_SETTINGS.read() {
+        Ok(guard) => guard.clone(),
+        Err(_) => return builder,
+    };
+    if let Some(proxy) = settings.proxy {
+        builder = builder.proxy(proxy);
+    }
+    for certificate in settings.certificates {
+        builder = builder.add_root_certificate(certificate);
+    }
+    builder
+}
+
+fn load(settings: &NetworkSettings) -> Result<LoadedNetworkSettings, NetworkSettingsError> {
+    let proxy = settings.proxy.as_ref().map(load_proxy).transpose()?;
+    let mut certificates = Vec::new();
+    for path in &settings.ca_certificates {
+        certificates.extend(load_certificates(path)?);
+    }
+    Ok(LoadedNetworkSettings {
+        proxy,
+        certificates,
+    })
+}
+
+fn load_proxy(settings: &ProxySettings) -> Result<Proxy, NetworkSettingsError> {
+    let mut proxy =
+        Proxy::all(settings.url.as_str()).map_err(|source| NetworkSettingsError::InvalidProxy {
+            url: settings.url.clone(),
+            source,
+        })?;
+    if let Some(auth) = &settings.basic_auth {
+        proxy = proxy.basic_auth(&auth.username, &auth.password);
+    }
+    let no_proxy = if settings.no_proxy.is_empty() {
+        NoProxy::from_env()
+    } else {
+        NoProxy::from_string(&settings.no_proxy.join(","))
+    };
+    Ok(proxy.no_proxy(no_proxy))
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<Certificate>, NetworkSettingsError> {
+    let pem = std::fs::read(path).map_err(|source| NetworkSettingsError::ReadCertificate {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let certificates = Certificate::from_pem_bundle(&pem).map_err(|err| {
+        NetworkSettingsError::InvalidCertificate {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        }
+    })?;
+    if certificates.is_empty() {
+        return Err(NetworkSettingsError::InvalidCertificate {
+            path: path.to_path_buf(),
+            reason: "no PEM certificates found".to_string(),
+        });
+    }
+    Ok(certificates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_unreadable_and_empty_certificate_files() {
+        let dir = std::env::temp_dir().join(format!("codex-network-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let empty = dir.join("empty.pem");
+        std::fs::write(&empty, "not a certificate\n").expect("write pem");
+
+        let missing = NetworkSettings {
+            proxy: None,
+            ca_certificates: vec![dir.join("missing.pem")],
+        };
+        assert!(matches!(
+            load(&missing),
+            Err(NetworkSettingsError::ReadCertificate { .. })
+        ));
+
+        let invalid = NetworkSettings {
+            proxy: None,
+            ca_certificates: vec![empty.clone()],
+        };
+        let Err(NetworkSettingsError::InvalidCertificate { path, .. }) = load(&invalid) else {
+            panic!("expected an invalid certificate error");
+        };
+        assert_eq!(path, empty);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn proxy_url_is_validated() {
+        let settings = |url: &str| NetworkSettings {
+            proxy: Some(ProxySettings {
+                url: url.to_string(),
+                no_proxy: vec!["localhost".to_string()],
+                basic_auth: Some(ProxyBasicAuth {
+                    username: "alice".to_string(),
+                    password: "secret".to_string(),
+                }),
+            }),
+            ca_certificates: Vec::new(),
+        };
+
+        assert!(load(&settings("http://proxy.corp:8080")).is_ok());
+        assert!(matches!(
+            load(&settings("http://[bad")),
+            Err(NetworkSettingsError::InvalidProxy { .. })
+        ));
+    }
+}
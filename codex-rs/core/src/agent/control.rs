@@ -2,10 +2,13 @@ use crate::agent::AgentStatus;
 use crate::agent::guards::Guards;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::thread_manager::NewThread;
 use crate::thread_manager::ThreadManagerState;
 use codex_protocol::ThreadId;
 use codex_protocol::protocol::Op;
+use codex_protocol::protocol::TokenUsage;
 use codex_protocol::user_input::UserInput;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Weak;
 use tokio::sync::watch;
@@ -57,6 +60,28 @@ impl AgentControl {
         Ok(new_thread.thread_id)
     }
 
+    /// Fork the thread recorded at `rollout_path` into a new thread that keeps
+    /// the history before the `nth_user_message`-th user message. The fork is
+    /// a regular thread, so it does not take a spawn slot.
+    pub(crate) async fn fork_thread(
+        &self,
+        config: crate::config::Config,
+        rollout_path: PathBuf,
+        nth_user_message: usize,
+    ) -> CodexResult<NewThread> {
+        let state = self.upgrade()?;
+        let new_thread = state
+            .fork_thread(
+                nth_user_message,
+                config,
+                rollout_path,
+                AgentControl::new(self.manager.clone()),
+            )
+            .await?;
+        state.notify_thread_created(new_thread.thread_id);
+        Ok(new_thread)
+    }
+
     /// Send a `user` prompt to an existing agent thread.
     pub(crate) async fn send_prompt(
         &self,
@@ -111,6 +136,13 @@ impl AgentControl {
         thread.agent_status().await
     }
 
+    /// Token totals `agent_id` has reported so far, if it is still running.
+    pub(crate) async fn get_token_usage(&self, agent_id: ThreadId) -> Option<TokenUsage> {
+        let state = self.upgrade().ok()?;
+        let thread = state.get_thread(agent_id).await.ok()?;
+        thread.total_token_usage()
+    }
+
     /// Subscribe to status updates for `agent_id`, yielding the latest value and changes.
     pub(crate) async fn subscribe_status(
         &self,
@@ -231,6 +263,7 @@ mod tests {
     async fn on_event_updates_status_from_task_complete() {
         let status = agent_status_from_event(&EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: Some("done".to_string()),
+            metadata: None,
         }));
         let expected = AgentStatus::Completed(Some("done".to_string()));
         assert_eq!(status, Some(expected));
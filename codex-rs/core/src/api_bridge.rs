@@ -3,12 +3,16 @@ use chrono::Utc;
 use codex_api::AuthProvider as ApiAuthProvider;
 use codex_api::TransportError;
 use codex_api::error::ApiError;
+use codex_api::rate_limits::parse_provider_quota;
 use codex_api::rate_limits::parse_rate_limit;
+use codex_api::rate_limits::parse_retry_after;
 use http::HeaderMap;
 use serde::Deserialize;
+use std::path::Path;
 
 use crate::auth::CodexAuth;
 use crate::error::CodexErr;
+use crate::error::RateLimitedError;
 use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
 use crate::error::UsageLimitReachedError;
@@ -66,8 +70,9 @@ pub(crate) fn map_api_error(err: ApiError) -> CodexErr {
                         }
                     }
 
-                    CodexErr::RetryLimit(RetryLimitReachedError {
-                        status,
+                    CodexErr::RateLimited(RateLimitedError {
+                        retry_after: headers.as_ref().and_then(parse_retry_after),
+                        quota: headers.as_ref().and_then(parse_provider_quota),
                         request_id: extract_request_id(headers.as_ref()),
                     })
                 } else {
@@ -104,6 +109,23 @@ fn extract_request_id(headers: Option<&HeaderMap>) -> Option<String> {
     })
 }
 
+/// Like [`auth_provider_from_auth`], but providers configured with `entra_id`
+/// authenticate with a Microsoft Entra ID access token.
+pub(crate) async fn auth_provider_for_request(
+    auth: Option<CodexAuth>,
+    provider: &ModelProviderInfo,
+    codex_home: &Path,
+) -> crate::error::Result<CoreAuthProvider> {
+    if let Some(entra_id) = &provider.entra_id {
+        let token = crate::auth::entra::access_token(entra_id, codex_home).await?;
+        return Ok(CoreAuthProvider {
+            token: Some(token),
+            account_id: None,
+        });
+    }
+    auth_provider_from_auth(auth, provider)
+}
+
 pub(crate) fn auth_provider_from_auth(
     auth: Option<CodexAuth>,
     provider: &ModelProviderInfo,
@@ -1,12 +1,22 @@
+use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
+use crate::plan_scope;
 use crate::protocol::FileChange;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::MaybeApplyPatchVerified;
+use codex_git::GhostCommit;
+use codex_protocol::approvals::ApprovalSource;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::AskForApproval;
+use codex_utils_absolute_path::AbsolutePathBuf;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 
 pub const CODEX_APPLY_PATCH_ARG1: &str = "--codex-run-as-apply-patch";
@@ -31,9 +41,102 @@ pub(crate) struct ApplyPatchExec {
     pub(crate) action: ApplyPatchAction,
     pub(crate) auto_approved: bool,
     pub(crate) exec_approval_requirement: ExecApprovalRequirement,
+    /// Files outside the approved plan that running the patch adds to its
+    /// scope (`plan_scope`).
+    pub(crate) scope_expansion: Vec<AbsolutePathBuf>,
+}
+
+/// Verifies an `apply_patch` invocation. With [`Feature::PatchMerge`] on,
+/// updates that no longer match their file are merged against the turn's
+/// ghost snapshot, which is the copy the model most likely read.
+pub(crate) async fn verify_apply_patch(
+    session: &Session,
+    turn: &TurnContext,
+    command: &[String],
+    cwd: &Path,
+) -> MaybeApplyPatchVerified {
+    let snapshot = if session.features().enabled(Feature::PatchMerge) {
+        turn_snapshot(session, turn).await
+    } else {
+        None
+    };
+    let Some(ghost_commit) = snapshot else {
+        return codex_apply_patch::maybe_parse_apply_patch_verified(command, cwd);
+    };
+
+    let repo_path = turn.cwd.clone();
+    let argv = command.to_vec();
+    let patch_cwd = cwd.to_path_buf();
+    let merged = tokio::task::spawn_blocking(move || {
+        let base = |path: &Path| {
+            codex_git::read_path_from_ghost_commit(&repo_path, &ghost_commit, path)
+                .ok()
+                .flatten()
+        };
+        codex_apply_patch::maybe_parse_apply_patch_verified_with_base(&argv, &patch_cwd, &base)
+    })
+    .await;
+    match merged {
+        Ok(verified) => verified,
+        Err(err) => {
+            tracing::warn!("apply_patch merge verification failed: {err}");
+            codex_apply_patch::maybe_parse_apply_patch_verified(command, cwd)
+        }
+    }
+}
+
+/// The snapshot taken at the start of this turn, or the latest one in
+/// history when the turn did not record its own.
+async fn turn_snapshot(session: &Session, turn: &TurnContext) -> Option<GhostCommit> {
+    if let Some(ghost_commit) = session.services.turn_snapshots.get(&turn.sub_id).await {
+        return Some(ghost_commit);
+    }
+    session
+        .clone_history()
+        .await
+        .raw_items()
+        .iter()
+        .rev()
+        .find_map(|item| match item {
+            ResponseItem::GhostSnapshot { ghost_commit } => Some(ghost_commit.clone()),
+            _ => None,
+        })
 }
 
 pub(crate) async fn apply_patch(
+    session: &Session,
+    turn_context: &TurnContext,
+    action: ApplyPatchAction,
+) -> InternalApplyPatchInvocation {
+    let invocation = assess_patch(turn_context, action);
+    if !session.features().enabled(Feature::PlanScope) {
+        return invocation;
+    }
+    let InternalApplyPatchInvocation::DelegateToExec(mut apply) = invocation else {
+        return invocation;
+    };
+    let Some(scope) = session.plan_scope().await else {
+        return InternalApplyPatchInvocation::DelegateToExec(apply);
+    };
+    let outside = plan_scope::files_outside_scope(&apply.action, &scope);
+    if outside.is_empty() {
+        return InternalApplyPatchInvocation::DelegateToExec(apply);
+    }
+    if turn_context.approval_policy == AskForApproval::Never {
+        return InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+            plan_scope::rejected_message(&outside, &turn_context.cwd),
+        )));
+    }
+    apply.auto_approved = false;
+    apply.exec_approval_requirement = ExecApprovalRequirement::NeedsApproval {
+        reason: Some(plan_scope::approval_reason(&outside, &turn_context.cwd)),
+        proposed_execpolicy_amendment: None,
+    };
+    apply.scope_expansion = outside;
+    InternalApplyPatchInvocation::DelegateToExec(apply)
+}
+
+fn assess_patch(
     turn_context: &TurnContext,
     action: ApplyPatchAction,
 ) -> InternalApplyPatchInvocation {
@@ -51,8 +154,12 @@ pub(crate) async fn apply_patch(
             auto_approved: !user_explicitly_approved,
             exec_approval_requirement: ExecApprovalRequirement::Skip {
                 bypass_sandbox: false,
+                source: ApprovalSource::ModeDefault {
+                    approval_policy: turn_context.approval_policy,
+                },
                 proposed_execpolicy_amendment: None,
             },
+            scope_expansion: Vec::new(),
         }),
         SafetyCheck::AskUser => {
             // Delegate the approval prompt (including cached approvals) to the
@@ -65,6 +172,7 @@ pub(crate) async fn apply_patch(
                     reason: None,
                     proposed_execpolicy_amendment: None,
                 },
+                scope_expansion: Vec::new(),
             })
         }
         SafetyCheck::Reject { reason } => InternalApplyPatchInvocation::Output(Err(
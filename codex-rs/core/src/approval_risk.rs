@@ -0,0 +1,139 @@
+//! Coarse risk classification attached to approval requests.
+//!
+//! The level is a hint for approval UIs (which prompt to highlight, which
+//! ones deserve a "details" expander); it never changes whether an approval
+//! is requested or how the decision is applied.
+
+use std::collections::HashMap;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::ApprovalRisk;
+use codex_protocol::protocol::FileChange;
+use codex_protocol::protocol::SandboxPolicy;
+
+use crate::command_safety::is_dangerous_command::command_might_be_dangerous;
+use crate::command_safety::is_safe_command::is_known_safe_command;
+
+/// Classifies a command awaiting approval. `read_only` is the exec policy's
+/// verdict for the command.
+pub(crate) fn command_risk(
+    command: &[String],
+    read_only: bool,
+    sandbox_policy: &SandboxPolicy,
+) -> ApprovalRisk {
+    if command_might_be_dangerous(command) {
+        ApprovalRisk::High
+    } else if read_only || is_known_safe_command(command) {
+        ApprovalRisk::Low
+    } else if matches!(sandbox_policy, SandboxPolicy::DangerFullAccess) {
+        ApprovalRisk::High
+    } else {
+        ApprovalRisk::Medium
+    }
+}
+
+/// Classifies a patch awaiting approval: deletions and writes outside `cwd`
+/// are high, patches that only add files under `cwd` are low.
+pub(crate) fn patch_risk(changes: &HashMap<PathBuf, FileChange>, cwd: &Path) -> ApprovalRisk {
+    let outside_cwd = |path: &Path| {
+        path.components().any(|c| matches!(c, Component::ParentDir))
+            || !cwd.join(path).starts_with(cwd)
+    };
+    let mut only_adds = true;
+    for (path, change) in changes {
+        let destination = match change {
+            FileChange::Add { .. } => None,
+            FileChange::Delete { .. } => return ApprovalRisk::High,
+            FileChange::Update { move_path, .. } => {
+                only_adds = false;
+                move_path.as_deref()
+            }
+        };
+        if outside_cwd(path) || destination.is_some_and(outside_cwd) {
+            return ApprovalRisk::High;
+        }
+    }
+    if only_adds {
+        ApprovalRisk::Low
+    } else {
+        ApprovalRisk::Medium
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn cmd(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn command_risk_levels() {
+        let workspace = SandboxPolicy::new_workspace_write_policy();
+        assert_eq!(
+            command_risk(&cmd(&["ls", "-la"]), false, &workspace),
+            ApprovalRisk::Low
+        );
+        assert_eq!(
+            command_risk(&cmd(&["cargo", "test"]), false, &workspace),
+            ApprovalRisk::Medium
+        );
+        assert_eq!(
+            command_risk(
+                &cmd(&["cargo", "test"]),
+                false,
+                &SandboxPolicy::DangerFullAccess
+            ),
+            ApprovalRisk::High
+        );
+        assert_eq!(
+            command_risk(&cmd(&["git", "reset", "--hard"]), false, &workspace),
+            ApprovalRisk::High
+        );
+    }
+
+    #[test]
+    fn patch_risk_levels() {
+        let cwd = Path::new("/repo");
+        let add = FileChange::Add {
+            content: "x\n".to_string(),
+        };
+        let update = FileChange::Update {
+            unified_diff: String::new(),
+            move_path: None,
+        };
+
+        let adds_only = HashMap::from([(PathBuf::from("/repo/new.txt"), add.clone())]);
+        assert_eq!(patch_risk(&adds_only, cwd), ApprovalRisk::Low);
+
+        let update_inside = HashMap::from([
+            (PathBuf::from("/repo/new.txt"), add),
+            (PathBuf::from("src/lib.rs"), update.clone()),
+        ]);
+        assert_eq!(patch_risk(&update_inside, cwd), ApprovalRisk::Medium);
+
+        let update_outside = HashMap::from([(PathBuf::from("/etc/hosts"), update)]);
+        assert_eq!(patch_risk(&update_outside, cwd), ApprovalRisk::High);
+
+        let move_outside = HashMap::from([(
+            PathBuf::from("/repo/a.txt"),
+            FileChange::Update {
+                unified_diff: String::new(),
+                move_path: Some(PathBuf::from("/tmp/a.txt")),
+            },
+        )]);
+        assert_eq!(patch_risk(&move_outside, cwd), ApprovalRisk::High);
+
+        let delete = HashMap::from([(
+            PathBuf::from("/repo/a.txt"),
+            FileChange::Delete {
+                content: "x\n".to_string(),
+            },
+        )]);
+        assert_eq!(patch_risk(&delete, cwd), ApprovalRisk::High);
+    }
+}
@@ -0,0 +1,559 @@
+//! Append-only audit log of agent actions (`[audit]`).
+//!
+//! Every command a session runs, every file a patch writes, every approval
+//! decision and every subagent run is appended as one JSON line to
+//! `$CODEX_HOME/audit/audit.jsonl`, with the time, the session id and the
+//! outcome. Records are never rewritten: once the file reaches
+//! `max_file_bytes` it is renamed to `audit-<timestamp>.jsonl` and a new one
+//! is started, and only rotated files beyond `max_files` are deleted. All
+//! Codex processes sharing a `CODEX_HOME` append to the same file under an
+//! exclusive lock on `audit.lock`. `codex audit show` reads the log back.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ThreadId;
+use codex_protocol::approvals::ApprovalJournalEntry;
+use codex_protocol::approvals::ApprovalSource;
+use codex_protocol::protocol::AgentStatus;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ExecCommandSource;
+use codex_protocol::protocol::FileChange;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::config::types::AuditConfig;
+use crate::parse_command::shlex_join;
+
+/// Directory under `CODEX_HOME` holding the log.
+pub const AUDIT_DIR: &str = "audit";
+const AUDIT_FILE: &str = "audit.jsonl";
+const LOCK_FILE: &str = "audit.lock";
+const ROTATED_PREFIX: &str = "audit-";
+
+/// One line of the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// RFC 3339 timestamp.
+    pub ts: String,
+    pub session_id: String,
+    #[serde(flatten)]
+    pub action: AuditAction,
+    pub outcome: AuditOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A command finished.
+    Exec {
+        call_id: String,
+        turn_id: String,
+        command: Vec<String>,
+        cwd: PathBuf,
+        source: ExecCommandSource,
+        exit_code: i32,
+        duration_ms: u64,
+    },
+    /// A patch was applied (or failed to apply).
+    PatchApply {
+        call_id: String,
+        turn_id: String,
+        files: Vec<PathBuf>,
+    },
+    /// A file written by a successfully applied patch.
+    FileWrite {
+        call_id: String,
+        turn_id: String,
+        path: PathBuf,
+        change: FileWriteKind,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        moved_to: Option<PathBuf>,
+    },
+    /// A tool call was allowed or refused, by the user or automatically.
+    Approval {
+        call_id: String,
+        turn_id: String,
+        tool: String,
+        subject: String,
+        source: ApprovalSource,
+    },
+    /// A subagent run started.
+    SubagentStart {
+        run_id: String,
+        name: String,
+        prompt: String,
+    },
+    /// A subagent run ended.
+    SubagentEnd {
+        run_id: String,
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileWriteKind {
+    Add,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+    Approved,
+    Denied,
+    Started,
+}
+
+impl AuditAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AuditAction::Exec { .. } => "exec",
+            AuditAction::PatchApply { .. } => "patch_apply",
+            AuditAction::FileWrite { .. } => "file_write",
+            AuditAction::Approval { .. } => "approval",
+            AuditAction::SubagentStart { .. } => "subagent_start",
+            AuditAction::SubagentEnd { .. } => "subagent_end",
+        }
+    }
+
+    /// One-line description for `codex audit show`.
+    pub fn summary(&self) -> String {
+        match self {
+            AuditAction::Exec {
+                command, exit_code, ..
+            } => format!("{} (exit {exit_code})", shlex_join(command)),
+            AuditAction::PatchApply { files, .. } => files
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            AuditAction::FileWrite {
+                path,
+                change,
+                moved_to,
+                ..
+            } => {
+                let change = match change {
+                    FileWriteKind::Add => "add",
+                    FileWriteKind::Update => "update",
+                    FileWriteKind::Delete => "delete",
+                };
+                match moved_to {
+                    Some(dest) => format!("{change} {} -> {}", path.display(), dest.display()),
+                    None => format!("{change} {}", path.display()),
+                }
+            }
+            AuditAction::Approval { tool, subject, .. } => format!("{tool}: {subject}"),
+            AuditAction::SubagentStart { name, .. } => name.clone(),
+            AuditAction::SubagentEnd { name, error, .. } => match error {
+                Some(error) => format!("{name}: {error}"),
+                None => name.clone(),
+            },
+        }
+    }
+}
+
+impl AuditOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Failure => "failure",
+            AuditOutcome::Approved => "approved",
+            AuditOutcome::Denied => "denied",
+            AuditOutcome::Started => "started",
+        }
+    }
+}
+
+/// Per-session writer. Records are queued and appended in order by a
+/// background task so recording never blocks the session.
+pub(crate) struct AuditLog {
+    session_id: ThreadId,
+    tx: mpsc::UnboundedSender<AuditRecord>,
+}
+
+impl AuditLog {
+    pub(crate) fn start(codex_home: &Path, config: AuditConfig, session_id: ThreadId) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<AuditRecord>();
+        let dir = codex_home.join(AUDIT_DIR);
+        tokio::spawn(async move {
+            while let Some(record) = rx.recv().await {
+                let dir = dir.clone();
+                let config = config.clone();
+                let result =
+                    tokio::task::spawn_blocking(move || append_record(&dir, &config, &record))
+                        .await;
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(err)) => warn!("failed to write audit record: {err}"),
+                    Err(err) => warn!("audit writer task failed: {err}"),
+                }
+            }
+        });
+        Self { session_id, tx }
+    }
+
+    pub(crate) fn record(&self, action: AuditAction, outcome: AuditOutcome) {
+        let record = AuditRecord {
+            ts: chrono::Utc::now().to_rfc3339(),
+            session_id: self.session_id.to_string(),
+            action,
+            outcome,
+        };
+        if self.tx.send(record).is_err() {
+            warn!("audit writer stopped; dropping record");
+        }
+    }
+
+    pub(crate) fn record_approval(&self, entry: &ApprovalJournalEntry) {
+        self.record(
+            AuditAction::Approval {
+                call_id: entry.call_id.clone(),
+                turn_id: entry.turn_id.clone(),
+                tool: entry.tool_name.clone(),
+                subject: entry.subject.clone(),
+                source: entry.source.clone(),
+            },
+            if entry.approved {
+                AuditOutcome::Approved
+            } else {
+                AuditOutcome::Denied
+            },
+        );
+    }
+
+    /// Records the actions reported by `msg`, if any.
+    pub(crate) fn observe_event(&self, msg: &EventMsg) {
+        for (action, outcome) in actions_for_event(msg) {
+            self.record(action, outcome);
+        }
+    }
+}
+
+fn actions_for_event(msg: &EventMsg) -> Vec<(AuditAction, AuditOutcome)> {
+    match msg {
+        EventMsg::ExecCommandEnd(event) => vec![(
+            AuditAction::Exec {
+                call_id: event.call_id.clone(),
+                turn_id: event.turn_id.clone(),
+                command: event.command.clone(),
+                cwd: event.cwd.clone(),
+                source: event.source,
+                exit_code: event.exit_code,
+                duration_ms: u64::try_from(event.duration.as_millis()).unwrap_or(u64::MAX),
+            },
+            outcome(event.exit_code == 0),
+        )],
+        EventMsg::PatchApplyEnd(event) => {
+            let mut changes: Vec<(&PathBuf, &FileChange)> = event.changes.iter().collect();
+            changes.sort_by(|a, b| a.0.cmp(b.0));
+            let mut actions = vec![(
+                AuditAction::PatchApply {
+                    call_id: event.call_id.clone(),
+                    turn_id: event.turn_id.clone(),
+                    files: changes.iter().map(|(path, _)| (*path).clone()).collect(),
+                },
+                outcome(event.success),
+            )];
+            if event.success {
+                actions.extend(changes.into_iter().map(|(path, change)| {
+                    let (change, moved_to) = match change {
+                        FileChange::Add { .. } => (FileWriteKind::Add, None),
+                        FileChange::Delete { .. } => (FileWriteKind::Delete, None),
+                        FileChange::Update { move_path, .. } => {
+                            (FileWriteKind::Update, move_path.clone())
+                        }
+                    };
+                    (
+                        AuditAction::FileWrite {
+                            call_id: event.call_id.clone(),
+                            turn_id: event.turn_id.clone(),
+                            path: path.clone(),
+                            change,
+                            moved_to,
+                        },
+                        AuditOutcome::Success,
+                    )
+                }));
+            }
+            actions
+        }
+        EventMsg::SubAgentRunBegin(event) => vec![(
+            AuditAction::SubagentStart {
+                run_id: event.run_id.clone(),
+                name: event.name.clone(),
+                prompt: event.prompt.clone(),
+            },
+            AuditOutcome::Started,
+        )],
+        EventMsg::CollabAgentSpawnEnd(event) => vec![(
+            AuditAction::SubagentStart {
+                run_id: event
+                    .new_thread_id
+                    .map(|thread_id| thread_id.to_string())
+                    .unwrap_or_default(),
+                name: "spawn_agent".to_string(),
+                prompt: event.prompt.clone(),
+            },
+            if event.new_thread_id.is_some() {
+                AuditOutcome::Started
+            } else {
+                AuditOutcome::Failure
+            },
+        )],
+        EventMsg::SubAgentRunEnd(event) => {
+            let error = match &event.status {
+                AgentStatus::Completed(_) => None,
+                AgentStatus::Errored(message) => Some(message.clone()),
+                status => Some(format!("{status:?}")),
+            };
+            let succeeded = error.is_none();
+            vec![(
+                AuditAction::SubagentEnd {
+                    run_id: event.run_id.clone(),
+                    name: event.name.clone(),
+                    error,
+                },
+                outcome(succeeded),
+            )]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn outcome(success: bool) -> AuditOutcome {
+    if success {
+        AuditOutcome::Success
+    } else {
+        AuditOutcome::Failure
+    }
+}
+
+/// Appends `record` under the lock, rotating the file first when the line
+/// would take it past `max_file_bytes`.
+fn append_record(dir: &Path, config: &AuditConfig, record: &AuditRecord) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(record).map_err(std::io::Error::other)?;
+    line.push('\n');
+
+    create_private_dir(dir)?;
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LOCK_FILE))?;
+    lock.lock()?;
+
+    let path = dir.join(AUDIT_FILE);
+    let len = std::fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+    if len > 0 && len + line.len() as u64 > config.max_file_bytes {
+        rotate(dir, &path, config.max_files)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&path)?;
+    file.write_all(line.as_bytes())?;
+    file.flush()?;
+    File::unlock(&lock)?;
+    Ok(())
+}
+
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(dir)
+}
+
+fn rotate(dir: &Path, path: &Path, max_files: Option<usize>) -> std::io::Result<()> {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.9fZ");
+    let mut rotated = dir.join(format!("{ROTATED_PREFIX}{stamp}.jsonl"));
+    let mut suffix = 1;
+    while rotated.exists() {
+        rotated = dir.join(format!("{ROTATED_PREFIX}{stamp}-{suffix}.jsonl"));
+        suffix += 1;
+    }
+    std::fs::rename(path, &rotated)?;
+
+    if let Some(max_files) = max_files {
+        let files = rotated_files(dir)?;
+        let excess = files.len().saturating_sub(max_files);
+        for old in &files[..excess] {
+            std::fs::remove_file(old)?;
+        }
+    }
+    Ok(())
+}
+
+/// Rotated files, oldest first.
+fn rotated_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(ROTATED_PREFIX) && name.ends_with(".jsonl"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Reads every record under `$CODEX_HOME/audit`, oldest first. Lines that do
+/// not parse are skipped.
+pub fn read_audit_log(codex_home: &Path) -> std::io::Result<Vec<AuditRecord>> {
+    let dir = codex_home.join(AUDIT_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = rotated_files(&dir)?;
+    files.push(dir.join(AUDIT_FILE));
+
+    let mut records = Vec::new();
+    for file in files {
+        let contents = match std::fs::read_to_string(&file) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        records.extend(
+            contents
+                .lines()
+                .filter_map(|line| serde_json::from_str::<AuditRecord>(line).ok()),
+        );
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::PatchApplyEndEvent;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn record(session: &str, command: &str) -> AuditRecord {
+        AuditRecord {
+            ts: "2026-01-01T00:00:00Z".to_string(),
+            session_id: session.to_string(),
+            action: AuditAction::Exec {
+                call_id: "call-1".to_string(),
+                turn_id: "turn-1".to_string(),
+                command: vec!["bash".to_string(), "-lc".to_string(), command.to_string()],
+                cwd: PathBuf::from("/repo"),
+                source: ExecCommandSource::Agent,
+                exit_code: 0,
+                duration_ms: 12,
+            },
+            outcome: AuditOutcome::Success,
+        }
+    }
+
+    #[test]
+    fn records_are_appended_and_rotated() {
+        let home = TempDir::new().expect("tempdir");
+        let dir = home.path().join(AUDIT_DIR);
+        let config = AuditConfig {
+            max_file_bytes: 400,
+            max_files: Some(2),
+        };
+
+        for i in 0..12 {
+            append_record(&dir, &config, &record("s", &format!("echo {i}"))).expect("append");
+        }
+
+        // Each record fills a file, so only the active file and the two
+        // newest rotated ones are left, in order.
+        assert_eq!(rotated_files(&dir).expect("list").len(), 2);
+        assert_eq!(
+            read_audit_log(home.path()).expect("read"),
+            vec![
+                record("s", "echo 9"),
+                record("s", "echo 10"),
+                record("s", "echo 11")
+            ]
+        );
+    }
+
+    #[test]
+    fn record_serializes_flat() {
+        let value = serde_json::to_value(record("abc", "ls")).expect("serialize");
+        assert_eq!(value["action"], "exec");
+        assert_eq!(value["session_id"], "abc");
+        assert_eq!(value["outcome"], "success");
+        assert_eq!(value["exit_code"], 0);
+    }
+
+    #[test]
+    fn successful_patch_records_each_file() {
+        let event = EventMsg::PatchApplyEnd(PatchApplyEndEvent {
+            call_id: "call-1".to_string(),
+            turn_id: "turn-1".to_string(),
+            stdout: String::new(),
+            stderr: String::new(),
+            success: true,
+            changes: HashMap::from([
+                (
+                    PathBuf::from("/repo/b.rs"),
+                    FileChange::Delete {
+                        content: String::new(),
+                    },
+                ),
+                (
+                    PathBuf::from("/repo/a.rs"),
+                    FileChange::Update {
+                        unified_diff: String::new(),
+                        move_path: Some(PathBuf::from("/repo/c.rs")),
+                    },
+                ),
+            ]),
+        });
+
+        let summaries: Vec<(&str, String, AuditOutcome)> = actions_for_event(&event)
+            .iter()
+            .map(|(action, outcome)| (action.name(), action.summary(), *outcome))
+            .collect();
+        assert_eq!(
+            summaries,
+            vec![
+                (
+                    "patch_apply",
+                    "/repo/a.rs, /repo/b.rs".to_string(),
+                    AuditOutcome::Success
+                ),
+                (
+                    "file_write",
+                    "update /repo/a.rs -> /repo/c.rs".to_string(),
+                    AuditOutcome::Success
+                ),
+                (
+                    "file_write",
+                    "delete /repo/b.rs".to_string(),
+                    AuditOutcome::Success
+                ),
+            ]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod entra;
 mod storage;
 
 use chrono::Utc;
@@ -21,6 +22,7 @@ pub use crate::auth::storage::AuthCredentialsStoreMode;
 pub use crate::auth::storage::AuthDotJson;
 use crate::auth::storage::AuthStorageBackend;
 use crate::auth::storage::create_auth_storage;
+use crate::auth::storage::migrate_auth_file_to_keyring;
 use crate::config::Config;
 use crate::error::RefreshTokenFailedError;
 use crate::error::RefreshTokenFailedReason;
@@ -257,6 +259,32 @@ pub fn save_auth(
     storage.save(auth)
 }
 
+/// Move plaintext `auth.json` credentials (the default scope and every
+/// account) into the OS keyring. Requires `cli_auth_credentials_store` to be
+/// `keyring` or `auto`, since file storage would no longer find them. Returns
+/// the number of scopes that were moved.
+pub fn migrate_auth_to_keyring(
+    codex_home: &Path,
+    auth_credentials_store_mode: AuthCredentialsStoreMode,
+) -> std::io::Result<usize> {
+    if auth_credentials_store_mode == AuthCredentialsStoreMode::File {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "set `cli_auth_credentials_store = \"keyring\"` (or \"auto\") in config.toml before moving credentials into the keyring",
+        ));
+    }
+    let accounts = crate::accounts::list_accounts(codex_home)?.accounts;
+    let scopes =
+        std::iter::once(None).chain(accounts.into_iter().map(|account| Some(account.name)));
+    let mut moved = 0;
+    for account_name in scopes {
+        if migrate_auth_file_to_keyring(codex_home.to_path_buf(), account_name)? {
+            moved += 1;
+        }
+    }
+    Ok(moved)
+}
+
 /// Load CLI auth data using the configured credential store backend.
 /// Returns `None` when no credentials are stored. This function is
 /// provided only for tests. Production code should not directly load
@@ -0,0 +1,457 @@
+//! Microsoft Entra ID tokens for Azure OpenAI providers.
+//!
+//! A provider with an `entra_id` section authenticates with a bearer token
+//! from the tenant's v2.0 token endpoint instead of an API key. With a client
+//! secret the client credentials grant is used directly; otherwise the user
+//! signs in once through the device code flow (`codex login azure`) and the
+//! refresh token, stored under `CODEX_HOME/entra/`, is redeemed as needed.
+//! Access tokens are cached per process and renewed shortly before they
+//! expire.
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use serde::Deserialize;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+use crate::default_client::build_reqwest_client;
+use crate::error::CodexErr;
+use crate::error::EnvVarError;
+use crate::error::RefreshTokenFailedError;
+use crate::error::RefreshTokenFailedReason;
+use crate::model_provider_info::EntraIdConfig;
+
+const DEFAULT_AUTHORITY: &str = "https://login.microsoftonline.com";
+const DEFAULT_SCOPE: &str = "https://cognitiveservices.azure.com/.default";
+
+/// Tokens this close to expiry are renewed before use.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Error)]
+pub enum EntraTokenError {
+    #[error("environment variable `{0}` with the Entra ID client secret is not set")]
+    MissingSecret(String),
+    #[error("not signed in to Entra ID tenant `{tenant_id}`; run `codex login azure`")]
+    NotLoggedIn { tenant_id: String },
+    #[error("Entra ID rejected the request: {0}")]
+    Rejected(String),
+    #[error("Entra ID request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl From<EntraTokenError> for CodexErr {
+    fn from(err: EntraTokenError) -> Self {
+        match err {
+            EntraTokenError::MissingSecret(var) => CodexErr::EnvVar(EnvVarError {
+                var,
+                instructions: Some(
+                    "Export the client secret of the Entra ID application configured under `entra_id`."
+                        .to_string(),
+                ),
+            }),
+            EntraTokenError::NotLoggedIn { .. } | EntraTokenError::Rejected(_) => {
+                CodexErr::RefreshTokenFailed(RefreshTokenFailedError::new(
+                    RefreshTokenFailedReason::Other,
+                    err.to_string(),
+                ))
+            }
+            EntraTokenError::Request(err) => CodexErr::Io(std::io::Error::other(err)),
+            EntraTokenError::Io(err) => CodexErr::Io(err),
+        }
+    }
+}
+
+/// What the user needs to complete a device code sign-in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceCodePrompt {
+    pub verification_uri: String,
+    pub user_code: String,
+    /// Entra's ready-made instruction text.
+    pub message: String,
+}
+
+struct CachedToken {
+    access_token: String,
+    expires_at: Instant,
+}
+
+/// Keyed by authority, tenant, client and scope. The async mutex also keeps
+/// concurrent requests from renewing the same token twice.
+static TOKEN_CACHE: LazyLock<Mutex<HashMap<String, CachedToken>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+    refresh_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    message: String,
+    expires_in: u64,
+    #[serde(default = "default_interval")]
+    interval: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredRefreshToken {
+    refresh_token: String,
+}
+
+impl EntraIdConfig {
+    /// The authority host tokens are requested from.
+    pub(crate) fn authority(&self) -> &str {
+        self.authority
+            .as_deref()
+            .unwrap_or(DEFAULT_AUTHORITY)
+            .trim_end_matches('/')
+    }
+
+    fn scope(&self) -> &str {
+        self.scope.as_deref().unwrap_or(DEFAULT_SCOPE)
+    }
+
+    fn endpoint(&self, name: &str) -> String {
+        format!("{}/{}/oauth2/v2.0/{name}", self.authority(), self.tenant_id)
+    }
+
+    fn cache_key(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            self.authority(),
+            self.tenant_id,
+            self.client_id,
+            self.scope()
+        )
+    }
+
+    /// The user scope plus `offline_access`, so a refresh token is issued.
+    fn delegated_scope(&self) -> String {
+        format!("{} offline_access", self.scope())
+    }
+}
+
+/// Returns a valid access token for `config`, acquiring or renewing it when
+/// the cached one is missing or about to expire.
+pub async fn access_token(
+    config: &EntraIdConfig,
+    codex_home: &Path,
+) -> Result<String, EntraTokenError> {
+    let key = config.cache_key();
+    let mut cache = TOKEN_CACHE.lock().await;
+    if let Some(cached) = cache.get(&key)
+        && cached.expires_at > Instant::now() + EXPIRY_MARGIN
+    {
+        return Ok(cached.access_token.clone());
+    }
+
+    let token = match &config.client_secret_env {
+        Some(secret_env) => client_credentials(config, secret_env).await?,
+        None => redeem_stored_refresh_token(config, codex_home).await?,
+    };
+    let access_token = token.access_token.clone();
+    cache.insert(
+        key,
+        CachedToken {
+            access_token: token.access_token,
+            expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+        },
+    );
+    Ok(access_token)
+}
+
+async fn client_credentials(
+    config: &EntraIdConfig,
+    secret_env: &str,
+) -> Result<TokenResponse, EntraTokenError> {
+    let secret = std::env::var(secret_env)
+        .ok()
+        .filter(|secret| !secret.trim().is_empty())
+        .ok_or_else(|| EntraTokenError::MissingSecret(secret_env.to_string()))?;
+    request_token(
+        config,
+        &[
+            ("grant_type", "client_credentials"),
+            ("client_id", &config.client_id),
+            ("client_secret", &secret),
+            ("scope", config.scope()),
+        ],
+    )
+    .await
+}
+
+async fn redeem_stored_refresh_token(
+    config: &EntraIdConfig,
+    codex_home: &Path,
+) -> Result<TokenResponse, EntraTokenError> {
+    let path = refresh_token_path(config, codex_home);
+    let stored = match std::fs::read_to_string(&path) {
+        Ok(contents) => {
+            serde_json::from_str::<StoredRefreshToken>(&contents).map_err(std::io::Error::other)?
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Err(EntraTokenError::NotLoggedIn {
+                tenant_id: config.tenant_id.clone(),
+            });
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let scope = config.delegated_scope();
+    let token = request_token(
+        config,
+        &[
+            ("grant_type", "refresh_token"),
+            ("client_id", &config.client_id),
+            ("refresh_token", &stored.refresh_token),
+            ("scope", &scope),
+        ],
+    )
+    .await?;
+    // Entra rotates refresh tokens; keep the newest one.
+    if let Some(refresh_token) = &token.refresh_token {
+        store_refresh_token(&path, refresh_token)?;
+    }
+    Ok(token)
+}
+
+/// Signs in through the device code flow and stores the refresh token for
+/// later sessions. `on_prompt` is called once with the code to enter.
+pub async fn device_code_login(
+    config: &EntraIdConfig,
+    codex_home: &Path,
+    on_prompt: impl FnOnce(&DeviceCodePrompt),
+) -> Result<(), EntraTokenError> {
+    let client = build_reqwest_client();
+    let scope = config.delegated_scope();
+    let response = client
+        .post(config.endpoint("devicecode"))
+        .form(&[("client_id", config.client_id.as_str()), ("scope", &scope)])
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(rejection(response).await);
+    }
+    let device: DeviceCodeResponse = response.json().await?;
+    on_prompt(&DeviceCodePrompt {
+        verification_uri: device.verification_uri.clone(),
+        user_code: device.user_code.clone(),
+        message: device.message.clone(),
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(device.expires_in);
+    let mut interval = device.interval;
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        if Instant::now() >= deadline {
+            return Err(EntraTokenError::Rejected(
+                "the device code expired before sign-in completed".to_string(),
+            ));
+        }
+        let response = client
+            .post(config.endpoint("token"))
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("client_id", config.client_id.as_str()),
+                ("device_code", &device.device_code),
+            ])
+            .send()
+            .await?;
+        if response.status().is_success() {
+            let token: TokenResponse = response.json().await?;
+            let Some(refresh_token) = &token.refresh_token else {
+                return Err(EntraTokenError::Rejected(
+                    "no refresh token was issued; check that the app allows `offline_access`"
+                        .to_string(),
+                ));
+            };
+            store_refresh_token(&refresh_token_path(config, codex_home), refresh_token)?;
+            TOKEN_CACHE.lock().await.insert(
+                config.cache_key(),
+                CachedToken {
+                    access_token: token.access_token,
+                    expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+                },
+            );
+            return Ok(());
+        }
+        let error: ErrorResponse = response.json().await?;
+        match error.error.as_str() {
+            "authorization_pending" => {}
+            "slow_down" => interval += 5,
+            _ => return Err(EntraTokenError::Rejected(describe(&error))),
+        }
+    }
+}
+
+async fn request_token(
+    config: &EntraIdConfig,
+    form: &[(&str, &str)],
+) -> Result<TokenResponse, EntraTokenError> {
+    let response = build_reqwest_client()
+        .post(config.endpoint("token"))
+        .form(form)
+        .send()
+        .await?;
+    if response.status().is_success() {
+        Ok(response.json().await?)
+    } else {
+        Err(rejection(response).await)
+    }
+}
+
+async fn rejection(response: reqwest::Response) -> EntraTokenError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    match serde_json::from_str::<ErrorResponse>(&body) {
+        Ok(error) => EntraTokenError::Rejected(describe(&error)),
+        Err(_) => EntraTokenError::Rejected(format!("{status}: {body}")),
+    }
+}
+
+fn describe(error: &ErrorResponse) -> String {
+    match &error.error_description {
+        Some(description) => format!("{}: {description}", error.error),
+        None => error.error.clone(),
+    }
+}
+
+fn refresh_token_path(config: &EntraIdConfig, codex_home: &Path) -> PathBuf {
+    let name: String = format!("{}-{}", config.tenant_id, config.client_id)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    codex_home.join("entra").join(format!("{name}.json"))
+}
+
+fn store_refresh_token(path: &Path, refresh_token: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let contents = serde_json::to_string(&StoredRefreshToken {
+        refresh_token: refresh_token.to_string(),
+    })
+    .map_err(std::io::Error::other)?;
+    let mut options = OpenOptions::new();
+    options.truncate(true).write(true).create(true);
+    #[cfg(unix)]
+    {
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(contents.as_bytes())?;
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_string_contains;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    fn config(server: &MockServer, client_secret_env: Option<&str>) -> EntraIdConfig {
+        EntraIdConfig {
+            tenant_id: "contoso".to_string(),
+            client_id: format!("client-{}", server.address().port()),
+            client_secret_env: client_secret_env.map(str::to_string),
+            scope: None,
+            authority: Some(server.uri()),
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_token_is_redeemed_rotated_and_cached() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/contoso/oauth2/v2.0/token"))
+            .and(body_string_contains("grant_type=refresh_token"))
+            .and(body_string_contains("refresh_token=stored"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "access-1",
+                "expires_in": 3600,
+                "refresh_token": "rotated",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let config = config(&server, None);
+        let token_path = refresh_token_path(&config, codex_home.path());
+
+        let err = access_token(&config, codex_home.path())
+            .await
+            .expect_err("no stored token yet");
+        assert!(matches!(err, EntraTokenError::NotLoggedIn { .. }));
+
+        store_refresh_token(&token_path, "stored").expect("store token");
+        assert_eq!(
+            access_token(&config, codex_home.path())
+                .await
+                .expect("token"),
+            "access-1"
+        );
+        // Served from the cache; the mock only answers once.
+        assert_eq!(
+            access_token(&config, codex_home.path())
+                .await
+                .expect("token"),
+            "access-1"
+        );
+        let stored: StoredRefreshToken =
+            serde_json::from_str(&std::fs::read_to_string(&token_path).expect("read token"))
+                .expect("parse token");
+        assert_eq!(stored.refresh_token, "rotated");
+    }
+
+    #[tokio::test]
+    async fn client_credentials_require_the_secret() {
+        let server = MockServer::start().await;
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let config = config(&server, Some("CODEX_TEST_ENTRA_SECRET_UNSET"));
+
+        let err = access_token(&config, codex_home.path())
+            .await
+            .expect_err("secret is unset");
+        assert!(matches!(
+            err,
+            EntraTokenError::MissingSecret(var) if var == "CODEX_TEST_ENTRA_SECRET_UNSET"
+        ));
+    }
+}
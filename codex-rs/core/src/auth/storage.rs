@@ -15,6 +15,7 @@ use std::os::unix::fs::OpenOptionsExt;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use tracing::info;
 use tracing::warn;
 
 use crate::accounts::ACCOUNTS_AUTH_DIR_NAME;
@@ -222,6 +223,38 @@ impl KeyringAuthStorage {
         }
     }
 
+    fn fallback_file(&self) -> std::io::Result<PathBuf> {
+        match self.account_name.as_deref() {
+            Some(account_name) => get_auth_file_for_account(&self.codex_home, account_name),
+            None => Ok(get_auth_file(&self.codex_home)),
+        }
+    }
+
+    /// Moves a plaintext `auth.json` left behind by file storage into the
+    /// keyring, so switching to keyring storage keeps the user logged in.
+    fn migrate_fallback_file(&self) -> std::io::Result<Option<AuthDotJson>> {
+        let file_storage = FileAuthStorage {
+            auth_file: self.fallback_file()?,
+        };
+        let auth = match file_storage.load() {
+            Ok(Some(auth)) => auth,
+            Ok(None) => return Ok(None),
+            Err(err) => {
+                warn!(
+                    "ignoring unreadable {}: {err}",
+                    file_storage.auth_file.display()
+                );
+                return Ok(None);
+            }
+        };
+        self.save(&auth)?;
+        info!(
+            "moved CLI auth from {} into the keyring",
+            file_storage.auth_file.display()
+        );
+        Ok(Some(auth))
+    }
+
     fn save_to_keyring(&self, key: &str, value: &str) -> std::io::Result<()> {
         match self.keyring_store.save(KEYRING_SERVICE, key, value) {
             Ok(()) => Ok(()),
@@ -240,7 +273,10 @@ impl KeyringAuthStorage {
 impl AuthStorageBackend for KeyringAuthStorage {
     fn load(&self) -> std::io::Result<Option<AuthDotJson>> {
         let key = compute_store_key(&self.codex_home, self.account_name.as_deref())?;
-        self.load_from_keyring(&key)
+        match self.load_from_keyring(&key)? {
+            Some(auth) => Ok(Some(auth)),
+            None => self.migrate_fallback_file(),
+        }
     }
 
     fn save(&self, auth: &AuthDotJson) -> std::io::Result<()> {
@@ -248,11 +284,7 @@ impl AuthStorageBackend for KeyringAuthStorage {
         // Simpler error mapping per style: prefer method reference over closure
         let serialized = serde_json::to_string(auth).map_err(std::io::Error::other)?;
         self.save_to_keyring(&key, &serialized)?;
-        let auth_file = match self.account_name.as_deref() {
-            Some(account_name) => get_auth_file_for_account(&self.codex_home, account_name)?,
-            None => get_auth_file(&self.codex_home),
-        };
-        if let Err(err) = delete_file_if_exists_at_path(&auth_file) {
+        if let Err(err) = delete_file_if_exists_at_path(&self.fallback_file()?) {
             warn!("failed to remove CLI auth fallback file: {err}");
         }
         Ok(())
@@ -266,11 +298,7 @@ impl AuthStorageBackend for KeyringAuthStorage {
             .map_err(|err| {
                 std::io::Error::other(format!("failed to delete auth from keyring: {err}"))
             })?;
-        let auth_file = match self.account_name.as_deref() {
-            Some(account_name) => get_auth_file_for_account(&self.codex_home, account_name)?,
-            None => get_auth_file(&self.codex_home),
-        };
-        let file_removed = delete_file_if_exists_at_path(&auth_file)?;
+        let file_removed = delete_file_if_exists_at_path(&self.fallback_file()?)?;
         Ok(keyring_removed || file_removed)
     }
 }
@@ -333,6 +361,50 @@ impl AuthStorageBackend for AutoAuthStorage {
     }
 }
 
+/// Moves the plaintext `auth.json` of one account scope into the keyring,
+/// replacing any entry the keyring already has for that scope. Returns
+/// `Ok(false)` when there was no file to move, and an error when the file is
+/// still on disk afterwards.
+pub(super) fn migrate_auth_file_to_keyring(
+    codex_home: PathBuf,
+    account_name: Option<String>,
+) -> std::io::Result<bool> {
+    migrate_auth_file_to_keyring_with_keyring_store(
+        codex_home,
+        account_name,
+        Arc::new(DefaultKeyringStore),
+    )
+}
+
+fn migrate_auth_file_to_keyring_with_keyring_store(
+    codex_home: PathBuf,
+    account_name: Option<String>,
+    keyring_store: Arc<dyn KeyringStore>,
+) -> std::io::Result<bool> {
+    let storage = match account_name {
+        Some(account_name) => {
+            KeyringAuthStorage::new_for_account(codex_home, account_name, keyring_store)?
+        }
+        None => KeyringAuthStorage::new(codex_home, keyring_store),
+    };
+    let file_storage = FileAuthStorage {
+        auth_file: storage.fallback_file()?,
+    };
+    // Read the file directly: `load` prefers an existing keyring entry and
+    // would leave the plaintext copy behind.
+    let Some(auth) = file_storage.load()? else {
+        return Ok(false);
+    };
+    storage.save(&auth)?;
+    if file_storage.auth_file.exists() {
+        return Err(std::io::Error::other(format!(
+            "moved the credentials into the keyring but could not remove {}",
+            file_storage.auth_file.display()
+        )));
+    }
+    Ok(true)
+}
+
 pub(super) fn create_auth_storage(
     codex_home: PathBuf,
     mode: AuthCredentialsStoreMode,
@@ -568,6 +640,72 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn keyring_auth_storage_load_moves_fallback_file_into_keyring() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let mock_keyring = MockKeyringStore::default();
+        let expected = auth_with_prefix("plaintext");
+        FileAuthStorage::new(codex_home.path().to_path_buf()).save(&expected)?;
+        let storage = KeyringAuthStorage::new(
+            codex_home.path().to_path_buf(),
+            Arc::new(mock_keyring.clone()),
+        );
+
+        let loaded = storage.load()?;
+
+        assert_eq!(loaded, Some(expected.clone()));
+        assert!(!get_auth_file(codex_home.path()).exists());
+        let key = compute_store_key(codex_home.path(), None)?;
+        assert!(mock_keyring.contains(&key));
+        assert_eq!(storage.load()?, Some(expected));
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_auth_file_to_keyring_reports_moved_scopes() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let mock_keyring = MockKeyringStore::default();
+        FileAuthStorage::new_for_account(codex_home.path().to_path_buf(), "work".to_string())?
+            .save(&auth_with_prefix("work"))?;
+
+        let moved = |account_name: Option<&str>| {
+            migrate_auth_file_to_keyring_with_keyring_store(
+                codex_home.path().to_path_buf(),
+                account_name.map(str::to_string),
+                Arc::new(mock_keyring.clone()),
+            )
+        };
+
+        assert!(!moved(None)?);
+        assert!(moved(Some("work"))?);
+        assert!(!moved(Some("work"))?);
+        let key = compute_store_key(codex_home.path(), Some("work"))?;
+        assert!(mock_keyring.contains(&key));
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_auth_file_to_keyring_replaces_an_existing_keyring_entry() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let mock_keyring = MockKeyringStore::default();
+        let keyring_storage = KeyringAuthStorage::new(
+            codex_home.path().to_path_buf(),
+            Arc::new(mock_keyring.clone()),
+        );
+        keyring_storage.save(&auth_with_prefix("old"))?;
+        FileAuthStorage::new(codex_home.path().to_path_buf()).save(&auth_with_prefix("new"))?;
+
+        assert!(migrate_auth_file_to_keyring_with_keyring_store(
+            codex_home.path().to_path_buf(),
+            None,
+            Arc::new(mock_keyring.clone()),
+        )?);
+
+        assert!(!get_auth_file(codex_home.path()).exists());
+        assert_eq!(keyring_storage.load()?, Some(auth_with_prefix("new")));
+        Ok(())
+    }
+
     #[test]
     fn keyring_auth_storage_compute_store_key_for_home_directory() -> anyhow::Result<()> {
         let codex_home = PathBuf::from("~/.codex");
@@ -0,0 +1,254 @@
+//! Background tasks started via `Op::StartBackgroundTask`.
+//!
+//! A background task is a prompt (optionally run under a subagent definition)
+//! executed in its own child thread. The parent session only tracks the task
+//! and reports its start and end, so the user can keep chatting while it runs.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::agent::AgentStatus;
+use crate::codex::Session;
+use crate::subagents::runner::apply_definition;
+use crate::subagents::runner::wait_for_final_status;
+use crate::tools::handlers::collab::build_agent_spawn_config;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::BackgroundTaskBeginEvent;
+use codex_protocol::protocol::BackgroundTaskEndEvent;
+use codex_protocol::protocol::BackgroundTaskInfo;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ListBackgroundTasksResponseEvent;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+#[derive(Debug, Clone, PartialEq)]
+struct BackgroundTask {
+    subagent: Option<String>,
+    prompt: String,
+    started_at: String,
+}
+
+/// Tasks started from this session that have not reached a final status.
+#[derive(Default)]
+pub(crate) struct BackgroundTasks {
+    tasks: Mutex<HashMap<ThreadId, BackgroundTask>>,
+}
+
+impl BackgroundTasks {
+    async fn insert(&self, thread_id: ThreadId, task: BackgroundTask) {
+        self.tasks.lock().await.insert(thread_id, task);
+    }
+
+    async fn remove(&self, thread_id: &ThreadId) -> Option<BackgroundTask> {
+        self.tasks.lock().await.remove(thread_id)
+    }
+
+    async fn contains(&self, thread_id: &ThreadId) -> bool {
+        self.tasks.lock().await.contains_key(thread_id)
+    }
+
+    /// Snapshot of the tracked tasks, oldest first.
+    async fn snapshot(&self) -> Vec<(ThreadId, BackgroundTask)> {
+        let mut tasks: Vec<_> = self
+            .tasks
+            .lock()
+            .await
+            .iter()
+            .map(|(thread_id, task)| (*thread_id, task.clone()))
+            .collect();
+        tasks.sort_by(|(_, a), (_, b)| a.started_at.cmp(&b.started_at));
+        tasks
+    }
+}
+
+pub(crate) async fn start_background_task(
+    sess: &Arc<Session>,
+    sub_id: String,
+    prompt: String,
+    subagent: Option<String>,
+) {
+    let turn = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+    let mut config = match build_agent_spawn_config(&sess.get_base_instructions().await, &turn) {
+        Ok(config) => config,
+        Err(err) => {
+            send_error(sess, sub_id, err.to_string()).await;
+            return;
+        }
+    };
+
+    if let Some(name) = &subagent {
+        let outcome = sess.services.subagents_manager.subagents_for_cwd(&turn.cwd);
+        let Some(definition) = outcome.find(name) else {
+            send_error(sess, sub_id, format!("unknown subagent `{name}`")).await;
+            return;
+        };
+        if let Err(message) = apply_definition(&mut config, definition) {
+            send_error(sess, sub_id, message).await;
+            return;
+        }
+    }
+
+    let agent_control = &sess.services.agent_control;
+    let thread_id = match agent_control.spawn_agent(config, prompt.clone()).await {
+        Ok(thread_id) => thread_id,
+        Err(err) => {
+            send_error(
+                sess,
+                sub_id,
+                format!("failed to start background task: {err}"),
+            )
+            .await;
+            return;
+        }
+    };
+
+    sess.services
+        .background_tasks
+        .insert(
+            thread_id,
+            BackgroundTask {
+                subagent: subagent.clone(),
+                prompt: prompt.clone(),
+                started_at: chrono::Utc::now().to_rfc3339(),
+            },
+        )
+        .await;
+
+    let task_id = thread_id.to_string();
+    sess.send_event(
+        &turn,
+        EventMsg::BackgroundTaskBegin(BackgroundTaskBeginEvent {
+            task_id: task_id.clone(),
+            subagent: subagent.clone(),
+            prompt,
+        }),
+    )
+    .await;
+
+    let sess = Arc::clone(sess);
+    tokio::spawn(async move {
+        let status = wait_for_final_status(&sess, thread_id).await;
+        if !matches!(status, AgentStatus::Shutdown | AgentStatus::NotFound)
+            && let Err(err) = sess.services.agent_control.shutdown_agent(thread_id).await
+        {
+            warn!("failed to shut down background task thread {thread_id}: {err}");
+        }
+        sess.services.background_tasks.remove(&thread_id).await;
+        sess.send_event(
+            &turn,
+            EventMsg::BackgroundTaskEnd(BackgroundTaskEndEvent {
+                task_id,
+                subagent,
+                status,
+            }),
+        )
+        .await;
+    });
+}
+
+pub(crate) async fn list_background_tasks(sess: &Session, sub_id: String) {
+    let mut tasks = Vec::new();
+    for (thread_id, task) in sess.services.background_tasks.snapshot().await {
+        tasks.push(BackgroundTaskInfo {
+            task_id: thread_id.to_string(),
+            subagent: task.subagent,
+            prompt: task.prompt,
+            status: sess.services.agent_control.get_status(thread_id).await,
+            started_at: task.started_at,
+        });
+    }
+    sess.send_event_raw(Event {
+        id: sub_id,
+        msg: EventMsg::ListBackgroundTasksResponse(ListBackgroundTasksResponseEvent { tasks }),
+    })
+    .await;
+}
+
+pub(crate) async fn cancel_background_task(sess: &Session, sub_id: String, task_id: String) {
+    let thread_id = match ThreadId::from_string(&task_id) {
+        Ok(thread_id) if sess.services.background_tasks.contains(&thread_id).await => thread_id,
+        _ => {
+            send_error(sess, sub_id, format!("unknown background task `{task_id}`")).await;
+            return;
+        }
+    };
+    // The waiter spawned in `start_background_task` observes the shutdown and
+    // reports `BackgroundTaskEnd`.
+    if let Err(err) = sess.services.agent_control.shutdown_agent(thread_id).await {
+        send_error(
+            sess,
+            sub_id,
+            format!("failed to cancel background task: {err}"),
+        )
+        .await;
+    }
+}
+
+async fn send_error(sess: &Session, sub_id: String, message: String) {
+    sess.send_event_raw(Event {
+        id: sub_id,
+        msg: EventMsg::Error(ErrorEvent {
+            message,
+            codex_error_info: None,
+        }),
+    })
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codex::make_session_and_context_with_rx;
+    use pretty_assertions::assert_eq;
+
+    fn task(prompt: &str, started_at: &str) -> BackgroundTask {
+        BackgroundTask {
+            subagent: None,
+            prompt: prompt.to_string(),
+            started_at: started_at.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn snapshot_lists_tasks_oldest_first() {
+        let tasks = BackgroundTasks::default();
+        let first = ThreadId::new();
+        let second = ThreadId::new();
+        tasks
+            .insert(second, task("second", "2025-01-01T00:00:02+00:00"))
+            .await;
+        tasks
+            .insert(first, task("first", "2025-01-01T00:00:01+00:00"))
+            .await;
+
+        assert_eq!(
+            tasks.snapshot().await,
+            vec![
+                (first, task("first", "2025-01-01T00:00:01+00:00")),
+                (second, task("second", "2025-01-01T00:00:02+00:00")),
+            ]
+        );
+
+        tasks.remove(&first).await;
+        assert_eq!(
+            tasks.snapshot().await,
+            vec![(second, task("second", "2025-01-01T00:00:02+00:00"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn cancel_unknown_task_reports_error() {
+        let (session, _turn, rx) = make_session_and_context_with_rx().await;
+        let task_id = ThreadId::new().to_string();
+
+        cancel_background_task(&session, "sub".to_string(), task_id.clone()).await;
+
+        let event = rx.recv().await.expect("error event");
+        let EventMsg::Error(ErrorEvent { message, .. }) = event.msg else {
+            panic!("expected error event, got {:?}", event.msg);
+        };
+        assert_eq!(message, format!("unknown background task `{task_id}`"));
+    }
+}
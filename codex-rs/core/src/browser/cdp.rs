@@ -0,0 +1,208 @@
+//! Minimal Chrome DevTools Protocol client.
+//!
+//! One websocket to the browser carries everything: page commands are sent
+//! with the `sessionId` of a target attached in flattened mode. Commands are
+//! issued one at a time; events that arrive while waiting for a response are
+//! buffered for [`CdpConnection::wait_for_event`].
+
+use std::collections::VecDeque;
+
+use futures::SinkExt;
+use futures::StreamExt;
+use serde_json::Value;
+use serde_json::json;
+use tokio::net::TcpStream;
+use tokio_tungstenite::MaybeTlsStream;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Older events are dropped once this many are buffered; only recent page
+/// lifecycle events are ever waited for.
+const MAX_BUFFERED_EVENTS: usize = 256;
+
+pub(super) struct CdpConnection {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+    events: VecDeque<CdpEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CdpEvent {
+    session_id: Option<String>,
+    method: String,
+    params: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Incoming {
+    Response {
+        id: u64,
+        result: Result<Value, String>,
+    },
+    Event(CdpEvent),
+}
+
+impl CdpConnection {
+    pub(super) async fn connect(url: &str) -> Result<Self, String> {
+        let (socket, _) = connect_async(url)
+            .await
+            .map_err(|err| format!("failed to connect to the browser at {url}: {err}"))?;
+        Ok(Self {
+            socket,
+            next_id: 0,
+            events: VecDeque::new(),
+        })
+    }
+
+    /// Sends `method` and waits for its result. `session_id` addresses an
+    /// attached target; `None` talks to the browser itself.
+    pub(super) async fn call(
+        &mut self,
+        session_id: Option<&str>,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, String> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let mut request = json!({
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        if let Some(session_id) = session_id {
+            request["sessionId"] = Value::String(session_id.to_string());
+        }
+        self.socket
+            .send(Message::Text(request.to_string()))
+            .await
+            .map_err(|err| format!("failed to send {method}: {err}"))?;
+
+        loop {
+            match self.next_message().await? {
+                Incoming::Response {
+                    id: response_id,
+                    result,
+                } if response_id == id => {
+                    return result.map_err(|err| format!("{method} failed: {err}"));
+                }
+                // A response to a command whose caller timed out.
+                Incoming::Response { .. } => {}
+                Incoming::Event(event) => self.buffer(event),
+            }
+        }
+    }
+
+    /// Waits for the next `method` event from `session_id`, including events
+    /// already buffered.
+    pub(super) async fn wait_for_event(
+        &mut self,
+        session_id: Option<&str>,
+        method: &str,
+    ) -> Result<Value, String> {
+        let matches =
+            |event: &CdpEvent| event.method == method && event.session_id.as_deref() == session_id;
+        if let Some(event) = self
+            .events
+            .iter()
+            .position(matches)
+            .and_then(|idx| self.events.remove(idx))
+        {
+            return Ok(event.params);
+        }
+        loop {
+            match self.next_message().await? {
+                Incoming::Event(event) if matches(&event) => return Ok(event.params),
+                Incoming::Event(event) => self.buffer(event),
+                Incoming::Response { .. } => {}
+            }
+        }
+    }
+
+    /// Forgets buffered events, e.g. before starting a navigation so an
+    /// earlier load event is not mistaken for the new one.
+    pub(super) fn clear_events(&mut self) {
+        self.events.clear();
+    }
+
+    fn buffer(&mut self, event: CdpEvent) {
+        if self.events.len() == MAX_BUFFERED_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    async fn next_message(&mut self) -> Result<Incoming, String> {
+        loop {
+            let message = match self.socket.next().await {
+                Some(Ok(message)) => message,
+                Some(Err(err)) => return Err(format!("DevTools connection failed: {err}")),
+                None => return Err("the browser closed the DevTools connection".to_string()),
+            };
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Close(_) => {
+                    return Err("the browser closed the DevTools connection".to_string());
+                }
+                _ => continue,
+            };
+            if let Some(incoming) = parse_message(&text) {
+                return Ok(incoming);
+            }
+        }
+    }
+}
+
+fn parse_message(text: &str) -> Option<Incoming> {
+    let mut message: Value = serde_json::from_str(text).ok()?;
+    if let Some(id) = message["id"].as_u64() {
+        let result = match message.get("error") {
+            Some(error) => Err(error["message"]
+                .as_str()
+                .unwrap_or("unknown error")
+                .to_string()),
+            None => Ok(message["result"].take()),
+        };
+        return Some(Incoming::Response { id, result });
+    }
+    Some(Incoming::Event(CdpEvent {
+        session_id: message["sessionId"].as_str().map(str::to_string),
+        method: message["method"].as_str()?.to_string(),
+        params: message["params"].take(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_responses_errors_and_events() {
+        assert_eq!(
+            parse_message(r#"{"id": 3, "result": {"frameId": "F"}}"#),
+            Some(Incoming::Response {
+                id: 3,
+                result: Ok(json!({"frameId": "F"})),
+            })
+        );
+        assert_eq!(
+            parse_message(r#"{"id": 4, "error": {"code": -32000, "message": "No node"}}"#),
+            Some(Incoming::Response {
+                id: 4,
+                result: Err("No node".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_message(
+                r#"{"method": "Page.loadEventFired", "params": {"timestamp": 1.5}, "sessionId": "S"}"#
+            ),
+            Some(Incoming::Event(CdpEvent {
+                session_id: Some("S".to_string()),
+                method: "Page.loadEventFired".to_string(),
+                params: json!({"timestamp": 1.5}),
+            }))
+        );
+        assert_eq!(parse_message("not json"), None);
+    }
+}
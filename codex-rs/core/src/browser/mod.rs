@@ -0,0 +1,505 @@
+//! Headless Chromium for the `browser_*` tools.
+//!
+//! The browser is launched on the first tool call of a session with a
+//! throwaway profile and `--remote-debugging-port=0`; the DevTools websocket
+//! URL is read from its stderr. A single page is created and kept for the
+//! rest of the session, so navigation, clicks and screenshots all act on the
+//! same tab. The process is killed when the session ends.
+
+mod cdp;
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::BufReader;
+use tokio::process::Child;
+use tokio::process::ChildStderr;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tracing::debug;
+use url::Host;
+use url::Url;
+
+use crate::config::types::BrowserConfig;
+use cdp::CdpConnection;
+
+/// Binaries tried, in order, when `browser.executable` is not set.
+const BROWSER_CANDIDATES: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+];
+
+const MACOS_CHROME: &str = "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome";
+
+/// Time given to the page to react to a click before it is inspected.
+const CLICK_SETTLE: Duration = Duration::from_millis(500);
+
+/// The session's browser, launched on first use.
+#[derive(Default)]
+pub(crate) struct BrowserSession {
+    browser: Mutex<Option<Browser>>,
+}
+
+struct Browser {
+    cdp: CdpConnection,
+    /// Flattened session id of the page target.
+    page: String,
+    timeout: Duration,
+    _process: Child,
+    _profile: TempDir,
+}
+
+/// Where the page ended up after an action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PageInfo {
+    pub url: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub(crate) struct PageText {
+    pub url: String,
+    pub title: String,
+    pub text: String,
+}
+
+#[derive(Deserialize)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+impl BrowserSession {
+    pub(crate) async fn navigate(
+        &self,
+        config: &BrowserConfig,
+        url: &Url,
+    ) -> Result<PageInfo, String> {
+        let mut guard = self.browser(config).await?;
+        let browser = guard.as_mut().ok_or_else(not_running)?;
+        let result = browser.navigate(url).await;
+        close_if_disconnected(&mut guard, result)
+    }
+
+    /// PNG screenshot, base64-encoded.
+    pub(crate) async fn screenshot(
+        &self,
+        config: &BrowserConfig,
+        full_page: bool,
+    ) -> Result<String, String> {
+        let mut guard = self.browser(config).await?;
+        let browser = guard.as_mut().ok_or_else(not_running)?;
+        let result = browser.screenshot(full_page).await;
+        close_if_disconnected(&mut guard, result)
+    }
+
+    /// Visible text of the element matching `selector`, or of the whole page.
+    pub(crate) async fn read_text(
+        &self,
+        config: &BrowserConfig,
+        selector: Option<&str>,
+    ) -> Result<PageText, String> {
+        let mut guard = self.browser(config).await?;
+        let browser = guard.as_mut().ok_or_else(not_running)?;
+        let result = browser.read_text(selector).await;
+        close_if_disconnected(&mut guard, result)
+    }
+
+    pub(crate) async fn click(
+        &self,
+        config: &BrowserConfig,
+        selector: &str,
+    ) -> Result<PageInfo, String> {
+        let mut guard = self.browser(config).await?;
+        let browser = guard.as_mut().ok_or_else(not_running)?;
+        let result = browser.click(selector).await;
+        close_if_disconnected(&mut guard, result)
+    }
+
+    async fn browser(
+        &self,
+        config: &BrowserConfig,
+    ) -> Result<tokio::sync::MutexGuard<'_, Option<Browser>>, String> {
+        let mut guard = self.browser.lock().await;
+        if guard.is_none() {
+            *guard = Some(Browser::launch(config).await?);
+        }
+        Ok(guard)
+    }
+}
+
+fn not_running() -> String {
+    "the browser is not running".to_string()
+}
+
+/// Drops a browser whose DevTools connection is gone so the next call starts
+/// a fresh one.
+fn close_if_disconnected<T>(
+    browser: &mut Option<Browser>,
+    result: Result<T, String>,
+) -> Result<T, String> {
+    if let Err(err) = &result
+        && err.contains("DevTools connection")
+    {
+        *browser = None;
+    }
+    result
+}
+
+impl Browser {
+    async fn launch(config: &BrowserConfig) -> Result<Self, String> {
+        let executable = find_executable(config)?;
+        let profile = tempfile::Builder::new()
+            .prefix("codex-browser-")
+            .tempdir()
+            .map_err(|err| format!("failed to create a browser profile: {err}"))?;
+        let mut process = Command::new(&executable)
+            .args(launch_args(config, profile.path()))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| format!("failed to start {}: {err}", executable.display()))?;
+        let stderr = process
+            .stderr
+            .take()
+            .ok_or_else(|| "browser stderr is not available".to_string())?;
+
+        let setup = async {
+            let ws_url = read_devtools_url(stderr).await?;
+            let mut cdp = CdpConnection::connect(&ws_url).await?;
+            let target = cdp
+                .call(None, "Target.createTarget", json!({ "url": "about:blank" }))
+                .await?;
+            let target_id = target["targetId"]
+                .as_str()
+                .ok_or_else(|| "Target.createTarget returned no targetId".to_string())?;
+            let attached = cdp
+                .call(
+                    None,
+                    "Target.attachToTarget",
+                    json!({ "targetId": target_id, "flatten": true }),
+                )
+                .await?;
+            let page = attached["sessionId"]
+                .as_str()
+                .ok_or_else(|| "Target.attachToTarget returned no sessionId".to_string())?
+                .to_string();
+            cdp.call(Some(&page), "Page.enable", json!({})).await?;
+            cdp.call(
+                Some(&page),
+                "Emulation.setDeviceMetricsOverride",
+                json!({
+                    "width": config.width,
+                    "height": config.height,
+                    "deviceScaleFactor": 1,
+                    "mobile": false,
+                }),
+            )
+            .await?;
+            Ok::<_, String>((cdp, page))
+        };
+        let (cdp, page) = tokio::time::timeout(config.timeout, setup)
+            .await
+            .map_err(|_| {
+                format!(
+                    "{} did not start within {}s",
+                    executable.display(),
+                    config.timeout.as_secs()
+                )
+            })??;
+        Ok(Self {
+            cdp,
+            page,
+            timeout: config.timeout,
+            _process: process,
+            _profile: profile,
+        })
+    }
+
+    async fn navigate(&mut self, url: &Url) -> Result<PageInfo, String> {
+        let timeout = self.timeout;
+        let load = async {
+            self.cdp.clear_events();
+            let result = self
+                .cdp
+                .call(
+                    Some(&self.page),
+                    "Page.navigate",
+                    json!({ "url": url.as_str() }),
+                )
+                .await?;
+            if let Some(error) = result["errorText"].as_str().filter(|e| !e.is_empty()) {
+                return Err(format!("failed to open {url}: {error}"));
+            }
+            // Same-document navigations (e.g. a new #fragment) do not load.
+            if result.get("loaderId").is_some() {
+                self.cdp
+                    .wait_for_event(Some(&self.page), "Page.loadEventFired")
+                    .await?;
+            }
+            Ok(())
+        };
+        tokio::time::timeout(timeout, load)
+            .await
+            .map_err(|_| format!("{url} did not finish loading within {}s", timeout.as_secs()))??;
+        self.page_info().await
+    }
+
+    async fn screenshot(&mut self, full_page: bool) -> Result<String, String> {
+        let mut params = json!({ "format": "png" });
+        if full_page {
+            let metrics = self.call("Page.getLayoutMetrics", json!({})).await?;
+            let size = &metrics["cssContentSize"];
+            params["captureBeyondViewport"] = Value::Bool(true);
+            params["clip"] = json!({
+                "x": 0,
+                "y": 0,
+                "width": size["width"],
+                "height": size["height"],
+                "scale": 1,
+            });
+        }
+        let result = self.call("Page.captureScreenshot", params).await?;
+        result["data"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Page.captureScreenshot returned no data".to_string())
+    }
+
+    async fn read_text(&mut self, selector: Option<&str>) -> Result<PageText, String> {
+        let value = self.evaluate(&read_text_script(selector)).await?;
+        if value.is_null() {
+            return Err(no_match(selector.unwrap_or("body")));
+        }
+        serde_json::from_value(value).map_err(|err| format!("unexpected page text: {err}"))
+    }
+
+    async fn click(&mut self, selector: &str) -> Result<PageInfo, String> {
+        let value = self.evaluate(&element_center_script(selector)).await?;
+        if value.is_null() {
+            return Err(no_match(selector));
+        }
+        let point: Point =
+            serde_json::from_value(value).map_err(|err| format!("unexpected position: {err}"))?;
+        for event in ["mousePressed", "mouseReleased"] {
+            self.call(
+                "Input.dispatchMouseEvent",
+                json!({
+                    "type": event,
+                    "x": point.x,
+                    "y": point.y,
+                    "button": "left",
+                    "clickCount": 1,
+                }),
+            )
+            .await?;
+        }
+        tokio::time::sleep(CLICK_SETTLE).await;
+        self.page_info().await
+    }
+
+    async fn page_info(&mut self) -> Result<PageInfo, String> {
+        let value = self
+            .evaluate("({ url: location.href, title: document.title })")
+            .await?;
+        serde_json::from_value(value).map_err(|err| format!("unexpected page info: {err}"))
+    }
+
+    /// Evaluates `expression` in the page and returns its JSON value.
+    async fn evaluate(&mut self, expression: &str) -> Result<Value, String> {
+        let mut result = self
+            .call(
+                "Runtime.evaluate",
+                json!({
+                    "expression": expression,
+                    "returnByValue": true,
+                    "awaitPromise": true,
+                }),
+            )
+            .await?;
+        if let Some(details) = result.get("exceptionDetails") {
+            let message = details["exception"]["description"]
+                .as_str()
+                .or_else(|| details["text"].as_str())
+                .unwrap_or("script failed");
+            return Err(message.to_string());
+        }
+        Ok(result["result"]["value"].take())
+    }
+
+    /// A page command, bounded by the configured timeout.
+    async fn call(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let timeout = self.timeout;
+        tokio::time::timeout(timeout, self.cdp.call(Some(&self.page), method, params))
+            .await
+            .map_err(|_| format!("{method} timed out after {}s", timeout.as_secs()))?
+    }
+}
+
+/// Whether opening `url` needs the user's approval: everything except
+/// loopback hosts and `browser.allowed_origins` does.
+pub(crate) fn needs_approval(url: &Url, config: &BrowserConfig) -> bool {
+    let loopback = match url.host() {
+        Some(Host::Domain(domain)) => domain == "localhost" || domain.ends_with(".localhost"),
+        Some(Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    };
+    if loopback {
+        return false;
+    }
+    let origin = url.origin();
+    !config
+        .allowed_origins
+        .iter()
+        .any(|allowed| Url::parse(allowed.trim()).is_ok_and(|allowed| allowed.origin() == origin))
+}
+
+fn find_executable(config: &BrowserConfig) -> Result<PathBuf, String> {
+    if let Some(executable) = &config.executable {
+        return Ok(executable.clone());
+    }
+    if let Some(path) = BROWSER_CANDIDATES
+        .iter()
+        .find_map(|name| which::which(name).ok())
+    {
+        return Ok(path);
+    }
+    if cfg!(target_os = "macos") && Path::new(MACOS_CHROME).exists() {
+        return Ok(PathBuf::from(MACOS_CHROME));
+    }
+    Err("no Chromium or Chrome found; set browser.executable in config.toml".to_string())
+}
+
+fn launch_args(config: &BrowserConfig, profile: &Path) -> Vec<String> {
+    let mut args = vec![
+        "--remote-debugging-port=0".to_string(),
+        format!("--user-data-dir={}", profile.display()),
+        format!("--window-size={},{}", config.width, config.height),
+        "--no-first-run".to_string(),
+        "--no-default-browser-check".to_string(),
+        "--disable-extensions".to_string(),
+    ];
+    if config.headless {
+        args.push("--headless=new".to_string());
+    }
+    // Chromium refuses to start its sandbox as root, which is common in
+    // containers.
+    if running_as_root() {
+        args.push("--no-sandbox".to_string());
+    }
+    args.push("about:blank".to_string());
+    args
+}
+
+#[cfg(unix)]
+fn running_as_root() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(not(unix))]
+fn running_as_root() -> bool {
+    false
+}
+
+/// Reads stderr until Chromium prints its DevTools endpoint, then keeps
+/// draining it in the background so the browser never blocks on a full pipe.
+async fn read_devtools_url(stderr: ChildStderr) -> Result<String, String> {
+    let mut lines = BufReader::new(stderr).lines();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|err| format!("failed to read browser output: {err}"))?
+    {
+        if let Some(url) = devtools_url(&line) {
+            tokio::spawn(async move {
+                while let Ok(Some(line)) = lines.next_line().await {
+                    debug!(target: "codex_core::browser", "{line}");
+                }
+            });
+            return Ok(url);
+        }
+    }
+    Err("the browser exited before opening a DevTools port".to_string())
+}
+
+fn devtools_url(line: &str) -> Option<String> {
+    let url = line.trim().strip_prefix("DevTools listening on ")?;
+    url.starts_with("ws://").then(|| url.to_string())
+}
+
+fn no_match(selector: &str) -> String {
+    format!("no element matches `{selector}`")
+}
+
+fn read_text_script(selector: Option<&str>) -> String {
+    let target = match selector {
+        Some(selector) => format!("document.querySelector({})", js_string(selector)),
+        None => "document.body".to_string(),
+    };
+    format!(
+        "(() => {{ const el = {target}; if (!el) return null; \
+         return {{ url: location.href, title: document.title, text: el.innerText }}; }})()"
+    )
+}
+
+fn element_center_script(selector: &str) -> String {
+    format!(
+        "(() => {{ const el = document.querySelector({}); if (!el) return null; \
+         el.scrollIntoView({{ block: 'center', inline: 'center' }}); \
+         const r = el.getBoundingClientRect(); \
+         return {{ x: r.left + r.width / 2, y: r.top + r.height / 2 }}; }})()",
+        js_string(selector)
+    )
+}
+
+/// `value` as a JavaScript string literal.
+fn js_string(value: &str) -> String {
+    Value::String(value.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn loopback_and_listed_origins_skip_approval() {
+        let config = BrowserConfig {
+            allowed_origins: vec!["https://staging.example.com/".to_string()],
+            ..BrowserConfig::default()
+        };
+        let needs = |url: &str| needs_approval(&Url::parse(url).expect("valid url"), &config);
+        assert!(!needs("http://localhost:3000/login"));
+        assert!(!needs("http://127.0.0.1:8080/"));
+        assert!(!needs("https://staging.example.com/dashboard"));
+        assert!(needs("http://staging.example.com/"));
+        assert!(needs("https://example.com/"));
+    }
+
+    #[test]
+    fn reads_devtools_url_and_quotes_selectors() {
+        assert_eq!(
+            devtools_url("DevTools listening on ws://127.0.0.1:41235/devtools/browser/abc\n"),
+            Some("ws://127.0.0.1:41235/devtools/browser/abc".to_string())
+        );
+        assert_eq!(devtools_url("[1234:5678:ERROR] something else"), None);
+        assert_eq!(
+            read_text_script(Some("a[title=\"x\"]")),
+            "(() => { const el = document.querySelector(\"a[title=\\\"x\\\"]\"); if (!el) return null; \
+             return { url: location.href, title: document.title, text: el.innerText }; })()"
+        );
+    }
+}
@@ -0,0 +1,482 @@
+//! Report of which optional subsystems are usable in this environment.
+//!
+//! Each check answers "is it on, and if not, why": the sandbox backend, the
+//! credential keyring, image input for the configured model, MCP servers, and
+//! a few tools Codex shells out to. `codex capabilities` and the app-server
+//! `capabilities/read` request both render this report; the tool list is also
+//! part of the app-server `initialize` response.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use codex_app_server_protocol::AuthMode;
+use codex_app_server_protocol::Capability;
+use codex_app_server_protocol::CapabilityStatus;
+use codex_keyring_store::DefaultKeyringStore;
+use codex_keyring_store::KeyringStore;
+use codex_protocol::config_types::WebSearchMode;
+use codex_protocol::openai_models::ModelInfo;
+use codex_protocol::protocol::McpStartupCompleteEvent;
+use codex_protocol::protocol::SandboxPolicy;
+use codex_rmcp_client::OAuthCredentialsStoreMode;
+
+use crate::auth::AuthCredentialsStoreMode;
+use crate::auth::CodexAuth;
+use crate::auth::read_openai_api_key_from_env;
+use crate::config::Config;
+use crate::config::types::McpServerConfig;
+use crate::features::Feature;
+use crate::mcp::probe_mcp_servers;
+use crate::models_manager::manager::OPENAI_DEFAULT_CHATGPT_MODEL;
+use crate::models_manager::model_info::find_model_info_for_slug;
+use crate::models_manager::model_info::with_config_overrides;
+use crate::seatbelt::MACOS_PATH_TO_SEATBELT_EXECUTABLE;
+use crate::tools::spec::ToolsConfig;
+use crate::tools::spec::ToolsConfigParams;
+use crate::tools::spec::build_specs;
+
+/// Keyring entry read to check that the backend answers. It never exists, so
+/// a working keyring reports "no entry" without prompting.
+const KEYRING_PROBE_SERVICE: &str = "Codex Auth";
+const KEYRING_PROBE_ACCOUNT: &str = "capabilities-probe";
+
+/// Runs every check against `config`. MCP servers are started (and stopped
+/// again) unless `skip_mcp` is set, in which case only their config is
+/// reported.
+pub async fn collect_capabilities(config: &Config, skip_mcp: bool) -> Vec<Capability> {
+    let mut capabilities = vec![
+        auth_capability(config),
+        sandbox_capability(config),
+        keyring_capability(
+            config.cli_auth_credentials_store_mode,
+            config.mcp_oauth_credentials_store_mode,
+            &DefaultKeyringStore,
+        ),
+        vision_capability(config),
+        web_search_capability(config.web_search_mode),
+        git_capability(),
+    ];
+    let startup = if skip_mcp {
+        None
+    } else {
+        Some(probe_mcp_servers(config).await)
+    };
+    capabilities.extend(mcp_capabilities(&config.mcp_servers, startup.as_ref()));
+    capabilities
+}
+
+/// Names of the built-in tools a new thread would offer the model under
+/// `config`, sorted. MCP tools are not included.
+pub fn builtin_tool_names(config: &Config) -> Vec<String> {
+    let (_, model_info) = configured_model(config);
+    let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
+        model_info: &model_info,
+        features: &config.features,
+        web_search_mode: config.web_search_mode,
+    });
+    tools_config.hosted_tools = config.hosted_tools_for(&model_info.slug);
+    tools_config.local_web_search = config.web_search_provider.is_some();
+    tools_config.browser_tools = config.browser.is_some();
+    let (specs, _) = build_specs(&tools_config, None).build();
+    let mut names: Vec<String> = specs
+        .iter()
+        .map(|tool| tool.spec.name().to_string())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// The model a new thread would use, without consulting the remote model
+/// list.
+fn configured_model(config: &Config) -> (&str, ModelInfo) {
+    let model = config
+        .model
+        .as_deref()
+        .unwrap_or(OPENAI_DEFAULT_CHATGPT_MODEL);
+    let model_info = with_config_overrides(find_model_info_for_slug(model), config);
+    (model, model_info)
+}
+
+fn capability(id: &str, status: CapabilityStatus, detail: impl Into<String>) -> Capability {
+    Capability {
+        id: id.to_string(),
+        status,
+        detail: detail.into(),
+    }
+}
+
+fn auth_capability(config: &Config) -> Capability {
+    if !config.model_provider.requires_openai_auth {
+        return capability(
+            "auth",
+            CapabilityStatus::Active,
+            format!(
+                "provider `{}` does not use a Codex login",
+                config.model_provider_id
+            ),
+        );
+    }
+    match CodexAuth::from_auth_storage(&config.codex_home, config.cli_auth_credentials_store_mode) {
+        Ok(Some(auth)) => {
+            let detail = match auth.mode {
+                AuthMode::ChatGPT => "signed in with ChatGPT",
+                AuthMode::ApiKey => "signed in with an API key",
+            };
+            capability("auth", CapabilityStatus::Active, detail)
+        }
+        Ok(None) if read_openai_api_key_from_env().is_some() => {
+            capability("auth", CapabilityStatus::Active, "OPENAI_API_KEY is set")
+        }
+        Ok(None) => capability(
+            "auth",
+            CapabilityStatus::Unavailable,
+            "not signed in; run `codex login`",
+        ),
+        Err(err) => capability(
+            "auth",
+            CapabilityStatus::Unavailable,
+            format!("failed to read stored credentials: {err}"),
+        ),
+    }
+}
+
+fn sandbox_capability(config: &Config) -> Capability {
+    match config.sandbox_policy.get() {
+        SandboxPolicy::DangerFullAccess => {
+            return capability(
+                "sandbox",
+                CapabilityStatus::Disabled,
+                "sandbox_mode is danger-full-access",
+            );
+        }
+        SandboxPolicy::ExternalSandbox { .. } => {
+            return capability(
+                "sandbox",
+                CapabilityStatus::Disabled,
+                "commands run in an external sandbox",
+            );
+        }
+        SandboxPolicy::ReadOnly | SandboxPolicy::WorkspaceWrite { .. } => {}
+    }
+
+    if let Some(container) = &config.container_sandbox {
+        let program = container.runtime.program();
+        return if which::which(program).is_ok() {
+            capability(
+                "sandbox",
+                CapabilityStatus::Active,
+                format!("{program} container ({})", container.image),
+            )
+        } else {
+            capability(
+                "sandbox",
+                CapabilityStatus::Unavailable,
+                format!("`{program}` not found on PATH (sandbox.backend = \"container\")"),
+            )
+        };
+    }
+
+    if cfg!(target_os = "macos") {
+        seatbelt_capability(Path::new(MACOS_PATH_TO_SEATBELT_EXECUTABLE))
+    } else if cfg!(target_os = "linux") {
+        linux_sandbox_capability(config.codex_linux_sandbox_exe.as_deref())
+    } else if cfg!(target_os = "windows") {
+        if config.features.enabled(Feature::WindowsSandboxElevated) {
+            capability(
+                "sandbox",
+                CapabilityStatus::Active,
+                "Windows restricted token (elevated)",
+            )
+        } else if config.features.enabled(Feature::WindowsSandbox) {
+            capability(
+                "sandbox",
+                CapabilityStatus::Active,
+                "Windows restricted token",
+            )
+        } else {
+            capability(
+                "sandbox",
+                CapabilityStatus::Unavailable,
+                "enable `experimental_windows_sandbox` or `elevated_windows_sandbox` to sandbox commands",
+            )
+        }
+    } else {
+        capability(
+            "sandbox",
+            CapabilityStatus::Unavailable,
+            "no sandbox backend exists for this platform",
+        )
+    }
+}
+
+fn seatbelt_capability(sandbox_exec: &Path) -> Capability {
+    if sandbox_exec.is_file() {
+        capability(
+            "sandbox",
+            CapabilityStatus::Active,
+            format!("Seatbelt ({})", sandbox_exec.display()),
+        )
+    } else {
+        capability(
+            "sandbox",
+            CapabilityStatus::Unavailable,
+            format!("{} not found", sandbox_exec.display()),
+        )
+    }
+}
+
+fn linux_sandbox_capability(helper: Option<&Path>) -> Capability {
+    match helper {
+        Some(helper) if helper.is_file() => capability(
+            "sandbox",
+            CapabilityStatus::Active,
+            format!("Landlock and seccomp ({})", helper.display()),
+        ),
+        Some(helper) => capability(
+            "sandbox",
+            CapabilityStatus::Unavailable,
+            format!("sandbox helper {} not found", helper.display()),
+        ),
+        None => capability(
+            "sandbox",
+            CapabilityStatus::Unavailable,
+            "this process was started without the codex-linux-sandbox helper",
+        ),
+    }
+}
+
+fn keyring_capability(
+    cli_mode: AuthCredentialsStoreMode,
+    mcp_mode: OAuthCredentialsStoreMode,
+    store: &dyn KeyringStore,
+) -> Capability {
+    if cli_mode == AuthCredentialsStoreMode::File && mcp_mode == OAuthCredentialsStoreMode::File {
+        return capability(
+            "keyring",
+            CapabilityStatus::Disabled,
+            "cli_auth_credentials_store and mcp_oauth_credentials_store are \"file\"",
+        );
+    }
+    match store.load(KEYRING_PROBE_SERVICE, KEYRING_PROBE_ACCOUNT) {
+        Ok(_) => capability("keyring", CapabilityStatus::Active, "system keyring"),
+        Err(err) => {
+            let consequence = if cli_mode == AuthCredentialsStoreMode::Keyring
+                || mcp_mode == OAuthCredentialsStoreMode::Keyring
+            {
+                "logins that require the keyring will fail"
+            } else {
+                "credentials fall back to files in CODEX_HOME"
+            };
+            capability(
+                "keyring",
+                CapabilityStatus::Unavailable,
+                format!("{}; {consequence}", err.message()),
+            )
+        }
+    }
+}
+
+fn vision_capability(config: &Config) -> Capability {
+    let (model, model_info) = configured_model(config);
+    if model_info.supports_images() {
+        capability(
+            "vision",
+            CapabilityStatus::Active,
+            format!("{model} accepts image input"),
+        )
+    } else if config.model_supports_images == Some(false) {
+        capability(
+            "vision",
+            CapabilityStatus::Disabled,
+            "model_supports_images = false",
+        )
+    } else {
+        capability(
+            "vision",
+            CapabilityStatus::Unavailable,
+            format!("{model} does not accept image input"),
+        )
+    }
+}
+
+fn web_search_capability(mode: Option<WebSearchMode>) -> Capability {
+    match mode {
+        Some(WebSearchMode::Live) => {
+            capability("web_search", CapabilityStatus::Active, "live results")
+        }
+        Some(WebSearchMode::Cached) => {
+            capability("web_search", CapabilityStatus::Active, "cached results")
+        }
+        Some(WebSearchMode::Disabled) | None => capability(
+            "web_search",
+            CapabilityStatus::Disabled,
+            "web_search is off",
+        ),
+    }
+}
+
+fn git_capability() -> Capability {
+    match which::which("git") {
+        Ok(path) => capability("git", CapabilityStatus::Active, path.display().to_string()),
+        Err(_) => capability(
+            "git",
+            CapabilityStatus::Unavailable,
+            "git not found on PATH; undo, turn diffs, and checkpoints need it",
+        ),
+    }
+}
+
+/// One entry per configured server, sorted by name. Without a startup
+/// summary, enabled servers are reported as configured but not checked.
+fn mcp_capabilities(
+    servers: &HashMap<String, McpServerConfig>,
+    startup: Option<&(McpStartupCompleteEvent, HashMap<String, usize>)>,
+) -> Vec<Capability> {
+    let mut names: Vec<&String> = servers.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            let id = format!("mcp:{name}");
+            let server = &servers[name];
+            if !server.enabled {
+                let detail = match &server.disabled_reason {
+                    Some(reason) => format!("disabled by {reason}"),
+                    None => "enabled = false".to_string(),
+                };
+                return capability(&id, CapabilityStatus::Disabled, detail);
+            }
+            let Some((summary, tool_counts)) = startup else {
+                return capability(&id, CapabilityStatus::Active, "configured; not started");
+            };
+            if let Some(failure) = summary.failed.iter().find(|f| &f.server == name) {
+                capability(&id, CapabilityStatus::Unavailable, failure.error.clone())
+            } else if summary.ready.contains(name) {
+                let tools = tool_counts.get(name).copied().unwrap_or(0);
+                capability(
+                    &id,
+                    CapabilityStatus::Active,
+                    format!("ready with {tools} tool(s)"),
+                )
+            } else {
+                capability(&id, CapabilityStatus::Unavailable, "startup did not finish")
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_keyring_store::tests::MockKeyringStore;
+    use codex_protocol::protocol::McpStartupFailure;
+    use keyring::Error as KeyringError;
+    use pretty_assertions::assert_eq;
+
+    use crate::config::types::McpServerTransportConfig;
+
+    fn stdio_server(enabled: bool) -> McpServerConfig {
+        McpServerConfig {
+            transport: McpServerTransportConfig::Stdio {
+                command: "server".to_string(),
+                args: Vec::new(),
+                env: None,
+                env_vars: Vec::new(),
+                cwd: None,
+            },
+            enabled,
+            disabled_reason: None,
+            startup_timeout_sec: None,
+            tool_timeout_sec: None,
+            enabled_tools: None,
+            disabled_tools: None,
+            default_tool_approval: None,
+            tool_approvals: None,
+        }
+    }
+
+    #[test]
+    fn keyring_errors_explain_the_fallback() {
+        let store = MockKeyringStore::default();
+        assert_eq!(
+            keyring_capability(
+                AuthCredentialsStoreMode::Auto,
+                OAuthCredentialsStoreMode::Auto,
+                &store,
+            )
+            .status,
+            CapabilityStatus::Active
+        );
+
+        store.set_error(
+            KEYRING_PROBE_ACCOUNT,
+            KeyringError::PlatformFailure("no secret service".into()),
+        );
+        let auto = keyring_capability(
+            AuthCredentialsStoreMode::Auto,
+            OAuthCredentialsStoreMode::Auto,
+            &store,
+        );
+        assert_eq!(auto.status, CapabilityStatus::Unavailable);
+        assert!(
+            auto.detail
+                .ends_with("credentials fall back to files in CODEX_HOME")
+        );
+        let required = keyring_capability(
+            AuthCredentialsStoreMode::Keyring,
+            OAuthCredentialsStoreMode::Auto,
+            &store,
+        );
+        assert!(required.detail.ends_with("will fail"));
+
+        assert_eq!(
+            keyring_capability(
+                AuthCredentialsStoreMode::File,
+                OAuthCredentialsStoreMode::File,
+                &store,
+            )
+            .status,
+            CapabilityStatus::Disabled
+        );
+    }
+
+    #[test]
+    fn mcp_servers_report_startup_outcome() {
+        let servers = HashMap::from([
+            ("docs".to_string(), stdio_server(true)),
+            ("broken".to_string(), stdio_server(true)),
+            ("off".to_string(), stdio_server(false)),
+        ]);
+        let summary = McpStartupCompleteEvent {
+            ready: vec!["docs".to_string()],
+            failed: vec![McpStartupFailure {
+                server: "broken".to_string(),
+                error: "connection refused".to_string(),
+            }],
+            cancelled: Vec::new(),
+        };
+        let startup = (summary, HashMap::from([("docs".to_string(), 3)]));
+
+        assert_eq!(
+            mcp_capabilities(&servers, Some(&startup)),
+            vec![
+                capability(
+                    "mcp:broken",
+                    CapabilityStatus::Unavailable,
+                    "connection refused"
+                ),
+                capability("mcp:docs", CapabilityStatus::Active, "ready with 3 tool(s)"),
+                capability("mcp:off", CapabilityStatus::Disabled, "enabled = false"),
+            ]
+        );
+        assert_eq!(
+            mcp_capabilities(&servers, None)[1],
+            capability(
+                "mcp:docs",
+                CapabilityStatus::Active,
+                "configured; not started"
+            )
+        );
+    }
+}
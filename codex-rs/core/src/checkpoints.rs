@@ -0,0 +1,176 @@
+//! Named checkpoints created via `Op::CreateCheckpoint`.
+//!
+//! A checkpoint is a ghost snapshot of the working tree that the user labels
+//! explicitly. Unlike the per-turn snapshots consumed by `Op::Undo`, any
+//! checkpoint can be restored by id, which reverts several turns at once.
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use codex_git::CreateGhostCommitOptions;
+use codex_git::GhostCommit;
+use codex_git::GitToolingError;
+use codex_git::create_ghost_commit;
+use codex_git::diff_stat_between;
+use codex_protocol::protocol::Checkpoint;
+use codex_protocol::protocol::CheckpointCreatedEvent;
+use codex_protocol::protocol::CheckpointDiffStat;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ListCheckpointsResponseEvent;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+
+#[derive(Clone)]
+pub(crate) struct StoredCheckpoint {
+    pub(crate) checkpoint: Checkpoint,
+    pub(crate) ghost_commit: GhostCommit,
+}
+
+#[derive(Default)]
+pub(crate) struct Checkpoints {
+    entries: Mutex<Vec<StoredCheckpoint>>,
+    next_id: AtomicU64,
+}
+
+impl Checkpoints {
+    async fn push(&self, name: String, ghost_commit: GhostCommit) -> Checkpoint {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let checkpoint = Checkpoint {
+            id: id.to_string(),
+            name,
+            commit_id: ghost_commit.id().to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            diffstat: None,
+        };
+        self.entries.lock().await.push(StoredCheckpoint {
+            checkpoint: checkpoint.clone(),
+            ghost_commit,
+        });
+        checkpoint
+    }
+
+    async fn snapshot(&self) -> Vec<StoredCheckpoint> {
+        self.entries.lock().await.clone()
+    }
+
+    pub(crate) async fn get(&self, id: &str) -> Option<StoredCheckpoint> {
+        self.entries
+            .lock()
+            .await
+            .iter()
+            .find(|entry| entry.checkpoint.id == id)
+            .cloned()
+    }
+
+    /// Drops every checkpoint created after `id`, which stays in place so it
+    /// can be restored again.
+    pub(crate) async fn truncate_after(&self, id: &str) {
+        let mut entries = self.entries.lock().await;
+        if let Some(idx) = entries.iter().position(|entry| entry.checkpoint.id == id) {
+            entries.truncate(idx + 1);
+        }
+    }
+}
+
+pub(crate) async fn create_checkpoint(sess: &Session, sub_id: String, name: String) {
+    let turn_context = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+    let name = match name.trim() {
+        "" => format!(
+            "checkpoint {}",
+            sess.services.checkpoints.next_id.load(Ordering::Relaxed) + 1
+        ),
+        name => name.to_string(),
+    };
+
+    let msg =
+        match snapshot_worktree(&turn_context, Some(format!("codex checkpoint: {name}"))).await {
+            Ok(ghost_commit) => {
+                let checkpoint = sess.services.checkpoints.push(name, ghost_commit).await;
+                EventMsg::CheckpointCreated(CheckpointCreatedEvent { checkpoint })
+            }
+            Err(message) => EventMsg::Error(ErrorEvent {
+                message,
+                codex_error_info: None,
+            }),
+        };
+    sess.send_event_raw(Event { id: sub_id, msg }).await;
+}
+
+pub(crate) async fn list_checkpoints(sess: &Session, sub_id: String) {
+    let entries = sess.services.checkpoints.snapshot().await;
+    let mut checkpoints: Vec<Checkpoint> = entries
+        .iter()
+        .map(|entry| entry.checkpoint.clone())
+        .collect();
+
+    if !checkpoints.is_empty() {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+        match snapshot_worktree(&turn_context, None).await {
+            Ok(current) => {
+                let repo_path = turn_context.cwd.clone();
+                let stats = tokio::task::spawn_blocking(move || {
+                    entries
+                        .iter()
+                        .map(|entry| {
+                            diff_stat_between(&repo_path, entry.ghost_commit.id(), current.id())
+                                .map_err(|err| {
+                                    warn!(
+                                        "failed to diff checkpoint {}: {err}",
+                                        entry.checkpoint.id
+                                    )
+                                })
+                                .ok()
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await
+                .unwrap_or_default();
+                for (checkpoint, stat) in checkpoints.iter_mut().zip(stats) {
+                    checkpoint.diffstat = stat.map(|stat| CheckpointDiffStat {
+                        files_changed: stat.files_changed,
+                        insertions: stat.insertions,
+                        deletions: stat.deletions,
+                    });
+                }
+            }
+            Err(message) => {
+                warn!("failed to snapshot working tree for checkpoint diffstat: {message}")
+            }
+        }
+    }
+
+    sess.send_event_raw(Event {
+        id: sub_id,
+        msg: EventMsg::ListCheckpointsResponse(ListCheckpointsResponseEvent { checkpoints }),
+    })
+    .await;
+}
+
+async fn snapshot_worktree(
+    turn_context: &TurnContext,
+    message: Option<String>,
+) -> Result<GhostCommit, String> {
+    let repo_path = turn_context.cwd.clone();
+    let ghost_snapshot = turn_context.ghost_snapshot.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let mut options = CreateGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot);
+        if let Some(message) = message.as_deref() {
+            options = options.message(message);
+        }
+        create_ghost_commit(&options)
+    })
+    .await;
+    match result {
+        Ok(Ok(ghost_commit)) => Ok(ghost_commit),
+        Ok(Err(GitToolingError::NotAGitRepository { .. })) => {
+            Err("Checkpoints require a Git repository.".to_string())
+        }
+        Ok(Err(err)) => Err(format!("Failed to snapshot the working tree: {err}")),
+        Err(err) => Err(format!("Checkpoint snapshot task failed: {err}")),
+    }
+}
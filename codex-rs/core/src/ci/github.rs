@@ -0,0 +1,130 @@
+//! The parts of the GitHub REST API `codex ci` needs: check runs of a commit
+//! and GitHub Actions job logs.
+
+use reqwest::RequestBuilder;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use super::CheckRun;
+use super::GitHubRepo;
+use crate::default_client::build_reqwest_client;
+
+pub const DEFAULT_GITHUB_API_URL: &str = "https://api.github.com";
+
+const CHECK_RUNS_PER_PAGE: usize = 100;
+/// Pages of check runs read per commit; more than 500 checks is not a
+/// configuration worth waiting on.
+const MAX_CHECK_RUN_PAGES: usize = 5;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GitHubError {
+    #[error("GitHub request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("GitHub returned {status} for {url}: {message}")]
+    Status {
+        status: StatusCode,
+        url: String,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct GitHubClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CheckRunsPage {
+    total_count: usize,
+    check_runs: Vec<CheckRun>,
+}
+
+impl GitHubClient {
+    pub fn new(api_url: impl Into<String>, token: Option<String>) -> Self {
+        Self {
+            client: build_reqwest_client(),
+            api_url: api_url.into().trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    /// Uses `GITHUB_API_URL` (for GitHub Enterprise) and `GITHUB_TOKEN` or
+    /// `GH_TOKEN` from the environment. Public repositories work without a
+    /// token, subject to lower rate limits; job logs always need one.
+    pub fn from_env() -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        Self::new(
+            env("GITHUB_API_URL").unwrap_or_else(|| DEFAULT_GITHUB_API_URL.to_string()),
+            env("GITHUB_TOKEN").or_else(|| env("GH_TOKEN")),
+        )
+    }
+
+    /// Every check run reported for `sha`.
+    pub async fn check_runs(
+        &self,
+        repo: &GitHubRepo,
+        sha: &str,
+    ) -> Result<Vec<CheckRun>, GitHubError> {
+        let mut runs = Vec::new();
+        for page in 1..=MAX_CHECK_RUN_PAGES {
+            let url = format!(
+                "{}/repos/{}/{}/commits/{sha}/check-runs?per_page={CHECK_RUNS_PER_PAGE}&page={page}",
+                self.api_url, repo.owner, repo.name
+            );
+            let page: CheckRunsPage = self.send(self.get(&url), &url).await?.json().await?;
+            let last_page = page.check_runs.len() < CHECK_RUNS_PER_PAGE;
+            runs.extend(page.check_runs);
+            if last_page || runs.len() >= page.total_count {
+                break;
+            }
+        }
+        Ok(runs)
+    }
+
+    /// The plain-text log of a GitHub Actions job.
+    pub async fn job_log(&self, repo: &GitHubRepo, job_id: u64) -> Result<String, GitHubError> {
+        let url = format!(
+            "{}/repos/{}/{}/actions/jobs/{job_id}/logs",
+            self.api_url, repo.owner, repo.name
+        );
+        // The response redirects to a short-lived download URL, which reqwest
+        // follows without forwarding the token.
+        Ok(self.send(self.get(&url), &url).await?.text().await?)
+    }
+
+    fn get(&self, url: &str) -> RequestBuilder {
+        let request = self
+            .client
+            .get(url)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send(
+        &self,
+        request: RequestBuilder,
+        url: &str,
+    ) -> Result<reqwest::Response, GitHubError> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|value| value.get("message")?.as_str().map(str::to_string))
+            .unwrap_or(body);
+        Err(GitHubError::Status {
+            status,
+            url: url.to_string(),
+            message,
+        })
+    }
+}
@@ -0,0 +1,367 @@
+//! Reconciling local agent work with remote CI.
+//!
+//! After a branch is pushed, `codex ci watch` polls the checks GitHub reports
+//! for the pushed commit. When some fail, the logs of the failing GitHub
+//! Actions jobs go through the same triage as failing local test runs (see
+//! `test_failures`), and a headless fix turn starts from the digest
+//! (see [`run_fix_turn`]). Its commits are pushed and the checks are watched
+//! again, up to a fixed number of fix turns.
+
+mod github;
+mod runner;
+
+use std::fmt::Write as _;
+
+use serde::Deserialize;
+
+use crate::test_failures::digest_output;
+use crate::truncate::TruncationPolicy;
+
+pub use github::DEFAULT_GITHUB_API_URL;
+pub use github::GitHubClient;
+pub use github::GitHubError;
+pub use runner::CiFixOutcome;
+pub use runner::run_fix_turn;
+
+/// Token budget for the raw log kept below a job's failure digest.
+const JOB_LOG_TOKENS: usize = 4_000;
+/// Lines kept from the end of a job log in which no test failure is
+/// recognized (build errors, lint failures).
+const JOB_LOG_TAIL_LINES: usize = 120;
+/// Failing checks described in one fix prompt.
+const MAX_CHECKS_PER_PROMPT: usize = 5;
+
+/// `owner/name` of a GitHub repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubRepo {
+    pub owner: String,
+    pub name: String,
+}
+
+impl GitHubRepo {
+    /// Parses a git remote URL: `https://host/owner/name(.git)`,
+    /// `ssh://git@host/owner/name.git`, or `git@host:owner/name.git`.
+    pub fn from_remote_url(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let path = match url.split_once("://") {
+            Some((_, rest)) => rest.split_once('/')?.1,
+            None => url.split_once(':')?.1,
+        };
+        let path = path.trim_end_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        let mut segments = path.rsplit('/');
+        let name = segments.next()?;
+        let owner = segments.next()?;
+        if owner.is_empty() || name.is_empty() {
+            return None;
+        }
+        Some(Self {
+            owner: owner.to_string(),
+            name: name.to_string(),
+        })
+    }
+}
+
+/// A check run as returned by the GitHub checks API.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CheckRun {
+    pub id: u64,
+    pub name: String,
+    /// `queued`, `in_progress`, or `completed`.
+    pub status: String,
+    /// Set once completed: `success`, `failure`, `timed_out`, `skipped`, ...
+    #[serde(default)]
+    pub conclusion: Option<String>,
+    #[serde(default)]
+    pub html_url: Option<String>,
+    #[serde(default)]
+    pub app: Option<CheckRunApp>,
+    #[serde(default)]
+    pub output: Option<CheckRunOutput>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CheckRunApp {
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CheckRunOutput {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub summary: Option<String>,
+}
+
+impl CheckRun {
+    pub fn is_completed(&self) -> bool {
+        self.status == "completed"
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self.conclusion.as_deref(),
+            Some("failure" | "timed_out" | "cancelled" | "action_required")
+        )
+    }
+
+    /// Whether this check is a GitHub Actions job, whose id is also the job
+    /// id for fetching its log.
+    pub fn is_github_actions(&self) -> bool {
+        self.app
+            .as_ref()
+            .is_some_and(|app| app.slug == "github-actions")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CiState {
+    /// No check has been reported for the commit (yet).
+    NoChecks,
+    Pending {
+        completed: usize,
+        total: usize,
+    },
+    Passed,
+    /// Every check completed and these failed.
+    Failed(Vec<CheckRun>),
+}
+
+/// The state of a commit's checks. Failures are only reported once every
+/// check has completed, so a fix turn sees all of them at once.
+pub fn ci_state(runs: &[CheckRun]) -> CiState {
+    if runs.is_empty() {
+        return CiState::NoChecks;
+    }
+    let completed = runs.iter().filter(|run| run.is_completed()).count();
+    if completed < runs.len() {
+        return CiState::Pending {
+            completed,
+            total: runs.len(),
+        };
+    }
+    let failed: Vec<CheckRun> = runs
+        .iter()
+        .filter(|run| run.is_failure())
+        .cloned()
+        .collect();
+    if failed.is_empty() {
+        CiState::Passed
+    } else {
+        CiState::Failed(failed)
+    }
+}
+
+/// What a fix turn is told about one failing check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailedCheck {
+    pub name: String,
+    pub conclusion: String,
+    pub url: Option<String>,
+    /// Triage digest of the job log, the end of the log, or the check's own
+    /// summary when there is no log.
+    pub details: String,
+}
+
+impl FailedCheck {
+    /// Describes a failing check from its log, when one could be fetched,
+    /// and otherwise from the summary the check reported.
+    pub fn new(run: &CheckRun, log: Option<&str>) -> Self {
+        let details = match log {
+            Some(log) => digest_job_log(log),
+            None => run
+                .output
+                .as_ref()
+                .map(|output| {
+                    [output.title.as_deref(), output.summary.as_deref()]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .filter(|details| !details.trim().is_empty())
+                .unwrap_or_else(|| "No log or summary is available for this check.".to_string()),
+        };
+        Self {
+            name: run.name.clone(),
+            conclusion: run.conclusion.clone().unwrap_or_default(),
+            url: run.html_url.clone(),
+            details,
+        }
+    }
+}
+
+/// Condenses a GitHub Actions job log: the digest of the test failures it
+/// contains, or its last lines when none are recognized.
+pub fn digest_job_log(log: &str) -> String {
+    let log = strip_log_timestamps(log);
+    if let Some(digest) = digest_output(&log, TruncationPolicy::Tokens(JOB_LOG_TOKENS)) {
+        return digest;
+    }
+    let lines: Vec<&str> = log.lines().collect();
+    let start = lines.len().saturating_sub(JOB_LOG_TAIL_LINES);
+    let mut tail = String::new();
+    if start > 0 {
+        let _ = writeln!(tail, "[{start} earlier log lines omitted]");
+    }
+    tail.push_str(&lines[start..].join("\n"));
+    tail
+}
+
+/// Removes the timestamp GitHub Actions puts in front of every log line
+/// (`2024-05-01T12:34:56.1234567Z `), which would defeat the test parsers.
+fn strip_log_timestamps(log: &str) -> String {
+    log.lines()
+        .map(|line| match line.split_once(' ') {
+            Some((stamp, rest)) if is_log_timestamp(stamp) => rest,
+            _ if is_log_timestamp(line) => "",
+            _ => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_log_timestamp(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= 20
+        && bytes[4] == b'-'
+        && bytes[10] == b'T'
+        && text.ends_with('Z')
+        && text[..4].bytes().all(|b| b.is_ascii_digit())
+}
+
+/// The prompt of a fix turn for checks that failed on `sha` of `branch`.
+pub fn fix_prompt(branch: &str, sha: &str, failed: &[FailedCheck]) -> String {
+    let short_sha = sha.get(..7).unwrap_or(sha);
+    let mut prompt = format!(
+        "CI failed for branch `{branch}` at commit {short_sha}. Fix the cause of the failures \
+         below in this working tree, run the relevant checks locally where you can, and commit \
+         the fix. Do not push: the commit is pushed for you and CI is watched again.\n"
+    );
+    for check in failed.iter().take(MAX_CHECKS_PER_PROMPT) {
+        let _ = write!(prompt, "\n## `{}` ({})\n", check.name, check.conclusion);
+        if let Some(url) = &check.url {
+            let _ = writeln!(prompt, "{url}");
+        }
+        let _ = writeln!(prompt, "\n{}", check.details.trim_end());
+    }
+    if failed.len() > MAX_CHECKS_PER_PROMPT {
+        let _ = write!(
+            prompt,
+            "\n{} more failing check(s) are not shown; they may share a cause with the ones above.\n",
+            failed.len() - MAX_CHECKS_PER_PROMPT
+        );
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn run(name: &str, status: &str, conclusion: Option<&str>) -> CheckRun {
+        CheckRun {
+            id: 1,
+            name: name.to_string(),
+            status: status.to_string(),
+            conclusion: conclusion.map(str::to_string),
+            html_url: None,
+            app: None,
+            output: None,
+        }
+    }
+
+    #[test]
+    fn parses_github_remotes() {
+        let repo = |url: &str| GitHubRepo::from_remote_url(url).map(|r| (r.owner, r.name));
+        let expected = Some(("octo".to_string(), "widgets".to_string()));
+        assert_eq!(repo("https://github.com/octo/widgets.git"), expected);
+        assert_eq!(repo("https://github.com/octo/widgets/"), expected);
+        assert_eq!(repo("git@github.com:octo/widgets.git"), expected);
+        assert_eq!(repo("ssh://git@github.com/octo/widgets"), expected);
+        assert_eq!(repo("https://github.com/widgets"), None);
+    }
+
+    #[test]
+    fn waits_for_every_check_before_reporting_failures() {
+        assert_eq!(ci_state(&[]), CiState::NoChecks);
+        assert_eq!(
+            ci_state(&[
+                run("lint", "completed", Some("failure")),
+                run("test", "in_progress", None),
+            ]),
+            CiState::Pending {
+                completed: 1,
+                total: 2
+            }
+        );
+        assert_eq!(
+            ci_state(&[
+                run("lint", "completed", Some("success")),
+                run("docs", "completed", Some("skipped")),
+            ]),
+            CiState::Passed
+        );
+        assert_eq!(
+            ci_state(&[
+                run("lint", "completed", Some("failure")),
+                run("test", "completed", Some("success")),
+            ]),
+            CiState::Failed(vec![run("lint", "completed", Some("failure"))])
+        );
+    }
+
+    #[test]
+    fn digests_test_failures_in_timestamped_logs() {
+        let log = "\
+2024-05-01T12:00:00.0000000Z ##[group]Run cargo test
+2024-05-01T12:00:01.0000000Z running 1 test
+2024-05-01T12:00:01.0000000Z test math::adds ... FAILED
+2024-05-01T12:00:01.0000000Z
+2024-05-01T12:00:01.0000000Z failures:
+2024-05-01T12:00:01.0000000Z
+2024-05-01T12:00:01.0000000Z ---- math::adds stdout ----
+2024-05-01T12:00:01.0000000Z thread 'math::adds' panicked at src/math.rs:12:9:
+2024-05-01T12:00:01.0000000Z assertion failed: 1 + 1 == 3
+2024-05-01T12:00:01.0000000Z
+2024-05-01T12:00:01.0000000Z failures:
+2024-05-01T12:00:01.0000000Z     math::adds
+2024-05-01T12:00:01.0000000Z
+2024-05-01T12:00:01.0000000Z test result: FAILED. 0 passed; 1 failed
+";
+        let digest = digest_job_log(log);
+        assert!(digest.starts_with("Test failure digest (cargo test): 1 failing test"));
+        assert!(digest.contains("src/math.rs:12: assertion failed: 1 + 1 == 3"));
+
+        let build_log = (1..=130)
+            .map(|n| format!("2024-05-01T12:00:00.0000000Z line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let tail = digest_job_log(&build_log);
+        assert!(tail.starts_with("[10 earlier log lines omitted]\nline 11\n"));
+        assert!(tail.ends_with("line 130"));
+    }
+
+    #[test]
+    fn fix_prompt_lists_failed_checks() {
+        let mut lint = run("lint", "completed", Some("failure"));
+        lint.html_url = Some("https://github.com/octo/widgets/runs/1".to_string());
+        lint.output = Some(CheckRunOutput {
+            title: Some("clippy".to_string()),
+            summary: Some("2 warnings".to_string()),
+        });
+
+        assert_eq!(
+            fix_prompt(
+                "feature",
+                "0123456789abcdef",
+                &[FailedCheck::new(&lint, None)]
+            ),
+            "CI failed for branch `feature` at commit 0123456. Fix the cause of the failures \
+             below in this working tree, run the relevant checks locally where you can, and \
+             commit the fix. Do not push: the commit is pushed for you and CI is watched again.\n\
+             \n## `lint` (failure)\nhttps://github.com/octo/widgets/runs/1\n\nclippy\n2 warnings\n"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! Runs a fix turn for failing CI as a headless thread.
+
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::Op;
+use codex_protocol::user_input::UserInput;
+
+use crate::NewThread;
+use crate::ThreadManager;
+use crate::config::Config;
+use crate::error::Result as CodexResult;
+use crate::schedules::wait_for_turn;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CiFixOutcome {
+    /// Thread whose rollout records the fix turn.
+    pub thread_id: ThreadId,
+    pub last_agent_message: Option<String>,
+    /// Set when the turn failed.
+    pub error: Option<String>,
+}
+
+/// Starts a thread with `config` (whose cwd should be the repository), runs
+/// `prompt` (see [`super::fix_prompt`]) as a single turn, and shuts the thread
+/// down.
+///
+/// `config` is expected to disable approvals: nobody is around to answer them.
+pub async fn run_fix_turn(
+    thread_manager: &ThreadManager,
+    config: Config,
+    prompt: String,
+) -> CodexResult<CiFixOutcome> {
+    let NewThread {
+        thread_id, thread, ..
+    } = thread_manager.start_thread(config).await?;
+
+    thread
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: prompt,
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await?;
+    let (last_agent_message, error) = wait_for_turn(&thread).await?;
+
+    thread.submit(Op::Shutdown).await?;
+    while !matches!(thread.next_event().await?.msg, EventMsg::ShutdownComplete) {}
+    thread_manager.remove_thread(&thread_id).await;
+
+    Ok(CiFixOutcome {
+        thread_id,
+        last_agent_message,
+        error,
+    })
+}
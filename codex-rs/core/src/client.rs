@@ -1,14 +1,20 @@
 use std::sync::Arc;
 use std::sync::OnceLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 
 use crate::api_bridge::CoreAuthProvider;
-use crate::api_bridge::auth_provider_from_auth;
+use crate::api_bridge::auth_provider_for_request;
 use crate::api_bridge::map_api_error;
 use crate::auth::UnauthorizedRecovery;
 use codex_api::AggregateStreamExt;
+use codex_api::AnthropicClient as ApiAnthropicClient;
+use codex_api::AnthropicRequestBuilder as ApiAnthropicRequestBuilder;
 use codex_api::ChatClient as ApiChatClient;
 use codex_api::CompactClient as ApiCompactClient;
 use codex_api::CompactionInput as ApiCompactionInput;
+use codex_api::GeminiClient as ApiGeminiClient;
+use codex_api::GeminiRequestBuilder as ApiGeminiRequestBuilder;
 use codex_api::Prompt as ApiPrompt;
 use codex_api::RequestTelemetry;
 use codex_api::ReqwestTransport;
@@ -54,6 +60,7 @@ use crate::auth::RefreshTokenError;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
+use crate::client_common::tools::ToolSpec;
 use crate::config::Config;
 use crate::default_client::build_reqwest_client;
 use crate::error::CodexErr;
@@ -63,7 +70,10 @@ use crate::features::Feature;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
+use crate::text_tool_calls;
+use crate::tools::spec::create_tools_json_for_anthropic_api;
 use crate::tools::spec::create_tools_json_for_chat_completions_api;
+use crate::tools::spec::create_tools_json_for_gemini_api;
 use crate::tools::spec::create_tools_json_for_responses_api;
 
 pub const WEB_SEARCH_ELIGIBLE_HEADER: &str = "x-oai-web-search-eligible";
@@ -80,6 +90,20 @@ struct ModelClientState {
     effort: Option<ReasoningEffortConfig>,
     summary: ReasoningSummaryConfig,
     session_source: SessionSource,
+    /// Tools are passed as text rather than natively; see [`text_tool_calls`].
+    text_tool_calls: AtomicBool,
+}
+
+impl ModelClientState {
+    /// The model name sent to the provider, which is the Azure deployment
+    /// when one is mapped for the model.
+    fn request_model(&self) -> &str {
+        self.provider.request_model(&self.model_info.slug)
+    }
+
+    async fn api_auth(&self, auth: Option<crate::auth::CodexAuth>) -> Result<CoreAuthProvider> {
+        auth_provider_for_request(auth, &self.provider, &self.config.codex_home).await
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -117,6 +141,8 @@ impl ModelClient {
         conversation_id: ThreadId,
         session_source: SessionSource,
     ) -> Self {
+        let text_tool_calls = AtomicBool::new(!model_info.supports_tool_calls);
+        let provider = provider.for_model(&model_info.slug);
         Self {
             state: Arc::new(ModelClientState {
                 config,
@@ -128,6 +154,7 @@ impl ModelClient {
                 effort,
                 summary,
                 session_source,
+                text_tool_calls,
             }),
         }
     }
@@ -211,7 +238,7 @@ impl ModelClient {
             .state
             .provider
             .to_api_provider(auth.as_ref().map(|a| a.mode))?;
-        let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
+        let api_auth = self.state.api_auth(auth.clone()).await?;
         let transport = ReqwestTransport::new(build_reqwest_client());
         let request_telemetry = self.build_request_telemetry();
         let client = ApiCompactClient::new(transport, api_provider, api_auth)
@@ -219,7 +246,7 @@ impl ModelClient {
 
         let instructions = prompt.base_instructions.text.clone();
         let payload = ApiCompactionInput {
-            model: &self.state.model_info.slug,
+            model: self.state.request_model(),
             input: &prompt.input,
             instructions: &instructions,
         };
@@ -251,10 +278,42 @@ impl ModelClientSession {
     ///
     /// For Chat providers, the underlying stream is optionally aggregated
     /// based on the `show_raw_agent_reasoning` flag in the config.
+    ///
+    /// Models without native tool calling get the tools as text instead. A
+    /// provider that rejects a request for carrying tools switches the rest of
+    /// the session to text tool calls.
     pub async fn stream(&mut self, prompt: &Prompt) -> Result<ResponseStream> {
+        if prompt.tools.is_empty() {
+            return self.stream_prompt(prompt).await;
+        }
+        if !self.state.text_tool_calls.load(Ordering::Relaxed) {
+            match self.stream_prompt(prompt).await {
+                Err(CodexErr::InvalidRequest(body))
+                    if text_tool_calls::rejects_native_tools(&body) =>
+                {
+                    warn!(
+                        "{} rejected native tool calls; falling back to text tool calls: {body}",
+                        self.state.model_info.slug
+                    );
+                    self.state.text_tool_calls.store(true, Ordering::Relaxed);
+                }
+                result => return result,
+            }
+        }
+        let stream = self
+            .stream_prompt(&text_tool_calls::encode_prompt(prompt))
+            .await?;
+        Ok(text_tool_calls::decode_stream(stream, &prompt.tools))
+    }
+
+    async fn stream_prompt(&mut self, prompt: &Prompt) -> Result<ResponseStream> {
         match self.state.provider.wire_api {
             WireApi::Responses => self.stream_responses_api(prompt).await,
             WireApi::ResponsesWebsocket => self.stream_responses_websocket(prompt).await,
+            WireApi::Mock => crate::mock_provider::stream_mock_response(
+                self.state.config.mock_responses_file.as_deref(),
+                &prompt.input,
+            ),
             WireApi::Chat => {
                 let api_stream = self.stream_chat_completions(prompt).await?;
 
@@ -270,6 +329,20 @@ impl ModelClientSession {
                     ))
                 }
             }
+            WireApi::Anthropic => {
+                let api_stream = self.stream_anthropic_messages(prompt).await?;
+                Ok(map_response_stream(
+                    api_stream,
+                    self.state.otel_manager.clone(),
+                ))
+            }
+            WireApi::Gemini => {
+                let api_stream = self.stream_gemini_generate_content(prompt).await?;
+                Ok(map_response_stream(
+                    api_stream,
+                    self.state.otel_manager.clone(),
+                ))
+            }
         }
     }
 
@@ -300,11 +373,23 @@ impl ModelClientSession {
             None
         };
 
-        let include = if reasoning.is_some() {
+        let mut include = if reasoning.is_some() {
             vec!["reasoning.encrypted_content".to_string()]
         } else {
             Vec::new()
         };
+        // Hosted tool results are only returned when asked for.
+        for tool in &prompt.tools {
+            match tool {
+                ToolSpec::CodeInterpreter { .. } => {
+                    include.push("code_interpreter_call.outputs".to_string());
+                }
+                ToolSpec::FileSearch { .. } => {
+                    include.push("file_search_call.results".to_string());
+                }
+                _ => {}
+            }
+        }
 
         let verbosity = if model_info.support_verbosity {
             self.state
@@ -375,7 +460,7 @@ impl ModelClientSession {
 
         let store = store_override.unwrap_or(false);
         let payload = ResponseCreateWsRequest {
-            model: self.state.model_info.slug.clone(),
+            model: self.state.request_model().to_string(),
             instructions: api_prompt.instructions.clone(),
             input: api_prompt.input.clone(),
             tools: api_prompt.tools.clone(),
@@ -463,7 +548,7 @@ impl ModelClientSession {
                 .state
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
-            let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
+            let api_auth = self.state.api_auth(auth.clone()).await?;
             let transport = ReqwestTransport::new(build_reqwest_client());
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let client = ApiChatClient::new(transport, api_provider, api_auth)
@@ -471,7 +556,7 @@ impl ModelClientSession {
 
             let stream_result = client
                 .stream_prompt(
-                    &self.state.model_info.slug,
+                    self.state.request_model(),
                     &api_prompt,
                     Some(conversation_id.clone()),
                     Some(session_source.clone()),
@@ -491,6 +576,142 @@ impl ModelClientSession {
         }
     }
 
+    /// Streams a turn via the Anthropic Messages API.
+    ///
+    /// Reasoning effort maps onto an extended-thinking budget; like the Chat
+    /// Completions path, `output_schema` is not supported.
+    async fn stream_anthropic_messages(&self, prompt: &Prompt) -> Result<ApiResponseStream> {
+        if prompt.output_schema.is_some() {
+            return Err(CodexErr::UnsupportedOperation(
+                "output_schema is not supported for the Anthropic Messages API".to_string(),
+            ));
+        }
+
+        let auth_manager = self.state.auth_manager.clone();
+        let instructions = prompt.base_instructions.text.clone();
+        let tools_json = create_tools_json_for_anthropic_api(&prompt.tools)?;
+        let input = prompt.get_formatted_input();
+        let thinking_budget = self
+            .state
+            .effort
+            .or(self.state.model_info.default_reasoning_level)
+            .and_then(anthropic_thinking_budget);
+        let conversation_id = self.state.conversation_id.to_string();
+        let session_source = self.state.session_source.clone();
+
+        let mut auth_recovery = auth_manager
+            .as_ref()
+            .map(super::auth::AuthManager::unauthorized_recovery);
+        loop {
+            let auth = match auth_manager.as_ref() {
+                Some(manager) => manager.auth().await,
+                None => None,
+            };
+            let api_provider = self
+                .state
+                .provider
+                .to_api_provider(auth.as_ref().map(|a| a.mode))?;
+            let api_auth = self.state.api_auth(auth.clone()).await?;
+            let request = ApiAnthropicRequestBuilder::new(
+                self.state.request_model(),
+                &instructions,
+                &input,
+                &tools_json,
+            )
+            .parallel_tool_calls(prompt.parallel_tool_calls)
+            .thinking_budget(thinking_budget)
+            .conversation_id(Some(conversation_id.clone()))
+            .session_source(Some(session_source.clone()))
+            .build(&api_provider)
+            .map_err(map_api_error)?;
+            let transport = ReqwestTransport::new(build_reqwest_client());
+            let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
+            let client = ApiAnthropicClient::new(transport, api_provider, api_auth)
+                .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
+
+            match client.stream_request(request).await {
+                Ok(stream) => return Ok(stream),
+                Err(ApiError::Transport(TransportError::Http { status, .. }))
+                    if status == StatusCode::UNAUTHORIZED =>
+                {
+                    handle_unauthorized(status, &mut auth_recovery).await?;
+                    continue;
+                }
+                Err(err) => return Err(map_api_error(err)),
+            }
+        }
+    }
+
+    /// Streams a turn via Gemini `streamGenerateContent`.
+    ///
+    /// Safety settings from `[gemini]` are passed through unchanged; like the
+    /// other non-Responses paths, `output_schema` is not supported.
+    async fn stream_gemini_generate_content(&self, prompt: &Prompt) -> Result<ApiResponseStream> {
+        if prompt.output_schema.is_some() {
+            return Err(CodexErr::UnsupportedOperation(
+                "output_schema is not supported for the Gemini API".to_string(),
+            ));
+        }
+
+        let auth_manager = self.state.auth_manager.clone();
+        let instructions = prompt.base_instructions.text.clone();
+        let tools_json = create_tools_json_for_gemini_api(&prompt.tools)?;
+        let input = prompt.get_formatted_input();
+        let safety_settings: Vec<Value> = self
+            .state
+            .config
+            .gemini_safety_settings
+            .iter()
+            .map(|setting| {
+                serde_json::json!({
+                    "category": setting.category,
+                    "threshold": setting.threshold,
+                })
+            })
+            .collect();
+        let conversation_id = self.state.conversation_id.to_string();
+        let session_source = self.state.session_source.clone();
+
+        let mut auth_recovery = auth_manager
+            .as_ref()
+            .map(super::auth::AuthManager::unauthorized_recovery);
+        loop {
+            let auth = match auth_manager.as_ref() {
+                Some(manager) => manager.auth().await,
+                None => None,
+            };
+            let api_provider = self
+                .state
+                .provider
+                .to_api_provider(auth.as_ref().map(|a| a.mode))?;
+            let api_auth = self.state.api_auth(auth.clone()).await?;
+            let request = ApiGeminiRequestBuilder::new(&instructions, &input, &tools_json)
+                .safety_settings(safety_settings.clone())
+                .conversation_id(Some(conversation_id.clone()))
+                .session_source(Some(session_source.clone()))
+                .build()
+                .map_err(map_api_error)?;
+            let transport = ReqwestTransport::new(build_reqwest_client());
+            let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
+            let client = ApiGeminiClient::new(transport, api_provider, api_auth)
+                .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
+
+            match client
+                .stream_request(self.state.request_model(), request)
+                .await
+            {
+                Ok(stream) => return Ok(stream),
+                Err(ApiError::Transport(TransportError::Http { status, .. }))
+                    if status == StatusCode::UNAUTHORIZED =>
+                {
+                    handle_unauthorized(status, &mut auth_recovery).await?;
+                    continue;
+                }
+                Err(err) => return Err(map_api_error(err)),
+            }
+        }
+    }
+
     /// Streams a turn via the OpenAI Responses API.
     ///
     /// Handles SSE fixtures, reasoning summaries, verbosity, and the
@@ -519,7 +740,7 @@ impl ModelClientSession {
                 .state
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
-            let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
+            let api_auth = self.state.api_auth(auth.clone()).await?;
             let transport = ReqwestTransport::new(build_reqwest_client());
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let compression = self.responses_request_compression(auth.as_ref());
@@ -530,7 +751,7 @@ impl ModelClientSession {
             let options = self.build_responses_options(prompt, compression);
 
             let stream_result = client
-                .stream_prompt(&self.state.model_info.slug, &api_prompt, options)
+                .stream_prompt(self.state.request_model(), &api_prompt, options)
                 .await;
 
             match stream_result {
@@ -565,7 +786,7 @@ impl ModelClientSession {
                 .state
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
-            let api_auth = auth_provider_from_auth(auth.clone(), &self.state.provider)?;
+            let api_auth = self.state.api_auth(auth.clone()).await?;
             let compression = self.responses_request_compression(auth.as_ref());
 
             let options = self.build_responses_options(prompt, compression);
@@ -617,6 +838,18 @@ impl ModelClient {
 }
 
 /// Adapts the core `Prompt` type into the `codex-api` payload shape.
+/// Extended-thinking budget for a reasoning effort; `none` and `minimal`
+/// leave thinking off.
+fn anthropic_thinking_budget(effort: ReasoningEffortConfig) -> Option<u64> {
+    match effort {
+        ReasoningEffortConfig::None | ReasoningEffortConfig::Minimal => None,
+        ReasoningEffortConfig::Low => Some(4_096),
+        ReasoningEffortConfig::Medium => Some(10_240),
+        ReasoningEffortConfig::High => Some(32_768),
+        ReasoningEffortConfig::XHigh => Some(65_536),
+    }
+}
+
 fn build_api_prompt(prompt: &Prompt, instructions: String, tools_json: Vec<Value>) -> ApiPrompt {
     ApiPrompt {
         instructions,
@@ -178,6 +178,17 @@ pub(crate) mod tools {
             #[serde(skip_serializing_if = "Option::is_none")]
             external_web_access: Option<bool>,
         },
+        // Provider-hosted tools, configured per model under `[hosted_tools]`.
+        // https://platform.openai.com/docs/guides/tools-code-interpreter
+        #[serde(rename = "code_interpreter")]
+        CodeInterpreter { container: CodeInterpreterContainer },
+        // https://platform.openai.com/docs/guides/tools-file-search
+        #[serde(rename = "file_search")]
+        FileSearch {
+            vector_store_ids: Vec<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            max_num_results: Option<u32>,
+        },
         #[serde(rename = "custom")]
         Freeform(FreeformTool),
     }
@@ -188,11 +199,20 @@ pub(crate) mod tools {
                 ToolSpec::Function(tool) => tool.name.as_str(),
                 ToolSpec::LocalShell {} => "local_shell",
                 ToolSpec::WebSearch { .. } => "web_search",
+                ToolSpec::CodeInterpreter { .. } => "code_interpreter",
+                ToolSpec::FileSearch { .. } => "file_search",
                 ToolSpec::Freeform(tool) => tool.name.as_str(),
             }
         }
     }
 
+    /// Container the hosted code interpreter runs in; `auto` lets the
+    /// provider create (and reuse) one per conversation.
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+    pub struct CodeInterpreterContainer {
+        pub(crate) r#type: String,
+    }
+
     #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
     pub struct FreeformTool {
         pub(crate) name: String,
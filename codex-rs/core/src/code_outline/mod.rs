@@ -0,0 +1,361 @@
+//! Structural code navigation backed by tree-sitter.
+//!
+//! [`outline_file`] lists the definitions in one file, [`SymbolIndex`] keeps
+//! the definitions of a whole project and answers exact (`find_symbol`) and
+//! fuzzy (symbol `@`-mentions) lookups, and [`find_references`] finds the
+//! uses of an identifier. Rust, Python, JavaScript, TypeScript, and Go are
+//! supported; other files are skipped.
+
+mod parse;
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use serde::Serialize;
+
+use crate::workspace_files::file_stamp;
+use crate::workspace_files::is_under;
+use crate::workspace_files::list_files;
+use crate::workspace_files::read_text;
+
+use self::parse::Language;
+pub use self::parse::Reference;
+pub use self::parse::Symbol;
+pub use self::parse::SymbolKind;
+use self::parse::outline_source;
+use self::parse::references_in_source;
+
+/// Larger files are usually generated and are not worth parsing.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Indexes shared by every session in the process, keyed by project root, so
+/// repeated lookups only re-parse files that changed.
+static SHARED_INDEXES: LazyLock<Mutex<HashMap<PathBuf, SymbolIndex>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SymbolMatch {
+    /// Path relative to the project root.
+    pub path: String,
+    #[serde(flatten)]
+    pub symbol: Symbol,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReferenceMatch {
+    /// Path relative to the project root.
+    pub path: String,
+    #[serde(flatten)]
+    pub reference: Reference,
+}
+
+/// Whether definitions can be extracted from `path`.
+pub fn is_supported(path: &Path) -> bool {
+    Language::from_path(path).is_some()
+}
+
+/// Definitions in the file at `path`, or `None` when its language is not
+/// supported.
+pub fn outline_file(path: &Path) -> io::Result<Option<Vec<Symbol>>> {
+    let Some(language) = Language::from_path(path) else {
+        return Ok(None);
+    };
+    let source = std::fs::read_to_string(path)?;
+    Ok(Some(outline_source(language, &source)))
+}
+
+/// Uses of the identifier `name` in supported files under `root`, outside of
+/// the definitions of that name. Stops after `limit` matches.
+pub fn find_references(
+    root: &Path,
+    name: &str,
+    path_prefix: Option<&str>,
+    limit: usize,
+) -> io::Result<Vec<ReferenceMatch>> {
+    let (files, _) = list_files(root)?;
+    let mut matches = Vec::new();
+    for rel in files {
+        if !is_under(&rel, path_prefix) {
+            continue;
+        }
+        let path = root.join(&rel);
+        let Some(language) = Language::from_path(&path) else {
+            continue;
+        };
+        let Some(source) = read_source(&path) else {
+            continue;
+        };
+        // Parsing is far more expensive than a substring check.
+        if !source.contains(name) {
+            continue;
+        }
+        for reference in references_in_source(language, &source, name) {
+            matches.push(ReferenceMatch {
+                path: rel.clone(),
+                reference,
+            });
+            if matches.len() >= limit {
+                return Ok(matches);
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// Runs `f` on the process-wide index of `root` after bringing it up to
+/// date. Blocking; run it off the async runtime.
+pub fn with_shared_index<R>(root: &Path, f: impl FnOnce(&SymbolIndex) -> R) -> io::Result<R> {
+    let mut indexes = SHARED_INDEXES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let index = indexes
+        .entry(root.to_path_buf())
+        .or_insert_with(|| SymbolIndex::new(root.to_path_buf()));
+    index.refresh()?;
+    Ok(f(index))
+}
+
+/// Definitions of every supported file in a project, refreshed
+/// incrementally: only files whose size or modification time changed are
+/// parsed again.
+#[derive(Debug)]
+pub struct SymbolIndex {
+    root: PathBuf,
+    files: HashMap<String, IndexedFile>,
+}
+
+#[derive(Debug)]
+struct IndexedFile {
+    stamp: (u64, u64),
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolIndex {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            files: HashMap::new(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn refresh(&mut self) -> io::Result<()> {
+        let (files, _) = list_files(&self.root)?;
+        let mut refreshed = HashMap::new();
+        for rel in files {
+            let path = self.root.join(&rel);
+            let Some(language) = Language::from_path(&path) else {
+                continue;
+            };
+            let Some(stamp) = file_stamp(&path) else {
+                continue;
+            };
+            if let Some(indexed) = self.files.remove(&rel)
+                && indexed.stamp == stamp
+            {
+                refreshed.insert(rel, indexed);
+                continue;
+            }
+            let symbols = if stamp.0 > MAX_FILE_BYTES {
+                Vec::new()
+            } else {
+                read_source(&path)
+                    .map(|source| outline_source(language, &source))
+                    .unwrap_or_default()
+            };
+            refreshed.insert(rel, IndexedFile { stamp, symbols });
+        }
+        self.files = refreshed;
+        Ok(())
+    }
+
+    /// Definitions named exactly `name`, sorted by path and line. A
+    /// qualified name such as `Config::load` or `Config.load` also matches
+    /// on the container.
+    pub fn find(
+        &self,
+        name: &str,
+        kind: Option<SymbolKind>,
+        path_prefix: Option<&str>,
+    ) -> Vec<SymbolMatch> {
+        let (container, name) = match name.rsplit_once("::").or_else(|| name.rsplit_once('.')) {
+            Some((container, name)) => (container.rsplit([':', '.']).next(), name),
+            None => (None, name),
+        };
+        let mut matches = self.matching(|symbol| {
+            symbol.name == name
+                && kind.is_none_or(|kind| symbol.kind == kind)
+                && container.is_none_or(|container| symbol.container.as_deref() == Some(container))
+        });
+        matches.retain(|found| is_under(&found.path, path_prefix));
+        matches.sort_by(|a, b| (&a.path, a.symbol.start_line).cmp(&(&b.path, b.symbol.start_line)));
+        matches
+    }
+
+    /// Definitions in files under `path_prefix`, sorted by path and line.
+    pub fn symbols_under(&self, path_prefix: Option<&str>) -> Vec<SymbolMatch> {
+        let mut matches: Vec<SymbolMatch> = self
+            .files
+            .iter()
+            .filter(|(path, _)| is_under(path, path_prefix))
+            .flat_map(|(path, file)| {
+                file.symbols.iter().map(|symbol| SymbolMatch {
+                    path: path.clone(),
+                    symbol: symbol.clone(),
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| (&a.path, a.symbol.start_line).cmp(&(&b.path, b.symbol.start_line)));
+        matches
+    }
+
+    /// Up to `limit` definitions whose name contains `query`, ignoring case.
+    /// Exact names rank before prefixes, prefixes before other matches, and
+    /// shorter names first within each group.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SymbolMatch> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(u8, SymbolMatch)> = self
+            .matching(|symbol| symbol.name.to_lowercase().contains(&query))
+            .into_iter()
+            .map(|found| {
+                let name = found.symbol.name.to_lowercase();
+                let rank = if name == query {
+                    0
+                } else if name.starts_with(&query) {
+                    1
+                } else {
+                    2
+                };
+                (rank, found)
+            })
+            .collect();
+        matches.sort_by(|(rank_a, a), (rank_b, b)| {
+            (*rank_a, a.symbol.name.len(), &a.path, a.symbol.start_line).cmp(&(
+                *rank_b,
+                b.symbol.name.len(),
+                &b.path,
+                b.symbol.start_line,
+            ))
+        });
+        matches.truncate(limit);
+        matches.into_iter().map(|(_, found)| found).collect()
+    }
+
+    fn matching(&self, predicate: impl Fn(&Symbol) -> bool) -> Vec<SymbolMatch> {
+        self.files
+            .iter()
+            .flat_map(|(path, file)| {
+                file.symbols
+                    .iter()
+                    .filter(|symbol| predicate(symbol))
+                    .map(|symbol| SymbolMatch {
+                        path: path.clone(),
+                        symbol: symbol.clone(),
+                    })
+            })
+            .collect()
+    }
+}
+
+fn read_source(path: &Path) -> Option<String> {
+    let (size, _) = file_stamp(path)?;
+    if size > MAX_FILE_BYTES {
+        return None;
+    }
+    read_text(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn write(root: &Path, rel: &str, contents: &str) {
+        let path = root.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create dir");
+        }
+        std::fs::write(path, contents).expect("write file");
+    }
+
+    fn locations(matches: &[SymbolMatch]) -> Vec<(String, usize)> {
+        matches
+            .iter()
+            .map(|found| (found.path.clone(), found.symbol.start_line))
+            .collect()
+    }
+
+    #[test]
+    fn index_finds_and_searches_definitions() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        write(
+            root,
+            "src/config.rs",
+            "pub struct Config;\n\nimpl Config {\n    pub fn load() -> Self { Config }\n}\n",
+        );
+        write(root, "web/load.ts", "export function load() {}\n");
+        write(root, "README.md", "fn load() {}\n");
+
+        let mut index = SymbolIndex::new(root.to_path_buf());
+        index.refresh().expect("refresh");
+
+        assert_eq!(
+            locations(&index.find("load", None, None)),
+            vec![
+                ("src/config.rs".to_string(), 4),
+                ("web/load.ts".to_string(), 1)
+            ]
+        );
+        assert_eq!(
+            locations(&index.find("Config::load", None, None)),
+            vec![("src/config.rs".to_string(), 4)]
+        );
+        assert_eq!(
+            locations(&index.find("load", Some(SymbolKind::Function), Some("web"))),
+            vec![("web/load.ts".to_string(), 1)]
+        );
+
+        let names: Vec<String> = index
+            .search("CONF", 10)
+            .into_iter()
+            .map(|found| found.symbol.name)
+            .collect();
+        assert_eq!(names, vec!["Config".to_string()]);
+
+        write(root, "web/load.ts", "export function reload() {}\n");
+        index.refresh().expect("refresh");
+        assert_eq!(
+            index.find("load", Some(SymbolKind::Function), Some("web")),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn find_references_reports_uses() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        write(
+            root,
+            "src/lib.rs",
+            "fn load() {}\n\nfn main() {\n    load();\n}\n",
+        );
+        write(root, "src/other.rs", "fn unrelated() {}\n");
+
+        let references = find_references(root, "load", None, 10).expect("references");
+        let found: Vec<(String, usize)> = references
+            .iter()
+            .map(|found| (found.path.clone(), found.reference.line))
+            .collect();
+        assert_eq!(found, vec![("src/lib.rs".to_string(), 4)]);
+        assert_eq!(references[0].reference.text, "load();");
+    }
+}
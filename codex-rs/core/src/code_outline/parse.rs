@@ -0,0 +1,504 @@
+//! Extracts definitions and identifier references from a single source file.
+//!
+//! Definitions are found by walking the syntax tree and matching node kinds
+//! from a small per-language table, so supporting another grammar only needs
+//! a new `Language` variant and its table.
+
+use std::path::Path;
+
+use serde::Serialize;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+use tree_sitter::Tree;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Interface,
+    Trait,
+    Type,
+    Module,
+    Constant,
+    Macro,
+}
+
+impl SymbolKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SymbolKind::Function => "function",
+            SymbolKind::Method => "method",
+            SymbolKind::Class => "class",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Type => "type",
+            SymbolKind::Module => "module",
+            SymbolKind::Constant => "constant",
+            SymbolKind::Macro => "macro",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            SymbolKind::Function,
+            SymbolKind::Method,
+            SymbolKind::Class,
+            SymbolKind::Struct,
+            SymbolKind::Enum,
+            SymbolKind::Interface,
+            SymbolKind::Trait,
+            SymbolKind::Type,
+            SymbolKind::Module,
+            SymbolKind::Constant,
+            SymbolKind::Macro,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str() == value)
+    }
+
+    /// Kinds whose members are reported as methods.
+    fn has_methods(self) -> bool {
+        matches!(
+            self,
+            SymbolKind::Class | SymbolKind::Interface | SymbolKind::Trait | SymbolKind::Struct
+        )
+    }
+}
+
+/// A definition in a source file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Name of the enclosing definition (class, impl target, module, ...).
+    pub container: Option<String>,
+    /// 1-based, inclusive.
+    pub start_line: usize,
+    /// 1-based, inclusive.
+    pub end_line: usize,
+    /// First line of the definition, trimmed.
+    pub signature: String,
+}
+
+/// A use of an identifier outside its definition.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Reference {
+    /// 1-based.
+    pub line: usize,
+    /// 1-based, in characters.
+    pub column: usize,
+    /// The source line, trimmed.
+    pub text: String,
+}
+
+/// How a node kind maps to a symbol.
+enum Def {
+    Symbol(SymbolKind),
+    /// Not reported itself, but names the container of its children (Rust
+    /// `impl` blocks).
+    Container,
+    /// A Go `type_spec`, whose kind depends on the type it declares.
+    GoType,
+    /// A JavaScript/TypeScript `const f = () => ...`, reported only when the
+    /// value is a function.
+    FunctionVariable,
+}
+
+const MAX_SIGNATURE_CHARS: usize = 160;
+
+impl Language {
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Language::Rust),
+            "py" | "pyi" => Some(Language::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Language::JavaScript),
+            "ts" | "mts" | "cts" => Some(Language::TypeScript),
+            "tsx" => Some(Language::Tsx),
+            "go" => Some(Language::Go),
+            _ => None,
+        }
+    }
+
+    fn grammar(self) -> tree_sitter::Language {
+        match self {
+            Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Language::Python => tree_sitter_python::LANGUAGE.into(),
+            Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Language::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Language::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+
+    fn definition(self, node_kind: &str) -> Option<Def> {
+        let def = match (self, node_kind) {
+            (Language::Rust, "function_item" | "function_signature_item") => {
+                Def::Symbol(SymbolKind::Function)
+            }
+            (Language::Rust, "struct_item" | "union_item") => Def::Symbol(SymbolKind::Struct),
+            (Language::Rust, "enum_item") => Def::Symbol(SymbolKind::Enum),
+            (Language::Rust, "trait_item") => Def::Symbol(SymbolKind::Trait),
+            (Language::Rust, "type_item") => Def::Symbol(SymbolKind::Type),
+            (Language::Rust, "mod_item") => Def::Symbol(SymbolKind::Module),
+            (Language::Rust, "const_item" | "static_item") => Def::Symbol(SymbolKind::Constant),
+            (Language::Rust, "macro_definition") => Def::Symbol(SymbolKind::Macro),
+            (Language::Rust, "impl_item") => Def::Container,
+
+            (Language::Python, "function_definition") => Def::Symbol(SymbolKind::Function),
+            (Language::Python, "class_definition") => Def::Symbol(SymbolKind::Class),
+
+            (
+                Language::JavaScript | Language::TypeScript | Language::Tsx,
+                "function_declaration" | "generator_function_declaration" | "function_signature",
+            ) => Def::Symbol(SymbolKind::Function),
+            (
+                Language::JavaScript | Language::TypeScript | Language::Tsx,
+                "method_definition" | "method_signature" | "abstract_method_signature",
+            ) => Def::Symbol(SymbolKind::Method),
+            (
+                Language::JavaScript | Language::TypeScript | Language::Tsx,
+                "class_declaration" | "abstract_class_declaration",
+            ) => Def::Symbol(SymbolKind::Class),
+            (
+                Language::JavaScript | Language::TypeScript | Language::Tsx,
+                "variable_declarator",
+            ) => Def::FunctionVariable,
+            (Language::TypeScript | Language::Tsx, "interface_declaration") => {
+                Def::Symbol(SymbolKind::Interface)
+            }
+            (Language::TypeScript | Language::Tsx, "type_alias_declaration") => {
+                Def::Symbol(SymbolKind::Type)
+            }
+            (Language::TypeScript | Language::Tsx, "enum_declaration") => {
+                Def::Symbol(SymbolKind::Enum)
+            }
+            (Language::TypeScript | Language::Tsx, "internal_module" | "module") => {
+                Def::Symbol(SymbolKind::Module)
+            }
+
+            (Language::Go, "function_declaration") => Def::Symbol(SymbolKind::Function),
+            (Language::Go, "method_declaration") => Def::Symbol(SymbolKind::Method),
+            (Language::Go, "type_spec") => Def::GoType,
+            (Language::Go, "const_spec") => Def::Symbol(SymbolKind::Constant),
+            _ => return None,
+        };
+        Some(def)
+    }
+}
+
+fn parse(language: Language, source: &str) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(&language.grammar()).ok()?;
+    parser.parse(source, None)
+}
+
+/// Definitions in `source`, in source order.
+pub(crate) fn outline_source(language: Language, source: &str) -> Vec<Symbol> {
+    let Some(tree) = parse(language, source) else {
+        return Vec::new();
+    };
+    let mut symbols = Vec::new();
+    let mut containers = Vec::new();
+    collect_symbols(
+        language,
+        source,
+        tree.root_node(),
+        &mut containers,
+        &mut symbols,
+    );
+    symbols
+}
+
+/// Identifiers named `name` in `source` that are not the name of a
+/// definition.
+pub(crate) fn references_in_source(language: Language, source: &str, name: &str) -> Vec<Reference> {
+    let Some(tree) = parse(language, source) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = source.lines().collect();
+    let mut references = Vec::new();
+    let mut cursor = tree.walk();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.child_count() == 0 {
+            if node.kind().ends_with("identifier")
+                && node_text(node, source) == name
+                && !is_definition_name(language, node)
+            {
+                let position = node.start_position();
+                let line = lines.get(position.row).copied().unwrap_or_default();
+                references.push(Reference {
+                    line: position.row + 1,
+                    column: line
+                        .get(..position.column)
+                        .map_or(position.column, |prefix| prefix.chars().count())
+                        + 1,
+                    text: line.trim().to_string(),
+                });
+            }
+            continue;
+        }
+        // Pushed in reverse so nodes pop in source order.
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    references
+}
+
+fn is_definition_name(language: Language, node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    language.definition(parent.kind()).is_some()
+        && parent
+            .child_by_field_name("name")
+            .is_some_and(|name| name.id() == node.id())
+}
+
+fn collect_symbols(
+    language: Language,
+    source: &str,
+    node: Node,
+    containers: &mut Vec<(String, SymbolKind)>,
+    symbols: &mut Vec<Symbol>,
+) {
+    let mut pushed_container = false;
+    match language.definition(node.kind()) {
+        Some(Def::Container) => {
+            // `impl Trait for Type` and `impl Type` both describe `Type`.
+            if let Some(target) = node.child_by_field_name("type") {
+                containers.push((base_type_name(target, source), SymbolKind::Struct));
+                pushed_container = true;
+            }
+        }
+        Some(def) => {
+            if let Some(symbol) = symbol_for(language, source, node, def, containers) {
+                containers.push((symbol.name.clone(), symbol.kind));
+                symbols.push(symbol);
+                pushed_container = true;
+            }
+        }
+        None => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_symbols(language, source, child, containers, symbols);
+    }
+
+    if pushed_container {
+        containers.pop();
+    }
+}
+
+fn symbol_for(
+    language: Language,
+    source: &str,
+    node: Node,
+    def: Def,
+    containers: &[(String, SymbolKind)],
+) -> Option<Symbol> {
+    let name = node_text(node.child_by_field_name("name")?, source).to_string();
+    let mut kind = match def {
+        Def::Symbol(kind) => kind,
+        Def::Container => return None,
+        Def::GoType => match node.child_by_field_name("type").map(|ty| ty.kind()) {
+            Some("struct_type") => SymbolKind::Struct,
+            Some("interface_type") => SymbolKind::Interface,
+            _ => SymbolKind::Type,
+        },
+        Def::FunctionVariable => {
+            match node.child_by_field_name("value").map(|value| value.kind()) {
+                Some("arrow_function" | "function_expression" | "function") => SymbolKind::Function,
+                _ => return None,
+            }
+        }
+    };
+
+    let mut container = containers.last().map(|(name, _)| name.clone());
+    if kind == SymbolKind::Function
+        && containers
+            .last()
+            .is_some_and(|(_, container_kind)| container_kind.has_methods())
+    {
+        kind = SymbolKind::Method;
+    }
+    if language == Language::Go
+        && kind == SymbolKind::Method
+        && let Some(receiver) = node.child_by_field_name("receiver")
+    {
+        container = last_descendant_of_kind(receiver, "type_identifier")
+            .map(|ty| node_text(ty, source).to_string());
+    }
+
+    let start_line = node.start_position().row + 1;
+    let signature: String = source
+        .lines()
+        .nth(start_line - 1)
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(MAX_SIGNATURE_CHARS)
+        .collect();
+    Some(Symbol {
+        name,
+        kind,
+        container,
+        start_line,
+        end_line: node.end_position().row + 1,
+        signature,
+    })
+}
+
+/// `Foo` for `Foo`, `Foo<T>`, `&mut foo::Foo<T>`, and so on.
+fn base_type_name(node: Node, source: &str) -> String {
+    last_descendant_of_kind(node, "type_identifier")
+        .map(|ty| node_text(ty, source).to_string())
+        .unwrap_or_else(|| node_text(node, source).to_string())
+}
+
+fn last_descendant_of_kind<'tree>(node: Node<'tree>, kind: &str) -> Option<Node<'tree>> {
+    if node.kind() == kind {
+        return Some(node);
+    }
+    // Generic arguments come after the base type, so search only up to them.
+    let mut found = None;
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if matches!(child.kind(), "type_arguments" | "type_parameters") {
+            break;
+        }
+        if let Some(descendant) = last_descendant_of_kind(child, kind) {
+            found = Some(descendant);
+        }
+    }
+    found
+}
+
+fn node_text<'a>(node: Node, source: &'a str) -> &'a str {
+    source.get(node.byte_range()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn summary(language: Language, source: &str) -> Vec<(String, &'static str, Option<String>)> {
+        outline_source(language, source)
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.kind.as_str(), symbol.container))
+            .collect()
+    }
+
+    fn entry(
+        name: &str,
+        kind: &'static str,
+        container: Option<&str>,
+    ) -> (String, &'static str, Option<String>) {
+        (name.to_string(), kind, container.map(str::to_string))
+    }
+
+    #[test]
+    fn rust_outline_attributes_methods_to_impl_targets() {
+        let source = r#"
+mod config {
+    pub struct Config<T> {
+        value: T,
+    }
+
+    impl<T> Display for Config<T> {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            Ok(())
+        }
+    }
+}
+
+pub trait Loader {
+    fn load(&self) -> Config;
+}
+
+const LIMIT: usize = 3;
+"#;
+        assert_eq!(
+            summary(Language::Rust, source),
+            vec![
+                entry("config", "module", None),
+                entry("Config", "struct", Some("config")),
+                entry("fmt", "method", Some("Config")),
+                entry("Loader", "trait", None),
+                entry("load", "method", Some("Loader")),
+                entry("LIMIT", "constant", None),
+            ]
+        );
+        let symbols = outline_source(Language::Rust, source);
+        assert_eq!((symbols[2].start_line, symbols[2].end_line), (8, 10));
+        assert_eq!(
+            symbols[2].signature,
+            "fn fmt(&self, f: &mut Formatter) -> fmt::Result {"
+        );
+    }
+
+    #[test]
+    fn python_and_typescript_outlines() {
+        let python = "class Repo:\n    def fetch(self):\n        pass\n\ndef main():\n    pass\n";
+        assert_eq!(
+            summary(Language::Python, python),
+            vec![
+                entry("Repo", "class", None),
+                entry("fetch", "method", Some("Repo")),
+                entry("main", "function", None),
+            ]
+        );
+
+        let typescript = "export interface Props { name: string }\nexport class View {\n  render(): void {}\n}\nexport const load = async () => {};\nconst limit = 3;\n";
+        assert_eq!(
+            summary(Language::TypeScript, typescript),
+            vec![
+                entry("Props", "interface", None),
+                entry("View", "class", None),
+                entry("render", "method", Some("View")),
+                entry("load", "function", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn go_methods_use_the_receiver_type() {
+        let source = "package main\n\ntype Server struct{}\n\nfunc (s *Server) Start() error { return nil }\n\nfunc main() {}\n";
+        assert_eq!(
+            summary(Language::Go, source),
+            vec![
+                entry("Server", "struct", None),
+                entry("Start", "method", Some("Server")),
+                entry("main", "function", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn references_skip_definitions() {
+        let source = "fn load() {}\n\nfn main() {\n    load();\n    let x = loader::load;\n}\n";
+        let references: Vec<(usize, usize)> = references_in_source(Language::Rust, source, "load")
+            .into_iter()
+            .map(|reference| (reference.line, reference.column))
+            .collect();
+        assert_eq!(references, vec![(4, 5), (5, 21)]);
+    }
+}
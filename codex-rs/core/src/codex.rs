@@ -6,6 +6,7 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 use crate::AuthManager;
 use crate::CodexAuth;
@@ -13,16 +14,31 @@ use crate::SandboxState;
 use crate::agent::AgentControl;
 use crate::agent::AgentStatus;
 use crate::agent::agent_status_from_event;
+use crate::audit::AuditLog;
+use crate::background_tasks::BackgroundTasks;
+use crate::browser::BrowserSession;
+use crate::checkpoints::Checkpoints;
 use crate::compact;
 use crate::compact::run_inline_auto_compact_task;
 use crate::compact::should_use_remote_compact_task;
 use crate::compact_remote::run_inline_remote_auto_compact_task;
+use crate::compact_strategies::run_configured_auto_compact;
+use crate::cost::CostTracker;
 use crate::exec_policy::ExecPolicyManager;
+use crate::external_edits::ExternalEdits;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::governor::Governor;
+use crate::governor::GovernorLease;
+use crate::hooks::Hooks;
+use crate::input_queue::InputQueue;
 use crate::models_manager::manager::ModelsManager;
+use crate::network_allowlist::NetworkAllowlist;
+use crate::network_capture::NetworkCapture;
 use crate::parse_command::parse_command;
 use crate::parse_turn_item;
+use crate::sandbox_capabilities::detect_sandbox_capabilities;
+use crate::sandbox_capabilities::policy_uses_sandbox;
 use crate::stream_events_utils::HandleOutputCtx;
 use crate::stream_events_utils::handle_non_tool_response_item;
 use crate::stream_events_utils::handle_output_item_done;
@@ -32,6 +48,7 @@ use crate::user_notification::UserNotifier;
 use crate::util::error_or_panic;
 use async_channel::Receiver;
 use async_channel::Sender;
+use async_channel::WeakSender;
 use codex_protocol::ThreadId;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::config_types::Settings;
@@ -40,10 +57,13 @@ use codex_protocol::items::TurnItem;
 use codex_protocol::items::UserMessageItem;
 use codex_protocol::models::BaseInstructions;
 use codex_protocol::openai_models::ModelInfo;
+use codex_protocol::plan_tool::ProposedPlan;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ItemStartedEvent;
+use codex_protocol::protocol::PatchPreview;
+use codex_protocol::protocol::ProviderRateLimitEvent;
 use codex_protocol::protocol::RawResponseItemEvent;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
@@ -55,6 +75,7 @@ use codex_protocol::request_user_input::RequestUserInputArgs;
 use codex_protocol::request_user_input::RequestUserInputResponse;
 use codex_rmcp_client::ElicitationResponse;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
+use codex_utils_absolute_path::AbsolutePathBuf;
 use futures::future::BoxFuture;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
@@ -83,18 +104,26 @@ use tracing::warn;
 
 use crate::ModelProviderInfo;
 use crate::WireApi;
+use crate::approval_risk::command_risk;
+use crate::approval_risk::patch_risk;
 use crate::client::ModelClient;
 use crate::client::ModelClientSession;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
+use crate::command_explanation::explain_command;
 use crate::compact::collect_user_messages;
 use crate::config::Config;
 use crate::config::Constrained;
 use crate::config::ConstraintResult;
 use crate::config::GhostSnapshotConfig;
+use crate::config::types::ApprovalTimeout;
+use crate::config::types::ExecLimits;
 use crate::config::types::McpServerConfig;
 use crate::config::types::ShellEnvironmentPolicy;
+use crate::config::validate::ConfigDiagnosticKind;
+use crate::config::validate::validate_config_layers;
 use crate::context_manager::ContextManager;
+use crate::context_providers::ContextProviders;
 use crate::environment_context::EnvironmentContext;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
@@ -102,23 +131,39 @@ use crate::error::Result as CodexResult;
 use crate::exec::StreamOutput;
 use crate::exec_policy::ExecPolicyUpdateError;
 use crate::feedback_tags;
+use crate::git_info::checked_out_branch;
 use crate::instructions::UserInstructions;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_resource_mentions::McpResourceMentions;
+use crate::mcp_sampling::McpSampler;
 use crate::model_provider_info::CHAT_WIRE_API_DEPRECATION_SUMMARY;
+use crate::next_edit::NextEditRequest;
+use crate::pinned_context::PinnedContext;
+use crate::plan_mode;
+use crate::plan_scope;
+use crate::plugin_tools::load_plugin_tools;
 use crate::project_doc::get_user_instructions;
+use crate::project_toolchain::detect_project_toolchains;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
+use crate::protocol::ApprovalKind;
+use crate::protocol::ApprovalTimedOutEvent;
+use crate::protocol::ApprovalTimeoutAction;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::ConfigReloadedEvent;
 use crate::protocol::DeprecationNoticeEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
+use crate::protocol::McpInvocation;
 use crate::protocol::McpServerRefreshConfig;
+use crate::protocol::McpToolCallApprovalRequestEvent;
 use crate::protocol::Op;
+use crate::protocol::PlanApprovalRequestEvent;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReasoningContentDeltaEvent;
 use crate::protocol::ReasoningRawContentDeltaEvent;
@@ -134,11 +179,13 @@ use crate::protocol::Submission;
 use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
-use crate::protocol::TurnDiffEvent;
+use crate::protocol::TurnMetadata;
 use crate::protocol::WarningEvent;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::rollout::map_session_init_error;
+use crate::runtime_state::RuntimeStateTracker;
+use crate::session_tmp::SessionTmpDir;
 use crate::shell;
 use crate::shell_snapshot::ShellSnapshot;
 use crate::skills::SkillError;
@@ -149,20 +196,28 @@ use crate::skills::build_skill_injections;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
 use crate::state::SessionState;
+use crate::subagents::SubAgentMerges;
+use crate::subagents::SubAgentsManager;
 use crate::tasks::GhostSnapshotTask;
+use crate::tasks::RedoStack;
 use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
+use crate::tasks::TurnSnapshots;
 use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::parallel::ToolCallRuntime;
+use crate::tools::sandboxing::ApprovalJournal;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::ToolsConfigParams;
+use crate::tools::trimming::ToolTrimming;
 use crate::turn_diff_tracker::TurnDiffTracker;
+use crate::turn_diff_tracker::file_diffs_for_changes;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::user_notification::UserNotification;
 use crate::util::backoff;
+use crate::worktree_isolation::WorktreeIsolation;
 use codex_async_utils::OrCancelExt;
 use codex_otel::OtelManager;
 use codex_protocol::config_types::CollaborationMode;
@@ -187,6 +242,8 @@ pub struct Codex {
     pub(crate) rx_event: Receiver<Event>,
     // Last known status of the agent.
     pub(crate) agent_status: watch::Receiver<AgentStatus>,
+    // Token totals from the session's latest token count.
+    pub(crate) total_token_usage: watch::Receiver<Option<TokenUsage>>,
 }
 
 /// Wrapper returned by [`Codex::spawn`] containing the spawned [`Codex`],
@@ -201,6 +258,10 @@ pub struct CodexSpawnOk {
 
 pub(crate) const INITIAL_SUBMIT_ID: &str = "";
 pub(crate) const SUBMISSION_CHANNEL_CAPACITY: usize = 64;
+/// How many times a turn waits out a provider 429 before failing.
+const MAX_RATE_LIMIT_RETRIES: u64 = 10;
+/// Longest a single rate-limit wait may last, whatever the provider asks.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(5 * 60);
 static CHAT_WIRE_API_DEPRECATION_EMITTED: AtomicBool = AtomicBool::new(false);
 
 fn maybe_push_chat_wire_api_deprecation(
@@ -261,7 +322,8 @@ impl Codex {
 
         let exec_policy = ExecPolicyManager::load(&config.features, &config.config_layer_stack)
             .await
-            .map_err(|err| CodexErr::Fatal(format!("failed to load rules: {err}")))?;
+            .map_err(|err| CodexErr::Fatal(format!("failed to load rules: {err}")))?
+            .with_read_only_commands(config.read_only_commands.clone());
 
         let config = Arc::new(config);
         let _ = models_manager
@@ -310,6 +372,7 @@ impl Codex {
             cwd: config.cwd.clone(),
             original_config_do_not_use: Arc::clone(&config),
             session_source,
+            plan_mode: false,
         };
 
         // Generate a unique ID for the lifetime of this Codex session.
@@ -323,6 +386,7 @@ impl Codex {
             models_manager.clone(),
             exec_policy,
             tx_event.clone(),
+            tx_sub.downgrade(),
             agent_status_tx.clone(),
             conversation_history,
             session_source_clone,
@@ -335,6 +399,7 @@ impl Codex {
             map_session_init_error(&e, &config.codex_home)
         })?;
         let thread_id = session.conversation_id;
+        let total_token_usage = session.total_token_usage.subscribe();
 
         // This task will run until Op::Shutdown is received.
         tokio::spawn(submission_loop(session, config, rx_sub));
@@ -343,6 +408,7 @@ impl Codex {
             tx_sub,
             rx_event,
             agent_status: agent_status_rx,
+            total_token_usage,
         };
 
         #[allow(deprecated)]
@@ -386,6 +452,10 @@ impl Codex {
     pub(crate) async fn agent_status(&self) -> AgentStatus {
         self.agent_status.borrow().clone()
     }
+
+    pub(crate) fn total_token_usage(&self) -> Option<TokenUsage> {
+        self.total_token_usage.borrow().clone()
+    }
 }
 
 fn resolve_session_collaboration_settings(
@@ -440,6 +510,7 @@ pub(crate) struct Session {
     pub(crate) conversation_id: ThreadId,
     tx_event: Sender<Event>,
     agent_status: watch::Sender<AgentStatus>,
+    total_token_usage: watch::Sender<Option<TokenUsage>>,
     state: Mutex<SessionState>,
     /// The set of enabled features should be invariant for the lifetime of the
     /// session.
@@ -448,6 +519,10 @@ pub(crate) struct Session {
     pub(crate) active_turn: Mutex<Option<ActiveTurn>>,
     pub(crate) services: SessionServices,
     next_internal_sub_id: AtomicU64,
+    /// Lets the session submit ops to its own submission loop, e.g. to
+    /// interrupt the turn when an approval times out. Weak for the same
+    /// reason as in `InputQueue`.
+    tx_sub: WeakSender<Submission>,
 }
 
 /// The context needed for a single turn of the thread.
@@ -472,6 +547,7 @@ pub(crate) struct TurnContext {
     pub(crate) codex_linux_sandbox_exe: Option<PathBuf>,
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) truncation_policy: TruncationPolicy,
+    pub(crate) exec_limits: ExecLimits,
 }
 
 impl TurnContext {
@@ -529,6 +605,9 @@ pub(crate) struct SessionConfiguration {
     original_config_do_not_use: Arc<Config>,
     /// Source of the session (cli, vscode, exec, mcp, ...)
     session_source: SessionSource,
+    /// When set, write tools stay locked in each turn until the user approves
+    /// a plan submitted through `submit_plan`.
+    plan_mode: bool,
 }
 
 impl SessionConfiguration {
@@ -552,6 +631,9 @@ impl SessionConfiguration {
         if let Some(cwd) = updates.cwd.clone() {
             next_configuration.cwd = cwd;
         }
+        if let Some(plan_mode) = updates.plan_mode {
+            next_configuration.plan_mode = plan_mode;
+        }
         Ok(next_configuration)
     }
 }
@@ -565,6 +647,10 @@ pub(crate) struct SessionSettingsUpdate {
     pub(crate) reasoning_summary: Option<ReasoningSummaryConfig>,
     pub(crate) final_output_json_schema: Option<Option<Value>>,
     pub(crate) personality: Option<Personality>,
+    pub(crate) plan_mode: Option<bool>,
+    /// Branch whose `[branches]` policies these settings carry; recorded and
+    /// announced only once they apply.
+    pub(crate) checked_out_branch: Option<Option<String>>,
 }
 
 impl Session {
@@ -609,11 +695,15 @@ impl Session {
             session_configuration.session_source.clone(),
         );
 
-        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_info: &model_info,
             features: &per_turn_config.features,
             web_search_mode: per_turn_config.web_search_mode,
         });
+        tools_config.plan_mode = session_configuration.plan_mode;
+        tools_config.hosted_tools = per_turn_config.hosted_tools_for(&model_info.slug);
+        tools_config.local_web_search = per_turn_config.web_search_provider.is_some();
+        tools_config.browser_tools = per_turn_config.browser.is_some();
 
         TurnContext {
             sub_id,
@@ -632,6 +722,7 @@ impl Session {
             codex_linux_sandbox_exe: per_turn_config.codex_linux_sandbox_exe.clone(),
             tool_call_gate: Arc::new(ReadinessFlag::new()),
             truncation_policy: model_info.truncation_policy.into(),
+            exec_limits: per_turn_config.exec_limits,
         }
     }
 
@@ -643,6 +734,7 @@ impl Session {
         models_manager: Arc<ModelsManager>,
         exec_policy: ExecPolicyManager,
         tx_event: Sender<Event>,
+        tx_sub: WeakSender<Submission>,
         agent_status: watch::Sender<AgentStatus>,
         initial_history: InitialHistory,
         session_source: SessionSource,
@@ -737,7 +829,33 @@ impl Session {
                 }),
             });
         }
+        // Replaced options are reported by the deprecation notices above.
+        for diagnostic in validate_config_layers(&config.config_layer_stack) {
+            if diagnostic.kind == ConfigDiagnosticKind::Deprecated {
+                continue;
+            }
+            post_session_configured_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::Warning(WarningEvent {
+                    message: diagnostic.to_string(),
+                }),
+            });
+        }
         maybe_push_chat_wire_api_deprecation(&config, &mut post_session_configured_events);
+        let plugin_tools = if config.features.enabled(Feature::PluginTools) {
+            let outcome = load_plugin_tools(&config.cwd, &config.codex_home);
+            for (path, error) in outcome.errors {
+                post_session_configured_events.push(Event {
+                    id: INITIAL_SUBMIT_ID.to_owned(),
+                    msg: EventMsg::Warning(WarningEvent {
+                        message: format!("Skipped plugin {}: {error}", path.display()),
+                    }),
+                });
+            }
+            outcome.tools.into_iter().map(Arc::new).collect()
+        } else {
+            Vec::new()
+        };
 
         let auth = auth_manager.auth().await;
         let auth = auth.as_ref();
@@ -788,7 +906,46 @@ impl Session {
                 otel_manager.clone(),
             );
         }
-        let state = SessionState::new(session_configuration.clone());
+        let mut state = SessionState::new(session_configuration.clone());
+        state.checked_out_branch = config
+            .branch_config
+            .as_ref()
+            .and_then(|branch_config| branch_config.branch.clone());
+
+        let session_tmp = match SessionTmpDir::create(conversation_id) {
+            Ok(session_tmp) => Some(session_tmp),
+            Err(err) => {
+                warn!("failed to create session temp dir: {err}");
+                None
+            }
+        };
+        let network_capture = if config.features.enabled(Feature::NetworkCapture) {
+            match NetworkCapture::start().await {
+                Ok(network_capture) => Some(network_capture),
+                Err(err) => {
+                    warn!("failed to start network capture proxy: {err}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let network_proxy = match NetworkAllowlist::new(&config.network_allowlist) {
+            Some(allowlist) => match NetworkCapture::start_filtered(allowlist).await {
+                Ok(network_proxy) => Some(network_proxy),
+                Err(err) => {
+                    warn!("failed to start network allowlist proxy: {err}");
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let external_edits = config.features.enabled(Feature::ExternalEdits).then(|| {
+            let external_edits = ExternalEdits::default();
+            external_edits.watch(&config.cwd);
+            external_edits
+        });
 
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
@@ -803,20 +960,57 @@ impl Session {
             otel_manager,
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_journal: Mutex::new(ApprovalJournal::default()),
             skills_manager,
+            subagents_manager: Arc::new(SubAgentsManager::new(
+                config.codex_home.clone(),
+                config.subagent_extra_dirs.clone(),
+            )),
             agent_control,
+            background_tasks: BackgroundTasks::default(),
+            input_queue: InputQueue::new(tx_sub.clone()),
+            checkpoints: Checkpoints::default(),
+            subagent_merges: SubAgentMerges::default(),
+            redo_stack: RedoStack::default(),
+            turn_snapshots: TurnSnapshots::default(),
+            pinned_context: PinnedContext::default(),
+            runtime_state: RuntimeStateTracker::default(),
+            hooks: Hooks::new(&config.hooks),
+            context_providers: ContextProviders::new(&config.context_providers),
+            mcp_resource_mentions: McpResourceMentions::default(),
+            session_tmp,
+            network_capture,
+            network_proxy,
+            external_edits,
+            governor: config
+                .governor
+                .clone()
+                .map(|governor| Governor::new(&config.codex_home, governor)),
+            audit_log: config
+                .audit
+                .clone()
+                .map(|audit| AuditLog::start(&config.codex_home, audit, conversation_id)),
+            worktree_isolation: config.worktree_isolation.clone().map(|isolation| {
+                WorktreeIsolation::new(isolation, &config.codex_home, conversation_id)
+            }),
+            cost_tracker: CostTracker::new(config.budget.clone()),
+            tool_trimming: ToolTrimming::default(),
+            browser: BrowserSession::default(),
+            plugin_tools,
         };
 
         let sess = Arc::new(Session {
             conversation_id,
             tx_event: tx_event.clone(),
             agent_status,
+            total_token_usage: watch::channel(None).0,
             state: Mutex::new(state),
             features: config.features.clone(),
             pending_mcp_server_refresh_config: Mutex::new(None),
             active_turn: Mutex::new(None),
             services,
             next_internal_sub_id: AtomicU64::new(0),
+            tx_sub,
         });
 
         // Dispatch the SessionConfiguredEvent first and then report any errors.
@@ -853,10 +1047,13 @@ impl Session {
         };
         let cancel_token = sess.mcp_startup_cancellation_token().await;
 
-        sess.services
-            .mcp_connection_manager
-            .write()
-            .await
+        let mut mcp_connection_manager = sess.services.mcp_connection_manager.write().await;
+        mcp_connection_manager.set_sampler(Arc::new(McpSampler::new(
+            config.mcp_sampling.clone(),
+            Arc::downgrade(&sess),
+        )));
+        mcp_connection_manager.set_health_config(config.mcp_health.clone());
+        mcp_connection_manager
             .initialize(
                 &config.mcp_servers,
                 config.mcp_oauth_credentials_store_mode,
@@ -866,6 +1063,18 @@ impl Session {
                 sandbox_state,
             )
             .await;
+        drop(mcp_connection_manager);
+
+        // Child threads share their parent's definitions; only top-level
+        // sessions watch the subagent roots.
+        if !matches!(
+            session_configuration.session_source,
+            SessionSource::SubAgent(_)
+        ) {
+            sess.services
+                .subagents_manager
+                .start_watching(session_configuration.cwd.clone(), tx_event.clone());
+        }
 
         // record_initial_history can emit events. We record only after the SessionConfiguredEvent is emitted.
         sess.record_initial_history(initial_history).await;
@@ -966,12 +1175,37 @@ impl Session {
                     let mut state = self.state.lock().await;
                     state.set_token_info(Some(info));
                 }
+                self.services
+                    .pinned_context
+                    .seed_from_rollout(&rollout_items)
+                    .await;
+                // A fork is a new session, so it starts with its own budget.
+                if !persist {
+                    self.services
+                        .cost_tracker
+                        .seed_from_rollout(&rollout_items)
+                        .await;
+                }
 
                 // If persisting, persist all rollout items as-is (recorder filters)
                 if persist && !rollout_items.is_empty() {
                     self.persist_rollout_items(&rollout_items).await;
                 }
 
+                if let InitialHistory::Resumed(_) = conversation_history
+                    && self.enabled(Feature::ResumeRuntimeState)
+                {
+                    crate::runtime_state::restore_on_resume(self, &turn_context, &rollout_items)
+                        .await;
+                }
+
+                if let InitialHistory::Resumed(_) = conversation_history
+                    && self.enabled(Feature::ResumeBriefing)
+                {
+                    crate::resume_briefing::brief_on_resume(self, &turn_context, &rollout_items)
+                        .await;
+                }
+
                 // Append the current session's initial context after the reconstructed history.
                 let initial_context = self.build_initial_context(&turn_context).await;
                 self.record_conversation_items(&turn_context, &initial_context)
@@ -1007,6 +1241,43 @@ impl Session {
         }
     }
 
+    /// Re-resolves `[branches]` overrides when the checked-out branch changed
+    /// since they were last applied. The resulting policies are folded into
+    /// `updates`; `new_turn_with_sub_id` records the branch and announces them
+    /// with `EventMsg::ConfigReloaded` once they pass the session's
+    /// constraints, so a rejected switch is retried on the next turn.
+    pub(crate) async fn apply_branch_config(
+        &self,
+        mut updates: SessionSettingsUpdate,
+    ) -> SessionSettingsUpdate {
+        let (branch, approval_policy, sandbox_policy) = {
+            let state = self.state.lock().await;
+            let Some(branch_config) = state
+                .session_configuration
+                .original_config_do_not_use
+                .branch_config
+                .clone()
+            else {
+                return updates;
+            };
+            let cwd = updates
+                .cwd
+                .clone()
+                .unwrap_or_else(|| state.session_configuration.cwd.clone());
+            let branch = checked_out_branch(&cwd);
+            if branch == state.checked_out_branch {
+                return updates;
+            }
+            let (approval_policy, sandbox_policy) = branch_config.resolve(branch.as_deref());
+            (branch, approval_policy, sandbox_policy)
+        };
+
+        updates.approval_policy = Some(approval_policy);
+        updates.sandbox_policy = Some(sandbox_policy);
+        updates.checked_out_branch = Some(branch);
+        updates
+    }
+
     pub(crate) async fn new_turn_with_sub_id(
         &self,
         sub_id: String,
@@ -1019,6 +1290,9 @@ impl Session {
                     let sandbox_policy_changed =
                         state.session_configuration.sandbox_policy != next.sandbox_policy;
                     state.session_configuration = next.clone();
+                    if let Some(branch) = &updates.checked_out_branch {
+                        state.checked_out_branch = branch.clone();
+                    }
                     (next, sandbox_policy_changed)
                 }
                 Err(err) => {
@@ -1036,6 +1310,18 @@ impl Session {
             }
         };
 
+        if let Some(branch) = updates.checked_out_branch {
+            self.send_event_raw(Event {
+                id: sub_id.clone(),
+                msg: EventMsg::ConfigReloaded(ConfigReloadedEvent {
+                    branch,
+                    approval_policy: session_configuration.approval_policy.value(),
+                    sandbox_policy: session_configuration.sandbox_policy.get().clone(),
+                }),
+            })
+            .await;
+        }
+
         Ok(self
             .new_turn_from_configuration(
                 sub_id,
@@ -1049,10 +1335,15 @@ impl Session {
     async fn new_turn_from_configuration(
         &self,
         sub_id: String,
-        session_configuration: SessionConfiguration,
+        mut session_configuration: SessionConfiguration,
         final_output_json_schema: Option<Option<Value>>,
         sandbox_policy_changed: bool,
     ) -> Arc<TurnContext> {
+        if let Some(worktree_isolation) = &self.services.worktree_isolation
+            && let Some(cwd) = worktree_isolation.map_cwd(&session_configuration.cwd).await
+        {
+            session_configuration.cwd = cwd;
+        }
         let per_turn_config = Self::build_per_turn_config(&session_configuration);
 
         if sandbox_policy_changed {
@@ -1094,6 +1385,28 @@ impl Session {
         if let Some(final_schema) = final_output_json_schema {
             turn_context.final_output_json_schema = final_schema;
         }
+        turn_context.tools_config.plugin_tools = self.services.plugin_tools.clone();
+        if let Some(session_tmp) = &self.services.session_tmp {
+            session_tmp.apply_to_env_policy(&mut turn_context.shell_environment_policy);
+            session_tmp
+                .apply_to_sandbox_policy(&mut turn_context.sandbox_policy, &turn_context.cwd);
+        }
+        if let Some(network_capture) = &self.services.network_capture
+            && turn_context.sandbox_policy.has_full_network_access()
+        {
+            network_capture.apply_to_env_policy(&mut turn_context.shell_environment_policy);
+        }
+        if let Some(network_proxy) = &self.services.network_proxy
+            && matches!(
+                turn_context.sandbox_policy,
+                SandboxPolicy::WorkspaceWrite {
+                    network_access: false,
+                    ..
+                }
+            )
+        {
+            network_proxy.apply_to_env_policy(&mut turn_context.shell_environment_policy);
+        }
         Arc::new(turn_context)
     }
 
@@ -1211,6 +1524,18 @@ impl Session {
         }
     }
 
+    fn build_plan_mode_update_item(
+        previous: Option<&Arc<TurnContext>>,
+        next: &TurnContext,
+    ) -> Option<ResponseItem> {
+        let enabled = next.tools_config.plan_mode;
+        let previously_enabled = previous.is_some_and(|prev| prev.tools_config.plan_mode);
+        if enabled == previously_enabled {
+            return None;
+        }
+        Some(plan_mode::instructions(enabled).into())
+    }
+
     fn build_settings_update_items(
         &self,
         previous_context: Option<&Arc<TurnContext>>,
@@ -1240,11 +1565,17 @@ impl Session {
         {
             update_items.push(personality_item);
         }
+        if let Some(plan_mode_item) =
+            Self::build_plan_mode_update_item(previous_context, current_context)
+        {
+            update_items.push(plan_mode_item);
+        }
         update_items
     }
 
     /// Persist the event to rollout and send it to clients.
     pub(crate) async fn send_event(&self, turn_context: &TurnContext, msg: EventMsg) {
+        self.services.hooks.observe_event(self, turn_context, &msg);
         let legacy_source = msg.clone();
         let event = Event {
             id: turn_context.sub_id.clone(),
@@ -1270,6 +1601,10 @@ impl Session {
         // Persist the event into rollout (recorder filters as needed)
         let rollout_items = vec![RolloutItem::EventMsg(event.msg.clone())];
         self.persist_rollout_items(&rollout_items).await;
+        crate::runtime_state::observe_event(self, &event.msg).await;
+        if let Some(audit_log) = self.services.audit_log.as_ref() {
+            audit_log.observe_event(&event.msg);
+        }
         if let Err(e) = self.tx_event.send(event).await {
             debug!("dropping event because channel is closed: {e}");
         }
@@ -1384,17 +1719,56 @@ impl Session {
         }
 
         let parsed_cmd = parse_command(&command);
+        let read_only = self.services.exec_policy.is_read_only(&command);
+        let risk = command_risk(&command, read_only, &turn_context.sandbox_policy);
+        let matched_rule = self
+            .services
+            .exec_policy
+            .matching_command_rule(&command, &cwd);
+        let explanation = if self.features.enabled(Feature::ApprovalExplanations) {
+            explain_command(&parsed_cmd)
+        } else {
+            None
+        };
+        let sandbox = if policy_uses_sandbox(&turn_context.sandbox_policy) {
+            let codex_home = self
+                .state
+                .lock()
+                .await
+                .session_configuration
+                .original_config_do_not_use
+                .codex_home
+                .clone();
+            Some(detect_sandbox_capabilities(
+                &codex_home,
+                turn_context.codex_linux_sandbox_exe.as_ref(),
+            ))
+        } else {
+            None
+        };
         let event = EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
-            call_id,
+            call_id: call_id.clone(),
             turn_id: turn_context.sub_id.clone(),
             command,
             cwd,
             reason,
             proposed_execpolicy_amendment,
             parsed_cmd,
+            explanation,
+            sandbox_policy: Some(turn_context.sandbox_policy.clone()),
+            risk: Some(risk),
+            matched_rule,
+            sandbox,
         });
         self.send_event(turn_context, event).await;
-        rx_approve.await.unwrap_or_default()
+        self.await_approval(
+            turn_context,
+            call_id,
+            ApprovalKind::Exec,
+            read_only,
+            rx_approve,
+        )
+        .await
     }
 
     pub async fn request_patch_approval(
@@ -1404,7 +1778,8 @@ impl Session {
         changes: HashMap<PathBuf, FileChange>,
         reason: Option<String>,
         grant_root: Option<PathBuf>,
-    ) -> oneshot::Receiver<ReviewDecision> {
+        preview: Option<PatchPreview>,
+    ) -> ReviewDecision {
         let sub_id = turn_context.sub_id.clone();
         // Add the tx_approve callback to the map before sending the request.
         let (tx_approve, rx_approve) = oneshot::channel();
@@ -1423,15 +1798,236 @@ impl Session {
             warn!("Overwriting existing pending approval for sub_id: {event_id}");
         }
 
+        let files = file_diffs_for_changes(&changes);
+        let risk = patch_risk(&changes, &turn_context.cwd);
         let event = EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
-            call_id,
+            call_id: call_id.clone(),
             turn_id: turn_context.sub_id.clone(),
             changes,
             reason,
             grant_root,
+            files,
+            sandbox_policy: Some(turn_context.sandbox_policy.clone()),
+            risk: Some(risk),
+            preview,
         });
         self.send_event(turn_context, event).await;
-        rx_approve
+        self.await_approval(
+            turn_context,
+            call_id,
+            ApprovalKind::Patch,
+            false,
+            rx_approve,
+        )
+        .await
+    }
+
+    /// Sends a `submit_plan` plan to the user and waits for the decision. An
+    /// approval unlocks write tools for the rest of the turn.
+    pub async fn request_plan_approval(
+        &self,
+        turn_context: &TurnContext,
+        call_id: String,
+        plan: ProposedPlan,
+    ) -> ReviewDecision {
+        let sub_id = turn_context.sub_id.clone();
+        let (tx_approve, rx_approve) = oneshot::channel();
+        let event_id = sub_id.clone();
+        let prev_entry = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => {
+                    let mut ts = at.turn_state.lock().await;
+                    ts.insert_pending_approval(sub_id, tx_approve)
+                }
+                None => None,
+            }
+        };
+        if prev_entry.is_some() {
+            warn!("Overwriting existing pending approval for sub_id: {event_id}");
+        }
+
+        let scope = plan_scope::scope_from_plan(&plan, &turn_context.cwd);
+        let event = EventMsg::PlanApprovalRequest(PlanApprovalRequestEvent {
+            call_id: call_id.clone(),
+            turn_id: turn_context.sub_id.clone(),
+            plan,
+        });
+        self.send_event(turn_context, event).await;
+        let decision = self
+            .await_approval(turn_context, call_id, ApprovalKind::Plan, false, rx_approve)
+            .await;
+        if matches!(
+            decision,
+            ReviewDecision::Approved | ReviewDecision::ApprovedForSession
+        ) {
+            let active = self.active_turn.lock().await;
+            if let Some(at) = active.as_ref() {
+                at.turn_state.lock().await.approve_plan(scope);
+            }
+        }
+        decision
+    }
+
+    /// Asks the user whether an MCP tool marked `always_ask` may run.
+    pub async fn request_mcp_tool_approval(
+        &self,
+        turn_context: &TurnContext,
+        call_id: String,
+        invocation: McpInvocation,
+    ) -> ReviewDecision {
+        let sub_id = turn_context.sub_id.clone();
+        let (tx_approve, rx_approve) = oneshot::channel();
+        let event_id = sub_id.clone();
+        let prev_entry = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => {
+                    let mut ts = at.turn_state.lock().await;
+                    ts.insert_pending_approval(sub_id, tx_approve)
+                }
+                None => None,
+            }
+        };
+        if prev_entry.is_some() {
+            warn!("Overwriting existing pending approval for sub_id: {event_id}");
+        }
+
+        let event = EventMsg::McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent {
+            call_id: call_id.clone(),
+            turn_id: turn_context.sub_id.clone(),
+            invocation,
+        });
+        self.send_event(turn_context, event).await;
+        self.await_approval(
+            turn_context,
+            call_id,
+            ApprovalKind::McpTool,
+            false,
+            rx_approve,
+        )
+        .await
+    }
+
+    /// Waits for the answer to an approval prompt. When `[approval_timeouts]`
+    /// has an entry for `kind` and it expires first, the configured action
+    /// decides instead; `read_only` says whether `allow-read-only` may approve.
+    async fn await_approval(
+        &self,
+        turn_context: &TurnContext,
+        call_id: String,
+        kind: ApprovalKind,
+        read_only: bool,
+        rx_approve: oneshot::Receiver<ReviewDecision>,
+    ) -> ReviewDecision {
+        let Some(ApprovalTimeout { timeout, action }) =
+            self.get_config().await.approval_timeouts.for_kind(kind)
+        else {
+            return rx_approve.await.unwrap_or_default();
+        };
+        if let Ok(decision) = tokio::time::timeout(timeout, rx_approve).await {
+            return decision.unwrap_or_default();
+        }
+
+        // Drop the pending entry so a late answer is reported as stale.
+        {
+            let mut active = self.active_turn.lock().await;
+            if let Some(at) = active.as_mut() {
+                let mut ts = at.turn_state.lock().await;
+                ts.remove_pending_approval(&turn_context.sub_id);
+            }
+        }
+
+        let decision = match action {
+            ApprovalTimeoutAction::AllowReadOnly if read_only => ReviewDecision::Approved,
+            ApprovalTimeoutAction::Deny | ApprovalTimeoutAction::AllowReadOnly => {
+                ReviewDecision::Denied
+            }
+            ApprovalTimeoutAction::AbortTurn => ReviewDecision::Abort,
+        };
+        warn!(
+            call_id = %call_id,
+            ?kind,
+            ?action,
+            "approval timed out after {}s",
+            timeout.as_secs()
+        );
+        self.send_event(
+            turn_context,
+            EventMsg::ApprovalTimedOut(ApprovalTimedOutEvent {
+                call_id,
+                turn_id: turn_context.sub_id.clone(),
+                kind,
+                timeout_secs: timeout.as_secs(),
+                action,
+                decision: decision.clone(),
+            }),
+        )
+        .await;
+
+        // Same as the user picking "abort": the tool call is rejected and the
+        // turn is interrupted. The interrupt goes through the submission loop
+        // because it waits for the running turn, which is our caller.
+        if action == ApprovalTimeoutAction::AbortTurn
+            && let Some(tx_sub) = self.tx_sub.upgrade()
+        {
+            let submission = Submission {
+                id: self.next_internal_sub_id(),
+                op: Op::Interrupt,
+            };
+            if let Err(err) = tx_sub.send(submission).await {
+                warn!("failed to abort the turn after an approval timeout: {err}");
+            }
+        }
+        decision
+    }
+
+    /// Whether a plan was approved in the running turn (plan mode).
+    pub(crate) async fn plan_approved(&self) -> bool {
+        let active = self.active_turn.lock().await;
+        match active.as_ref() {
+            Some(at) => at.turn_state.lock().await.plan_approved(),
+            None => false,
+        }
+    }
+
+    /// Files the approved plan may patch; `None` before a plan is approved.
+    pub(crate) async fn plan_scope(&self) -> Option<Vec<AbsolutePathBuf>> {
+        let active = self.active_turn.lock().await;
+        let at = active.as_ref()?;
+        let ts = at.turn_state.lock().await;
+        ts.plan_scope().map(<[AbsolutePathBuf]>::to_vec)
+    }
+
+    /// Adds files the user approved patching to the plan's scope.
+    pub(crate) async fn expand_plan_scope(&self, paths: Vec<AbsolutePathBuf>) {
+        let active = self.active_turn.lock().await;
+        if let Some(at) = active.as_ref() {
+            at.turn_state.lock().await.expand_plan_scope(paths);
+        }
+    }
+
+    /// Counts a retried model request toward the running turn's stats.
+    pub(crate) async fn record_turn_retry(&self) {
+        let active = self.active_turn.lock().await;
+        if let Some(at) = active.as_ref() {
+            at.turn_state.lock().await.stats.retries += 1;
+        }
+    }
+
+    /// Model, timing, retry and token stats of the running turn.
+    pub(crate) async fn turn_metadata(&self, turn_context: &TurnContext) -> Option<TurnMetadata> {
+        let active = self.active_turn.lock().await;
+        let at = active.as_ref()?;
+        let ts = at.turn_state.lock().await;
+        Some(TurnMetadata {
+            model: turn_context.client.get_model(),
+            reasoning_effort: turn_context.client.get_reasoning_effort(),
+            duration_ms: u64::try_from(ts.stats.started_at.elapsed().as_millis())
+                .unwrap_or(u64::MAX),
+            retries: ts.stats.retries,
+            token_usage: ts.stats.token_usage.clone(),
+        })
     }
 
     pub async fn request_user_input(
@@ -1505,6 +2101,7 @@ impl Session {
         match entry {
             Some(tx_approve) => {
                 tx_approve.send(decision).ok();
+                crate::runtime_state::approval_answered(self, sub_id).await;
             }
             None => {
                 warn!("No pending approval found for sub_id: {sub_id}");
@@ -1581,7 +2178,12 @@ impl Session {
         turn_context: &TurnContext,
     ) {
         let mut state = self.state.lock().await;
-        state.record_items(items.iter(), turn_context.truncation_policy);
+        let policy = if self.enabled(Feature::AdaptiveTruncation) {
+            state.history.output_truncation_policy(turn_context)
+        } else {
+            turn_context.truncation_policy
+        };
+        state.record_items(items.iter(), policy);
     }
 
     pub(crate) async fn record_model_warning(&self, message: impl Into<String>, ctx: &TurnContext) {
@@ -1664,6 +2266,9 @@ impl Session {
         {
             items.push(collab_instructions.into());
         }
+        if turn_context.tools_config.plan_mode {
+            items.push(plan_mode::instructions(true).into());
+        }
         if let Some(user_instructions) = turn_context.user_instructions.as_deref() {
             items.push(
                 UserInstructions {
@@ -1673,10 +2278,13 @@ impl Session {
                 .into(),
             );
         }
-        items.push(ResponseItem::from(EnvironmentContext::new(
-            Some(turn_context.cwd.clone()),
-            shell.as_ref().clone(),
-        )));
+        let mut environment_context =
+            EnvironmentContext::new(Some(turn_context.cwd.clone()), shell.as_ref().clone());
+        if self.enabled(Feature::ProjectToolchain) {
+            environment_context =
+                environment_context.with_toolchains(detect_project_toolchains(&turn_context.cwd));
+        }
+        items.push(ResponseItem::from(environment_context));
         items
     }
 
@@ -1711,7 +2319,47 @@ impl Session {
                 );
             }
         }
+        if let Some(token_usage) = token_usage {
+            let active = self.active_turn.lock().await;
+            if let Some(at) = active.as_ref() {
+                at.turn_state
+                    .lock()
+                    .await
+                    .stats
+                    .token_usage
+                    .add_assign(token_usage);
+            }
+        }
         self.send_token_count_event(turn_context).await;
+        if let Some(token_usage) = token_usage {
+            self.record_cost(turn_context, token_usage).await;
+        }
+    }
+
+    /// Adds a finished `Op::RunSubAgent` run to the per-subagent totals.
+    pub(crate) async fn record_subagent_usage(&self, name: &str, usage: Option<&TokenUsage>) {
+        let mut state = self.state.lock().await;
+        state.tool_usage.record_subagent_run(name, usage);
+    }
+
+    async fn record_cost(&self, turn_context: &TurnContext, token_usage: &TokenUsage) {
+        let outcome = self
+            .services
+            .cost_tracker
+            .record(
+                &turn_context.sub_id,
+                &turn_context.client.get_model(),
+                token_usage,
+            )
+            .await;
+        if let Some(event) = outcome.event {
+            self.send_event(turn_context, EventMsg::CostUpdate(event))
+                .await;
+        }
+        for message in outcome.warnings {
+            self.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+                .await;
+        }
     }
 
     pub(crate) async fn recompute_token_usage(&self, turn_context: &TurnContext) {
@@ -1765,11 +2413,24 @@ impl Session {
     }
 
     async fn send_token_count_event(&self, turn_context: &TurnContext) {
-        let (info, rate_limits) = {
+        let (info, rate_limits, tool_usage, subagent_usage) = {
             let state = self.state.lock().await;
-            state.token_info_and_rate_limits()
+            let (info, rate_limits) = state.token_info_and_rate_limits();
+            (
+                info,
+                rate_limits,
+                state.tool_usage.tool_usage(),
+                state.tool_usage.subagent_usage(),
+            )
         };
-        let event = EventMsg::TokenCount(TokenCountEvent { info, rate_limits });
+        self.total_token_usage
+            .send_replace(info.as_ref().map(|info| info.total_token_usage.clone()));
+        let event = EventMsg::TokenCount(TokenCountEvent {
+            info,
+            rate_limits,
+            tool_usage,
+            subagent_usage,
+        });
         self.send_event(turn_context, event).await;
     }
 
@@ -2041,6 +2702,15 @@ impl Session {
         let cancel_token = self.reset_mcp_startup_cancellation_token().await;
 
         let mut refreshed_manager = McpConnectionManager::default();
+        {
+            let manager = self.services.mcp_connection_manager.read().await;
+            if let Some(sampler) = manager.sampler() {
+                refreshed_manager.set_sampler(sampler);
+            }
+            if let Some(health) = manager.health_config() {
+                refreshed_manager.set_health_config(health);
+            }
+        }
         refreshed_manager
             .initialize(
                 &mcp_servers,
@@ -2141,6 +2811,12 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::PatchApproval { id, decision } => {
                 handlers::patch_approval(&sess, id, decision).await;
             }
+            Op::PlanApproval { id, decision } => {
+                handlers::plan_approval(&sess, id, decision).await;
+            }
+            Op::McpToolCallApproval { id, decision } => {
+                handlers::mcp_tool_call_approval(&sess, id, decision).await;
+            }
             Op::UserInputAnswer { id, response } => {
                 handlers::request_user_input_response(&sess, id, response).await;
             }
@@ -2157,21 +2833,52 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::RefreshMcpServers { config } => {
                 handlers::refresh_mcp_servers(&sess, config).await;
             }
+            Op::AddMcpServer {
+                name,
+                config: server_config,
+            } => {
+                handlers::add_mcp_server(&sess, &config, sub.id.clone(), name, server_config).await;
+            }
+            Op::RemoveMcpServer { name } => {
+                handlers::remove_mcp_server(&sess, sub.id.clone(), name).await;
+            }
             Op::ListCustomPrompts => {
                 handlers::list_custom_prompts(&sess, sub.id.clone()).await;
             }
+            Op::ListMcpPrompts => {
+                handlers::list_mcp_prompts(&sess, sub.id.clone()).await;
+            }
             Op::ListSkills { cwds, force_reload } => {
                 handlers::list_skills(&sess, sub.id.clone(), cwds, force_reload).await;
             }
             Op::Undo => {
                 handlers::undo(&sess, sub.id.clone()).await;
             }
+            Op::Redo => {
+                handlers::redo(&sess, sub.id.clone()).await;
+            }
+            Op::RestoreFile { turn_id, path } => {
+                handlers::restore_file(&sess, sub.id.clone(), turn_id, path).await;
+            }
+            Op::RevertHunks {
+                turn_id,
+                path,
+                hunks,
+            } => {
+                handlers::revert_hunks(&sess, sub.id.clone(), turn_id, path, hunks).await;
+            }
             Op::Compact => {
                 handlers::compact(&sess, sub.id.clone()).await;
             }
+            Op::CompactRange { from_turn, to_turn } => {
+                handlers::compact_range(&sess, sub.id.clone(), from_turn, to_turn).await;
+            }
             Op::ThreadRollback { num_turns } => {
                 handlers::thread_rollback(&sess, sub.id.clone(), num_turns).await;
             }
+            Op::ForkConversation { num_turns } => {
+                handlers::fork_conversation(&sess, sub.id.clone(), num_turns).await;
+            }
             Op::RunUserShellCommand { command } => {
                 handlers::run_user_shell_command(
                     &sess,
@@ -2196,6 +2903,118 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::Review { review_request } => {
                 handlers::review(&sess, &config, sub.id.clone(), review_request).await;
             }
+            Op::ListApprovalJournal { limit } => {
+                handlers::list_approval_journal(&sess, sub.id.clone(), limit).await;
+            }
+            Op::ListSubAgents => {
+                handlers::list_subagents(&sess, sub.id.clone()).await;
+            }
+            Op::RunSubAgent {
+                name,
+                prompt,
+                dry_run,
+            } => {
+                handlers::run_subagent(&sess, sub.id.clone(), name, prompt, dry_run).await;
+            }
+            Op::CancelSubAgent { run_id } => {
+                handlers::cancel_subagent(&sess, sub.id.clone(), run_id).await;
+            }
+            Op::MergeSubAgentChanges { run_ids } => {
+                handlers::merge_subagent_changes(&sess, sub.id.clone(), run_ids).await;
+            }
+            Op::ApplySubAgentMerge {
+                merge_id,
+                resolutions,
+            } => {
+                handlers::apply_subagent_merge(&sess, sub.id.clone(), merge_id, resolutions).await;
+            }
+            Op::StartBackgroundTask { prompt, subagent } => {
+                handlers::start_background_task(&sess, sub.id.clone(), prompt, subagent).await;
+            }
+            Op::ListBackgroundTasks => {
+                handlers::list_background_tasks(&sess, sub.id.clone()).await;
+            }
+            Op::CancelBackgroundTask { task_id } => {
+                handlers::cancel_background_task(&sess, sub.id.clone(), task_id).await;
+            }
+            Op::QueueUserInput { items } => {
+                handlers::queue_user_input(&sess, sub.id.clone(), items, &mut previous_context)
+                    .await;
+            }
+            Op::ListQueuedUserInputs => {
+                handlers::list_queued_user_inputs(&sess, sub.id.clone()).await;
+            }
+            Op::ClearQueuedUserInputs => {
+                handlers::clear_queued_user_inputs(&sess, sub.id.clone()).await;
+            }
+            Op::SetPlanMode { enabled } => {
+                handlers::set_plan_mode(&sess, sub.id.clone(), enabled).await;
+            }
+            Op::CreateCheckpoint { name } => {
+                handlers::create_checkpoint(&sess, sub.id.clone(), name).await;
+            }
+            Op::ListCheckpoints => {
+                handlers::list_checkpoints(&sess, sub.id.clone()).await;
+            }
+            Op::RestoreCheckpoint { id } => {
+                handlers::restore_checkpoint(&sess, sub.id.clone(), id).await;
+            }
+            Op::ReviewWorktree => {
+                handlers::review_worktree(&sess, sub.id.clone()).await;
+            }
+            Op::FinishWorktree { action } => {
+                handlers::finish_worktree(&sess, sub.id.clone(), action).await;
+            }
+            Op::PinContext { content } => {
+                handlers::pin_context(&sess, sub.id.clone(), content).await;
+            }
+            Op::UnpinContext { id } => {
+                handlers::unpin_context(&sess, sub.id.clone(), id).await;
+            }
+            Op::ListPinnedContext => {
+                handlers::list_pinned_context(&sess, sub.id.clone()).await;
+            }
+            Op::InspectContext => {
+                handlers::inspect_context(&sess, sub.id.clone()).await;
+            }
+            Op::DropContextItem { id } => {
+                handlers::drop_context_item(&sess, sub.id.clone(), id).await;
+            }
+            Op::ListMemory => {
+                handlers::list_memory(&sess, sub.id.clone()).await;
+            }
+            Op::UpdateMemory { id, text } => {
+                handlers::update_memory(&sess, sub.id.clone(), id, text).await;
+            }
+            Op::DeleteMemory { id } => {
+                handlers::delete_memory(&sess, sub.id.clone(), id).await;
+            }
+            Op::TerminalInput { process_id, input } => {
+                handlers::terminal_input(&sess, sub.id.clone(), process_id, input).await;
+            }
+            Op::SetTerminalSize { rows, cols } => {
+                handlers::set_terminal_size(&sess, rows, cols).await;
+            }
+            Op::SuggestNextEdit {
+                request_id,
+                path,
+                prefix,
+                suffix,
+                model,
+            } => {
+                handlers::suggest_next_edit(
+                    &sess,
+                    sub.id.clone(),
+                    NextEditRequest {
+                        request_id,
+                        path,
+                        prefix,
+                        suffix,
+                        model,
+                    },
+                )
+                .await;
+            }
             _ => {} // Ignore unknown ops; enum is non_exhaustive to allow extensions.
         }
     }
@@ -2210,37 +3029,58 @@ mod handlers {
 
     use crate::codex::spawn_review_thread;
     use crate::config::Config;
+    use crate::config::types::McpServerConfig;
 
     use crate::mcp::auth::compute_auth_statuses;
     use crate::mcp::collect_mcp_snapshot_from_manager;
+    use crate::next_edit::NextEditRequest;
+    use crate::project_memory::MemoryEdit;
     use crate::review_prompts::resolve_review_request;
+    use crate::tasks::CompactRangeTask;
     use crate::tasks::CompactTask;
+    use crate::tasks::RedoTask;
     use crate::tasks::RegularTask;
+    use crate::tasks::RestoreCheckpointTask;
+    use crate::tasks::RestoreFileTask;
+    use crate::tasks::RevertHunksTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
+    use codex_protocol::custom_prompts::CustomCommand;
     use codex_protocol::custom_prompts::CustomPrompt;
+    use codex_protocol::protocol::ApprovalJournalResponseEvent;
     use codex_protocol::protocol::CodexErrorInfo;
+    use codex_protocol::protocol::DiffHunk;
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
+    use codex_protocol::protocol::ListMcpPromptsResponseEvent;
     use codex_protocol::protocol::ListSkillsResponseEvent;
+    use codex_protocol::protocol::ListSubAgentsResponseEvent;
+    use codex_protocol::protocol::McpServerChange;
     use codex_protocol::protocol::McpServerRefreshConfig;
+    use codex_protocol::protocol::McpToolsChangedEvent;
     use codex_protocol::protocol::Op;
+    use codex_protocol::protocol::PinnedContent;
+    use codex_protocol::protocol::PlanModeUpdatedEvent;
     use codex_protocol::protocol::ReviewDecision;
     use codex_protocol::protocol::ReviewRequest;
     use codex_protocol::protocol::SkillsListEntry;
     use codex_protocol::protocol::ThreadRolledBackEvent;
     use codex_protocol::protocol::TurnAbortReason;
     use codex_protocol::protocol::WarningEvent;
+    use codex_protocol::protocol::WorktreeFinishAction;
     use codex_protocol::request_user_input::RequestUserInputResponse;
+    use codex_protocol::subagents::SubAgentMergeResolution;
 
     use crate::context_manager::is_user_turn_boundary;
+    use crate::subagents::SubAgentDefinition;
     use codex_protocol::config_types::CollaborationMode;
     use codex_protocol::config_types::Settings;
     use codex_protocol::user_input::UserInput;
     use codex_rmcp_client::ElicitationAction;
     use codex_rmcp_client::ElicitationResponse;
+    use codex_utils_pty::TerminalSize;
     use mcp_types::RequestId;
     use std::path::PathBuf;
     use std::sync::Arc;
@@ -2293,13 +3133,66 @@ mod handlers {
         }
     }
 
+    /// Commands from `.codex/commands/` and `$CODEX_HOME/commands/`, read
+    /// fresh so edits apply without restarting the session.
+    async fn load_session_custom_commands(sess: &Session) -> Vec<CustomCommand> {
+        let (cwd, codex_home) = {
+            let state = sess.state.lock().await;
+            let config = &state.session_configuration;
+            (
+                config.cwd.clone(),
+                config.original_config_do_not_use.codex_home.clone(),
+            )
+        };
+        tokio::task::spawn_blocking(move || {
+            crate::custom_commands::load_custom_commands(&cwd, &codex_home)
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    /// Replaces a leading `/<name> ARGS` custom command with its body and
+    /// switches the turn to the command's `model` and `mode`, if it sets them.
+    async fn expand_custom_command(
+        sess: &Session,
+        items: &mut [UserInput],
+        updates: &mut SessionSettingsUpdate,
+    ) {
+        let starts_with_slash = items
+            .iter()
+            .any(|item| matches!(item, UserInput::Text { text, .. } if text.starts_with('/')));
+        if !starts_with_slash {
+            return;
+        }
+        let commands = load_session_custom_commands(sess).await;
+        let Some(command) = crate::custom_commands::expand_command(&commands, items) else {
+            return;
+        };
+        let current = match &updates.collaboration_mode {
+            Some(mode) => mode.clone(),
+            None => sess
+                .state
+                .lock()
+                .await
+                .session_configuration
+                .collaboration_mode
+                .clone(),
+        };
+        let presets = sess.services.models_manager.list_collaboration_modes();
+        if let Some(mode) =
+            crate::custom_commands::command_collaboration_mode(command, &current, &presets)
+        {
+            updates.collaboration_mode = Some(mode);
+        }
+    }
+
     pub async fn user_input_or_turn(
         sess: &Arc<Session>,
         sub_id: String,
         op: Op,
         previous_context: &mut Option<Arc<TurnContext>>,
     ) {
-        let (items, updates) = match op {
+        let (mut items, mut updates) = match op {
             Op::UserTurn {
                 cwd,
                 approval_policy,
@@ -2329,6 +3222,7 @@ mod handlers {
                         reasoning_summary: Some(summary),
                         final_output_json_schema: Some(final_output_json_schema),
                         personality,
+                        ..Default::default()
                     },
                 )
             }
@@ -2344,6 +3238,7 @@ mod handlers {
             ),
             _ => unreachable!(),
         };
+        expand_custom_command(sess, &mut items, &mut updates).await;
 
         let previous_collaboration_mode = sess
             .state
@@ -2353,15 +3248,64 @@ mod handlers {
             .collaboration_mode
             .clone();
         let next_collaboration_mode = updates.collaboration_mode.clone();
+        let updates = sess.apply_branch_config(updates).await;
+        let cwd = match &updates.cwd {
+            Some(cwd) => cwd.clone(),
+            None => sess.state.lock().await.session_configuration.cwd.clone(),
+        };
+        if !crate::worktree_isolation::prepare_turn(sess, &sub_id, &cwd).await {
+            return;
+        }
         let Ok(current_context) = sess.new_turn_with_sub_id(sub_id, updates).await else {
             // new_turn_with_sub_id already emits the error event.
             return;
         };
+        let items = match crate::mcp_prompts::expand_prompt_command(sess, items).await {
+            Ok(items) => items,
+            Err(message) => {
+                sess.send_event(
+                    &current_context,
+                    EventMsg::Error(ErrorEvent {
+                        message,
+                        codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    }),
+                )
+                .await;
+                return;
+            }
+        };
+        let has_images = items
+            .iter()
+            .any(|item| matches!(item, UserInput::Image { .. } | UserInput::LocalImage { .. }));
+        if has_images && !current_context.client.get_model_info().supports_images() {
+            let model = current_context.client.get_model();
+            sess.send_event(
+                &current_context,
+                EventMsg::Error(ErrorEvent {
+                    message: format!(
+                        "{model} does not accept images. Remove the attached images or switch to a model that does; set `model_supports_images = true` if this model supports them."
+                    ),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            )
+            .await;
+            return;
+        }
         current_context
             .client
             .get_otel_manager()
             .user_prompt(&items);
 
+        if sess
+            .services
+            .hooks
+            .before_prompt_submit(sess, &current_context, &items)
+            .await
+        {
+            // The hook outcome event already tells the client why.
+            return;
+        }
+
         // Attempt to inject input into current task
         if let Err(items) = sess.inject_input(items).await {
             let update_items = sess.build_settings_update_items(
@@ -2464,6 +3408,41 @@ mod handlers {
         }
     }
 
+    pub async fn plan_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
+        match decision {
+            ReviewDecision::Abort => {
+                sess.interrupt_task().await;
+            }
+            other => sess.notify_approval(&id, other).await,
+        }
+    }
+
+    pub async fn mcp_tool_call_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
+        match decision {
+            ReviewDecision::Abort => {
+                sess.interrupt_task().await;
+            }
+            other => sess.notify_approval(&id, other).await,
+        }
+    }
+
+    pub async fn set_plan_mode(sess: &Arc<Session>, sub_id: String, enabled: bool) {
+        override_turn_context(
+            sess,
+            sub_id.clone(),
+            SessionSettingsUpdate {
+                plan_mode: Some(enabled),
+                ..Default::default()
+            },
+        )
+        .await;
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::PlanModeUpdated(PlanModeUpdatedEvent { enabled }),
+        })
+        .await;
+    }
+
     pub async fn request_user_input_response(
         sess: &Arc<Session>,
         id: String,
@@ -2524,6 +3503,103 @@ mod handlers {
         *guard = Some(refresh_config);
     }
 
+    pub async fn add_mcp_server(
+        sess: &Arc<Session>,
+        config: &Arc<Config>,
+        sub_id: String,
+        name: String,
+        server_config: serde_json::Value,
+    ) {
+        let server_config = match parse_mcp_server(&name, server_config) {
+            Ok(server_config) => server_config,
+            Err(error) => {
+                send_mcp_tools_changed(sess, sub_id, name, McpServerChange::Failed { error }).await;
+                return;
+            }
+        };
+        let store_mode = config.mcp_oauth_credentials_store_mode;
+        let auth_entry =
+            compute_auth_statuses(std::iter::once((&name, &server_config)), store_mode)
+                .await
+                .remove(&name);
+        let cancel_token = sess.mcp_startup_cancellation_token().await.child_token();
+        let ready = sess
+            .services
+            .mcp_connection_manager
+            .write()
+            .await
+            .add_server(
+                name.clone(),
+                server_config,
+                store_mode,
+                auth_entry,
+                sess.get_tx_event(),
+                cancel_token,
+            );
+
+        // Startup can take as long as the server's startup timeout; keep
+        // handling other ops meanwhile.
+        let sess = Arc::clone(sess);
+        tokio::spawn(async move {
+            let change = match ready.await {
+                Ok(tools) => McpServerChange::Added { tools },
+                Err(error) => McpServerChange::Failed { error },
+            };
+            send_mcp_tools_changed(&sess, sub_id, name, change).await;
+        });
+    }
+
+    pub async fn remove_mcp_server(sess: &Session, sub_id: String, name: String) {
+        let removed = sess
+            .services
+            .mcp_connection_manager
+            .write()
+            .await
+            .remove_server(&name);
+        let change = if removed {
+            McpServerChange::Removed
+        } else {
+            McpServerChange::Failed {
+                error: format!("no MCP server named `{name}` is running"),
+            }
+        };
+        send_mcp_tools_changed(sess, sub_id, name, change).await;
+    }
+
+    fn parse_mcp_server(
+        name: &str,
+        server_config: serde_json::Value,
+    ) -> Result<McpServerConfig, String> {
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name {
+            return Err(format!(
+                "invalid MCP server name `{name}` (use letters, numbers, '-', '_')"
+            ));
+        }
+        let server_config = serde_json::from_value::<McpServerConfig>(server_config)
+            .map_err(|err| format!("invalid config for MCP server `{name}`: {err}"))?;
+        if !server_config.enabled {
+            return Err(format!("MCP server `{name}` is disabled in its config"));
+        }
+        Ok(server_config)
+    }
+
+    async fn send_mcp_tools_changed(
+        sess: &Session,
+        sub_id: String,
+        server: String,
+        change: McpServerChange,
+    ) {
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::McpToolsChanged(McpToolsChangedEvent { server, change }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
     pub async fn list_mcp_tools(sess: &Session, config: &Arc<Config>, sub_id: String) {
         let mcp_connection_manager = sess.services.mcp_connection_manager.read().await;
         let snapshot = collect_mcp_snapshot_from_manager(
@@ -2537,28 +3613,135 @@ mod handlers {
         .await;
         let event = Event {
             id: sub_id,
-            msg: EventMsg::McpListToolsResponse(snapshot),
+            msg: EventMsg::McpListToolsResponse(snapshot),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn list_custom_prompts(sess: &Session, sub_id: String) {
+        let custom_prompts: Vec<CustomPrompt> =
+            if let Some(dir) = crate::custom_prompts::default_prompts_dir() {
+                crate::custom_prompts::discover_prompts_in(&dir).await
+            } else {
+                Vec::new()
+            };
+        let custom_commands = load_session_custom_commands(sess).await;
+
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListCustomPromptsResponse(ListCustomPromptsResponseEvent {
+                custom_prompts,
+                custom_commands,
+            }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn list_mcp_prompts(sess: &Session, sub_id: String) {
+        let prompts = sess
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .list_all_prompts()
+            .await;
+
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListMcpPromptsResponse(ListMcpPromptsResponseEvent { prompts }),
         };
         sess.send_event_raw(event).await;
     }
 
-    pub async fn list_custom_prompts(sess: &Session, sub_id: String) {
-        let custom_prompts: Vec<CustomPrompt> =
-            if let Some(dir) = crate::custom_prompts::default_prompts_dir() {
-                crate::custom_prompts::discover_prompts_in(&dir).await
-            } else {
-                Vec::new()
-            };
+    pub async fn list_subagents(sess: &Session, sub_id: String) {
+        let cwd = {
+            let state = sess.state.lock().await;
+            state.session_configuration.cwd.clone()
+        };
+        let outcome = sess.services.subagents_manager.subagents_for_cwd(&cwd);
 
         let event = Event {
             id: sub_id,
-            msg: EventMsg::ListCustomPromptsResponse(ListCustomPromptsResponseEvent {
-                custom_prompts,
+            msg: EventMsg::ListSubAgentsResponse(ListSubAgentsResponseEvent {
+                subagents: outcome
+                    .subagents
+                    .iter()
+                    .map(SubAgentDefinition::to_info)
+                    .collect(),
+                errors: outcome.errors,
             }),
         };
         sess.send_event_raw(event).await;
     }
 
+    pub async fn run_subagent(
+        sess: &Arc<Session>,
+        sub_id: String,
+        name: String,
+        prompt: String,
+        dry_run: bool,
+    ) {
+        crate::subagents::runner::run_subagent(sess, sub_id, name, prompt, dry_run).await;
+    }
+
+    pub async fn cancel_subagent(sess: &Session, sub_id: String, run_id: String) {
+        crate::subagents::runner::cancel_subagent(sess, sub_id, run_id).await;
+    }
+
+    pub async fn merge_subagent_changes(sess: &Session, sub_id: String, run_ids: Vec<String>) {
+        crate::subagents::merge::merge_subagent_changes(sess, sub_id, run_ids).await;
+    }
+
+    pub async fn apply_subagent_merge(
+        sess: &Session,
+        sub_id: String,
+        merge_id: String,
+        resolutions: Vec<SubAgentMergeResolution>,
+    ) {
+        crate::subagents::merge::apply_subagent_merge(sess, sub_id, merge_id, resolutions).await;
+    }
+
+    pub async fn start_background_task(
+        sess: &Arc<Session>,
+        sub_id: String,
+        prompt: String,
+        subagent: Option<String>,
+    ) {
+        crate::background_tasks::start_background_task(sess, sub_id, prompt, subagent).await;
+    }
+
+    pub async fn list_background_tasks(sess: &Session, sub_id: String) {
+        crate::background_tasks::list_background_tasks(sess, sub_id).await;
+    }
+
+    pub async fn cancel_background_task(sess: &Session, sub_id: String, task_id: String) {
+        crate::background_tasks::cancel_background_task(sess, sub_id, task_id).await;
+    }
+
+    pub async fn queue_user_input(
+        sess: &Arc<Session>,
+        sub_id: String,
+        items: Vec<UserInput>,
+        previous_context: &mut Option<Arc<TurnContext>>,
+    ) {
+        let Some(next) = crate::input_queue::queue_user_input(sess, sub_id, items).await else {
+            return;
+        };
+        let op = Op::UserInput {
+            items: next.items,
+            final_output_json_schema: None,
+        };
+        user_input_or_turn(sess, next.id, op, previous_context).await;
+    }
+
+    pub async fn list_queued_user_inputs(sess: &Session, sub_id: String) {
+        crate::input_queue::list_queued_user_inputs(sess, sub_id).await;
+    }
+
+    pub async fn clear_queued_user_inputs(sess: &Session, sub_id: String) {
+        crate::input_queue::clear_queued_user_inputs(sess, sub_id).await;
+    }
+
     pub async fn list_skills(
         sess: &Session,
         sub_id: String,
@@ -2592,12 +3775,145 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
+    pub async fn list_approval_journal(sess: &Session, sub_id: String, limit: Option<usize>) {
+        let entries = sess.services.approval_journal.lock().await.recent(limit);
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ApprovalJournalResponse(ApprovalJournalResponseEvent { entries }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
     pub async fn undo(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
         sess.spawn_task(turn_context, Vec::new(), UndoTask::new())
             .await;
     }
 
+    pub async fn redo(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(turn_context, Vec::new(), RedoTask::new())
+            .await;
+    }
+
+    pub async fn restore_file(
+        sess: &Arc<Session>,
+        sub_id: String,
+        turn_id: Option<String>,
+        path: PathBuf,
+    ) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(
+            turn_context,
+            Vec::new(),
+            RestoreFileTask::new(turn_id, path),
+        )
+        .await;
+    }
+
+    pub async fn revert_hunks(
+        sess: &Arc<Session>,
+        sub_id: String,
+        turn_id: Option<String>,
+        path: PathBuf,
+        hunks: Vec<DiffHunk>,
+    ) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(
+            turn_context,
+            Vec::new(),
+            RevertHunksTask::new(turn_id, path, hunks),
+        )
+        .await;
+    }
+
+    pub async fn fork_conversation(sess: &Arc<Session>, sub_id: String, num_turns: Option<u32>) {
+        crate::conversation_fork::fork_conversation(sess, sub_id, num_turns).await;
+    }
+
+    pub async fn create_checkpoint(sess: &Session, sub_id: String, name: String) {
+        crate::checkpoints::create_checkpoint(sess, sub_id, name).await;
+    }
+
+    pub async fn list_checkpoints(sess: &Session, sub_id: String) {
+        crate::checkpoints::list_checkpoints(sess, sub_id).await;
+    }
+
+    pub async fn restore_checkpoint(sess: &Arc<Session>, sub_id: String, id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(turn_context, Vec::new(), RestoreCheckpointTask::new(id))
+            .await;
+    }
+
+    pub async fn review_worktree(sess: &Session, sub_id: String) {
+        crate::worktree_isolation::review_worktree(sess, sub_id).await;
+    }
+
+    pub async fn finish_worktree(sess: &Session, sub_id: String, action: WorktreeFinishAction) {
+        crate::worktree_isolation::finish_worktree(sess, sub_id, action).await;
+    }
+
+    pub async fn pin_context(sess: &Session, sub_id: String, content: PinnedContent) {
+        crate::pinned_context::pin_context(sess, sub_id, content).await;
+    }
+
+    pub async fn unpin_context(sess: &Session, sub_id: String, id: String) {
+        crate::pinned_context::unpin_context(sess, sub_id, id).await;
+    }
+
+    pub async fn list_pinned_context(sess: &Session, sub_id: String) {
+        crate::pinned_context::list_pinned_context(sess, sub_id).await;
+    }
+
+    pub async fn inspect_context(sess: &Session, sub_id: String) {
+        crate::context_inspector::inspect_context(sess, sub_id).await;
+    }
+
+    pub async fn drop_context_item(sess: &Session, sub_id: String, id: String) {
+        crate::context_inspector::drop_context_item(sess, sub_id, id).await;
+    }
+
+    pub async fn list_memory(sess: &Session, sub_id: String) {
+        crate::project_memory::list_memory(sess, sub_id).await;
+    }
+
+    pub async fn update_memory(sess: &Session, sub_id: String, id: String, text: String) {
+        crate::project_memory::edit_memory(sess, sub_id, MemoryEdit::Update { id, text }).await;
+    }
+
+    pub async fn delete_memory(sess: &Session, sub_id: String, id: String) {
+        crate::project_memory::edit_memory(sess, sub_id, MemoryEdit::Delete { id }).await;
+    }
+
+    pub async fn terminal_input(sess: &Session, sub_id: String, process_id: String, input: String) {
+        if let Err(err) = sess
+            .services
+            .unified_exec_manager
+            .write_user_input(&process_id, &input)
+            .await
+        {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!("failed to send input to process {process_id}: {err}"),
+                    codex_error_info: None,
+                }),
+            })
+            .await;
+        }
+    }
+
+    pub async fn set_terminal_size(sess: &Session, rows: u16, cols: u16) {
+        sess.services
+            .unified_exec_manager
+            .set_terminal_size(TerminalSize { rows, cols })
+            .await;
+    }
+
+    pub async fn suggest_next_edit(sess: &Arc<Session>, sub_id: String, request: NextEditRequest) {
+        crate::next_edit::suggest_next_edit(sess, sub_id, request).await;
+    }
+
     pub async fn compact(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
 
@@ -2613,6 +3929,16 @@ mod handlers {
         .await;
     }
 
+    pub async fn compact_range(sess: &Arc<Session>, sub_id: String, from_turn: u32, to_turn: u32) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(
+            turn_context,
+            Vec::new(),
+            CompactRangeTask { from_turn, to_turn },
+        )
+        .await;
+    }
+
     pub async fn thread_rollback(sess: &Arc<Session>, sub_id: String, num_turns: u32) {
         if num_turns == 0 {
             sess.send_event_raw(Event {
@@ -2662,6 +3988,19 @@ mod handlers {
             .unified_exec_manager
             .terminate_all_processes()
             .await;
+        sess.services
+            .unified_exec_manager
+            .terminate_background_processes()
+            .await;
+        if let Some(session_tmp) = &sess.services.session_tmp {
+            session_tmp.cleanup();
+        }
+        if let Some(network_capture) = &sess.services.network_capture {
+            network_capture.shutdown();
+        }
+        if let Some(network_proxy) = &sess.services.network_proxy {
+            network_proxy.shutdown();
+        }
         info!("Shutting down Codex instance");
         let history = sess.clone_history().await;
         let turn_count = history
@@ -2711,7 +4050,11 @@ mod handlers {
     ) {
         let turn_context = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
         sess.refresh_mcp_servers_if_requested(&turn_context).await;
-        match resolve_review_request(review_request, turn_context.cwd.as_path()) {
+        match resolve_review_request(
+            review_request,
+            turn_context.cwd.as_path(),
+            config.review_split_by_area,
+        ) {
             Ok(resolved) => {
                 spawn_review_thread(
                     Arc::clone(sess),
@@ -2811,6 +4154,7 @@ async fn spawn_review_thread(
         codex_linux_sandbox_exe: parent_turn_context.codex_linux_sandbox_exe.clone(),
         tool_call_gate: Arc::new(ReadinessFlag::new()),
         truncation_policy: model_info.truncation_policy.into(),
+        exec_limits: parent_turn_context.exec_limits,
     };
 
     // Seed the child task with the review prompt as the initial user message.
@@ -2820,7 +4164,8 @@ async fn spawn_review_thread(
         text_elements: Vec::new(),
     }];
     let tc = Arc::new(review_turn_context);
-    sess.spawn_task(tc.clone(), input, ReviewTask::new()).await;
+    sess.spawn_task(tc.clone(), input, ReviewTask::by_area(resolved.areas))
+        .await;
 
     // Announce entering review mode so UIs can switch modes.
     let review_request = ReviewRequest {
@@ -2893,6 +4238,16 @@ pub(crate) async fn run_turn(
         return None;
     }
 
+    if let Some(message) = sess
+        .services
+        .cost_tracker
+        .limit_reached(&turn_context.sub_id)
+        .await
+    {
+        send_budget_exceeded(&sess, &turn_context, message).await;
+        return None;
+    }
+
     let model_info = turn_context.client.get_model_info();
     let auto_compact_limit = model_info.auto_compact_token_limit().unwrap_or(i64::MAX);
     let total_usage_tokens = sess.get_total_token_usage().await;
@@ -2932,6 +4287,32 @@ pub(crate) async fn run_turn(
             .await;
     }
 
+    if let Some(item) = sess
+        .services
+        .context_providers
+        .gather(&sess, &turn_context, &input)
+        .await
+    {
+        sess.record_conversation_items(&turn_context, &[item]).await;
+    }
+
+    if let Some(item) = sess
+        .services
+        .mcp_resource_mentions
+        .gather(&sess, &turn_context, &input)
+        .await
+    {
+        sess.record_conversation_items(&turn_context, &[item]).await;
+    }
+
+    if let Some(item) = crate::symbol_mentions::gather(&sess, &turn_context, &input).await {
+        sess.record_conversation_items(&turn_context, &[item]).await;
+    }
+
+    if let Some(item) = crate::editor_context::gather(&sess, &turn_context, &input).await {
+        sess.record_conversation_items(&turn_context, &[item]).await;
+    }
+
     sess.maybe_start_ghost_snapshot(Arc::clone(&turn_context), cancellation_token.child_token())
         .await;
     let mut last_agent_message: Option<String> = None;
@@ -2956,7 +4337,11 @@ pub(crate) async fn run_turn(
         let sampling_request_input: Vec<ResponseItem> = {
             sess.record_conversation_items(&turn_context, &pending_input)
                 .await;
-            sess.clone_history().await.for_prompt()
+            crate::external_edits::record_external_changes(&sess, &turn_context).await;
+            let mut input = sess.clone_history().await.for_prompt();
+            // Pinned context is not part of the history, so compaction cannot drop it.
+            crate::pinned_context::prepend_pinned_context(&sess, &mut input).await;
+            input
         };
 
         let sampling_request_input_messages = sampling_request_input
@@ -2985,6 +4370,17 @@ pub(crate) async fn run_turn(
                 let total_usage_tokens = sess.get_total_token_usage().await;
                 let token_limit_reached = total_usage_tokens >= auto_compact_limit;
 
+                if needs_follow_up
+                    && let Some(message) = sess
+                        .services
+                        .cost_tracker
+                        .limit_reached(&turn_context.sub_id)
+                        .await
+                {
+                    send_budget_exceeded(&sess, &turn_context, message).await;
+                    break;
+                }
+
                 // as long as compaction works well in getting us way below the token limit, we shouldn't worry about being in an infinite loop.
                 if token_limit_reached && needs_follow_up {
                     run_auto_compact(&sess, &turn_context).await;
@@ -3027,6 +4423,9 @@ pub(crate) async fn run_turn(
             }
             Err(e) => {
                 info!("Turn error: {e:#}");
+                sess.services
+                    .otel_manager
+                    .counter("codex.turn.error", 1, &[]);
                 let event = EventMsg::Error(e.to_error_event(None));
                 sess.send_event(&turn_context, event).await;
                 // let the user continue the conversation
@@ -3038,7 +4437,18 @@ pub(crate) async fn run_turn(
     last_agent_message
 }
 
+async fn send_budget_exceeded(sess: &Session, turn_context: &TurnContext, message: String) {
+    let event = EventMsg::Error(ErrorEvent {
+        message,
+        codex_error_info: Some(CodexErrorInfo::BudgetExceeded),
+    });
+    sess.send_event(turn_context, event).await;
+}
+
 async fn run_auto_compact(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) {
+    if run_configured_auto_compact(sess, turn_context).await {
+        return;
+    }
     if should_use_remote_compact_task(sess.as_ref(), &turn_context.client.get_provider()) {
         run_inline_remote_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context)).await;
     } else {
@@ -3070,15 +4480,30 @@ async fn run_sampling_request(
         .list_all_tools()
         .or_cancel(&cancellation_token)
         .await?;
-    let router = Arc::new(ToolRouter::from_config(
+    let mut router = ToolRouter::from_config(
         &turn_context.tools_config,
         Some(
             mcp_tools
-                .into_iter()
-                .map(|(name, tool)| (name, tool.tool))
+                .iter()
+                .map(|(name, tool)| (name.clone(), tool.tool.clone()))
                 .collect(),
         ),
-    ));
+    );
+    if turn_context.tools_config.tool_trimming {
+        let prompt = input
+            .iter()
+            .rev()
+            .find_map(|item| match parse_turn_item(item) {
+                Some(TurnItem::UserMessage(user_message)) => Some(user_message.message()),
+                _ => None,
+            })
+            .unwrap_or_default();
+        sess.services
+            .tool_trimming
+            .trim(&mut router, &mcp_tools, &prompt)
+            .await;
+    }
+    let router = Arc::new(router);
 
     let model_supports_parallel = turn_context
         .client
@@ -3097,6 +4522,7 @@ async fn run_sampling_request(
     };
 
     let mut retries = 0;
+    let mut rate_limit_retries = 0;
     loop {
         let err = match try_run_sampling_request(
             Arc::clone(&router),
@@ -3124,6 +4550,49 @@ async fn run_sampling_request(
             Err(err) => err,
         };
 
+        if let Some(governor) = sess.services.governor.as_ref()
+            && let Some(cooldown) = crate::governor::rate_limit_cooldown(&err)
+        {
+            let account = governor_account(&sess, &turn_context).await;
+            governor.block_account(&account, cooldown).await;
+        }
+
+        // A 429 says when to come back, so wait it out instead of spending
+        // the stream retry budget on requests that are bound to fail.
+        if let CodexErr::RateLimited(rate_limited) = &err
+            && rate_limit_retries < MAX_RATE_LIMIT_RETRIES
+        {
+            rate_limit_retries += 1;
+            sess.record_turn_retry().await;
+            let delay = rate_limited
+                .retry_after
+                .unwrap_or_else(|| backoff(rate_limit_retries))
+                .min(MAX_RATE_LIMIT_WAIT);
+            warn!(
+                "rate limited - retrying sampling request ({rate_limit_retries}/{MAX_RATE_LIMIT_RETRIES} in {delay:?})...",
+            );
+            sess.send_event(
+                &turn_context,
+                EventMsg::ProviderRateLimit(ProviderRateLimitEvent {
+                    quota: rate_limited.quota.clone(),
+                    resume_in_seconds: Some(delay.as_secs_f64().ceil() as u64),
+                }),
+            )
+            .await;
+            tokio::time::sleep(delay)
+                .or_cancel(&cancellation_token)
+                .await?;
+            sess.send_event(
+                &turn_context,
+                EventMsg::ProviderRateLimit(ProviderRateLimitEvent {
+                    quota: None,
+                    resume_in_seconds: None,
+                }),
+            )
+            .await;
+            continue;
+        }
+
         if !err.is_retryable() {
             return Err(err);
         }
@@ -3132,6 +4601,7 @@ async fn run_sampling_request(
         let max_retries = turn_context.client.get_provider().stream_max_retries();
         if retries < max_retries {
             retries += 1;
+            sess.record_turn_retry().await;
             let delay = match &err {
                 CodexErr::Stream(_, requested_delay) => {
                     requested_delay.unwrap_or_else(|| backoff(retries))
@@ -3159,6 +4629,54 @@ async fn run_sampling_request(
     }
 }
 
+/// Key under which rate limits are shared with other sessions: the provider
+/// and, when signed in with ChatGPT, the account.
+async fn governor_account(sess: &Session, turn_context: &TurnContext) -> String {
+    let provider = turn_context.client.get_provider().name;
+    let account = sess
+        .services
+        .auth_manager
+        .auth()
+        .await
+        .and_then(|auth| auth.get_account_id());
+    match account {
+        Some(account) => format!("{provider}/{account}"),
+        None => provider,
+    }
+}
+
+/// Waits until the `[governor]` limits let a model request start, telling the
+/// user once what it is waiting for.
+async fn acquire_governor_lease(
+    sess: &Session,
+    turn_context: &TurnContext,
+    cancellation_token: &CancellationToken,
+) -> CodexResult<Option<GovernorLease>> {
+    let Some(governor) = sess.services.governor.as_ref() else {
+        return Ok(None);
+    };
+    let account = governor_account(sess, turn_context).await;
+    let mut notified = false;
+    loop {
+        match governor.try_acquire(&account).await {
+            Ok(lease) => return Ok(Some(lease)),
+            Err(wait) => {
+                if !notified {
+                    sess.notify_background_event(
+                        turn_context,
+                        format!("Waiting for other Codex sessions: {}", wait.reason),
+                    )
+                    .await;
+                    notified = true;
+                }
+                tokio::time::sleep(wait.poll_delay())
+                    .or_cancel(cancellation_token)
+                    .await?;
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 struct SamplingRequestResult {
     needs_follow_up: bool,
@@ -3188,6 +4706,7 @@ async fn drain_in_flight(
 #[instrument(level = "trace",
     skip_all,
     fields(
+        otel.name = "codex.model_request",
         turn_id = %turn_context.sub_id,
         model = %turn_context.client.get_model()
     )
@@ -3219,6 +4738,7 @@ async fn try_run_sampling_request(
         developer_instructions: turn_context.developer_instructions.clone(),
         final_output_json_schema: turn_context.final_output_json_schema.clone(),
         truncation_policy: Some(turn_context.truncation_policy.into()),
+        tool_trimming: router.tool_trimming(),
     });
 
     feedback_tags!(
@@ -3231,6 +4751,8 @@ async fn try_run_sampling_request(
     );
 
     sess.persist_rollout_items(&[rollout_item]).await;
+    let mut governor_lease =
+        acquire_governor_lease(&sess, &turn_context, &cancellation_token).await?;
     let mut stream = client_session
         .stream(prompt)
         .instrument(trace_span!("stream_request"))
@@ -3321,6 +4843,16 @@ async fn try_run_sampling_request(
                 // token usage is available to avoid duplicate TokenCount events.
                 sess.update_rate_limits(&turn_context, snapshot).await;
             }
+            ResponseEvent::ProviderQuota(quota) => {
+                sess.send_event(
+                    &turn_context,
+                    EventMsg::ProviderRateLimit(ProviderRateLimitEvent {
+                        quota: Some(quota),
+                        resume_in_seconds: None,
+                    }),
+                )
+                .await;
+            }
             ResponseEvent::ModelsEtag(etag) => {
                 // Update internal state with latest models etag
                 let config = sess.get_config().await;
@@ -3335,6 +4867,13 @@ async fn try_run_sampling_request(
             } => {
                 sess.update_token_usage_info(&turn_context, token_usage.as_ref())
                     .await;
+                if let Some(governor) = sess.services.governor.as_ref()
+                    && let Some(token_usage) = token_usage.as_ref()
+                {
+                    governor.record_tokens(token_usage.total_tokens).await;
+                }
+                // Tool calls still running do not count as a model request.
+                drop(governor_lease.take());
                 should_emit_turn_diff = true;
 
                 needs_follow_up |= sess.has_pending_input().await;
@@ -3414,12 +4953,12 @@ async fn try_run_sampling_request(
     drain_in_flight(&mut in_flight, sess.clone(), turn_context.clone()).await?;
 
     if should_emit_turn_diff {
-        let unified_diff = {
+        let turn_diff = {
             let mut tracker = turn_diff_tracker.lock().await;
-            tracker.get_unified_diff()
+            tracker.get_turn_diff()
         };
-        if let Ok(Some(unified_diff)) = unified_diff {
-            let msg = EventMsg::TurnDiff(TurnDiffEvent { unified_diff });
+        if let Ok(Some(turn_diff)) = turn_diff {
+            let msg = EventMsg::TurnDiff(turn_diff);
             sess.clone().send_event(&turn_context, msg).await;
         }
     }
@@ -3459,6 +4998,8 @@ mod tests {
     use super::*;
     use crate::CodexAuth;
     use crate::config::ConfigBuilder;
+    use crate::config::branches::BranchConfig;
+    use crate::config::branches::BranchRule;
     use crate::config::test_config;
     use crate::exec::ExecToolCallOutput;
     use crate::function_tool::FunctionCallError;
@@ -3508,6 +5049,86 @@ mod tests {
         expects_apply_patch_instructions: bool,
     }
 
+    #[tokio::test]
+    async fn branch_switch_reapplies_branch_config() {
+        let (sess, _tc, rx) = make_session_and_context_with_rx().await;
+        let repo = tempfile::tempdir().expect("create temp dir");
+        std::fs::create_dir(repo.path().join(".git")).expect("create .git");
+        let head = repo.path().join(".git/HEAD");
+        std::fs::write(&head, "ref: refs/heads/main\n").expect("write HEAD");
+        {
+            let mut state = sess.state.lock().await;
+            let mut config = (*state.session_configuration.original_config_do_not_use).clone();
+            config.branch_config = Some(BranchConfig {
+                branch: Some("main".to_string()),
+                rules: vec![BranchRule {
+                    pattern: "codex/*".to_string(),
+                    approval_policy: Some(AskForApproval::Never),
+                    sandbox_policy: Some(SandboxPolicy::DangerFullAccess),
+                }],
+                base_approval_policy: AskForApproval::UnlessTrusted,
+                base_sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            });
+            state.session_configuration.original_config_do_not_use = Arc::new(config);
+            state.session_configuration.cwd = repo.path().to_path_buf();
+            state.checked_out_branch = Some("main".to_string());
+        }
+
+        let unchanged = sess
+            .apply_branch_config(SessionSettingsUpdate::default())
+            .await;
+        assert_eq!(
+            (unchanged.approval_policy, unchanged.sandbox_policy),
+            (None, None)
+        );
+        assert!(rx.is_empty());
+
+        std::fs::write(&head, "ref: refs/heads/codex/fix\n").expect("write HEAD");
+        let switched = sess
+            .apply_branch_config(SessionSettingsUpdate::default())
+            .await;
+        assert_eq!(
+            (
+                switched.approval_policy,
+                switched.sandbox_policy.clone(),
+                switched.checked_out_branch.clone()
+            ),
+            (
+                Some(AskForApproval::Never),
+                Some(SandboxPolicy::DangerFullAccess),
+                Some(Some("codex/fix".to_string()))
+            )
+        );
+        // Nothing is recorded until the settings are applied to a turn.
+        assert_eq!(
+            sess.state.lock().await.checked_out_branch,
+            Some("main".to_string())
+        );
+        assert!(rx.is_empty());
+
+        sess.new_turn_with_sub_id("sub".to_string(), switched)
+            .await
+            .expect("new turn");
+        assert_eq!(
+            sess.state.lock().await.checked_out_branch,
+            Some("codex/fix".to_string())
+        );
+        let reloaded = loop {
+            let event = rx.recv().await.expect("config reloaded event");
+            if let EventMsg::ConfigReloaded(reloaded) = event.msg {
+                break reloaded;
+            }
+        };
+        assert_eq!(
+            reloaded,
+            ConfigReloadedEvent {
+                branch: Some("codex/fix".to_string()),
+                approval_policy: AskForApproval::Never,
+                sandbox_policy: SandboxPolicy::DangerFullAccess,
+            }
+        );
+    }
+
     #[tokio::test]
     async fn get_base_instructions_no_user_content() {
         let prompt_with_apply_patch_instructions =
@@ -3648,24 +5269,32 @@ mod tests {
             TokenCountEvent {
                 info: Some(info1),
                 rate_limits: None,
+                tool_usage: Vec::new(),
+                subagent_usage: Vec::new(),
             },
         )));
         rollout_items.push(RolloutItem::EventMsg(EventMsg::TokenCount(
             TokenCountEvent {
                 info: None,
                 rate_limits: None,
+                tool_usage: Vec::new(),
+                subagent_usage: Vec::new(),
             },
         )));
         rollout_items.push(RolloutItem::EventMsg(EventMsg::TokenCount(
             TokenCountEvent {
                 info: Some(info2.clone()),
                 rate_limits: None,
+                tool_usage: Vec::new(),
+                subagent_usage: Vec::new(),
             },
         )));
         rollout_items.push(RolloutItem::EventMsg(EventMsg::TokenCount(
             TokenCountEvent {
                 info: None,
                 rate_limits: None,
+                tool_usage: Vec::new(),
+                subagent_usage: Vec::new(),
             },
         )));
 
@@ -3855,6 +5484,7 @@ mod tests {
             cwd: config.cwd.clone(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
+            plan_mode: false,
         };
 
         let mut state = SessionState::new(session_configuration);
@@ -3931,6 +5561,7 @@ mod tests {
             cwd: config.cwd.clone(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
+            plan_mode: false,
         };
 
         let mut state = SessionState::new(session_configuration);
@@ -4191,6 +5822,7 @@ mod tests {
             cwd: config.cwd.clone(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
+            plan_mode: false,
         };
         let per_turn_config = Session::build_per_turn_config(&session_configuration);
         let model_info = ModelsManager::construct_model_info_offline(
@@ -4220,8 +5852,35 @@ mod tests {
             otel_manager: otel_manager.clone(),
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_journal: Mutex::new(ApprovalJournal::default()),
             skills_manager,
+            subagents_manager: Arc::new(SubAgentsManager::new(
+                config.codex_home.clone(),
+                config.subagent_extra_dirs.clone(),
+            )),
             agent_control,
+            background_tasks: BackgroundTasks::default(),
+            input_queue: InputQueue::new(async_channel::bounded(1).0.downgrade()),
+            checkpoints: Checkpoints::default(),
+            subagent_merges: SubAgentMerges::default(),
+            redo_stack: RedoStack::default(),
+            turn_snapshots: TurnSnapshots::default(),
+            pinned_context: PinnedContext::default(),
+            runtime_state: RuntimeStateTracker::default(),
+            hooks: Hooks::default(),
+            context_providers: ContextProviders::default(),
+            mcp_resource_mentions: McpResourceMentions::default(),
+            session_tmp: None,
+            network_capture: None,
+            network_proxy: None,
+            external_edits: None,
+            governor: None,
+            audit_log: None,
+            worktree_isolation: None,
+            cost_tracker: CostTracker::new(config.budget.clone()),
+            tool_trimming: ToolTrimming::default(),
+            browser: BrowserSession::default(),
+            plugin_tools: Vec::new(),
         };
 
         let turn_context = Session::make_turn_context(
@@ -4239,12 +5898,14 @@ mod tests {
             conversation_id,
             tx_event,
             agent_status: agent_status_tx,
+            total_token_usage: watch::channel(None).0,
             state: Mutex::new(state),
             features: config.features.clone(),
             pending_mcp_server_refresh_config: Mutex::new(None),
             active_turn: Mutex::new(None),
             services,
             next_internal_sub_id: AtomicU64::new(0),
+            tx_sub: async_channel::bounded(1).0.downgrade(),
         };
 
         (session, turn_context)
@@ -4296,6 +5957,7 @@ mod tests {
             cwd: config.cwd.clone(),
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
+            plan_mode: false,
         };
         let per_turn_config = Session::build_per_turn_config(&session_configuration);
         let model_info = ModelsManager::construct_model_info_offline(
@@ -4325,8 +5987,35 @@ mod tests {
             otel_manager: otel_manager.clone(),
             models_manager: Arc::clone(&models_manager),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_journal: Mutex::new(ApprovalJournal::default()),
             skills_manager,
+            subagents_manager: Arc::new(SubAgentsManager::new(
+                config.codex_home.clone(),
+                config.subagent_extra_dirs.clone(),
+            )),
             agent_control,
+            background_tasks: BackgroundTasks::default(),
+            input_queue: InputQueue::new(async_channel::bounded(1).0.downgrade()),
+            checkpoints: Checkpoints::default(),
+            subagent_merges: SubAgentMerges::default(),
+            redo_stack: RedoStack::default(),
+            turn_snapshots: TurnSnapshots::default(),
+            pinned_context: PinnedContext::default(),
+            runtime_state: RuntimeStateTracker::default(),
+            hooks: Hooks::default(),
+            context_providers: ContextProviders::default(),
+            mcp_resource_mentions: McpResourceMentions::default(),
+            session_tmp: None,
+            network_capture: None,
+            network_proxy: None,
+            external_edits: None,
+            governor: None,
+            audit_log: None,
+            worktree_isolation: None,
+            cost_tracker: CostTracker::new(config.budget.clone()),
+            tool_trimming: ToolTrimming::default(),
+            browser: BrowserSession::default(),
+            plugin_tools: Vec::new(),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -4344,12 +6033,14 @@ mod tests {
             conversation_id,
             tx_event,
             agent_status: agent_status_tx,
+            total_token_usage: watch::channel(None).0,
             state: Mutex::new(state),
             features: config.features.clone(),
             pending_mcp_server_refresh_config: Mutex::new(None),
             active_turn: Mutex::new(None),
             services,
             next_internal_sub_id: AtomicU64::new(0),
+            tx_sub: async_channel::bounded(1).0.downgrade(),
         });
 
         (session, turn_context, rx_event)
@@ -4528,7 +6219,7 @@ mod tests {
             text: "start review".to_string(),
             text_elements: Vec::new(),
         }];
-        sess.spawn_task(Arc::clone(&tc), input, ReviewTask::new())
+        sess.spawn_task(Arc::clone(&tc), input, ReviewTask::default())
             .await;
 
         sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
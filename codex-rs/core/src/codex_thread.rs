@@ -4,6 +4,7 @@ use crate::error::Result as CodexResult;
 use crate::protocol::Event;
 use crate::protocol::Op;
 use crate::protocol::Submission;
+use crate::protocol::TokenUsage;
 use std::path::PathBuf;
 use tokio::sync::watch;
 
@@ -43,6 +44,11 @@ impl CodexThread {
         self.codex.agent_status.clone()
     }
 
+    /// Token totals from the thread's latest token count.
+    pub(crate) fn total_token_usage(&self) -> Option<TokenUsage> {
+        self.codex.total_token_usage()
+    }
+
     pub fn rollout_path(&self) -> PathBuf {
         self.rollout_path.clone()
     }
@@ -0,0 +1,402 @@
+//! One-line, plain-language explanations of commands awaiting approval.
+//!
+//! Explanations are built offline from the parsed command, so they add no
+//! latency to the approval prompt. They describe the common commands a
+//! non-expert user is likely to be asked about (file operations, git,
+//! package managers, build tools) and name the paths each one touches;
+//! anything else falls back to "run <program>". Enabled with
+//! `Feature::ApprovalExplanations`.
+
+use std::path::Path;
+
+use codex_protocol::parse_command::ParsedCommand;
+
+/// Paths listed per command before the rest are summarized as "N more".
+const MAX_LISTED_PATHS: usize = 3;
+
+/// Keeps the explanation to a single line in the approval prompt.
+const MAX_EXPLANATION_CHARS: usize = 240;
+
+/// Explains the commands in `parsed`, in the order they run.
+pub(crate) fn explain_command(parsed: &[ParsedCommand]) -> Option<String> {
+    let parts: Vec<String> = parsed.iter().map(explain_parsed).collect();
+    if parts.is_empty() {
+        return None;
+    }
+    let text = parts.join(", then ");
+    let mut chars = text.chars();
+    let mut explanation: String = chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default();
+    if explanation.chars().count() > MAX_EXPLANATION_CHARS {
+        explanation = explanation
+            .chars()
+            .take(MAX_EXPLANATION_CHARS - 1)
+            .collect();
+        explanation.push('…');
+    } else {
+        explanation.push('.');
+    }
+    Some(explanation)
+}
+
+fn explain_parsed(parsed: &ParsedCommand) -> String {
+    match parsed {
+        ParsedCommand::Read { path, .. } => format!("read {}", path.display()),
+        ParsedCommand::ListFiles { path, .. } => format!(
+            "list the files in {}",
+            path.as_deref().unwrap_or("the current directory")
+        ),
+        ParsedCommand::Search { query, path, .. } => match (query, path) {
+            (Some(query), Some(path)) => format!("search {path} for \"{query}\""),
+            (Some(query), None) => format!("search the files for \"{query}\""),
+            (None, Some(path)) => format!("search {path}"),
+            (None, None) => "search the files".to_string(),
+        },
+        ParsedCommand::Unknown { cmd } => explain_unknown(cmd),
+    }
+}
+
+fn explain_unknown(cmd: &str) -> String {
+    let Some(tokens) = shlex::split(cmd) else {
+        return format!("run `{cmd}`");
+    };
+    let (tokens, writes) = split_redirections(tokens);
+    let (as_root, tokens) = match tokens.split_first() {
+        Some((first, rest)) if first == "sudo" => (true, rest.to_vec()),
+        _ => (false, tokens),
+    };
+    let mut text = explain_program(&tokens);
+    if !writes.is_empty() {
+        let writes: Vec<&str> = writes.iter().map(String::as_str).collect();
+        text.push_str(&format!(" and write the output to {}", list_paths(&writes)));
+    }
+    if as_root {
+        text.push_str(" with administrator (root) privileges");
+    }
+    text
+}
+
+/// Separates `> file` / `>> file` redirections from the command words.
+fn split_redirections(tokens: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut words = Vec::new();
+    let mut writes = Vec::new();
+    let mut tokens = tokens.into_iter();
+    while let Some(token) = tokens.next() {
+        let target = token
+            .strip_prefix(">>")
+            .or_else(|| token.strip_prefix('>'))
+            .or_else(|| token.strip_prefix("1>"))
+            .or_else(|| token.strip_prefix("2>"));
+        match target {
+            Some("") => {
+                if let Some(path) = tokens.next() {
+                    writes.push(path);
+                }
+            }
+            // `2>&1` and friends duplicate a descriptor instead of writing a file.
+            Some(target) if target.starts_with('&') => {}
+            Some(target) => writes.push(target.to_string()),
+            None => words.push(token),
+        }
+    }
+    writes.retain(|path| path != "/dev/null");
+    (words, writes)
+}
+
+fn explain_program(tokens: &[String]) -> String {
+    let Some((program, args)) = tokens.split_first() else {
+        return "run an empty command".to_string();
+    };
+    let name = Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(program);
+    let operands = operands(args);
+    let recursive = has_short_flag(args, 'r')
+        || has_short_flag(args, 'R')
+        || args.iter().any(|arg| arg == "--recursive");
+
+    match name {
+        "rm" => {
+            let what = if recursive {
+                "recursively delete"
+            } else {
+                "delete"
+            };
+            format!("{what} {}", list_paths(&operands))
+        }
+        "rmdir" => format!("delete the empty directory {}", list_paths(&operands)),
+        "mv" => transfer("move", &operands),
+        "cp" => transfer("copy", &operands),
+        "mkdir" => format!("create the directory {}", list_paths(&operands)),
+        "touch" => format!("create {} or update its timestamp", list_paths(&operands)),
+        "chmod" => format!(
+            "change the permissions of {}",
+            list_paths(operands.get(1..).unwrap_or_default())
+        ),
+        "chown" => format!(
+            "change the owner of {}",
+            list_paths(operands.get(1..).unwrap_or_default())
+        ),
+        "ln" => match operands.as_slice() {
+            [target, link] => format!("create a link {link} pointing to {target}"),
+            _ => "create a link".to_string(),
+        },
+        "cd" => format!(
+            "change into {}",
+            operands.first().copied().unwrap_or("the home directory")
+        ),
+        "echo" | "printf" => "print text".to_string(),
+        "sed" if args.iter().any(|arg| arg.starts_with("-i")) => format!(
+            "edit {} in place",
+            list_paths(operands.get(1..).unwrap_or_default())
+        ),
+        "curl" | "wget" => match operands.iter().find(|op| op.contains("://")) {
+            Some(url) => format!("make a network request to {url}"),
+            None => "make a network request".to_string(),
+        },
+        "git" => explain_git(args),
+        "npm" | "pnpm" | "yarn" | "bun" | "pip" | "pip3" | "uv" | "poetry" => {
+            explain_package_manager(name, &operands)
+        }
+        "cargo" | "go" => explain_build_tool(name, &operands),
+        "make" | "just" => match operands.first() {
+            Some(target) => format!("run the {name} target {target}"),
+            None => format!("run the default {name} target"),
+        },
+        "python" | "python3" | "node" | "bash" | "sh" | "zsh" | "ruby" | "perl" => {
+            if args.iter().any(|arg| arg == "-c" || arg == "-e") {
+                format!("run inline {name} code")
+            } else {
+                match operands.first() {
+                    Some(script) => format!("run the script {script} with {name}"),
+                    None => format!("start {name}"),
+                }
+            }
+        }
+        "kill" | "pkill" | "killall" => "stop running processes".to_string(),
+        "tar" | "zip" | "unzip" => format!("create or extract an archive with {name}"),
+        "docker" | "podman" | "kubectl" => match operands.first() {
+            Some(sub) => format!("run `{name} {sub}`"),
+            None => format!("run {name}"),
+        },
+        "for" | "while" | "if" | "case" => "run a shell script".to_string(),
+        _ => {
+            let paths: Vec<&str> = operands
+                .iter()
+                .copied()
+                .filter(|op| op.contains('/') || op.contains('.'))
+                .collect();
+            if paths.is_empty() {
+                format!("run {name}")
+            } else {
+                format!("run {name} on {}", list_paths(&paths))
+            }
+        }
+    }
+}
+
+fn explain_git(args: &[String]) -> String {
+    // Skip global options such as `-C <dir>` and `-c key=value`.
+    let mut idx = 0;
+    while let Some(arg) = args.get(idx) {
+        match arg.as_str() {
+            "-C" | "-c" => idx += 2,
+            arg if arg.starts_with('-') => idx += 1,
+            _ => break,
+        }
+    }
+    let Some((sub, rest)) = args.get(idx..).and_then(<[String]>::split_first) else {
+        return "run git".to_string();
+    };
+    let operands = operands(rest);
+    let has = |flag: &str| rest.iter().any(|arg| arg == flag);
+
+    match sub.as_str() {
+        "status" => "show which files git sees as changed".to_string(),
+        "diff" | "log" | "show" | "blame" => format!("show git {sub} output"),
+        "add" => format!("stage {} for the next commit", list_paths(&operands)),
+        "commit" => "create a git commit".to_string(),
+        "push" if has("--force") || has("-f") || has("--force-with-lease") => {
+            "force-push commits to a remote, which can overwrite history there".to_string()
+        }
+        "push" => "upload commits to a remote".to_string(),
+        "pull" => "download and merge changes from a remote".to_string(),
+        "fetch" => "download changes from a remote".to_string(),
+        "clone" => "download a copy of a repository".to_string(),
+        "checkout" | "switch" => match operands.first() {
+            Some(target) => format!("switch to {target} or restore files from it"),
+            None => "switch branches".to_string(),
+        },
+        "restore" => format!("discard changes to {}", list_paths(&operands)),
+        "reset" if has("--hard") => "discard all uncommitted changes".to_string(),
+        "reset" => "unstage changes".to_string(),
+        "clean" => "delete untracked files".to_string(),
+        "stash" => "set uncommitted changes aside".to_string(),
+        "rebase" => "rewrite the current branch on top of another".to_string(),
+        "merge" => match operands.first() {
+            Some(branch) => format!("merge {branch} into the current branch"),
+            None => "merge into the current branch".to_string(),
+        },
+        "branch" if has("-d") || has("-D") || has("--delete") => {
+            format!("delete the branch {}", list_paths(&operands))
+        }
+        "branch" => "list or create branches".to_string(),
+        "rm" => format!("delete {} and stage the removal", list_paths(&operands)),
+        "mv" => transfer("move", &operands),
+        sub => format!("run git {sub}"),
+    }
+}
+
+fn explain_package_manager(name: &str, operands: &[&str]) -> String {
+    let packages = |rest: &[&str]| {
+        if rest.is_empty() {
+            "install the project's dependencies".to_string()
+        } else {
+            format!("install the packages {}", list_paths(rest))
+        }
+    };
+    match operands.split_first() {
+        None if matches!(name, "yarn" | "pnpm" | "bun") => packages(&[]),
+        None => format!("run {name}"),
+        Some((&("install" | "i" | "add" | "ci" | "sync"), rest)) => packages(rest),
+        Some((&("uninstall" | "remove" | "rm"), rest)) => {
+            format!("remove the packages {}", list_paths(rest))
+        }
+        Some((&("test" | "t"), _)) => "run the tests".to_string(),
+        Some((&"build", _)) => "build the project".to_string(),
+        Some((&("run" | "exec" | "x" | "dlx"), rest)) => match rest.first() {
+            Some(script) => format!("run the {name} script {script}"),
+            None => format!("run a {name} script"),
+        },
+        Some((sub, _)) => format!("run `{name} {sub}`"),
+    }
+}
+
+fn explain_build_tool(name: &str, operands: &[&str]) -> String {
+    match operands.split_first() {
+        None => format!("run {name}"),
+        Some((&"build", _)) => "build the project".to_string(),
+        Some((&("check" | "vet"), _)) => "check the project for errors".to_string(),
+        Some((&("test" | "nextest"), _)) => "run the tests".to_string(),
+        Some((&"run", _)) => "build and run the project".to_string(),
+        Some((&"clippy", _)) => "lint the project".to_string(),
+        Some((&"fmt", _)) => "format the code".to_string(),
+        Some((&("add" | "get"), rest)) => format!("add the dependencies {}", list_paths(rest)),
+        Some((&"install", rest)) => format!("install {}", list_paths(rest)),
+        Some((&"clean", _)) => "delete build artifacts".to_string(),
+        Some((&"publish", _)) => "publish the package to a registry".to_string(),
+        Some((sub, _)) => format!("run `{name} {sub}`"),
+    }
+}
+
+/// `move a and b to dir`.
+fn transfer(verb: &str, operands: &[&str]) -> String {
+    match operands.split_last() {
+        Some((dest, sources)) if !sources.is_empty() => {
+            format!("{verb} {} to {dest}", list_paths(sources))
+        }
+        _ => format!("{verb} files"),
+    }
+}
+
+fn operands(args: &[String]) -> Vec<&str> {
+    args.iter()
+        .map(String::as_str)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect()
+}
+
+fn has_short_flag(args: &[String], flag: char) -> bool {
+    args.iter().any(|arg| {
+        arg.strip_prefix('-')
+            .is_some_and(|flags| !flags.starts_with('-') && flags.contains(flag))
+    })
+}
+
+fn list_paths(paths: &[&str]) -> String {
+    match paths {
+        [] => "files".to_string(),
+        [only] => (*only).to_string(),
+        _ if paths.len() > MAX_LISTED_PATHS => format!(
+            "{} and {} more",
+            paths[..MAX_LISTED_PATHS].join(", "),
+            paths.len() - MAX_LISTED_PATHS
+        ),
+        [init @ .., last] => format!("{} and {last}", init.join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_command::parse_command;
+    use pretty_assertions::assert_eq;
+
+    fn explain(script: &str) -> Option<String> {
+        explain_command(&parse_command(&[
+            "bash".to_string(),
+            "-lc".to_string(),
+            script.to_string(),
+        ]))
+    }
+
+    #[test]
+    fn explains_file_operations_and_their_paths() {
+        assert_eq!(
+            explain("rm -rf build dist").as_deref(),
+            Some("Recursively delete build and dist.")
+        );
+        assert_eq!(
+            explain("mv a.txt b.txt docs/").as_deref(),
+            Some("Move a.txt and b.txt to docs/.")
+        );
+        assert_eq!(
+            explain("sudo chmod +x scripts/install.sh").as_deref(),
+            Some(
+                "Change the permissions of scripts/install.sh with administrator (root) privileges."
+            )
+        );
+        assert_eq!(
+            explain("echo done > out.log 2>&1").as_deref(),
+            Some("Print text and write the output to out.log.")
+        );
+    }
+
+    #[test]
+    fn explains_git_and_package_managers() {
+        assert_eq!(
+            explain("git -C repo push --force origin main").as_deref(),
+            Some("Force-push commits to a remote, which can overwrite history there.")
+        );
+        assert_eq!(
+            explain("git reset --hard HEAD~1").as_deref(),
+            Some("Discard all uncommitted changes.")
+        );
+        assert_eq!(
+            explain("npm install lodash").as_deref(),
+            Some("Install the packages lodash.")
+        );
+        assert_eq!(
+            explain("cargo test -p codex-core").as_deref(),
+            Some("Run the tests.")
+        );
+    }
+
+    #[test]
+    fn joins_sequences_and_falls_back_to_the_program_name() {
+        assert_eq!(
+            explain("mkdir -p out && ./gen.sh config.yaml").as_deref(),
+            Some("Create the directory out, then run gen.sh on config.yaml.")
+        );
+        assert_eq!(explain_command(&[]), None);
+    }
+
+    #[test]
+    fn list_paths_summarizes_long_lists() {
+        assert_eq!(list_paths(&["a", "b", "c"]), "a, b and c");
+        assert_eq!(list_paths(&["a", "b", "c", "d", "e"]), "a, b, c and 2 more");
+    }
+}
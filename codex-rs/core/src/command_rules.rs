@@ -0,0 +1,344 @@
+//! Declarative allow/deny rules for shell commands (`[[command_rules]]`).
+//!
+//! Rules come from the `command_rules` array of every config layer and from
+//! the `policies.toml` next to each layer's config.toml (so
+//! `$CODEX_HOME/policies.toml` and, for trusted projects,
+//! `<repo>/.codex/policies.toml`). A rule matches a command by program,
+//! argument patterns and the directory it runs in, and either approves it,
+//! asks the user, or refuses it. When several rules match, the most
+//! restrictive one wins, so a project cannot loosen a rule from the user's
+//! config. The exec policy consults these rules for commands that no `.rules`
+//! prefix rule matched, before falling back to the approval policy.
+//!
+//! The same files also hold the `[git_guardrails]` section, which is loaded
+//! here and evaluated by [`GitGuardrails`].
+
+use std::ffi::OsStr;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_app_server_protocol::ConfigLayerSource;
+use codex_protocol::protocol::CommandRuleDecision;
+use codex_protocol::protocol::CommandRuleMatch;
+use tokio::fs;
+use tracing::warn;
+use wildmatch::WildMatch;
+
+use crate::bash::extract_bash_command;
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::config::types::CommandRuleToml;
+use crate::config::types::GitGuardrailsToml;
+use crate::config::types::PoliciesToml;
+use crate::config_loader::ConfigLayerEntry;
+use crate::config_loader::ConfigLayerStack;
+use crate::config_loader::ConfigLayerStackOrdering;
+use crate::git_guardrails::GitGuardrails;
+use crate::guardrail::simple_commands;
+
+const POLICIES_FILE: &str = "policies.toml";
+const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Default)]
+pub(crate) struct CommandRules {
+    rules: Vec<CommandRule>,
+    git_guardrails: GitGuardrails,
+}
+
+struct CommandRule {
+    label: String,
+    program: WildMatch,
+    /// Also match the pattern against the program's file name.
+    match_file_name: bool,
+    args: Vec<WildMatch>,
+    any_arg: Vec<WildMatch>,
+    paths: Vec<PathBuf>,
+    decision: CommandRuleDecision,
+    reason: Option<String>,
+    source: PathBuf,
+}
+
+impl CommandRules {
+    /// Reads the rules of every layer that has a config folder, lowest
+    /// precedence first. Files that fail to parse are skipped with a warning.
+    pub(crate) async fn load(config_stack: &ConfigLayerStack) -> Self {
+        let mut rules = Self::default();
+        for layer in config_stack.get_layers(ConfigLayerStackOrdering::LowestPrecedenceFirst) {
+            let Some(folder) = layer.config_folder() else {
+                continue;
+            };
+            let folder = folder.into_path_buf();
+            let base = folder.parent().unwrap_or(&folder).to_path_buf();
+
+            if let Some(value) = layer.config.get("command_rules") {
+                let source = layer_config_file(layer, &folder);
+                match value.clone().try_into::<Vec<CommandRuleToml>>() {
+                    Ok(entries) => rules.extend(entries, &base, &source),
+                    Err(err) => warn!("ignoring command_rules in {}: {err}", source.display()),
+                }
+            }
+            if let Some(value) = layer.config.get("git_guardrails") {
+                let source = layer_config_file(layer, &folder);
+                match value.clone().try_into::<GitGuardrailsToml>() {
+                    Ok(section) => rules.git_guardrails.extend(section, &source),
+                    Err(err) => warn!("ignoring git_guardrails in {}: {err}", source.display()),
+                }
+            }
+
+            let policies_path = folder.join(POLICIES_FILE);
+            match fs::read_to_string(&policies_path).await {
+                Ok(contents) => match toml::from_str::<PoliciesToml>(&contents) {
+                    Ok(policies) => {
+                        rules.extend(policies.command_rules, &base, &policies_path);
+                        if let Some(section) = policies.git_guardrails {
+                            rules.git_guardrails.extend(section, &policies_path);
+                        }
+                    }
+                    Err(err) => warn!("ignoring {}: {err}", policies_path.display()),
+                },
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => warn!("failed to read {}: {err}", policies_path.display()),
+            }
+        }
+        rules
+    }
+
+    #[cfg(test)]
+    pub(crate) fn from_toml(entries: Vec<CommandRuleToml>, base: &Path, source: &Path) -> Self {
+        let mut rules = Self::default();
+        rules.extend(entries, base, source);
+        rules
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_git_guardrails(mut self, section: GitGuardrailsToml, source: &Path) -> Self {
+        self.git_guardrails.extend(section, source);
+        self
+    }
+
+    fn extend(&mut self, entries: Vec<CommandRuleToml>, base: &Path, source: &Path) {
+        self.rules.extend(
+            entries
+                .into_iter()
+                .map(|entry| CommandRule::new(entry, base, source)),
+        );
+    }
+
+    /// The most restrictive rule matching `command` (a single command, not a
+    /// shell script) run in `cwd`. Among equally restrictive rules, the one
+    /// from the higher-precedence layer wins.
+    pub(crate) fn evaluate(&self, command: &[String], cwd: &Path) -> Option<CommandRuleMatch> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.matches(command, cwd))
+            .max_by_key(|rule| rule.decision)
+            .map(CommandRule::to_match)
+    }
+
+    /// The strictest `[git_guardrails]` check `command` trips in `cwd`. Unlike
+    /// [`Self::evaluate`], `command` may be a whole shell script.
+    pub(crate) fn git_guardrail(&self, command: &[String], cwd: &Path) -> Option<CommandRuleMatch> {
+        self.git_guardrails.evaluate(command, cwd)
+    }
+
+    /// For a shell script that could not be split into plain commands, so
+    /// that [`Self::evaluate`] only sees the shell itself: the first
+    /// `auto_deny` rule whose program appears anywhere in the script. The
+    /// script may not actually run it, so the match asks rather than refuses.
+    pub(crate) fn denied_program_in_script(
+        &self,
+        command: &[String],
+        cwd: &Path,
+    ) -> Option<CommandRuleMatch> {
+        if extract_bash_command(command).is_none()
+            || parse_shell_lc_plain_commands(command).is_some()
+        {
+            return None;
+        }
+        let words: Vec<String> = simple_commands(command).into_iter().flatten().collect();
+        let words: Vec<&str> = words
+            .iter()
+            .flat_map(|word| word.split_whitespace())
+            .collect();
+        self.rules
+            .iter()
+            .filter(|rule| rule.decision == CommandRuleDecision::AutoDeny && rule.applies_in(cwd))
+            .rev()
+            .find_map(|rule| {
+                let program = words.iter().find(|word| rule.matches_program(word))?;
+                Some(CommandRuleMatch {
+                    decision: CommandRuleDecision::AlwaysAsk,
+                    reason: Some(format!(
+                        "the script could not be checked command by command and mentions \
+                         `{program}`, which this rule rejects"
+                    )),
+                    ..rule.to_match()
+                })
+            })
+    }
+}
+
+impl CommandRule {
+    fn new(entry: CommandRuleToml, base: &Path, source: &Path) -> Self {
+        let label = entry.name.unwrap_or_else(|| {
+            std::iter::once(entry.program.as_str())
+                .chain(entry.args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        Self {
+            label,
+            match_file_name: !entry.program.contains('/'),
+            program: WildMatch::new(&entry.program),
+            args: entry.args.iter().map(|arg| WildMatch::new(arg)).collect(),
+            any_arg: entry
+                .any_arg
+                .iter()
+                .map(|arg| WildMatch::new(arg))
+                .collect(),
+            paths: entry.paths.iter().map(|path| base.join(path)).collect(),
+            decision: entry.decision,
+            reason: entry.reason,
+            source: source.to_path_buf(),
+        }
+    }
+
+    fn matches(&self, command: &[String], cwd: &Path) -> bool {
+        let Some((program, args)) = command.split_first() else {
+            return false;
+        };
+        self.matches_program(program)
+            && args.len() >= self.args.len()
+            && self
+                .args
+                .iter()
+                .zip(args)
+                .all(|(pattern, arg)| pattern.matches(arg))
+            && (self.any_arg.is_empty()
+                || args
+                    .iter()
+                    .any(|arg| self.any_arg.iter().any(|pattern| pattern.matches(arg))))
+            && self.applies_in(cwd)
+    }
+
+    fn matches_program(&self, program: &str) -> bool {
+        self.program.matches(program)
+            || (self.match_file_name
+                && Path::new(program)
+                    .file_name()
+                    .and_then(OsStr::to_str)
+                    .is_some_and(|name| self.program.matches(name)))
+    }
+
+    fn applies_in(&self, cwd: &Path) -> bool {
+        self.paths.is_empty() || self.paths.iter().any(|dir| cwd.starts_with(dir))
+    }
+
+    fn to_match(&self) -> CommandRuleMatch {
+        CommandRuleMatch {
+            rule: self.label.clone(),
+            decision: self.decision,
+            source: self.source.clone(),
+            reason: self.reason.clone(),
+        }
+    }
+}
+
+fn layer_config_file(layer: &ConfigLayerEntry, folder: &Path) -> PathBuf {
+    match &layer.name {
+        ConfigLayerSource::System { file } | ConfigLayerSource::User { file } => file.to_path_buf(),
+        _ => folder.join(CONFIG_FILE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn rules(src: &str) -> CommandRules {
+        let policies: PoliciesToml = toml::from_str(src).expect("parse policies");
+        CommandRules::from_toml(
+            policies.command_rules,
+            Path::new("/repo"),
+            Path::new("/repo/.codex/policies.toml"),
+        )
+    }
+
+    fn cmd(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn matches_program_args_and_paths() {
+        let rules = rules(
+            r#"
+[[command_rules]]
+program = "git"
+args = ["push"]
+any_arg = ["--force", "-f"]
+decision = "auto_deny"
+
+[[command_rules]]
+name = "tests"
+program = "cargo"
+args = ["test"]
+paths = ["crates"]
+decision = "auto_approve"
+"#,
+        );
+        let cwd = Path::new("/repo/crates/core");
+
+        assert_eq!(
+            rules
+                .evaluate(&cmd(&["/usr/bin/git", "push", "origin", "-f"]), cwd)
+                .map(|m| (m.rule, m.decision)),
+            Some(("git push".to_string(), CommandRuleDecision::AutoDeny))
+        );
+        assert_eq!(rules.evaluate(&cmd(&["git", "push", "origin"]), cwd), None);
+        assert_eq!(
+            rules
+                .evaluate(&cmd(&["cargo", "test", "-p", "core"]), cwd)
+                .map(|m| m.rule),
+            Some("tests".to_string())
+        );
+        assert_eq!(
+            rules.evaluate(&cmd(&["cargo", "test"]), Path::new("/repo/docs")),
+            None
+        );
+    }
+
+    #[test]
+    fn most_restrictive_match_wins() {
+        let rules = rules(
+            r#"
+[[command_rules]]
+program = "rm"
+decision = "auto_approve"
+
+[[command_rules]]
+name = "no recursive rm"
+program = "rm"
+args = ["-r*"]
+decision = "always_ask"
+reason = "deletes directories"
+"#,
+        );
+
+        assert_eq!(
+            rules.evaluate(&cmd(&["rm", "-rf", "target"]), Path::new("/repo")),
+            Some(CommandRuleMatch {
+                rule: "no recursive rm".to_string(),
+                decision: CommandRuleDecision::AlwaysAsk,
+                source: PathBuf::from("/repo/.codex/policies.toml"),
+                reason: Some("deletes directories".to_string()),
+            })
+        );
+        assert_eq!(
+            rules
+                .evaluate(&cmd(&["rm", "notes.txt"]), Path::new("/repo"))
+                .map(|m| m.decision),
+            Some(CommandRuleDecision::AutoApprove)
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use crate::command_safety::is_safe_command::is_known_safe_command;
+
+/// Prefixes that only read the workspace (or write build caches that the
+/// sandbox keeps confined) but are not on the known-safe list.
+const BUILTIN_READ_ONLY_PREFIXES: &[&[&str]] = &[
+    &["cargo", "check"],
+    &["cargo", "metadata"],
+    &["cargo", "tree"],
+];
+
+/// Classifies a single, already-split command for the `read_only_auto_approve`
+/// feature: known-safe commands, the built-in read-only prefixes, and any
+/// prefix from the `read_only_commands` config list.
+pub(crate) fn is_read_only_command(command: &[String], extra_prefixes: &[Vec<String>]) -> bool {
+    if is_known_safe_command(command) {
+        return true;
+    }
+    BUILTIN_READ_ONLY_PREFIXES
+        .iter()
+        .any(|prefix| has_prefix(command, prefix))
+        || extra_prefixes
+            .iter()
+            .any(|prefix| has_prefix(command, prefix))
+}
+
+fn has_prefix<S: AsRef<str>>(command: &[String], prefix: &[S]) -> bool {
+    !prefix.is_empty()
+        && command.len() >= prefix.len()
+        && command
+            .iter()
+            .zip(prefix)
+            .all(|(arg, expected)| arg == expected.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn argv(command: &str) -> Vec<String> {
+        shlex::split(command).expect("valid command")
+    }
+
+    #[test]
+    fn classifies_known_safe_builtin_and_configured_prefixes() {
+        let extra = vec![argv("just --list")];
+        let classified: Vec<(&str, bool)> = [
+            "git status",
+            "cargo check --all-targets",
+            "just --list",
+            "cargo build",
+            "just deploy",
+            "rm -rf target",
+        ]
+        .into_iter()
+        .map(|command| (command, is_read_only_command(&argv(command), &extra)))
+        .collect();
+
+        assert_eq!(
+            classified,
+            vec![
+                ("git status", true),
+                ("cargo check --all-targets", true),
+                ("just --list", true),
+                ("cargo build", false),
+                ("just deploy", false),
+                ("rm -rf target", false),
+            ]
+        );
+    }
+}
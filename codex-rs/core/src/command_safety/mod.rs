@@ -1,3 +1,4 @@
 pub mod is_dangerous_command;
+pub(crate) mod is_read_only_command;
 pub mod is_safe_command;
 pub mod windows_safe_commands;
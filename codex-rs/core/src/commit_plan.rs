@@ -0,0 +1,478 @@
+//! Splitting the working tree into several commits for `codex commit --all`
+//! and `codex commit --split`.
+//!
+//! Every change since `HEAD`, untracked files included, is cut into
+//! [`ChangeUnit`]s: one per file, or one per hunk when splitting. A read-only
+//! headless `codex exec` run groups the units into a [`CommitPlan`] and writes
+//! a message for each group. Each group is then staged on its own with
+//! `git apply --cached` ([`stage_units`]) and committed, so the working tree
+//! itself is never touched.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use std::process::Stdio;
+
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::config::types::CommitConvention;
+use crate::pre_commit::CommitMessage;
+use crate::pre_commit::MAX_REVIEW_DIFF_BYTES;
+use crate::pre_commit::PreCommitError;
+use crate::pre_commit::commit_message_schema;
+use crate::pre_commit::git;
+use crate::pre_commit::message_guidelines;
+
+/// A piece of the working tree diff that is committed as a whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeUnit {
+    /// Number the model refers to the unit by, starting at 1.
+    pub id: usize,
+    pub path: String,
+    /// The file's `diff --git` header, up to its first hunk.
+    header: String,
+    /// The unit's hunks, or the whole binary patch.
+    body: String,
+}
+
+impl ChangeUnit {
+    /// The unit as a patch that `git apply` accepts on its own.
+    pub fn patch(&self) -> String {
+        format!("{}{}", self.header, self.body)
+    }
+
+    /// The `@@ ... @@` line of the unit's first hunk.
+    pub fn hunk_header(&self) -> Option<&str> {
+        let line = self.body.lines().next()?;
+        line.starts_with("@@ ").then_some(line)
+    }
+
+    fn is_binary(&self) -> bool {
+        self.header.lines().any(|line| line == "GIT binary patch")
+    }
+}
+
+/// Final answer of the commit plan run, shaped by [`commit_plan_schema`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CommitPlan {
+    pub commits: Vec<PlannedCommit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PlannedCommit {
+    /// Ids of the [`ChangeUnit`]s in this commit.
+    pub changes: Vec<usize>,
+    pub message: CommitMessage,
+}
+
+impl CommitPlan {
+    /// Drops unknown and repeated unit ids (a unit stays in the first commit
+    /// that lists it) and the commits left empty, and returns the ids of the
+    /// units that no commit lists.
+    pub fn normalize(&mut self, units: &[ChangeUnit]) -> Vec<usize> {
+        let known: HashSet<usize> = units.iter().map(|unit| unit.id).collect();
+        let mut seen = HashSet::new();
+        for commit in &mut self.commits {
+            commit
+                .changes
+                .retain(|id| known.contains(id) && seen.insert(*id));
+            commit.changes.sort_unstable();
+        }
+        self.commits.retain(|commit| !commit.changes.is_empty());
+        units
+            .iter()
+            .map(|unit| unit.id)
+            .filter(|id| !seen.contains(id))
+            .collect()
+    }
+}
+
+/// Changes of the repository at `root` since `HEAD`, staged or not, followed
+/// by a new-file diff for every untracked file that is not ignored.
+pub fn working_tree_diff(root: &Path) -> Result<String, PreCommitError> {
+    let mut diff = git(
+        root,
+        &[
+            "diff",
+            "HEAD",
+            "--binary",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-renames",
+        ],
+    )?;
+    let untracked = git(root, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+    for path in untracked.split('\0').filter(|path| !path.is_empty()) {
+        let output = Command::new("git")
+            .args([
+                "diff",
+                "--no-index",
+                "--binary",
+                "--no-color",
+                "--no-ext-diff",
+                "--",
+                "/dev/null",
+                path,
+            ])
+            .current_dir(root)
+            .output()?;
+        // `--no-index` exits with 1 when the files differ, which they do.
+        if output.status.code() != Some(1) {
+            return Err(PreCommitError::Git(format!(
+                "git diff --no-index failed for {path}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        diff.push_str(&String::from_utf8_lossy(&output.stdout));
+    }
+    Ok(diff)
+}
+
+/// Cuts `diff` into units: one per file, or one per hunk with `split`.
+/// Binary patches and changes without hunks (such as a mode change) always
+/// stay whole.
+pub fn change_units(diff: &str, split: bool) -> Vec<ChangeUnit> {
+    let mut units = Vec::new();
+    for section in file_sections(diff) {
+        let mut header = String::new();
+        let mut hunks: Vec<String> = Vec::new();
+        for line in section.split_inclusive('\n') {
+            if line.starts_with("@@ ") {
+                hunks.push(String::new());
+            }
+            match hunks.last_mut() {
+                Some(hunk) => hunk.push_str(line),
+                None => header.push_str(line),
+            }
+        }
+        let path = section_path(&header);
+        if split && !hunks.is_empty() {
+            for hunk in hunks {
+                units.push(ChangeUnit {
+                    id: units.len() + 1,
+                    path: path.clone(),
+                    header: header.clone(),
+                    body: hunk,
+                });
+            }
+        } else {
+            units.push(ChangeUnit {
+                id: units.len() + 1,
+                path,
+                header,
+                body: hunks.concat(),
+            });
+        }
+    }
+    units
+}
+
+fn file_sections(diff: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = Vec::new();
+    let mut offset = 0;
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff --git ") {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, start)| &diff[*start..starts.get(i + 1).copied().unwrap_or(diff.len())])
+        .collect()
+}
+
+/// Path of a file section, from `+++ b/...` or, for deletions and changes
+/// without hunks, from the `diff --git` line.
+fn section_path(header: &str) -> String {
+    let mut old = None;
+    for line in header.lines() {
+        if let Some(path) = line.strip_prefix("+++ b/") {
+            return path.to_string();
+        }
+        if let Some(path) = line.strip_prefix("--- a/") {
+            old = Some(path);
+        }
+    }
+    if let Some(path) = old {
+        return path.to_string();
+    }
+    let first = header.lines().next().unwrap_or_default();
+    first
+        .rsplit_once(" b/")
+        .map(|(_, path)| path.to_string())
+        .unwrap_or_default()
+}
+
+/// Prompt for the commit plan run over `units`. Once the diffs reach
+/// [`MAX_REVIEW_DIFF_BYTES`], the remaining units are listed by path only.
+pub fn commit_plan_prompt(
+    units: &[ChangeUnit],
+    convention: CommitConvention,
+    instructions: Option<&str>,
+) -> String {
+    let mut prompt = format!(
+        "Group the working tree changes below into commits, one per logical change, in the \
+order they should be committed. Each change has a number; list the numbers that belong to a \
+commit in its `changes` and use every number exactly once. A change that another change needs \
+goes in the same commit or an earlier one. Keep unrelated changes apart, but do not split up a \
+change that only makes sense as a whole. Then write each commit's `message`. {} Do not modify \
+any files.",
+        message_guidelines(convention)
+    );
+    if let Some(instructions) = instructions.map(str::trim).filter(|text| !text.is_empty()) {
+        prompt.push_str("\n\n");
+        prompt.push_str(instructions);
+    }
+    let mut budget = MAX_REVIEW_DIFF_BYTES;
+    for unit in units {
+        prompt.push_str(&format!("\n\n### Change {}: {}\n", unit.id, unit.path));
+        let patch = if unit.is_binary() {
+            format!("{}[binary content]\n", unit.header)
+        } else {
+            unit.patch()
+        };
+        if patch.len() > budget {
+            budget = 0;
+            prompt.push_str("[diff omitted]\n");
+            continue;
+        }
+        budget -= patch.len();
+        prompt.push_str("```diff\n");
+        prompt.push_str(&patch);
+        prompt.push_str("```\n");
+    }
+    prompt
+}
+
+/// JSON Schema passed to `codex exec --output-schema` for the commit plan
+/// run.
+pub fn commit_plan_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "commits": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "changes": { "type": "array", "items": { "type": "integer" } },
+                        "message": commit_message_schema()
+                    },
+                    "required": ["changes", "message"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["commits"],
+        "additionalProperties": false
+    })
+}
+
+/// Resets the index of the repository at `root` to `HEAD`, keeping the
+/// working tree as it is.
+pub fn unstage_all(root: &Path) -> Result<(), PreCommitError> {
+    git(root, &["reset", "--quiet"]).map(|_| ())
+}
+
+/// Stages the units listed in `ids`, in diff order, without touching the
+/// working tree.
+pub fn stage_units(root: &Path, units: &[ChangeUnit], ids: &[usize]) -> Result<(), PreCommitError> {
+    let mut patch = String::new();
+    let mut header: Option<&str> = None;
+    for unit in units.iter().filter(|unit| ids.contains(&unit.id)) {
+        // Hunks of the same file share one header.
+        if header != Some(unit.header.as_str()) {
+            patch.push_str(&unit.header);
+            header = Some(unit.header.as_str());
+        }
+        patch.push_str(&unit.body);
+    }
+    let mut child = Command::new("git")
+        .args(["apply", "--cached", "--whitespace=nowarn", "-"])
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(patch.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(PreCommitError::Git(format!(
+            "git apply failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Commits the staged changes with `message` and returns the new commit's
+/// abbreviated id.
+pub fn commit_staged(root: &Path, message: &str) -> Result<String, PreCommitError> {
+    git(root, &["commit", "--quiet", "--message", message])?;
+    Ok(git(root, &["rev-parse", "--short", "HEAD"])?
+        .trim()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn message(subject: &str) -> CommitMessage {
+        CommitMessage {
+            kind: "fix".to_string(),
+            scope: None,
+            subject: subject.to_string(),
+            body: None,
+            breaking: false,
+        }
+    }
+
+    #[test]
+    fn units_are_files_or_hunks() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,2 @@
+-a
++b
+ c
+@@ -10,2 +10,2 @@
+-x
++y
+ z
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+index 3333333..0000000
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+";
+        let by_file = change_units(diff, false);
+        assert_eq!(
+            by_file
+                .iter()
+                .map(|unit| (unit.id, unit.path.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(1, "src/lib.rs"), (2, "old.txt"), (3, "run.sh")]
+        );
+        assert!(by_file[0].patch().contains("@@ -10,2 +10,2 @@"));
+
+        let by_hunk = change_units(diff, true);
+        assert_eq!(
+            by_hunk
+                .iter()
+                .map(|unit| (unit.id, unit.path.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, "src/lib.rs"),
+                (2, "src/lib.rs"),
+                (3, "old.txt"),
+                (4, "run.sh")
+            ]
+        );
+        assert!(by_hunk[1].patch().starts_with("diff --git a/src/lib.rs"));
+        assert!(!by_hunk[1].patch().contains("-a\n"));
+    }
+
+    #[test]
+    fn normalize_drops_unknown_and_repeated_changes() {
+        let units = change_units(
+            "\
+diff --git a/a b/a
+--- a/a
++++ b/a
+@@ -1 +1 @@
+-1
++2
+diff --git a/b b/b
+--- a/b
++++ b/b
+@@ -1 +1 @@
+-1
++2
+diff --git a/c b/c
+--- a/c
++++ b/c
+@@ -1 +1 @@
+-1
++2
+",
+            false,
+        );
+        let mut plan = CommitPlan {
+            commits: vec![
+                PlannedCommit {
+                    changes: vec![2, 7],
+                    message: message("first"),
+                },
+                PlannedCommit {
+                    changes: vec![2],
+                    message: message("repeat"),
+                },
+                PlannedCommit {
+                    changes: vec![1],
+                    message: message("second"),
+                },
+            ],
+        };
+
+        assert_eq!(plan.normalize(&units), vec![3]);
+        assert_eq!(
+            plan.commits
+                .iter()
+                .map(|commit| (commit.message.subject.as_str(), commit.changes.clone()))
+                .collect::<Vec<_>>(),
+            vec![("first", vec![2]), ("second", vec![1])]
+        );
+    }
+
+    #[test]
+    fn commits_hunks_of_one_file_separately() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        let root = repo.path();
+        let run = |args: &[&str]| git(root, args).expect("git");
+        run(&["init", "--quiet"]);
+        run(&["config", "user.email", "dev@example.com"]);
+        run(&["config", "user.name", "Dev"]);
+        let lines: Vec<String> = (1..=20).map(|n| format!("line {n}\n")).collect();
+        fs::write(root.join("a.txt"), lines.concat()).expect("write");
+        run(&["add", "."]);
+        run(&["commit", "--quiet", "-m", "init"]);
+
+        let mut edited = lines.clone();
+        edited[1] = "line two\n".to_string();
+        edited[18] = "line nineteen\n".to_string();
+        fs::write(root.join("a.txt"), edited.concat()).expect("write");
+        fs::write(root.join("new.txt"), "hello\n").expect("write");
+
+        let units = change_units(&working_tree_diff(root).expect("diff"), true);
+        assert_eq!(units.len(), 3);
+        assert_eq!(units[2].path, "new.txt");
+
+        unstage_all(root).expect("reset");
+        stage_units(root, &units, &[2, 3]).expect("stage");
+        commit_staged(root, "second hunk and new file").expect("commit");
+        stage_units(root, &units, &[1]).expect("stage");
+        commit_staged(root, "first hunk").expect("commit");
+
+        assert_eq!(run(&["status", "--porcelain"]), "");
+        let first = run(&["show", "--format=", "HEAD~1", "--", "a.txt"]);
+        assert!(first.contains("+line nineteen") && !first.contains("+line two"));
+    }
+}
@@ -102,6 +102,7 @@ async fn run_compact_task_inner(
         developer_instructions: turn_context.developer_instructions.clone(),
         final_output_json_schema: turn_context.final_output_json_schema.clone(),
         truncation_policy: Some(turn_context.truncation_policy.into()),
+        tool_trimming: None,
     });
     sess.persist_rollout_items(&[rollout_item]).await;
 
@@ -327,7 +328,7 @@ fn build_compacted_history_with_limit(
     history
 }
 
-async fn drain_to_completed(
+pub(crate) async fn drain_to_completed(
     sess: &Session,
     turn_context: &TurnContext,
     prompt: &Prompt,
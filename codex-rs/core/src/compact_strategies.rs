@@ -0,0 +1,506 @@
+//! Compaction strategies selected with `[compaction]` in config.toml, and
+//! `Op::CompactRange`.
+//!
+//! `drop_tool_output` and `sliding_window` shrink the history locally
+//! without a model call. `summarize_oldest` with a target, and
+//! `Op::CompactRange`, summarize one contiguous run of user turns and keep
+//! everything around it verbatim. Without a target, `summarize_oldest` is the
+//! whole-history summary in [`crate::compact`].
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::Prompt;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::codex::get_last_assistant_message_from_turn;
+use crate::compact::SUMMARY_PREFIX;
+use crate::compact::drain_to_completed;
+use crate::config::types::CompactionStrategy;
+use crate::context_manager::ContextManager;
+use crate::context_manager::estimate_item_tokens;
+use crate::context_manager::user_message_positions;
+use crate::error::Result as CodexResult;
+use crate::protocol::CompactedItem;
+use crate::protocol::ContextCompactedEvent;
+use crate::protocol::ErrorEvent;
+use crate::protocol::EventMsg;
+use crate::protocol::TurnStartedEvent;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::user_input::UserInput;
+use tracing::warn;
+
+/// Target used by the strategies that need one when
+/// `compaction.target_context_percent` is unset.
+const DEFAULT_TARGET_CONTEXT_PERCENT: u8 = 50;
+
+const DROPPED_TOOL_OUTPUT_PLACEHOLDER: &str = "[tool output dropped by compaction]";
+
+/// Runs auto-compaction with the configured strategy. Returns `false` when
+/// the caller should fall back to summarizing the whole history, either
+/// because that is the configured behavior or because the strategy could not
+/// bring the history under its target.
+pub(crate) async fn run_configured_auto_compact(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+) -> bool {
+    let config = turn_context.client.config();
+    let strategy = config.compaction_strategy;
+    let target_percent = config.compaction_target_context_percent;
+    if strategy == CompactionStrategy::SummarizeOldest && target_percent.is_none() {
+        return false;
+    }
+    let Some(budget) = history_budget(
+        turn_context,
+        target_percent.unwrap_or(DEFAULT_TARGET_CONTEXT_PERCENT),
+    ) else {
+        return false;
+    };
+
+    let items = sess.clone_history().await.raw_items().to_vec();
+    match strategy {
+        CompactionStrategy::SummarizeOldest => {
+            let Some(range) = oldest_turns_over_budget(&items, budget) else {
+                return false;
+            };
+            match summarize_range(sess, turn_context, items, range).await {
+                Ok(()) => true,
+                Err(err) => {
+                    warn!("failed to summarize the oldest turns: {err}");
+                    false
+                }
+            }
+        }
+        CompactionStrategy::DropToolOutput => {
+            let mut items = items;
+            let dropped = drop_tool_outputs(&mut items, budget);
+            if dropped == 0 || history_tokens(&items) > budget {
+                return false;
+            }
+            replace_history_locally(
+                sess,
+                turn_context,
+                items,
+                format!("Dropped {dropped} old tool output(s) to free up context."),
+            )
+            .await;
+            true
+        }
+        CompactionStrategy::SlidingWindow => {
+            let (items, dropped) = slide_window(items, budget);
+            if dropped == 0 || history_tokens(&items) > budget {
+                return false;
+            }
+            replace_history_locally(
+                sess,
+                turn_context,
+                items,
+                format!("Dropped the {dropped} oldest turn(s) to free up context."),
+            )
+            .await;
+            true
+        }
+    }
+}
+
+/// Handles `Op::CompactRange`.
+pub(crate) async fn run_compact_range_task(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    from_turn: u32,
+    to_turn: u32,
+) {
+    let start_event = EventMsg::TurnStarted(TurnStartedEvent {
+        model_context_window: turn_context.client.get_model_context_window(),
+    });
+    sess.send_event(&turn_context, start_event).await;
+
+    let items = sess.clone_history().await.raw_items().to_vec();
+    let range = match turn_range(&items, from_turn, to_turn) {
+        Ok(range) => range,
+        Err(message) => {
+            let event = EventMsg::Error(ErrorEvent {
+                message,
+                codex_error_info: None,
+            });
+            sess.send_event(&turn_context, event).await;
+            return;
+        }
+    };
+    if let Err(err) = summarize_range(&sess, &turn_context, items, range).await {
+        let event = EventMsg::Error(err.to_error_event(None));
+        sess.send_event(&turn_context, event).await;
+    }
+}
+
+/// Tokens left for history items once `target_percent` of the context
+/// window is reserved, minus the base instructions.
+fn history_budget(turn_context: &TurnContext, target_percent: u8) -> Option<i64> {
+    let context_window = turn_context.client.get_model_context_window()?;
+    let target = context_window.saturating_mul(i64::from(target_percent)) / 100;
+    let base_tokens = ContextManager::new()
+        .estimate_token_count(turn_context)
+        .unwrap_or(0);
+    Some(target.saturating_sub(base_tokens))
+}
+
+fn history_tokens(items: &[ResponseItem]) -> i64 {
+    items.iter().fold(0i64, |acc, item| {
+        acc.saturating_add(estimate_item_tokens(item))
+    })
+}
+
+/// Summarizes `items[range]` with the model and swaps it for the summary,
+/// keeping everything before and after the range verbatim.
+async fn summarize_range(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    items: Vec<ResponseItem>,
+    range: Range<usize>,
+) -> CodexResult<()> {
+    let compact_input: ResponseInputItem = ResponseInputItem::from(vec![UserInput::Text {
+        text: turn_context.compact_prompt().to_string(),
+        // Compaction prompt is synthesized; no UI element ranges to preserve.
+        text_elements: Vec::new(),
+    }]);
+    let mut prompt_history = ContextManager::new();
+    prompt_history.replace(items.get(range.clone()).unwrap_or_default().to_vec());
+    prompt_history.record_items(&[compact_input.into()], turn_context.truncation_policy);
+    let prompt = Prompt {
+        input: prompt_history.for_prompt(),
+        base_instructions: sess.get_base_instructions().await,
+        personality: turn_context.personality,
+        ..Default::default()
+    };
+    drain_to_completed(sess, turn_context.as_ref(), &prompt).await?;
+
+    // The summary was recorded into the live history; rebuild from the
+    // snapshot taken before the request.
+    let summary_suffix =
+        get_last_assistant_message_from_turn(sess.clone_history().await.raw_items())
+            .unwrap_or_default();
+    let summary_text = format!("{SUMMARY_PREFIX}\n{summary_suffix}");
+    let new_history = replace_range_with_summary(&items, range, &summary_text);
+
+    sess.replace_history(new_history.clone()).await;
+    sess.recompute_token_usage(turn_context).await;
+    let rollout_item = RolloutItem::Compacted(CompactedItem {
+        message: summary_text,
+        replacement_history: Some(new_history),
+    });
+    sess.persist_rollout_items(&[rollout_item]).await;
+    let event = EventMsg::ContextCompacted(ContextCompactedEvent {});
+    sess.send_event(turn_context, event).await;
+    Ok(())
+}
+
+async fn replace_history_locally(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    items: Vec<ResponseItem>,
+    message: String,
+) {
+    sess.replace_history(items.clone()).await;
+    sess.recompute_token_usage(turn_context).await;
+    let rollout_item = RolloutItem::Compacted(CompactedItem {
+        message: String::new(),
+        replacement_history: Some(items),
+    });
+    sess.persist_rollout_items(&[rollout_item]).await;
+    let event = EventMsg::ContextCompacted(ContextCompactedEvent {});
+    sess.send_event(turn_context, event).await;
+    sess.notify_background_event(turn_context, message).await;
+}
+
+/// Item range covering user turns `from_turn..=to_turn` (1-based).
+fn turn_range(
+    items: &[ResponseItem],
+    from_turn: u32,
+    to_turn: u32,
+) -> Result<Range<usize>, String> {
+    if from_turn == 0 || from_turn > to_turn {
+        return Err(format!(
+            "invalid turn range {from_turn}..={to_turn}: turns are numbered from 1"
+        ));
+    }
+    let positions = user_message_positions(items);
+    let from = usize::try_from(from_turn - 1).unwrap_or(usize::MAX);
+    let to = usize::try_from(to_turn).unwrap_or(usize::MAX);
+    let Some(&start) = positions.get(from).filter(|_| to <= positions.len()) else {
+        return Err(format!(
+            "turn {to_turn} does not exist; this thread has {} turn(s)",
+            positions.len()
+        ));
+    };
+    let end = positions.get(to).copied().unwrap_or(items.len());
+    Ok(start..end)
+}
+
+/// Oldest turns to summarize so the remaining ones fit in `budget`. The
+/// latest turn is always kept verbatim. `None` when nothing can be
+/// summarized.
+fn oldest_turns_over_budget(items: &[ResponseItem], budget: i64) -> Option<Range<usize>> {
+    let positions = user_message_positions(items);
+    let (&first, &last) = (positions.first()?, positions.last()?);
+    let mut keep_from = last;
+    let mut recent_tokens = history_tokens(items.get(last..)?);
+    for &start in positions.iter().rev().skip(1) {
+        let turn_tokens = history_tokens(items.get(start..keep_from)?);
+        if recent_tokens.saturating_add(turn_tokens) > budget {
+            break;
+        }
+        recent_tokens = recent_tokens.saturating_add(turn_tokens);
+        keep_from = start;
+    }
+    (keep_from > first).then_some(first..keep_from)
+}
+
+/// Replaces tool outputs, oldest first, with a placeholder until the history
+/// fits in `budget`. Outputs of the latest turn are kept because the model
+/// is likely still working with them. Returns how many outputs were dropped.
+fn drop_tool_outputs(items: &mut [ResponseItem], budget: i64) -> usize {
+    let mut excess = history_tokens(items).saturating_sub(budget);
+    if excess <= 0 {
+        return 0;
+    }
+    let protected_from = user_message_positions(items)
+        .last()
+        .copied()
+        .unwrap_or(items.len());
+    let mut dropped = 0;
+    for item in items.iter_mut().take(protected_from) {
+        if excess <= 0 {
+            break;
+        }
+        let before = estimate_item_tokens(item);
+        let replaced = match item {
+            ResponseItem::FunctionCallOutput { output, .. }
+                if output.content != DROPPED_TOOL_OUTPUT_PLACEHOLDER =>
+            {
+                output.content = DROPPED_TOOL_OUTPUT_PLACEHOLDER.to_string();
+                output.content_items = None;
+                true
+            }
+            ResponseItem::CustomToolCallOutput { output, .. }
+                if output != DROPPED_TOOL_OUTPUT_PLACEHOLDER =>
+            {
+                *output = DROPPED_TOOL_OUTPUT_PLACEHOLDER.to_string();
+                true
+            }
+            _ => false,
+        };
+        if replaced {
+            dropped += 1;
+            excess = excess.saturating_sub(before.saturating_sub(estimate_item_tokens(item)));
+        }
+    }
+    dropped
+}
+
+/// Drops whole user turns, oldest first, until the history fits in
+/// `budget`. Items before the first turn (the initial context) and the
+/// latest turn are always kept. Returns the new history and how many turns
+/// were dropped.
+fn slide_window(items: Vec<ResponseItem>, budget: i64) -> (Vec<ResponseItem>, usize) {
+    let positions = user_message_positions(&items);
+    let Some(&first) = positions.first() else {
+        return (items, 0);
+    };
+    let mut tokens = history_tokens(&items);
+    let mut cut_end = first;
+    let mut dropped = 0;
+    for turn in positions.windows(2) {
+        if tokens <= budget {
+            break;
+        }
+        let (start, end) = (turn[0], turn[1]);
+        tokens = tokens.saturating_sub(history_tokens(&items[start..end]));
+        cut_end = end;
+        dropped += 1;
+    }
+    if dropped == 0 {
+        return (items, 0);
+    }
+    let new_history = items[..first]
+        .iter()
+        // Keep ghost snapshots so `/undo` still works across the dropped turns.
+        .chain(
+            items[first..cut_end]
+                .iter()
+                .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. })),
+        )
+        .chain(items[cut_end..].iter())
+        .cloned()
+        .collect();
+    (new_history, dropped)
+}
+
+fn replace_range_with_summary(
+    items: &[ResponseItem],
+    range: Range<usize>,
+    summary_text: &str,
+) -> Vec<ResponseItem> {
+    let summary = ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: summary_text.to_string(),
+        }],
+        end_turn: None,
+    };
+    items[..range.start]
+        .iter()
+        .cloned()
+        .chain(std::iter::once(summary))
+        // Required to keep `/undo` available after compaction.
+        .chain(
+            items[range.clone()]
+                .iter()
+                .filter(|item| matches!(item, ResponseItem::GhostSnapshot { .. }))
+                .cloned(),
+        )
+        .chain(items[range.end..].iter().cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_git::GhostCommit;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    fn user(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        }
+    }
+
+    fn assistant(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        }
+    }
+
+    fn tool_output(call_id: &str, content: &str) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn ghost(id: &str) -> ResponseItem {
+        ResponseItem::GhostSnapshot {
+            ghost_commit: GhostCommit::new(id.to_string(), None, Vec::new(), Vec::new()),
+        }
+    }
+
+    fn three_turns() -> Vec<ResponseItem> {
+        vec![
+            assistant("initial context"),
+            user("turn one"),
+            assistant("reply one"),
+            user("turn two"),
+            ghost("snapshot-two"),
+            assistant("reply two"),
+            user("turn three"),
+            assistant("reply three"),
+        ]
+    }
+
+    #[test]
+    fn turn_range_covers_whole_turns() {
+        let items = three_turns();
+        assert_eq!(turn_range(&items, 1, 2), Ok(1..6));
+        assert_eq!(turn_range(&items, 3, 3), Ok(6..8));
+        assert!(turn_range(&items, 0, 1).is_err());
+        assert!(turn_range(&items, 2, 1).is_err());
+        assert!(turn_range(&items, 2, 4).is_err());
+    }
+
+    #[test]
+    fn replace_range_with_summary_keeps_surrounding_turns_and_snapshots() {
+        let items = three_turns();
+        let new_history = replace_range_with_summary(&items, 1..6, "summary");
+        assert_eq!(
+            new_history,
+            vec![
+                assistant("initial context"),
+                user("summary"),
+                ghost("snapshot-two"),
+                user("turn three"),
+                assistant("reply three"),
+            ]
+        );
+    }
+
+    #[test]
+    fn oldest_turns_over_budget_keeps_recent_turns_that_fit() {
+        let items = three_turns();
+        let last_two = history_tokens(&items[3..]);
+        assert_eq!(oldest_turns_over_budget(&items, last_two), Some(1..3));
+        // The latest turn is kept even when it alone exceeds the budget.
+        assert_eq!(oldest_turns_over_budget(&items, 0), Some(1..6));
+        assert_eq!(oldest_turns_over_budget(&items, i64::MAX), None);
+    }
+
+    #[test]
+    fn drop_tool_outputs_skips_latest_turn_and_stops_at_budget() {
+        let big = "x".repeat(4_000);
+        let mut items = vec![
+            user("turn one"),
+            tool_output("call-1", &big),
+            tool_output("call-2", &big),
+            user("turn two"),
+            tool_output("call-3", &big),
+        ];
+        let budget = history_tokens(&items) - history_tokens(&items[1..2]) / 2;
+
+        assert_eq!(drop_tool_outputs(&mut items, budget), 1);
+        assert_eq!(
+            items[1],
+            tool_output("call-1", DROPPED_TOOL_OUTPUT_PLACEHOLDER)
+        );
+        assert_eq!(items[2], tool_output("call-2", &big));
+
+        assert_eq!(drop_tool_outputs(&mut items, 0), 1);
+        assert_eq!(items[4], tool_output("call-3", &big));
+    }
+
+    #[test]
+    fn slide_window_drops_oldest_turns_but_keeps_context_and_latest_turn() {
+        let items = three_turns();
+        let budget = history_tokens(&items) - history_tokens(&items[1..3]);
+        let (new_history, dropped) = slide_window(items.clone(), budget);
+        assert_eq!(dropped, 1);
+        assert_eq!(new_history, [&items[..1], &items[3..]].concat());
+
+        let (new_history, dropped) = slide_window(items.clone(), 0);
+        assert_eq!(dropped, 2);
+        assert_eq!(
+            new_history,
+            vec![
+                assistant("initial context"),
+                ghost("snapshot-two"),
+                user("turn three"),
+                assistant("reply three"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Branch-scoped overrides declared under `[branches."<pattern>"]`.
+//!
+//! Patterns are matched against the branch checked out in the session cwd
+//! (`*` and `?` wildcards). They are resolved when the config is loaded and
+//! again at the start of every turn, so switching branches mid-session swaps
+//! the approval and sandbox policies the agent runs with.
+
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::SandboxPolicy;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use wildmatch::WildMatch;
+
+/// Settings that may be overridden for branches matching a pattern.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct BranchConfigToml {
+    /// Approval policy to use while a matching branch is checked out.
+    pub approval_policy: Option<AskForApproval>,
+
+    /// Sandbox mode to use while a matching branch is checked out.
+    pub sandbox_mode: Option<SandboxMode>,
+}
+
+/// A `[branches."<pattern>"]` entry with its sandbox mode already resolved
+/// against the rest of the config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchRule {
+    pub pattern: String,
+    pub approval_policy: Option<AskForApproval>,
+    pub sandbox_policy: Option<SandboxPolicy>,
+}
+
+impl BranchRule {
+    fn matches(&self, branch: &str) -> bool {
+        WildMatch::new(&self.pattern).matches(branch)
+    }
+
+    /// Exact names beat globs; among globs the longer pattern wins.
+    fn specificity(&self) -> (bool, usize) {
+        let is_exact = !self.pattern.contains(['*', '?']);
+        (is_exact, self.pattern.len())
+    }
+}
+
+/// Resolved branch overrides, kept on [`crate::config::Config`] so the
+/// session can re-resolve them after a branch switch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchConfig {
+    /// Branch checked out in `cwd` when the config was loaded (`None` on a
+    /// detached HEAD or outside a repository).
+    pub branch: Option<String>,
+    pub rules: Vec<BranchRule>,
+    /// Policies in effect when no rule matches.
+    pub base_approval_policy: AskForApproval,
+    pub base_sandbox_policy: SandboxPolicy,
+}
+
+impl BranchConfig {
+    /// Returns the approval and sandbox policies for `branch`. When several
+    /// patterns match, each setting comes from the most specific rule that
+    /// sets it.
+    pub fn resolve(&self, branch: Option<&str>) -> (AskForApproval, SandboxPolicy) {
+        let mut matching: Vec<&BranchRule> = match branch {
+            Some(branch) => self
+                .rules
+                .iter()
+                .filter(|rule| rule.matches(branch))
+                .collect(),
+            None => Vec::new(),
+        };
+        matching.sort_by_key(|rule| rule.specificity());
+
+        let mut approval_policy = self.base_approval_policy;
+        let mut sandbox_policy = self.base_sandbox_policy.clone();
+        for rule in matching {
+            if let Some(policy) = rule.approval_policy {
+                approval_policy = policy;
+            }
+            if let Some(policy) = &rule.sandbox_policy {
+                sandbox_policy = policy.clone();
+            }
+        }
+        (approval_policy, sandbox_policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn rule(
+        pattern: &str,
+        approval_policy: Option<AskForApproval>,
+        sandbox_policy: Option<SandboxPolicy>,
+    ) -> BranchRule {
+        BranchRule {
+            pattern: pattern.to_string(),
+            approval_policy,
+            sandbox_policy,
+        }
+    }
+
+    fn branch_config() -> BranchConfig {
+        BranchConfig {
+            branch: None,
+            rules: vec![
+                rule(
+                    "main",
+                    Some(AskForApproval::UnlessTrusted),
+                    Some(SandboxPolicy::new_read_only_policy()),
+                ),
+                rule("codex/*", Some(AskForApproval::OnRequest), None),
+                rule(
+                    "codex/sandbox-*",
+                    None,
+                    Some(SandboxPolicy::DangerFullAccess),
+                ),
+            ],
+            base_approval_policy: AskForApproval::OnFailure,
+            base_sandbox_policy: SandboxPolicy::new_workspace_write_policy(),
+        }
+    }
+
+    #[test]
+    fn exact_branch_overrides_base() {
+        assert_eq!(
+            branch_config().resolve(Some("main")),
+            (
+                AskForApproval::UnlessTrusted,
+                SandboxPolicy::new_read_only_policy()
+            )
+        );
+    }
+
+    #[test]
+    fn more_specific_patterns_layer_over_broader_ones() {
+        assert_eq!(
+            branch_config().resolve(Some("codex/sandbox-fix")),
+            (AskForApproval::OnRequest, SandboxPolicy::DangerFullAccess)
+        );
+    }
+
+    #[test]
+    fn unmatched_or_detached_uses_base() {
+        let expected = (
+            AskForApproval::OnFailure,
+            SandboxPolicy::new_workspace_write_policy(),
+        );
+        assert_eq!(branch_config().resolve(Some("feature/x")), expected);
+        assert_eq!(branch_config().resolve(None), expected);
+    }
+}
@@ -164,6 +164,19 @@ mod document_helpers {
         {
             entry["disabled_tools"] = array_from_iter(disabled_tools.iter().cloned());
         }
+        if let Some(approval) = config.default_tool_approval {
+            entry["default_tool_approval"] = value(approval.as_str());
+        }
+        if let Some(tool_approvals) = &config.tool_approvals
+            && !tool_approvals.is_empty()
+        {
+            let approvals: Vec<(String, String)> = tool_approvals
+                .iter()
+                .map(|(tool, approval)| (tool.clone(), approval.as_str().to_string()))
+                .collect();
+            entry["tool_approvals"] =
+                table_from_pairs(approvals.iter().map(|(tool, approval)| (tool, approval)));
+        }
 
         entry
     }
@@ -1360,6 +1373,8 @@ gpt-5 = "gpt-5.1"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["one".to_string(), "two".to_string()]),
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
 
@@ -1382,6 +1397,8 @@ gpt-5 = "gpt-5.1"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: Some(vec!["forbidden".to_string()]),
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
 
@@ -1447,6 +1464,8 @@ foo = { command = "cmd" }
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
 
@@ -1491,6 +1510,8 @@ foo = { command = "cmd" } # keep me
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
 
@@ -1534,6 +1555,8 @@ foo = { command = "cmd", args = ["--flag"] } # keep me
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
 
@@ -1578,6 +1601,8 @@ foo = { command = "cmd" }
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
 
@@ -1,22 +1,81 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::config::branches::BranchConfig;
+use crate::config::branches::BranchConfigToml;
+use crate::config::branches::BranchRule;
 use crate::config::edit::ConfigEdit;
 use crate::config::edit::ConfigEditsBuilder;
+use crate::config::types::ApprovalTimeouts;
+use crate::config::types::ApprovalTimeoutsToml;
+use crate::config::types::AuditConfig;
+use crate::config::types::AuditToml;
+use crate::config::types::BrowserConfig;
+use crate::config::types::BrowserToml;
+use crate::config::types::BudgetConfig;
+use crate::config::types::BudgetToml;
+use crate::config::types::CommandRuleToml;
+use crate::config::types::CompactionStrategy;
+use crate::config::types::ContainerSandboxConfig;
+use crate::config::types::ContextProviderConfig;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::EditingMode;
+use crate::config::types::EmbeddingBackend;
+use crate::config::types::ExecLimits;
+use crate::config::types::GeminiSafetySetting;
+use crate::config::types::GeminiToml;
+use crate::config::types::GitGuardrailsToml;
+use crate::config::types::GovernorConfig;
+use crate::config::types::GovernorToml;
+use crate::config::types::GuardrailConfig;
+use crate::config::types::GuardrailToml;
 use crate::config::types::History;
+use crate::config::types::HookConfig;
+use crate::config::types::HostedToolsToml;
+use crate::config::types::KeybindingsToml;
+use crate::config::types::LocalModelsConfig;
+use crate::config::types::LocalModelsToml;
+use crate::config::types::McpHealthConfig;
+use crate::config::types::McpHealthToml;
+use crate::config::types::McpSamplingConfig;
+use crate::config::types::McpSamplingToml;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerDisabledReason;
 use crate::config::types::McpServerTransportConfig;
+use crate::config::types::NetworkToml;
 use crate::config::types::Notice;
+use crate::config::types::NotificationMethod;
 use crate::config::types::Notifications;
+use crate::config::types::OfflineConfig;
+use crate::config::types::OfflineToml;
+use crate::config::types::OfflineTool;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
+use crate::config::types::PreCommitConfig;
+use crate::config::types::PreCommitToml;
+use crate::config::types::SandboxBackendKind;
+use crate::config::types::SandboxToml;
 use crate::config::types::SandboxWorkspaceWrite;
+use crate::config::types::SemanticIndexConfig;
+use crate::config::types::SemanticIndexToml;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::SkillsConfig;
+use crate::config::types::StatusLineItem;
+use crate::config::types::TelemetryConfig;
+use crate::config::types::TelemetryToml;
+use crate::config::types::ThemePaletteToml;
+use crate::config::types::ThemeVariant;
+use crate::config::types::TimeboxConfig;
+use crate::config::types::TimeboxToml;
 use crate::config::types::Tui;
 use crate::config::types::UriBasedFileOpener;
+use crate::config::types::VoiceConfig;
+use crate::config::types::WebFetchConfig;
+use crate::config::types::WebFetchToml;
+use crate::config::types::WebSearchProviderKind;
+use crate::config::types::WebSearchProviderToml;
+use crate::config::types::WorktreeIsolationConfig;
+use crate::config::types::WorktreeIsolationToml;
 use crate::config_loader::ConfigLayerStack;
 use crate::config_loader::ConfigRequirements;
 use crate::config_loader::LoaderOverrides;
@@ -28,8 +87,11 @@ use crate::features::Feature;
 use crate::features::FeatureOverrides;
 use crate::features::Features;
 use crate::features::FeaturesToml;
+use crate::git_info::checked_out_branch;
 use crate::git_info::resolve_root_git_project_for_trust;
+use crate::mock_provider::MOCK_MODEL;
 use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
+use crate::model_provider_info::MOCK_PROVIDER_ID;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::OLLAMA_CHAT_PROVIDER_ID;
 use crate::model_provider_info::OLLAMA_OSS_PROVIDER_ID;
@@ -40,6 +102,7 @@ use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
+use codex_client::NetworkSettings;
 use codex_protocol::config_types::AltScreenMode;
 use codex_protocol::config_types::ForcedLoginMethod;
 use codex_protocol::config_types::ModeKind;
@@ -67,15 +130,18 @@ use std::path::PathBuf;
 use tempfile::tempdir;
 
 use crate::config::profile::ConfigProfile;
+use crate::config::profile::resolve_profile;
 use toml::Value as TomlValue;
 use toml_edit::DocumentMut;
 
+pub mod branches;
 mod constraint;
 pub mod edit;
 pub mod profile;
 pub mod schema;
 pub mod service;
 pub mod types;
+pub mod validate;
 pub use constraint::Constrained;
 pub use constraint::ConstraintError;
 pub use constraint::ConstraintResult;
@@ -121,18 +187,32 @@ pub struct Config {
     /// Model used specifically for review sessions.
     pub review_model: Option<String>,
 
+    /// Review each top-level area of a change with its own reviewer and
+    /// merge the findings.
+    pub review_split_by_area: bool,
+
     /// Size of the context window for the model, in tokens.
     pub model_context_window: Option<i64>,
 
     /// Token usage threshold triggering auto-compaction of conversation history.
     pub model_auto_compact_token_limit: Option<i64>,
 
+    /// How auto-compaction shrinks the history (`compaction.strategy`).
+    pub compaction_strategy: CompactionStrategy,
+
+    /// Share of the context window, in percent, that compaction shrinks the
+    /// history to (`compaction.target_context_percent`).
+    pub compaction_target_context_percent: Option<u8>,
+
     /// Key into the model_providers map that specifies which provider to use.
     pub model_provider_id: String,
 
     /// Info needed to make an API request to the model.
     pub model_provider: ModelProviderInfo,
 
+    /// Script replayed by providers with `wire_api = "mock"`.
+    pub mock_responses_file: Option<PathBuf>,
+
     /// Optionally specify the personality of the model
     pub model_personality: Option<Personality>,
 
@@ -149,8 +229,30 @@ pub struct Config {
     /// was coerced to read-only because native auto mode is unsupported.
     pub forced_auto_mode_downgraded_on_windows: bool,
 
+    /// Domains reachable through the session's filtering proxy when a
+    /// workspace-write sandbox has no network access
+    /// (`sandbox_workspace_write.network_allowlist`).
+    pub network_allowlist: Vec<String>,
+
+    /// Set when `sandbox.backend = "container"`: sandboxed commands run in
+    /// this container instead of the platform sandbox.
+    pub container_sandbox: Option<ContainerSandboxConfig>,
+
+    /// Resource limits for each shell tool command (`[exec_limits]`).
+    pub exec_limits: ExecLimits,
+
     pub shell_environment_policy: ShellEnvironmentPolicy,
 
+    /// Extra command prefixes (already split into argv) treated as read-only
+    /// when the `read_only_auto_approve` feature is enabled.
+    pub read_only_commands: Vec<Vec<String>>,
+
+    /// How long approval prompts may go unanswered, per kind of approval.
+    pub approval_timeouts: ApprovalTimeouts,
+
+    /// Provider-hosted tools enabled per model slug (`"*"` for any model).
+    pub hosted_tools: HashMap<String, HostedToolsToml>,
+
     /// When `true`, `AgentReasoning` events emitted by the backend will be
     /// suppressed from the frontend output. This can reduce visual noise when
     /// users are only interested in the final agent responses.
@@ -198,6 +300,13 @@ pub struct Config {
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
 
+    /// How the TUI posts notifications (`tui.notification_method`).
+    pub tui_notification_method: NotificationMethod,
+
+    /// Minimum turn length, in seconds, for a turn-complete notification
+    /// (`tui.notification_min_turn_sec`).
+    pub tui_notification_min_turn_sec: u64,
+
     /// Enable ASCII animations and shimmer effects in the TUI.
     pub animations: bool,
 
@@ -215,6 +324,30 @@ pub struct Config {
     /// - `never`: Never use alternate screen (inline mode, preserves scrollback).
     pub tui_alternate_screen: AltScreenMode,
 
+    /// Push-to-talk voice input in the TUI composer (`tui.voice`); `None`
+    /// when not configured.
+    pub tui_voice: Option<VoiceConfig>,
+
+    /// Remapped TUI keys (`tui.keybindings`), as written; the TUI parses and
+    /// validates them at startup.
+    pub tui_keybindings: KeybindingsToml,
+
+    /// Name of the TUI color theme (`tui.theme`); `None` means `default`.
+    pub tui_theme: Option<String>,
+
+    /// Light or dark variant of the theme (`tui.theme_variant`).
+    pub tui_theme_variant: ThemeVariant,
+
+    /// Colors overriding the theme's (`tui.theme_palette`), as written; the
+    /// TUI parses them at startup.
+    pub tui_theme_palette: ThemePaletteToml,
+
+    /// Items of the TUI status line (`tui.status_line`); empty hides it.
+    pub tui_status_line: Vec<StatusLineItem>,
+
+    /// Key scheme of the TUI composer (`tui.editing_mode`).
+    pub tui_editing_mode: EditingMode,
+
     /// The directory that should be treated as the current working directory
     /// for the session. All relative paths inside the business-logic layer are
     /// resolved against this path.
@@ -233,8 +366,8 @@ pub struct Config {
     /// keyring: Use an OS-specific keyring service.
     ///          Credentials stored in the keyring will only be readable by Codex unless the user explicitly grants access via OS-level keyring access.
     ///          https://github.com/openai/codex/blob/main/codex-rs/rmcp-client/src/oauth.rs#L2
-    /// file: CODEX_HOME/.credentials.json
-    ///       This file will be readable to Codex and other applications running as the same user.
+    /// file: one file per server under CODEX_HOME/mcp_auth/
+    ///       These files will be readable to Codex and other applications running as the same user.
     /// auto (default): keyring if available, otherwise file.
     pub mcp_oauth_credentials_store_mode: OAuthCredentialsStoreMode,
 
@@ -243,6 +376,75 @@ pub struct Config {
     /// When unset, Codex will bind to an ephemeral port chosen by the OS.
     pub mcp_oauth_callback_port: Option<u16>,
 
+    /// Which MCP servers may request model completions, and the limits applied to them.
+    pub mcp_sampling: McpSamplingConfig,
+
+    /// Liveness pings and automatic restarts for running MCP servers.
+    pub mcp_health: McpHealthConfig,
+
+    /// Domain policy and limits for the `web_fetch` tool.
+    pub web_fetch: WebFetchConfig,
+
+    /// Search API used by the local `web_search` tool, which then replaces
+    /// the hosted one.
+    pub web_search_provider: Option<WebSearchProviderToml>,
+
+    /// Settings for the `browser_*` tools; `None` (and no browser tools)
+    /// unless `[browser]` is configured.
+    pub browser: Option<BrowserConfig>,
+
+    /// Limits on model requests shared with the other Codex processes on
+    /// this machine; `None` when `[governor]` is not configured.
+    pub governor: Option<GovernorConfig>,
+
+    /// Audit log of agent actions; `None` unless `[audit]` is configured and
+    /// enabled.
+    pub audit: Option<AuditConfig>,
+
+    /// OTLP export of spans and metrics; `None` unless `[telemetry]` is
+    /// configured and enabled, and never in offline mode.
+    pub telemetry: Option<TelemetryConfig>,
+
+    /// Run sessions or turns in a separate git worktree; `None` unless
+    /// `[worktree_isolation]` is configured and enabled.
+    pub worktree_isolation: Option<WorktreeIsolationConfig>,
+
+    /// `safetySettings` sent with every Gemini request, from `[gemini]`.
+    pub gemini_safety_settings: Vec<GeminiSafetySetting>,
+
+    /// Whether and for which providers local model servers are probed for
+    /// their context length and tool-call support.
+    pub local_models: LocalModelsConfig,
+
+    /// Proxy and extra CA certificates applied to every HTTP client; also
+    /// installed process-wide when the config is loaded.
+    pub network: NetworkSettings,
+
+    /// Set when `[offline]` is enabled: only local endpoints are used.
+    pub offline: Option<OfflineConfig>,
+
+    /// Protected branches and the optional model check used by the
+    /// `destructive_guardrail` feature.
+    pub guardrail: GuardrailConfig,
+
+    /// How `codex exec --max-duration` wraps up a run that runs out of time.
+    pub timebox: TimeboxConfig,
+
+    /// Checks run by the pre-commit hook that `codex hooks install` adds.
+    pub pre_commit: PreCommitConfig,
+
+    /// Model prices and spending limits (`[budget]`).
+    pub budget: BudgetConfig,
+
+    /// How the `semantic_search` index embeds code (`[semantic_index]`).
+    pub semantic_index: SemanticIndexConfig,
+
+    /// External commands run at session events (`[[hooks]]`).
+    pub hooks: Vec<HookConfig>,
+
+    /// Sources of extra context gathered at turn start (`[[context_providers]]`).
+    pub context_providers: Vec<ContextProviderConfig>,
+
     /// Combined provider map (defaults merged with user-defined overrides).
     pub model_providers: HashMap<String, ModelProviderInfo>,
 
@@ -258,6 +460,19 @@ pub struct Config {
     /// Maximum number of agent threads that can be open concurrently.
     pub agent_max_threads: Option<usize>,
 
+    /// Additional subagent search directories from `subagents.extra_dirs`
+    /// (profile value wins over the top-level one).
+    pub subagent_extra_dirs: Vec<PathBuf>,
+
+    /// Whether `Op::RunSubAgent` runs edit an isolated worktree and stage
+    /// their changes for `Op::MergeSubAgentChanges` (`subagents.stage_changes`,
+    /// profile value wins over the top-level one).
+    pub subagent_stage_changes: bool,
+
+    /// Branch-scoped approval/sandbox overrides from `[branches]`, or `None`
+    /// when no patterns are configured.
+    pub branch_config: Option<BranchConfig>,
+
     /// Directory containing all Codex state (defaults to `~/.codex` but can be
     /// overridden by the `CODEX_HOME` environment variable).
     pub codex_home: PathBuf,
@@ -288,6 +503,12 @@ pub struct Config {
     /// Optional override to force-enable reasoning summaries for the configured model.
     pub model_supports_reasoning_summaries: Option<bool>,
 
+    /// Optional override for whether the configured model accepts image input.
+    pub model_supports_images: Option<bool>,
+
+    /// Optional override for whether the configured model supports native tool calls.
+    pub model_supports_tool_calls: Option<bool>,
+
     /// Optional verbosity control for GPT-5 models (Responses API `text.verbosity`).
     pub model_verbosity: Option<Verbosity>,
 
@@ -413,10 +634,11 @@ impl ConfigBuilder {
         // Note that each layer in ConfigLayerStack should have resolved
         // relative paths to absolute paths based on the parent folder of the
         // respective config file, so we should be safe to deserialize without
-        // AbsolutePathBufGuard here.
+        // AbsolutePathBufGuard here. When it does not deserialize, the layers
+        // are checked against the schema to point at the offending file.
         let config_toml: ConfigToml = merged_toml
             .try_into()
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            .map_err(|err| validate::invalid_config_error(&config_layer_stack, err))?;
         Config::load_config_with_layer_stack(
             config_toml,
             harness_overrides,
@@ -540,6 +762,22 @@ fn filter_mcp_servers_by_requirements(
     }
 }
 
+/// Offline mode: streamable HTTP servers on non-local hosts are not started.
+fn disable_remote_mcp_servers(
+    mcp_servers: &mut HashMap<String, McpServerConfig>,
+    offline: &OfflineConfig,
+) {
+    for (name, server) in mcp_servers.iter_mut() {
+        if let McpServerTransportConfig::StreamableHttp { url, .. } = &server.transport
+            && server.enabled
+            && let Err(err) = offline.check_url(&format!("MCP server `{name}`"), url)
+        {
+            server.enabled = false;
+            server.disabled_reason = Some(McpServerDisabledReason::Offline(err));
+        }
+    }
+}
+
 fn constrain_mcp_servers(
     mcp_servers: HashMap<String, McpServerConfig>,
     mcp_requirements: Option<&Sourced<BTreeMap<String, McpServerRequirement>>>,
@@ -746,10 +984,17 @@ pub struct ConfigToml {
     pub model: Option<String>,
     /// Review model override used by the `/review` feature.
     pub review_model: Option<String>,
+    /// Review each top-level directory touched by the change with its own
+    /// reviewer and merge their findings. Defaults to false.
+    pub review_split_by_area: Option<bool>,
 
     /// Provider to use from the model_providers map.
     pub model_provider: Option<String>,
 
+    /// JSON script of responses replayed by the `mock` provider (selected
+    /// with `model = "mock"`), one entry per model request.
+    pub mock_responses_file: Option<AbsolutePathBuf>,
+
     /// Size of the context window for the model, in tokens.
     pub model_context_window: Option<i64>,
 
@@ -759,6 +1004,34 @@ pub struct ConfigToml {
     /// Default approval policy for executing commands.
     pub approval_policy: Option<AskForApproval>,
 
+    /// Extra command prefixes (e.g. `"cargo check"`, `"just --list"`) that the
+    /// `read_only_auto_approve` feature may run without asking for approval.
+    #[serde(default)]
+    pub read_only_commands: Option<Vec<String>>,
+
+    /// Per-kind approval timeouts (`[approval_timeouts.exec]`, `.patch`,
+    /// `.plan`) with the action applied when a prompt goes unanswered.
+    #[serde(default)]
+    pub approval_timeouts: Option<ApprovalTimeoutsToml>,
+
+    /// Allow/deny rules for shell commands, checked before the approval
+    /// policy. Entries from every config layer (and each layer's
+    /// `policies.toml`) apply together; they are read by the exec policy
+    /// loader rather than merged.
+    #[serde(default)]
+    pub command_rules: Option<Vec<CommandRuleToml>>,
+
+    /// Checks for force pushes, history rewrites and `git clean`. Sections
+    /// from every config layer (and each layer's `policies.toml`) apply
+    /// together, like `command_rules`.
+    #[serde(default)]
+    pub git_guardrails: Option<GitGuardrailsToml>,
+
+    /// Provider-hosted tools (`code_interpreter`, `file_search`) keyed by
+    /// model slug, e.g. `[hosted_tools."gpt-5.1"]`; `"*"` matches any model.
+    #[serde(default)]
+    pub hosted_tools: HashMap<String, HostedToolsToml>,
+
     #[serde(default)]
     pub shell_environment_policy: ShellEnvironmentPolicyToml,
 
@@ -768,6 +1041,12 @@ pub struct ConfigToml {
     /// Sandbox configuration to apply if `sandbox` is `WorkspaceWrite`.
     pub sandbox_workspace_write: Option<SandboxWorkspaceWrite>,
 
+    /// Backend that runs sandboxed commands (`[sandbox]`).
+    pub sandbox: Option<SandboxToml>,
+
+    /// Resource limits for shell tool commands.
+    pub exec_limits: Option<ExecLimits>,
+
     /// Optional external command to spawn for end-user notifications.
     #[serde(default)]
     pub notify: Option<Vec<String>>,
@@ -821,6 +1100,96 @@ pub struct ConfigToml {
     /// When unset, Codex will bind to an ephemeral port chosen by the OS.
     pub mcp_oauth_callback_port: Option<u16>,
 
+    /// Lets the listed MCP servers request model completions (`sampling/createMessage`).
+    #[serde(default)]
+    pub mcp_sampling: Option<McpSamplingToml>,
+
+    /// Pings running MCP servers and restarts the ones that stop answering.
+    #[serde(default)]
+    pub mcp_health: Option<McpHealthToml>,
+
+    /// Domain allow/deny lists and download limits for the `web_fetch` tool.
+    #[serde(default)]
+    pub web_fetch: Option<WebFetchToml>,
+
+    /// Search API (Bing, Brave, SearxNG or a custom endpoint) for a local
+    /// `web_search` tool that works with any model provider.
+    #[serde(default)]
+    pub web_search_provider: Option<WebSearchProviderToml>,
+
+    /// Headless Chromium driven by the `browser_*` tools. The tools are only
+    /// offered when this section is present.
+    #[serde(default)]
+    pub browser: Option<BrowserToml>,
+
+    /// Concurrency, token and rate-limit budgets shared by all sessions using
+    /// this `CODEX_HOME`.
+    #[serde(default)]
+    pub governor: Option<GovernorToml>,
+
+    /// Append-only audit log of commands, file writes, approvals and
+    /// subagent runs.
+    #[serde(default)]
+    pub audit: Option<AuditToml>,
+
+    /// Export spans and metrics to an OpenTelemetry (OTLP) collector.
+    #[serde(default)]
+    pub telemetry: Option<TelemetryToml>,
+
+    /// Run sessions or turns in an automatically created git worktree and
+    /// branch, merged into the checkout or discarded at the end.
+    #[serde(default)]
+    pub worktree_isolation: Option<WorktreeIsolationToml>,
+
+    /// Gemini-only request options such as safety settings.
+    #[serde(default)]
+    pub gemini: Option<GeminiToml>,
+
+    /// Probe local model servers for context length and tool-call support.
+    #[serde(default)]
+    pub local_models: Option<LocalModelsToml>,
+
+    /// Proxy and extra CA certificates for corporate networks.
+    #[serde(default)]
+    pub network: Option<NetworkToml>,
+
+    /// Offline mode for isolated networks.
+    #[serde(default)]
+    pub offline: Option<OfflineToml>,
+
+    /// Settings for the `destructive_guardrail` feature, which requires
+    /// approval for clearly destructive commands.
+    #[serde(default)]
+    pub guardrail: Option<GuardrailToml>,
+
+    /// Grace period and handling of unfinished work for
+    /// `codex exec --max-duration`.
+    #[serde(default)]
+    pub timebox: Option<TimeboxToml>,
+
+    /// Checks run by the pre-commit hook installed with `codex hooks install`.
+    #[serde(default)]
+    pub pre_commit: Option<PreCommitToml>,
+
+    /// Per-model prices used to estimate cost, and per-turn and per-session
+    /// spending limits.
+    #[serde(default)]
+    pub budget: Option<BudgetToml>,
+
+    /// Embedding backend for the `semantic_search` index: built-in hashing or
+    /// a local model.
+    #[serde(default)]
+    pub semantic_index: Option<SemanticIndexToml>,
+
+    /// External commands run at session events, e.g. before tool calls or when a turn ends.
+    #[serde(default)]
+    pub hooks: Vec<HookConfig>,
+
+    /// Commands or MCP resources whose output is added to the context at the
+    /// start of every turn, e.g. tickets from an internal tracker.
+    #[serde(default)]
+    pub context_providers: Vec<ContextProviderConfig>,
+
     /// User-defined provider entries that extend/override the built-in list.
     #[serde(default)]
     pub model_providers: HashMap<String, ModelProviderInfo>,
@@ -868,6 +1237,15 @@ pub struct ConfigToml {
     /// Override to force-enable reasoning summaries for the configured model.
     pub model_supports_reasoning_summaries: Option<bool>,
 
+    /// Whether the configured model accepts images. Image attachments are
+    /// refused for models that do not; set this for models Codex does not know.
+    pub model_supports_images: Option<bool>,
+
+    /// Whether the configured model supports native tool (function) calling.
+    /// When false, tools are described in the instructions and the model calls
+    /// them with fenced JSON blocks instead.
+    pub model_supports_tool_calls: Option<bool>,
+
     /// EXPERIMENTAL
     /// Optionally specify a personality for the model
     pub model_personality: Option<Personality>,
@@ -886,6 +1264,16 @@ pub struct ConfigToml {
     /// Agent-related settings (thread limits, etc.).
     pub agents: Option<AgentsToml>,
 
+    /// Subagent discovery settings (additional search directories).
+    pub subagents: Option<SubAgentsToml>,
+
+    /// How auto-compaction shrinks the conversation history.
+    pub compaction: Option<CompactionToml>,
+
+    /// Overrides applied while a matching git branch is checked out, keyed
+    /// by branch name or glob pattern (e.g. `main`, `codex/*`).
+    pub branches: Option<BTreeMap<String, BranchConfigToml>>,
+
     /// User-level skill config entries keyed by SKILL.md path.
     pub skills: Option<SkillsConfig>,
 
@@ -1004,6 +1392,30 @@ pub struct AgentsToml {
     pub max_threads: Option<usize>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SubAgentsToml {
+    /// Additional directories scanned for `<name>.md` subagent definitions,
+    /// after `<git root>/.codex/agents` and `$CODEX_HOME/agents`. Earlier
+    /// entries win when the same name appears more than once.
+    pub extra_dirs: Option<Vec<AbsolutePathBuf>>,
+    /// Run each subagent in its own git worktree and stage the resulting
+    /// changes for review instead of editing the working tree directly.
+    pub stage_changes: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct CompactionToml {
+    /// Strategy used when the history reaches the auto-compaction limit.
+    pub strategy: Option<CompactionStrategy>,
+    /// Share of the context window, in percent, that the history is shrunk
+    /// to. `summarize_oldest` keeps the most recent turns that fit in this
+    /// budget verbatim; the other strategies default to 50.
+    #[schemars(range(min = 1, max = 100))]
+    pub target_context_percent: Option<u8>,
+}
+
 impl From<ToolsToml> for Tools {
     fn from(tools_toml: ToolsToml) -> Self {
         Self {
@@ -1063,6 +1475,7 @@ impl ConfigToml {
                     network_access,
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
+                    ..
                 }) => SandboxPolicy::WorkspaceWrite {
                     writable_roots: writable_roots.clone(),
                     network_access: *network_access,
@@ -1117,16 +1530,7 @@ impl ConfigToml {
         let profile = override_profile.or_else(|| self.profile.clone());
 
         match profile {
-            Some(key) => {
-                if let Some(profile) = self.profiles.get(key.as_str()) {
-                    return Ok(profile.clone());
-                }
-
-                Err(std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    format!("config profile `{key}` not found"),
-                ))
-            }
+            Some(key) => resolve_profile(&self.profiles, &key),
             None => Ok(ConfigProfile::default()),
         }
     }
@@ -1181,6 +1585,47 @@ pub fn resolve_oss_provider(
     }
 }
 
+/// Whether a `[branches]` setting passes `requirements.toml`. Forbidden
+/// settings are dropped with a warning when the config loads; otherwise every
+/// turn on a matching branch would fail to apply them.
+fn branch_setting_allowed(pattern: &str, key: &str, allowed: ConstraintResult<()>) -> bool {
+    match allowed {
+        Ok(()) => true,
+        Err(err) => {
+            tracing::warn!("ignoring {key} in [branches.\"{pattern}\"]: {err}");
+            false
+        }
+    }
+}
+
+/// Resolve the container sandbox from `[sandbox]`; `None` unless the
+/// container backend is selected.
+fn resolve_container_sandbox(
+    sandbox: Option<&SandboxToml>,
+) -> std::io::Result<Option<ContainerSandboxConfig>> {
+    let Some(sandbox) = sandbox else {
+        return Ok(None);
+    };
+    if sandbox.backend.unwrap_or_default() != SandboxBackendKind::Container {
+        return Ok(None);
+    }
+    let container = sandbox.container.clone().unwrap_or_default();
+    let image = container
+        .image
+        .filter(|image| !image.trim().is_empty())
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "sandbox.container.image is required when sandbox.backend = \"container\"",
+            )
+        })?;
+    Ok(Some(ContainerSandboxConfig {
+        runtime: container.runtime.unwrap_or_default(),
+        image,
+        args: container.args,
+    }))
+}
+
 /// Resolve the web search mode from explicit config and feature flags.
 fn resolve_web_search_mode(
     config_toml: &ConfigToml,
@@ -1245,16 +1690,7 @@ impl Config {
             .or(cfg.profile.as_ref())
             .cloned();
         let config_profile = match active_profile_name.as_ref() {
-            Some(key) => cfg
-                .profiles
-                .get(key)
-                .ok_or_else(|| {
-                    std::io::Error::new(
-                        std::io::ErrorKind::NotFound,
-                        format!("config profile `{key}` not found"),
-                    )
-                })?
-                .clone(),
+            Some(key) => resolve_profile(&cfg.profiles, key)?,
             None => ConfigProfile::default(),
         };
 
@@ -1263,8 +1699,21 @@ impl Config {
             web_search_request: override_tools_web_search_request,
         };
 
-        let features = Features::from_config(&cfg, &config_profile, feature_overrides);
-        let web_search_mode = resolve_web_search_mode(&cfg, &config_profile, &features);
+        let mut features = Features::from_config(&cfg, &config_profile, feature_overrides);
+        let offline = cfg.offline.clone().and_then(OfflineToml::resolve);
+        let offline_blocks = |tool: OfflineTool| {
+            offline
+                .as_ref()
+                .is_some_and(|offline| !offline.allows_tool(tool))
+        };
+        if offline_blocks(OfflineTool::WebFetch) {
+            features.disable(Feature::WebFetch);
+        }
+        let web_search_mode = if offline_blocks(OfflineTool::WebSearch) {
+            Some(WebSearchMode::Disabled)
+        } else {
+            resolve_web_search_mode(&cfg, &config_profile, &features)
+        };
         #[cfg(target_os = "windows")]
         {
             // Base flag controls sandbox on/off; elevated only applies when base is enabled.
@@ -1274,6 +1723,10 @@ impl Config {
                 sandbox_enabled && features.enabled(Feature::WindowsSandboxElevated);
             crate::safety::set_windows_elevated_sandbox_enabled(elevated_enabled);
         }
+        // Must be set before the sandbox policy is derived so Windows does not
+        // downgrade workspace-write when the container backend is selected.
+        let container_sandbox = resolve_container_sandbox(cfg.sandbox.as_ref())?;
+        crate::safety::set_container_sandbox(container_sandbox.clone());
 
         let resolved_cwd = {
             use std::env;
@@ -1301,18 +1754,23 @@ impl Config {
             .get_active_project(&resolved_cwd)
             .unwrap_or(ProjectConfig { trust_level: None });
 
-        let SandboxPolicyResolution {
-            policy: mut sandbox_policy,
-            forced_auto_mode_downgraded_on_windows,
-        } = cfg.derive_sandbox_policy(sandbox_mode, config_profile.sandbox_mode, &resolved_cwd);
-        if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &mut sandbox_policy {
-            for path in additional_writable_roots {
-                if !writable_roots.iter().any(|existing| existing == &path) {
-                    writable_roots.push(path);
+        let resolve_sandbox_policy = |sandbox_mode: Option<SandboxMode>| {
+            let mut resolution =
+                cfg.derive_sandbox_policy(sandbox_mode, config_profile.sandbox_mode, &resolved_cwd);
+            if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &mut resolution.policy {
+                for path in &additional_writable_roots {
+                    if !writable_roots.contains(path) {
+                        writable_roots.push(path.clone());
+                    }
                 }
             }
-        }
-        let approval_policy = approval_policy_override
+            resolution
+        };
+        let SandboxPolicyResolution {
+            policy: base_sandbox_policy,
+            forced_auto_mode_downgraded_on_windows,
+        } = resolve_sandbox_policy(sandbox_mode);
+        let base_approval_policy = approval_policy_override
             .or(config_profile.approval_policy)
             .or(cfg.approval_policy)
             .unwrap_or_else(|| {
@@ -1324,6 +1782,47 @@ impl Config {
                     AskForApproval::default()
                 }
             });
+        // Branch rules sit between explicit CLI overrides and the profile.
+        let branch_config = cfg
+            .branches
+            .as_ref()
+            .filter(|branches| !branches.is_empty())
+            .map(|branches| BranchConfig {
+                branch: checked_out_branch(&resolved_cwd),
+                rules: branches
+                    .iter()
+                    .map(|(pattern, branch_toml)| BranchRule {
+                        pattern: pattern.clone(),
+                        approval_policy: branch_toml
+                            .approval_policy
+                            .filter(|_| approval_policy_override.is_none())
+                            .filter(|policy| {
+                                branch_setting_allowed(
+                                    pattern,
+                                    "approval_policy",
+                                    requirements.approval_policy.can_set(policy),
+                                )
+                            }),
+                        sandbox_policy: branch_toml
+                            .sandbox_mode
+                            .filter(|_| sandbox_mode.is_none())
+                            .map(|mode| resolve_sandbox_policy(Some(mode)).policy)
+                            .filter(|policy| {
+                                branch_setting_allowed(
+                                    pattern,
+                                    "sandbox_mode",
+                                    requirements.sandbox_policy.can_set(policy),
+                                )
+                            }),
+                    })
+                    .collect(),
+                base_approval_policy,
+                base_sandbox_policy: base_sandbox_policy.clone(),
+            });
+        let (approval_policy, sandbox_policy) = match &branch_config {
+            Some(branch_config) => branch_config.resolve(branch_config.branch.as_deref()),
+            None => (base_approval_policy, base_sandbox_policy),
+        };
         // TODO(dylan): We should be able to leverage ConfigLayerStack so that
         // we can reliably check this at every config level.
         let did_user_set_custom_approval_policy_or_sandbox_mode = approval_policy_override
@@ -1346,10 +1845,23 @@ impl Config {
             model_providers.entry(key).or_insert(provider);
         }
 
+        // `model = "mock"` implies the built-in mock provider unless a
+        // provider was chosen explicitly.
+        let is_mock_model = model
+            .as_deref()
+            .or(config_profile.model.as_deref())
+            .or(cfg.model.as_deref())
+            == Some(MOCK_MODEL);
         let model_provider_id = model_provider
             .or(config_profile.model_provider)
             .or(cfg.model_provider)
-            .unwrap_or_else(|| "openai".to_string());
+            .unwrap_or_else(|| {
+                if is_mock_model {
+                    MOCK_PROVIDER_ID.to_string()
+                } else {
+                    "openai".to_string()
+                }
+            });
         let model_provider = model_providers
             .get(&model_provider_id)
             .ok_or_else(|| {
@@ -1359,8 +1871,38 @@ impl Config {
                 )
             })?
             .clone();
+        if let Some(offline) = &offline
+            && model_provider_id != MOCK_PROVIDER_ID
+        {
+            offline
+                .check_url(
+                    &format!("model provider `{model_provider_id}`"),
+                    &model_provider.base_url_or_default(None),
+                )
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+            if let Some(entra_id) = &model_provider.entra_id {
+                offline
+                    .check_url(
+                        &format!("Entra ID sign-in for `{model_provider_id}`"),
+                        entra_id.authority(),
+                    )
+                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+            }
+        }
 
         let shell_environment_policy = cfg.shell_environment_policy.into();
+        let network_allowlist = cfg
+            .sandbox_workspace_write
+            .as_ref()
+            .map(|sandbox| sandbox.network_allowlist.clone())
+            .unwrap_or_default();
+        let read_only_commands = cfg
+            .read_only_commands
+            .iter()
+            .flatten()
+            .filter_map(|prefix| shlex::split(prefix))
+            .filter(|argv| !argv.is_empty())
+            .collect();
 
         let history = cfg.history.unwrap_or_default();
 
@@ -1376,6 +1918,76 @@ impl Config {
             ));
         }
 
+        if let Some(provider) = &cfg.web_search_provider
+            && matches!(
+                provider.provider,
+                WebSearchProviderKind::Searxng | WebSearchProviderKind::Custom
+            )
+            && provider.endpoint.is_none()
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "web_search_provider.endpoint is required for searxng and custom providers",
+            ));
+        }
+
+        let exec_limits = cfg.exec_limits.unwrap_or_default();
+        if exec_limits
+            .nice
+            .is_some_and(|nice| !(0..=19).contains(&nice))
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "exec_limits.nice must be between 0 and 19",
+            ));
+        }
+
+        if let Some(semantic_index) = &cfg.semantic_index
+            && semantic_index.embedding == Some(EmbeddingBackend::Model)
+            && semantic_index
+                .command
+                .as_ref()
+                .is_none_or(|command| command.is_empty())
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "semantic_index.command is required when embedding = \"model\"",
+            ));
+        }
+
+        let network = cfg
+            .network
+            .clone()
+            .unwrap_or_default()
+            .resolve()
+            .map_err(|message| std::io::Error::new(std::io::ErrorKind::InvalidInput, message))?;
+        codex_client::set_network_settings(&network)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+
+        let subagent_extra_dirs = config_profile
+            .subagents
+            .as_ref()
+            .and_then(|subagents| subagents.extra_dirs.clone())
+            .or_else(|| {
+                cfg.subagents
+                    .as_ref()
+                    .and_then(|subagents| subagents.extra_dirs.clone())
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .map(AbsolutePathBuf::into_path_buf)
+            .collect();
+        let subagent_stage_changes = config_profile
+            .subagents
+            .as_ref()
+            .and_then(|subagents| subagents.stage_changes)
+            .or_else(|| {
+                cfg.subagents
+                    .as_ref()
+                    .and_then(|subagents| subagents.stage_changes)
+            })
+            .unwrap_or(false);
+
         let ghost_snapshot = {
             let mut config = GhostSnapshotConfig::default();
             if let Some(ghost_snapshot) = cfg.ghost_snapshot.as_ref()
@@ -1453,12 +2065,20 @@ impl Config {
 
         let check_for_update_on_startup = cfg.check_for_update_on_startup.unwrap_or(true);
 
+        requirements.check_model_provider(&model_provider_id)?;
+        let mut audit = cfg.audit.clone();
+        if requirements.audit_log_required() {
+            audit.get_or_insert_with(AuditToml::default).enabled = Some(true);
+        }
+
         // Ensure that every field of ConfigRequirements is applied to the final
-        // Config.
+        // Config; the last two were applied above.
         let ConfigRequirements {
             approval_policy: mut constrained_approval_policy,
             sandbox_policy: mut constrained_sandbox_policy,
             mcp_servers,
+            allowed_model_providers: _,
+            require_audit_log: _,
         } = requirements;
 
         constrained_approval_policy
@@ -1468,23 +2088,46 @@ impl Config {
             .set(sandbox_policy)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{e}")))?;
 
-        let mcp_servers = constrain_mcp_servers(cfg.mcp_servers.clone(), mcp_servers.as_ref())
+        let is_offline = offline.is_some();
+        let mut configured_mcp_servers = cfg.mcp_servers.clone();
+        if let Some(offline) = &offline {
+            disable_remote_mcp_servers(&mut configured_mcp_servers, offline);
+        }
+        let mcp_servers = constrain_mcp_servers(configured_mcp_servers, mcp_servers.as_ref())
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{e}")))?;
 
         let config = Self {
             model,
             did_user_override_model,
             review_model,
+            review_split_by_area: cfg.review_split_by_area.unwrap_or(false),
             model_context_window: cfg.model_context_window,
             model_auto_compact_token_limit: cfg.model_auto_compact_token_limit,
+            compaction_strategy: cfg
+                .compaction
+                .as_ref()
+                .and_then(|compaction| compaction.strategy)
+                .unwrap_or_default(),
+            compaction_target_context_percent: cfg
+                .compaction
+                .as_ref()
+                .and_then(|compaction| compaction.target_context_percent)
+                .map(|percent| percent.clamp(1, 100)),
             model_provider_id,
             model_provider,
+            mock_responses_file: cfg.mock_responses_file.map(AbsolutePathBuf::into_path_buf),
             cwd: resolved_cwd,
             approval_policy: constrained_approval_policy,
             sandbox_policy: constrained_sandbox_policy,
             did_user_set_custom_approval_policy_or_sandbox_mode,
             forced_auto_mode_downgraded_on_windows,
+            network_allowlist,
+            container_sandbox,
+            exec_limits,
             shell_environment_policy,
+            read_only_commands,
+            approval_timeouts: cfg.approval_timeouts.clone().unwrap_or_default().into(),
+            hosted_tools: cfg.hosted_tools.clone(),
             notify: cfg.notify,
             user_instructions,
             base_instructions,
@@ -1499,6 +2142,44 @@ impl Config {
             // is important in code to differentiate the mode from the store implementation.
             mcp_oauth_credentials_store_mode: cfg.mcp_oauth_credentials_store.unwrap_or_default(),
             mcp_oauth_callback_port: cfg.mcp_oauth_callback_port,
+            mcp_sampling: cfg.mcp_sampling.clone().unwrap_or_default().into(),
+            mcp_health: cfg.mcp_health.clone().unwrap_or_default().into(),
+            web_fetch: cfg.web_fetch.clone().unwrap_or_default().into(),
+            web_search_provider: cfg
+                .web_search_provider
+                .clone()
+                .filter(|_| !offline_blocks(OfflineTool::WebSearch)),
+            browser: cfg
+                .browser
+                .clone()
+                .filter(|_| !offline_blocks(OfflineTool::Browser))
+                .map(Into::into),
+            governor: cfg.governor.clone().map(Into::into),
+            audit: audit.and_then(AuditToml::resolve),
+            telemetry: if is_offline {
+                None
+            } else {
+                cfg.telemetry.clone().and_then(TelemetryToml::resolve)
+            },
+            worktree_isolation: cfg
+                .worktree_isolation
+                .clone()
+                .and_then(WorktreeIsolationToml::resolve),
+            gemini_safety_settings: cfg
+                .gemini
+                .clone()
+                .map(|gemini| gemini.safety_settings)
+                .unwrap_or_default(),
+            local_models: cfg.local_models.clone().unwrap_or_default().into(),
+            network,
+            offline,
+            guardrail: cfg.guardrail.clone().unwrap_or_default().into(),
+            timebox: cfg.timebox.clone().unwrap_or_default().into(),
+            pre_commit: cfg.pre_commit.clone().unwrap_or_default().into(),
+            budget: cfg.budget.clone().unwrap_or_default().into(),
+            semantic_index: cfg.semantic_index.clone().unwrap_or_default().into(),
+            hooks: cfg.hooks.clone(),
+            context_providers: cfg.context_providers.clone(),
             model_providers,
             project_doc_max_bytes: cfg.project_doc_max_bytes.unwrap_or(PROJECT_DOC_MAX_BYTES),
             project_doc_fallback_filenames: cfg
@@ -1516,6 +2197,9 @@ impl Config {
                 .collect(),
             tool_output_token_limit: cfg.tool_output_token_limit,
             agent_max_threads,
+            subagent_extra_dirs,
+            subagent_stage_changes,
+            branch_config,
             codex_home,
             config_layer_stack,
             history,
@@ -1535,6 +2219,8 @@ impl Config {
                 .or(cfg.model_reasoning_summary)
                 .unwrap_or_default(),
             model_supports_reasoning_summaries: cfg.model_supports_reasoning_summaries,
+            model_supports_images: cfg.model_supports_images,
+            model_supports_tool_calls: cfg.model_supports_tool_calls,
             model_verbosity: config_profile.model_verbosity.or(cfg.model_verbosity),
             chatgpt_base_url: config_profile
                 .chatgpt_base_url
@@ -1551,23 +2237,38 @@ impl Config {
             active_project,
             windows_wsl_setup_acknowledged: cfg.windows_wsl_setup_acknowledged.unwrap_or(false),
             notices: cfg.notice.unwrap_or_default(),
-            check_for_update_on_startup,
+            check_for_update_on_startup: check_for_update_on_startup && !is_offline,
             disable_paste_burst: cfg.disable_paste_burst.unwrap_or(false),
-            analytics_enabled: config_profile
-                .analytics
-                .as_ref()
-                .and_then(|a| a.enabled)
-                .or(cfg.analytics.as_ref().and_then(|a| a.enabled)),
-            feedback_enabled: cfg
-                .feedback
-                .as_ref()
-                .and_then(|feedback| feedback.enabled)
-                .unwrap_or(true),
+            analytics_enabled: if is_offline {
+                Some(false)
+            } else {
+                config_profile
+                    .analytics
+                    .as_ref()
+                    .and_then(|a| a.enabled)
+                    .or(cfg.analytics.as_ref().and_then(|a| a.enabled))
+            },
+            feedback_enabled: !is_offline
+                && cfg
+                    .feedback
+                    .as_ref()
+                    .and_then(|feedback| feedback.enabled)
+                    .unwrap_or(true),
             tui_notifications: cfg
                 .tui
                 .as_ref()
                 .map(|t| t.notifications.clone())
                 .unwrap_or_default(),
+            tui_notification_method: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.notification_method)
+                .unwrap_or_default(),
+            tui_notification_min_turn_sec: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.notification_min_turn_sec)
+                .unwrap_or_default(),
             animations: cfg.tui.as_ref().map(|t| t.animations).unwrap_or(true),
             show_tooltips: cfg.tui.as_ref().map(|t| t.show_tooltips).unwrap_or(true),
             experimental_mode: cfg.tui.as_ref().and_then(|t| t.experimental_mode),
@@ -1576,6 +2277,33 @@ impl Config {
                 .as_ref()
                 .map(|t| t.alternate_screen)
                 .unwrap_or_default(),
+            tui_voice: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.voice.clone())
+                .map(Into::into),
+            tui_keybindings: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.keybindings.clone())
+                .unwrap_or_default(),
+            tui_theme: cfg.tui.as_ref().and_then(|t| t.theme.clone()),
+            tui_theme_variant: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.theme_variant)
+                .unwrap_or_default(),
+            tui_theme_palette: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.theme_palette.clone())
+                .unwrap_or_default(),
+            tui_status_line: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.status_line.clone())
+                .unwrap_or_default(),
+            tui_editing_mode: cfg.tui.as_ref().map(|t| t.editing_mode).unwrap_or_default(),
             otel: {
                 let t: OtelConfigToml = cfg.otel.unwrap_or_default();
                 let log_user_prompt = t.log_user_prompt.unwrap_or(false);
@@ -1584,12 +2312,22 @@ impl Config {
                     .unwrap_or(DEFAULT_OTEL_ENVIRONMENT.to_string());
                 let exporter = t.exporter.unwrap_or(OtelExporterKind::None);
                 let trace_exporter = t.trace_exporter.unwrap_or_else(|| exporter.clone());
-                OtelConfig {
-                    log_user_prompt,
-                    environment,
-                    exporter,
-                    trace_exporter,
-                    metrics_exporter: OtelExporterKind::Statsig,
+                if is_offline {
+                    OtelConfig {
+                        log_user_prompt,
+                        environment,
+                        exporter: OtelExporterKind::None,
+                        trace_exporter: OtelExporterKind::None,
+                        metrics_exporter: OtelExporterKind::None,
+                    }
+                } else {
+                    OtelConfig {
+                        log_user_prompt,
+                        environment,
+                        exporter,
+                        trace_exporter,
+                        metrics_exporter: OtelExporterKind::Statsig,
+                    }
                 }
             },
         };
@@ -1658,6 +2396,15 @@ impl Config {
             self.features.disable(Feature::WindowsSandboxElevated);
         }
     }
+
+    /// Hosted tools configured for `model`, falling back to the `"*"` entry.
+    pub fn hosted_tools_for(&self, model: &str) -> HostedToolsToml {
+        self.hosted_tools
+            .get(model)
+            .or_else(|| self.hosted_tools.get("*"))
+            .cloned()
+            .unwrap_or_default()
+    }
 }
 
 pub(crate) fn uses_deprecated_instructions_file(config_layer_stack: &ConfigLayerStack) -> bool {
@@ -1724,14 +2471,21 @@ mod tests {
     use crate::config::edit::ConfigEdit;
     use crate::config::edit::ConfigEditsBuilder;
     use crate::config::edit::apply_blocking;
+    use crate::config::types::ContainerRuntime;
     use crate::config::types::FeedbackConfigToml;
     use crate::config::types::HistoryPersistence;
+    use crate::config::types::HostedFileSearchToml;
+    use crate::config::types::KeyList;
     use crate::config::types::McpServerTransportConfig;
     use crate::config::types::Notifications;
+    use crate::config::types::WorktreeScope;
     use crate::config_loader::RequirementSource;
     use crate::features::Feature;
 
     use super::*;
+    use crate::config::types::OtelHttpProtocol;
+    use codex_client::ProxyBasicAuth;
+    use codex_client::ProxySettings;
     use core_test_support::test_absolute_path;
     use pretty_assertions::assert_eq;
 
@@ -1755,6 +2509,8 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            default_tool_approval: None,
+            tool_approvals: None,
         }
     }
 
@@ -1772,6 +2528,8 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            default_tool_approval: None,
+            tool_approvals: None,
         }
     }
 
@@ -1807,6 +2565,39 @@ persistence = "none"
         );
     }
 
+    #[test]
+    fn hosted_tools_fall_back_to_wildcard_entry() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[hosted_tools."*"]
+code_interpreter = true
+
+[hosted_tools."gpt-5.1"]
+file_search = { vector_store_ids = ["vs_1"], max_num_results = 4 }
+"#,
+        )
+        .expect("hosted_tools should parse");
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.hosted_tools_for("gpt-5.1"),
+            HostedToolsToml {
+                code_interpreter: false,
+                file_search: Some(HostedFileSearchToml {
+                    vector_store_ids: vec!["vs_1".to_string()],
+                    max_num_results: Some(4),
+                }),
+            }
+        );
+        assert!(config.hosted_tools_for("gpt-5.1-codex").code_interpreter);
+        Ok(())
+    }
+
     #[test]
     fn tui_config_missing_notifications_field_defaults_to_enabled() {
         let cfg = r#"
@@ -1821,14 +2612,125 @@ persistence = "none"
             tui,
             Tui {
                 notifications: Notifications::Enabled(true),
+                notification_method: NotificationMethod::Auto,
+                notification_min_turn_sec: 0,
                 animations: true,
                 show_tooltips: true,
                 experimental_mode: None,
                 alternate_screen: AltScreenMode::Auto,
+                voice: None,
+                keybindings: None,
+                theme: None,
+                theme_variant: ThemeVariant::Auto,
+                theme_palette: None,
+                status_line: None,
+                editing_mode: EditingMode::Emacs,
             }
         );
     }
 
+    #[test]
+    fn tui_theme_settings_parse() {
+        let cfg = r##"
+[tui]
+theme = "solarized"
+theme_variant = "light"
+
+[tui.theme_palette]
+accent = "#268bd2"
+error = "light-red"
+"##;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("theme settings should parse");
+        let tui = parsed.tui.expect("tui section");
+        assert_eq!(tui.theme.as_deref(), Some("solarized"));
+        assert_eq!(tui.theme_variant, ThemeVariant::Light);
+        assert_eq!(
+            tui.theme_palette,
+            Some(ThemePaletteToml {
+                accent: Some("#268bd2".to_string()),
+                error: Some("light-red".to_string()),
+                ..Default::default()
+            })
+        );
+        assert!(toml::from_str::<ConfigToml>("[tui]\ntheme_variant = \"dim\"\n").is_err());
+    }
+
+    #[test]
+    fn tui_notification_method_and_min_turn_parse() {
+        let cfg = r#"
+[tui]
+notifications = ["approval-requested"]
+notification_method = "native"
+notification_min_turn_sec = 60
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("notification settings should parse");
+        let tui = parsed.tui.expect("tui section");
+        assert_eq!(tui.notification_method, NotificationMethod::Native);
+        assert_eq!(tui.notification_min_turn_sec, 60);
+        assert!(toml::from_str::<ConfigToml>("[tui]\nnotification_method = \"bell\"\n").is_err());
+    }
+
+    #[test]
+    fn tui_status_line_items_parse() {
+        let cfg = r#"
+[tui]
+status_line = ["model", "context", "cost", "tasks"]
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("status line should parse");
+        assert_eq!(
+            parsed.tui.and_then(|tui| tui.status_line),
+            Some(vec![
+                StatusLineItem::Model,
+                StatusLineItem::Context,
+                StatusLineItem::Cost,
+                StatusLineItem::Tasks,
+            ])
+        );
+        assert!(toml::from_str::<ConfigToml>("[tui]\nstatus_line = [\"clock\"]\n").is_err());
+    }
+
+    #[test]
+    fn tui_editing_mode_parses() {
+        let parsed = toml::from_str::<ConfigToml>("[tui]\nediting_mode = \"vim\"\n")
+            .expect("editing mode should parse");
+        assert_eq!(
+            parsed.tui.map(|tui| tui.editing_mode),
+            Some(EditingMode::Vim)
+        );
+        assert!(toml::from_str::<ConfigToml>("[tui]\nediting_mode = \"helix\"\n").is_err());
+    }
+
+    #[test]
+    fn tui_keybindings_accept_a_key_or_a_list() {
+        let cfg = r#"
+[tui.keybindings]
+submit = "ctrl-enter"
+newline = ["enter", "shift-enter"]
+deny = []
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("keybindings should parse");
+        let keybindings = parsed
+            .tui
+            .and_then(|tui| tui.keybindings)
+            .expect("keybindings section");
+
+        assert_eq!(
+            keybindings.submit.as_ref().map(KeyList::keys),
+            Some(&["ctrl-enter".to_string()][..])
+        );
+        assert_eq!(
+            keybindings.newline.as_ref().map(KeyList::keys),
+            Some(&["enter".to_string(), "shift-enter".to_string()][..])
+        );
+        assert_eq!(keybindings.deny, Some(KeyList::Many(Vec::new())));
+        assert_eq!(keybindings.approve, None);
+        assert!(toml::from_str::<ConfigToml>("[tui.keybindings]\nsubmt = \"enter\"\n").is_err());
+    }
+
     #[test]
     fn test_sandbox_config_parsing() {
         let sandbox_full_access = r#"
@@ -2156,6 +3058,290 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn network_allowlist_keeps_sandbox_network_disabled() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+sandbox_mode = "workspace-write"
+
+[sandbox_workspace_write]
+network_allowlist = ["crates.io", "github.com"]
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.network_allowlist,
+            vec!["crates.io".to_string(), "github.com".to_string()]
+        );
+        assert!(!config.sandbox_policy.get().has_full_network_access());
+
+        Ok(())
+    }
+
+    #[test]
+    fn container_sandbox_requires_image() {
+        let parse = |toml: &str| {
+            toml::from_str::<ConfigToml>(toml)
+                .expect("TOML deserialization should succeed")
+                .sandbox
+        };
+
+        let sandbox = parse(
+            r#"
+[sandbox]
+backend = "container"
+
+[sandbox.container]
+image = "ubuntu:24.04"
+runtime = "podman"
+args = ["--memory=4g"]
+"#,
+        );
+        assert_eq!(
+            resolve_container_sandbox(sandbox.as_ref()).expect("valid container sandbox"),
+            Some(ContainerSandboxConfig {
+                runtime: ContainerRuntime::Podman,
+                image: "ubuntu:24.04".to_string(),
+                args: vec!["--memory=4g".to_string()],
+            })
+        );
+
+        let missing_image = parse("[sandbox]\nbackend = \"container\"\n");
+        assert!(resolve_container_sandbox(missing_image.as_ref()).is_err());
+
+        let platform = parse("[sandbox.container]\nimage = \"ubuntu:24.04\"\n");
+        assert_eq!(
+            resolve_container_sandbox(platform.as_ref()).expect("platform backend"),
+            None
+        );
+    }
+
+    #[test]
+    fn audit_section_enables_log() {
+        let parse = |toml: &str| {
+            toml::from_str::<ConfigToml>(toml)
+                .expect("TOML deserialization should succeed")
+                .audit
+                .and_then(AuditToml::resolve)
+        };
+
+        assert_eq!(
+            parse("[audit]\nmax_files = 3\n"),
+            Some(AuditConfig {
+                max_file_bytes: 10 * 1024 * 1024,
+                max_files: Some(3),
+            })
+        );
+        assert_eq!(parse("[audit]\nenabled = false\n"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn telemetry_section_resolves_exporters() {
+        let parse = |toml: &str| {
+            toml::from_str::<ConfigToml>(toml)
+                .expect("TOML deserialization should succeed")
+                .telemetry
+                .and_then(TelemetryToml::resolve)
+        };
+
+        assert_eq!(
+            parse(
+                r#"
+[telemetry]
+endpoint = "http://localhost:4318/"
+protocol = "http/json"
+traces = false
+"#
+            ),
+            Some(TelemetryConfig {
+                trace_exporter: None,
+                metrics_exporter: Some(OtelExporterKind::OtlpHttp {
+                    endpoint: "http://localhost:4318/v1/metrics".to_string(),
+                    headers: HashMap::new(),
+                    protocol: OtelHttpProtocol::Json,
+                    tls: None,
+                }),
+                service_name: None,
+            })
+        );
+        let grpc = OtelExporterKind::OtlpGrpc {
+            endpoint: "http://localhost:4317".to_string(),
+            headers: HashMap::new(),
+            tls: None,
+        };
+        assert_eq!(
+            parse("[telemetry]\nendpoint = \"http://localhost:4317\"\n"),
+            Some(TelemetryConfig {
+                trace_exporter: Some(grpc.clone()),
+                metrics_exporter: Some(grpc),
+                service_name: None,
+            })
+        );
+        assert_eq!(
+            parse("[telemetry]\nendpoint = \"http://localhost:4317\"\nenabled = false\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn managed_requirements_keep_audit_log_on_and_restrict_providers() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let stack = ConfigLayerStack::new(
+            Vec::new(),
+            ConfigRequirements {
+                allowed_model_providers: Some(Sourced::new(
+                    vec!["openai".to_string()],
+                    RequirementSource::Unknown,
+                )),
+                require_audit_log: Some(Sourced::new(true, RequirementSource::Unknown)),
+                ..Default::default()
+            },
+            crate::config_loader::ConfigRequirementsToml::default(),
+        )?;
+        let load = |toml: &str| {
+            Config::load_config_with_layer_stack(
+                toml::from_str(toml).expect("TOML deserialization should succeed"),
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+                stack.clone(),
+            )
+        };
+
+        let config = load("[audit]\nenabled = false\n")?;
+        assert_eq!(
+            config.audit,
+            Some(AuditConfig {
+                max_file_bytes: 10 * 1024 * 1024,
+                max_files: None,
+            })
+        );
+
+        let err = load("model_provider = \"ollama\"\n").expect_err("provider is not allowed");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("`model_provider`"));
+        Ok(())
+    }
+
+    #[test]
+    fn worktree_isolation_section_enables_isolation() {
+        let parse = |toml: &str| {
+            toml::from_str::<ConfigToml>(toml)
+                .expect("TOML deserialization should succeed")
+                .worktree_isolation
+                .and_then(WorktreeIsolationToml::resolve)
+        };
+
+        assert_eq!(
+            parse("[worktree_isolation]\n"),
+            Some(WorktreeIsolationConfig {
+                scope: WorktreeScope::Session,
+                branch_prefix: "codex/".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("[worktree_isolation]\nscope = \"turn\"\nbranch_prefix = \"agent/\"\n"),
+            Some(WorktreeIsolationConfig {
+                scope: WorktreeScope::Turn,
+                branch_prefix: "agent/".to_string(),
+            })
+        );
+        assert_eq!(parse("[worktree_isolation]\nenabled = false\n"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn local_models_section_lists_extra_providers() {
+        let parse = |toml: &str| -> LocalModelsConfig {
+            toml::from_str::<ConfigToml>(toml)
+                .expect("TOML deserialization should succeed")
+                .local_models
+                .unwrap_or_default()
+                .into()
+        };
+
+        assert_eq!(parse(""), LocalModelsConfig::default());
+        assert_eq!(
+            parse("[local_models]\nprobe = false\nproviders = [\"vllm\"]\n"),
+            LocalModelsConfig {
+                probe: false,
+                providers: vec!["vllm".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn network_section_resolves_proxy_and_certificates() -> anyhow::Result<()> {
+        let base = TempDir::new()?;
+        let parse = |toml: &str| -> Result<NetworkSettings, String> {
+            let _guard = AbsolutePathBufGuard::new(base.path());
+            toml::from_str::<ConfigToml>(toml)
+                .expect("TOML deserialization should succeed")
+                .network
+                .unwrap_or_default()
+                .resolve()
+        };
+
+        assert_eq!(parse(""), Ok(NetworkSettings::default()));
+        assert_eq!(
+            parse(
+                r#"
+[network]
+proxy = "http://proxy.corp:8080"
+no_proxy = ["localhost", ".corp"]
+proxy_username = "alice"
+ca_certificates = ["corp-ca.pem"]
+"#
+            ),
+            Ok(NetworkSettings {
+                proxy: Some(ProxySettings {
+                    url: "http://proxy.corp:8080".to_string(),
+                    no_proxy: vec!["localhost".to_string(), ".corp".to_string()],
+                    basic_auth: Some(ProxyBasicAuth {
+                        username: "alice".to_string(),
+                        password: String::new(),
+                    }),
+                }),
+                ca_certificates: vec![base.path().join("corp-ca.pem")],
+            })
+        );
+        assert!(parse("[network]\nproxy_username = \"alice\"\n").is_err());
+        assert!(
+            parse("[network]\nproxy = \"http://proxy\"\nproxy_password_env = \"X\"\n").is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn gemini_section_parses_safety_settings() {
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[[gemini.safety_settings]]
+category = "HARM_CATEGORY_DANGEROUS_CONTENT"
+threshold = "BLOCK_ONLY_HIGH"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        assert_eq!(
+            cfg.gemini,
+            Some(GeminiToml {
+                safety_settings: vec![GeminiSafetySetting {
+                    category: "HARM_CATEGORY_DANGEROUS_CONTENT".to_string(),
+                    threshold: "BLOCK_ONLY_HIGH".to_string(),
+                }],
+            })
+        );
+    }
+
     #[test]
     fn config_defaults_to_file_cli_auth_store_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2175,6 +3361,59 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn offline_mode_requires_a_local_provider_and_turns_off_network_features() -> anyhow::Result<()>
+    {
+        let codex_home = TempDir::new()?;
+        let load = |toml: &str| {
+            let cfg: ConfigToml =
+                toml::from_str(toml).expect("TOML deserialization should succeed");
+            Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+        };
+
+        let err = load("[offline]\n").expect_err("the OpenAI provider is not local");
+        assert_eq!(
+            err.to_string(),
+            "offline mode: model provider `openai` needs `https://api.openai.com/v1`, which is not a local endpoint; add its host to `offline.allow_hosts` or disable offline mode"
+        );
+
+        let config = load(
+            r#"
+model_provider = "ollama"
+
+[features]
+web_fetch = true
+
+[mcp_servers.local]
+url = "http://127.0.0.1:9000/mcp"
+
+[mcp_servers.remote]
+url = "https://mcp.example.com/mcp"
+
+[offline]
+"#,
+        )?;
+        assert_eq!(config.offline, Some(OfflineConfig::default()));
+        assert!(!config.check_for_update_on_startup);
+        assert_eq!(config.analytics_enabled, Some(false));
+        assert!(!config.feedback_enabled);
+        assert_eq!(config.otel.metrics_exporter, OtelExporterKind::None);
+        assert!(!config.features.enabled(Feature::WebFetch));
+        assert_eq!(config.web_search_mode, Some(WebSearchMode::Disabled));
+        let servers = config.mcp_servers.get();
+        assert!(servers["local"].enabled);
+        assert!(!servers["remote"].enabled);
+        assert!(matches!(
+            servers["remote"].disabled_reason,
+            Some(McpServerDisabledReason::Offline(_))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn config_honors_explicit_keyring_auth_store_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2304,6 +3543,199 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn profile_subagent_extra_dirs_override_base() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let shared = codex_home.path().join("shared");
+        let team = codex_home.path().join("team");
+        let mut profiles = HashMap::new();
+        profiles.insert(
+            "team".to_string(),
+            ConfigProfile {
+                subagents: Some(SubAgentsToml {
+                    extra_dirs: Some(vec![AbsolutePathBuf::from_absolute_path(&team)?]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        );
+        let cfg = ConfigToml {
+            subagents: Some(SubAgentsToml {
+                extra_dirs: Some(vec![AbsolutePathBuf::from_absolute_path(&shared)?]),
+                ..Default::default()
+            }),
+            profiles,
+            ..Default::default()
+        };
+
+        let base = Config::load_from_base_config_with_overrides(
+            cfg.clone(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        let with_profile = Config::load_from_base_config_with_overrides(
+            ConfigToml {
+                profile: Some("team".to_string()),
+                ..cfg
+            },
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            (base.subagent_extra_dirs, with_profile.subagent_extra_dirs),
+            (vec![shared], vec![team])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn mock_model_selects_mock_provider() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let fixture = codex_home.path().join("mock.json");
+        let cfg = ConfigToml {
+            model: Some("mock".to_string()),
+            mock_responses_file: Some(AbsolutePathBuf::from_absolute_path(&fixture)?),
+            ..Default::default()
+        };
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            (
+                config.model_provider_id,
+                config.model_provider,
+                config.mock_responses_file,
+            ),
+            (
+                MOCK_PROVIDER_ID.to_string(),
+                crate::model_provider_info::create_mock_provider(),
+                Some(fixture),
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn branch_overrides_apply_below_cli_overrides() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let repo = TempDir::new()?;
+        std::fs::create_dir(repo.path().join(".git"))?;
+        std::fs::write(repo.path().join(".git/HEAD"), "ref: refs/heads/main\n")?;
+        let cfg = ConfigToml {
+            approval_policy: Some(AskForApproval::OnRequest),
+            branches: Some(BTreeMap::from([(
+                "main".to_string(),
+                BranchConfigToml {
+                    approval_policy: Some(AskForApproval::UnlessTrusted),
+                    sandbox_mode: Some(SandboxMode::ReadOnly),
+                },
+            )])),
+            ..Default::default()
+        };
+
+        let on_main = Config::load_from_base_config_with_overrides(
+            cfg.clone(),
+            ConfigOverrides {
+                cwd: Some(repo.path().to_path_buf()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        let with_cli_override = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                cwd: Some(repo.path().to_path_buf()),
+                approval_policy: Some(AskForApproval::Never),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            (
+                on_main.approval_policy.value(),
+                on_main.sandbox_policy.get().clone(),
+                on_main
+                    .branch_config
+                    .as_ref()
+                    .and_then(|branch_config| branch_config.branch.clone()),
+            ),
+            (
+                AskForApproval::UnlessTrusted,
+                SandboxPolicy::new_read_only_policy(),
+                Some("main".to_string()),
+            )
+        );
+        assert_eq!(
+            (
+                with_cli_override.approval_policy.value(),
+                with_cli_override.sandbox_policy.get().clone(),
+            ),
+            (AskForApproval::Never, SandboxPolicy::new_read_only_policy())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn branch_overrides_forbidden_by_requirements_are_dropped() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let stack = ConfigLayerStack::new(
+            Vec::new(),
+            ConfigRequirements {
+                approval_policy: Constrained::new(
+                    AskForApproval::OnRequest,
+                    |policy| match policy {
+                        AskForApproval::Never => Err(ConstraintError::InvalidValue {
+                            field_name: "approval_policy",
+                            candidate: "never".to_string(),
+                            allowed: "[on-request]".to_string(),
+                            requirement_source: RequirementSource::Unknown,
+                        }),
+                        _ => Ok(()),
+                    },
+                )?,
+                ..Default::default()
+            },
+            crate::config_loader::ConfigRequirementsToml::default(),
+        )?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+approval_policy = "on-request"
+
+[branches."codex/*"]
+approval_policy = "never"
+sandbox_mode = "read-only"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let config = Config::load_config_with_layer_stack(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+            stack,
+        )?;
+
+        assert_eq!(
+            config
+                .branch_config
+                .map(|branch_config| branch_config.rules),
+            Some(vec![BranchRule {
+                pattern: "codex/*".to_string(),
+                approval_policy: None,
+                sandbox_policy: Some(SandboxPolicy::new_read_only_policy()),
+            }])
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn project_profile_overrides_user_profile() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2350,6 +3782,119 @@ model = "gpt-project"
         Ok(())
     }
 
+    #[tokio::test]
+    async fn repo_default_profile_extends_a_user_profile() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let workspace = TempDir::new()?;
+        let workspace_key = workspace.path().to_string_lossy().replace('\\', "\\\\");
+        std::fs::write(
+            codex_home.path().join(CONFIG_TOML_FILE),
+            format!(
+                r#"
+[profiles.base]
+model = "gpt-base"
+model_reasoning_effort = "high"
+features = {{ shell_snapshot = true }}
+
+[projects."{workspace_key}"]
+trust_level = "trusted"
+"#,
+            ),
+        )?;
+        std::fs::write(workspace.path().join(".git"), "gitdir: here")?;
+        std::fs::create_dir_all(workspace.path().join(".codex"))?;
+        std::fs::write(
+            workspace.path().join(".codex").join(CONFIG_TOML_FILE),
+            r#"
+profile = "repo"
+
+[profiles.repo]
+extends = "base"
+model = "gpt-repo"
+features = { web_search_request = true }
+"#,
+        )?;
+        let nested = workspace.path().join("crates");
+        std::fs::create_dir_all(&nested)?;
+
+        let config = ConfigBuilder::default()
+            .codex_home(codex_home.path().to_path_buf())
+            .harness_overrides(ConfigOverrides {
+                cwd: Some(nested),
+                ..Default::default()
+            })
+            .build()
+            .await?;
+
+        assert_eq!(config.active_profile.as_deref(), Some("repo"));
+        assert_eq!(config.model.as_deref(), Some("gpt-repo"));
+        assert_eq!(config.model_reasoning_effort, Some(ReasoningEffort::High));
+        assert!(config.features.enabled(Feature::ShellSnapshot));
+
+        Ok(())
+    }
+
+    #[test]
+    fn profiles_inherit_along_their_extends_chain() -> std::io::Result<()> {
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[profiles.base]
+model = "gpt-base"
+approval_policy = "never"
+features = { shell_snapshot = true, web_search_request = true }
+
+[profiles.team]
+extends = "base"
+sandbox_mode = "workspace-write"
+features = { web_search_request = false }
+
+[profiles.me]
+extends = "team"
+model = "gpt-me"
+
+[profiles.loop_a]
+extends = "loop_b"
+
+[profiles.loop_b]
+extends = "loop_a"
+
+[profiles.orphan]
+extends = "missing"
+"#,
+        )
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+        let me = cfg.get_config_profile(Some("me".to_string()))?;
+        assert_eq!(me.extends.as_deref(), Some("team"));
+        assert_eq!(me.model.as_deref(), Some("gpt-me"));
+        assert_eq!(me.approval_policy, Some(AskForApproval::Never));
+        assert_eq!(me.sandbox_mode, Some(SandboxMode::WorkspaceWrite));
+        assert_eq!(
+            me.features.map(|features| features.entries),
+            Some(BTreeMap::from([
+                ("shell_snapshot".to_string(), true),
+                ("web_search_request".to_string(), false),
+            ]))
+        );
+
+        assert_eq!(
+            cfg.get_config_profile(Some("loop_a".to_string()))
+                .map_err(|err| err.to_string())
+                .err()
+                .as_deref(),
+            Some("config profiles extend each other in a cycle: loop_a -> loop_b -> loop_a")
+        );
+        assert_eq!(
+            cfg.get_config_profile(Some("orphan".to_string()))
+                .map_err(|err| err.to_string())
+                .err()
+                .as_deref(),
+            Some("config profile `missing` (extended by `orphan`) not found")
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn profile_sandbox_mode_overrides_base() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2597,6 +4142,8 @@ model = "gpt-project"
                 tool_timeout_sec: Some(Duration::from_secs(5)),
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
 
@@ -2751,6 +4298,8 @@ bearer_token = "secret"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         )]);
 
@@ -2820,6 +4369,8 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         )]);
 
@@ -2869,6 +4420,8 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         )]);
 
@@ -2916,6 +4469,8 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         )]);
 
@@ -2979,6 +4534,8 @@ startup_timeout_sec = 2.0
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         )]);
         apply_blocking(
@@ -3054,6 +4611,8 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         )]);
 
@@ -3082,6 +4641,8 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
         apply_blocking(
@@ -3148,6 +4709,8 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    default_tool_approval: None,
+                    tool_approvals: None,
                 },
             ),
             (
@@ -3166,6 +4729,8 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    default_tool_approval: None,
+                    tool_approvals: None,
                 },
             ),
         ]);
@@ -3247,6 +4812,8 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         )]);
 
@@ -3290,6 +4857,8 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["allowed".to_string()]),
                 disabled_tools: Some(vec!["blocked".to_string()]),
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         )]);
 
@@ -3601,6 +5170,9 @@ model_verbosity = "high"
             stream_max_retries: Some(10),
             stream_idle_timeout_ms: Some(300_000),
             requires_openai_auth: false,
+            api_version: None,
+            deployments: None,
+            entra_id: None,
         };
         let model_provider_map = {
             let mut model_provider_map = built_in_model_providers();
@@ -3657,15 +5229,25 @@ model_verbosity = "high"
                 model: Some("o3".to_string()),
                 did_user_override_model: false,
                 review_model: None,
+                review_split_by_area: false,
                 model_context_window: None,
                 model_auto_compact_token_limit: None,
+                compaction_strategy: CompactionStrategy::default(),
+                compaction_target_context_percent: None,
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
+                mock_responses_file: None,
                 approval_policy: Constrained::allow_any(AskForApproval::Never),
                 sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
                 did_user_set_custom_approval_policy_or_sandbox_mode: true,
                 forced_auto_mode_downgraded_on_windows: false,
+                network_allowlist: Vec::new(),
+                container_sandbox: None,
+                exec_limits: ExecLimits::default(),
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
+                read_only_commands: Vec::new(),
+                approval_timeouts: ApprovalTimeouts::default(),
+                hosted_tools: HashMap::new(),
                 user_instructions: None,
                 notify: None,
                 cwd: fixture.cwd(),
@@ -3673,11 +5255,34 @@ model_verbosity = "high"
                 mcp_servers: Constrained::allow_any(HashMap::new()),
                 mcp_oauth_credentials_store_mode: Default::default(),
                 mcp_oauth_callback_port: None,
+                mcp_sampling: McpSamplingConfig::default(),
+                mcp_health: McpHealthConfig::default(),
+                web_fetch: WebFetchConfig::default(),
+                web_search_provider: None,
+                browser: None,
+                governor: None,
+                audit: None,
+                telemetry: None,
+                worktree_isolation: None,
+                gemini_safety_settings: Vec::new(),
+                local_models: LocalModelsConfig::default(),
+                network: NetworkSettings::default(),
+                offline: None,
+                guardrail: GuardrailConfig::default(),
+                timebox: TimeboxConfig::default(),
+                pre_commit: PreCommitConfig::default(),
+                budget: BudgetConfig::default(),
+                semantic_index: SemanticIndexConfig::default(),
+                hooks: Vec::new(),
+                context_providers: Vec::new(),
                 model_providers: fixture.model_provider_map.clone(),
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 tool_output_token_limit: None,
                 agent_max_threads: None,
+                subagent_extra_dirs: Vec::new(),
+                subagent_stage_changes: false,
+                branch_config: None,
                 codex_home: fixture.codex_home(),
                 config_layer_stack: Default::default(),
                 history: History::default(),
@@ -3688,6 +5293,8 @@ model_verbosity = "high"
                 model_reasoning_effort: Some(ReasoningEffort::High),
                 model_reasoning_summary: ReasoningSummary::Detailed,
                 model_supports_reasoning_summaries: None,
+                model_supports_images: None,
+                model_supports_tool_calls: None,
                 model_verbosity: None,
                 model_personality: None,
                 chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -3708,12 +5315,21 @@ model_verbosity = "high"
                 check_for_update_on_startup: true,
                 disable_paste_burst: false,
                 tui_notifications: Default::default(),
+                tui_notification_method: NotificationMethod::default(),
+                tui_notification_min_turn_sec: 0,
                 animations: true,
                 show_tooltips: true,
                 experimental_mode: None,
                 analytics_enabled: Some(true),
                 feedback_enabled: true,
                 tui_alternate_screen: AltScreenMode::Auto,
+                tui_voice: None,
+                tui_keybindings: KeybindingsToml::default(),
+                tui_theme: None,
+                tui_theme_variant: ThemeVariant::default(),
+                tui_theme_palette: ThemePaletteToml::default(),
+                tui_status_line: Vec::new(),
+                tui_editing_mode: EditingMode::Emacs,
                 otel: OtelConfig::default(),
             },
             o3_profile_config
@@ -3739,15 +5355,25 @@ model_verbosity = "high"
             model: Some("gpt-3.5-turbo".to_string()),
             did_user_override_model: false,
             review_model: None,
+            review_split_by_area: false,
             model_context_window: None,
             model_auto_compact_token_limit: None,
+            compaction_strategy: CompactionStrategy::default(),
+            compaction_target_context_percent: None,
             model_provider_id: "openai-chat-completions".to_string(),
             model_provider: fixture.openai_chat_completions_provider.clone(),
+            mock_responses_file: None,
             approval_policy: Constrained::allow_any(AskForApproval::UnlessTrusted),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            network_allowlist: Vec::new(),
+            container_sandbox: None,
+            exec_limits: ExecLimits::default(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            read_only_commands: Vec::new(),
+            approval_timeouts: ApprovalTimeouts::default(),
+            hosted_tools: HashMap::new(),
             user_instructions: None,
             notify: None,
             cwd: fixture.cwd(),
@@ -3755,11 +5381,34 @@ model_verbosity = "high"
             mcp_servers: Constrained::allow_any(HashMap::new()),
             mcp_oauth_credentials_store_mode: Default::default(),
             mcp_oauth_callback_port: None,
+            mcp_sampling: McpSamplingConfig::default(),
+            mcp_health: McpHealthConfig::default(),
+            web_fetch: WebFetchConfig::default(),
+            web_search_provider: None,
+            browser: None,
+            governor: None,
+            audit: None,
+            telemetry: None,
+            worktree_isolation: None,
+            gemini_safety_settings: Vec::new(),
+            local_models: LocalModelsConfig::default(),
+            network: NetworkSettings::default(),
+            offline: None,
+            guardrail: GuardrailConfig::default(),
+            timebox: TimeboxConfig::default(),
+            pre_commit: PreCommitConfig::default(),
+            budget: BudgetConfig::default(),
+            semantic_index: SemanticIndexConfig::default(),
+            hooks: Vec::new(),
+            context_providers: Vec::new(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             agent_max_threads: None,
+            subagent_extra_dirs: Vec::new(),
+            subagent_stage_changes: false,
+            branch_config: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
@@ -3770,6 +5419,8 @@ model_verbosity = "high"
             model_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::default(),
             model_supports_reasoning_summaries: None,
+            model_supports_images: None,
+            model_supports_tool_calls: None,
             model_verbosity: None,
             model_personality: None,
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -3790,12 +5441,21 @@ model_verbosity = "high"
             check_for_update_on_startup: true,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_notification_method: NotificationMethod::default(),
+            tui_notification_min_turn_sec: 0,
             animations: true,
             show_tooltips: true,
             experimental_mode: None,
             analytics_enabled: Some(true),
             feedback_enabled: true,
             tui_alternate_screen: AltScreenMode::Auto,
+            tui_voice: None,
+            tui_keybindings: KeybindingsToml::default(),
+            tui_theme: None,
+            tui_theme_variant: ThemeVariant::default(),
+            tui_theme_palette: ThemePaletteToml::default(),
+            tui_status_line: Vec::new(),
+            tui_editing_mode: EditingMode::Emacs,
             otel: OtelConfig::default(),
         };
 
@@ -3836,15 +5496,25 @@ model_verbosity = "high"
             model: Some("o3".to_string()),
             did_user_override_model: false,
             review_model: None,
+            review_split_by_area: false,
             model_context_window: None,
             model_auto_compact_token_limit: None,
+            compaction_strategy: CompactionStrategy::default(),
+            compaction_target_context_percent: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            mock_responses_file: None,
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            network_allowlist: Vec::new(),
+            container_sandbox: None,
+            exec_limits: ExecLimits::default(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            read_only_commands: Vec::new(),
+            approval_timeouts: ApprovalTimeouts::default(),
+            hosted_tools: HashMap::new(),
             user_instructions: None,
             notify: None,
             cwd: fixture.cwd(),
@@ -3852,11 +5522,34 @@ model_verbosity = "high"
             mcp_servers: Constrained::allow_any(HashMap::new()),
             mcp_oauth_credentials_store_mode: Default::default(),
             mcp_oauth_callback_port: None,
+            mcp_sampling: McpSamplingConfig::default(),
+            mcp_health: McpHealthConfig::default(),
+            web_fetch: WebFetchConfig::default(),
+            web_search_provider: None,
+            browser: None,
+            governor: None,
+            audit: None,
+            telemetry: None,
+            worktree_isolation: None,
+            gemini_safety_settings: Vec::new(),
+            local_models: LocalModelsConfig::default(),
+            network: NetworkSettings::default(),
+            offline: None,
+            guardrail: GuardrailConfig::default(),
+            timebox: TimeboxConfig::default(),
+            pre_commit: PreCommitConfig::default(),
+            budget: BudgetConfig::default(),
+            semantic_index: SemanticIndexConfig::default(),
+            hooks: Vec::new(),
+            context_providers: Vec::new(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             agent_max_threads: None,
+            subagent_extra_dirs: Vec::new(),
+            subagent_stage_changes: false,
+            branch_config: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
@@ -3867,6 +5560,8 @@ model_verbosity = "high"
             model_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::default(),
             model_supports_reasoning_summaries: None,
+            model_supports_images: None,
+            model_supports_tool_calls: None,
             model_verbosity: None,
             model_personality: None,
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -3887,12 +5582,21 @@ model_verbosity = "high"
             check_for_update_on_startup: true,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_notification_method: NotificationMethod::default(),
+            tui_notification_min_turn_sec: 0,
             animations: true,
             show_tooltips: true,
             experimental_mode: None,
             analytics_enabled: Some(false),
             feedback_enabled: true,
             tui_alternate_screen: AltScreenMode::Auto,
+            tui_voice: None,
+            tui_keybindings: KeybindingsToml::default(),
+            tui_theme: None,
+            tui_theme_variant: ThemeVariant::default(),
+            tui_theme_palette: ThemePaletteToml::default(),
+            tui_status_line: Vec::new(),
+            tui_editing_mode: EditingMode::Emacs,
             otel: OtelConfig::default(),
         };
 
@@ -3919,15 +5623,25 @@ model_verbosity = "high"
             model: Some("gpt-5.1".to_string()),
             did_user_override_model: false,
             review_model: None,
+            review_split_by_area: false,
             model_context_window: None,
             model_auto_compact_token_limit: None,
+            compaction_strategy: CompactionStrategy::default(),
+            compaction_target_context_percent: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
+            mock_responses_file: None,
             approval_policy: Constrained::allow_any(AskForApproval::OnFailure),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::new_read_only_policy()),
             did_user_set_custom_approval_policy_or_sandbox_mode: true,
             forced_auto_mode_downgraded_on_windows: false,
+            network_allowlist: Vec::new(),
+            container_sandbox: None,
+            exec_limits: ExecLimits::default(),
             shell_environment_policy: ShellEnvironmentPolicy::default(),
+            read_only_commands: Vec::new(),
+            approval_timeouts: ApprovalTimeouts::default(),
+            hosted_tools: HashMap::new(),
             user_instructions: None,
             notify: None,
             cwd: fixture.cwd(),
@@ -3935,11 +5649,34 @@ model_verbosity = "high"
             mcp_servers: Constrained::allow_any(HashMap::new()),
             mcp_oauth_credentials_store_mode: Default::default(),
             mcp_oauth_callback_port: None,
+            mcp_sampling: McpSamplingConfig::default(),
+            mcp_health: McpHealthConfig::default(),
+            web_fetch: WebFetchConfig::default(),
+            web_search_provider: None,
+            browser: None,
+            governor: None,
+            audit: None,
+            telemetry: None,
+            worktree_isolation: None,
+            gemini_safety_settings: Vec::new(),
+            local_models: LocalModelsConfig::default(),
+            network: NetworkSettings::default(),
+            offline: None,
+            guardrail: GuardrailConfig::default(),
+            timebox: TimeboxConfig::default(),
+            pre_commit: PreCommitConfig::default(),
+            budget: BudgetConfig::default(),
+            semantic_index: SemanticIndexConfig::default(),
+            hooks: Vec::new(),
+            context_providers: Vec::new(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
             agent_max_threads: None,
+            subagent_extra_dirs: Vec::new(),
+            subagent_stage_changes: false,
+            branch_config: None,
             codex_home: fixture.codex_home(),
             config_layer_stack: Default::default(),
             history: History::default(),
@@ -3950,6 +5687,8 @@ model_verbosity = "high"
             model_reasoning_effort: Some(ReasoningEffort::High),
             model_reasoning_summary: ReasoningSummary::Detailed,
             model_supports_reasoning_summaries: None,
+            model_supports_images: None,
+            model_supports_tool_calls: None,
             model_verbosity: Some(Verbosity::High),
             model_personality: None,
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -3970,12 +5709,21 @@ model_verbosity = "high"
             check_for_update_on_startup: true,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_notification_method: NotificationMethod::default(),
+            tui_notification_min_turn_sec: 0,
             animations: true,
             show_tooltips: true,
             experimental_mode: None,
             analytics_enabled: Some(true),
             feedback_enabled: true,
             tui_alternate_screen: AltScreenMode::Auto,
+            tui_voice: None,
+            tui_keybindings: KeybindingsToml::default(),
+            tui_theme: None,
+            tui_theme_variant: ThemeVariant::default(),
+            tui_theme_palette: ThemePaletteToml::default(),
+            tui_status_line: Vec::new(),
+            tui_editing_mode: EditingMode::Emacs,
             otel: OtelConfig::default(),
         };
 
@@ -1,9 +1,14 @@
+use std::collections::HashMap;
+use std::io;
+
 use codex_utils_absolute_path::AbsolutePathBuf;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
+use toml::Value as TomlValue;
 
 use crate::config::types::Personality;
+use crate::config_loader::merge_toml_values;
 use crate::protocol::AskForApproval;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::config_types::SandboxMode;
@@ -16,6 +21,9 @@ use codex_protocol::openai_models::ReasoningEffort;
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct ConfigProfile {
+    /// Name of another profile this one starts from. Settings made here win
+    /// over the inherited ones; `features` are merged toggle by toggle.
+    pub extends: Option<String>,
     pub model: Option<String>,
     /// The key in the `model_providers` map identifying the
     /// [`ModelProviderInfo`] to use.
@@ -40,6 +48,7 @@ pub struct ConfigProfile {
     pub tools_view_image: Option<bool>,
     pub web_search: Option<WebSearchMode>,
     pub analytics: Option<crate::config::types::AnalyticsConfigToml>,
+    pub subagents: Option<crate::config::SubAgentsToml>,
     /// Optional feature toggles scoped to this profile.
     #[serde(default)]
     // Injects known feature keys into the schema and forbids unknown keys.
@@ -48,6 +57,136 @@ pub struct ConfigProfile {
     pub oss_provider: Option<String>,
 }
 
+impl ConfigProfile {
+    /// `self` with every setting it leaves unset taken from `base`.
+    fn inherit(self, base: ConfigProfile) -> Self {
+        let features = match (self.features, base.features) {
+            (Some(mut own), Some(base)) => {
+                for (key, enabled) in base.entries {
+                    own.entries.entry(key).or_insert(enabled);
+                }
+                Some(own)
+            }
+            (own, base) => own.or(base),
+        };
+        Self {
+            extends: self.extends,
+            model: self.model.or(base.model),
+            model_provider: self.model_provider.or(base.model_provider),
+            approval_policy: self.approval_policy.or(base.approval_policy),
+            sandbox_mode: self.sandbox_mode.or(base.sandbox_mode),
+            model_reasoning_effort: self.model_reasoning_effort.or(base.model_reasoning_effort),
+            model_reasoning_summary: self
+                .model_reasoning_summary
+                .or(base.model_reasoning_summary),
+            model_verbosity: self.model_verbosity.or(base.model_verbosity),
+            model_personality: self.model_personality.or(base.model_personality),
+            chatgpt_base_url: self.chatgpt_base_url.or(base.chatgpt_base_url),
+            model_instructions_file: self
+                .model_instructions_file
+                .or(base.model_instructions_file),
+            experimental_instructions_file: self
+                .experimental_instructions_file
+                .or(base.experimental_instructions_file),
+            experimental_compact_prompt_file: self
+                .experimental_compact_prompt_file
+                .or(base.experimental_compact_prompt_file),
+            include_apply_patch_tool: self
+                .include_apply_patch_tool
+                .or(base.include_apply_patch_tool),
+            experimental_use_unified_exec_tool: self
+                .experimental_use_unified_exec_tool
+                .or(base.experimental_use_unified_exec_tool),
+            experimental_use_freeform_apply_patch: self
+                .experimental_use_freeform_apply_patch
+                .or(base.experimental_use_freeform_apply_patch),
+            tools_web_search: self.tools_web_search.or(base.tools_web_search),
+            tools_view_image: self.tools_view_image.or(base.tools_view_image),
+            web_search: self.web_search.or(base.web_search),
+            analytics: self.analytics.or(base.analytics),
+            subagents: self.subagents.or(base.subagents),
+            features,
+            oss_provider: self.oss_provider.or(base.oss_provider),
+        }
+    }
+}
+
+/// Looks up profile `name` and applies its chain of `extends`.
+pub fn resolve_profile(
+    profiles: &HashMap<String, ConfigProfile>,
+    name: &str,
+) -> io::Result<ConfigProfile> {
+    let mut chain = vec![name.to_string()];
+    let mut resolved = profiles.get(name).cloned().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("config profile `{name}` not found"),
+        )
+    })?;
+    let mut next = resolved.extends.clone();
+    while let Some(base_name) = next {
+        if chain.contains(&base_name) {
+            chain.push(base_name);
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "config profiles extend each other in a cycle: {}",
+                    chain.join(" -> ")
+                ),
+            ));
+        }
+        let base = profiles.get(&base_name).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "config profile `{base_name}` (extended by `{}`) not found",
+                    chain.last().map(String::as_str).unwrap_or(name)
+                ),
+            )
+        })?;
+        next = base.extends.clone();
+        chain.push(base_name);
+        resolved = resolved.inherit(base);
+    }
+    Ok(resolved)
+}
+
+/// `effective` (the merged config layers) with the settings of the active
+/// profile laid over the top-level keys they override, as shown by
+/// `codex config show --resolved`. The `profiles` table is left out.
+pub fn resolved_config_toml(
+    effective: &TomlValue,
+    active: Option<(&str, &ConfigProfile)>,
+) -> io::Result<TomlValue> {
+    let mut resolved = effective.clone();
+    if let TomlValue::Table(table) = &mut resolved {
+        table.remove("profiles");
+    }
+    let Some((name, profile)) = active else {
+        return Ok(resolved);
+    };
+    let TomlValue::Table(mut overlay) = TomlValue::try_from(profile).map_err(io::Error::other)?
+    else {
+        return Ok(resolved);
+    };
+    overlay.remove("extends");
+    let mut tools = toml::map::Map::new();
+    for (key, tools_key) in [
+        ("tools_web_search", "web_search"),
+        ("tools_view_image", "view_image"),
+    ] {
+        if let Some(value) = overlay.remove(key) {
+            tools.insert(tools_key.to_string(), value);
+        }
+    }
+    if !tools.is_empty() {
+        overlay.insert("tools".to_string(), TomlValue::Table(tools));
+    }
+    overlay.insert("profile".to_string(), TomlValue::String(name.to_string()));
+    merge_toml_values(&mut resolved, &TomlValue::Table(overlay));
+    Ok(resolved)
+}
+
 impl From<ConfigProfile> for codex_app_server_protocol::Profile {
     fn from(config_profile: ConfigProfile) -> Self {
         Self {
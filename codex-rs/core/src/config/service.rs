@@ -2,6 +2,8 @@ use super::CONFIG_TOML_FILE;
 use super::ConfigToml;
 use crate::config::edit::ConfigEdit;
 use crate::config::edit::ConfigEditsBuilder;
+use crate::config::validate::ConfigDiagnostic;
+use crate::config::validate::validate_config_layers;
 use crate::config_loader::ConfigLayerEntry;
 use crate::config_loader::ConfigLayerStack;
 use crate::config_loader::ConfigRequirementsToml;
@@ -195,6 +197,31 @@ impl ConfigService {
         }
     }
 
+    /// Checks the layers as seen from `cwd` (without project layers when
+    /// omitted) against the `config.toml` schema.
+    pub async fn validate(
+        &self,
+        cwd: Option<&Path>,
+    ) -> Result<Vec<ConfigDiagnostic>, ConfigServiceError> {
+        let layers = match cwd {
+            Some(cwd) => {
+                let cwd = AbsolutePathBuf::try_from(cwd.to_path_buf()).map_err(|err| {
+                    ConfigServiceError::io("failed to resolve config cwd to an absolute path", err)
+                })?;
+                load_config_layers_state(
+                    &self.codex_home,
+                    Some(cwd),
+                    &self.cli_overrides,
+                    self.loader_overrides.clone(),
+                )
+                .await
+            }
+            None => self.load_thread_agnostic_config().await,
+        }
+        .map_err(|err| ConfigServiceError::io("failed to read configuration layers", err))?;
+        Ok(validate_config_layers(&layers))
+    }
+
     pub async fn write_value(
         &self,
         params: ConfigValueWriteParams,
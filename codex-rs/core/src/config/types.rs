@@ -4,10 +4,18 @@
 // definitions that do not contain business logic.
 
 use crate::config_loader::RequirementSource;
+use crate::offline::OfflineError;
+use codex_client::NetworkSettings;
+use codex_client::ProxyBasicAuth;
+use codex_client::ProxySettings;
 pub use codex_protocol::config_types::AltScreenMode;
 pub use codex_protocol::config_types::ModeKind;
 pub use codex_protocol::config_types::Personality;
 pub use codex_protocol::config_types::WebSearchMode;
+pub use codex_protocol::protocol::ApprovalKind;
+pub use codex_protocol::protocol::ApprovalTimeoutAction;
+pub use codex_protocol::protocol::CommandRuleDecision;
+pub use codex_protocol::protocol::HookEventKind;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
@@ -28,6 +36,7 @@ pub const DEFAULT_OTEL_ENVIRONMENT: &str = "dev";
 pub enum McpServerDisabledReason {
     Unknown,
     Requirements { source: RequirementSource },
+    Offline(OfflineError),
 }
 
 impl fmt::Display for McpServerDisabledReason {
@@ -37,6 +46,7 @@ impl fmt::Display for McpServerDisabledReason {
             McpServerDisabledReason::Requirements { source } => {
                 write!(f, "requirements ({source})")
             }
+            McpServerDisabledReason::Offline(err) => write!(f, "{err}"),
         }
     }
 }
@@ -73,6 +83,49 @@ pub struct McpServerConfig {
     /// Explicit deny-list of tools. These tools will be removed after applying `enabled_tools`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disabled_tools: Option<Vec<String>>,
+
+    /// Approval policy for tools without an entry in `tool_approvals`.
+    /// Defaults to `auto_approve`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_tool_approval: Option<McpToolApproval>,
+
+    /// Per-tool approval policies, keyed by the tool name the server reports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_approvals: Option<HashMap<String, McpToolApproval>>,
+}
+
+impl McpServerConfig {
+    /// Approval policy for `tool` on this server.
+    pub fn tool_approval(&self, tool: &str) -> McpToolApproval {
+        self.tool_approvals
+            .as_ref()
+            .and_then(|approvals| approvals.get(tool).copied())
+            .or(self.default_tool_approval)
+            .unwrap_or_default()
+    }
+}
+
+/// Whether calls to an MCP tool need the user's approval.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum McpToolApproval {
+    /// Ask before every call (the answer may cover the rest of the session).
+    AlwaysAsk,
+    /// Call the tool without asking.
+    #[default]
+    AutoApprove,
+    /// Reject every call; the model is told the tool is blocked.
+    Blocked,
+}
+
+impl McpToolApproval {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::AlwaysAsk => "always_ask",
+            Self::AutoApprove => "auto_approve",
+            Self::Blocked => "blocked",
+        }
+    }
 }
 
 // Raw MCP config shape used for deserialization and JSON Schema generation.
@@ -113,6 +166,10 @@ pub(crate) struct RawMcpServerConfig {
     pub enabled_tools: Option<Vec<String>>,
     #[serde(default)]
     pub disabled_tools: Option<Vec<String>>,
+    #[serde(default)]
+    pub default_tool_approval: Option<McpToolApproval>,
+    #[serde(default)]
+    pub tool_approvals: Option<HashMap<String, McpToolApproval>>,
 }
 
 impl<'de> Deserialize<'de> for McpServerConfig {
@@ -134,6 +191,8 @@ impl<'de> Deserialize<'de> for McpServerConfig {
         let enabled = raw.enabled.unwrap_or_else(default_enabled);
         let enabled_tools = raw.enabled_tools.clone();
         let disabled_tools = raw.disabled_tools.clone();
+        let default_tool_approval = raw.default_tool_approval;
+        let tool_approvals = raw.tool_approvals.take();
 
         fn throw_if_set<E, T>(transport: &str, field: &str, value: Option<&T>) -> Result<(), E>
         where
@@ -188,6 +247,8 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             disabled_reason: None,
             enabled_tools,
             disabled_tools,
+            default_tool_approval,
+            tool_approvals,
         })
     }
 }
@@ -228,100 +289,1420 @@ pub enum McpServerTransportConfig {
     },
 }
 
-mod option_duration_secs {
-    use serde::Deserialize;
-    use serde::Deserializer;
-    use serde::Serializer;
-    use std::time::Duration;
+mod option_duration_secs {
+    use serde::Deserialize;
+    use serde::Deserializer;
+    use serde::Serializer;
+    use std::time::Duration;
+
+    pub fn serialize<S>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(duration) => serializer.serialize_some(&duration.as_secs_f64()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let secs = Option::<f64>::deserialize(deserializer)?;
+        secs.map(|secs| Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, JsonSchema)]
+pub enum UriBasedFileOpener {
+    #[serde(rename = "vscode")]
+    VsCode,
+
+    #[serde(rename = "vscode-insiders")]
+    VsCodeInsiders,
+
+    #[serde(rename = "windsurf")]
+    Windsurf,
+
+    #[serde(rename = "cursor")]
+    Cursor,
+
+    /// Option to disable the URI-based file opener.
+    #[serde(rename = "none")]
+    None,
+}
+
+impl UriBasedFileOpener {
+    pub fn get_scheme(&self) -> Option<&str> {
+        match self {
+            UriBasedFileOpener::VsCode => Some("vscode"),
+            UriBasedFileOpener::VsCodeInsiders => Some("vscode-insiders"),
+            UriBasedFileOpener::Windsurf => Some("windsurf"),
+            UriBasedFileOpener::Cursor => Some("cursor"),
+            UriBasedFileOpener::None => None,
+        }
+    }
+}
+
+/// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct History {
+    /// If true, history entries will not be written to disk.
+    pub persistence: HistoryPersistence,
+
+    /// If set, the maximum size of the history file in bytes. The oldest entries
+    /// are dropped once the file exceeds this limit.
+    pub max_bytes: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistoryPersistence {
+    /// Save all history entries to disk.
+    #[default]
+    SaveAll,
+    /// Do not write history to disk.
+    None,
+}
+
+/// How auto-compaction shrinks the conversation history.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionStrategy {
+    /// Ask the model to summarize the history. With
+    /// `target_context_percent`, only the oldest turns are summarized and
+    /// the recent ones are kept verbatim.
+    #[default]
+    SummarizeOldest,
+    /// Replace tool outputs, oldest first, with a placeholder. No model call.
+    DropToolOutput,
+    /// Drop whole turns, oldest first. No model call.
+    SlidingWindow,
+}
+
+// ===== Analytics configuration =====
+
+/// Analytics settings loaded from config.toml. Fields are optional so we can apply defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct AnalyticsConfigToml {
+    /// When `false`, disables analytics across Codex product surfaces in this profile.
+    pub enabled: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct FeedbackConfigToml {
+    /// When `false`, disables the feedback flow across Codex product surfaces.
+    pub enabled: Option<bool>,
+}
+
+// ===== Hooks configuration =====
+
+/// One `[[hooks]]` entry: an external command run at the given session events.
+/// The event payload is passed as JSON on stdin.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct HookConfig {
+    /// Identifier used in logs and hook outcome events.
+    pub id: Option<String>,
+
+    /// Event(s) that trigger the hook, e.g. `"tool.call.begin"`.
+    pub when: HookWhen,
+
+    /// Command (argv) to run. Relative paths resolve from the git root, or the
+    /// session cwd outside a repository.
+    pub command: Vec<String>,
+
+    /// Kill the hook after this many milliseconds. Defaults to 10 seconds.
+    pub timeout_ms: Option<u64>,
+
+    /// On `prompt.submit` / `tool.call.begin`, wait for the hook and let it
+    /// block (exit code 2) or rewrite the action. Other hooks never delay the
+    /// agent, except `turn.post_process` hooks, which always finish before the
+    /// turn completes.
+    #[serde(default)]
+    pub blocking: bool,
+
+    /// Regex on the event subject: the tool name for `tool.call.*`, the MCP
+    /// tool name for `tool.mcp.*`, and the exec source for `tool.exec.*`.
+    pub matcher: Option<String>,
+
+    /// Include the tool call's arguments as `tool_input` in the payload.
+    #[serde(default)]
+    pub include_tool_arguments: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum HookWhen {
+    One(HookEventKind),
+    Many(Vec<HookEventKind>),
+}
+
+impl HookWhen {
+    pub fn contains(&self, kind: HookEventKind) -> bool {
+        match self {
+            HookWhen::One(when) => *when == kind,
+            HookWhen::Many(when) => when.contains(&kind),
+        }
+    }
+}
+
+// ===== Context provider configuration =====
+
+/// One `[[context_providers]]` entry: a source of extra context gathered at
+/// the start of every turn. Set either `command` or both `mcp_server` and
+/// `resource_uri`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ContextProviderConfig {
+    /// Name shown as the source of the provider's snippets.
+    pub id: String,
+
+    /// Command (argv) that receives the turn as JSON on stdin and prints the
+    /// context on stdout. Relative paths resolve from the git root, or the
+    /// session cwd outside a repository.
+    pub command: Option<Vec<String>>,
+
+    /// MCP server whose resource `resource_uri` is read instead of running a
+    /// command.
+    pub mcp_server: Option<String>,
+
+    /// Resource read from `mcp_server`; its text contents become the context.
+    pub resource_uri: Option<String>,
+
+    /// Give up on the provider after this many milliseconds. Defaults to 5
+    /// seconds.
+    pub timeout_ms: Option<u64>,
+
+    /// Bytes of context kept from this provider. Defaults to 8 KiB.
+    pub max_bytes: Option<usize>,
+}
+
+// ===== Hosted tool configuration =====
+
+/// `[hosted_tools."<model>"]`: provider-hosted tools offered to a model in
+/// addition to the local ones. The `"*"` entry applies to models without an
+/// entry of their own. Only Responses API providers run hosted tools.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct HostedToolsToml {
+    /// Let the model run Python in a provider-managed container.
+    #[serde(default)]
+    pub code_interpreter: bool,
+    /// Let the model search provider-hosted vector stores.
+    pub file_search: Option<HostedFileSearchToml>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct HostedFileSearchToml {
+    /// IDs of the vector stores to search.
+    pub vector_store_ids: Vec<String>,
+    /// Maximum number of results returned per search.
+    pub max_num_results: Option<u32>,
+}
+
+impl HostedToolsToml {
+    pub fn is_empty(&self) -> bool {
+        !self.code_interpreter && self.file_search.is_none()
+    }
+}
+
+// ===== Approval timeout configuration =====
+
+/// `[approval_timeouts]`: how long each kind of approval prompt may wait for
+/// an answer. Prompts without an entry wait indefinitely.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ApprovalTimeoutsToml {
+    /// Command approvals, including retries without the sandbox.
+    pub exec: Option<ApprovalTimeoutToml>,
+    /// `apply_patch` approvals.
+    pub patch: Option<ApprovalTimeoutToml>,
+    /// Plan approvals requested by `submit_plan` in plan mode.
+    pub plan: Option<ApprovalTimeoutToml>,
+    /// Approvals for MCP tools marked `always_ask`.
+    pub mcp_tool: Option<ApprovalTimeoutToml>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ApprovalTimeoutToml {
+    /// Seconds to wait for an answer before applying `action`.
+    pub seconds: u64,
+    /// What to do once the timeout expires. Defaults to `deny`.
+    pub action: Option<ApprovalTimeoutAction>,
+}
+
+/// Effective approval timeouts; `None` waits for the user indefinitely.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ApprovalTimeouts {
+    pub exec: Option<ApprovalTimeout>,
+    pub patch: Option<ApprovalTimeout>,
+    pub plan: Option<ApprovalTimeout>,
+    pub mcp_tool: Option<ApprovalTimeout>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ApprovalTimeout {
+    pub timeout: Duration,
+    pub action: ApprovalTimeoutAction,
+}
+
+impl ApprovalTimeouts {
+    pub fn for_kind(&self, kind: ApprovalKind) -> Option<ApprovalTimeout> {
+        match kind {
+            ApprovalKind::Exec => self.exec,
+            ApprovalKind::Patch => self.patch,
+            ApprovalKind::Plan => self.plan,
+            ApprovalKind::McpTool => self.mcp_tool,
+        }
+    }
+}
+
+impl From<ApprovalTimeoutToml> for ApprovalTimeout {
+    fn from(toml: ApprovalTimeoutToml) -> Self {
+        Self {
+            timeout: Duration::from_secs(toml.seconds),
+            action: toml.action.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<ApprovalTimeoutsToml> for ApprovalTimeouts {
+    fn from(toml: ApprovalTimeoutsToml) -> Self {
+        Self {
+            exec: toml.exec.map(Into::into),
+            patch: toml.patch.map(Into::into),
+            plan: toml.plan.map(Into::into),
+            mcp_tool: toml.mcp_tool.map(Into::into),
+        }
+    }
+}
+
+// ===== Command rules =====
+
+/// One `[[command_rules]]` entry, in config.toml or `policies.toml` next to
+/// it (e.g. `.codex/policies.toml`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct CommandRuleToml {
+    /// Shown in approval prompts and the approval journal.
+    pub name: Option<String>,
+    /// Program name or path (`git`, `/usr/bin/rm`); `*` and `?` wildcards are
+    /// allowed. A bare name also matches the program by file name.
+    pub program: String,
+    /// Patterns the leading arguments must match, in order. Further
+    /// arguments are not checked.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Patterns of which at least one must match some argument, wherever it
+    /// appears (e.g. `["--force", "-f"]`).
+    #[serde(default)]
+    pub any_arg: Vec<String>,
+    /// Directories the command must run in (or below). Relative entries are
+    /// resolved against the folder containing the `.codex` directory. Empty
+    /// means anywhere.
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    pub decision: CommandRuleDecision,
+    /// Explanation shown with the decision.
+    pub reason: Option<String>,
+}
+
+/// `[git_guardrails]`: checks for destructive git operations, in config.toml
+/// or `policies.toml`. Present in any layer turns them on; unset decisions
+/// use the defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct GitGuardrailsToml {
+    /// Branches that must not be force-pushed, deleted or rewritten. A
+    /// trailing `*` matches a prefix, as in `release/*`. Entries from every
+    /// layer apply together. Defaults to `main` and `master`.
+    pub protected_branches: Option<Vec<String>>,
+    /// Force pushes to and deletions of protected branches, and
+    /// `git push --mirror`. Defaults to `auto_deny`.
+    pub force_push: Option<CommandRuleDecision>,
+    /// `commit --amend`, `rebase`, `reset <commit>`, `branch -D/-f`,
+    /// `checkout -B`, `update-ref` on protected branches, and
+    /// `filter-branch`/`filter-repo`. Defaults to `always_ask`.
+    pub history_rewrite: Option<CommandRuleDecision>,
+    /// `git clean` runs that delete files. Defaults to `always_ask`.
+    pub clean: Option<CommandRuleDecision>,
+}
+
+/// Contents of a `policies.toml` file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct PoliciesToml {
+    #[serde(default)]
+    pub command_rules: Vec<CommandRuleToml>,
+    #[serde(default)]
+    pub git_guardrails: Option<GitGuardrailsToml>,
+}
+
+// ===== MCP health check configuration =====
+
+const DEFAULT_MCP_HEALTH_PING_INTERVAL_SEC: u64 = 30;
+const DEFAULT_MCP_HEALTH_UNRESPONSIVE_TIMEOUT_SEC: u64 = 10;
+const DEFAULT_MCP_HEALTH_MAX_RESTARTS: u32 = 5;
+
+/// `[mcp_health]` settings loaded from config.toml. Fields are optional so we can apply defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct McpHealthToml {
+    /// Seconds between liveness pings to each running MCP server. `0` disables
+    /// health checks. Defaults to 30.
+    pub ping_interval_sec: Option<u64>,
+
+    /// A server that does not answer a ping within this many seconds is
+    /// treated as unresponsive and restarted. Defaults to 10.
+    pub unresponsive_timeout_sec: Option<u64>,
+
+    /// Consecutive restart attempts before a server is given up on for the
+    /// rest of the session. Defaults to 5.
+    pub max_restarts: Option<u32>,
+}
+
+/// Effective MCP health check settings after defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpHealthConfig {
+    pub ping_interval_sec: u64,
+    pub unresponsive_timeout_sec: u64,
+    pub max_restarts: u32,
+}
+
+impl Default for McpHealthConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval_sec: DEFAULT_MCP_HEALTH_PING_INTERVAL_SEC,
+            unresponsive_timeout_sec: DEFAULT_MCP_HEALTH_UNRESPONSIVE_TIMEOUT_SEC,
+            max_restarts: DEFAULT_MCP_HEALTH_MAX_RESTARTS,
+        }
+    }
+}
+
+impl From<McpHealthToml> for McpHealthConfig {
+    fn from(toml: McpHealthToml) -> Self {
+        let defaults = Self::default();
+        Self {
+            ping_interval_sec: toml.ping_interval_sec.unwrap_or(defaults.ping_interval_sec),
+            unresponsive_timeout_sec: toml
+                .unresponsive_timeout_sec
+                .unwrap_or(defaults.unresponsive_timeout_sec),
+            max_restarts: toml.max_restarts.unwrap_or(defaults.max_restarts),
+        }
+    }
+}
+
+// ===== MCP sampling configuration =====
+
+const DEFAULT_MCP_SAMPLING_MAX_TOKENS_PER_REQUEST: i64 = 4096;
+const DEFAULT_MCP_SAMPLING_MAX_REQUESTS_PER_SESSION: u32 = 20;
+const DEFAULT_MCP_SAMPLING_MAX_REQUESTS_PER_MINUTE: u32 = 5;
+
+/// `[mcp_sampling]` settings loaded from config.toml. Fields are optional so we can apply defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct McpSamplingToml {
+    /// MCP servers that may request model completions via `sampling/createMessage`.
+    /// Servers not listed here are not offered the sampling capability.
+    pub servers: Option<Vec<String>>,
+
+    /// When `false`, sampling requests run without asking the user first. Defaults to `true`.
+    pub require_approval: Option<bool>,
+
+    /// Requests asking for more than this many output tokens are rejected. Defaults to 4096.
+    pub max_tokens_per_request: Option<i64>,
+
+    /// Maximum number of sampling requests served per session. Defaults to 20.
+    pub max_requests_per_session: Option<u32>,
+
+    /// Maximum number of sampling requests a single server may make in any
+    /// one-minute window. Defaults to 5.
+    pub max_requests_per_minute: Option<u32>,
+}
+
+/// Effective MCP sampling settings after defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpSamplingConfig {
+    pub servers: Vec<String>,
+    pub require_approval: bool,
+    pub max_tokens_per_request: i64,
+    pub max_requests_per_session: u32,
+    pub max_requests_per_minute: u32,
+}
+
+impl Default for McpSamplingConfig {
+    fn default() -> Self {
+        Self {
+            servers: Vec::new(),
+            require_approval: true,
+            max_tokens_per_request: DEFAULT_MCP_SAMPLING_MAX_TOKENS_PER_REQUEST,
+            max_requests_per_session: DEFAULT_MCP_SAMPLING_MAX_REQUESTS_PER_SESSION,
+            max_requests_per_minute: DEFAULT_MCP_SAMPLING_MAX_REQUESTS_PER_MINUTE,
+        }
+    }
+}
+
+impl From<McpSamplingToml> for McpSamplingConfig {
+    fn from(toml: McpSamplingToml) -> Self {
+        let defaults = Self::default();
+        Self {
+            servers: toml.servers.unwrap_or(defaults.servers),
+            require_approval: toml.require_approval.unwrap_or(defaults.require_approval),
+            max_tokens_per_request: toml
+                .max_tokens_per_request
+                .unwrap_or(defaults.max_tokens_per_request),
+            max_requests_per_session: toml
+                .max_requests_per_session
+                .unwrap_or(defaults.max_requests_per_session),
+            max_requests_per_minute: toml
+                .max_requests_per_minute
+                .unwrap_or(defaults.max_requests_per_minute),
+        }
+    }
+}
+
+// ===== web_search provider configuration =====
+
+/// Search API behind the local `web_search` tool.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WebSearchProviderKind {
+    /// Bing Web Search API v7.
+    Bing,
+    /// Brave Search API.
+    Brave,
+    /// A SearxNG instance with the JSON output format enabled.
+    Searxng,
+    /// An endpoint answering `GET ?q=<query>&count=<n>` with
+    /// `{"results": [{"title", "url", "snippet"}]}`.
+    Custom,
+}
+
+/// `[web_search_provider]`: run `web_search` locally against a search API
+/// instead of the model provider's hosted search.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WebSearchProviderToml {
+    pub provider: WebSearchProviderKind,
+
+    /// API URL. Required for `searxng` and `custom`; Bing and Brave default
+    /// to their public endpoints.
+    pub endpoint: Option<String>,
+
+    /// Environment variable holding the API key. Defaults to
+    /// `BING_SEARCH_API_KEY` or `BRAVE_SEARCH_API_KEY`. For `searxng` and
+    /// `custom`, the key is sent as a bearer token.
+    pub api_key_env: Option<String>,
+
+    /// Results returned per search. Defaults to 5.
+    pub max_results: Option<usize>,
+}
+
+// ===== web_fetch configuration =====
+
+const DEFAULT_WEB_FETCH_MAX_BYTES: usize = 5 * 1024 * 1024;
+const DEFAULT_WEB_FETCH_TIMEOUT_SECONDS: u64 = 30;
+
+/// `[web_fetch]`: which sites the `web_fetch` tool may download from and the
+/// limits applied to each download.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WebFetchToml {
+    /// Domains that may be fetched, including their subdomains. When set,
+    /// other domains are refused. Without network access in the sandbox,
+    /// only these domains can be fetched.
+    pub allowed_domains: Option<Vec<String>>,
+
+    /// Domains (and their subdomains) that are never fetched.
+    pub denied_domains: Option<Vec<String>>,
+
+    /// Responses larger than this many bytes are cut off. Defaults to 5 MiB.
+    pub max_bytes: Option<usize>,
+
+    /// Seconds allowed for the whole download. Defaults to 30.
+    pub timeout_seconds: Option<u64>,
+}
+
+/// Effective `web_fetch` settings after defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebFetchConfig {
+    pub allowed_domains: Vec<String>,
+    pub denied_domains: Vec<String>,
+    pub max_bytes: usize,
+    pub timeout: Duration,
+}
+
+impl Default for WebFetchConfig {
+    fn default() -> Self {
+        Self {
+            allowed_domains: Vec::new(),
+            denied_domains: Vec::new(),
+            max_bytes: DEFAULT_WEB_FETCH_MAX_BYTES,
+            timeout: Duration::from_secs(DEFAULT_WEB_FETCH_TIMEOUT_SECONDS),
+        }
+    }
+}
+
+impl From<WebFetchToml> for WebFetchConfig {
+    fn from(toml: WebFetchToml) -> Self {
+        let defaults = Self::default();
+        Self {
+            allowed_domains: toml.allowed_domains.unwrap_or(defaults.allowed_domains),
+            denied_domains: toml.denied_domains.unwrap_or(defaults.denied_domains),
+            max_bytes: toml.max_bytes.unwrap_or(defaults.max_bytes),
+            timeout: toml
+                .timeout_seconds
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.timeout),
+        }
+    }
+}
+
+// ===== Browser configuration =====
+
+const DEFAULT_BROWSER_WIDTH: u32 = 1280;
+const DEFAULT_BROWSER_HEIGHT: u32 = 800;
+const DEFAULT_BROWSER_TIMEOUT_SECONDS: u64 = 30;
+
+/// `[browser]`: enables the `browser_*` tools, which drive a headless
+/// Chromium over the DevTools protocol.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct BrowserToml {
+    /// Chromium or Chrome binary. Defaults to the first of `chromium`,
+    /// `chromium-browser`, `google-chrome` and `google-chrome-stable` on
+    /// `PATH`.
+    pub executable: Option<PathBuf>,
+
+    /// Run without a window. Defaults to true.
+    pub headless: Option<bool>,
+
+    /// Origins (e.g. `https://staging.example.com`) the agent may open
+    /// without asking. Loopback addresses are always allowed.
+    pub allowed_origins: Option<Vec<String>>,
+
+    /// Viewport size in CSS pixels. Defaults to 1280x800.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+
+    /// Limit for launching the browser, page loads and other commands.
+    /// Defaults to 30 seconds.
+    pub timeout_seconds: Option<u64>,
+}
+
+/// Effective `browser` settings after defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowserConfig {
+    pub executable: Option<PathBuf>,
+    pub headless: bool,
+    pub allowed_origins: Vec<String>,
+    pub width: u32,
+    pub height: u32,
+    pub timeout: Duration,
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        Self {
+            executable: None,
+            headless: true,
+            allowed_origins: Vec::new(),
+            width: DEFAULT_BROWSER_WIDTH,
+            height: DEFAULT_BROWSER_HEIGHT,
+            timeout: Duration::from_secs(DEFAULT_BROWSER_TIMEOUT_SECONDS),
+        }
+    }
+}
+
+impl From<BrowserToml> for BrowserConfig {
+    fn from(toml: BrowserToml) -> Self {
+        let defaults = Self::default();
+        Self {
+            executable: toml.executable,
+            headless: toml.headless.unwrap_or(defaults.headless),
+            allowed_origins: toml.allowed_origins.unwrap_or(defaults.allowed_origins),
+            width: toml.width.unwrap_or(defaults.width),
+            height: toml.height.unwrap_or(defaults.height),
+            timeout: toml
+                .timeout_seconds
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.timeout),
+        }
+    }
+}
+
+// ===== Guardrail configuration =====
+
+/// `[guardrail]`: settings for the `destructive_guardrail` feature.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct GuardrailToml {
+    /// Model asked about commands the rules do not flag, e.g. a small, cheap
+    /// one. When unset only the rules are applied.
+    pub model: Option<String>,
+
+    /// Branches that must not be force-pushed or deleted. A trailing `*`
+    /// matches a prefix, as in `release/*`. Defaults to `main` and `master`.
+    pub protected_branches: Option<Vec<String>>,
+}
+
+/// Effective guardrail settings after defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardrailConfig {
+    pub model: Option<String>,
+    pub protected_branches: Vec<String>,
+}
+
+impl Default for GuardrailConfig {
+    fn default() -> Self {
+        Self {
+            model: None,
+            protected_branches: vec!["main".to_string(), "master".to_string()],
+        }
+    }
+}
+
+impl From<GuardrailToml> for GuardrailConfig {
+    fn from(toml: GuardrailToml) -> Self {
+        let defaults = Self::default();
+        Self {
+            model: toml.model,
+            protected_branches: toml
+                .protected_branches
+                .unwrap_or(defaults.protected_branches),
+        }
+    }
+}
+
+// ===== Timebox configuration =====
+
+const DEFAULT_TIMEBOX_GRACE_SECONDS: u64 = 300;
+
+/// What happens to unfinished work when `codex exec --max-duration` runs out.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeboxPersist {
+    /// Leave the changes in the working tree.
+    #[default]
+    None,
+    /// Commit the changes to the current branch.
+    Commit,
+    /// Commit the changes to a new `codex/timebox-<thread id>` branch.
+    Branch,
+    /// Stash the changes, including untracked files.
+    Stash,
+}
+
+/// `[timebox]`: how `codex exec --max-duration` wraps up a run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct TimeboxToml {
+    /// Seconds the agent gets to summarize after the limit is reached before
+    /// the turn is interrupted. Defaults to 300.
+    pub grace_seconds: Option<u64>,
+
+    /// What to do with unfinished changes. Defaults to `none`.
+    pub persist: Option<TimeboxPersist>,
+}
+
+/// Effective `timebox` settings after defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeboxConfig {
+    pub grace: Duration,
+    pub persist: TimeboxPersist,
+}
+
+impl Default for TimeboxConfig {
+    fn default() -> Self {
+        Self {
+            grace: Duration::from_secs(DEFAULT_TIMEBOX_GRACE_SECONDS),
+            persist: TimeboxPersist::None,
+        }
+    }
+}
+
+impl From<TimeboxToml> for TimeboxConfig {
+    fn from(toml: TimeboxToml) -> Self {
+        let defaults = Self::default();
+        Self {
+            grace: toml
+                .grace_seconds
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.grace),
+            persist: toml.persist.unwrap_or(defaults.persist),
+        }
+    }
+}
+
+// ===== Pre-commit configuration =====
+
+const DEFAULT_PRE_COMMIT_TIMEOUT_SECONDS: u64 = 120;
+
+/// Style of the commit messages `codex commit` writes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitConvention {
+    /// `type(scope)!: subject`, as in Conventional Commits.
+    #[default]
+    Conventional,
+    /// A capitalized imperative subject without a type prefix.
+    Plain,
+}
+
+/// `[pre_commit]`: checks run by the hook that `codex hooks install` adds to
+/// a repository.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct PreCommitToml {
+    /// Block commits whose staged lines look like API keys, tokens, or
+    /// private keys. Defaults to true.
+    pub secrets_scan: Option<bool>,
+
+    /// Review the staged diff with a read-only `codex exec` run and block the
+    /// commit when the reviewer reports a blocking problem. Defaults to false.
+    pub review: Option<bool>,
+
+    /// Model for the review; the configured model when unset.
+    pub review_model: Option<String>,
+
+    /// Extra guidance appended to the review prompt, e.g. what counts as
+    /// blocking in this repository.
+    pub review_instructions: Option<String>,
+
+    /// Seconds the review may take. A review that runs out of time is
+    /// skipped with a warning instead of blocking the commit. Defaults to 120.
+    pub timeout_seconds: Option<u64>,
+
+    /// Style of the messages `codex commit` writes. Defaults to
+    /// `conventional`.
+    pub commit_convention: Option<CommitConvention>,
+
+    /// Extra guidance appended to the commit message prompt, e.g. a ticket
+    /// prefix or the scopes used in this repository.
+    pub commit_instructions: Option<String>,
+}
+
+/// Effective `pre_commit` settings after defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreCommitConfig {
+    pub secrets_scan: bool,
+    pub review: bool,
+    pub review_model: Option<String>,
+    pub review_instructions: Option<String>,
+    pub timeout: Duration,
+    pub commit_convention: CommitConvention,
+    pub commit_instructions: Option<String>,
+}
+
+impl Default for PreCommitConfig {
+    fn default() -> Self {
+        Self {
+            secrets_scan: true,
+            review: false,
+            review_model: None,
+            review_instructions: None,
+            timeout: Duration::from_secs(DEFAULT_PRE_COMMIT_TIMEOUT_SECONDS),
+            commit_convention: CommitConvention::default(),
+            commit_instructions: None,
+        }
+    }
+}
+
+impl From<PreCommitToml> for PreCommitConfig {
+    fn from(toml: PreCommitToml) -> Self {
+        let defaults = Self::default();
+        Self {
+            secrets_scan: toml.secrets_scan.unwrap_or(defaults.secrets_scan),
+            review: toml.review.unwrap_or(defaults.review),
+            review_model: toml.review_model,
+            review_instructions: toml.review_instructions,
+            timeout: toml
+                .timeout_seconds
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.timeout),
+            commit_convention: toml.commit_convention.unwrap_or(defaults.commit_convention),
+            commit_instructions: toml.commit_instructions,
+        }
+    }
+}
+
+// ===== Budget configuration =====
+
+const DEFAULT_BUDGET_WARN_AT_PERCENT: u8 = 80;
+
+/// USD prices of one model, per million tokens.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+
+    /// Price of input tokens served from the prompt cache. Defaults to
+    /// `input_per_million`.
+    pub cached_input_per_million: Option<f64>,
+
+    /// Price of output tokens, including reasoning tokens.
+    pub output_per_million: f64,
+}
+
+/// `[budget]`: estimated cost tracking and spending limits. Costs are only
+/// tracked for models listed under `[budget.prices]`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct BudgetToml {
+    /// Stop the session from starting new turns (and stop the running turn)
+    /// once its estimated cost reaches this many USD.
+    pub max_usd_per_session: Option<f64>,
+
+    /// Stop a turn once its estimated cost reaches this many USD.
+    pub max_usd_per_turn: Option<f64>,
+
+    /// Warn once spending reaches this percentage of a limit. Defaults to 80.
+    pub warn_at_percent: Option<u8>,
+
+    /// Prices by model slug. A key also applies to every model whose slug
+    /// starts with it; the longest matching key wins.
+    #[serde(default)]
+    pub prices: BTreeMap<String, ModelPrice>,
+}
+
+/// Effective `budget` settings after defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetConfig {
+    pub max_usd_per_session: Option<f64>,
+    pub max_usd_per_turn: Option<f64>,
+    pub warn_at_percent: u8,
+    pub prices: BTreeMap<String, ModelPrice>,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            max_usd_per_session: None,
+            max_usd_per_turn: None,
+            warn_at_percent: DEFAULT_BUDGET_WARN_AT_PERCENT,
+            prices: BTreeMap::new(),
+        }
+    }
+}
+
+impl From<BudgetToml> for BudgetConfig {
+    fn from(toml: BudgetToml) -> Self {
+        Self {
+            max_usd_per_session: toml.max_usd_per_session,
+            max_usd_per_turn: toml.max_usd_per_turn,
+            warn_at_percent: toml
+                .warn_at_percent
+                .unwrap_or(DEFAULT_BUDGET_WARN_AT_PERCENT)
+                .min(100),
+            prices: toml.prices,
+        }
+    }
+}
+
+impl BudgetConfig {
+    /// Price of `model`: an exact entry, else the longest key that `model`
+    /// starts with.
+    pub fn price_for(&self, model: &str) -> Option<&ModelPrice> {
+        self.prices.get(model).or_else(|| {
+            self.prices
+                .iter()
+                .filter(|(key, _)| model.starts_with(key.as_str()))
+                .max_by_key(|(key, _)| key.len())
+                .map(|(_, price)| price)
+        })
+    }
+}
+
+// ===== Semantic index configuration =====
+
+const DEFAULT_EMBEDDING_BATCH_SIZE: usize = 32;
+
+/// How the `semantic_search` index turns code into vectors.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingBackend {
+    /// Feature hashing over identifiers. Instant and model-free, but only
+    /// matches code that shares words with the query.
+    #[default]
+    Hashing,
+    /// A local embedding model served by `command`, such as a candle or ONNX
+    /// Runtime script. Source code never leaves the machine.
+    Model,
+}
+
+/// Hardware a local embedding model should run on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingDevice {
+    /// CUDA when an NVIDIA driver is present, Metal on Apple silicon,
+    /// otherwise the CPU.
+    #[default]
+    Auto,
+    /// The CPU.
+    Cpu,
+    /// An NVIDIA GPU through CUDA.
+    Cuda,
+    /// An Apple GPU through Metal.
+    Metal,
+}
+
+/// `[semantic_index]`: how the workspace index behind `semantic_search`
+/// embeds code.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SemanticIndexToml {
+    /// `hashing` (default) or `model`.
+    pub embedding: Option<EmbeddingBackend>,
+
+    /// Program and arguments serving the local model when `embedding =
+    /// "model"`. It reads `{"texts": [...]}` lines on stdin and answers each
+    /// with an `{"embeddings": [[...], ...]}` line on stdout.
+    pub command: Option<Vec<String>>,
+
+    /// Model the command should load, passed as `CODEX_EMBEDDING_MODEL`.
+    /// The index remembers it, so changing it re-embeds every file.
+    pub model: Option<String>,
+
+    /// Device passed to the command as `CODEX_EMBEDDING_DEVICE`. Defaults to
+    /// `auto`.
+    pub device: Option<EmbeddingDevice>,
+
+    /// Texts sent to the command per request. Defaults to 32.
+    pub batch_size: Option<usize>,
+}
+
+/// Effective `semantic_index` settings after defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SemanticIndexConfig {
+    pub embedding: EmbeddingBackend,
+    pub command: Vec<String>,
+    pub model: Option<String>,
+    pub device: EmbeddingDevice,
+    pub batch_size: usize,
+}
+
+impl Default for SemanticIndexConfig {
+    fn default() -> Self {
+        Self {
+            embedding: EmbeddingBackend::Hashing,
+            command: Vec::new(),
+            model: None,
+            device: EmbeddingDevice::Auto,
+            batch_size: DEFAULT_EMBEDDING_BATCH_SIZE,
+        }
+    }
+}
+
+impl From<SemanticIndexToml> for SemanticIndexConfig {
+    fn from(toml: SemanticIndexToml) -> Self {
+        Self {
+            embedding: toml.embedding.unwrap_or_default(),
+            command: toml.command.unwrap_or_default(),
+            model: toml.model,
+            device: toml.device.unwrap_or_default(),
+            batch_size: toml
+                .batch_size
+                .unwrap_or(DEFAULT_EMBEDDING_BATCH_SIZE)
+                .max(1),
+        }
+    }
+}
+
+// ===== Governor configuration =====
+
+/// `[governor]`: limits shared by every Codex process using the same
+/// `CODEX_HOME`. Leaving a limit unset leaves it unbounded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct GovernorToml {
+    /// Maximum number of model requests in flight across all sessions.
+    pub max_concurrent_requests: Option<usize>,
+
+    /// Maximum number of tokens used per minute across all sessions.
+    pub tokens_per_minute: Option<u64>,
+
+    /// When an account is rate-limited, make the other sessions on it wait
+    /// out the cooldown too. Defaults to `true`.
+    pub share_rate_limits: Option<bool>,
+}
+
+/// Effective `[governor]` settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GovernorConfig {
+    pub max_concurrent_requests: Option<usize>,
+    pub tokens_per_minute: Option<u64>,
+    pub share_rate_limits: bool,
+}
+
+impl From<GovernorToml> for GovernorConfig {
+    fn from(toml: GovernorToml) -> Self {
+        Self {
+            max_concurrent_requests: toml.max_concurrent_requests,
+            tokens_per_minute: toml.tokens_per_minute,
+            share_rate_limits: toml.share_rate_limits.unwrap_or(true),
+        }
+    }
+}
+
+// ===== Audit log configuration =====
+
+/// Default size at which `audit.jsonl` is rotated.
+const DEFAULT_AUDIT_MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// `[audit]`: append-only log of commands, file writes, approvals and
+/// subagent runs under `$CODEX_HOME/audit/`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct AuditToml {
+    /// Defaults to `true` when the section is present.
+    pub enabled: Option<bool>,
+
+    /// Size in bytes at which `audit.jsonl` is rotated. Defaults to 10 MiB.
+    pub max_file_bytes: Option<u64>,
+
+    /// Number of rotated files to keep. Unset keeps all of them.
+    pub max_files: Option<usize>,
+}
+
+/// Effective `[audit]` settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditConfig {
+    pub max_file_bytes: u64,
+    pub max_files: Option<usize>,
+}
+
+impl AuditToml {
+    /// `None` when the log is disabled.
+    pub fn resolve(self) -> Option<AuditConfig> {
+        self.enabled.unwrap_or(true).then(|| AuditConfig {
+            max_file_bytes: self
+                .max_file_bytes
+                .unwrap_or(DEFAULT_AUDIT_MAX_FILE_BYTES)
+                .max(1),
+            max_files: self.max_files,
+        })
+    }
+}
+
+// ===== Telemetry configuration =====
+
+/// Wire protocol of the `[telemetry]` collector.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+pub enum TelemetryProtocol {
+    #[default]
+    #[serde(rename = "grpc")]
+    Grpc,
+    #[serde(rename = "http/protobuf")]
+    HttpProtobuf,
+    #[serde(rename = "http/json")]
+    HttpJson,
+}
+
+/// `[telemetry]`: export spans for turns, model requests, tool calls and
+/// subagent runs, and metrics for tokens, latency and errors, to an OTLP
+/// collector.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct TelemetryToml {
+    /// Defaults to `true` when the section is present.
+    pub enabled: Option<bool>,
+
+    /// Collector URL, e.g. `http://localhost:4317` for gRPC or
+    /// `http://localhost:4318` for HTTP. For HTTP, `/v1/traces` and
+    /// `/v1/metrics` are appended.
+    pub endpoint: String,
+
+    /// `grpc` (default), `http/protobuf` or `http/json`.
+    pub protocol: Option<TelemetryProtocol>,
+
+    /// Headers sent with every export, e.g. an API key.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    pub tls: Option<OtelTlsConfig>,
+
+    /// Export spans. Defaults to `true`.
+    pub traces: Option<bool>,
+
+    /// Export metrics. Defaults to `true`; replaces the built-in usage
+    /// analytics for this process.
+    pub metrics: Option<bool>,
+
+    /// `service.name` of the exported data. Defaults to the client's name
+    /// (`codex_cli_rs`, `codex_exec`, `codex_app_server`, ...).
+    pub service_name: Option<String>,
+}
+
+/// Effective `[telemetry]` settings: the exporter for each signal, `None`
+/// when that signal is off.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryConfig {
+    pub trace_exporter: Option<OtelExporterKind>,
+    pub metrics_exporter: Option<OtelExporterKind>,
+    pub service_name: Option<String>,
+}
+
+impl TelemetryToml {
+    /// `None` when telemetry is disabled.
+    pub fn resolve(self) -> Option<TelemetryConfig> {
+        if !self.enabled.unwrap_or(true) {
+            return None;
+        }
+        let exporter = |signal: &str| {
+            let protocol = match self.protocol.unwrap_or_default() {
+                TelemetryProtocol::Grpc => {
+                    return OtelExporterKind::OtlpGrpc {
+                        endpoint: self.endpoint.clone(),
+                        headers: self.headers.clone(),
+                        tls: self.tls.clone(),
+                    };
+                }
+                TelemetryProtocol::HttpProtobuf => OtelHttpProtocol::Binary,
+                TelemetryProtocol::HttpJson => OtelHttpProtocol::Json,
+            };
+            OtelExporterKind::OtlpHttp {
+                endpoint: format!("{}/v1/{signal}", self.endpoint.trim_end_matches('/')),
+                headers: self.headers.clone(),
+                protocol,
+                tls: self.tls.clone(),
+            }
+        };
+        Some(TelemetryConfig {
+            trace_exporter: self.traces.unwrap_or(true).then(|| exporter("traces")),
+            metrics_exporter: self.metrics.unwrap_or(true).then(|| exporter("metrics")),
+            service_name: self.service_name.clone(),
+        })
+    }
+}
+
+// ===== Worktree isolation configuration =====
+
+/// Default prefix of the branches created for isolated worktrees.
+const DEFAULT_WORKTREE_BRANCH_PREFIX: &str = "codex/";
+
+/// How long an isolated worktree lives.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WorktreeScope {
+    /// One worktree for the whole session, reviewed with `/worktree`.
+    #[default]
+    Session,
+    /// The worktree is reviewed at the end of every turn; a new one is
+    /// created for the next turn once it is merged or discarded.
+    Turn,
+}
+
+/// `[worktree_isolation]`: run the agent against a git worktree on its own
+/// branch instead of the checked-out tree.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WorktreeIsolationToml {
+    /// Defaults to `true` when the section is present.
+    pub enabled: Option<bool>,
+
+    /// `session` (default) or `turn`.
+    pub scope: Option<WorktreeScope>,
+
+    /// Prefix of the branch created for each worktree. Defaults to `codex/`.
+    pub branch_prefix: Option<String>,
+}
+
+/// Effective `[worktree_isolation]` settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorktreeIsolationConfig {
+    pub scope: WorktreeScope,
+    pub branch_prefix: String,
+}
 
-    pub fn serialize<S>(value: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        match value {
-            Some(duration) => serializer.serialize_some(&duration.as_secs_f64()),
-            None => serializer.serialize_none(),
-        }
+impl WorktreeIsolationToml {
+    /// `None` when isolation is disabled.
+    pub fn resolve(self) -> Option<WorktreeIsolationConfig> {
+        self.enabled
+            .unwrap_or(true)
+            .then(|| WorktreeIsolationConfig {
+                scope: self.scope.unwrap_or_default(),
+                branch_prefix: self
+                    .branch_prefix
+                    .unwrap_or_else(|| DEFAULT_WORKTREE_BRANCH_PREFIX.to_string()),
+            })
     }
+}
 
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let secs = Option::<f64>::deserialize(deserializer)?;
-        secs.map(|secs| Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom))
-            .transpose()
-    }
+/// `[gemini]`: request options that only apply to the Gemini provider.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct GeminiToml {
+    /// Sent as `safetySettings` on every request, e.g.
+    /// `{ category = "HARM_CATEGORY_DANGEROUS_CONTENT", threshold = "BLOCK_ONLY_HIGH" }`.
+    #[serde(default)]
+    pub safety_settings: Vec<GeminiSafetySetting>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, JsonSchema)]
-pub enum UriBasedFileOpener {
-    #[serde(rename = "vscode")]
-    VsCode,
+/// One Gemini safety setting; values are passed through unchecked.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct GeminiSafetySetting {
+    pub category: String,
+    pub threshold: String,
+}
 
-    #[serde(rename = "vscode-insiders")]
-    VsCodeInsiders,
+/// `[local_models]`: how local model servers (Ollama, LM Studio, llama.cpp,
+/// vLLM) are probed for their context length and tool-call support.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct LocalModelsToml {
+    /// Defaults to `true`.
+    pub probe: Option<bool>,
 
-    #[serde(rename = "windsurf")]
-    Windsurf,
+    /// Provider ids to probe besides the built-in `ollama`, `ollama-chat` and
+    /// `lmstudio`, e.g. a `[model_providers.vllm]` entry.
+    #[serde(default)]
+    pub providers: Vec<String>,
+}
 
-    #[serde(rename = "cursor")]
-    Cursor,
+/// Effective `[local_models]` settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalModelsConfig {
+    pub probe: bool,
+    pub providers: Vec<String>,
+}
 
-    /// Option to disable the URI-based file opener.
-    #[serde(rename = "none")]
-    None,
+impl Default for LocalModelsConfig {
+    fn default() -> Self {
+        Self {
+            probe: true,
+            providers: Vec::new(),
+        }
+    }
 }
 
-impl UriBasedFileOpener {
-    pub fn get_scheme(&self) -> Option<&str> {
-        match self {
-            UriBasedFileOpener::VsCode => Some("vscode"),
-            UriBasedFileOpener::VsCodeInsiders => Some("vscode-insiders"),
-            UriBasedFileOpener::Windsurf => Some("windsurf"),
-            UriBasedFileOpener::Cursor => Some("cursor"),
-            UriBasedFileOpener::None => None,
+impl From<LocalModelsToml> for LocalModelsConfig {
+    fn from(toml: LocalModelsToml) -> Self {
+        Self {
+            probe: toml.probe.unwrap_or(true),
+            providers: toml.providers,
         }
     }
 }
 
-/// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
+/// `[network]`: proxy and extra CA certificates used by every HTTP client
+/// (model API, MCP servers and login).
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[schemars(deny_unknown_fields)]
-pub struct History {
-    /// If true, history entries will not be written to disk.
-    pub persistence: HistoryPersistence,
+pub struct NetworkToml {
+    /// Proxy for HTTP and HTTPS requests, e.g. `http://proxy.corp:8080`. When
+    /// unset, `HTTPS_PROXY` and friends apply as before.
+    pub proxy: Option<String>,
 
-    /// If set, the maximum size of the history file in bytes. The oldest entries
-    /// are dropped once the file exceeds this limit.
-    pub max_bytes: Option<usize>,
+    /// Hosts that bypass `proxy`; defaults to `NO_PROXY`.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+
+    /// Basic-auth user for the proxy.
+    pub proxy_username: Option<String>,
+
+    /// Environment variable holding the proxy password.
+    pub proxy_password_env: Option<String>,
+
+    /// PEM files trusted in addition to the system roots, such as the CA of
+    /// a TLS-inspecting gateway.
+    #[serde(default)]
+    pub ca_certificates: Vec<AbsolutePathBuf>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Default, JsonSchema)]
-#[serde(rename_all = "kebab-case")]
-pub enum HistoryPersistence {
-    /// Save all history entries to disk.
-    #[default]
-    SaveAll,
-    /// Do not write history to disk.
-    None,
+impl NetworkToml {
+    /// Reads the proxy password from its environment variable.
+    pub fn resolve(self) -> Result<NetworkSettings, String> {
+        let password = match &self.proxy_password_env {
+            Some(var) => Some(std::env::var(var).map_err(|_| {
+                format!("network.proxy_password_env: environment variable `{var}` is not set")
+            })?),
+            None => None,
+        };
+        let basic_auth = match (self.proxy_username, password) {
+            (Some(username), password) => Some(ProxyBasicAuth {
+                username,
+                password: password.unwrap_or_default(),
+            }),
+            (None, Some(_)) => {
+                return Err(
+                    "network.proxy_password_env requires network.proxy_username".to_string()
+                );
+            }
+            (None, None) => None,
+        };
+        let proxy = match self.proxy {
+            Some(url) => Some(ProxySettings {
+                url,
+                no_proxy: self.no_proxy,
+                basic_auth,
+            }),
+            None if basic_auth.is_some() => {
+                return Err("network.proxy_username requires network.proxy".to_string());
+            }
+            None => None,
+        };
+        Ok(NetworkSettings {
+            proxy,
+            ca_certificates: self
+                .ca_certificates
+                .into_iter()
+                .map(AbsolutePathBuf::into_path_buf)
+                .collect(),
+        })
+    }
 }
 
-// ===== Analytics configuration =====
+/// Tools that reach the internet; disabled in offline mode unless listed in
+/// `offline.allow_tools`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OfflineTool {
+    WebSearch,
+    WebFetch,
+    Browser,
+}
 
-/// Analytics settings loaded from config.toml. Fields are optional so we can apply defaults.
+/// `[offline]`: restrict Codex to local endpoints on isolated networks.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[schemars(deny_unknown_fields)]
-pub struct AnalyticsConfigToml {
-    /// When `false`, disables analytics across Codex product surfaces in this profile.
+pub struct OfflineToml {
+    /// Defaults to `true` when the section is present.
     pub enabled: Option<bool>,
+
+    /// Hosts treated as local besides loopback and private addresses, e.g.
+    /// an internal model gateway. A leading `.` matches subdomains.
+    #[serde(default)]
+    pub allow_hosts: Vec<String>,
+
+    /// Network tools to keep, e.g. `web_search` backed by a local SearXNG.
+    #[serde(default)]
+    pub allow_tools: Vec<OfflineTool>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
-#[schemars(deny_unknown_fields)]
-pub struct FeedbackConfigToml {
-    /// When `false`, disables the feedback flow across Codex product surfaces.
-    pub enabled: Option<bool>,
+/// Effective `[offline]` settings.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OfflineConfig {
+    pub allow_hosts: Vec<String>,
+    pub allow_tools: Vec<OfflineTool>,
+}
+
+impl OfflineToml {
+    /// `None` when offline mode is disabled.
+    pub fn resolve(self) -> Option<OfflineConfig> {
+        self.enabled.unwrap_or(true).then_some(OfflineConfig {
+            allow_hosts: self.allow_hosts,
+            allow_tools: self.allow_tools,
+        })
+    }
 }
 
 // ===== OTEL configuration =====
@@ -420,15 +1801,39 @@ impl Default for Notifications {
     }
 }
 
+/// How the TUI posts desktop notifications.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationMethod {
+    /// OSC 9 escape sequences, or Windows toasts in Windows Terminal under WSL.
+    #[default]
+    Auto,
+    /// Always OSC 9 escape sequences, which the terminal turns into notifications.
+    Osc9,
+    /// The system notifier: `notify-send` on Linux, `osascript` on macOS and
+    /// toasts on Windows and WSL.
+    Native,
+}
+
 /// Collection of settings that are specific to the TUI.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct Tui {
     /// Enable desktop notifications from the TUI when the terminal is unfocused.
-    /// Defaults to `true`.
+    /// Defaults to `true`. A list enables only the named triggers:
+    /// `agent-turn-complete` and `approval-requested`.
     #[serde(default)]
     pub notifications: Notifications,
 
+    /// How notifications are posted: `auto` (default), `osc9` or `native`.
+    #[serde(default)]
+    pub notification_method: NotificationMethod,
+
+    /// Only notify about a finished turn that ran at least this many seconds.
+    /// Defaults to `0` (every turn).
+    #[serde(default)]
+    pub notification_min_turn_sec: u64,
+
     /// Enable animations (welcome screen, shimmer effects, spinners).
     /// Defaults to `true`.
     #[serde(default = "default_true")]
@@ -454,12 +1859,271 @@ pub struct Tui {
     /// scrollback in terminal multiplexers like Zellij that follow the xterm spec.
     #[serde(default)]
     pub alternate_screen: AltScreenMode,
+
+    /// Push-to-talk voice input in the composer. Disabled unless set.
+    #[serde(default)]
+    pub voice: Option<VoiceToml>,
+
+    /// Remapped keys for TUI actions. Unset actions keep their defaults.
+    #[serde(default)]
+    pub keybindings: Option<KeybindingsToml>,
+
+    /// Color theme: `default`, `solarized`, `gruvbox` or `high-contrast`.
+    /// `default` keeps the terminal's own ANSI colors. Can be switched at
+    /// runtime with `/theme`.
+    #[serde(default)]
+    pub theme: Option<String>,
+
+    /// Which variant of the theme to use.
+    ///
+    /// - `auto` (default): light on a light terminal background, dark
+    ///   otherwise.
+    /// - `light` / `dark`: always use that variant.
+    #[serde(default)]
+    pub theme_variant: ThemeVariant,
+
+    /// Colors that replace the theme's own, whichever theme is active.
+    #[serde(default)]
+    pub theme_palette: Option<ThemePaletteToml>,
+
+    /// Items of the status line shown below the composer, in order, e.g.
+    /// `["model", "context", "cost"]`. The status line is hidden unless set.
+    #[serde(default)]
+    pub status_line: Option<Vec<StatusLineItem>>,
+
+    /// How the composer edits text: `emacs` (default) or `vim` for modal
+    /// editing with normal and insert modes.
+    #[serde(default)]
+    pub editing_mode: EditingMode,
 }
 
 const fn default_true() -> bool {
     true
 }
 
+// ===== Keybinding configuration =====
+
+/// One key such as `"enter"`, `"ctrl-j"`, `"shift-enter"` or `"f2"`, or a
+/// list of them. An empty list unbinds the action.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    pub fn keys(&self) -> &[String] {
+        match self {
+            KeyList::One(key) => std::slice::from_ref(key),
+            KeyList::Many(keys) => keys,
+        }
+    }
+}
+
+/// `[tui.keybindings]`: keys for the TUI actions that can be remapped. The
+/// TUI parses the keys and rejects a key bound to two actions that are
+/// active at the same time.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct KeybindingsToml {
+    /// Send the composer's message. Defaults to `enter`.
+    pub submit: Option<KeyList>,
+
+    /// Insert a newline in the composer. Defaults to `["shift-enter", "ctrl-j"]`.
+    pub newline: Option<KeyList>,
+
+    /// Interrupt the running turn. Defaults to `esc`.
+    pub interrupt: Option<KeyList>,
+
+    /// Switch to the next session tab. Defaults to `ctrl-pagedown`.
+    pub next_tab: Option<KeyList>,
+
+    /// Switch to the previous session tab. Defaults to `ctrl-pageup`.
+    pub previous_tab: Option<KeyList>,
+
+    /// Scroll the transcript and other pagers up one line. Defaults to `["up", "k"]`.
+    pub scroll_up: Option<KeyList>,
+
+    /// Scroll down one line. Defaults to `["down", "j"]`.
+    pub scroll_down: Option<KeyList>,
+
+    /// Scroll up one page. Defaults to `["pageup", "shift-space", "ctrl-b"]`.
+    pub page_up: Option<KeyList>,
+
+    /// Scroll down one page. Defaults to `["pagedown", "space", "ctrl-f"]`.
+    pub page_down: Option<KeyList>,
+
+    /// Approve a command, patch or tool call. Defaults to `y`.
+    pub approve: Option<KeyList>,
+
+    /// Approve and stop asking for the rest of the session. Defaults to `a`.
+    pub approve_for_session: Option<KeyList>,
+
+    /// Decline an approval request. Defaults to `n`.
+    pub deny: Option<KeyList>,
+}
+
+// ===== Editing mode configuration =====
+
+/// Key scheme of the TUI composer (`tui.editing_mode`).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EditingMode {
+    /// Always inserting, with Emacs-style Ctrl/Alt shortcuts.
+    #[default]
+    Emacs,
+    /// Modal editing: `Esc` switches to normal mode for motions, operators
+    /// and registers; `i`, `a` and friends go back to inserting.
+    Vim,
+}
+
+// ===== Status line configuration =====
+
+/// One item of the TUI status line (`tui.status_line`).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusLineItem {
+    /// Active model and reasoning effort.
+    Model,
+    /// Percentage of the context window left.
+    Context,
+    /// Tokens used so far in the session.
+    Tokens,
+    /// Session cost in USD, once the model's prices are known.
+    Cost,
+    /// Sandbox and approval mode.
+    Sandbox,
+    /// Number of running background tasks and subagents.
+    Tasks,
+}
+
+// ===== Theme configuration =====
+
+/// Light or dark variant of a TUI theme.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeVariant {
+    /// Follow the terminal's background color.
+    #[default]
+    Auto,
+    /// Colors meant for a light background.
+    Light,
+    /// Colors meant for a dark background.
+    Dark,
+}
+
+/// `[tui.theme_palette]`: colors as `#rrggbb` or an ANSI color name such as
+/// `cyan` or `light-red`. Unset roles keep the theme's color.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ThemePaletteToml {
+    /// Selection, hints and status indicators (ANSI cyan by default).
+    pub accent: Option<String>,
+
+    /// Success and added lines (ANSI green by default).
+    pub success: Option<String>,
+
+    /// Errors, failures and removed lines (ANSI red by default).
+    pub error: Option<String>,
+
+    /// Codex's own messages and headers (ANSI magenta by default).
+    pub codex: Option<String>,
+
+    /// Background of the user's messages. Defaults to a shade of the
+    /// terminal background.
+    pub user_message_bg: Option<String>,
+}
+
+// ===== Voice input configuration =====
+
+const DEFAULT_WHISPER_CPP_BINARY: &str = "whisper-cli";
+
+/// Speech-to-text backend for `[tui.voice]`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceBackend {
+    /// The whisper.cpp command-line tool with a local GGML model.
+    #[default]
+    WhisperCpp,
+    /// Any command that prints the transcript of `{input}` to stdout.
+    Command,
+}
+
+/// `[tui.voice]`: how the composer records and transcribes speech.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct VoiceToml {
+    /// Command that records from the default microphone into the WAV file
+    /// `{output}` until it is interrupted. Defaults to SoX:
+    /// `["rec", "-q", "-c", "1", "-r", "16000", "-b", "16", "{output}"]`.
+    pub record_command: Option<Vec<String>>,
+
+    /// Transcription backend. Defaults to `whisper_cpp`.
+    pub backend: Option<VoiceBackend>,
+
+    /// whisper.cpp executable. Defaults to `whisper-cli`.
+    pub whisper_cpp_binary: Option<String>,
+
+    /// GGML model used by whisper.cpp, e.g. `ggml-base.en.bin`. Required for
+    /// the `whisper_cpp` backend.
+    pub whisper_cpp_model: Option<PathBuf>,
+
+    /// Command for the `command` backend; `{input}` is replaced with the
+    /// recorded WAV file.
+    pub transcribe_command: Option<Vec<String>>,
+
+    /// Spoken language, e.g. `en` or `ja`. whisper.cpp detects it when unset.
+    pub language: Option<String>,
+}
+
+/// Effective `tui.voice` settings after defaults are applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoiceConfig {
+    pub record_command: Vec<String>,
+    pub backend: VoiceBackend,
+    pub whisper_cpp_binary: String,
+    pub whisper_cpp_model: Option<PathBuf>,
+    pub transcribe_command: Vec<String>,
+    pub language: Option<String>,
+}
+
+impl Default for VoiceConfig {
+    fn default() -> Self {
+        Self {
+            record_command: [
+                "rec", "-q", "-c", "1", "-r", "16000", "-b", "16", "{output}",
+            ]
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+            backend: VoiceBackend::WhisperCpp,
+            whisper_cpp_binary: DEFAULT_WHISPER_CPP_BINARY.to_string(),
+            whisper_cpp_model: None,
+            transcribe_command: Vec::new(),
+            language: None,
+        }
+    }
+}
+
+impl From<VoiceToml> for VoiceConfig {
+    fn from(toml: VoiceToml) -> Self {
+        let defaults = Self::default();
+        Self {
+            record_command: toml.record_command.unwrap_or(defaults.record_command),
+            backend: toml.backend.unwrap_or(defaults.backend),
+            whisper_cpp_binary: toml
+                .whisper_cpp_binary
+                .unwrap_or(defaults.whisper_cpp_binary),
+            whisper_cpp_model: toml.whisper_cpp_model,
+            transcribe_command: toml
+                .transcribe_command
+                .unwrap_or(defaults.transcribe_command),
+            language: toml.language,
+        }
+    }
+}
+
 /// Settings for notices we display to users via the tui and app-server clients
 /// (primarily the Codex IDE extension). NOTE: these are different from
 /// notifications - notices are warnings, NUX screens, acknowledgements, etc.
@@ -508,12 +2172,106 @@ pub struct SandboxWorkspaceWrite {
     pub writable_roots: Vec<AbsolutePathBuf>,
     #[serde(default)]
     pub network_access: bool,
+    /// Domains commands may still reach while `network_access` is off. Traffic
+    /// is forced through a local proxy that only connects to these hosts and
+    /// their subdomains.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub network_allowlist: Vec<String>,
     #[serde(default)]
     pub exclude_tmpdir_env_var: bool,
     #[serde(default)]
     pub exclude_slash_tmp: bool,
 }
 
+/// `[exec_limits]`: resource limits for each command run by the `shell` tool.
+/// Unset fields are unlimited.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ExecLimits {
+    /// Wall-clock seconds, enforced even when the model asks for a longer
+    /// timeout.
+    pub max_runtime_secs: Option<u64>,
+
+    /// CPU seconds per process (`RLIMIT_CPU`). Unix only.
+    pub max_cpu_secs: Option<u64>,
+
+    /// Resident memory of the command and everything it starts, in MiB.
+    /// Linux and macOS only.
+    pub max_memory_mb: Option<u64>,
+
+    /// Combined stdout and stderr bytes.
+    pub max_output_bytes: Option<u64>,
+
+    /// Scheduling niceness, `0`-`19`. Unix only.
+    pub nice: Option<i32>,
+}
+
+/// Backend that runs sandboxed commands (`[sandbox].backend`).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxBackendKind {
+    /// Seatbelt on macOS, Landlock + seccomp on Linux, the restricted-token
+    /// sandbox on Windows.
+    #[default]
+    Platform,
+    /// A Docker or Podman container with the writable roots bind-mounted.
+    Container,
+}
+
+/// Container engine CLI used by the container sandbox.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn program(self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+}
+
+/// `[sandbox]`: selects where sandboxed commands run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SandboxToml {
+    /// Defaults to `platform`.
+    pub backend: Option<SandboxBackendKind>,
+
+    pub container: Option<ContainerSandboxToml>,
+}
+
+/// `[sandbox.container]`: settings for `backend = "container"`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ContainerSandboxToml {
+    /// Image commands run in. Required for the container backend; it must
+    /// provide the shell Codex invokes commands with.
+    pub image: Option<String>,
+
+    /// Defaults to `docker`.
+    pub runtime: Option<ContainerRuntime>,
+
+    /// Extra arguments passed to `<runtime> run` before the image, e.g.
+    /// `["--memory=4g"]`.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Resolved container sandbox settings, present when
+/// `sandbox.backend = "container"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerSandboxConfig {
+    pub runtime: ContainerRuntime,
+    pub image: String,
+    pub args: Vec<String>,
+}
+
 impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings {
     fn from(sandbox_workspace_write: SandboxWorkspaceWrite) -> Self {
         Self {
@@ -851,6 +2609,31 @@ mod tests {
         assert_eq!(cfg.disabled_tools, Some(vec!["blocked".to_string()]));
     }
 
+    #[test]
+    fn deserialize_server_config_with_tool_approvals() {
+        let cfg: McpServerConfig = toml::from_str(
+            r#"
+            command = "echo"
+            default_tool_approval = "always_ask"
+
+            [tool_approvals]
+            list_issues = "auto_approve"
+            delete_repo = "blocked"
+        "#,
+        )
+        .expect("should deserialize tool approvals");
+
+        assert_eq!(
+            cfg.tool_approval("list_issues"),
+            McpToolApproval::AutoApprove
+        );
+        assert_eq!(cfg.tool_approval("delete_repo"), McpToolApproval::Blocked);
+        assert_eq!(
+            cfg.tool_approval("create_issue"),
+            McpToolApproval::AlwaysAsk
+        );
+    }
+
     #[test]
     fn deserialize_rejects_command_and_url() {
         toml::from_str::<McpServerConfig>(
@@ -0,0 +1,760 @@
+//! Checks config layers against the `config.toml` schema.
+//!
+//! Deserializing [`ConfigToml`](super::ConfigToml) ignores keys it does not
+//! know, so a misspelled key silently has no effect, and a value of the wrong
+//! type fails the whole load without naming the file it came from.
+//! [`validate_config_layers`] walks every layer against [`config_schema`] and
+//! reports unknown keys, type mismatches, and options that have been replaced,
+//! each with the file and line it appears on and a suggested fix.
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use codex_app_server_protocol::ConfigLayerSource;
+use schemars::schema::InstanceType;
+use schemars::schema::RootSchema;
+use schemars::schema::Schema;
+use schemars::schema::SchemaObject;
+use schemars::schema::SingleOrVec;
+use serde_json::Value as JsonValue;
+use toml::Value as TomlValue;
+use toml::de::DeTable;
+use toml::de::DeValue;
+
+use super::CONFIG_TOML_FILE;
+use super::schema::config_schema;
+use crate::config_loader::ConfigLayerStack;
+use crate::features::Feature;
+use crate::features::legacy_feature_for_key;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigDiagnosticKind {
+    /// A key the schema does not know; it has no effect.
+    UnknownKey,
+    /// A value of the wrong type; the configuration does not load.
+    TypeMismatch,
+    /// An option that still works (or is ignored) but has been replaced.
+    Deprecated,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDiagnostic {
+    pub kind: ConfigDiagnosticKind,
+    /// The layer the key was found in.
+    pub source: ConfigLayerSource,
+    /// The file behind `source`, for layers read from one.
+    pub file: Option<PathBuf>,
+    /// Dotted path of the key, e.g. `profiles.fast.model`. Array elements
+    /// appear as `[0]`.
+    pub key_path: String,
+    /// 1-based position of the key in `file`.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    /// The key or value to use instead.
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.line, self.column) {
+            (Some(file), Some(line), Some(column)) => {
+                write!(f, "{}:{line}:{column}: ", file.display())?
+            }
+            (Some(file), _, _) => write!(f, "{}: ", file.display())?,
+            (None, _, _) => write!(f, "{}: ", describe_source(&self.source))?,
+        }
+        f.write_str(&self.message)?;
+        match (&self.suggestion, self.kind) {
+            (Some(suggestion), ConfigDiagnosticKind::Deprecated) => {
+                write!(f, "; use `{suggestion}` instead")
+            }
+            (Some(suggestion), _) => write!(f, "; did you mean `{suggestion}`?"),
+            (None, _) => Ok(()),
+        }
+    }
+}
+
+/// Validates every layer of `stack`, highest precedence first. A layer's file
+/// is read for positions only when the layer has something to report.
+pub fn validate_config_layers(stack: &ConfigLayerStack) -> Vec<ConfigDiagnostic> {
+    let schema = config_schema();
+    stack
+        .layers_high_to_low()
+        .into_iter()
+        .flat_map(|layer| {
+            validate_layer(&schema, &layer.name, &layer.config, layer_file(&layer.name))
+        })
+        .collect()
+}
+
+/// The error for a merged config that does not deserialize: the type
+/// mismatches found in its layers with their positions, or `err` when the
+/// schema does not explain it.
+pub(crate) fn invalid_config_error(stack: &ConfigLayerStack, err: toml::de::Error) -> io::Error {
+    let mismatches: Vec<String> = validate_config_layers(stack)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.kind == ConfigDiagnosticKind::TypeMismatch)
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+    if mismatches.is_empty() {
+        return io::Error::new(io::ErrorKind::InvalidData, err);
+    }
+    io::Error::new(io::ErrorKind::InvalidData, mismatches.join("\n"))
+}
+
+/// The file a layer is read from, if any.
+pub fn layer_file(source: &ConfigLayerSource) -> Option<PathBuf> {
+    match source {
+        ConfigLayerSource::System { file }
+        | ConfigLayerSource::User { file }
+        | ConfigLayerSource::LegacyManagedConfigTomlFromFile { file } => Some(file.to_path_buf()),
+        ConfigLayerSource::Project { dot_codex_folder } => {
+            Some(dot_codex_folder.as_path().join(CONFIG_TOML_FILE))
+        }
+        ConfigLayerSource::Mdm { .. }
+        | ConfigLayerSource::SessionFlags
+        | ConfigLayerSource::LegacyManagedConfigTomlFromMdm => None,
+    }
+}
+
+fn describe_source(source: &ConfigLayerSource) -> &'static str {
+    match source {
+        ConfigLayerSource::SessionFlags => "-c overrides",
+        _ => "managed preferences",
+    }
+}
+
+fn validate_layer(
+    schema: &RootSchema,
+    source: &ConfigLayerSource,
+    config: &TomlValue,
+    file: Option<PathBuf>,
+) -> Vec<ConfigDiagnostic> {
+    let mut findings = Vec::new();
+    Validator { root: schema }.check_object(&schema.schema, config, &mut Vec::new(), &mut findings);
+    if findings.is_empty() {
+        return Vec::new();
+    }
+
+    let contents = file
+        .as_ref()
+        .and_then(|file| std::fs::read_to_string(file).ok());
+    findings
+        .into_iter()
+        .map(|finding| {
+            let position = contents
+                .as_deref()
+                .and_then(|contents| position_of(contents, &finding.path));
+            ConfigDiagnostic {
+                kind: finding.kind,
+                source: source.clone(),
+                file: file.clone(),
+                key_path: key_path(&finding.path),
+                line: position.map(|(line, _)| line),
+                column: position.map(|(_, column)| column),
+                message: finding.message,
+                suggestion: finding.suggestion,
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+struct Finding {
+    kind: ConfigDiagnosticKind,
+    path: Vec<Segment>,
+    message: String,
+    suggestion: Option<String>,
+}
+
+/// What a schema accepts, for "must be ..." messages.
+enum Expected {
+    Value(JsonValue),
+    Type(InstanceType),
+}
+
+struct Validator<'a> {
+    root: &'a RootSchema,
+}
+
+impl Validator<'_> {
+    fn check(
+        &self,
+        schema: &Schema,
+        value: &TomlValue,
+        path: &mut Vec<Segment>,
+        out: &mut Vec<Finding>,
+    ) {
+        // `true` accepts anything; `false` only appears as
+        // `additionalProperties`, which `check_object` handles.
+        if let Schema::Object(object) = schema {
+            self.check_object(object, value, path, out);
+        }
+    }
+
+    fn check_object(
+        &self,
+        object: &SchemaObject,
+        value: &TomlValue,
+        path: &mut Vec<Segment>,
+        out: &mut Vec<Finding>,
+    ) {
+        if let Some(target) = self.resolve(object) {
+            self.check(target, value, path, out);
+            return;
+        }
+
+        if let Some(subschemas) = &object.subschemas {
+            for schema in subschemas.all_of.iter().flatten() {
+                self.check(schema, value, path, out);
+            }
+            let alternatives: Vec<&Schema> = subschemas
+                .any_of
+                .iter()
+                .chain(subschemas.one_of.iter())
+                .flatten()
+                .collect();
+            if !alternatives.is_empty() {
+                self.check_alternatives(&alternatives, value, path, out);
+            }
+        }
+
+        if let Some(types) = &object.instance_type
+            && !instance_types(types).iter().any(|ty| fits(*ty, value))
+        {
+            let schema = Schema::Object(object.clone());
+            out.push(self.mismatch(&[&schema], value, path));
+            return;
+        }
+        let allowed = object
+            .enum_values
+            .clone()
+            .or_else(|| object.const_value.clone().map(|value| vec![value]));
+        if let Some(allowed) = allowed
+            && !allowed.iter().any(|allowed| same_value(allowed, value))
+        {
+            let schema = Schema::Object(object.clone());
+            out.push(self.mismatch(&[&schema], value, path));
+            return;
+        }
+
+        match value {
+            TomlValue::Table(table) => {
+                let Some(validation) = &object.object else {
+                    return;
+                };
+                for (key, child) in table {
+                    path.push(Segment::Key(key.clone()));
+                    if let Some((replacement, ignored)) = deprecation(path) {
+                        let effect = if ignored { " and ignored" } else { "" };
+                        out.push(Finding {
+                            kind: ConfigDiagnosticKind::Deprecated,
+                            path: path.clone(),
+                            message: format!("`{}` is deprecated{effect}", key_path(path)),
+                            suggestion: Some(replacement),
+                        });
+                    } else if let Some(property) = validation.properties.get(key) {
+                        self.check(property, child, path, out);
+                    } else {
+                        match validation.additional_properties.as_deref() {
+                            Some(Schema::Bool(false)) => out.push(Finding {
+                                kind: ConfigDiagnosticKind::UnknownKey,
+                                path: path.clone(),
+                                message: format!("unknown key `{}`", key_path(path)),
+                                suggestion: closest(key, validation.properties.keys()),
+                            }),
+                            Some(schema) => self.check(schema, child, path, out),
+                            None => {}
+                        }
+                    }
+                    path.pop();
+                }
+            }
+            TomlValue::Array(items) => {
+                let Some(SingleOrVec::Single(item_schema)) =
+                    object.array.as_ref().and_then(|array| array.items.as_ref())
+                else {
+                    return;
+                };
+                for (index, item) in items.iter().enumerate() {
+                    path.push(Segment::Index(index));
+                    self.check(item_schema, item, path, out);
+                    path.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// `anyOf` / `oneOf`: the value is fine if one alternative accepts it.
+    /// Otherwise the findings of the closest alternative are reported — one
+    /// that accepts the value's shape, with the fewest findings — or a single
+    /// mismatch listing everything the alternatives accept.
+    fn check_alternatives(
+        &self,
+        alternatives: &[&Schema],
+        value: &TomlValue,
+        path: &mut Vec<Segment>,
+        out: &mut Vec<Finding>,
+    ) {
+        let mut closest: Option<Vec<Finding>> = None;
+        for alternative in alternatives {
+            let mut findings = Vec::new();
+            self.check(alternative, value, path, &mut findings);
+            if findings.is_empty() {
+                return;
+            }
+            let rejects_value = findings.iter().any(|finding| {
+                finding.kind == ConfigDiagnosticKind::TypeMismatch
+                    && finding.path.len() == path.len()
+            });
+            if !rejects_value
+                && closest
+                    .as_ref()
+                    .is_none_or(|best| findings.len() < best.len())
+            {
+                closest = Some(findings);
+            }
+        }
+        match closest {
+            Some(findings) => out.extend(findings),
+            None => out.push(self.mismatch(alternatives, value, path)),
+        }
+    }
+
+    fn mismatch(&self, schemas: &[&Schema], value: &TomlValue, path: &[Segment]) -> Finding {
+        let mut expected = Vec::new();
+        for schema in schemas {
+            self.expected(schema, &mut expected);
+        }
+        let mut accepted: Vec<String> = Vec::new();
+        for item in &expected {
+            let text = match item {
+                Expected::Value(value) => value.to_string(),
+                Expected::Type(ty) => type_name(*ty).to_string(),
+            };
+            if !accepted.contains(&text) {
+                accepted.push(text);
+            }
+        }
+        let suggestion = match value {
+            TomlValue::String(value) => closest(
+                value,
+                expected.iter().filter_map(|item| match item {
+                    Expected::Value(JsonValue::String(allowed)) => Some(allowed),
+                    _ => None,
+                }),
+            ),
+            _ => None,
+        };
+        Finding {
+            kind: ConfigDiagnosticKind::TypeMismatch,
+            path: path.to_vec(),
+            message: format!(
+                "`{}` must be {}, not {}",
+                key_path(path),
+                join_or(&accepted),
+                describe_value(value)
+            ),
+            suggestion,
+        }
+    }
+
+    fn expected(&self, schema: &Schema, into: &mut Vec<Expected>) {
+        let Schema::Object(object) = schema else {
+            return;
+        };
+        if let Some(target) = self.resolve(object) {
+            self.expected(target, into);
+            return;
+        }
+        if let Some(values) = &object.enum_values {
+            into.extend(values.iter().cloned().map(Expected::Value));
+            return;
+        }
+        if let Some(value) = &object.const_value {
+            into.push(Expected::Value(value.clone()));
+            return;
+        }
+        if let Some(subschemas) = &object.subschemas {
+            let nested = subschemas
+                .all_of
+                .iter()
+                .chain(subschemas.any_of.iter())
+                .chain(subschemas.one_of.iter())
+                .flatten();
+            for schema in nested {
+                self.expected(schema, into);
+            }
+        }
+        if let Some(types) = &object.instance_type {
+            into.extend(instance_types(types).iter().copied().map(Expected::Type));
+        }
+    }
+
+    fn resolve(&self, object: &SchemaObject) -> Option<&Schema> {
+        let name = object
+            .reference
+            .as_deref()?
+            .strip_prefix("#/definitions/")?;
+        self.root.definitions.get(name)
+    }
+}
+
+/// Options that have been replaced, as the key to use instead and whether the
+/// option is ignored. Profiles accept the same options as the top level.
+fn deprecation(path: &[Segment]) -> Option<(String, bool)> {
+    let keys: Vec<&str> = path
+        .iter()
+        .map(|segment| match segment {
+            Segment::Key(key) => Some(key.as_str()),
+            Segment::Index(_) => None,
+        })
+        .collect::<Option<_>>()?;
+    let keys = match keys.as_slice() {
+        ["profiles", _, rest @ ..] if !rest.is_empty() => rest,
+        keys => keys,
+    };
+    let feature = |feature: Feature| format!("features.{}", feature.key());
+    let replacement = match keys {
+        ["experimental_instructions_file"] => {
+            return Some(("model_instructions_file".into(), true));
+        }
+        ["experimental_use_unified_exec_tool"] => feature(Feature::UnifiedExec),
+        ["experimental_use_freeform_apply_patch" | "include_apply_patch_tool"] => {
+            feature(Feature::ApplyPatchFreeform)
+        }
+        ["tools_web_search"] | ["tools", "web_search" | "web_search_request"] => {
+            feature(Feature::WebSearchRequest)
+        }
+        ["features", key] => {
+            feature(legacy_feature_for_key(key).filter(|legacy| legacy.key() != *key)?)
+        }
+        ["ghost_snapshot", "ignore_untracked_files_over_bytes"] => {
+            "ghost_snapshot.ignore_large_untracked_files".to_string()
+        }
+        ["ghost_snapshot", "large_untracked_dir_warning_threshold"] => {
+            "ghost_snapshot.ignore_large_untracked_dirs".to_string()
+        }
+        _ => return None,
+    };
+    Some((replacement, false))
+}
+
+/// 1-based line and column of the deepest key along `path` that `contents`
+/// spells out.
+fn position_of(contents: &str, path: &[Segment]) -> Option<(usize, usize)> {
+    let document = DeTable::parse(contents).ok()?;
+    let mut current: Option<&DeValue<'_>> = None;
+    let mut offset = None;
+    for segment in path {
+        let next = match (segment, current) {
+            (Segment::Key(key), None) => document
+                .get_ref()
+                .get_key_value(key.as_str())
+                .map(|(key, value)| (key.span(), value)),
+            (Segment::Key(key), Some(DeValue::Table(table))) => table
+                .get_key_value(key.as_str())
+                .map(|(key, value)| (key.span(), value)),
+            (Segment::Index(index), Some(DeValue::Array(items))) => {
+                items.get(*index).map(|item| (item.span(), item))
+            }
+            _ => None,
+        };
+        let Some((span, value)) = next else {
+            break;
+        };
+        offset = Some(span.start);
+        current = Some(value.get_ref());
+    }
+
+    let before = &contents[..offset?];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+    Some((line, column))
+}
+
+fn key_path(path: &[Segment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                let bare = !key.is_empty()
+                    && key
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+                if bare {
+                    out.push_str(key);
+                } else {
+                    out.push_str(&format!("{key:?}"));
+                }
+            }
+            Segment::Index(index) => out.push_str(&format!("[{index}]")),
+        }
+    }
+    out
+}
+
+fn instance_types(types: &SingleOrVec<InstanceType>) -> &[InstanceType] {
+    match types {
+        SingleOrVec::Single(ty) => std::slice::from_ref(ty.as_ref()),
+        SingleOrVec::Vec(types) => types,
+    }
+}
+
+fn fits(ty: InstanceType, value: &TomlValue) -> bool {
+    matches!(
+        (ty, value),
+        (
+            InstanceType::String,
+            TomlValue::String(_) | TomlValue::Datetime(_)
+        ) | (InstanceType::Integer, TomlValue::Integer(_))
+            | (
+                InstanceType::Number,
+                TomlValue::Integer(_) | TomlValue::Float(_)
+            )
+            | (InstanceType::Boolean, TomlValue::Boolean(_))
+            | (InstanceType::Array, TomlValue::Array(_))
+            | (InstanceType::Object, TomlValue::Table(_))
+    )
+}
+
+fn same_value(allowed: &JsonValue, value: &TomlValue) -> bool {
+    match (allowed, value) {
+        (JsonValue::String(allowed), TomlValue::String(value)) => allowed == value,
+        (JsonValue::Bool(allowed), TomlValue::Boolean(value)) => allowed == value,
+        (JsonValue::Number(allowed), TomlValue::Integer(value)) => allowed.as_i64() == Some(*value),
+        (JsonValue::Number(allowed), TomlValue::Float(value)) => allowed.as_f64() == Some(*value),
+        _ => false,
+    }
+}
+
+fn type_name(ty: InstanceType) -> &'static str {
+    match ty {
+        InstanceType::Null => "null",
+        InstanceType::Boolean => "a boolean",
+        InstanceType::Object => "a table",
+        InstanceType::Array => "an array",
+        InstanceType::Number => "a number",
+        InstanceType::String => "a string",
+        InstanceType::Integer => "an integer",
+    }
+}
+
+fn describe_value(value: &TomlValue) -> String {
+    match value {
+        TomlValue::String(value) => format!("{value:?}"),
+        TomlValue::Integer(value) => value.to_string(),
+        TomlValue::Float(value) => value.to_string(),
+        TomlValue::Boolean(value) => value.to_string(),
+        TomlValue::Datetime(_) => "a date-time".to_string(),
+        TomlValue::Array(_) => "an array".to_string(),
+        TomlValue::Table(_) => "a table".to_string(),
+    }
+}
+
+/// `a`, `a or b`, `a, b, or c`.
+fn join_or(items: &[String]) -> String {
+    match items {
+        [] => "something else".to_string(),
+        [only] => only.clone(),
+        [first, second] => format!("{first} or {second}"),
+        [rest @ .., last] => format!("{}, or {last}", rest.join(", ")),
+    }
+}
+
+/// The candidate closest to `input` by edit distance, if it is close enough
+/// to be a likely typo.
+fn closest<'a>(input: &str, candidates: impl IntoIterator<Item = &'a String>) -> Option<String> {
+    let limit = input.chars().count() / 3 + 1;
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(input, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_utils_absolute_path::AbsolutePathBuf;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn validate(contents: &str) -> Vec<ConfigDiagnostic> {
+        let dir = TempDir::new().expect("tempdir");
+        let path = dir.path().join(CONFIG_TOML_FILE);
+        std::fs::write(&path, contents).expect("write config");
+        let source = ConfigLayerSource::User {
+            file: AbsolutePathBuf::from_absolute_path(&path).expect("absolute path"),
+        };
+        let config: TomlValue = toml::from_str(contents).expect("parse config");
+        let mut diagnostics =
+            validate_layer(&config_schema(), &source, &config, layer_file(&source));
+        diagnostics.sort_by(|a, b| a.key_path.cmp(&b.key_path));
+        diagnostics
+    }
+
+    fn summary(diagnostics: &[ConfigDiagnostic]) -> Vec<(String, Option<usize>, Option<String>)> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.key_path.clone(),
+                    diagnostic.line,
+                    diagnostic.suggestion.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_valid_config() {
+        let diagnostics = validate(
+            r#"
+model = "gpt-5.1-codex"
+approval_policy = "on-request"
+
+[features]
+unified_exec = true
+
+[profiles.fast]
+extends = "base"
+model_reasoning_effort = "low"
+"#,
+        );
+        assert_eq!(summary(&diagnostics), Vec::new());
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_positions_and_suggestions() {
+        let diagnostics = validate(
+            r#"modle = "gpt-5.1-codex"
+
+[tui]
+animatoins = false
+
+[profiles.fast]
+sandbox_mod = "read-only"
+"#,
+        );
+
+        assert_eq!(
+            summary(&diagnostics),
+            vec![
+                ("modle".to_string(), Some(1), Some("model".to_string())),
+                (
+                    "profiles.fast.sandbox_mod".to_string(),
+                    Some(7),
+                    Some("sandbox_mode".to_string())
+                ),
+                (
+                    "tui.animatoins".to_string(),
+                    Some(4),
+                    Some("animations".to_string())
+                ),
+            ]
+        );
+        assert!(
+            diagnostics
+                .iter()
+                .all(|diagnostic| diagnostic.kind == ConfigDiagnosticKind::UnknownKey)
+        );
+        assert!(diagnostics[2].to_string().ends_with(
+            "config.toml:4:1: unknown key `tui.animatoins`; did you mean `animations`?"
+        ));
+    }
+
+    #[test]
+    fn reports_type_mismatches() {
+        let diagnostics = validate(
+            r#"approval_policy = "on-requst"
+
+[tui]
+animations = "yes"
+"#,
+        );
+
+        assert_eq!(
+            summary(&diagnostics),
+            vec![
+                (
+                    "approval_policy".to_string(),
+                    Some(1),
+                    Some("on-request".to_string())
+                ),
+                ("tui.animations".to_string(), Some(4), None),
+            ]
+        );
+        assert!(
+            diagnostics
+                .iter()
+                .all(|diagnostic| diagnostic.kind == ConfigDiagnosticKind::TypeMismatch)
+        );
+        assert_eq!(
+            diagnostics[1].message,
+            "`tui.animations` must be a boolean, not \"yes\""
+        );
+    }
+
+    #[test]
+    fn reports_deprecated_options_with_their_replacement() {
+        let diagnostics = validate(
+            r#"experimental_use_unified_exec_tool = true
+
+[profiles.old]
+experimental_instructions_file = "instructions.md"
+"#,
+        );
+
+        assert_eq!(
+            summary(&diagnostics),
+            vec![
+                (
+                    "experimental_use_unified_exec_tool".to_string(),
+                    Some(1),
+                    Some("features.unified_exec".to_string())
+                ),
+                (
+                    "profiles.old.experimental_instructions_file".to_string(),
+                    Some(4),
+                    Some("model_instructions_file".to_string())
+                ),
+            ]
+        );
+        assert!(
+            diagnostics
+                .iter()
+                .all(|diagnostic| diagnostic.kind == ConfigDiagnosticKind::Deprecated)
+        );
+    }
+}
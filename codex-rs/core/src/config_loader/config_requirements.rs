@@ -45,6 +45,9 @@ pub struct ConfigRequirements {
     pub approval_policy: Constrained<AskForApproval>,
     pub sandbox_policy: Constrained<SandboxPolicy>,
     pub mcp_servers: Option<Sourced<BTreeMap<String, McpServerRequirement>>>,
+    pub allowed_model_providers: Option<Sourced<Vec<String>>>,
+    /// When set, the `[audit]` log is on whatever `config.toml` says.
+    pub require_audit_log: Option<Sourced<bool>>,
 }
 
 impl Default for ConfigRequirements {
@@ -53,10 +56,35 @@ impl Default for ConfigRequirements {
             approval_policy: Constrained::allow_any_from_default(),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::ReadOnly),
             mcp_servers: None,
+            allowed_model_providers: None,
+            require_audit_log: None,
         }
     }
 }
 
+impl ConfigRequirements {
+    /// Fails when `provider_id` is not in `allowed_model_providers`.
+    pub fn check_model_provider(&self, provider_id: &str) -> Result<(), ConstraintError> {
+        match &self.allowed_model_providers {
+            Some(allowed) if !allowed.iter().any(|id| id == provider_id) => {
+                Err(ConstraintError::InvalidValue {
+                    field_name: "model_provider",
+                    candidate: provider_id.to_string(),
+                    allowed: format!("{:?}", allowed.value),
+                    requirement_source: allowed.source.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn audit_log_required(&self) -> bool {
+        self.require_audit_log
+            .as_ref()
+            .is_some_and(|required| required.value)
+    }
+}
+
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum McpServerIdentity {
@@ -75,6 +103,10 @@ pub struct ConfigRequirementsToml {
     pub allowed_approval_policies: Option<Vec<AskForApproval>>,
     pub allowed_sandbox_modes: Option<Vec<SandboxModeRequirement>>,
     pub mcp_servers: Option<BTreeMap<String, McpServerRequirement>>,
+    /// Ids of the `model_provider`s that may be selected.
+    pub allowed_model_providers: Option<Vec<String>>,
+    /// Keep the `[audit]` log on; `config.toml` cannot turn it off.
+    pub require_audit_log: Option<bool>,
 }
 
 /// Value paired with the requirement source it came from, for better error
@@ -104,6 +136,8 @@ pub struct ConfigRequirementsWithSources {
     pub allowed_approval_policies: Option<Sourced<Vec<AskForApproval>>>,
     pub allowed_sandbox_modes: Option<Sourced<Vec<SandboxModeRequirement>>>,
     pub mcp_servers: Option<Sourced<BTreeMap<String, McpServerRequirement>>>,
+    pub allowed_model_providers: Option<Sourced<Vec<String>>>,
+    pub require_audit_log: Option<Sourced<bool>>,
 }
 
 impl ConfigRequirementsWithSources {
@@ -135,6 +169,8 @@ impl ConfigRequirementsWithSources {
                 allowed_approval_policies,
                 allowed_sandbox_modes,
                 mcp_servers,
+                allowed_model_providers,
+                require_audit_log,
             }
         );
     }
@@ -144,11 +180,15 @@ impl ConfigRequirementsWithSources {
             allowed_approval_policies,
             allowed_sandbox_modes,
             mcp_servers,
+            allowed_model_providers,
+            require_audit_log,
         } = self;
         ConfigRequirementsToml {
             allowed_approval_policies: allowed_approval_policies.map(|sourced| sourced.value),
             allowed_sandbox_modes: allowed_sandbox_modes.map(|sourced| sourced.value),
             mcp_servers: mcp_servers.map(|sourced| sourced.value),
+            allowed_model_providers: allowed_model_providers.map(|sourced| sourced.value),
+            require_audit_log: require_audit_log.map(|sourced| sourced.value),
         }
     }
 }
@@ -185,6 +225,8 @@ impl ConfigRequirementsToml {
         self.allowed_approval_policies.is_none()
             && self.allowed_sandbox_modes.is_none()
             && self.mcp_servers.is_none()
+            && self.allowed_model_providers.is_none()
+            && self.require_audit_log.is_none()
     }
 }
 
@@ -196,8 +238,17 @@ impl TryFrom<ConfigRequirementsWithSources> for ConfigRequirements {
             allowed_approval_policies,
             allowed_sandbox_modes,
             mcp_servers,
+            allowed_model_providers,
+            require_audit_log,
         } = toml;
 
+        if allowed_model_providers
+            .as_ref()
+            .is_some_and(|allowed| allowed.is_empty())
+        {
+            return Err(ConstraintError::empty_field("allowed_model_providers"));
+        }
+
         let approval_policy: Constrained<AskForApproval> = match allowed_approval_policies {
             Some(Sourced {
                 value: policies,
@@ -274,6 +325,8 @@ impl TryFrom<ConfigRequirementsWithSources> for ConfigRequirements {
             approval_policy,
             sandbox_policy,
             mcp_servers,
+            allowed_model_providers,
+            require_audit_log,
         })
     }
 }
@@ -292,6 +345,8 @@ mod tests {
             allowed_approval_policies,
             allowed_sandbox_modes,
             mcp_servers,
+            allowed_model_providers,
+            require_audit_log,
         } = toml;
         ConfigRequirementsWithSources {
             allowed_approval_policies: allowed_approval_policies
@@ -299,6 +354,10 @@ mod tests {
             allowed_sandbox_modes: allowed_sandbox_modes
                 .map(|value| Sourced::new(value, RequirementSource::Unknown)),
             mcp_servers: mcp_servers.map(|value| Sourced::new(value, RequirementSource::Unknown)),
+            allowed_model_providers: allowed_model_providers
+                .map(|value| Sourced::new(value, RequirementSource::Unknown)),
+            require_audit_log: require_audit_log
+                .map(|value| Sourced::new(value, RequirementSource::Unknown)),
         }
     }
 
@@ -319,6 +378,8 @@ mod tests {
             allowed_approval_policies: Some(allowed_approval_policies.clone()),
             allowed_sandbox_modes: Some(allowed_sandbox_modes.clone()),
             mcp_servers: None,
+            allowed_model_providers: Some(vec!["openai".to_string()]),
+            require_audit_log: Some(true),
         };
 
         target.merge_unset_fields(source.clone(), other);
@@ -330,8 +391,13 @@ mod tests {
                     allowed_approval_policies,
                     source.clone()
                 )),
-                allowed_sandbox_modes: Some(Sourced::new(allowed_sandbox_modes, source)),
+                allowed_sandbox_modes: Some(Sourced::new(allowed_sandbox_modes, source.clone())),
                 mcp_servers: None,
+                allowed_model_providers: Some(Sourced::new(
+                    vec!["openai".to_string()],
+                    source.clone()
+                )),
+                require_audit_log: Some(Sourced::new(true, source)),
             }
         );
     }
@@ -360,6 +426,8 @@ mod tests {
                 )),
                 allowed_sandbox_modes: None,
                 mcp_servers: None,
+                allowed_model_providers: None,
+                require_audit_log: None,
             }
         );
         Ok(())
@@ -396,6 +464,8 @@ mod tests {
                 )),
                 allowed_sandbox_modes: None,
                 mcp_servers: None,
+                allowed_model_providers: None,
+                require_audit_log: None,
             }
         );
         Ok(())
@@ -595,4 +665,33 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn deserialize_model_provider_and_audit_requirements() -> Result<()> {
+        let toml_str = r#"
+            allowed_model_providers = ["openai", "azure"]
+            require_audit_log = true
+        "#;
+        let requirements: ConfigRequirements =
+            with_unknown_source(from_str(toml_str)?).try_into()?;
+
+        assert_eq!(requirements.check_model_provider("azure"), Ok(()));
+        assert_eq!(
+            requirements.check_model_provider("ollama"),
+            Err(ConstraintError::InvalidValue {
+                field_name: "model_provider",
+                candidate: "ollama".into(),
+                allowed: "[\"openai\", \"azure\"]".into(),
+                requirement_source: RequirementSource::Unknown,
+            })
+        );
+        assert!(requirements.audit_log_required());
+
+        let empty: ConfigRequirementsToml = from_str("allowed_model_providers = []")?;
+        assert_eq!(
+            ConfigRequirements::try_from(with_unknown_source(empty)).err(),
+            Some(ConstraintError::empty_field("allowed_model_providers"))
+        );
+        Ok(())
+    }
 }
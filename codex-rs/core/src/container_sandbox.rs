@@ -0,0 +1,322 @@
+//! Container sandbox backend (`sandbox.backend = "container"`).
+//!
+//! Instead of Seatbelt or Landlock, sandboxed commands run through
+//! `docker run` / `podman run` in the configured image. The policy is mapped
+//! onto the container: writable roots are bind-mounted read-write at the same
+//! path (with their read-only subpaths such as `.git` mounted read-only on
+//! top), the working directory is mounted read-only when it is not writable,
+//! and `--network=none` is used unless the policy grants full network access.
+//! Nothing else from the host is visible, so this is stricter than the
+//! platform sandboxes' "read anything" rule. Each container gets a unique
+//! `codex-<uuid>` name so it can be stopped when the command times out or is
+//! cancelled; killing the runtime CLI alone leaves the container running.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+
+use tokio::process::Command;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::types::ContainerRuntime;
+use crate::config::types::ContainerSandboxConfig;
+use crate::protocol::SandboxPolicy;
+use crate::spawn::CODEX_SANDBOX_ENV_VAR;
+
+/// Selected at config load, like the Windows sandbox flags in `safety`.
+static CONTAINER_SANDBOX: RwLock<Option<ContainerSandboxConfig>> = RwLock::new(None);
+
+/// Prefix of the `--name` given to every sandbox container.
+const CONTAINER_NAME_PREFIX: &str = "codex-";
+
+/// Host-specific variables that would break the image's own environment or
+/// point at the host's container engine.
+const HOST_ONLY_ENV_VARS: [&str; 12] = [
+    "PATH",
+    "HOME",
+    "TMPDIR",
+    "SHELL",
+    "USER",
+    "LOGNAME",
+    "PWD",
+    "OLDPWD",
+    "SHLVL",
+    "DOCKER_HOST",
+    "DOCKER_CONTEXT",
+    "CONTAINER_HOST",
+];
+
+pub(crate) fn set_container_sandbox(config: Option<ContainerSandboxConfig>) {
+    *CONTAINER_SANDBOX
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = config;
+}
+
+pub(crate) fn container_sandbox() -> Option<ContainerSandboxConfig> {
+    CONTAINER_SANDBOX
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Builds the full `<runtime> run ... <image> <command>` invocation. `env` is
+/// the environment the runtime CLI is spawned with; its variables are
+/// forwarded by name so their values never appear on the command line.
+pub(crate) fn create_container_command_args(
+    config: &ContainerSandboxConfig,
+    command: Vec<String>,
+    command_cwd: &Path,
+    sandbox_policy: &SandboxPolicy,
+    sandbox_policy_cwd: &Path,
+    env: &HashMap<String, String>,
+) -> Vec<String> {
+    let mut args = vec![
+        config.runtime.program().to_string(),
+        "run".to_string(),
+        "--rm".to_string(),
+        "--init".to_string(),
+        format!("--name={CONTAINER_NAME_PREFIX}{}", Uuid::new_v4()),
+    ];
+    if !sandbox_policy.has_full_network_access() {
+        args.push("--network=none".to_string());
+    }
+    args.extend(user_args(config.runtime));
+    for (path, writable) in mounts(command_cwd, sandbox_policy, sandbox_policy_cwd) {
+        let path = path.to_string_lossy();
+        let mode = if writable { "rw" } else { "ro" };
+        args.push(format!("--volume={path}:{path}:{mode}"));
+    }
+    args.push(format!("--workdir={}", command_cwd.to_string_lossy()));
+    args.push(format!("--env={CODEX_SANDBOX_ENV_VAR}=container"));
+    let mut keys: Vec<&String> = env
+        .keys()
+        .filter(|key| !HOST_ONLY_ENV_VARS.contains(&key.as_str()))
+        .collect();
+    keys.sort();
+    args.extend(keys.into_iter().map(|key| format!("--env={key}")));
+    args.extend(config.args.iter().cloned());
+    args.push(config.image.clone());
+    args.extend(command);
+    args
+}
+
+/// A container started by [`create_container_command_args`], identified by
+/// the runtime that started it and its `--name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SandboxContainer {
+    program: String,
+    name: String,
+}
+
+impl SandboxContainer {
+    /// Recovers the container from a `<runtime> run --name=codex-... ...`
+    /// invocation. Only the runtime's own flags (before the image) are searched.
+    pub(crate) fn from_command(command: &[String]) -> Option<Self> {
+        let [program, run, flags @ ..] = command else {
+            return None;
+        };
+        if run != "run" {
+            return None;
+        }
+        flags
+            .iter()
+            .take_while(|arg| arg.starts_with("--"))
+            .find_map(|arg| arg.strip_prefix("--name="))
+            .filter(|name| name.starts_with(CONTAINER_NAME_PREFIX))
+            .map(|name| Self {
+                program: program.clone(),
+                name: name.to_string(),
+            })
+    }
+
+    /// Runs `<runtime> kill <name>`. Failures are only logged: the container
+    /// may already have exited and been removed by `--rm`.
+    pub(crate) async fn kill(&self) {
+        let result = Command::new(&self.program)
+            .arg("kill")
+            .arg(&self.name)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .await;
+        match result {
+            Ok(status) if status.success() => {}
+            Ok(status) => warn!("`{} kill {}` exited with {status}", self.program, self.name),
+            Err(err) => warn!("failed to run `{} kill {}`: {err}", self.program, self.name),
+        }
+    }
+}
+
+/// Bind mounts as `(path, writable)`. The working directories come first so
+/// the writable roots and their read-only subpaths are layered on top.
+fn mounts(
+    command_cwd: &Path,
+    sandbox_policy: &SandboxPolicy,
+    sandbox_policy_cwd: &Path,
+) -> Vec<(PathBuf, bool)> {
+    let writable_roots = sandbox_policy.get_writable_roots_with_cwd(sandbox_policy_cwd);
+    let mut mounts: Vec<(PathBuf, bool)> = Vec::new();
+    for cwd in [sandbox_policy_cwd, command_cwd] {
+        let covered = writable_roots
+            .iter()
+            .any(|root| cwd.starts_with(root.root.as_path()))
+            || mounts.iter().any(|(path, _)| cwd.starts_with(path));
+        if !covered {
+            mounts.push((cwd.to_path_buf(), false));
+        }
+    }
+    for root in writable_roots {
+        mounts.push((root.root.into_path_buf(), true));
+        mounts.extend(
+            root.read_only_subpaths
+                .into_iter()
+                .map(|subpath| (subpath.into_path_buf(), false)),
+        );
+    }
+    mounts
+}
+
+/// Runs as the invoking user so files written to the workspace keep their
+/// owner. Rootless Podman maps the user itself with `--userns=keep-id`.
+#[cfg(unix)]
+fn user_args(runtime: ContainerRuntime) -> Vec<String> {
+    match runtime {
+        ContainerRuntime::Docker => {
+            // SAFETY: getuid/getgid cannot fail and have no preconditions.
+            let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+            vec![format!("--user={uid}:{gid}")]
+        }
+        ContainerRuntime::Podman => vec!["--userns=keep-id".to_string()],
+    }
+}
+
+#[cfg(not(unix))]
+fn user_args(_runtime: ContainerRuntime) -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_utils_absolute_path::AbsolutePathBuf;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn config(runtime: ContainerRuntime) -> ContainerSandboxConfig {
+        ContainerSandboxConfig {
+            runtime,
+            image: "ubuntu:24.04".to_string(),
+            args: vec!["--memory=4g".to_string()],
+        }
+    }
+
+    #[test]
+    fn workspace_write_mounts_roots_and_disables_network() {
+        let tmp = TempDir::new().expect("tempdir");
+        let workspace = tmp.path().join("workspace");
+        let extra = tmp.path().join("extra");
+        std::fs::create_dir_all(workspace.join(".git")).expect("create workspace");
+        std::fs::create_dir_all(&extra).expect("create extra root");
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![AbsolutePathBuf::try_from(extra.clone()).expect("absolute")],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+        let env = HashMap::from([
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            ("RUST_LOG".to_string(), "debug".to_string()),
+        ]);
+
+        let args = create_container_command_args(
+            &config(ContainerRuntime::Docker),
+            vec!["bash".to_string(), "-lc".to_string(), "ls".to_string()],
+            &workspace,
+            &policy,
+            &workspace,
+            &env,
+        );
+
+        assert_eq!(&args[..4], ["docker", "run", "--rm", "--init"]);
+        assert!(args[4].starts_with("--name=codex-"));
+        assert_eq!(args[5], "--network=none");
+        let volumes: Vec<&String> = args
+            .iter()
+            .filter(|arg| arg.starts_with("--volume="))
+            .collect();
+        let volume = |path: &Path, mode: &str| format!("--volume={0}:{0}:{mode}", path.display());
+        assert!(volumes.contains(&&volume(&extra, "rw")));
+        assert!(volumes.contains(&&volume(&workspace, "rw")));
+        assert!(volumes.contains(&&volume(&workspace.join(".git"), "ro")));
+        assert!(args.contains(&"--env=RUST_LOG".to_string()));
+        assert!(!args.contains(&"--env=PATH".to_string()));
+        assert_eq!(
+            &args[args.len() - 5..],
+            ["--memory=4g", "ubuntu:24.04", "bash", "-lc", "ls"]
+        );
+    }
+
+    #[test]
+    fn read_only_policy_mounts_cwd_read_only() {
+        let tmp = TempDir::new().expect("tempdir");
+        let workspace = tmp.path().to_path_buf();
+        let subdir = workspace.join("crate");
+
+        let args = create_container_command_args(
+            &config(ContainerRuntime::Podman),
+            vec!["ls".to_string()],
+            &subdir,
+            &SandboxPolicy::ReadOnly,
+            &workspace,
+            &HashMap::new(),
+        );
+
+        let volumes: Vec<String> = args
+            .iter()
+            .filter(|arg| arg.starts_with("--volume="))
+            .cloned()
+            .collect();
+        assert_eq!(
+            volumes,
+            vec![format!("--volume={0}:{0}:ro", workspace.display())]
+        );
+        assert!(args.contains(&format!("--workdir={}", subdir.display())));
+        assert!(args.contains(&"--network=none".to_string()));
+        if cfg!(unix) {
+            assert!(args.contains(&"--userns=keep-id".to_string()));
+        }
+    }
+
+    #[test]
+    fn container_is_recovered_from_its_run_command() {
+        let args = create_container_command_args(
+            &config(ContainerRuntime::Podman),
+            vec!["docker".to_string(), "--name=codex-other".to_string()],
+            Path::new("/work"),
+            &SandboxPolicy::ReadOnly,
+            Path::new("/work"),
+            &HashMap::new(),
+        );
+        let name = args[4].strip_prefix("--name=").expect("name flag");
+
+        assert_eq!(
+            SandboxContainer::from_command(&args),
+            Some(SandboxContainer {
+                program: "podman".to_string(),
+                name: name.to_string(),
+            })
+        );
+        assert_eq!(
+            SandboxContainer::from_command(&[
+                "bash".to_string(),
+                "-lc".to_string(),
+                "docker run --name=codex-x img".to_string(),
+            ]),
+            None
+        );
+    }
+}
@@ -0,0 +1,481 @@
+//! Breakdown of the context carried by the next sampling request
+//! (`Op::InspectContext`) and removal of single items from it
+//! (`Op::DropContextItem`).
+//!
+//! Items are reported in request order: the base instructions, the pinned
+//! items, the messages recorded before the first user turn (instructions and
+//! environment context), then one entry per user turn followed by the outputs
+//! of the tools called in that turn. Token counts use the same byte-based
+//! estimate as the context usage indicator.
+
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::CompactedItem;
+use codex_protocol::protocol::ContextInspectionEvent;
+use codex_protocol::protocol::ContextInspectionItem;
+use codex_protocol::protocol::ContextItemKind;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::PinnedContent;
+use codex_protocol::protocol::RolloutItem;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::compact::content_items_to_text;
+use crate::compact::is_summary_message;
+use crate::context_manager::estimate_item_tokens;
+use crate::context_manager::user_message_positions;
+use crate::instructions::SkillInstructions;
+use crate::instructions::UserInstructions;
+use crate::pinned_context::pinned_item_tokens;
+use crate::pinned_context::unpin_context;
+use crate::session_prefix::ENVIRONMENT_CONTEXT_OPEN_TAG;
+use crate::truncate::approx_token_count;
+
+const PIN_ID_PREFIX: &str = "pin:";
+const ITEM_ID_PREFIX: &str = "item:";
+const TURN_ID_PREFIX: &str = "turn:";
+const OUTPUT_ID_PREFIX: &str = "output:";
+
+/// Replaces the content of a dropped tool output, so the call keeps a
+/// matching output item.
+const DROPPED_OUTPUT_PLACEHOLDER: &str = "[output removed from the context by the user]";
+
+const MAX_LABEL_CHARS: usize = 80;
+
+pub(crate) async fn inspect_context(sess: &Session, sub_id: String) {
+    let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+    send_inspection(sess, &turn_context).await;
+}
+
+pub(crate) async fn drop_context_item(sess: &Session, sub_id: String, id: String) {
+    let has_active_turn = { sess.active_turn.lock().await.is_some() };
+    if has_active_turn {
+        send_error(
+            sess,
+            sub_id,
+            "Cannot drop context items while a turn is in progress.".to_string(),
+        )
+        .await;
+        return;
+    }
+
+    let turn_context = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+    if let Some(pin_id) = id.strip_prefix(PIN_ID_PREFIX) {
+        // Reports `PinnedContextUpdated`, or an error for unknown ids.
+        unpin_context(sess, sub_id, pin_id.to_string()).await;
+    } else {
+        let history = sess.clone_history().await;
+        let items = match drop_from_history(history.raw_items(), &id) {
+            Ok(items) => items,
+            Err(message) => {
+                send_error(sess, sub_id, message).await;
+                return;
+            }
+        };
+        sess.replace_history(items.clone()).await;
+        sess.recompute_token_usage(turn_context.as_ref()).await;
+        sess.persist_rollout_items(&[RolloutItem::Compacted(CompactedItem {
+            message: String::new(),
+            replacement_history: Some(items),
+        })])
+        .await;
+    }
+    send_inspection(sess, &turn_context).await;
+}
+
+async fn send_inspection(sess: &Session, turn_context: &TurnContext) {
+    let base_instructions = sess.get_base_instructions().await;
+    let mut items = vec![ContextInspectionItem {
+        id: None,
+        kind: ContextItemKind::BaseInstructions,
+        label: "Base instructions".to_string(),
+        tokens: i64::try_from(approx_token_count(&base_instructions.text)).unwrap_or(i64::MAX),
+        turn: None,
+    }];
+    for (pinned, tokens) in pinned_item_tokens(sess).await {
+        let label = match &pinned.content {
+            PinnedContent::File { path } => format!("Pinned file {}", path.display()),
+            PinnedContent::Message { text } | PinnedContent::Note { text } => preview(text),
+        };
+        items.push(ContextInspectionItem {
+            id: Some(format!("{PIN_ID_PREFIX}{}", pinned.id)),
+            kind: ContextItemKind::Pinned,
+            label,
+            tokens,
+            turn: None,
+        });
+    }
+    items.extend(history_breakdown(sess.clone_history().await.raw_items()));
+
+    let event = ContextInspectionEvent {
+        total_tokens: total_tokens(&items),
+        items,
+        context_window: turn_context.client.get_model_context_window(),
+    };
+    sess.send_event_raw(Event {
+        id: turn_context.sub_id.clone(),
+        msg: EventMsg::ContextInspection(event),
+    })
+    .await;
+}
+
+async fn send_error(sess: &Session, sub_id: String, message: String) {
+    sess.send_event_raw(Event {
+        id: sub_id,
+        msg: EventMsg::Error(ErrorEvent {
+            message,
+            codex_error_info: None,
+        }),
+    })
+    .await;
+}
+
+/// Tool outputs are also counted in their turn, so they are left out.
+fn total_tokens(items: &[ContextInspectionItem]) -> i64 {
+    items
+        .iter()
+        .filter(|item| item.kind != ContextItemKind::ToolOutput)
+        .map(|item| item.tokens)
+        .sum()
+}
+
+/// Entries for the conversation history: each item before the first user
+/// turn, then every turn followed by its tool outputs.
+fn history_breakdown(items: &[ResponseItem]) -> Vec<ContextInspectionItem> {
+    let positions = user_message_positions(items);
+    let first_turn = positions.first().copied().unwrap_or(items.len());
+    let mut entries = Vec::new();
+
+    for (idx, item) in items[..first_turn].iter().enumerate() {
+        if matches!(item, ResponseItem::GhostSnapshot { .. }) {
+            continue;
+        }
+        let (kind, label) = classify_prefix_item(item);
+        entries.push(ContextInspectionItem {
+            id: Some(format!("{ITEM_ID_PREFIX}{idx}")),
+            kind,
+            label,
+            tokens: estimate_item_tokens(item),
+            turn: None,
+        });
+    }
+
+    for (turn_idx, &start) in positions.iter().enumerate() {
+        let end = positions.get(turn_idx + 1).copied().unwrap_or(items.len());
+        let turn_items = &items[start..end];
+        let turn = u32::try_from(turn_idx + 1).unwrap_or(u32::MAX);
+        let label = match &items[start] {
+            ResponseItem::Message { content, .. } => {
+                let text = content_items_to_text(content).unwrap_or_default();
+                if is_summary_message(&text) {
+                    "Summary of earlier turns".to_string()
+                } else {
+                    preview(&text)
+                }
+            }
+            _ => String::new(),
+        };
+        entries.push(ContextInspectionItem {
+            id: Some(format!("{TURN_ID_PREFIX}{turn}")),
+            kind: ContextItemKind::Turn,
+            label,
+            tokens: turn_items.iter().map(estimate_item_tokens).sum(),
+            turn: Some(turn),
+        });
+
+        for item in turn_items {
+            let call_id = match item {
+                ResponseItem::FunctionCallOutput { call_id, .. }
+                | ResponseItem::CustomToolCallOutput { call_id, .. } => call_id,
+                _ => continue,
+            };
+            entries.push(ContextInspectionItem {
+                id: Some(format!("{OUTPUT_ID_PREFIX}{call_id}")),
+                kind: ContextItemKind::ToolOutput,
+                label: tool_call_label(turn_items, call_id),
+                tokens: estimate_item_tokens(item),
+                turn: Some(turn),
+            });
+        }
+    }
+    entries
+}
+
+fn classify_prefix_item(item: &ResponseItem) -> (ContextItemKind, String) {
+    let ResponseItem::Message { role, content, .. } = item else {
+        return (ContextItemKind::Other, "Other item".to_string());
+    };
+    let text = content_items_to_text(content).unwrap_or_default();
+    if role == "developer" {
+        (ContextItemKind::DeveloperInstructions, preview(&text))
+    } else if UserInstructions::is_user_instructions(content) {
+        (ContextItemKind::UserInstructions, preview(&text))
+    } else if SkillInstructions::is_skill_instructions(content) {
+        (ContextItemKind::SkillInstructions, preview(&text))
+    } else if text.trim_start().starts_with(ENVIRONMENT_CONTEXT_OPEN_TAG) {
+        (
+            ContextItemKind::EnvironmentContext,
+            "Environment context".to_string(),
+        )
+    } else if is_summary_message(&text) {
+        (
+            ContextItemKind::Other,
+            "Summary of earlier turns".to_string(),
+        )
+    } else {
+        (ContextItemKind::Other, preview(&text))
+    }
+}
+
+/// Name and arguments of the call that produced the output for `call_id`.
+fn tool_call_label(items: &[ResponseItem], call_id: &str) -> String {
+    items
+        .iter()
+        .find_map(|item| match item {
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id: id,
+                ..
+            } if id == call_id => Some(preview(&format!("{name} {arguments}"))),
+            ResponseItem::CustomToolCall {
+                name,
+                input,
+                call_id: id,
+                ..
+            } if id == call_id => Some(preview(&format!("{name} {input}"))),
+            _ => None,
+        })
+        .unwrap_or_else(|| format!("Tool output {call_id}"))
+}
+
+/// First non-empty line of `text`, cut to [`MAX_LABEL_CHARS`].
+fn preview(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    if line.chars().count() > MAX_LABEL_CHARS {
+        let cut: String = line.chars().take(MAX_LABEL_CHARS - 1).collect();
+        format!("{cut}…")
+    } else {
+        line.to_string()
+    }
+}
+
+/// History with the item reported as `id` removed. Dropped tool outputs are
+/// replaced by a placeholder; ghost snapshots of a dropped turn are kept so
+/// `/undo` keeps working.
+fn drop_from_history(items: &[ResponseItem], id: &str) -> Result<Vec<ResponseItem>, String> {
+    let positions = user_message_positions(items);
+    let unknown = || format!("No context item with id {id}.");
+
+    if let Some(idx) = id.strip_prefix(ITEM_ID_PREFIX) {
+        let idx: usize = idx.parse().map_err(|_| unknown())?;
+        let first_turn = positions.first().copied().unwrap_or(items.len());
+        if idx >= first_turn {
+            return Err(unknown());
+        }
+        let mut items = items.to_vec();
+        items.remove(idx);
+        return Ok(items);
+    }
+
+    if let Some(turn) = id.strip_prefix(TURN_ID_PREFIX) {
+        let turn: usize = turn.parse().map_err(|_| unknown())?;
+        let start = *turn
+            .checked_sub(1)
+            .and_then(|idx| positions.get(idx))
+            .ok_or_else(unknown)?;
+        let end = positions.get(turn).copied().unwrap_or(items.len());
+        return Ok(items
+            .iter()
+            .enumerate()
+            .filter(|(idx, item)| {
+                !(start..end).contains(idx) || matches!(item, ResponseItem::GhostSnapshot { .. })
+            })
+            .map(|(_, item)| item.clone())
+            .collect());
+    }
+
+    if let Some(target) = id.strip_prefix(OUTPUT_ID_PREFIX) {
+        let mut items = items.to_vec();
+        let output = items
+            .iter_mut()
+            .find(|item| {
+                matches!(
+                    item,
+                    ResponseItem::FunctionCallOutput { call_id, .. }
+                    | ResponseItem::CustomToolCallOutput { call_id, .. }
+                        if call_id == target
+                )
+            })
+            .ok_or_else(unknown)?;
+        match output {
+            ResponseItem::FunctionCallOutput { output, .. } => {
+                *output = FunctionCallOutputPayload {
+                    content: DROPPED_OUTPUT_PLACEHOLDER.to_string(),
+                    content_items: None,
+                    success: output.success,
+                };
+            }
+            ResponseItem::CustomToolCallOutput { output, .. } => {
+                *output = DROPPED_OUTPUT_PLACEHOLDER.to_string();
+            }
+            _ => {}
+        }
+        return Ok(items);
+    }
+
+    Err(unknown())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+            end_turn: None,
+        }
+    }
+
+    fn call(call_id: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{\"command\":[\"ls\"]}".to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    fn output(call_id: &str, content: &str) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                content_items: None,
+                success: Some(true),
+            },
+        }
+    }
+
+    fn history() -> Vec<ResponseItem> {
+        vec![
+            message("developer", "Follow the sandbox rules."),
+            message(
+                "user",
+                "<environment_context>\n  <cwd>/repo</cwd>\n</environment_context>",
+            ),
+            message("user", "List the files\nin the repo."),
+            call("c1"),
+            output("c1", &"README.md\n".repeat(100)),
+            message("assistant", "There is a README."),
+            message("user", "Thanks"),
+        ]
+    }
+
+    #[test]
+    fn breakdown_lists_prefix_items_turns_and_tool_outputs() {
+        let items = history();
+        let entries = history_breakdown(&items);
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.id.clone().unwrap_or_default(),
+                    entry.kind,
+                    entry.label.clone(),
+                    entry.turn,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "item:0".to_string(),
+                    ContextItemKind::DeveloperInstructions,
+                    "Follow the sandbox rules.".to_string(),
+                    None,
+                ),
+                (
+                    "item:1".to_string(),
+                    ContextItemKind::EnvironmentContext,
+                    "Environment context".to_string(),
+                    None,
+                ),
+                (
+                    "turn:1".to_string(),
+                    ContextItemKind::Turn,
+                    "List the files".to_string(),
+                    Some(1),
+                ),
+                (
+                    "output:c1".to_string(),
+                    ContextItemKind::ToolOutput,
+                    "shell {\"command\":[\"ls\"]}".to_string(),
+                    Some(1),
+                ),
+                (
+                    "turn:2".to_string(),
+                    ContextItemKind::Turn,
+                    "Thanks".to_string(),
+                    Some(2),
+                ),
+            ]
+        );
+        let turn_tokens: i64 = items[2..6].iter().map(estimate_item_tokens).sum();
+        assert_eq!(entries[2].tokens, turn_tokens);
+        assert_eq!(
+            total_tokens(&entries),
+            items.iter().map(estimate_item_tokens).sum::<i64>()
+        );
+    }
+
+    #[test]
+    fn drop_removes_turns_and_replaces_tool_outputs() {
+        let items = history();
+
+        assert_eq!(
+            drop_from_history(&items, "turn:1"),
+            Ok(vec![items[0].clone(), items[1].clone(), items[6].clone()])
+        );
+        assert_eq!(
+            drop_from_history(&items, "item:1").map(|items| items.len()),
+            Ok(6)
+        );
+
+        let dropped = drop_from_history(&items, "output:c1").expect("drop output");
+        assert_eq!(
+            dropped[4],
+            output("c1", DROPPED_OUTPUT_PLACEHOLDER),
+            "the call keeps a matching output"
+        );
+
+        for id in ["turn:0", "turn:3", "item:2", "output:missing", "bogus"] {
+            assert_eq!(
+                drop_from_history(&items, id),
+                Err(format!("No context item with id {id}."))
+            );
+        }
+    }
+
+    #[test]
+    fn preview_uses_the_first_non_empty_line() {
+        assert_eq!(preview("\n  Fix the bug  \nmore"), "Fix the bug");
+        let long = "x".repeat(MAX_LABEL_CHARS + 5);
+        assert_eq!(preview(&long).chars().count(), MAX_LABEL_CHARS);
+        assert!(preview(&long).ends_with('…'));
+    }
+}
@@ -6,6 +6,7 @@ use crate::session_prefix::is_session_prefix;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::approx_token_count;
 use crate::truncate::approx_tokens_from_byte_count;
+use crate::truncate::budgeted_truncation_policy;
 use crate::truncate::truncate_function_output_items_with_policy;
 use crate::truncate::truncate_text;
 use crate::user_shell_command::is_user_shell_command_text;
@@ -15,6 +16,7 @@ use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::protocol::TokenUsageInfo;
+use std::collections::HashSet;
 use std::ops::Deref;
 
 /// Transcript of thread history
@@ -93,27 +95,55 @@ impl ContextManager {
         let base_tokens = i64::try_from(approx_token_count(&base_instructions)).unwrap_or(i64::MAX);
 
         let items_tokens = self.items.iter().fold(0i64, |acc, item| {
-            acc + match item {
-                ResponseItem::GhostSnapshot { .. } => 0,
-                ResponseItem::Reasoning {
-                    encrypted_content: Some(content),
+            acc.saturating_add(estimate_item_tokens(item))
+        });
+
+        Some(base_tokens.saturating_add(items_tokens))
+    }
+
+    /// Policy for tool outputs recorded now: the turn's policy, shrunk so the
+    /// calls still waiting for output share what is left of the context window.
+    pub(crate) fn output_truncation_policy(&self, turn_context: &TurnContext) -> TruncationPolicy {
+        let policy = turn_context.truncation_policy;
+        let (Some(context_window), Some(used)) = (
+            turn_context.client.get_model_context_window(),
+            self.estimate_token_count(turn_context),
+        ) else {
+            return policy;
+        };
+        budgeted_truncation_policy(
+            policy,
+            context_window.saturating_sub(used),
+            self.pending_tool_call_count(),
+        )
+    }
+
+    /// Tool calls since the last user turn whose outputs are not recorded yet.
+    pub(crate) fn pending_tool_call_count(&self) -> usize {
+        let turn_start = self
+            .items
+            .iter()
+            .rposition(is_user_turn_boundary)
+            .map_or(0, |idx| idx + 1);
+        let mut pending = HashSet::new();
+        for item in &self.items[turn_start..] {
+            match item {
+                ResponseItem::FunctionCall { call_id, .. }
+                | ResponseItem::CustomToolCall { call_id, .. }
+                | ResponseItem::LocalShellCall {
+                    call_id: Some(call_id),
                     ..
-                }
-                | ResponseItem::Compaction {
-                    encrypted_content: content,
                 } => {
-                    let reasoning_bytes = estimate_reasoning_length(content.len());
-                    i64::try_from(approx_tokens_from_byte_count(reasoning_bytes))
-                        .unwrap_or(i64::MAX)
+                    pending.insert(call_id.as_str());
                 }
-                item => {
-                    let serialized = serde_json::to_string(item).unwrap_or_default();
-                    i64::try_from(approx_token_count(&serialized)).unwrap_or(i64::MAX)
+                ResponseItem::FunctionCallOutput { call_id, .. }
+                | ResponseItem::CustomToolCallOutput { call_id, .. } => {
+                    pending.remove(call_id.as_str());
                 }
+                _ => {}
             }
-        });
-
-        Some(base_tokens.saturating_add(items_tokens))
+        }
+        pending.len()
     }
 
     pub(crate) fn remove_first_item(&mut self) {
@@ -298,6 +328,8 @@ impl ContextManager {
             | ResponseItem::LocalShellCall { .. }
             | ResponseItem::FunctionCall { .. }
             | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::CodeInterpreterCall { .. }
+            | ResponseItem::FileSearchCall { .. }
             | ResponseItem::CustomToolCall { .. }
             | ResponseItem::Compaction { .. }
             | ResponseItem::GhostSnapshot { .. }
@@ -307,7 +339,7 @@ impl ContextManager {
 }
 
 /// API messages include every non-system item (user/assistant messages, reasoning,
-/// tool calls, tool outputs, shell calls, and web-search calls).
+/// tool calls, tool outputs, shell calls, and web-search and other hosted tool calls).
 fn is_api_message(message: &ResponseItem) -> bool {
     match message {
         ResponseItem::Message { role, .. } => role.as_str() != "system",
@@ -318,12 +350,36 @@ fn is_api_message(message: &ResponseItem) -> bool {
         | ResponseItem::LocalShellCall { .. }
         | ResponseItem::Reasoning { .. }
         | ResponseItem::WebSearchCall { .. }
+        | ResponseItem::CodeInterpreterCall { .. }
+        | ResponseItem::FileSearchCall { .. }
         | ResponseItem::Compaction { .. } => true,
         ResponseItem::GhostSnapshot { .. } => false,
         ResponseItem::Other => false,
     }
 }
 
+/// Coarse token estimate for a single history item, using the same
+/// heuristics as [`ContextManager::estimate_token_count`].
+pub(crate) fn estimate_item_tokens(item: &ResponseItem) -> i64 {
+    match item {
+        ResponseItem::GhostSnapshot { .. } => 0,
+        ResponseItem::Reasoning {
+            encrypted_content: Some(content),
+            ..
+        }
+        | ResponseItem::Compaction {
+            encrypted_content: content,
+        } => {
+            let reasoning_bytes = estimate_reasoning_length(content.len());
+            i64::try_from(approx_tokens_from_byte_count(reasoning_bytes)).unwrap_or(i64::MAX)
+        }
+        item => {
+            let serialized = serde_json::to_string(item).unwrap_or_default();
+            i64::try_from(approx_token_count(&serialized)).unwrap_or(i64::MAX)
+        }
+    }
+}
+
 fn estimate_reasoning_length(encoded_len: usize) -> usize {
     encoded_len
         .saturating_mul(3)
@@ -366,7 +422,8 @@ pub(crate) fn is_user_turn_boundary(item: &ResponseItem) -> bool {
     true
 }
 
-fn user_message_positions(items: &[ResponseItem]) -> Vec<usize> {
+/// Indices of the items that start a user turn.
+pub(crate) fn user_message_positions(items: &[ResponseItem]) -> Vec<usize> {
     let mut positions = Vec::new();
     for (idx, item) in items.iter().enumerate() {
         if is_user_turn_boundary(item) {
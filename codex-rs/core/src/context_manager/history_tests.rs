@@ -194,6 +194,31 @@ fn remove_first_item_removes_matching_output_for_function_call() {
     assert_eq!(h.raw_items(), vec![]);
 }
 
+#[test]
+fn pending_tool_call_count_only_counts_current_turn_calls_without_output() {
+    let call = |call_id: &str| ResponseItem::FunctionCall {
+        id: None,
+        name: "do_it".to_string(),
+        arguments: "{}".to_string(),
+        call_id: call_id.to_string(),
+    };
+    let output = |call_id: &str| ResponseItem::CustomToolCallOutput {
+        call_id: call_id.to_string(),
+        output: "ok".to_string(),
+    };
+    let h = create_history_with_items(vec![
+        user_input_text_msg("first"),
+        call("call-0"),
+        user_input_text_msg("second"),
+        call("call-1"),
+        call("call-2"),
+        call("call-3"),
+        output("call-2"),
+    ]);
+
+    assert_eq!(h.pending_tool_call_count(), 2);
+}
+
 #[test]
 fn remove_first_item_removes_matching_call_for_output() {
     let items = vec![
@@ -0,0 +1,367 @@
+//! `[[context_providers]]`: extra context gathered at the start of each turn.
+//!
+//! A command provider receives one JSON payload on stdin with `type`
+//! (`"context.request"`), `thread_id`, `turn_id`, `cwd`, and `prompt` (the
+//! text of the user's message), and prints its context on stdout: either
+//! plain text or `{"snippets": [{"title": "...", "content": "..."}]}`. An MCP
+//! provider reads one resource from a configured server instead.
+//!
+//! Providers run concurrently. Each one's output is cut to its `max_bytes`,
+//! and all of them together to [`MAX_TOTAL_BYTES`], in configuration order.
+//! The result is recorded as a `<context_providers>` message right after the
+//! user's prompt. A provider that fails or times out is skipped with a
+//! warning; it never blocks the turn.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::WarningEvent;
+use codex_protocol::user_input::UserInput;
+use codex_utils_string::take_bytes_at_char_boundary;
+use mcp_types::ReadResourceRequestParams;
+use mcp_types::ReadResourceResultContents;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::ContextProviderConfig;
+use crate::git_info::get_git_repo_root;
+use crate::session_prefix::CONTEXT_PROVIDERS_OPEN_TAG;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_MAX_BYTES: usize = 8 * 1024;
+
+/// Budget for all providers together, so they cannot crowd out the
+/// conversation.
+const MAX_TOTAL_BYTES: usize = 32 * 1024;
+
+const TRUNCATION_MARKER: &str = "\n[truncated]";
+
+#[derive(Default)]
+pub(crate) struct ContextProviders {
+    entries: Vec<ContextProviderConfig>,
+}
+
+/// One piece of context returned by a provider.
+#[derive(Debug, Clone, PartialEq)]
+struct Snippet {
+    source: String,
+    title: Option<String>,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct ProviderOutput {
+    snippets: Vec<OutputSnippet>,
+}
+
+#[derive(Deserialize)]
+struct OutputSnippet {
+    #[serde(default)]
+    title: Option<String>,
+    content: String,
+}
+
+impl ContextProviders {
+    pub(crate) fn new(configs: &[ContextProviderConfig]) -> Self {
+        let entries = configs
+            .iter()
+            .filter(|config| {
+                let has_command = config.command.as_ref().is_some_and(|argv| !argv.is_empty());
+                let has_resource = config.mcp_server.is_some() && config.resource_uri.is_some();
+                if has_command == has_resource {
+                    warn!(
+                        "ignoring context provider `{}`: set either `command` or both `mcp_server` and `resource_uri`",
+                        config.id
+                    );
+                    return false;
+                }
+                true
+            })
+            .cloned()
+            .collect();
+        Self { entries }
+    }
+
+    /// Runs every provider for the turn and returns the message to record,
+    /// or `None` when no provider returned anything.
+    pub(crate) async fn gather(
+        &self,
+        sess: &Session,
+        turn: &TurnContext,
+        input: &[UserInput],
+    ) -> Option<ResponseItem> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let prompt = input
+            .iter()
+            .filter_map(|item| match item {
+                UserInput::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let payload = json!({
+            "type": "context.request",
+            "thread_id": sess.conversation_id.to_string(),
+            "turn_id": turn.sub_id,
+            "cwd": turn.cwd,
+            "prompt": prompt,
+        })
+        .to_string();
+        let cwd = get_git_repo_root(&turn.cwd).unwrap_or_else(|| turn.cwd.clone());
+
+        let results = futures::future::join_all(
+            self.entries
+                .iter()
+                .map(|entry| run_provider(sess, entry, &payload, &cwd)),
+        )
+        .await;
+
+        let mut total_budget = MAX_TOTAL_BYTES;
+        let mut snippets = Vec::new();
+        for (entry, result) in self.entries.iter().zip(results) {
+            match result {
+                Ok(provided) => {
+                    let mut budget = entry.max_bytes.unwrap_or(DEFAULT_MAX_BYTES);
+                    let provided = take_budget(provided, &mut budget);
+                    snippets.extend(take_budget(provided, &mut total_budget));
+                }
+                Err(err) => {
+                    let message = format!("Context provider `{}` failed: {err}", entry.id);
+                    warn!("{message}");
+                    sess.send_event(turn, EventMsg::Warning(WarningEvent { message }))
+                        .await;
+                }
+            }
+        }
+        if snippets.is_empty() {
+            return None;
+        }
+        Some(ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: render(&snippets),
+            }],
+            end_turn: None,
+        })
+    }
+}
+
+async fn run_provider(
+    sess: &Session,
+    entry: &ContextProviderConfig,
+    payload: &str,
+    cwd: &Path,
+) -> Result<Vec<Snippet>, String> {
+    let timeout = entry
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_TIMEOUT);
+    let run = async {
+        match (&entry.command, &entry.mcp_server, &entry.resource_uri) {
+            (Some(command), _, _) => run_command(command, payload, cwd)
+                .await
+                .map(|stdout| parse_output(&entry.id, &stdout)),
+            (None, Some(server), Some(uri)) => read_resource(sess, &entry.id, server, uri).await,
+            _ => Ok(Vec::new()),
+        }
+    };
+    match tokio::time::timeout(timeout, run).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("timed out after {}ms", timeout.as_millis())),
+    }
+}
+
+async fn run_command(command: &[String], payload: &str, cwd: &Path) -> Result<String, String> {
+    let Some((program, args)) = command.split_first() else {
+        return Err("empty command".to_string());
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("failed to spawn `{program}`: {err}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A provider that ignores stdin may exit before reading it.
+        let _ = stdin.write_all(payload.as_bytes()).await;
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|err| format!("failed to wait for `{program}`: {err}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("exited with {}: {}", output.status, stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+async fn read_resource(
+    sess: &Session,
+    id: &str,
+    server: &str,
+    uri: &str,
+) -> Result<Vec<Snippet>, String> {
+    let result = sess
+        .read_resource(
+            server,
+            ReadResourceRequestParams {
+                uri: uri.to_string(),
+            },
+        )
+        .await
+        .map_err(|err| format!("failed to read `{uri}` from `{server}`: {err:#}"))?;
+    Ok(result
+        .contents
+        .into_iter()
+        .filter_map(|contents| match contents {
+            ReadResourceResultContents::TextResourceContents(text) => Some(text.text),
+            ReadResourceResultContents::BlobResourceContents(_) => None,
+        })
+        .filter(|text| !text.trim().is_empty())
+        .map(|content| Snippet {
+            source: id.to_string(),
+            title: None,
+            content,
+        })
+        .collect())
+}
+
+/// Reads a command's stdout as `{"snippets": [...]}`, or as a single snippet
+/// of plain text.
+fn parse_output(id: &str, stdout: &str) -> Vec<Snippet> {
+    let stdout = stdout.trim();
+    if let Ok(output) = serde_json::from_str::<ProviderOutput>(stdout) {
+        return output
+            .snippets
+            .into_iter()
+            .filter(|snippet| !snippet.content.trim().is_empty())
+            .map(|snippet| Snippet {
+                source: id.to_string(),
+                title: snippet.title,
+                content: snippet.content,
+            })
+            .collect();
+    }
+    if stdout.is_empty() {
+        return Vec::new();
+    }
+    vec![Snippet {
+        source: id.to_string(),
+        title: None,
+        content: stdout.to_string(),
+    }]
+}
+
+/// Keeps snippets in order until `budget` bytes of content are used; the
+/// snippet that crosses the limit is cut off and the rest are dropped.
+fn take_budget(snippets: Vec<Snippet>, budget: &mut usize) -> Vec<Snippet> {
+    let mut kept = Vec::new();
+    for mut snippet in snippets {
+        if *budget == 0 {
+            break;
+        }
+        if snippet.content.len() > *budget {
+            let head = take_bytes_at_char_boundary(&snippet.content, *budget);
+            snippet.content = format!("{head}{TRUNCATION_MARKER}");
+            *budget = 0;
+        } else {
+            *budget -= snippet.content.len();
+        }
+        kept.push(snippet);
+    }
+    kept
+}
+
+fn render(snippets: &[Snippet]) -> String {
+    let mut text = format!("{CONTEXT_PROVIDERS_OPEN_TAG}\n");
+    for snippet in snippets {
+        let source = snippet.source.replace('"', "'");
+        match &snippet.title {
+            Some(title) => text.push_str(&format!(
+                "<snippet source=\"{source}\" title=\"{}\">\n",
+                title.replace('"', "'")
+            )),
+            None => text.push_str(&format!("<snippet source=\"{source}\">\n")),
+        }
+        text.push_str(snippet.content.trim_end());
+        text.push_str("\n</snippet>\n");
+    }
+    text.push_str("</context_providers>");
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn snippet(content: &str) -> Snippet {
+        Snippet {
+            source: "tickets".to_string(),
+            title: None,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_json_snippets_or_plain_text() {
+        assert_eq!(
+            parse_output(
+                "tickets",
+                r#"{"snippets": [{"title": "PROJ-1", "content": "Fix login"}, {"content": " "}]}"#
+            ),
+            vec![Snippet {
+                source: "tickets".to_string(),
+                title: Some("PROJ-1".to_string()),
+                content: "Fix login".to_string(),
+            }]
+        );
+        assert_eq!(
+            parse_output("tickets", "  on-call: alice\n"),
+            vec![snippet("on-call: alice")]
+        );
+        assert_eq!(parse_output("tickets", "\n"), Vec::new());
+    }
+
+    #[test]
+    fn budgets_cut_off_later_snippets() {
+        let mut budget = 8;
+        assert_eq!(
+            take_budget(
+                vec![snippet("abcde"), snippet("fghij"), snippet("klm")],
+                &mut budget
+            ),
+            vec![snippet("abcde"), snippet("fgh\n[truncated]")]
+        );
+        assert_eq!(budget, 0);
+    }
+
+    #[test]
+    fn renders_snippets_with_their_source() {
+        let mut titled = snippet("Fix login\n");
+        titled.title = Some("PROJ-1 \"urgent\"".to_string());
+        assert_eq!(
+            render(&[titled, snippet("on-call: alice")]),
+            "<context_providers>\n\
+             <snippet source=\"tickets\" title=\"PROJ-1 'urgent'\">\nFix login\n</snippet>\n\
+             <snippet source=\"tickets\">\non-call: alice\n</snippet>\n\
+             </context_providers>"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Branching a thread via `Op::ForkConversation`.
+//!
+//! The branch is a new thread seeded with a copy of this thread's rollout, so
+//! the user can explore an alternative approach without losing the current
+//! one. Its session metadata records this thread as `forked_from_id`, which is
+//! how clients list the branches of a thread.
+
+use std::sync::Arc;
+
+use crate::codex::Session;
+use crate::tools::handlers::collab::build_agent_spawn_config;
+use codex_protocol::protocol::ConversationForkedEvent;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+
+pub(crate) async fn fork_conversation(sess: &Arc<Session>, sub_id: String, num_turns: Option<u32>) {
+    let turn = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+
+    // The fork reads this thread's rollout, so make sure it is complete.
+    sess.flush_rollout().await;
+    let rollout_path = sess
+        .services
+        .rollout
+        .lock()
+        .await
+        .as_ref()
+        .map(|recorder| recorder.rollout_path.clone());
+    let Some(rollout_path) = rollout_path else {
+        send_error(
+            sess,
+            sub_id,
+            "this thread is not persisted, so it cannot be forked",
+        )
+        .await;
+        return;
+    };
+
+    let config = match build_agent_spawn_config(&sess.get_base_instructions().await, &turn) {
+        Ok(config) => config,
+        Err(err) => {
+            send_error(sess, sub_id, &err.to_string()).await;
+            return;
+        }
+    };
+
+    let nth_user_message = num_turns.map_or(usize::MAX, |n| n as usize);
+    let new_thread = match sess
+        .services
+        .agent_control
+        .fork_thread(config, rollout_path, nth_user_message)
+        .await
+    {
+        Ok(new_thread) => new_thread,
+        Err(err) => {
+            send_error(sess, sub_id, &format!("failed to fork thread: {err}")).await;
+            return;
+        }
+    };
+
+    sess.send_event(
+        &turn,
+        EventMsg::ConversationForked(ConversationForkedEvent {
+            thread_id: new_thread.thread_id,
+            rollout_path: new_thread.session_configured.rollout_path,
+            num_turns,
+        }),
+    )
+    .await;
+}
+
+async fn send_error(sess: &Session, sub_id: String, message: &str) {
+    sess.send_event_raw(Event {
+        id: sub_id,
+        msg: EventMsg::Error(ErrorEvent {
+            message: message.to_string(),
+            codex_error_info: None,
+        }),
+    })
+    .await;
+}
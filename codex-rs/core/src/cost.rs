@@ -0,0 +1,288 @@
+//! Estimated cost tracking and `[budget]` spending limits.
+//!
+//! The token usage of every model response is priced with `[budget.prices]`
+//! and added to the running turn and session totals, which are reported via
+//! `EventMsg::CostUpdate`. Crossing `warn_at_percent` of a limit produces a
+//! warning; reaching a limit stops the running turn before its next model
+//! request, and reaching the session limit also refuses new turns.
+
+use codex_protocol::protocol::CostUpdateEvent;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::TokenUsage;
+use tokio::sync::Mutex;
+
+use crate::config::types::BudgetConfig;
+use crate::config::types::ModelPrice;
+
+pub(crate) struct CostTracker {
+    budget: BudgetConfig,
+    state: Mutex<CostState>,
+}
+
+/// What recording one model response produced.
+pub(crate) struct CostOutcome {
+    /// `None` when the model has no price.
+    pub(crate) event: Option<CostUpdateEvent>,
+    pub(crate) warnings: Vec<String>,
+}
+
+impl CostTracker {
+    pub(crate) fn new(budget: BudgetConfig) -> Self {
+        Self {
+            budget,
+            state: Mutex::new(CostState::default()),
+        }
+    }
+
+    /// Add the cost of one response of `model` to the totals of `turn_id`
+    /// and the session.
+    pub(crate) async fn record(
+        &self,
+        turn_id: &str,
+        model: &str,
+        usage: &TokenUsage,
+    ) -> CostOutcome {
+        self.state
+            .lock()
+            .await
+            .record(&self.budget, turn_id, model, usage)
+    }
+
+    /// Why `turn_id` may not make another model request, if a limit has
+    /// been reached. A turn that has not recorded any cost yet is only
+    /// checked against the session limit.
+    pub(crate) async fn limit_reached(&self, turn_id: &str) -> Option<String> {
+        self.state.lock().await.limit_reached(&self.budget, turn_id)
+    }
+
+    /// Restore the session total from the last `CostUpdate` in a resumed
+    /// rollout.
+    pub(crate) async fn seed_from_rollout(&self, rollout_items: &[RolloutItem]) {
+        let Some(session_usd) = rollout_items.iter().rev().find_map(|item| match item {
+            RolloutItem::EventMsg(EventMsg::CostUpdate(ev)) => Some(ev.session_usd),
+            _ => None,
+        }) else {
+            return;
+        };
+        self.state.lock().await.session_usd = session_usd;
+    }
+}
+
+#[derive(Default)]
+struct CostState {
+    session_usd: f64,
+    turn_id: String,
+    turn_usd: f64,
+    warned_session: bool,
+    warned_turn: bool,
+    warned_unpriced: bool,
+}
+
+impl CostState {
+    fn record(
+        &mut self,
+        budget: &BudgetConfig,
+        turn_id: &str,
+        model: &str,
+        usage: &TokenUsage,
+    ) -> CostOutcome {
+        if self.turn_id != turn_id {
+            self.turn_id = turn_id.to_string();
+            self.turn_usd = 0.0;
+            self.warned_turn = false;
+        }
+        let Some(price) = budget.price_for(model) else {
+            let has_limits =
+                budget.max_usd_per_session.is_some() || budget.max_usd_per_turn.is_some();
+            let mut warnings = Vec::new();
+            if has_limits && !self.warned_unpriced {
+                self.warned_unpriced = true;
+                warnings.push(format!(
+                    "No price for model `{model}` in [budget.prices]; its cost is not counted against the budget."
+                ));
+            }
+            return CostOutcome {
+                event: None,
+                warnings,
+            };
+        };
+
+        let cost = usage_cost(price, usage);
+        self.session_usd += cost;
+        self.turn_usd += cost;
+
+        let mut warnings = Vec::new();
+        let warn_fraction = f64::from(budget.warn_at_percent) / 100.0;
+        if let Some(max) = budget.max_usd_per_session
+            && !self.warned_session
+            && self.session_usd >= max * warn_fraction
+        {
+            self.warned_session = true;
+            warnings.push(format!(
+                "This session has spent an estimated ${:.2} of its ${max:.2} budget.",
+                self.session_usd
+            ));
+        }
+        if let Some(max) = budget.max_usd_per_turn
+            && !self.warned_turn
+            && self.turn_usd >= max * warn_fraction
+        {
+            self.warned_turn = true;
+            warnings.push(format!(
+                "This turn has spent an estimated ${:.2} of its ${max:.2} budget.",
+                self.turn_usd
+            ));
+        }
+
+        CostOutcome {
+            event: Some(CostUpdateEvent {
+                turn_usd: self.turn_usd,
+                session_usd: self.session_usd,
+                max_usd_per_turn: budget.max_usd_per_turn,
+                max_usd_per_session: budget.max_usd_per_session,
+            }),
+            warnings,
+        }
+    }
+
+    fn limit_reached(&self, budget: &BudgetConfig, turn_id: &str) -> Option<String> {
+        if let Some(max) = budget.max_usd_per_session
+            && self.session_usd >= max
+        {
+            return Some(format!(
+                "Session budget reached: an estimated ${:.2} spent of max_usd_per_session = ${max:.2}. Raise [budget].max_usd_per_session to continue.",
+                self.session_usd
+            ));
+        }
+        if let Some(max) = budget.max_usd_per_turn
+            && self.turn_id == turn_id
+            && self.turn_usd >= max
+        {
+            return Some(format!(
+                "Turn budget reached: an estimated ${:.2} spent of max_usd_per_turn = ${max:.2}.",
+                self.turn_usd
+            ));
+        }
+        None
+    }
+}
+
+/// Estimated USD cost of `usage` at `price`.
+pub(crate) fn usage_cost(price: &ModelPrice, usage: &TokenUsage) -> f64 {
+    let input = usage.input_tokens.max(0);
+    let cached = usage.cached_input_tokens.clamp(0, input);
+    let cached_price = price
+        .cached_input_per_million
+        .unwrap_or(price.input_per_million);
+    let micro_usd = (input - cached) as f64 * price.input_per_million
+        + cached as f64 * cached_price
+        + usage.output_tokens.max(0) as f64 * price.output_per_million;
+    micro_usd / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    fn price(input: f64, cached: Option<f64>, output: f64) -> ModelPrice {
+        ModelPrice {
+            input_per_million: input,
+            cached_input_per_million: cached,
+            output_per_million: output,
+        }
+    }
+
+    fn usage(input: i64, cached: i64, output: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: cached,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+        }
+    }
+
+    fn budget(session: Option<f64>, turn: Option<f64>) -> BudgetConfig {
+        BudgetConfig {
+            max_usd_per_session: session,
+            max_usd_per_turn: turn,
+            warn_at_percent: 80,
+            prices: BTreeMap::from([("gpt-5".to_string(), price(1.0, None, 10.0))]),
+        }
+    }
+
+    #[test]
+    fn price_for_prefers_exact_then_longest_prefix() {
+        let budget = BudgetConfig {
+            prices: BTreeMap::from([
+                ("gpt-5".to_string(), price(1.0, None, 10.0)),
+                ("gpt-5-codex".to_string(), price(2.0, None, 20.0)),
+                ("gpt-5-codex-mini".to_string(), price(0.5, None, 5.0)),
+            ]),
+            ..BudgetConfig::default()
+        };
+
+        assert_eq!(
+            budget.price_for("gpt-5-codex-mini"),
+            Some(&price(0.5, None, 5.0))
+        );
+        assert_eq!(
+            budget.price_for("gpt-5-codex-max"),
+            Some(&price(2.0, None, 20.0))
+        );
+        assert_eq!(budget.price_for("gpt-5.1"), Some(&price(1.0, None, 10.0)));
+        assert_eq!(budget.price_for("o3"), None);
+    }
+
+    #[test]
+    fn usage_cost_prices_cached_input_separately() {
+        let cost = usage_cost(
+            &price(2.0, Some(0.5), 8.0),
+            &usage(1_000_000, 400_000, 500_000),
+        );
+        assert!((cost - (0.6 * 2.0 + 0.4 * 0.5 + 0.5 * 8.0)).abs() < 1e-9);
+
+        let cost = usage_cost(&price(2.0, None, 8.0), &usage(1_000_000, 400_000, 0));
+        assert!((cost - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn warns_once_then_reports_limits() {
+        let budget = budget(Some(1.0), Some(0.5));
+        let mut state = CostState::default();
+
+        // 0.1M input at $1 + 0.035M output at $10 = $0.45 per response.
+        let response = usage(100_000, 0, 35_000);
+        let outcome = state.record(&budget, "t1", "gpt-5", &response);
+        assert_eq!(outcome.warnings.len(), 1, "turn warning at 80% of $0.50");
+        assert_eq!(state.limit_reached(&budget, "t1"), None);
+
+        let outcome = state.record(&budget, "t1", "gpt-5", &response);
+        assert_eq!(outcome.warnings.len(), 1, "session warning only");
+        let event = outcome.event.expect("priced model");
+        assert!((event.turn_usd - 0.9).abs() < 1e-9);
+        assert!(state.limit_reached(&budget, "t1").is_some());
+        assert_eq!(state.limit_reached(&budget, "t2"), None);
+
+        let outcome = state.record(&budget, "t2", "gpt-5", &response);
+        assert_eq!(outcome.warnings.len(), 1, "a new turn warns again");
+        assert!((outcome.event.expect("priced model").turn_usd - 0.45).abs() < 1e-9);
+        let reason = state.limit_reached(&budget, "t3").expect("session limit");
+        assert!(reason.starts_with("Session budget reached"));
+    }
+
+    #[test]
+    fn unpriced_model_warns_once_when_limits_are_set() {
+        let budget = budget(Some(1.0), None);
+        let mut state = CostState::default();
+
+        let outcome = state.record(&budget, "t1", "o3", &usage(1_000, 0, 1_000));
+        assert!(outcome.event.is_none());
+        assert_eq!(outcome.warnings.len(), 1);
+        let outcome = state.record(&budget, "t2", "o3", &usage(1_000, 0, 1_000));
+        assert!(outcome.warnings.is_empty());
+    }
+}
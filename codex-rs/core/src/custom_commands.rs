@@ -0,0 +1,388 @@
+//! Custom slash commands: `<name>.md` files in `<git root>/.codex/commands/`
+//! and `$CODEX_HOME/commands/`, invoked as `/<name> [ARGS]`.
+//!
+//! Like MCP prompt commands, clients send the command as plain text and the
+//! session replaces it with the file's body before the turn starts, so every
+//! client gets the same expansion. `$ARGUMENTS` in the body becomes the text
+//! after the command name. Optional frontmatter sets `description` and
+//! `argument-hint` for the clients' command lists, and `model` and `mode`
+//! (`plan`, `pair_programming` or `execute`) for the turn the command starts.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::config_types::CollaborationMode;
+use codex_protocol::config_types::ModeKind;
+use codex_protocol::custom_prompts::CustomCommand;
+use codex_protocol::custom_prompts::CustomCommandSource;
+use codex_protocol::user_input::UserInput;
+use tracing::warn;
+
+use crate::custom_prompts::split_frontmatter_fields;
+use crate::git_info::get_git_repo_root;
+
+const COMMANDS_DIR_NAME: &str = "commands";
+const ARGUMENTS_PLACEHOLDER: &str = "$ARGUMENTS";
+
+/// Directories scanned for commands, highest priority first:
+/// `<git root>/.codex/commands`, then `$CODEX_HOME/commands`, the same order
+/// as subagents.
+pub fn custom_command_search_roots(
+    cwd: &Path,
+    codex_home: &Path,
+) -> Vec<(PathBuf, CustomCommandSource)> {
+    let mut roots = Vec::new();
+    if let Some(git_root) = get_git_repo_root(cwd) {
+        roots.push((
+            git_root.join(".codex").join(COMMANDS_DIR_NAME),
+            CustomCommandSource::Repo,
+        ));
+    }
+    roots.push((
+        codex_home.join(COMMANDS_DIR_NAME),
+        CustomCommandSource::User,
+    ));
+    roots
+}
+
+/// Loads the commands visible from `cwd`, sorted by name. When a name is
+/// defined in both roots, the repo's command wins.
+pub fn load_custom_commands(cwd: &Path, codex_home: &Path) -> Vec<CustomCommand> {
+    load_custom_commands_from_roots(custom_command_search_roots(cwd, codex_home))
+}
+
+fn load_custom_commands_from_roots<I>(roots: I) -> Vec<CustomCommand>
+where
+    I: IntoIterator<Item = (PathBuf, CustomCommandSource)>,
+{
+    let mut commands = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for (dir, source) in roots {
+        for path in list_markdown_files(&dir) {
+            let Some(command) = parse_command_file(&path, source) else {
+                continue;
+            };
+            if seen.insert(command.name.clone()) {
+                commands.push(command);
+            }
+        }
+    }
+    commands.sort_by(|a, b| a.name.cmp(&b.name));
+    commands
+}
+
+fn list_markdown_files(dir: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warn!("failed to read commands dir {}: {err:#}", dir.display());
+            }
+            return Vec::new();
+        }
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+        })
+        .filter(|path| fs::metadata(path).is_ok_and(|m| m.is_file()))
+        .collect();
+    files.sort();
+    files
+}
+
+fn parse_command_file(path: &Path, source: CustomCommandSource) -> Option<CustomCommand> {
+    let name = path.file_stem().and_then(|stem| stem.to_str())?.to_string();
+    if !is_valid_name(&name) {
+        warn!("skipping command {}: invalid name", path.display());
+        return None;
+    }
+    let contents = fs::read_to_string(path)
+        .inspect_err(|err| warn!("failed to read command {}: {err:#}", path.display()))
+        .ok()?;
+    let (fields, body) = split_frontmatter_fields(&contents);
+    let mut command = CustomCommand {
+        name,
+        path: path.to_path_buf(),
+        content: body,
+        description: None,
+        argument_hint: None,
+        model: None,
+        mode: None,
+        source,
+    };
+    for (key, value) in fields {
+        let value = value.trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match key.as_str() {
+            "description" => command.description = Some(value),
+            "argument-hint" | "argument_hint" => command.argument_hint = Some(value),
+            "model" => command.model = Some(value),
+            "mode" => match parse_mode(&value) {
+                Some(mode) => command.mode = Some(mode),
+                None => warn!(
+                    "ignoring unknown mode `{value}` in command {}",
+                    path.display()
+                ),
+            },
+            _ => {}
+        }
+    }
+    Some(command)
+}
+
+/// Names become `/<name>`, so they are a single token that cannot be
+/// mistaken for `/prompts:` or `/mcp:` commands.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn parse_mode(value: &str) -> Option<ModeKind> {
+    match value.to_ascii_lowercase().replace('-', "_").as_str() {
+        "plan" => Some(ModeKind::Plan),
+        "pair_programming" => Some(ModeKind::PairProgramming),
+        "execute" => Some(ModeKind::Execute),
+        _ => None,
+    }
+}
+
+/// Replaces every `$ARGUMENTS` in `content` with `args`. A body without the
+/// placeholder gets non-empty arguments appended after a blank line, so they
+/// are never dropped.
+fn expand_arguments(content: &str, args: &str) -> String {
+    if content.contains(ARGUMENTS_PLACEHOLDER) {
+        return content.replace(ARGUMENTS_PLACEHOLDER, args);
+    }
+    if args.is_empty() {
+        return content.to_string();
+    }
+    format!("{}\n\n{args}", content.trim_end())
+}
+
+/// Expands a command at the start of the first text item in place and
+/// returns it. Input that does not start with a known command is left
+/// unchanged.
+pub(crate) fn expand_command<'a>(
+    commands: &'a [CustomCommand],
+    items: &mut [UserInput],
+) -> Option<&'a CustomCommand> {
+    let item = items
+        .iter_mut()
+        .find(|item| matches!(item, UserInput::Text { .. }))?;
+    let UserInput::Text {
+        text,
+        text_elements,
+    } = item
+    else {
+        return None;
+    };
+    let rest = text.strip_prefix('/')?;
+    let (name, args) = rest
+        .split_once(char::is_whitespace)
+        .map_or((rest, ""), |(name, args)| (name, args.trim()));
+    let command = commands.iter().find(|command| command.name == name)?;
+    *text = expand_arguments(&command.content, args);
+    // Element ranges pointed into the command line, which is gone.
+    text_elements.clear();
+    Some(command)
+}
+
+/// The collaboration mode a command's turn runs in, when its frontmatter
+/// sets `model` or `mode`. A `mode` switches to that preset but keeps the
+/// current model and reasoning effort unless `model` overrides the model.
+pub(crate) fn command_collaboration_mode(
+    command: &CustomCommand,
+    current: &CollaborationMode,
+    presets: &[CollaborationMode],
+) -> Option<CollaborationMode> {
+    if command.model.is_none() && command.mode.is_none() {
+        return None;
+    }
+    let base = command
+        .mode
+        .and_then(|kind| presets.iter().find(|preset| mode_kind(preset) == kind))
+        .map_or_else(
+            || current.clone(),
+            |preset| {
+                preset.with_updates(
+                    Some(current.model().to_string()),
+                    Some(current.reasoning_effort()),
+                    None,
+                )
+            },
+        );
+    Some(base.with_updates(command.model.clone(), None, None))
+}
+
+fn mode_kind(mode: &CollaborationMode) -> ModeKind {
+    match mode {
+        CollaborationMode::Plan(_) => ModeKind::Plan,
+        CollaborationMode::PairProgramming(_) => ModeKind::PairProgramming,
+        CollaborationMode::Execute(_) => ModeKind::Execute,
+        CollaborationMode::Custom(_) => ModeKind::Custom,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::config_types::Settings;
+    use codex_protocol::openai_models::ReasoningEffort;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, contents: &str) {
+        fs::create_dir_all(dir).expect("create dir");
+        fs::write(dir.join(name), contents).expect("write file");
+    }
+
+    fn command(name: &str, content: &str) -> CustomCommand {
+        CustomCommand {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/commands/{name}.md")),
+            content: content.to_string(),
+            description: None,
+            argument_hint: None,
+            model: None,
+            mode: None,
+            source: CustomCommandSource::User,
+        }
+    }
+
+    fn text(text: &str) -> UserInput {
+        UserInput::Text {
+            text: text.to_string(),
+            text_elements: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn repo_commands_override_home_commands() {
+        let tmp = TempDir::new().expect("tempdir");
+        let repo = tmp.path().join("repo");
+        let home = tmp.path().join("home");
+        write(
+            &repo,
+            "review.md",
+            "---\ndescription: Review the diff\nargument-hint: [FILE]\nmode: plan\nmodel: \"o3\"\n---\nReview $ARGUMENTS.\n",
+        );
+        write(&home, "review.md", "Home review.\n");
+        write(&home, "explain.md", "---\nmode: sideways\n---\nExplain.\n");
+        write(&home, "bad:name.md", "Never loaded.\n");
+
+        let commands = load_custom_commands_from_roots([
+            (repo.clone(), CustomCommandSource::Repo),
+            (home.clone(), CustomCommandSource::User),
+        ]);
+
+        assert_eq!(
+            commands,
+            vec![
+                CustomCommand {
+                    mode: None,
+                    path: home.join("explain.md"),
+                    ..command("explain", "Explain.\n")
+                },
+                CustomCommand {
+                    path: repo.join("review.md"),
+                    description: Some("Review the diff".to_string()),
+                    argument_hint: Some("[FILE]".to_string()),
+                    model: Some("o3".to_string()),
+                    mode: Some(ModeKind::Plan),
+                    source: CustomCommandSource::Repo,
+                    ..command("review", "Review $ARGUMENTS.\n")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn expands_arguments_in_the_first_text_item() {
+        let commands = vec![
+            command("fix", "Fix $ARGUMENTS, then test $ARGUMENTS."),
+            command("plain", "Summarize the changes.\n"),
+        ];
+
+        let mut items = vec![
+            UserInput::LocalImage {
+                path: PathBuf::from("shot.png"),
+            },
+            text("/fix  the parser "),
+        ];
+        let expanded = expand_command(&commands, &mut items).map(|command| command.name.clone());
+        assert_eq!(expanded, Some("fix".to_string()));
+        assert_eq!(items[1], text("Fix the parser, then test the parser."));
+
+        let mut items = vec![text("/plain focus on tests")];
+        expand_command(&commands, &mut items);
+        assert_eq!(
+            items,
+            vec![text("Summarize the changes.\n\nfocus on tests")]
+        );
+
+        let mut items = vec![text("/unknown x")];
+        assert_eq!(expand_command(&commands, &mut items), None);
+        assert_eq!(items, vec![text("/unknown x")]);
+    }
+
+    #[test]
+    fn mode_and_model_come_from_frontmatter() {
+        let current = CollaborationMode::Custom(Settings {
+            model: "gpt-5.1-codex".to_string(),
+            reasoning_effort: Some(ReasoningEffort::High),
+            developer_instructions: None,
+        });
+        let plan = CollaborationMode::Plan(Settings {
+            model: "preset-model".to_string(),
+            reasoning_effort: Some(ReasoningEffort::Medium),
+            developer_instructions: Some("Plan first.".to_string()),
+        });
+        let presets = vec![plan];
+
+        assert_eq!(
+            command_collaboration_mode(&command("x", ""), &current, &presets),
+            None
+        );
+        assert_eq!(
+            command_collaboration_mode(
+                &CustomCommand {
+                    mode: Some(ModeKind::Plan),
+                    ..command("x", "")
+                },
+                &current,
+                &presets
+            ),
+            Some(CollaborationMode::Plan(Settings {
+                model: "gpt-5.1-codex".to_string(),
+                reasoning_effort: Some(ReasoningEffort::High),
+                developer_instructions: Some("Plan first.".to_string()),
+            }))
+        );
+        assert_eq!(
+            command_collaboration_mode(
+                &CustomCommand {
+                    model: Some("o3".to_string()),
+                    ..command("x", "")
+                },
+                &current,
+                &presets
+            ),
+            Some(CollaborationMode::Custom(Settings {
+                model: "o3".to_string(),
+                reasoning_effort: Some(ReasoningEffort::High),
+                developer_instructions: None,
+            }))
+        );
+    }
+}
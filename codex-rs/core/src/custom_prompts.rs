@@ -81,17 +81,34 @@ pub async fn discover_prompts_in_excluding(
 /// - `argument-hint` or `argument_hint`: brief hint string shown after the description
 ///   Returns (description, argument_hint, body_without_frontmatter).
 fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, String) {
+    let (fields, body) = split_frontmatter_fields(content);
+    let mut desc: Option<String> = None;
+    let mut hint: Option<String> = None;
+    for (key, val) in fields {
+        match key.as_str() {
+            "description" => desc = Some(val),
+            "argument-hint" | "argument_hint" => hint = Some(val),
+            _ => {}
+        }
+    }
+    (desc, hint, body)
+}
+
+/// Splits YAML-like `key: value` frontmatter off the beginning of `content`.
+/// Keys are lowercased and surrounding quotes are stripped from values.
+/// Without a (terminated) frontmatter block, there are no fields and the body
+/// is `content` as-is.
+pub(crate) fn split_frontmatter_fields(content: &str) -> (Vec<(String, String)>, String) {
     let mut segments = content.split_inclusive('\n');
     let Some(first_segment) = segments.next() else {
-        return (None, None, String::new());
+        return (Vec::new(), String::new());
     };
     let first_line = first_segment.trim_end_matches(['\r', '\n']);
     if first_line.trim() != "---" {
-        return (None, None, content.to_string());
+        return (Vec::new(), content.to_string());
     }
 
-    let mut desc: Option<String> = None;
-    let mut hint: Option<String> = None;
+    let mut fields: Vec<(String, String)> = Vec::new();
     let mut frontmatter_closed = false;
     let mut consumed = first_segment.len();
 
@@ -121,11 +138,7 @@ fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, String)
                     val = val[1..val.len().saturating_sub(1)].to_string();
                 }
             }
-            match key.as_str() {
-                "description" => desc = Some(val),
-                "argument-hint" | "argument_hint" => hint = Some(val),
-                _ => {}
-            }
+            fields.push((key, val));
         }
 
         consumed += segment.len();
@@ -133,7 +146,7 @@ fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, String)
 
     if !frontmatter_closed {
         // Unterminated frontmatter: treat input as-is.
-        return (None, None, content.to_string());
+        return (Vec::new(), content.to_string());
     }
 
     let body = if consumed >= content.len() {
@@ -141,7 +154,7 @@ fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, String)
     } else {
         content[consumed..].to_string()
     };
-    (desc, hint, body)
+    (fields, body)
 }
 
 #[cfg(test)]
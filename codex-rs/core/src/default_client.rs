@@ -173,6 +173,7 @@ pub fn build_reqwest_client() -> reqwest::Client {
     if is_sandboxed() {
         builder = builder.no_proxy();
     }
+    builder = codex_client::apply_network_settings(builder);
 
     builder.build().unwrap_or_else(|_| reqwest::Client::new())
 }
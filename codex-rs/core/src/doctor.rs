@@ -0,0 +1,552 @@
+//! Diagnostics behind `codex doctor`.
+//!
+//! Each [`DoctorCheck`] looks at one thing that commonly breaks a setup (the
+//! config file, the login, the model provider, the sandbox, MCP servers,
+//! subagent definitions, and the rollout directory) and, when something is
+//! wrong, says how to fix it. The report serializes as-is for `--json`, so it
+//! can be attached to bug reports.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use codex_api::AuthProvider as _;
+use codex_app_server_protocol::AuthMode;
+use codex_app_server_protocol::CapabilityStatus;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::AuthManager;
+use crate::api_bridge::auth_provider_for_request;
+use crate::auth::CodexAuth;
+use crate::auth::read_openai_api_key_from_env;
+use crate::capabilities::collect_capabilities;
+use crate::config::Config;
+use crate::config::validate::validate_config_layers;
+use crate::default_client::build_reqwest_client;
+use crate::model_provider_info::WireApi;
+use crate::models_manager::manager::OPENAI_DEFAULT_CHATGPT_MODEL;
+use crate::rollout::SESSIONS_SUBDIR;
+use crate::subagents::load_subagents;
+
+/// How long the provider may take to answer the reachability request.
+const PROVIDER_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+const PROVIDER_UNREACHABLE_FIX: &str = "check the network connection, proxy variables \
+(HTTPS_PROXY), and the provider's `base_url`";
+
+const KEYRING_FIX: &str = "set `cli_auth_credentials_store = \"file\"` and \
+`mcp_oauth_credentials_store = \"file\"` to stop using the keyring";
+
+/// Damaged rollout files named in the report; the rest are only counted.
+const MAX_LISTED_ROLLOUTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DoctorStatus {
+    Ok,
+    Warning,
+    Error,
+    /// Not run, because of `--offline` or an earlier failure.
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoctorCheck {
+    /// `config`, `auth`, `provider`, `sandbox`, `keyring`, `mcp:<server>`,
+    /// `subagents`, or `rollouts`.
+    pub id: String,
+    pub status: DoctorStatus,
+    pub detail: String,
+    /// What to do about a warning or error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoctorReport {
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    pub codex_home: PathBuf,
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorCheck {
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+impl DoctorReport {
+    pub fn has_errors(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| check.status == DoctorStatus::Error)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DoctorOptions {
+    /// Send a request to the model provider.
+    pub network: bool,
+    /// Start the configured MCP servers (and stop them again).
+    pub mcp: bool,
+}
+
+/// Runs every check. `config` is the outcome of loading the configuration,
+/// so a config that does not parse is reported instead of stopping the run;
+/// the checks that need it are then skipped.
+pub async fn run_doctor(
+    codex_home: &Path,
+    config: io::Result<Config>,
+    options: DoctorOptions,
+) -> DoctorReport {
+    let mut checks = Vec::new();
+    match &config {
+        Ok(config) => {
+            let path = codex_home.join("config.toml");
+            let detail = if path.is_file() {
+                format!("loaded {}", path.display())
+            } else {
+                format!("{} does not exist; using defaults", path.display())
+            };
+            let problems = validate_config_layers(&config.config_layer_stack);
+            checks.push(match problems.first() {
+                None => check("config", DoctorStatus::Ok, detail),
+                Some(first) => check(
+                    "config",
+                    DoctorStatus::Warning,
+                    format!("{detail}; {} problem(s), first: {first}", problems.len()),
+                )
+                .with_fix("run `codex config check` to list them"),
+            });
+        }
+        Err(err) => checks.push(
+            check("config", DoctorStatus::Error, err.to_string()).with_fix(
+                "fix the file and line named above, or move the file aside to start from defaults",
+            ),
+        ),
+    }
+
+    match &config {
+        Ok(config) => {
+            checks.push(auth_check(config));
+            checks.push(if options.network {
+                provider_check(config).await
+            } else {
+                skipped("provider", "--offline")
+            });
+            checks.extend(capability_checks(config, options.mcp).await);
+            checks.push(subagents_check(config));
+        }
+        Err(_) => {
+            for id in ["auth", "provider", "sandbox", "subagents"] {
+                checks.push(skipped(id, "the configuration did not load"));
+            }
+        }
+    }
+    checks.push(rollouts_check(&codex_home.join(SESSIONS_SUBDIR)));
+
+    DoctorReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        codex_home: codex_home.to_path_buf(),
+        checks,
+    }
+}
+
+fn check(id: &str, status: DoctorStatus, detail: impl Into<String>) -> DoctorCheck {
+    DoctorCheck {
+        id: id.to_string(),
+        status,
+        detail: detail.into(),
+        fix: None,
+    }
+}
+
+fn skipped(id: &str, reason: &str) -> DoctorCheck {
+    check(id, DoctorStatus::Skipped, format!("skipped: {reason}"))
+}
+
+fn auth_check(config: &Config) -> DoctorCheck {
+    let provider = &config.model_provider;
+    let id = &config.model_provider_id;
+    if !provider.requires_openai_auth {
+        if provider.entra_id.is_some() {
+            return check(
+                "auth",
+                DoctorStatus::Ok,
+                format!("provider `{id}` signs in with Microsoft Entra ID"),
+            );
+        }
+        return match provider.api_key() {
+            Ok(Some(_)) => check(
+                "auth",
+                DoctorStatus::Ok,
+                format!(
+                    "{} is set for provider `{id}`",
+                    provider.env_key.as_deref().unwrap_or_default()
+                ),
+            ),
+            Ok(None) if provider.experimental_bearer_token.is_some() => check(
+                "auth",
+                DoctorStatus::Ok,
+                format!("provider `{id}` uses experimental_bearer_token"),
+            ),
+            Ok(None) => check(
+                "auth",
+                DoctorStatus::Ok,
+                format!("provider `{id}` does not need credentials"),
+            ),
+            Err(err) => check("auth", DoctorStatus::Error, err.to_string()).with_fix(format!(
+                "export {} before starting codex",
+                provider
+                    .env_key
+                    .as_deref()
+                    .unwrap_or("the provider's API key")
+            )),
+        };
+    }
+
+    match CodexAuth::from_auth_storage(&config.codex_home, config.cli_auth_credentials_store_mode) {
+        Ok(Some(auth)) => match auth.mode {
+            AuthMode::ApiKey => check("auth", DoctorStatus::Ok, "signed in with an API key"),
+            AuthMode::ChatGPT => match auth.get_token_data() {
+                Ok(tokens) => {
+                    let account = tokens
+                        .id_token
+                        .email
+                        .map(|email| format!(" as {email}"))
+                        .unwrap_or_default();
+                    check(
+                        "auth",
+                        DoctorStatus::Ok,
+                        format!("signed in with ChatGPT{account}"),
+                    )
+                }
+                Err(_) => check(
+                    "auth",
+                    DoctorStatus::Error,
+                    "the stored ChatGPT login has no tokens",
+                )
+                .with_fix("run `codex login` again"),
+            },
+        },
+        Ok(None) if read_openai_api_key_from_env().is_some() => {
+            check("auth", DoctorStatus::Ok, "OPENAI_API_KEY is set")
+        }
+        Ok(None) => check("auth", DoctorStatus::Error, "not signed in")
+            .with_fix("run `codex login`, or set OPENAI_API_KEY"),
+        Err(err) => check(
+            "auth",
+            DoctorStatus::Error,
+            format!("failed to read stored credentials: {err}"),
+        )
+        .with_fix("run `codex logout`, then `codex login`"),
+    }
+}
+
+/// Sends `GET <base_url>/models` with the provider's credentials. Any answer
+/// proves the endpoint is reachable; 401 and 403 mean the credentials were
+/// rejected.
+async fn provider_check(config: &Config) -> DoctorCheck {
+    let model = config
+        .model
+        .as_deref()
+        .unwrap_or(OPENAI_DEFAULT_CHATGPT_MODEL);
+    let provider = config.model_provider.for_model(model);
+    let auth = AuthManager::shared(
+        config.codex_home.clone(),
+        false,
+        config.cli_auth_credentials_store_mode,
+    )
+    .auth()
+    .await;
+    let auth_mode = auth.as_ref().map(|auth| auth.mode);
+    let api_provider = match provider.to_api_provider(auth_mode) {
+        Ok(api_provider) => api_provider,
+        Err(err) => {
+            return check("provider", DoctorStatus::Error, err.to_string())
+                .with_fix("check `http_headers` and `env_http_headers` of the provider");
+        }
+    };
+    let credentials = match auth_provider_for_request(auth, &provider, &config.codex_home).await {
+        Ok(credentials) => credentials,
+        Err(err) => {
+            return check(
+                "provider",
+                DoctorStatus::Error,
+                format!("could not get credentials: {err}"),
+            )
+            .with_fix("fix the `auth` check first");
+        }
+    };
+
+    let url = api_provider.url_for_path("models");
+    let mut request = build_reqwest_client()
+        .get(&url)
+        .headers(api_provider.headers.clone())
+        .timeout(PROVIDER_PROBE_TIMEOUT);
+    if let Some(token) = credentials.bearer_token() {
+        request = match provider.wire_api {
+            WireApi::Anthropic => request.header("x-api-key", token),
+            WireApi::Gemini => request.header("x-goog-api-key", token),
+            _ => request.bearer_auth(token),
+        };
+    }
+    if let Some(account_id) = credentials.account_id() {
+        request = request.header("ChatGPT-Account-ID", account_id);
+    }
+
+    let name = &config.model_provider_id;
+    match request.send().await {
+        Ok(response) => {
+            let status = response.status();
+            let detail = format!("`{name}` answered {status} from {url}");
+            if status == reqwest::StatusCode::UNAUTHORIZED
+                || status == reqwest::StatusCode::FORBIDDEN
+            {
+                let fix = if provider.requires_openai_auth {
+                    "run `codex login` again".to_string()
+                } else {
+                    format!(
+                        "check the key in {}",
+                        provider
+                            .env_key
+                            .as_deref()
+                            .unwrap_or("the provider's configuration")
+                    )
+                };
+                check("provider", DoctorStatus::Error, detail).with_fix(fix)
+            } else if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                check("provider", DoctorStatus::Warning, detail)
+                    .with_fix("the provider is having trouble; try again later")
+            } else {
+                check("provider", DoctorStatus::Ok, detail)
+            }
+        }
+        Err(err) => {
+            let detail = format!("could not reach {url}: {err}");
+            check("provider", DoctorStatus::Error, detail).with_fix(PROVIDER_UNREACHABLE_FIX)
+        }
+    }
+}
+
+/// The sandbox, keyring, and MCP entries of the capability report.
+async fn capability_checks(config: &Config, start_mcp: bool) -> Vec<DoctorCheck> {
+    collect_capabilities(config, !start_mcp)
+        .await
+        .into_iter()
+        .filter_map(|capability| {
+            let id = capability.id.as_str();
+            let (problem, fix) = if id == "sandbox" {
+                (DoctorStatus::Error, sandbox_fix().to_string())
+            } else if id == "keyring" {
+                (DoctorStatus::Warning, KEYRING_FIX.to_string())
+            } else if let Some(server) = id.strip_prefix("mcp:") {
+                let fix = format!(
+                    "check `[mcp_servers.{server}]` in config.toml (or `codex mcp get {server}`), \
+or set `enabled = false` there"
+                );
+                (DoctorStatus::Error, fix)
+            } else {
+                return None;
+            };
+            let detail = capability.detail;
+            Some(match capability.status {
+                CapabilityStatus::Active | CapabilityStatus::Disabled => {
+                    check(id, DoctorStatus::Ok, detail)
+                }
+                CapabilityStatus::Unavailable => check(id, problem, detail).with_fix(fix),
+            })
+        })
+        .collect()
+}
+
+fn sandbox_fix() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "start codex from an install that ships the codex-linux-sandbox helper \
+(the npm package or a release archive)"
+    } else if cfg!(target_os = "macos") {
+        "/usr/bin/sandbox-exec ships with macOS; check that the system install is intact"
+    } else if cfg!(target_os = "windows") {
+        "set `experimental_windows_sandbox = true` under `[features]`"
+    } else {
+        "run codex inside a container or VM and set `sandbox_mode = \"danger-full-access\"`"
+    }
+}
+
+fn subagents_check(config: &Config) -> DoctorCheck {
+    let outcome = load_subagents(&config.cwd, &config.codex_home, &config.subagent_extra_dirs);
+    if outcome.errors.is_empty() {
+        return check(
+            "subagents",
+            DoctorStatus::Ok,
+            format!("{} definition(s) loaded", outcome.subagents.len()),
+        );
+    }
+    let errors: Vec<String> = outcome
+        .errors
+        .iter()
+        .map(|error| format!("{}: {}", error.path.display(), error.message))
+        .collect();
+    check("subagents", DoctorStatus::Error, errors.join("; "))
+        .with_fix("fix the front matter of these files or remove them")
+}
+
+/// Checks that `sessions` is writable and that every rollout starts with
+/// readable session metadata.
+fn rollouts_check(sessions: &Path) -> DoctorCheck {
+    if !sessions.exists() {
+        return check(
+            "rollouts",
+            DoctorStatus::Ok,
+            format!("{} does not exist yet", sessions.display()),
+        );
+    }
+    let probe = sessions.join(".codex-doctor-probe");
+    if let Err(err) = fs::write(&probe, b"").and_then(|()| fs::remove_file(&probe)) {
+        return check(
+            "rollouts",
+            DoctorStatus::Error,
+            format!("cannot write to {}: {err}", sessions.display()),
+        )
+        .with_fix(format!(
+            "make {} writable by this user; new sessions cannot be saved",
+            sessions.display()
+        ));
+    }
+
+    let mut rollouts = Vec::new();
+    collect_rollouts(sessions, &mut rollouts);
+    let damaged: Vec<&PathBuf> = rollouts
+        .iter()
+        .filter(|path| !starts_with_session_meta(path))
+        .collect();
+    if damaged.is_empty() {
+        return check(
+            "rollouts",
+            DoctorStatus::Ok,
+            format!("{} session(s) in {}", rollouts.len(), sessions.display()),
+        );
+    }
+    let mut listed: Vec<String> = damaged
+        .iter()
+        .take(MAX_LISTED_ROLLOUTS)
+        .map(|path| path.display().to_string())
+        .collect();
+    if damaged.len() > MAX_LISTED_ROLLOUTS {
+        listed.push(format!("and {} more", damaged.len() - MAX_LISTED_ROLLOUTS));
+    }
+    check(
+        "rollouts",
+        DoctorStatus::Warning,
+        format!(
+            "{} of {} session(s) cannot be read: {}",
+            damaged.len(),
+            rollouts.len(),
+            listed.join(", ")
+        ),
+    )
+    .with_fix("move these files out of the sessions directory; they cannot be resumed")
+}
+
+fn collect_rollouts(dir: &Path, rollouts: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_rollouts(&path, rollouts);
+        } else if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("rollout-") && name.ends_with(".jsonl"))
+        {
+            rollouts.push(path);
+        }
+    }
+}
+
+/// Empty files count as readable: a session writes its metadata lazily.
+fn starts_with_session_meta(path: &Path) -> bool {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return false;
+    };
+    let Some(first) = contents.lines().next() else {
+        return true;
+    };
+    serde_json::from_str::<serde_json::Value>(first)
+        .is_ok_and(|line| line.get("type").and_then(|kind| kind.as_str()) == Some("session_meta"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn rollouts_check_lists_damaged_sessions() {
+        let home = tempfile::tempdir().expect("tempdir");
+        let sessions = home.path().join(SESSIONS_SUBDIR);
+        let day = sessions.join("2026").join("10").join("18");
+        fs::create_dir_all(&day).expect("create sessions");
+        fs::write(
+            day.join("rollout-good.jsonl"),
+            "{\"timestamp\":\"t\",\"type\":\"session_meta\",\"payload\":{}}\n",
+        )
+        .expect("write");
+        fs::write(day.join("rollout-empty.jsonl"), "").expect("write");
+        fs::write(day.join("rollout-bad.jsonl"), "{\"truncat").expect("write");
+        fs::write(day.join("notes.txt"), "not a rollout").expect("write");
+
+        let report = rollouts_check(&sessions);
+
+        assert_eq!(report.status, DoctorStatus::Warning);
+        assert_eq!(
+            report.detail,
+            format!(
+                "1 of 3 session(s) cannot be read: {}",
+                day.join("rollout-bad.jsonl").display()
+            )
+        );
+        assert!(!sessions.join(".codex-doctor-probe").exists());
+    }
+
+    #[tokio::test]
+    async fn unreadable_config_skips_dependent_checks() {
+        let home = tempfile::tempdir().expect("tempdir");
+        let report = run_doctor(
+            home.path(),
+            Err(io::Error::other("config.toml:3: expected `=`")),
+            DoctorOptions {
+                network: false,
+                mcp: false,
+            },
+        )
+        .await;
+
+        assert!(report.has_errors());
+        assert_eq!(
+            report
+                .checks
+                .iter()
+                .map(|check| (check.id.as_str(), check.status))
+                .collect::<Vec<_>>(),
+            vec![
+                ("config", DoctorStatus::Error),
+                ("auth", DoctorStatus::Skipped),
+                ("provider", DoctorStatus::Skipped),
+                ("sandbox", DoctorStatus::Skipped),
+                ("subagents", DoctorStatus::Skipped),
+                ("rollouts", DoctorStatus::Ok),
+            ]
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Editor content attached to a prompt by the client.
+//!
+//! [`UserInput::EditorContext`] items (a selection, or a whole open file) and
+//! [`UserInput::TerminalOutput`] items are recorded as one `<editor_context>`
+//! message right after the user's prompt, so the prompt text stays what the
+//! user typed. Each item is capped, and so is the turn's total; a truncated
+//! attachment is reported with a warning.
+
+use std::path::Path;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::WarningEvent;
+use codex_protocol::user_input::UserInput;
+use codex_utils_string::take_bytes_at_char_boundary;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::session_prefix::EDITOR_CONTEXT_OPEN_TAG;
+
+/// Per-attachment cap, so one large file cannot crowd out the rest.
+const MAX_ITEM_BYTES: usize = 128 * 1024;
+
+/// Budget for all editor context attached to one turn.
+const MAX_TOTAL_BYTES: usize = 256 * 1024;
+
+const TRUNCATION_MARKER: &str = "\n[truncated]";
+
+#[derive(Debug, Clone, PartialEq)]
+struct Attachment {
+    /// Where the text came from, for the truncation warning.
+    source: String,
+    /// Element name and attributes of the rendered tag.
+    tag: &'static str,
+    attributes: Vec<(&'static str, String)>,
+    text: String,
+    truncated: bool,
+}
+
+/// Returns the message to record for the editor context in `input`, or
+/// `None` when there is none.
+pub(crate) async fn gather(
+    sess: &Session,
+    turn: &TurnContext,
+    input: &[UserInput],
+) -> Option<ResponseItem> {
+    let attachments = collect(&turn.cwd, input);
+    if attachments.is_empty() {
+        return None;
+    }
+    for attachment in attachments.iter().filter(|a| a.truncated) {
+        let message = format!(
+            "Attached context from {} was truncated to fit the size limit.",
+            attachment.source
+        );
+        sess.send_event(turn, EventMsg::Warning(WarningEvent { message }))
+            .await;
+    }
+    Some(ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: render(&attachments),
+        }],
+        end_turn: None,
+    })
+}
+
+fn collect(cwd: &Path, input: &[UserInput]) -> Vec<Attachment> {
+    let mut budget = MAX_TOTAL_BYTES;
+    let mut attachments = Vec::new();
+    for item in input {
+        let (source, tag, attributes, text) = match item {
+            UserInput::EditorContext {
+                path,
+                start_line,
+                end_line,
+                text,
+            } => {
+                let path = path.strip_prefix(cwd).unwrap_or(path).display().to_string();
+                let mut attributes = vec![("path", path.clone())];
+                let tag = match start_line {
+                    Some(start) => {
+                        let end = end_line.unwrap_or(*start).max(*start);
+                        attributes.push(("lines", format!("{start}-{end}")));
+                        "selection"
+                    }
+                    None => "file",
+                };
+                (path, tag, attributes, text)
+            }
+            UserInput::TerminalOutput {
+                terminal,
+                command,
+                exit_code,
+                text,
+            } => {
+                let mut attributes = vec![("name", terminal.clone())];
+                if let Some(command) = command {
+                    attributes.push(("command", command.clone()));
+                }
+                if let Some(exit_code) = exit_code {
+                    attributes.push(("exit_code", exit_code.to_string()));
+                }
+                (format!("terminal {terminal}"), "terminal", attributes, text)
+            }
+            _ => continue,
+        };
+        let limit = MAX_ITEM_BYTES.min(budget);
+        let truncated = text.len() > limit;
+        let text = if truncated {
+            format!(
+                "{}{TRUNCATION_MARKER}",
+                take_bytes_at_char_boundary(text, limit)
+            )
+        } else {
+            text.clone()
+        };
+        budget = budget.saturating_sub(text.len());
+        attachments.push(Attachment {
+            source,
+            tag,
+            attributes,
+            text,
+            truncated,
+        });
+    }
+    attachments
+}
+
+fn render(attachments: &[Attachment]) -> String {
+    let mut text = format!("{EDITOR_CONTEXT_OPEN_TAG}\n");
+    for attachment in attachments {
+        text.push('<');
+        text.push_str(attachment.tag);
+        for (name, value) in &attachment.attributes {
+            text.push_str(&format!(" {name}=\"{}\"", value.replace('"', "'")));
+        }
+        text.push_str(">\n");
+        text.push_str(attachment.text.trim_end());
+        text.push_str(&format!("\n</{}>\n", attachment.tag));
+    }
+    text.push_str("</editor_context>");
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn renders_attachments_with_paths_relative_to_cwd() {
+        let input = vec![
+            UserInput::Text {
+                text: "Why is this slow?".to_string(),
+                text_elements: Vec::new(),
+            },
+            UserInput::EditorContext {
+                path: PathBuf::from("/repo/src/lib.rs"),
+                start_line: Some(10),
+                end_line: Some(12),
+                text: "fn slow() {\n    loop {}\n}\n".to_string(),
+            },
+            UserInput::EditorContext {
+                path: PathBuf::from("/elsewhere/notes.md"),
+                start_line: None,
+                end_line: None,
+                text: "# Notes".to_string(),
+            },
+            UserInput::TerminalOutput {
+                terminal: "zsh".to_string(),
+                command: Some("cargo test".to_string()),
+                exit_code: Some(101),
+                text: "test slow ... FAILED\n".to_string(),
+            },
+        ];
+
+        let attachments = collect(Path::new("/repo"), &input);
+
+        assert_eq!(
+            render(&attachments),
+            "<editor_context>\n<selection path=\"src/lib.rs\" lines=\"10-12\">\nfn slow() {\n    loop {}\n}\n</selection>\n<file path=\"/elsewhere/notes.md\">\n# Notes\n</file>\n<terminal name=\"zsh\" command=\"cargo test\" exit_code=\"101\">\ntest slow ... FAILED\n</terminal>\n</editor_context>"
+        );
+    }
+
+    #[test]
+    fn truncates_past_the_item_limit() {
+        let input = vec![UserInput::EditorContext {
+            path: PathBuf::from("big.txt"),
+            start_line: None,
+            end_line: None,
+            text: "a".repeat(MAX_ITEM_BYTES + 1),
+        }];
+
+        let attachments = collect(Path::new("/repo"), &input);
+
+        assert!(attachments[0].truncated);
+        assert!(attachments[0].text.ends_with(TRUNCATION_MARKER));
+        assert_eq!(
+            attachments[0].text.len(),
+            MAX_ITEM_BYTES + TRUNCATION_MARKER.len()
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::codex::TurnContext;
+use crate::project_toolchain::ProjectToolchain;
 use crate::shell::Shell;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseItem;
@@ -13,11 +14,24 @@ use std::path::PathBuf;
 pub(crate) struct EnvironmentContext {
     pub cwd: Option<PathBuf>,
     pub shell: Shell,
+    /// Project toolchains detected at session start. Only populated for the
+    /// initial context; turn-to-turn updates leave it empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub toolchains: Vec<ProjectToolchain>,
 }
 
 impl EnvironmentContext {
     pub fn new(cwd: Option<PathBuf>, shell: Shell) -> Self {
-        Self { cwd, shell }
+        Self {
+            cwd,
+            shell,
+            toolchains: Vec::new(),
+        }
+    }
+
+    pub fn with_toolchains(mut self, toolchains: Vec<ProjectToolchain>) -> Self {
+        self.toolchains = toolchains;
+        self
     }
 
     /// Compares two environment contexts, ignoring the shell. Useful when
@@ -28,6 +42,8 @@ impl EnvironmentContext {
             cwd,
             // should compare all fields except shell
             shell: _,
+            // detected once for the initial context, never diffed
+            toolchains: _,
         } = other;
 
         self.cwd == *cwd
@@ -56,6 +72,9 @@ impl EnvironmentContext {
     /// <environment_context>
     ///   <cwd>...</cwd>
     ///   <shell>...</shell>
+    ///   <project_toolchain>
+    ///     <toolchain name="..." version="..." build="..." test="..." lint="..." />
+    ///   </project_toolchain>
     /// </environment_context>
     /// ```
     pub fn serialize_to_xml(self) -> String {
@@ -66,11 +85,39 @@ impl EnvironmentContext {
 
         let shell_name = self.shell.name();
         lines.push(format!("  <shell>{shell_name}</shell>"));
+        if !self.toolchains.is_empty() {
+            lines.push("  <project_toolchain>".to_string());
+            lines.extend(self.toolchains.iter().map(toolchain_to_xml));
+            lines.push("  </project_toolchain>".to_string());
+        }
         lines.push(ENVIRONMENT_CONTEXT_CLOSE_TAG.to_string());
         lines.join("\n")
     }
 }
 
+fn toolchain_to_xml(toolchain: &ProjectToolchain) -> String {
+    let attributes = [
+        ("name", Some(&toolchain.name)),
+        ("version", toolchain.version.as_ref()),
+        ("build", toolchain.build.as_ref()),
+        ("test", toolchain.test.as_ref()),
+        ("lint", toolchain.lint.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| Some(format!("{key}=\"{}\"", escape_xml_attribute(value?))))
+    .collect::<Vec<_>>()
+    .join(" ");
+    format!("    <toolchain {attributes} />")
+}
+
+fn escape_xml_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 impl From<EnvironmentContext> for ResponseItem {
     fn from(ec: EnvironmentContext) -> Self {
         ResponseItem::Message {
@@ -116,6 +163,36 @@ mod tests {
         assert_eq!(context.serialize_to_xml(), expected);
     }
 
+    #[test]
+    fn serialize_environment_context_with_toolchains() {
+        let context = EnvironmentContext::new(None, fake_shell()).with_toolchains(vec![
+            ProjectToolchain {
+                name: "pnpm".to_string(),
+                version: Some("9.1.0".to_string()),
+                build: Some("pnpm run build".to_string()),
+                test: Some("pnpm test".to_string()),
+                lint: None,
+            },
+            ProjectToolchain {
+                name: "maven".to_string(),
+                version: None,
+                build: Some("mvn compile".to_string()),
+                test: Some("mvn test -Dx=\"y\"".to_string()),
+                lint: None,
+            },
+        ]);
+
+        let expected = r#"<environment_context>
+  <shell>bash</shell>
+  <project_toolchain>
+    <toolchain name="pnpm" version="9.1.0" build="pnpm run build" test="pnpm test" />
+    <toolchain name="maven" build="mvn compile" test="mvn test -Dx=&quot;y&quot;" />
+  </project_toolchain>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
     #[test]
     fn serialize_read_only_environment_context() {
         let context = EnvironmentContext::new(None, fake_shell());
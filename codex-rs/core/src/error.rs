@@ -1,4 +1,5 @@
 use crate::exec::ExecToolCallOutput;
+use crate::exec_limits::ExecLimitExceeded;
 use crate::token_data::KnownPlan;
 use crate::token_data::PlanType;
 use crate::truncate::TruncationPolicy;
@@ -11,6 +12,7 @@ use codex_async_utils::CancelErr;
 use codex_protocol::ThreadId;
 use codex_protocol::protocol::CodexErrorInfo;
 use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::ProviderQuota;
 use codex_protocol::protocol::RateLimitSnapshot;
 use reqwest::StatusCode;
 use serde_json;
@@ -51,6 +53,13 @@ pub enum SandboxErr {
     #[error("command was killed by a signal")]
     Signal(i32),
 
+    /// Command was killed for exceeding an `[exec_limits]` limit
+    #[error("command killed: {limit}")]
+    LimitExceeded {
+        limit: ExecLimitExceeded,
+        output: Box<ExecToolCallOutput>,
+    },
+
     /// Error from linux landlock
     #[error("Landlock was not able to fully enforce all sandbox rules")]
     LandlockRestrict,
@@ -134,6 +143,10 @@ pub enum CodexErr {
     #[error("{0}")]
     RetryLimit(RetryLimitReachedError),
 
+    /// The provider rate-limited the request (HTTP 429).
+    #[error("{0}")]
+    RateLimited(RateLimitedError),
+
     /// Agent loop died unexpectedly
     #[error("internal error; agent loop died unexpectedly")]
     InternalAgentDied,
@@ -212,6 +225,7 @@ impl CodexErr {
             | CodexErr::ResponseStreamFailed(_)
             | CodexErr::ConnectionFailed(_)
             | CodexErr::InternalServerError
+            | CodexErr::RateLimited(_)
             | CodexErr::InternalAgentDied
             | CodexErr::Io(_)
             | CodexErr::Json(_)
@@ -351,6 +365,28 @@ impl std::fmt::Display for RetryLimitReachedError {
     }
 }
 
+#[derive(Debug)]
+pub struct RateLimitedError {
+    /// How long the provider asked to wait, from `retry-after` or the
+    /// `x-ratelimit-reset-*` headers.
+    pub retry_after: Option<Duration>,
+    pub quota: Option<ProviderQuota>,
+    pub request_id: Option<String>,
+}
+
+impl std::fmt::Display for RateLimitedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rate limited by the provider (429 Too Many Requests)")?;
+        if let Some(retry_after) = self.retry_after {
+            write!(f, ", retry after {}s", retry_after.as_secs_f64().ceil())?;
+        }
+        if let Some(request_id) = &self.request_id {
+            write!(f, ", request id: {request_id}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct UsageLimitReachedError {
     pub(crate) plan_type: Option<PlanType>,
@@ -488,9 +524,11 @@ impl CodexErr {
             CodexErr::UsageLimitReached(_)
             | CodexErr::QuotaExceeded
             | CodexErr::UsageNotIncluded => CodexErrorInfo::UsageLimitExceeded,
-            CodexErr::RetryLimit(_) => CodexErrorInfo::ResponseTooManyFailedAttempts {
-                http_status_code: self.http_status_code_value(),
-            },
+            CodexErr::RetryLimit(_) | CodexErr::RateLimited(_) => {
+                CodexErrorInfo::ResponseTooManyFailedAttempts {
+                    http_status_code: self.http_status_code_value(),
+                }
+            }
             CodexErr::ConnectionFailed(_) => CodexErrorInfo::HttpConnectionFailed {
                 http_status_code: self.http_status_code_value(),
             },
@@ -524,6 +562,7 @@ impl CodexErr {
     pub fn http_status_code_value(&self) -> Option<u16> {
         let http_status_code = match self {
             CodexErr::RetryLimit(err) => Some(err.status),
+            CodexErr::RateLimited(_) => Some(StatusCode::TOO_MANY_REQUESTS),
             CodexErr::UnexpectedStatus(err) => Some(err.status),
             CodexErr::ConnectionFailed(err) => err.source.status(),
             CodexErr::ResponseStreamFailed(err) => err.source.status(),
@@ -560,6 +599,9 @@ pub fn get_error_message_ui(e: &CodexErr) -> String {
                 output.duration.as_millis()
             )
         }
+        CodexErr::Sandbox(SandboxErr::LimitExceeded { limit, .. }) => {
+            format!("error: command killed: {limit}")
+        }
         _ => e.to_string(),
     };
 
@@ -725,6 +767,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn limit_exceeded_reports_the_limit() {
+        let output = ExecToolCallOutput {
+            exit_code: 137,
+            stdout: StreamOutput::new(String::new()),
+            stderr: StreamOutput::new(String::new()),
+            aggregated_output: StreamOutput::new(String::new()),
+            duration: Duration::from_millis(5),
+            timed_out: false,
+        };
+        let err = CodexErr::Sandbox(SandboxErr::LimitExceeded {
+            limit: ExecLimitExceeded::Memory { mb: 512 },
+            output: Box::new(output),
+        });
+        assert_eq!(
+            get_error_message_ui(&err),
+            "error: command killed: exceeded memory limit (512 MiB)"
+        );
+    }
+
     #[test]
     fn usage_limit_reached_error_formats_free_plan() {
         let err = UsageLimitReachedError {
@@ -4,6 +4,7 @@ use codex_protocol::items::ReasoningItem;
 use codex_protocol::items::TurnItem;
 use codex_protocol::items::UserMessageItem;
 use codex_protocol::items::WebSearchItem;
+use codex_protocol::models::CodeInterpreterOutput;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ReasoningItemContent;
 use codex_protocol::models::ReasoningItemReasoningSummary;
@@ -13,6 +14,8 @@ use codex_protocol::models::is_image_close_tag_text;
 use codex_protocol::models::is_image_open_tag_text;
 use codex_protocol::models::is_local_image_close_tag_text;
 use codex_protocol::models::is_local_image_open_tag_text;
+use codex_protocol::protocol::HostedToolCallEvent;
+use codex_protocol::protocol::HostedToolKind;
 use codex_protocol::user_input::UserInput;
 use tracing::warn;
 use uuid::Uuid;
@@ -139,16 +142,81 @@ pub fn parse_turn_item(item: &ResponseItem) -> Option<TurnItem> {
     }
 }
 
+/// Summarizes a completed provider-hosted tool call for the event stream.
+pub(crate) fn hosted_tool_call_event(item: &ResponseItem) -> Option<HostedToolCallEvent> {
+    match item {
+        ResponseItem::CodeInterpreterCall {
+            id,
+            status,
+            code,
+            outputs,
+            ..
+        } => {
+            let mut logs = Vec::new();
+            let mut image_urls = Vec::new();
+            for output in outputs.iter().flatten() {
+                match output {
+                    CodeInterpreterOutput::Logs { logs: text } => logs.push(text.as_str()),
+                    CodeInterpreterOutput::Image { url } => image_urls.push(url.clone()),
+                    CodeInterpreterOutput::Other => {}
+                }
+            }
+            Some(HostedToolCallEvent {
+                call_id: id.clone().unwrap_or_default(),
+                tool: HostedToolKind::CodeInterpreter,
+                status: status.clone(),
+                input: code.clone().unwrap_or_default(),
+                output: logs.concat(),
+                image_urls,
+            })
+        }
+        ResponseItem::FileSearchCall {
+            id,
+            status,
+            queries,
+            results,
+        } => {
+            let matches: Vec<String> = results
+                .iter()
+                .flatten()
+                .map(|result| {
+                    let name = result
+                        .filename
+                        .as_deref()
+                        .or(result.file_id.as_deref())
+                        .unwrap_or("(unknown file)");
+                    match result.score {
+                        Some(score) => format!("{name} (score {score:.2})"),
+                        None => name.to_string(),
+                    }
+                })
+                .collect();
+            Some(HostedToolCallEvent {
+                call_id: id.clone().unwrap_or_default(),
+                tool: HostedToolKind::FileSearch,
+                status: status.clone(),
+                input: queries.join("\n"),
+                output: matches.join("\n"),
+                image_urls: Vec::new(),
+            })
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::hosted_tool_call_event;
     use super::parse_turn_item;
     use codex_protocol::items::AgentMessageContent;
     use codex_protocol::items::TurnItem;
+    use codex_protocol::models::CodeInterpreterOutput;
     use codex_protocol::models::ContentItem;
     use codex_protocol::models::ReasoningItemContent;
     use codex_protocol::models::ReasoningItemReasoningSummary;
     use codex_protocol::models::ResponseItem;
     use codex_protocol::models::WebSearchAction;
+    use codex_protocol::protocol::HostedToolKind;
     use codex_protocol::user_input::UserInput;
     use pretty_assertions::assert_eq;
 
@@ -434,4 +502,30 @@ mod tests {
             other => panic!("expected TurnItem::WebSearch, got {other:?}"),
         }
     }
+
+    #[test]
+    fn maps_code_interpreter_call_to_hosted_tool_event() {
+        let item = ResponseItem::CodeInterpreterCall {
+            id: Some("ci_1".to_string()),
+            status: Some("completed".to_string()),
+            code: Some("print(2)".to_string()),
+            container_id: "cntr_1".to_string(),
+            outputs: Some(vec![
+                CodeInterpreterOutput::Logs {
+                    logs: "2\n".to_string(),
+                },
+                CodeInterpreterOutput::Image {
+                    url: "https://example.com/plot.png".to_string(),
+                },
+            ]),
+        };
+
+        let event = hosted_tool_call_event(&item).expect("expected hosted tool event");
+
+        assert_eq!(event.call_id, "ci_1");
+        assert_eq!(event.tool, HostedToolKind::CodeInterpreter);
+        assert_eq!(event.input, "print(2)");
+        assert_eq!(event.output, "2\n");
+        assert_eq!(event.image_urls, vec!["https://example.com/plot.png"]);
+    }
 }
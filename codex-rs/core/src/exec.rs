@@ -6,6 +6,9 @@ use std::io;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::ExitStatus;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -16,9 +19,14 @@ use tokio::io::BufReader;
 use tokio::process::Child;
 use tokio_util::sync::CancellationToken;
 
+use crate::config::types::ExecLimits;
+use crate::container_sandbox::SandboxContainer;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::SandboxErr;
+use crate::exec_limits::ExecLimitExceeded;
+use crate::exec_limits::killed_by_cpu_limit;
+use crate::exec_limits::watch;
 use crate::get_platform_sandbox;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
@@ -125,6 +133,9 @@ pub enum SandboxType {
 
     /// Only available on Windows.
     WindowsRestrictedToken,
+
+    /// Docker or Podman, selected with `sandbox.backend = "container"`.
+    Container,
 }
 
 #[derive(Clone)]
@@ -205,6 +216,7 @@ pub(crate) async fn execute_exec_env(
         sandbox_permissions,
         justification,
         arg0,
+        limits,
     } = env;
 
     let params = ExecParams {
@@ -218,7 +230,7 @@ pub(crate) async fn execute_exec_env(
     };
 
     let start = Instant::now();
-    let raw_output_result = exec(params, sandbox, sandbox_policy, stdout_stream).await;
+    let raw_output_result = exec(params, sandbox, sandbox_policy, stdout_stream, limits).await;
     let duration = start.elapsed();
     finalize_exec_result(raw_output_result, sandbox, duration)
 }
@@ -333,6 +345,7 @@ async fn exec_windows_sandbox(
         stderr,
         aggregated_output,
         timed_out: capture.timed_out,
+        limit_exceeded: None,
     })
 }
 
@@ -346,6 +359,23 @@ fn finalize_exec_result(
             #[allow(unused_mut)]
             let mut timed_out = raw_output.timed_out;
 
+            if let Some(limit) = raw_output.limit_exceeded {
+                let mut aggregated_output = raw_output.aggregated_output.from_utf8_lossy();
+                aggregated_output.text = format!("killed: {limit}\n{}", aggregated_output.text);
+                let output = ExecToolCallOutput {
+                    exit_code: EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE,
+                    stdout: raw_output.stdout.from_utf8_lossy(),
+                    stderr: raw_output.stderr.from_utf8_lossy(),
+                    aggregated_output,
+                    duration,
+                    timed_out: false,
+                };
+                return Err(CodexErr::Sandbox(SandboxErr::LimitExceeded {
+                    limit,
+                    output: Box::new(output),
+                }));
+            }
+
             #[cfg(target_family = "unix")]
             {
                 if let Some(signal) = raw_output.exit_status.signal() {
@@ -405,6 +435,9 @@ pub(crate) mod errors {
                 SandboxTransformError::MissingLinuxSandboxExecutable => {
                     CodexErr::LandlockSandboxExecutableNotProvided
                 }
+                SandboxTransformError::MissingContainerSandboxConfig => {
+                    CodexErr::UnsupportedOperation(err.to_string())
+                }
                 #[cfg(not(target_os = "macos"))]
                 SandboxTransformError::SeatbeltUnavailable => CodexErr::UnsupportedOperation(
                     "seatbelt sandbox is only available on macOS".to_string(),
@@ -489,6 +522,7 @@ struct RawExecToolCallOutput {
     pub stderr: StreamOutput<Vec<u8>>,
     pub aggregated_output: StreamOutput<Vec<u8>>,
     pub timed_out: bool,
+    pub limit_exceeded: Option<ExecLimitExceeded>,
 }
 
 impl StreamOutput<String> {
@@ -548,6 +582,7 @@ async fn exec(
     sandbox: SandboxType,
     sandbox_policy: &SandboxPolicy,
     stdout_stream: Option<StdoutStream>,
+    limits: ExecLimits,
 ) -> Result<RawExecToolCallOutput> {
     #[cfg(target_os = "windows")]
     if sandbox == SandboxType::WindowsRestrictedToken
@@ -573,6 +608,10 @@ async fn exec(
             "command args are empty",
         ))
     })?;
+    let container = match sandbox {
+        SandboxType::Container => SandboxContainer::from_command(&command),
+        _ => None,
+    };
     let arg0_ref = arg0.as_deref();
     let child = spawn_child_async(
         PathBuf::from(program),
@@ -582,17 +621,29 @@ async fn exec(
         sandbox_policy,
         StdioPolicy::RedirectForShellTool,
         env,
+        &limits,
     )
     .await?;
-    consume_truncated_output(child, expiration, stdout_stream).await
+    let mut output =
+        consume_truncated_output(child, expiration, stdout_stream, limits, container).await?;
+    if output.limit_exceeded.is_none()
+        && let Some(secs) = limits.max_cpu_secs
+        && killed_by_cpu_limit(&output.exit_status)
+    {
+        output.limit_exceeded = Some(ExecLimitExceeded::CpuTime { secs });
+    }
+    Ok(output)
 }
 
 /// Consumes the output of a child process, truncating it so it is suitable for
-/// use as the output of a `shell` tool call. Also enforces specified timeout.
+/// use as the output of a `shell` tool call. Also enforces specified timeout
+/// and the runtime, memory and output limits.
 async fn consume_truncated_output(
     mut child: Child,
     expiration: ExecExpiration,
     stdout_stream: Option<StdoutStream>,
+    limits: ExecLimits,
+    container: Option<SandboxContainer>,
 ) -> Result<RawExecToolCallOutput> {
     // Both stdout and stderr were configured with `Stdio::piped()`
     // above, therefore `take()` should normally return `Some`.  If it doesn't
@@ -609,31 +660,43 @@ async fn consume_truncated_output(
         ))
     })?;
 
+    let output_bytes = Arc::new(AtomicU64::new(0));
     let stdout_handle = tokio::spawn(read_capped(
         BufReader::new(stdout_reader),
         stdout_stream.clone(),
         false,
+        Arc::clone(&output_bytes),
     ));
     let stderr_handle = tokio::spawn(read_capped(
         BufReader::new(stderr_reader),
         stdout_stream.clone(),
         true,
+        Arc::clone(&output_bytes),
     ));
 
-    let (exit_status, timed_out) = tokio::select! {
+    let child_pid = child.id();
+    let (exit_status, timed_out, limit_exceeded) = tokio::select! {
         status_result = child.wait() => {
             let exit_status = status_result?;
-            (exit_status, false)
+            (exit_status, false, None)
         }
         _ = expiration.wait() => {
             kill_child_process_group(&mut child)?;
             child.start_kill()?;
-            (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + TIMEOUT_CODE), true)
+            kill_container(container.as_ref()).await;
+            (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + TIMEOUT_CODE), true, None)
+        }
+        limit = watch(limits, child_pid, output_bytes) => {
+            kill_child_process_group(&mut child)?;
+            child.start_kill()?;
+            kill_container(container.as_ref()).await;
+            (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE), false, Some(limit))
         }
         _ = tokio::signal::ctrl_c() => {
             kill_child_process_group(&mut child)?;
             child.start_kill()?;
-            (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE), false)
+            kill_container(container.as_ref()).await;
+            (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE), false, None)
         }
     };
 
@@ -704,13 +767,24 @@ async fn consume_truncated_output(
         stderr,
         aggregated_output,
         timed_out,
+        limit_exceeded,
     })
 }
 
+/// Killing the runtime CLI does not stop the container it started.
+async fn kill_container(container: Option<&SandboxContainer>) {
+    if let Some(container) = container {
+        container.kill().await;
+    }
+}
+
+/// Reads `reader` to EOF, keeping at most `EXEC_OUTPUT_MAX_BYTES` and adding
+/// every byte read to `total_bytes`.
 async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
     mut reader: R,
     stream: Option<StdoutStream>,
     is_stderr: bool,
+    total_bytes: Arc<AtomicU64>,
 ) -> io::Result<StreamOutput<Vec<u8>>> {
     let mut buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY.min(EXEC_OUTPUT_MAX_BYTES));
     let mut tmp = [0u8; READ_CHUNK_SIZE];
@@ -721,6 +795,7 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
         if n == 0 {
             break;
         }
+        total_bytes.fetch_add(n as u64, Ordering::Relaxed);
 
         if let Some(stream) = &stream
             && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL
@@ -842,7 +917,9 @@ mod tests {
             writer.write_all(&bytes).await.expect("write");
         });
 
-        let out = read_capped(reader, None, false).await.expect("read");
+        let out = read_capped(reader, None, false, Arc::default())
+            .await
+            .expect("read");
         assert_eq!(out.text.len(), EXEC_OUTPUT_MAX_BYTES);
     }
 
@@ -882,7 +959,14 @@ mod tests {
             arg0: None,
         };
 
-        let output = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await?;
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::ReadOnly,
+            None,
+            ExecLimits::default(),
+        )
+        .await?;
         assert!(output.timed_out);
 
         let stdout = output.stdout.from_utf8_lossy().text;
@@ -947,6 +1031,42 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn output_limit_kills_command() -> Result<()> {
+        let env = ExecEnv {
+            command: vec![
+                "/bin/sh".to_string(),
+                "-c".to_string(),
+                "while :; do echo spam; done".to_string(),
+            ],
+            cwd: std::env::current_dir()?,
+            env: std::env::vars().collect(),
+            expiration: 10_000.into(),
+            sandbox: SandboxType::None,
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+            arg0: None,
+            limits: ExecLimits {
+                max_output_bytes: Some(64 * 1024),
+                ..Default::default()
+            },
+        };
+        let result = execute_exec_env(env, &SandboxPolicy::DangerFullAccess, None).await;
+        let (limit, output) = match result {
+            Err(CodexErr::Sandbox(SandboxErr::LimitExceeded { limit, output })) => (limit, output),
+            other => panic!("expected limit error, got {other:?}"),
+        };
+        assert_eq!(limit, ExecLimitExceeded::Output { bytes: 64 * 1024 });
+        assert!(
+            output
+                .aggregated_output
+                .text
+                .starts_with("killed: exceeded output limit (65536 bytes)\n")
+        );
+        Ok(())
+    }
+
     #[cfg(unix)]
     fn long_running_command() -> Vec<String> {
         vec![
@@ -0,0 +1,236 @@
+//! Resource limits for shell tool commands (`[exec_limits]`).
+//!
+//! CPU time and niceness are set on the child before it execs (`RLIMIT_CPU`,
+//! `setpriority`), so they survive the Seatbelt and Landlock wrappers and are
+//! inherited by everything the command starts. Runtime, memory and output are
+//! watched from the parent while the command runs: memory is the resident set
+//! of the whole session the command leads, which is what actually exhausts a
+//! machine, and unlike `RLIMIT_AS` does not break runtimes that reserve large
+//! address ranges up front. When a limit trips the process group is killed and
+//! the call fails with [`SandboxErr::LimitExceeded`](crate::error::SandboxErr).
+
+use std::fmt;
+use std::process::ExitStatus;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use crate::config::types::ExecLimits;
+
+/// How often memory use and output volume are sampled.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Seconds between the soft `RLIMIT_CPU` (SIGXCPU) and the hard one (SIGKILL),
+/// for commands that handle SIGXCPU.
+#[cfg(unix)]
+const CPU_LIMIT_GRACE_SECS: u64 = 5;
+
+/// The limit that got a command killed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecLimitExceeded {
+    Runtime { secs: u64 },
+    CpuTime { secs: u64 },
+    Memory { mb: u64 },
+    Output { bytes: u64 },
+}
+
+impl fmt::Display for ExecLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecLimitExceeded::Runtime { secs } => write!(f, "exceeded runtime limit ({secs}s)"),
+            ExecLimitExceeded::CpuTime { secs } => {
+                write!(f, "exceeded CPU time limit ({secs}s)")
+            }
+            ExecLimitExceeded::Memory { mb } => write!(f, "exceeded memory limit ({mb} MiB)"),
+            ExecLimitExceeded::Output { bytes } => {
+                write!(f, "exceeded output limit ({bytes} bytes)")
+            }
+        }
+    }
+}
+
+/// Runs in the forked child before exec.
+#[cfg(unix)]
+pub(crate) fn apply_in_child(limits: &ExecLimits) -> std::io::Result<()> {
+    if let Some(secs) = limits.max_cpu_secs {
+        let limit = libc::rlimit {
+            rlim_cur: secs as libc::rlim_t,
+            rlim_max: secs.saturating_add(CPU_LIMIT_GRACE_SECS) as libc::rlim_t,
+        };
+        // SAFETY: setrlimit only reads the struct passed by reference.
+        if unsafe { libc::setrlimit(libc::RLIMIT_CPU, &limit) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    if let Some(nice) = limits.nice {
+        // SAFETY: adjusts the calling process's own priority.
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Whether `status` reports the command was stopped by `RLIMIT_CPU`, either
+/// directly or through the shell that ran it.
+pub(crate) fn killed_by_cpu_limit(status: &ExitStatus) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        status.signal() == Some(libc::SIGXCPU) || status.code() == Some(128 + libc::SIGXCPU)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = status;
+        false
+    }
+}
+
+/// Resolves once a runtime, memory or output limit is exceeded by the
+/// command whose session is led by `pid`; never resolves when none is set.
+/// `output_bytes` counts everything the command has written so far.
+pub(crate) async fn watch(
+    limits: ExecLimits,
+    pid: Option<u32>,
+    output_bytes: Arc<AtomicU64>,
+) -> ExecLimitExceeded {
+    let deadline = limits
+        .max_runtime_secs
+        .map(|secs| tokio::time::Instant::now() + Duration::from_secs(secs));
+    if deadline.is_none() && limits.max_memory_mb.is_none() && limits.max_output_bytes.is_none() {
+        return std::future::pending().await;
+    }
+    let mut interval = tokio::time::interval(WATCH_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = sleep_until(deadline) => {
+                return ExecLimitExceeded::Runtime {
+                    secs: limits.max_runtime_secs.unwrap_or_default(),
+                };
+            }
+        }
+        if let Some(bytes) = limits.max_output_bytes
+            && output_bytes.load(Ordering::Relaxed) > bytes
+        {
+            return ExecLimitExceeded::Output { bytes };
+        }
+        if let Some(mb) = limits.max_memory_mb
+            && let Some(pid) = pid
+            && let Some(rss) = session_rss_bytes(pid).await
+            && rss > mb.saturating_mul(1024 * 1024)
+        {
+            return ExecLimitExceeded::Memory { mb };
+        }
+    }
+}
+
+async fn sleep_until(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Resident memory of every process in the session led by `sid`. Commands
+/// are spawned with `setsid`, so this covers everything they start.
+#[cfg(target_os = "linux")]
+async fn session_rss_bytes(sid: u32) -> Option<u64> {
+    tokio::task::spawn_blocking(move || {
+        // SAFETY: sysconf has no preconditions.
+        let page_size = u64::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).ok()?;
+        let pages: u64 = std::fs::read_dir("/proc")
+            .ok()?
+            .flatten()
+            .filter_map(|entry| std::fs::read_to_string(entry.path().join("stat")).ok())
+            .filter_map(|stat| parse_proc_stat(&stat))
+            .filter(|(session, _)| *session == sid)
+            .map(|(_, rss_pages)| rss_pages)
+            .sum();
+        Some(pages.saturating_mul(page_size))
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Resident memory of the process group led by `pgid`, from `ps`.
+#[cfg(target_os = "macos")]
+async fn session_rss_bytes(pgid: u32) -> Option<u64> {
+    let output = tokio::process::Command::new("/bin/ps")
+        .args(["-A", "-o", "pgid=,rss="])
+        .output()
+        .await
+        .ok()?;
+    let kib = sum_ps_rss_kib(&String::from_utf8_lossy(&output.stdout), pgid);
+    Some(kib.saturating_mul(1024))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+async fn session_rss_bytes(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// `(session id, resident pages)` from a `/proc/<pid>/stat` line.
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_stat(stat: &str) -> Option<(u32, u64)> {
+    // The command name is parenthesized and may contain spaces.
+    let fields: Vec<&str> = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .collect();
+    // Fields after the name start at `state` (3rd); `session` is the 6th and
+    // `rss` the 24th.
+    let session = fields.get(3)?.parse().ok()?;
+    let rss_pages = fields.get(21)?.parse().ok()?;
+    Some((session, rss_pages))
+}
+
+/// Sums the `rss` column (KiB) of `ps -o pgid=,rss=` rows in `pgid`.
+#[cfg(any(target_os = "macos", test))]
+fn sum_ps_rss_kib(ps_output: &str, pgid: u32) -> u64 {
+    ps_output
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let group: u32 = columns.next()?.parse().ok()?;
+            let rss: u64 = columns.next()?.parse().ok()?;
+            (group == pgid).then_some(rss)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_session_and_rss_from_proc_stat() {
+        let stat = "4242 (cargo test (x)) S 4200 4242 4242 34817 4242 4194304 1 0 0 0 \
+                    12 3 0 0 20 0 1 0 1000 123456789 2048 18446744073709551615 1 1 0 0";
+        assert_eq!(parse_proc_stat(stat), Some((4242, 2048)));
+        assert_eq!(parse_proc_stat("garbage"), None);
+    }
+
+    #[test]
+    fn sums_ps_rss_for_group() {
+        let output = "  100  2048\n  100  1024\n  200  4096\nbogus line\n";
+        assert_eq!(sum_ps_rss_kib(output, 100), 3072);
+        assert_eq!(sum_ps_rss_kib(output, 300), 0);
+    }
+
+    #[tokio::test]
+    async fn output_limit_trips_watch() {
+        let limits = ExecLimits {
+            max_output_bytes: Some(10),
+            ..Default::default()
+        };
+        let output_bytes = Arc::new(AtomicU64::new(11));
+        assert_eq!(
+            watch(limits, None, output_bytes).await,
+            ExecLimitExceeded::Output { bytes: 10 }
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
@@ -5,6 +6,8 @@ use std::sync::Arc;
 
 use arc_swap::ArcSwap;
 
+use crate::command_rules::CommandRules;
+use crate::command_safety::is_read_only_command::is_read_only_command;
 use crate::config_loader::ConfigLayerStack;
 use crate::config_loader::ConfigLayerStackOrdering;
 use crate::is_dangerous_command::command_might_be_dangerous;
@@ -17,6 +20,9 @@ use codex_execpolicy::Policy;
 use codex_execpolicy::PolicyParser;
 use codex_execpolicy::RuleMatch;
 use codex_execpolicy::blocking_append_allow_prefix_rule;
+use codex_protocol::approvals::ApprovalSource;
+use codex_protocol::approvals::CommandRuleDecision;
+use codex_protocol::approvals::CommandRuleMatch;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::SandboxPolicy;
@@ -85,15 +91,31 @@ pub enum ExecPolicyUpdateError {
 
 pub(crate) struct ExecPolicyManager {
     policy: ArcSwap<Policy>,
+    /// Extra prefixes from the `read_only_commands` config list.
+    read_only_commands: Vec<Vec<String>>,
+    /// `[[command_rules]]` consulted for commands no `.rules` file matches.
+    command_rules: CommandRules,
 }
 
 impl ExecPolicyManager {
     pub(crate) fn new(policy: Arc<Policy>) -> Self {
         Self {
             policy: ArcSwap::from(policy),
+            read_only_commands: Vec::new(),
+            command_rules: CommandRules::default(),
         }
     }
 
+    pub(crate) fn with_read_only_commands(mut self, read_only_commands: Vec<Vec<String>>) -> Self {
+        self.read_only_commands = read_only_commands;
+        self
+    }
+
+    pub(crate) fn with_command_rules(mut self, command_rules: CommandRules) -> Self {
+        self.command_rules = command_rules;
+        self
+    }
+
     pub(crate) async fn load(
         features: &Features,
         config_stack: &ConfigLayerStack,
@@ -103,13 +125,53 @@ impl ExecPolicyManager {
         if let Some(err) = warning.as_ref() {
             tracing::warn!("failed to parse rules: {err}");
         }
-        Ok(Self::new(Arc::new(policy)))
+        let command_rules = CommandRules::load(config_stack).await;
+        Ok(Self::new(Arc::new(policy)).with_command_rules(command_rules))
     }
 
     pub(crate) fn current(&self) -> Arc<Policy> {
         self.policy.load_full()
     }
 
+    /// Whether every command in `command` (including each part of a plain
+    /// `bash -lc` script) only reads the workspace. Used by the
+    /// `allow-read-only` approval timeout action.
+    pub(crate) fn is_read_only(&self, command: &[String]) -> bool {
+        let commands =
+            parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+        commands
+            .iter()
+            .all(|cmd| is_read_only_command(cmd, &self.read_only_commands))
+    }
+
+    /// The most restrictive `[[command_rules]]` entry matching any command in
+    /// `command` (including each part of a plain `bash -lc` script). Attached
+    /// to approval requests so the user can see which rule applied.
+    pub(crate) fn matching_command_rule(
+        &self,
+        command: &[String],
+        cwd: &Path,
+    ) -> Option<CommandRuleMatch> {
+        let commands =
+            parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+        commands
+            .iter()
+            .filter_map(|cmd| self.command_rules.evaluate(cmd, cwd))
+            .chain(self.script_rule(command, cwd))
+            .max_by_key(|rule| rule.decision)
+    }
+
+    /// The strictest rule that applies to `command` as a whole script: a
+    /// `[git_guardrails]` check, or an `auto_deny` program hidden in a script
+    /// that could not be split into plain commands.
+    fn script_rule(&self, command: &[String], cwd: &Path) -> Option<CommandRuleMatch> {
+        self.command_rules
+            .git_guardrail(command, cwd)
+            .into_iter()
+            .chain(self.command_rules.denied_program_in_script(command, cwd))
+            .max_by_key(|rule| rule.decision)
+    }
+
     pub(crate) async fn create_exec_approval_requirement_for_command(
         &self,
         features: &Features,
@@ -117,11 +179,33 @@ impl ExecPolicyManager {
         approval_policy: AskForApproval,
         sandbox_policy: &SandboxPolicy,
         sandbox_permissions: SandboxPermissions,
+        cwd: &Path,
     ) -> ExecApprovalRequirement {
         let exec_policy = self.current();
         let commands =
             parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+        let read_only_prefixes = (features.enabled(Feature::ReadOnlyAutoApprove)
+            && read_only_sandbox_enforced(sandbox_policy))
+        .then_some(self.read_only_commands.as_slice());
+        // One entry per command that no `.rules` prefix rule matched: the
+        // command rule that decided it, if any.
+        let rule_matches = RefCell::new(Vec::new());
         let exec_policy_fallback = |cmd: &[String]| {
+            let rule_match = self.command_rules.evaluate(cmd, cwd);
+            let decision = rule_match.as_ref().map(|rule| match rule.decision {
+                CommandRuleDecision::AutoApprove => Decision::Allow,
+                CommandRuleDecision::AlwaysAsk => Decision::Prompt,
+                CommandRuleDecision::AutoDeny => Decision::Forbidden,
+            });
+            rule_matches.borrow_mut().push(rule_match);
+            if let Some(decision) = decision {
+                return decision;
+            }
+            if let Some(prefixes) = read_only_prefixes
+                && is_read_only_command(cmd, prefixes)
+            {
+                return Decision::Allow;
+            }
             render_decision_for_unmatched_command(
                 approval_policy,
                 sandbox_policy,
@@ -130,10 +214,25 @@ impl ExecPolicyManager {
             )
         };
         let evaluation = exec_policy.check_multiple(commands.iter(), &exec_policy_fallback);
-
-        match evaluation.decision {
+        let rule_matches = rule_matches.into_inner();
+        let strictest_rule = rule_matches
+            .iter()
+            .flatten()
+            .max_by_key(|rule| rule.decision);
+        let asked_by_rule =
+            strictest_rule.is_some_and(|rule| rule.decision == CommandRuleDecision::AlwaysAsk);
+
+        let requirement = match evaluation.decision {
             Decision::Forbidden => ExecApprovalRequirement::Forbidden {
-                reason: derive_forbidden_reason(command, &evaluation),
+                reason: match strictest_rule {
+                    Some(rule)
+                        if rule.decision == CommandRuleDecision::AutoDeny
+                            && !has_policy_decision(&evaluation, Decision::Forbidden) =>
+                    {
+                        command_rule_reason(command, "rejected", rule)
+                    }
+                    _ => derive_forbidden_reason(command, &evaluation),
+                },
             },
             Decision::Prompt => {
                 if matches!(approval_policy, AskForApproval::Never) {
@@ -141,9 +240,18 @@ impl ExecPolicyManager {
                         reason: PROMPT_CONFLICT_REASON.to_string(),
                     }
                 } else {
+                    let reason = derive_prompt_reason(command, &evaluation).or_else(|| {
+                        strictest_rule
+                            .filter(|_| asked_by_rule)
+                            .map(|rule| command_rule_reason(command, "requires approval", rule))
+                    });
                     ExecApprovalRequirement::NeedsApproval {
-                        reason: derive_prompt_reason(command, &evaluation),
-                        proposed_execpolicy_amendment: if features.enabled(Feature::ExecPolicy) {
+                        reason,
+                        // An execpolicy allow rule would not get past an
+                        // `always_ask` rule, so do not offer one.
+                        proposed_execpolicy_amendment: if features.enabled(Feature::ExecPolicy)
+                            && !asked_by_rule
+                        {
                             try_derive_execpolicy_amendment_for_prompt_rules(
                                 &evaluation.matched_rules,
                             )
@@ -158,12 +266,53 @@ impl ExecPolicyManager {
                 bypass_sandbox: evaluation.matched_rules.iter().any(|rule_match| {
                     is_policy_match(rule_match) && rule_match.decision() == Decision::Allow
                 }),
+                source: derive_allow_source(
+                    &commands,
+                    &evaluation,
+                    &rule_matches,
+                    approval_policy,
+                    read_only_prefixes,
+                ),
                 proposed_execpolicy_amendment: if features.enabled(Feature::ExecPolicy) {
                     try_derive_execpolicy_amendment_for_allow_rules(&evaluation.matched_rules)
                 } else {
                     None
                 },
             },
+        };
+        self.apply_script_rules(command, approval_policy, cwd, requirement)
+    }
+
+    /// Refuses or escalates git operations flagged by `[git_guardrails]` and
+    /// scripts that may hide an `auto_deny` program, whatever the sandbox
+    /// policy and any allow rule decided.
+    fn apply_script_rules(
+        &self,
+        command: &[String],
+        approval_policy: AskForApproval,
+        cwd: &Path,
+        requirement: ExecApprovalRequirement,
+    ) -> ExecApprovalRequirement {
+        if matches!(requirement, ExecApprovalRequirement::Forbidden { .. }) {
+            return requirement;
+        }
+        let Some(rule) = self.script_rule(command, cwd) else {
+            return requirement;
+        };
+        match rule.decision {
+            CommandRuleDecision::AutoApprove => requirement,
+            CommandRuleDecision::AutoDeny => ExecApprovalRequirement::Forbidden {
+                reason: command_rule_reason(command, "rejected", &rule),
+            },
+            CommandRuleDecision::AlwaysAsk if approval_policy == AskForApproval::Never => {
+                ExecApprovalRequirement::Forbidden {
+                    reason: command_rule_reason(command, "rejected", &rule),
+                }
+            }
+            CommandRuleDecision::AlwaysAsk => ExecApprovalRequirement::NeedsApproval {
+                reason: Some(command_rule_reason(command, "requires approval", &rule)),
+                proposed_execpolicy_amendment: None,
+            },
         }
     }
 
@@ -260,7 +409,62 @@ pub async fn load_exec_policy(config_stack: &ConfigLayerStack) -> Result<Policy,
     Ok(policy)
 }
 
+/// Explains why an allowed command did not need approval: an explicit allow
+/// rule wins, then `[[command_rules]]` when they approved every command, then
+/// the built-in safe-command list, then the read-only classifier (when
+/// enabled), then the mode default.
+fn derive_allow_source(
+    commands: &[Vec<String>],
+    evaluation: &Evaluation,
+    rule_matches: &[Option<CommandRuleMatch>],
+    approval_policy: AskForApproval,
+    read_only_prefixes: Option<&[Vec<String>]>,
+) -> ApprovalSource {
+    let policy_rule = evaluation
+        .matched_rules
+        .iter()
+        .find_map(|rule_match| match rule_match {
+            RuleMatch::PrefixRuleMatch {
+                matched_prefix,
+                decision: Decision::Allow,
+                justification,
+            } => Some(ApprovalSource::PolicyRule {
+                matched_prefix: matched_prefix.clone(),
+                justification: justification.clone(),
+            }),
+            _ => None,
+        });
+    if let Some(source) = policy_rule {
+        return source;
+    }
+    if let Some(Some(rule)) = rule_matches.first()
+        && rule_matches.len() == commands.len()
+        && rule_matches.iter().all(Option::is_some)
+    {
+        return ApprovalSource::CommandRule { rule: rule.clone() };
+    }
+    if commands.iter().all(|cmd| is_known_safe_command(cmd)) {
+        ApprovalSource::KnownSafeCommand
+    } else if let Some(prefixes) = read_only_prefixes
+        && commands
+            .iter()
+            .all(|cmd| is_read_only_command(cmd, prefixes))
+    {
+        ApprovalSource::ReadOnlyCommand
+    } else {
+        ApprovalSource::ModeDefault { approval_policy }
+    }
+}
+
 /// If a command is not matched by any execpolicy rule, derive a [`Decision`].
+/// Whether the read-only sandbox will confine a command. `cargo check` and
+/// friends still run build scripts and proc macros, so read-only commands only
+/// skip approval when nothing they run can write.
+fn read_only_sandbox_enforced(sandbox_policy: &SandboxPolicy) -> bool {
+    // On Windows, ReadOnly is not a real sandbox.
+    matches!(sandbox_policy, SandboxPolicy::ReadOnly) && !cfg!(windows)
+}
+
 pub fn render_decision_for_unmatched_command(
     approval_policy: AskForApproval,
     sandbox_policy: &SandboxPolicy,
@@ -409,6 +613,30 @@ fn derive_prompt_reason(command_args: &[String], evaluation: &Evaluation) -> Opt
     }
 }
 
+fn has_policy_decision(evaluation: &Evaluation, decision: Decision) -> bool {
+    evaluation
+        .matched_rules
+        .iter()
+        .any(|rule_match| is_policy_match(rule_match) && rule_match.decision() == decision)
+}
+
+/// Reason for a decision made by a `[[command_rules]]` entry, naming the rule
+/// and the file it came from.
+fn command_rule_reason(command_args: &[String], outcome: &str, rule: &CommandRuleMatch) -> String {
+    let command = render_shlex_command(command_args);
+    let source = rule.source.display();
+    match &rule.reason {
+        Some(reason) => format!(
+            "`{command}` {outcome}: command rule `{}` ({source}): {reason}",
+            rule.rule
+        ),
+        None => format!(
+            "`{command}` {outcome} by command rule `{}` ({source})",
+            rule.rule
+        ),
+    }
+}
+
 fn render_shlex_command(args: &[String]) -> String {
     shlex_try_join(args.iter().map(String::as_str)).unwrap_or_else(|_| args.join(" "))
 }
@@ -719,6 +947,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -757,6 +986,7 @@ prefix_rule(
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -786,6 +1016,7 @@ prefix_rule(
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -816,6 +1047,7 @@ prefix_rule(
                 AskForApproval::Never,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -839,6 +1071,7 @@ prefix_rule(
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -873,6 +1106,7 @@ prefix_rule(
                     AskForApproval::UnlessTrusted,
                     &SandboxPolicy::DangerFullAccess,
                     SandboxPermissions::UseDefault,
+                    Path::new("/tmp"),
                 )
                 .await,
             ExecApprovalRequirement::NeedsApproval {
@@ -947,6 +1181,7 @@ prefix_rule(
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -974,6 +1209,7 @@ prefix_rule(
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -1004,6 +1240,7 @@ prefix_rule(
                 AskForApproval::OnRequest,
                 &SandboxPolicy::DangerFullAccess,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -1031,6 +1268,7 @@ prefix_rule(
                 AskForApproval::UnlessTrusted,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -1069,6 +1307,7 @@ prefix_rule(
                     AskForApproval::UnlessTrusted,
                     &SandboxPolicy::ReadOnly,
                     SandboxPermissions::UseDefault,
+                    Path::new("/tmp"),
                 )
                 .await,
             ExecApprovalRequirement::NeedsApproval {
@@ -1092,6 +1331,7 @@ prefix_rule(
                 AskForApproval::OnRequest,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -1099,11 +1339,111 @@ prefix_rule(
             requirement,
             ExecApprovalRequirement::Skip {
                 bypass_sandbox: false,
+                source: ApprovalSource::KnownSafeCommand,
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command)),
             }
         );
     }
 
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn read_only_auto_approve_skips_approval_but_keeps_sandbox() {
+        let mut features = Features::with_defaults();
+        features.enable(Feature::ReadOnlyAutoApprove);
+        let manager = ExecPolicyManager::default()
+            .with_read_only_commands(vec![vec!["just".to_string(), "--list".to_string()]]);
+
+        let mut requirements = Vec::new();
+        for command in ["cargo check --all-targets", "just --list", "cargo build"] {
+            let command = shlex::split(command).expect("valid command");
+            requirements.push(
+                manager
+                    .create_exec_approval_requirement_for_command(
+                        &features,
+                        &command,
+                        AskForApproval::UnlessTrusted,
+                        &SandboxPolicy::ReadOnly,
+                        SandboxPermissions::RequireEscalated,
+                        Path::new("/tmp"),
+                    )
+                    .await,
+            );
+        }
+
+        assert_eq!(
+            requirements,
+            vec![
+                ExecApprovalRequirement::Skip {
+                    bypass_sandbox: false,
+                    source: ApprovalSource::ReadOnlyCommand,
+                    proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
+                        "cargo".to_string(),
+                        "check".to_string(),
+                        "--all-targets".to_string(),
+                    ])),
+                },
+                ExecApprovalRequirement::Skip {
+                    bypass_sandbox: false,
+                    source: ApprovalSource::ReadOnlyCommand,
+                    proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
+                        "just".to_string(),
+                        "--list".to_string(),
+                    ])),
+                },
+                ExecApprovalRequirement::NeedsApproval {
+                    reason: None,
+                    proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
+                        "cargo".to_string(),
+                        "build".to_string(),
+                    ])),
+                },
+            ]
+        );
+        assert!(requirements[0].keeps_sandbox());
+    }
+
+    #[tokio::test]
+    async fn read_only_auto_approve_needs_a_read_only_sandbox() {
+        let mut features = Features::with_defaults();
+        features.enable(Feature::ReadOnlyAutoApprove);
+        let manager = ExecPolicyManager::default()
+            .with_read_only_commands(vec![vec!["just".to_string(), "--list".to_string()]]);
+
+        for sandbox_policy in [
+            SandboxPolicy::new_workspace_write_policy(),
+            SandboxPolicy::DangerFullAccess,
+        ] {
+            for command in ["cargo check --all-targets", "just --list"] {
+                let command = shlex::split(command).expect("valid command");
+                let requirement = manager
+                    .create_exec_approval_requirement_for_command(
+                        &features,
+                        &command,
+                        AskForApproval::UnlessTrusted,
+                        &sandbox_policy,
+                        SandboxPermissions::UseDefault,
+                        Path::new("/tmp"),
+                    )
+                    .await;
+                assert!(
+                    matches!(requirement, ExecApprovalRequirement::NeedsApproval { .. }),
+                    "{command:?} under {sandbox_policy:?}: {requirement:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn is_read_only_checks_every_command_in_a_script() {
+        let manager = ExecPolicyManager::default()
+            .with_read_only_commands(vec![vec!["just".to_string(), "--list".to_string()]]);
+        let bash = |script: &str| vec!["bash".to_string(), "-lc".to_string(), script.to_string()];
+
+        assert!(manager.is_read_only(&bash("ls && just --list")));
+        assert!(!manager.is_read_only(&bash("ls && rm -rf target")));
+        assert!(!manager.is_read_only(&["touch".to_string(), "x".to_string()]));
+    }
+
     #[tokio::test]
     async fn proposed_execpolicy_amendment_is_suppressed_when_policy_matches_allow() {
         let policy_src = r#"prefix_rule(pattern=["echo"], decision="allow")"#;
@@ -1122,6 +1462,7 @@ prefix_rule(
                 AskForApproval::OnRequest,
                 &SandboxPolicy::ReadOnly,
                 SandboxPermissions::UseDefault,
+                Path::new("/tmp"),
             )
             .await;
 
@@ -1129,6 +1470,10 @@ prefix_rule(
             requirement,
             ExecApprovalRequirement::Skip {
                 bypass_sandbox: true,
+                source: ApprovalSource::PolicyRule {
+                    matched_prefix: vec!["echo".to_string()],
+                    justification: None,
+                },
                 proposed_execpolicy_amendment: None,
             }
         );
@@ -1176,6 +1521,9 @@ prefix_rule(
                 "On non-Windows, rely on the read-only sandbox to prevent harm.",
                 ExecApprovalRequirement::Skip {
                     bypass_sandbox: false,
+                    source: ApprovalSource::ModeDefault {
+                        approval_policy: AskForApproval::OnRequest,
+                    },
                     proposed_execpolicy_amendment: expected_amendment.clone(),
                 },
             )
@@ -1189,6 +1537,7 @@ prefix_rule(
                     AskForApproval::OnRequest,
                     &SandboxPolicy::ReadOnly,
                     permissions,
+                    Path::new("/tmp"),
                 )
                 .await,
             "{pwsh_approval_reason}"
@@ -1212,6 +1561,7 @@ prefix_rule(
                     AskForApproval::OnRequest,
                     &SandboxPolicy::ReadOnly,
                     permissions,
+                    Path::new("/tmp"),
                 )
                 .await,
             r#"On all platforms, a forbidden command should require approval
@@ -1231,10 +1581,227 @@ prefix_rule(
                     AskForApproval::Never,
                     &SandboxPolicy::ReadOnly,
                     permissions,
+                    Path::new("/tmp"),
                 )
                 .await,
             r#"On all platforms, a forbidden command should require approval
             (unless AskForApproval::Never is specified)."#
         );
     }
+
+    #[tokio::test]
+    async fn command_rules_decide_commands_without_policy_rules() {
+        let policies: crate::config::types::PoliciesToml = toml::from_str(
+            r#"
+[[command_rules]]
+name = "no force push"
+program = "git"
+args = ["push"]
+any_arg = ["--force"]
+decision = "auto_deny"
+reason = "rewrites shared history"
+
+[[command_rules]]
+program = "npm"
+args = ["publish"]
+decision = "always_ask"
+
+[[command_rules]]
+program = "cargo"
+args = ["build"]
+decision = "auto_approve"
+"#,
+        )
+        .expect("parse policies");
+        let manager = ExecPolicyManager::default().with_command_rules(CommandRules::from_toml(
+            policies.command_rules,
+            Path::new("/repo"),
+            Path::new("/repo/.codex/policies.toml"),
+        ));
+        async fn check(
+            manager: &ExecPolicyManager,
+            command: Vec<String>,
+            approval_policy: AskForApproval,
+        ) -> ExecApprovalRequirement {
+            manager
+                .create_exec_approval_requirement_for_command(
+                    &Features::with_defaults(),
+                    &command,
+                    approval_policy,
+                    &SandboxPolicy::DangerFullAccess,
+                    SandboxPermissions::UseDefault,
+                    Path::new("/repo"),
+                )
+                .await
+        }
+
+        assert_eq!(
+            check(
+                &manager,
+                vec_str(&["bash", "-lc", "git add . && git push --force"]),
+                AskForApproval::OnRequest,
+            )
+            .await,
+            ExecApprovalRequirement::Forbidden {
+                reason: "`bash -lc 'git add . && git push --force'` rejected: command rule `no force push` (/repo/.codex/policies.toml): rewrites shared history".to_string(),
+            }
+        );
+        assert_eq!(
+            check(&manager, vec_str(&["npm", "publish"]), AskForApproval::OnFailure).await,
+            ExecApprovalRequirement::NeedsApproval {
+                reason: Some(
+                    "`npm publish` requires approval by command rule `npm publish` (/repo/.codex/policies.toml)"
+                        .to_string()
+                ),
+                proposed_execpolicy_amendment: None,
+            }
+        );
+        let ExecApprovalRequirement::Skip {
+            bypass_sandbox,
+            source,
+            ..
+        } = check(
+            &manager,
+            vec_str(&["cargo", "build"]),
+            AskForApproval::UnlessTrusted,
+        )
+        .await
+        else {
+            panic!("expected cargo build to be auto-approved");
+        };
+        assert!(!bypass_sandbox);
+        assert_eq!(
+            source,
+            ApprovalSource::CommandRule {
+                rule: CommandRuleMatch {
+                    rule: "cargo build".to_string(),
+                    decision: CommandRuleDecision::AutoApprove,
+                    source: PathBuf::from("/repo/.codex/policies.toml"),
+                    reason: None,
+                },
+            }
+        );
+    }
+    #[tokio::test]
+    async fn git_guardrails_apply_in_every_sandbox_mode() {
+        let section: crate::config::types::GitGuardrailsToml = toml::from_str(
+            r#"
+protected_branches = ["main"]
+history_rewrite = "always_ask"
+"#,
+        )
+        .expect("parse git_guardrails");
+        let manager = ExecPolicyManager::default().with_command_rules(
+            CommandRules::default()
+                .with_git_guardrails(section, Path::new("/repo/.codex/policies.toml")),
+        );
+        async fn check(
+            manager: &ExecPolicyManager,
+            command: &[&str],
+            approval_policy: AskForApproval,
+        ) -> ExecApprovalRequirement {
+            manager
+                .create_exec_approval_requirement_for_command(
+                    &Features::with_defaults(),
+                    &vec_str(command),
+                    approval_policy,
+                    &SandboxPolicy::DangerFullAccess,
+                    SandboxPermissions::UseDefault,
+                    Path::new("/repo"),
+                )
+                .await
+        }
+
+        assert_eq!(
+            check(&manager, &["git", "push", "--force", "origin", "main"], AskForApproval::Never).await,
+            ExecApprovalRequirement::Forbidden {
+                reason: "`git push --force origin main` rejected: command rule `git_guardrails.force_push` (/repo/.codex/policies.toml): `git push` force-pushes to the protected branch `main`".to_string(),
+            }
+        );
+        assert_eq!(
+            check(&manager, &["git", "clean", "-fdx"], AskForApproval::OnRequest).await,
+            ExecApprovalRequirement::NeedsApproval {
+                reason: Some("`git clean -fdx` requires approval: command rule `git_guardrails.clean` (/repo/.codex/policies.toml): `git clean` permanently deletes untracked and ignored files, such as `.env` and local build state".to_string()),
+                proposed_execpolicy_amendment: None,
+            }
+        );
+        assert!(matches!(
+            check(&manager, &["git", "clean", "-fd"], AskForApproval::Never).await,
+            ExecApprovalRequirement::Forbidden { .. }
+        ));
+        assert!(matches!(
+            check(
+                &manager,
+                &["git", "push", "origin", "feature"],
+                AskForApproval::Never
+            )
+            .await,
+            ExecApprovalRequirement::Skip { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn unparsed_scripts_mentioning_denied_programs_need_approval() {
+        let policies: crate::config::types::PoliciesToml = toml::from_str(
+            r#"
+[[command_rules]]
+name = "no terraform"
+program = "terraform"
+decision = "auto_deny"
+"#,
+        )
+        .expect("parse policies");
+        let manager = ExecPolicyManager::default().with_command_rules(CommandRules::from_toml(
+            policies.command_rules,
+            Path::new("/repo"),
+            Path::new("/repo/.codex/policies.toml"),
+        ));
+        async fn check(
+            manager: &ExecPolicyManager,
+            script: &str,
+            approval_policy: AskForApproval,
+        ) -> ExecApprovalRequirement {
+            manager
+                .create_exec_approval_requirement_for_command(
+                    &Features::with_defaults(),
+                    &vec_str(&["bash", "-lc", script]),
+                    approval_policy,
+                    &SandboxPolicy::DangerFullAccess,
+                    SandboxPermissions::UseDefault,
+                    Path::new("/repo"),
+                )
+                .await
+        }
+        let script = "for d in a b; do terraform destroy; done";
+
+        assert_eq!(
+            check(&manager, script, AskForApproval::OnRequest).await,
+            ExecApprovalRequirement::NeedsApproval {
+                reason: Some(
+                    "`bash -lc 'for d in a b; do terraform destroy; done'` requires approval: command rule `no terraform` (/repo/.codex/policies.toml): the script could not be checked command by command and mentions `terraform`, which this rule rejects"
+                        .to_string()
+                ),
+                proposed_execpolicy_amendment: None,
+            }
+        );
+        assert!(matches!(
+            check(&manager, script, AskForApproval::Never).await,
+            ExecApprovalRequirement::Forbidden { .. }
+        ));
+        assert_eq!(
+            manager
+                .matching_command_rule(&vec_str(&["bash", "-lc", script]), Path::new("/repo"))
+                .map(|rule| rule.decision),
+            Some(CommandRuleDecision::AlwaysAsk)
+        );
+        assert!(matches!(
+            check(
+                &manager,
+                "for d in a b; do echo $d; done",
+                AskForApproval::Never
+            )
+            .await,
+            ExecApprovalRequirement::Skip { .. }
+        ));
+    }
 }
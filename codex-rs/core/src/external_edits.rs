@@ -0,0 +1,340 @@
+//! Tells the model about workspace files edited outside the agent.
+//!
+//! With the `external_edits` feature enabled, the session watches the turn's
+//! working directory. Changes seen while no tool call is running (and not
+//! right after one finished) are attributed to someone else, typically the
+//! user's editor. Before each sampling request the pending changes are
+//! recorded in history as an `<external_file_changes>` message, so the model
+//! re-reads those files instead of overwriting them, and reported to clients
+//! as an `ExternalFilesChanged` event.
+//!
+//! Writes by background processes the agent left running are indistinguishable
+//! from user edits and are reported as well.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ExternalFileChange;
+use codex_protocol::protocol::ExternalFileChangeKind;
+use codex_protocol::protocol::ExternalFilesChangedEvent;
+use notify::EventKind;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::session_prefix::EXTERNAL_FILE_CHANGES_OPEN_TAG;
+use crate::workspace_files::SKIPPED_DIRS;
+
+/// File events can arrive a little after the tool call that caused them
+/// returns; changes within this window are still attributed to the agent.
+const AGENT_QUIET_PERIOD: Duration = Duration::from_secs(1);
+
+/// At most this many paths are listed in the message to the model.
+const MAX_LISTED_CHANGES: usize = 50;
+
+#[derive(Default)]
+pub(crate) struct ExternalEdits {
+    state: Arc<Mutex<WatchState>>,
+    watcher: Mutex<Option<(PathBuf, RecommendedWatcher)>>,
+}
+
+#[derive(Default)]
+struct WatchState {
+    /// Absolute paths changed since the last report, mapped to whether the
+    /// first event seen for the path created it.
+    changes: BTreeMap<PathBuf, bool>,
+    running_tool_calls: usize,
+    quiet_until: Option<Instant>,
+}
+
+impl WatchState {
+    fn agent_active(&self) -> bool {
+        self.running_tool_calls > 0
+            || self
+                .quiet_until
+                .is_some_and(|quiet_until| Instant::now() < quiet_until)
+    }
+}
+
+/// Marks a tool call as running; file changes seen meanwhile are the agent's.
+pub(crate) struct AgentActivity {
+    state: Arc<Mutex<WatchState>>,
+}
+
+impl Drop for AgentActivity {
+    fn drop(&mut self) {
+        let mut state = lock(&self.state);
+        state.running_tool_calls = state.running_tool_calls.saturating_sub(1);
+        state.quiet_until = Some(Instant::now() + AGENT_QUIET_PERIOD);
+    }
+}
+
+impl ExternalEdits {
+    /// Watches `root` recursively, replacing any previous watch on another
+    /// directory. Changes already recorded are kept.
+    pub(crate) fn watch(&self, root: &Path) {
+        let mut watcher_slot = match self.watcher.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        if watcher_slot
+            .as_ref()
+            .is_some_and(|(watched, _)| watched == root)
+        {
+            return;
+        }
+
+        let state = Arc::clone(&self.state);
+        let watched_root = root.to_path_buf();
+        let mut watcher =
+            match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                if let Ok(event) = res {
+                    record_event(&state, &watched_root, &event);
+                }
+            }) {
+                Ok(watcher) => watcher,
+                Err(err) => {
+                    warn!("failed to create external edits watcher: {err:#}");
+                    return;
+                }
+            };
+        if let Err(err) = watcher.watch(root, RecursiveMode::Recursive) {
+            warn!(
+                "failed to watch {} for external edits: {err:#}",
+                root.display()
+            );
+            return;
+        }
+        *watcher_slot = Some((root.to_path_buf(), watcher));
+    }
+
+    pub(crate) fn agent_activity(&self) -> AgentActivity {
+        lock(&self.state).running_tool_calls += 1;
+        AgentActivity {
+            state: Arc::clone(&self.state),
+        }
+    }
+
+    /// Drains the recorded changes, with paths relative to `cwd` where possible.
+    fn take_changes(&self, cwd: &Path) -> Vec<ExternalFileChange> {
+        let changes = std::mem::take(&mut lock(&self.state).changes);
+        changes
+            .into_iter()
+            .filter(|(path, _)| !path.is_dir())
+            .filter_map(|(path, created)| {
+                let kind = change_kind(path.exists(), created)?;
+                let path = path
+                    .strip_prefix(cwd)
+                    .map(Path::to_path_buf)
+                    .unwrap_or(path);
+                Some(ExternalFileChange { path, kind })
+            })
+            .collect()
+    }
+}
+
+/// Records the changes made outside the agent since the previous request
+/// and tells the model and clients about them.
+pub(crate) async fn record_external_changes(sess: &Session, turn_context: &TurnContext) {
+    let Some(external_edits) = sess.services.external_edits.as_ref() else {
+        return;
+    };
+    external_edits.watch(&turn_context.cwd);
+    let mut changes = external_edits.take_changes(&turn_context.cwd);
+    if changes.is_empty() {
+        return;
+    }
+    let ignored = git_ignored(&turn_context.cwd, &changes).await;
+    changes.retain(|change| !ignored.contains(&change.path));
+    if changes.is_empty() {
+        return;
+    }
+
+    let item = ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: format_notice(&changes),
+        }],
+        end_turn: None,
+    };
+    sess.record_conversation_items(turn_context, &[item]).await;
+    sess.send_event(
+        turn_context,
+        EventMsg::ExternalFilesChanged(ExternalFilesChangedEvent { changes }),
+    )
+    .await;
+}
+
+fn record_event(state: &Mutex<WatchState>, root: &Path, event: &notify::Event) {
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return;
+    }
+    let mut state = lock(state);
+    if state.agent_active() {
+        return;
+    }
+    let created = matches!(event.kind, EventKind::Create(_));
+    for path in &event.paths {
+        let Ok(rel) = path.strip_prefix(root) else {
+            continue;
+        };
+        if is_relevant_path(rel) {
+            state.changes.entry(path.clone()).or_insert(created);
+        }
+    }
+}
+
+/// Skips directories (hidden and build output) and editor scratch files that
+/// never hold user edits worth reporting.
+fn is_relevant_path(rel: &Path) -> bool {
+    let mut components = rel.components().peekable();
+    while let Some(component) = components.next() {
+        let name = component.as_os_str().to_string_lossy();
+        if components.peek().is_some() {
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()) {
+                return false;
+            }
+        } else if name.is_empty()
+            || name.ends_with('~')
+            || name.starts_with(".#")
+            || name == "4913"
+            || [".swp", ".swo", ".swx"]
+                .iter()
+                .any(|suffix| name.ends_with(suffix))
+        {
+            return false;
+        }
+    }
+    true
+}
+
+/// A file that was created and removed again between two requests is not
+/// reported at all.
+fn change_kind(exists: bool, created: bool) -> Option<ExternalFileChangeKind> {
+    match (exists, created) {
+        (true, true) => Some(ExternalFileChangeKind::Created),
+        (true, false) => Some(ExternalFileChangeKind::Modified),
+        (false, true) => None,
+        (false, false) => Some(ExternalFileChangeKind::Deleted),
+    }
+}
+
+/// Paths among `changes` that git ignores in `cwd`; empty outside a repository.
+async fn git_ignored(cwd: &Path, changes: &[ExternalFileChange]) -> Vec<PathBuf> {
+    let output = tokio::process::Command::new("git")
+        .arg("check-ignore")
+        .arg("--")
+        .args(changes.iter().map(|change| change.path.as_os_str()))
+        .current_dir(cwd)
+        .output()
+        .await;
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(PathBuf::from)
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn format_notice(changes: &[ExternalFileChange]) -> String {
+    let mut lines = vec![
+        EXTERNAL_FILE_CHANGES_OPEN_TAG.to_string(),
+        "These files were changed outside your tool calls (most likely by the user) since your \
+         last request. Re-read them before editing and keep their changes."
+            .to_string(),
+    ];
+    for change in changes.iter().take(MAX_LISTED_CHANGES) {
+        let kind = match change.kind {
+            ExternalFileChangeKind::Created => "created",
+            ExternalFileChangeKind::Modified => "modified",
+            ExternalFileChangeKind::Deleted => "deleted",
+        };
+        lines.push(format!("- {kind}: {}", change.path.display()));
+    }
+    if changes.len() > MAX_LISTED_CHANGES {
+        lines.push(format!(
+            "- … and {} more",
+            changes.len() - MAX_LISTED_CHANGES
+        ));
+    }
+    lines.push("</external_file_changes>".to_string());
+    lines.join("\n")
+}
+
+fn lock(state: &Mutex<WatchState>) -> std::sync::MutexGuard<'_, WatchState> {
+    match state.lock() {
+        Ok(guard) => guard,
+        Err(err) => err.into_inner(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn skips_hidden_and_build_dirs_and_editor_scratch_files() {
+        assert!(is_relevant_path(Path::new("src/lib.rs")));
+        assert!(is_relevant_path(Path::new(".env")));
+        assert!(!is_relevant_path(Path::new(".git/index")));
+        assert!(!is_relevant_path(Path::new("target/debug/app")));
+        assert!(!is_relevant_path(Path::new("src/.lib.rs.swp")));
+        assert!(!is_relevant_path(Path::new("src/lib.rs~")));
+    }
+
+    #[test]
+    fn changes_are_dropped_while_a_tool_call_runs() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "hi").expect("write");
+        let edits = ExternalEdits::default();
+        let event = notify::Event::new(EventKind::Modify(notify::event::ModifyKind::Any))
+            .add_path(file.clone());
+
+        {
+            let _activity = edits.agent_activity();
+            record_event(&edits.state, dir.path(), &event);
+        }
+        assert_eq!(edits.take_changes(dir.path()), Vec::new());
+
+        lock(&edits.state).quiet_until = None;
+        record_event(&edits.state, dir.path(), &event);
+        assert_eq!(
+            edits.take_changes(dir.path()),
+            vec![ExternalFileChange {
+                path: PathBuf::from("notes.txt"),
+                kind: ExternalFileChangeKind::Modified,
+            }]
+        );
+    }
+
+    #[test]
+    fn files_created_and_removed_between_requests_are_not_reported() {
+        assert_eq!(change_kind(false, true), None);
+        assert_eq!(
+            change_kind(false, false),
+            Some(ExternalFileChangeKind::Deleted)
+        );
+        assert_eq!(
+            change_kind(true, true),
+            Some(ExternalFileChangeKind::Created)
+        );
+    }
+}
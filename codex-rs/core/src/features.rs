@@ -16,6 +16,7 @@ use std::collections::BTreeSet;
 
 mod legacy;
 pub(crate) use legacy::LegacyFeatureToggles;
+pub(crate) use legacy::legacy_feature_for_key;
 pub(crate) use legacy::legacy_feature_keys;
 
 /// High-level lifecycle stage for a feature.
@@ -107,6 +108,73 @@ pub enum Feature {
     CollaborationModes,
     /// Use the Responses API WebSocket transport for OpenAI by default.
     ResponsesWebsockets,
+    /// Detect project toolchains and describe them in the environment context.
+    ProjectToolchain,
+    /// Run commands classified as read-only without asking, even when they
+    /// request escalated permissions or the approval policy is `untrusted`.
+    ReadOnlyAutoApprove,
+    /// Route agent-run commands through a local proxy and report their
+    /// outbound connections at the end of each turn.
+    NetworkCapture,
+    /// Expose the `blame_context` tool that summarizes the commits behind a
+    /// file region.
+    BlameContext,
+    /// Expose the `memory` tool and load `.codex/memory.md` into new sessions.
+    ProjectMemory,
+    /// Attach a plain-language explanation to command approval requests.
+    ApprovalExplanations,
+    /// Expose the `semantic_search` tool backed by a local index in
+    /// `.codex/index/`.
+    SemanticSearch,
+    /// Expose the tree-sitter backed `code_outline` and `find_symbol` tools.
+    CodeNavigation,
+    /// Expose the `scan_todos` tool that lists TODO/FIXME comments with blame.
+    ScanTodos,
+    /// Fall back to a three-way merge against the turn's ghost snapshot when
+    /// an `apply_patch` no longer matches its file.
+    PatchMerge,
+    /// Preview `apply_patch` calls against the working tree in the approval
+    /// dialog and refuse to apply them if their files change before approval.
+    PatchPreview,
+    /// Size tool-output truncation from the context window left in the turn
+    /// and the number of tool calls still waiting for output.
+    AdaptiveTruncation,
+    /// Watch the workspace and tell the model about files edited outside the
+    /// agent before its next request.
+    ExternalEdits,
+    /// Record the workspace's git state after each turn and brief the model
+    /// on what changed when the session is resumed.
+    ResumeBriefing,
+    /// Require approval for clearly destructive commands, such as mass
+    /// deletion or force pushes to protected branches.
+    DestructiveGuardrail,
+    /// Expose the `shell_session` tool that keeps a shell open across calls.
+    ShellSession,
+    /// Expose the `background_process` tool for dev servers and other
+    /// long-running processes.
+    BackgroundProcesses,
+    /// Replace the output of failed test runs with a digest of the failures.
+    TestFailureDigest,
+    /// Expose the `web_fetch` tool for downloading web pages.
+    WebFetch,
+    /// Send full schemas only for MCP tools relevant to the prompt; the rest
+    /// are listed by name and described on demand via `describe_tool`.
+    ToolTrimming,
+    /// Let the agent propose follow-up tasks at the end of a turn via
+    /// `suggest_follow_ups`.
+    FollowUps,
+    /// Once a plan is approved, require approval before `apply_patch` touches
+    /// files outside the plan's `affected_files`.
+    PlanScope,
+    /// Load `.codex/glossary.md` and `.codex/glossary.toml` into new sessions
+    /// and expose the `define` tool.
+    ProjectGlossary,
+    /// Record approvals, queued inputs, and in-flight commands in the rollout
+    /// and restore them when the thread is resumed.
+    ResumeRuntimeState,
+    /// Load tools declared in `.codex/plugins/` and `$CODEX_HOME/plugins/`
+    /// when a session starts.
+    PluginTools,
 }
 
 impl Feature {
@@ -461,4 +529,158 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Beta,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::ProjectToolchain,
+        key: "project_toolchain",
+        stage: Stage::Stable,
+        default_enabled: true,
+    },
+    FeatureSpec {
+        id: Feature::ReadOnlyAutoApprove,
+        key: "read_only_auto_approve",
+        stage: Stage::Experimental {
+            name: "Auto-approve read-only commands",
+            menu_description: "Run read-only commands (git status/log/diff, ls, cat, rg, cargo check, ...) without asking for approval when the sandbox is read-only. They still run inside the sandbox; anything that can write still asks.",
+            announcement: "NEW! Skip approvals for read-only commands. Enable in /experimental!",
+        },
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::NetworkCapture,
+        key: "network_capture",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::BlameContext,
+        key: "blame_context",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ProjectMemory,
+        key: "project_memory",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ApprovalExplanations,
+        key: "approval_explanations",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::SemanticSearch,
+        key: "semantic_search",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::CodeNavigation,
+        key: "code_navigation",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ScanTodos,
+        key: "scan_todos",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::PatchMerge,
+        key: "patch_merge",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::PatchPreview,
+        key: "patch_preview",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::AdaptiveTruncation,
+        key: "adaptive_truncation",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ExternalEdits,
+        key: "external_edits",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ResumeBriefing,
+        key: "resume_briefing",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::DestructiveGuardrail,
+        key: "destructive_guardrail",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ShellSession,
+        key: "shell_session",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::BackgroundProcesses,
+        key: "background_processes",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::TestFailureDigest,
+        key: "test_failure_digest",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::WebFetch,
+        key: "web_fetch",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ToolTrimming,
+        key: "tool_trimming",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::FollowUps,
+        key: "follow_ups",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::PlanScope,
+        key: "plan_scope",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ProjectGlossary,
+        key: "project_glossary",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ResumeRuntimeState,
+        key: "resume_runtime_state",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::PluginTools,
+        key: "plugin_tools",
+        stage: Stage::Beta,
+        default_enabled: false,
+    },
 ];
@@ -36,13 +36,17 @@ pub(crate) fn legacy_feature_keys() -> impl Iterator<Item = &'static str> {
 }
 
 pub(crate) fn feature_for_key(key: &str) -> Option<Feature> {
+    let feature = legacy_feature_for_key(key)?;
+    log_alias(key, feature);
+    Some(feature)
+}
+
+/// Like [`feature_for_key`], without logging the alias.
+pub(crate) fn legacy_feature_for_key(key: &str) -> Option<Feature> {
     ALIASES
         .iter()
         .find(|alias| alias.legacy_key == key)
-        .map(|alias| {
-            log_alias(alias.legacy_key, alias.feature);
-            alias.feature
-        })
+        .map(|alias| alias.feature)
 }
 
 #[derive(Debug, Default)]
@@ -0,0 +1,511 @@
+//! Git-aware command rules (`[git_guardrails]`).
+//!
+//! A `[git_guardrails]` section in config.toml or a `policies.toml` (usually
+//! `<repo>/.codex/policies.toml`) turns on checks for three kinds of git
+//! operations: force pushes to protected branches, history rewrites on
+//! protected branches, and `git clean` runs that delete untracked files. Each
+//! kind has a [`CommandRuleDecision`]; `auto_deny` refuses the command and
+//! `always_ask` needs the user's approval. The exec policy applies the result
+//! after everything else, so it holds in every sandbox mode and cannot be
+//! approved away by a `.rules` allow rule. As with `[[command_rules]]`, when
+//! several layers set a decision the most restrictive one wins, and protected
+//! branches from every layer apply together.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::CommandRuleDecision;
+use codex_protocol::protocol::CommandRuleMatch;
+
+use crate::config::types::GitGuardrailsToml;
+use crate::git_info::checked_out_branch;
+use crate::guardrail::program_name;
+use crate::guardrail::simple_commands;
+
+const DEFAULT_PROTECTED_BRANCHES: [&str; 2] = ["main", "master"];
+
+/// Kinds of git operations the guardrails check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GitOperation {
+    ForcePush,
+    HistoryRewrite,
+    Clean,
+}
+
+impl GitOperation {
+    fn label(self) -> &'static str {
+        match self {
+            GitOperation::ForcePush => "git_guardrails.force_push",
+            GitOperation::HistoryRewrite => "git_guardrails.history_rewrite",
+            GitOperation::Clean => "git_guardrails.clean",
+        }
+    }
+
+    fn default_decision(self) -> CommandRuleDecision {
+        match self {
+            GitOperation::ForcePush => CommandRuleDecision::AutoDeny,
+            GitOperation::HistoryRewrite | GitOperation::Clean => CommandRuleDecision::AlwaysAsk,
+        }
+    }
+}
+
+/// `[git_guardrails]` sections of every config layer, merged.
+#[derive(Debug, Default)]
+pub(crate) struct GitGuardrails {
+    protected_branches: Vec<String>,
+    force_push: Option<(CommandRuleDecision, PathBuf)>,
+    history_rewrite: Option<(CommandRuleDecision, PathBuf)>,
+    clean: Option<(CommandRuleDecision, PathBuf)>,
+    /// The last file with a `[git_guardrails]` section; the guardrails are
+    /// off while this is `None`.
+    source: Option<PathBuf>,
+}
+
+impl GitGuardrails {
+    /// Adds the section from `source`. Call in order of increasing precedence.
+    pub(crate) fn extend(&mut self, toml: GitGuardrailsToml, source: &Path) {
+        for branch in toml.protected_branches.unwrap_or_default() {
+            if !self.protected_branches.contains(&branch) {
+                self.protected_branches.push(branch);
+            }
+        }
+        for (setting, decision) in [
+            (&mut self.force_push, toml.force_push),
+            (&mut self.history_rewrite, toml.history_rewrite),
+            (&mut self.clean, toml.clean),
+        ] {
+            if let Some(decision) = decision
+                && setting
+                    .as_ref()
+                    .is_none_or(|(current, _)| decision >= *current)
+            {
+                *setting = Some((decision, source.to_path_buf()));
+            }
+        }
+        self.source = Some(source.to_path_buf());
+    }
+
+    /// The strictest guardrail that `command` (including each part of a
+    /// `bash -lc` script) trips when run in `cwd`.
+    pub(crate) fn evaluate(&self, command: &[String], cwd: &Path) -> Option<CommandRuleMatch> {
+        let source = self.source.as_ref()?;
+        let git_commands: Vec<Vec<String>> = simple_commands(command)
+            .into_iter()
+            .filter(|words| {
+                words
+                    .first()
+                    .is_some_and(|program| program_name(program) == "git")
+            })
+            .collect();
+        if git_commands.is_empty() {
+            return None;
+        }
+        let protected_branches = if self.protected_branches.is_empty() {
+            DEFAULT_PROTECTED_BRANCHES.map(str::to_string).to_vec()
+        } else {
+            self.protected_branches.clone()
+        };
+        let current_branch = checked_out_branch(cwd);
+        git_commands
+            .iter()
+            .filter_map(|words| {
+                flag_git_operation(&words[1..], &protected_branches, current_branch.as_deref())
+            })
+            .filter_map(|(operation, explanation)| {
+                let (decision, source) = match self.setting(operation) {
+                    Some((decision, source)) => (*decision, source),
+                    None => (operation.default_decision(), source),
+                };
+                (decision != CommandRuleDecision::AutoApprove).then(|| CommandRuleMatch {
+                    rule: operation.label().to_string(),
+                    decision,
+                    source: source.clone(),
+                    reason: Some(explanation),
+                })
+            })
+            .max_by_key(|rule| rule.decision)
+    }
+
+    fn setting(&self, operation: GitOperation) -> Option<&(CommandRuleDecision, PathBuf)> {
+        match operation {
+            GitOperation::ForcePush => self.force_push.as_ref(),
+            GitOperation::HistoryRewrite => self.history_rewrite.as_ref(),
+            GitOperation::Clean => self.clean.as_ref(),
+        }
+    }
+}
+
+/// Classifies the arguments of a `git` invocation and explains what it
+/// would do. `current_branch` is the branch checked out in the working
+/// directory.
+fn flag_git_operation(
+    args: &[String],
+    protected_branches: &[String],
+    current_branch: Option<&str>,
+) -> Option<(GitOperation, String)> {
+    let (subcommand, args) = git_subcommand(args)?;
+    let protected_current =
+        current_branch.filter(|branch| is_protected(branch, protected_branches));
+    let rewrite = |explanation: String| Some((GitOperation::HistoryRewrite, explanation));
+    match subcommand.as_str() {
+        "push" => flag_push(args, protected_branches, current_branch)
+            .map(|explanation| (GitOperation::ForcePush, explanation)),
+        "clean" => flag_clean(args).map(|explanation| (GitOperation::Clean, explanation)),
+        "filter-branch" | "filter-repo" => rewrite(format!(
+            "`git {subcommand}` rewrites the history of the repository"
+        )),
+        "commit" if args.iter().any(|arg| arg == "--amend") => {
+            let branch = protected_current?;
+            rewrite(format!(
+                "`git commit --amend` rewrites the last commit of the protected branch `{branch}`"
+            ))
+        }
+        "rebase" => {
+            if args.iter().any(|arg| {
+                matches!(
+                    arg.as_str(),
+                    "--abort" | "--quit" | "--continue" | "--skip" | "--edit-todo"
+                )
+            }) {
+                return None;
+            }
+            // `git rebase <upstream> <branch>` checks out `<branch>` first.
+            let positional: Vec<&String> =
+                args.iter().filter(|arg| !arg.starts_with('-')).collect();
+            let branch = match positional.get(1) {
+                Some(branch) => Some(branch.as_str()),
+                None => current_branch,
+            }
+            .filter(|branch| is_protected(branch, protected_branches))?;
+            rewrite(format!(
+                "`git rebase` rewrites the protected branch `{branch}`"
+            ))
+        }
+        "reset" => {
+            let branch = protected_current?;
+            let moves_head = args
+                .iter()
+                .take_while(|arg| *arg != "--")
+                .filter(|arg| !arg.starts_with('-'))
+                .any(|arg| looks_like_revision(arg));
+            moves_head.then(|| {
+                (
+                    GitOperation::HistoryRewrite,
+                    format!("`git reset` moves the protected branch `{branch}` to another commit"),
+                )
+            })
+        }
+        "branch" => {
+            let deletes = args
+                .iter()
+                .any(|arg| matches!(arg.as_str(), "-D" | "-d" | "--delete"));
+            let moves = args
+                .iter()
+                .any(|arg| matches!(arg.as_str(), "-f" | "--force" | "-M" | "-m" | "--move"));
+            if !deletes && !moves {
+                return None;
+            }
+            let branch = args
+                .iter()
+                .filter(|arg| !arg.starts_with('-'))
+                .find(|arg| is_protected(arg, protected_branches))?;
+            let action = if deletes { "deletes" } else { "moves" };
+            rewrite(format!(
+                "`git branch` {action} the protected branch `{branch}`"
+            ))
+        }
+        "checkout" | "switch" => {
+            let branch = args
+                .iter()
+                .skip_while(|arg| !matches!(arg.as_str(), "-B" | "-C" | "--force-create"))
+                .nth(1)
+                .filter(|branch| is_protected(branch, protected_branches))?;
+            rewrite(format!(
+                "`git {subcommand}` resets the protected branch `{branch}`"
+            ))
+        }
+        "update-ref" => {
+            let branch = args
+                .iter()
+                .filter(|arg| !arg.starts_with('-'))
+                .find_map(|arg| arg.strip_prefix("refs/heads/"))
+                .filter(|branch| is_protected(branch, protected_branches))?;
+            rewrite(format!(
+                "`git update-ref` moves the protected branch `{branch}`"
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Splits `git` arguments into the subcommand and its arguments, skipping
+/// global options such as `-C <dir>` or `-c key=value`.
+pub(crate) fn git_subcommand(args: &[String]) -> Option<(&String, &[String])> {
+    let mut rest = args;
+    while let Some(first) = rest.first() {
+        match first.as_str() {
+            "-C" | "-c" | "--git-dir" | "--work-tree" => rest = rest.get(2..).unwrap_or(&[]),
+            option if option.starts_with('-') => rest = &rest[1..],
+            _ => break,
+        }
+    }
+    rest.split_first()
+}
+
+/// Explains a `git push` (given the arguments after `push`) that
+/// force-pushes to or deletes a protected branch, or mirrors the repository.
+/// Without a refspec the push goes to `current_branch`.
+pub(crate) fn flag_push(
+    args: &[String],
+    protected_branches: &[String],
+    current_branch: Option<&str>,
+) -> Option<String> {
+    let mut force = false;
+    let mut delete = false;
+    let mut positional = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--mirror" => {
+                return Some(
+                    "`git push --mirror` overwrites every branch on the remote".to_string(),
+                );
+            }
+            "--force" | "--delete" => {
+                force |= arg == "--force";
+                delete |= arg == "--delete";
+            }
+            option
+                if option.starts_with("--force-with-lease") || option == "--force-if-includes" =>
+            {
+                force = true;
+            }
+            option if option.starts_with("--") => {}
+            option if option.starts_with('-') => {
+                force |= option.contains('f');
+                delete |= option.contains('d');
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+
+    // The first positional argument is the remote; the rest are refspecs.
+    let refspecs = positional.get(1..).unwrap_or(&[]);
+    let mut targets: Vec<(String, bool, bool)> = refspecs
+        .iter()
+        .map(|refspec| {
+            let plus = refspec.starts_with('+');
+            let refspec = refspec.trim_start_matches('+');
+            let (src, dst) = refspec.split_once(':').unwrap_or((refspec, refspec));
+            let dst = dst.trim_start_matches("refs/heads/").to_string();
+            (dst, force || plus, delete || src.is_empty())
+        })
+        .collect();
+    if refspecs.is_empty()
+        && let Some(branch) = current_branch
+    {
+        targets.push((branch.to_string(), force, delete));
+    }
+
+    targets.into_iter().find_map(|(branch, force, delete)| {
+        if !is_protected(&branch, protected_branches) {
+            return None;
+        }
+        if delete {
+            Some(format!(
+                "`git push` deletes the protected branch `{branch}`"
+            ))
+        } else if force {
+            Some(format!(
+                "`git push` force-pushes to the protected branch `{branch}`"
+            ))
+        } else {
+            None
+        }
+    })
+}
+
+/// Explains a `git clean` that deletes files rather than listing them.
+fn flag_clean(args: &[String]) -> Option<String> {
+    let mut force = false;
+    let mut dry_run = false;
+    let mut ignored = false;
+    for arg in args.iter().take_while(|arg| *arg != "--") {
+        match arg.as_str() {
+            "--force" => force = true,
+            "--dry-run" => dry_run = true,
+            option if option.starts_with("--") => {}
+            option if option.starts_with('-') => {
+                force |= option.contains('f');
+                dry_run |= option.contains('n');
+                ignored |= option.contains('x') || option.contains('X');
+            }
+            _ => {}
+        }
+    }
+    if !force || dry_run {
+        return None;
+    }
+    Some(if ignored {
+        "`git clean` permanently deletes untracked and ignored files, such as `.env` and local build state".to_string()
+    } else {
+        "`git clean` permanently deletes untracked files".to_string()
+    })
+}
+
+/// Whether a `git reset` argument names a commit rather than a path.
+fn looks_like_revision(arg: &str) -> bool {
+    arg != "HEAD"
+        && (arg.starts_with("HEAD")
+            || arg.contains('~')
+            || arg.contains('^')
+            || arg.contains("@{")
+            || matches!(arg, "ORIG_HEAD" | "FETCH_HEAD" | "@")
+            || arg.starts_with("origin/")
+            || (arg.len() >= 7 && arg.chars().all(|ch| ch.is_ascii_hexdigit())))
+}
+
+/// Matches exact names and patterns ending in `*`, such as `release/*`.
+fn is_protected(branch: &str, protected_branches: &[String]) -> bool {
+    protected_branches
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => branch.starts_with(prefix),
+            None => branch == pattern,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn words(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    fn flag(items: &[&str], current_branch: &str) -> Option<(GitOperation, String)> {
+        flag_git_operation(
+            &words(items),
+            &words(&["main", "release/*"]),
+            Some(current_branch),
+        )
+    }
+
+    #[test]
+    fn flags_history_rewrites_on_protected_branches() {
+        assert_eq!(
+            flag(&["commit", "--amend", "--no-edit"], "main").map(|(op, _)| op),
+            Some(GitOperation::HistoryRewrite)
+        );
+        assert_eq!(flag(&["commit", "--amend"], "feature"), None);
+        assert_eq!(
+            flag(&["reset", "--hard", "HEAD~3"], "release/2.0"),
+            Some((
+                GitOperation::HistoryRewrite,
+                "`git reset` moves the protected branch `release/2.0` to another commit"
+                    .to_string()
+            ))
+        );
+        assert_eq!(flag(&["reset", "HEAD", "src/lib.rs"], "main"), None);
+        assert_eq!(flag(&["reset", "--hard"], "main"), None);
+        assert_eq!(
+            flag(&["rebase", "-i", "origin/main"], "main").map(|(op, _)| op),
+            Some(GitOperation::HistoryRewrite)
+        );
+        assert_eq!(flag(&["rebase", "--continue"], "main"), None);
+        assert_eq!(flag(&["rebase", "main"], "feature"), None);
+        assert_eq!(
+            flag(&["branch", "-D", "main"], "feature"),
+            Some((
+                GitOperation::HistoryRewrite,
+                "`git branch` deletes the protected branch `main`".to_string()
+            ))
+        );
+        assert_eq!(
+            flag(&["switch", "-C", "main", "origin/main"], "feature").map(|(op, _)| op),
+            Some(GitOperation::HistoryRewrite)
+        );
+        assert_eq!(
+            flag(&["filter-repo", "--path", "secrets"], "feature").map(|(op, _)| op),
+            Some(GitOperation::HistoryRewrite)
+        );
+    }
+
+    #[test]
+    fn flags_force_pushes_and_cleans() {
+        assert_eq!(
+            flag(&["-C", "repo", "push", "-f"], "main"),
+            Some((
+                GitOperation::ForcePush,
+                "`git push` force-pushes to the protected branch `main`".to_string()
+            ))
+        );
+        assert_eq!(
+            flag(&["push", "--force", "origin", "feature"], "main"),
+            None
+        );
+        assert_eq!(
+            flag(&["clean", "-fdx"], "feature"),
+            Some((
+                GitOperation::Clean,
+                "`git clean` permanently deletes untracked and ignored files, such as `.env` and local build state".to_string()
+            ))
+        );
+        assert_eq!(flag(&["clean", "-ndx"], "feature"), None);
+    }
+
+    #[test]
+    fn layers_merge_strictest_decision() {
+        let mut guardrails = GitGuardrails::default();
+        assert_eq!(
+            guardrails.evaluate(&words(&["git", "clean", "-fd"]), Path::new("/")),
+            None
+        );
+
+        guardrails.extend(
+            GitGuardrailsToml {
+                clean: Some(CommandRuleDecision::AutoDeny),
+                ..Default::default()
+            },
+            Path::new("/home/me/.codex/config.toml"),
+        );
+        guardrails.extend(
+            GitGuardrailsToml {
+                protected_branches: Some(words(&["develop"])),
+                clean: Some(CommandRuleDecision::AutoApprove),
+                history_rewrite: Some(CommandRuleDecision::AutoApprove),
+                ..Default::default()
+            },
+            Path::new("/repo/.codex/policies.toml"),
+        );
+
+        assert_eq!(
+            guardrails.evaluate(
+                &words(&["bash", "-lc", "make clean && git clean -fd"]),
+                Path::new("/")
+            ),
+            Some(CommandRuleMatch {
+                rule: "git_guardrails.clean".to_string(),
+                decision: CommandRuleDecision::AutoDeny,
+                source: PathBuf::from("/home/me/.codex/config.toml"),
+                reason: Some("`git clean` permanently deletes untracked files".to_string()),
+            })
+        );
+        assert_eq!(
+            guardrails
+                .evaluate(
+                    &words(&["git", "push", "origin", ":develop"]),
+                    Path::new("/")
+                )
+                .map(|rule| (rule.decision, rule.source)),
+            Some((
+                CommandRuleDecision::AutoDeny,
+                PathBuf::from("/repo/.codex/policies.toml")
+            ))
+        );
+        assert_eq!(
+            guardrails.evaluate(&words(&["git", "branch", "-D", "develop"]), Path::new("/")),
+            None
+        );
+    }
+}
@@ -42,6 +42,28 @@ pub fn get_git_repo_root(base_dir: &Path) -> Option<PathBuf> {
     None
 }
 
+/// Return the branch checked out in the repository containing `cwd`, or
+/// `None` outside a repository or on a detached HEAD.
+///
+/// Like [`get_git_repo_root`] this reads `.git/HEAD` directly (following the
+/// `gitdir:` indirection used by worktrees) instead of spawning `git`, so it
+/// is cheap enough to call before every turn.
+pub fn checked_out_branch(cwd: &Path) -> Option<String> {
+    let repo_root = get_git_repo_root(cwd)?;
+    let dot_git = repo_root.join(".git");
+    let git_dir = if dot_git.is_dir() {
+        dot_git
+    } else {
+        let contents = std::fs::read_to_string(&dot_git).ok()?;
+        let gitdir = contents.trim().strip_prefix("gitdir:")?.trim();
+        resolve_path(&repo_root, &PathBuf::from(gitdir))
+    };
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    head.trim()
+        .strip_prefix("ref: refs/heads/")
+        .map(str::to_string)
+}
+
 /// Timeout for git commands to prevent freezing on large repositories
 const GIT_COMMAND_TIMEOUT: TokioDuration = TokioDuration::from_secs(5);
 
@@ -184,7 +206,10 @@ pub async fn git_diff_to_remote(cwd: &Path) -> Option<GitDiffToRemote> {
 }
 
 /// Run a git command with a timeout to prevent blocking on large repositories
-async fn run_git_command_with_timeout(args: &[&str], cwd: &Path) -> Option<std::process::Output> {
+pub(crate) async fn run_git_command_with_timeout(
+    args: &[&str],
+    cwd: &Path,
+) -> Option<std::process::Output> {
     let result = timeout(
         GIT_COMMAND_TIMEOUT,
         Command::new("git").args(args).current_dir(cwd).output(),
@@ -896,6 +921,27 @@ mod tests {
         assert_eq!(git_info.branch, Some("feature-branch".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_checked_out_branch_reads_head() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let repo_path = create_test_git_repo(&temp_dir).await;
+        let nested = repo_path.join("nested");
+        fs::create_dir(&nested).expect("Failed to create nested dir");
+
+        Command::new("git")
+            .args(["checkout", "-b", "codex/feature"])
+            .current_dir(&repo_path)
+            .output()
+            .await
+            .expect("Failed to create branch");
+
+        assert_eq!(
+            checked_out_branch(&nested),
+            Some("codex/feature".to_string())
+        );
+        assert_eq!(checked_out_branch(temp_dir.path()), None);
+    }
+
     #[tokio::test]
     async fn test_get_git_working_tree_state_clean_repo() {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
@@ -0,0 +1,443 @@
+//! Coordination of model requests across the Codex processes on a machine.
+//!
+//! Sessions, background tasks and daemons that share a `CODEX_HOME` also
+//! share one state file, `governor.json`, guarded by an exclusive lock on
+//! `governor.lock`. Each model request takes a lease before it is sent and
+//! gives it back when its stream ends, so `[governor]` limits on concurrent
+//! requests and tokens per minute hold across processes. When the provider
+//! rate-limits an account, the cooldown is written to the same file and the
+//! other sessions on that account wait it out instead of hitting the limit
+//! again.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::types::GovernorConfig;
+use crate::error::CodexErr;
+
+const STATE_FILE: &str = "governor.json";
+const LOCK_FILE: &str = "governor.lock";
+/// Leases of processes that died without releasing them are dropped after
+/// this long even when their pid has been reused.
+const LEASE_TTL: Duration = Duration::from_secs(10 * 60);
+const TOKEN_WINDOW: Duration = Duration::from_secs(60);
+/// How often a waiting request checks the state again.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Rate-limit cooldowns shared with other sessions are capped so a bad
+/// `retry-after` cannot stall every session for long.
+const MAX_SHARED_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+/// Cooldown shared after a 429 that did not say how long to wait.
+const DEFAULT_RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct GovernorState {
+    #[serde(default)]
+    leases: Vec<LeaseRecord>,
+    #[serde(default)]
+    usage: Vec<UsageRecord>,
+    /// Account key to the time (Unix ms) until which it is rate-limited.
+    #[serde(default)]
+    blocked: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LeaseRecord {
+    id: String,
+    pid: u32,
+    account: String,
+    expires_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct UsageRecord {
+    at: i64,
+    tokens: u64,
+}
+
+/// Why a request cannot start yet.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GovernorWait {
+    pub reason: String,
+    pub retry_in: Duration,
+}
+
+impl GovernorWait {
+    /// How long to sleep before checking again.
+    pub(crate) fn poll_delay(&self) -> Duration {
+        self.retry_in.clamp(MIN_POLL_INTERVAL, POLL_INTERVAL)
+    }
+}
+
+#[derive(Debug)]
+struct GovernorFiles {
+    state: PathBuf,
+    lock: PathBuf,
+}
+
+pub(crate) struct Governor {
+    config: GovernorConfig,
+    files: Arc<GovernorFiles>,
+}
+
+/// A running model request. Dropping it frees the slot for other sessions.
+pub(crate) struct GovernorLease {
+    files: Arc<GovernorFiles>,
+    /// `None` when the state file could not be used; the request then runs
+    /// without coordination.
+    id: Option<String>,
+}
+
+impl Governor {
+    pub(crate) fn new(codex_home: &Path, config: GovernorConfig) -> Self {
+        Self {
+            config,
+            files: Arc::new(GovernorFiles {
+                state: codex_home.join(STATE_FILE),
+                lock: codex_home.join(LOCK_FILE),
+            }),
+        }
+    }
+
+    /// Takes a lease for a request on `account` if the shared limits allow
+    /// it now.
+    pub(crate) async fn try_acquire(&self, account: &str) -> Result<GovernorLease, GovernorWait> {
+        let files = Arc::clone(&self.files);
+        let config = self.config.clone();
+        let account = account.to_string();
+        let id = Uuid::new_v4().to_string();
+        let pid = std::process::id();
+        let lease_id = id.clone();
+        let result = run_blocking(Arc::clone(&files), move |state| {
+            let now = now_millis();
+            prune(state, now, is_process_alive);
+            try_acquire(state, &config, &account, pid, &lease_id, now)
+        })
+        .await;
+        match result {
+            Some(Ok(())) => Ok(GovernorLease {
+                files,
+                id: Some(id),
+            }),
+            Some(Err(wait)) => Err(wait),
+            None => Ok(GovernorLease { files, id: None }),
+        }
+    }
+
+    /// Counts `tokens` used by a finished request against the shared budget.
+    pub(crate) async fn record_tokens(&self, tokens: i64) {
+        if self.config.tokens_per_minute.is_none() || tokens <= 0 {
+            return;
+        }
+        let tokens = tokens.unsigned_abs();
+        run_blocking(Arc::clone(&self.files), move |state| {
+            state.usage.push(UsageRecord {
+                at: now_millis(),
+                tokens,
+            });
+        })
+        .await;
+    }
+
+    /// Makes every session on `account` wait `cooldown` before its next
+    /// request, after the provider rate-limited this one.
+    pub(crate) async fn block_account(&self, account: &str, cooldown: Duration) {
+        if !self.config.share_rate_limits {
+            return;
+        }
+        let account = account.to_string();
+        let until = now_millis() + duration_millis(cooldown.min(MAX_SHARED_COOLDOWN));
+        run_blocking(Arc::clone(&self.files), move |state| {
+            let blocked = state.blocked.entry(account).or_insert(until);
+            *blocked = (*blocked).max(until);
+        })
+        .await;
+    }
+}
+
+impl Drop for GovernorLease {
+    fn drop(&mut self) {
+        let Some(id) = self.id.take() else {
+            return;
+        };
+        let files = Arc::clone(&self.files);
+        let release = move || {
+            if let Err(err) = with_state(&files, |state| state.leases.retain(|l| l.id != id)) {
+                warn!("failed to release governor lease: {err}");
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn_blocking(release);
+            }
+            Err(_) => release(),
+        }
+    }
+}
+
+/// How long other sessions on the account should hold off after `err`, if
+/// it is a rate limit.
+pub(crate) fn rate_limit_cooldown(err: &CodexErr) -> Option<Duration> {
+    match err {
+        CodexErr::Stream(_, Some(delay)) => Some(*delay),
+        CodexErr::RateLimited(err) => Some(err.retry_after.unwrap_or(DEFAULT_RATE_LIMIT_COOLDOWN)),
+        CodexErr::RetryLimit(err) if err.status == StatusCode::TOO_MANY_REQUESTS => {
+            Some(DEFAULT_RATE_LIMIT_COOLDOWN)
+        }
+        _ => None,
+    }
+}
+
+/// Drops expired leases, leases of exited processes, usage outside the
+/// token window and finished cooldowns.
+fn prune(state: &mut GovernorState, now: i64, is_alive: impl Fn(u32) -> bool) {
+    state
+        .leases
+        .retain(|lease| lease.expires_at > now && is_alive(lease.pid));
+    let window_start = now - duration_millis(TOKEN_WINDOW);
+    state.usage.retain(|usage| usage.at > window_start);
+    state.blocked.retain(|_, until| *until > now);
+}
+
+fn try_acquire(
+    state: &mut GovernorState,
+    config: &GovernorConfig,
+    account: &str,
+    pid: u32,
+    id: &str,
+    now: i64,
+) -> Result<(), GovernorWait> {
+    if let Some(until) = state.blocked.get(account) {
+        let retry_in = millis_duration(until - now);
+        return Err(GovernorWait {
+            reason: format!(
+                "the provider rate-limited this account; retrying in {}s",
+                retry_in.as_secs().max(1)
+            ),
+            retry_in,
+        });
+    }
+    if let Some(max) = config.max_concurrent_requests
+        && state.leases.len() >= max
+    {
+        return Err(GovernorWait {
+            reason: format!(
+                "{} model requests are already running across sessions (limit {max})",
+                state.leases.len()
+            ),
+            retry_in: POLL_INTERVAL,
+        });
+    }
+    if let Some(limit) = config.tokens_per_minute {
+        let used: u64 = state.usage.iter().map(|usage| usage.tokens).sum();
+        if used >= limit {
+            // The budget frees up as the oldest usage leaves the window.
+            let oldest = state
+                .usage
+                .iter()
+                .map(|usage| usage.at)
+                .min()
+                .unwrap_or(now);
+            let retry_in = millis_duration(oldest + duration_millis(TOKEN_WINDOW) - now);
+            return Err(GovernorWait {
+                reason: format!("{used} of {limit} tokens per minute are used across sessions"),
+                retry_in,
+            });
+        }
+    }
+    state.leases.push(LeaseRecord {
+        id: id.to_string(),
+        pid,
+        account: account.to_string(),
+        expires_at: now + duration_millis(LEASE_TTL),
+    });
+    Ok(())
+}
+
+/// Runs `f` on the shared state off the async runtime. Returns `None` and
+/// logs when the state file cannot be used.
+async fn run_blocking<T: Send + 'static>(
+    files: Arc<GovernorFiles>,
+    f: impl FnOnce(&mut GovernorState) -> T + Send + 'static,
+) -> Option<T> {
+    match tokio::task::spawn_blocking(move || with_state(&files, f)).await {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(err)) => {
+            warn!("failed to update governor state: {err}");
+            None
+        }
+        Err(err) => {
+            warn!("governor state task failed: {err}");
+            None
+        }
+    }
+}
+
+/// Locks the state file, applies `f` and writes the result back.
+fn with_state<T>(
+    files: &GovernorFiles,
+    f: impl FnOnce(&mut GovernorState) -> T,
+) -> std::io::Result<T> {
+    if let Some(parent) = files.lock.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&files.lock)?;
+    lock.lock()?;
+    // A missing or unreadable file starts from an empty state.
+    let mut state = std::fs::read_to_string(&files.state)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default();
+    let value = f(&mut state);
+    let contents = serde_json::to_string(&state).map_err(std::io::Error::other)?;
+    std::fs::write(&files.state, contents)?;
+    File::unlock(&lock)?;
+    Ok(value)
+}
+
+#[cfg(unix)]
+fn is_process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // kill(pid, 0) only checks that the process exists.
+    !(unsafe { libc::kill(pid, 0) } == -1
+        && std::io::Error::last_os_error().raw_os_error() == Some(libc::ESRCH))
+}
+
+#[cfg(not(unix))]
+fn is_process_alive(_pid: u32) -> bool {
+    // Leases of exited processes expire after `LEASE_TTL`.
+    true
+}
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn duration_millis(duration: Duration) -> i64 {
+    i64::try_from(duration.as_millis()).unwrap_or(i64::MAX)
+}
+
+fn millis_duration(millis: i64) -> Duration {
+    Duration::from_millis(u64::try_from(millis).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn config(
+        max_concurrent_requests: Option<usize>,
+        tokens_per_minute: Option<u64>,
+    ) -> GovernorConfig {
+        GovernorConfig {
+            max_concurrent_requests,
+            tokens_per_minute,
+            share_rate_limits: true,
+        }
+    }
+
+    #[test]
+    fn limits_concurrent_requests_across_processes() {
+        let config = config(Some(2), None);
+        let mut state = GovernorState::default();
+        assert_eq!(try_acquire(&mut state, &config, "a", 10, "one", 0), Ok(()));
+        assert_eq!(try_acquire(&mut state, &config, "b", 11, "two", 0), Ok(()));
+        assert_eq!(
+            try_acquire(&mut state, &config, "a", 10, "three", 0),
+            Err(GovernorWait {
+                reason: "2 model requests are already running across sessions (limit 2)"
+                    .to_string(),
+                retry_in: POLL_INTERVAL,
+            })
+        );
+
+        // Process 11 exited without releasing its lease.
+        prune(&mut state, 1_000, |pid| pid != 11);
+        assert_eq!(
+            try_acquire(&mut state, &config, "a", 10, "three", 1_000),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn waits_for_token_budget_and_account_cooldowns() {
+        let config = config(None, Some(1_000));
+        let mut state = GovernorState {
+            usage: vec![
+                UsageRecord {
+                    at: 5_000,
+                    tokens: 600,
+                },
+                UsageRecord {
+                    at: 20_000,
+                    tokens: 400,
+                },
+            ],
+            ..GovernorState::default()
+        };
+        assert_eq!(
+            try_acquire(&mut state, &config, "a", 10, "one", 30_000),
+            Err(GovernorWait {
+                reason: "1000 of 1000 tokens per minute are used across sessions".to_string(),
+                retry_in: Duration::from_secs(35),
+            })
+        );
+        prune(&mut state, 65_001, |_| true);
+        assert_eq!(
+            try_acquire(&mut state, &config, "a", 10, "one", 65_001),
+            Ok(())
+        );
+
+        state.blocked.insert("a".to_string(), 75_001);
+        assert_eq!(
+            try_acquire(&mut state, &config, "a", 10, "two", 65_001),
+            Err(GovernorWait {
+                reason: "the provider rate-limited this account; retrying in 10s".to_string(),
+                retry_in: Duration::from_secs(10),
+            })
+        );
+        assert_eq!(
+            try_acquire(&mut state, &config, "b", 10, "two", 65_001),
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn leases_are_shared_through_the_state_file() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let first = Governor::new(codex_home.path(), config(Some(1), None));
+        let second = Governor::new(codex_home.path(), config(Some(1), None));
+
+        let lease = first.try_acquire("a").await.expect("first lease");
+        assert!(second.try_acquire("b").await.is_err());
+
+        drop(lease);
+        let mut acquired = None;
+        for _ in 0..50 {
+            if let Ok(lease) = second.try_acquire("b").await {
+                acquired = Some(lease);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(acquired.is_some(), "lease should be released on drop");
+    }
+}
@@ -0,0 +1,508 @@
+//! Guardrail for clearly destructive commands (`destructive_guardrail`).
+//!
+//! Before a shell command runs, a set of rules looks for mass deletion,
+//! force pushes to protected branches and dropped databases. When
+//! `[guardrail].model` is set, commands the rules and the approval policy
+//! would let through are also shown to that model. A flagged command always
+//! needs the user's approval, whatever the approval policy says, and the
+//! approval request explains what was flagged; with `approval_policy =
+//! "never"` it is refused instead.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::AskForApproval;
+use futures::StreamExt;
+use regex::Regex;
+use tracing::warn;
+
+use crate::Prompt;
+use crate::bash::extract_bash_command;
+use crate::client::ModelClient;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::GuardrailConfig;
+use crate::features::Feature;
+use crate::git_guardrails::flag_push;
+use crate::git_guardrails::git_subcommand;
+use crate::git_info::checked_out_branch;
+use crate::is_safe_command::is_known_safe_command;
+use crate::tools::sandboxing::ExecApprovalRequirement;
+
+/// The model check fails open after this long.
+const MODEL_CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+const MODEL_CHECK_INSTRUCTIONS: &str = "You review shell commands that a coding agent is about to run on a developer's machine. \
+Decide whether the command is clearly destructive: deleting large parts of the filesystem or a repository, \
+rewriting or deleting shared git history, dropping or wiping databases, or erasing disks. \
+Ordinary edits, builds, tests and deleting build output are not destructive. \
+Reply with exactly one line: `SAFE`, or `DESTRUCTIVE: <one sentence saying what would be destroyed>`.";
+
+/// Programs whose input is SQL, so `DROP ...` in the script is meant.
+const SQL_CLIENTS: &[&str] = &[
+    "psql",
+    "mysql",
+    "mariadb",
+    "sqlite3",
+    "sqlcmd",
+    "clickhouse-client",
+    "cockroach",
+    "duckdb",
+];
+
+#[expect(clippy::expect_used)]
+static DROP_SQL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(drop\s+(database|schema|table)|truncate\s+table)\b").expect("valid regex")
+});
+
+/// Applies the guardrail to the approval requirement computed by the exec
+/// policy. Returns `requirement` unchanged when the feature is off or nothing
+/// is flagged.
+pub(crate) async fn apply_guardrail(
+    session: &Session,
+    turn: &TurnContext,
+    command: &[String],
+    cwd: &Path,
+    requirement: ExecApprovalRequirement,
+) -> ExecApprovalRequirement {
+    if !session.features().enabled(Feature::DestructiveGuardrail)
+        || matches!(requirement, ExecApprovalRequirement::Forbidden { .. })
+    {
+        return requirement;
+    }
+    let config = turn.client.config();
+    let current_branch = checked_out_branch(cwd);
+    let mut flagged = flag_command(
+        command,
+        &config.guardrail.protected_branches,
+        current_branch.as_deref(),
+    );
+    if flagged.is_none()
+        && matches!(requirement, ExecApprovalRequirement::Skip { .. })
+        && let Some(model) = &config.guardrail.model
+        && !is_known_safe_command(command)
+    {
+        flagged = model_check(session, turn, &config.guardrail, model, command).await;
+    }
+    let Some(explanation) = flagged else {
+        return requirement;
+    };
+    if turn.approval_policy == AskForApproval::Never {
+        return ExecApprovalRequirement::Forbidden {
+            reason: format!(
+                "Guardrail refused this command: {explanation}. The approval policy does not allow asking the user."
+            ),
+        };
+    }
+    ExecApprovalRequirement::NeedsApproval {
+        reason: Some(format!("Guardrail flagged this command: {explanation}")),
+        proposed_execpolicy_amendment: None,
+    }
+}
+
+/// Checks `command` against the rules and explains the first match.
+/// `current_branch` is where a `git push` without a refspec goes.
+pub(crate) fn flag_command(
+    command: &[String],
+    protected_branches: &[String],
+    current_branch: Option<&str>,
+) -> Option<String> {
+    let (script, segments) = match extract_bash_command(command) {
+        Some((_, script)) => (script.to_string(), split_script(script)),
+        None => (command.join(" "), vec![command.to_vec()]),
+    };
+    for segment in &segments {
+        let words = strip_wrappers(segment);
+        let Some(program) = words.first() else {
+            continue;
+        };
+        let args = &words[1..];
+        let flagged = match program_name(program) {
+            "rm" => flag_rm(args),
+            "find" => flag_find(args),
+            "git" => git_subcommand(args)
+                .filter(|(subcommand, _)| *subcommand == "push")
+                .and_then(|(_, push_args)| {
+                    flag_push(push_args, protected_branches, current_branch)
+                }),
+            "dropdb" => Some("`dropdb` deletes a database".to_string()),
+            "redis-cli" => args
+                .iter()
+                .find(|arg| matches!(arg.to_ascii_lowercase().as_str(), "flushall" | "flushdb"))
+                .map(|arg| format!("`redis-cli {arg}` deletes every key")),
+            "dd" => args
+                .iter()
+                .find(|arg| arg.starts_with("of=/dev/") && !arg.starts_with("of=/dev/null"))
+                .map(|arg| format!("`dd {arg}` overwrites a device")),
+            name if name == "mkfs" || name.starts_with("mkfs.") => {
+                Some(format!("`{name}` formats a device"))
+            }
+            _ => None,
+        };
+        if flagged.is_some() {
+            return flagged;
+        }
+    }
+    let runs_sql = segments.iter().any(|segment| {
+        strip_wrappers(segment)
+            .first()
+            .is_some_and(|program| SQL_CLIENTS.contains(&program_name(program)))
+    });
+    if runs_sql && let Some(found) = DROP_SQL.find(&script) {
+        return Some(format!(
+            "the SQL `{}` deletes a database object",
+            found.as_str()
+        ));
+    }
+    None
+}
+
+/// Each simple command in `command`, including every part of a `bash -lc`
+/// script, without wrappers such as `sudo` or `env`.
+pub(crate) fn simple_commands(command: &[String]) -> Vec<Vec<String>> {
+    let segments = match extract_bash_command(command) {
+        Some((_, script)) => split_script(script),
+        None => vec![command.to_vec()],
+    };
+    segments
+        .iter()
+        .map(|segment| strip_wrappers(segment).to_vec())
+        .filter(|words| !words.is_empty())
+        .collect()
+}
+
+/// Splits a shell script into simple commands at unquoted `;`, `&`, `|`,
+/// newlines, parentheses and backticks.
+fn split_script(script: &str) -> Vec<Vec<String>> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    for ch in script.chars() {
+        match (quote, ch) {
+            (Some(open), _) if ch == open => {
+                quote = None;
+                current.push(ch);
+            }
+            (Some(_), _) => current.push(ch),
+            (None, '\'' | '"') => {
+                quote = Some(ch);
+                current.push(ch);
+            }
+            (None, ';' | '&' | '|' | '\n' | '(' | ')' | '`') => {
+                pieces.push(std::mem::take(&mut current));
+            }
+            (None, _) => current.push(ch),
+        }
+    }
+    pieces.push(current);
+    pieces
+        .iter()
+        .map(|piece| {
+            shlex::split(piece)
+                .unwrap_or_else(|| piece.split_whitespace().map(str::to_string).collect())
+        })
+        .filter(|words| !words.is_empty())
+        .collect()
+}
+
+/// Drops `sudo`, `env`, variable assignments and similar prefixes that run
+/// the actual command.
+fn strip_wrappers(words: &[String]) -> &[String] {
+    let mut rest = words;
+    while let Some(first) = rest.first() {
+        let is_assignment = first
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty() && !name.starts_with('-'));
+        if is_assignment
+            || matches!(
+                program_name(first),
+                "sudo" | "env" | "nohup" | "time" | "command" | "exec" | "xargs"
+            )
+        {
+            rest = &rest[1..];
+        } else if matches!(first.as_str(), "-u" | "-g" | "-U") && rest.len() < words.len() {
+            // `sudo -u postgres psql ...`
+            rest = rest.get(2..).unwrap_or(&[]);
+        } else if first.starts_with('-') && rest.len() < words.len() {
+            // Other options of the wrapper.
+            rest = &rest[1..];
+        } else {
+            break;
+        }
+    }
+    rest
+}
+
+pub(crate) fn program_name(program: &str) -> &str {
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+/// Targets whose recursive deletion wipes far more than a project directory.
+fn is_broad_target(target: &str) -> bool {
+    let trimmed = target.trim_end_matches('/');
+    if matches!(
+        trimmed,
+        "" | "*"
+            | "."
+            | "./*"
+            | ".."
+            | "../*"
+            | "~"
+            | "~/*"
+            | "$HOME"
+            | "${HOME}"
+            | "$HOME/*"
+            | "/*"
+    ) {
+        return true;
+    }
+    // A top-level directory such as `/usr` or `/home`.
+    trimmed.starts_with('/') && trimmed.matches('/').count() == 1
+}
+
+fn flag_rm(args: &[String]) -> Option<String> {
+    let mut recursive = false;
+    let mut targets = Vec::new();
+    let mut options_done = false;
+    for arg in args {
+        if !options_done && arg == "--" {
+            options_done = true;
+        } else if !options_done && arg.starts_with("--") {
+            recursive |= arg == "--recursive";
+            if arg == "--no-preserve-root" {
+                return Some("`rm --no-preserve-root` can delete the whole filesystem".to_string());
+            }
+        } else if !options_done && arg.starts_with('-') && arg.len() > 1 {
+            recursive |= arg.contains('r') || arg.contains('R');
+        } else {
+            targets.push(arg.as_str());
+        }
+    }
+    if !recursive {
+        return None;
+    }
+    targets
+        .into_iter()
+        .find(|target| is_broad_target(target))
+        .map(|target| format!("`rm` recursively deletes `{target}`"))
+}
+
+fn flag_find(args: &[String]) -> Option<String> {
+    if !args.iter().any(|arg| arg == "-delete") {
+        return None;
+    }
+    let root = args
+        .iter()
+        .take_while(|arg| !arg.starts_with('-'))
+        .find(|path| is_broad_target(path))?;
+    Some(format!(
+        "`find {root} -delete` deletes everything it matches under `{root}`"
+    ))
+}
+
+/// Asks `model` whether `command` is destructive. Returns the model's
+/// explanation when it is; errors and timeouts are logged and let the
+/// command through.
+async fn model_check(
+    session: &Session,
+    turn: &TurnContext,
+    config: &GuardrailConfig,
+    model: &str,
+    command: &[String],
+) -> Option<String> {
+    let check = async {
+        let client = guardrail_client(session, turn, model).await;
+        let prompt = Prompt {
+            input: vec![ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: format!(
+                        "Working directory: {}\nProtected branches: {}\nCommand: {}",
+                        turn.cwd.display(),
+                        config.protected_branches.join(", "),
+                        shlex::try_join(command.iter().map(String::as_str))
+                            .unwrap_or_else(|_| command.join(" "))
+                    ),
+                }],
+                end_turn: None,
+            }],
+            base_instructions: BaseInstructions {
+                text: MODEL_CHECK_INSTRUCTIONS.to_string(),
+            },
+            ..Default::default()
+        };
+        let mut client_session = client.new_session();
+        let mut stream = client_session
+            .stream(&prompt)
+            .await
+            .map_err(|err| err.to_string())?;
+        let mut text = String::new();
+        while let Some(event) = stream.next().await {
+            match event.map_err(|err| err.to_string())? {
+                ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. })
+                    if role == "assistant" =>
+                {
+                    for item in content {
+                        if let ContentItem::OutputText { text: chunk } = item {
+                            text.push_str(&chunk);
+                        }
+                    }
+                }
+                ResponseEvent::Completed { .. } => return Ok(parse_verdict(&text)),
+                _ => {}
+            }
+        }
+        Err("stream closed before response.completed".to_string())
+    };
+    match tokio::time::timeout(MODEL_CHECK_TIMEOUT, check).await {
+        Ok(Ok(verdict)) => verdict,
+        Ok(Err(err)) => {
+            warn!("guardrail model check failed: {err}");
+            None
+        }
+        Err(_) => {
+            warn!(
+                "guardrail model check timed out after {}s",
+                MODEL_CHECK_TIMEOUT.as_secs()
+            );
+            None
+        }
+    }
+}
+
+async fn guardrail_client(session: &Session, turn: &TurnContext, model: &str) -> ModelClient {
+    let mut config = (*turn.client.config()).clone();
+    config.model = Some(model.to_string());
+    let model_info = session
+        .services
+        .models_manager
+        .get_model_info(model, &config)
+        .await;
+    let otel_manager = turn
+        .client
+        .get_otel_manager()
+        .with_model(model, model_info.slug.as_str());
+    let config = Arc::new(config);
+    ModelClient::new(
+        config.clone(),
+        turn.client.get_auth_manager(),
+        model_info,
+        otel_manager,
+        turn.client.get_provider(),
+        config.model_reasoning_effort,
+        config.model_reasoning_summary,
+        session.conversation_id,
+        turn.client.get_session_source(),
+    )
+}
+
+/// Reads the model's one-line answer; anything but `DESTRUCTIVE: ...` counts
+/// as safe.
+fn parse_verdict(text: &str) -> Option<String> {
+    let line = text.trim().lines().next()?.trim().trim_matches('`');
+    let reason = line.strip_prefix("DESTRUCTIVE")?;
+    let reason = reason.trim_start_matches(':').trim();
+    Some(if reason.is_empty() {
+        "the guardrail model judged it destructive".to_string()
+    } else {
+        format!("the guardrail model judged it destructive: {reason}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn words(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    fn protected() -> Vec<String> {
+        words(&["main", "master", "release/*"])
+    }
+
+    fn flag(items: &[&str]) -> Option<String> {
+        flag_command(&words(items), &protected(), Some("feature"))
+    }
+
+    fn flag_script(script: &str) -> Option<String> {
+        flag_command(&words(&["bash", "-lc", script]), &protected(), Some("main"))
+    }
+
+    #[test]
+    fn flags_mass_deletion() {
+        assert_eq!(
+            flag(&["rm", "-rf", "/"]),
+            Some("`rm` recursively deletes `/`".to_string())
+        );
+        assert_eq!(
+            flag_script("cd /tmp && sudo rm -r -f $HOME"),
+            Some("`rm` recursively deletes `$HOME`".to_string())
+        );
+        assert_eq!(
+            flag_script("find ~ -name '*.log' -delete"),
+            Some("`find ~ -delete` deletes everything it matches under `~`".to_string())
+        );
+        assert_eq!(flag(&["rm", "-rf", "target"]), None);
+        assert_eq!(flag(&["rm", "/tmp/file"]), None);
+        assert_eq!(flag_script("find . -name '*.pyc' -print"), None);
+    }
+
+    #[test]
+    fn flags_force_pushes_to_protected_branches() {
+        assert_eq!(
+            flag(&["git", "push", "--force", "origin", "main"]),
+            Some("`git push` force-pushes to the protected branch `main`".to_string())
+        );
+        assert_eq!(
+            flag(&["git", "push", "origin", "+HEAD:release/1.2"]),
+            Some("`git push` force-pushes to the protected branch `release/1.2`".to_string())
+        );
+        assert_eq!(
+            flag(&["git", "push", "origin", ":master"]),
+            Some("`git push` deletes the protected branch `master`".to_string())
+        );
+        assert_eq!(
+            flag_script("git push -f"),
+            Some("`git push` force-pushes to the protected branch `main`".to_string())
+        );
+        assert_eq!(flag(&["git", "push", "-f", "origin", "feature"]), None);
+        assert_eq!(flag(&["git", "push", "origin", "main"]), None);
+        assert_eq!(flag(&["git", "-C", "repo", "push", "--force"]), None);
+    }
+
+    #[test]
+    fn flags_dropped_databases() {
+        assert_eq!(
+            flag(&["psql", "-c", "DROP DATABASE app;"]),
+            Some("the SQL `DROP DATABASE` deletes a database object".to_string())
+        );
+        assert_eq!(
+            flag_script("echo 'drop table users' | mysql app"),
+            Some("the SQL `drop table` deletes a database object".to_string())
+        );
+        assert_eq!(
+            flag(&["dropdb", "app"]),
+            Some("`dropdb` deletes a database".to_string())
+        );
+        assert_eq!(flag_script("grep -r 'DROP TABLE' migrations"), None);
+    }
+
+    #[test]
+    fn parses_model_verdicts() {
+        assert_eq!(parse_verdict("SAFE"), None);
+        assert_eq!(
+            parse_verdict("DESTRUCTIVE: wipes the staging bucket\n"),
+            Some("the guardrail model judged it destructive: wipes the staging bucket".to_string())
+        );
+        assert_eq!(parse_verdict(""), None);
+    }
+}
@@ -0,0 +1,901 @@
+//! `[[hooks]]`: external commands run at session events.
+//!
+//! Every hook receives one JSON payload on stdin with at least `type` (the
+//! [`HookEventKind`]), `thread_id`, `turn_id`, and `cwd`. Hooks are
+//! observe-only by default: they run in the background and failures are only
+//! logged. A `blocking = true` hook on `prompt.submit` or `tool.call.begin` is
+//! awaited before the action proceeds and can veto it:
+//!
+//! - exit code 0: allow. Stdout may carry a JSON verdict,
+//!   `{"decision": "block", "reason": "..."}` or
+//!   `{"updated_arguments": ...}` (tool calls only).
+//! - exit code 2: block, with stderr as the reason.
+//! - anything else (including a timeout): the hook failed; the action proceeds.
+//!
+//! Verdicts and failures of blocking hooks are reported as
+//! [`EventMsg::HookOutcome`].
+//!
+//! `turn.post_process` hooks are awaited when a turn finishes, before
+//! `TurnComplete`. Their payload adds `last_agent_message` and `effects`, a
+//! manifest of the commands, patches, MCP calls, and web searches of the turn.
+//! Stdout may be plain text (one annotation) or JSON with any of
+//! `annotations` (strings), `artifacts` (`{"name", "contents"}`, written under
+//! `$CODEX_HOME/artifacts/<thread_id>/`), and `notifications`
+//! (`{"title", "body"}`, also passed to the `notify` program). The result is
+//! sent and recorded as [`EventMsg::TurnAnnotations`].
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Output;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ExecCommandSource;
+use codex_protocol::protocol::HookEventKind;
+use codex_protocol::protocol::HookNotification;
+use codex_protocol::protocol::HookOutcome;
+use codex_protocol::protocol::HookOutcomeEvent;
+use codex_protocol::protocol::TurnAnnotationsEvent;
+use codex_protocol::user_input::UserInput;
+use regex::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::debug;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::HookConfig;
+use crate::config::types::HookWhen;
+use crate::function_tool::FunctionCallError;
+use crate::git_info::get_git_repo_root;
+use crate::tools::context::ToolPayload;
+use crate::user_notification::UserNotification;
+
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Exit code a hook uses to block the action.
+const BLOCK_EXIT_CODE: i32 = 2;
+
+/// Directory under `$CODEX_HOME` holding `turn.post_process` artifacts.
+const ARTIFACTS_SUBDIR: &str = "artifacts";
+
+#[derive(Default)]
+pub(crate) struct Hooks {
+    entries: Vec<Arc<HookEntry>>,
+    /// Effects of running turns by turn id, collected only when a
+    /// `turn.post_process` hook is configured.
+    effects: Option<Mutex<HashMap<String, TurnEffects>>>,
+}
+
+/// What a turn did, handed to `turn.post_process` hooks as `effects`.
+#[derive(Debug, Default, Serialize, PartialEq)]
+struct TurnEffects {
+    commands: Vec<CommandEffect>,
+    /// Files touched by successfully applied patches.
+    files_changed: BTreeSet<PathBuf>,
+    mcp_calls: Vec<McpCallEffect>,
+    web_searches: Vec<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct CommandEffect {
+    command: Vec<String>,
+    cwd: PathBuf,
+    exit_code: i32,
+    source: ExecCommandSource,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct McpCallEffect {
+    server: String,
+    tool: String,
+    success: bool,
+}
+
+impl TurnEffects {
+    fn record(&mut self, msg: &EventMsg) {
+        match msg {
+            EventMsg::ExecCommandEnd(event) => self.commands.push(CommandEffect {
+                command: event.command.clone(),
+                cwd: event.cwd.clone(),
+                exit_code: event.exit_code,
+                source: event.source,
+            }),
+            EventMsg::PatchApplyEnd(event) if event.success => {
+                self.files_changed.extend(event.changes.keys().cloned());
+            }
+            EventMsg::McpToolCallEnd(event) => self.mcp_calls.push(McpCallEffect {
+                server: event.invocation.server.clone(),
+                tool: event.invocation.tool.clone(),
+                success: event.is_success(),
+            }),
+            EventMsg::WebSearchEnd(event) => self.web_searches.push(event.query.clone()),
+            _ => {}
+        }
+    }
+}
+
+/// What a `turn.post_process` hook printed on stdout.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+struct PostProcessOutput {
+    #[serde(default)]
+    annotations: Vec<String>,
+    #[serde(default)]
+    artifacts: Vec<HookArtifact>,
+    #[serde(default)]
+    notifications: Vec<HookNotification>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct HookArtifact {
+    name: String,
+    contents: String,
+}
+
+struct HookEntry {
+    config: HookConfig,
+    matcher: Option<Regex>,
+}
+
+impl HookEntry {
+    fn label(&self) -> &str {
+        self.config.id.as_deref().unwrap_or("<unnamed>")
+    }
+
+    fn timeout(&self) -> Duration {
+        self.config
+            .timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_HOOK_TIMEOUT)
+    }
+}
+
+/// What a blocking hook decided.
+#[derive(Debug, Clone, PartialEq)]
+enum HookVerdict {
+    Allow,
+    Block(String),
+    /// Replacement tool arguments.
+    Modify(String),
+}
+
+impl Hooks {
+    pub(crate) fn new(configs: &[HookConfig]) -> Self {
+        let entries = configs
+            .iter()
+            .filter_map(|config| {
+                if config.command.is_empty() {
+                    warn!("ignoring hook {:?}: empty command", config.id);
+                    return None;
+                }
+                let matcher = match config.matcher.as_deref().map(Regex::new).transpose() {
+                    Ok(matcher) => matcher,
+                    Err(err) => {
+                        warn!("ignoring hook {:?}: invalid matcher: {err}", config.id);
+                        return None;
+                    }
+                };
+                if config.blocking && !blocking_supported(&config.when) {
+                    warn!(
+                        "hook {:?}: `blocking` only applies to prompt.submit and tool.call.begin",
+                        config.id
+                    );
+                }
+                Some(Arc::new(HookEntry {
+                    config: config.clone(),
+                    matcher,
+                }))
+            })
+            .collect::<Vec<_>>();
+        let post_process = entries
+            .iter()
+            .any(|entry| entry.config.when.contains(HookEventKind::TurnPostProcess));
+        Self {
+            entries,
+            effects: post_process.then(Mutex::default),
+        }
+    }
+
+    fn matching<'a>(
+        &'a self,
+        kind: HookEventKind,
+        subject: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a Arc<HookEntry>> + 'a {
+        self.entries.iter().filter(move |entry| {
+            entry.config.when.contains(kind)
+                // Events without a subject cannot be narrowed by `matcher`.
+                && match (&entry.matcher, subject) {
+                    (Some(matcher), Some(subject)) => matcher.is_match(subject),
+                    _ => true,
+                }
+        })
+    }
+
+    /// Runs the observe-only hooks for an event emitted by the session.
+    pub(crate) fn observe_event(&self, sess: &Session, turn: &TurnContext, msg: &EventMsg) {
+        if self.entries.is_empty() {
+            return;
+        }
+        if let Some(effects) = &self.effects
+            && let Ok(mut effects) = effects.lock()
+        {
+            // Aborted turns never reach `after_turn`.
+            if matches!(msg, EventMsg::TurnAborted(_)) {
+                effects.remove(&turn.sub_id);
+            } else {
+                effects.entry(turn.sub_id.clone()).or_default().record(msg);
+            }
+        }
+        let Some((kind, subject)) = hook_event_for(msg) else {
+            return;
+        };
+        let mut matching = self.matching(kind, subject.as_deref()).peekable();
+        if matching.peek().is_none() {
+            return;
+        }
+
+        let mut payload = base_payload(sess, turn, kind);
+        if let Ok(Value::Object(fields)) = serde_json::to_value(msg) {
+            for (key, value) in fields {
+                if key != "type" {
+                    payload.entry(key).or_insert(value);
+                }
+            }
+        }
+        let payload = Value::Object(payload);
+        let cwd = hook_cwd(&turn.cwd);
+        for entry in matching {
+            spawn_observer(Arc::clone(entry), payload.clone(), cwd.clone());
+        }
+    }
+
+    /// Runs `prompt.submit` hooks. Returns `true` when a blocking hook
+    /// rejected the prompt.
+    pub(crate) async fn before_prompt_submit(
+        &self,
+        sess: &Session,
+        turn: &TurnContext,
+        items: &[UserInput],
+    ) -> bool {
+        let kind = HookEventKind::PromptSubmit;
+        if self.matching(kind, None).next().is_none() {
+            return false;
+        }
+        let prompt = items
+            .iter()
+            .filter_map(|item| match item {
+                UserInput::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut payload = base_payload(sess, turn, kind);
+        payload.insert("prompt".to_string(), Value::String(prompt));
+        let payload = Value::Object(payload);
+        let cwd = hook_cwd(&turn.cwd);
+
+        for entry in self.matching(kind, None) {
+            if !entry.config.blocking {
+                spawn_observer(Arc::clone(entry), payload.clone(), cwd.clone());
+                continue;
+            }
+            let outcome = match run_hook(entry, &payload, &cwd).await {
+                Ok(HookVerdict::Allow) => None,
+                Ok(HookVerdict::Block(reason)) => Some(HookOutcome::Blocked { reason }),
+                Ok(HookVerdict::Modify(_)) => Some(HookOutcome::Failed {
+                    error: "`updated_arguments` is not supported for prompt.submit".to_string(),
+                }),
+                Err(error) => Some(HookOutcome::Failed { error }),
+            };
+            let Some(outcome) = outcome else {
+                continue;
+            };
+            let blocked = matches!(outcome, HookOutcome::Blocked { .. });
+            send_outcome(sess, turn, entry, kind, None, outcome).await;
+            if blocked {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Runs `tool.call.begin` hooks. Blocking hooks may rewrite `payload`'s
+    /// arguments; a block is returned as the error to hand back to the model.
+    pub(crate) async fn before_tool_call(
+        &self,
+        sess: &Session,
+        turn: &TurnContext,
+        call_id: &str,
+        tool_name: &str,
+        payload: &mut ToolPayload,
+    ) -> Result<(), FunctionCallError> {
+        let kind = HookEventKind::ToolCallBegin;
+        let entries: Vec<Arc<HookEntry>> = self.matching(kind, Some(tool_name)).cloned().collect();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let cwd = hook_cwd(&turn.cwd);
+
+        for entry in entries {
+            let mut hook_payload = base_payload(sess, turn, kind);
+            hook_payload.insert("call_id".to_string(), json!(call_id));
+            hook_payload.insert("tool_name".to_string(), json!(tool_name));
+            if entry.config.include_tool_arguments {
+                hook_payload.insert("tool_input".to_string(), tool_input(payload));
+            }
+            let hook_payload = Value::Object(hook_payload);
+            if !entry.config.blocking {
+                spawn_observer(entry, hook_payload, cwd.clone());
+                continue;
+            }
+
+            let outcome = match run_hook(&entry, &hook_payload, &cwd).await {
+                Ok(HookVerdict::Allow) => continue,
+                Ok(HookVerdict::Block(reason)) => HookOutcome::Blocked { reason },
+                Ok(HookVerdict::Modify(arguments)) => match replace_arguments(payload, arguments) {
+                    Ok(()) => HookOutcome::Modified,
+                    Err(error) => HookOutcome::Failed { error },
+                },
+                Err(error) => HookOutcome::Failed { error },
+            };
+            let blocked = match &outcome {
+                HookOutcome::Blocked { reason } => Some(reason.clone()),
+                _ => None,
+            };
+            send_outcome(sess, turn, &entry, kind, Some(call_id), outcome).await;
+            if let Some(reason) = blocked {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "tool call blocked by hook `{}`: {reason}",
+                    entry.label()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs `tool.call.end` hooks (observe-only).
+    pub(crate) fn after_tool_call(
+        &self,
+        sess: &Session,
+        turn: &TurnContext,
+        call_id: &str,
+        tool_name: &str,
+        success: bool,
+    ) {
+        let kind = HookEventKind::ToolCallEnd;
+        let mut matching = self.matching(kind, Some(tool_name)).peekable();
+        if matching.peek().is_none() {
+            return;
+        }
+        let mut payload = base_payload(sess, turn, kind);
+        payload.insert("call_id".to_string(), json!(call_id));
+        payload.insert("tool_name".to_string(), json!(tool_name));
+        payload.insert("success".to_string(), json!(success));
+        let payload = Value::Object(payload);
+        let cwd = hook_cwd(&turn.cwd);
+        for entry in matching {
+            spawn_observer(Arc::clone(entry), payload.clone(), cwd.clone());
+        }
+    }
+
+    /// Runs `turn.post_process` hooks for a finished turn and reports what
+    /// they returned. Called right before `TurnComplete` is sent.
+    pub(crate) async fn after_turn(
+        &self,
+        sess: &Session,
+        turn: &TurnContext,
+        last_agent_message: Option<&str>,
+    ) {
+        let Some(effects) = &self.effects else {
+            return;
+        };
+        let effects = effects
+            .lock()
+            .ok()
+            .and_then(|mut effects| effects.remove(&turn.sub_id))
+            .unwrap_or_default();
+        let kind = HookEventKind::TurnPostProcess;
+        let mut payload = base_payload(sess, turn, kind);
+        payload.insert("last_agent_message".to_string(), json!(last_agent_message));
+        payload.insert("effects".to_string(), json!(effects));
+        let payload = Value::Object(payload);
+        let cwd = hook_cwd(&turn.cwd);
+        let artifacts_dir = turn
+            .client
+            .config()
+            .codex_home
+            .join(ARTIFACTS_SUBDIR)
+            .join(sess.conversation_id.to_string());
+
+        for entry in self.matching(kind, None) {
+            let output = run_command(entry, &payload, &cwd).await.and_then(|output| {
+                parse_post_process(
+                    output.status.code(),
+                    &String::from_utf8_lossy(&output.stdout),
+                    &String::from_utf8_lossy(&output.stderr),
+                )
+            });
+            let output = match output {
+                Ok(output) => output,
+                Err(error) => {
+                    send_outcome(sess, turn, entry, kind, None, HookOutcome::Failed { error })
+                        .await;
+                    continue;
+                }
+            };
+
+            let mut artifacts = Vec::with_capacity(output.artifacts.len());
+            for artifact in &output.artifacts {
+                match write_artifact(&artifacts_dir, artifact).await {
+                    Ok(path) => artifacts.push(path),
+                    Err(error) => {
+                        send_outcome(sess, turn, entry, kind, None, HookOutcome::Failed { error })
+                            .await;
+                    }
+                }
+            }
+            for notification in &output.notifications {
+                sess.notifier().notify(&UserNotification::HookNotification {
+                    thread_id: sess.conversation_id.to_string(),
+                    turn_id: turn.sub_id.clone(),
+                    hook_id: entry.config.id.clone(),
+                    title: notification.title.clone(),
+                    body: notification.body.clone(),
+                });
+            }
+            if output.annotations.is_empty()
+                && artifacts.is_empty()
+                && output.notifications.is_empty()
+            {
+                continue;
+            }
+            sess.send_event(
+                turn,
+                EventMsg::TurnAnnotations(TurnAnnotationsEvent {
+                    hook_id: entry.config.id.clone(),
+                    annotations: output.annotations,
+                    artifacts,
+                    notifications: output.notifications,
+                }),
+            )
+            .await;
+        }
+    }
+}
+
+fn blocking_supported(when: &HookWhen) -> bool {
+    match when {
+        HookWhen::One(kind) => kind.supports_blocking(),
+        HookWhen::Many(kinds) => kinds.iter().any(|kind| kind.supports_blocking()),
+    }
+}
+
+/// Maps a session event to the hook event it triggers and its matcher subject.
+fn hook_event_for(msg: &EventMsg) -> Option<(HookEventKind, Option<String>)> {
+    let exec_subject = |source: ExecCommandSource| {
+        let subject = match source {
+            ExecCommandSource::Agent => "shell",
+            ExecCommandSource::UserShell => "user_shell",
+            ExecCommandSource::UnifiedExecStartup | ExecCommandSource::UnifiedExecInteraction => {
+                "unified_exec"
+            }
+        };
+        Some(subject.to_string())
+    };
+    Some(match msg {
+        EventMsg::TurnStarted(_) => (HookEventKind::TurnBegin, None),
+        EventMsg::TurnComplete(_) => (HookEventKind::TurnEnd, None),
+        EventMsg::WebSearchBegin(_) => (HookEventKind::WebSearchBegin, None),
+        EventMsg::WebSearchEnd(_) => (HookEventKind::WebSearchEnd, None),
+        EventMsg::ExecCommandBegin(event) => {
+            (HookEventKind::ToolExecBegin, exec_subject(event.source))
+        }
+        EventMsg::ExecCommandEnd(event) => (HookEventKind::ToolExecEnd, exec_subject(event.source)),
+        EventMsg::PatchApplyBegin(_) => (HookEventKind::ToolApplyPatchBegin, None),
+        EventMsg::PatchApplyEnd(_) => (HookEventKind::ToolApplyPatchEnd, None),
+        EventMsg::McpToolCallBegin(event) => (
+            HookEventKind::ToolMcpBegin,
+            Some(event.invocation.tool.clone()),
+        ),
+        EventMsg::McpToolCallEnd(event) => (
+            HookEventKind::ToolMcpEnd,
+            Some(event.invocation.tool.clone()),
+        ),
+        _ => return None,
+    })
+}
+
+fn base_payload(sess: &Session, turn: &TurnContext, kind: HookEventKind) -> Map<String, Value> {
+    let mut payload = Map::new();
+    payload.insert("type".to_string(), json!(kind));
+    payload.insert(
+        "thread_id".to_string(),
+        json!(sess.conversation_id.to_string()),
+    );
+    payload.insert("turn_id".to_string(), json!(turn.sub_id));
+    payload.insert("cwd".to_string(), json!(turn.cwd));
+    payload
+}
+
+fn tool_input(payload: &ToolPayload) -> Value {
+    match payload {
+        ToolPayload::Function { arguments } => json!({
+            "type": "function",
+            "arguments": arguments,
+        }),
+        ToolPayload::Custom { input } => json!({
+            "type": "custom",
+            "input": input,
+        }),
+        ToolPayload::LocalShell { params } => json!({
+            "type": "local_shell",
+            "command": params.command,
+            "workdir": params.workdir,
+        }),
+        ToolPayload::Mcp {
+            server,
+            tool,
+            raw_arguments,
+        } => json!({
+            "type": "mcp",
+            "server": server,
+            "tool": tool,
+            "arguments": raw_arguments,
+        }),
+    }
+}
+
+fn replace_arguments(payload: &mut ToolPayload, updated: String) -> Result<(), String> {
+    match payload {
+        ToolPayload::Function { arguments } => *arguments = updated,
+        ToolPayload::Custom { input } => *input = updated,
+        ToolPayload::Mcp { raw_arguments, .. } => *raw_arguments = updated,
+        ToolPayload::LocalShell { .. } => {
+            return Err("`updated_arguments` is not supported for local_shell calls".to_string());
+        }
+    }
+    Ok(())
+}
+
+async fn send_outcome(
+    sess: &Session,
+    turn: &TurnContext,
+    entry: &HookEntry,
+    when: HookEventKind,
+    call_id: Option<&str>,
+    outcome: HookOutcome,
+) {
+    if let HookOutcome::Failed { error } = &outcome {
+        warn!("hook `{}` failed: {error}", entry.label());
+    }
+    sess.send_event(
+        turn,
+        EventMsg::HookOutcome(HookOutcomeEvent {
+            hook_id: entry.config.id.clone(),
+            when,
+            call_id: call_id.map(str::to_string),
+            outcome,
+        }),
+    )
+    .await;
+}
+
+/// Hooks run from the repository root so relative script paths such as
+/// `.codex/hooks/foo.py` resolve regardless of the session's subdirectory.
+fn hook_cwd(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+fn spawn_observer(entry: Arc<HookEntry>, payload: Value, cwd: PathBuf) {
+    tokio::spawn(async move {
+        if let Err(err) = run_hook(&entry, &payload, &cwd).await {
+            warn!("hook `{}` failed: {err}", entry.label());
+        }
+    });
+}
+
+async fn run_hook(entry: &HookEntry, payload: &Value, cwd: &Path) -> Result<HookVerdict, String> {
+    let output = run_command(entry, payload, cwd).await?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let verdict = parse_verdict(output.status.code(), &stdout, &stderr);
+    debug!(
+        target: "codex_core::hooks",
+        hook = entry.label(),
+        status = ?output.status.code(),
+        verdict = ?verdict,
+        "hook finished"
+    );
+    verdict
+}
+
+/// Runs the hook's command with `payload` on stdin, within its timeout.
+async fn run_command(entry: &HookEntry, payload: &Value, cwd: &Path) -> Result<Output, String> {
+    let Some((program, args)) = entry.config.command.split_first() else {
+        return Err("empty command".to_string());
+    };
+    debug!(
+        target: "codex_core::hooks",
+        hook = entry.label(),
+        event = %payload["type"],
+        "running hook"
+    );
+
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("failed to spawn `{program}`: {err}"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = payload.to_string();
+        // A hook that ignores stdin may exit before reading it; that is fine.
+        let _ = stdin.write_all(input.as_bytes()).await;
+    }
+
+    let timeout = entry.timeout();
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => output.map_err(|err| format!("failed to wait for hook: {err}")),
+        Err(_) => Err(format!("timed out after {}ms", timeout.as_millis())),
+    }
+}
+
+fn parse_verdict(code: Option<i32>, stdout: &str, stderr: &str) -> Result<HookVerdict, String> {
+    match code {
+        Some(0) => {}
+        Some(BLOCK_EXIT_CODE) => {
+            let reason = stderr.trim();
+            return Ok(HookVerdict::Block(if reason.is_empty() {
+                "blocked by hook".to_string()
+            } else {
+                reason.to_string()
+            }));
+        }
+        Some(code) => return Err(format!("exited with code {code}: {}", stderr.trim())),
+        None => return Err("terminated by signal".to_string()),
+    }
+
+    // Plain-text output is just informational.
+    let Ok(Value::Object(decision)) = serde_json::from_str::<Value>(stdout.trim()) else {
+        return Ok(HookVerdict::Allow);
+    };
+    if decision.get("decision").and_then(Value::as_str) == Some("block") {
+        let reason = decision
+            .get("reason")
+            .and_then(Value::as_str)
+            .unwrap_or("blocked by hook");
+        return Ok(HookVerdict::Block(reason.to_string()));
+    }
+    match decision.get("updated_arguments") {
+        Some(Value::String(arguments)) => Ok(HookVerdict::Modify(arguments.clone())),
+        Some(Value::Null) | None => Ok(HookVerdict::Allow),
+        Some(arguments) => Ok(HookVerdict::Modify(arguments.to_string())),
+    }
+}
+
+fn parse_post_process(
+    code: Option<i32>,
+    stdout: &str,
+    stderr: &str,
+) -> Result<PostProcessOutput, String> {
+    match code {
+        Some(0) => {}
+        Some(code) => return Err(format!("exited with code {code}: {}", stderr.trim())),
+        None => return Err("terminated by signal".to_string()),
+    }
+    let stdout = stdout.trim();
+    if stdout.is_empty() {
+        return Ok(PostProcessOutput::default());
+    }
+    match serde_json::from_str::<Value>(stdout) {
+        Ok(value @ Value::Object(_)) => {
+            serde_json::from_value(value).map_err(|err| format!("invalid output: {err}"))
+        }
+        // Anything else is a note for the transcript.
+        _ => Ok(PostProcessOutput {
+            annotations: vec![stdout.to_string()],
+            ..Default::default()
+        }),
+    }
+}
+
+/// Writes `artifact` into `dir`. Names must be plain file names so a hook
+/// cannot write outside the thread's artifact directory.
+async fn write_artifact(dir: &Path, artifact: &HookArtifact) -> Result<PathBuf, String> {
+    let name = Path::new(&artifact.name);
+    if name.file_name() != Some(name.as_os_str()) {
+        return Err(format!(
+            "artifact name `{}` must be a plain file name",
+            artifact.name
+        ));
+    }
+    tokio::fs::create_dir_all(dir)
+        .await
+        .map_err(|err| format!("failed to create {}: {err}", dir.display()))?;
+    let path = dir.join(name);
+    tokio::fs::write(&path, &artifact.contents)
+        .await
+        .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn hook(when: HookEventKind, matcher: Option<&str>) -> HookConfig {
+        HookConfig {
+            id: None,
+            when: HookWhen::One(when),
+            command: vec!["true".to_string()],
+            timeout_ms: None,
+            blocking: false,
+            matcher: matcher.map(str::to_string),
+            include_tool_arguments: false,
+        }
+    }
+
+    #[test]
+    fn matcher_filters_on_subject() {
+        let hooks = Hooks::new(&[
+            hook(HookEventKind::ToolCallBegin, Some("^apply_patch$")),
+            hook(HookEventKind::ToolCallBegin, None),
+            hook(HookEventKind::ToolCallEnd, None),
+        ]);
+        let count = |subject| {
+            hooks
+                .matching(HookEventKind::ToolCallBegin, subject)
+                .count()
+        };
+        assert_eq!(count(Some("apply_patch")), 2);
+        assert_eq!(count(Some("shell")), 1);
+        assert_eq!(count(None), 2);
+    }
+
+    #[test]
+    fn invalid_matcher_is_skipped() {
+        let hooks = Hooks::new(&[hook(HookEventKind::TurnEnd, Some("("))]);
+        assert_eq!(hooks.entries.len(), 0);
+    }
+
+    #[test]
+    fn exit_codes_map_to_verdicts() {
+        assert_eq!(parse_verdict(Some(0), "", ""), Ok(HookVerdict::Allow));
+        assert_eq!(
+            parse_verdict(Some(2), "", "dirty tree\n"),
+            Ok(HookVerdict::Block("dirty tree".to_string()))
+        );
+        assert!(parse_verdict(Some(1), "", "boom").is_err());
+    }
+
+    #[test]
+    fn stdout_json_can_block_or_rewrite() {
+        assert_eq!(
+            parse_verdict(Some(0), r#"{"decision":"block","reason":"nope"}"#, ""),
+            Ok(HookVerdict::Block("nope".to_string()))
+        );
+        assert_eq!(
+            parse_verdict(Some(0), r#"{"updated_arguments":{"cmd":"ls"}}"#, ""),
+            Ok(HookVerdict::Modify(r#"{"cmd":"ls"}"#.to_string()))
+        );
+        assert_eq!(
+            parse_verdict(Some(0), "logged\n", ""),
+            Ok(HookVerdict::Allow)
+        );
+    }
+
+    #[test]
+    fn post_process_output_is_json_or_a_note() {
+        assert_eq!(
+            parse_post_process(
+                Some(0),
+                r#"{"annotations":["2 tests added"],"notifications":[{"title":"done"}]}"#,
+                ""
+            ),
+            Ok(PostProcessOutput {
+                annotations: vec!["2 tests added".to_string()],
+                artifacts: Vec::new(),
+                notifications: vec![HookNotification {
+                    title: "done".to_string(),
+                    body: None,
+                }],
+            })
+        );
+        assert_eq!(
+            parse_post_process(Some(0), "touched 3 files\n", ""),
+            Ok(PostProcessOutput {
+                annotations: vec!["touched 3 files".to_string()],
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse_post_process(Some(0), "", ""),
+            Ok(PostProcessOutput::default())
+        );
+        assert!(parse_post_process(Some(0), r#"{"annotations":"x"}"#, "").is_err());
+        assert!(parse_post_process(Some(1), "", "boom").is_err());
+    }
+
+    #[test]
+    fn effects_are_tracked_only_with_post_process_hooks() {
+        assert!(
+            Hooks::new(&[hook(HookEventKind::TurnEnd, None)])
+                .effects
+                .is_none()
+        );
+        assert!(
+            Hooks::new(&[hook(HookEventKind::TurnPostProcess, None)])
+                .effects
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn artifacts_stay_in_their_directory() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let artifact = |name: &str| HookArtifact {
+            name: name.to_string(),
+            contents: "report".to_string(),
+        };
+        let path = write_artifact(dir.path(), &artifact("report.md"))
+            .await
+            .expect("write artifact");
+        assert_eq!(path, dir.path().join("report.md"));
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), "report");
+        for name in ["../escape.md", "nested/report.md", ".."] {
+            assert!(write_artifact(dir.path(), &artifact(name)).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_command_with_payload_on_stdin() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let entry = HookEntry {
+            config: HookConfig {
+                command: vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    "grep -q tool.call.begin && echo '{\"decision\":\"block\",\"reason\":\"seen\"}'"
+                        .to_string(),
+                ],
+                ..hook(HookEventKind::ToolCallBegin, None)
+            },
+            matcher: None,
+        };
+        let payload = json!({ "type": HookEventKind::ToolCallBegin });
+        assert_eq!(
+            run_hook(&entry, &payload, dir.path()).await,
+            Ok(HookVerdict::Block("seen".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn slow_hook_times_out() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let entry = HookEntry {
+            config: HookConfig {
+                command: vec!["sleep".to_string(), "5".to_string()],
+                timeout_ms: Some(50),
+                ..hook(HookEventKind::TurnEnd, None)
+            },
+            matcher: None,
+        };
+        assert!(run_hook(&entry, &json!({}), dir.path()).await.is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! User input queued via `Op::QueueUserInput` while a turn is running.
+//!
+//! Each queued input runs as its own turn, in order: when a turn completes the
+//! next entry is resubmitted to the session's submission loop as
+//! `Op::UserInput`, so it starts exactly like input typed after the turn.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use async_channel::WeakSender;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::Op;
+use codex_protocol::protocol::QueuedUserInput;
+use codex_protocol::protocol::QueuedUserInputsEvent;
+use codex_protocol::protocol::Submission;
+use codex_protocol::user_input::UserInput;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::codex::Session;
+
+pub(crate) struct InputQueue {
+    inputs: Mutex<VecDeque<QueuedUserInput>>,
+    /// Weak so the queue does not keep the submission loop alive after the
+    /// owning `Codex` is dropped.
+    tx_sub: WeakSender<Submission>,
+}
+
+impl InputQueue {
+    pub(crate) fn new(tx_sub: WeakSender<Submission>) -> Self {
+        Self {
+            inputs: Mutex::new(VecDeque::new()),
+            tx_sub,
+        }
+    }
+
+    async fn push(&self, input: QueuedUserInput) {
+        self.inputs.lock().await.push_back(input);
+    }
+
+    async fn pop_front(&self) -> Option<QueuedUserInput> {
+        self.inputs.lock().await.pop_front()
+    }
+
+    async fn clear(&self) {
+        self.inputs.lock().await.clear();
+    }
+
+    async fn snapshot(&self) -> Vec<QueuedUserInput> {
+        self.inputs.lock().await.iter().cloned().collect()
+    }
+
+    /// Resubmits the next queued input to the submission loop and returns its
+    /// id, or `None` when the queue is empty or the loop has gone away.
+    async fn submit_next(&self) -> Option<String> {
+        let sender = self.tx_sub.upgrade()?;
+        let next = self.pop_front().await?;
+        let id = next.id;
+        let submission = Submission {
+            id: id.clone(),
+            op: Op::UserInput {
+                items: next.items,
+                final_output_json_schema: None,
+            },
+        };
+        if let Err(err) = sender.send(submission).await {
+            warn!("failed to start queued user input {id}: {err}");
+            return None;
+        }
+        Some(id)
+    }
+}
+
+/// Queues `items` behind the running turn. When no turn is running, returns
+/// the input that should start a turn right away: the oldest queued entry,
+/// which is `items` itself unless earlier input is still waiting (for example
+/// after an interrupt).
+pub(crate) async fn queue_user_input(
+    sess: &Session,
+    sub_id: String,
+    items: Vec<UserInput>,
+) -> Option<QueuedUserInput> {
+    let queue = &sess.services.input_queue;
+    queue
+        .push(QueuedUserInput {
+            id: sub_id.clone(),
+            items,
+        })
+        .await;
+
+    let has_active_turn = { sess.active_turn.lock().await.is_some() };
+    let next = if has_active_turn {
+        None
+    } else {
+        queue.pop_front().await
+    };
+    send_queued_inputs(sess, sub_id).await;
+    next
+}
+
+/// Puts inputs that were waiting when the thread was last running back in
+/// the queue after a resume. Like input left over after an interrupt, the
+/// oldest one runs when the next input is queued or the next turn completes.
+pub(crate) async fn restore_queued_user_inputs(
+    sess: &Session,
+    sub_id: String,
+    inputs: Vec<QueuedUserInput>,
+) {
+    for input in inputs {
+        sess.services.input_queue.push(input).await;
+    }
+    send_queued_inputs(sess, sub_id).await;
+}
+
+pub(crate) async fn list_queued_user_inputs(sess: &Session, sub_id: String) {
+    send_queued_inputs(sess, sub_id).await;
+}
+
+pub(crate) async fn clear_queued_user_inputs(sess: &Session, sub_id: String) {
+    sess.services.input_queue.clear().await;
+    send_queued_inputs(sess, sub_id).await;
+}
+
+/// Starts the next queued input, if any, after a turn has completed.
+pub(crate) async fn start_next_queued_input(sess: &Arc<Session>) {
+    if let Some(sub_id) = sess.services.input_queue.submit_next().await {
+        send_queued_inputs(sess, sub_id).await;
+    }
+}
+
+async fn send_queued_inputs(sess: &Session, sub_id: String) {
+    let inputs = sess.services.input_queue.snapshot().await;
+    sess.send_event_raw(Event {
+        id: sub_id,
+        msg: EventMsg::QueuedUserInputs(QueuedUserInputsEvent { inputs }),
+    })
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn queued(id: &str, text: &str) -> QueuedUserInput {
+        QueuedUserInput {
+            id: id.to_string(),
+            items: vec![UserInput::Text {
+                text: text.to_string(),
+                text_elements: Vec::new(),
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn submit_next_resubmits_oldest_input_as_user_input() {
+        let (tx_sub, rx_sub) = async_channel::unbounded();
+        let queue = InputQueue::new(tx_sub.downgrade());
+        queue.push(queued("first", "one")).await;
+        queue.push(queued("second", "two")).await;
+
+        assert_eq!(queue.submit_next().await, Some("first".to_string()));
+
+        let submission = rx_sub.recv().await.expect("submission");
+        assert_eq!(submission.id, "first");
+        let Op::UserInput {
+            items,
+            final_output_json_schema,
+        } = submission.op
+        else {
+            panic!("expected Op::UserInput");
+        };
+        assert_eq!(items, queued("first", "one").items);
+        assert_eq!(final_output_json_schema, None);
+        assert_eq!(queue.snapshot().await, vec![queued("second", "two")]);
+    }
+
+    #[tokio::test]
+    async fn submit_next_keeps_input_when_loop_is_gone() {
+        let (tx_sub, rx_sub) = async_channel::unbounded::<Submission>();
+        let queue = InputQueue::new(tx_sub.downgrade());
+        drop(tx_sub);
+        drop(rx_sub);
+        queue.push(queued("first", "one")).await;
+
+        assert_eq!(queue.submit_next().await, None);
+        assert_eq!(queue.snapshot().await, vec![queued("first", "one")]);
+    }
+}
@@ -8,48 +8,95 @@
 pub mod accounts;
 pub mod api_bridge;
 mod apply_patch;
+mod approval_risk;
+pub mod audit;
 pub mod auth;
+mod background_tasks;
 pub mod bash;
+mod browser;
+pub mod capabilities;
+mod checkpoints;
+pub mod ci;
 mod client;
 mod client_common;
+pub mod code_outline;
 pub mod codex;
 mod codex_thread;
+mod command_explanation;
+mod command_rules;
+pub mod commit_plan;
 mod compact_remote;
+mod compact_strategies;
+mod container_sandbox;
+mod conversation_fork;
 pub use codex_thread::CodexThread;
 mod agent;
 mod codex_delegate;
 mod command_safety;
 pub mod config;
 pub mod config_loader;
+mod context_inspector;
 mod context_manager;
+mod context_providers;
+mod cost;
+mod custom_commands;
 pub mod custom_prompts;
+pub mod doctor;
+mod editor_context;
 pub mod env;
 mod environment_context;
 pub mod error;
 pub mod exec;
 pub mod exec_env;
+pub mod exec_limits;
 mod exec_policy;
+mod external_edits;
 pub mod features;
 mod flags;
+mod git_guardrails;
 pub mod git_info;
+mod governor;
+mod guardrail;
+mod hooks;
+mod input_queue;
 pub mod instructions;
 pub mod landlock;
 pub mod mcp;
 mod mcp_connection_manager;
+mod mcp_prompts;
+mod mcp_resource_mentions;
+mod mcp_sampling;
 pub mod models_manager;
 pub use mcp_connection_manager::MCP_SANDBOX_STATE_CAPABILITY;
 pub use mcp_connection_manager::MCP_SANDBOX_STATE_METHOD;
 pub use mcp_connection_manager::SandboxState;
 mod mcp_tool_call;
 mod message_history;
+mod mock_provider;
 mod model_provider_info;
+mod network_allowlist;
+mod network_capture;
+mod next_edit;
+pub mod offline;
 pub mod parse_command;
+mod patch_preview;
 pub mod path_utils;
+mod pinned_context;
+mod plan_mode;
+mod plan_scope;
+mod plugin_tools;
 pub mod powershell;
+pub mod pre_commit;
+pub mod sandbox_capabilities;
 pub mod sandboxing;
+pub mod schedules;
 mod session_prefix;
+mod session_tmp;
 mod stream_events_utils;
+mod symbol_mentions;
+mod test_failures;
 mod text_encoding;
+mod text_tool_calls;
 pub mod token_data;
 mod truncate;
 mod unified_exec;
@@ -82,24 +129,41 @@ pub use auth::AuthManager;
 pub use auth::CodexAuth;
 pub mod default_client;
 pub mod project_doc;
+mod project_glossary;
+mod project_memory;
+mod project_toolchain;
+mod resume_briefing;
 mod rollout;
+mod runtime_state;
 pub(crate) mod safety;
 pub mod seatbelt;
+pub mod semantic_index;
 pub mod shell;
 pub mod shell_snapshot;
 pub mod skills;
 pub mod spawn;
+pub mod subagents;
 pub mod terminal;
 mod tools;
 pub mod turn_diff_tracker;
+mod web_fetch;
+mod web_search;
+mod workspace_files;
+mod worktree_isolation;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::INTERACTIVE_SESSION_SOURCES;
 pub use rollout::RolloutRecorder;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
+pub use rollout::archive_rollout;
+pub use rollout::export as session_export;
+pub use rollout::find_archived_thread_path_by_id_str;
 #[deprecated(note = "use find_thread_path_by_id_str")]
 pub use rollout::find_conversation_path_by_id_str;
 pub use rollout::find_thread_path_by_id_str;
+pub use rollout::import as history_import;
+pub use rollout::index as session_index;
+pub use rollout::labels as thread_labels;
 pub use rollout::list::Cursor;
 pub use rollout::list::ThreadItem;
 pub use rollout::list::ThreadSortKey;
@@ -107,6 +171,10 @@ pub use rollout::list::ThreadsPage;
 pub use rollout::list::parse_cursor;
 pub use rollout::list::read_head_for_summary;
 pub use rollout::list::read_session_meta_line;
+pub use rollout::search as thread_search;
+pub use rollout::share as session_share;
+pub use rollout::stats as thread_stats;
+pub use rollout::transfer as session_transfer;
 mod function_tool;
 mod state;
 mod tasks;
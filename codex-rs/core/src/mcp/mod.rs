@@ -4,7 +4,9 @@ use std::env;
 use std::path::PathBuf;
 
 use async_channel::unbounded;
+use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::McpListToolsResponseEvent;
+use codex_protocol::protocol::McpStartupCompleteEvent;
 use codex_protocol::protocol::SandboxPolicy;
 use mcp_types::Tool as McpTool;
 use tokio_util::sync::CancellationToken;
@@ -23,6 +25,7 @@ pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent
             tools: HashMap::new(),
             resources: HashMap::new(),
             resource_templates: HashMap::new(),
+            prompts: HashMap::new(),
             auth_statuses: HashMap::new(),
         };
     }
@@ -64,6 +67,60 @@ pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent
     snapshot
 }
 
+/// Starts the enabled MCP servers the way a session would and waits for all
+/// of them to finish starting. Returns the startup summary and the number of
+/// tools each ready server exposes.
+pub async fn probe_mcp_servers(
+    config: &Config,
+) -> (McpStartupCompleteEvent, HashMap<String, usize>) {
+    if config.mcp_servers.is_empty() {
+        return (McpStartupCompleteEvent::default(), HashMap::new());
+    }
+
+    let auth_status_entries = compute_auth_statuses(
+        config.mcp_servers.iter(),
+        config.mcp_oauth_credentials_store_mode,
+    )
+    .await;
+
+    let mut mcp_connection_manager = McpConnectionManager::default();
+    let (tx_event, rx_event) = unbounded();
+    let cancel_token = CancellationToken::new();
+    let sandbox_state = SandboxState {
+        sandbox_policy: SandboxPolicy::ReadOnly,
+        codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
+        sandbox_cwd: env::current_dir().unwrap_or_else(|_| PathBuf::from("/")),
+    };
+
+    mcp_connection_manager
+        .initialize(
+            &config.mcp_servers,
+            config.mcp_oauth_credentials_store_mode,
+            auth_status_entries,
+            tx_event,
+            cancel_token.clone(),
+            sandbox_state,
+        )
+        .await;
+
+    let mut summary = McpStartupCompleteEvent::default();
+    while let Ok(event) = rx_event.recv().await {
+        if let EventMsg::McpStartupComplete(complete) = event.msg {
+            summary = complete;
+            break;
+        }
+    }
+
+    let mut tool_counts = HashMap::new();
+    for tool in mcp_connection_manager.list_all_tools().await.into_values() {
+        *tool_counts.entry(tool.server_name).or_insert(0) += 1;
+    }
+
+    cancel_token.cancel();
+
+    (summary, tool_counts)
+}
+
 pub fn split_qualified_tool_name(qualified_name: &str) -> Option<(String, String)> {
     let mut parts = qualified_name.split(MCP_TOOL_NAME_DELIMITER);
     let prefix = parts.next()?;
@@ -97,10 +154,11 @@ pub(crate) async fn collect_mcp_snapshot_from_manager(
     mcp_connection_manager: &McpConnectionManager,
     auth_status_entries: HashMap<String, crate::mcp::auth::McpAuthStatusEntry>,
 ) -> McpListToolsResponseEvent {
-    let (tools, resources, resource_templates) = tokio::join!(
+    let (tools, resources, resource_templates, prompts) = tokio::join!(
         mcp_connection_manager.list_all_tools(),
         mcp_connection_manager.list_all_resources(),
         mcp_connection_manager.list_all_resource_templates(),
+        mcp_connection_manager.list_all_prompts(),
     );
 
     let auth_statuses = auth_status_entries
@@ -115,6 +173,7 @@ pub(crate) async fn collect_mcp_snapshot_from_manager(
             .collect(),
         resources,
         resource_templates,
+        prompts,
         auth_statuses,
     }
 }
@@ -24,6 +24,8 @@ use codex_async_utils::OrCancelExt;
 use codex_protocol::approvals::ElicitationRequestEvent;
 use codex_protocol::protocol::Event;
 use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::McpServerHealthEvent;
+use codex_protocol::protocol::McpServerHealthStatus;
 use codex_protocol::protocol::McpStartupCompleteEvent;
 use codex_protocol::protocol::McpStartupFailure;
 use codex_protocol::protocol::McpStartupStatus;
@@ -33,20 +35,26 @@ use codex_rmcp_client::ElicitationResponse;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_rmcp_client::RmcpClient;
 use codex_rmcp_client::SendElicitation;
+use codex_rmcp_client::is_transport_closed;
 use futures::future::BoxFuture;
 use futures::future::FutureExt;
 use futures::future::Shared;
 use mcp_types::ClientCapabilities;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::Implementation;
+use mcp_types::ListPromptsRequestParams;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
 use mcp_types::ListResourcesResult;
+use mcp_types::Prompt;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
 use mcp_types::Resource;
 use mcp_types::ResourceTemplate;
+use mcp_types::SubscribeRequestParams;
 use mcp_types::Tool;
 
 use serde::Deserialize;
@@ -58,12 +66,17 @@ use tokio::sync::Mutex;
 use tokio::sync::oneshot;
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
+use tokio_util::sync::DropGuard;
 use tracing::instrument;
 use tracing::warn;
 
 use crate::codex::INITIAL_SUBMIT_ID;
+use crate::config::types::McpHealthConfig;
 use crate::config::types::McpServerConfig;
+use crate::config::types::McpServerDisabledReason;
 use crate::config::types::McpServerTransportConfig;
+use crate::config::types::McpToolApproval;
+use crate::mcp_sampling::McpSampler;
 
 /// Delimiter used to separate the server name from the tool name in a fully
 /// qualified tool name.
@@ -79,6 +92,13 @@ pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
 /// Default timeout for individual tool calls.
 const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Delay before the first restart of an unresponsive server; doubled for each
+/// further attempt.
+const MCP_RESTART_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound for the delay between restart attempts.
+const MCP_RESTART_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
 /// The Responses API requires tool names to match `^[a-zA-Z0-9_-]+$`.
 /// MCP server/tool names are user-controlled, so sanitize the fully-qualified
 /// name we expose to the model by replacing any disallowed character with `_`.
@@ -158,7 +178,7 @@ pub(crate) struct ToolInfo {
 type ResponderMap = HashMap<(String, RequestId), oneshot::Sender<ElicitationResponse>>;
 
 #[derive(Clone, Default)]
-struct ElicitationRequestManager {
+pub(crate) struct ElicitationRequestManager {
     requests: Arc<Mutex<ResponderMap>>,
 }
 
@@ -178,30 +198,44 @@ impl ElicitationRequestManager {
             .map_err(|e| anyhow!("failed to send elicitation response: {e:?}"))
     }
 
+    /// Shows `message` to the user as an elicitation and waits for the
+    /// matching `Op::ResolveElicitation`.
+    pub(crate) async fn request(
+        &self,
+        server_name: String,
+        id: RequestId,
+        message: String,
+        tx_event: Sender<Event>,
+    ) -> Result<ElicitationResponse> {
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut lock = self.requests.lock().await;
+            lock.insert((server_name.clone(), id.clone()), tx);
+        }
+        let _ = tx_event
+            .send(Event {
+                id: "mcp_elicitation_request".to_string(),
+                msg: EventMsg::ElicitationRequest(ElicitationRequestEvent {
+                    server_name,
+                    id,
+                    message,
+                }),
+            })
+            .await;
+        rx.await
+            .context("elicitation request channel closed unexpectedly")
+    }
+
     fn make_sender(&self, server_name: String, tx_event: Sender<Event>) -> SendElicitation {
-        let elicitation_requests = self.requests.clone();
+        let elicitation_requests = self.clone();
         Box::new(move |id, elicitation| {
             let elicitation_requests = elicitation_requests.clone();
             let tx_event = tx_event.clone();
             let server_name = server_name.clone();
             async move {
-                let (tx, rx) = oneshot::channel();
-                {
-                    let mut lock = elicitation_requests.lock().await;
-                    lock.insert((server_name.clone(), id.clone()), tx);
-                }
-                let _ = tx_event
-                    .send(Event {
-                        id: "mcp_elicitation_request".to_string(),
-                        msg: EventMsg::ElicitationRequest(ElicitationRequestEvent {
-                            server_name,
-                            id,
-                            message: elicitation.message,
-                        }),
-                    })
-                    .await;
-                rx.await
-                    .context("elicitation request channel closed unexpectedly")
+                elicitation_requests
+                    .request(server_name, id, elicitation.message, tx_event)
+                    .await
             }
             .boxed()
         })
@@ -215,6 +249,8 @@ struct ManagedClient {
     tool_filter: ToolFilter,
     tool_timeout: Option<Duration>,
     server_supports_sandbox_state_capability: bool,
+    server_supports_resource_subscriptions: bool,
+    server_supports_prompts: bool,
 }
 
 impl ManagedClient {
@@ -235,20 +271,26 @@ impl ManagedClient {
     }
 }
 
-#[derive(Clone)]
-struct AsyncManagedClient {
-    client: Shared<BoxFuture<'static, Result<ManagedClient, StartupOutcomeError>>>,
+type StartupFuture = Shared<BoxFuture<'static, Result<ManagedClient, StartupOutcomeError>>>;
+
+/// Everything needed to start (or restart) one server's client.
+struct ClientStartup {
+    server_name: String,
+    config: McpServerConfig,
+    store_mode: OAuthCredentialsStoreMode,
+    tx_event: Sender<Event>,
+    elicitation_requests: ElicitationRequestManager,
+    sampler: Option<Arc<McpSampler>>,
 }
 
-impl AsyncManagedClient {
-    fn new(
-        server_name: String,
-        config: McpServerConfig,
-        store_mode: OAuthCredentialsStoreMode,
-        cancel_token: CancellationToken,
-        tx_event: Sender<Event>,
-        elicitation_requests: ElicitationRequestManager,
-    ) -> Self {
+impl ClientStartup {
+    fn start(&self, cancel_token: CancellationToken) -> StartupFuture {
+        let server_name = self.server_name.clone();
+        let config = self.config.clone();
+        let store_mode = self.store_mode;
+        let tx_event = self.tx_event.clone();
+        let elicitation_requests = self.elicitation_requests.clone();
+        let sampler = self.sampler.clone();
         let tool_filter = ToolFilter::from_config(&config);
         let fut = async move {
             if let Err(error) = validate_mcp_server_name(&server_name) {
@@ -265,6 +307,7 @@ impl AsyncManagedClient {
                 tool_filter,
                 tx_event,
                 elicitation_requests,
+                sampler,
             )
             .or_cancel(&cancel_token)
             .await
@@ -273,13 +316,104 @@ impl AsyncManagedClient {
                 Err(CancelErr::Cancelled) => Err(StartupOutcomeError::Cancelled),
             }
         };
+        fut.boxed().shared()
+    }
+}
+
+#[derive(Clone)]
+struct AsyncManagedClient {
+    startup: Arc<ClientStartup>,
+    /// Replaced with a fresh startup when the health monitor restarts the
+    /// server.
+    client: Arc<std::sync::RwLock<StartupFuture>>,
+    /// Held while reconnecting so concurrent requests that saw the same
+    /// closed connection restart the server only once.
+    reconnecting: Arc<tokio::sync::Mutex<()>>,
+    /// Stops the health monitor and reconnects when the server is removed.
+    stop: CancellationToken,
+}
+
+impl AsyncManagedClient {
+    fn new(
+        server_name: String,
+        config: McpServerConfig,
+        store_mode: OAuthCredentialsStoreMode,
+        cancel_token: CancellationToken,
+        tx_event: Sender<Event>,
+        elicitation_requests: ElicitationRequestManager,
+        sampler: Option<Arc<McpSampler>>,
+        stop: CancellationToken,
+    ) -> Self {
+        let startup = ClientStartup {
+            server_name,
+            config,
+            store_mode,
+            tx_event,
+            elicitation_requests,
+            sampler,
+        };
+        let client = startup.start(cancel_token);
         Self {
-            client: fut.boxed().shared(),
+            startup: Arc::new(startup),
+            client: Arc::new(std::sync::RwLock::new(client)),
+            reconnecting: Arc::default(),
+            stop,
         }
     }
 
     async fn client(&self) -> Result<ManagedClient, StartupOutcomeError> {
-        self.client.clone().await
+        let client = match self.client.read() {
+            Ok(client) => client.clone(),
+            Err(err) => err.into_inner().clone(),
+        };
+        client.await
+    }
+
+    /// Starts a new connection to the server in place of the current one and
+    /// waits for it to become ready. Callers see the new connection (or its
+    /// startup error) as soon as this is called.
+    async fn restart(&self, cancel_token: CancellationToken) -> Result<(), StartupOutcomeError> {
+        let client = self.startup.start(cancel_token);
+        match self.client.write() {
+            Ok(mut current) => *current = client,
+            Err(err) => *err.into_inner() = client,
+        }
+        self.client().await.map(|_| ())
+    }
+
+    /// Replaces `stale`, whose connection closed with `error`, with a new
+    /// connection unless a concurrent request already did, and returns the
+    /// current one.
+    async fn reconnect(
+        &self,
+        stale: &ManagedClient,
+        error: String,
+        sandbox_state: &std::sync::Mutex<Option<SandboxState>>,
+        cancel_token: CancellationToken,
+    ) -> Result<ManagedClient, StartupOutcomeError> {
+        let _reconnecting = self.reconnecting.lock().await;
+        if let Ok(current) = self.client().await
+            && !Arc::ptr_eq(&current.client, &stale.client)
+        {
+            return Ok(current);
+        }
+
+        let server = self.startup.server_name.as_str();
+        let tx_event = &self.startup.tx_event;
+        emit_health(
+            tx_event,
+            server,
+            McpServerHealthStatus::Degraded {
+                error,
+                restart_attempt: 1,
+                retry_in_ms: 0,
+            },
+        )
+        .await;
+        self.restart(cancel_token).await?;
+        emit_health(tx_event, server, McpServerHealthStatus::Recovered).await;
+        resend_sandbox_state(self, sandbox_state).await;
+        self.client().await
     }
 
     async fn notify_sandbox_state_change(&self, sandbox_state: &SandboxState) -> Result<()> {
@@ -307,9 +441,37 @@ pub struct SandboxState {
 pub(crate) struct McpConnectionManager {
     clients: HashMap<String, AsyncManagedClient>,
     elicitation_requests: ElicitationRequestManager,
+    sampler: Option<Arc<McpSampler>>,
+    health: Option<McpHealthConfig>,
+    /// Last sandbox state sent to the servers, re-sent after a restart.
+    sandbox_state: Arc<std::sync::Mutex<Option<SandboxState>>>,
+    /// Stops the health monitors when the manager is dropped or replaced.
+    health_monitors: Option<DropGuard>,
+    /// Parent of each server's `stop` token; cancelled by `health_monitors`.
+    servers_token: CancellationToken,
 }
 
 impl McpConnectionManager {
+    /// Serves `sampling/createMessage` for servers that `sampler` allows.
+    /// Must be called before [`McpConnectionManager::initialize`].
+    pub(crate) fn set_sampler(&mut self, sampler: Arc<McpSampler>) {
+        self.sampler = Some(sampler);
+    }
+
+    pub(crate) fn sampler(&self) -> Option<Arc<McpSampler>> {
+        self.sampler.clone()
+    }
+
+    /// Pings running servers and restarts unresponsive ones as `health`
+    /// describes. Must be called before [`McpConnectionManager::initialize`].
+    pub(crate) fn set_health_config(&mut self, health: McpHealthConfig) {
+        self.health = Some(health);
+    }
+
+    pub(crate) fn health_config(&self) -> Option<McpHealthConfig> {
+        self.health.clone()
+    }
+
     pub async fn initialize(
         &mut self,
         mcp_servers: &HashMap<String, McpServerConfig>,
@@ -325,6 +487,32 @@ impl McpConnectionManager {
         let mut clients = HashMap::new();
         let mut join_set = JoinSet::new();
         let elicitation_requests = ElicitationRequestManager::default();
+        set_sandbox_state(&self.sandbox_state, &initial_sandbox_state);
+        let health_token = CancellationToken::new();
+        self.health_monitors = Some(health_token.clone().drop_guard());
+        self.servers_token = health_token.child_token();
+        // Servers turned off by offline mode are reported as failed so the
+        // user sees why they are missing.
+        let mut offline_failures = Vec::new();
+        for (server_name, cfg) in mcp_servers {
+            if let Some(reason @ McpServerDisabledReason::Offline(_)) = &cfg.disabled_reason {
+                let error = reason.to_string();
+                let _ = emit_update(
+                    &tx_event,
+                    McpStartupUpdateEvent {
+                        server: server_name.clone(),
+                        status: McpStartupStatus::Failed {
+                            error: error.clone(),
+                        },
+                    },
+                )
+                .await;
+                offline_failures.push(McpStartupFailure {
+                    server: server_name.clone(),
+                    error,
+                });
+            }
+        }
         let mcp_servers = mcp_servers.clone();
         for (server_name, cfg) in mcp_servers.into_iter().filter(|(_, cfg)| cfg.enabled) {
             let cancel_token = cancel_token.child_token();
@@ -343,11 +531,18 @@ impl McpConnectionManager {
                 cancel_token.clone(),
                 tx_event.clone(),
                 elicitation_requests.clone(),
+                self.sampler.clone(),
+                self.servers_token.child_token(),
             );
             clients.insert(server_name.clone(), async_managed_client.clone());
             let tx_event = tx_event.clone();
             let auth_entry = auth_entries.get(&server_name).cloned();
             let sandbox_state = initial_sandbox_state.clone();
+            let health = self
+                .health
+                .clone()
+                .filter(|health| health.ping_interval_sec > 0);
+            let latest_sandbox_state = Arc::clone(&self.sandbox_state);
             join_set.spawn(async move {
                 let outcome = async_managed_client.client().await;
                 if cancel_token.is_cancelled() {
@@ -364,6 +559,13 @@ impl McpConnectionManager {
                                 "Failed to notify sandbox state to MCP server {server_name}: {e:#}",
                             );
                         }
+                        if let Some(health) = health {
+                            tokio::spawn(monitor_health(
+                                async_managed_client.clone(),
+                                health,
+                                latest_sandbox_state,
+                            ));
+                        }
                         McpStartupStatus::Ready
                     }
                     Err(error) => {
@@ -392,7 +594,10 @@ impl McpConnectionManager {
         self.elicitation_requests = elicitation_requests.clone();
         tokio::spawn(async move {
             let outcomes = join_set.join_all().await;
-            let mut summary = McpStartupCompleteEvent::default();
+            let mut summary = McpStartupCompleteEvent {
+                failed: offline_failures,
+                ..Default::default()
+            };
             for (server_name, outcome) in outcomes {
                 match outcome {
                     Ok(_) => summary.ready.push(server_name),
@@ -414,6 +619,73 @@ impl McpConnectionManager {
         });
     }
 
+    /// Starts `server_name` next to the running servers, replacing a server
+    /// with the same name. The returned future resolves once it is ready,
+    /// with the fully qualified names of its tools, or with a user-facing
+    /// startup error; it does not borrow the manager, so callers can release
+    /// their lock before awaiting it.
+    pub(crate) fn add_server(
+        &mut self,
+        server_name: String,
+        config: McpServerConfig,
+        store_mode: OAuthCredentialsStoreMode,
+        auth_entry: Option<McpAuthStatusEntry>,
+        tx_event: Sender<Event>,
+        cancel_token: CancellationToken,
+    ) -> BoxFuture<'static, Result<Vec<String>, String>> {
+        self.remove_server(&server_name);
+        let client = AsyncManagedClient::new(
+            server_name.clone(),
+            config,
+            store_mode,
+            cancel_token,
+            tx_event,
+            self.elicitation_requests.clone(),
+            self.sampler.clone(),
+            self.servers_token.child_token(),
+        );
+        self.clients.insert(server_name.clone(), client.clone());
+        let health = self
+            .health
+            .clone()
+            .filter(|health| health.ping_interval_sec > 0);
+        let sandbox_state = Arc::clone(&self.sandbox_state);
+        async move {
+            let managed = client
+                .client()
+                .await
+                .map_err(|err| mcp_init_error_display(&server_name, auth_entry.as_ref(), &err))?;
+            if client.stop.is_cancelled() {
+                return Err(format!(
+                    "MCP server `{server_name}` was removed before it finished starting"
+                ));
+            }
+            resend_sandbox_state(&client, &sandbox_state).await;
+            if let Some(health) = health {
+                tokio::spawn(monitor_health(client, health, sandbox_state));
+            }
+            let mut tools: Vec<String> =
+                qualify_tools(filter_tools(managed.tools, managed.tool_filter))
+                    .into_keys()
+                    .collect();
+            tools.sort();
+            Ok(tools)
+        }
+        .boxed()
+    }
+
+    /// Stops `server_name` and drops its tools. Returns `false` when no such
+    /// server is running.
+    pub(crate) fn remove_server(&mut self, server_name: &str) -> bool {
+        match self.clients.remove(server_name) {
+            Some(client) => {
+                client.stop.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
     async fn client_by_name(&self, name: &str) -> Result<ManagedClient> {
         self.clients
             .get(name)
@@ -423,6 +695,38 @@ impl McpConnectionManager {
             .context("failed to get client")
     }
 
+    /// Opens a new connection to a streamable HTTP server whose previous one
+    /// closed, e.g. because the server expired the session. Other errors, and
+    /// closed stdio servers (left to the health monitor), are returned as is.
+    async fn reconnect_if_closed(
+        &self,
+        server: &str,
+        stale: &ManagedClient,
+        err: anyhow::Error,
+    ) -> Result<ManagedClient> {
+        let Some(async_client) = self.clients.get(server) else {
+            return Err(err);
+        };
+        let is_http = matches!(
+            async_client.startup.config.transport,
+            McpServerTransportConfig::StreamableHttp { .. }
+        );
+        if !is_http || !is_transport_closed(&err) {
+            return Err(err);
+        }
+        let error = format!("{err:#}");
+        warn!("MCP server {server} closed the connection; reconnecting: {error}");
+        async_client
+            .reconnect(
+                stale,
+                error.clone(),
+                &self.sandbox_state,
+                async_client.stop.child_token(),
+            )
+            .await
+            .map_err(|reconnect_err| anyhow!("{error}; reconnecting failed: {reconnect_err}"))
+    }
+
     pub async fn resolve_elicitation(
         &self,
         server_name: String,
@@ -515,6 +819,72 @@ impl McpConnectionManager {
         aggregated
     }
 
+    /// Returns the prompts of every server that advertises the `prompts`
+    /// capability, keyed by server name.
+    pub async fn list_all_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        let mut join_set = JoinSet::new();
+
+        for (server_name, async_managed_client) in &self.clients {
+            let server_name = server_name.clone();
+            let Ok(managed_client) = async_managed_client.client().await else {
+                continue;
+            };
+            if !managed_client.server_supports_prompts {
+                continue;
+            }
+            let timeout = managed_client.tool_timeout;
+            let client = managed_client.client.clone();
+
+            join_set.spawn(async move {
+                let mut collected: Vec<Prompt> = Vec::new();
+                let mut cursor: Option<String> = None;
+
+                loop {
+                    let params = cursor.as_ref().map(|next| ListPromptsRequestParams {
+                        cursor: Some(next.clone()),
+                    });
+                    let response = match client.list_prompts(params, timeout).await {
+                        Ok(result) => result,
+                        Err(err) => return (server_name, Err(err)),
+                    };
+
+                    collected.extend(response.prompts);
+
+                    match response.next_cursor {
+                        Some(next) => {
+                            if cursor.as_ref() == Some(&next) {
+                                return (
+                                    server_name,
+                                    Err(anyhow!("prompts/list returned duplicate cursor")),
+                                );
+                            }
+                            cursor = Some(next);
+                        }
+                        None => return (server_name, Ok(collected)),
+                    }
+                }
+            });
+        }
+
+        let mut aggregated: HashMap<String, Vec<Prompt>> = HashMap::new();
+
+        while let Some(join_res) = join_set.join_next().await {
+            match join_res {
+                Ok((server_name, Ok(prompts))) => {
+                    aggregated.insert(server_name, prompts);
+                }
+                Ok((server_name, Err(err))) => {
+                    warn!("Failed to list prompts for MCP server '{server_name}': {err:#}");
+                }
+                Err(err) => {
+                    warn!("Task panic when listing prompts for MCP server: {err:#}");
+                }
+            }
+        }
+
+        aggregated
+    }
+
     /// Returns a single map that contains all resource templates. Each key is the
     /// server name and the value is a vector of resource templates.
     pub async fn list_all_resource_templates(&self) -> HashMap<String, Vec<ResourceTemplate>> {
@@ -600,11 +970,21 @@ impl McpConnectionManager {
             ));
         }
 
-        client
+        let err = match client
             .client
             .call_tool(tool.to_string(), arguments, client.tool_timeout)
             .await
-            .with_context(|| format!("tool call failed for `{server}/{tool}`"))
+        {
+            Ok(result) => return Ok(result),
+            Err(err) => err.context(format!("tool call failed for `{server}/{tool}`")),
+        };
+        // The call is not repeated: it may have reached the server before the
+        // connection closed, and tools can have side effects.
+        self.reconnect_if_closed(server, &client, err).await?;
+        Err(anyhow!(
+            "the connection to MCP server `{server}` closed during the call to `{tool}` and has been re-established; \
+             the call may not have run, so call the tool again if it is still needed"
+        ))
     }
 
     /// List resources from the specified server.
@@ -646,16 +1026,93 @@ impl McpConnectionManager {
         params: ReadResourceRequestParams,
     ) -> Result<ReadResourceResult> {
         let managed = self.client_by_name(server).await?;
-        let client = managed.client.clone();
-        let timeout = managed.tool_timeout;
         let uri = params.uri.clone();
 
-        client
-            .read_resource(params, timeout)
+        let err = match managed
+            .client
+            .read_resource(params.clone(), managed.tool_timeout)
+            .await
+        {
+            Ok(result) => return Ok(result),
+            Err(err) => err,
+        };
+        let managed = self
+            .reconnect_if_closed(server, &managed, err)
+            .await
+            .with_context(|| format!("resources/read failed for `{server}` ({uri})"))?;
+        managed
+            .client
+            .read_resource(params, managed.tool_timeout)
             .await
             .with_context(|| format!("resources/read failed for `{server}` ({uri})"))
     }
 
+    /// Fetch a prompt, with its arguments filled in, from the specified server.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        params: GetPromptRequestParams,
+    ) -> Result<GetPromptResult> {
+        let managed = self.client_by_name(server).await?;
+        let name = params.name.clone();
+
+        let err = match managed
+            .client
+            .get_prompt(params.clone(), managed.tool_timeout)
+            .await
+        {
+            Ok(result) => return Ok(result),
+            Err(err) => err,
+        };
+        let managed = self
+            .reconnect_if_closed(server, &managed, err)
+            .await
+            .with_context(|| format!("prompts/get failed for `{server}` ({name})"))?;
+        managed
+            .client
+            .get_prompt(params, managed.tool_timeout)
+            .await
+            .with_context(|| format!("prompts/get failed for `{server}` ({name})"))
+    }
+
+    /// Asks `server` to report changes to `uri`. Servers without the
+    /// `resources.subscribe` capability are left alone.
+    pub async fn subscribe_resource(&self, server: &str, uri: String) -> Result<()> {
+        let managed = self.client_by_name(server).await?;
+        if !managed.server_supports_resource_subscriptions {
+            return Ok(());
+        }
+
+        managed
+            .client
+            .subscribe_resource(
+                SubscribeRequestParams { uri: uri.clone() },
+                managed.tool_timeout,
+            )
+            .await
+            .with_context(|| format!("resources/subscribe failed for `{server}` ({uri})"))
+    }
+
+    /// Resource URIs `server` reported as updated since the previous call.
+    pub async fn take_updated_resources(&self, server: &str) -> HashSet<String> {
+        match self.client_by_name(server).await {
+            Ok(managed) => managed.client.take_updated_resources(),
+            Err(_) => HashSet::new(),
+        }
+    }
+
+    pub(crate) fn has_server(&self, server: &str) -> bool {
+        self.clients.contains_key(server)
+    }
+
+    /// Approval policy configured for `tool` on `server`.
+    pub(crate) fn tool_approval(&self, server: &str, tool: &str) -> McpToolApproval {
+        self.clients
+            .get(server)
+            .map(|client| client.startup.config.tool_approval(tool))
+            .unwrap_or_default()
+    }
+
     pub async fn parse_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
         self.list_all_tools()
             .await
@@ -664,6 +1121,7 @@ impl McpConnectionManager {
     }
 
     pub async fn notify_sandbox_state_change(&self, sandbox_state: &SandboxState) -> Result<()> {
+        set_sandbox_state(&self.sandbox_state, sandbox_state);
         let mut join_set = JoinSet::new();
 
         for async_managed_client in self.clients.values() {
@@ -704,6 +1162,137 @@ async fn emit_update(
         .await
 }
 
+async fn emit_health(tx_event: &Sender<Event>, server: &str, status: McpServerHealthStatus) {
+    let _ = tx_event
+        .send(Event {
+            id: INITIAL_SUBMIT_ID.to_owned(),
+            msg: EventMsg::McpServerHealth(McpServerHealthEvent {
+                server: server.to_string(),
+                status,
+            }),
+        })
+        .await;
+}
+
+fn set_sandbox_state(slot: &std::sync::Mutex<Option<SandboxState>>, sandbox_state: &SandboxState) {
+    let mut slot = match slot.lock() {
+        Ok(guard) => guard,
+        Err(err) => err.into_inner(),
+    };
+    *slot = Some(sandbox_state.clone());
+}
+
+/// Delay before restart attempt `attempt` (1-based): doubles from
+/// [`MCP_RESTART_INITIAL_BACKOFF`] up to [`MCP_RESTART_MAX_BACKOFF`].
+fn restart_backoff(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    MCP_RESTART_INITIAL_BACKOFF
+        .saturating_mul(factor)
+        .min(MCP_RESTART_MAX_BACKOFF)
+}
+
+/// Pings a ready server every `ping_interval_sec` and restarts it when a ping
+/// fails, so a crashed server does not silently lose its tools for the rest
+/// of the session. Runs until the server is removed or given up on.
+async fn monitor_health(
+    client: AsyncManagedClient,
+    health: McpHealthConfig,
+    sandbox_state: Arc<std::sync::Mutex<Option<SandboxState>>>,
+) {
+    let cancel_token = client.stop.clone();
+    let server = client.startup.server_name.as_str();
+    let ping_interval = Duration::from_secs(health.ping_interval_sec);
+    let ping_timeout = Duration::from_secs(health.unresponsive_timeout_sec);
+    loop {
+        if tokio::time::sleep(ping_interval)
+            .or_cancel(&cancel_token)
+            .await
+            .is_err()
+        {
+            return;
+        }
+        let Ok(managed) = client.client().await else {
+            return;
+        };
+        let error = match managed.client.ping(Some(ping_timeout)).await {
+            Ok(()) => continue,
+            Err(err) => format!("{err:#}"),
+        };
+        drop(managed);
+        warn!("MCP server {server} is unresponsive: {error}");
+        if !restart_with_backoff(&client, error, health.max_restarts, &cancel_token).await {
+            return;
+        }
+
+        resend_sandbox_state(&client, &sandbox_state).await;
+    }
+}
+
+/// Sends the latest sandbox state to a restarted server.
+async fn resend_sandbox_state(
+    client: &AsyncManagedClient,
+    sandbox_state: &std::sync::Mutex<Option<SandboxState>>,
+) {
+    let sandbox_state = match sandbox_state.lock() {
+        Ok(guard) => guard.clone(),
+        Err(err) => err.into_inner().clone(),
+    };
+    if let Some(sandbox_state) = sandbox_state
+        && let Err(err) = client.notify_sandbox_state_change(&sandbox_state).await
+    {
+        warn!(
+            "Failed to notify sandbox state to MCP server {}: {err:#}",
+            client.startup.server_name
+        );
+    }
+}
+
+/// Restarts the server, retrying with exponential backoff. Returns `true`
+/// once it is ready again and `false` after `max_restarts` failed attempts
+/// or on cancellation.
+async fn restart_with_backoff(
+    client: &AsyncManagedClient,
+    mut error: String,
+    max_restarts: u32,
+    cancel_token: &CancellationToken,
+) -> bool {
+    let server = client.startup.server_name.as_str();
+    let tx_event = &client.startup.tx_event;
+    for restart_attempt in 1..=max_restarts {
+        let delay = restart_backoff(restart_attempt);
+        emit_health(
+            tx_event,
+            server,
+            McpServerHealthStatus::Degraded {
+                error: error.clone(),
+                restart_attempt,
+                retry_in_ms: u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+            },
+        )
+        .await;
+        if tokio::time::sleep(delay)
+            .or_cancel(cancel_token)
+            .await
+            .is_err()
+        {
+            return false;
+        }
+        match client.restart(cancel_token.child_token()).await {
+            Ok(()) => {
+                emit_health(tx_event, server, McpServerHealthStatus::Recovered).await;
+                return true;
+            }
+            Err(StartupOutcomeError::Cancelled) => return false,
+            Err(err) => {
+                error = err.to_string();
+                warn!("Restarting MCP server {server} failed: {error}");
+            }
+        }
+    }
+    emit_health(tx_event, server, McpServerHealthStatus::Failed { error }).await;
+    false
+}
+
 /// A tool is allowed to be used if both are true:
 /// 1. enabled is None (no allowlist is set) or the tool is explicitly enabled.
 /// 2. The tool is not explicitly disabled.
@@ -799,12 +1388,15 @@ async fn start_server_task(
     tool_filter: ToolFilter,
     tx_event: Sender<Event>,
     elicitation_requests: ElicitationRequestManager,
+    sampler: Option<Arc<McpSampler>>,
 ) -> Result<ManagedClient, StartupOutcomeError> {
+    let sampler = sampler.filter(|sampler| sampler.allows_server(&server_name));
     let params = mcp_types::InitializeRequestParams {
         capabilities: ClientCapabilities {
             experimental: None,
             roots: None,
-            sampling: None,
+            // https://modelcontextprotocol.io/specification/2025-06-18/client/sampling#capabilities
+            sampling: sampler.as_ref().map(|_| json!({})),
             // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
             // indicates this should be an empty object.
             elicitation: Some(json!({})),
@@ -821,10 +1413,17 @@ async fn start_server_task(
         protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
     };
 
+    let send_sampling = sampler.map(|sampler| {
+        sampler.make_sender(
+            server_name.clone(),
+            elicitation_requests.clone(),
+            tx_event.clone(),
+        )
+    });
     let send_elicitation = elicitation_requests.make_sender(server_name.clone(), tx_event);
 
     let initialize_result = client
-        .initialize(params, startup_timeout, send_elicitation)
+        .initialize_with_sampling(params, startup_timeout, send_elicitation, send_sampling)
         .await
         .map_err(StartupOutcomeError::from)?;
 
@@ -839,12 +1438,23 @@ async fn start_server_task(
         .and_then(|exp| exp.get(MCP_SANDBOX_STATE_CAPABILITY))
         .is_some();
 
+    let server_supports_resource_subscriptions = initialize_result
+        .capabilities
+        .resources
+        .as_ref()
+        .and_then(|resources| resources.subscribe)
+        .unwrap_or(false);
+
+    let server_supports_prompts = initialize_result.capabilities.prompts.is_some();
+
     let managed = ManagedClient {
         client: Arc::clone(&client),
         tools,
         tool_timeout: Some(tool_timeout),
         tool_filter,
         server_supports_sandbox_state_capability,
+        server_supports_resource_subscriptions,
+        server_supports_prompts,
     };
 
     Ok(managed)
@@ -1159,6 +1769,15 @@ mod tests {
         assert_eq!(filtered[0].tool_name, "tool_a");
     }
 
+    #[test]
+    fn restart_backoff_doubles_up_to_the_cap() {
+        let delays: Vec<u64> = (1..=8)
+            .map(|attempt| restart_backoff(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(restart_backoff(u32::MAX), MCP_RESTART_MAX_BACKOFF);
+    }
+
     #[test]
     fn mcp_init_error_display_prompts_for_github_pat() {
         let server_name = "github";
@@ -1176,6 +1795,8 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -1221,6 +1842,8 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
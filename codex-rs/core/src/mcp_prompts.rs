@@ -0,0 +1,241 @@
+//! `/mcp:<server>:<prompt> KEY=value ...` commands that expand an MCP server
+//! prompt into the turn input.
+//!
+//! Clients send the command as plain text; the session fetches the prompt
+//! with `prompts/get` and replaces the command with the prompt's messages
+//! before the turn starts, so every client gets the same expansion.
+
+use codex_protocol::custom_prompts::MCP_PROMPTS_CMD_PREFIX;
+use codex_protocol::user_input::UserInput;
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResourceResource;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::codex::Session;
+
+#[derive(Debug, PartialEq)]
+struct PromptCommand<'a> {
+    server: &'a str,
+    prompt: &'a str,
+    args: &'a str,
+}
+
+/// Replaces an MCP prompt command in `items` with the prompt's contents.
+///
+/// Input that does not start with a command for a running server is returned
+/// unchanged. The error is a user-facing message.
+pub(crate) async fn expand_prompt_command(
+    sess: &Session,
+    mut items: Vec<UserInput>,
+) -> Result<Vec<UserInput>, String> {
+    let Some((index, command)) = items
+        .iter()
+        .enumerate()
+        .find_map(|(index, item)| match item {
+            UserInput::Text { text, .. } => parse_command(text).map(|command| (index, command)),
+            _ => None,
+        })
+    else {
+        return Ok(items);
+    };
+
+    let result = {
+        let manager = sess.services.mcp_connection_manager.read().await;
+        if !manager.has_server(command.server) {
+            return Ok(items);
+        }
+        let arguments = parse_arguments(command.args)?;
+        manager
+            .get_prompt(
+                command.server,
+                GetPromptRequestParams {
+                    name: command.prompt.to_string(),
+                    arguments: (!arguments.is_empty()).then_some(Value::Object(arguments)),
+                },
+            )
+            .await
+            .map_err(|err| format!("{err:#}"))?
+    };
+
+    let expanded = prompt_inputs(result);
+    if expanded.is_empty() {
+        return Err(format!(
+            "MCP prompt `{}` on `{}` returned no text or images",
+            command.prompt, command.server
+        ));
+    }
+    items.splice(index..=index, expanded);
+    Ok(items)
+}
+
+/// Splits `/mcp:<server>:<prompt> KEY=value ...` into its parts, or returns
+/// `None` when `text` is not an MCP prompt command.
+fn parse_command(text: &str) -> Option<PromptCommand<'_>> {
+    let rest = text
+        .trim_start()
+        .strip_prefix('/')?
+        .strip_prefix(MCP_PROMPTS_CMD_PREFIX)?
+        .strip_prefix(':')?;
+    let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let (server, prompt) = name.split_once(':')?;
+    if server.is_empty() || prompt.is_empty() {
+        return None;
+    }
+    Some(PromptCommand {
+        server,
+        prompt,
+        args,
+    })
+}
+
+fn parse_arguments(args: &str) -> Result<Map<String, Value>, String> {
+    let tokens = shlex::split(args)
+        .ok_or_else(|| "Could not parse MCP prompt arguments: unbalanced quotes".to_string())?;
+    let mut arguments = Map::new();
+    for token in tokens {
+        match token.split_once('=') {
+            Some((key, value)) if !key.is_empty() => {
+                arguments.insert(key.to_string(), Value::String(value.to_string()));
+            }
+            _ => {
+                return Err(format!(
+                    "MCP prompt arguments must be KEY=value pairs; got `{token}`"
+                ));
+            }
+        }
+    }
+    Ok(arguments)
+}
+
+/// Flattens the prompt's messages into turn input: text parts (including
+/// embedded text resources) are joined into one text item, images are kept
+/// as images, and other content is dropped.
+fn prompt_inputs(result: GetPromptResult) -> Vec<UserInput> {
+    let mut texts = Vec::new();
+    let mut images = Vec::new();
+    for message in result.messages {
+        match message.content {
+            ContentBlock::TextContent(text) => texts.push(text.text),
+            ContentBlock::ImageContent(image) => images.push(UserInput::Image {
+                image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+            }),
+            ContentBlock::EmbeddedResource(resource) => match resource.resource {
+                EmbeddedResourceResource::TextResourceContents(contents) => {
+                    texts.push(contents.text);
+                }
+                EmbeddedResourceResource::BlobResourceContents(_) => {}
+            },
+            ContentBlock::ResourceLink(link) => texts.push(link.uri),
+            ContentBlock::AudioContent(_) => {}
+        }
+    }
+
+    let mut inputs = Vec::new();
+    if !texts.is_empty() {
+        inputs.push(UserInput::Text {
+            text: texts.join("\n\n"),
+            text_elements: Vec::new(),
+        });
+    }
+    inputs.extend(images);
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ImageContent;
+    use mcp_types::PromptMessage;
+    use mcp_types::Role;
+    use mcp_types::TextContent;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_prompt_command_with_arguments() {
+        let Some(command) = parse_command("/mcp:github:review_pr pr=42 focus=\"error handling\"")
+        else {
+            panic!("expected a prompt command");
+        };
+        assert_eq!(
+            command,
+            PromptCommand {
+                server: "github",
+                prompt: "review_pr",
+                args: "pr=42 focus=\"error handling\"",
+            }
+        );
+
+        let mut arguments = Map::new();
+        arguments.insert("pr".to_string(), Value::String("42".to_string()));
+        arguments.insert(
+            "focus".to_string(),
+            Value::String("error handling".to_string()),
+        );
+        assert_eq!(parse_arguments(command.args), Ok(arguments));
+    }
+
+    #[test]
+    fn ignores_text_that_is_not_a_prompt_command() {
+        assert_eq!(parse_command("/mcp"), None);
+        assert_eq!(parse_command("/mcp:github"), None);
+        assert_eq!(parse_command("/prompts:review"), None);
+        assert_eq!(parse_command("explain /mcp:github:review_pr"), None);
+    }
+
+    #[test]
+    fn rejects_positional_arguments() {
+        assert_eq!(
+            parse_arguments("42"),
+            Err("MCP prompt arguments must be KEY=value pairs; got `42`".to_string())
+        );
+    }
+
+    #[test]
+    fn flattens_prompt_messages() {
+        let result = GetPromptResult {
+            description: None,
+            messages: vec![
+                PromptMessage {
+                    role: Role::User,
+                    content: ContentBlock::TextContent(TextContent {
+                        annotations: None,
+                        text: "Review PR 42.".to_string(),
+                        r#type: "text".to_string(),
+                    }),
+                },
+                PromptMessage {
+                    role: Role::User,
+                    content: ContentBlock::ImageContent(ImageContent {
+                        annotations: None,
+                        data: "AAAA".to_string(),
+                        mime_type: "image/png".to_string(),
+                        r#type: "image".to_string(),
+                    }),
+                },
+                PromptMessage {
+                    role: Role::User,
+                    content: ContentBlock::TextContent(TextContent {
+                        annotations: None,
+                        text: "Focus on error handling.".to_string(),
+                        r#type: "text".to_string(),
+                    }),
+                },
+            ],
+        };
+        assert_eq!(
+            prompt_inputs(result),
+            vec![
+                UserInput::Text {
+                    text: "Review PR 42.\n\nFocus on error handling.".to_string(),
+                    text_elements: Vec::new(),
+                },
+                UserInput::Image {
+                    image_url: "data:image/png;base64,AAAA".to_string(),
+                },
+            ]
+        );
+    }
+}
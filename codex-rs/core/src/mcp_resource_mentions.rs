@@ -0,0 +1,322 @@
+//! `@server:uri` mentions that attach MCP resources to a prompt.
+//!
+//! When a turn starts, every mention whose `server` is a running MCP server is
+//! read and recorded as an `<mcp_resources>` message right after the user's
+//! prompt. The session remembers what was attached and subscribes to updates
+//! where the server supports it; when a server reports that an attached
+//! resource changed, the next turn re-reads it so the model sees the fresh
+//! contents. A resource that cannot be read is skipped with a warning.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::WarningEvent;
+use codex_protocol::user_input::UserInput;
+use codex_utils_string::take_bytes_at_char_boundary;
+use mcp_types::ReadResourceRequestParams;
+use mcp_types::ReadResourceResultContents;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::session_prefix::MCP_RESOURCES_OPEN_TAG;
+
+/// Per-resource cap, so one large document cannot crowd out the rest.
+const MAX_RESOURCE_BYTES: usize = 16 * 1024;
+
+/// Budget for all resources attached to one turn.
+const MAX_TOTAL_BYTES: usize = 64 * 1024;
+
+const TRUNCATION_MARKER: &str = "\n[truncated]";
+
+/// Trailing characters that end a sentence rather than the URI.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ')', ']', '"', '\''];
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct ResourceRef {
+    server: String,
+    uri: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct AttachedContent {
+    resource: ResourceRef,
+    updated: bool,
+    content: String,
+}
+
+/// Resources attached with `@server:uri` during this session.
+#[derive(Default)]
+pub(crate) struct McpResourceMentions {
+    attached: Mutex<BTreeSet<ResourceRef>>,
+}
+
+impl McpResourceMentions {
+    /// Reads the resources mentioned in `input`, plus attached resources the
+    /// server has updated since the previous turn, and returns the message
+    /// to record, or `None` when there is nothing to add.
+    pub(crate) async fn gather(
+        &self,
+        sess: &Session,
+        turn: &TurnContext,
+        input: &[UserInput],
+    ) -> Option<ResponseItem> {
+        let mentions = {
+            let manager = sess.services.mcp_connection_manager.read().await;
+            let mut mentions = Vec::new();
+            for item in input {
+                if let UserInput::Text { text, .. } = item {
+                    mentions.extend(
+                        parse_mentions(text)
+                            .into_iter()
+                            .filter(|mention| manager.has_server(&mention.server)),
+                    );
+                }
+            }
+            mentions
+        };
+
+        let previously_attached: Vec<ResourceRef> = self.lock_attached().iter().cloned().collect();
+        let refreshed = updated_resources(sess, &previously_attached, &mentions).await;
+        if mentions.is_empty() && refreshed.is_empty() {
+            return None;
+        }
+
+        let mut to_read: Vec<(ResourceRef, bool)> = Vec::new();
+        for mention in mentions {
+            if !to_read.iter().any(|(resource, _)| *resource == mention) {
+                to_read.push((mention, false));
+            }
+        }
+        to_read.extend(refreshed.into_iter().map(|resource| (resource, true)));
+
+        let mut budget = MAX_TOTAL_BYTES;
+        let mut attached = Vec::new();
+        for (resource, updated) in to_read {
+            match read_resource(sess, &resource).await {
+                Ok(content) => {
+                    if !updated {
+                        self.attach(sess, &resource).await;
+                    }
+                    let content = truncate(content, MAX_RESOURCE_BYTES.min(budget));
+                    budget = budget.saturating_sub(content.len());
+                    attached.push(AttachedContent {
+                        resource,
+                        updated,
+                        content,
+                    });
+                }
+                Err(err) => {
+                    let message = format!(
+                        "Could not attach `@{}:{}`: {err}",
+                        resource.server, resource.uri
+                    );
+                    warn!("{message}");
+                    sess.send_event(turn, EventMsg::Warning(WarningEvent { message }))
+                        .await;
+                }
+            }
+            if budget == 0 {
+                break;
+            }
+        }
+        if attached.is_empty() {
+            return None;
+        }
+        Some(ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: render(&attached),
+            }],
+            end_turn: None,
+        })
+    }
+
+    /// Remembers `resource` and, the first time it is attached, asks the
+    /// server for update notifications.
+    async fn attach(&self, sess: &Session, resource: &ResourceRef) {
+        if !self.lock_attached().insert(resource.clone()) {
+            return;
+        }
+        if let Err(err) = sess
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .subscribe_resource(&resource.server, resource.uri.clone())
+            .await
+        {
+            warn!(
+                "failed to subscribe to `{}` on `{}`: {err:#}",
+                resource.uri, resource.server
+            );
+        }
+    }
+
+    fn lock_attached(&self) -> std::sync::MutexGuard<'_, BTreeSet<ResourceRef>> {
+        match self.attached.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+/// Attached resources the servers reported as updated, minus the ones the
+/// user mentioned again this turn (those are read anyway).
+async fn updated_resources(
+    sess: &Session,
+    attached: &[ResourceRef],
+    mentions: &[ResourceRef],
+) -> Vec<ResourceRef> {
+    if attached.is_empty() {
+        return Vec::new();
+    }
+    let mut by_server: BTreeMap<&str, Vec<&ResourceRef>> = BTreeMap::new();
+    for resource in attached {
+        by_server
+            .entry(resource.server.as_str())
+            .or_default()
+            .push(resource);
+    }
+    let manager = sess.services.mcp_connection_manager.read().await;
+    let mut refreshed = Vec::new();
+    for (server, resources) in by_server {
+        let updated = manager.take_updated_resources(server).await;
+        refreshed.extend(
+            resources
+                .into_iter()
+                .filter(|resource| updated.contains(&resource.uri) && !mentions.contains(resource))
+                .cloned(),
+        );
+    }
+    refreshed
+}
+
+async fn read_resource(sess: &Session, resource: &ResourceRef) -> Result<String, String> {
+    let result = sess
+        .read_resource(
+            &resource.server,
+            ReadResourceRequestParams {
+                uri: resource.uri.clone(),
+            },
+        )
+        .await
+        .map_err(|err| format!("{err:#}"))?;
+    let mut texts = Vec::new();
+    let mut skipped_blobs = 0;
+    for contents in result.contents {
+        match contents {
+            ReadResourceResultContents::TextResourceContents(text) => texts.push(text.text),
+            ReadResourceResultContents::BlobResourceContents(_) => skipped_blobs += 1,
+        }
+    }
+    if texts.is_empty() && skipped_blobs > 0 {
+        return Err("the resource only has binary contents".to_string());
+    }
+    Ok(texts.join("\n"))
+}
+
+/// Finds `@server:uri` tokens in `text`. The server part must be a valid MCP
+/// server name; whether it names a running server is checked by the caller.
+fn parse_mentions(text: &str) -> Vec<ResourceRef> {
+    text.split_whitespace()
+        .filter_map(|token| {
+            let rest = token.strip_prefix('@')?;
+            let (server, uri) = rest.split_once(':')?;
+            let uri = uri.trim_end_matches(TRAILING_PUNCTUATION);
+            let valid_server = !server.is_empty()
+                && server
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            (valid_server && !uri.is_empty()).then(|| ResourceRef {
+                server: server.to_string(),
+                uri: uri.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn truncate(content: String, max_bytes: usize) -> String {
+    if content.len() <= max_bytes {
+        return content;
+    }
+    let head = take_bytes_at_char_boundary(&content, max_bytes);
+    format!("{head}{TRUNCATION_MARKER}")
+}
+
+fn render(attached: &[AttachedContent]) -> String {
+    let mut text = format!("{MCP_RESOURCES_OPEN_TAG}\n");
+    for item in attached {
+        let server = item.resource.server.replace('"', "'");
+        let uri = item.resource.uri.replace('"', "'");
+        let updated = if item.updated {
+            " updated=\"true\""
+        } else {
+            ""
+        };
+        text.push_str(&format!(
+            "<resource server=\"{server}\" uri=\"{uri}\"{updated}>\n"
+        ));
+        text.push_str(item.content.trim_end());
+        text.push_str("\n</resource>\n");
+    }
+    text.push_str("</mcp_resources>");
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn resource(server: &str, uri: &str) -> ResourceRef {
+        ResourceRef {
+            server: server.to_string(),
+            uri: uri.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_server_uri_mentions() {
+        assert_eq!(
+            parse_mentions(
+                "Compare @docs:file:///guide.md with @wiki:page/42, not @src/main.rs or a@b:c or @bad name:x"
+            ),
+            vec![
+                resource("docs", "file:///guide.md"),
+                resource("wiki", "page/42"),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_attached_resources() {
+        let attached = vec![
+            AttachedContent {
+                resource: resource("docs", "file:///guide.md"),
+                updated: false,
+                content: "Guide\n".to_string(),
+            },
+            AttachedContent {
+                resource: resource("wiki", "page/42"),
+                updated: true,
+                content: "New text".to_string(),
+            },
+        ];
+        assert_eq!(
+            render(&attached),
+            "<mcp_resources>\n<resource server=\"docs\" uri=\"file:///guide.md\">\nGuide\n</resource>\n<resource server=\"wiki\" uri=\"page/42\" updated=\"true\">\nNew text\n</resource>\n</mcp_resources>"
+        );
+    }
+
+    #[test]
+    fn truncates_long_resources() {
+        assert_eq!(truncate("abcdef".to_string(), 3), "abc\n[truncated]");
+        assert_eq!(truncate("abc".to_string(), 3), "abc");
+    }
+}
@@ -0,0 +1,423 @@
+//! Serves MCP `sampling/createMessage` requests by running a one-off model
+//! completion in the owning session.
+//!
+//! Only servers listed in `[mcp_sampling].servers` are offered the sampling
+//! capability. Each request is confirmed with the user through the regular
+//! MCP elicitation prompt (unless `require_approval = false`), counted against
+//! a per-session budget and a per-server rate limit, and billed through the
+//! session's token usage like any other model call.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::Weak;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use async_channel::Sender;
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::Event;
+use codex_rmcp_client::ElicitationAction;
+use codex_rmcp_client::SendSampling;
+use futures::prelude::*;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::CreateMessageResultContent;
+use mcp_types::Role;
+use mcp_types::SamplingMessage;
+use mcp_types::SamplingMessageContent;
+use mcp_types::TextContent;
+
+use crate::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::config::types::McpSamplingConfig;
+use crate::mcp_connection_manager::ElicitationRequestManager;
+
+const DEFAULT_SAMPLING_INSTRUCTIONS: &str =
+    "You are answering a completion request issued by an MCP server on the user's behalf.";
+
+/// How many characters of the last sampling message to show in the approval prompt.
+const APPROVAL_PREVIEW_CHARS: usize = 200;
+
+/// Window for `max_requests_per_minute`.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+pub(crate) struct McpSampler {
+    config: McpSamplingConfig,
+    session: Weak<Session>,
+    requests_served: AtomicU32,
+    /// Start times of each server's requests within the last rate-limit window.
+    recent_requests: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl McpSampler {
+    pub(crate) fn new(config: McpSamplingConfig, session: Weak<Session>) -> Self {
+        Self {
+            config,
+            session,
+            requests_served: AtomicU32::new(0),
+            recent_requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn allows_server(&self, server_name: &str) -> bool {
+        self.config.servers.iter().any(|name| name == server_name)
+    }
+
+    /// Builds the handler passed to the server's client. Approval prompts are
+    /// routed through `approvals` so they resolve via `Op::ResolveElicitation`.
+    pub(crate) fn make_sender(
+        self: &Arc<Self>,
+        server_name: String,
+        approvals: ElicitationRequestManager,
+        tx_event: Sender<Event>,
+    ) -> SendSampling {
+        let sampler = Arc::clone(self);
+        Box::new(move |id, params| {
+            let sampler = Arc::clone(&sampler);
+            let approvals = approvals.clone();
+            let tx_event = tx_event.clone();
+            let server_name = server_name.clone();
+            async move {
+                // Checked before asking so a noisy server cannot flood the user
+                // with approval prompts.
+                sampler.check_request(&server_name, &params, Instant::now())?;
+                if sampler.config.require_approval {
+                    let message = approval_message(&server_name, &params);
+                    let response = approvals
+                        .request(server_name.clone(), id, message, tx_event)
+                        .await?;
+                    if response.action != ElicitationAction::Accept {
+                        bail!("user declined the sampling request from `{server_name}`");
+                    }
+                }
+                sampler.reserve_request(&server_name, Instant::now())?;
+                sampler.sample(params).await
+            }
+            .boxed()
+        })
+    }
+
+    fn check_request(
+        &self,
+        server_name: &str,
+        params: &CreateMessageRequestParams,
+        now: Instant,
+    ) -> Result<()> {
+        if params.max_tokens > self.config.max_tokens_per_request {
+            bail!(
+                "sampling request asks for {} tokens but mcp_sampling.max_tokens_per_request is {}",
+                params.max_tokens,
+                self.config.max_tokens_per_request
+            );
+        }
+        if self.requests_served.load(Ordering::SeqCst) >= self.config.max_requests_per_session {
+            bail!(self.budget_exhausted_message());
+        }
+        let mut recent = self.lock_recent_requests();
+        if self.recent_request_count(&mut recent, server_name, now)
+            >= self.config.max_requests_per_minute as usize
+        {
+            bail!(self.rate_limited_message(server_name));
+        }
+        Ok(())
+    }
+
+    /// Counts the request against the session budget and the server's rate
+    /// limit, failing if either is used up.
+    fn reserve_request(&self, server_name: &str, now: Instant) -> Result<()> {
+        let mut recent = self.lock_recent_requests();
+        if self.recent_request_count(&mut recent, server_name, now)
+            >= self.config.max_requests_per_minute as usize
+        {
+            bail!(self.rate_limited_message(server_name));
+        }
+        let limit = self.config.max_requests_per_session;
+        self.requests_served
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |served| {
+                (served < limit).then_some(served + 1)
+            })
+            .map_err(|_| anyhow!(self.budget_exhausted_message()))?;
+        recent
+            .entry(server_name.to_string())
+            .or_default()
+            .push_back(now);
+        Ok(())
+    }
+
+    /// Drops requests older than the rate-limit window and returns how many
+    /// `server_name` made within it.
+    fn recent_request_count(
+        &self,
+        recent: &mut HashMap<String, VecDeque<Instant>>,
+        server_name: &str,
+        now: Instant,
+    ) -> usize {
+        let Some(starts) = recent.get_mut(server_name) else {
+            return 0;
+        };
+        while starts
+            .front()
+            .is_some_and(|start| now.saturating_duration_since(*start) >= RATE_LIMIT_WINDOW)
+        {
+            starts.pop_front();
+        }
+        starts.len()
+    }
+
+    fn lock_recent_requests(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<String, VecDeque<Instant>>> {
+        match self.recent_requests.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        }
+    }
+
+    fn rate_limited_message(&self, server_name: &str) -> String {
+        format!(
+            "`{server_name}` reached mcp_sampling.max_requests_per_minute ({}); try again later",
+            self.config.max_requests_per_minute
+        )
+    }
+
+    fn budget_exhausted_message(&self) -> String {
+        format!(
+            "mcp_sampling.max_requests_per_session ({}) reached for this session",
+            self.config.max_requests_per_session
+        )
+    }
+
+    async fn sample(&self, params: CreateMessageRequestParams) -> Result<CreateMessageResult> {
+        let Some(sess) = self.session.upgrade() else {
+            bail!("session is no longer running");
+        };
+        let turn_context = sess.new_default_turn().await;
+        let prompt = Prompt {
+            input: sampling_messages_to_input(params.messages)?,
+            base_instructions: BaseInstructions {
+                text: params
+                    .system_prompt
+                    .unwrap_or_else(|| DEFAULT_SAMPLING_INSTRUCTIONS.to_string()),
+            },
+            ..Default::default()
+        };
+
+        let mut client_session = turn_context.client.new_session();
+        let mut stream = client_session.stream(&prompt).await?;
+        let mut text = String::new();
+        while let Some(event) = stream.next().await {
+            match event? {
+                ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. })
+                    if role == "assistant" =>
+                {
+                    for item in content {
+                        if let ContentItem::OutputText { text: chunk } = item {
+                            text.push_str(&chunk);
+                        }
+                    }
+                }
+                ResponseEvent::Completed { token_usage, .. } => {
+                    sess.update_token_usage_info(&turn_context, token_usage.as_ref())
+                        .await;
+                    return Ok(CreateMessageResult {
+                        content: CreateMessageResultContent::TextContent(TextContent {
+                            annotations: None,
+                            text,
+                            r#type: "text".to_string(),
+                        }),
+                        model: turn_context.client.get_model(),
+                        role: Role::Assistant,
+                        stop_reason: Some("endTurn".to_string()),
+                    });
+                }
+                _ => {}
+            }
+        }
+        bail!("stream closed before response.completed")
+    }
+}
+
+fn sampling_messages_to_input(messages: Vec<SamplingMessage>) -> Result<Vec<ResponseItem>> {
+    messages
+        .into_iter()
+        .map(|message| {
+            let (role, item) = match (message.role, message.content) {
+                (Role::User, SamplingMessageContent::TextContent(content)) => {
+                    ("user", ContentItem::InputText { text: content.text })
+                }
+                (Role::Assistant, SamplingMessageContent::TextContent(content)) => {
+                    ("assistant", ContentItem::OutputText { text: content.text })
+                }
+                (Role::User, SamplingMessageContent::ImageContent(image)) => (
+                    "user",
+                    ContentItem::InputImage {
+                        image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+                    },
+                ),
+                (_, SamplingMessageContent::ImageContent(_)) => {
+                    bail!("image content is only supported in user sampling messages")
+                }
+                (_, SamplingMessageContent::AudioContent(_)) => {
+                    bail!("audio content is not supported in sampling messages")
+                }
+            };
+            Ok(ResponseItem::Message {
+                id: None,
+                role: role.to_string(),
+                content: vec![item],
+                end_turn: None,
+            })
+        })
+        .collect()
+}
+
+fn approval_message(server_name: &str, params: &CreateMessageRequestParams) -> String {
+    let preview = params
+        .messages
+        .last()
+        .and_then(|message| match &message.content {
+            SamplingMessageContent::TextContent(content) => Some(content.text.as_str()),
+            _ => None,
+        })
+        .map(|text| {
+            let mut preview: String = text.chars().take(APPROVAL_PREVIEW_CHARS).collect();
+            if preview.len() < text.len() {
+                preview.push('…');
+            }
+            preview
+        })
+        .unwrap_or_default();
+    format!(
+        "MCP server `{server_name}` wants to run a model completion (up to {} tokens): {preview}",
+        params.max_tokens
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn text_message(role: Role, text: &str) -> SamplingMessage {
+        SamplingMessage {
+            content: SamplingMessageContent::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            }),
+            role,
+        }
+    }
+
+    fn params(max_tokens: i64) -> CreateMessageRequestParams {
+        CreateMessageRequestParams {
+            include_context: None,
+            max_tokens,
+            messages: vec![text_message(Role::User, "summarize the repo")],
+            metadata: None,
+            model_preferences: None,
+            stop_sequences: None,
+            system_prompt: None,
+            temperature: None,
+        }
+    }
+
+    fn sampler(config: McpSamplingConfig) -> McpSampler {
+        McpSampler::new(config, Weak::new())
+    }
+
+    #[test]
+    fn only_listed_servers_are_allowed() {
+        let sampler = sampler(McpSamplingConfig {
+            servers: vec!["docs".to_string()],
+            ..Default::default()
+        });
+        assert!(sampler.allows_server("docs"));
+        assert!(!sampler.allows_server("other"));
+    }
+
+    #[test]
+    fn rejects_requests_over_token_limit() {
+        let sampler = sampler(McpSamplingConfig {
+            max_tokens_per_request: 100,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        assert!(sampler.check_request("docs", &params(100), now).is_ok());
+        assert!(sampler.check_request("docs", &params(101), now).is_err());
+    }
+
+    #[test]
+    fn enforces_per_session_request_budget() {
+        let sampler = sampler(McpSamplingConfig {
+            max_requests_per_session: 2,
+            ..Default::default()
+        });
+        let now = Instant::now();
+        assert!(sampler.reserve_request("docs", now).is_ok());
+        assert!(sampler.reserve_request("wiki", now).is_ok());
+        assert!(sampler.reserve_request("docs", now).is_err());
+        assert!(sampler.check_request("docs", &params(10), now).is_err());
+    }
+
+    #[test]
+    fn enforces_per_server_rate_limit() {
+        let sampler = sampler(McpSamplingConfig {
+            max_requests_per_minute: 2,
+            ..Default::default()
+        });
+        let start = Instant::now();
+        assert!(sampler.reserve_request("docs", start).is_ok());
+        assert!(sampler.reserve_request("docs", start).is_ok());
+        assert!(sampler.check_request("docs", &params(10), start).is_err());
+        assert!(sampler.reserve_request("docs", start).is_err());
+
+        // Other servers have their own window.
+        assert!(sampler.check_request("wiki", &params(10), start).is_ok());
+
+        // Requests age out of the window.
+        let later = start + RATE_LIMIT_WINDOW;
+        assert!(sampler.check_request("docs", &params(10), later).is_ok());
+        assert!(sampler.reserve_request("docs", later).is_ok());
+    }
+
+    #[test]
+    fn converts_sampling_messages_to_model_input() {
+        let input = sampling_messages_to_input(vec![
+            text_message(Role::User, "hi"),
+            text_message(Role::Assistant, "hello"),
+        ])
+        .expect("convert messages");
+        assert_eq!(
+            input,
+            vec![
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "hi".to_string()
+                    }],
+                    end_turn: None,
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: "hello".to_string()
+                    }],
+                    end_turn: None,
+                },
+            ]
+        );
+    }
+}
@@ -1,16 +1,31 @@
 use std::time::Instant;
 
+use serde::Serialize;
 use tracing::error;
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::config::types::McpToolApproval;
+use crate::protocol::AskForApproval;
 use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
 use crate::protocol::McpToolCallBeginEvent;
 use crate::protocol::McpToolCallEndEvent;
+use crate::protocol::ReviewDecision;
+use crate::tools::sandboxing::is_approved_for_session;
+use crate::tools::sandboxing::with_cached_approval;
+use codex_protocol::approvals::ApprovalJournalEntry;
+use codex_protocol::approvals::ApprovalSource;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 
+/// Session approval cache key for MCP tools marked `always_ask`.
+#[derive(Serialize)]
+struct McpToolApprovalKey {
+    server: String,
+    tool: String,
+}
+
 /// Handles the specified tool call dispatches the appropriate
 /// `McpToolCallBegin` and `McpToolCallEnd` events to the `Session`.
 pub(crate) async fn handle_mcp_tool_call(
@@ -48,6 +63,17 @@ pub(crate) async fn handle_mcp_tool_call(
         arguments: arguments_value.clone(),
     };
 
+    if let Err(message) = check_tool_approval(sess, turn_context, &call_id, &invocation).await {
+        return ResponseInputItem::FunctionCallOutput {
+            call_id,
+            output: FunctionCallOutputPayload {
+                content: message,
+                success: Some(false),
+                ..Default::default()
+            },
+        };
+    }
+
     let tool_call_begin_event = EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
         call_id: call_id.clone(),
         invocation: invocation.clone(),
@@ -81,6 +107,113 @@ pub(crate) async fn handle_mcp_tool_call(
     ResponseInputItem::McpToolCallOutput { call_id, result }
 }
 
+/// Applies the server's `default_tool_approval` / `tool_approvals` config.
+/// The error is the message returned to the model when the call may not run.
+async fn check_tool_approval(
+    sess: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    invocation: &McpInvocation,
+) -> Result<(), String> {
+    let approval = sess
+        .services
+        .mcp_connection_manager
+        .read()
+        .await
+        .tool_approval(&invocation.server, &invocation.tool);
+    let subject = format!("{}.{}", invocation.server, invocation.tool);
+    match approval {
+        McpToolApproval::AutoApprove => Ok(()),
+        McpToolApproval::Blocked => {
+            record_approval(
+                sess,
+                turn_context,
+                call_id,
+                subject.clone(),
+                ApprovalSource::McpToolBlocked,
+                false,
+            )
+            .await;
+            Err(format!(
+                "MCP tool `{subject}` is blocked by the user's configuration; do not retry it."
+            ))
+        }
+        McpToolApproval::AlwaysAsk => {
+            if turn_context.approval_policy == AskForApproval::Never {
+                return Err(format!(
+                    "MCP tool `{subject}` requires approval, but the approval policy does not allow asking the user."
+                ));
+            }
+            let key = McpToolApprovalKey {
+                server: invocation.server.clone(),
+                tool: invocation.tool.clone(),
+            };
+            let already_approved =
+                is_approved_for_session(&sess.services, std::slice::from_ref(&key)).await;
+            let decision = with_cached_approval(&sess.services, "mcp", vec![key], || {
+                sess.request_mcp_tool_approval(
+                    turn_context,
+                    call_id.to_string(),
+                    invocation.clone(),
+                )
+            })
+            .await;
+            let source = if already_approved {
+                ApprovalSource::SessionAllowlist
+            } else {
+                ApprovalSource::User {
+                    decision: decision.clone(),
+                }
+            };
+            let approved = matches!(
+                decision,
+                ReviewDecision::Approved
+                    | ReviewDecision::ApprovedForSession
+                    | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+            );
+            record_approval(
+                sess,
+                turn_context,
+                call_id,
+                subject.clone(),
+                source,
+                approved,
+            )
+            .await;
+            if approved {
+                Ok(())
+            } else {
+                Err(format!(
+                    "The user declined the call to MCP tool `{subject}`."
+                ))
+            }
+        }
+    }
+}
+
+async fn record_approval(
+    sess: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    subject: String,
+    source: ApprovalSource,
+    approved: bool,
+) {
+    let entry = ApprovalJournalEntry {
+        call_id: call_id.to_string(),
+        turn_id: turn_context.sub_id.clone(),
+        tool_name: "mcp".to_string(),
+        subject,
+        source,
+        approved,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Some(audit_log) = sess.services.audit_log.as_ref() {
+        audit_log.record_approval(&entry);
+    }
+    sess.services.approval_journal.lock().await.record(entry);
+}
+
 async fn notify_mcp_tool_call_event(sess: &Session, turn_context: &TurnContext, event: EventMsg) {
     sess.send_event(turn_context, event).await;
 }
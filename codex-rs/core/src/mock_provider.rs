@@ -0,0 +1,253 @@
+//! Offline model provider (`wire_api = "mock"`) that replays scripted
+//! responses from `mock_responses_file`.
+//!
+//! The fixture is a JSON document listing one entry per model request:
+//!
+//! ```json
+//! {
+//!   "responses": [
+//!     { "items": [{ "type": "function_call", "name": "shell", "arguments": "{\"command\":[\"ls\"]}", "call_id": "call-1" }] },
+//!     { "items": [{ "type": "message", "role": "assistant", "content": [{ "type": "output_text", "text": "Done." }] }] }
+//!   ]
+//! }
+//! ```
+//!
+//! The entry to replay is chosen from the conversation history rather than a
+//! counter, so a resumed or forked session picks up where the script left off.
+
+use std::path::Path;
+
+use codex_protocol::models::ResponseItem;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::error::CodexErr;
+use crate::error::Result;
+
+/// Model slug that selects the built-in mock provider when no
+/// `model_provider` is configured.
+pub(crate) const MOCK_MODEL: &str = "mock";
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct MockScript {
+    responses: Vec<MockResponse>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct MockResponse {
+    items: Vec<ResponseItem>,
+}
+
+/// Streams the scripted response for the next model request in `input`.
+pub(crate) fn stream_mock_response(
+    fixture: Option<&Path>,
+    input: &[ResponseItem],
+) -> Result<ResponseStream> {
+    let Some(fixture) = fixture else {
+        return Err(CodexErr::InvalidRequest(
+            "the mock provider requires `mock_responses_file` to be set".to_string(),
+        ));
+    };
+    let script = load_script(fixture)?;
+    let index = prior_model_responses(input);
+    let defined = script.responses.len();
+    let Some(response) = script.responses.into_iter().nth(index) else {
+        return Err(CodexErr::InvalidRequest(format!(
+            "mock script {} has no response #{} (it defines {defined})",
+            fixture.display(),
+            index + 1,
+        )));
+    };
+
+    let mut events = vec![ResponseEvent::Created];
+    events.extend(
+        response
+            .items
+            .into_iter()
+            .map(ResponseEvent::OutputItemDone),
+    );
+    events.push(ResponseEvent::Completed {
+        response_id: format!("mock-resp-{}", index + 1),
+        token_usage: None,
+    });
+
+    let (tx_event, rx_event) = mpsc::channel(events.len());
+    for event in events {
+        // The channel is sized to hold every event, so this never fails.
+        let _ = tx_event.try_send(Ok(event));
+    }
+    Ok(ResponseStream { rx_event })
+}
+
+fn load_script(path: &Path) -> Result<MockScript> {
+    let contents = std::fs::read_to_string(path).map_err(|err| {
+        CodexErr::InvalidRequest(format!(
+            "failed to read mock script {}: {err}",
+            path.display()
+        ))
+    })?;
+    serde_json::from_str(&contents).map_err(|err| {
+        CodexErr::InvalidRequest(format!(
+            "failed to parse mock script {}: {err}",
+            path.display()
+        ))
+    })
+}
+
+/// Counts the model responses already present in `input`: each maximal run
+/// of model-produced items (assistant messages, reasoning, tool calls) is one
+/// response.
+fn prior_model_responses(input: &[ResponseItem]) -> usize {
+    let mut count = 0;
+    let mut in_response = false;
+    for item in input {
+        match item {
+            ResponseItem::Message { role, .. } if role == "assistant" => {}
+            ResponseItem::Reasoning { .. }
+            | ResponseItem::LocalShellCall { .. }
+            | ResponseItem::FunctionCall { .. }
+            | ResponseItem::CustomToolCall { .. }
+            | ResponseItem::WebSearchCall { .. }
+            | ResponseItem::CodeInterpreterCall { .. }
+            | ResponseItem::FileSearchCall { .. } => {}
+            ResponseItem::GhostSnapshot { .. } | ResponseItem::Other => continue,
+            _ => {
+                in_response = false;
+                continue;
+            }
+        }
+        if !in_response {
+            count += 1;
+            in_response = true;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use futures::StreamExt;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![if role == "assistant" {
+                ContentItem::OutputText {
+                    text: text.to_string(),
+                }
+            } else {
+                ContentItem::InputText {
+                    text: text.to_string(),
+                }
+            }],
+            end_turn: None,
+        }
+    }
+
+    fn function_call(call_id: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: r#"{"command":["ls"]}"#.to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    fn function_call_output(call_id: &str) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: "ok".to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn counts_prior_model_responses_from_history() {
+        assert_eq!(prior_model_responses(&[message("user", "hi")]), 0);
+        assert_eq!(
+            prior_model_responses(&[
+                message("user", "hi"),
+                function_call("call-1"),
+                function_call("call-2"),
+                function_call_output("call-1"),
+                function_call_output("call-2"),
+                message("assistant", "done"),
+                message("user", "again"),
+            ]),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn replays_scripted_response_for_next_request() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let fixture = dir.path().join("mock.json");
+        std::fs::write(
+            &fixture,
+            r#"{
+              "responses": [
+                { "items": [{ "type": "function_call", "name": "shell", "arguments": "{\"command\":[\"ls\"]}", "call_id": "call-1" }] },
+                { "items": [{ "type": "message", "role": "assistant", "content": [{ "type": "output_text", "text": "done" }] }] }
+              ]
+            }"#,
+        )
+        .expect("write fixture");
+
+        let input = vec![
+            message("user", "list files"),
+            function_call("call-1"),
+            function_call_output("call-1"),
+        ];
+        let stream = stream_mock_response(Some(&fixture), &input).expect("stream");
+        let events: Vec<String> = stream
+            .map(|event| format!("{:?}", event.expect("event")))
+            .collect()
+            .await;
+
+        assert_eq!(
+            events,
+            vec![
+                format!("{:?}", ResponseEvent::Created),
+                format!(
+                    "{:?}",
+                    ResponseEvent::OutputItemDone(message("assistant", "done"))
+                ),
+                format!(
+                    "{:?}",
+                    ResponseEvent::Completed {
+                        response_id: "mock-resp-2".to_string(),
+                        token_usage: None,
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn exhausted_script_is_an_error() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let fixture = dir.path().join("mock.json");
+        std::fs::write(&fixture, r#"{ "responses": [] }"#).expect("write fixture");
+
+        let err = match stream_mock_response(Some(&fixture), &[message("user", "hi")]) {
+            Ok(_) => panic!("expected an error"),
+            Err(err) => err.to_string(),
+        };
+        assert_eq!(
+            err,
+            format!(
+                "mock script {} has no response #1 (it defines 0)",
+                fixture.display()
+            )
+        );
+    }
+}
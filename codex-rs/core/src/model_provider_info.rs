@@ -30,6 +30,8 @@ const MAX_REQUEST_MAX_RETRIES: u64 = 100;
 pub const CHAT_WIRE_API_DEPRECATION_SUMMARY: &str = r#"Support for the "chat" wire API is deprecated and will soon be removed. Update your model provider definition in config.toml to use wire_api = "responses"."#;
 
 const OPENAI_PROVIDER_NAME: &str = "OpenAI";
+const ANTHROPIC_DEFAULT_BASE_URL: &str = "https://api.anthropic.com/v1";
+const GEMINI_DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
 
 /// Wire protocol that the provider speaks. Most third-party services only
 /// implement the classic OpenAI Chat Completions JSON schema, whereas OpenAI
@@ -50,6 +52,18 @@ pub enum WireApi {
     /// Regular Chat Completions compatible with `/v1/chat/completions`.
     #[default]
     Chat,
+
+    /// Offline provider that replays scripted responses from
+    /// `mock_responses_file` instead of calling an API.
+    Mock,
+
+    /// Anthropic Messages API at `/v1/messages`, authenticated with the
+    /// `x-api-key` header.
+    Anthropic,
+
+    /// Gemini API `models/<model>:streamGenerateContent`, authenticated with
+    /// the `x-goog-api-key` header.
+    Gemini,
 }
 
 /// Serializable representation of a provider definition.
@@ -105,6 +119,38 @@ pub struct ModelProviderInfo {
     /// and API key (if needed) comes from the "env_key" environment variable.
     #[serde(default)]
     pub requires_openai_auth: bool,
+
+    /// Azure OpenAI `api-version` sent as a query parameter on every request.
+    /// An `api-version` entry in `query_params` takes precedence.
+    pub api_version: Option<String>,
+
+    /// Maps model slugs to Azure deployment names. The deployment is sent in
+    /// place of the model and substituted for `{deployment}` in `base_url`;
+    /// models without an entry are sent as-is.
+    pub deployments: Option<HashMap<String, String>>,
+
+    /// Authenticate with a Microsoft Entra ID token instead of an API key.
+    pub entra_id: Option<EntraIdConfig>,
+}
+
+/// Microsoft Entra ID (Azure AD) application used to obtain access tokens.
+///
+/// With `client_secret_env` set, tokens come from the client credentials
+/// grant. Otherwise the user signs in once with `codex login azure` (device
+/// code flow) and the stored refresh token is used from then on.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct EntraIdConfig {
+    /// Directory (tenant) ID or domain.
+    pub tenant_id: String,
+    /// Application (client) ID.
+    pub client_id: String,
+    /// Environment variable holding the client secret.
+    pub client_secret_env: Option<String>,
+    /// Token scope; defaults to `https://cognitiveservices.azure.com/.default`.
+    pub scope: Option<String>,
+    /// Authority host; defaults to `https://login.microsoftonline.com`.
+    pub authority: Option<String>,
 }
 
 impl ModelProviderInfo {
@@ -133,19 +179,60 @@ impl ModelProviderInfo {
         Ok(headers)
     }
 
-    pub(crate) fn to_api_provider(
-        &self,
-        auth_mode: Option<AuthMode>,
-    ) -> crate::error::Result<ApiProvider> {
-        let default_base_url = if matches!(auth_mode, Some(AuthMode::ChatGPT)) {
+    /// `base_url`, or the endpoint used when it is unset.
+    pub(crate) fn base_url_or_default(&self, auth_mode: Option<AuthMode>) -> String {
+        let default_base_url = if self.wire_api == WireApi::Anthropic {
+            ANTHROPIC_DEFAULT_BASE_URL
+        } else if self.wire_api == WireApi::Gemini {
+            GEMINI_DEFAULT_BASE_URL
+        } else if matches!(auth_mode, Some(AuthMode::ChatGPT)) {
             "https://chatgpt.com/backend-api/codex"
         } else {
             "https://api.openai.com/v1"
         };
-        let base_url = self
-            .base_url
+        self.base_url
             .clone()
-            .unwrap_or_else(|| default_base_url.to_string());
+            .unwrap_or_else(|| default_base_url.to_string())
+    }
+
+    /// Deployment configured for `model`, if any.
+    pub fn deployment_for(&self, model: &str) -> Option<&str> {
+        self.deployments
+            .as_ref()
+            .and_then(|deployments| deployments.get(model))
+            .map(String::as_str)
+    }
+
+    /// The model name to put on the wire: the deployment for `model` when one
+    /// is mapped, `model` otherwise.
+    pub fn request_model<'a>(&'a self, model: &'a str) -> &'a str {
+        self.deployment_for(model).unwrap_or(model)
+    }
+
+    /// This provider as used for `model`, with `{deployment}` in `base_url`
+    /// replaced by the model's deployment name.
+    pub fn for_model(&self, model: &str) -> ModelProviderInfo {
+        let mut provider = self.clone();
+        if let Some(base_url) = &mut provider.base_url
+            && base_url.contains("{deployment}")
+        {
+            *base_url = base_url.replace("{deployment}", self.request_model(model));
+        }
+        provider
+    }
+
+    pub(crate) fn to_api_provider(
+        &self,
+        auth_mode: Option<AuthMode>,
+    ) -> crate::error::Result<ApiProvider> {
+        let base_url = self.base_url_or_default(auth_mode);
+        let mut query_params = self.query_params.clone();
+        if let Some(api_version) = &self.api_version {
+            query_params
+                .get_or_insert_default()
+                .entry("api-version".to_string())
+                .or_insert_with(|| api_version.clone());
+        }
 
         let headers = self.build_header_map()?;
         let retry = ApiRetryConfig {
@@ -159,11 +246,14 @@ impl ModelProviderInfo {
         Ok(ApiProvider {
             name: self.name.clone(),
             base_url,
-            query_params: self.query_params.clone(),
+            query_params,
             wire: match self.wire_api {
                 WireApi::Responses => ApiWireApi::Responses,
                 WireApi::ResponsesWebsocket => ApiWireApi::Responses,
                 WireApi::Chat => ApiWireApi::Chat,
+                WireApi::Mock => ApiWireApi::Responses,
+                WireApi::Anthropic => ApiWireApi::Anthropic,
+                WireApi::Gemini => ApiWireApi::Gemini,
             },
             headers,
             retry,
@@ -254,6 +344,9 @@ impl ModelProviderInfo {
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
             requires_openai_auth: true,
+            api_version: None,
+            deployments: None,
+            entra_id: None,
         }
     }
 
@@ -268,6 +361,9 @@ pub const DEFAULT_OLLAMA_PORT: u16 = 11434;
 pub const LMSTUDIO_OSS_PROVIDER_ID: &str = "lmstudio";
 pub const OLLAMA_OSS_PROVIDER_ID: &str = "ollama";
 pub const OLLAMA_CHAT_PROVIDER_ID: &str = "ollama-chat";
+pub const MOCK_PROVIDER_ID: &str = "mock";
+pub const ANTHROPIC_PROVIDER_ID: &str = "anthropic";
+pub const GEMINI_PROVIDER_ID: &str = "gemini";
 
 /// Built-in default provider list.
 pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
@@ -291,6 +387,9 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
             LMSTUDIO_OSS_PROVIDER_ID,
             create_oss_provider(DEFAULT_LMSTUDIO_PORT, WireApi::Responses),
         ),
+        (MOCK_PROVIDER_ID, create_mock_provider()),
+        (ANTHROPIC_PROVIDER_ID, create_anthropic_provider()),
+        (GEMINI_PROVIDER_ID, create_gemini_provider()),
     ]
     .into_iter()
     .map(|(k, v)| (k.to_string(), v))
@@ -332,6 +431,87 @@ pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> M
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
         requires_openai_auth: false,
+        api_version: None,
+        deployments: None,
+        entra_id: None,
+    }
+}
+
+/// Scripted provider used with `model = "mock"`; see `mock_responses_file`.
+pub fn create_mock_provider() -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: "Mock".into(),
+        base_url: None,
+        env_key: None,
+        env_key_instructions: None,
+        experimental_bearer_token: None,
+        wire_api: WireApi::Mock,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: None,
+        requires_openai_auth: false,
+        api_version: None,
+        deployments: None,
+        entra_id: None,
+    }
+}
+
+/// Anthropic's Messages API, keyed by `ANTHROPIC_API_KEY`. Like the OpenAI
+/// provider, `ANTHROPIC_BASE_URL` points it at a proxy or gateway.
+pub fn create_anthropic_provider() -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: "Anthropic".into(),
+        base_url: std::env::var("ANTHROPIC_BASE_URL")
+            .ok()
+            .filter(|v| !v.trim().is_empty()),
+        env_key: Some("ANTHROPIC_API_KEY".into()),
+        env_key_instructions: Some(
+            "Create an API key at https://console.anthropic.com/settings/keys and export it as ANTHROPIC_API_KEY."
+                .into(),
+        ),
+        experimental_bearer_token: None,
+        wire_api: WireApi::Anthropic,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: None,
+        stream_max_retries: None,
+        stream_idle_timeout_ms: None,
+        requires_openai_auth: false,
+        api_version: None,
+        deployments: None,
+        entra_id: None,
+    }
+}
+
+/// Google's Gemini API, keyed by `GEMINI_API_KEY`; `GEMINI_BASE_URL`
+/// overrides the endpoint.
+pub fn create_gemini_provider() -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: "Gemini".into(),
+        base_url: std::env::var("GEMINI_BASE_URL")
+            .ok()
+            .filter(|v| !v.trim().is_empty()),
+        env_key: Some("GEMINI_API_KEY".into()),
+        env_key_instructions: Some(
+            "Create an API key at https://aistudio.google.com/apikey and export it as GEMINI_API_KEY."
+                .into(),
+        ),
+        experimental_bearer_token: None,
+        wire_api: WireApi::Gemini,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: None,
+        stream_max_retries: None,
+        stream_idle_timeout_ms: None,
+        requires_openai_auth: false,
+        api_version: None,
+        deployments: None,
+        entra_id: None,
     }
 }
 
@@ -360,6 +540,9 @@ base_url = "http://localhost:11434/v1"
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
+            api_version: None,
+            deployments: None,
+            entra_id: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -390,6 +573,9 @@ query_params = { api-version = "2025-04-01-preview" }
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
+            api_version: None,
+            deployments: None,
+            entra_id: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -423,12 +609,50 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
+            api_version: None,
+            deployments: None,
+            entra_id: None,
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
         assert_eq!(expected_provider, provider);
     }
 
+    #[test]
+    fn anthropic_provider_defaults_to_the_messages_api() {
+        let provider = ModelProviderInfo {
+            base_url: None,
+            ..create_anthropic_provider()
+        };
+
+        let api = provider.to_api_provider(None).expect("api provider");
+        assert_eq!(api.wire, ApiWireApi::Anthropic);
+        assert_eq!(
+            api.url_for_path("messages"),
+            "https://api.anthropic.com/v1/messages"
+        );
+        assert_eq!(provider.env_key.as_deref(), Some("ANTHROPIC_API_KEY"));
+        assert!(!provider.requires_openai_auth);
+    }
+
+    #[test]
+    fn gemini_provider_streams_generate_content_over_sse() {
+        let provider = ModelProviderInfo {
+            base_url: None,
+            query_params: Some(maplit::hashmap! {
+                "key".to_string() => "v".to_string(),
+            }),
+            ..create_gemini_provider()
+        };
+
+        let api = provider.to_api_provider(None).expect("api provider");
+        assert_eq!(api.wire, ApiWireApi::Gemini);
+        assert_eq!(
+            api.url_for_path("models/gemini-2.0-flash:streamGenerateContent?alt=sse"),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.0-flash:streamGenerateContent?alt=sse&key=v"
+        );
+    }
+
     #[test]
     fn detects_azure_responses_base_urls() {
         let positive_cases = [
@@ -454,6 +678,9 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 stream_max_retries: None,
                 stream_idle_timeout_ms: None,
                 requires_openai_auth: false,
+                api_version: None,
+                deployments: None,
+                entra_id: None,
             };
             let api = provider.to_api_provider(None).expect("api provider");
             assert!(
@@ -476,6 +703,9 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
             requires_openai_auth: false,
+            api_version: None,
+            deployments: None,
+            entra_id: None,
         };
         let named_api = named_provider.to_api_provider(None).expect("api provider");
         assert!(named_api.is_azure_responses_endpoint());
@@ -500,6 +730,9 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 stream_max_retries: None,
                 stream_idle_timeout_ms: None,
                 requires_openai_auth: false,
+                api_version: None,
+                deployments: None,
+                entra_id: None,
             };
             let api = provider.to_api_provider(None).expect("api provider");
             assert!(
@@ -508,4 +741,60 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             );
         }
     }
+
+    #[test]
+    fn azure_deployments_and_api_version_shape_requests() {
+        let provider: ModelProviderInfo = toml::from_str(
+            r#"
+name = "Azure"
+base_url = "https://example.openai.azure.com/openai/deployments/{deployment}"
+wire_api = "chat"
+api_version = "2024-10-21"
+deployments = { "gpt-4o" = "prod-gpt4o" }
+
+[entra_id]
+tenant_id = "contoso.onmicrosoft.com"
+client_id = "00000000-0000-0000-0000-000000000001"
+client_secret_env = "AZURE_CLIENT_SECRET"
+"#,
+        )
+        .expect("parse provider");
+
+        assert_eq!(
+            provider.entra_id,
+            Some(EntraIdConfig {
+                tenant_id: "contoso.onmicrosoft.com".into(),
+                client_id: "00000000-0000-0000-0000-000000000001".into(),
+                client_secret_env: Some("AZURE_CLIENT_SECRET".into()),
+                scope: None,
+                authority: None,
+            })
+        );
+        assert_eq!(provider.request_model("gpt-4o"), "prod-gpt4o");
+        assert_eq!(provider.request_model("o3"), "o3");
+
+        let api = provider
+            .for_model("gpt-4o")
+            .to_api_provider(None)
+            .expect("api provider");
+        assert_eq!(
+            api.url_for_path("chat/completions"),
+            "https://example.openai.azure.com/openai/deployments/prod-gpt4o/chat/completions?api-version=2024-10-21"
+        );
+
+        let pinned = ModelProviderInfo {
+            query_params: Some(maplit::hashmap! {
+                "api-version".to_string() => "preview".to_string(),
+            }),
+            ..provider
+        };
+        let api = pinned.to_api_provider(None).expect("api provider");
+        assert_eq!(
+            api.query_params
+                .as_ref()
+                .and_then(|q| q.get("api-version"))
+                .map(String::as_str),
+            Some("preview")
+        );
+    }
 }
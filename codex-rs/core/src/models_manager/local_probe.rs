@@ -0,0 +1,333 @@
+//! Capability probing for local model servers.
+//!
+//! Local models rarely match the built-in metadata: the context window is
+//! whatever the server was started with, and whether the model can be given
+//! tools depends on its chat template. Before a session starts, the server is
+//! asked directly, trying the native endpoints of Ollama (`/api/show`), LM
+//! Studio (`/api/v0/models/<model>`) and llama.cpp (`/props`) before the
+//! OpenAI-compatible `/models` list that vLLM annotates with `max_model_len`.
+//!
+//! A probed context window also lowers the auto-compaction threshold so that
+//! a small window keeps room for the next reply, and a model reported without
+//! tool support is driven through [`crate::text_tool_calls`] from the first
+//! request instead of after a rejected one.
+
+use std::time::Duration;
+
+use codex_protocol::openai_models::ModelInfo;
+use serde_json::Value;
+use serde_json::json;
+use tracing::debug;
+use tracing::info;
+
+use crate::config::Config;
+use crate::default_client::build_reqwest_client;
+use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
+use crate::model_provider_info::ModelProviderInfo;
+use crate::model_provider_info::OLLAMA_CHAT_PROVIDER_ID;
+use crate::model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Tokens kept free for the next reply when compacting a probed window.
+const REPLY_RESERVE_TOKENS: i64 = 4_096;
+
+/// What a local server reported about a model; `None` means it did not say.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct LocalModelCapabilities {
+    pub context_window: Option<i64>,
+    pub supports_tool_calls: Option<bool>,
+}
+
+impl LocalModelCapabilities {
+    fn is_empty(&self) -> bool {
+        self.context_window.is_none() && self.supports_tool_calls.is_none()
+    }
+}
+
+/// Whether the configured provider is a local server that should be probed.
+pub(crate) fn should_probe(config: &Config) -> bool {
+    if !config.local_models.probe {
+        return false;
+    }
+    let provider_id = config.model_provider_id.as_str();
+    matches!(
+        provider_id,
+        OLLAMA_OSS_PROVIDER_ID | OLLAMA_CHAT_PROVIDER_ID | LMSTUDIO_OSS_PROVIDER_ID
+    ) || config
+        .local_models
+        .providers
+        .iter()
+        .any(|id| id == provider_id)
+}
+
+/// Asks the provider's server about `model`. Endpoints the server does not
+/// implement are skipped; an unreachable server yields empty capabilities.
+pub(crate) async fn probe_local_model(
+    provider: &ModelProviderInfo,
+    model: &str,
+) -> LocalModelCapabilities {
+    let Some(base_url) = provider.base_url.as_deref() else {
+        return LocalModelCapabilities::default();
+    };
+    let base_url = base_url.trim_end_matches('/');
+    let root = base_url.strip_suffix("/v1").unwrap_or(base_url);
+    let api_key = provider.api_key().ok().flatten();
+    let client = build_reqwest_client();
+    let fetch = |request: reqwest::RequestBuilder| {
+        let request = match &api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        };
+        fetch_json(request)
+    };
+
+    let mut capabilities = fetch(
+        client
+            .post(format!("{root}/api/show"))
+            .json(&json!({"model": model})),
+    )
+    .await
+    .map(|body| parse_ollama_show(&body))
+    .unwrap_or_default();
+    if capabilities.is_empty() {
+        capabilities = fetch(client.get(format!("{root}/api/v0/models/{model}")))
+            .await
+            .map(|body| parse_lmstudio_model(&body))
+            .unwrap_or_default();
+    }
+    if capabilities.is_empty() {
+        capabilities = fetch(client.get(format!("{root}/props")))
+            .await
+            .map(|body| parse_llama_cpp_props(&body))
+            .unwrap_or_default();
+    }
+    if capabilities.is_empty() {
+        capabilities = fetch(client.get(format!("{base_url}/models")))
+            .await
+            .map(|body| parse_openai_models(&body, model))
+            .unwrap_or_default();
+    }
+    info!(
+        model,
+        context_window = ?capabilities.context_window,
+        supports_tool_calls = ?capabilities.supports_tool_calls,
+        "probed local model server"
+    );
+    capabilities
+}
+
+async fn fetch_json(request: reqwest::RequestBuilder) -> Option<Value> {
+    let response = match request.timeout(PROBE_TIMEOUT).send().await {
+        Ok(response) => response,
+        Err(err) => {
+            debug!("local model probe failed: {err}");
+            return None;
+        }
+    };
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json().await.ok()
+}
+
+/// Ollama serves `num_ctx` from the Modelfile when one is set, which is the
+/// window it actually runs with; otherwise the trained context length under
+/// `model_info.<arch>.context_length`. `capabilities` lists `tools` for
+/// models whose template accepts them.
+fn parse_ollama_show(body: &Value) -> LocalModelCapabilities {
+    let num_ctx = body
+        .get("parameters")
+        .and_then(Value::as_str)
+        .and_then(|parameters| {
+            parameters.lines().find_map(|line| {
+                let mut parts = line.split_whitespace();
+                (parts.next() == Some("num_ctx"))
+                    .then(|| parts.next()?.parse::<i64>().ok())
+                    .flatten()
+            })
+        });
+    let trained = body
+        .get("model_info")
+        .and_then(Value::as_object)
+        .and_then(|info| {
+            info.iter()
+                .find(|(key, _)| key.ends_with(".context_length"))
+                .and_then(|(_, value)| value.as_i64())
+        });
+    LocalModelCapabilities {
+        context_window: num_ctx.or(trained),
+        supports_tool_calls: capability_list(body, "tools"),
+    }
+}
+
+/// LM Studio reports the loaded context length while a model is loaded and
+/// the maximum otherwise; `capabilities` lists `tool_use`.
+fn parse_lmstudio_model(body: &Value) -> LocalModelCapabilities {
+    let context_window = body
+        .get("loaded_context_length")
+        .or_else(|| body.get("max_context_length"))
+        .and_then(Value::as_i64);
+    LocalModelCapabilities {
+        context_window,
+        supports_tool_calls: capability_list(body, "tool_use"),
+    }
+}
+
+/// llama.cpp reports the slot context size and, since it parses chat
+/// templates itself, whether the template handles tool calls.
+fn parse_llama_cpp_props(body: &Value) -> LocalModelCapabilities {
+    let context_window = body
+        .get("default_generation_settings")
+        .and_then(|settings| settings.get("n_ctx"))
+        .and_then(Value::as_i64);
+    let supports_tool_calls = body
+        .get("chat_template_caps")
+        .and_then(|caps| {
+            caps.get("supports_tool_calls")
+                .or_else(|| caps.get("supports_tools"))
+        })
+        .and_then(Value::as_bool)
+        .or_else(|| {
+            body.get("chat_template")
+                .and_then(Value::as_str)
+                .map(|template| template.contains("tools"))
+        });
+    LocalModelCapabilities {
+        context_window,
+        supports_tool_calls,
+    }
+}
+
+/// vLLM adds `max_model_len` to its `/models` entries; tool support depends
+/// on server flags it does not report.
+fn parse_openai_models(body: &Value, model: &str) -> LocalModelCapabilities {
+    let context_window = body
+        .get("data")
+        .and_then(Value::as_array)
+        .and_then(|models| {
+            models
+                .iter()
+                .find(|entry| entry.get("id").and_then(Value::as_str) == Some(model))
+        })
+        .and_then(|entry| entry.get("max_model_len"))
+        .and_then(Value::as_i64);
+    LocalModelCapabilities {
+        context_window,
+        supports_tool_calls: None,
+    }
+}
+
+fn capability_list(body: &Value, capability: &str) -> Option<bool> {
+    body.get("capabilities")
+        .and_then(Value::as_array)
+        .map(|capabilities| {
+            capabilities
+                .iter()
+                .any(|value| value.as_str() == Some(capability))
+        })
+}
+
+/// Applies probed capabilities to the built-in metadata. Explicit
+/// `model_context_window` and `model_auto_compact_token_limit` settings still
+/// win, as they are applied afterwards.
+pub(crate) fn apply_capabilities(
+    mut model: ModelInfo,
+    capabilities: LocalModelCapabilities,
+) -> ModelInfo {
+    if let Some(supports_tool_calls) = capabilities.supports_tool_calls {
+        model.supports_tool_calls = supports_tool_calls;
+    }
+    if let Some(context_window) = capabilities.context_window {
+        model.context_window = Some(context_window);
+        model.auto_compact_token_limit = Some(compact_token_limit(context_window));
+    }
+    model
+}
+
+/// 90% of the window, as for hosted models, but never closer than
+/// [`REPLY_RESERVE_TOKENS`] to the end and never below half of it.
+fn compact_token_limit(context_window: i64) -> i64 {
+    let reserve = (context_window / 10).max(REPLY_RESERVE_TOKENS);
+    (context_window - reserve).max(context_window / 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn ollama_prefers_num_ctx_over_trained_length() {
+        let body = json!({
+            "parameters": "stop \"<|im_end|>\"\nnum_ctx 16384",
+            "model_info": {"qwen2.context_length": 32768},
+            "capabilities": ["completion", "tools"],
+        });
+        assert_eq!(
+            parse_ollama_show(&body),
+            LocalModelCapabilities {
+                context_window: Some(16_384),
+                supports_tool_calls: Some(true),
+            }
+        );
+
+        let body = json!({
+            "model_info": {"llama.context_length": 8192},
+            "capabilities": ["completion"],
+        });
+        assert_eq!(
+            parse_ollama_show(&body),
+            LocalModelCapabilities {
+                context_window: Some(8_192),
+                supports_tool_calls: Some(false),
+            }
+        );
+    }
+
+    #[test]
+    fn lmstudio_llama_cpp_and_vllm_responses_are_parsed() {
+        assert_eq!(
+            parse_lmstudio_model(&json!({
+                "max_context_length": 131072,
+                "loaded_context_length": 8192,
+                "capabilities": ["tool_use"],
+            })),
+            LocalModelCapabilities {
+                context_window: Some(8_192),
+                supports_tool_calls: Some(true),
+            }
+        );
+        assert_eq!(
+            parse_llama_cpp_props(&json!({
+                "default_generation_settings": {"n_ctx": 4096},
+                "chat_template": "{{ bos_token }}{% for message in messages %}",
+            })),
+            LocalModelCapabilities {
+                context_window: Some(4_096),
+                supports_tool_calls: Some(false),
+            }
+        );
+        assert_eq!(
+            parse_openai_models(
+                &json!({"data": [
+                    {"id": "other", "max_model_len": 2048},
+                    {"id": "Qwen/Qwen2.5-Coder-7B", "max_model_len": 32768},
+                ]}),
+                "Qwen/Qwen2.5-Coder-7B",
+            ),
+            LocalModelCapabilities {
+                context_window: Some(32_768),
+                supports_tool_calls: None,
+            }
+        );
+    }
+
+    #[test]
+    fn small_windows_keep_room_for_the_reply() {
+        assert_eq!(compact_token_limit(4_096), 2_048);
+        assert_eq!(compact_token_limit(8_192), 4_096);
+        assert_eq!(compact_token_limit(32_768), 28_672);
+        assert_eq!(compact_token_limit(131_072), 117_965);
+    }
+}
@@ -9,6 +9,8 @@ use crate::error::Result as CoreResult;
 use crate::features::Feature;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::models_manager::collaboration_mode_presets::builtin_collaboration_mode_presets;
+use crate::models_manager::local_probe;
+use crate::models_manager::local_probe::LocalModelCapabilities;
 use crate::models_manager::model_info;
 use crate::models_manager::model_presets::builtin_model_presets;
 use codex_api::ModelsClient;
@@ -19,6 +21,7 @@ use codex_protocol::openai_models::ModelInfo;
 use codex_protocol::openai_models::ModelPreset;
 use codex_protocol::openai_models::ModelsResponse;
 use http::HeaderMap;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -31,7 +34,7 @@ const MODEL_CACHE_FILE: &str = "models_cache.json";
 const DEFAULT_MODEL_CACHE_TTL: Duration = Duration::from_secs(300);
 const MODELS_REFRESH_TIMEOUT: Duration = Duration::from_secs(5);
 const OPENAI_DEFAULT_API_MODEL: &str = "gpt-5.2-codex";
-const OPENAI_DEFAULT_CHATGPT_MODEL: &str = "gpt-5.2-codex";
+pub(crate) const OPENAI_DEFAULT_CHATGPT_MODEL: &str = "gpt-5.2-codex";
 const CODEX_AUTO_BALANCED_MODEL: &str = "codex-auto-balanced";
 
 /// Strategy for refreshing available models.
@@ -54,6 +57,8 @@ pub struct ModelsManager {
     etag: RwLock<Option<String>>,
     cache_manager: ModelsCacheManager,
     provider: ModelProviderInfo,
+    /// Probe results for local model servers, keyed by base URL and model.
+    local_capabilities: RwLock<HashMap<(String, String), LocalModelCapabilities>>,
 }
 
 impl ModelsManager {
@@ -70,6 +75,7 @@ impl ModelsManager {
             etag: RwLock::new(None),
             cache_manager,
             provider: ModelProviderInfo::create_openai_provider(),
+            local_capabilities: RwLock::new(HashMap::new()),
         }
     }
 
@@ -150,12 +156,39 @@ impl ModelsManager {
             .await
             .into_iter()
             .find(|m| m.slug == model);
-        let model = if let Some(remote) = remote {
+        let model_info = if let Some(remote) = remote {
             remote
         } else {
             model_info::find_model_info_for_slug(model)
         };
-        model_info::with_config_overrides(model, config)
+        let model_info = if local_probe::should_probe(config) {
+            let capabilities = self.local_model_capabilities(model, config).await;
+            local_probe::apply_capabilities(model_info, capabilities)
+        } else {
+            model_info
+        };
+        model_info::with_config_overrides(model_info, config)
+    }
+
+    /// Probes the configured local provider once per base URL and model.
+    async fn local_model_capabilities(
+        &self,
+        model: &str,
+        config: &Config,
+    ) -> LocalModelCapabilities {
+        let key = (
+            config.model_provider.base_url.clone().unwrap_or_default(),
+            model.to_string(),
+        );
+        if let Some(capabilities) = self.local_capabilities.read().await.get(&key) {
+            return *capabilities;
+        }
+        let capabilities = local_probe::probe_local_model(&config.model_provider, model).await;
+        self.local_capabilities
+            .write()
+            .await
+            .insert(key, capabilities);
+        capabilities
     }
 
     /// Refresh models if the provided ETag differs from the cached ETag.
@@ -330,6 +363,7 @@ impl ModelsManager {
             etag: RwLock::new(None),
             cache_manager,
             provider,
+            local_capabilities: RwLock::new(HashMap::new()),
         }
     }
 
@@ -432,6 +466,9 @@ mod tests {
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(5_000),
             requires_openai_auth: false,
+            api_version: None,
+            deployments: None,
+            entra_id: None,
         }
     }
 
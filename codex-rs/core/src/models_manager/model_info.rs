@@ -4,6 +4,7 @@ use codex_protocol::config_types::Personality;
 use codex_protocol::config_types::Verbosity;
 use codex_protocol::openai_models::ApplyPatchToolType;
 use codex_protocol::openai_models::ConfigShellToolType;
+use codex_protocol::openai_models::InputModality;
 use codex_protocol::openai_models::ModelInfo;
 use codex_protocol::openai_models::ModelInstructionsTemplate;
 use codex_protocol::openai_models::ModelVisibility;
@@ -12,6 +13,7 @@ use codex_protocol::openai_models::ReasoningEffort;
 use codex_protocol::openai_models::ReasoningEffortPreset;
 use codex_protocol::openai_models::TruncationMode;
 use codex_protocol::openai_models::TruncationPolicyConfig;
+use codex_protocol::openai_models::default_input_modalities;
 
 use crate::config::Config;
 use crate::truncate::approx_bytes_for_tokens;
@@ -33,6 +35,8 @@ const PERSONALITY_FRIENDLY: &str = include_str!("../../templates/personalities/f
 const PERSONALITY_PRAGMATIC: &str = include_str!("../../templates/personalities/pragmatic.md");
 
 pub(crate) const CONTEXT_WINDOW_272K: i64 = 272_000;
+const CONTEXT_WINDOW_200K: i64 = 200_000;
+const CONTEXT_WINDOW_1M: i64 = 1_048_576;
 
 macro_rules! model_info {
     (
@@ -65,6 +69,8 @@ macro_rules! model_info {
             auto_compact_token_limit: None,
             effective_context_window_percent: 95,
             experimental_supported_tools: Vec::new(),
+            input_modalities: default_input_modalities(),
+            supports_tool_calls: true,
         };
 
         $(
@@ -78,6 +84,16 @@ pub(crate) fn with_config_overrides(mut model: ModelInfo, config: &Config) -> Mo
     if let Some(supports_reasoning_summaries) = config.model_supports_reasoning_summaries {
         model.supports_reasoning_summaries = supports_reasoning_summaries;
     }
+    if let Some(supports_images) = config.model_supports_images {
+        model.input_modalities = if supports_images {
+            default_input_modalities()
+        } else {
+            vec![InputModality::Text]
+        };
+    }
+    if let Some(supports_tool_calls) = config.model_supports_tool_calls {
+        model.supports_tool_calls = supports_tool_calls;
+    }
     if let Some(context_window) = config.model_context_window {
         model.context_window = Some(context_window);
     }
@@ -134,6 +150,7 @@ pub(crate) fn find_model_info_for_slug(slug: &str) -> ModelInfo {
             slug,
             apply_patch_tool_type: Some(ApplyPatchToolType::Function),
             context_window: Some(96_000),
+            input_modalities: vec![InputModality::Text],
         )
     } else if slug.starts_with("gpt-4o") {
         model_info!(
@@ -148,6 +165,7 @@ pub(crate) fn find_model_info_for_slug(slug: &str) -> ModelInfo {
             base_instructions: BASE_INSTRUCTIONS_WITH_APPLY_PATCH.to_string(),
             supports_reasoning_summaries: false,
             context_window: Some(16_385),
+            input_modalities: vec![InputModality::Text],
         )
     } else if slug.starts_with("test-gpt-5") {
         model_info!(
@@ -301,6 +319,26 @@ pub(crate) fn find_model_info_for_slug(slug: &str) -> ModelInfo {
             truncation_policy: TruncationPolicyConfig::bytes(10_000),
             context_window: Some(CONTEXT_WINDOW_272K),
         )
+    } else if slug.starts_with("claude") {
+        model_info!(
+            slug,
+            apply_patch_tool_type: Some(ApplyPatchToolType::Function),
+            shell_type: ConfigShellToolType::ShellCommand,
+            supports_parallel_tool_calls: true,
+            truncation_policy: TruncationPolicyConfig::tokens(10_000),
+            context_window: Some(CONTEXT_WINDOW_200K),
+        )
+    } else if slug.starts_with("gemini") {
+        model_info!(
+            slug,
+            apply_patch_tool_type: Some(ApplyPatchToolType::Function),
+            shell_type: ConfigShellToolType::ShellCommand,
+            supports_parallel_tool_calls: true,
+            truncation_policy: TruncationPolicyConfig::tokens(10_000),
+            context_window: Some(CONTEXT_WINDOW_1M),
+            supported_reasoning_levels: Vec::new(),
+            default_reasoning_level: None,
+        )
     } else {
         warn!("Unknown model {slug} is used. This will degrade the performance of Codex.");
         model_info!(
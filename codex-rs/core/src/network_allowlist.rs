@@ -0,0 +1,73 @@
+//! Domain allowlist for sandboxed network access
+//! (`sandbox_workspace_write.network_allowlist`).
+//!
+//! When a workspace-write sandbox has `network_access = false` but lists
+//! domains here, the session starts a filtering instance of the
+//! [`NetworkCapture`](crate::network_capture::NetworkCapture) proxy. Commands
+//! get the proxy through the usual `HTTP_PROXY` family of variables, and the
+//! platform sandbox lets them reach only the proxy's loopback port, so every
+//! connection goes through the host check below. Requests for other hosts are
+//! answered with `403 Forbidden` and show up in the turn's network activity.
+
+/// Hosts the filtering proxy may connect to. An entry matches the domain
+/// itself and all of its subdomains; a leading `*.` is accepted and ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NetworkAllowlist {
+    domains: Vec<String>,
+}
+
+impl NetworkAllowlist {
+    /// Returns `None` when no usable domain is listed.
+    pub(crate) fn new(domains: &[String]) -> Option<Self> {
+        let domains: Vec<String> = domains
+            .iter()
+            .map(|domain| normalize_host(domain.trim().trim_start_matches("*.")))
+            .filter(|domain| !domain.is_empty())
+            .collect();
+        (!domains.is_empty()).then_some(Self { domains })
+    }
+
+    pub(crate) fn allows(&self, host: &str) -> bool {
+        let host = normalize_host(host);
+        self.domains.iter().any(|domain| {
+            host == *domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn allowlist(domains: &[&str]) -> Option<NetworkAllowlist> {
+        let domains: Vec<String> = domains.iter().map(ToString::to_string).collect();
+        NetworkAllowlist::new(&domains)
+    }
+
+    #[test]
+    fn matches_domains_and_subdomains() {
+        let allowlist = allowlist(&["crates.io", "*.GitHub.com", " "]).expect("allowlist");
+
+        assert!(allowlist.allows("crates.io"));
+        assert!(allowlist.allows("static.crates.io."));
+        assert!(allowlist.allows("github.com"));
+        assert!(allowlist.allows("codeload.github.com"));
+        assert!(!allowlist.allows("notcrates.io"));
+        assert!(!allowlist.allows("crates.io.evil.com"));
+        assert!(!allowlist.allows("example.com"));
+    }
+
+    #[test]
+    fn empty_allowlist_is_none() {
+        assert_eq!(allowlist(&[]), None);
+        assert_eq!(allowlist(&["", "*."]), None);
+    }
+}
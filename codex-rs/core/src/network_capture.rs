@@ -0,0 +1,532 @@
+//! Records the outbound connections made by agent-run commands.
+//!
+//! With the `network_capture` feature enabled, each session starts a local
+//! HTTP proxy and exports it through `HTTP_PROXY` / `HTTPS_PROXY` /
+//! `ALL_PROXY` to commands run in turns that have network access. The proxy
+//! relays `CONNECT` tunnels and plain absolute-form HTTP requests, and records
+//! host, port, and byte counts for each connection once it closes. At the end
+//! of a turn the recorded connections are reported as a `NetworkActivity`
+//! event, which is also persisted to the rollout for later audit.
+//!
+//! Only clients that honor the proxy variables are captured; a command that
+//! opens sockets directly bypasses the log.
+//!
+//! The same proxy also enforces `sandbox_workspace_write.network_allowlist`
+//! (see [`crate::network_allowlist`]): a filtering instance refuses hosts
+//! outside the allowlist and exports its port so the sandbox can admit it.
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use codex_protocol::protocol::NetworkConnection;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::config::types::EnvironmentVariablePattern;
+use crate::config::types::ShellEnvironmentPolicy;
+use crate::network_allowlist::NetworkAllowlist;
+use crate::spawn::CODEX_SANDBOX_NETWORK_PROXY_PORT_ENV_VAR;
+
+const PROXY_ENV_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+
+/// Requests with a longer head than this are rejected.
+const MAX_REQUEST_HEAD_BYTES: usize = 64 * 1024;
+
+/// Error recorded for requests refused by the allowlist.
+const BLOCKED_BY_ALLOWLIST: &str = "blocked by network allowlist";
+
+/// Ports of the filtering proxies running in this process. The port a command
+/// carries in its environment is only admitted by the sandbox if it is here.
+static FILTERING_PROXY_PORTS: LazyLock<Mutex<HashSet<u16>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Whether `port` belongs to a running allowlist proxy of this process.
+pub(crate) fn is_filtering_proxy_port(port: u16) -> bool {
+    match FILTERING_PROXY_PORTS.lock() {
+        Ok(ports) => ports.contains(&port),
+        Err(poisoned) => poisoned.into_inner().contains(&port),
+    }
+}
+
+fn set_filtering_proxy_port(port: u16, running: bool) {
+    let mut ports = match FILTERING_PROXY_PORTS.lock() {
+        Ok(ports) => ports,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if running {
+        ports.insert(port);
+    } else {
+        ports.remove(&port);
+    }
+}
+
+pub(crate) struct NetworkCapture {
+    addr: SocketAddr,
+    connections: Arc<Mutex<Vec<NetworkConnection>>>,
+    shutdown: CancellationToken,
+    allowlist: Option<Arc<NetworkAllowlist>>,
+}
+
+impl NetworkCapture {
+    /// Binds the proxy on a loopback port and starts accepting connections.
+    pub(crate) async fn start() -> std::io::Result<Self> {
+        Self::bind(None).await
+    }
+
+    /// Starts a proxy that only connects to hosts on `allowlist`.
+    pub(crate) async fn start_filtered(allowlist: NetworkAllowlist) -> std::io::Result<Self> {
+        Self::bind(Some(Arc::new(allowlist))).await
+    }
+
+    async fn bind(allowlist: Option<Arc<NetworkAllowlist>>) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let connections = Arc::new(Mutex::new(Vec::new()));
+        let shutdown = CancellationToken::new();
+        if allowlist.is_some() {
+            set_filtering_proxy_port(addr.port(), true);
+        }
+        tokio::spawn(accept_loop(
+            listener,
+            Arc::clone(&connections),
+            shutdown.clone(),
+            allowlist.clone(),
+        ));
+        Ok(Self {
+            addr,
+            connections,
+            shutdown,
+            allowlist,
+        })
+    }
+
+    fn proxy_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Routes commands run under `policy` through the proxy, even when the
+    /// policy restricts inherited variables. A filtering proxy also exports
+    /// its port for the sandbox.
+    pub(crate) fn apply_to_env_policy(&self, policy: &mut ShellEnvironmentPolicy) {
+        let url = self.proxy_url();
+        let mut vars: Vec<(&str, String)> = PROXY_ENV_VARS
+            .iter()
+            .map(|name| (*name, url.clone()))
+            .collect();
+        if self.allowlist.is_some() {
+            vars.push((
+                CODEX_SANDBOX_NETWORK_PROXY_PORT_ENV_VAR,
+                self.addr.port().to_string(),
+            ));
+        }
+        for (name, value) in vars {
+            policy.r#set.insert(name.to_string(), value);
+            if !policy.include_only.is_empty() {
+                policy
+                    .include_only
+                    .push(EnvironmentVariablePattern::new_case_insensitive(name));
+            }
+        }
+    }
+
+    /// Returns the connections closed since the last call.
+    pub(crate) fn take_connections(&self) -> Vec<NetworkConnection> {
+        match self.connections.lock() {
+            Ok(mut guard) => std::mem::take(&mut *guard),
+            Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+        }
+    }
+
+    pub(crate) fn shutdown(&self) {
+        self.shutdown.cancel();
+        if self.allowlist.is_some() {
+            set_filtering_proxy_port(self.addr.port(), false);
+        }
+    }
+}
+
+impl Drop for NetworkCapture {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+async fn accept_loop(
+    listener: TcpListener,
+    connections: Arc<Mutex<Vec<NetworkConnection>>>,
+    shutdown: CancellationToken,
+    allowlist: Option<Arc<NetworkAllowlist>>,
+) {
+    loop {
+        let accepted = tokio::select! {
+            _ = shutdown.cancelled() => return,
+            accepted = listener.accept() => accepted,
+        };
+        let (client, _) = match accepted {
+            Ok(accepted) => accepted,
+            Err(err) => {
+                debug!("network capture proxy accept failed: {err}");
+                continue;
+            }
+        };
+        let connections = Arc::clone(&connections);
+        let shutdown = shutdown.clone();
+        let allowlist = allowlist.clone();
+        tokio::spawn(async move {
+            let relayed = tokio::select! {
+                _ = shutdown.cancelled() => return,
+                relayed = relay(client, allowlist.as_deref()) => relayed,
+            };
+            if let Some(connection) = relayed {
+                debug!(
+                    "network capture: {} {}:{} sent={} received={}",
+                    connection.method,
+                    connection.host,
+                    connection.port,
+                    connection.bytes_sent,
+                    connection.bytes_received
+                );
+                match connections.lock() {
+                    Ok(mut guard) => guard.push(connection),
+                    Err(poisoned) => poisoned.into_inner().push(connection),
+                }
+            }
+        });
+    }
+}
+
+/// Relays one client connection. Returns `None` when the client did not send
+/// a usable proxy request.
+async fn relay(
+    mut client: TcpStream,
+    allowlist: Option<&NetworkAllowlist>,
+) -> Option<NetworkConnection> {
+    let (head, rest) = read_request_head(&mut client).await?;
+    let request = parse_request_head(&head)?;
+    let mut connection = NetworkConnection {
+        host: request.host.clone(),
+        port: request.port,
+        bytes_sent: 0,
+        bytes_received: 0,
+        method: request.method.clone(),
+        error: None,
+    };
+
+    if allowlist.is_some_and(|allowlist| !allowlist.allows(&request.host)) {
+        let _ = client
+            .write_all(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n")
+            .await;
+        connection.error = Some(BLOCKED_BY_ALLOWLIST.to_string());
+        return Some(connection);
+    }
+
+    let mut upstream = match TcpStream::connect((request.host.as_str(), request.port)).await {
+        Ok(upstream) => upstream,
+        Err(err) => {
+            let _ = client
+                .write_all(b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\n\r\n")
+                .await;
+            connection.error = Some(err.to_string());
+            return Some(connection);
+        }
+    };
+
+    let forwarded = if request.is_connect() {
+        if client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await
+            .is_err()
+        {
+            return Some(connection);
+        }
+        rest
+    } else {
+        let mut forwarded = request.origin_form_head(&head).into_bytes();
+        forwarded.extend_from_slice(&rest);
+        forwarded
+    };
+    if !forwarded.is_empty() {
+        if let Err(err) = upstream.write_all(&forwarded).await {
+            connection.error = Some(err.to_string());
+            return Some(connection);
+        }
+        connection.bytes_sent += forwarded.len() as u64;
+    }
+
+    match tokio::io::copy_bidirectional(&mut client, &mut upstream).await {
+        Ok((sent, received)) => {
+            connection.bytes_sent += sent;
+            connection.bytes_received += received;
+        }
+        // A reset mid-stream is normal for aborted downloads; keep what we know.
+        Err(err) => debug!("network capture relay ended: {err}"),
+    }
+    Some(connection)
+}
+
+/// Reads up to the end of the request head. Returns the head (without the
+/// blank line) and any bytes received after it.
+async fn read_request_head(client: &mut TcpStream) -> Option<(String, Vec<u8>)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = client.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..read]);
+        if let Some(end) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            let head = String::from_utf8(buf[..end].to_vec()).ok()?;
+            return Some((head, buf[end + 4..].to_vec()));
+        }
+        if buf.len() > MAX_REQUEST_HEAD_BYTES {
+            return None;
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct ProxyRequest {
+    method: String,
+    host: String,
+    port: u16,
+    /// Path and query for plain HTTP requests.
+    path: String,
+}
+
+impl ProxyRequest {
+    fn is_connect(&self) -> bool {
+        self.method.eq_ignore_ascii_case("CONNECT")
+    }
+
+    /// Rewrites the absolute-form request line to the origin form the
+    /// upstream server expects.
+    fn origin_form_head(&self, head: &str) -> String {
+        let (request_line, headers) = head.split_once("\r\n").unwrap_or((head, ""));
+        let version = request_line.rsplit(' ').next().unwrap_or("HTTP/1.1");
+        let mut out = format!("{} {} {version}\r\n", self.method, self.path);
+        if !headers.is_empty() {
+            out.push_str(headers);
+            out.push_str("\r\n");
+        }
+        out.push_str("\r\n");
+        out
+    }
+}
+
+fn parse_request_head(head: &str) -> Option<ProxyRequest> {
+    let request_line = head.lines().next()?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?;
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_host_port(target, 443)?;
+        return Some(ProxyRequest {
+            method,
+            host,
+            port,
+            path: String::new(),
+        });
+    }
+
+    let rest = target.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = split_host_port(authority, 80)?;
+    Some(ProxyRequest {
+        method,
+        host,
+        port,
+        path: path.to_string(),
+    })
+}
+
+fn split_host_port(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    // Bracketed IPv6 literal, e.g. `[::1]:8080`.
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, after) = rest.split_once(']')?;
+        let port = match after.strip_prefix(':') {
+            Some(port) => port.parse().ok()?,
+            None => default_port,
+        };
+        return Some((host.to_string(), port));
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) => Some((host.to_string(), port.parse().ok()?)),
+        None => Some((authority.to_string(), default_port)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_connect_and_absolute_form_requests() {
+        assert_eq!(
+            parse_request_head("CONNECT api.github.com:443 HTTP/1.1\r\nHost: api.github.com"),
+            Some(ProxyRequest {
+                method: "CONNECT".to_string(),
+                host: "api.github.com".to_string(),
+                port: 443,
+                path: String::new(),
+            })
+        );
+        assert_eq!(
+            parse_request_head("GET http://example.com/a?b=1 HTTP/1.1\r\nHost: example.com"),
+            Some(ProxyRequest {
+                method: "GET".to_string(),
+                host: "example.com".to_string(),
+                port: 80,
+                path: "/a?b=1".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_request_head("GET http://[::1]:8080 HTTP/1.1").map(|r| (r.host, r.port)),
+            Some(("::1".to_string(), 8080))
+        );
+        assert_eq!(parse_request_head("GET /relative HTTP/1.1"), None);
+    }
+
+    #[test]
+    fn rewrites_request_line_to_origin_form() {
+        let head = "GET http://example.com/a HTTP/1.1\r\nHost: example.com";
+        let request = parse_request_head(head).expect("parse");
+        assert_eq!(
+            request.origin_form_head(head),
+            "GET /a HTTP/1.1\r\nHost: example.com\r\n\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn records_tunneled_connection_bytes() -> anyhow::Result<()> {
+        let upstream = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let upstream_port = upstream.local_addr()?.port();
+        tokio::spawn(async move {
+            if let Ok((mut socket, _)) = upstream.accept().await {
+                let mut buf = [0u8; 4];
+                if socket.read_exact(&mut buf).await.is_ok() {
+                    let _ = socket.write_all(b"pong!").await;
+                }
+            }
+        });
+
+        let capture = NetworkCapture::start().await?;
+        let mut client = TcpStream::connect(capture.addr).await?;
+        client
+            .write_all(format!("CONNECT 127.0.0.1:{upstream_port} HTTP/1.1\r\n\r\n").as_bytes())
+            .await?;
+        let mut established = [0u8; 39];
+        client.read_exact(&mut established).await?;
+        client.write_all(b"ping").await?;
+        let mut reply = Vec::new();
+        client.read_to_end(&mut reply).await?;
+        assert_eq!(reply, b"pong!");
+        drop(client);
+
+        let mut connections = Vec::new();
+        for _ in 0..50 {
+            connections = capture.take_connections();
+            if !connections.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            connections,
+            vec![NetworkConnection {
+                host: "127.0.0.1".to_string(),
+                port: upstream_port,
+                bytes_sent: 4,
+                bytes_received: 5,
+                method: "CONNECT".to_string(),
+                error: None,
+            }]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn filtered_proxy_refuses_hosts_outside_allowlist() -> anyhow::Result<()> {
+        let allowlist =
+            NetworkAllowlist::new(&["crates.io".to_string()]).expect("non-empty allowlist");
+        let capture = NetworkCapture::start_filtered(allowlist).await?;
+        let mut client = TcpStream::connect(capture.addr).await?;
+        client
+            .write_all(b"CONNECT example.com:443 HTTP/1.1\r\n\r\n")
+            .await?;
+        let mut reply = Vec::new();
+        client.read_to_end(&mut reply).await?;
+        assert!(reply.starts_with(b"HTTP/1.1 403 Forbidden"));
+
+        let mut connections = Vec::new();
+        for _ in 0..50 {
+            connections = capture.take_connections();
+            if !connections.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            connections,
+            vec![NetworkConnection {
+                host: "example.com".to_string(),
+                port: 443,
+                bytes_sent: 0,
+                bytes_received: 0,
+                method: "CONNECT".to_string(),
+                error: Some(BLOCKED_BY_ALLOWLIST.to_string()),
+            }]
+        );
+
+        assert!(is_filtering_proxy_port(capture.addr.port()));
+        capture.shutdown();
+        assert!(!is_filtering_proxy_port(capture.addr.port()));
+        Ok(())
+    }
+
+    #[test]
+    fn env_policy_exports_proxy_variables() {
+        let capture = NetworkCapture {
+            addr: SocketAddr::from(([127, 0, 0, 1], 3128)),
+            connections: Arc::default(),
+            shutdown: CancellationToken::new(),
+            allowlist: None,
+        };
+        let mut policy = ShellEnvironmentPolicy {
+            include_only: vec![EnvironmentVariablePattern::new_case_insensitive("PATH")],
+            ..Default::default()
+        };
+
+        capture.apply_to_env_policy(&mut policy);
+
+        let env = crate::exec_env::create_env(&policy);
+        assert_eq!(
+            env.get("HTTPS_PROXY").map(String::as_str),
+            Some("http://127.0.0.1:3128")
+        );
+        assert_eq!(
+            env.get("http_proxy").map(String::as_str),
+            Some("http://127.0.0.1:3128")
+        );
+        assert_eq!(env.get(CODEX_SANDBOX_NETWORK_PROXY_PORT_ENV_VAR), None);
+    }
+}
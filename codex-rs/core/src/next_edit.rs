@@ -0,0 +1,329 @@
+//! Inline "next edit" suggestions (`Op::SuggestNextEdit`).
+//!
+//! A side request, outside the session's turns, shows a small (usually fast)
+//! model the last messages of the conversation and the file around the
+//! user's cursor, and asks for the text the user is about to type there. The
+//! history is not touched and nothing is recorded in the rollout.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_protocol::items::AgentMessageContent;
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::NextEditSuggestionEvent;
+use futures::StreamExt;
+use tracing::warn;
+
+use crate::Prompt;
+use crate::client::ModelClient;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::event_mapping::parse_turn_item;
+
+/// Editors cancel stale requests quickly; give up well before a person would
+/// notice the ghost text arriving late.
+const SUGGESTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most recent user / assistant messages shown to the model.
+const MAX_RECENT_MESSAGES: usize = 6;
+const MAX_MESSAGE_CHARS: usize = 1_500;
+const MAX_PREFIX_CHARS: usize = 6_000;
+const MAX_SUFFIX_CHARS: usize = 2_000;
+
+const CURSOR_MARKER: &str = "<|cursor|>";
+const NO_SUGGESTION: &str = "NONE";
+
+const INSTRUCTIONS: &str = "You predict the next edit a developer makes in their editor while working with a coding agent. \
+You get the recent conversation with the agent and a file with the cursor marked as <|cursor|>. \
+Reply with only the text to insert at the cursor: no explanation, no code fences, and nothing that repeats text before or after the cursor. \
+Keep it short (at most a few lines) and consistent with the surrounding code and the conversation. \
+Reply with exactly NONE when there is no confident suggestion.";
+
+pub(crate) struct NextEditRequest {
+    pub request_id: String,
+    pub path: PathBuf,
+    pub prefix: String,
+    pub suffix: String,
+    pub model: Option<String>,
+}
+
+/// Answers with `EventMsg::NextEditSuggestion` from a spawned task, so the
+/// submission loop is free while the model runs.
+pub(crate) async fn suggest_next_edit(
+    sess: &Arc<Session>,
+    sub_id: String,
+    request: NextEditRequest,
+) {
+    let sess = Arc::clone(sess);
+    let turn = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+    tokio::spawn(async move {
+        let result =
+            tokio::time::timeout(SUGGESTION_TIMEOUT, suggest(&sess, &turn, &request)).await;
+        let text = match result {
+            Ok(Ok(text)) => text,
+            Ok(Err(err)) => {
+                warn!("next edit suggestion failed: {err}");
+                None
+            }
+            Err(_) => {
+                warn!(
+                    "next edit suggestion timed out after {}s",
+                    SUGGESTION_TIMEOUT.as_secs()
+                );
+                None
+            }
+        };
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::NextEditSuggestion(NextEditSuggestionEvent {
+                request_id: request.request_id,
+                text,
+            }),
+        })
+        .await;
+    });
+}
+
+async fn suggest(
+    sess: &Session,
+    turn: &TurnContext,
+    request: &NextEditRequest,
+) -> Result<Option<String>, String> {
+    let model = request
+        .model
+        .clone()
+        .unwrap_or_else(|| turn.client.get_model());
+    let client = next_edit_client(sess, turn, &model).await;
+    let history = sess.clone_history().await;
+    let prompt = Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: build_input(
+                    &recent_messages(history.raw_items()),
+                    &request.path,
+                    &request.prefix,
+                    &request.suffix,
+                ),
+            }],
+            end_turn: None,
+        }],
+        base_instructions: BaseInstructions {
+            text: INSTRUCTIONS.to_string(),
+        },
+        ..Default::default()
+    };
+    let mut client_session = client.new_session();
+    let mut stream = client_session
+        .stream(&prompt)
+        .await
+        .map_err(|err| err.to_string())?;
+    let mut text = String::new();
+    while let Some(event) = stream.next().await {
+        match event.map_err(|err| err.to_string())? {
+            ResponseEvent::OutputItemDone(ResponseItem::Message { role, content, .. })
+                if role == "assistant" =>
+            {
+                for item in content {
+                    if let ContentItem::OutputText { text: chunk } = item {
+                        text.push_str(&chunk);
+                    }
+                }
+            }
+            ResponseEvent::Completed { .. } => return Ok(parse_suggestion(&text, &request.suffix)),
+            _ => {}
+        }
+    }
+    Err("stream closed before response.completed".to_string())
+}
+
+async fn next_edit_client(session: &Session, turn: &TurnContext, model: &str) -> ModelClient {
+    let mut config = (*turn.client.config()).clone();
+    config.model = Some(model.to_string());
+    let model_info = session
+        .services
+        .models_manager
+        .get_model_info(model, &config)
+        .await;
+    let otel_manager = turn
+        .client
+        .get_otel_manager()
+        .with_model(model, model_info.slug.as_str());
+    let config = Arc::new(config);
+    ModelClient::new(
+        config.clone(),
+        turn.client.get_auth_manager(),
+        model_info,
+        otel_manager,
+        turn.client.get_provider(),
+        config.model_reasoning_effort,
+        config.model_reasoning_summary,
+        session.conversation_id,
+        turn.client.get_session_source(),
+    )
+}
+
+/// The last user and assistant messages, oldest first, as `role: text`.
+fn recent_messages(items: &[ResponseItem]) -> Vec<String> {
+    let mut messages: Vec<String> = items
+        .iter()
+        .rev()
+        .filter_map(|item| match parse_turn_item(item)? {
+            TurnItem::UserMessage(message) => Some(format!("user: {}", message.message())),
+            TurnItem::AgentMessage(message) => {
+                let text: String = message
+                    .content
+                    .iter()
+                    .map(|AgentMessageContent::Text { text }| text.as_str())
+                    .collect();
+                Some(format!("assistant: {text}"))
+            }
+            _ => None,
+        })
+        .take(MAX_RECENT_MESSAGES)
+        .map(|message| truncate_end(&message, MAX_MESSAGE_CHARS))
+        .collect();
+    messages.reverse();
+    messages
+}
+
+fn build_input(messages: &[String], path: &Path, prefix: &str, suffix: &str) -> String {
+    let mut input = String::new();
+    if !messages.is_empty() {
+        input.push_str("Recent conversation:\n");
+        for message in messages {
+            input.push_str(message);
+            input.push('\n');
+        }
+        input.push('\n');
+    }
+    input.push_str(&format!("File: {}\n```\n", path.display()));
+    input.push_str(&truncate_start(prefix, MAX_PREFIX_CHARS));
+    input.push_str(CURSOR_MARKER);
+    input.push_str(&truncate_end(suffix, MAX_SUFFIX_CHARS));
+    input.push_str("\n```\n");
+    input
+}
+
+/// The text to insert, without code fences, the cursor marker, or text that
+/// `suffix` already starts with. `None` for `NONE` or an empty answer.
+fn parse_suggestion(text: &str, suffix: &str) -> Option<String> {
+    let mut text = text.trim_end();
+    if text.trim_start().starts_with("```") {
+        let body = text.trim_start();
+        let body = body.split_once('\n').map_or("", |(_, rest)| rest);
+        text = body.strip_suffix("```").unwrap_or(body).trim_end();
+    }
+    if text.trim() == NO_SUGGESTION {
+        return None;
+    }
+    let text = text.replace(CURSOR_MARKER, "");
+    let suffix_line = suffix.lines().next().unwrap_or("").trim();
+    let text = if !suffix_line.is_empty() {
+        text.strip_suffix(suffix_line).unwrap_or(&text).to_string()
+    } else {
+        text
+    };
+    if text.trim().is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn truncate_start(text: &str, max_chars: usize) -> String {
+    let count = text.chars().count();
+    text.chars().skip(count.saturating_sub(max_chars)).collect()
+}
+
+fn truncate_end(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        let content = if role == "assistant" {
+            ContentItem::OutputText {
+                text: text.to_string(),
+            }
+        } else {
+            ContentItem::InputText {
+                text: text.to_string(),
+            }
+        };
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![content],
+            end_turn: None,
+        }
+    }
+
+    #[test]
+    fn recent_messages_keeps_the_latest_turns_in_order() {
+        let mut items = vec![message(
+            "user",
+            "<environment_context>\n  <cwd>/repo</cwd>\n</environment_context>",
+        )];
+        for i in 0..5 {
+            items.push(message("user", &format!("question {i}")));
+            items.push(message("assistant", &format!("answer {i}")));
+        }
+
+        assert_eq!(
+            recent_messages(&items),
+            vec![
+                "user: question 2",
+                "assistant: answer 2",
+                "user: question 3",
+                "assistant: answer 3",
+                "user: question 4",
+                "assistant: answer 4",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_suggestion_strips_fences_and_the_existing_suffix() {
+        assert_eq!(
+            parse_suggestion("```rust\nlet x = 1;\n```", ""),
+            Some("let x = 1;".to_string())
+        );
+        assert_eq!(
+            parse_suggestion("foo(bar);", ");\nnext line"),
+            Some("foo(bar".to_string())
+        );
+        assert_eq!(parse_suggestion("NONE", ""), None);
+        assert_eq!(parse_suggestion("  \n", ""), None);
+    }
+
+    #[test]
+    fn build_input_marks_the_cursor_and_truncates_the_prefix_from_the_start() {
+        let prefix = format!("{}fn main() {{\n    ", "x".repeat(MAX_PREFIX_CHARS));
+        let input = build_input(
+            &["user: add logging".to_string()],
+            Path::new("src/main.rs"),
+            &prefix,
+            "\n}",
+        );
+
+        assert!(
+            input.starts_with("Recent conversation:\nuser: add logging\n\nFile: src/main.rs\n")
+        );
+        assert!(input.contains("fn main() {\n    <|cursor|>\n}"));
+        assert!(input.len() < MAX_PREFIX_CHARS + 200);
+    }
+}
@@ -0,0 +1,156 @@
+//! Offline mode for isolated networks.
+//!
+//! With `[offline]` enabled, Codex only talks to local endpoints: loopback
+//! and private addresses, `localhost`, and the hosts listed in
+//! `offline.allow_hosts`. The model provider must be local or the config is
+//! rejected, remote MCP servers are not started, update checks and telemetry
+//! are off, and the tools that reach the internet (`web_search`, `web_fetch`,
+//! `browser_*`) are disabled unless listed in `offline.allow_tools`. Whatever
+//! is refused reports an [`OfflineError`] naming the component and the
+//! endpoint it would have needed.
+
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+
+use thiserror::Error;
+use url::Host;
+use url::Url;
+
+use crate::config::types::OfflineConfig;
+use crate::config::types::OfflineTool;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error(
+    "offline mode: {component} needs `{target}`, which is not a local endpoint; add its host to `offline.allow_hosts` or disable offline mode"
+)]
+pub struct OfflineError {
+    /// What tried to go online, e.g. "model provider `openai`".
+    pub component: String,
+    /// The URL (or host) it needed.
+    pub target: String,
+}
+
+impl OfflineError {
+    pub fn new(component: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            component: component.into(),
+            target: target.into(),
+        }
+    }
+}
+
+impl OfflineConfig {
+    /// Whether `url` points at a local endpoint. Unparseable URLs are not.
+    pub fn is_local_url(&self, url: &str) -> bool {
+        let Ok(url) = Url::parse(url) else {
+            return false;
+        };
+        match url.host() {
+            Some(Host::Ipv4(ip)) => is_local_ip(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => is_local_ip(IpAddr::V6(ip)),
+            Some(Host::Domain(domain)) => self.is_local_host(domain),
+            None => false,
+        }
+    }
+
+    fn is_local_host(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        if host == "localhost" || host.ends_with(".localhost") {
+            return true;
+        }
+        self.allow_hosts.iter().any(|allowed| {
+            let allowed = allowed.to_ascii_lowercase();
+            match allowed.strip_prefix('.') {
+                Some(suffix) => host == suffix || host.ends_with(&allowed),
+                None => host == allowed,
+            }
+        })
+    }
+
+    /// `Ok` when `url` is local, otherwise the error to report for
+    /// `component`.
+    pub fn check_url(&self, component: &str, url: &str) -> Result<(), OfflineError> {
+        if self.is_local_url(url) {
+            Ok(())
+        } else {
+            Err(OfflineError::new(component, url))
+        }
+    }
+
+    pub fn allows_tool(&self, tool: OfflineTool) -> bool {
+        self.allow_tools.contains(&tool)
+    }
+}
+
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_local_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_local_ipv4(mapped),
+            None => is_local_ipv6(ip),
+        },
+    }
+}
+
+fn is_local_ipv4(ip: Ipv4Addr) -> bool {
+    ip.is_loopback() || ip.is_private() || ip.is_link_local()
+}
+
+/// Loopback, unique local (`fc00::/7`) and link-local (`fe80::/10`).
+fn is_local_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    ip.is_loopback() || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn loopback_private_and_allowed_hosts_are_local() {
+        let offline = OfflineConfig {
+            allow_hosts: vec!["models.corp".to_string(), ".intra.example".to_string()],
+            allow_tools: Vec::new(),
+        };
+
+        for url in [
+            "http://localhost:11434/v1",
+            "http://127.0.0.1:8080",
+            "http://[::1]:1234/v1",
+            "http://10.1.2.3/v1",
+            "http://192.168.0.10:8000",
+            "http://[fd00::1]/v1",
+            "https://models.corp/v1",
+            "https://llm.intra.example/v1",
+            "https://intra.example/v1",
+        ] {
+            assert!(offline.is_local_url(url), "{url} should be local");
+        }
+        for url in [
+            "https://api.openai.com/v1",
+            "https://models.corp.evil.com/v1",
+            "http://8.8.8.8/",
+            "not a url",
+        ] {
+            assert!(!offline.is_local_url(url), "{url} should not be local");
+        }
+    }
+
+    #[test]
+    fn check_url_names_the_component() {
+        let offline = OfflineConfig::default();
+        assert_eq!(
+            offline.check_url("model provider `openai`", "https://api.openai.com/v1"),
+            Err(OfflineError::new(
+                "model provider `openai`",
+                "https://api.openai.com/v1"
+            ))
+        );
+        assert_eq!(
+            offline.check_url("model provider `ollama`", "http://localhost:11434/v1"),
+            Ok(())
+        );
+    }
+}
@@ -11,7 +11,8 @@ use std::error::Error;
 
 /// Build an OpenTelemetry provider from the app Config.
 ///
-/// Returns `None` when OTEL export is disabled.
+/// `[telemetry]` takes precedence over `[otel]` for traces, and over the
+/// built-in analytics for metrics. Returns `None` when OTEL export is disabled.
 pub fn build_provider(
     config: &Config,
     service_version: &str,
@@ -64,9 +65,15 @@ pub fn build_provider(
         },
     };
 
+    let telemetry = config.telemetry.as_ref();
     let exporter = to_otel_exporter(&config.otel.exporter);
-    let trace_exporter = to_otel_exporter(&config.otel.trace_exporter);
-    let metrics_exporter = if config
+    let trace_exporter = match telemetry.and_then(|t| t.trace_exporter.as_ref()) {
+        Some(kind) => to_otel_exporter(kind),
+        None => to_otel_exporter(&config.otel.trace_exporter),
+    };
+    let metrics_exporter = if let Some(kind) = telemetry.and_then(|t| t.metrics_exporter.as_ref()) {
+        to_otel_exporter(kind)
+    } else if config
         .analytics_enabled
         .unwrap_or(default_analytics_enabled)
     {
@@ -76,7 +83,10 @@ pub fn build_provider(
     };
 
     let originator = originator();
-    let service_name = service_name_override.unwrap_or(originator.value.as_str());
+    let service_name = telemetry
+        .and_then(|t| t.service_name.as_deref())
+        .or(service_name_override)
+        .unwrap_or(originator.value.as_str());
 
     OtelProvider::from(&OtelSettings {
         service_name: service_name.to_string(),
@@ -0,0 +1,209 @@
+//! Dry run of `apply_patch` calls (`patch_preview` feature).
+//!
+//! A verified patch already knows how each chunk lands in the working tree;
+//! [`build_preview`] turns that into a [`PatchPreview`] that is emitted as an
+//! event and shown in the approval dialog. [`PatchBaseline`] remembers what
+//! the touched files looked like at that point so a patch whose files changed
+//! while the user was deciding is rejected instead of applied to content
+//! nobody reviewed.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::ChunkOutcome;
+use codex_protocol::protocol::FileDiffStatus;
+use codex_protocol::protocol::PatchHunkResult;
+use codex_protocol::protocol::PatchHunkStatus;
+use codex_protocol::protocol::PatchPreview;
+use codex_protocol::protocol::PatchPreviewFile;
+
+/// What applying `action` to the working tree would do, file by file.
+pub(crate) fn build_preview(action: &ApplyPatchAction) -> PatchPreview {
+    let mut files: Vec<PatchPreviewFile> = action
+        .changes()
+        .iter()
+        .map(|(path, change)| {
+            let (status, move_path) = match change {
+                ApplyPatchFileChange::Add { .. } => (FileDiffStatus::Added, None),
+                ApplyPatchFileChange::Delete { .. } => (FileDiffStatus::Deleted, None),
+                ApplyPatchFileChange::Update {
+                    move_path: Some(dest),
+                    ..
+                } => (FileDiffStatus::Renamed, Some(dest.clone())),
+                ApplyPatchFileChange::Update { .. } => (FileDiffStatus::Modified, None),
+            };
+            let hunks = action
+                .chunk_reports()
+                .get(path)
+                .map(|chunks| {
+                    chunks
+                        .iter()
+                        .map(|chunk| PatchHunkResult {
+                            line: chunk.line,
+                            status: hunk_status(chunk.outcome),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            PatchPreviewFile {
+                path: path.clone(),
+                move_path,
+                status,
+                hunks,
+            }
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    PatchPreview { files }
+}
+
+pub(crate) fn hunk_status(outcome: ChunkOutcome) -> PatchHunkStatus {
+    match outcome {
+        ChunkOutcome::Applied => PatchHunkStatus::Applied,
+        ChunkOutcome::Fuzzy { .. } => PatchHunkStatus::Fuzzy,
+        ChunkOutcome::Merged => PatchHunkStatus::Merged,
+        ChunkOutcome::AlreadyApplied => PatchHunkStatus::AlreadyApplied,
+        ChunkOutcome::Conflict => PatchHunkStatus::Conflict,
+    }
+}
+
+/// Contents of the files a patch touches, as of its preview.
+#[derive(Debug, Clone)]
+pub(crate) struct PatchBaseline {
+    /// Hash of each file's bytes; `None` when it did not exist.
+    files: Vec<(PathBuf, Option<u64>)>,
+}
+
+impl PatchBaseline {
+    pub(crate) fn capture(action: &ApplyPatchAction) -> Self {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for (path, change) in action.changes() {
+            paths.push(path.clone());
+            if let ApplyPatchFileChange::Update {
+                move_path: Some(dest),
+                ..
+            } = change
+            {
+                paths.push(dest.clone());
+            }
+        }
+        paths.sort();
+        paths.dedup();
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let hash = content_hash(&path);
+                (path, hash)
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Files whose contents differ from the preview.
+    pub(crate) fn changed_paths(&self) -> Vec<PathBuf> {
+        self.files
+            .iter()
+            .filter(|(path, hash)| content_hash(path) != *hash)
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+fn content_hash(path: &Path) -> Option<u64> {
+    let bytes = std::fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_apply_patch::MaybeApplyPatchVerified;
+    use codex_apply_patch::maybe_parse_apply_patch_verified;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn verify(patch: &str, cwd: &Path) -> ApplyPatchAction {
+        let argv = vec!["apply_patch".to_string(), patch.to_string()];
+        match maybe_parse_apply_patch_verified(&argv, cwd) {
+            MaybeApplyPatchVerified::Body(action) => action,
+            other => panic!("expected a verified patch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn preview_lists_files_by_status() {
+        let tmp = tempdir().expect("tempdir");
+        std::fs::write(tmp.path().join("old.txt"), "one\ntwo\n").expect("write");
+        std::fs::write(tmp.path().join("gone.txt"), "bye\n").expect("write");
+        let action = verify(
+            "*** Begin Patch\n\
+             *** Add File: new.txt\n\
+             +hello\n\
+             *** Delete File: gone.txt\n\
+             *** Update File: old.txt\n\
+             *** Move to: moved.txt\n\
+             @@\n\
+             -two\n\
+             +three\n\
+             *** End Patch",
+            tmp.path(),
+        );
+
+        let preview = build_preview(&action);
+
+        let summary: Vec<(PathBuf, FileDiffStatus, Option<PathBuf>)> = preview
+            .files
+            .iter()
+            .map(|file| (file.path.clone(), file.status, file.move_path.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (tmp.path().join("gone.txt"), FileDiffStatus::Deleted, None),
+                (tmp.path().join("new.txt"), FileDiffStatus::Added, None),
+                (
+                    tmp.path().join("old.txt"),
+                    FileDiffStatus::Renamed,
+                    Some(tmp.path().join("moved.txt"))
+                ),
+            ]
+        );
+        assert_eq!(preview.conflicts(), 0);
+        assert!(preview.files.iter().all(|file| file.hunks.is_empty()));
+    }
+
+    #[test]
+    fn baseline_detects_changed_and_created_files() {
+        let tmp = tempdir().expect("tempdir");
+        let existing = tmp.path().join("a.txt");
+        std::fs::write(&existing, "one\n").expect("write");
+        let action = verify(
+            "*** Begin Patch\n\
+             *** Update File: a.txt\n\
+             @@\n\
+             -one\n\
+             +two\n\
+             *** Add File: b.txt\n\
+             +new\n\
+             *** End Patch",
+            tmp.path(),
+        );
+
+        let baseline = PatchBaseline::capture(&action);
+        assert_eq!(baseline.changed_paths(), Vec::<PathBuf>::new());
+
+        std::fs::write(&existing, "one\nmore\n").expect("write");
+        std::fs::write(tmp.path().join("b.txt"), "raced\n").expect("write");
+        assert_eq!(
+            baseline.changed_paths(),
+            vec![existing, tmp.path().join("b.txt")]
+        );
+    }
+}
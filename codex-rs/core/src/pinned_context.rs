@@ -0,0 +1,341 @@
+//! Context pinned via `Op::PinContext`.
+//!
+//! Pinned items live outside the conversation history and are prepended to
+//! the input of every sampling request, so compaction never summarizes them
+//! away. Pinned files are re-read for each request, so the model always sees
+//! their current contents.
+
+use std::path::Path;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ListPinnedContextResponseEvent;
+use codex_protocol::protocol::PinnedContent;
+use codex_protocol::protocol::PinnedContextItem;
+use codex_protocol::protocol::PinnedContextUpdatedEvent;
+use codex_protocol::protocol::RolloutItem;
+use tokio::sync::Mutex;
+
+use crate::codex::Session;
+use crate::session_prefix::PINNED_CONTEXT_OPEN_TAG;
+use crate::truncate::approx_token_count;
+
+/// Pinned files larger than this are cut off so one file cannot crowd out
+/// the rest of the context window.
+const MAX_PINNED_FILE_BYTES: usize = 32 * 1024;
+
+#[derive(Default)]
+pub(crate) struct PinnedContext {
+    items: Mutex<Vec<PinnedContextItem>>,
+    next_id: AtomicU64,
+}
+
+impl PinnedContext {
+    async fn push(&self, content: PinnedContent) -> Vec<PinnedContextItem> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut items = self.items.lock().await;
+        items.push(PinnedContextItem {
+            id: id.to_string(),
+            content,
+        });
+        items.clone()
+    }
+
+    /// Removes the item with `id`; `None` when there is no such item.
+    async fn remove(&self, id: &str) -> Option<Vec<PinnedContextItem>> {
+        let mut items = self.items.lock().await;
+        let idx = items.iter().position(|item| item.id == id)?;
+        items.remove(idx);
+        Some(items.clone())
+    }
+
+    pub(crate) async fn snapshot(&self) -> Vec<PinnedContextItem> {
+        self.items.lock().await.clone()
+    }
+
+    /// Restores the items recorded in a resumed or forked rollout.
+    pub(crate) async fn seed_from_rollout(&self, rollout_items: &[RolloutItem]) {
+        let Some(seeded) = rollout_items.iter().rev().find_map(|item| match item {
+            RolloutItem::EventMsg(EventMsg::PinnedContextUpdated(ev)) => Some(ev.items.clone()),
+            _ => None,
+        }) else {
+            return;
+        };
+        let max_id = seeded
+            .iter()
+            .filter_map(|item| item.id.parse::<u64>().ok())
+            .max()
+            .unwrap_or(0);
+        self.next_id.fetch_max(max_id, Ordering::Relaxed);
+        *self.items.lock().await = seeded;
+    }
+}
+
+pub(crate) async fn pin_context(sess: &Session, sub_id: String, content: PinnedContent) {
+    let cwd = sess.new_default_turn().await.cwd.clone();
+    let msg = match validate_content(content, &cwd).await {
+        Ok(content) => {
+            let items = sess.services.pinned_context.push(content).await;
+            EventMsg::PinnedContextUpdated(PinnedContextUpdatedEvent { items })
+        }
+        Err(message) => EventMsg::Error(ErrorEvent {
+            message,
+            codex_error_info: None,
+        }),
+    };
+    send_event(sess, sub_id, msg).await;
+}
+
+pub(crate) async fn unpin_context(sess: &Session, sub_id: String, id: String) {
+    let msg = match sess.services.pinned_context.remove(&id).await {
+        Some(items) => EventMsg::PinnedContextUpdated(PinnedContextUpdatedEvent { items }),
+        None => EventMsg::Error(ErrorEvent {
+            message: format!("No pinned item with id {id}."),
+            codex_error_info: None,
+        }),
+    };
+    send_event(sess, sub_id, msg).await;
+}
+
+pub(crate) async fn list_pinned_context(sess: &Session, sub_id: String) {
+    let items = sess.services.pinned_context.snapshot().await;
+    sess.send_event_raw(Event {
+        id: sub_id,
+        msg: EventMsg::ListPinnedContextResponse(ListPinnedContextResponseEvent { items }),
+    })
+    .await;
+}
+
+/// Goes through `send_event` so updates are recorded in the rollout and can
+/// be restored on resume.
+async fn send_event(sess: &Session, sub_id: String, msg: EventMsg) {
+    let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+    sess.send_event(&turn_context, msg).await;
+}
+
+/// Rejects empty text and resolves pinned files to absolute paths, so they
+/// keep pointing at the same file if the session cwd changes.
+async fn validate_content(content: PinnedContent, cwd: &Path) -> Result<PinnedContent, String> {
+    match content {
+        PinnedContent::Message { text } | PinnedContent::Note { text }
+            if text.trim().is_empty() =>
+        {
+            Err("Nothing to pin: the text is empty.".to_string())
+        }
+        PinnedContent::File { path } => {
+            let path = cwd.join(path);
+            match tokio::fs::metadata(&path).await {
+                Ok(metadata) if metadata.is_file() => Ok(PinnedContent::File { path }),
+                Ok(_) => Err(format!("Cannot pin {}: not a file.", path.display())),
+                Err(err) => Err(format!("Cannot pin {}: {err}", path.display())),
+            }
+        }
+        content => Ok(content),
+    }
+}
+
+/// Prepends the pinned items to `input` as a single session-prefix message.
+/// Does nothing when nothing is pinned.
+pub(crate) async fn prepend_pinned_context(sess: &Session, input: &mut Vec<ResponseItem>) {
+    let items = sess.services.pinned_context.snapshot().await;
+    if items.is_empty() {
+        return;
+    }
+    let mut files = Vec::new();
+    for item in &items {
+        if let PinnedContent::File { path } = &item.content {
+            files.push(read_pinned_file(path).await);
+        }
+    }
+    let text = format_pinned_context(&items, files);
+    input.insert(
+        0,
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text }],
+            end_turn: None,
+        },
+    );
+}
+
+async fn read_pinned_file(path: &Path) -> String {
+    match tokio::fs::read(path).await {
+        Ok(bytes) if bytes.len() > MAX_PINNED_FILE_BYTES => {
+            let head = String::from_utf8_lossy(&bytes[..MAX_PINNED_FILE_BYTES]);
+            format!(
+                "{head}\n[truncated: showing the first {MAX_PINNED_FILE_BYTES} of {} bytes]",
+                bytes.len()
+            )
+        }
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(err) => format!("[could not read the file: {err}]"),
+    }
+}
+
+/// Renders the pinned items; `files` holds the contents of the pinned files
+/// in the order they appear in `items`.
+fn format_pinned_context(items: &[PinnedContextItem], files: Vec<String>) -> String {
+    let mut files = files.into_iter();
+    let mut text = format!(
+        "{PINNED_CONTEXT_OPEN_TAG}\nThe user pinned these items so they stay available for the whole conversation.\n"
+    );
+    for item in items {
+        let contents = matches!(item.content, PinnedContent::File { .. })
+            .then(|| files.next().unwrap_or_default());
+        text.push('\n');
+        text.push_str(&format_pinned_item(item, contents));
+        text.push('\n');
+    }
+    text.push_str("</pinned_context>");
+    text
+}
+
+/// Renders one pinned item; `contents` holds the file contents for pinned
+/// files.
+fn format_pinned_item(item: &PinnedContextItem, contents: Option<String>) -> String {
+    let PinnedContextItem { id, content } = item;
+    match content {
+        PinnedContent::Message { text } => {
+            format!("<pinned_message id=\"{id}\">\n{text}\n</pinned_message>")
+        }
+        PinnedContent::Note { text } => {
+            format!("<pinned_note id=\"{id}\">\n{text}\n</pinned_note>")
+        }
+        PinnedContent::File { path } => format!(
+            "<pinned_file id=\"{id}\" path=\"{}\">\n{}\n</pinned_file>",
+            path.display(),
+            contents.unwrap_or_default()
+        ),
+    }
+}
+
+/// Pinned items with the estimated tokens each adds to the next request.
+pub(crate) async fn pinned_item_tokens(sess: &Session) -> Vec<(PinnedContextItem, i64)> {
+    let items = sess.services.pinned_context.snapshot().await;
+    let mut sized = Vec::with_capacity(items.len());
+    for item in items {
+        let contents = match &item.content {
+            PinnedContent::File { path } => Some(read_pinned_file(path).await),
+            PinnedContent::Message { .. } | PinnedContent::Note { .. } => None,
+        };
+        let tokens = approx_token_count(&format_pinned_item(&item, contents));
+        sized.push((item, i64::try_from(tokens).unwrap_or(i64::MAX)));
+    }
+    sized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn item(id: &str, content: PinnedContent) -> PinnedContextItem {
+        PinnedContextItem {
+            id: id.to_string(),
+            content,
+        }
+    }
+
+    #[test]
+    fn format_pinned_context_renders_each_kind() {
+        let items = vec![
+            item(
+                "1",
+                PinnedContent::Note {
+                    text: "Target Python 3.9.".to_string(),
+                },
+            ),
+            item(
+                "2",
+                PinnedContent::File {
+                    path: PathBuf::from("/repo/SPEC.md"),
+                },
+            ),
+            item(
+                "3",
+                PinnedContent::Message {
+                    text: "Use the v2 API.".to_string(),
+                },
+            ),
+        ];
+
+        assert_eq!(
+            format_pinned_context(&items, vec!["# Spec".to_string()]),
+            "<pinned_context>\nThe user pinned these items so they stay available for the whole conversation.\n\
+             \n<pinned_note id=\"1\">\nTarget Python 3.9.\n</pinned_note>\n\
+             \n<pinned_file id=\"2\" path=\"/repo/SPEC.md\">\n# Spec\n</pinned_file>\n\
+             \n<pinned_message id=\"3\">\nUse the v2 API.\n</pinned_message>\n\
+             </pinned_context>"
+        );
+    }
+
+    #[tokio::test]
+    async fn seed_from_rollout_uses_the_latest_update() {
+        let pinned = PinnedContext::default();
+        let update = |items: Vec<PinnedContextItem>| {
+            RolloutItem::EventMsg(EventMsg::PinnedContextUpdated(PinnedContextUpdatedEvent {
+                items,
+            }))
+        };
+        let note = |text: &str| PinnedContent::Note {
+            text: text.to_string(),
+        };
+        pinned
+            .seed_from_rollout(&[
+                update(vec![item("1", note("old"))]),
+                update(vec![item("4", note("kept"))]),
+            ])
+            .await;
+
+        assert_eq!(pinned.snapshot().await, vec![item("4", note("kept"))]);
+        let items = pinned.push(note("new")).await;
+        assert_eq!(items.last().map(|item| item.id.as_str()), Some("5"));
+        assert_eq!(pinned.remove("9").await, None);
+    }
+
+    #[tokio::test]
+    async fn validate_content_rejects_empty_text_and_missing_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("notes.md"), "notes").expect("write");
+
+        assert!(
+            validate_content(
+                PinnedContent::Note {
+                    text: "  ".to_string()
+                },
+                dir.path()
+            )
+            .await
+            .is_err()
+        );
+        assert!(
+            validate_content(
+                PinnedContent::File {
+                    path: PathBuf::from("missing.md")
+                },
+                dir.path()
+            )
+            .await
+            .is_err()
+        );
+        assert_eq!(
+            validate_content(
+                PinnedContent::File {
+                    path: PathBuf::from("notes.md")
+                },
+                dir.path()
+            )
+            .await,
+            Ok(PinnedContent::File {
+                path: dir.path().join("notes.md")
+            })
+        );
+    }
+}
@@ -0,0 +1,24 @@
+//! Plan mode: while enabled, each turn must get a plan approved through the
+//! `submit_plan` tool before any tool call that might change the workspace may
+//! run. The registry asks the handler (`ToolHandler::is_mutating`), so known
+//! safe commands and read-only tools stay available. Approval is tracked per
+//! turn in `TurnState`.
+
+use codex_protocol::models::DeveloperInstructions;
+
+const PLAN_MODE_ENABLED: &str = include_str!("../templates/plan_mode/enabled.md");
+const PLAN_MODE_DISABLED: &str = "Plan mode is off. You no longer need an approved plan before running commands or applying patches.";
+
+pub(crate) fn locked_tool_message(tool_name: &str) -> String {
+    format!(
+        "{tool_name} is locked: plan mode is on and no plan has been approved in this turn. Call `submit_plan` and wait for approval first."
+    )
+}
+
+pub(crate) fn instructions(enabled: bool) -> DeveloperInstructions {
+    DeveloperInstructions::new(if enabled {
+        PLAN_MODE_ENABLED
+    } else {
+        PLAN_MODE_DISABLED
+    })
+}
@@ -0,0 +1,137 @@
+//! Plan scope (`plan_scope`): once a plan is approved in plan mode,
+//! `apply_patch` may only touch the files listed in the plan's
+//! `affected_files`. A patch reaching outside that list needs the user's
+//! approval, which adds its files to the scope for the rest of the turn; with
+//! `approval_policy = "never"` it is refused instead.
+
+use std::path::Path;
+
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+use codex_protocol::plan_tool::ProposedPlan;
+use codex_utils_absolute_path::AbsolutePathBuf;
+
+/// Resolves the plan's `affected_files` against `cwd`. An entry naming a
+/// directory covers everything below it.
+pub(crate) fn scope_from_plan(plan: &ProposedPlan, cwd: &Path) -> Vec<AbsolutePathBuf> {
+    plan.affected_files
+        .iter()
+        .map(|file| file.trim())
+        .filter(|file| !file.is_empty())
+        .filter_map(|file| AbsolutePathBuf::resolve_path_against_base(file, cwd).ok())
+        .collect()
+}
+
+/// Paths the patch writes (including move destinations) that the scope does
+/// not cover, in a stable order.
+pub(crate) fn files_outside_scope(
+    action: &ApplyPatchAction,
+    scope: &[AbsolutePathBuf],
+) -> Vec<AbsolutePathBuf> {
+    let cwd = action.cwd.as_path();
+    let mut outside = Vec::new();
+    for (path, change) in action.changes() {
+        let destination = match change {
+            ApplyPatchFileChange::Update {
+                move_path: Some(dest),
+                ..
+            } => Some(dest.as_path()),
+            _ => None,
+        };
+        for path in std::iter::once(path.as_path()).chain(destination) {
+            let Ok(path) = AbsolutePathBuf::resolve_path_against_base(path, cwd) else {
+                continue;
+            };
+            let covered = scope
+                .iter()
+                .any(|entry| path.as_path().starts_with(entry.as_path()));
+            if !covered && !outside.contains(&path) {
+                outside.push(path);
+            }
+        }
+    }
+    outside.sort_by(|a, b| a.as_path().cmp(b.as_path()));
+    outside
+}
+
+/// Reason shown in the approval request for a patch outside the scope.
+pub(crate) fn approval_reason(files: &[AbsolutePathBuf], cwd: &Path) -> String {
+    format!(
+        "Outside the approved plan's files: {}. Approving adds them to the plan for the rest of this turn.",
+        display_files(files, cwd)
+    )
+}
+
+/// Tool output when a patch outside the scope cannot be approved.
+pub(crate) fn rejected_message(files: &[AbsolutePathBuf], cwd: &Path) -> String {
+    format!(
+        "patch rejected: {} {} not in the approved plan's affected_files, and the approval policy does not allow asking the user to expand the plan. Keep to the planned files.",
+        display_files(files, cwd),
+        if files.len() == 1 { "is" } else { "are" }
+    )
+}
+
+fn display_files(files: &[AbsolutePathBuf], cwd: &Path) -> String {
+    files
+        .iter()
+        .map(|file| {
+            file.as_path()
+                .strip_prefix(cwd)
+                .unwrap_or(file.as_path())
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_apply_patch::MaybeApplyPatchVerified;
+    use codex_protocol::plan_tool::PlanRisk;
+    use pretty_assertions::assert_eq;
+
+    fn plan(files: &[&str]) -> ProposedPlan {
+        ProposedPlan {
+            summary: "Fix the parser".to_string(),
+            steps: vec!["Edit the parser".to_string()],
+            affected_files: files.iter().map(ToString::to_string).collect(),
+            risk: PlanRisk::Low,
+            risk_notes: None,
+        }
+    }
+
+    #[test]
+    fn flags_files_and_move_destinations_outside_the_plan() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cwd = dir.path();
+        std::fs::create_dir(cwd.join("src")).expect("mkdir");
+        std::fs::write(cwd.join("src/parser.rs"), "old\n").expect("write");
+        let patch = "*** Begin Patch
+*** Update File: src/parser.rs
+*** Move to: src/lexer.rs
+@@
+-old
++new
+*** Add File: tests/parser.rs
++test
+*** Add File: README.md
++readme
+*** End Patch";
+        let argv = vec!["apply_patch".to_string(), patch.to_string()];
+        let MaybeApplyPatchVerified::Body(action) =
+            codex_apply_patch::maybe_parse_apply_patch_verified(&argv, cwd)
+        else {
+            panic!("expected a verified patch");
+        };
+        let scope = scope_from_plan(&plan(&["src/parser.rs", "./tests/", " "]), cwd);
+
+        let outside = files_outside_scope(&action, &scope);
+
+        assert_eq!(
+            display_files(&outside, cwd),
+            "README.md, src/lexer.rs".to_string()
+        );
+    }
+}
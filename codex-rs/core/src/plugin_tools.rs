@@ -0,0 +1,565 @@
+//! Plugin tools (`plugin_tools` feature): tools provided by a local executable
+//! or WASM module and declared by a manifest in `.codex/plugins/`.
+//!
+//! Each `<name>.toml` manifest declares one tool named after the file:
+//!
+//! ```toml
+//! description = "Check a migration against the current schema"
+//! command = ["python3", "./check_migration.py"]  # or: wasm = "./check.wasm"
+//! timeout_ms = 30000
+//!
+//! [input_schema]
+//! type = "object"
+//! properties = { path = { type = "string" } }
+//! required = ["path"]
+//!
+//! [sandbox]
+//! mode = "read-only"        # or "workspace-write"
+//! network_access = false
+//! ```
+//!
+//! A call runs the tool in the turn's working directory with the model's
+//! arguments (a JSON object) on stdin. Its stdout must be JSON and is returned
+//! to the model; a non-zero exit fails the call with stderr. `output_schema`,
+//! when present, is shown to the model with the description.
+//!
+//! The tool always runs in the platform sandbox with the capabilities its
+//! manifest asks for, and a call fails when the session's own sandbox policy
+//! does not grant them. Calls go through the exec policy and the session's
+//! approval policy like shell commands, and tools that can write to the
+//! workspace count as mutating, so they stay locked in plan mode. WASM modules
+//! run under `wasmtime` with the working directory preopened.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::protocol::SandboxPolicy;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::codex::TurnContext;
+use crate::exec::ExecExpiration;
+use crate::exec::SandboxType;
+use crate::exec_env::create_env;
+use crate::git_info::get_git_repo_root;
+use crate::sandboxing::CommandSpec;
+use crate::sandboxing::SandboxManager;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::sandboxing::SandboxablePreference;
+use crate::tools::spec::plugin_tool_to_openai_tool;
+
+const PLUGINS_DIR_NAME: &str = "plugins";
+const MAX_NAME_LEN: usize = 64;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const WASM_RUNTIME: &str = "wasmtime";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginManifest {
+    description: String,
+    #[serde(default)]
+    command: Option<Vec<String>>,
+    #[serde(default)]
+    wasm: Option<String>,
+    input_schema: JsonValue,
+    #[serde(default)]
+    output_schema: Option<JsonValue>,
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+    #[serde(default)]
+    sandbox: PluginSandboxToml,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginSandboxToml {
+    #[serde(default)]
+    mode: Option<SandboxMode>,
+    #[serde(default)]
+    network_access: bool,
+}
+
+/// How a plugin tool is run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PluginRunner {
+    /// argv, with `./` paths resolved against the manifest's directory.
+    Command(Vec<String>),
+    /// WASI module run under `wasmtime`.
+    Wasm(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PluginTool {
+    pub name: String,
+    pub description: String,
+    pub runner: PluginRunner,
+    pub input_schema: JsonValue,
+    pub output_schema: Option<JsonValue>,
+    pub timeout: Duration,
+    pub sandbox_mode: SandboxMode,
+    pub network_access: bool,
+    /// Manifest the tool was loaded from.
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct PluginLoadOutcome {
+    pub tools: Vec<PluginTool>,
+    /// Manifests that were skipped, with the reason.
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+/// Directories scanned for manifests, highest priority first:
+/// `<git root>/.codex/plugins`, then `$CODEX_HOME/plugins`.
+fn plugin_search_roots(cwd: &Path, codex_home: &Path) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Some(git_root) = get_git_repo_root(cwd) {
+        roots.push(git_root.join(".codex").join(PLUGINS_DIR_NAME));
+    }
+    roots.push(codex_home.join(PLUGINS_DIR_NAME));
+    roots
+}
+
+pub(crate) fn load_plugin_tools(cwd: &Path, codex_home: &Path) -> PluginLoadOutcome {
+    load_plugin_tools_from_roots(plugin_search_roots(cwd, codex_home))
+}
+
+/// Loads manifests from `roots` in order. When the same name appears in more
+/// than one root, the first root wins.
+fn load_plugin_tools_from_roots(roots: Vec<PathBuf>) -> PluginLoadOutcome {
+    let mut outcome = PluginLoadOutcome::default();
+    let mut seen = HashSet::new();
+    for root in roots {
+        for path in list_manifests(&root) {
+            match parse_manifest(&path) {
+                Ok(tool) => {
+                    if seen.insert(tool.name.clone()) {
+                        outcome.tools.push(tool);
+                    }
+                }
+                Err(err) => outcome.errors.push((path, err)),
+            }
+        }
+    }
+    outcome.tools.sort_by(|a, b| a.name.cmp(&b.name));
+    outcome
+}
+
+fn list_manifests(dir: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warn!("failed to read plugins dir {}: {err:#}", dir.display());
+            }
+            return Vec::new();
+        }
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter(|path| fs::metadata(path).is_ok_and(|m| m.is_file()))
+        .collect();
+    files.sort();
+    files
+}
+
+fn parse_manifest(path: &Path) -> Result<PluginTool, String> {
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_string();
+    if name.is_empty()
+        || name.len() > MAX_NAME_LEN
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!(
+            "invalid name `{name}`: use up to {MAX_NAME_LEN} of [A-Za-z0-9_-]"
+        ));
+    }
+    let contents = fs::read_to_string(path).map_err(|err| format!("failed to read: {err}"))?;
+    let manifest: PluginManifest =
+        toml::from_str(&contents).map_err(|err| format!("invalid manifest: {err}"))?;
+
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let runner = match (manifest.command, manifest.wasm) {
+        (Some(command), None) if !command.is_empty() => PluginRunner::Command(
+            command
+                .into_iter()
+                .map(|arg| resolve_relative(dir, arg))
+                .collect(),
+        ),
+        (None, Some(wasm)) => PluginRunner::Wasm(PathBuf::from(resolve_relative(dir, wasm))),
+        _ => return Err("set either a non-empty `command` or `wasm`".to_string()),
+    };
+    if !manifest.input_schema.is_object() {
+        return Err("`input_schema` must be a table".to_string());
+    }
+    let sandbox_mode = manifest.sandbox.mode.unwrap_or(SandboxMode::ReadOnly);
+    if manifest.sandbox.network_access && sandbox_mode == SandboxMode::ReadOnly {
+        return Err(
+            "`sandbox.network_access` requires `sandbox.mode = \"workspace-write\"`".into(),
+        );
+    }
+
+    let tool = PluginTool {
+        name,
+        description: manifest.description.trim().to_string(),
+        runner,
+        input_schema: manifest.input_schema,
+        output_schema: manifest.output_schema,
+        timeout: manifest
+            .timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_TIMEOUT),
+        sandbox_mode,
+        network_access: manifest.sandbox.network_access,
+        path: path.to_path_buf(),
+    };
+    plugin_tool_to_openai_tool(&tool).map_err(|err| format!("invalid `input_schema`: {err}"))?;
+    Ok(tool)
+}
+
+/// Resolves `./` and `../` paths against the manifest's directory so a
+/// plugin can ship its script next to the manifest.
+fn resolve_relative(dir: &Path, arg: String) -> String {
+    if arg.starts_with("./") || arg.starts_with("../") {
+        dir.join(arg).to_string_lossy().into_owned()
+    } else {
+        arg
+    }
+}
+
+impl PluginTool {
+    /// Whether a call can change the workspace.
+    pub(crate) fn is_mutating(&self) -> bool {
+        self.sandbox_mode != SandboxMode::ReadOnly
+    }
+
+    /// The command shown when asking the user to approve a call.
+    pub(crate) fn approval_command(&self) -> Vec<String> {
+        match &self.runner {
+            PluginRunner::Command(argv) => argv.clone(),
+            PluginRunner::Wasm(module) => vec![
+                WASM_RUNTIME.to_string(),
+                "run".to_string(),
+                module.to_string_lossy().into_owned(),
+            ],
+        }
+    }
+
+    /// The policy the tool runs under, or why `session_policy` does not allow
+    /// the capabilities the manifest asks for.
+    fn sandbox_policy(&self, session_policy: &SandboxPolicy) -> Result<SandboxPolicy, String> {
+        let denied = |capability: &str| {
+            format!(
+                "plugin `{}` needs {capability}, which the current sandbox policy does not allow",
+                self.name
+            )
+        };
+        let policy = match self.sandbox_mode {
+            SandboxMode::ReadOnly => SandboxPolicy::ReadOnly,
+            SandboxMode::WorkspaceWrite => {
+                if matches!(session_policy, SandboxPolicy::ReadOnly) {
+                    return Err(denied("write access to the workspace"));
+                }
+                SandboxPolicy::WorkspaceWrite {
+                    writable_roots: Vec::new(),
+                    network_access: self.network_access,
+                    exclude_tmpdir_env_var: false,
+                    exclude_slash_tmp: false,
+                }
+            }
+            SandboxMode::DangerFullAccess => {
+                if !matches!(session_policy, SandboxPolicy::DangerFullAccess) {
+                    return Err(denied("full access"));
+                }
+                SandboxPolicy::DangerFullAccess
+            }
+        };
+        if self.network_access && !session_policy.has_full_network_access() {
+            return Err(denied("network access"));
+        }
+        Ok(policy)
+    }
+
+    fn argv(&self, cwd: &Path) -> Result<Vec<String>, String> {
+        match &self.runner {
+            PluginRunner::Command(argv) => Ok(argv.clone()),
+            PluginRunner::Wasm(module) => {
+                let runtime = which::which(WASM_RUNTIME).map_err(|_| {
+                    format!(
+                        "plugin `{}` is a WASM module and needs `{WASM_RUNTIME}` on PATH",
+                        self.name
+                    )
+                })?;
+                let mut argv = vec![
+                    runtime.to_string_lossy().into_owned(),
+                    "run".to_string(),
+                    format!("--dir={}", cwd.display()),
+                ];
+                if self.network_access {
+                    argv.push("-Sinherit-network".to_string());
+                }
+                argv.push(module.to_string_lossy().into_owned());
+                Ok(argv)
+            }
+        }
+    }
+
+    /// Runs the tool with `arguments` on stdin and returns its JSON output.
+    pub(crate) async fn run(&self, turn: &TurnContext, arguments: &str) -> Result<String, String> {
+        let policy = self.sandbox_policy(&turn.sandbox_policy)?;
+        let mut argv = self.argv(&turn.cwd)?.into_iter();
+        let Some(program) = argv.next() else {
+            return Err(format!("plugin `{}` has an empty command", self.name));
+        };
+        let manager = SandboxManager::new();
+        let sandbox = manager.select_initial(&policy, SandboxablePreference::Auto);
+        if sandbox == SandboxType::WindowsRestrictedToken {
+            return Err("plugin tools cannot be sandboxed on Windows yet".to_string());
+        }
+        let spec = CommandSpec {
+            program,
+            args: argv.collect(),
+            cwd: turn.cwd.clone(),
+            env: create_env(&turn.shell_environment_policy),
+            expiration: ExecExpiration::Timeout(self.timeout),
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+        };
+        let exec_env = manager
+            .transform(
+                spec,
+                &policy,
+                sandbox,
+                &turn.cwd,
+                turn.codex_linux_sandbox_exe.as_ref(),
+            )
+            .map_err(|err| format!("failed to sandbox plugin `{}`: {err}", self.name))?;
+        let stdout = run_sandboxed(
+            &exec_env.command,
+            exec_env.arg0.as_deref(),
+            &exec_env.cwd,
+            exec_env.env,
+            arguments,
+            self.timeout,
+        )
+        .await?;
+
+        let output: JsonValue = serde_json::from_str(stdout.trim())
+            .map_err(|err| format!("plugin `{}` did not print JSON: {err}", self.name))?;
+        Ok(output.to_string())
+    }
+}
+
+async fn run_sandboxed(
+    command: &[String],
+    #[cfg_attr(not(unix), allow(unused_variables))] arg0: Option<&str>,
+    cwd: &Path,
+    env: HashMap<String, String>,
+    input: &str,
+    timeout: Duration,
+) -> Result<String, String> {
+    let Some((program, args)) = command.split_first() else {
+        return Err("empty command".to_string());
+    };
+    let mut cmd = Command::new(program);
+    #[cfg(unix)]
+    if let Some(arg0) = arg0 {
+        cmd.arg0(arg0);
+    }
+    let mut child = cmd
+        .args(args)
+        .current_dir(cwd)
+        .env_clear()
+        .envs(env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("failed to spawn `{program}`: {err}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A tool that ignores its input may exit before reading it.
+        let _ = stdin.write_all(input.as_bytes()).await;
+    }
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(output) => output.map_err(|err| format!("failed to wait for `{program}`: {err}"))?,
+        Err(_) => return Err(format!("timed out after {}ms", timeout.as_millis())),
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("exited with {}: {}", output.status, stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        fs::create_dir_all(dir).expect("create dir");
+        let path = dir.join(name);
+        fs::write(&path, contents).expect("write file");
+        path
+    }
+
+    #[test]
+    fn loads_manifest_and_resolves_relative_command() {
+        let tmp = TempDir::new().expect("tempdir");
+        let path = write(
+            tmp.path(),
+            "check_migration.toml",
+            r#"
+description = "Check a migration"
+command = ["python3", "./check.py"]
+timeout_ms = 500
+
+[input_schema]
+type = "object"
+properties = { path = { type = "string" } }
+required = ["path"]
+
+[sandbox]
+mode = "workspace-write"
+network_access = true
+"#,
+        );
+
+        let outcome = load_plugin_tools_from_roots(vec![tmp.path().to_path_buf()]);
+
+        assert_eq!(outcome.errors, Vec::new());
+        assert_eq!(
+            outcome.tools,
+            vec![PluginTool {
+                name: "check_migration".to_string(),
+                description: "Check a migration".to_string(),
+                runner: PluginRunner::Command(vec![
+                    "python3".to_string(),
+                    tmp.path().join("./check.py").to_string_lossy().into_owned(),
+                ]),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": { "path": { "type": "string" } },
+                    "required": ["path"],
+                }),
+                output_schema: None,
+                timeout: Duration::from_millis(500),
+                sandbox_mode: SandboxMode::WorkspaceWrite,
+                network_access: true,
+                path,
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_manifests_and_keeps_first_duplicate() {
+        let tmp = TempDir::new().expect("tempdir");
+        let repo = tmp.path().join("repo");
+        let user = tmp.path().join("user");
+        let schema = "[input_schema]\ntype = \"object\"\n";
+        write(
+            &repo,
+            "lint.toml",
+            &format!("description = \"repo\"\nwasm = \"./lint.wasm\"\n{schema}"),
+        );
+        write(
+            &user,
+            "lint.toml",
+            &format!("description = \"user\"\ncommand = [\"lint\"]\n{schema}"),
+        );
+        let both = write(
+            &user,
+            "both.toml",
+            &format!("description = \"x\"\ncommand = [\"a\"]\nwasm = \"b.wasm\"\n{schema}"),
+        );
+        let network = write(
+            &user,
+            "fetch.toml",
+            &format!(
+                "description = \"x\"\ncommand = [\"a\"]\n{schema}[sandbox]\nnetwork_access = true\n"
+            ),
+        );
+
+        let outcome = load_plugin_tools_from_roots(vec![repo.clone(), user]);
+
+        assert_eq!(
+            outcome
+                .tools
+                .iter()
+                .map(|tool| (tool.name.as_str(), tool.description.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("lint", "repo")]
+        );
+        assert_eq!(
+            outcome.tools[0].runner,
+            PluginRunner::Wasm(repo.join("./lint.wasm"))
+        );
+        assert_eq!(
+            outcome.errors,
+            vec![
+                (
+                    both,
+                    "set either a non-empty `command` or `wasm`".to_string()
+                ),
+                (
+                    network,
+                    "`sandbox.network_access` requires `sandbox.mode = \"workspace-write\"`"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn sandbox_policy_requires_session_capabilities() {
+        let tool = |sandbox_mode, network_access| PluginTool {
+            name: "tool".to_string(),
+            description: String::new(),
+            runner: PluginRunner::Command(vec!["tool".to_string()]),
+            input_schema: serde_json::json!({ "type": "object" }),
+            output_schema: None,
+            timeout: DEFAULT_TIMEOUT,
+            sandbox_mode,
+            network_access,
+            path: PathBuf::from("tool.toml"),
+        };
+        let workspace_write = SandboxPolicy::new_workspace_write_policy();
+
+        assert_eq!(
+            tool(SandboxMode::ReadOnly, false).sandbox_policy(&SandboxPolicy::DangerFullAccess),
+            Ok(SandboxPolicy::ReadOnly)
+        );
+        assert_eq!(
+            tool(SandboxMode::WorkspaceWrite, false).sandbox_policy(&workspace_write),
+            Ok(SandboxPolicy::new_workspace_write_policy())
+        );
+        assert!(
+            tool(SandboxMode::WorkspaceWrite, false)
+                .sandbox_policy(&SandboxPolicy::ReadOnly)
+                .is_err()
+        );
+        assert!(
+            tool(SandboxMode::WorkspaceWrite, true)
+                .sandbox_policy(&workspace_write)
+                .is_err()
+        );
+    }
+}
@@ -15,6 +15,8 @@
 
 use crate::config::Config;
 use crate::features::Feature;
+use crate::project_glossary::render_glossary_section;
+use crate::project_memory::render_memory_section;
 use crate::skills::SkillMetadata;
 use crate::skills::render_skills_section;
 use dunce::canonicalize as normalize_path;
@@ -34,8 +36,8 @@ pub const LOCAL_PROJECT_DOC_FILENAME: &str = "AGENTS.override.md";
 /// be concatenated with the following separator.
 const PROJECT_DOC_SEPARATOR: &str = "\n\n--- project-doc ---\n\n";
 
-/// Combines `Config::instructions` and `AGENTS.md` (if present) into a single
-/// string of instructions.
+/// Combines `Config::instructions`, `AGENTS.md`, and the project memory and
+/// glossary (if present) into a single string of instructions.
 pub(crate) async fn get_user_instructions(
     config: &Config,
     skills: Option<&[SkillMetadata]>,
@@ -61,6 +63,24 @@ pub(crate) async fn get_user_instructions(
         }
     };
 
+    if config.features.enabled(Feature::ProjectMemory)
+        && let Some(memory_section) = render_memory_section(&config.cwd).await
+    {
+        if !output.is_empty() {
+            output.push_str("\n\n");
+        }
+        output.push_str(&memory_section);
+    }
+
+    if config.features.enabled(Feature::ProjectGlossary)
+        && let Some(glossary_section) = render_glossary_section(&config.cwd).await
+    {
+        if !output.is_empty() {
+            output.push_str("\n\n");
+        }
+        output.push_str(&glossary_section);
+    }
+
     let skills_section = skills.and_then(render_skills_section);
     if let Some(skills_section) = skills_section {
         if !output.is_empty() {
@@ -490,6 +510,54 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn project_memory_is_appended_when_enabled() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fs::create_dir(tmp.path().join(".git")).unwrap();
+        fs::write(tmp.path().join("AGENTS.md"), "base doc").unwrap();
+        fs::create_dir(tmp.path().join(".codex")).unwrap();
+        fs::write(
+            tmp.path().join(".codex/memory.md"),
+            "# Project memory\n\n- uses pnpm\n",
+        )
+        .unwrap();
+
+        let mut cfg = make_config(&tmp, 4096, None).await;
+        let res = get_user_instructions(&cfg, None).await;
+        assert_eq!(res.as_deref(), Some("base doc"));
+
+        cfg.features.enable(Feature::ProjectMemory);
+        let res = get_user_instructions(&cfg, None)
+            .await
+            .expect("instructions expected");
+        assert!(res.starts_with("base doc\n\n## Project memory\n"));
+        assert!(res.ends_with("\n- [1] uses pnpm"));
+    }
+
+    #[tokio::test]
+    async fn project_glossary_is_appended_when_enabled() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fs::create_dir(tmp.path().join(".git")).unwrap();
+        fs::write(tmp.path().join("AGENTS.md"), "base doc").unwrap();
+        fs::create_dir(tmp.path().join(".codex")).unwrap();
+        fs::write(
+            tmp.path().join(".codex/glossary.md"),
+            "# Glossary\n\n- **SLO**: availability target\n",
+        )
+        .unwrap();
+
+        let mut cfg = make_config(&tmp, 4096, None).await;
+        let res = get_user_instructions(&cfg, None).await;
+        assert_eq!(res.as_deref(), Some("base doc"));
+
+        cfg.features.enable(Feature::ProjectGlossary);
+        let res = get_user_instructions(&cfg, None)
+            .await
+            .expect("instructions expected");
+        assert!(res.starts_with("base doc\n\n## Project glossary\n"));
+        assert!(res.ends_with("\n- SLO: availability target"));
+    }
+
     #[tokio::test]
     async fn skills_are_appended_to_project_doc() {
         let tmp = tempfile::tempdir().expect("tempdir");
@@ -0,0 +1,423 @@
+//! Project glossary: the domain terms, acronyms, and naming conventions of a
+//! project, defined by its maintainers.
+//!
+//! Terms live in `.codex/glossary.md` and/or `.codex/glossary.toml` at the
+//! root of the git repository (or the working directory outside a
+//! repository). When `Feature::ProjectGlossary` is enabled a compact listing
+//! is added to the user instructions of every new session, and the `define`
+//! tool returns the full definitions.
+//!
+//! In Markdown every `- term: definition` bullet is an entry; the term may be
+//! bold or in backticks, aliases go in parentheses after it, and indented
+//! lines continue the definition:
+//!
+//! ```markdown
+//! - **SLO** (service level objective): availability target of a service,
+//!   measured over 28 days.
+//! - `*Handler`: a tool implementation registered in `tools/spec.rs`.
+//! ```
+//!
+//! In TOML, `[terms]` maps a term to its definition or to a table with
+//! `definition` and `aliases`:
+//!
+//! ```toml
+//! [terms]
+//! PR = "pull request"
+//! SLO = { definition = "availability target of a service", aliases = ["service level objective"] }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_utils_string::take_bytes_at_char_boundary;
+use serde::Deserialize;
+
+use crate::git_info::get_git_repo_root;
+
+const GLOSSARY_DIR: &str = ".codex";
+const GLOSSARY_FILENAMES: [&str; 2] = ["glossary.md", "glossary.toml"];
+
+/// Longest definition shown in the session instructions; `define` returns
+/// the rest.
+const MAX_LISTED_DEFINITION_BYTES: usize = 160;
+
+/// Terms beyond this many bytes are left out of the session instructions so
+/// a large glossary cannot crowd out the rest of the context.
+const MAX_LOADED_GLOSSARY_BYTES: usize = 8 * 1024;
+
+/// Most entries `define` returns for a query that is not an exact match.
+const MAX_DEFINE_MATCHES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GlossaryEntry {
+    pub(crate) term: String,
+    pub(crate) aliases: Vec<String>,
+    pub(crate) definition: String,
+}
+
+/// The terms of a glossary together with the files they came from.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Glossary {
+    pub(crate) paths: Vec<PathBuf>,
+    pub(crate) entries: Vec<GlossaryEntry>,
+}
+
+#[derive(Deserialize)]
+struct GlossaryToml {
+    #[serde(default)]
+    terms: BTreeMap<String, TermToml>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TermToml {
+    Definition(String),
+    Entry {
+        definition: String,
+        #[serde(default)]
+        aliases: Vec<String>,
+    },
+}
+
+/// The glossary files that may exist for the project containing `cwd`.
+pub(crate) fn glossary_paths(cwd: &Path) -> Vec<PathBuf> {
+    let dir = get_git_repo_root(cwd)
+        .unwrap_or_else(|| cwd.to_path_buf())
+        .join(GLOSSARY_DIR);
+    GLOSSARY_FILENAMES
+        .iter()
+        .map(|filename| dir.join(filename))
+        .collect()
+}
+
+/// Reads the glossary of the project containing `cwd`. Missing files are
+/// skipped; a glossary with no files has no entries.
+pub(crate) async fn read_glossary(cwd: &Path) -> Result<Glossary, String> {
+    let mut glossary = Glossary::default();
+    for path in glossary_paths(cwd) {
+        let contents = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(format!("Failed to read {}: {err}", path.display())),
+        };
+        let entries = if path.extension().is_some_and(|ext| ext == "toml") {
+            parse_toml(&contents).map_err(|err| format!("Invalid {}: {err}", path.display()))?
+        } else {
+            parse_markdown(&contents)
+        };
+        glossary.entries.extend(entries);
+        glossary.paths.push(path);
+    }
+    Ok(glossary)
+}
+
+/// The instructions section listing the glossary of the project containing
+/// `cwd`, or `None` when there is nothing defined.
+pub(crate) async fn render_glossary_section(cwd: &Path) -> Option<String> {
+    match read_glossary(cwd).await {
+        Ok(glossary) => format_glossary_section(&glossary),
+        Err(err) => {
+            tracing::warn!("failed to read project glossary: {err}");
+            None
+        }
+    }
+}
+
+fn format_glossary_section(glossary: &Glossary) -> Option<String> {
+    if glossary.entries.is_empty() {
+        return None;
+    }
+    let paths = glossary
+        .paths
+        .iter()
+        .map(|path| format!("`{}`", path.display()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut section = format!(
+        "## Project glossary\nTerms with a specific meaning in this project ({paths}). Use them with these meanings and follow the naming conventions; call `define` for the full definition of a term.\n"
+    );
+    let mut remaining = MAX_LOADED_GLOSSARY_BYTES;
+    for (loaded, entry) in glossary.entries.iter().enumerate() {
+        let line = format!(
+            "- {}: {}\n",
+            entry_heading(entry),
+            shorten(&entry.definition)
+        );
+        if line.len() > remaining {
+            let omitted = glossary.entries.len() - loaded;
+            section.push_str(&format!(
+                "({omitted} more terms omitted; look them up with `define`.)\n"
+            ));
+            break;
+        }
+        remaining -= line.len();
+        section.push_str(&line);
+    }
+    Some(section.trim_end().to_string())
+}
+
+/// `term (alias, alias)`.
+fn entry_heading(entry: &GlossaryEntry) -> String {
+    if entry.aliases.is_empty() {
+        entry.term.clone()
+    } else {
+        format!("{} ({})", entry.term, entry.aliases.join(", "))
+    }
+}
+
+fn shorten(definition: &str) -> String {
+    if definition.len() <= MAX_LISTED_DEFINITION_BYTES {
+        return definition.to_string();
+    }
+    let head = take_bytes_at_char_boundary(definition, MAX_LISTED_DEFINITION_BYTES);
+    format!("{}…", head.trim_end())
+}
+
+/// Entries whose term or an alias is `query` (ignoring case). Without such
+/// an entry, those whose term, aliases, or definition mention it.
+pub(crate) fn lookup<'a>(entries: &'a [GlossaryEntry], query: &str) -> Vec<&'a GlossaryEntry> {
+    let query = normalize_term(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let names = |entry: &'a GlossaryEntry| {
+        std::iter::once(&entry.term)
+            .chain(&entry.aliases)
+            .map(|name| normalize_term(name))
+    };
+    let exact: Vec<_> = entries
+        .iter()
+        .filter(|entry| names(entry).any(|name| name == query))
+        .collect();
+    if !exact.is_empty() {
+        return exact;
+    }
+    entries
+        .iter()
+        .filter(|entry| {
+            names(entry).any(|name| name.contains(&query))
+                || entry.definition.to_lowercase().contains(&query)
+        })
+        .take(MAX_DEFINE_MATCHES)
+        .collect()
+}
+
+/// Full definitions of `entries`, one paragraph each.
+pub(crate) fn format_definitions(entries: &[&GlossaryEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| format!("{}: {}", entry_heading(entry), entry.definition))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn normalize_term(term: &str) -> String {
+    term.trim()
+        .trim_matches(['`', '*', '_'])
+        .trim()
+        .to_lowercase()
+}
+
+fn parse_toml(contents: &str) -> Result<Vec<GlossaryEntry>, toml::de::Error> {
+    let glossary: GlossaryToml = toml::from_str(contents)?;
+    Ok(glossary
+        .terms
+        .into_iter()
+        .map(|(term, value)| {
+            let (definition, aliases) = match value {
+                TermToml::Definition(definition) => (definition, Vec::new()),
+                TermToml::Entry {
+                    definition,
+                    aliases,
+                } => (definition, aliases),
+            };
+            GlossaryEntry {
+                term,
+                aliases,
+                definition: fold_whitespace(&definition),
+            }
+        })
+        .collect())
+}
+
+fn parse_markdown(contents: &str) -> Vec<GlossaryEntry> {
+    let mut entries: Vec<GlossaryEntry> = Vec::new();
+    let mut in_entry = false;
+    for line in contents.lines() {
+        if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            let entry = parse_markdown_item(item);
+            in_entry = entry.is_some();
+            entries.extend(entry);
+        } else if in_entry
+            && line.starts_with([' ', '\t'])
+            && !line.trim().is_empty()
+            && let Some(entry) = entries.last_mut()
+        {
+            entry.definition.push(' ');
+            entry.definition.push_str(&fold_whitespace(line));
+        } else {
+            in_entry = false;
+        }
+    }
+    entries
+}
+
+/// `**term** (alias, alias): definition`.
+fn parse_markdown_item(item: &str) -> Option<GlossaryEntry> {
+    let (name, definition) = item.split_once(": ")?;
+    let definition = fold_whitespace(definition);
+    let (term, aliases) = match name.trim().strip_suffix(')') {
+        Some(rest) => {
+            let (term, aliases) = rest.split_once(" (")?;
+            let aliases = aliases
+                .split(',')
+                .map(|alias| strip_emphasis(alias).to_string())
+                .filter(|alias| !alias.is_empty())
+                .collect();
+            (term, aliases)
+        }
+        None => (name, Vec::new()),
+    };
+    let term = strip_emphasis(term);
+    if term.is_empty() || definition.is_empty() {
+        return None;
+    }
+    Some(GlossaryEntry {
+        term: term.to_string(),
+        aliases,
+        definition,
+    })
+}
+
+/// Drops the bold or italic markers around a term; backticks are kept since
+/// they mark identifiers.
+fn strip_emphasis(text: &str) -> &str {
+    text.trim().trim_matches(['*', '_']).trim()
+}
+
+fn fold_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entry(term: &str, aliases: &[&str], definition: &str) -> GlossaryEntry {
+        GlossaryEntry {
+            term: term.to_string(),
+            aliases: aliases.iter().map(ToString::to_string).collect(),
+            definition: definition.to_string(),
+        }
+    }
+
+    #[test]
+    fn markdown_bullets_define_terms() {
+        let contents = "# Glossary\n\nIntro paragraph: not a term.\n\n- **SLO** (service level objective, objective): availability target,\n  measured over 28 days.\n- `*Handler`: a tool implementation.\n- not a definition\n\n## Conventions\n* Crate names: prefixed with `codex-`.\n";
+
+        assert_eq!(
+            parse_markdown(contents),
+            vec![
+                entry(
+                    "SLO",
+                    &["service level objective", "objective"],
+                    "availability target, measured over 28 days."
+                ),
+                entry("`*Handler`", &[], "a tool implementation."),
+                entry("Crate names", &[], "prefixed with `codex-`."),
+            ]
+        );
+    }
+
+    #[test]
+    fn toml_terms_accept_strings_and_tables() {
+        let contents = r#"
+[terms]
+PR = "pull request"
+SLO = { definition = "availability target", aliases = ["service level objective"] }
+"#;
+
+        assert_eq!(
+            parse_toml(contents).expect("parse"),
+            vec![
+                entry("PR", &[], "pull request"),
+                entry("SLO", &["service level objective"], "availability target"),
+            ]
+        );
+        assert!(parse_toml("[terms]\nPR = 1\n").is_err());
+    }
+
+    #[test]
+    fn lookup_prefers_exact_names() {
+        let entries = vec![
+            entry("SLO", &["service level objective"], "availability target"),
+            entry("SLI", &[], "metric an SLO is measured against"),
+            entry("`*Handler`", &[], "a tool implementation"),
+        ];
+
+        assert_eq!(lookup(&entries, "slo"), vec![&entries[0]]);
+        assert_eq!(
+            lookup(&entries, "Service Level Objective"),
+            vec![&entries[0]]
+        );
+        assert_eq!(lookup(&entries, "*Handler"), vec![&entries[2]]);
+        assert_eq!(lookup(&entries, "SL"), vec![&entries[0], &entries[1]]);
+        assert_eq!(lookup(&entries, "latency"), Vec::<&GlossaryEntry>::new());
+        assert_eq!(
+            format_definitions(&[&entries[0]]),
+            "SLO (service level objective): availability target"
+        );
+    }
+
+    #[test]
+    fn glossary_section_is_compact() {
+        assert_eq!(format_glossary_section(&Glossary::default()), None);
+
+        let glossary = Glossary {
+            paths: vec![PathBuf::from("/repo/.codex/glossary.md")],
+            entries: vec![
+                entry("PR", &[], "pull request"),
+                entry("SLO", &["service level objective"], &"x".repeat(200)),
+            ],
+        };
+        let section = format_glossary_section(&glossary).expect("section");
+        assert!(section.starts_with("## Project glossary\n"));
+        assert!(section.contains("(`/repo/.codex/glossary.md`)"));
+        assert!(section.ends_with(&format!(
+            "- PR: pull request\n- SLO (service level objective): {}…",
+            "x".repeat(MAX_LISTED_DEFINITION_BYTES)
+        )));
+    }
+
+    #[tokio::test]
+    async fn reads_both_files_from_the_project_root() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join(".git")).expect("git dir");
+        std::fs::create_dir(dir.path().join(".codex")).expect("codex dir");
+        std::fs::write(
+            dir.path().join(".codex/glossary.md"),
+            "- PR: pull request\n",
+        )
+        .expect("write md");
+        std::fs::write(
+            dir.path().join(".codex/glossary.toml"),
+            "[terms]\nSLO = \"availability target\"\n",
+        )
+        .expect("write toml");
+        let nested = dir.path().join("src");
+        std::fs::create_dir(&nested).expect("nested dir");
+
+        let glossary = read_glossary(&nested).await.expect("read");
+
+        assert_eq!(glossary.paths, glossary_paths(&nested));
+        assert_eq!(
+            glossary.entries,
+            vec![
+                entry("PR", &[], "pull request"),
+                entry("SLO", &[], "availability target"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,321 @@
+//! Project memory: durable facts recorded with the `memory` tool.
+//!
+//! Entries live in `.codex/memory.md` at the root of the git repository (or
+//! the working directory outside a repository), one `- ` bullet per fact, so
+//! they can be reviewed and edited like any other file. Edits only touch the
+//! bullet they target; anything else a user wrote in the file is kept. When
+//! `Feature::ProjectMemory` is enabled the entries are added to the user
+//! instructions of every new session in the project.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ListMemoryResponseEvent;
+use codex_protocol::protocol::MemoryEntry;
+
+use crate::codex::Session;
+use crate::git_info::get_git_repo_root;
+
+const MEMORY_DIR: &str = ".codex";
+const MEMORY_FILENAME: &str = "memory.md";
+
+/// Written at the top of a memory file created by the first `add`.
+const MEMORY_HEADER: &str = "# Project memory\n\nDurable facts recorded by Codex. Review, edit, or delete them with `/memory`.\n\n";
+
+/// Entries beyond this many bytes are left out of the session instructions so
+/// a long-lived memory file cannot crowd out the rest of the context.
+const MAX_LOADED_MEMORY_BYTES: usize = 16 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MemoryEdit {
+    Add { text: String },
+    Update { id: String, text: String },
+    Delete { id: String },
+}
+
+/// The memory file for the project containing `cwd`.
+pub(crate) fn memory_path(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd)
+        .unwrap_or_else(|| cwd.to_path_buf())
+        .join(MEMORY_DIR)
+        .join(MEMORY_FILENAME)
+}
+
+/// Entries of the memory file at `path`; empty when the file does not exist.
+pub(crate) async fn read_entries(path: &Path) -> std::io::Result<Vec<MemoryEntry>> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(contents) => Ok(parse_entries(&contents)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Applies `edit` to the memory file at `path`, creating it on the first
+/// `add`, and returns the entries after the change.
+pub(crate) async fn edit_entries(
+    path: &Path,
+    edit: MemoryEdit,
+) -> Result<Vec<MemoryEntry>, String> {
+    let contents = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(format!("Failed to read {}: {err}", path.display())),
+    };
+    let updated = apply_edit(&contents, &edit)?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|err| format!("Failed to create {}: {err}", parent.display()))?;
+    }
+    tokio::fs::write(path, &updated)
+        .await
+        .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    Ok(parse_entries(&updated))
+}
+
+/// The instructions section listing the memory of the project containing
+/// `cwd`, or `None` when there is nothing recorded.
+pub(crate) async fn render_memory_section(cwd: &Path) -> Option<String> {
+    let path = memory_path(cwd);
+    let entries = match read_entries(&path).await {
+        Ok(entries) => entries,
+        Err(err) => {
+            tracing::warn!("failed to read project memory {}: {err}", path.display());
+            return None;
+        }
+    };
+    format_memory_section(&path, &entries)
+}
+
+fn format_memory_section(path: &Path, entries: &[MemoryEntry]) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+    let mut section = format!(
+        "## Project memory\nFacts recorded in earlier sessions in this project (`{}`). They may be out of date: verify them before relying on them, and update or delete stale entries with the `memory` tool.\n",
+        path.display()
+    );
+    let mut remaining = MAX_LOADED_MEMORY_BYTES;
+    for (loaded, entry) in entries.iter().enumerate() {
+        let line = format!("- [{}] {}\n", entry.id, entry.text);
+        if line.len() > remaining {
+            let omitted = entries.len() - loaded;
+            section.push_str(&format!(
+                "({omitted} more entries omitted; list them with the `memory` tool.)\n"
+            ));
+            break;
+        }
+        remaining -= line.len();
+        section.push_str(&line);
+    }
+    Some(section.trim_end().to_string())
+}
+
+fn entry_text(line: &str) -> Option<&str> {
+    line.strip_prefix("- ")
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+fn parse_entries(contents: &str) -> Vec<MemoryEntry> {
+    contents
+        .lines()
+        .filter_map(entry_text)
+        .enumerate()
+        .map(|(idx, text)| MemoryEntry {
+            id: (idx + 1).to_string(),
+            text: text.to_string(),
+        })
+        .collect()
+}
+
+/// Each entry is a single bullet line, so newlines are folded into spaces.
+fn normalize_text(text: &str) -> Result<String, String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return Err("Memory entries cannot be empty.".to_string());
+    }
+    Ok(text)
+}
+
+/// Index in `lines` of the entry with `id`.
+fn entry_line(lines: &[String], id: &str) -> Result<usize, String> {
+    let missing = || format!("No memory entry with id {id}.");
+    let position = id.trim().parse::<usize>().map_err(|_| missing())?;
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| entry_text(line).is_some())
+        .nth(position.checked_sub(1).ok_or_else(missing)?)
+        .map(|(idx, _)| idx)
+        .ok_or_else(missing)
+}
+
+fn apply_edit(contents: &str, edit: &MemoryEdit) -> Result<String, String> {
+    let contents = if contents.trim().is_empty() {
+        MEMORY_HEADER
+    } else {
+        contents
+    };
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    match edit {
+        MemoryEdit::Add { text } => {
+            let line = format!("- {}", normalize_text(text)?);
+            lines.push(line);
+        }
+        MemoryEdit::Update { id, text } => {
+            let idx = entry_line(&lines, id)?;
+            lines[idx] = format!("- {}", normalize_text(text)?);
+        }
+        MemoryEdit::Delete { id } => {
+            let idx = entry_line(&lines, id)?;
+            lines.remove(idx);
+        }
+    }
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    Ok(updated)
+}
+
+pub(crate) async fn list_memory(sess: &Session, sub_id: String) {
+    let path = memory_path(&sess.new_default_turn().await.cwd);
+    let msg = match read_entries(&path).await {
+        Ok(entries) => EventMsg::ListMemoryResponse(ListMemoryResponseEvent { path, entries }),
+        Err(err) => EventMsg::Error(ErrorEvent {
+            message: format!("Failed to read {}: {err}", path.display()),
+            codex_error_info: None,
+        }),
+    };
+    sess.send_event_raw(Event { id: sub_id, msg }).await;
+}
+
+pub(crate) async fn edit_memory(sess: &Session, sub_id: String, edit: MemoryEdit) {
+    let path = memory_path(&sess.new_default_turn().await.cwd);
+    let msg = match edit_entries(&path, edit).await {
+        Ok(entries) => EventMsg::ListMemoryResponse(ListMemoryResponseEvent { path, entries }),
+        Err(message) => EventMsg::Error(ErrorEvent {
+            message,
+            codex_error_info: None,
+        }),
+    };
+    sess.send_event_raw(Event { id: sub_id, msg }).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entry(id: &str, text: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: id.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn first_add_writes_the_header() {
+        let updated = apply_edit(
+            "",
+            &MemoryEdit::Add {
+                text: "Run `just fmt`\nbefore committing.".to_string(),
+            },
+        )
+        .expect("add");
+
+        assert_eq!(
+            updated,
+            format!("{MEMORY_HEADER}- Run `just fmt` before committing.\n")
+        );
+        assert_eq!(
+            parse_entries(&updated),
+            vec![entry("1", "Run `just fmt` before committing.")]
+        );
+    }
+
+    #[test]
+    fn edits_target_bullets_and_keep_other_lines() {
+        let contents = "# Notes\n\nHand-written intro.\n- uses pnpm\n- tests need docker\n";
+
+        let updated = apply_edit(
+            contents,
+            &MemoryEdit::Update {
+                id: "2".to_string(),
+                text: "integration tests need docker".to_string(),
+            },
+        )
+        .expect("update");
+        assert_eq!(
+            updated,
+            "# Notes\n\nHand-written intro.\n- uses pnpm\n- integration tests need docker\n"
+        );
+
+        let updated = apply_edit(
+            &updated,
+            &MemoryEdit::Delete {
+                id: "1".to_string(),
+            },
+        )
+        .expect("delete");
+        assert_eq!(
+            updated,
+            "# Notes\n\nHand-written intro.\n- integration tests need docker\n"
+        );
+
+        for id in ["0", "2", "x"] {
+            assert_eq!(
+                apply_edit(&updated, &MemoryEdit::Delete { id: id.to_string() }),
+                Err(format!("No memory entry with id {id}."))
+            );
+        }
+        assert!(
+            apply_edit(
+                &updated,
+                &MemoryEdit::Add {
+                    text: "  ".to_string()
+                }
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn memory_section_lists_entries_with_ids() {
+        let path = PathBuf::from("/repo/.codex/memory.md");
+        assert_eq!(format_memory_section(&path, &[]), None);
+
+        let section = format_memory_section(
+            &path,
+            &[entry("1", "uses pnpm"), entry("2", "tests need docker")],
+        )
+        .expect("section");
+        assert!(section.starts_with("## Project memory\n"));
+        assert!(section.ends_with("- [1] uses pnpm\n- [2] tests need docker"));
+    }
+
+    #[tokio::test]
+    async fn edit_entries_creates_the_file_under_the_project_root() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join(".git")).expect("git dir");
+        let nested = dir.path().join("src");
+        std::fs::create_dir(&nested).expect("nested dir");
+
+        let path = memory_path(&nested);
+        assert_eq!(path, dir.path().join(".codex").join("memory.md"));
+        assert_eq!(read_entries(&path).await.expect("read"), Vec::new());
+
+        let entries = edit_entries(
+            &path,
+            MemoryEdit::Add {
+                text: "uses pnpm".to_string(),
+            },
+        )
+        .await
+        .expect("add");
+        assert_eq!(entries, vec![entry("1", "uses pnpm")]);
+        assert_eq!(read_entries(&path).await.expect("read"), entries);
+    }
+}
@@ -0,0 +1,323 @@
+//! Static detection of the build toolchains used by the project in `cwd`.
+//!
+//! Detection only inspects marker files (`Cargo.toml`, `package.json`,
+//! lockfiles, ...) and never runs the tools, so it is cheap enough to do when
+//! the initial context is built. Versions are the ones pinned by the project
+//! (e.g. `rust-toolchain.toml`, `packageManager`, `go.mod`), not the versions
+//! installed on the machine.
+
+use crate::git_info::get_git_repo_root;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// A detected toolchain and the commands the project expects to be used with it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct ProjectToolchain {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lint: Option<String>,
+}
+
+/// Detects toolchains in `cwd`, falling back to the git root when `cwd` itself
+/// has no recognizable project files (e.g. when started in a subdirectory).
+pub(crate) fn detect_project_toolchains(cwd: &Path) -> Vec<ProjectToolchain> {
+    let toolchains = detect_in_dir(cwd);
+    if !toolchains.is_empty() {
+        return toolchains;
+    }
+    match get_git_repo_root(cwd) {
+        Some(root) if root != cwd => detect_in_dir(&root),
+        _ => Vec::new(),
+    }
+}
+
+fn detect_in_dir(dir: &Path) -> Vec<ProjectToolchain> {
+    [
+        detect_cargo,
+        detect_node,
+        detect_python,
+        detect_go,
+        detect_maven,
+    ]
+    .iter()
+    .filter_map(|detect| detect(dir))
+    .collect()
+}
+
+fn detect_cargo(dir: &Path) -> Option<ProjectToolchain> {
+    if !dir.join("Cargo.toml").is_file() {
+        return None;
+    }
+
+    #[derive(Deserialize)]
+    struct RustToolchainFile {
+        toolchain: Option<RustToolchainSection>,
+    }
+    #[derive(Deserialize)]
+    struct RustToolchainSection {
+        channel: Option<String>,
+    }
+
+    let version = read_to_string(&dir.join("rust-toolchain.toml"))
+        .and_then(|contents| toml::from_str::<RustToolchainFile>(&contents).ok())
+        .and_then(|file| file.toolchain?.channel)
+        .or_else(|| read_first_line(&dir.join("rust-toolchain")));
+
+    Some(ProjectToolchain {
+        name: "cargo".to_string(),
+        version,
+        build: Some("cargo build".to_string()),
+        test: Some("cargo test".to_string()),
+        lint: Some("cargo clippy --all-targets".to_string()),
+    })
+}
+
+fn detect_node(dir: &Path) -> Option<ProjectToolchain> {
+    #[derive(Deserialize)]
+    struct PackageJson {
+        #[serde(default, rename = "packageManager")]
+        package_manager: Option<String>,
+        #[serde(default)]
+        scripts: std::collections::HashMap<String, String>,
+    }
+
+    let package_json: PackageJson =
+        serde_json::from_str(&read_to_string(&dir.join("package.json"))?).ok()?;
+
+    // `packageManager` (corepack) is authoritative; otherwise infer from the lockfile.
+    let (manager, version) = match package_json
+        .package_manager
+        .as_deref()
+        .and_then(|spec| spec.split_once('@'))
+    {
+        Some((name, version)) => {
+            // Drop the corepack integrity hash (`pnpm@9.1.0+sha512...`).
+            let version = version.split('+').next().unwrap_or(version);
+            (name.to_string(), Some(version.to_string()))
+        }
+        None => {
+            let manager = if dir.join("pnpm-lock.yaml").is_file() {
+                "pnpm"
+            } else if dir.join("yarn.lock").is_file() {
+                "yarn"
+            } else if dir.join("bun.lockb").is_file() || dir.join("bun.lock").is_file() {
+                "bun"
+            } else {
+                "npm"
+            };
+            (manager.to_string(), None)
+        }
+    };
+
+    let script = |name: &str| {
+        package_json
+            .scripts
+            .contains_key(name)
+            .then(|| format!("{manager} run {name}"))
+    };
+    Some(ProjectToolchain {
+        build: script("build"),
+        test: package_json
+            .scripts
+            .contains_key("test")
+            .then(|| format!("{manager} test")),
+        lint: script("lint"),
+        name: manager,
+        version,
+    })
+}
+
+fn detect_python(dir: &Path) -> Option<ProjectToolchain> {
+    let pyproject = read_to_string(&dir.join("pyproject.toml"))?;
+    let pyproject: toml::Value = toml::from_str(&pyproject).ok()?;
+    let tool = pyproject.get("tool");
+
+    let runner = if dir.join("uv.lock").is_file() {
+        "uv"
+    } else if dir.join("poetry.lock").is_file() || tool.and_then(|t| t.get("poetry")).is_some() {
+        "poetry"
+    } else {
+        return None;
+    };
+
+    let version = read_first_line(&dir.join(".python-version")).or_else(|| {
+        pyproject
+            .get("project")
+            .and_then(|project| project.get("requires-python"))
+            .and_then(toml::Value::as_str)
+            .map(str::to_string)
+    });
+    let lint = tool
+        .and_then(|t| t.get("ruff"))
+        .map(|_| format!("{runner} run ruff check"));
+
+    Some(ProjectToolchain {
+        name: runner.to_string(),
+        version,
+        build: Some(format!("{runner} build")),
+        test: Some(format!("{runner} run pytest")),
+        lint,
+    })
+}
+
+fn detect_go(dir: &Path) -> Option<ProjectToolchain> {
+    let go_mod = read_to_string(&dir.join("go.mod"))?;
+    let version = go_mod.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("go ")
+            .map(|version| version.trim().to_string())
+    });
+    Some(ProjectToolchain {
+        name: "go".to_string(),
+        version,
+        build: Some("go build ./...".to_string()),
+        test: Some("go test ./...".to_string()),
+        lint: Some("go vet ./...".to_string()),
+    })
+}
+
+fn detect_maven(dir: &Path) -> Option<ProjectToolchain> {
+    if !dir.join("pom.xml").is_file() {
+        return None;
+    }
+    let mvn = if dir.join("mvnw").is_file() {
+        "./mvnw"
+    } else {
+        "mvn"
+    };
+    Some(ProjectToolchain {
+        name: "maven".to_string(),
+        version: None,
+        build: Some(format!("{mvn} compile")),
+        test: Some(format!("{mvn} test")),
+        lint: None,
+    })
+}
+
+fn read_to_string(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+fn read_first_line(path: &Path) -> Option<String> {
+    read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, contents: &str) {
+        fs::write(dir.join(name), contents).expect("write file");
+    }
+
+    #[test]
+    fn detects_pnpm_from_lockfile_and_cargo_with_pinned_channel() {
+        let tmp = TempDir::new().expect("tempdir");
+        write(tmp.path(), "Cargo.toml", "[workspace]\n");
+        write(
+            tmp.path(),
+            "rust-toolchain.toml",
+            "[toolchain]\nchannel = \"1.90.0\"\n",
+        );
+        write(
+            tmp.path(),
+            "package.json",
+            r#"{"scripts": {"build": "tsc", "test": "vitest"}}"#,
+        );
+        write(tmp.path(), "pnpm-lock.yaml", "");
+
+        assert_eq!(
+            detect_project_toolchains(tmp.path()),
+            vec![
+                ProjectToolchain {
+                    name: "cargo".to_string(),
+                    version: Some("1.90.0".to_string()),
+                    build: Some("cargo build".to_string()),
+                    test: Some("cargo test".to_string()),
+                    lint: Some("cargo clippy --all-targets".to_string()),
+                },
+                ProjectToolchain {
+                    name: "pnpm".to_string(),
+                    version: None,
+                    build: Some("pnpm run build".to_string()),
+                    test: Some("pnpm test".to_string()),
+                    lint: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn package_manager_field_wins_over_lockfile() {
+        let tmp = TempDir::new().expect("tempdir");
+        write(
+            tmp.path(),
+            "package.json",
+            r#"{"packageManager": "pnpm@9.1.0+sha512.abc", "scripts": {"lint": "eslint ."}}"#,
+        );
+        write(tmp.path(), "package-lock.json", "{}");
+
+        assert_eq!(
+            detect_project_toolchains(tmp.path()),
+            vec![ProjectToolchain {
+                name: "pnpm".to_string(),
+                version: Some("9.1.0".to_string()),
+                build: None,
+                test: None,
+                lint: Some("pnpm run lint".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn detects_uv_and_go() {
+        let tmp = TempDir::new().expect("tempdir");
+        write(
+            tmp.path(),
+            "pyproject.toml",
+            "[project]\nname = \"x\"\nrequires-python = \">=3.11\"\n\n[tool.ruff]\n",
+        );
+        write(tmp.path(), "uv.lock", "");
+        write(tmp.path(), "go.mod", "module example.com/x\n\ngo 1.22\n");
+
+        assert_eq!(
+            detect_project_toolchains(tmp.path()),
+            vec![
+                ProjectToolchain {
+                    name: "uv".to_string(),
+                    version: Some(">=3.11".to_string()),
+                    build: Some("uv build".to_string()),
+                    test: Some("uv run pytest".to_string()),
+                    lint: Some("uv run ruff check".to_string()),
+                },
+                ProjectToolchain {
+                    name: "go".to_string(),
+                    version: Some("1.22".to_string()),
+                    build: Some("go build ./...".to_string()),
+                    test: Some("go test ./...".to_string()),
+                    lint: Some("go vet ./...".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_directory_has_no_toolchains() {
+        let tmp = TempDir::new().expect("tempdir");
+        assert_eq!(detect_project_toolchains(tmp.path()), Vec::new());
+    }
+}
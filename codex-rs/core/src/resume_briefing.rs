@@ -0,0 +1,432 @@
+//! "What changed since I left" briefing for resumed sessions.
+//!
+//! With the `resume_briefing` feature enabled, the git state of the workspace
+//! (HEAD, branch, and the content of files with uncommitted changes) is written
+//! to the rollout at the end of every turn. When the session is resumed, the
+//! last snapshot is compared with the workspace as it is now; new commits,
+//! a branch switch, and files whose uncommitted changes differ are recorded as
+//! a `<resume_briefing>` message right after the resumed history and reported
+//! to clients as a `ResumeBriefing` event. The latest CI run on the branch is
+//! included when the GitHub CLI is installed and authenticated.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::BriefingCommit;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ResumeBriefingEvent;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::WorkspaceFileState;
+use codex_protocol::protocol::WorkspaceSnapshotEvent;
+use serde::Deserialize;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::git_info::current_branch_name;
+use crate::git_info::run_git_command_with_timeout;
+use crate::session_prefix::RESUME_BRIEFING_OPEN_TAG;
+
+/// Snapshots skip workspaces with more uncommitted files than this; comparing
+/// them file by file would not produce a useful briefing anyway.
+const MAX_SNAPSHOT_FILES: usize = 500;
+
+/// At most this many commits and files are listed in the briefing.
+const MAX_LISTED_COMMITS: usize = 20;
+const MAX_LISTED_FILES: usize = 50;
+
+const CI_STATUS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Records the current git state of `cwd` in the rollout.
+pub(crate) async fn record_workspace_snapshot(sess: &Session, cwd: &Path) {
+    if let Some(snapshot) = capture_snapshot(cwd).await {
+        sess.persist_rollout_items(&[RolloutItem::EventMsg(EventMsg::WorkspaceSnapshot(snapshot))])
+            .await;
+    }
+}
+
+/// Compares the workspace with the last snapshot in `rollout_items` and, if it
+/// changed, tells the model and clients. A fresh snapshot is recorded either
+/// way so that resuming again does not repeat the same briefing.
+pub(crate) async fn brief_on_resume(
+    sess: &Session,
+    turn_context: &TurnContext,
+    rollout_items: &[RolloutItem],
+) {
+    let Some(previous) = last_snapshot(rollout_items) else {
+        return;
+    };
+    let cwd = &turn_context.cwd;
+    let Some(current) = capture_snapshot(cwd).await else {
+        return;
+    };
+
+    let (new_commits, new_commit_count) = match (&previous.head, &current.head) {
+        (Some(previous_head), Some(head)) if previous_head != head => {
+            commits_since(cwd, previous_head).await
+        }
+        _ => (Vec::new(), 0),
+    };
+    let briefing = compare_snapshots(previous, &current, new_commits, new_commit_count);
+    sess.persist_rollout_items(&[RolloutItem::EventMsg(EventMsg::WorkspaceSnapshot(
+        current.clone(),
+    ))])
+    .await;
+    let Some(mut briefing) = briefing else {
+        return;
+    };
+    if let Some(branch) = &current.branch {
+        briefing.ci_status = ci_status(cwd, branch).await;
+    }
+
+    let item = ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: format_briefing(&briefing, current.taken_at),
+        }],
+        end_turn: None,
+    };
+    sess.record_conversation_items(turn_context, &[item]).await;
+    sess.send_event(turn_context, EventMsg::ResumeBriefing(briefing))
+        .await;
+}
+
+fn last_snapshot(rollout_items: &[RolloutItem]) -> Option<&WorkspaceSnapshotEvent> {
+    rollout_items.iter().rev().find_map(|item| match item {
+        RolloutItem::EventMsg(EventMsg::WorkspaceSnapshot(snapshot)) => Some(snapshot),
+        _ => None,
+    })
+}
+
+/// Git state of `cwd`; `None` outside a repository or when git fails.
+async fn capture_snapshot(cwd: &Path) -> Option<WorkspaceSnapshotEvent> {
+    let root = git_stdout(cwd, &["rev-parse", "--show-toplevel"]).await?;
+    let root = PathBuf::from(root.trim());
+    let status = git_stdout(
+        &root,
+        &["status", "--porcelain=v1", "-z", "--untracked-files=all"],
+    )
+    .await?;
+    let dirty = parse_porcelain_z(&status);
+    if dirty.len() > MAX_SNAPSHOT_FILES {
+        return None;
+    }
+
+    let existing: Vec<&str> = dirty
+        .iter()
+        .filter(|path| root.join(path).is_file())
+        .filter_map(|path| path.to_str())
+        .collect();
+    let mut blobs: HashMap<&str, String> = HashMap::new();
+    if !existing.is_empty() {
+        let mut args = vec!["hash-object", "--"];
+        args.extend(existing.iter().copied());
+        let hashes = git_stdout(&root, &args).await?;
+        blobs.extend(
+            existing
+                .iter()
+                .copied()
+                .zip(hashes.lines().map(str::to_string)),
+        );
+    }
+    let dirty_files = dirty
+        .iter()
+        .map(|path| WorkspaceFileState {
+            path: path.clone(),
+            blob: path.to_str().and_then(|path| blobs.get(path).cloned()),
+        })
+        .collect();
+
+    let head = git_stdout(&root, &["rev-parse", "HEAD"])
+        .await
+        .map(|head| head.trim().to_string());
+    Some(WorkspaceSnapshotEvent {
+        taken_at: chrono::Utc::now().timestamp(),
+        head,
+        branch: current_branch_name(&root).await,
+        dirty_files,
+    })
+}
+
+/// Paths from `git status --porcelain=v1 -z`; the source of a rename or copy
+/// is dropped since the destination carries the content.
+fn parse_porcelain_z(output: &str) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let mut entries = output.split('\0').filter(|entry| !entry.is_empty());
+    while let Some(entry) = entries.next() {
+        let Some((status, path)) = entry.split_at_checked(3) else {
+            continue;
+        };
+        paths.push(PathBuf::from(path));
+        if status.contains(['R', 'C']) {
+            entries.next();
+        }
+    }
+    paths
+}
+
+/// Subjects of the newest commits after `since` plus the total count.
+async fn commits_since(cwd: &Path, since: &str) -> (Vec<BriefingCommit>, usize) {
+    let range = format!("{since}..HEAD");
+    let count = git_stdout(cwd, &["rev-list", "--count", &range])
+        .await
+        .and_then(|count| count.trim().parse().ok())
+        .unwrap_or(0);
+    let limit = MAX_LISTED_COMMITS.to_string();
+    let log = git_stdout(cwd, &["log", "-n", &limit, "--format=%h%x1f%s", &range])
+        .await
+        .unwrap_or_default();
+    let commits = log
+        .lines()
+        .filter_map(|line| line.split_once('\u{001f}'))
+        .map(|(sha, subject)| BriefingCommit {
+            sha: sha.to_string(),
+            subject: subject.to_string(),
+        })
+        .collect();
+    (commits, count)
+}
+
+fn compare_snapshots(
+    previous: &WorkspaceSnapshotEvent,
+    current: &WorkspaceSnapshotEvent,
+    new_commits: Vec<BriefingCommit>,
+    new_commit_count: usize,
+) -> Option<ResumeBriefingEvent> {
+    let before: HashMap<&PathBuf, &Option<String>> = previous
+        .dirty_files
+        .iter()
+        .map(|file| (&file.path, &file.blob))
+        .collect();
+    let after: HashMap<&PathBuf, &Option<String>> = current
+        .dirty_files
+        .iter()
+        .map(|file| (&file.path, &file.blob))
+        .collect();
+
+    let mut changed_files: BTreeSet<PathBuf> = after
+        .iter()
+        .filter(|(path, blob)| before.get(*path) != Some(*blob))
+        .map(|(path, _)| (*path).clone())
+        .collect();
+    // A file that is clean again after HEAD moved was most likely committed;
+    // the new commits already cover it.
+    if previous.head == current.head {
+        changed_files.extend(
+            before
+                .keys()
+                .filter(|path| !after.contains_key(*path))
+                .map(|path| (*path).clone()),
+        );
+    }
+    let previous_branch = if previous.branch != current.branch {
+        previous.branch.clone()
+    } else {
+        None
+    };
+
+    if changed_files.is_empty() && new_commit_count == 0 && previous_branch.is_none() {
+        return None;
+    }
+    Some(ResumeBriefingEvent {
+        since: previous.taken_at,
+        previous_branch,
+        branch: current.branch.clone(),
+        new_commits,
+        new_commit_count,
+        changed_files: changed_files.into_iter().collect(),
+        ci_status: None,
+    })
+}
+
+fn format_briefing(briefing: &ResumeBriefingEvent, now: i64) -> String {
+    let mut lines = vec![
+        RESUME_BRIEFING_OPEN_TAG.to_string(),
+        format!(
+            "This session was resumed. The workspace changed since its last turn ({} ago); \
+             re-read anything you rely on from earlier in the conversation.",
+            format_elapsed(now - briefing.since)
+        ),
+    ];
+    if let Some(previous_branch) = &briefing.previous_branch {
+        let branch = briefing.branch.as_deref().unwrap_or("(detached HEAD)");
+        lines.push(format!("Branch: {previous_branch} -> {branch}"));
+    }
+    if briefing.new_commit_count > 0 {
+        lines.push(format!("New commits ({}):", briefing.new_commit_count));
+        for commit in &briefing.new_commits {
+            lines.push(format!("- {} {}", commit.sha, commit.subject));
+        }
+        if briefing.new_commit_count > briefing.new_commits.len() {
+            lines.push(format!(
+                "- … and {} more",
+                briefing.new_commit_count - briefing.new_commits.len()
+            ));
+        }
+    }
+    if !briefing.changed_files.is_empty() {
+        lines.push("Uncommitted changes that differ from then:".to_string());
+        for path in briefing.changed_files.iter().take(MAX_LISTED_FILES) {
+            lines.push(format!("- {}", path.display()));
+        }
+        if briefing.changed_files.len() > MAX_LISTED_FILES {
+            lines.push(format!(
+                "- … and {} more",
+                briefing.changed_files.len() - MAX_LISTED_FILES
+            ));
+        }
+    }
+    if let Some(ci_status) = &briefing.ci_status {
+        lines.push(format!("Latest CI run: {ci_status}"));
+    }
+    lines.push("</resume_briefing>".to_string());
+    lines.join("\n")
+}
+
+fn format_elapsed(seconds: i64) -> String {
+    let minutes = seconds.max(0) / 60;
+    match minutes {
+        0 => "less than a minute".to_string(),
+        1..60 => format!("{minutes} min"),
+        60..1440 => format!("{} h", minutes / 60),
+        1440..2880 => "1 day".to_string(),
+        _ => format!("{} days", minutes / 1440),
+    }
+}
+
+/// Conclusion (or status, while running) of the latest workflow run on
+/// `branch`, via `gh run list`.
+async fn ci_status(cwd: &Path, branch: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Run {
+        workflow_name: String,
+        status: String,
+        conclusion: String,
+    }
+
+    let output = timeout(
+        CI_STATUS_TIMEOUT,
+        Command::new("gh")
+            .args(["run", "list", "--limit", "1", "--branch", branch])
+            .args(["--json", "workflowName,status,conclusion"])
+            .current_dir(cwd)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let run = serde_json::from_slice::<Vec<Run>>(&output.stdout)
+        .ok()?
+        .into_iter()
+        .next()?;
+    let outcome = if run.conclusion.is_empty() {
+        run.status
+    } else {
+        run.conclusion
+    };
+    Some(format!("{} on {branch}: {outcome}", run.workflow_name))
+}
+
+async fn git_stdout(cwd: &Path, args: &[&str]) -> Option<String> {
+    let output = run_git_command_with_timeout(args, cwd).await?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn snapshot(
+        head: &str,
+        branch: &str,
+        files: &[(&str, Option<&str>)],
+    ) -> WorkspaceSnapshotEvent {
+        WorkspaceSnapshotEvent {
+            taken_at: 1_000,
+            head: Some(head.to_string()),
+            branch: Some(branch.to_string()),
+            dirty_files: files
+                .iter()
+                .map(|(path, blob)| WorkspaceFileState {
+                    path: PathBuf::from(path),
+                    blob: blob.map(str::to_string),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parses_renames_and_untracked_files() {
+        let output = " M src/lib.rs\0R  new.rs\0old.rs\0?? notes.txt\0";
+        assert_eq!(
+            parse_porcelain_z(output),
+            vec![
+                PathBuf::from("src/lib.rs"),
+                PathBuf::from("new.rs"),
+                PathBuf::from("notes.txt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unchanged_workspace_needs_no_briefing() {
+        let previous = snapshot("abc", "main", &[("a.rs", Some("1"))]);
+        assert_eq!(compare_snapshots(&previous, &previous, Vec::new(), 0), None);
+    }
+
+    #[test]
+    fn reports_edited_and_reverted_files() {
+        let previous = snapshot("abc", "main", &[("a.rs", Some("1")), ("b.rs", Some("2"))]);
+        let current = snapshot("abc", "main", &[("a.rs", Some("9")), ("c.rs", None)]);
+
+        let briefing = compare_snapshots(&previous, &current, Vec::new(), 0).expect("briefing");
+        assert_eq!(
+            briefing.changed_files,
+            vec![
+                PathBuf::from("a.rs"),
+                PathBuf::from("b.rs"),
+                PathBuf::from("c.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn files_cleaned_by_new_commits_are_left_to_the_commit_list() {
+        let previous = snapshot("abc", "main", &[("a.rs", Some("1"))]);
+        let current = snapshot("def", "feature", &[]);
+        let commits = vec![BriefingCommit {
+            sha: "def".to_string(),
+            subject: "Finish a.rs".to_string(),
+        }];
+
+        let briefing = compare_snapshots(&previous, &current, commits, 1).expect("briefing");
+        assert_eq!(briefing.changed_files, Vec::<PathBuf>::new());
+        assert_eq!(briefing.previous_branch, Some("main".to_string()));
+        assert_eq!(
+            format_briefing(&briefing, 1_000 + 3 * 86_400),
+            "<resume_briefing>\n\
+             This session was resumed. The workspace changed since its last turn (3 days ago); \
+             re-read anything you rely on from earlier in the conversation.\n\
+             Branch: main -> feature\n\
+             New commits (1):\n\
+             - def Finish a.rs\n\
+             </resume_briefing>"
+        );
+    }
+}
@@ -1,15 +1,51 @@
 use codex_git::merge_base_with_head;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::ReviewTarget;
+use std::collections::BTreeMap;
 use std::path::Path;
+use std::process::Command;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ResolvedReviewRequest {
     pub target: ReviewTarget,
     pub prompt: String,
     pub user_facing_hint: String,
+    /// With `review_split_by_area`, the areas reviewed one after another by
+    /// separate reviewers; empty for a single review.
+    pub areas: Vec<ReviewArea>,
 }
 
+/// Top-level directories of the change reviewed together; `.` stands for
+/// files at the repository root.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReviewArea {
+    pub dirs: Vec<String>,
+}
+
+impl ReviewArea {
+    /// Appended to the review prompt of this area's reviewer.
+    pub fn instructions(&self) -> String {
+        let dirs: Vec<String> = self
+            .dirs
+            .iter()
+            .map(|dir| {
+                if dir == "." {
+                    "files at the repository root".to_string()
+                } else {
+                    format!("`{dir}/`")
+                }
+            })
+            .collect();
+        format!(
+            "This review is split by area and other reviewers cover the rest of the change. Only report findings in {}.",
+            dirs.join(", ")
+        )
+    }
+}
+
+/// More areas than this are folded into the last one.
+const MAX_REVIEW_AREAS: usize = 6;
+
 const UNCOMMITTED_PROMPT: &str = "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.";
 
 const BASE_BRANCH_PROMPT_BACKUP: &str = "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{upstream}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings.";
@@ -19,20 +55,31 @@ const COMMIT_PROMPT_WITH_TITLE: &str = "Review the code changes introduced by co
 const COMMIT_PROMPT: &str =
     "Review the code changes introduced by commit {sha}. Provide prioritized, actionable findings.";
 
+const RANGE_PROMPT: &str = "Review the code changes in the revision range {range}. Run `git diff {range}` to inspect them. Provide prioritized, actionable findings.";
+
+const PULL_REQUEST_PROMPT: &str = "Review GitHub pull request #{number} ({url}). Its head commit {headSha} has been fetched, and the merge base with the base branch is {mergeBaseSha}. Run `git diff {mergeBaseSha} {headSha}` to inspect the changes. Provide prioritized, actionable findings.";
+
 pub fn resolve_review_request(
     request: ReviewRequest,
     cwd: &Path,
+    split_by_area: bool,
 ) -> anyhow::Result<ResolvedReviewRequest> {
     let target = request.target;
     let prompt = review_prompt(&target, cwd)?;
     let user_facing_hint = request
         .user_facing_hint
         .unwrap_or_else(|| user_facing_hint(&target));
+    let areas = if split_by_area {
+        group_into_areas(&changed_paths(&target, cwd))
+    } else {
+        Vec::new()
+    };
 
     Ok(ResolvedReviewRequest {
         target,
         prompt,
         user_facing_hint,
+        areas,
     })
 }
 
@@ -57,6 +104,20 @@ pub fn review_prompt(target: &ReviewTarget, cwd: &Path) -> anyhow::Result<String
                 Ok(COMMIT_PROMPT.replace("{sha}", sha))
             }
         }
+        ReviewTarget::Range { range } => {
+            validate_range(range)?;
+            Ok(RANGE_PROMPT.replace("{range}", range))
+        }
+        ReviewTarget::PullRequest { url } => {
+            let pull_request = PullRequestRef::parse(url)?;
+            let head = fetch_pull_request(&pull_request, cwd)?;
+            let merge_base = pull_request_merge_base(&head, cwd)?;
+            Ok(PULL_REQUEST_PROMPT
+                .replace("{number}", &pull_request.number.to_string())
+                .replace("{url}", url)
+                .replace("{headSha}", &head)
+                .replace("{mergeBaseSha}", &merge_base))
+        }
         ReviewTarget::Custom { instructions } => {
             let prompt = instructions.trim();
             if prompt.is_empty() {
@@ -67,6 +128,185 @@ pub fn review_prompt(target: &ReviewTarget, cwd: &Path) -> anyhow::Result<String
     }
 }
 
+/// A pull request on GitHub, from a URL such as
+/// `https://github.com/owner/repo/pull/123`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PullRequestRef {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+}
+
+impl PullRequestRef {
+    pub fn parse(url: &str) -> anyhow::Result<Self> {
+        let invalid = || anyhow::anyhow!("not a GitHub pull request URL: {url}");
+        let path = url
+            .trim()
+            .strip_prefix("https://github.com/")
+            .or_else(|| url.trim().strip_prefix("http://github.com/"))
+            .ok_or_else(invalid)?;
+        let mut parts = path.split('/');
+        let (Some(owner), Some(repo), Some("pull"), Some(number)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        let number = number
+            .split(['#', '?'])
+            .next()
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(invalid)?;
+        if owner.is_empty() || repo.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            owner: owner.to_string(),
+            repo: repo.trim_end_matches(".git").to_string(),
+            number,
+        })
+    }
+
+    /// Local ref the pull request's head is fetched into.
+    fn local_ref(&self) -> String {
+        format!("refs/codex/review/pr-{}", self.number)
+    }
+}
+
+/// Fetches the pull request's head, from `origin` when it points at the same
+/// GitHub repository, and returns its commit.
+fn fetch_pull_request(pull_request: &PullRequestRef, cwd: &Path) -> anyhow::Result<String> {
+    let slug = format!("{}/{}", pull_request.owner, pull_request.repo);
+    let remote = match git(cwd, &["remote", "get-url", "origin"]) {
+        Ok(origin) if origin.trim().trim_end_matches(".git").ends_with(&slug) => {
+            "origin".to_string()
+        }
+        _ => format!("https://github.com/{slug}.git"),
+    };
+    let refspec = format!(
+        "+refs/pull/{}/head:{}",
+        pull_request.number,
+        pull_request.local_ref()
+    );
+    git(cwd, &["fetch", "--no-tags", &remote, &refspec])?;
+    Ok(git(cwd, &["rev-parse", &pull_request.local_ref()])?
+        .trim()
+        .to_string())
+}
+
+/// Merge base of the pull request's head with the remote's default branch,
+/// or with `HEAD` when `origin/HEAD` is not set.
+fn pull_request_merge_base(head: &str, cwd: &Path) -> anyhow::Result<String> {
+    let base = if git(cwd, &["rev-parse", "--verify", "--quiet", "origin/HEAD"]).is_ok() {
+        "origin/HEAD"
+    } else {
+        "HEAD"
+    };
+    Ok(git(cwd, &["merge-base", base, head])?.trim().to_string())
+}
+
+fn validate_range(range: &str) -> anyhow::Result<()> {
+    if range.trim().is_empty() {
+        anyhow::bail!("Review range cannot be empty");
+    }
+    if range.starts_with('-') || range.chars().any(char::is_whitespace) {
+        anyhow::bail!("invalid review range: {range}");
+    }
+    Ok(())
+}
+
+/// Files changed by `target`, relative to the repository root. Empty when
+/// they cannot be listed (for example for custom instructions).
+fn changed_paths(target: &ReviewTarget, cwd: &Path) -> Vec<String> {
+    let outputs = match target {
+        ReviewTarget::UncommittedChanges => vec![
+            git(cwd, &["diff", "--name-only", "HEAD"]),
+            git(cwd, &["ls-files", "--others", "--exclude-standard"]),
+        ],
+        ReviewTarget::BaseBranch { branch } => match merge_base_with_head(cwd, branch) {
+            Ok(Some(merge_base)) => vec![git(cwd, &["diff", "--name-only", &merge_base])],
+            _ => Vec::new(),
+        },
+        ReviewTarget::Commit { sha, .. } => vec![git(
+            cwd,
+            &[
+                "diff-tree",
+                "--no-commit-id",
+                "--name-only",
+                "-r",
+                "--root",
+                sha,
+            ],
+        )],
+        ReviewTarget::Range { range } => vec![git(cwd, &["diff", "--name-only", range])],
+        ReviewTarget::PullRequest { url } => match PullRequestRef::parse(url) {
+            Ok(pull_request) => {
+                let head = pull_request.local_ref();
+                match pull_request_merge_base(&head, cwd) {
+                    Ok(merge_base) => {
+                        vec![git(cwd, &["diff", "--name-only", &merge_base, &head])]
+                    }
+                    Err(_) => Vec::new(),
+                }
+            }
+            Err(_) => Vec::new(),
+        },
+        ReviewTarget::Custom { .. } => Vec::new(),
+    };
+    outputs
+        .into_iter()
+        .filter_map(Result::ok)
+        .flat_map(|output| {
+            output
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Groups changed files by top-level directory, largest areas first. Small
+/// areas beyond [`MAX_REVIEW_AREAS`] share the last reviewer. A change that
+/// touches a single area is not split.
+fn group_into_areas(paths: &[String]) -> Vec<ReviewArea> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for path in paths {
+        let dir = match path.split_once('/') {
+            Some((dir, _)) => dir.to_string(),
+            None => ".".to_string(),
+        };
+        *counts.entry(dir).or_default() += 1;
+    }
+    if counts.len() < 2 {
+        return Vec::new();
+    }
+    let mut dirs: Vec<(String, usize)> = counts.into_iter().collect();
+    // Stable sort keeps directories with equal counts in name order.
+    dirs.sort_by(|a, b| b.1.cmp(&a.1));
+    let mut areas: Vec<ReviewArea> = Vec::new();
+    for (index, (dir, _)) in dirs.into_iter().enumerate() {
+        if index < MAX_REVIEW_AREAS {
+            areas.push(ReviewArea { dirs: vec![dir] });
+        } else if let Some(last) = areas.last_mut() {
+            last.dirs.push(dir);
+        }
+    }
+    areas
+}
+
+fn git(cwd: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git").args(args).current_dir(cwd).output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 pub fn user_facing_hint(target: &ReviewTarget) -> String {
     match target {
         ReviewTarget::UncommittedChanges => "current changes".to_string(),
@@ -79,6 +319,14 @@ pub fn user_facing_hint(target: &ReviewTarget) -> String {
                 format!("commit {short_sha}")
             }
         }
+        ReviewTarget::Range { range } => format!("changes in {range}"),
+        ReviewTarget::PullRequest { url } => match PullRequestRef::parse(url) {
+            Ok(pull_request) => format!(
+                "pull request {}/{}#{}",
+                pull_request.owner, pull_request.repo, pull_request.number
+            ),
+            Err(_) => format!("pull request {url}"),
+        },
         ReviewTarget::Custom { instructions } => instructions.trim().to_string(),
     }
 }
@@ -91,3 +339,78 @@ impl From<ResolvedReviewRequest> for ReviewRequest {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_pull_request_urls() {
+        assert_eq!(
+            PullRequestRef::parse("https://github.com/openai/codex/pull/4321/files").unwrap(),
+            PullRequestRef {
+                owner: "openai".to_string(),
+                repo: "codex".to_string(),
+                number: 4321,
+            }
+        );
+        assert_eq!(
+            PullRequestRef::parse("https://github.com/openai/codex/pull/7#discussion_r1")
+                .unwrap()
+                .number,
+            7
+        );
+        assert!(PullRequestRef::parse("https://github.com/openai/codex/issues/7").is_err());
+        assert!(PullRequestRef::parse("https://gitlab.com/openai/codex/pull/7").is_err());
+    }
+
+    #[test]
+    fn groups_changed_files_by_top_level_directory() {
+        let paths: Vec<String> = [
+            "core/src/a.rs",
+            "core/src/b.rs",
+            "tui/src/c.rs",
+            "README.md",
+            "docs/x.md",
+            "exec/src/d.rs",
+            "cli/src/e.rs",
+            "protocol/src/f.rs",
+            "utils/g.rs",
+        ]
+        .iter()
+        .map(|path| path.to_string())
+        .collect();
+
+        let dirs: Vec<Vec<String>> = group_into_areas(&paths)
+            .into_iter()
+            .map(|area| area.dirs)
+            .collect();
+        assert_eq!(
+            dirs,
+            vec![
+                vec!["core"],
+                vec!["."],
+                vec!["cli"],
+                vec!["docs"],
+                vec!["exec"],
+                vec!["protocol", "tui", "utils"],
+            ]
+        );
+        assert_eq!(
+            group_into_areas(&["core/a.rs".to_string(), "core/b.rs".to_string()]),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn area_instructions_name_the_directories() {
+        let area = ReviewArea {
+            dirs: vec!["core".to_string(), ".".to_string()],
+        };
+        assert!(
+            area.instructions()
+                .ends_with("Only report findings in `core/`, files at the repository root.")
+        );
+    }
+}
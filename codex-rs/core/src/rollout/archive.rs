@@ -0,0 +1,279 @@
+//! Cold storage for archived threads.
+//!
+//! Archiving compresses a rollout with zstd into `~/.codex/archived_sessions`,
+//! which default listings and id lookups never scan. Resuming an archived
+//! rollout first restores it to the dated sessions directory so the recorder
+//! can keep appending to a plain `.jsonl` file.
+
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::ARCHIVED_SESSIONS_SUBDIR;
+use super::SESSIONS_SUBDIR;
+use super::list::parse_timestamp_uuid_from_filename;
+
+/// File name suffix of rollouts compressed by [`archive_rollout`].
+pub(crate) const COMPRESSED_ROLLOUT_SUFFIX: &str = ".jsonl.zst";
+
+/// Archives are written once and rarely read, so favour ratio over speed.
+const COMPRESSION_LEVEL: i32 = 10;
+
+pub(crate) fn is_compressed_rollout(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(COMPRESSED_ROLLOUT_SUFFIX))
+}
+
+/// Compresses the rollout at `rollout_path` into the archived sessions
+/// directory and removes the original. Returns the path of the archive.
+pub async fn archive_rollout(codex_home: &Path, rollout_path: &Path) -> io::Result<PathBuf> {
+    let file_name = rollout_file_name(rollout_path)?;
+    if !file_name.ends_with(".jsonl") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a rollout file", rollout_path.display()),
+        ));
+    }
+    let archive_dir = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    tokio::fs::create_dir_all(&archive_dir).await?;
+    let target = archive_dir.join(format!("{file_name}.zst"));
+
+    let source = rollout_path.to_path_buf();
+    let destination = target.clone();
+    tokio::task::spawn_blocking(move || {
+        write_atomically(&destination, |output| {
+            let mut input = File::open(&source)?;
+            let mut encoder = zstd::stream::write::Encoder::new(output, COMPRESSION_LEVEL)?;
+            io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?.sync_all()
+        })?;
+        fs::remove_file(&source)
+    })
+    .await
+    .map_err(io::Error::other)??;
+    Ok(target)
+}
+
+/// Moves an archived rollout back to `sessions/YYYY/MM/DD`, decompressing it
+/// if needed. Uncompressed archives from older versions are moved as-is.
+pub async fn unarchive_rollout(codex_home: &Path, archived_path: &Path) -> io::Result<PathBuf> {
+    let file_name = rollout_file_name(archived_path)?;
+    let Some((created_at, _)) = parse_timestamp_uuid_from_filename(file_name) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a rollout file", archived_path.display()),
+        ));
+    };
+    let target_dir = sessions_day_dir(codex_home, created_at);
+    tokio::fs::create_dir_all(&target_dir).await?;
+    let target = target_dir.join(file_name.strip_suffix(".zst").unwrap_or(file_name));
+    if tokio::fs::try_exists(&target).await? {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", target.display()),
+        ));
+    }
+
+    if !is_compressed_rollout(archived_path) {
+        tokio::fs::rename(archived_path, &target).await?;
+        return Ok(target);
+    }
+
+    let source = archived_path.to_path_buf();
+    let destination = target.clone();
+    tokio::task::spawn_blocking(move || {
+        write_atomically(&destination, |mut output| {
+            let input = File::open(&source)?;
+            zstd::stream::copy_decode(input, &mut output)?;
+            output.sync_all()
+        })?;
+        fs::remove_file(&source)
+    })
+    .await
+    .map_err(io::Error::other)??;
+    Ok(target)
+}
+
+/// Returns the path to resume from: archived rollouts are restored to the
+/// sessions directory first, anything else is returned unchanged.
+pub(crate) async fn unarchive_rollout_if_archived(
+    codex_home: &Path,
+    path: PathBuf,
+) -> io::Result<PathBuf> {
+    let archive_dir = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    if is_compressed_rollout(&path) || path.parent() == Some(archive_dir.as_path()) {
+        unarchive_rollout(codex_home, &path).await
+    } else {
+        Ok(path)
+    }
+}
+
+/// Locates an archived rollout by thread id. Returns `Ok(None)` when there is
+/// no such archive or the id is not a UUID.
+pub async fn find_archived_thread_path_by_id_str(
+    codex_home: &Path,
+    id_str: &str,
+) -> io::Result<Option<PathBuf>> {
+    let Ok(id) = Uuid::parse_str(id_str) else {
+        return Ok(None);
+    };
+    let archive_dir = codex_home.join(ARCHIVED_SESSIONS_SUBDIR);
+    let mut entries = match tokio::fs::read_dir(&archive_dir).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        let file_name = entry.file_name();
+        let Some(name) = file_name.to_str() else {
+            continue;
+        };
+        if parse_timestamp_uuid_from_filename(name).is_some_and(|(_, file_id)| file_id == id) {
+            return Ok(Some(entry.path()));
+        }
+    }
+    Ok(None)
+}
+
+/// Reads a whole rollout, decompressing archived ones.
+pub(crate) async fn read_rollout_to_string(path: &Path) -> io::Result<String> {
+    if !is_compressed_rollout(path) {
+        return tokio::fs::read_to_string(path).await;
+    }
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let bytes = zstd::stream::decode_all(File::open(&path)?)?;
+        String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+fn rollout_file_name(path: &Path) -> io::Result<&str> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} has no file name", path.display()),
+            )
+        })
+}
+
+fn sessions_day_dir(codex_home: &Path, created_at: OffsetDateTime) -> PathBuf {
+    codex_home
+        .join(SESSIONS_SUBDIR)
+        .join(format!("{:04}", created_at.year()))
+        .join(format!("{:02}", u8::from(created_at.month())))
+        .join(format!("{:02}", created_at.day()))
+}
+
+/// Writes `destination` through a temporary sibling so readers never observe
+/// a partially written file.
+fn write_atomically(
+    destination: &Path,
+    write: impl FnOnce(File) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut tmp = destination.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let result = File::create(&tmp)
+        .and_then(write)
+        .and_then(|()| fs::rename(&tmp, destination));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    const FILE_NAME: &str =
+        "rollout-2025-03-04T05-06-07-0194f5a6-89ab-7cde-8f01-23456789abcd.jsonl";
+    const ID: &str = "0194f5a6-89ab-7cde-8f01-23456789abcd";
+
+    fn write_rollout(codex_home: &Path) -> PathBuf {
+        let dir = codex_home.join(SESSIONS_SUBDIR).join("2025/03/04");
+        fs::create_dir_all(&dir).expect("create sessions dir");
+        let path = dir.join(FILE_NAME);
+        fs::write(&path, "{\"line\":1}\n{\"line\":2}\n").expect("write rollout");
+        path
+    }
+
+    #[tokio::test]
+    async fn archive_round_trip_restores_original_location() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let original = write_rollout(home.path());
+
+        let archived = archive_rollout(home.path(), &original).await?;
+        assert_eq!(
+            archived,
+            home.path()
+                .join(ARCHIVED_SESSIONS_SUBDIR)
+                .join(format!("{FILE_NAME}.zst"))
+        );
+        assert!(!original.exists());
+        assert_eq!(
+            read_rollout_to_string(&archived).await?,
+            "{\"line\":1}\n{\"line\":2}\n"
+        );
+        assert_eq!(
+            find_archived_thread_path_by_id_str(home.path(), ID).await?,
+            Some(archived.clone())
+        );
+
+        let restored = unarchive_rollout_if_archived(home.path(), archived.clone()).await?;
+        assert_eq!(restored, original);
+        assert!(!archived.exists());
+        assert_eq!(
+            fs::read_to_string(&restored)?,
+            "{\"line\":1}\n{\"line\":2}\n"
+        );
+        assert_eq!(
+            find_archived_thread_path_by_id_str(home.path(), ID).await?,
+            None
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unarchive_moves_legacy_uncompressed_archives() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let archive_dir = home.path().join(ARCHIVED_SESSIONS_SUBDIR);
+        fs::create_dir_all(&archive_dir)?;
+        let legacy = archive_dir.join(FILE_NAME);
+        fs::write(&legacy, "{}\n")?;
+
+        let restored = unarchive_rollout_if_archived(home.path(), legacy.clone()).await?;
+        assert_eq!(
+            restored,
+            home.path()
+                .join(SESSIONS_SUBDIR)
+                .join("2025/03/04")
+                .join(FILE_NAME)
+        );
+        assert!(!legacy.exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn live_rollouts_are_left_in_place() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let original = write_rollout(home.path());
+        assert_eq!(
+            unarchive_rollout_if_archived(home.path(), original.clone()).await?,
+            original
+        );
+        Ok(())
+    }
+}
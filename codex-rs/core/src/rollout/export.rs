@@ -0,0 +1,426 @@
+//! Whole-session transcripts as Markdown, HTML, or JSON, for attaching to
+//! PRs and incident reports.
+//!
+//! Turns are collected the same way as for [`super::share`]: messages,
+//! reasoning summaries, commands, diffs, and subagent messages, with secrets
+//! redacted. HTML is the share page of every turn. Markdown and HTML cut long
+//! tool output; JSON keeps it whole.
+
+use std::fmt;
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::SessionMeta;
+use serde::Serialize;
+
+use super::share::CallKind;
+use super::share::Entry;
+use super::share::MAX_OUTPUT_LINES;
+use super::share::Redactor;
+use super::share::ShareError;
+use super::share::ShareOptions;
+use super::share::Turn;
+use super::share::collect_turns;
+use super::share::default_title;
+use super::share::render_share_html;
+use super::share::truncate_lines;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl ExportFormat {
+    /// File extension for documents in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = ShareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "html" => Ok(ExportFormat::Html),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(ShareError::InvalidFormat(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportedTranscript {
+    pub contents: String,
+    pub total_turns: usize,
+    /// Number of secrets replaced with `[REDACTED]`.
+    pub redactions: usize,
+}
+
+/// Renders every turn of a rollout in `format`.
+pub fn export_transcript(
+    items: &[RolloutItem],
+    format: ExportFormat,
+) -> Result<ExportedTranscript, ShareError> {
+    if format == ExportFormat::Html {
+        let shared = render_share_html(items, &ShareOptions::default())?;
+        return Ok(ExportedTranscript {
+            contents: shared.html,
+            total_turns: shared.total_turns,
+            redactions: shared.redactions,
+        });
+    }
+
+    let meta = items.iter().find_map(|item| match item {
+        RolloutItem::SessionMeta(line) => Some(&line.meta),
+        _ => None,
+    });
+    let turns = collect_turns(items);
+    if turns.is_empty() {
+        return Err(ShareError::NoTurns);
+    }
+
+    let mut redactor = Redactor::default();
+    let contents = if format == ExportFormat::Json {
+        render_json(meta, &turns, &mut redactor)
+    } else {
+        render_markdown(meta, &turns, &mut redactor)
+    };
+    Ok(ExportedTranscript {
+        contents,
+        total_turns: turns.len(),
+        redactions: redactor.count,
+    })
+}
+
+fn render_markdown(meta: Option<&SessionMeta>, turns: &[Turn], redactor: &mut Redactor) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", redactor.redact(&default_title(turns)));
+    if let Some(meta) = meta {
+        let _ = writeln!(
+            out,
+            "_{}_\n",
+            redactor.redact(&format!(
+                "Session {} · {} · {} · {} turns",
+                meta.id,
+                meta.timestamp,
+                meta.cwd.display(),
+                turns.len()
+            ))
+        );
+    }
+
+    for (index, turn) in turns.iter().enumerate() {
+        let _ = writeln!(out, "## Turn {}\n", index + 1);
+        for entry in &turn.entries {
+            render_markdown_entry(&mut out, entry, redactor);
+        }
+    }
+
+    if redactor.count > 0 {
+        let _ = writeln!(
+            out,
+            "---\n\n_{} value(s) that looked like secrets were redacted._",
+            redactor.count
+        );
+    }
+    out
+}
+
+fn render_markdown_entry(out: &mut String, entry: &Entry, redactor: &mut Redactor) {
+    match entry {
+        Entry::User(text) => {
+            let _ = writeln!(out, "**User**\n\n{}\n", redactor.redact(text).trim_end());
+        }
+        Entry::Assistant(text) => {
+            let _ = writeln!(
+                out,
+                "**Assistant**\n\n{}\n",
+                redactor.redact(text).trim_end()
+            );
+        }
+        Entry::Reasoning(text) => {
+            let _ = writeln!(out, "_Reasoning_\n\n{}\n", quote(&redactor.redact(text)));
+        }
+        Entry::WebSearch(query) => {
+            let _ = writeln!(out, "_Searched the web: {}_\n", redactor.redact(query));
+        }
+        Entry::ToolCall {
+            name,
+            input,
+            output,
+            kind,
+        } => {
+            let input = redactor.redact(input);
+            match kind {
+                CallKind::Subagent => {
+                    let _ = writeln!(out, "**Subagent** (`{name}`)\n\n{}\n", quote(&input));
+                }
+                CallKind::Patch => {
+                    let _ = writeln!(out, "**Patch** (`{name}`)\n\n{}", fenced("diff", &input));
+                }
+                CallKind::Command => {
+                    let _ = writeln!(out, "**Command** (`{name}`)\n\n{}", fenced("sh", &input));
+                }
+                CallKind::Tool => {
+                    let _ = writeln!(out, "**Tool** (`{name}`)\n\n{}", fenced("", &input));
+                }
+            }
+            if let Some(output) = output {
+                let output = truncate_lines(&redactor.redact(output), MAX_OUTPUT_LINES);
+                let _ = writeln!(out, "Output:\n\n{}", fenced("text", &output));
+            }
+        }
+    }
+}
+
+/// A fenced code block whose fence is longer than any backtick run in `text`.
+fn fenced(language: &str, text: &str) -> String {
+    let longest_run = text
+        .split(|c: char| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!(
+        "{fence}{language}\n{}\n{fence}\n",
+        text.trim_end_matches('\n')
+    )
+}
+
+fn quote(text: &str) -> String {
+    text.trim_end()
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Serialize)]
+struct JsonTranscript {
+    session: Option<JsonSession>,
+    title: String,
+    turns: Vec<JsonTurn>,
+}
+
+#[derive(Serialize)]
+struct JsonSession {
+    id: String,
+    timestamp: String,
+    cwd: String,
+}
+
+#[derive(Serialize)]
+struct JsonTurn {
+    turn: usize,
+    entries: Vec<JsonEntry>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonEntry {
+    User {
+        text: String,
+    },
+    Assistant {
+        text: String,
+    },
+    Reasoning {
+        text: String,
+    },
+    WebSearch {
+        query: String,
+    },
+    ToolCall {
+        name: String,
+        kind: &'static str,
+        input: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+}
+
+fn render_json(meta: Option<&SessionMeta>, turns: &[Turn], redactor: &mut Redactor) -> String {
+    let transcript = JsonTranscript {
+        session: meta.map(|meta| JsonSession {
+            id: meta.id.to_string(),
+            timestamp: meta.timestamp.clone(),
+            cwd: redactor.redact(&meta.cwd.display().to_string()),
+        }),
+        title: redactor.redact(&default_title(turns)),
+        turns: turns
+            .iter()
+            .enumerate()
+            .map(|(index, turn)| JsonTurn {
+                turn: index + 1,
+                entries: turn
+                    .entries
+                    .iter()
+                    .map(|entry| json_entry(entry, redactor))
+                    .collect(),
+            })
+            .collect(),
+    };
+    let mut json = serde_json::to_string_pretty(&transcript).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+fn json_entry(entry: &Entry, redactor: &mut Redactor) -> JsonEntry {
+    match entry {
+        Entry::User(text) => JsonEntry::User {
+            text: redactor.redact(text),
+        },
+        Entry::Assistant(text) => JsonEntry::Assistant {
+            text: redactor.redact(text),
+        },
+        Entry::Reasoning(text) => JsonEntry::Reasoning {
+            text: redactor.redact(text),
+        },
+        Entry::WebSearch(query) => JsonEntry::WebSearch {
+            query: redactor.redact(query),
+        },
+        Entry::ToolCall {
+            name,
+            input,
+            output,
+            kind,
+        } => JsonEntry::ToolCall {
+            name: name.clone(),
+            kind: match kind {
+                CallKind::Tool => "tool",
+                CallKind::Command => "command",
+                CallKind::Patch => "patch",
+                CallKind::Subagent => "subagent",
+            },
+            input: redactor.redact(input),
+            output: output.as_deref().map(|output| redactor.redact(output)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::models::ResponseItem;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn items() -> Vec<RolloutItem> {
+        vec![
+            RolloutItem::ResponseItem(ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: "Investigate the outage".to_string(),
+                }],
+                end_turn: None,
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "spawn_agent".to_string(),
+                arguments: r#"{"message":"Read the logs","agent_type":"explorer"}"#.to_string(),
+                call_id: "call_1".to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                call_id: "call_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "agent-1".to_string(),
+                    ..Default::default()
+                },
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":"cat ```notes```"}"#.to_string(),
+                call_id: "call_2".to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: "The API_KEY=abcdef123456 was rotated.".to_string(),
+                }],
+                end_turn: None,
+            }),
+        ]
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!(
+            "md".parse::<ExportFormat>().ok(),
+            Some(ExportFormat::Markdown)
+        );
+        assert_eq!(
+            "HTML".parse::<ExportFormat>().ok(),
+            Some(ExportFormat::Html)
+        );
+        assert_eq!(
+            "pdf"
+                .parse::<ExportFormat>()
+                .map_err(|err| err.to_string())
+                .err(),
+            Some("unknown export format `pdf`; use md, html or json".to_string())
+        );
+    }
+
+    #[test]
+    fn exports_markdown_with_subagents_and_safe_fences() {
+        let exported = export_transcript(&items(), ExportFormat::Markdown).expect("export");
+
+        assert_eq!(exported.total_turns, 1);
+        assert_eq!(exported.redactions, 1);
+        assert_eq!(
+            exported.contents,
+            "# Investigate the outage\n\n\
+             ## Turn 1\n\n\
+             **User**\n\nInvestigate the outage\n\n\
+             **Subagent** (`spawn_agent`)\n\n> [explorer] Read the logs\n\n\
+             Output:\n\n```text\nagent-1\n```\n\n\
+             **Command** (`shell`)\n\n````sh\ncat ```notes```\n````\n\n\
+             **Assistant**\n\nThe API_KEY=[REDACTED] was rotated.\n\n\
+             ---\n\n_1 value(s) that looked like secrets were redacted._\n"
+        );
+    }
+
+    #[test]
+    fn exports_json_entries() {
+        let exported = export_transcript(&items(), ExportFormat::Json).expect("export");
+        let value: serde_json::Value = serde_json::from_str(&exported.contents).expect("json");
+
+        assert_eq!(
+            value["turns"][0]["entries"][1],
+            json!({
+                "type": "tool_call",
+                "name": "spawn_agent",
+                "kind": "subagent",
+                "input": "[explorer] Read the logs",
+                "output": "agent-1",
+            })
+        );
+        assert_eq!(value["session"], serde_json::Value::Null);
+    }
+}
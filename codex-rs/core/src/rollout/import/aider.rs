@@ -0,0 +1,204 @@
+//! aider chat histories (`.aider.chat.history.md`).
+//!
+//! Every session starts with a `# aider chat started at <time>` heading.
+//! User input is written with a `#### ` prefix on every line, tool output is
+//! quoted with `> `, and the assistant's replies are plain Markdown.
+
+use chrono::DateTime;
+use chrono::Local;
+use chrono::NaiveDateTime;
+use chrono::TimeZone;
+use chrono::Utc;
+
+use super::HistoryImportError;
+use super::ImportedConversation;
+use super::ImportedRole;
+use super::SessionConverter;
+use super::push_message;
+
+const ID: &str = "aider";
+const SESSION_HEADING: &str = "# aider chat started at ";
+const USER_PREFIX: &str = "#### ";
+
+pub struct AiderConverter;
+
+impl SessionConverter for AiderConverter {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn detect(&self, contents: &str) -> bool {
+        contents
+            .lines()
+            .any(|line| line.starts_with(SESSION_HEADING))
+    }
+
+    /// One conversation per session heading.
+    fn parse(&self, contents: &str) -> Result<Vec<ImportedConversation>, HistoryImportError> {
+        let mut conversations = Vec::new();
+        let mut session: Option<Session> = None;
+        for line in contents.lines() {
+            if let Some(started_at) = line.strip_prefix(SESSION_HEADING) {
+                if let Some(session) = session.take() {
+                    conversations.push(session.finish());
+                }
+                session = Some(Session::new(started_at));
+                continue;
+            }
+            let Some(session) = session.as_mut() else {
+                continue;
+            };
+            if let Some(input) = line
+                .strip_prefix(USER_PREFIX)
+                .or_else(|| (line.trim_end() == USER_PREFIX.trim_end()).then_some(""))
+            {
+                session.push_line(Some(ImportedRole::User), input);
+            } else if line.starts_with('>') {
+                // Tool output (file edits, command results, warnings).
+                session.push_line(None, "");
+            } else {
+                session.push_line(Some(ImportedRole::Assistant), line);
+            }
+        }
+        if let Some(session) = session {
+            conversations.push(session.finish());
+        }
+        Ok(conversations)
+    }
+}
+
+struct Session {
+    conversation: ImportedConversation,
+    started_at: Option<DateTime<Utc>>,
+    /// Role and lines of the block being read; `None` while
+    /// skipping tool output.
+    block: Option<(ImportedRole, Vec<String>)>,
+}
+
+impl Session {
+    fn new(started_at: &str) -> Self {
+        let started_at = NaiveDateTime::parse_from_str(started_at.trim(), "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .and_then(|time| Local.from_local_datetime(&time).earliest())
+            .map(|time| time.with_timezone(&Utc));
+        Self {
+            conversation: ImportedConversation {
+                source: ID,
+                title: None,
+                cwd: None,
+                messages: Vec::new(),
+            },
+            started_at,
+            block: None,
+        }
+    }
+
+    fn push_line(&mut self, role: Option<ImportedRole>, line: &str) {
+        if let (Some((current, lines)), Some(role)) = (&mut self.block, role)
+            && *current == role
+        {
+            lines.push(line.to_string());
+            return;
+        }
+        self.flush();
+        self.block = role.map(|role| (role, vec![line.to_string()]));
+    }
+
+    fn flush(&mut self) {
+        let Some((role, lines)) = self.block.take() else {
+            return;
+        };
+        let text = lines.join("\n").trim().to_string();
+        // `/add`, `/run` and other aider commands are not prompts.
+        if text.is_empty() || (role == ImportedRole::User && text.starts_with('/')) {
+            return;
+        }
+        let timestamp = if self.conversation.messages.is_empty() {
+            self.started_at
+        } else {
+            None
+        };
+        push_message(&mut self.conversation.messages, role, text, timestamp);
+    }
+
+    fn finish(mut self) -> ImportedConversation {
+        self.flush();
+        self.conversation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rollout::import::ImportedMessage;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_sessions_and_skips_commands_and_tool_output() {
+        let history = "
+# aider chat started at 2024-05-01 10:00:00
+
+> Add .aider* to .gitignore (recommended)? (Y)es/(N)o [Yes]: y
+
+#### /add src/app.py
+
+> Added src/app.py to the chat
+
+#### Rename `run` to `main`.
+#### Keep the CLI flags.
+
+I'll rename the function.
+
+src/app.py
+```python
+def main():
+    pass
+```
+
+> Applied edit to src/app.py
+
+# aider chat started at 2024-05-02 09:30:00
+
+#### What does this repo do?
+
+It is a CLI.
+";
+
+        assert!(AiderConverter.detect(history));
+        let conversations = AiderConverter.parse(history).expect("parse");
+        assert_eq!(conversations.len(), 2);
+        assert_eq!(
+            conversations[0]
+                .messages
+                .iter()
+                .map(|message| (message.role, message.text.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    ImportedRole::User,
+                    "Rename `run` to `main`.\nKeep the CLI flags."
+                ),
+                (
+                    ImportedRole::Assistant,
+                    "I'll rename the function.\n\nsrc/app.py\n```python\ndef main():\n    pass\n```"
+                ),
+            ]
+        );
+        assert!(conversations[0].messages[0].timestamp.is_some());
+        assert_eq!(
+            conversations[1].messages,
+            vec![
+                ImportedMessage {
+                    role: ImportedRole::User,
+                    text: "What does this repo do?".to_string(),
+                    timestamp: conversations[1].messages[0].timestamp,
+                },
+                ImportedMessage {
+                    role: ImportedRole::Assistant,
+                    text: "It is a CLI.".to_string(),
+                    timestamp: None,
+                },
+            ]
+        );
+    }
+}
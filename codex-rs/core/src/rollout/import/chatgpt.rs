@@ -0,0 +1,195 @@
+//! ChatGPT data exports: `conversations.json`, or a single conversation
+//! object from it.
+
+use chrono::DateTime;
+use serde_json::Value;
+
+use super::HistoryImportError;
+use super::ImportedConversation;
+use super::ImportedMessage;
+use super::ImportedRole;
+use super::SessionConverter;
+use super::push_message;
+
+const ID: &str = "chatgpt";
+
+pub struct ChatGptConverter;
+
+impl SessionConverter for ChatGptConverter {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    /// A JSON array, or an object with a `mapping`.
+    fn detect(&self, contents: &str) -> bool {
+        match serde_json::from_str::<Value>(contents) {
+            Ok(Value::Array(_)) => true,
+            Ok(Value::Object(object)) => object.contains_key("mapping"),
+            _ => false,
+        }
+    }
+
+    fn parse(&self, contents: &str) -> Result<Vec<ImportedConversation>, HistoryImportError> {
+        parse_conversations(contents)
+    }
+}
+
+fn parse_conversations(contents: &str) -> Result<Vec<ImportedConversation>, HistoryImportError> {
+    let invalid = |message: String| HistoryImportError::Invalid { tool: ID, message };
+    let value: Value = serde_json::from_str(contents).map_err(|err| invalid(err.to_string()))?;
+    let conversations = match value {
+        Value::Array(conversations) => conversations,
+        object @ Value::Object(_) => vec![object],
+        _ => return Err(invalid("expected a conversation or a list of them".into())),
+    };
+
+    conversations
+        .iter()
+        .map(|conversation| {
+            let mapping = conversation
+                .get("mapping")
+                .and_then(Value::as_object)
+                .ok_or_else(|| invalid("conversation without `mapping`".into()))?;
+            Ok(ImportedConversation {
+                source: ID,
+                title: conversation
+                    .get("title")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                cwd: None,
+                messages: chatgpt_branch_messages(conversation, mapping),
+            })
+        })
+        .collect()
+}
+
+/// Walks from `current_node` up to the root so only the branch the user last
+/// saw is imported (edited prompts and regenerations create sibling nodes).
+fn chatgpt_branch_messages(
+    conversation: &Value,
+    mapping: &serde_json::Map<String, Value>,
+) -> Vec<ImportedMessage> {
+    let mut node_id = conversation
+        .get("current_node")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let mut branch = Vec::new();
+    while let Some(id) = node_id.take() {
+        let Some(node) = mapping.get(&id) else {
+            break;
+        };
+        if let Some(message) = node.get("message") {
+            branch.push(message);
+        }
+        node_id = node
+            .get("parent")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        if branch.len() > mapping.len() {
+            break;
+        }
+    }
+
+    let mut messages = Vec::new();
+    for message in branch.into_iter().rev() {
+        let role = match message.pointer("/author/role").and_then(Value::as_str) {
+            Some("user") => ImportedRole::User,
+            Some("assistant") => ImportedRole::Assistant,
+            _ => continue,
+        };
+        if message
+            .pointer("/metadata/is_visually_hidden_from_conversation")
+            .and_then(Value::as_bool)
+            == Some(true)
+        {
+            continue;
+        }
+        let Some(text) = chatgpt_content_text(message.get("content")) else {
+            continue;
+        };
+        let timestamp = message
+            .get("create_time")
+            .and_then(Value::as_f64)
+            .and_then(|secs| DateTime::from_timestamp_millis((secs * 1000.0) as i64));
+        push_message(&mut messages, role, text, timestamp);
+    }
+    messages
+}
+
+fn chatgpt_content_text(content: Option<&Value>) -> Option<String> {
+    let content = content?;
+    match content.get("content_type").and_then(Value::as_str) {
+        Some("text" | "multimodal_text") => {}
+        _ => return None,
+    }
+    let text = content
+        .get("parts")?
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rollout::import::parse_export;
+    use chrono::Utc;
+    use pretty_assertions::assert_eq;
+
+    fn utc(ts: &str) -> Option<DateTime<Utc>> {
+        Some(
+            DateTime::parse_from_rfc3339(ts)
+                .expect("valid timestamp")
+                .with_timezone(&Utc),
+        )
+    }
+
+    #[test]
+    fn parses_chatgpt_export_following_current_branch() {
+        let export = r#"[{
+            "title": "Regex help",
+            "current_node": "c",
+            "mapping": {
+                "root": {"id": "root", "message": null, "parent": null, "children": ["a"]},
+                "a": {"id": "a", "parent": "root", "children": ["b", "b2"], "message": {
+                    "author": {"role": "user"}, "create_time": 1717236000.0,
+                    "content": {"content_type": "text", "parts": ["Match an email?"]}}},
+                "b2": {"id": "b2", "parent": "a", "children": [], "message": {
+                    "author": {"role": "assistant"}, "create_time": 1717236001.0,
+                    "content": {"content_type": "text", "parts": ["Discarded regeneration"]}}},
+                "b": {"id": "b", "parent": "a", "children": ["c"], "message": {
+                    "author": {"role": "tool"}, "create_time": 1717236002.0,
+                    "content": {"content_type": "code", "text": "search()"}}},
+                "c": {"id": "c", "parent": "b", "children": [], "message": {
+                    "author": {"role": "assistant"}, "create_time": 1717236003.0,
+                    "content": {"content_type": "text", "parts": ["Use `\\S+@\\S+`."]}}}
+            }
+        }, {"title": "Empty", "current_node": null, "mapping": {}}]"#;
+
+        assert!(ChatGptConverter.detect(export));
+        assert_eq!(
+            parse_export(export, &ChatGptConverter).expect("parse"),
+            vec![ImportedConversation {
+                source: ID,
+                title: Some("Regex help".to_string()),
+                cwd: None,
+                messages: vec![
+                    ImportedMessage {
+                        role: ImportedRole::User,
+                        text: "Match an email?".to_string(),
+                        timestamp: utc("2024-06-01T10:00:00Z"),
+                    },
+                    ImportedMessage {
+                        role: ImportedRole::Assistant,
+                        text: "Use `\\S+@\\S+`.".to_string(),
+                        timestamp: utc("2024-06-01T10:00:03Z"),
+                    },
+                ],
+            }]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Claude Code session logs: JSON lines under
+//! `~/.claude/projects/<project>/<session>.jsonl`.
+
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Utc;
+use serde_json::Value;
+
+use super::HistoryImportError;
+use super::ImportedConversation;
+use super::ImportedRole;
+use super::SessionConverter;
+use super::push_message;
+
+const ID: &str = "claude-code";
+
+pub struct ClaudeCodeConverter;
+
+impl SessionConverter for ClaudeCodeConverter {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    /// JSON lines whose records carry a `type`.
+    fn detect(&self, contents: &str) -> bool {
+        let has_type = |text: &str| {
+            serde_json::from_str::<Value>(text)
+                .ok()
+                .is_some_and(|value| value.get("type").is_some())
+        };
+        has_type(contents)
+            || contents
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .is_some_and(has_type)
+    }
+
+    fn parse(&self, contents: &str) -> Result<Vec<ImportedConversation>, HistoryImportError> {
+        Ok(vec![parse_session(contents)?])
+    }
+}
+
+fn parse_session(contents: &str) -> Result<ImportedConversation, HistoryImportError> {
+    let mut conversation = ImportedConversation {
+        source: ID,
+        title: None,
+        cwd: None,
+        messages: Vec::new(),
+    };
+
+    for (idx, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Value =
+            serde_json::from_str(line).map_err(|err| HistoryImportError::Invalid {
+                tool: ID,
+                message: format!("line {}: {err}", idx + 1),
+            })?;
+
+        if conversation.cwd.is_none()
+            && let Some(cwd) = record.get("cwd").and_then(Value::as_str)
+        {
+            conversation.cwd = Some(PathBuf::from(cwd));
+        }
+        let role = match record.get("type").and_then(Value::as_str) {
+            Some("user") => ImportedRole::User,
+            Some("assistant") => ImportedRole::Assistant,
+            Some("summary") => {
+                if conversation.title.is_none() {
+                    conversation.title = record
+                        .get("summary")
+                        .and_then(Value::as_str)
+                        .map(str::to_string);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        // Sidechains are Claude Code's own subagent transcripts; meta records
+        // are injected caveats. Neither was part of the visible conversation.
+        if is_true(&record, "isSidechain") || is_true(&record, "isMeta") {
+            continue;
+        }
+        let Some(text) = record
+            .get("message")
+            .and_then(|message| message.get("content"))
+            .and_then(claude_content_text)
+        else {
+            continue;
+        };
+        let timestamp = record
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.with_timezone(&Utc));
+        push_message(&mut conversation.messages, role, text, timestamp);
+    }
+
+    Ok(conversation)
+}
+
+/// Text of a Claude message `content`, which is either a string or a list of
+/// blocks. Only `text` blocks are kept.
+fn claude_content_text(content: &Value) -> Option<String> {
+    let text = match content {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter(|block| block.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|block| block.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        _ => return None,
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn is_true(record: &Value, key: &str) -> bool {
+    record.get(key).and_then(Value::as_bool) == Some(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rollout::import::ImportedMessage;
+    use pretty_assertions::assert_eq;
+
+    fn utc(ts: &str) -> Option<DateTime<Utc>> {
+        Some(
+            DateTime::parse_from_rfc3339(ts)
+                .expect("valid timestamp")
+                .with_timezone(&Utc),
+        )
+    }
+
+    #[test]
+    fn parses_claude_code_session_log() {
+        let log = [
+            r#"{"type":"summary","summary":"Fix flaky test"}"#,
+            r#"{"type":"user","cwd":"/work/app","timestamp":"2025-06-01T10:00:00Z","message":{"role":"user","content":"Why does this test flake?"}}"#,
+            r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"<caveat>"}}"#,
+            r#"{"type":"assistant","timestamp":"2025-06-01T10:00:05Z","message":{"role":"assistant","content":[{"type":"text","text":"It races the timer."},{"type":"tool_use","id":"t1","name":"Bash","input":{}}]}}"#,
+            r#"{"type":"user","timestamp":"2025-06-01T10:00:06Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"ok"}]}}"#,
+            r#"{"type":"assistant","timestamp":"2025-06-01T10:00:07Z","message":{"role":"assistant","content":[{"type":"text","text":"Use a fake clock."}]}}"#,
+            r#"{"type":"assistant","isSidechain":true,"message":{"role":"assistant","content":"subagent chatter"}}"#,
+        ]
+        .join("\n");
+
+        assert!(ClaudeCodeConverter.detect(&log));
+        assert_eq!(
+            ClaudeCodeConverter.parse(&log).expect("parse"),
+            vec![ImportedConversation {
+                source: ID,
+                title: Some("Fix flaky test".to_string()),
+                cwd: Some(PathBuf::from("/work/app")),
+                messages: vec![
+                    ImportedMessage {
+                        role: ImportedRole::User,
+                        text: "Why does this test flake?".to_string(),
+                        timestamp: utc("2025-06-01T10:00:00Z"),
+                    },
+                    ImportedMessage {
+                        role: ImportedRole::Assistant,
+                        text: "It races the timer.\n\nUse a fake clock.".to_string(),
+                        timestamp: utc("2025-06-01T10:00:05Z"),
+                    },
+                ],
+            }]
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! Conversion of session logs from other agent tools into rollout files, so
+//! history search, resume, and export work across tools after migrating.
+//!
+//! Each supported tool is a [`SessionConverter`] listed in [`CONVERTERS`]:
+//! - `claude-code`: Claude Code session logs
+//!   (`~/.claude/projects/<project>/<session>.jsonl`).
+//! - `chatgpt`: ChatGPT data exports (`conversations.json`, or a single
+//!   conversation object from it).
+//! - `aider`: aider chat histories (`.aider.chat.history.md`).
+//!
+//! Only user and assistant text is imported. Tool calls, tool results, and
+//! attachments recorded by the other tool cannot be replayed by Codex, so they
+//! are dropped.
+
+mod aider;
+mod chatgpt;
+mod claude_code;
+
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Utc;
+use codex_protocol::ThreadId;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::AgentMessageEvent;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionMeta;
+use codex_protocol::protocol::SessionMetaLine;
+use codex_protocol::protocol::SessionSource;
+use codex_protocol::protocol::UserMessageEvent;
+
+use super::SESSIONS_SUBDIR;
+use crate::config::Config;
+
+pub use aider::AiderConverter;
+pub use chatgpt::ChatGptConverter;
+pub use claude_code::ClaudeCodeConverter;
+
+/// Reads the session logs of one tool. Supporting another tool means
+/// implementing this trait and adding the converter to [`CONVERTERS`].
+pub trait SessionConverter: Send + Sync {
+    /// Name that selects the converter (`codex import --from <id>`).
+    fn id(&self) -> &'static str;
+
+    /// Whether `contents` looks like a log of this tool.
+    fn detect(&self, contents: &str) -> bool;
+
+    /// The conversations in `contents`; a log may hold several.
+    fn parse(&self, contents: &str) -> Result<Vec<ImportedConversation>, HistoryImportError>;
+}
+
+/// The built-in converters, in the order detection tries them.
+pub static CONVERTERS: &[&dyn SessionConverter] =
+    &[&ChatGptConverter, &ClaudeCodeConverter, &AiderConverter];
+
+/// The converter registered as `id`.
+pub fn converter(id: &str) -> Result<&'static dyn SessionConverter, HistoryImportError> {
+    CONVERTERS
+        .iter()
+        .copied()
+        .find(|converter| converter.id() == id)
+        .ok_or_else(|| HistoryImportError::UnknownTool {
+            tool: id.to_string(),
+            supported: CONVERTERS
+                .iter()
+                .map(|converter| converter.id())
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+}
+
+/// The first converter that recognizes `contents`.
+pub fn detect_converter(contents: &str) -> Option<&'static dyn SessionConverter> {
+    CONVERTERS
+        .iter()
+        .copied()
+        .find(|converter| converter.detect(contents))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HistoryImportError {
+    #[error("could not detect which tool wrote the session log; pass --from")]
+    UnknownFormat,
+    #[error("unknown tool `{tool}`; supported: {supported}")]
+    UnknownTool { tool: String, supported: String },
+    #[error("invalid {tool} session log: {message}")]
+    Invalid { tool: &'static str, message: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportedRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedMessage {
+    pub role: ImportedRole,
+    pub text: String,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedConversation {
+    /// Id of the converter that read the conversation.
+    pub source: &'static str,
+    pub title: Option<String>,
+    /// Working directory recorded by the source tool, if any.
+    pub cwd: Option<PathBuf>,
+    pub messages: Vec<ImportedMessage>,
+}
+
+/// Parses `contents` with `converter`. Conversations without any user or
+/// assistant text are omitted.
+pub fn parse_export(
+    contents: &str,
+    converter: &dyn SessionConverter,
+) -> Result<Vec<ImportedConversation>, HistoryImportError> {
+    Ok(converter
+        .parse(contents)?
+        .into_iter()
+        .filter(|conversation| !conversation.messages.is_empty())
+        .collect())
+}
+
+/// Appends a message, merging it into the previous one when the role repeats
+/// (both tools split a single reply across several records).
+fn push_message(
+    messages: &mut Vec<ImportedMessage>,
+    role: ImportedRole,
+    text: String,
+    timestamp: Option<DateTime<Utc>>,
+) {
+    if let Some(last) = messages.last_mut()
+        && last.role == role
+    {
+        last.text.push_str("\n\n");
+        last.text.push_str(&text);
+        return;
+    }
+    messages.push(ImportedMessage {
+        role,
+        text,
+        timestamp,
+    });
+}
+
+/// Writes `conversation` as a new rollout under `$CODEX_HOME/sessions`, dated
+/// by its first message so it sorts among existing sessions. The result can be
+/// resumed like any other session. Returns the new thread id and file path.
+pub fn write_imported_rollout(
+    config: &Config,
+    conversation: &ImportedConversation,
+) -> io::Result<(ThreadId, PathBuf)> {
+    let thread_id = ThreadId::new();
+    let started_at = conversation
+        .messages
+        .first()
+        .and_then(|message| message.timestamp)
+        .unwrap_or_else(Utc::now);
+    let (mut file, path) = create_rollout_file(&config.codex_home, thread_id, started_at)?;
+
+    let cwd = conversation
+        .cwd
+        .clone()
+        .unwrap_or_else(|| config.cwd.clone());
+    let mut lines = vec![RolloutLine {
+        timestamp: format_timestamp(started_at),
+        item: RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta {
+                id: thread_id,
+                timestamp: format_timestamp(started_at),
+                cwd,
+                originator: format!("history_import:{}", conversation.source),
+                cli_version: env!("CARGO_PKG_VERSION").to_string(),
+                source: SessionSource::Cli,
+                model_provider: Some(config.model_provider_id.clone()),
+                ..Default::default()
+            },
+            git: None,
+        }),
+    }];
+    for message in &conversation.messages {
+        let timestamp = format_timestamp(message.timestamp.unwrap_or(started_at));
+        let (response_item, event) = match message.role {
+            ImportedRole::User => (
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: message.text.clone(),
+                    }],
+                    end_turn: None,
+                },
+                EventMsg::UserMessage(UserMessageEvent {
+                    message: message.text.clone(),
+                    images: None,
+                    local_images: Vec::new(),
+                    text_elements: Vec::new(),
+                }),
+            ),
+            ImportedRole::Assistant => (
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: message.text.clone(),
+                    }],
+                    end_turn: None,
+                },
+                EventMsg::AgentMessage(AgentMessageEvent {
+                    message: message.text.clone(),
+                }),
+            ),
+        };
+        lines.push(RolloutLine {
+            timestamp: timestamp.clone(),
+            item: RolloutItem::ResponseItem(response_item),
+        });
+        lines.push(RolloutLine {
+            timestamp,
+            item: RolloutItem::EventMsg(event),
+        });
+    }
+
+    for line in &lines {
+        let mut json = serde_json::to_string(line)?;
+        json.push('\n');
+        file.write_all(json.as_bytes())?;
+    }
+    file.flush()?;
+    Ok((thread_id, path))
+}
+
+/// Creates the rollout file of `thread_id` in the dated sessions directory
+/// that listings scan, named after `started_at` like a recorded thread.
+pub(crate) fn create_rollout_file(
+    codex_home: &Path,
+    thread_id: ThreadId,
+    started_at: DateTime<Utc>,
+) -> io::Result<(fs::File, PathBuf)> {
+    let local = started_at.with_timezone(&Local);
+    let dir = codex_home
+        .join(SESSIONS_SUBDIR)
+        .join(local.format("%Y").to_string())
+        .join(local.format("%m").to_string())
+        .join(local.format("%d").to_string());
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "rollout-{}-{thread_id}.jsonl",
+        local.format("%Y-%m-%dT%H-%M-%S")
+    ));
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    Ok((file, path))
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn converters_are_found_by_id_and_contents() {
+        assert_eq!(
+            converter("aider").map(SessionConverter::id).ok(),
+            Some("aider")
+        );
+        assert_eq!(
+            converter("cursor").map_err(|err| err.to_string()).err(),
+            Some("unknown tool `cursor`; supported: chatgpt, claude-code, aider".to_string())
+        );
+        assert_eq!(
+            detect_converter(r#"{"type":"user","message":{"content":"hi"}}"#)
+                .map(SessionConverter::id),
+            Some("claude-code")
+        );
+        assert_eq!(
+            detect_converter("[]").map(SessionConverter::id),
+            Some("chatgpt")
+        );
+    }
+
+    #[test]
+    fn rejects_unrecognized_input() {
+        assert!(detect_converter("just some text").is_none());
+        assert!(matches!(
+            parse_export("[1, 2]", &ChatGptConverter),
+            Err(HistoryImportError::Invalid { .. })
+        ));
+    }
+}
@@ -0,0 +1,857 @@
+//! SQLite index over the rollouts in `~/.codex/sessions` and
+//! `~/.codex/archived_sessions`.
+//!
+//! Rollout files stay the source of truth. The index keeps, for every file,
+//! what thread listing and history search need (the head records, session
+//! source and provider, the model of each turn, patched paths, and the
+//! searchable text) so that neither has to open every rollout. It is created
+//! by `codex sessions migrate`; from then on every listing or search first
+//! syncs it, re-reading only files whose size or modification time changed.
+//! Without an index, listing and search read the files as before.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::SessionSource;
+use rusqlite::Connection;
+use rusqlite::Transaction;
+use rusqlite::params;
+use time::OffsetDateTime;
+use tracing::warn;
+
+use super::ARCHIVED_SESSIONS_SUBDIR;
+use super::SESSIONS_SUBDIR;
+use super::archive::is_compressed_rollout;
+use super::labels::ThreadLabels;
+use super::list::Cursor;
+use super::list::ProviderMatcher;
+use super::list::ThreadItem;
+use super::list::ThreadSortKey;
+use super::list::ThreadsPage;
+use super::list::build_next_cursor;
+use super::list::format_rfc3339;
+use super::list::is_rollout_file_name;
+use super::list::parse_timestamp_uuid_from_filename;
+use super::list::summarize_head;
+use super::search::FilterFields;
+use super::search::RolloutText;
+use super::search::SearchFilters;
+use super::search::SearchQuery;
+use super::search::ThreadHit;
+use super::search::label_text;
+
+/// Name of the index database inside `CODEX_HOME`.
+pub const SESSION_INDEX_FILE: &str = "session_index.sqlite";
+
+/// Bumped whenever the tables change. The index only holds data derived from
+/// the rollouts, so an index with another version is rebuilt from scratch.
+const SCHEMA_VERSION: i64 = 1;
+
+/// Shortest term the trigram full-text index can look up; shorter terms
+/// fall back to a scan of the stored text.
+const MIN_FTS_TERM_CHARS: usize = 3;
+
+/// How long to wait for another process that is syncing the same index.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SCHEMA: &str = r#"
+DROP TABLE IF EXISTS documents_fts;
+DROP TABLE IF EXISTS documents;
+DROP TABLE IF EXISTS thread_files;
+DROP TABLE IF EXISTS turns;
+DROP TABLE IF EXISTS threads;
+
+CREATE TABLE threads (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL UNIQUE,
+    archived INTEGER NOT NULL,
+    file_size INTEGER NOT NULL,
+    -- Seconds since the epoch, 0 when the file system does not report it.
+    file_mtime INTEGER NOT NULL,
+    -- From the file name: what listings sort and page by.
+    file_ts INTEGER NOT NULL,
+    file_uuid TEXT NOT NULL,
+    -- Whether the head has the session meta and a user message.
+    listable INTEGER NOT NULL,
+    source TEXT,
+    model_provider TEXT,
+    head TEXT NOT NULL,
+    created_at TEXT,
+    -- From the session meta.
+    thread_id TEXT,
+    started_at TEXT,
+    cwd TEXT,
+    repository_url TEXT
+);
+CREATE INDEX threads_by_created ON threads (archived, file_ts DESC, file_uuid DESC);
+CREATE INDEX threads_by_updated ON threads (archived, file_mtime DESC, file_uuid DESC);
+CREATE INDEX threads_by_thread_id ON threads (thread_id);
+
+CREATE TABLE turns (
+    thread INTEGER NOT NULL REFERENCES threads (id) ON DELETE CASCADE,
+    seq INTEGER NOT NULL,
+    model TEXT NOT NULL,
+    PRIMARY KEY (thread, seq)
+);
+
+CREATE TABLE thread_files (
+    thread INTEGER NOT NULL REFERENCES threads (id) ON DELETE CASCADE,
+    path TEXT NOT NULL,
+    PRIMARY KEY (thread, path)
+);
+
+CREATE TABLE documents (
+    id INTEGER PRIMARY KEY,
+    thread INTEGER NOT NULL REFERENCES threads (id) ON DELETE CASCADE,
+    seq INTEGER NOT NULL,
+    text TEXT NOT NULL
+);
+CREATE INDEX documents_by_thread ON documents (thread, seq);
+
+CREATE VIRTUAL TABLE documents_fts USING fts5 (
+    text,
+    content = 'documents',
+    content_rowid = 'id',
+    tokenize = 'trigram'
+);
+CREATE TRIGGER documents_insert AFTER INSERT ON documents BEGIN
+    INSERT INTO documents_fts (rowid, text) VALUES (new.id, new.text);
+END;
+CREATE TRIGGER documents_delete AFTER DELETE ON documents BEGIN
+    INSERT INTO documents_fts (documents_fts, rowid, text) VALUES ('delete', old.id, old.text);
+END;
+"#;
+
+/// An open session index.
+pub struct SessionIndex {
+    conn: Connection,
+    codex_home: PathBuf,
+}
+
+/// What [`SessionIndex::sync`] did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncStats {
+    /// Rollouts that were new or changed and were (re-)read.
+    pub indexed: usize,
+    /// Rollouts that no longer exist.
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// A rollout file found on disk.
+struct RolloutFile {
+    path: PathBuf,
+    archived: bool,
+    ts: OffsetDateTime,
+    uuid: String,
+    size: i64,
+    mtime: i64,
+}
+
+impl SessionIndex {
+    /// Opens the index in `codex_home`, or returns `None` when
+    /// `codex sessions migrate` has not created one.
+    pub fn open(codex_home: &Path) -> io::Result<Option<Self>> {
+        let path = codex_home.join(SESSION_INDEX_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::connect(codex_home, &path).map(Some)
+    }
+
+    /// Opens the index in `codex_home`, creating an empty one if needed.
+    pub fn create(codex_home: &Path) -> io::Result<Self> {
+        fs::create_dir_all(codex_home)?;
+        Self::connect(codex_home, &codex_home.join(SESSION_INDEX_FILE))
+    }
+
+    /// Deletes the index in `codex_home` and creates an empty one.
+    pub fn rebuild(codex_home: &Path) -> io::Result<Self> {
+        for suffix in ["", "-wal", "-shm"] {
+            let path = codex_home.join(format!("{SESSION_INDEX_FILE}{suffix}"));
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Self::create(codex_home)
+    }
+
+    fn connect(codex_home: &Path, path: &Path) -> io::Result<Self> {
+        let conn = Connection::open(path).map_err(io::Error::other)?;
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(io::Error::other)?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))
+            .map_err(io::Error::other)?;
+        conn.pragma_update(None, "foreign_keys", true)
+            .map_err(io::Error::other)?;
+        let version: i64 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(io::Error::other)?;
+        if version != SCHEMA_VERSION {
+            conn.execute_batch(SCHEMA).map_err(io::Error::other)?;
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+                .map_err(io::Error::other)?;
+        }
+        Ok(Self {
+            conn,
+            codex_home: codex_home.to_path_buf(),
+        })
+    }
+
+    /// Brings the index up to date with the rollout files on disk.
+    pub fn sync(&mut self) -> io::Result<SyncStats> {
+        let mut files = Vec::new();
+        collect_rollout_files(&self.codex_home.join(SESSIONS_SUBDIR), false, &mut files)?;
+        collect_rollout_files(
+            &self.codex_home.join(ARCHIVED_SESSIONS_SUBDIR),
+            true,
+            &mut files,
+        )?;
+
+        let tx = self.conn.transaction().map_err(io::Error::other)?;
+        let mut known = indexed_files(&tx).map_err(io::Error::other)?;
+        let mut stats = SyncStats::default();
+        for file in files {
+            let key = file.path.to_string_lossy().into_owned();
+            if known.remove(&key) == Some((file.size, file.mtime)) {
+                stats.unchanged += 1;
+                continue;
+            }
+            let text = match read_rollout(&file.path) {
+                Ok(text) => text,
+                Err(err) => {
+                    warn!("failed to index {}: {err}", file.path.display());
+                    continue;
+                }
+            };
+            index_rollout(&tx, &file, &key, &text).map_err(io::Error::other)?;
+            stats.indexed += 1;
+        }
+        for path in known.keys() {
+            tx.execute("DELETE FROM threads WHERE path = ?1", [path])
+                .map_err(io::Error::other)?;
+            stats.removed += 1;
+        }
+        tx.commit().map_err(io::Error::other)?;
+        Ok(stats)
+    }
+
+    /// Lists the threads in `~/.codex/sessions` like the file scan in
+    /// [`super::list`] does, with the same ordering and cursors.
+    pub(super) fn list_threads(
+        &self,
+        page_size: usize,
+        cursor: Option<&Cursor>,
+        sort_key: ThreadSortKey,
+        allowed_sources: &[SessionSource],
+        provider_matcher: Option<&ProviderMatcher<'_>>,
+    ) -> io::Result<ThreadsPage> {
+        let sort_column = match sort_key {
+            ThreadSortKey::CreatedAt => "file_ts",
+            ThreadSortKey::UpdatedAt => "file_mtime",
+        };
+        let sql = format!(
+            "SELECT path, head, created_at, file_mtime, source, model_provider FROM threads \
+             WHERE archived = 0 AND listable = 1 \
+             AND (?1 IS NULL OR {sort_column} < ?1 OR ({sort_column} = ?1 AND file_uuid < ?2)) \
+             ORDER BY {sort_column} DESC, file_uuid DESC"
+        );
+        let (anchor_ts, anchor_uuid) = match cursor {
+            Some(cursor) => (
+                Some(cursor.ts.unix_timestamp()),
+                Some(cursor.id.to_string()),
+            ),
+            None => (None, None),
+        };
+
+        let mut statement = self.conn.prepare(&sql).map_err(io::Error::other)?;
+        let mut rows = statement
+            .query(params![anchor_ts, anchor_uuid])
+            .map_err(io::Error::other)?;
+        let mut items = Vec::with_capacity(page_size);
+        let mut scanned = 0usize;
+        let mut more_matches_available = false;
+        while let Some(row) = rows.next().map_err(io::Error::other)? {
+            scanned += 1;
+            if items.len() == page_size {
+                more_matches_available = true;
+                break;
+            }
+            let source: Option<String> = row.get(4).map_err(io::Error::other)?;
+            let source = source.and_then(|source| serde_json::from_str(&source).ok());
+            if !allowed_sources.is_empty()
+                && !source.is_some_and(|source| allowed_sources.contains(&source))
+            {
+                continue;
+            }
+            let model_provider: Option<String> = row.get(5).map_err(io::Error::other)?;
+            if let Some(matcher) = provider_matcher
+                && !matcher.matches(model_provider.as_deref())
+            {
+                continue;
+            }
+            let path: String = row.get(0).map_err(io::Error::other)?;
+            let head: String = row.get(1).map_err(io::Error::other)?;
+            let created_at: Option<String> = row.get(2).map_err(io::Error::other)?;
+            let mtime: i64 = row.get(3).map_err(io::Error::other)?;
+            let updated_at = known_mtime(mtime)
+                .and_then(format_rfc3339)
+                .or_else(|| created_at.clone());
+            items.push(ThreadItem {
+                path: PathBuf::from(path),
+                head: serde_json::from_str(&head).unwrap_or_default(),
+                created_at,
+                updated_at,
+            });
+        }
+
+        let next_cursor = if more_matches_available {
+            build_next_cursor(&items, sort_key)
+        } else {
+            None
+        };
+        Ok(ThreadsPage {
+            items,
+            next_cursor,
+            num_scanned_files: scanned,
+            reached_scan_cap: false,
+        })
+    }
+
+    /// Same as running [`SearchQuery::search_thread`] on the threads in
+    /// `~/.codex/sessions` from `allowed_sources`, most recently updated
+    /// first, stopping after `limit` hits.
+    pub fn search(
+        &self,
+        query: &SearchQuery,
+        labels: &BTreeMap<String, ThreadLabels>,
+        filters: &SearchFilters,
+        allowed_sources: &[SessionSource],
+        limit: usize,
+    ) -> io::Result<Vec<ThreadHit>> {
+        let candidates = self.candidates(query, labels).map_err(io::Error::other)?;
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT id, source, thread_id, started_at, cwd, repository_url FROM threads \
+                 WHERE archived = 0 AND listable = 1 AND thread_id IS NOT NULL \
+                 ORDER BY file_mtime DESC, file_uuid DESC",
+            )
+            .map_err(io::Error::other)?;
+        let mut rows = statement.query([]).map_err(io::Error::other)?;
+        let mut hits = Vec::new();
+        while let Some(row) = rows.next().map_err(io::Error::other)? {
+            if hits.len() >= limit {
+                break;
+            }
+            let thread: i64 = row.get(0).map_err(io::Error::other)?;
+            if candidates
+                .as_ref()
+                .is_some_and(|candidates| !candidates.contains(&thread))
+            {
+                continue;
+            }
+            let source: Option<String> = row.get(1).map_err(io::Error::other)?;
+            let source = source.and_then(|source| serde_json::from_str(&source).ok());
+            if !allowed_sources.is_empty()
+                && !source.is_some_and(|source| allowed_sources.contains(&source))
+            {
+                continue;
+            }
+            let thread_id: String = row.get(2).map_err(io::Error::other)?;
+            let started_at: Option<String> = row.get(3).map_err(io::Error::other)?;
+            let cwd: Option<String> = row.get(4).map_err(io::Error::other)?;
+            let repository_url: Option<String> = row.get(5).map_err(io::Error::other)?;
+            let started_at = started_at.unwrap_or_default();
+            let cwd = cwd.unwrap_or_default();
+
+            let models = self
+                .thread_strings(
+                    "SELECT model FROM turns WHERE thread = ?1 ORDER BY seq",
+                    thread,
+                )
+                .map_err(io::Error::other)?;
+            let files = self
+                .thread_strings("SELECT path FROM thread_files WHERE thread = ?1", thread)
+                .map_err(io::Error::other)?;
+            if !filters.matches_fields(&FilterFields {
+                started_at: &started_at,
+                cwd: &cwd,
+                repository_url: repository_url.as_deref(),
+                models: &models,
+                files: &files,
+            }) {
+                continue;
+            }
+
+            let thread_labels = labels.get(&thread_id);
+            let documents = self
+                .thread_strings(
+                    "SELECT text FROM documents WHERE thread = ?1 ORDER BY seq",
+                    thread,
+                )
+                .map_err(io::Error::other)?;
+            let Some(found) = query.search_messages(&documents, thread_labels) else {
+                continue;
+            };
+            let Ok(thread_id) = ThreadId::from_string(&thread_id) else {
+                continue;
+            };
+            hits.push(ThreadHit {
+                thread_id,
+                started_at,
+                cwd: PathBuf::from(cwd),
+                name: thread_labels.and_then(|labels| labels.name.clone()),
+                model: models.last().cloned(),
+                snippet: found.snippet,
+            });
+        }
+        Ok(hits)
+    }
+
+    /// Threads that may contain every term of `query`, narrowed with the
+    /// full-text index, or `None` when the query is empty. Candidates are
+    /// confirmed by [`SearchQuery::search_messages`].
+    fn candidates(
+        &self,
+        query: &SearchQuery,
+        labels: &BTreeMap<String, ThreadLabels>,
+    ) -> rusqlite::Result<Option<HashSet<i64>>> {
+        let mut candidates: Option<HashSet<i64>> = None;
+        for term in query.terms() {
+            let mut matching = if term.chars().count() >= MIN_FTS_TERM_CHARS {
+                self.threads_where(
+                    "SELECT documents.thread FROM documents_fts \
+                     JOIN documents ON documents.id = documents_fts.rowid \
+                     WHERE documents_fts MATCH ?1",
+                    &format!("\"{}\"", term.replace('"', "\"\"")),
+                )?
+            } else {
+                let escaped = term
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_");
+                self.threads_where(
+                    "SELECT thread FROM documents WHERE text LIKE ?1 ESCAPE '\\'",
+                    &format!("%{escaped}%"),
+                )?
+            };
+            // Names and tags are not part of the index.
+            for (thread_id, thread_labels) in labels {
+                if label_text(thread_labels).contains(term.as_str()) {
+                    matching.extend(
+                        self.threads_where(
+                            "SELECT id FROM threads WHERE thread_id = ?1",
+                            thread_id,
+                        )?,
+                    );
+                }
+            }
+            candidates = Some(match candidates {
+                Some(previous) => previous.intersection(&matching).copied().collect(),
+                None => matching,
+            });
+        }
+        Ok(candidates)
+    }
+
+    fn threads_where(&self, sql: &str, value: &str) -> rusqlite::Result<HashSet<i64>> {
+        let mut statement = self.conn.prepare_cached(sql)?;
+        let threads = statement.query_map([value], |row| row.get(0))?;
+        threads.collect()
+    }
+
+    fn thread_strings(&self, sql: &str, thread: i64) -> rusqlite::Result<Vec<String>> {
+        let mut statement = self.conn.prepare_cached(sql)?;
+        let values = statement.query_map([thread], |row| row.get(0))?;
+        values.collect()
+    }
+}
+
+/// Lists threads from the session index, syncing it first, or returns `None`
+/// when there is no index.
+pub(super) async fn list_indexed_threads(
+    codex_home: &Path,
+    page_size: usize,
+    cursor: Option<&Cursor>,
+    sort_key: ThreadSortKey,
+    allowed_sources: &[SessionSource],
+    model_providers: Option<&[String]>,
+    default_provider: &str,
+) -> io::Result<Option<ThreadsPage>> {
+    let codex_home = codex_home.to_path_buf();
+    let cursor = cursor.cloned();
+    let allowed_sources = allowed_sources.to_vec();
+    let model_providers = model_providers.map(<[String]>::to_vec);
+    let default_provider = default_provider.to_string();
+    tokio::task::spawn_blocking(move || {
+        let Some(mut index) = SessionIndex::open(&codex_home)? else {
+            return Ok(None);
+        };
+        index.sync()?;
+        let provider_matcher = model_providers
+            .as_deref()
+            .and_then(|filters| ProviderMatcher::new(filters, &default_provider));
+        index
+            .list_threads(
+                page_size,
+                cursor.as_ref(),
+                sort_key,
+                &allowed_sources,
+                provider_matcher.as_ref(),
+            )
+            .map(Some)
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Size and modification time of every indexed rollout, by path.
+fn indexed_files(tx: &Transaction<'_>) -> rusqlite::Result<HashMap<String, (i64, i64)>> {
+    let mut statement = tx.prepare("SELECT path, file_size, file_mtime FROM threads")?;
+    let files = statement.query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?;
+    files.collect()
+}
+
+/// Replaces whatever the index holds for `file` with what `text` contains.
+fn index_rollout(
+    tx: &Transaction<'_>,
+    file: &RolloutFile,
+    key: &str,
+    text: &str,
+) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM threads WHERE path = ?1", [key])?;
+
+    let summary = summarize_head(text);
+    let rollout = RolloutText::parse(text);
+    let meta_line = rollout.meta.as_ref();
+    let source = summary
+        .source
+        .as_ref()
+        .and_then(|source| serde_json::to_string(source).ok());
+    let head = serde_json::to_string(&summary.head)
+        .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?;
+    tx.execute(
+        "INSERT INTO threads (path, archived, file_size, file_mtime, file_ts, file_uuid, \
+         listable, source, model_provider, head, created_at, thread_id, started_at, cwd, \
+         repository_url) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        params![
+            key,
+            file.archived,
+            file.size,
+            file.mtime,
+            file.ts.unix_timestamp(),
+            file.uuid,
+            summary.saw_session_meta && summary.saw_user_event,
+            source,
+            summary.model_provider,
+            head,
+            summary.created_at,
+            meta_line.map(|line| line.meta.id.to_string()),
+            meta_line.map(|line| line.meta.timestamp.clone()),
+            meta_line.map(|line| line.meta.cwd.to_string_lossy().into_owned()),
+            meta_line
+                .and_then(|line| line.git.as_ref())
+                .and_then(|git| git.repository_url.clone()),
+        ],
+    )?;
+    let thread = tx.last_insert_rowid();
+
+    for (seq, model) in rollout.models.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO turns (thread, seq, model) VALUES (?1, ?2, ?3)",
+            params![thread, seq as i64, model],
+        )?;
+    }
+    for path in &rollout.files {
+        tx.execute(
+            "INSERT OR IGNORE INTO thread_files (thread, path) VALUES (?1, ?2)",
+            params![thread, path],
+        )?;
+    }
+    for (seq, document) in rollout.documents().iter().enumerate() {
+        tx.execute(
+            "INSERT INTO documents (thread, seq, text) VALUES (?1, ?2, ?3)",
+            params![thread, seq as i64, document],
+        )?;
+    }
+    Ok(())
+}
+
+/// Adds every rollout under `dir` (at any depth) to `files`.
+fn collect_rollout_files(
+    dir: &Path,
+    archived: bool,
+    files: &mut Vec<RolloutFile>,
+) -> io::Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_rollout_files(&entry.path(), archived, files)?;
+            continue;
+        }
+        if !file_type.is_file() {
+            continue;
+        }
+        let file_name = entry.file_name();
+        let Some(name) = file_name.to_str() else {
+            continue;
+        };
+        if !is_rollout_file_name(name) {
+            continue;
+        }
+        let Some((ts, uuid)) = parse_timestamp_uuid_from_filename(name) else {
+            continue;
+        };
+        let metadata = entry.metadata()?;
+        let mtime = metadata
+            .modified()
+            .map(|modified| OffsetDateTime::from(modified).unix_timestamp())
+            .unwrap_or(0);
+        files.push(RolloutFile {
+            path: entry.path(),
+            archived,
+            ts,
+            uuid: uuid.to_string(),
+            size: i64::try_from(metadata.len()).unwrap_or(i64::MAX),
+            mtime,
+        });
+    }
+    Ok(())
+}
+
+/// Reads a whole rollout, decompressing archived ones.
+fn read_rollout(path: &Path) -> io::Result<String> {
+    if !is_compressed_rollout(path) {
+        return fs::read_to_string(path);
+    }
+    let bytes = zstd::stream::decode_all(File::open(path)?)?;
+    String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn known_mtime(mtime: i64) -> Option<OffsetDateTime> {
+    if mtime == 0 {
+        return None;
+    }
+    OffsetDateTime::from_unix_timestamp(mtime).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const THREAD_ID: &str = "0198c4f6-3c2b-7d10-9a77-3f1c2b0e5d41";
+    const PATCH: &str =
+        "*** Begin Patch\n*** Update File: migrations/0042_invoices.sql\n@@\n-a\n+b\n*** End Patch";
+
+    fn write_rollout(codex_home: &Path, id: &str, message: &str) -> PathBuf {
+        let dir = codex_home.join(SESSIONS_SUBDIR).join("2025/06/01");
+        fs::create_dir_all(&dir).expect("create sessions dir");
+        let path = dir.join(format!("rollout-2025-06-01T10-00-00-{id}.jsonl"));
+        let lines = [
+            json!({
+                "timestamp": "2025-06-01T10:00:00.000Z",
+                "type": "session_meta",
+                "payload": {
+                    "id": id,
+                    "timestamp": "2025-06-01T10:00:00.000Z",
+                    "cwd": "/home/alice/billing",
+                    "originator": "codex_cli_rs",
+                    "cli_version": "0.0.0",
+                    "source": "cli",
+                },
+            }),
+            json!({
+                "timestamp": "2025-06-01T10:00:01.000Z",
+                "type": "turn_context",
+                "payload": {
+                    "cwd": "/home/alice/billing",
+                    "approval_policy": "on-request",
+                    "sandbox_policy": { "type": "read-only" },
+                    "model": "gpt-5.1-codex",
+                    "summary": "auto",
+                },
+            }),
+            json!({
+                "timestamp": "2025-06-01T10:00:02.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "message",
+                    "role": "user",
+                    "content": [{ "type": "input_text", "text": message }],
+                },
+            }),
+            json!({
+                "timestamp": "2025-06-01T10:00:02.000Z",
+                "type": "event_msg",
+                "payload": { "type": "user_message", "message": message, "kind": "plain" },
+            }),
+            json!({
+                "timestamp": "2025-06-01T10:00:03.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "custom_tool_call",
+                    "name": "apply_patch",
+                    "input": PATCH,
+                    "call_id": "call-1",
+                },
+            }),
+        ];
+        let contents: String = lines.iter().map(|line| format!("{line}\n")).collect();
+        fs::write(&path, contents).expect("write rollout");
+        path
+    }
+
+    #[test]
+    fn sync_reads_only_new_and_changed_rollouts() -> io::Result<()> {
+        let home = TempDir::new()?;
+        let first = write_rollout(home.path(), THREAD_ID, "The invoice job fails");
+        let second = write_rollout(
+            home.path(),
+            "0198c4f6-3c2b-7d10-9a77-3f1c2b0e5d42",
+            "Add a dark mode",
+        );
+
+        assert!(SessionIndex::open(home.path())?.is_none());
+        let mut index = SessionIndex::create(home.path())?;
+        assert_eq!(
+            index.sync()?,
+            SyncStats {
+                indexed: 2,
+                removed: 0,
+                unchanged: 0,
+            }
+        );
+        assert_eq!(
+            index.sync()?,
+            SyncStats {
+                indexed: 0,
+                removed: 0,
+                unchanged: 2,
+            }
+        );
+
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&first)?
+            .write_all(b"{}\n")?;
+        fs::remove_file(&second)?;
+        assert_eq!(
+            index.sync()?,
+            SyncStats {
+                indexed: 1,
+                removed: 1,
+                unchanged: 0,
+            }
+        );
+
+        let mut reopened = SessionIndex::open(home.path())?.expect("index exists");
+        assert_eq!(
+            reopened.sync()?,
+            SyncStats {
+                indexed: 0,
+                removed: 0,
+                unchanged: 1,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn search_matches_text_labels_and_filters() -> io::Result<()> {
+        let home = TempDir::new()?;
+        write_rollout(home.path(), THREAD_ID, "The invoice job fails");
+        let mut index = SessionIndex::create(home.path())?;
+        index.sync()?;
+
+        let search =
+            |query: &str, labels: &BTreeMap<String, ThreadLabels>, filters: SearchFilters| {
+                index
+                    .search(
+                        &SearchQuery::new(query),
+                        labels,
+                        &filters,
+                        &[SessionSource::Cli],
+                        10,
+                    )
+                    .expect("search")
+            };
+        let no_labels = BTreeMap::new();
+
+        let hits = search("INVOICE job", &no_labels, SearchFilters::default());
+        assert_eq!(
+            hits,
+            vec![ThreadHit {
+                thread_id: ThreadId::from_string(THREAD_ID).expect("thread id"),
+                started_at: "2025-06-01T10:00:00.000Z".to_string(),
+                cwd: PathBuf::from("/home/alice/billing"),
+                name: None,
+                model: Some("gpt-5.1-codex".to_string()),
+                snippet: Some("The invoice job fails".to_string()),
+            }]
+        );
+        assert_eq!(
+            search("0042_invoices", &no_labels, SearchFilters::default()).len(),
+            1
+        );
+        assert_eq!(search("jo", &no_labels, SearchFilters::default()).len(), 1);
+        assert!(search("invoice oauth", &no_labels, SearchFilters::default()).is_empty());
+
+        let labels = BTreeMap::from([(
+            THREAD_ID.to_string(),
+            ThreadLabels {
+                name: Some("Billing outage".to_string()),
+                tags: vec!["incident".to_string()],
+            },
+        )]);
+        let hits = search("outage invoice", &labels, SearchFilters::default());
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].name.as_deref(), Some("Billing outage"));
+
+        assert_eq!(
+            search(
+                "invoice",
+                &no_labels,
+                SearchFilters {
+                    file: Some("0042".to_string()),
+                    model: Some("codex".to_string()),
+                    ..Default::default()
+                },
+            )
+            .len(),
+            1
+        );
+        assert!(
+            search(
+                "invoice",
+                &no_labels,
+                SearchFilters {
+                    repo: Some("frontend".to_string()),
+                    ..Default::default()
+                },
+            )
+            .is_empty()
+        );
+        Ok(())
+    }
+}
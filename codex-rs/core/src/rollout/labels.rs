@@ -0,0 +1,213 @@
+//! Names and tags given to threads.
+//!
+//! Rollouts are append-only and archived ones are compressed, so labels live
+//! beside them in `~/.codex/thread_labels.json`, keyed by thread id. A thread
+//! without labels has no entry.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use codex_protocol::ThreadId;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+pub const THREAD_LABELS_FILENAME: &str = "thread_labels.json";
+
+/// Longest thread name or tag accepted, in characters.
+const MAX_LABEL_CHARS: usize = 200;
+
+/// Serializes read-modify-write cycles of the labels file in this process.
+static LABELS_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadLabels {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl ThreadLabels {
+    /// Whether the thread carries every tag in `tags` (already normalized).
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter().all(|tag| self.tags.contains(tag))
+    }
+}
+
+/// Labels of every labelled thread, keyed by thread id.
+pub async fn read_thread_labels(codex_home: &Path) -> io::Result<BTreeMap<String, ThreadLabels>> {
+    let path = codex_home.join(THREAD_LABELS_FILENAME);
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(err) => return Err(err),
+    };
+    serde_json::from_str(&contents).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid {}: {err}", path.display()),
+        )
+    })
+}
+
+/// Renames a thread; `None` removes its name.
+pub async fn set_thread_name(
+    codex_home: &Path,
+    thread_id: ThreadId,
+    name: Option<String>,
+) -> io::Result<ThreadLabels> {
+    let name = match name {
+        Some(name) => normalize_name(&name)?,
+        None => None,
+    };
+    update_labels(codex_home, thread_id, |labels| labels.name = name).await
+}
+
+/// Replaces the tags of a thread. Tags are lowercased, deduplicated, and
+/// sorted.
+pub async fn set_thread_tags(
+    codex_home: &Path,
+    thread_id: ThreadId,
+    tags: &[String],
+) -> io::Result<ThreadLabels> {
+    let tags = normalize_tags(tags)?;
+    update_labels(codex_home, thread_id, |labels| labels.tags = tags).await
+}
+
+/// Forgets the labels of a deleted thread.
+pub async fn remove_thread_labels(codex_home: &Path, thread_id: ThreadId) -> io::Result<()> {
+    update_labels(codex_home, thread_id, |labels| {
+        *labels = ThreadLabels::default();
+    })
+    .await
+    .map(|_| ())
+}
+
+/// The form tags are stored and compared in: trimmed and lowercased, without
+/// duplicates, sorted. Tags cannot be empty or contain whitespace or commas.
+pub fn normalize_tags(tags: &[String]) -> io::Result<Vec<String>> {
+    let mut normalized = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() || tag.contains(|c: char| c.is_whitespace() || c == ',') {
+            return Err(invalid_input(format!(
+                "invalid tag `{tag}`: tags must be single words"
+            )));
+        }
+        check_length(&tag)?;
+        normalized.push(tag);
+    }
+    normalized.sort();
+    normalized.dedup();
+    Ok(normalized)
+}
+
+fn normalize_name(name: &str) -> io::Result<Option<String>> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Ok(None);
+    }
+    check_length(&name)?;
+    Ok(Some(name))
+}
+
+fn check_length(label: &str) -> io::Result<()> {
+    if label.chars().count() > MAX_LABEL_CHARS {
+        return Err(invalid_input(format!(
+            "thread names and tags are limited to {MAX_LABEL_CHARS} characters"
+        )));
+    }
+    Ok(())
+}
+
+fn invalid_input(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+async fn update_labels(
+    codex_home: &Path,
+    thread_id: ThreadId,
+    update: impl FnOnce(&mut ThreadLabels),
+) -> io::Result<ThreadLabels> {
+    let _guard = LABELS_LOCK.lock().await;
+    let mut all = read_thread_labels(codex_home).await?;
+    let key = thread_id.to_string();
+    let mut labels = all.remove(&key).unwrap_or_default();
+    update(&mut labels);
+    if labels != ThreadLabels::default() {
+        all.insert(key, labels.clone());
+    }
+
+    let path = codex_home.join(THREAD_LABELS_FILENAME);
+    let tmp = codex_home.join(format!("{THREAD_LABELS_FILENAME}.tmp"));
+    let contents = serde_json::to_string_pretty(&all).map_err(io::Error::other)?;
+    tokio::fs::create_dir_all(codex_home).await?;
+    tokio::fs::write(&tmp, contents).await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    Ok(labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn labels_round_trip_and_empty_entries_are_dropped() {
+        let home = TempDir::new().expect("tempdir");
+        let thread_id = ThreadId::new();
+
+        set_thread_name(
+            home.path(),
+            thread_id,
+            Some("  Fix   login flow ".to_string()),
+        )
+        .await
+        .expect("name");
+        let labels = set_thread_tags(
+            home.path(),
+            thread_id,
+            &["Bug".to_string(), "auth".to_string(), "bug".to_string()],
+        )
+        .await
+        .expect("tags");
+        assert_eq!(
+            labels,
+            ThreadLabels {
+                name: Some("Fix login flow".to_string()),
+                tags: vec!["auth".to_string(), "bug".to_string()],
+            }
+        );
+        assert!(labels.has_tags(&["bug".to_string()]));
+        assert!(!labels.has_tags(&["bug".to_string(), "ui".to_string()]));
+        assert_eq!(
+            read_thread_labels(home.path())
+                .await
+                .expect("read")
+                .get(&thread_id.to_string()),
+            Some(&labels)
+        );
+
+        remove_thread_labels(home.path(), thread_id)
+            .await
+            .expect("remove");
+        assert_eq!(
+            read_thread_labels(home.path()).await.expect("read"),
+            BTreeMap::new()
+        );
+    }
+
+    #[test]
+    fn tags_must_be_single_words() {
+        assert!(normalize_tags(&["two words".to_string()]).is_err());
+        assert!(normalize_tags(&["a,b".to_string()]).is_err());
+        assert!(normalize_tags(&[" ".to_string()]).is_err());
+        assert_eq!(
+            normalize_tags(&["UI".to_string()]).expect("tags"),
+            vec!["ui".to_string()]
+        );
+    }
+}
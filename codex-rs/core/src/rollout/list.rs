@@ -16,12 +16,16 @@ use time::macros::format_description;
 use uuid::Uuid;
 
 use super::SESSIONS_SUBDIR;
+use super::archive::COMPRESSED_ROLLOUT_SUFFIX;
+use super::archive::is_compressed_rollout;
+use super::index::list_indexed_threads;
 use crate::protocol::EventMsg;
 use codex_file_search as file_search;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::RolloutLine;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
+use tracing::warn;
 
 /// Returned page of thread (thread) summaries.
 #[derive(Debug, Default, PartialEq)]
@@ -59,13 +63,13 @@ pub type ConversationItem = ThreadItem;
 pub type ConversationsPage = ThreadsPage;
 
 #[derive(Default)]
-struct HeadTailSummary {
-    head: Vec<serde_json::Value>,
-    saw_session_meta: bool,
-    saw_user_event: bool,
-    source: Option<SessionSource>,
-    model_provider: Option<String>,
-    created_at: Option<String>,
+pub(super) struct HeadTailSummary {
+    pub(super) head: Vec<serde_json::Value>,
+    pub(super) saw_session_meta: bool,
+    pub(super) saw_user_event: bool,
+    pub(super) source: Option<SessionSource>,
+    pub(super) model_provider: Option<String>,
+    pub(super) created_at: Option<String>,
     updated_at: Option<String>,
 }
 
@@ -95,8 +99,8 @@ pub(crate) struct ThreadListConfig<'a> {
 /// Pagination cursor identifying a file by timestamp and UUID.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Cursor {
-    ts: OffsetDateTime,
-    id: Uuid,
+    pub(super) ts: OffsetDateTime,
+    pub(super) id: Uuid,
 }
 
 impl Cursor {
@@ -272,6 +276,22 @@ pub(crate) async fn get_threads(
     model_providers: Option<&[String]>,
     default_provider: &str,
 ) -> io::Result<ThreadsPage> {
+    match list_indexed_threads(
+        codex_home,
+        page_size,
+        cursor,
+        sort_key,
+        allowed_sources,
+        model_providers,
+        default_provider,
+    )
+    .await
+    {
+        Ok(Some(page)) => return Ok(page),
+        Ok(None) => {}
+        Err(err) => warn!("session index unavailable, scanning rollout files: {err}"),
+    }
+
     let root = codex_home.join(SESSIONS_SUBDIR);
     get_threads_in_root(
         root,
@@ -633,7 +653,7 @@ pub fn parse_cursor(token: &str) -> Option<Cursor> {
     Some(Cursor::new(ts, uuid))
 }
 
-fn build_next_cursor(items: &[ThreadItem], sort_key: ThreadSortKey) -> Option<Cursor> {
+pub(super) fn build_next_cursor(items: &[ThreadItem], sort_key: ThreadSortKey) -> Option<Cursor> {
     let last = items.last()?;
     let file_name = last.path.file_name()?.to_string_lossy();
     let (created_ts, id) = parse_timestamp_uuid_from_filename(&file_name)?;
@@ -759,7 +779,7 @@ async fn collect_flat_rollout_files(
         let Some(name_str) = file_name.to_str() else {
             continue;
         };
-        if !name_str.starts_with("rollout-") || !name_str.ends_with(".jsonl") {
+        if !is_rollout_file_name(name_str) {
             continue;
         }
         let Some((ts, id)) = parse_timestamp_uuid_from_filename(name_str) else {
@@ -779,7 +799,7 @@ async fn collect_rollout_day_files(
     day_path: &Path,
 ) -> io::Result<Vec<(OffsetDateTime, Uuid, PathBuf)>> {
     let mut day_files = collect_files(day_path, |name_str, path| {
-        if !name_str.starts_with("rollout-") || !name_str.ends_with(".jsonl") {
+        if !is_rollout_file_name(name_str) {
             return None;
         }
 
@@ -791,8 +811,15 @@ async fn collect_rollout_day_files(
     Ok(day_files)
 }
 
-fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
-    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
+/// Matches live rollouts and the compressed ones kept in cold storage.
+pub(super) fn is_rollout_file_name(name: &str) -> bool {
+    name.starts_with("rollout-")
+        && (name.ends_with(".jsonl") || name.ends_with(COMPRESSED_ROLLOUT_SUFFIX))
+}
+
+pub(crate) fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
+    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl, optionally with a trailing `.zst`.
+    let name = name.strip_suffix(".zst").unwrap_or(name);
     let core = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
 
     // Scan from the right for a '-' such that the suffix parses as a UUID.
@@ -849,7 +876,7 @@ async fn collect_flat_files_by_updated_at(
         let Some(name_str) = file_name.to_str() else {
             continue;
         };
-        if !name_str.starts_with("rollout-") || !name_str.ends_with(".jsonl") {
+        if !is_rollout_file_name(name_str) {
             continue;
         }
         let Some((_ts, id)) = parse_timestamp_uuid_from_filename(name_str) else {
@@ -910,13 +937,13 @@ async fn walk_rollout_files(
     Ok(())
 }
 
-struct ProviderMatcher<'a> {
+pub(super) struct ProviderMatcher<'a> {
     filters: &'a [String],
     matches_default_provider: bool,
 }
 
 impl<'a> ProviderMatcher<'a> {
-    fn new(filters: &'a [String], default_provider: &'a str) -> Option<Self> {
+    pub(super) fn new(filters: &'a [String], default_provider: &'a str) -> Option<Self> {
         if filters.is_empty() {
             return None;
         }
@@ -928,7 +955,7 @@ impl<'a> ProviderMatcher<'a> {
         })
     }
 
-    fn matches(&self, session_provider: Option<&str>) -> bool {
+    pub(super) fn matches(&self, session_provider: Option<&str>) -> bool {
         match session_provider {
             Some(provider) => self.filters.iter().any(|candidate| candidate == provider),
             None => self.matches_default_provider,
@@ -939,6 +966,15 @@ impl<'a> ProviderMatcher<'a> {
 async fn read_head_summary(path: &Path, head_limit: usize) -> io::Result<HeadTailSummary> {
     use tokio::io::AsyncBufReadExt;
 
+    if is_compressed_rollout(path) {
+        let path = path.to_path_buf();
+        return tokio::task::spawn_blocking(move || {
+            read_compressed_head_summary(&path, head_limit)
+        })
+        .await
+        .map_err(io::Error::other)?;
+    }
+
     let file = tokio::fs::File::open(path).await?;
     let reader = tokio::io::BufReader::new(file);
     let mut lines = reader.lines();
@@ -947,55 +983,90 @@ async fn read_head_summary(path: &Path, head_limit: usize) -> io::Result<HeadTai
     while summary.head.len() < head_limit {
         let line_opt = lines.next_line().await?;
         let Some(line) = line_opt else { break };
-        let trimmed = line.trim();
-        if trimmed.is_empty() {
-            continue;
+        if summarize_head_line(&mut summary, &line) {
+            break;
         }
+    }
 
-        let parsed: Result<RolloutLine, _> = serde_json::from_str(trimmed);
-        let Ok(rollout_line) = parsed else { continue };
-
-        match rollout_line.item {
-            RolloutItem::SessionMeta(session_meta_line) => {
-                summary.source = Some(session_meta_line.meta.source.clone());
-                summary.model_provider = session_meta_line.meta.model_provider.clone();
-                summary.created_at = summary
-                    .created_at
-                    .clone()
-                    .or_else(|| Some(rollout_line.timestamp.clone()));
-                if let Ok(val) = serde_json::to_value(session_meta_line) {
-                    summary.head.push(val);
-                    summary.saw_session_meta = true;
-                }
-            }
-            RolloutItem::ResponseItem(item) => {
-                summary.created_at = summary
-                    .created_at
-                    .clone()
-                    .or_else(|| Some(rollout_line.timestamp.clone()));
-                if let Ok(val) = serde_json::to_value(item) {
-                    summary.head.push(val);
-                }
-            }
-            RolloutItem::TurnContext(_) => {
-                // Not included in `head`; skip.
-            }
-            RolloutItem::Compacted(_) => {
-                // Not included in `head`; skip.
-            }
-            RolloutItem::EventMsg(ev) => {
-                if matches!(ev, EventMsg::UserMessage(_)) {
-                    summary.saw_user_event = true;
-                }
-            }
+    Ok(summary)
+}
+
+/// Same as [`read_head_summary`] for an archived rollout; only the head of the
+/// stream is decompressed.
+fn read_compressed_head_summary(path: &Path, head_limit: usize) -> io::Result<HeadTailSummary> {
+    use std::io::BufRead;
+
+    let file = std::fs::File::open(path)?;
+    let reader = std::io::BufReader::new(zstd::stream::read::Decoder::new(file)?);
+    let mut summary = HeadTailSummary::default();
+    for line in reader.lines() {
+        if summary.head.len() >= head_limit || summarize_head_line(&mut summary, &line?) {
+            break;
         }
+    }
+    Ok(summary)
+}
 
-        if summary.saw_session_meta && summary.saw_user_event {
+/// Same as [`read_head_summary`] for a rollout already read into memory.
+pub(super) fn summarize_head(text: &str) -> HeadTailSummary {
+    let mut summary = HeadTailSummary::default();
+    for line in text.lines() {
+        if summary.head.len() >= HEAD_RECORD_LIMIT || summarize_head_line(&mut summary, line) {
             break;
         }
     }
+    summary
+}
 
-    Ok(summary)
+/// Folds one rollout line into `summary`. Returns true once both the session
+/// meta and a user message have been seen, i.e. reading can stop.
+fn summarize_head_line(summary: &mut HeadTailSummary, line: &str) -> bool {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return false;
+    }
+
+    let parsed: Result<RolloutLine, _> = serde_json::from_str(trimmed);
+    let Ok(rollout_line) = parsed else {
+        return false;
+    };
+
+    match rollout_line.item {
+        RolloutItem::SessionMeta(session_meta_line) => {
+            summary.source = Some(session_meta_line.meta.source.clone());
+            summary.model_provider = session_meta_line.meta.model_provider.clone();
+            summary.created_at = summary
+                .created_at
+                .clone()
+                .or_else(|| Some(rollout_line.timestamp.clone()));
+            if let Ok(val) = serde_json::to_value(session_meta_line) {
+                summary.head.push(val);
+                summary.saw_session_meta = true;
+            }
+        }
+        RolloutItem::ResponseItem(item) => {
+            summary.created_at = summary
+                .created_at
+                .clone()
+                .or_else(|| Some(rollout_line.timestamp.clone()));
+            if let Ok(val) = serde_json::to_value(item) {
+                summary.head.push(val);
+            }
+        }
+        RolloutItem::TurnContext(_) => {
+            // Not included in `head`; skip.
+        }
+        RolloutItem::Compacted(_) | RolloutItem::RuntimeState(_) => {
+            // Not included in `head`; skip.
+        }
+        RolloutItem::EventMsg(ev) => {
+            if matches!(ev, EventMsg::UserMessage(_)) {
+                summary.saw_user_event = true;
+            }
+        }
+    }
+
+    summary.saw_session_meta && summary.saw_user_event
 }
 
 /// Read up to `HEAD_RECORD_LIMIT` records from the start of the rollout file at `path`.
@@ -1035,7 +1106,7 @@ async fn file_modified_time(path: &Path) -> io::Result<Option<OffsetDateTime>> {
     Ok(truncate_to_seconds(dt))
 }
 
-fn format_rfc3339(dt: OffsetDateTime) -> Option<String> {
+pub(super) fn format_rfc3339(dt: OffsetDateTime) -> Option<String> {
     dt.format(&Rfc3339).ok()
 }
 
@@ -7,12 +7,24 @@ pub const ARCHIVED_SESSIONS_SUBDIR: &str = "archived_sessions";
 pub const INTERACTIVE_SESSION_SOURCES: &[SessionSource] =
     &[SessionSource::Cli, SessionSource::VSCode];
 
+pub mod archive;
 pub(crate) mod error;
+pub mod export;
+pub mod import;
+pub mod index;
+pub mod labels;
 pub mod list;
 pub(crate) mod policy;
 pub mod recorder;
+pub mod search;
+pub mod share;
+pub mod stats;
+pub mod transfer;
 pub(crate) mod truncation;
 
+pub use archive::archive_rollout;
+pub use archive::find_archived_thread_path_by_id_str;
+pub use archive::unarchive_rollout;
 pub use codex_protocol::protocol::SessionMeta;
 pub(crate) use error::map_session_init_error;
 pub use list::find_thread_path_by_id_str;
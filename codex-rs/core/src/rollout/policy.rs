@@ -9,9 +9,10 @@ pub(crate) fn is_persisted_response_item(item: &RolloutItem) -> bool {
         RolloutItem::ResponseItem(item) => should_persist_response_item(item),
         RolloutItem::EventMsg(ev) => should_persist_event_msg(ev),
         // Persist Codex executive markers so we can analyze flows (e.g., compaction, API turns).
-        RolloutItem::Compacted(_) | RolloutItem::TurnContext(_) | RolloutItem::SessionMeta(_) => {
-            true
-        }
+        RolloutItem::Compacted(_)
+        | RolloutItem::TurnContext(_)
+        | RolloutItem::SessionMeta(_)
+        | RolloutItem::RuntimeState(_) => true,
     }
 }
 
@@ -27,6 +28,8 @@ pub(crate) fn should_persist_response_item(item: &ResponseItem) -> bool {
         | ResponseItem::CustomToolCall { .. }
         | ResponseItem::CustomToolCallOutput { .. }
         | ResponseItem::WebSearchCall { .. }
+        | ResponseItem::CodeInterpreterCall { .. }
+        | ResponseItem::FileSearchCall { .. }
         | ResponseItem::GhostSnapshot { .. }
         | ResponseItem::Compaction { .. } => true,
         ResponseItem::Other => false,
@@ -42,11 +45,17 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::AgentReasoning(_)
         | EventMsg::AgentReasoningRawContent(_)
         | EventMsg::TokenCount(_)
+        | EventMsg::CostUpdate(_)
         | EventMsg::ContextCompacted(_)
         | EventMsg::EnteredReviewMode(_)
         | EventMsg::ExitedReviewMode(_)
         | EventMsg::ThreadRolledBack(_)
+        | EventMsg::NetworkActivity(_)
+        | EventMsg::TurnAnnotations(_)
         | EventMsg::UndoCompleted(_)
+        | EventMsg::PinnedContextUpdated(_)
+        | EventMsg::ApprovalTimedOut(_)
+        | EventMsg::WorkspaceSnapshot(_)
         | EventMsg::TurnAborted(_) => true,
         EventMsg::Error(_)
         | EventMsg::Warning(_)
@@ -62,6 +71,7 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::McpToolCallEnd(_)
         | EventMsg::WebSearchBegin(_)
         | EventMsg::WebSearchEnd(_)
+        | EventMsg::HostedToolCall(_)
         | EventMsg::ExecCommandBegin(_)
         | EventMsg::TerminalInteraction(_)
         | EventMsg::ExecCommandOutputDelta(_)
@@ -70,19 +80,58 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::RequestUserInput(_)
         | EventMsg::ElicitationRequest(_)
         | EventMsg::ApplyPatchApprovalRequest(_)
+        | EventMsg::PlanApprovalRequest(_)
+        | EventMsg::McpToolCallApprovalRequest(_)
         | EventMsg::BackgroundEvent(_)
         | EventMsg::StreamError(_)
+        | EventMsg::ProviderRateLimit(_)
         | EventMsg::PatchApplyBegin(_)
         | EventMsg::PatchApplyEnd(_)
+        | EventMsg::PatchHunkReport(_)
+        | EventMsg::PatchPreview(_)
+        | EventMsg::ExternalFilesChanged(_)
+        | EventMsg::ResumeBriefing(_)
         | EventMsg::TurnDiff(_)
         | EventMsg::GetHistoryEntryResponse(_)
         | EventMsg::UndoStarted(_)
         | EventMsg::McpListToolsResponse(_)
         | EventMsg::McpStartupUpdate(_)
         | EventMsg::McpStartupComplete(_)
+        | EventMsg::McpServerHealth(_)
+        | EventMsg::McpToolsChanged(_)
         | EventMsg::ListCustomPromptsResponse(_)
+        | EventMsg::ListMcpPromptsResponse(_)
         | EventMsg::ListSkillsResponse(_)
+        | EventMsg::ApprovalJournalResponse(_)
+        | EventMsg::ListSubAgentsResponse(_)
+        | EventMsg::SubAgentsUpdated(_)
+        | EventMsg::ConfigReloaded(_)
+        | EventMsg::SubAgentRunBegin(_)
+        | EventMsg::SubAgentRunEnd(_)
+        | EventMsg::SubAgentPreview(_)
+        | EventMsg::SubAgentMergePlan(_)
+        | EventMsg::SubAgentMergeApplied(_)
+        | EventMsg::BackgroundTaskBegin(_)
+        | EventMsg::BackgroundTaskEnd(_)
+        | EventMsg::ListBackgroundTasksResponse(_)
+        | EventMsg::BackgroundProcessesUpdated(_)
+        | EventMsg::QueuedUserInputs(_)
+        | EventMsg::HookOutcome(_)
+        | EventMsg::PlanModeUpdated(_)
+        | EventMsg::ConversationForked(_)
+        | EventMsg::CheckpointCreated(_)
+        | EventMsg::ListCheckpointsResponse(_)
+        | EventMsg::CheckpointRestored(_)
+        | EventMsg::WorktreeCreated(_)
+        | EventMsg::WorktreeReview(_)
+        | EventMsg::WorktreeFinished(_)
+        | EventMsg::ListPinnedContextResponse(_)
+        | EventMsg::ContextInspection(_)
+        | EventMsg::ListMemoryResponse(_)
+        | EventMsg::NextEditSuggestion(_)
+        | EventMsg::SandboxDenied(_)
         | EventMsg::PlanUpdate(_)
+        | EventMsg::FollowUpSuggestions(_)
         | EventMsg::ShutdownComplete
         | EventMsg::ViewImageToolCall(_)
         | EventMsg::DeprecationNotice(_)
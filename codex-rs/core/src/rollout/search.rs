@@ -0,0 +1,518 @@
+//! Full-text search over the conversation recorded in a rollout.
+//!
+//! What the user and the assistant said is searched, together with the
+//! commands that were run, the paths of patched files, and the thread's name
+//! and tags. Tool output and injected context are not: they would match
+//! almost any query.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Local;
+use chrono::NaiveDate;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionMetaLine;
+
+use super::archive::read_rollout_to_string;
+use super::labels::ThreadLabels;
+use super::share::CallKind;
+use super::share::Entry;
+use super::share::collect_turns;
+use super::stats::patch_paths;
+
+/// Characters of context kept before a match in a snippet.
+const SNIPPET_BEFORE_CHARS: usize = 60;
+/// Characters kept from the start of the match onwards.
+const SNIPPET_AFTER_CHARS: usize = 120;
+
+/// Case-insensitive terms that must all appear in a thread.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchQuery {
+    terms: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch {
+    /// Excerpt of the first message mentioning a term, when the match is not
+    /// only in the thread's name or tags.
+    pub snippet: Option<String>,
+}
+
+/// Thread metadata a search can be narrowed by.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchFilters {
+    /// Threads started on or after this day (local time).
+    pub since: Option<NaiveDate>,
+    /// Threads started on or before this day (local time).
+    pub until: Option<NaiveDate>,
+    /// Case-insensitive part of the working directory or repository URL.
+    pub repo: Option<String>,
+    /// Case-insensitive part of the name of a model any turn used.
+    pub model: Option<String>,
+    /// Case-insensitive part of the path of a file any patch touched.
+    pub file: Option<String>,
+}
+
+/// The thread metadata [`SearchFilters`] look at, read from a rollout or
+/// from the session index.
+pub(super) struct FilterFields<'a> {
+    pub(super) started_at: &'a str,
+    pub(super) cwd: &'a str,
+    pub(super) repository_url: Option<&'a str>,
+    pub(super) models: &'a [String],
+    pub(super) files: &'a [String],
+}
+
+/// A thread found by [`SearchQuery::search_thread`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadHit {
+    pub thread_id: ThreadId,
+    /// When the thread started (RFC 3339).
+    pub started_at: String,
+    pub cwd: PathBuf,
+    pub name: Option<String>,
+    /// Model of the latest turn.
+    pub model: Option<String>,
+    pub snippet: Option<String>,
+}
+
+impl SearchQuery {
+    pub fn new(query: &str) -> Self {
+        Self {
+            terms: query.split_whitespace().map(str::to_lowercase).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub(super) fn terms(&self) -> &[String] {
+        &self.terms
+    }
+
+    /// Searches the rollout at `path` (archived or not), returning `None`
+    /// when some term appears neither in its messages nor in `labels`.
+    pub async fn search_rollout(
+        &self,
+        path: &Path,
+        labels: Option<&ThreadLabels>,
+    ) -> io::Result<Option<SearchMatch>> {
+        let text = read_rollout_to_string(path).await?;
+        Ok(self.search_messages(&RolloutText::parse(&text).documents(), labels))
+    }
+
+    /// Searches the rollout at `path` like [`Self::search_rollout`] after
+    /// checking it against `filters`. `labels` holds the names and tags of
+    /// all threads, by thread id. An empty query matches every thread that
+    /// passes the filters.
+    pub async fn search_thread(
+        &self,
+        path: &Path,
+        labels: &BTreeMap<String, ThreadLabels>,
+        filters: &SearchFilters,
+    ) -> io::Result<Option<ThreadHit>> {
+        let text = read_rollout_to_string(path).await?;
+        let rollout = RolloutText::parse(&text);
+        let Some(meta_line) = &rollout.meta else {
+            return Ok(None);
+        };
+        if !filters.matches(&rollout) {
+            return Ok(None);
+        }
+        let meta = &meta_line.meta;
+        let thread_labels = labels.get(&meta.id.to_string());
+        let Some(found) = self.search_messages(&rollout.documents(), thread_labels) else {
+            return Ok(None);
+        };
+        Ok(Some(ThreadHit {
+            thread_id: meta.id,
+            started_at: meta.timestamp.clone(),
+            cwd: meta.cwd.clone(),
+            name: thread_labels.and_then(|labels| labels.name.clone()),
+            model: rollout.models.last().cloned(),
+            snippet: found.snippet,
+        }))
+    }
+
+    pub(super) fn search_messages(
+        &self,
+        messages: &[String],
+        labels: Option<&ThreadLabels>,
+    ) -> Option<SearchMatch> {
+        let label_text = labels.map(label_text).unwrap_or_default();
+        let lowered: Vec<String> = messages
+            .iter()
+            .map(|message| message.to_lowercase())
+            .collect();
+        let found = |term: &String| {
+            label_text.contains(term.as_str())
+                || lowered
+                    .iter()
+                    .any(|message| message.contains(term.as_str()))
+        };
+        if !self.terms.iter().all(found) {
+            return None;
+        }
+        let snippet = self.terms.iter().find_map(|term| {
+            messages
+                .iter()
+                .find_map(|message| snippet_around(message, term))
+        });
+        Some(SearchMatch { snippet })
+    }
+}
+
+impl SearchFilters {
+    fn matches(&self, rollout: &RolloutText) -> bool {
+        let Some(meta_line) = &rollout.meta else {
+            return false;
+        };
+        let cwd = meta_line.meta.cwd.to_string_lossy();
+        self.matches_fields(&FilterFields {
+            started_at: &meta_line.meta.timestamp,
+            cwd: &cwd,
+            repository_url: meta_line
+                .git
+                .as_ref()
+                .and_then(|git| git.repository_url.as_deref()),
+            models: &rollout.models,
+            files: &rollout.files,
+        })
+    }
+
+    pub(super) fn matches_fields(&self, fields: &FilterFields<'_>) -> bool {
+        if self.since.is_some() || self.until.is_some() {
+            let Ok(started_at) = DateTime::parse_from_rfc3339(fields.started_at) else {
+                return false;
+            };
+            let day = started_at.with_timezone(&Local).date_naive();
+            if self.since.is_some_and(|since| day < since)
+                || self.until.is_some_and(|until| day > until)
+            {
+                return false;
+            }
+        }
+        if let Some(repo) = &self.repo {
+            let repo = repo.to_lowercase();
+            let cwd = fields.cwd.to_lowercase();
+            let url = fields.repository_url.unwrap_or_default().to_lowercase();
+            if !cwd.contains(&repo) && !url.contains(&repo) {
+                return false;
+            }
+        }
+        if let Some(model) = &self.model {
+            let model = model.to_lowercase();
+            if !fields
+                .models
+                .iter()
+                .any(|used| used.to_lowercase().contains(&model))
+            {
+                return false;
+            }
+        }
+        if let Some(file) = &self.file {
+            let file = file.to_lowercase();
+            if !fields
+                .files
+                .iter()
+                .any(|path| path.to_lowercase().contains(&file))
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The searchable parts of a rollout.
+#[derive(Default)]
+pub(super) struct RolloutText {
+    pub(super) meta: Option<SessionMetaLine>,
+    /// Model of each turn, in order.
+    pub(super) models: Vec<String>,
+    /// Every user and assistant message, in order.
+    pub(super) messages: Vec<String>,
+    /// Commands run and paths of patched files, in order.
+    pub(super) activity: Vec<String>,
+    /// Paths of patched files, in order.
+    pub(super) files: Vec<String>,
+}
+
+impl RolloutText {
+    pub(super) fn parse(text: &str) -> Self {
+        let mut rollout = Self::default();
+        let mut items = Vec::new();
+        for line in text.lines() {
+            let Ok(line) = serde_json::from_str::<RolloutLine>(line) else {
+                continue;
+            };
+            match line.item {
+                RolloutItem::SessionMeta(meta) if rollout.meta.is_none() => {
+                    rollout.meta = Some(meta);
+                }
+                RolloutItem::TurnContext(context) => rollout.models.push(context.model),
+                item @ RolloutItem::ResponseItem(_) => items.push(item),
+                _ => {}
+            }
+        }
+        for entry in collect_turns(&items)
+            .into_iter()
+            .flat_map(|turn| turn.entries)
+        {
+            match entry {
+                Entry::User(text) | Entry::Assistant(text) => rollout.messages.push(text),
+                Entry::ToolCall {
+                    input,
+                    kind: CallKind::Command,
+                    ..
+                } => rollout.activity.push(input),
+                Entry::ToolCall {
+                    input,
+                    kind: CallKind::Patch,
+                    ..
+                } => {
+                    for path in patch_paths(&input) {
+                        rollout.activity.push(path.to_string());
+                        rollout.files.push(path.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        rollout
+    }
+
+    /// Messages first, so snippets prefer them over commands and paths.
+    pub(super) fn documents(&self) -> Vec<String> {
+        self.messages
+            .iter()
+            .chain(&self.activity)
+            .cloned()
+            .collect()
+    }
+}
+
+/// A thread's name and tags, lowercased for matching.
+pub(super) fn label_text(labels: &ThreadLabels) -> String {
+    let mut text = labels.name.clone().unwrap_or_default();
+    for tag in &labels.tags {
+        text.push(' ');
+        text.push_str(tag);
+    }
+    text.to_lowercase()
+}
+
+/// A one-line excerpt of `message` around the first case-insensitive
+/// occurrence of `term` (which is lowercase).
+fn snippet_around(message: &str, term: &str) -> Option<String> {
+    // Lowercasing can change a character's length, so match char by char.
+    let chars: Vec<char> = message.chars().collect();
+    let term: Vec<char> = term.chars().collect();
+    let start = (0..chars.len()).find(|&start| {
+        let mut lowered = chars[start..].iter().flat_map(|c| c.to_lowercase());
+        term.iter()
+            .all(|expected| lowered.next() == Some(*expected))
+    })?;
+
+    let from = start.saturating_sub(SNIPPET_BEFORE_CHARS);
+    let to = (start + SNIPPET_AFTER_CHARS).min(chars.len());
+    let excerpt: String = chars[from..to].iter().collect();
+    let mut snippet = excerpt.split_whitespace().collect::<Vec<_>>().join(" ");
+    if from > 0 {
+        snippet.insert(0, '…');
+    }
+    if to < chars.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn messages() -> Vec<String> {
+        vec![
+            "Why does the login page\nreturn 500?".to_string(),
+            "The session cookie is missing its SameSite attribute.".to_string(),
+        ]
+    }
+
+    #[test]
+    fn every_term_must_match_somewhere() {
+        let query = SearchQuery::new("Login  samesite");
+        assert_eq!(
+            query.search_messages(&messages(), None),
+            Some(SearchMatch {
+                snippet: Some("Why does the login page return 500?".to_string()),
+            })
+        );
+        assert_eq!(
+            SearchQuery::new("login oauth").search_messages(&messages(), None),
+            None
+        );
+    }
+
+    #[test]
+    fn labels_count_as_matches() {
+        let labels = ThreadLabels {
+            name: Some("Auth outage".to_string()),
+            tags: vec!["incident".to_string()],
+        };
+        assert_eq!(
+            SearchQuery::new("incident outage").search_messages(&messages(), Some(&labels)),
+            Some(SearchMatch { snippet: None })
+        );
+        assert_eq!(
+            SearchQuery::new("incident cookie")
+                .search_messages(&messages(), Some(&labels))
+                .and_then(|found| found.snippet),
+            Some("The session cookie is missing its SameSite attribute.".to_string())
+        );
+    }
+
+    const PATCH: &str =
+        "*** Begin Patch\n*** Update File: migrations/0042_invoices.sql\n@@\n-a\n+b\n*** End Patch";
+
+    fn rollout() -> String {
+        [
+            json!({
+                "timestamp": "2025-06-01T10:00:00.000Z",
+                "type": "session_meta",
+                "payload": {
+                    "id": "0198c4f6-3c2b-7d10-9a77-3f1c2b0e5d41",
+                    "timestamp": "2025-06-01T10:00:00.000Z",
+                    "cwd": "/home/alice/billing",
+                    "originator": "codex_cli_rs",
+                    "cli_version": "0.0.0",
+                    "git": { "repository_url": "https://github.com/acme/billing.git" },
+                },
+            }),
+            json!({
+                "timestamp": "2025-06-01T10:00:01.000Z",
+                "type": "turn_context",
+                "payload": {
+                    "cwd": "/home/alice/billing",
+                    "approval_policy": "on-request",
+                    "sandbox_policy": { "type": "read-only" },
+                    "model": "gpt-5.1-codex",
+                    "summary": "auto",
+                },
+            }),
+            json!({
+                "timestamp": "2025-06-01T10:00:02.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "message",
+                    "role": "user",
+                    "content": [{ "type": "input_text", "text": "The invoice job fails" }],
+                },
+            }),
+            json!({
+                "timestamp": "2025-06-01T10:00:03.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "function_call",
+                    "name": "shell",
+                    "arguments": "{\"command\":[\"sqlx\",\"migrate\",\"run\"]}",
+                    "call_id": "call-1",
+                },
+            }),
+            json!({
+                "timestamp": "2025-06-01T10:00:04.000Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "custom_tool_call",
+                    "name": "apply_patch",
+                    "input": PATCH,
+                    "call_id": "call-2",
+                },
+            }),
+        ]
+        .iter()
+        .map(|line| format!("{line}\n"))
+        .collect()
+    }
+
+    #[test]
+    fn commands_and_patched_paths_are_searched() {
+        let rollout = RolloutText::parse(&rollout());
+        assert_eq!(rollout.models, vec!["gpt-5.1-codex".to_string()]);
+        assert_eq!(
+            SearchQuery::new("migrate 0042_invoices")
+                .search_messages(&rollout.documents(), None)
+                .and_then(|found| found.snippet),
+            Some("sqlx migrate run".to_string())
+        );
+    }
+
+    #[test]
+    fn filters_check_dates_repository_and_model() {
+        let rollout = RolloutText::parse(&rollout());
+        let day = |day: u32| NaiveDate::from_ymd_opt(2025, 6, day);
+        let matches = |filters: SearchFilters| filters.matches(&rollout);
+
+        assert!(matches(SearchFilters::default()));
+        assert!(matches(SearchFilters {
+            repo: Some("ACME/billing".to_string()),
+            model: Some("codex".to_string()),
+            ..Default::default()
+        }));
+        assert!(!matches(SearchFilters {
+            repo: Some("frontend".to_string()),
+            ..Default::default()
+        }));
+        assert!(!matches(SearchFilters {
+            model: Some("o3".to_string()),
+            ..Default::default()
+        }));
+        assert!(matches(SearchFilters {
+            file: Some("0042_INVOICES".to_string()),
+            ..Default::default()
+        }));
+        assert!(!matches(SearchFilters {
+            file: Some("schema.rs".to_string()),
+            ..Default::default()
+        }));
+        assert!(!matches(SearchFilters {
+            since: day(3),
+            ..Default::default()
+        }));
+        assert!(!matches(SearchFilters {
+            until: NaiveDate::from_ymd_opt(2025, 5, 30),
+            ..Default::default()
+        }));
+        assert!(matches(SearchFilters {
+            since: NaiveDate::from_ymd_opt(2025, 5, 30),
+            until: day(3),
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn snippets_are_trimmed_around_the_match() {
+        let message = format!("{} needle {}", "a".repeat(100), "b".repeat(200));
+        let snippet = snippet_around(&message, "needle").expect("snippet");
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+        assert_eq!(
+            snippet.chars().count(),
+            2 + SNIPPET_BEFORE_CHARS + SNIPPET_AFTER_CHARS
+        );
+
+        assert_eq!(
+            snippet_around("İstanbul trip", "trip"),
+            Some("İstanbul trip".to_string())
+        );
+        assert_eq!(snippet_around("short", "long"), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Per-thread figures for history views: the model of the latest turn and a
+//! diffstat of the thread's `apply_patch` calls.
+//!
+//! The diffstat counts the lines of the patches the model sent, so a patch
+//! that failed to apply still counts, and edits made by shell commands do not.
+
+use std::collections::BTreeSet;
+
+use codex_protocol::protocol::RolloutItem;
+
+use super::share::CallKind;
+use super::share::Entry;
+use super::share::collect_turns;
+
+const FILE_HEADERS: &[&str] = &["*** Add File: ", "*** Update File: ", "*** Delete File: "];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThreadStats {
+    /// Model of the latest turn, when the rollout recorded its context.
+    pub model: Option<String>,
+    /// Distinct files added, updated, or deleted.
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+pub fn thread_stats(items: &[RolloutItem]) -> ThreadStats {
+    let model = items.iter().rev().find_map(|item| match item {
+        RolloutItem::TurnContext(context) => Some(context.model.clone()),
+        _ => None,
+    });
+    let mut stats = ThreadStats {
+        model,
+        ..Default::default()
+    };
+    let mut files = BTreeSet::new();
+    for turn in collect_turns(items) {
+        for entry in turn.entries {
+            if let Entry::ToolCall {
+                input,
+                kind: CallKind::Patch,
+                ..
+            } = entry
+            {
+                count_patch(&input, &mut files, &mut stats);
+            }
+        }
+    }
+    stats.files_changed = files.len();
+    stats
+}
+
+/// Paths of the files an `apply_patch` patch adds, updates, or deletes.
+pub(super) fn patch_paths(patch: &str) -> impl Iterator<Item = &str> {
+    patch.lines().filter_map(|line| {
+        FILE_HEADERS
+            .iter()
+            .find_map(|header| line.strip_prefix(header))
+            .map(str::trim)
+    })
+}
+
+fn count_patch(patch: &str, files: &mut BTreeSet<String>, stats: &mut ThreadStats) {
+    for line in patch.lines() {
+        if let Some(path) = FILE_HEADERS
+            .iter()
+            .find_map(|header| line.strip_prefix(header))
+        {
+            files.insert(path.trim().to_string());
+        } else if line.starts_with("***") {
+            continue;
+        } else if line.starts_with('+') {
+            stats.insertions += 1;
+        } else if line.starts_with('-') {
+            stats.deletions += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::ResponseItem;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn counts_patch_lines_and_files() {
+        let patch = |input: &str| {
+            RolloutItem::ResponseItem(ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call".to_string(),
+                name: "apply_patch".to_string(),
+                input: input.to_string(),
+            })
+        };
+        let items = vec![
+            RolloutItem::ResponseItem(ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: "Rename the flag".to_string(),
+                }],
+                end_turn: None,
+            }),
+            patch("*** Begin Patch\n*** Update File: a.rs\n@@\n-old\n+new\n+more\n*** End Patch"),
+            patch(
+                "*** Begin Patch\n*** Add File: b.rs\n+b\n*** Update File: a.rs\n@@\n-more\n*** End Patch",
+            ),
+        ];
+
+        assert_eq!(
+            thread_stats(&items),
+            ThreadStats {
+                model: None,
+                files_changed: 2,
+                insertions: 3,
+                deletions: 2,
+            }
+        );
+    }
+}
@@ -16,6 +16,7 @@ use time::macros::format_description;
 use uuid::Uuid;
 
 use crate::rollout::INTERACTIVE_SESSION_SOURCES;
+use crate::rollout::index::SessionIndex;
 use crate::rollout::list::Cursor;
 use crate::rollout::list::ThreadItem;
 use crate::rollout::list::ThreadSortKey;
@@ -1130,3 +1131,93 @@ async fn test_model_provider_filter_selects_only_matching_sessions() -> Result<(
 
     Ok(())
 }
+
+/// Every page of `get_threads`, following cursors, as `(items, next_cursor)`.
+async fn list_all_pages(
+    home: &Path,
+    sort_key: ThreadSortKey,
+    allowed_sources: &[SessionSource],
+) -> Result<Vec<(Vec<ThreadItem>, Option<Cursor>)>> {
+    let mut pages = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = get_threads(
+            home,
+            2,
+            cursor.as_ref(),
+            sort_key,
+            allowed_sources,
+            None,
+            TEST_PROVIDER,
+        )
+        .await?;
+        cursor = page.next_cursor.clone();
+        pages.push((page.items, page.next_cursor));
+        if cursor.is_none() {
+            return Ok(pages);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_session_index_lists_like_the_file_scan() -> Result<()> {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    write_session_file(
+        home,
+        "2025-01-01T12-00-00",
+        Uuid::from_u128(1),
+        1,
+        Some(SessionSource::Cli),
+    )?;
+    write_session_file(
+        home,
+        "2025-01-02T12-00-00",
+        Uuid::from_u128(2),
+        1,
+        Some(SessionSource::Cli),
+    )?;
+    write_session_file(
+        home,
+        "2025-01-02T12-00-00",
+        Uuid::from_u128(3),
+        1,
+        Some(SessionSource::VSCode),
+    )?;
+    write_session_file(
+        home,
+        "2025-01-03T12-00-00",
+        Uuid::from_u128(4),
+        1,
+        Some(SessionSource::Exec),
+    )?;
+    write_session_file(
+        home,
+        "2025-01-04T12-00-00",
+        Uuid::from_u128(5),
+        1,
+        Some(SessionSource::Cli),
+    )?;
+
+    let mut scanned = Vec::new();
+    for sort_key in [ThreadSortKey::CreatedAt, ThreadSortKey::UpdatedAt] {
+        for sources in [NO_SOURCE_FILTER, INTERACTIVE_SESSION_SOURCES] {
+            scanned.push(list_all_pages(home, sort_key, sources).await?);
+        }
+    }
+    assert_eq!(scanned[0].len(), 3);
+
+    let stats = SessionIndex::create(home)?.sync()?;
+    assert_eq!(stats.indexed, 5);
+
+    let mut indexed = Vec::new();
+    for sort_key in [ThreadSortKey::CreatedAt, ThreadSortKey::UpdatedAt] {
+        for sources in [NO_SOURCE_FILTER, INTERACTIVE_SESSION_SOURCES] {
+            indexed.push(list_all_pages(home, sort_key, sources).await?);
+        }
+    }
+    assert_eq!(indexed, scanned);
+
+    Ok(())
+}
@@ -0,0 +1,400 @@
+//! Runtime state recorded in the rollout (`resume_runtime_state` feature).
+//!
+//! Pinned context and the workspace snapshot already travel with the rollout.
+//! This covers the rest of what a resumed thread needs to continue mid-task:
+//! approvals granted for the session, inputs queued behind the running turn,
+//! and the commands and approval prompts of a turn that was cut off. Whenever
+//! one of them changes, a [`RolloutItem::RuntimeState`] snapshot is appended,
+//! and resuming restores the last one. Processes do not outlive Codex, so
+//! commands that were still running and prompts nobody answered are reported
+//! to the model instead of being restarted.
+
+use std::sync::Mutex;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ApprovalKind;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::PendingApproval;
+use codex_protocol::protocol::QueuedUserInput;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RunningCommand;
+use codex_protocol::protocol::RuntimeStateItem;
+use codex_protocol::protocol::WarningEvent;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::features::Feature;
+use crate::session_prefix::INTERRUPTED_WORK_OPEN_TAG;
+
+#[derive(Default)]
+pub(crate) struct RuntimeStateTracker {
+    state: Mutex<TrackedState>,
+}
+
+/// Everything in [`RuntimeStateItem`] except the approvals, which are read
+/// from the session's approval store when a snapshot is taken.
+#[derive(Debug, Default, PartialEq)]
+struct TrackedState {
+    running_commands: Vec<RunningCommand>,
+    pending_approvals: Vec<PendingApproval>,
+    queued_inputs: Vec<QueuedUserInput>,
+}
+
+impl TrackedState {
+    /// Applies `msg`; returns whether anything changed.
+    fn apply(&mut self, msg: &EventMsg) -> bool {
+        match msg {
+            EventMsg::ExecApprovalRequest(event) => {
+                self.pending_approvals.push(PendingApproval {
+                    call_id: event.call_id.clone(),
+                    turn_id: event.turn_id.clone(),
+                    kind: ApprovalKind::Exec,
+                    summary: event.command.join(" "),
+                });
+                true
+            }
+            EventMsg::ApplyPatchApprovalRequest(event) => {
+                let mut files = event
+                    .changes
+                    .keys()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>();
+                files.sort();
+                self.pending_approvals.push(PendingApproval {
+                    call_id: event.call_id.clone(),
+                    turn_id: event.turn_id.clone(),
+                    kind: ApprovalKind::Patch,
+                    summary: files.join(", "),
+                });
+                true
+            }
+            EventMsg::PlanApprovalRequest(event) => {
+                self.pending_approvals.push(PendingApproval {
+                    call_id: event.call_id.clone(),
+                    turn_id: event.turn_id.clone(),
+                    kind: ApprovalKind::Plan,
+                    summary: event.plan.summary.clone(),
+                });
+                true
+            }
+            EventMsg::McpToolCallApprovalRequest(event) => {
+                self.pending_approvals.push(PendingApproval {
+                    call_id: event.call_id.clone(),
+                    turn_id: event.turn_id.clone(),
+                    kind: ApprovalKind::McpTool,
+                    summary: format!("{}.{}", event.invocation.server, event.invocation.tool),
+                });
+                true
+            }
+            EventMsg::ExecCommandBegin(event) => {
+                self.resolve(&event.call_id);
+                if self
+                    .running_commands
+                    .iter()
+                    .any(|command| command.call_id == event.call_id)
+                {
+                    return false;
+                }
+                self.running_commands.push(RunningCommand {
+                    call_id: event.call_id.clone(),
+                    turn_id: event.turn_id.clone(),
+                    command: event.command.clone(),
+                    cwd: event.cwd.clone(),
+                    process_id: event.process_id.clone(),
+                });
+                true
+            }
+            EventMsg::ExecCommandEnd(event) => {
+                let before = self.running_commands.len();
+                self.running_commands
+                    .retain(|command| command.call_id != event.call_id);
+                self.running_commands.len() != before
+            }
+            EventMsg::PatchApplyBegin(event) => self.resolve(&event.call_id),
+            EventMsg::ApprovalTimedOut(event) => self.resolve(&event.call_id),
+            EventMsg::QueuedUserInputs(event) => {
+                if self.queued_inputs == event.inputs {
+                    return false;
+                }
+                self.queued_inputs = event.inputs.clone();
+                true
+            }
+            EventMsg::TurnComplete(_) | EventMsg::TurnAborted(_) => {
+                let changed =
+                    !self.running_commands.is_empty() || !self.pending_approvals.is_empty();
+                self.running_commands.clear();
+                self.pending_approvals.clear();
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    fn resolve(&mut self, call_id: &str) -> bool {
+        let before = self.pending_approvals.len();
+        self.pending_approvals
+            .retain(|approval| approval.call_id != call_id);
+        self.pending_approvals.len() != before
+    }
+
+    /// A turn has one approval prompt at a time, answered by turn id.
+    fn answered(&mut self, turn_id: &str) -> bool {
+        let before = self.pending_approvals.len();
+        self.pending_approvals
+            .retain(|approval| approval.turn_id != turn_id);
+        self.pending_approvals.len() != before
+    }
+}
+
+/// Tracks `msg` and appends a snapshot to the rollout when it changed the
+/// recoverable state.
+pub(crate) async fn observe_event(sess: &Session, msg: &EventMsg) {
+    if !sess.enabled(Feature::ResumeRuntimeState) {
+        return;
+    }
+    let changed = match sess.services.runtime_state.state.lock() {
+        Ok(mut state) => state.apply(msg),
+        Err(_) => false,
+    };
+    if changed {
+        record_snapshot(sess).await;
+    }
+}
+
+/// Called when the user answers the approval prompt of `turn_id`.
+pub(crate) async fn approval_answered(sess: &Session, turn_id: &str) {
+    if !sess.enabled(Feature::ResumeRuntimeState) {
+        return;
+    }
+    let changed = match sess.services.runtime_state.state.lock() {
+        Ok(mut state) => state.answered(turn_id),
+        Err(_) => false,
+    };
+    if changed {
+        record_snapshot(sess).await;
+    }
+}
+
+async fn record_snapshot(sess: &Session) {
+    let approved_for_session = sess
+        .services
+        .tool_approvals
+        .lock()
+        .await
+        .session_approvals();
+    let item = {
+        let Ok(state) = sess.services.runtime_state.state.lock() else {
+            return;
+        };
+        RuntimeStateItem {
+            approved_for_session,
+            queued_inputs: state.queued_inputs.clone(),
+            running_commands: state.running_commands.clone(),
+            pending_approvals: state.pending_approvals.clone(),
+        }
+    };
+    sess.persist_rollout_items(&[RolloutItem::RuntimeState(item)])
+        .await;
+}
+
+/// Restores the last recorded runtime state of a resumed thread and tells the
+/// model about work the previous process left unfinished.
+pub(crate) async fn restore_on_resume(
+    sess: &Session,
+    turn_context: &TurnContext,
+    rollout_items: &[RolloutItem],
+) {
+    let Some(state) = last_state(rollout_items) else {
+        return;
+    };
+    if !state.approved_for_session.is_empty() {
+        let mut store = sess.services.tool_approvals.lock().await;
+        for key in &state.approved_for_session {
+            store.restore_session_approval(key.clone());
+        }
+    }
+    if !state.queued_inputs.is_empty() {
+        crate::input_queue::restore_queued_user_inputs(
+            sess,
+            turn_context.sub_id.clone(),
+            state.queued_inputs.clone(),
+        )
+        .await;
+    }
+
+    let Some(notice) = format_interrupted_work(state) else {
+        return;
+    };
+    let item = ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText { text: notice }],
+        end_turn: None,
+    };
+    sess.record_conversation_items(turn_context, &[item]).await;
+    sess.send_event(
+        turn_context,
+        EventMsg::Warning(WarningEvent {
+            message: format!(
+                "The previous session stopped mid-turn: {} command(s) were still running and {} \
+                 approval request(s) were unanswered. Codex was told they did not finish.",
+                state.running_commands.len(),
+                state.pending_approvals.len()
+            ),
+        }),
+    )
+    .await;
+    // Record that the interruption was reported so a later resume does not
+    // report it again.
+    record_snapshot(sess).await;
+}
+
+fn last_state(rollout_items: &[RolloutItem]) -> Option<&RuntimeStateItem> {
+    rollout_items.iter().rev().find_map(|item| match item {
+        RolloutItem::RuntimeState(state) => Some(state),
+        _ => None,
+    })
+}
+
+fn format_interrupted_work(state: &RuntimeStateItem) -> Option<String> {
+    if state.running_commands.is_empty() && state.pending_approvals.is_empty() {
+        return None;
+    }
+    let mut lines = vec![
+        INTERRUPTED_WORK_OPEN_TAG.to_string(),
+        "The previous session ended in the middle of a turn.".to_string(),
+    ];
+    if !state.running_commands.is_empty() {
+        lines.push(
+            "These commands were still running and were stopped; their remaining output is lost:"
+                .to_string(),
+        );
+        for command in &state.running_commands {
+            let background = command
+                .process_id
+                .as_ref()
+                .map(|id| format!(", process {id} no longer exists"))
+                .unwrap_or_default();
+            lines.push(format!(
+                "- `{}` (in {}{background})",
+                command.command.join(" "),
+                command.cwd.display()
+            ));
+        }
+    }
+    if !state.pending_approvals.is_empty() {
+        lines.push(
+            "These approval requests were never answered, so the calls did not run:".to_string(),
+        );
+        for approval in &state.pending_approvals {
+            let kind = match approval.kind {
+                ApprovalKind::Exec => "command",
+                ApprovalKind::Patch => "patch to",
+                ApprovalKind::Plan => "plan",
+                ApprovalKind::McpTool => "MCP tool",
+            };
+            lines.push(format!("- {kind} {}", approval.summary));
+        }
+    }
+    lines.push(
+        "Check the state of the workspace before continuing, and run again whatever is still needed."
+            .to_string(),
+    );
+    lines.push("</interrupted_work>".to_string());
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::ExecApprovalRequestEvent;
+    use codex_protocol::protocol::ExecCommandBeginEvent;
+    use codex_protocol::protocol::ExecCommandSource;
+    use codex_protocol::protocol::TurnCompleteEvent;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn approval_request(call_id: &str) -> EventMsg {
+        EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+            call_id: call_id.to_string(),
+            turn_id: "turn-1".to_string(),
+            command: vec!["cargo".to_string(), "test".to_string()],
+            cwd: PathBuf::from("/repo"),
+            reason: None,
+            proposed_execpolicy_amendment: None,
+            parsed_cmd: Vec::new(),
+            explanation: None,
+            sandbox_policy: None,
+            risk: None,
+            matched_rule: None,
+            sandbox: None,
+        })
+    }
+
+    fn command_begin(call_id: &str) -> EventMsg {
+        EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+            call_id: call_id.to_string(),
+            process_id: Some("1000".to_string()),
+            turn_id: "turn-1".to_string(),
+            command: vec!["cargo".to_string(), "test".to_string()],
+            cwd: PathBuf::from("/repo"),
+            parsed_cmd: Vec::new(),
+            source: ExecCommandSource::Agent,
+            interaction_input: None,
+        })
+    }
+
+    #[test]
+    fn approvals_become_running_commands_and_clear_at_turn_end() {
+        let mut state = TrackedState::default();
+        assert!(state.apply(&approval_request("call-1")));
+        assert_eq!(state.pending_approvals.len(), 1);
+
+        assert!(state.apply(&command_begin("call-1")));
+        assert_eq!(state.pending_approvals, Vec::new());
+        assert_eq!(state.running_commands.len(), 1);
+        assert!(!state.apply(&command_begin("call-1")));
+
+        assert!(state.apply(&EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message: None,
+            metadata: None,
+        })));
+        assert_eq!(state, TrackedState::default());
+    }
+
+    #[test]
+    fn answered_approvals_are_dropped_by_turn() {
+        let mut state = TrackedState::default();
+        state.apply(&approval_request("call-1"));
+        assert!(!state.answered("turn-2"));
+        assert!(state.answered("turn-1"));
+        assert_eq!(state.pending_approvals, Vec::new());
+    }
+
+    #[test]
+    fn interrupted_work_lists_commands_and_prompts() {
+        let mut state = TrackedState::default();
+        state.apply(&command_begin("call-1"));
+        state.apply(&approval_request("call-2"));
+        let item = RuntimeStateItem {
+            running_commands: state.running_commands,
+            pending_approvals: state.pending_approvals,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_interrupted_work(&item).as_deref(),
+            Some(
+                "<interrupted_work>\n\
+                 The previous session ended in the middle of a turn.\n\
+                 These commands were still running and were stopped; their remaining output is lost:\n\
+                 - `cargo test` (in /repo, process 1000 no longer exists)\n\
+                 These approval requests were never answered, so the calls did not run:\n\
+                 - command cargo test\n\
+                 Check the state of the workspace before continuing, and run again whatever is still needed.\n\
+                 </interrupted_work>"
+            )
+        );
+        assert_eq!(format_interrupted_work(&RuntimeStateItem::default()), None);
+    }
+}
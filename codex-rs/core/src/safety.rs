@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
 
+use crate::config::types::ContainerSandboxConfig;
 use crate::exec::SandboxType;
 use crate::util::resolve_path;
 
@@ -122,7 +123,22 @@ pub fn assess_patch_safety(
     }
 }
 
+/// Selects the container backend for sandboxed commands; `None` restores the
+/// platform sandbox.
+pub fn set_container_sandbox(config: Option<ContainerSandboxConfig>) {
+    crate::container_sandbox::set_container_sandbox(config);
+}
+
 pub fn get_platform_sandbox() -> Option<SandboxType> {
+    if crate::container_sandbox::container_sandbox().is_some() {
+        return Some(SandboxType::Container);
+    }
+    get_host_sandbox()
+}
+
+/// The OS sandbox, ignoring the container backend. Used for work that has to
+/// run the host's own binaries, such as `apply_patch`.
+pub(crate) fn get_host_sandbox() -> Option<SandboxType> {
     if cfg!(target_os = "macos") {
         Some(SandboxType::MacosSeatbelt)
     } else if cfg!(target_os = "linux") {
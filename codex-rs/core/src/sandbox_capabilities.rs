@@ -0,0 +1,176 @@
+//! Reports what the platform sandbox can enforce on this machine.
+//!
+//! The sandbox policy says what commands *should* be limited to; whether that
+//! holds depends on the backend: Seatbelt on macOS, Landlock + seccomp on
+//! Linux, and on Windows either the restricted-token sandbox or, after the
+//! elevated setup, a dedicated sandbox user with firewall rules. The container
+//! backend replaces all of them when configured. The result is shown by
+//! `codex sandbox status` and attached to exec approval requests so users know
+//! what "run in the sandbox" actually protects.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::SandboxBackend;
+use codex_protocol::protocol::SandboxCapabilities;
+use codex_protocol::protocol::SandboxPolicy;
+
+use crate::container_sandbox::container_sandbox;
+use crate::exec::SandboxType;
+use crate::safety::get_platform_sandbox;
+use crate::safety::is_windows_elevated_sandbox_enabled;
+use crate::windows_sandbox::sandbox_setup_is_complete;
+
+/// Lists the active Linux security modules.
+const LINUX_LSM_PATH: &str = "/sys/kernel/security/lsm";
+
+/// Detects the sandbox backend commands would run under and what it enforces.
+pub fn detect_sandbox_capabilities(
+    codex_home: &Path,
+    codex_linux_sandbox_exe: Option<&PathBuf>,
+) -> SandboxCapabilities {
+    match get_platform_sandbox() {
+        Some(SandboxType::MacosSeatbelt) => enforcing(SandboxBackend::MacosSeatbelt, Vec::new()),
+        Some(SandboxType::LinuxSeccomp) => linux_capabilities(
+            codex_linux_sandbox_exe.is_some(),
+            std::fs::read_to_string(LINUX_LSM_PATH).ok().as_deref(),
+        ),
+        Some(SandboxType::Container) => container_capabilities(
+            container_sandbox()
+                .map(|config| config.runtime.program())
+                .is_some_and(|program| which::which(program).is_ok()),
+        ),
+        Some(SandboxType::WindowsRestrictedToken) => windows_capabilities(
+            is_windows_elevated_sandbox_enabled(),
+            sandbox_setup_is_complete(codex_home),
+        ),
+        Some(SandboxType::None) | None if cfg!(target_os = "windows") => unsandboxed(
+            "The Windows sandbox is disabled, so `workspace-write` falls back to `read-only` \
+             and every write needs approval. Enable `experimental_windows_sandbox` under \
+             [features]."
+                .to_string(),
+        ),
+        Some(SandboxType::None) | None => {
+            unsandboxed("No platform sandbox is available on this OS.".to_string())
+        }
+    }
+}
+
+/// Whether commands under `policy` are run inside the platform sandbox.
+pub fn policy_uses_sandbox(policy: &SandboxPolicy) -> bool {
+    !matches!(
+        policy,
+        SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. }
+    )
+}
+
+fn linux_capabilities(has_helper: bool, lsm: Option<&str>) -> SandboxCapabilities {
+    let mut warnings = Vec::new();
+    if !has_helper {
+        warnings.push(
+            "The `codex-linux-sandbox` helper was not found; sandboxed commands will fail."
+                .to_string(),
+        );
+    }
+    if let Some(lsm) = lsm
+        && !lsm.trim().split(',').any(|module| module == "landlock")
+    {
+        warnings.push(format!(
+            "Landlock is not enabled in this kernel ({LINUX_LSM_PATH}); sandboxed commands will fail."
+        ));
+    }
+    enforcing(SandboxBackend::LinuxLandlock, warnings)
+}
+
+fn container_capabilities(runtime_found: bool) -> SandboxCapabilities {
+    let warnings = if runtime_found {
+        Vec::new()
+    } else {
+        vec![
+            "The container runtime was not found on PATH; sandboxed commands will fail."
+                .to_string(),
+        ]
+    };
+    enforcing(SandboxBackend::Container, warnings)
+}
+
+fn windows_capabilities(elevated: bool, setup_complete: bool) -> SandboxCapabilities {
+    if !elevated {
+        return SandboxCapabilities {
+            backend: SandboxBackend::WindowsRestrictedToken,
+            enforces_writable_roots: true,
+            enforces_network: false,
+            warnings: vec![
+                "Network access is only disabled through proxy environment variables. Enable \
+                 `elevated_windows_sandbox` to block it with firewall rules."
+                    .to_string(),
+            ],
+        };
+    }
+    let warnings = if setup_complete {
+        Vec::new()
+    } else {
+        vec![
+            "The elevated sandbox setup has not completed; sandboxed commands fail until it \
+             runs."
+                .to_string(),
+        ]
+    };
+    enforcing(SandboxBackend::WindowsElevated, warnings)
+}
+
+fn enforcing(backend: SandboxBackend, warnings: Vec<String>) -> SandboxCapabilities {
+    SandboxCapabilities {
+        backend,
+        enforces_writable_roots: true,
+        enforces_network: true,
+        warnings,
+    }
+}
+
+fn unsandboxed(warning: String) -> SandboxCapabilities {
+    SandboxCapabilities {
+        backend: SandboxBackend::None,
+        enforces_writable_roots: false,
+        enforces_network: false,
+        warnings: vec![warning],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn linux_warns_about_missing_helper_and_landlock() {
+        assert_eq!(
+            linux_capabilities(true, Some("lockdown,capability,landlock,yama\n")),
+            enforcing(SandboxBackend::LinuxLandlock, Vec::new())
+        );
+        assert_eq!(
+            linux_capabilities(true, None),
+            enforcing(SandboxBackend::LinuxLandlock, Vec::new())
+        );
+        assert_eq!(
+            linux_capabilities(false, Some("capability,apparmor"))
+                .warnings
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn windows_network_needs_elevated_backend() {
+        let restricted = windows_capabilities(false, true);
+        assert_eq!(restricted.backend, SandboxBackend::WindowsRestrictedToken);
+        assert!(restricted.enforces_writable_roots);
+        assert!(!restricted.enforces_network);
+
+        assert_eq!(
+            windows_capabilities(true, true),
+            enforcing(SandboxBackend::WindowsElevated, Vec::new())
+        );
+        assert_eq!(windows_capabilities(true, false).warnings.len(), 1);
+    }
+}
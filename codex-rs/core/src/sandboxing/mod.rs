@@ -6,12 +6,17 @@ sandbox placement and transformation of portable CommandSpec into a
 ready‑to‑spawn environment.
 */
 
+use crate::config::types::ExecLimits;
+use crate::container_sandbox::container_sandbox;
+use crate::container_sandbox::create_container_command_args;
 use crate::exec::ExecExpiration;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
 use crate::exec::execute_exec_env;
 use crate::landlock::create_linux_sandbox_command_args;
+#[cfg(target_os = "macos")]
+use crate::network_capture::is_filtering_proxy_port;
 use crate::protocol::SandboxPolicy;
 #[cfg(target_os = "macos")]
 use crate::seatbelt::MACOS_PATH_TO_SEATBELT_EXECUTABLE;
@@ -20,6 +25,8 @@ use crate::seatbelt::create_seatbelt_command_args;
 #[cfg(target_os = "macos")]
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use crate::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+#[cfg(target_os = "macos")]
+use crate::spawn::CODEX_SANDBOX_NETWORK_PROXY_PORT_ENV_VAR;
 use crate::tools::sandboxing::SandboxablePreference;
 pub use codex_protocol::models::SandboxPermissions;
 use std::collections::HashMap;
@@ -47,6 +54,7 @@ pub struct ExecEnv {
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
     pub arg0: Option<String>,
+    pub limits: ExecLimits,
 }
 
 pub enum SandboxPreference {
@@ -59,6 +67,8 @@ pub enum SandboxPreference {
 pub(crate) enum SandboxTransformError {
     #[error("missing codex-linux-sandbox executable path")]
     MissingLinuxSandboxExecutable,
+    #[error("container sandbox selected without `[sandbox.container]` settings")]
+    MissingContainerSandboxConfig,
     #[cfg(not(target_os = "macos"))]
     #[error("seatbelt sandbox is only available on macOS")]
     SeatbeltUnavailable,
@@ -108,6 +118,16 @@ impl SandboxManager {
                 "1".to_string(),
             );
         }
+        // Set by the session's allowlist proxy; the sandbox admits only that
+        // port, and only if a filtering proxy of this process listens on it.
+        #[cfg(target_os = "macos")]
+        let network_proxy_port = if policy.has_full_network_access() {
+            None
+        } else {
+            env.get(CODEX_SANDBOX_NETWORK_PROXY_PORT_ENV_VAR)
+                .and_then(|port| port.parse::<u16>().ok())
+                .filter(|port| is_filtering_proxy_port(*port))
+        };
 
         let mut command = Vec::with_capacity(1 + spec.args.len());
         command.push(spec.program);
@@ -119,8 +139,12 @@ impl SandboxManager {
             SandboxType::MacosSeatbelt => {
                 let mut seatbelt_env = HashMap::new();
                 seatbelt_env.insert(CODEX_SANDBOX_ENV_VAR.to_string(), "seatbelt".to_string());
-                let mut args =
-                    create_seatbelt_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let mut args = create_seatbelt_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    network_proxy_port,
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(MACOS_PATH_TO_SEATBELT_EXECUTABLE.to_string());
                 full_command.append(&mut args);
@@ -131,6 +155,9 @@ impl SandboxManager {
             SandboxType::LinuxSeccomp => {
                 let exe = codex_linux_sandbox_exe
                     .ok_or(SandboxTransformError::MissingLinuxSandboxExecutable)?;
+                // The allowlist proxy is not reachable on Linux: Landlock can
+                // only restrict the port, not the address, so the command
+                // keeps no network access at all.
                 let mut args =
                     create_linux_sandbox_command_args(command.clone(), policy, sandbox_policy_cwd);
                 let mut full_command = Vec::with_capacity(1 + args.len());
@@ -150,6 +177,19 @@ impl SandboxManager {
             // When building for non-Windows targets, this variant is never constructed.
             #[cfg(not(target_os = "windows"))]
             SandboxType::WindowsRestrictedToken => (command, HashMap::new(), None),
+            SandboxType::Container => {
+                let config = container_sandbox()
+                    .ok_or(SandboxTransformError::MissingContainerSandboxConfig)?;
+                let full_command = create_container_command_args(
+                    &config,
+                    command,
+                    &spec.cwd,
+                    policy,
+                    sandbox_policy_cwd,
+                    &env,
+                );
+                (full_command, HashMap::new(), None)
+            }
         };
 
         env.extend(sandbox_env);
@@ -163,6 +203,7 @@ impl SandboxManager {
             sandbox_permissions: spec.sandbox_permissions,
             justification: spec.justification,
             arg0: arg0_override,
+            limits: ExecLimits::default(),
         })
     }
 
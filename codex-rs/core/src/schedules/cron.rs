@@ -0,0 +1,219 @@
+//! Minimal five-field cron expressions (`minute hour day-of-month month day-of-week`).
+//!
+//! Each field accepts `*`, numbers, ranges (`1-5`), steps (`*/15`, `0-30/10`),
+//! and comma-separated lists of those. Day-of-week uses `0`–`6` with Sunday as
+//! `0` (`7` is also accepted for Sunday). The `@hourly`, `@daily`/`@nightly`,
+//! `@weekly`, and `@monthly` shorthands are supported as well.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::TimeZone;
+use chrono::Timelike;
+
+/// Upper bound on how far ahead [`CronExpr::next_after`] searches. Covers every
+/// satisfiable expression (e.g. `0 0 29 2 *` fires within four years).
+const MAX_SEARCH_MINUTES: i64 = 5 * 366 * 24 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    source: String,
+    minutes: BTreeSet<u32>,
+    hours: BTreeSet<u32>,
+    days_of_month: BTreeSet<u32>,
+    months: BTreeSet<u32>,
+    days_of_week: BTreeSet<u32>,
+    /// Cron's quirk: when both day fields are restricted, a day matches if
+    /// *either* matches.
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid cron expression `{expr}`: {message}")]
+pub struct CronParseError {
+    expr: String,
+    message: String,
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<Self, CronParseError> {
+        let trimmed = expr.trim();
+        let expanded = match trimmed {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@nightly" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let error = |message: String| CronParseError {
+            expr: expr.to_string(),
+            message,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields.as_slice() else {
+            return Err(error(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            )));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7).map_err(&error)?;
+        if days_of_week.remove(&7) {
+            days_of_week.insert(0);
+        }
+
+        Ok(Self {
+            source: trimmed.to_string(),
+            minutes: parse_field(minute, 0, 59).map_err(&error)?,
+            hours: parse_field(hour, 0, 23).map_err(&error)?,
+            days_of_month: parse_field(day_of_month, 1, 31).map_err(&error)?,
+            months: parse_field(month, 1, 12).map_err(&error)?,
+            days_of_week,
+            day_of_month_restricted: *day_of_month != "*",
+            day_of_week_restricted: *day_of_week != "*",
+        })
+    }
+
+    /// Returns the first minute strictly after `after` that matches the
+    /// expression, or `None` if nothing matches within the search window.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let mut candidate = after
+            .clone()
+            .with_second(0)?
+            .with_nanosecond(0)?
+            .checked_add_signed(Duration::minutes(1))?;
+        for _ in 0..MAX_SEARCH_MINUTES {
+            if self.matches(&candidate) {
+                return Some(candidate);
+            }
+            candidate = candidate.checked_add_signed(Duration::minutes(1))?;
+        }
+        None
+    }
+
+    fn matches<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        if !self.minutes.contains(&time.minute())
+            || !self.hours.contains(&time.hour())
+            || !self.months.contains(&time.month())
+        {
+            return false;
+        }
+        let dom = self.days_of_month.contains(&time.day());
+        let dow = self
+            .days_of_week
+            .contains(&time.weekday().num_days_from_sunday());
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+}
+
+impl fmt::Display for CronExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<BTreeSet<u32>, String> {
+    let mut values = BTreeSet::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("invalid step `{step}`"))?;
+                if step == 0 {
+                    return Err("step must be greater than 0".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_value(start, min, max)?, parse_value(end, min, max)?)
+        } else {
+            let value = parse_value(range, min, max)?;
+            // `5/15` means "from 5 to the end, every 15".
+            (value, if step > 1 { max } else { value })
+        };
+        if start > end {
+            return Err(format!("range `{range}` is reversed"));
+        }
+        values.extend((start..=end).step_by(step as usize));
+    }
+    Ok(values)
+}
+
+fn parse_value(value: &str, min: u32, max: u32) -> Result<u32, String> {
+    let parsed: u32 = value
+        .parse()
+        .map_err(|_| format!("invalid value `{value}`"))?;
+    if parsed < min || parsed > max {
+        return Err(format!("value {parsed} is outside {min}-{max}"));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use pretty_assertions::assert_eq;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s)
+            .expect("valid timestamp")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn nightly_fires_at_next_midnight() {
+        let expr = CronExpr::parse("@nightly").expect("parse");
+        assert_eq!(
+            expr.next_after(&at("2026-03-04T13:45:10Z")),
+            Some(at("2026-03-05T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn steps_and_lists() {
+        let expr = CronExpr::parse("*/20 9,17 * * 1-5").expect("parse");
+        // Friday 17:45 -> next is Monday 09:00.
+        assert_eq!(
+            expr.next_after(&at("2026-03-06T17:45:00Z")),
+            Some(at("2026-03-09T09:00:00Z"))
+        );
+        assert_eq!(
+            expr.next_after(&at("2026-03-09T09:00:00Z")),
+            Some(at("2026-03-09T09:20:00Z"))
+        );
+    }
+
+    #[test]
+    fn day_fields_are_ored_when_both_restricted() {
+        // Fires on Sundays and on the 10th: 2026-03-08 is a Sunday, the 10th a Tuesday.
+        let expr = CronExpr::parse("0 12 10 * 0").expect("parse");
+        assert_eq!(
+            expr.next_after(&at("2026-03-02T00:00:00Z")),
+            Some(at("2026-03-08T12:00:00Z"))
+        );
+        assert_eq!(
+            expr.next_after(&at("2026-03-08T12:00:00Z")),
+            Some(at("2026-03-10T12:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert!(CronExpr::parse("* * * *").is_err());
+        assert!(CronExpr::parse("60 * * * *").is_err());
+        assert!(CronExpr::parse("*/0 * * * *").is_err());
+        assert!(CronExpr::parse("5-1 * * * *").is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! Scheduled prompts and subagent runs.
+//!
+//! Each schedule is stored as `$CODEX_HOME/schedules/<id>.toml` and names a
+//! cron expression, a working directory, and either a prompt or a subagent to
+//! run there. `codex schedule run` evaluates the expressions in local time and
+//! starts due schedules as headless threads (see [`run_schedule`]); every run
+//! is recorded as a regular rollout and reported through the `notify` hook.
+
+mod cron;
+mod runner;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Local;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+
+pub use cron::CronExpr;
+pub use cron::CronParseError;
+pub use runner::ScheduleRunOutcome;
+pub use runner::run_schedule;
+pub(crate) use runner::wait_for_turn;
+
+pub const SCHEDULES_SUBDIR: &str = "schedules";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub id: String,
+    /// Five-field cron expression (or `@daily`-style shorthand), in local time.
+    pub cron: String,
+    pub prompt: String,
+    /// Subagent definition to run with `prompt`; a plain turn when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subagent: Option<String>,
+    pub cwd: PathBuf,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Thread that recorded the most recent run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_thread_id: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleError {
+    #[error(transparent)]
+    Cron(#[from] CronParseError),
+    #[error("invalid schedule id `{0}`: use letters, digits, `-`, and `_`")]
+    InvalidId(String),
+    #[error("schedule `{0}` already exists")]
+    AlreadyExists(String),
+    #[error("schedule `{0}` not found")]
+    NotFound(String),
+    #[error("failed to parse {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error(transparent)]
+    Serialize(#[from] toml::ser::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl Schedule {
+    pub fn cron_expr(&self) -> Result<CronExpr, CronParseError> {
+        CronExpr::parse(&self.cron)
+    }
+
+    /// Next time this schedule should fire after its last run (or creation).
+    pub fn next_run_at(&self) -> Option<DateTime<Local>> {
+        let since = self.last_run_at.unwrap_or(self.created_at);
+        self.cron_expr()
+            .ok()?
+            .next_after(&since.with_timezone(&Local))
+    }
+
+    /// Whether a run is due at `now`. Missed runs collapse into a single run.
+    pub fn is_due(&self, now: DateTime<Local>) -> bool {
+        self.next_run_at().is_some_and(|next| next <= now)
+    }
+}
+
+pub fn schedules_dir(codex_home: &Path) -> PathBuf {
+    codex_home.join(SCHEDULES_SUBDIR)
+}
+
+fn schedule_path(codex_home: &Path, id: &str) -> PathBuf {
+    schedules_dir(codex_home).join(format!("{id}.toml"))
+}
+
+fn validate_id(id: &str) -> Result<(), ScheduleError> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ScheduleError::InvalidId(id.to_string()))
+    }
+}
+
+/// Loads every schedule under `$CODEX_HOME/schedules`, sorted by id.
+pub fn load_schedules(codex_home: &Path) -> Result<Vec<Schedule>, ScheduleError> {
+    let dir = schedules_dir(codex_home);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut schedules = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("toml") {
+            continue;
+        }
+        schedules.push(read_schedule(&path)?);
+    }
+    schedules.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(schedules)
+}
+
+pub fn load_schedule(codex_home: &Path, id: &str) -> Result<Schedule, ScheduleError> {
+    validate_id(id)?;
+    let path = schedule_path(codex_home, id);
+    if !path.exists() {
+        return Err(ScheduleError::NotFound(id.to_string()));
+    }
+    read_schedule(&path)
+}
+
+fn read_schedule(path: &Path) -> Result<Schedule, ScheduleError> {
+    let contents = fs::read_to_string(path)?;
+    toml::from_str(&contents).map_err(|source| ScheduleError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Writes a new schedule, refusing to overwrite an existing id.
+pub fn add_schedule(codex_home: &Path, schedule: &Schedule) -> Result<PathBuf, ScheduleError> {
+    validate_id(&schedule.id)?;
+    schedule.cron_expr()?;
+    if schedule_path(codex_home, &schedule.id).exists() {
+        return Err(ScheduleError::AlreadyExists(schedule.id.clone()));
+    }
+    save_schedule(codex_home, schedule)
+}
+
+/// Writes `schedule`, replacing any existing file with the same id.
+pub fn save_schedule(codex_home: &Path, schedule: &Schedule) -> Result<PathBuf, ScheduleError> {
+    validate_id(&schedule.id)?;
+    fs::create_dir_all(schedules_dir(codex_home))?;
+    let path = schedule_path(codex_home, &schedule.id);
+    fs::write(&path, toml::to_string_pretty(schedule)?)?;
+    Ok(path)
+}
+
+pub fn remove_schedule(codex_home: &Path, id: &str) -> Result<(), ScheduleError> {
+    validate_id(id)?;
+    match fs::remove_file(schedule_path(codex_home, id)) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            Err(ScheduleError::NotFound(id.to_string()))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn schedule(id: &str, cron: &str) -> Schedule {
+        Schedule {
+            id: id.to_string(),
+            cron: cron.to_string(),
+            prompt: "update the CHANGELOG draft".to_string(),
+            subagent: None,
+            cwd: PathBuf::from("/work/app"),
+            created_at: Utc::now(),
+            last_run_at: None,
+            last_thread_id: None,
+        }
+    }
+
+    #[test]
+    fn add_list_remove_roundtrip() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let nightly = schedule("nightly", "@nightly");
+        add_schedule(codex_home.path(), &nightly)?;
+        add_schedule(codex_home.path(), &schedule("hourly", "0 * * * *"))?;
+
+        let ids: Vec<String> = load_schedules(codex_home.path())?
+            .into_iter()
+            .map(|schedule| schedule.id)
+            .collect();
+        assert_eq!(ids, vec!["hourly".to_string(), "nightly".to_string()]);
+        assert_eq!(load_schedule(codex_home.path(), "nightly")?, nightly);
+
+        assert!(matches!(
+            add_schedule(codex_home.path(), &nightly),
+            Err(ScheduleError::AlreadyExists(_))
+        ));
+        remove_schedule(codex_home.path(), "nightly")?;
+        assert!(matches!(
+            remove_schedule(codex_home.path(), "nightly"),
+            Err(ScheduleError::NotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_ids_and_cron() {
+        let codex_home = TempDir::new().expect("tempdir");
+        assert!(matches!(
+            add_schedule(codex_home.path(), &schedule("../escape", "@daily")),
+            Err(ScheduleError::InvalidId(_))
+        ));
+        assert!(matches!(
+            add_schedule(codex_home.path(), &schedule("bad", "every day")),
+            Err(ScheduleError::Cron(_))
+        ));
+    }
+
+    #[test]
+    fn due_after_next_fire_time_passes() {
+        let mut hourly = schedule("hourly", "@hourly");
+        hourly.created_at = Utc::now() - chrono::Duration::hours(2);
+        assert!(hourly.is_due(Local::now()));
+
+        hourly.last_run_at = Some(Utc::now());
+        assert!(!hourly.is_due(Local::now()));
+    }
+}
@@ -0,0 +1,118 @@
+//! Runs a single schedule as a headless thread.
+
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::AgentStatus;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::Op;
+use codex_protocol::user_input::UserInput;
+
+use super::Schedule;
+use crate::CodexThread;
+use crate::NewThread;
+use crate::ThreadManager;
+use crate::config::Config;
+use crate::error::Result as CodexResult;
+use crate::user_notification::UserNotification;
+use crate::user_notification::UserNotifier;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleRunOutcome {
+    /// Thread whose rollout records the run.
+    pub thread_id: ThreadId,
+    pub last_agent_message: Option<String>,
+    /// Set when the turn or subagent failed.
+    pub error: Option<String>,
+}
+
+/// Starts a thread with `config` (whose cwd should be the schedule's), runs
+/// the schedule's prompt or subagent to completion, shuts the thread down,
+/// and reports the result through the `notify` hook.
+///
+/// `config` is expected to disable approvals: nobody is around to answer them.
+pub async fn run_schedule(
+    thread_manager: &ThreadManager,
+    config: Config,
+    schedule: &Schedule,
+) -> CodexResult<ScheduleRunOutcome> {
+    let notify = config.notify.clone();
+    let NewThread {
+        thread_id, thread, ..
+    } = thread_manager.start_thread(config).await?;
+
+    let (last_agent_message, error) = match &schedule.subagent {
+        Some(name) => {
+            thread
+                .submit(Op::RunSubAgent {
+                    name: name.clone(),
+                    prompt: schedule.prompt.clone(),
+                    dry_run: false,
+                })
+                .await?;
+            wait_for_subagent(&thread).await?
+        }
+        None => {
+            thread
+                .submit(Op::UserInput {
+                    items: vec![UserInput::Text {
+                        text: schedule.prompt.clone(),
+                        text_elements: Vec::new(),
+                    }],
+                    final_output_json_schema: None,
+                })
+                .await?;
+            wait_for_turn(&thread).await?
+        }
+    };
+
+    thread.submit(Op::Shutdown).await?;
+    while !matches!(thread.next_event().await?.msg, EventMsg::ShutdownComplete) {}
+    thread_manager.remove_thread(&thread_id).await;
+
+    UserNotifier::new(notify).notify(&UserNotification::ScheduledRunComplete {
+        schedule_id: schedule.id.clone(),
+        thread_id: thread_id.to_string(),
+        cwd: schedule.cwd.display().to_string(),
+        last_assistant_message: last_agent_message.clone(),
+        error: error.clone(),
+    });
+
+    Ok(ScheduleRunOutcome {
+        thread_id,
+        last_agent_message,
+        error,
+    })
+}
+
+pub(crate) async fn wait_for_turn(
+    thread: &CodexThread,
+) -> CodexResult<(Option<String>, Option<String>)> {
+    let mut error = None;
+    loop {
+        match thread.next_event().await?.msg {
+            EventMsg::Error(event) => error = Some(event.message),
+            EventMsg::TurnComplete(event) => return Ok((event.last_agent_message, error)),
+            EventMsg::TurnAborted(_) => {
+                return Ok((None, error.or_else(|| Some("turn aborted".to_string()))));
+            }
+            _ => {}
+        }
+    }
+}
+
+async fn wait_for_subagent(thread: &CodexThread) -> CodexResult<(Option<String>, Option<String>)> {
+    loop {
+        match thread.next_event().await?.msg {
+            // `Op::RunSubAgent` reports setup failures (e.g. an unknown name)
+            // as an error without a run.
+            EventMsg::Error(event) => return Ok((None, Some(event.message))),
+            EventMsg::SubAgentRunEnd(event) => {
+                return Ok(match event.status {
+                    AgentStatus::Completed(message) => (message, None),
+                    AgentStatus::Errored(message) => (None, Some(message)),
+                    status => (None, Some(format!("subagent ended as {status:?}"))),
+                });
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Splits files into line ranges that are embedded separately.
+
+/// Chunks end at the first blank line once they reach this many lines, so
+/// they tend to follow function and paragraph boundaries.
+const MIN_CHUNK_LINES: usize = 20;
+
+/// Hard cap for chunks that never hit a blank line.
+const MAX_CHUNK_LINES: usize = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Chunk {
+    /// 1-based, inclusive.
+    pub start_line: usize,
+    /// 1-based, inclusive.
+    pub end_line: usize,
+    pub text: String,
+}
+
+pub(crate) fn chunk_lines(contents: &str) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut start_line = 1;
+    for (idx, line) in contents.lines().enumerate() {
+        if current.is_empty() {
+            start_line = idx + 1;
+        }
+        current.push(line);
+        let at_boundary = current.len() >= MIN_CHUNK_LINES && line.trim().is_empty();
+        if at_boundary || current.len() >= MAX_CHUNK_LINES {
+            push_chunk(&mut chunks, start_line, &current);
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        push_chunk(&mut chunks, start_line, &current);
+    }
+    chunks
+}
+
+fn push_chunk(chunks: &mut Vec<Chunk>, start_line: usize, lines: &[&str]) {
+    if lines.iter().all(|line| line.trim().is_empty()) {
+        return;
+    }
+    chunks.push(Chunk {
+        start_line,
+        end_line: start_line + lines.len() - 1,
+        text: lines.join("\n"),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn chunks_break_at_blank_lines_after_the_minimum() {
+        let mut contents = String::new();
+        for i in 1..=25 {
+            contents.push_str(&format!("line {i}\n"));
+        }
+        contents.push('\n');
+        contents.push_str("tail\n");
+
+        let chunks = chunk_lines(&contents);
+        let ranges: Vec<(usize, usize)> = chunks
+            .iter()
+            .map(|chunk| (chunk.start_line, chunk.end_line))
+            .collect();
+        assert_eq!(ranges, vec![(1, 26), (27, 27)]);
+        assert_eq!(chunks[1].text, "tail");
+    }
+
+    #[test]
+    fn long_runs_are_capped_and_blank_chunks_dropped() {
+        let contents = "x\n".repeat(MAX_CHUNK_LINES + 5);
+        let ranges: Vec<(usize, usize)> = chunk_lines(&contents)
+            .iter()
+            .map(|chunk| (chunk.start_line, chunk.end_line))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![(1, MAX_CHUNK_LINES), (MAX_CHUNK_LINES + 1, 65)]
+        );
+        assert_eq!(chunk_lines("\n\n  \n"), Vec::new());
+    }
+}
@@ -0,0 +1,126 @@
+//! Local text embeddings for the workspace index.
+//!
+//! Vectors are built with feature hashing over identifier-aware tokens:
+//! `parseConfigFile` and `parse_config_file` both contribute `parse`,
+//! `config`, and `file`, and character trigrams of each token let
+//! `parser` and `parsing` land near each other. This needs no model or
+//! network access and is deterministic, so indexes can be updated
+//! incrementally across runs.
+
+/// Dimensions of every embedding; changing it invalidates stored indexes.
+pub(crate) const DIMS: usize = 256;
+
+/// Weight of a character trigram relative to a whole token.
+const TRIGRAM_WEIGHT: f32 = 0.3;
+
+/// Words that appear in almost every chunk and carry no meaning on their own.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "as", "be", "by", "const", "def", "else", "fn", "for", "from", "if",
+    "import", "in", "is", "it", "let", "mut", "not", "of", "or", "pub", "return", "self", "the",
+    "this", "to", "use", "var",
+];
+
+/// Embeds `text` as an L2-normalized vector of `DIMS` components.
+pub(crate) fn embed(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; DIMS];
+    for token in tokens(text) {
+        add_feature(&mut vector, &token, 1.0);
+        let chars: Vec<char> = token.chars().collect();
+        if chars.len() > 3 {
+            for window in chars.windows(3) {
+                let trigram: String = window.iter().collect();
+                add_feature(&mut vector, &format!("#{trigram}"), TRIGRAM_WEIGHT);
+            }
+        }
+    }
+    // Dampen repeated terms so one long identifier list cannot dominate.
+    for value in &mut vector {
+        *value = value.signum() * value.abs().ln_1p();
+    }
+    normalize(&mut vector);
+    vector
+}
+
+/// Cosine similarity of two normalized vectors.
+pub(crate) fn similarity(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Lowercased words of `text`, split at non-alphanumeric characters and at
+/// camelCase boundaries.
+fn tokens(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .flat_map(split_camel_case)
+        .map(|word| word.to_lowercase())
+        .filter(|word| word.chars().count() > 1 && !STOPWORDS.contains(&word.as_str()))
+}
+
+fn split_camel_case(word: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let chars: Vec<(usize, char)> = word.char_indices().collect();
+    for pair in chars.windows(2) {
+        let (_, prev) = pair[0];
+        let (idx, next) = pair[1];
+        if prev.is_lowercase() && next.is_uppercase() {
+            parts.push(&word[start..idx]);
+            start = idx;
+        }
+    }
+    parts.push(&word[start..]);
+    parts
+}
+
+/// Signed feature hashing: the sign bit keeps colliding features from only
+/// ever adding up.
+fn add_feature(vector: &mut [f32], feature: &str, weight: f32) {
+    let hash = fnv1a(feature.as_bytes());
+    let bucket = (hash % DIMS as u64) as usize;
+    let sign = if (hash >> 63) == 0 { 1.0 } else { -1.0 };
+    vector[bucket] += sign * weight;
+}
+
+/// FNV-1a, chosen because it is stable across Rust versions and platforms.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+pub(super) fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector {
+            *value /= norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tokens_split_identifiers() {
+        let tokens: Vec<String> = tokens("fn parseConfigFile(path: &Path) -> load_toml").collect();
+        assert_eq!(
+            tokens,
+            vec!["parse", "config", "file", "path", "path", "load", "toml"]
+        );
+    }
+
+    #[test]
+    fn related_code_scores_higher_than_unrelated_code() {
+        let query = embed("where is the config file parsed");
+        let related = embed("pub fn parse_config_file(path: &Path) -> Result<ConfigToml> {");
+        let unrelated = embed("fn render_status_bar(frame: &mut Frame, width: u16) {");
+
+        assert!(similarity(&query, &related) > similarity(&query, &unrelated));
+        assert!((similarity(&related, &related) - 1.0).abs() < 1e-5);
+        assert!(embed("").iter().all(|v| *v == 0.0));
+    }
+}
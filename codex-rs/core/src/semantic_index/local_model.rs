@@ -0,0 +1,296 @@
+//! Embeddings from a local model served by `[semantic_index].command`.
+//!
+//! The command starts on first use and keeps running until the index update
+//! or search is done, so the model loads once. Requests and responses are
+//! single JSON lines:
+//!
+//! ```text
+//! -> {"texts": ["src/config.rs\nfn parse_config() ...", ...]}
+//! <- {"embeddings": [[0.12, -0.03, ...], ...]}
+//! ```
+//!
+//! An `{"error": "..."}` line fails the update. `CODEX_EMBEDDING_MODEL` and
+//! `CODEX_EMBEDDING_DEVICE` (`cpu`, `cuda` or `metal`) tell the command which
+//! model to load and where to run it.
+
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::path::Path;
+use std::process::Child;
+use std::process::ChildStdin;
+use std::process::ChildStdout;
+use std::process::Command;
+use std::process::Stdio;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::config::types::EmbeddingDevice;
+use crate::config::types::SemanticIndexConfig;
+
+use super::embedding::normalize;
+
+pub(crate) struct LocalModel {
+    command: Vec<String>,
+    model: Option<String>,
+    device: EmbeddingDevice,
+    batch_size: usize,
+    process: Option<ModelProcess>,
+}
+
+struct ModelProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+#[derive(Serialize)]
+struct EmbedRequest<'a> {
+    texts: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbedResponse {
+    #[serde(default)]
+    embeddings: Option<Vec<Vec<f32>>>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl LocalModel {
+    pub(crate) fn new(config: &SemanticIndexConfig) -> Self {
+        Self {
+            command: config.command.clone(),
+            model: config.model.clone(),
+            device: resolve_device(config.device),
+            batch_size: config.batch_size.max(1),
+            process: None,
+        }
+    }
+
+    /// Embeds `texts` in batches, returning one L2-normalized vector per text.
+    pub(crate) fn embed(&mut self, texts: &[String]) -> io::Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            let embeddings = self.request(batch)?;
+            if embeddings.len() != batch.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "embedding command returned {} vectors for {} texts",
+                        embeddings.len(),
+                        batch.len()
+                    ),
+                ));
+            }
+            vectors.extend(embeddings);
+        }
+        Ok(vectors)
+    }
+
+    fn request(&mut self, texts: &[String]) -> io::Result<Vec<Vec<f32>>> {
+        // A process that failed a request is dropped (and killed), so the
+        // next request starts a fresh one.
+        let mut process = match self.process.take() {
+            Some(process) => process,
+            None => self.spawn()?,
+        };
+        let line = serde_json::to_string(&EmbedRequest { texts }).map_err(io::Error::other)?;
+        writeln!(process.stdin, "{line}")?;
+        process.stdin.flush()?;
+        let mut response = String::new();
+        if process.stdout.read_line(&mut response)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "embedding command exited without answering",
+            ));
+        }
+        let embeddings = parse_response(&response)?;
+        self.process = Some(process);
+        Ok(embeddings)
+    }
+
+    fn spawn(&self) -> io::Result<ModelProcess> {
+        let Some((program, args)) = self.command.split_first() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "semantic_index.command is empty",
+            ));
+        };
+        let mut command = Command::new(program);
+        command
+            .args(args)
+            .env("CODEX_EMBEDDING_DEVICE", device_name(self.device))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(model) = &self.model {
+            command.env("CODEX_EMBEDDING_MODEL", model);
+        }
+        let mut child = command.spawn().map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("failed to start embedding command `{program}`: {err}"),
+            )
+        })?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            return Err(io::Error::other("embedding command has no stdio"));
+        };
+        // Model runtimes log to stderr; keep it out of the terminal.
+        if let Some(stderr) = child.stderr.take() {
+            std::thread::spawn(move || {
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    tracing::debug!("embedding command: {line}");
+                }
+            });
+        }
+        Ok(ModelProcess {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        })
+    }
+}
+
+impl Drop for ModelProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn parse_response(line: &str) -> io::Result<Vec<Vec<f32>>> {
+    let response: EmbedResponse = serde_json::from_str(line).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid response from embedding command: {err}"),
+        )
+    })?;
+    if let Some(error) = response.error {
+        return Err(io::Error::other(format!(
+            "embedding command failed: {error}"
+        )));
+    }
+    let mut embeddings = response.embeddings.unwrap_or_default();
+    let dims = embeddings.first().map_or(0, Vec::len);
+    if embeddings
+        .iter()
+        .any(|vector| vector.is_empty() || vector.len() != dims)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "embedding command returned vectors of different or zero length",
+        ));
+    }
+    for vector in &mut embeddings {
+        normalize(vector);
+    }
+    Ok(embeddings)
+}
+
+/// Turns `auto` into the device this machine can use.
+pub(crate) fn resolve_device(device: EmbeddingDevice) -> EmbeddingDevice {
+    match device {
+        EmbeddingDevice::Auto => detect_device(
+            nvidia_driver_present(),
+            cfg!(all(target_os = "macos", target_arch = "aarch64")),
+        ),
+        device => device,
+    }
+}
+
+fn detect_device(nvidia_driver: bool, apple_silicon: bool) -> EmbeddingDevice {
+    if nvidia_driver {
+        EmbeddingDevice::Cuda
+    } else if apple_silicon {
+        EmbeddingDevice::Metal
+    } else {
+        EmbeddingDevice::Cpu
+    }
+}
+
+fn nvidia_driver_present() -> bool {
+    // `CUDA_VISIBLE_DEVICES=""` (or `-1`) is the usual way to hide GPUs.
+    if matches!(
+        std::env::var("CUDA_VISIBLE_DEVICES").as_deref(),
+        Ok("" | "-1")
+    ) {
+        return false;
+    }
+    Path::new("/proc/driver/nvidia/version").exists() || which::which("nvidia-smi").is_ok()
+}
+
+fn device_name(device: EmbeddingDevice) -> &'static str {
+    match device {
+        EmbeddingDevice::Auto => "auto",
+        EmbeddingDevice::Cpu => "cpu",
+        EmbeddingDevice::Cuda => "cuda",
+        EmbeddingDevice::Metal => "metal",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn auto_prefers_cuda_then_metal() {
+        assert_eq!(detect_device(true, true), EmbeddingDevice::Cuda);
+        assert_eq!(detect_device(false, true), EmbeddingDevice::Metal);
+        assert_eq!(detect_device(false, false), EmbeddingDevice::Cpu);
+        assert_eq!(resolve_device(EmbeddingDevice::Cpu), EmbeddingDevice::Cpu);
+    }
+
+    #[test]
+    fn responses_are_normalized_and_validated() {
+        assert_eq!(
+            parse_response(r#"{"embeddings": [[3.0, 4.0]]}"#).expect("parse"),
+            vec![vec![0.6, 0.8]]
+        );
+        assert!(parse_response(r#"{"embeddings": [[1.0], [1.0, 2.0]]}"#).is_err());
+        let err = parse_response(r#"{"error": "out of memory"}"#).expect_err("error");
+        assert_eq!(err.to_string(), "embedding command failed: out of memory");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn keeps_one_process_across_batches() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let log = dir.path().join("starts");
+        let script = format!(
+            r#"echo "start $CODEX_EMBEDDING_DEVICE $CODEX_EMBEDDING_MODEL" >> '{}'
+while read -r line; do echo '{{"embeddings": [[2, 0], [0, 1]]}}'; done"#,
+            log.display()
+        );
+        let config = SemanticIndexConfig {
+            command: vec!["sh".to_string(), "-c".to_string(), script],
+            model: Some("bge-small".to_string()),
+            device: EmbeddingDevice::Cpu,
+            batch_size: 2,
+            ..Default::default()
+        };
+        let mut model = LocalModel::new(&config);
+        let texts: Vec<String> = ["a", "b", "c", "d"].map(String::from).to_vec();
+
+        let vectors = model.embed(&texts).expect("embed");
+
+        assert_eq!(
+            vectors,
+            vec![
+                vec![1.0, 0.0],
+                vec![0.0, 1.0],
+                vec![1.0, 0.0],
+                vec![0.0, 1.0]
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&log).expect("log"),
+            "start cpu bge-small\n"
+        );
+        assert!(model.embed(&texts[..1]).is_err());
+    }
+}
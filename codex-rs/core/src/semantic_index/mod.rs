@@ -0,0 +1,575 @@
+//! Opt-in local index of the workspace for `semantic_search`.
+//!
+//! Files are split into line-range chunks, each chunk is embedded locally
+//! (by feature hashing, see [`embedding`], or by a local model, see
+//! [`local_model`]), and the vectors are stored in `.codex/index/` at the
+//! project root (the git root, or the working directory outside a
+//! repository). The index is updated incrementally: only files whose size
+//! or modification time changed since they were last indexed are
+//! re-embedded, and files that disappeared are dropped. Searches refresh the
+//! index first, so results always reflect the files on disk.
+//!
+//! The indexed files are the ones [`crate::workspace_files::list_files`]
+//! returns, so ignored files stay out of the index.
+
+mod chunking;
+mod embedding;
+mod local_model;
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::config::types::EmbeddingBackend;
+use crate::config::types::SemanticIndexConfig;
+use crate::git_info::get_git_repo_root;
+use crate::workspace_files::file_stamp;
+use crate::workspace_files::is_under;
+use crate::workspace_files::list_files;
+use crate::workspace_files::read_text;
+
+use self::chunking::chunk_lines;
+use self::embedding::DIMS;
+use self::embedding::embed;
+use self::embedding::similarity;
+use self::local_model::LocalModel;
+
+/// Directory of the index, relative to the project root.
+pub const INDEX_DIR: &str = ".codex/index";
+const INDEX_FILENAME: &str = "index.json";
+
+/// Bumped whenever chunking or embedding changes, so stale indexes are
+/// rebuilt instead of mixing incompatible vectors.
+const INDEX_VERSION: u32 = 1;
+
+/// Larger files are usually generated or data, not code worth searching.
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+
+/// Lines of each hit included in search results.
+const MAX_SNIPPET_LINES: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IndexUpdate {
+    pub root: PathBuf,
+    pub indexed_files: usize,
+    pub unchanged_files: usize,
+    pub removed_files: usize,
+    pub total_files: usize,
+    pub total_chunks: usize,
+    /// The walk stopped at the file limit, so some files are not indexed.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IndexStatus {
+    pub root: PathBuf,
+    pub index_path: PathBuf,
+    pub exists: bool,
+    pub files: usize,
+    pub chunks: usize,
+    /// Indexed files that changed on disk since they were indexed.
+    pub changed_files: usize,
+    /// Files on disk that are not indexed yet.
+    pub new_files: usize,
+    /// Indexed files that no longer exist.
+    pub deleted_files: usize,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchHit {
+    /// Path relative to the project root.
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+    pub snippet: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredIndex {
+    version: u32,
+    /// Which embedder produced the vectors; see [`Embedder::id`].
+    #[serde(default = "hashing_embedder_id")]
+    embedder: String,
+    /// Vector length, learned from the first embedding for local models.
+    dims: usize,
+    updated_at: Option<String>,
+    files: BTreeMap<String, StoredFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredFile {
+    size: u64,
+    mtime_ms: u64,
+    chunks: Vec<StoredChunk>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredChunk {
+    start_line: usize,
+    end_line: usize,
+    /// Components quantized to `i8` and base64-encoded to keep the index small.
+    vector: String,
+}
+
+/// The configured way of turning text into vectors.
+enum Embedder {
+    Hashing,
+    Model { id: String, model: LocalModel },
+}
+
+impl Embedder {
+    fn new(config: &SemanticIndexConfig) -> Self {
+        match config.embedding {
+            EmbeddingBackend::Hashing => Self::Hashing,
+            EmbeddingBackend::Model => Self::Model {
+                id: format!(
+                    "model:{}",
+                    config
+                        .model
+                        .clone()
+                        .unwrap_or_else(|| config.command.join(" "))
+                ),
+                model: LocalModel::new(config),
+            },
+        }
+    }
+
+    /// Recorded in the index: vectors from different embedders are not
+    /// comparable, so a mismatch means the index is rebuilt.
+    fn id(&self) -> &str {
+        match self {
+            Self::Hashing => HASHING_EMBEDDER_ID,
+            Self::Model { id, .. } => id,
+        }
+    }
+
+    /// Vector length, when it is known without embedding anything.
+    fn dims(&self) -> Option<usize> {
+        match self {
+            Self::Hashing => Some(DIMS),
+            Self::Model { .. } => None,
+        }
+    }
+
+    fn embed(&mut self, texts: &[String]) -> io::Result<Vec<Vec<f32>>> {
+        match self {
+            Self::Hashing => Ok(texts.iter().map(|text| embed(text)).collect()),
+            Self::Model { model, .. } => model.embed(texts),
+        }
+    }
+}
+
+const HASHING_EMBEDDER_ID: &str = "hashing";
+
+fn hashing_embedder_id() -> String {
+    HASHING_EMBEDDER_ID.to_string()
+}
+
+/// Root of the project containing `cwd`, which is where the index lives.
+pub fn index_root(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+pub fn index_path(root: &Path) -> PathBuf {
+    root.join(INDEX_DIR).join(INDEX_FILENAME)
+}
+
+/// Brings the index of `root` up to date, re-embedding every file when
+/// `rebuild` is set. Blocking; run it off the async runtime.
+pub fn build_index(
+    root: &Path,
+    rebuild: bool,
+    config: &SemanticIndexConfig,
+) -> io::Result<IndexUpdate> {
+    let mut embedder = Embedder::new(config);
+    let mut index = if rebuild {
+        StoredIndex::empty(&embedder)
+    } else {
+        load_index(root, &embedder)?
+    };
+    let update = refresh(root, &mut index, &mut embedder)?;
+    save_index(root, &mut index)?;
+    Ok(update)
+}
+
+/// Compares the stored index with the files on disk without changing it.
+/// An index built with a different embedder counts as empty.
+pub fn index_status(root: &Path, config: &SemanticIndexConfig) -> io::Result<IndexStatus> {
+    let index_path = index_path(root);
+    let exists = index_path.is_file();
+    let index = load_index(root, &Embedder::new(config))?;
+    let (files, _) = list_files(root)?;
+    let mut changed_files = 0;
+    let mut new_files = 0;
+    for rel in &files {
+        match (index.files.get(rel), file_stamp(&root.join(rel))) {
+            (Some(stored), Some((size, mtime_ms)))
+                if stored.size == size && stored.mtime_ms == mtime_ms => {}
+            (Some(_), _) => changed_files += 1,
+            (None, Some((size, _))) if size > MAX_FILE_BYTES => {}
+            (None, _) => new_files += 1,
+        }
+    }
+    let deleted_files = index
+        .files
+        .keys()
+        .filter(|rel| files.binary_search(rel).is_err())
+        .count();
+    Ok(IndexStatus {
+        root: root.to_path_buf(),
+        index_path,
+        exists,
+        files: index.files.len(),
+        chunks: index.files.values().map(|file| file.chunks.len()).sum(),
+        changed_files,
+        new_files,
+        deleted_files,
+        updated_at: index.updated_at,
+    })
+}
+
+/// Refreshes the index of `root` and returns the `limit` chunks most
+/// similar to `query`, optionally restricted to paths under `path_prefix`.
+pub fn search(
+    root: &Path,
+    query: &str,
+    limit: usize,
+    path_prefix: Option<&str>,
+    config: &SemanticIndexConfig,
+) -> io::Result<Vec<SearchHit>> {
+    let mut embedder = Embedder::new(config);
+    let mut index = load_index(root, &embedder)?;
+    let update = refresh(root, &mut index, &mut embedder)?;
+    if update.indexed_files > 0 || update.removed_files > 0 {
+        save_index(root, &mut index)?;
+    }
+
+    let query_vector = embedder
+        .embed(&[query.to_string()])?
+        .pop()
+        .unwrap_or_default();
+    if !index.files.is_empty() && query_vector.len() != index.dims {
+        return Err(dims_mismatch(query_vector.len(), index.dims));
+    }
+    let mut scored: Vec<(f32, &str, &StoredChunk)> = Vec::new();
+    for (rel, file) in &index.files {
+        if !is_under(rel, path_prefix) {
+            continue;
+        }
+        for chunk in &file.chunks {
+            let score = similarity(&query_vector, &decode_vector(&chunk.vector));
+            scored.push((score, rel.as_str(), chunk));
+        }
+    }
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.truncate(limit);
+
+    Ok(scored
+        .into_iter()
+        .filter(|(score, _, _)| *score > 0.0)
+        .map(|(score, rel, chunk)| SearchHit {
+            path: rel.to_string(),
+            start_line: chunk.start_line,
+            end_line: chunk.end_line,
+            score,
+            snippet: read_snippet(&root.join(rel), chunk.start_line, chunk.end_line),
+        })
+        .collect())
+}
+
+impl StoredIndex {
+    fn empty(embedder: &Embedder) -> Self {
+        Self {
+            version: INDEX_VERSION,
+            embedder: embedder.id().to_string(),
+            dims: embedder.dims().unwrap_or(0),
+            ..Default::default()
+        }
+    }
+
+    /// Whether vectors from `embedder` can be compared with the stored ones.
+    fn matches(&self, embedder: &Embedder) -> bool {
+        self.version == INDEX_VERSION
+            && self.embedder == embedder.id()
+            && embedder.dims().is_none_or(|dims| dims == self.dims)
+    }
+}
+
+fn load_index(root: &Path, embedder: &Embedder) -> io::Result<StoredIndex> {
+    let path = index_path(root);
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            return Ok(StoredIndex::empty(embedder));
+        }
+        Err(err) => return Err(err),
+    };
+    match serde_json::from_str::<StoredIndex>(&contents) {
+        Ok(index) if index.matches(embedder) => Ok(index),
+        Ok(_) => Ok(StoredIndex::empty(embedder)),
+        Err(err) => {
+            tracing::warn!("ignoring unreadable index {}: {err}", path.display());
+            Ok(StoredIndex::empty(embedder))
+        }
+    }
+}
+
+fn dims_mismatch(got: usize, stored: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "the embedding model returned {got}-dimensional vectors but the index holds {stored}-dimensional ones; rebuild it with `codex index build --rebuild`"
+        ),
+    )
+}
+
+/// Writes the index atomically, along with a `.gitignore` so it is never
+/// committed by accident.
+fn save_index(root: &Path, index: &mut StoredIndex) -> io::Result<()> {
+    let dir = root.join(INDEX_DIR);
+    std::fs::create_dir_all(&dir)?;
+    let gitignore = dir.join(".gitignore");
+    if !gitignore.exists() {
+        std::fs::write(&gitignore, "*\n")?;
+    }
+    index.updated_at = Some(chrono::Utc::now().to_rfc3339());
+    let json = serde_json::to_string(index).map_err(io::Error::other)?;
+    let tmp = dir.join(format!("{INDEX_FILENAME}.tmp"));
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, dir.join(INDEX_FILENAME))
+}
+
+/// Re-embeds new and changed files and drops deleted ones.
+fn refresh(
+    root: &Path,
+    index: &mut StoredIndex,
+    embedder: &mut Embedder,
+) -> io::Result<IndexUpdate> {
+    let (files, truncated) = list_files(root)?;
+    let mut indexed_files = 0;
+    let mut unchanged_files = 0;
+    let mut refreshed = BTreeMap::new();
+    for rel in files {
+        let path = root.join(&rel);
+        let Some((size, mtime_ms)) = file_stamp(&path) else {
+            continue;
+        };
+        if let Some(stored) = index.files.remove(&rel)
+            && stored.size == size
+            && stored.mtime_ms == mtime_ms
+        {
+            unchanged_files += 1;
+            refreshed.insert(rel, stored);
+            continue;
+        }
+        if size > MAX_FILE_BYTES {
+            continue;
+        }
+        let Some(contents) = read_text(&path) else {
+            continue;
+        };
+        let chunks = chunk_lines(&contents);
+        // The path is part of the embedded text so file and directory names
+        // count as matches too.
+        let texts: Vec<String> = chunks
+            .iter()
+            .map(|chunk| format!("{rel}\n{}", chunk.text))
+            .collect();
+        let vectors = embedder.embed(&texts)?;
+        if let Some(vector) = vectors.first() {
+            if index.dims == 0 {
+                index.dims = vector.len();
+            } else if vector.len() != index.dims {
+                return Err(dims_mismatch(vector.len(), index.dims));
+            }
+        }
+        let chunks = chunks
+            .into_iter()
+            .zip(vectors)
+            .map(|(chunk, vector)| StoredChunk {
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                vector: encode_vector(&vector),
+            })
+            .collect();
+        indexed_files += 1;
+        refreshed.insert(
+            rel,
+            StoredFile {
+                size,
+                mtime_ms,
+                chunks,
+            },
+        );
+    }
+    let removed_files = index.files.len();
+    index.files = refreshed;
+    Ok(IndexUpdate {
+        root: root.to_path_buf(),
+        indexed_files,
+        unchanged_files,
+        removed_files,
+        total_files: index.files.len(),
+        total_chunks: index.files.values().map(|file| file.chunks.len()).sum(),
+        truncated,
+    })
+}
+
+fn read_snippet(path: &Path, start_line: usize, end_line: usize) -> String {
+    let Some(contents) = read_text(path) else {
+        return String::new();
+    };
+    let count = (end_line + 1)
+        .saturating_sub(start_line)
+        .min(MAX_SNIPPET_LINES);
+    contents
+        .lines()
+        .skip(start_line.saturating_sub(1))
+        .take(count)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn encode_vector(vector: &[f32]) -> String {
+    let bytes: Vec<u8> = vector
+        .iter()
+        .map(|value| ((value * 127.0).round().clamp(-127.0, 127.0) as i8) as u8)
+        .collect();
+    BASE64.encode(bytes)
+}
+
+fn decode_vector(encoded: &str) -> Vec<f32> {
+    BASE64
+        .decode(encoded)
+        .map(|bytes| {
+            bytes
+                .into_iter()
+                .map(|byte| f32::from(byte as i8) / 127.0)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn write(root: &Path, rel: &str, contents: &str) {
+        let path = root.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create dir");
+        }
+        std::fs::write(path, contents).expect("write file");
+    }
+
+    #[test]
+    fn build_index_is_incremental() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        write(root, "src/config.rs", "fn parse_config_file() {}\n");
+        write(root, "src/render.rs", "fn render_status_bar() {}\n");
+        write(root, "node_modules/dep/index.js", "module.exports = {}\n");
+
+        let update = build_index(root, false, &SemanticIndexConfig::default()).expect("build");
+        assert_eq!((update.indexed_files, update.total_files), (2, 2));
+        assert!(root.join(INDEX_DIR).join(".gitignore").is_file());
+
+        std::fs::remove_file(root.join("src/render.rs")).expect("remove");
+        write(root, "src/cli.rs", "fn parse_args() {}\n");
+        let status = index_status(root, &SemanticIndexConfig::default()).expect("status");
+        assert_eq!(
+            (status.files, status.new_files, status.deleted_files),
+            (2, 1, 1)
+        );
+
+        let update = build_index(root, false, &SemanticIndexConfig::default()).expect("update");
+        assert_eq!(
+            (
+                update.indexed_files,
+                update.unchanged_files,
+                update.removed_files
+            ),
+            (1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn search_ranks_by_meaning_and_filters_by_path() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        write(
+            root,
+            "src/config.rs",
+            "pub fn parse_config_file(path: &Path) -> ConfigToml {\n    load_toml(path)\n}\n",
+        );
+        write(
+            root,
+            "src/render.rs",
+            "fn render_status_bar(frame: &mut Frame) {\n    draw(frame)\n}\n",
+        );
+
+        let hits = search(
+            root,
+            "where is the config file parsed",
+            5,
+            None,
+            &SemanticIndexConfig::default(),
+        )
+        .expect("search");
+        assert_eq!(
+            hits.first().map(|hit| (hit.path.as_str(), hit.start_line)),
+            Some(("src/config.rs", 1))
+        );
+        assert!(hits[0].snippet.starts_with("pub fn parse_config_file"));
+
+        let hits = search(
+            root,
+            "config",
+            5,
+            Some("src/render.rs"),
+            &SemanticIndexConfig::default(),
+        )
+        .expect("search");
+        assert!(hits.iter().all(|hit| hit.path == "src/render.rs"));
+    }
+
+    #[test]
+    fn index_built_by_another_embedder_is_discarded() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        write(root, "src/config.rs", "fn parse_config_file() {}\n");
+        build_index(root, false, &SemanticIndexConfig::default()).expect("build");
+
+        let model = SemanticIndexConfig {
+            embedding: EmbeddingBackend::Model,
+            command: vec!["embed-server".to_string()],
+            model: Some("bge-small".to_string()),
+            ..Default::default()
+        };
+        let status = index_status(root, &model).expect("status");
+        assert_eq!(
+            (status.exists, status.files, status.new_files),
+            (true, 0, 1)
+        );
+
+        let status = index_status(root, &SemanticIndexConfig::default()).expect("status");
+        assert_eq!((status.files, status.new_files), (1, 0));
+    }
+
+    #[test]
+    fn vectors_round_trip_through_quantization() {
+        let vector = embed("parse the config file");
+        let decoded = decode_vector(&encode_vector(&vector));
+        assert_eq!(decoded.len(), DIMS);
+        assert!(similarity(&vector, &decoded) > 0.99);
+    }
+}
@@ -6,10 +6,27 @@
 /// boundaries.
 pub(crate) const ENVIRONMENT_CONTEXT_OPEN_TAG: &str = "<environment_context>";
 pub(crate) const TURN_ABORTED_OPEN_TAG: &str = "<turn_aborted>";
+pub(crate) const PINNED_CONTEXT_OPEN_TAG: &str = "<pinned_context>";
+pub(crate) const EXTERNAL_FILE_CHANGES_OPEN_TAG: &str = "<external_file_changes>";
+pub(crate) const RESUME_BRIEFING_OPEN_TAG: &str = "<resume_briefing>";
+pub(crate) const CONTEXT_PROVIDERS_OPEN_TAG: &str = "<context_providers>";
+pub(crate) const INTERRUPTED_WORK_OPEN_TAG: &str = "<interrupted_work>";
+pub(crate) const MCP_RESOURCES_OPEN_TAG: &str = "<mcp_resources>";
+pub(crate) const SYMBOL_DEFINITIONS_OPEN_TAG: &str = "<symbol_definitions>";
+pub(crate) const EDITOR_CONTEXT_OPEN_TAG: &str = "<editor_context>";
 
 /// Returns true if `text` starts with a session prefix marker (case-insensitive).
 pub(crate) fn is_session_prefix(text: &str) -> bool {
     let trimmed = text.trim_start();
     let lowered = trimmed.to_ascii_lowercase();
-    lowered.starts_with(ENVIRONMENT_CONTEXT_OPEN_TAG) || lowered.starts_with(TURN_ABORTED_OPEN_TAG)
+    lowered.starts_with(ENVIRONMENT_CONTEXT_OPEN_TAG)
+        || lowered.starts_with(TURN_ABORTED_OPEN_TAG)
+        || lowered.starts_with(PINNED_CONTEXT_OPEN_TAG)
+        || lowered.starts_with(EXTERNAL_FILE_CHANGES_OPEN_TAG)
+        || lowered.starts_with(RESUME_BRIEFING_OPEN_TAG)
+        || lowered.starts_with(CONTEXT_PROVIDERS_OPEN_TAG)
+        || lowered.starts_with(INTERRUPTED_WORK_OPEN_TAG)
+        || lowered.starts_with(MCP_RESOURCES_OPEN_TAG)
+        || lowered.starts_with(SYMBOL_DEFINITIONS_OPEN_TAG)
+        || lowered.starts_with(EDITOR_CONTEXT_OPEN_TAG)
 }
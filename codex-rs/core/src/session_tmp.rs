@@ -0,0 +1,162 @@
+//! Per-session scratch directory exposed to commands as `$CODEX_TMP`.
+//!
+//! The directory lives under the OS temp dir, is made writable for
+//! workspace-write sandboxes, and is removed when the session shuts down (or
+//! when the session is dropped without a clean shutdown).
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::SandboxPolicy;
+use codex_utils_absolute_path::AbsolutePathBuf;
+use tempfile::TempDir;
+use tracing::warn;
+
+use crate::config::types::EnvironmentVariablePattern;
+use crate::config::types::ShellEnvironmentPolicy;
+
+pub(crate) const CODEX_TMP_ENV_VAR: &str = "CODEX_TMP";
+
+pub(crate) struct SessionTmpDir {
+    path: PathBuf,
+    dir: Mutex<Option<TempDir>>,
+}
+
+impl SessionTmpDir {
+    pub(crate) fn create(conversation_id: ThreadId) -> std::io::Result<Self> {
+        Self::create_in(&std::env::temp_dir(), conversation_id)
+    }
+
+    fn create_in(parent: &Path, conversation_id: ThreadId) -> std::io::Result<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix(&format!("codex-{conversation_id}-"))
+            .tempdir_in(parent)?;
+        Ok(Self {
+            path: dir.path().to_path_buf(),
+            dir: Mutex::new(Some(dir)),
+        })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Exports `$CODEX_TMP` to commands run under `policy`, keeping it visible
+    /// even when the policy restricts inherited variables.
+    pub(crate) fn apply_to_env_policy(&self, policy: &mut ShellEnvironmentPolicy) {
+        policy.r#set.insert(
+            CODEX_TMP_ENV_VAR.to_string(),
+            self.path.to_string_lossy().into_owned(),
+        );
+        if !policy.include_only.is_empty() {
+            policy
+                .include_only
+                .push(EnvironmentVariablePattern::new_case_insensitive(
+                    CODEX_TMP_ENV_VAR,
+                ));
+        }
+    }
+
+    /// Adds the directory to a workspace-write sandbox's writable roots when
+    /// the defaults (`/tmp`, `$TMPDIR`) do not already cover it.
+    pub(crate) fn apply_to_sandbox_policy(&self, policy: &mut SandboxPolicy, cwd: &Path) {
+        let already_writable = policy
+            .get_writable_roots_with_cwd(cwd)
+            .iter()
+            .any(|root| root.is_path_writable(&self.path));
+        if already_writable {
+            return;
+        }
+        if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = policy {
+            match AbsolutePathBuf::from_absolute_path(&self.path) {
+                Ok(path) => writable_roots.push(path),
+                Err(err) => warn!(
+                    "ignoring session temp dir {} as writable root: {err}",
+                    self.path.display()
+                ),
+            }
+        }
+    }
+
+    /// Removes the directory and everything in it. Later calls are no-ops.
+    pub(crate) fn cleanup(&self) {
+        let dir = match self.dir.lock() {
+            Ok(mut guard) => guard.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        if let Some(dir) = dir
+            && let Err(err) = dir.close()
+        {
+            warn!(
+                "failed to remove session temp dir {}: {err}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn cleanup_removes_directory_and_contents() {
+        let parent = tempfile::tempdir().expect("tempdir");
+        let tmp = SessionTmpDir::create_in(parent.path(), ThreadId::new()).expect("create");
+        std::fs::write(tmp.path().join("scratch.txt"), "x").expect("write scratch file");
+
+        tmp.cleanup();
+        tmp.cleanup();
+
+        assert!(!tmp.path().exists());
+    }
+
+    #[test]
+    fn env_policy_exports_codex_tmp_through_include_only() {
+        let parent = tempfile::tempdir().expect("tempdir");
+        let tmp = SessionTmpDir::create_in(parent.path(), ThreadId::new()).expect("create");
+        let mut policy = ShellEnvironmentPolicy {
+            include_only: vec![EnvironmentVariablePattern::new_case_insensitive("PATH")],
+            ..Default::default()
+        };
+
+        tmp.apply_to_env_policy(&mut policy);
+
+        let env = crate::exec_env::create_env(&policy);
+        assert_eq!(
+            env.get(CODEX_TMP_ENV_VAR),
+            Some(&tmp.path().to_string_lossy().into_owned())
+        );
+    }
+
+    #[test]
+    fn sandbox_policy_gains_root_only_when_tmp_is_excluded() {
+        let parent = tempfile::tempdir().expect("tempdir");
+        let tmp = SessionTmpDir::create_in(parent.path(), ThreadId::new()).expect("create");
+        let cwd = tempfile::tempdir().expect("cwd");
+        let mut policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+
+        tmp.apply_to_sandbox_policy(&mut policy, cwd.path());
+        tmp.apply_to_sandbox_policy(&mut policy, cwd.path());
+
+        assert_eq!(
+            policy,
+            SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![
+                    AbsolutePathBuf::from_absolute_path(tmp.path()).expect("absolute")
+                ],
+                network_access: false,
+                exclude_tmpdir_env_var: true,
+                exclude_slash_tmp: true,
+            }
+        );
+    }
+}
@@ -1,9 +1,11 @@
 mod service;
 mod session;
+mod tool_usage;
 mod turn;
 
 pub(crate) use service::SessionServices;
 pub(crate) use session::SessionState;
+pub(crate) use tool_usage::ToolUsageTracker;
 pub(crate) use turn::ActiveTurn;
 pub(crate) use turn::RunningTask;
 pub(crate) use turn::TaskKind;
@@ -3,13 +3,36 @@ use std::sync::Arc;
 use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::agent::AgentControl;
+use crate::audit::AuditLog;
+use crate::background_tasks::BackgroundTasks;
+use crate::browser::BrowserSession;
+use crate::checkpoints::Checkpoints;
+use crate::context_providers::ContextProviders;
+use crate::cost::CostTracker;
 use crate::exec_policy::ExecPolicyManager;
+use crate::external_edits::ExternalEdits;
+use crate::governor::Governor;
+use crate::hooks::Hooks;
+use crate::input_queue::InputQueue;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_resource_mentions::McpResourceMentions;
 use crate::models_manager::manager::ModelsManager;
+use crate::network_capture::NetworkCapture;
+use crate::pinned_context::PinnedContext;
+use crate::plugin_tools::PluginTool;
+use crate::runtime_state::RuntimeStateTracker;
+use crate::session_tmp::SessionTmpDir;
 use crate::skills::SkillsManager;
+use crate::subagents::SubAgentMerges;
+use crate::subagents::SubAgentsManager;
+use crate::tasks::RedoStack;
+use crate::tasks::TurnSnapshots;
+use crate::tools::sandboxing::ApprovalJournal;
 use crate::tools::sandboxing::ApprovalStore;
+use crate::tools::trimming::ToolTrimming;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::user_notification::UserNotifier;
+use crate::worktree_isolation::WorktreeIsolation;
 use codex_otel::OtelManager;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
@@ -28,6 +51,49 @@ pub(crate) struct SessionServices {
     pub(crate) models_manager: Arc<ModelsManager>,
     pub(crate) otel_manager: OtelManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
+    pub(crate) approval_journal: Mutex<ApprovalJournal>,
     pub(crate) skills_manager: Arc<SkillsManager>,
+    pub(crate) subagents_manager: Arc<SubAgentsManager>,
+    /// Changes staged by subagent runs, waiting for `Op::MergeSubAgentChanges`.
+    pub(crate) subagent_merges: SubAgentMerges,
     pub(crate) agent_control: AgentControl,
+    pub(crate) background_tasks: BackgroundTasks,
+    pub(crate) input_queue: InputQueue,
+    /// Named checkpoints created via `Op::CreateCheckpoint`.
+    pub(crate) checkpoints: Checkpoints,
+    /// Working-tree states discarded by `Op::Undo`, restored by `Op::Redo`.
+    pub(crate) redo_stack: RedoStack,
+    /// Per-turn snapshots used by `Op::RestoreFile` and `Op::RevertHunks`.
+    pub(crate) turn_snapshots: TurnSnapshots,
+    /// Context pinned via `Op::PinContext`, sent with every request.
+    pub(crate) pinned_context: PinnedContext,
+    /// Recoverable runtime state written to the rollout (`resume_runtime_state`).
+    pub(crate) runtime_state: RuntimeStateTracker,
+    pub(crate) hooks: Hooks,
+    /// `[[context_providers]]`, run at the start of every turn.
+    pub(crate) context_providers: ContextProviders,
+    /// MCP resources attached with `@server:uri`, refreshed when they change.
+    pub(crate) mcp_resource_mentions: McpResourceMentions,
+    /// Scratch directory exported as `$CODEX_TMP`; `None` if it could not be created.
+    pub(crate) session_tmp: Option<SessionTmpDir>,
+    /// Proxy recording agent network traffic (`network_capture` feature).
+    pub(crate) network_capture: Option<NetworkCapture>,
+    /// Filtering proxy for `sandbox_workspace_write.network_allowlist`.
+    pub(crate) network_proxy: Option<NetworkCapture>,
+    /// Watcher for edits made outside the agent (`external_edits` feature).
+    pub(crate) external_edits: Option<ExternalEdits>,
+    /// Limits shared with other Codex processes (`[governor]`).
+    pub(crate) governor: Option<Governor>,
+    /// Append-only record of this session's actions (`[audit]`).
+    pub(crate) audit_log: Option<AuditLog>,
+    /// Worktree the session's turns run in (`[worktree_isolation]`).
+    pub(crate) worktree_isolation: Option<WorktreeIsolation>,
+    /// Estimated spending and `[budget]` limits.
+    pub(crate) cost_tracker: CostTracker,
+    /// MCP tools whose schema `describe_tool` has fetched (`tool_trimming`).
+    pub(crate) tool_trimming: ToolTrimming,
+    /// Headless browser for the `browser_*` tools, launched on first use.
+    pub(crate) browser: BrowserSession,
+    /// Tools loaded from `.codex/plugins/` at session start (`plugin_tools`).
+    pub(crate) plugin_tools: Vec<Arc<PluginTool>>,
 }
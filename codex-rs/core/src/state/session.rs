@@ -7,6 +7,7 @@ use crate::context_manager::ContextManager;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
+use crate::state::ToolUsageTracker;
 use crate::truncate::TruncationPolicy;
 
 /// Persistent, session-scoped state previously stored directly on `Session`.
@@ -15,6 +16,9 @@ pub(crate) struct SessionState {
     pub(crate) history: ContextManager,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
     pub(crate) server_reasoning_included: bool,
+    /// Branch the `[branches]` overrides were last resolved for.
+    pub(crate) checked_out_branch: Option<String>,
+    pub(crate) tool_usage: ToolUsageTracker,
 }
 
 impl SessionState {
@@ -26,6 +30,8 @@ impl SessionState {
             history,
             latest_rate_limits: None,
             server_reasoning_included: false,
+            checked_out_branch: None,
+            tool_usage: ToolUsageTracker::default(),
         }
     }
 
@@ -35,7 +41,11 @@ impl SessionState {
         I: IntoIterator,
         I::Item: std::ops::Deref<Target = ResponseItem>,
     {
+        let recorded_from = self.history.raw_items().len();
         self.history.record_items(items, policy);
+        for item in &self.history.raw_items()[recorded_from..] {
+            self.tool_usage.observe(item);
+        }
     }
 
     pub(crate) fn clone_history(&self) -> ContextManager {
@@ -57,6 +67,7 @@ impl SessionState {
         model_context_window: Option<i64>,
     ) {
         self.history.update_token_info(usage, model_context_window);
+        self.tool_usage.attribute_follow_up(usage.output_tokens);
     }
 
     pub(crate) fn token_info(&self) -> Option<TokenUsageInfo> {
@@ -0,0 +1,217 @@
+//! Per-tool and per-subagent token accounting reported in `TokenCountEvent`.
+
+use std::collections::HashMap;
+
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::SubAgentTokenUsage;
+use codex_protocol::protocol::TokenUsage;
+use codex_protocol::protocol::ToolTokenUsage;
+
+use crate::context_manager::estimate_item_tokens;
+
+#[derive(Debug, Default)]
+pub(crate) struct ToolUsageTracker {
+    /// Tool name for each call whose output has not been recorded yet.
+    call_names: HashMap<String, String>,
+    /// Outputs recorded since the last model response, as (tool, tokens).
+    unanswered: Vec<(String, i64)>,
+    tools: Vec<ToolTokenUsage>,
+    subagents: Vec<SubAgentTokenUsage>,
+}
+
+impl ToolUsageTracker {
+    /// Looks at an item as it was recorded into history (after truncation).
+    pub(crate) fn observe(&mut self, item: &ResponseItem) {
+        match item {
+            ResponseItem::FunctionCall { name, call_id, .. }
+            | ResponseItem::CustomToolCall { name, call_id, .. } => {
+                self.call_names.insert(call_id.clone(), name.clone());
+            }
+            ResponseItem::LocalShellCall {
+                call_id: Some(call_id),
+                ..
+            } => {
+                self.call_names
+                    .insert(call_id.clone(), "local_shell".to_string());
+            }
+            ResponseItem::FunctionCallOutput { call_id, .. }
+            | ResponseItem::CustomToolCallOutput { call_id, .. } => {
+                let tool_name = self
+                    .call_names
+                    .remove(call_id)
+                    .unwrap_or_else(|| "unknown".to_string());
+                let tokens = estimate_item_tokens(item);
+                let entry = self.tool_entry(&tool_name);
+                entry.calls += 1;
+                entry.output_tokens = entry.output_tokens.saturating_add(tokens);
+                self.unanswered.push((tool_name, tokens));
+            }
+            _ => {}
+        }
+    }
+
+    /// Splits the completion tokens of a model response across the tool
+    /// outputs it answered, in proportion to their size.
+    pub(crate) fn attribute_follow_up(&mut self, completion_tokens: i64) {
+        let unanswered = std::mem::take(&mut self.unanswered);
+        if unanswered.is_empty() || completion_tokens <= 0 {
+            return;
+        }
+        let total_output: i64 = unanswered.iter().map(|(_, tokens)| *tokens).sum();
+        let count = unanswered.len() as i64;
+        let mut remaining = completion_tokens;
+        for (idx, (tool_name, tokens)) in unanswered.iter().enumerate() {
+            let share = if idx + 1 == unanswered.len() {
+                remaining
+            } else if total_output > 0 {
+                (i128::from(completion_tokens) * i128::from(*tokens) / i128::from(total_output))
+                    as i64
+            } else {
+                completion_tokens / count
+            };
+            remaining -= share;
+            let entry = self.tool_entry(tool_name);
+            entry.follow_up_tokens = entry.follow_up_tokens.saturating_add(share);
+        }
+    }
+
+    pub(crate) fn record_subagent_run(&mut self, name: &str, usage: Option<&TokenUsage>) {
+        let index = match self.subagents.iter().position(|entry| entry.name == name) {
+            Some(index) => index,
+            None => {
+                self.subagents.push(SubAgentTokenUsage {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+                self.subagents.len() - 1
+            }
+        };
+        let entry = &mut self.subagents[index];
+        entry.runs += 1;
+        if let Some(usage) = usage {
+            entry.token_usage.add_assign(usage);
+        }
+    }
+
+    /// Tools ordered by the tokens attributed to them, largest first.
+    pub(crate) fn tool_usage(&self) -> Vec<ToolTokenUsage> {
+        let mut tools = self.tools.clone();
+        tools.sort_by_key(|entry| {
+            std::cmp::Reverse(entry.output_tokens.saturating_add(entry.follow_up_tokens))
+        });
+        tools
+    }
+
+    /// Subagents ordered by total tokens, largest first.
+    pub(crate) fn subagent_usage(&self) -> Vec<SubAgentTokenUsage> {
+        let mut subagents = self.subagents.clone();
+        subagents.sort_by_key(|entry| std::cmp::Reverse(entry.token_usage.total_tokens));
+        subagents
+    }
+
+    fn tool_entry(&mut self, tool_name: &str) -> &mut ToolTokenUsage {
+        let index = match self
+            .tools
+            .iter()
+            .position(|entry| entry.tool_name == tool_name)
+        {
+            Some(index) => index,
+            None => {
+                self.tools.push(ToolTokenUsage {
+                    tool_name: tool_name.to_string(),
+                    ..Default::default()
+                });
+                self.tools.len() - 1
+            }
+        };
+        &mut self.tools[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    fn call(name: &str, call_id: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: name.to_string(),
+            arguments: "{}".to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    fn output(call_id: &str, content: &str) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn attributes_outputs_and_follow_up_to_tools() {
+        let mut tracker = ToolUsageTracker::default();
+        let big = output("call-1", &"x".repeat(4_000));
+        let small = output("call-2", "ok");
+        tracker.observe(&call("shell", "call-1"));
+        tracker.observe(&call("read_file", "call-2"));
+        tracker.observe(&big);
+        tracker.observe(&small);
+        tracker.attribute_follow_up(100);
+
+        let big_tokens = estimate_item_tokens(&big);
+        let small_tokens = estimate_item_tokens(&small);
+        let big_share = 100 * big_tokens / (big_tokens + small_tokens);
+        assert_eq!(
+            tracker.tool_usage(),
+            vec![
+                ToolTokenUsage {
+                    tool_name: "shell".to_string(),
+                    calls: 1,
+                    output_tokens: big_tokens,
+                    follow_up_tokens: big_share,
+                },
+                ToolTokenUsage {
+                    tool_name: "read_file".to_string(),
+                    calls: 1,
+                    output_tokens: small_tokens,
+                    follow_up_tokens: 100 - big_share,
+                },
+            ]
+        );
+
+        // A later response answers nothing new.
+        tracker.attribute_follow_up(50);
+        let follow_ups: i64 = tracker
+            .tool_usage()
+            .iter()
+            .map(|entry| entry.follow_up_tokens)
+            .sum();
+        assert_eq!(follow_ups, 100);
+    }
+
+    #[test]
+    fn sums_subagent_runs_by_name() {
+        let mut tracker = ToolUsageTracker::default();
+        let usage = TokenUsage {
+            input_tokens: 10,
+            output_tokens: 5,
+            total_tokens: 15,
+            ..Default::default()
+        };
+        tracker.record_subagent_run("reviewer", Some(&usage));
+        tracker.record_subagent_run("reviewer", Some(&usage));
+        tracker.record_subagent_run("tester", None);
+
+        let subagents = tracker.subagent_usage();
+        assert_eq!(subagents[0].name, "reviewer");
+        assert_eq!(subagents[0].runs, 2);
+        assert_eq!(subagents[0].token_usage.total_tokens, 30);
+        assert_eq!(subagents[1].runs, 1);
+    }
+}
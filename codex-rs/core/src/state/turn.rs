@@ -3,6 +3,7 @@
 use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::Mutex;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
@@ -10,10 +11,12 @@ use tokio_util::task::AbortOnDropHandle;
 
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::request_user_input::RequestUserInputResponse;
+use codex_utils_absolute_path::AbsolutePathBuf;
 use tokio::sync::oneshot;
 
 use crate::codex::TurnContext;
 use crate::protocol::ReviewDecision;
+use crate::protocol::TokenUsage;
 use crate::tasks::SessionTask;
 
 /// Metadata about the currently running turn.
@@ -71,6 +74,29 @@ pub(crate) struct TurnState {
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
     pending_user_input: HashMap<String, oneshot::Sender<RequestUserInputResponse>>,
     pending_input: Vec<ResponseInputItem>,
+    /// Set once the user approves a `submit_plan` call (plan mode).
+    plan_approved: bool,
+    /// Files the approved plan may patch, plus any the user added since.
+    plan_scope: Vec<AbsolutePathBuf>,
+    /// Counters reported in `TurnCompleteEvent::metadata`.
+    pub(crate) stats: TurnStats,
+}
+
+/// Per-turn counters for the turn-complete metadata.
+pub(crate) struct TurnStats {
+    pub(crate) started_at: Instant,
+    pub(crate) retries: u32,
+    pub(crate) token_usage: TokenUsage,
+}
+
+impl Default for TurnStats {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            retries: 0,
+            token_usage: TokenUsage::default(),
+        }
+    }
 }
 
 impl TurnState {
@@ -127,6 +153,28 @@ impl TurnState {
     pub(crate) fn has_pending_input(&self) -> bool {
         !self.pending_input.is_empty()
     }
+
+    pub(crate) fn approve_plan(&mut self, scope: Vec<AbsolutePathBuf>) {
+        self.plan_approved = true;
+        self.plan_scope = scope;
+    }
+
+    pub(crate) fn plan_approved(&self) -> bool {
+        self.plan_approved
+    }
+
+    /// The approved plan's scope, or `None` before a plan is approved.
+    pub(crate) fn plan_scope(&self) -> Option<&[AbsolutePathBuf]> {
+        self.plan_approved.then_some(self.plan_scope.as_slice())
+    }
+
+    pub(crate) fn expand_plan_scope(&mut self, paths: Vec<AbsolutePathBuf>) {
+        for path in paths {
+            if !self.plan_scope.contains(&path) {
+                self.plan_scope.push(path);
+            }
+        }
+    }
 }
 
 impl ActiveTurn {
@@ -8,6 +8,7 @@ use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::error::CodexErr;
 use crate::error::Result;
+use crate::event_mapping::hosted_tool_call_event;
 use crate::function_tool::FunctionCallError;
 use crate::parse_turn_item;
 use crate::tools::parallel::ToolCallRuntime;
@@ -15,6 +16,7 @@ use crate::tools::router::ToolRouter;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
 use futures::Future;
 use tracing::debug;
 use tracing::instrument;
@@ -81,6 +83,12 @@ pub(crate) async fn handle_output_item_done(
                     .await;
             }
 
+            if let Some(event) = hosted_tool_call_event(&item) {
+                ctx.sess
+                    .send_event(&ctx.turn_context, EventMsg::HostedToolCall(event))
+                    .await;
+            }
+
             ctx.sess
                 .record_conversation_items(&ctx.turn_context, std::slice::from_ref(&item))
                 .await;
@@ -0,0 +1,404 @@
+use crate::git_info::get_git_repo_root;
+use crate::subagents::model::SubAgentDefinition;
+use crate::subagents::model::SubAgentLoadOutcome;
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::subagents::SubAgentLoadError;
+use codex_protocol::subagents::SubAgentSource;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use tracing::warn;
+
+#[derive(Debug, Deserialize)]
+struct SubAgentFrontmatter {
+    description: Option<String>,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    sandbox_mode: Option<SandboxMode>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    model_provider: Option<String>,
+    #[serde(default)]
+    env_allowed: Option<Vec<String>>,
+    #[serde(default)]
+    env_set: HashMap<String, String>,
+}
+
+const AGENTS_DIR_NAME: &str = "agents";
+const MAX_NAME_LEN: usize = 64;
+const MAX_DESCRIPTION_LEN: usize = 1024;
+
+#[derive(Debug)]
+enum SubAgentParseError {
+    Read(std::io::Error),
+    InvalidName,
+    MissingFrontmatter,
+    InvalidYaml(serde_yaml::Error),
+    MissingField(&'static str),
+    InvalidField { field: &'static str, reason: String },
+}
+
+impl fmt::Display for SubAgentParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubAgentParseError::Read(e) => write!(f, "failed to read file: {e}"),
+            SubAgentParseError::InvalidName => {
+                write!(f, "invalid name: only [A-Za-z0-9_-] are allowed")
+            }
+            SubAgentParseError::MissingFrontmatter => {
+                write!(f, "missing YAML frontmatter delimited by ---")
+            }
+            SubAgentParseError::InvalidYaml(e) => write!(f, "invalid YAML: {e}"),
+            SubAgentParseError::MissingField(field) => write!(f, "missing field `{field}`"),
+            SubAgentParseError::InvalidField { field, reason } => {
+                write!(f, "invalid {field}: {reason}")
+            }
+        }
+    }
+}
+
+impl Error for SubAgentParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubAgentRoot {
+    pub path: PathBuf,
+    pub source: SubAgentSource,
+}
+
+/// Directories scanned for `<name>.md` subagent definitions, highest priority
+/// first: `<git root>/.codex/agents`, then `$CODEX_HOME/agents`, then each of
+/// `extra_dirs` (`subagents.extra_dirs`) in configured order. Shared
+/// directories come last so both the repo and the user can override them.
+///
+/// Unlike skills, the repo root is always the git root; intermediate `.codex`
+/// directories between `cwd` and the root are not consulted.
+pub fn subagent_search_roots(
+    cwd: &Path,
+    codex_home: &Path,
+    extra_dirs: &[PathBuf],
+) -> Vec<SubAgentRoot> {
+    let mut roots = Vec::new();
+    if let Some(git_root) = get_git_repo_root(cwd) {
+        roots.push(SubAgentRoot {
+            path: git_root.join(".codex").join(AGENTS_DIR_NAME),
+            source: SubAgentSource::Repo,
+        });
+    }
+    roots.push(SubAgentRoot {
+        path: codex_home.join(AGENTS_DIR_NAME),
+        source: SubAgentSource::User,
+    });
+    roots.extend(extra_dirs.iter().map(|dir| SubAgentRoot {
+        path: dir.clone(),
+        source: SubAgentSource::Extra,
+    }));
+    roots
+}
+
+pub fn load_subagents(
+    cwd: &Path,
+    codex_home: &Path,
+    extra_dirs: &[PathBuf],
+) -> SubAgentLoadOutcome {
+    load_subagents_from_roots(subagent_search_roots(cwd, codex_home, extra_dirs))
+}
+
+/// Loads definitions from `roots` in order. When the same name appears in
+/// more than one root, the first root wins.
+pub(crate) fn load_subagents_from_roots<I>(roots: I) -> SubAgentLoadOutcome
+where
+    I: IntoIterator<Item = SubAgentRoot>,
+{
+    let mut outcome = SubAgentLoadOutcome::default();
+    let mut seen: HashSet<String> = HashSet::new();
+    for root in roots {
+        for path in list_markdown_files(&root.path) {
+            match parse_subagent_file(&path, root.source) {
+                Ok(subagent) => {
+                    if seen.insert(subagent.name.clone()) {
+                        outcome.subagents.push(subagent);
+                    }
+                }
+                Err(err) => outcome.errors.push(SubAgentLoadError {
+                    path,
+                    message: err.to_string(),
+                }),
+            }
+        }
+    }
+    outcome.subagents.sort_by(|a, b| a.name.cmp(&b.name));
+    outcome
+}
+
+fn list_markdown_files(dir: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                warn!("failed to read subagents dir {}: {err:#}", dir.display());
+            }
+            return Vec::new();
+        }
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+        })
+        .filter(|path| fs::metadata(path).is_ok_and(|m| m.is_file()))
+        .collect();
+    files.sort();
+    files
+}
+
+fn parse_subagent_file(
+    path: &Path,
+    source: SubAgentSource,
+) -> Result<SubAgentDefinition, SubAgentParseError> {
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(str::trim)
+        .unwrap_or_default()
+        .to_string();
+    if !is_valid_name(&name) {
+        return Err(SubAgentParseError::InvalidName);
+    }
+
+    let contents = fs::read_to_string(path).map_err(SubAgentParseError::Read)?;
+    let (frontmatter, body) =
+        split_frontmatter(&contents).ok_or(SubAgentParseError::MissingFrontmatter)?;
+    let parsed: SubAgentFrontmatter =
+        serde_yaml::from_str(frontmatter).map_err(SubAgentParseError::InvalidYaml)?;
+
+    let description = parsed
+        .description
+        .as_deref()
+        .map(sanitize_single_line)
+        .filter(|description| !description.is_empty())
+        .ok_or(SubAgentParseError::MissingField("description"))?;
+    if description.chars().count() > MAX_DESCRIPTION_LEN {
+        return Err(SubAgentParseError::InvalidField {
+            field: "description",
+            reason: format!("exceeds maximum length of {MAX_DESCRIPTION_LEN} characters"),
+        });
+    }
+
+    let env_allowed = parsed.env_allowed.map(|allowed| {
+        allowed
+            .iter()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect()
+    });
+    if let Some(key) = parsed.env_set.keys().find(|key| !is_valid_env_name(key)) {
+        return Err(SubAgentParseError::InvalidField {
+            field: "env_set",
+            reason: format!("`{key}` is not a valid environment variable name"),
+        });
+    }
+
+    Ok(SubAgentDefinition {
+        name,
+        description,
+        color: parsed
+            .color
+            .map(|color| color.trim().to_string())
+            .filter(|color| !color.is_empty()),
+        sandbox_mode: parsed.sandbox_mode,
+        model: parsed
+            .model
+            .map(|model| model.trim().to_string())
+            .filter(|model| !model.is_empty()),
+        model_provider: parsed
+            .model_provider
+            .map(|provider| provider.trim().to_string())
+            .filter(|provider| !provider.is_empty()),
+        env_allowed,
+        env_set: parsed.env_set,
+        instructions: body.trim().to_string(),
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn sanitize_single_line(raw: &str) -> String {
+    raw.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Splits `contents` into the YAML frontmatter (between the leading `---`
+/// lines) and the remaining body.
+fn split_frontmatter(contents: &str) -> Option<(&str, &str)> {
+    let mut segments = contents.split_inclusive('\n');
+    let first = segments.next()?;
+    if first.trim() != "---" {
+        return None;
+    }
+
+    let start = first.len();
+    let mut offset = start;
+    for segment in segments {
+        if segment.trim() == "---" {
+            return Some((
+                &contents[start..offset],
+                &contents[offset + segment.len()..],
+            ));
+        }
+        offset += segment.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        fs::create_dir_all(dir).expect("create dir");
+        let path = dir.join(name);
+        fs::write(&path, contents).expect("write file");
+        path
+    }
+
+    fn root(path: PathBuf, source: SubAgentSource) -> SubAgentRoot {
+        SubAgentRoot { path, source }
+    }
+
+    #[test]
+    fn loads_definition_with_frontmatter_and_body() {
+        let tmp = TempDir::new().expect("tempdir");
+        let path = write(
+            tmp.path(),
+            "reviewer.md",
+            "---\ndescription: Reviews  diffs\ncolor: blue\nsandbox_mode: read-only\nmodel: gpt-5.1\nmodel_provider: anthropic\nenv_allowed: [PATH, \"LC_*\"]\nenv_set:\n  CI: \"1\"\n---\nYou review code.\n",
+        );
+
+        let outcome =
+            load_subagents_from_roots([root(tmp.path().to_path_buf(), SubAgentSource::Repo)]);
+
+        assert_eq!(outcome.errors, Vec::new());
+        assert_eq!(
+            outcome.subagents,
+            vec![SubAgentDefinition {
+                name: "reviewer".to_string(),
+                description: "Reviews diffs".to_string(),
+                color: Some("blue".to_string()),
+                sandbox_mode: Some(SandboxMode::ReadOnly),
+                model: Some("gpt-5.1".to_string()),
+                model_provider: Some("anthropic".to_string()),
+                env_allowed: Some(vec!["PATH".to_string(), "LC_*".to_string()]),
+                env_set: HashMap::from([("CI".to_string(), "1".to_string())]),
+                instructions: "You review code.".to_string(),
+                path,
+                source: SubAgentSource::Repo,
+            }]
+        );
+    }
+
+    #[test]
+    fn earlier_root_wins_for_duplicate_names() {
+        let tmp = TempDir::new().expect("tempdir");
+        let repo = tmp.path().join("repo");
+        let user = tmp.path().join("user");
+        let repo_path = write(&repo, "helper.md", "---\ndescription: repo\n---\n");
+        write(&user, "helper.md", "---\ndescription: user\n---\n");
+
+        let outcome = load_subagents_from_roots([
+            root(repo, SubAgentSource::Repo),
+            root(user, SubAgentSource::User),
+        ]);
+
+        let names: Vec<(&str, &str, &Path)> = outcome
+            .subagents
+            .iter()
+            .map(|s| (s.name.as_str(), s.description.as_str(), s.path.as_path()))
+            .collect();
+        assert_eq!(names, vec![("helper", "repo", repo_path.as_path())]);
+    }
+
+    #[test]
+    fn extra_dirs_are_searched_after_user_root() {
+        let tmp = TempDir::new().expect("tempdir");
+        let codex_home = tmp.path().join("home");
+        let shared = tmp.path().join("shared");
+        let team = tmp.path().join("team");
+
+        assert_eq!(
+            subagent_search_roots(tmp.path(), &codex_home, &[shared.clone(), team.clone()]),
+            vec![
+                root(codex_home.join("agents"), SubAgentSource::User),
+                root(shared, SubAgentSource::Extra),
+                root(team, SubAgentSource::Extra),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_definitions() {
+        let tmp = TempDir::new().expect("tempdir");
+        let missing = write(tmp.path(), "missing.md", "no frontmatter\n");
+        let no_description = write(tmp.path(), "nodesc.md", "---\ncolor: red\n---\nbody\n");
+        let bad_name = write(tmp.path(), "bad name.md", "---\ndescription: x\n---\n");
+        let bad_env = write(
+            tmp.path(),
+            "badenv.md",
+            "---\ndescription: x\nenv_set:\n  \"1BAD\": y\n---\n",
+        );
+
+        let outcome =
+            load_subagents_from_roots([root(tmp.path().to_path_buf(), SubAgentSource::User)]);
+
+        assert_eq!(outcome.subagents, Vec::new());
+        assert_eq!(
+            outcome.errors,
+            vec![
+                SubAgentLoadError {
+                    path: bad_name,
+                    message: "invalid name: only [A-Za-z0-9_-] are allowed".to_string(),
+                },
+                SubAgentLoadError {
+                    path: bad_env,
+                    message: "invalid env_set: `1BAD` is not a valid environment variable name"
+                        .to_string(),
+                },
+                SubAgentLoadError {
+                    path: missing,
+                    message: "missing YAML frontmatter delimited by ---".to_string(),
+                },
+                SubAgentLoadError {
+                    path: no_description,
+                    message: "missing field `description`".to_string(),
+                },
+            ]
+        );
+    }
+}
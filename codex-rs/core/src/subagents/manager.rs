@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::RwLock;
+use std::sync::Weak;
+use std::time::Duration;
+
+use async_channel::Sender;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::SubAgentsUpdatedEvent;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::codex::INITIAL_SUBMIT_ID;
+use crate::subagents::loader::SubAgentRoot;
+use crate::subagents::loader::load_subagents_from_roots;
+use crate::subagents::loader::subagent_search_roots;
+use crate::subagents::model::SubAgentDefinition;
+use crate::subagents::model::SubAgentLoadOutcome;
+
+/// Editors usually touch a file several times per save; wait this long after
+/// the last change before reloading.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Session-wide registry of subagent definitions.
+///
+/// Definitions are cached per cwd. Once [`SubAgentsManager::start_watching`]
+/// has been called, edits under the search roots invalidate the cache and
+/// emit `EventMsg::SubAgentsUpdated` so clients can refresh their lists.
+pub(crate) struct SubAgentsManager {
+    codex_home: PathBuf,
+    extra_dirs: Vec<PathBuf>,
+    cache_by_cwd: RwLock<HashMap<PathBuf, SubAgentLoadOutcome>>,
+    watcher: Mutex<Option<RootsWatcher>>,
+}
+
+/// Watches the search roots. A root that does not exist yet is covered by a
+/// non-recursive watch on its nearest existing ancestor, and gets its own
+/// recursive watch once a change shows it has been created.
+struct RootsWatcher {
+    watcher: RecommendedWatcher,
+    roots: Vec<SubAgentRoot>,
+    watched: HashSet<PathBuf>,
+}
+
+impl RootsWatcher {
+    fn refresh(&mut self) {
+        // A watched directory that was deleted lost its watch with it.
+        self.watched.retain(|path| path.is_dir());
+        for root in &self.roots {
+            let Some((path, mode)) = watch_target(&root.path) else {
+                continue;
+            };
+            if self.watched.contains(path) {
+                continue;
+            }
+            match self.watcher.watch(path, mode) {
+                Ok(()) => {
+                    self.watched.insert(path.to_path_buf());
+                }
+                Err(err) => warn!("failed to watch {}: {err:#}", path.display()),
+            }
+        }
+    }
+}
+
+fn watch_target(root: &Path) -> Option<(&Path, RecursiveMode)> {
+    if root.is_dir() {
+        return Some((root, RecursiveMode::Recursive));
+    }
+    root.ancestors()
+        .skip(1)
+        .find(|ancestor| ancestor.is_dir())
+        .map(|ancestor| (ancestor, RecursiveMode::NonRecursive))
+}
+
+impl SubAgentsManager {
+    pub(crate) fn new(codex_home: PathBuf, extra_dirs: Vec<PathBuf>) -> Self {
+        Self {
+            codex_home,
+            extra_dirs,
+            cache_by_cwd: RwLock::new(HashMap::new()),
+            watcher: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn subagents_for_cwd(&self, cwd: &Path) -> SubAgentLoadOutcome {
+        let cached = match self.cache_by_cwd.read() {
+            Ok(cache) => cache.get(cwd).cloned(),
+            Err(err) => err.into_inner().get(cwd).cloned(),
+        };
+        if let Some(outcome) = cached {
+            return outcome;
+        }
+
+        let outcome = load_subagents_from_roots(subagent_search_roots(
+            cwd,
+            &self.codex_home,
+            &self.extra_dirs,
+        ));
+        match self.cache_by_cwd.write() {
+            Ok(mut cache) => {
+                cache.insert(cwd.to_path_buf(), outcome.clone());
+            }
+            Err(err) => {
+                err.into_inner().insert(cwd.to_path_buf(), outcome.clone());
+            }
+        }
+        outcome
+    }
+
+    fn clear_cache(&self) {
+        match self.cache_by_cwd.write() {
+            Ok(mut cache) => cache.clear(),
+            Err(err) => err.into_inner().clear(),
+        }
+    }
+
+    /// Watches the search roots for `cwd` and reports changes on `tx_event`.
+    /// Replaces any previous watcher. Failures are logged and leave the
+    /// session with load-on-demand behavior.
+    pub(crate) fn start_watching(self: &Arc<Self>, cwd: PathBuf, tx_event: Sender<Event>) {
+        let roots = subagent_search_roots(&cwd, &self.codex_home, &self.extra_dirs);
+        let (tx, rx) = mpsc::unbounded_channel();
+        let watched_roots = roots.clone();
+        let watcher =
+            match notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                let Ok(event) = res else {
+                    return;
+                };
+                if event
+                    .paths
+                    .iter()
+                    .any(|path| is_relevant_path(path, &watched_roots))
+                {
+                    let _ = tx.send(());
+                }
+            }) {
+                Ok(watcher) => watcher,
+                Err(err) => {
+                    warn!("failed to create subagents watcher: {err:#}");
+                    return;
+                }
+            };
+
+        let mut watcher = RootsWatcher {
+            watcher,
+            roots,
+            watched: HashSet::new(),
+        };
+        watcher.refresh();
+
+        match self.watcher.lock() {
+            Ok(mut guard) => *guard = Some(watcher),
+            Err(err) => *err.into_inner() = Some(watcher),
+        }
+        tokio::spawn(reload_on_change(Arc::downgrade(self), cwd, rx, tx_event));
+    }
+
+    /// Picks up roots created (or deleted) since the watches were set.
+    fn refresh_watches(&self) {
+        let mut guard = match self.watcher.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        if let Some(watcher) = guard.as_mut() {
+            watcher.refresh();
+        }
+    }
+}
+
+async fn reload_on_change(
+    manager: Weak<SubAgentsManager>,
+    cwd: PathBuf,
+    mut rx: mpsc::UnboundedReceiver<()>,
+    tx_event: Sender<Event>,
+) {
+    // The channel closes when the watcher (owned by the manager) is dropped.
+    while rx.recv().await.is_some() {
+        tokio::time::sleep(RELOAD_DEBOUNCE).await;
+        while rx.try_recv().is_ok() {}
+
+        let Some(manager) = manager.upgrade() else {
+            return;
+        };
+        manager.refresh_watches();
+        manager.clear_cache();
+        let outcome = manager.subagents_for_cwd(&cwd);
+        let event = Event {
+            id: INITIAL_SUBMIT_ID.to_owned(),
+            msg: EventMsg::SubAgentsUpdated(SubAgentsUpdatedEvent {
+                subagents: outcome
+                    .subagents
+                    .iter()
+                    .map(SubAgentDefinition::to_info)
+                    .collect(),
+                errors: outcome.errors,
+            }),
+        };
+        if tx_event.send(event).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Changes under a root, or to a directory on the way to a root that does
+/// not exist yet.
+fn is_relevant_path(path: &Path, roots: &[SubAgentRoot]) -> bool {
+    roots
+        .iter()
+        .any(|root| path.starts_with(&root.path) || root.path.starts_with(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn write_agent(dir: &Path, name: &str, description: &str) {
+        std::fs::create_dir_all(dir).expect("create agents dir");
+        std::fs::write(
+            dir.join(format!("{name}.md")),
+            format!("---\ndescription: {description}\n---\nBody\n"),
+        )
+        .expect("write agent");
+    }
+
+    #[tokio::test]
+    async fn emits_update_when_definition_changes() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let agents_dir = codex_home.path().join("agents");
+        write_agent(&agents_dir, "reviewer", "Reviews diffs");
+
+        let manager = Arc::new(SubAgentsManager::new(
+            codex_home.path().to_path_buf(),
+            Vec::new(),
+        ));
+        let (tx_event, rx_event) = async_channel::unbounded();
+        manager.start_watching(cwd.path().to_path_buf(), tx_event);
+        assert_eq!(
+            manager.subagents_for_cwd(cwd.path()).subagents[0].description,
+            "Reviews diffs"
+        );
+
+        write_agent(&agents_dir, "reviewer", "Reviews diffs strictly");
+        let event = tokio::time::timeout(Duration::from_secs(10), rx_event.recv())
+            .await
+            .expect("timed out waiting for update")
+            .expect("event channel closed");
+
+        let EventMsg::SubAgentsUpdated(update) = event.msg else {
+            panic!("unexpected event: {:?}", event.msg);
+        };
+        let descriptions: Vec<String> = update
+            .subagents
+            .into_iter()
+            .map(|subagent| subagent.description)
+            .collect();
+        assert_eq!(descriptions, vec!["Reviews diffs strictly".to_string()]);
+        assert_eq!(
+            manager.subagents_for_cwd(cwd.path()).subagents[0].description,
+            "Reviews diffs strictly"
+        );
+    }
+
+    async fn next_names(rx_event: &async_channel::Receiver<Event>) -> Vec<String> {
+        let event = tokio::time::timeout(Duration::from_secs(10), rx_event.recv())
+            .await
+            .expect("timed out waiting for update")
+            .expect("event channel closed");
+        let EventMsg::SubAgentsUpdated(update) = event.msg else {
+            panic!("unexpected event: {:?}", event.msg);
+        };
+        update
+            .subagents
+            .into_iter()
+            .map(|subagent| subagent.name)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn watches_agents_dir_created_after_start() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cwd = TempDir::new().expect("tempdir");
+        let manager = Arc::new(SubAgentsManager::new(
+            codex_home.path().to_path_buf(),
+            vec![codex_home.path().join("team").join("agents")],
+        ));
+        let (tx_event, rx_event) = async_channel::unbounded();
+        manager.start_watching(cwd.path().to_path_buf(), tx_event);
+
+        // Neither `team/` nor `team/agents/` exists yet.
+        let agents_dir = codex_home.path().join("team").join("agents");
+        write_agent(&agents_dir, "reviewer", "Reviews diffs");
+        assert_eq!(next_names(&rx_event).await, vec!["reviewer".to_string()]);
+
+        // Once the directory exists, files added to it are noticed too.
+        tokio::time::sleep(RELOAD_DEBOUNCE).await;
+        write_agent(&agents_dir, "tester", "Runs tests");
+        let mut names = next_names(&rx_event).await;
+        while names.len() < 2 {
+            names = next_names(&rx_event).await;
+        }
+        assert_eq!(names, vec!["reviewer".to_string(), "tester".to_string()]);
+    }
+}
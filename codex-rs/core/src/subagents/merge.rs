@@ -0,0 +1,664 @@
+//! Merge coordinator for changes staged by subagent runs.
+//!
+//! With `subagents.stage_changes`, every `Op::RunSubAgent` run edits its own
+//! git worktree checked out from a snapshot of the session's working tree,
+//! and the resulting diff is parked here instead of being written back.
+//! `Op::MergeSubAgentChanges` combines the staged diffs: hunks from
+//! different runs that do not overlap (context lines included) are merged
+//! into one diff, and files where they do overlap become conflicts. Nothing
+//! touches the working tree until `Op::ApplySubAgentMerge` applies the
+//! merged diff together with the version of each conflicted file the user
+//! picked.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use codex_git::CreateGhostCommitOptions;
+use codex_git::add_detached_worktree;
+use codex_git::apply_diff_to_worktree;
+use codex_git::create_ghost_commit;
+use codex_git::remove_worktree;
+use codex_git::worktree_diff;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::SubAgentMergeAppliedEvent;
+use codex_protocol::protocol::SubAgentMergePlanEvent;
+use codex_protocol::subagents::SubAgentMergeCandidate;
+use codex_protocol::subagents::SubAgentMergeConflict;
+use codex_protocol::subagents::SubAgentMergeResolution;
+use codex_protocol::subagents::SubAgentMergeRun;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::git_info::get_git_repo_root;
+
+/// Diff produced by one finished subagent run, waiting to be merged.
+#[derive(Debug, Clone)]
+pub(crate) struct StagedChanges {
+    pub(crate) run_id: String,
+    pub(crate) name: String,
+    pub(crate) diff: String,
+}
+
+#[derive(Debug, Clone)]
+struct PendingMerge {
+    runs: Vec<SubAgentMergeRun>,
+    plan: MergePlan,
+}
+
+#[derive(Default)]
+struct MergeState {
+    staged: Vec<StagedChanges>,
+    pending: HashMap<String, PendingMerge>,
+}
+
+#[derive(Default)]
+pub(crate) struct SubAgentMerges {
+    state: Mutex<MergeState>,
+    next_id: AtomicU64,
+}
+
+impl SubAgentMerges {
+    pub(crate) async fn stage(&self, changes: StagedChanges) {
+        self.state.lock().await.staged.push(changes);
+    }
+
+    /// Plans a merge of the staged runs in `run_ids` (all of them when empty)
+    /// and keeps it until it is applied.
+    async fn plan(&self, run_ids: &[String]) -> Result<SubAgentMergePlanEvent, String> {
+        let mut state = self.state.lock().await;
+        if let Some(unknown) = run_ids
+            .iter()
+            .find(|id| !state.staged.iter().any(|staged| &staged.run_id == *id))
+        {
+            return Err(format!("no staged changes for subagent run `{unknown}`"));
+        }
+        let selected: Vec<StagedChanges> = state
+            .staged
+            .iter()
+            .filter(|staged| run_ids.is_empty() || run_ids.contains(&staged.run_id))
+            .cloned()
+            .collect();
+        if selected.is_empty() {
+            return Err("No staged subagent changes to merge.".to_string());
+        }
+
+        let plan = plan_merge(&selected);
+        let runs: Vec<SubAgentMergeRun> = selected
+            .into_iter()
+            .map(|staged| SubAgentMergeRun {
+                run_id: staged.run_id,
+                name: staged.name,
+            })
+            .collect();
+        let merge_id = (self.next_id.fetch_add(1, Ordering::Relaxed) + 1).to_string();
+        let event = SubAgentMergePlanEvent {
+            merge_id: merge_id.clone(),
+            runs: runs.clone(),
+            merged_files: plan.merged.keys().cloned().collect(),
+            conflicts: plan.conflicts.clone(),
+        };
+        state.pending.insert(merge_id, PendingMerge { runs, plan });
+        Ok(event)
+    }
+
+    async fn pending(&self, merge_id: &str) -> Option<PendingMerge> {
+        self.state.lock().await.pending.get(merge_id).cloned()
+    }
+
+    /// Drops the staged changes of runs that were merged, along with every
+    /// pending plan that included them.
+    async fn finish(&self, runs: &[SubAgentMergeRun]) {
+        let merged = |run_id: &str| runs.iter().any(|run| run.run_id == run_id);
+        let mut state = self.state.lock().await;
+        state.staged.retain(|staged| !merged(&staged.run_id));
+        state
+            .pending
+            .retain(|_, pending| !pending.runs.iter().any(|run| merged(&run.run_id)));
+    }
+}
+
+/// Worktree a staged subagent run edits instead of the session's checkout.
+pub(crate) struct StagingWorktree {
+    repo_root: PathBuf,
+    path: PathBuf,
+    base_commit: String,
+    /// Working directory for the child thread inside the worktree.
+    pub(crate) cwd: PathBuf,
+}
+
+/// Snapshots the working tree at `cwd` (uncommitted edits included) and
+/// checks the snapshot out into a fresh worktree under `codex_home`.
+pub(crate) async fn create_staging_worktree(
+    cwd: &Path,
+    codex_home: &Path,
+) -> Result<StagingWorktree, String> {
+    let Some(repo_root) = get_git_repo_root(cwd) else {
+        return Err("subagents.stage_changes requires a Git repository.".to_string());
+    };
+    let cwd = cwd.to_path_buf();
+    let path = codex_home
+        .join("subagent-worktrees")
+        .join(uuid::Uuid::new_v4().to_string());
+    let result = tokio::task::spawn_blocking(move || {
+        let base = create_ghost_commit(
+            &CreateGhostCommitOptions::new(&repo_root).message("codex subagent base"),
+        )?;
+        add_detached_worktree(&repo_root, &path, base.id())?;
+        let cwd = match cwd.strip_prefix(&repo_root) {
+            Ok(relative) => path.join(relative),
+            Err(_) => path.clone(),
+        };
+        Ok::<_, codex_git::GitToolingError>(StagingWorktree {
+            repo_root,
+            path,
+            base_commit: base.id().to_string(),
+            cwd,
+        })
+    })
+    .await;
+    match result {
+        Ok(Ok(worktree)) => Ok(worktree),
+        Ok(Err(err)) => Err(format!(
+            "Failed to create a worktree for the subagent: {err}"
+        )),
+        Err(err) => Err(format!("Subagent worktree task failed: {err}")),
+    }
+}
+
+/// Collects the run's changes from its worktree, removes the worktree, and
+/// stages the changes. Returns the files the run changed.
+pub(crate) async fn stage_worktree_changes(
+    sess: &Session,
+    worktree: StagingWorktree,
+    run_id: String,
+    name: String,
+) -> Vec<PathBuf> {
+    let result = tokio::task::spawn_blocking(move || {
+        let diff = worktree_diff(&worktree.path, &worktree.base_commit);
+        if let Err(err) = remove_worktree(&worktree.repo_root, &worktree.path) {
+            warn!(
+                "failed to remove subagent worktree {}: {err}",
+                worktree.path.display()
+            );
+        }
+        diff
+    })
+    .await;
+    let diff = match result {
+        Ok(Ok(diff)) => diff,
+        Ok(Err(err)) => {
+            warn!("failed to collect changes of subagent run {run_id}: {err}");
+            return Vec::new();
+        }
+        Err(err) => {
+            warn!("subagent worktree task failed: {err}");
+            return Vec::new();
+        }
+    };
+
+    let files: Vec<PathBuf> = parse_diff(&diff)
+        .into_iter()
+        .map(|file| file.path)
+        .collect();
+    if !files.is_empty() {
+        sess.services
+            .subagent_merges
+            .stage(StagedChanges { run_id, name, diff })
+            .await;
+    }
+    files
+}
+
+/// Removes the worktree of a run that never started.
+pub(crate) async fn discard_staging_worktree(worktree: StagingWorktree) {
+    let result = tokio::task::spawn_blocking(move || {
+        remove_worktree(&worktree.repo_root, &worktree.path)
+            .map_err(|err| format!("{}: {err}", worktree.path.display()))
+    })
+    .await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(message)) => warn!("failed to remove subagent worktree {message}"),
+        Err(err) => warn!("subagent worktree task failed: {err}"),
+    }
+}
+
+pub(crate) async fn merge_subagent_changes(sess: &Session, sub_id: String, run_ids: Vec<String>) {
+    let msg = match sess.services.subagent_merges.plan(&run_ids).await {
+        Ok(plan) => EventMsg::SubAgentMergePlan(plan),
+        Err(message) => error_msg(message),
+    };
+    sess.send_event_raw(Event { id: sub_id, msg }).await;
+}
+
+pub(crate) async fn apply_subagent_merge(
+    sess: &Session,
+    sub_id: String,
+    merge_id: String,
+    resolutions: Vec<SubAgentMergeResolution>,
+) {
+    let msg = match apply_merge(sess, &sub_id, &merge_id, &resolutions).await {
+        Ok(applied_files) => EventMsg::SubAgentMergeApplied(SubAgentMergeAppliedEvent {
+            merge_id,
+            applied_files,
+        }),
+        Err(message) => error_msg(message),
+    };
+    sess.send_event_raw(Event { id: sub_id, msg }).await;
+}
+
+async fn apply_merge(
+    sess: &Session,
+    sub_id: &str,
+    merge_id: &str,
+    resolutions: &[SubAgentMergeResolution],
+) -> Result<Vec<PathBuf>, String> {
+    let merges = &sess.services.subagent_merges;
+    let Some(pending) = merges.pending(merge_id).await else {
+        return Err(format!("unknown subagent merge `{merge_id}`"));
+    };
+    let (diff, applied_files) = pending.plan.resolve(resolutions)?;
+
+    let turn = sess.new_default_turn_with_sub_id(sub_id.to_string()).await;
+    let repo_path = turn.cwd.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        // Check first so a diff that no longer applies leaves nothing behind.
+        apply_diff_to_worktree(&repo_path, &diff, true)?;
+        apply_diff_to_worktree(&repo_path, &diff, false)
+    })
+    .await;
+    match result {
+        Ok(Ok(())) => {
+            merges.finish(&pending.runs).await;
+            Ok(applied_files)
+        }
+        Ok(Err(err)) => Err(format!(
+            "The merged changes no longer apply to the working tree: {err}"
+        )),
+        Err(err) => Err(format!("Subagent merge task failed: {err}")),
+    }
+}
+
+fn error_msg(message: String) -> EventMsg {
+    EventMsg::Error(ErrorEvent {
+        message,
+        codex_error_info: None,
+    })
+}
+
+/// Result of combining the staged diffs of several runs.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct MergePlan {
+    /// Diff per file that merged without overlapping edits.
+    merged: BTreeMap<PathBuf, String>,
+    conflicts: Vec<SubAgentMergeConflict>,
+}
+
+impl MergePlan {
+    /// Builds the diff to apply, taking the chosen run's version of each
+    /// conflicted file. Every conflict needs a resolution.
+    fn resolve(
+        &self,
+        resolutions: &[SubAgentMergeResolution],
+    ) -> Result<(String, Vec<PathBuf>), String> {
+        let mut diff: String = self.merged.values().map(String::as_str).collect();
+        let mut files: Vec<PathBuf> = self.merged.keys().cloned().collect();
+        for conflict in &self.conflicts {
+            let Some(resolution) = resolutions
+                .iter()
+                .find(|resolution| resolution.path == conflict.path)
+            else {
+                return Err(format!(
+                    "missing resolution for conflicting file {}",
+                    conflict.path.display()
+                ));
+            };
+            let Some(run_id) = &resolution.run_id else {
+                continue;
+            };
+            let Some(candidate) = conflict
+                .candidates
+                .iter()
+                .find(|candidate| &candidate.run_id == run_id)
+            else {
+                return Err(format!(
+                    "subagent run `{run_id}` did not change {}",
+                    conflict.path.display()
+                ));
+            };
+            diff.push_str(&candidate.diff);
+            files.push(conflict.path.clone());
+        }
+        Ok((diff, files))
+    }
+}
+
+fn plan_merge(staged: &[StagedChanges]) -> MergePlan {
+    let mut by_path: BTreeMap<PathBuf, Vec<(&StagedChanges, FileDiff)>> = BTreeMap::new();
+    for changes in staged {
+        for file in parse_diff(&changes.diff) {
+            by_path
+                .entry(file.path.clone())
+                .or_default()
+                .push((changes, file));
+        }
+    }
+
+    let mut plan = MergePlan::default();
+    for (path, entries) in by_path {
+        if let [(_, file)] = entries.as_slice() {
+            plan.merged.insert(path, file.text.clone());
+            continue;
+        }
+        let files: Vec<&FileDiff> = entries.iter().map(|(_, file)| file).collect();
+        if files_overlap(&files) {
+            plan.conflicts.push(SubAgentMergeConflict {
+                path,
+                candidates: entries
+                    .iter()
+                    .map(|(changes, file)| SubAgentMergeCandidate {
+                        run_id: changes.run_id.clone(),
+                        name: changes.name.clone(),
+                        diff: file.text.clone(),
+                    })
+                    .collect(),
+            });
+        } else {
+            plan.merged.insert(path, combine_file_diffs(&files));
+        }
+    }
+    plan
+}
+
+/// One file's section of a `git diff`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FileDiff {
+    pub(crate) path: PathBuf,
+    /// The section exactly as git printed it.
+    text: String,
+    /// Lines before the first hunk (`diff --git`, `index`, `---`, `+++`, ...).
+    header: Vec<String>,
+    hunks: Vec<Hunk>,
+    /// Creations, deletions, renames, and binary or mode-only changes cannot
+    /// be merged hunk by hunk.
+    whole_file: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Hunk {
+    old_start: u64,
+    old_len: u64,
+    new_len: u64,
+    /// Text after the closing `@@`, such as the enclosing function name.
+    section: String,
+    lines: Vec<String>,
+}
+
+impl FileDiff {
+    /// Lines added and removed across all hunks.
+    pub(crate) fn line_counts(&self) -> (u32, u32) {
+        let lines = self.hunks.iter().flat_map(|hunk| &hunk.lines);
+        lines.fold((0, 0), |(added, removed), line| {
+            if line.starts_with('+') {
+                (added + 1, removed)
+            } else if line.starts_with('-') {
+                (added, removed + 1)
+            } else {
+                (added, removed)
+            }
+        })
+    }
+}
+
+impl Hunk {
+    /// Lines of the original file the hunk covers, as a half-open range.
+    /// Insertions into an empty range still claim the line they follow.
+    fn old_range(&self) -> (u64, u64) {
+        (self.old_start, self.old_start + self.old_len.max(1))
+    }
+}
+
+pub(crate) fn parse_diff(diff: &str) -> Vec<FileDiff> {
+    let mut sections: Vec<Vec<&str>> = Vec::new();
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff --git ") || sections.is_empty() {
+            sections.push(Vec::new());
+        }
+        if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+    sections
+        .into_iter()
+        .filter_map(|lines| parse_file_diff(&lines))
+        .collect()
+}
+
+fn parse_file_diff(lines: &[&str]) -> Option<FileDiff> {
+    let first = lines.first()?;
+    let mut header = Vec::new();
+    let mut hunks: Vec<Hunk> = Vec::new();
+    for line in lines {
+        if let Some(hunk) = parse_hunk_header(line) {
+            hunks.push(hunk);
+        } else if let Some(hunk) = hunks.last_mut() {
+            hunk.lines.push((*line).to_string());
+        } else {
+            header.push((*line).to_string());
+        }
+    }
+
+    let new_path = header
+        .iter()
+        .find_map(|line| line.strip_prefix("+++ b/"))
+        .or_else(|| header.iter().find_map(|line| line.strip_prefix("--- a/")))
+        .or_else(|| {
+            first
+                .strip_prefix("diff --git ")
+                .and_then(|paths| paths.rsplit_once(" b/"))
+                .map(|(_, path)| path)
+        })?;
+    let whole_file = hunks.is_empty()
+        || header.iter().any(|line| {
+            [
+                "new file mode",
+                "deleted file mode",
+                "rename from",
+                "copy from",
+                "Binary files",
+                "GIT binary patch",
+            ]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        });
+    Some(FileDiff {
+        path: PathBuf::from(new_path.trim_end()),
+        text: lines.concat(),
+        header,
+        hunks,
+        whole_file,
+    })
+}
+
+/// Parses `@@ -old_start[,old_len] +new_start[,new_len] @@[ section]`.
+fn parse_hunk_header(line: &str) -> Option<Hunk> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let parse_range = |range: &str| -> Option<(u64, u64)> {
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_len) = parse_range(old)?;
+    let (_, new_len) = parse_range(new)?;
+    Some(Hunk {
+        old_start,
+        old_len,
+        new_len,
+        section: section.trim_end_matches('\n').to_string(),
+        lines: Vec::new(),
+    })
+}
+
+/// Whether edits from different runs to the same file touch the same lines.
+/// Hunks that merely touch count as overlapping, as they do for `git merge`.
+fn files_overlap(files: &[&FileDiff]) -> bool {
+    if files.iter().any(|file| file.whole_file) {
+        return true;
+    }
+    files.iter().enumerate().any(|(idx, file)| {
+        files[idx + 1..].iter().any(|other| {
+            file.hunks.iter().any(|hunk| {
+                other.hunks.iter().any(|other_hunk| {
+                    let (start, end) = hunk.old_range();
+                    let (other_start, other_end) = other_hunk.old_range();
+                    start <= other_end && other_start <= end
+                })
+            })
+        })
+    })
+}
+
+/// Combines non-overlapping edits to one file into a single diff section,
+/// renumbering the new-file side of each hunk.
+fn combine_file_diffs(files: &[&FileDiff]) -> String {
+    let mut hunks: Vec<&Hunk> = files.iter().flat_map(|file| file.hunks.iter()).collect();
+    hunks.sort_by_key(|hunk| hunk.old_start);
+
+    // Blob ids in the `index` line describe one run's result, not the merge.
+    let mut text: String = files
+        .first()
+        .map(|file| {
+            file.header
+                .iter()
+                .filter(|line| !line.starts_with("index "))
+                .map(String::as_str)
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut offset: i64 = 0;
+    for hunk in hunks {
+        let mut new_start = hunk.old_start as i64 + offset;
+        if hunk.old_len == 0 {
+            new_start += 1;
+        }
+        if hunk.new_len == 0 {
+            new_start -= 1;
+        }
+        text.push_str(&format!(
+            "@@ -{},{} +{},{} @@{}\n",
+            hunk.old_start,
+            hunk.old_len,
+            new_start.max(0),
+            hunk.new_len,
+            hunk.section
+        ));
+        text.extend(hunk.lines.iter().map(String::as_str));
+        offset += hunk.new_len as i64 - hunk.old_len as i64;
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const BASE_DIFF_HEADER: &str = "diff --git a/src/lib.rs b/src/lib.rs\nindex 1111111..2222222 100644\n--- a/src/lib.rs\n+++ b/src/lib.rs\n";
+
+    fn staged(run_id: &str, diff: &str) -> StagedChanges {
+        StagedChanges {
+            run_id: run_id.to_string(),
+            name: format!("agent-{run_id}"),
+            diff: diff.to_string(),
+        }
+    }
+
+    fn hunk_at(line: u64, added: &str) -> String {
+        format!("@@ -{line},3 +{line},4 @@ fn f()\n ctx1\n ctx2\n+{added}\n ctx3\n")
+    }
+
+    #[test]
+    fn disjoint_hunks_in_one_file_are_merged_and_renumbered() {
+        let plan = plan_merge(&[
+            staged("a", &format!("{BASE_DIFF_HEADER}{}", hunk_at(1, "first"))),
+            staged("b", &format!("{BASE_DIFF_HEADER}{}", hunk_at(20, "second"))),
+        ]);
+
+        assert_eq!(plan.conflicts, Vec::new());
+        assert_eq!(
+            plan.merged.get(Path::new("src/lib.rs")).map(String::as_str),
+            Some(
+                "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+                 @@ -1,3 +1,4 @@ fn f()\n ctx1\n ctx2\n+first\n ctx3\n\
+                 @@ -20,3 +21,4 @@ fn f()\n ctx1\n ctx2\n+second\n ctx3\n"
+            )
+        );
+    }
+
+    #[test]
+    fn overlapping_hunks_become_a_conflict_with_each_runs_diff() {
+        let a = format!("{BASE_DIFF_HEADER}{}", hunk_at(10, "from a"));
+        let b = format!("{BASE_DIFF_HEADER}{}", hunk_at(12, "from b"));
+        let other = "diff --git a/README.md b/README.md\nnew file mode 100644\nindex 0000000..3333333\n--- /dev/null\n+++ b/README.md\n@@ -0,0 +1 @@\n+hello\n";
+        let plan = plan_merge(&[staged("a", &a), staged("b", &format!("{b}{other}"))]);
+
+        assert_eq!(
+            plan,
+            MergePlan {
+                merged: BTreeMap::from([(PathBuf::from("README.md"), other.to_string())]),
+                conflicts: vec![SubAgentMergeConflict {
+                    path: PathBuf::from("src/lib.rs"),
+                    candidates: vec![
+                        SubAgentMergeCandidate {
+                            run_id: "a".to_string(),
+                            name: "agent-a".to_string(),
+                            diff: a,
+                        },
+                        SubAgentMergeCandidate {
+                            run_id: "b".to_string(),
+                            name: "agent-b".to_string(),
+                            diff: b.clone(),
+                        },
+                    ],
+                }],
+            }
+        );
+
+        assert_eq!(
+            plan.resolve(&[]),
+            Err("missing resolution for conflicting file src/lib.rs".to_string())
+        );
+        assert_eq!(
+            plan.resolve(&[SubAgentMergeResolution {
+                path: PathBuf::from("src/lib.rs"),
+                run_id: Some("b".to_string()),
+            }]),
+            Ok((
+                format!("{other}{b}"),
+                vec![PathBuf::from("README.md"), PathBuf::from("src/lib.rs")]
+            ))
+        );
+    }
+
+    #[test]
+    fn files_created_by_two_runs_conflict() {
+        let created = "diff --git a/notes.txt b/notes.txt\nnew file mode 100644\n--- /dev/null\n+++ b/notes.txt\n@@ -0,0 +1 @@\n+note\n";
+        let plan = plan_merge(&[staged("a", created), staged("b", created)]);
+
+        assert_eq!(
+            (plan.merged.len(), plan.conflicts.len()),
+            (0, 1),
+            "both runs created notes.txt"
+        );
+    }
+}
@@ -0,0 +1,14 @@
+pub mod loader;
+pub(crate) mod manager;
+pub(crate) mod merge;
+pub mod model;
+pub(crate) mod runner;
+
+pub use loader::load_subagents;
+pub use loader::subagent_search_roots;
+pub(crate) use manager::SubAgentsManager;
+pub(crate) use merge::SubAgentMerges;
+pub use model::SubAgentDefinition;
+pub use model::SubAgentLoadOutcome;
+pub use runner::apply_definition;
+pub use runner::preview_subagent;
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::subagents::SubAgentInfo;
+use codex_protocol::subagents::SubAgentLoadError;
+use codex_protocol::subagents::SubAgentSource;
+
+/// A subagent loaded from `<name>.md`: YAML frontmatter plus a Markdown body
+/// that becomes the child thread's developer instructions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubAgentDefinition {
+    pub name: String,
+    pub description: String,
+    pub color: Option<String>,
+    pub sandbox_mode: Option<SandboxMode>,
+    pub model: Option<String>,
+    /// Key into `model_providers`; lets a subagent run on a different
+    /// provider than its parent.
+    pub model_provider: Option<String>,
+    /// When set, the child's exec environment keeps only these variables
+    /// (wildcards allowed) plus the keys of `env_set`.
+    pub env_allowed: Option<Vec<String>>,
+    /// Fixed variables injected into the child's exec environment.
+    pub env_set: HashMap<String, String>,
+    pub instructions: String,
+    pub path: PathBuf,
+    pub source: SubAgentSource,
+}
+
+impl SubAgentDefinition {
+    pub fn to_info(&self) -> SubAgentInfo {
+        SubAgentInfo {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            color: self.color.clone(),
+            sandbox_mode: self.sandbox_mode,
+            model: self.model.clone(),
+            path: self.path.clone(),
+            source: self.source,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SubAgentLoadOutcome {
+    pub subagents: Vec<SubAgentDefinition>,
+    pub errors: Vec<SubAgentLoadError>,
+}
+
+impl SubAgentLoadOutcome {
+    pub fn find(&self, name: &str) -> Option<&SubAgentDefinition> {
+        self.subagents.iter().find(|subagent| subagent.name == name)
+    }
+}
@@ -0,0 +1,565 @@
+//! Runs subagent definitions as child threads on behalf of `Op::RunSubAgent`.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::agent::AgentStatus;
+use crate::agent::status::is_final;
+use crate::codex::Session;
+use crate::config::Config;
+use crate::config::types::EnvironmentVariablePattern;
+use crate::config::types::ShellEnvironmentPolicyInherit;
+use crate::models_manager::manager::ModelsManager;
+use crate::models_manager::manager::RefreshStrategy;
+use crate::subagents::SubAgentDefinition;
+use crate::subagents::merge::create_staging_worktree;
+use crate::subagents::merge::discard_staging_worktree;
+use crate::subagents::merge::stage_worktree_changes;
+use crate::tools::handlers::collab::build_agent_spawn_config;
+use crate::tools::spec::ToolsConfig;
+use crate::tools::spec::ToolsConfigParams;
+use crate::tools::spec::build_specs;
+use codex_protocol::ThreadId;
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::SandboxPolicy;
+use codex_protocol::protocol::SubAgentRunBeginEvent;
+use codex_protocol::protocol::SubAgentRunEndEvent;
+use codex_protocol::subagents::SubAgentPreview;
+use tracing::Instrument;
+use tracing::Span;
+use tracing::field;
+use tracing::trace_span;
+use tracing::warn;
+
+pub(crate) async fn run_subagent(
+    sess: &Arc<Session>,
+    sub_id: String,
+    name: String,
+    prompt: String,
+    dry_run: bool,
+) {
+    let turn = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+    let outcome = sess.services.subagents_manager.subagents_for_cwd(&turn.cwd);
+    let Some(definition) = outcome.find(&name) else {
+        send_error(sess, sub_id, format!("unknown subagent `{name}`")).await;
+        return;
+    };
+
+    let parent_config = match build_agent_spawn_config(&sess.get_base_instructions().await, &turn) {
+        Ok(config) => config,
+        Err(err) => {
+            send_error(sess, sub_id, err.to_string()).await;
+            return;
+        }
+    };
+
+    if dry_run {
+        match preview_subagent(
+            parent_config,
+            definition,
+            prompt,
+            &sess.services.models_manager,
+        )
+        .await
+        {
+            Ok(preview) => {
+                sess.send_event(&turn, EventMsg::SubAgentPreview(preview))
+                    .await;
+            }
+            Err(message) => send_error(sess, sub_id, message).await,
+        }
+        return;
+    }
+
+    let mut config = parent_config;
+    if let Err(message) = apply_definition(&mut config, definition) {
+        send_error(sess, sub_id, message).await;
+        return;
+    }
+
+    // Staged runs edit a private worktree; their changes wait for a merge.
+    let worktree = if config.subagent_stage_changes {
+        match create_staging_worktree(&turn.cwd, &config.codex_home).await {
+            Ok(worktree) => {
+                config.cwd = worktree.cwd.clone();
+                Some(worktree)
+            }
+            Err(message) => {
+                send_error(sess, sub_id, message).await;
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
+    let agent_control = &sess.services.agent_control;
+    let thread_id = match agent_control.spawn_agent(config, prompt.clone()).await {
+        Ok(thread_id) => thread_id,
+        Err(err) => {
+            if let Some(worktree) = worktree {
+                discard_staging_worktree(worktree).await;
+            }
+            send_error(
+                sess,
+                sub_id,
+                format!("failed to start subagent `{name}`: {err}"),
+            )
+            .await;
+            return;
+        }
+    };
+
+    let run_id = thread_id.to_string();
+    sess.send_event(
+        &turn,
+        EventMsg::SubAgentRunBegin(SubAgentRunBeginEvent {
+            run_id: run_id.clone(),
+            name: name.clone(),
+            prompt,
+        }),
+    )
+    .await;
+
+    let run_span = trace_span!(
+        "subagent",
+        otel.name = "codex.subagent",
+        subagent.name = %name,
+        run.id = %run_id,
+        status = field::Empty,
+    );
+    let started = Instant::now();
+    let sess = Arc::clone(sess);
+    tokio::spawn(
+        async move {
+            let status = wait_for_final_status(&sess, thread_id).await;
+            let outcome = match &status {
+                AgentStatus::Completed(_) => "completed",
+                AgentStatus::Errored(_) => "errored",
+                AgentStatus::Shutdown => "shutdown",
+                AgentStatus::PendingInit | AgentStatus::Running | AgentStatus::NotFound => "lost",
+            };
+            Span::current().record("status", outcome);
+            let otel = &sess.services.otel_manager;
+            otel.counter("codex.subagent.run", 1, &[("status", outcome)]);
+            otel.record_duration(
+                "codex.subagent.run.duration_ms",
+                started.elapsed(),
+                &[("status", outcome)],
+            );
+            let token_usage = sess.services.agent_control.get_token_usage(thread_id).await;
+            sess.record_subagent_usage(&name, token_usage.as_ref())
+                .await;
+            // Runs are one-shot: release the thread slot once the child is done.
+            if !matches!(status, AgentStatus::Shutdown | AgentStatus::NotFound)
+                && let Err(err) = sess.services.agent_control.shutdown_agent(thread_id).await
+            {
+                warn!("failed to shut down subagent thread {thread_id}: {err}");
+            }
+            let staged_files = match worktree {
+                Some(worktree) => {
+                    stage_worktree_changes(&sess, worktree, run_id.clone(), name.clone()).await
+                }
+                None => Vec::new(),
+            };
+            sess.send_event(
+                &turn,
+                EventMsg::SubAgentRunEnd(SubAgentRunEndEvent {
+                    run_id,
+                    name,
+                    status,
+                    staged_files,
+                    token_usage,
+                }),
+            )
+            .await;
+        }
+        .instrument(run_span),
+    );
+}
+
+pub(crate) async fn cancel_subagent(sess: &Session, sub_id: String, run_id: String) {
+    let Ok(thread_id) = ThreadId::from_string(&run_id) else {
+        send_error(sess, sub_id, format!("invalid subagent run id `{run_id}`")).await;
+        return;
+    };
+    if let Err(err) = sess.services.agent_control.shutdown_agent(thread_id).await {
+        send_error(
+            sess,
+            sub_id,
+            format!("failed to cancel subagent run: {err}"),
+        )
+        .await;
+    }
+}
+
+/// Resolves what running `definition` on top of `parent_config` would do —
+/// model, policies, tools, and rendered instructions — without spawning a
+/// child thread.
+pub async fn preview_subagent(
+    parent_config: Config,
+    definition: &SubAgentDefinition,
+    prompt: String,
+    models_manager: &ModelsManager,
+) -> Result<SubAgentPreview, String> {
+    let mut config = parent_config;
+    apply_definition(&mut config, definition)?;
+
+    let model = models_manager
+        .get_default_model(&config.model, &config, RefreshStrategy::Offline)
+        .await;
+    let model_info = models_manager.get_model_info(&model, &config).await;
+    let tools_config = ToolsConfig::new(&ToolsConfigParams {
+        model_info: &model_info,
+        features: &config.features,
+        web_search_mode: config.web_search_mode,
+    });
+    let (specs, _registry) = build_specs(&tools_config, None).build();
+    let mut mcp_servers: Vec<String> = config
+        .mcp_servers
+        .get()
+        .iter()
+        .filter(|(_, server)| server.enabled)
+        .map(|(name, _)| name.clone())
+        .collect();
+    mcp_servers.sort();
+
+    Ok(SubAgentPreview {
+        name: definition.name.clone(),
+        path: definition.path.clone(),
+        model,
+        approval_policy: config.approval_policy.value(),
+        sandbox_policy: config.sandbox_policy.get().clone(),
+        tools: specs
+            .iter()
+            .map(|spec| spec.spec.name().to_string())
+            .collect(),
+        mcp_servers,
+        developer_instructions: config.developer_instructions,
+        prompt,
+    })
+}
+
+/// Layers the definition's instructions, model, provider, environment, and
+/// sandbox mode on top of the parent turn's settings.
+pub fn apply_definition(
+    config: &mut Config,
+    definition: &SubAgentDefinition,
+) -> Result<(), String> {
+    if !definition.instructions.is_empty() {
+        config.developer_instructions = Some(match config.developer_instructions.take() {
+            Some(existing) => format!("{existing}\n\n{}", definition.instructions),
+            None => definition.instructions.clone(),
+        });
+    }
+    if let Some(model) = &definition.model {
+        config.model = Some(model.clone());
+    }
+    if let Some(provider_id) = &definition.model_provider {
+        let Some(provider) = config.model_providers.get(provider_id) else {
+            return Err(format!("model_provider `{provider_id}` is not defined"));
+        };
+        // The managed policy is only checked when the config is loaded, so a
+        // definition must not be able to switch to a provider it disallows.
+        config
+            .config_layer_stack
+            .requirements()
+            .check_model_provider(provider_id)
+            .map_err(|err| err.to_string())?;
+        config.model_provider = provider.clone();
+        config.model_provider_id = provider_id.clone();
+    }
+    apply_env_policy(config, definition);
+    if let Some(sandbox_mode) = definition.sandbox_mode {
+        let policy = match sandbox_mode {
+            SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
+            SandboxMode::WorkspaceWrite => SandboxPolicy::new_workspace_write_policy(),
+            SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
+        };
+        config
+            .sandbox_policy
+            .set(policy)
+            .map_err(|err| format!("sandbox_policy is invalid: {err}"))?;
+    }
+    Ok(())
+}
+
+/// Narrows the inherited shell environment policy: `env_allowed` is
+/// intersected with the parent's `include_only` (so a definition can only
+/// drop variables, never regain ones the parent withholds) and `env_set`
+/// entries are injected on top.
+fn apply_env_policy(config: &mut Config, definition: &SubAgentDefinition) {
+    let policy = &mut config.shell_environment_policy;
+    if let Some(allowed) = &definition.env_allowed {
+        let allowed: Vec<EnvironmentVariablePattern> = allowed
+            .iter()
+            .map(|name| EnvironmentVariablePattern::new_case_insensitive(name))
+            .collect();
+        let allowed = if policy.include_only.is_empty() {
+            allowed
+        } else {
+            // Glob patterns cannot be intersected directly, so resolve both
+            // lists against the variables the parent could pass on.
+            let mut names: Vec<String> = std::env::vars()
+                .map(|(name, _)| name)
+                .chain(policy.r#set.keys().cloned())
+                .filter(|name| {
+                    policy.include_only.iter().any(|p| p.matches(name))
+                        && allowed.iter().any(|p| p.matches(name))
+                })
+                .collect();
+            names.sort();
+            names.dedup();
+            names
+                .iter()
+                .map(|name| EnvironmentVariablePattern::new_case_insensitive(name))
+                .collect()
+        };
+        policy.include_only = allowed
+            .into_iter()
+            .chain(
+                definition
+                    .env_set
+                    .keys()
+                    .map(|name| EnvironmentVariablePattern::new_case_insensitive(name)),
+            )
+            .collect();
+        // An empty allowlist still has to exclude everything.
+        if policy.include_only.is_empty() {
+            policy.inherit = ShellEnvironmentPolicyInherit::None;
+        }
+    }
+    policy.r#set.extend(
+        definition
+            .env_set
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone())),
+    );
+}
+
+pub(crate) async fn wait_for_final_status(sess: &Session, thread_id: ThreadId) -> AgentStatus {
+    let agent_control = &sess.services.agent_control;
+    let Ok(mut status_rx) = agent_control.subscribe_status(thread_id).await else {
+        return agent_control.get_status(thread_id).await;
+    };
+    loop {
+        let status = status_rx.borrow().clone();
+        if is_final(&status) {
+            return status;
+        }
+        if status_rx.changed().await.is_err() {
+            return agent_control.get_status(thread_id).await;
+        }
+    }
+}
+
+async fn send_error(sess: &Session, sub_id: String, message: String) {
+    sess.send_event_raw(Event {
+        id: sub_id,
+        msg: EventMsg::Error(ErrorEvent {
+            message,
+            codex_error_info: None,
+        }),
+    })
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codex::make_session_and_context;
+    use crate::config_loader::ConfigLayerStack;
+    use crate::config_loader::ConfigRequirements;
+    use crate::config_loader::ConfigRequirementsToml;
+    use crate::config_loader::RequirementSource;
+    use crate::config_loader::Sourced;
+    use codex_protocol::subagents::SubAgentSource;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn definition() -> SubAgentDefinition {
+        SubAgentDefinition {
+            name: "reviewer".to_string(),
+            description: "Reviews diffs".to_string(),
+            color: None,
+            sandbox_mode: Some(SandboxMode::ReadOnly),
+            model: Some("gpt-5.1".to_string()),
+            model_provider: None,
+            env_allowed: None,
+            env_set: HashMap::new(),
+            instructions: "Only review.".to_string(),
+            path: PathBuf::from("/tmp/reviewer.md"),
+            source: SubAgentSource::Repo,
+        }
+    }
+
+    #[tokio::test]
+    async fn apply_definition_layers_overrides_on_parent_config() {
+        let (_session, turn) = make_session_and_context().await;
+        let mut config = (*turn.client.config()).clone();
+        config.developer_instructions = Some("Parent instructions.".to_string());
+        config
+            .sandbox_policy
+            .set(SandboxPolicy::DangerFullAccess)
+            .expect("set sandbox policy");
+
+        apply_definition(&mut config, &definition()).expect("apply definition");
+
+        assert_eq!(
+            (
+                config.developer_instructions,
+                config.model,
+                config.sandbox_policy.get().clone(),
+            ),
+            (
+                Some("Parent instructions.\n\nOnly review.".to_string()),
+                Some("gpt-5.1".to_string()),
+                SandboxPolicy::new_read_only_policy(),
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn model_provider_switches_the_child_provider() {
+        let (_session, turn) = make_session_and_context().await;
+        let mut config = (*turn.client.config()).clone();
+        let definition = SubAgentDefinition {
+            model: Some("claude-sonnet-4-5".to_string()),
+            model_provider: Some("anthropic".to_string()),
+            ..definition()
+        };
+
+        apply_definition(&mut config, &definition).expect("apply definition");
+        assert_eq!(
+            (
+                config.model_provider_id.as_str(),
+                config.model_provider.name.as_str()
+            ),
+            ("anthropic", "Anthropic")
+        );
+
+        let unknown = SubAgentDefinition {
+            model_provider: Some("nope".to_string()),
+            ..definition()
+        };
+        assert_eq!(
+            apply_definition(&mut config, &unknown),
+            Err("model_provider `nope` is not defined".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn model_provider_must_be_allowed_by_requirements() {
+        let (_session, turn) = make_session_and_context().await;
+        let mut config = (*turn.client.config()).clone();
+        config.config_layer_stack = ConfigLayerStack::new(
+            Vec::new(),
+            ConfigRequirements {
+                allowed_model_providers: Some(Sourced::new(
+                    vec!["openai".to_string()],
+                    RequirementSource::Unknown,
+                )),
+                ..Default::default()
+            },
+            ConfigRequirementsToml::default(),
+        )
+        .expect("layer stack");
+        let definition = SubAgentDefinition {
+            model_provider: Some("anthropic".to_string()),
+            ..definition()
+        };
+
+        let err = apply_definition(&mut config, &definition).expect_err("provider is not allowed");
+        assert!(
+            err.contains("`anthropic` is not in the allowed set"),
+            "{err}"
+        );
+        assert_eq!(config.model_provider_id, "openai");
+    }
+
+    #[tokio::test]
+    async fn preview_resolves_invocation_without_spawning() {
+        let (session, turn) = make_session_and_context().await;
+        let mut parent_config = (*turn.client.config()).clone();
+        parent_config.developer_instructions = Some("Parent instructions.".to_string());
+
+        let preview = preview_subagent(
+            parent_config.clone(),
+            &definition(),
+            "Review the diff".to_string(),
+            &session.services.models_manager,
+        )
+        .await
+        .expect("preview");
+
+        assert!(!preview.tools.is_empty());
+        assert_eq!(
+            preview,
+            SubAgentPreview {
+                name: "reviewer".to_string(),
+                path: PathBuf::from("/tmp/reviewer.md"),
+                model: "gpt-5.1".to_string(),
+                approval_policy: parent_config.approval_policy.value(),
+                sandbox_policy: SandboxPolicy::new_read_only_policy(),
+                tools: preview.tools.clone(),
+                mcp_servers: Vec::new(),
+                developer_instructions: Some("Parent instructions.\n\nOnly review.".to_string()),
+                prompt: "Review the diff".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn env_allowed_restricts_inherited_environment() {
+        let (_session, turn) = make_session_and_context().await;
+        let mut config = (*turn.client.config()).clone();
+        let definition = SubAgentDefinition {
+            env_allowed: Some(vec!["PATH".to_string(), "LC_*".to_string()]),
+            env_set: HashMap::from([("CI".to_string(), "1".to_string())]),
+            ..definition()
+        };
+
+        apply_definition(&mut config, &definition).expect("apply definition");
+
+        let policy = &config.shell_environment_policy;
+        let visible: Vec<bool> = ["PATH", "LC_ALL", "CI", "OPENAI_API_KEY"]
+            .iter()
+            .map(|name| policy.include_only.iter().any(|p| p.matches(name)))
+            .collect();
+        assert_eq!(
+            (visible, policy.r#set.get("CI").cloned()),
+            (vec![true, true, true, false], Some("1".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn env_allowed_cannot_widen_parent_include_only() {
+        let (_session, turn) = make_session_and_context().await;
+        let mut config = (*turn.client.config()).clone();
+        let parent = &mut config.shell_environment_policy;
+        parent.include_only = vec![EnvironmentVariablePattern::new_case_insensitive("FOO_*")];
+        parent.r#set = HashMap::from([
+            ("FOO_A".to_string(), "1".to_string()),
+            ("FOO_B".to_string(), "2".to_string()),
+            ("BAR".to_string(), "3".to_string()),
+        ]);
+        let definition = SubAgentDefinition {
+            env_allowed: Some(vec!["FOO_A".to_string(), "BAR".to_string()]),
+            env_set: HashMap::from([("CI".to_string(), "1".to_string())]),
+            ..definition()
+        };
+
+        apply_definition(&mut config, &definition).expect("apply definition");
+
+        let policy = &config.shell_environment_policy;
+        let visible: Vec<bool> = ["FOO_A", "FOO_B", "BAR", "CI"]
+            .iter()
+            .map(|name| policy.include_only.iter().any(|p| p.matches(name)))
+            .collect();
+        assert_eq!(visible, vec![true, false, false, true]);
+    }
+}
@@ -0,0 +1,264 @@
+//! `@#name` mentions that attach a symbol's definition to a prompt.
+//!
+//! When a turn starts, every `@#name` (or qualified `@#Config.load` /
+//! `@#Config::load`) is looked up in the project's [`SymbolIndex`], and the
+//! source of the matching definitions is recorded as a
+//! `<symbol_definitions>` message right after the user's prompt, so the model
+//! sees the code without the user pasting it. A mention without a definition
+//! is skipped with a warning.
+//!
+//! [`SymbolIndex`]: crate::code_outline::SymbolIndex
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::WarningEvent;
+use codex_protocol::user_input::UserInput;
+use codex_utils_string::take_bytes_at_char_boundary;
+use tracing::warn;
+
+use crate::code_outline::SymbolMatch;
+use crate::code_outline::with_shared_index;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::git_info::get_git_repo_root;
+use crate::session_prefix::SYMBOL_DEFINITIONS_OPEN_TAG;
+
+/// Definitions attached per mention when a name is defined in several places.
+const MAX_DEFINITIONS_PER_MENTION: usize = 3;
+
+/// Per-definition cap, so one huge function cannot crowd out the rest.
+const MAX_DEFINITION_BYTES: usize = 16 * 1024;
+
+/// Budget for all definitions attached to one turn.
+const MAX_TOTAL_BYTES: usize = 48 * 1024;
+
+const TRUNCATION_MARKER: &str = "\n[truncated]";
+
+/// Trailing characters that end a sentence rather than the name.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ')', ']', '"', '\'', '?', '!'];
+
+#[derive(Debug, Clone, PartialEq)]
+struct AttachedDefinition {
+    mention: String,
+    found: SymbolMatch,
+    source: String,
+}
+
+/// Reads the definitions of the symbols mentioned in `input` and returns the
+/// message to record, or `None` when nothing was mentioned or found.
+pub(crate) async fn gather(
+    sess: &Session,
+    turn: &TurnContext,
+    input: &[UserInput],
+) -> Option<ResponseItem> {
+    let mut mentions: Vec<String> = Vec::new();
+    for item in input {
+        if let UserInput::Text { text, .. } = item {
+            for mention in parse_mentions(text) {
+                if !mentions.contains(&mention) {
+                    mentions.push(mention);
+                }
+            }
+        }
+    }
+    if mentions.is_empty() {
+        return None;
+    }
+
+    let root = get_git_repo_root(&turn.cwd).unwrap_or_else(|| turn.cwd.clone());
+    let resolved = tokio::task::spawn_blocking(move || resolve(&root, mentions))
+        .await
+        .unwrap_or_else(|err| {
+            warn!("symbol mention lookup panicked: {err}");
+            (Vec::new(), Vec::new())
+        });
+    let (attached, missing) = resolved;
+    for mention in missing {
+        let message = format!("Could not attach `@#{mention}`: no definition found.");
+        warn!("{message}");
+        sess.send_event(turn, EventMsg::Warning(WarningEvent { message }))
+            .await;
+    }
+    if attached.is_empty() {
+        return None;
+    }
+    Some(ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: render(&attached),
+        }],
+        end_turn: None,
+    })
+}
+
+/// Looks the mentions up in the index of `root` and reads their source.
+/// Returns the attached definitions and the mentions that matched nothing.
+/// Blocking.
+fn resolve(root: &Path, mentions: Vec<String>) -> (Vec<AttachedDefinition>, Vec<String>) {
+    let found = match with_shared_index(root, |index| {
+        mentions
+            .into_iter()
+            .map(|mention| {
+                let matches = index.find(&mention, None, None);
+                (mention, matches)
+            })
+            .collect::<Vec<_>>()
+    }) {
+        Ok(found) => found,
+        Err(err) => {
+            warn!("failed to index {}: {err}", root.display());
+            return (Vec::new(), Vec::new());
+        }
+    };
+
+    let mut budget = MAX_TOTAL_BYTES;
+    let mut attached = Vec::new();
+    let mut missing = Vec::new();
+    for (mention, matches) in found {
+        if matches.is_empty() {
+            missing.push(mention);
+            continue;
+        }
+        for found in matches.into_iter().take(MAX_DEFINITIONS_PER_MENTION) {
+            if budget == 0 {
+                break;
+            }
+            let path: PathBuf = root.join(&found.path);
+            let Some(source) = read_lines(&path, found.symbol.start_line, found.symbol.end_line)
+            else {
+                continue;
+            };
+            let source = truncate(source, MAX_DEFINITION_BYTES.min(budget));
+            budget = budget.saturating_sub(source.len());
+            attached.push(AttachedDefinition {
+                mention: mention.clone(),
+                found,
+                source,
+            });
+        }
+    }
+    (attached, missing)
+}
+
+/// Lines `start..=end` (1-based) of the file at `path`.
+fn read_lines(path: &Path, start: usize, end: usize) -> Option<String> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let lines: Vec<&str> = contents
+        .lines()
+        .skip(start.saturating_sub(1))
+        .take(end.saturating_sub(start) + 1)
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Finds `@#name` tokens in `text`. Names may be qualified with `.` or `::`.
+fn parse_mentions(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter_map(|token| {
+            let name = token
+                .strip_prefix("@#")?
+                .trim_end_matches(TRAILING_PUNCTUATION);
+            let valid = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '$' | '.' | ':'));
+            valid.then(|| name.to_string())
+        })
+        .collect()
+}
+
+fn truncate(content: String, max_bytes: usize) -> String {
+    if content.len() <= max_bytes {
+        return content;
+    }
+    let head = take_bytes_at_char_boundary(&content, max_bytes);
+    format!("{head}{TRUNCATION_MARKER}")
+}
+
+fn render(attached: &[AttachedDefinition]) -> String {
+    let mut text = format!("{SYMBOL_DEFINITIONS_OPEN_TAG}\n");
+    for item in attached {
+        let symbol = &item.found.symbol;
+        let name = match &symbol.container {
+            Some(container) => format!("{container}.{}", symbol.name),
+            None => symbol.name.clone(),
+        };
+        text.push_str(&format!(
+            "<definition mention=\"{}\" symbol=\"{}\" kind=\"{}\" path=\"{}\" lines=\"{}-{}\">\n",
+            item.mention.replace('"', "'"),
+            name.replace('"', "'"),
+            symbol.kind.as_str(),
+            item.found.path.replace('"', "'"),
+            symbol.start_line,
+            symbol.end_line,
+        ));
+        text.push_str(item.source.trim_end());
+        text.push_str("\n</definition>\n");
+    }
+    text.push_str("</symbol_definitions>");
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_outline::SymbolKind;
+    use pretty_assertions::assert_eq;
+
+    fn write(root: &Path, rel: &str, contents: &str) {
+        let path = root.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create dir");
+        }
+        std::fs::write(path, contents).expect("write file");
+    }
+
+    #[test]
+    fn parses_symbol_mentions() {
+        assert_eq!(
+            parse_mentions(
+                "Why does @#parse_frontmatter differ from @#Config::load, and @#Config.load? Not @src/main.rs, @#, or a@#b."
+            ),
+            vec![
+                "parse_frontmatter".to_string(),
+                "Config::load".to_string(),
+                "Config.load".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_mentions_to_their_definitions() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        write(
+            root,
+            "src/config.rs",
+            "use std::fs;\n\npub struct Config;\n\nimpl Config {\n    pub fn load() -> Self {\n        Config\n    }\n}\n",
+        );
+
+        let (attached, missing) = resolve(
+            root,
+            vec!["Config.load".to_string(), "missing_fn".to_string()],
+        );
+
+        assert_eq!(missing, vec!["missing_fn".to_string()]);
+        assert_eq!(attached.len(), 1);
+        assert_eq!(attached[0].found.symbol.kind, SymbolKind::Method);
+        assert_eq!(
+            render(&attached),
+            "<symbol_definitions>\n<definition mention=\"Config.load\" symbol=\"Config.load\" kind=\"method\" path=\"src/config.rs\" lines=\"6-8\">\n    pub fn load() -> Self {\n        Config\n    }\n</definition>\n</symbol_definitions>"
+        );
+    }
+
+    #[test]
+    fn truncates_long_definitions() {
+        assert_eq!(truncate("abcdef".to_string(), 3), "abc\n[truncated]");
+        assert_eq!(truncate("abc".to_string(), 3), "abc");
+    }
+}
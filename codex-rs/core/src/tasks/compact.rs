@@ -47,3 +47,41 @@ impl SessionTask for CompactTask {
         None
     }
 }
+
+/// Runs `Op::CompactRange`. Always compacts locally because remote
+/// compaction can only replace the whole history.
+#[derive(Clone, Copy)]
+pub(crate) struct CompactRangeTask {
+    pub(crate) from_turn: u32,
+    pub(crate) to_turn: u32,
+}
+
+#[async_trait]
+impl SessionTask for CompactRangeTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Compact
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        _cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let session = session.clone_session();
+        let _ =
+            session
+                .services
+                .otel_manager
+                .counter("codex.task.compact", 1, &[("type", "range")]);
+        crate::compact_strategies::run_compact_range_task(
+            session,
+            ctx,
+            self.from_turn,
+            self.to_turn,
+        )
+        .await;
+        None
+    }
+}
@@ -104,6 +104,15 @@ impl SessionTask for GhostSnapshotTask {
                                         .await;
                                 }
                             }
+                            // The new turn is about to edit the tree; undone
+                            // states can no longer be redone on top of it.
+                            session.session.services.redo_stack.clear().await;
+                            session
+                                .session
+                                .services
+                                .turn_snapshots
+                                .record(&ctx_for_task.sub_id, ghost_commit.clone())
+                                .await;
                             session
                                 .session
                                 .record_conversation_items(&ctx, &[ResponseItem::GhostSnapshot {
@@ -1,6 +1,10 @@
 mod compact;
 mod ghost_snapshot;
+mod redo;
 mod regular;
+mod restore_checkpoint;
+mod restore_file;
+mod revert_hunks;
 mod review;
 mod undo;
 mod user_shell;
@@ -19,8 +23,11 @@ use tracing::warn;
 use crate::AuthManager;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::features::Feature;
 use crate::models_manager::manager::ModelsManager;
+use crate::network_capture::NetworkCapture;
 use crate::protocol::EventMsg;
+use crate::protocol::NetworkActivityEvent;
 use crate::protocol::TurnAbortReason;
 use crate::protocol::TurnAbortedEvent;
 use crate::protocol::TurnCompleteEvent;
@@ -33,10 +40,17 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::user_input::UserInput;
 
+pub(crate) use compact::CompactRangeTask;
 pub(crate) use compact::CompactTask;
 pub(crate) use ghost_snapshot::GhostSnapshotTask;
+pub(crate) use redo::RedoTask;
 pub(crate) use regular::RegularTask;
+pub(crate) use restore_checkpoint::RestoreCheckpointTask;
+pub(crate) use restore_file::RestoreFileTask;
+pub(crate) use revert_hunks::RevertHunksTask;
 pub(crate) use review::ReviewTask;
+pub(crate) use undo::RedoStack;
+pub(crate) use undo::TurnSnapshots;
 pub(crate) use undo::UndoTask;
 pub(crate) use user_shell::UserShellCommandTask;
 
@@ -179,6 +193,7 @@ impl Session {
         turn_context: Arc<TurnContext>,
         last_agent_message: Option<String>,
     ) {
+        let metadata = self.turn_metadata(&turn_context).await;
         let mut active = self.active_turn.lock().await;
         let should_close_processes = if let Some(at) = active.as_mut()
             && at.remove_task(&turn_context.sub_id)
@@ -192,8 +207,38 @@ impl Session {
         if should_close_processes {
             self.close_unified_exec_processes().await;
         }
-        let event = EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message });
+        let connections: Vec<_> = [&self.services.network_capture, &self.services.network_proxy]
+            .into_iter()
+            .flatten()
+            .flat_map(NetworkCapture::take_connections)
+            .collect();
+        if !connections.is_empty() {
+            self.send_event(
+                turn_context.as_ref(),
+                EventMsg::NetworkActivity(NetworkActivityEvent { connections }),
+            )
+            .await;
+        }
+        if self.enabled(Feature::ResumeBriefing) {
+            crate::resume_briefing::record_workspace_snapshot(self, &turn_context.cwd).await;
+        }
+        self.services
+            .hooks
+            .after_turn(self, &turn_context, last_agent_message.as_deref())
+            .await;
+        crate::worktree_isolation::review_after_turn(self, &turn_context.sub_id).await;
+        turn_context
+            .client
+            .get_otel_manager()
+            .counter("codex.turn", 1, &[("status", "completed")]);
+        let event = EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message,
+            metadata,
+        });
         self.send_event(turn_context.as_ref(), event).await;
+        if should_close_processes {
+            crate::input_queue::start_next_queued_input(self).await;
+        }
     }
 
     async fn register_new_active_task(&self, task: RunningTask) {
@@ -267,6 +312,11 @@ impl Session {
             self.flush_rollout().await;
         }
 
+        task.turn_context.client.get_otel_manager().counter(
+            "codex.turn",
+            1,
+            &[("status", "aborted")],
+        );
         let event = EventMsg::TurnAborted(TurnAbortedEvent { reason });
         self.send_event(task.turn_context.as_ref(), event).await;
     }
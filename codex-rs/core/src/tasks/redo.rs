@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use crate::codex::TurnContext;
+use crate::protocol::EventMsg;
+use crate::protocol::UndoCompletedEvent;
+use crate::protocol::UndoKind;
+use crate::protocol::UndoStartedEvent;
+use crate::state::TaskKind;
+use crate::tasks::SessionTask;
+use crate::tasks::SessionTaskContext;
+use async_trait::async_trait;
+use codex_git::RestoreGhostCommitOptions;
+use codex_git::restore_ghost_commit_with_options;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::user_input::UserInput;
+use tokio_util::sync::CancellationToken;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+/// Reverts the most recent `Op::Undo` by restoring the snapshot it took of
+/// the working tree.
+pub(crate) struct RedoTask;
+
+impl RedoTask {
+    pub(crate) fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl SessionTask for RedoTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let _ = session
+            .session
+            .services
+            .otel_manager
+            .counter("codex.task.redo", 1, &[]);
+        let sess = session.clone_session();
+        let redo_stack = &sess.services.redo_stack;
+        sess.send_event(
+            ctx.as_ref(),
+            EventMsg::UndoStarted(UndoStartedEvent {
+                message: Some("Redo in progress...".to_string()),
+            }),
+        )
+        .await;
+
+        let mut completed = UndoCompletedEvent {
+            success: false,
+            message: None,
+            kind: UndoKind::Redo,
+            redo_commit_id: None,
+        };
+
+        if cancellation_token.is_cancelled() {
+            completed.message = Some("Redo cancelled.".to_string());
+            completed.redo_commit_id = redo_stack.next_commit_id().await;
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        }
+
+        let Some(entry) = redo_stack.pop().await else {
+            completed.message = Some("Nothing to redo.".to_string());
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        };
+
+        let commit_id = entry.pre_undo.id().to_string();
+        let pre_undo = entry.pre_undo.clone();
+        let repo_path = ctx.cwd.clone();
+        let ghost_snapshot = ctx.ghost_snapshot.clone();
+        let restore_result = tokio::task::spawn_blocking(move || {
+            let options = RestoreGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot);
+            restore_ghost_commit_with_options(&options, &pre_undo)
+        })
+        .await;
+
+        match restore_result {
+            Ok(Ok(())) => {
+                // Put the turn snapshot back so the redone turn can be undone again.
+                let mut items = sess.clone_history().await.raw_items().to_vec();
+                items.push(ResponseItem::GhostSnapshot {
+                    ghost_commit: entry.undone,
+                });
+                sess.replace_history(items).await;
+                let short_id: String = commit_id.chars().take(7).collect();
+                info!(commit_id = commit_id, "Redo restored pre-undo snapshot");
+                completed.success = true;
+                completed.message = Some(format!("Redo restored snapshot {short_id}."));
+            }
+            Ok(Err(err)) => {
+                let message = format!("Failed to restore snapshot {commit_id}: {err}");
+                warn!("{message}");
+                completed.message = Some(message);
+                redo_stack.push(entry).await;
+            }
+            Err(err) => {
+                let message = format!("Failed to restore snapshot {commit_id}: {err}");
+                error!("{message}");
+                completed.message = Some(message);
+                redo_stack.push(entry).await;
+            }
+        }
+
+        completed.redo_commit_id = redo_stack.next_commit_id().await;
+        sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+            .await;
+        None
+    }
+}
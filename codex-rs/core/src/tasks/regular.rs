@@ -29,7 +29,13 @@ impl SessionTask for RegularTask {
         cancellation_token: CancellationToken,
     ) -> Option<String> {
         let sess = session.clone_session();
-        let run_turn_span = trace_span!("run_turn");
+        let run_turn_span = trace_span!(
+            "run_turn",
+            otel.name = "codex.turn",
+            thread.id = %sess.conversation_id,
+            turn.id = %ctx.sub_id,
+            model = %ctx.client.get_model(),
+        );
         sess.set_server_reasoning_included(false).await;
         sess.services
             .otel_manager
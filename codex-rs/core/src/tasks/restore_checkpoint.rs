@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use crate::codex::TurnContext;
+use crate::protocol::CheckpointRestoredEvent;
+use crate::protocol::EventMsg;
+use crate::state::TaskKind;
+use crate::tasks::SessionTask;
+use crate::tasks::SessionTaskContext;
+use async_trait::async_trait;
+use codex_git::RestoreGhostCommitOptions;
+use codex_git::restore_ghost_commit_with_options;
+use codex_protocol::user_input::UserInput;
+use tokio_util::sync::CancellationToken;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+/// Restores the working tree to a named checkpoint. Runs as a task so it
+/// replaces any running turn instead of racing its edits.
+pub(crate) struct RestoreCheckpointTask {
+    checkpoint_id: String,
+}
+
+impl RestoreCheckpointTask {
+    pub(crate) fn new(checkpoint_id: String) -> Self {
+        Self { checkpoint_id }
+    }
+}
+
+#[async_trait]
+impl SessionTask for RestoreCheckpointTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let mut restored = CheckpointRestoredEvent {
+            checkpoint_id: self.checkpoint_id.clone(),
+            success: false,
+            message: None,
+        };
+
+        if cancellation_token.is_cancelled() {
+            restored.message = Some("Checkpoint restore cancelled.".to_string());
+            sess.send_event(ctx.as_ref(), EventMsg::CheckpointRestored(restored))
+                .await;
+            return None;
+        }
+
+        let checkpoints = &sess.services.checkpoints;
+        let Some(entry) = checkpoints.get(&self.checkpoint_id).await else {
+            restored.message = Some(format!("No checkpoint with id {}.", self.checkpoint_id));
+            sess.send_event(ctx.as_ref(), EventMsg::CheckpointRestored(restored))
+                .await;
+            return None;
+        };
+
+        let name = entry.checkpoint.name.clone();
+        let ghost_commit = entry.ghost_commit;
+        let repo_path = ctx.cwd.clone();
+        let ghost_snapshot = ctx.ghost_snapshot.clone();
+        let restore_result = tokio::task::spawn_blocking(move || {
+            let options = RestoreGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot);
+            restore_ghost_commit_with_options(&options, &ghost_commit)
+        })
+        .await;
+
+        match restore_result {
+            Ok(Ok(())) => {
+                checkpoints.truncate_after(&self.checkpoint_id).await;
+                info!(
+                    checkpoint_id = self.checkpoint_id,
+                    "restored named checkpoint"
+                );
+                restored.success = true;
+                restored.message = Some(format!("Restored checkpoint \"{name}\"."));
+            }
+            Ok(Err(err)) => {
+                let message = format!("Failed to restore checkpoint \"{name}\": {err}");
+                warn!("{message}");
+                restored.message = Some(message);
+            }
+            Err(err) => {
+                let message = format!("Failed to restore checkpoint \"{name}\": {err}");
+                error!("{message}");
+                restored.message = Some(message);
+            }
+        }
+
+        sess.send_event(ctx.as_ref(), EventMsg::CheckpointRestored(restored))
+            .await;
+        None
+    }
+}
@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::protocol::EventMsg;
+use crate::protocol::UndoCompletedEvent;
+use crate::protocol::UndoKind;
+use crate::protocol::UndoStartedEvent;
+use crate::state::TaskKind;
+use crate::tasks::SessionTask;
+use crate::tasks::SessionTaskContext;
+use async_trait::async_trait;
+use codex_git::GhostCommit;
+use codex_git::RestoredPath;
+use codex_git::restore_path_from_ghost_commit;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::user_input::UserInput;
+use tokio_util::sync::CancellationToken;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+/// Reverts one file to the snapshot taken at the start of a turn, keeping
+/// the rest of the turn's changes. History is left alone, so a later
+/// `Op::Undo` still reverts the whole turn.
+pub(crate) struct RestoreFileTask {
+    turn_id: Option<String>,
+    path: PathBuf,
+}
+
+impl RestoreFileTask {
+    pub(crate) fn new(turn_id: Option<String>, path: PathBuf) -> Self {
+        Self { turn_id, path }
+    }
+}
+
+#[async_trait]
+impl SessionTask for RestoreFileTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let _ = session
+            .session
+            .services
+            .otel_manager
+            .counter("codex.task.restore_file", 1, &[]);
+        let sess = session.clone_session();
+        let display_path = self.path.display().to_string();
+        sess.send_event(
+            ctx.as_ref(),
+            EventMsg::UndoStarted(UndoStartedEvent {
+                message: Some(format!("Restoring {display_path}...")),
+            }),
+        )
+        .await;
+
+        let mut completed = UndoCompletedEvent {
+            success: false,
+            message: None,
+            kind: UndoKind::RestoreFile,
+            redo_commit_id: sess.services.redo_stack.next_commit_id().await,
+        };
+
+        if cancellation_token.is_cancelled() {
+            completed.message = Some("File restore cancelled.".to_string());
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        }
+
+        let Some(ghost_commit) = turn_start_snapshot(&sess, self.turn_id.as_deref()).await else {
+            completed.message = Some(match self.turn_id.as_deref() {
+                Some(turn_id) => format!("No snapshot recorded for turn {turn_id}."),
+                None => "No ghost snapshot available to restore from.".to_string(),
+            });
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        };
+
+        let commit_id = ghost_commit.id().to_string();
+        let short_id: String = commit_id.chars().take(7).collect();
+        let repo_path = ctx.cwd.clone();
+        let path = self.path.clone();
+        let restore_result = tokio::task::spawn_blocking(move || {
+            restore_path_from_ghost_commit(&repo_path, &ghost_commit, &path)
+        })
+        .await;
+
+        match restore_result {
+            Ok(Ok(restored)) => {
+                info!(
+                    commit_id = commit_id,
+                    path = %display_path,
+                    "Restored file from ghost snapshot"
+                );
+                completed.success = true;
+                completed.message = Some(match restored {
+                    RestoredPath::Restored => {
+                        format!("Restored {display_path} from snapshot {short_id}.")
+                    }
+                    RestoredPath::Removed => {
+                        format!("Removed {display_path}; it did not exist in snapshot {short_id}.")
+                    }
+                });
+            }
+            Ok(Err(err)) => {
+                let message = format!("Failed to restore {display_path} from {short_id}: {err}");
+                warn!("{message}");
+                completed.message = Some(message);
+            }
+            Err(err) => {
+                let message = format!("Failed to restore {display_path} from {short_id}: {err}");
+                error!("{message}");
+                completed.message = Some(message);
+            }
+        }
+
+        sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+            .await;
+        None
+    }
+}
+
+/// The snapshot taken at the start of `turn_id`, or the most recent one
+/// still in history when no turn is named.
+pub(super) async fn turn_start_snapshot(
+    sess: &Session,
+    turn_id: Option<&str>,
+) -> Option<GhostCommit> {
+    match turn_id {
+        Some(turn_id) => sess.services.turn_snapshots.get(turn_id).await,
+        None => sess
+            .clone_history()
+            .await
+            .raw_items()
+            .iter()
+            .rev()
+            .find_map(|item| match item {
+                ResponseItem::GhostSnapshot { ghost_commit } => Some(ghost_commit.clone()),
+                _ => None,
+            }),
+    }
+}
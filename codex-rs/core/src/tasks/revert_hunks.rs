@@ -0,0 +1,271 @@
+use std::cmp::Reverse;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::codex::TurnContext;
+use crate::protocol::DiffHunk;
+use crate::protocol::EventMsg;
+use crate::protocol::UndoCompletedEvent;
+use crate::protocol::UndoKind;
+use crate::protocol::UndoStartedEvent;
+use crate::state::TaskKind;
+use crate::tasks::SessionTask;
+use crate::tasks::SessionTaskContext;
+use crate::tasks::restore_file::turn_start_snapshot;
+use async_trait::async_trait;
+use codex_git::GhostCommit;
+use codex_git::read_path_from_ghost_commit;
+use codex_protocol::user_input::UserInput;
+use tokio_util::sync::CancellationToken;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+/// Reverts some hunks of a turn's changes to one file, keeping the rest.
+/// The old lines come from the snapshot taken at the start of the turn, so
+/// the hunks must be the ranges that turn's diff reported.
+pub(crate) struct RevertHunksTask {
+    turn_id: Option<String>,
+    path: PathBuf,
+    hunks: Vec<DiffHunk>,
+}
+
+impl RevertHunksTask {
+    pub(crate) fn new(turn_id: Option<String>, path: PathBuf, hunks: Vec<DiffHunk>) -> Self {
+        Self {
+            turn_id,
+            path,
+            hunks,
+        }
+    }
+}
+
+#[async_trait]
+impl SessionTask for RevertHunksTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let _ = session
+            .session
+            .services
+            .otel_manager
+            .counter("codex.task.revert_hunks", 1, &[]);
+        let sess = session.clone_session();
+        let display_path = self.path.display().to_string();
+        sess.send_event(
+            ctx.as_ref(),
+            EventMsg::UndoStarted(UndoStartedEvent {
+                message: Some(format!("Reverting changes in {display_path}...")),
+            }),
+        )
+        .await;
+
+        let mut completed = UndoCompletedEvent {
+            success: false,
+            message: None,
+            kind: UndoKind::RevertHunks,
+            redo_commit_id: sess.services.redo_stack.next_commit_id().await,
+        };
+
+        if cancellation_token.is_cancelled() {
+            completed.message = Some("Hunk revert cancelled.".to_string());
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        }
+        if self.hunks.is_empty() {
+            completed.success = true;
+            completed.message = Some(format!("No hunks to revert in {display_path}."));
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        }
+
+        let Some(ghost_commit) = turn_start_snapshot(&sess, self.turn_id.as_deref()).await else {
+            completed.message = Some(match self.turn_id.as_deref() {
+                Some(turn_id) => format!("No snapshot recorded for turn {turn_id}."),
+                None => "No ghost snapshot available to revert from.".to_string(),
+            });
+            sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+                .await;
+            return None;
+        };
+
+        let repo_path = ctx.cwd.clone();
+        let path = self.path.clone();
+        let hunks = self.hunks.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            revert_file_hunks(&repo_path, &ghost_commit, &path, &hunks)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(())) => {
+                let count = self.hunks.len();
+                info!(path = %display_path, count, "Reverted hunks from ghost snapshot");
+                completed.success = true;
+                completed.message = Some(format!(
+                    "Reverted {count} {} in {display_path}.",
+                    if count == 1 { "hunk" } else { "hunks" }
+                ));
+            }
+            Ok(Err(err)) => {
+                let message = format!("Failed to revert hunks in {display_path}: {err}");
+                warn!("{message}");
+                completed.message = Some(message);
+            }
+            Err(err) => {
+                let message = format!("Failed to revert hunks in {display_path}: {err}");
+                error!("{message}");
+                completed.message = Some(message);
+            }
+        }
+
+        sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
+            .await;
+        None
+    }
+}
+
+/// Rewrites `path` with `hunks` reverted. A file the turn created is removed
+/// once nothing of it is left. Blocking.
+fn revert_file_hunks(
+    repo_path: &Path,
+    ghost_commit: &GhostCommit,
+    path: &Path,
+    hunks: &[DiffHunk],
+) -> Result<(), String> {
+    let original = read_path_from_ghost_commit(repo_path, ghost_commit, path)
+        .map_err(|err| err.to_string())?;
+    let target = repo_path.join(path);
+    let current = match std::fs::read_to_string(&target) {
+        Ok(current) => current,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.to_string()),
+    };
+    let reverted = revert_hunks(&current, original.as_deref().unwrap_or_default(), hunks)?;
+    if original.is_none() && reverted.is_empty() {
+        return match std::fs::remove_file(&target) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.to_string()),
+            _ => Ok(()),
+        };
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    std::fs::write(&target, reverted).map_err(|err| err.to_string())
+}
+
+/// Replaces each hunk's new lines in `current` with its old lines from
+/// `original`. Hunks are applied bottom-up so the line numbers of the ones
+/// above stay valid; they may come in any order but must not overlap.
+fn revert_hunks(current: &str, original: &str, hunks: &[DiffHunk]) -> Result<String, String> {
+    let original: Vec<&str> = original.split_inclusive('\n').collect();
+    let mut lines: Vec<&str> = current.split_inclusive('\n').collect();
+    let mut hunks = hunks.to_vec();
+    hunks.sort_by_key(|hunk| Reverse((hunk.new_start, hunk.new_lines)));
+
+    let mut floor = usize::MAX;
+    for hunk in hunks {
+        let header = format!(
+            "@@ -{},{} +{},{} @@",
+            hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines
+        );
+        let new = line_range(hunk.new_start, hunk.new_lines);
+        let old = line_range(hunk.old_start, hunk.old_lines);
+        if new.end > floor {
+            return Err(format!("hunk {header} overlaps another hunk"));
+        }
+        if new.end > lines.len() {
+            return Err(format!("hunk {header} is past the end of the file"));
+        }
+        let Some(old_lines) = original.get(old) else {
+            return Err(format!("hunk {header} is not in the snapshot"));
+        };
+        lines.splice(new.clone(), old_lines.iter().copied());
+        floor = new.start;
+    }
+    Ok(lines.concat())
+}
+
+/// 0-based line indices of a unified diff range, where a zero-length range
+/// starts at the line before the change.
+fn line_range(start: u32, len: u32) -> Range<usize> {
+    let first = if len == 0 {
+        start
+    } else {
+        start.saturating_sub(1)
+    } as usize;
+    first..first + len as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn hunk(old_start: u32, old_lines: u32, new_start: u32, new_lines: u32) -> DiffHunk {
+        DiffHunk {
+            old_start,
+            old_lines,
+            new_start,
+            new_lines,
+        }
+    }
+
+    #[test]
+    fn reverts_only_the_given_hunks() {
+        let original = "a\nb\nc\nd\ne\nf\n";
+        // b -> B, an inserted line after d, f removed.
+        let current = "a\nB\nc\nd\nnew\ne\n";
+        let changed = hunk(2, 1, 2, 1);
+        let inserted = hunk(4, 0, 5, 1);
+        let removed = hunk(6, 1, 6, 0);
+
+        assert_eq!(
+            revert_hunks(current, original, &[inserted]),
+            Ok("a\nB\nc\nd\ne\n".to_string())
+        );
+        assert_eq!(
+            revert_hunks(current, original, &[changed, removed]),
+            Ok("a\nb\nc\nd\nnew\ne\nf\n".to_string())
+        );
+        assert_eq!(
+            revert_hunks(current, original, &[removed, inserted, changed]),
+            Ok(original.to_string())
+        );
+    }
+
+    #[test]
+    fn reverting_an_added_file_empties_it() {
+        assert_eq!(
+            revert_hunks("one\ntwo\n", "", &[hunk(0, 0, 1, 2)]),
+            Ok(String::new())
+        );
+    }
+
+    #[test]
+    fn rejects_hunks_that_do_not_fit() {
+        assert!(revert_hunks("a\n", "a\n", &[hunk(1, 1, 3, 1)]).is_err());
+        assert!(revert_hunks("a\nb\n", "a\n", &[hunk(4, 1, 1, 1)]).is_err());
+        assert!(
+            revert_hunks(
+                "a\nb\nc\n",
+                "a\nb\nc\n",
+                &[hunk(1, 2, 1, 2), hunk(2, 2, 2, 2)]
+            )
+            .is_err()
+        );
+    }
+}
@@ -19,18 +19,25 @@ use crate::codex::TurnContext;
 use crate::codex_delegate::run_codex_thread_one_shot;
 use crate::review_format::format_review_findings_block;
 use crate::review_format::render_review_output_text;
+use crate::review_prompts::ReviewArea;
 use crate::state::TaskKind;
 use codex_protocol::user_input::UserInput;
 
 use super::SessionTask;
 use super::SessionTaskContext;
 
-#[derive(Clone, Copy)]
-pub(crate) struct ReviewTask;
+const PATCH_IS_INCORRECT: &str = "patch is incorrect";
+
+#[derive(Clone, Default)]
+pub(crate) struct ReviewTask {
+    /// With more than one area, each gets its own reviewer and the findings
+    /// are merged.
+    areas: Vec<ReviewArea>,
+}
 
 impl ReviewTask {
-    pub(crate) fn new() -> Self {
-        Self
+    pub(crate) fn by_area(areas: Vec<ReviewArea>) -> Self {
+        Self { areas }
     }
 }
 
@@ -54,16 +61,29 @@ impl SessionTask for ReviewTask {
             .counter("codex.task.review", 1, &[]);
 
         // Start sub-codex conversation and get the receiver for events.
-        let output = match start_review_conversation(
-            session.clone(),
-            ctx.clone(),
-            input,
-            cancellation_token.clone(),
-        )
-        .await
-        {
-            Some(receiver) => process_review_events(session.clone(), ctx.clone(), receiver).await,
-            None => None,
+        let output = if self.areas.len() > 1 {
+            review_by_area(
+                session.clone(),
+                ctx.clone(),
+                input,
+                &self.areas,
+                cancellation_token.clone(),
+            )
+            .await
+        } else {
+            match start_review_conversation(
+                session.clone(),
+                ctx.clone(),
+                input,
+                cancellation_token.clone(),
+            )
+            .await
+            {
+                Some(receiver) => {
+                    process_review_events(session.clone(), ctx.clone(), receiver).await
+                }
+                None => None,
+            }
         };
         if !cancellation_token.is_cancelled() {
             exit_review_mode(session.clone_session(), output.clone(), ctx.clone()).await;
@@ -111,6 +131,64 @@ async fn start_review_conversation(
         .map(|io| io.rx_event)
 }
 
+/// Runs one reviewer per area, one after another, and merges their output.
+/// Returns `None` as soon as one of them is interrupted.
+async fn review_by_area(
+    session: Arc<SessionTaskContext>,
+    ctx: Arc<TurnContext>,
+    input: Vec<UserInput>,
+    areas: &[ReviewArea],
+    cancellation_token: CancellationToken,
+) -> Option<ReviewOutputEvent> {
+    let mut outputs = Vec::with_capacity(areas.len());
+    for area in areas {
+        if cancellation_token.is_cancelled() {
+            return None;
+        }
+        let mut area_input = input.clone();
+        area_input.push(UserInput::Text {
+            text: area.instructions(),
+            text_elements: Vec::new(),
+        });
+        let receiver = start_review_conversation(
+            session.clone(),
+            ctx.clone(),
+            area_input,
+            cancellation_token.clone(),
+        )
+        .await?;
+        let output = process_review_events(session.clone(), ctx.clone(), receiver).await?;
+        outputs.push((area, output));
+    }
+    Some(merge_review_outputs(outputs))
+}
+
+/// The patch is incorrect when any area's reviewer says so; the confidence
+/// is the lowest of the reviewers'.
+fn merge_review_outputs(outputs: Vec<(&ReviewArea, ReviewOutputEvent)>) -> ReviewOutputEvent {
+    let mut merged = ReviewOutputEvent {
+        overall_confidence_score: 1.0,
+        ..Default::default()
+    };
+    let mut explanations = Vec::new();
+    for (area, output) in outputs {
+        merged.findings.extend(output.findings);
+        if merged.overall_correctness.is_empty() || output.overall_correctness == PATCH_IS_INCORRECT
+        {
+            merged.overall_correctness = output.overall_correctness;
+        }
+        merged.overall_confidence_score = merged
+            .overall_confidence_score
+            .min(output.overall_confidence_score);
+        let explanation = output.overall_explanation.trim();
+        if !explanation.is_empty() {
+            explanations.push(format!("{}: {explanation}", area.dirs.join(", ")));
+        }
+    }
+    merged.overall_explanation = explanations.join("\n");
+    merged
+}
+
 async fn process_review_events(
     session: Arc<SessionTaskContext>,
     ctx: Arc<TurnContext>,
@@ -1,22 +1,88 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::codex::TurnContext;
 use crate::protocol::EventMsg;
 use crate::protocol::UndoCompletedEvent;
+use crate::protocol::UndoKind;
 use crate::protocol::UndoStartedEvent;
 use crate::state::TaskKind;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
 use async_trait::async_trait;
+use codex_git::CreateGhostCommitOptions;
+use codex_git::GhostCommit;
 use codex_git::RestoreGhostCommitOptions;
+use codex_git::create_ghost_commit;
 use codex_git::restore_ghost_commit_with_options;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::user_input::UserInput;
+use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
 
+/// A working-tree state discarded by `Op::Undo`.
+pub(crate) struct RedoEntry {
+    /// Snapshot of the tree taken right before the undo restored `undone`.
+    pub(crate) pre_undo: GhostCommit,
+    /// The turn snapshot the undo consumed; put back into history on redo so
+    /// the turn can be undone again.
+    pub(crate) undone: GhostCommit,
+}
+
+/// Undone states, newest last. Cleared as soon as a new turn snapshots the
+/// working tree, since redoing past newer edits would discard them.
+#[derive(Default)]
+pub(crate) struct RedoStack {
+    entries: Mutex<Vec<RedoEntry>>,
+}
+
+impl RedoStack {
+    pub(crate) async fn push(&self, entry: RedoEntry) {
+        self.entries.lock().await.push(entry);
+    }
+
+    pub(crate) async fn pop(&self) -> Option<RedoEntry> {
+        self.entries.lock().await.pop()
+    }
+
+    pub(crate) async fn clear(&self) {
+        self.entries.lock().await.clear();
+    }
+
+    /// Id of the snapshot the next `Op::Redo` would restore.
+    pub(crate) async fn next_commit_id(&self) -> Option<String> {
+        self.entries
+            .lock()
+            .await
+            .last()
+            .map(|entry| entry.pre_undo.id().to_string())
+    }
+}
+
+/// Snapshot taken at the start of each turn, keyed by turn id, so
+/// `Op::RestoreFile` can target turns other than the latest. Unlike the
+/// history items these survive an undo, but not a resume.
+#[derive(Default)]
+pub(crate) struct TurnSnapshots {
+    by_turn: Mutex<HashMap<String, GhostCommit>>,
+}
+
+impl TurnSnapshots {
+    pub(crate) async fn record(&self, turn_id: &str, ghost_commit: GhostCommit) {
+        self.by_turn
+            .lock()
+            .await
+            .insert(turn_id.to_string(), ghost_commit);
+    }
+
+    pub(crate) async fn get(&self, turn_id: &str) -> Option<GhostCommit> {
+        self.by_turn.lock().await.get(turn_id).cloned()
+    }
+}
+
 pub(crate) struct UndoTask;
 
 impl UndoTask {
@@ -58,6 +124,8 @@ impl SessionTask for UndoTask {
                 EventMsg::UndoCompleted(UndoCompletedEvent {
                     success: false,
                     message: Some("Undo cancelled.".to_string()),
+                    kind: UndoKind::Undo,
+                    redo_commit_id: sess.services.redo_stack.next_commit_id().await,
                 }),
             )
             .await;
@@ -66,9 +134,12 @@ impl SessionTask for UndoTask {
 
         let history = sess.clone_history().await;
         let mut items = history.raw_items().to_vec();
+        let redo_stack = &sess.services.redo_stack;
         let mut completed = UndoCompletedEvent {
             success: false,
             message: None,
+            kind: UndoKind::Undo,
+            redo_commit_id: None,
         };
 
         let Some((idx, ghost_commit)) =
@@ -84,24 +155,42 @@ impl SessionTask for UndoTask {
                 })
         else {
             completed.message = Some("No ghost snapshot available to undo.".to_string());
+            completed.redo_commit_id = redo_stack.next_commit_id().await;
             sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
                 .await;
             return None;
         };
 
         let commit_id = ghost_commit.id().to_string();
+        let undone = ghost_commit.clone();
         let repo_path = ctx.cwd.clone();
         let ghost_snapshot = ctx.ghost_snapshot.clone();
         let restore_result = tokio::task::spawn_blocking(move || {
+            // Capture the current tree first so the undo can be redone.
+            let pre_undo = create_ghost_commit(
+                &CreateGhostCommitOptions::new(&repo_path)
+                    .ghost_snapshot(ghost_snapshot.clone())
+                    .message("codex snapshot before undo"),
+            );
             let options = RestoreGhostCommitOptions::new(&repo_path).ghost_snapshot(ghost_snapshot);
-            restore_ghost_commit_with_options(&options, &ghost_commit)
+            restore_ghost_commit_with_options(&options, &ghost_commit).map(|()| pre_undo)
         })
         .await;
 
         match restore_result {
-            Ok(Ok(())) => {
+            Ok(Ok(pre_undo)) => {
                 items.remove(idx);
                 sess.replace_history(items).await;
+                match pre_undo {
+                    Ok(pre_undo) => redo_stack.push(RedoEntry { pre_undo, undone }).await,
+                    Err(err) => {
+                        // Older entries would skip over this undo, so drop them too.
+                        warn!(
+                            "failed to snapshot working tree before undo; redo unavailable: {err}"
+                        );
+                        redo_stack.clear().await;
+                    }
+                }
                 let short_id: String = commit_id.chars().take(7).collect();
                 info!(commit_id = commit_id, "Undo restored ghost snapshot");
                 completed.success = true;
@@ -119,6 +208,7 @@ impl SessionTask for UndoTask {
             }
         }
 
+        completed.redo_commit_id = redo_stack.next_commit_id().await;
         sess.send_event(ctx.as_ref(), EventMsg::UndoCompleted(completed))
             .await;
         None
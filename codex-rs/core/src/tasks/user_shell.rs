@@ -10,6 +10,7 @@ use tracing::error;
 use uuid::Uuid;
 
 use crate::codex::TurnContext;
+use crate::config::types::ExecLimits;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
@@ -112,6 +113,8 @@ impl SessionTask for UserShellCommandTask {
             sandbox_permissions: SandboxPermissions::UseDefault,
             justification: None,
             arg0: None,
+            // `[exec_limits]` is for the agent's commands, not the user's own.
+            limits: ExecLimits::default(),
         };
 
         let stdout_stream = Some(StdoutStream {
@@ -0,0 +1,187 @@
+//! libtest output, as printed by `cargo test`.
+
+use super::TestFailure;
+use super::TestRunner;
+use super::parse_location;
+
+pub(super) fn parse(output: &str) -> Vec<TestFailure> {
+    let mut failures = Vec::new();
+    let mut listed: Vec<&str> = Vec::new();
+    let mut section: Option<(&str, Vec<&str>)> = None;
+    let mut in_list = false;
+
+    for line in output.lines() {
+        if let Some(name) = line
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        {
+            if let Some((name, body)) = section.take() {
+                failures.push(parse_section(name, &body));
+            }
+            section = Some((name, Vec::new()));
+            in_list = false;
+            continue;
+        }
+        if line == "failures:" {
+            if let Some((name, body)) = section.take() {
+                failures.push(parse_section(name, &body));
+            }
+            in_list = true;
+            continue;
+        }
+        if in_list {
+            if let Some(name) = line.strip_prefix("    ") {
+                listed.push(name.trim());
+                continue;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            in_list = false;
+        }
+        if let Some((_, body)) = section.as_mut() {
+            if line.starts_with("test result: ") {
+                if let Some((name, body)) = section.take() {
+                    failures.push(parse_section(name, &body));
+                }
+            } else {
+                body.push(line);
+            }
+        }
+    }
+    if let Some((name, body)) = section.take() {
+        failures.push(parse_section(name, &body));
+    }
+
+    // Tests that failed without printing anything only show up in the list.
+    if output.contains("test result: FAILED") {
+        for name in listed {
+            if !failures.iter().any(|failure| failure.test_id == name) {
+                let mut failure = TestFailure::new(TestRunner::Cargo, name);
+                failure.message = "test failed".to_string();
+                failures.push(failure);
+            }
+        }
+    }
+    failures
+}
+
+fn parse_section(name: &str, body: &[&str]) -> TestFailure {
+    let mut failure = TestFailure::new(TestRunner::Cargo, name);
+    let panic_index = body.iter().position(|line| line.contains(" panicked at "));
+
+    match panic_index {
+        Some(index) => {
+            let line = body[index];
+            let rest = line
+                .split_once(" panicked at ")
+                .map(|(_, rest)| rest)
+                .unwrap_or_default();
+            if let Some(quoted) = rest.strip_prefix('\'') {
+                // Before Rust 1.73: `panicked at 'message', src/lib.rs:10:5`.
+                if let Some((message, location)) = quoted.rsplit_once("', ") {
+                    failure.message = message.to_string();
+                    failure.location = parse_location(location);
+                }
+            } else {
+                failure.location = parse_location(rest);
+                failure.message = body[index + 1..]
+                    .iter()
+                    .take_while(|line| {
+                        !line.trim().is_empty()
+                            && !line.starts_with("note: ")
+                            && !line.starts_with("stack backtrace:")
+                    })
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+        }
+        None => {
+            failure.message = body
+                .iter()
+                .filter(|line| !line.trim().is_empty())
+                .take(5)
+                .copied()
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+    }
+
+    failure.stack = body
+        .iter()
+        .skip_while(|line| !line.starts_with("stack backtrace:"))
+        .filter_map(|line| line.trim_start().strip_prefix("at "))
+        .filter(|frame| !frame.starts_with("/rustc/") && !frame.contains("/.cargo/registry/"))
+        .map(|frame| frame.trim_start_matches("./").to_string())
+        .collect();
+    failure
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_failures::Location;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_panics_and_tests_listed_without_output() {
+        let output = "\
+running 3 tests
+test math::adds ... FAILED
+test math::old ... FAILED
+test math::silent ... FAILED
+
+failures:
+
+---- math::adds stdout ----
+thread 'math::adds' panicked at src/math.rs:12:9:
+assertion `left == right` failed
+  left: 3
+ right: 4
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+---- math::old stdout ----
+thread 'math::old' panicked at 'boom', src/math.rs:20:5
+
+
+failures:
+    math::adds
+    math::old
+    math::silent
+
+test result: FAILED. 0 passed; 3 failed; 0 ignored; 0 measured; 0 filtered out
+";
+
+        let failures = parse(output);
+        assert_eq!(
+            failures
+                .iter()
+                .map(|failure| (
+                    failure.test_id.as_str(),
+                    failure.message.as_str(),
+                    failure.location.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "math::adds",
+                    "assertion `left == right` failed\n  left: 3\n right: 4",
+                    Some(Location {
+                        path: "src/math.rs".to_string(),
+                        line: 12,
+                    })
+                ),
+                (
+                    "math::old",
+                    "boom",
+                    Some(Location {
+                        path: "src/math.rs".to_string(),
+                        line: 20,
+                    })
+                ),
+                ("math::silent", "test failed", None),
+            ]
+        );
+    }
+}
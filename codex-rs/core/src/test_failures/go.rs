@@ -0,0 +1,192 @@
+//! `go test` output, with or without `-v`.
+
+use std::collections::HashMap;
+
+use super::TestFailure;
+use super::TestRunner;
+use super::parse_location;
+
+pub(super) fn parse(output: &str) -> Vec<TestFailure> {
+    let mut failures: Vec<TestFailure> = Vec::new();
+    // With `-v`, a test's log lines stream before its `--- FAIL` line.
+    let mut run_logs: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut running: Option<&str> = None;
+    let mut current: Option<(&str, Vec<&str>)> = None;
+    let mut in_panic = false;
+
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        if let Some(rest) = trimmed.strip_prefix("--- FAIL: ") {
+            flush(&mut current, &mut run_logs, &mut failures);
+            let name = rest.split(" (").next().unwrap_or(rest).trim();
+            current = Some((name, Vec::new()));
+            running = None;
+            continue;
+        }
+        if let Some(rest) = trimmed
+            .strip_prefix("=== RUN ")
+            .or_else(|| trimmed.strip_prefix("=== CONT "))
+        {
+            flush(&mut current, &mut run_logs, &mut failures);
+            running = Some(rest.trim());
+            continue;
+        }
+        if trimmed.starts_with("--- ") || trimmed.starts_with("=== ") {
+            flush(&mut current, &mut run_logs, &mut failures);
+            running = None;
+            continue;
+        }
+        if let Some(message) = line.strip_prefix("panic: ") {
+            flush(&mut current, &mut run_logs, &mut failures);
+            if let Some(failure) = failures.last_mut() {
+                failure.message = message.trim_end_matches(" [recovered]").to_string();
+                failure.location = None;
+                in_panic = true;
+            }
+            continue;
+        }
+        if in_panic {
+            if line == "FAIL" || line.starts_with("FAIL\t") || line.starts_with("exit status") {
+                in_panic = false;
+            } else if let Some(frame) = line.strip_prefix('\t')
+                && let Some(failure) = failures.last_mut()
+            {
+                let frame = frame.split(" +0x").next().unwrap_or(frame);
+                if !frame.contains("/src/runtime/")
+                    && !frame.contains("/src/testing/")
+                    && let Some(location) = parse_location(frame)
+                {
+                    failure.stack.push(location.to_string());
+                    failure.location.get_or_insert(location);
+                }
+            }
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) || line.trim().is_empty() {
+            if !line.trim().is_empty() {
+                flush(&mut current, &mut run_logs, &mut failures);
+                running = None;
+            }
+            continue;
+        }
+        match (&mut current, running) {
+            (Some((_, body)), _) => body.push(line),
+            (None, Some(name)) => run_logs.entry(name).or_default().push(line),
+            (None, None) => {}
+        }
+    }
+    flush(&mut current, &mut run_logs, &mut failures);
+
+    // A parent test fails whenever one of its subtests does; keep only the
+    // parents that reported something themselves.
+    let names: Vec<String> = failures.iter().map(|f| f.test_id.clone()).collect();
+    failures.retain(|failure| {
+        !failure.message.is_empty()
+            || !names
+                .iter()
+                .any(|name| name.starts_with(&format!("{}/", failure.test_id)))
+    });
+    failures
+}
+
+fn flush<'a>(
+    current: &mut Option<(&'a str, Vec<&'a str>)>,
+    run_logs: &mut HashMap<&'a str, Vec<&'a str>>,
+    failures: &mut Vec<TestFailure>,
+) {
+    let Some((name, mut body)) = current.take() else {
+        return;
+    };
+    if body.is_empty() {
+        body = run_logs.remove(name).unwrap_or_default();
+    }
+    let mut failure = TestFailure::new(TestRunner::Go, name);
+    let mut messages = Vec::new();
+    for line in body {
+        let line = line.trim();
+        // `math_test.go:12: got 3, want 4`
+        match line.split_once(": ") {
+            Some((location, message)) if location.ends_with(".go") || location.contains(".go:") => {
+                if failure.location.is_none() {
+                    failure.location = parse_location(location);
+                }
+                messages.push(message);
+            }
+            _ => messages.push(line),
+        }
+    }
+    failure.message = messages.join("\n");
+    failures.push(failure);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_failures::Location;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_failures_subtests_and_panics() {
+        let output = "\
+=== RUN   TestAdd
+    math_test.go:12: got 3, want 4
+--- FAIL: TestAdd (0.00s)
+=== RUN   TestTable
+=== RUN   TestTable/negative
+    math_test.go:30: got -1, want 1
+--- FAIL: TestTable (0.00s)
+    --- FAIL: TestTable/negative (0.00s)
+=== RUN   TestIndex
+--- FAIL: TestIndex (0.00s)
+panic: runtime error: index out of range [3] with length 3 [recovered]
+\tpanic: runtime error: index out of range [3] with length 3
+
+goroutine 7 [running]:
+testing.tRunner.func1.2({0x1, 0x2})
+\t/usr/local/go/src/testing/testing.go:1545 +0x238
+example.com/m.Index(...)
+\t/home/dev/m/index.go:8 +0x1d
+example.com/m.TestIndex(0x0?)
+\t/home/dev/m/index_test.go:6 +0x25
+FAIL\texample.com/m\t0.004s
+";
+
+        let failures = parse(output);
+        assert_eq!(
+            failures
+                .iter()
+                .map(|failure| (
+                    failure.test_id.as_str(),
+                    failure.message.as_str(),
+                    failure.location.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "TestAdd",
+                    "got 3, want 4",
+                    Some(Location {
+                        path: "math_test.go".to_string(),
+                        line: 12,
+                    })
+                ),
+                (
+                    "TestTable/negative",
+                    "got -1, want 1",
+                    Some(Location {
+                        path: "math_test.go".to_string(),
+                        line: 30,
+                    })
+                ),
+                (
+                    "TestIndex",
+                    "runtime error: index out of range [3] with length 3",
+                    Some(Location {
+                        path: "/home/dev/m/index.go".to_string(),
+                        line: 8,
+                    })
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! jest (and vitest's jest-compatible reporter) output: `● suite › test` blocks.
+
+use super::TestFailure;
+use super::TestRunner;
+use super::parse_location;
+
+pub(super) fn parse(output: &str) -> Vec<TestFailure> {
+    let mut failures = Vec::new();
+    let mut file: Option<&str> = None;
+    let mut block: Option<(String, Vec<&str>)> = None;
+
+    for line in output.lines() {
+        let trimmed = line.trim_start();
+        if let Some(name) = trimmed.strip_prefix("● ") {
+            if let Some((test_id, body)) = block.take() {
+                failures.push(parse_block(test_id, &body));
+            }
+            let test_id = match file {
+                Some(file) => format!("{file} › {}", name.trim()),
+                None => name.trim().to_string(),
+            };
+            block = Some((test_id, Vec::new()));
+            continue;
+        }
+        let ends_block = trimmed.starts_with("FAIL ")
+            || trimmed.starts_with("PASS ")
+            || trimmed.starts_with("Test Suites:")
+            || trimmed.starts_with("Summary of all failing tests");
+        if ends_block {
+            if let Some((test_id, body)) = block.take() {
+                failures.push(parse_block(test_id, &body));
+            }
+            file = trimmed
+                .strip_prefix("FAIL ")
+                .and_then(|rest| rest.split_whitespace().next());
+            continue;
+        }
+        if let Some((_, body)) = block.as_mut() {
+            body.push(line);
+        }
+    }
+    if let Some((test_id, body)) = block.take() {
+        failures.push(parse_block(test_id, &body));
+    }
+    failures
+}
+
+fn parse_block(test_id: String, body: &[&str]) -> TestFailure {
+    let mut failure = TestFailure::new(TestRunner::Jest, test_id);
+    failure.message = body
+        .iter()
+        .map(|line| line.trim())
+        .take_while(|line| !is_code_frame(line) && !line.starts_with("at "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    failure.stack = body
+        .iter()
+        .filter_map(|line| line.trim().strip_prefix("at "))
+        .map(|frame| match frame.rsplit_once('(') {
+            Some((_, location)) => location.trim_end_matches(')'),
+            None => frame,
+        })
+        .filter(|frame| !frame.contains("node_modules") && !frame.starts_with("node:"))
+        .map(str::to_string)
+        .collect();
+    failure.location = failure
+        .stack
+        .first()
+        .and_then(|frame| parse_location(frame));
+    failure
+}
+
+/// `> 5 |     expect(sum(1, 2)).toBe(4);` and the `|   ^` marker below it.
+fn is_code_frame(line: &str) -> bool {
+    let rest = line.strip_prefix('>').unwrap_or(line).trim_start();
+    if rest.starts_with('|') {
+        return true;
+    }
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+    digits > 0 && rest[digits..].trim_start().starts_with('|')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_failures::Location;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_failure_blocks_and_skips_code_frames() {
+        let output = "\
+FAIL src/sum.test.js
+  ● math › adds numbers
+
+    expect(received).toBe(expected) // Object.is equality
+
+    Expected: 4
+    Received: 3
+
+      4 |   test('adds numbers', () => {
+    > 5 |     expect(sum(1, 2)).toBe(4);
+        |                       ^
+
+      at Object.toBe (src/sum.test.js:5:23)
+
+  ● math › throws
+
+    TypeError: Cannot read properties of undefined (reading 'x')
+
+      at x (src/sum.js:2:10)
+      at Object.<anonymous> (src/sum.test.js:9:5)
+      at Promise.then.completed (node_modules/jest-circus/build/utils.js:298:28)
+
+Test Suites: 1 failed, 1 total
+";
+
+        let failures = parse(output);
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].test_id, "src/sum.test.js › math › adds numbers");
+        assert_eq!(
+            failures[0].message,
+            "expect(received).toBe(expected) // Object.is equality\nExpected: 4\nReceived: 3"
+        );
+        assert_eq!(
+            failures[1].location,
+            Some(Location {
+                path: "src/sum.js".to_string(),
+                line: 2,
+            })
+        );
+        assert_eq!(
+            failures[1].stack,
+            vec![
+                "src/sum.js:2:10".to_string(),
+                "src/sum.test.js:9:5".to_string()
+            ]
+        );
+    }
+}
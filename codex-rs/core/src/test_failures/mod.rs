@@ -0,0 +1,369 @@
+//! Triage of failing test runs.
+//!
+//! When a command fails, its output is run through parsers for the common
+//! test runners (`cargo test`, pytest, jest, `go test`). Each failing test
+//! becomes a [`TestFailure`] with its id, message, location and a few stack
+//! frames; failures with the same message at the same file are grouped, and
+//! the groups are written as a short digest that goes to the model ahead of
+//! a shortened copy of the raw output. Fixing "the failing tests" then starts
+//! from a list of distinct problems instead of pages of runner output.
+
+mod cargo;
+mod go;
+mod jest;
+mod pytest;
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::truncate::TruncationPolicy;
+use crate::truncate::formatted_truncate_text;
+
+/// The raw output kept after a digest is cut to this many tokens.
+const RAW_OUTPUT_TOKENS_WITH_DIGEST: usize = 2_000;
+const MAX_GROUPS: usize = 10;
+const MAX_TEST_IDS_PER_GROUP: usize = 5;
+const MAX_MESSAGE_LINES: usize = 6;
+const MAX_LINE_CHARS: usize = 200;
+const MAX_STACK_FRAMES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TestRunner {
+    Cargo,
+    Pytest,
+    Jest,
+    Go,
+}
+
+impl TestRunner {
+    fn name(self) -> &'static str {
+        match self {
+            TestRunner::Cargo => "cargo test",
+            TestRunner::Pytest => "pytest",
+            TestRunner::Jest => "jest",
+            TestRunner::Go => "go test",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Location {
+    pub path: String,
+    pub line: u32,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.path, self.line)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TestFailure {
+    pub runner: TestRunner,
+    pub test_id: String,
+    /// One or more lines; the first is the headline.
+    pub message: String,
+    pub location: Option<Location>,
+    /// Innermost frames first, already filtered to project code where the
+    /// runner makes that possible.
+    pub stack: Vec<String>,
+}
+
+impl TestFailure {
+    fn new(runner: TestRunner, test_id: impl Into<String>) -> Self {
+        Self {
+            runner,
+            test_id: test_id.into(),
+            message: String::new(),
+            location: None,
+            stack: Vec::new(),
+        }
+    }
+}
+
+/// Failures recognized in `output`, across all supported runners.
+pub(crate) fn parse_failures(output: &str) -> Vec<TestFailure> {
+    let output = strip_ansi(output);
+    let mut failures = Vec::new();
+    failures.extend(cargo::parse(&output));
+    failures.extend(pytest::parse(&output));
+    failures.extend(jest::parse(&output));
+    failures.extend(go::parse(&output));
+
+    // Runners such as jest repeat every failure in a closing summary.
+    let mut seen = HashSet::new();
+    failures.retain(|failure| seen.insert((failure.test_id.clone(), failure.message.clone())));
+    failures
+}
+
+/// Replaces the output of a failed test run with a digest of its failures
+/// followed by the raw output cut to a smaller budget. Returns `None` when no
+/// failing tests are recognized.
+pub(crate) fn digest_output(output: &str, policy: TruncationPolicy) -> Option<String> {
+    let failures = parse_failures(output);
+    if failures.is_empty() {
+        return None;
+    }
+    let raw_policy = if policy.token_budget() > RAW_OUTPUT_TOKENS_WITH_DIGEST {
+        TruncationPolicy::Tokens(RAW_OUTPUT_TOKENS_WITH_DIGEST)
+    } else {
+        policy
+    };
+    Some(format!(
+        "{}\n\nRaw output:\n{}",
+        format_digest(&failures),
+        formatted_truncate_text(output, raw_policy)
+    ))
+}
+
+struct FailureGroup<'a> {
+    failures: Vec<&'a TestFailure>,
+}
+
+/// Groups failures that share a message (ignoring numbers) and a file.
+fn group_failures(failures: &[TestFailure]) -> Vec<FailureGroup<'_>> {
+    let mut keys: Vec<(String, Option<&str>)> = Vec::new();
+    let mut groups: Vec<FailureGroup<'_>> = Vec::new();
+    for failure in failures {
+        let key = (
+            normalize_message(&failure.message),
+            failure
+                .location
+                .as_ref()
+                .map(|location| location.path.as_str()),
+        );
+        match keys.iter().position(|existing| *existing == key) {
+            Some(index) => groups[index].failures.push(failure),
+            None => {
+                keys.push(key);
+                groups.push(FailureGroup {
+                    failures: vec![failure],
+                });
+            }
+        }
+    }
+    // Stable, so groups of equal size keep the order the runner printed.
+    groups.sort_by_key(|group| std::cmp::Reverse(group.failures.len()));
+    groups
+}
+
+fn format_digest(failures: &[TestFailure]) -> String {
+    let groups = group_failures(failures);
+    let mut runners: Vec<&str> = Vec::new();
+    for failure in failures {
+        if !runners.contains(&failure.runner.name()) {
+            runners.push(failure.runner.name());
+        }
+    }
+    let mut lines = vec![format!(
+        "Test failure digest ({}): {} failing {} in {} {}",
+        runners.join(", "),
+        failures.len(),
+        plural(failures.len(), "test", "tests"),
+        groups.len(),
+        plural(groups.len(), "group", "groups"),
+    )];
+
+    for (index, group) in groups.iter().take(MAX_GROUPS).enumerate() {
+        let first = group.failures[0];
+        let mut message_lines = first
+            .message
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .take(MAX_MESSAGE_LINES)
+            .map(|line| truncate_line(line.trim_end()));
+        let headline = message_lines
+            .next()
+            .unwrap_or_else(|| "failed without a message".to_string());
+        let location = first
+            .location
+            .as_ref()
+            .map(|location| format!("{location}: "))
+            .unwrap_or_default();
+        let count = if group.failures.len() > 1 {
+            format!("[{} tests] ", group.failures.len())
+        } else {
+            String::new()
+        };
+        lines.push(format!("{}. {count}{location}{headline}", index + 1));
+        for line in message_lines {
+            lines.push(format!("     {line}"));
+        }
+
+        let ids: Vec<&str> = group
+            .failures
+            .iter()
+            .take(MAX_TEST_IDS_PER_GROUP)
+            .map(|failure| failure.test_id.as_str())
+            .collect();
+        let more = group.failures.len().saturating_sub(MAX_TEST_IDS_PER_GROUP);
+        let more = if more > 0 {
+            format!(" and {more} more")
+        } else {
+            String::new()
+        };
+        lines.push(format!(
+            "   {}: {}{more}",
+            plural(group.failures.len(), "test", "tests"),
+            ids.join(", ")
+        ));
+        if !first.stack.is_empty() {
+            let frames: Vec<String> = first
+                .stack
+                .iter()
+                .take(MAX_STACK_FRAMES)
+                .map(|frame| truncate_line(frame))
+                .collect();
+            lines.push(format!("   stack: {}", frames.join(" <- ")));
+        }
+    }
+    if groups.len() > MAX_GROUPS {
+        lines.push(format!(
+            "… and {} more groups in the raw output",
+            groups.len() - MAX_GROUPS
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Parses `path:line` or `path:line:column`, ignoring a trailing colon.
+fn parse_location(text: &str) -> Option<Location> {
+    let text = text.trim().trim_end_matches(':');
+    let mut parts = text.rsplitn(3, ':');
+    let last = parts.next()?.parse::<u32>().ok()?;
+    let middle = parts.next()?;
+    let (path, line) = match (middle.parse::<u32>(), parts.next()) {
+        (Ok(line), Some(path)) => (path, line),
+        _ => (middle, last),
+    };
+    if path.is_empty() || path.contains(char::is_whitespace) {
+        return None;
+    }
+    Some(Location {
+        path: path.to_string(),
+        line,
+    })
+}
+
+/// Message text with digit runs collapsed, so failures that differ only in
+/// values, ids or addresses land in the same group.
+fn normalize_message(message: &str) -> String {
+    let mut normalized = String::with_capacity(message.len());
+    let mut in_digits = false;
+    for ch in message.chars() {
+        if ch.is_ascii_digit() {
+            if !in_digits {
+                normalized.push('N');
+            }
+            in_digits = true;
+        } else {
+            in_digits = false;
+            normalized.push(ch);
+        }
+    }
+    normalized
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '\u{1b}' && chars.peek() == Some(&'[') {
+            chars.next();
+            for next in chars.by_ref() {
+                if next.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(ch);
+        }
+    }
+    stripped
+}
+
+fn truncate_line(line: &str) -> String {
+    if line.chars().count() <= MAX_LINE_CHARS {
+        return line.to_string();
+    }
+    let truncated: String = line.chars().take(MAX_LINE_CHARS).collect();
+    format!("{truncated}…")
+}
+
+fn plural<'a>(count: usize, one: &'a str, many: &'a str) -> &'a str {
+    if count == 1 { one } else { many }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn failure(test_id: &str, message: &str, location: Option<(&str, u32)>) -> TestFailure {
+        TestFailure {
+            runner: TestRunner::Cargo,
+            test_id: test_id.to_string(),
+            message: message.to_string(),
+            location: location.map(|(path, line)| Location {
+                path: path.to_string(),
+                line,
+            }),
+            stack: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_locations_with_and_without_columns() {
+        assert_eq!(
+            parse_location("src/lib.rs:10:5:"),
+            Some(Location {
+                path: "src/lib.rs".to_string(),
+                line: 10,
+            })
+        );
+        assert_eq!(
+            parse_location("tests/test_api.py:42"),
+            Some(Location {
+                path: "tests/test_api.py".to_string(),
+                line: 42,
+            })
+        );
+        assert_eq!(parse_location("assertion failed: x"), None);
+    }
+
+    #[test]
+    fn groups_failures_by_message_and_file() {
+        let failures = vec![
+            failure(
+                "a::one",
+                "connection refused (os error 111)",
+                Some(("src/db.rs", 10)),
+            ),
+            failure("b::two", "expected 3, got 4", Some(("src/math.rs", 7))),
+            failure(
+                "a::three",
+                "connection refused (os error 111)",
+                Some(("src/db.rs", 22)),
+            ),
+        ];
+
+        assert_eq!(
+            format_digest(&failures),
+            "Test failure digest (cargo test): 3 failing tests in 2 groups\n\
+             1. [2 tests] src/db.rs:10: connection refused (os error 111)\n   \
+             tests: a::one, a::three\n\
+             2. src/math.rs:7: expected 3, got 4\n   \
+             test: b::two"
+        );
+    }
+
+    #[test]
+    fn output_without_failing_tests_is_left_alone() {
+        let output = "error: could not compile `app`\nerror[E0425]: cannot find value `x`";
+        assert_eq!(
+            digest_output(output, TruncationPolicy::Tokens(10_000)),
+            None
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! pytest output: the `FAILURES`/`ERRORS` sections and the short summary.
+
+use super::TestFailure;
+use super::TestRunner;
+use super::parse_location;
+
+struct Section<'a> {
+    name: &'a str,
+    body: Vec<&'a str>,
+}
+
+pub(super) fn parse(output: &str) -> Vec<TestFailure> {
+    let mut sections: Vec<Section<'_>> = Vec::new();
+    let mut summary: Vec<(&str, Option<&str>)> = Vec::new();
+    let mut block = Block::Other;
+
+    for line in output.lines() {
+        if line.starts_with('=') {
+            let title = line.trim_matches(|ch| ch == '=' || ch == ' ');
+            block = match title {
+                "FAILURES" | "ERRORS" => Block::Failures,
+                "short test summary info" => Block::Summary,
+                _ => Block::Other,
+            };
+            continue;
+        }
+        match block {
+            Block::Failures => {
+                if let Some(name) = section_header(line) {
+                    sections.push(Section {
+                        name,
+                        body: Vec::new(),
+                    });
+                } else if let Some(section) = sections.last_mut() {
+                    section.body.push(line);
+                }
+            }
+            Block::Summary => {
+                let entry = line
+                    .strip_prefix("FAILED ")
+                    .or_else(|| line.strip_prefix("ERROR "));
+                if let Some(entry) = entry {
+                    match entry.split_once(" - ") {
+                        Some((node_id, message)) => summary.push((node_id, Some(message))),
+                        None => summary.push((entry.trim(), None)),
+                    }
+                }
+            }
+            Block::Other => {}
+        }
+    }
+
+    if summary.is_empty() {
+        return sections
+            .iter()
+            .map(|section| failure_from_section(section.name, Some(section), None))
+            .collect();
+    }
+    summary
+        .into_iter()
+        .map(|(node_id, message)| {
+            let key = section_key(node_id);
+            let section = sections
+                .iter()
+                .find(|section| section.name == key || section.name.ends_with(&format!(" {key}")));
+            failure_from_section(node_id, section, message)
+        })
+        .collect()
+}
+
+enum Block {
+    Failures,
+    Summary,
+    Other,
+}
+
+/// `____ TestCalc.test_divide[0] ____`; the `_ _ _ _` lines between frames
+/// have no name.
+fn section_header(line: &str) -> Option<&str> {
+    if !(line.starts_with("__") && line.ends_with("__")) {
+        return None;
+    }
+    let name = line.trim_matches(|ch| ch == '_' || ch == ' ');
+    (!name.is_empty()).then_some(name)
+}
+
+/// Section headers name a test by its node id without the file, with `::`
+/// written as `.`; collection errors name the file itself.
+fn section_key(node_id: &str) -> String {
+    match node_id.split_once("::") {
+        Some((_, rest)) => rest.replace("::", "."),
+        None => node_id.to_string(),
+    }
+}
+
+fn failure_from_section(
+    test_id: &str,
+    section: Option<&Section<'_>>,
+    summary_message: Option<&str>,
+) -> TestFailure {
+    let mut failure = TestFailure::new(TestRunner::Pytest, test_id);
+    let Some(section) = section else {
+        failure.message = summary_message.unwrap_or_default().to_string();
+        return failure;
+    };
+
+    let error_lines: Vec<&str> = section
+        .body
+        .iter()
+        .filter_map(|line| line.strip_prefix('E'))
+        .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+        .map(str::trim)
+        .filter(|rest| !rest.is_empty())
+        .collect();
+    failure.message = if error_lines.is_empty() {
+        summary_message.unwrap_or_default().to_string()
+    } else {
+        error_lines.join("\n")
+    };
+
+    // `tests/test_calc.py:10: ZeroDivisionError`, outermost frame first.
+    let mut frames: Vec<String> = section
+        .body
+        .iter()
+        .filter(|line| !line.starts_with(char::is_whitespace))
+        .filter_map(|line| {
+            let location = line
+                .split_once(": ")
+                .map_or(*line, |(location, _)| location);
+            parse_location(location).filter(|location| location.path.ends_with(".py"))
+        })
+        .map(|location| location.to_string())
+        .collect();
+    frames.reverse();
+    failure.location = frames.first().and_then(|frame| parse_location(frame));
+    failure.stack = frames;
+    failure
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_failures::Location;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn matches_summary_lines_with_their_sections() {
+        let output = "\
+=================================== FAILURES ===================================
+_________________________________ test_answer __________________________________
+
+    def test_answer():
+>       assert inc(3) == 5
+E       assert 4 == 5
+E        +  where 4 = inc(3)
+
+test_sample.py:6: AssertionError
+___________________________ TestCalc.test_divide[0] ____________________________
+
+    def test_divide(self):
+>       divide(1, 0)
+
+tests/test_calc.py:10:
+_ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _ _
+
+    def divide(a, b):
+>       return a / b
+E       ZeroDivisionError: division by zero
+
+calc.py:2: ZeroDivisionError
+=========================== short test summary info ============================
+FAILED test_sample.py::test_answer - assert 4 == 5
+FAILED tests/test_calc.py::TestCalc::test_divide[0] - ZeroDivisionError: division by zero
+ERROR tests/test_db.py - ModuleNotFoundError: No module named 'psycopg'
+========================= 2 failed, 1 error in 0.12s ===========================
+";
+
+        let failures = parse(output);
+        assert_eq!(failures.len(), 3);
+        assert_eq!(failures[0].test_id, "test_sample.py::test_answer");
+        assert_eq!(failures[0].message, "assert 4 == 5\n+  where 4 = inc(3)");
+        assert_eq!(
+            failures[1].location,
+            Some(Location {
+                path: "calc.py".to_string(),
+                line: 2,
+            })
+        );
+        assert_eq!(
+            failures[1].stack,
+            vec!["calc.py:2".to_string(), "tests/test_calc.py:10".to_string()]
+        );
+        assert_eq!(
+            failures[2].message,
+            "ModuleNotFoundError: No module named 'psycopg'"
+        );
+    }
+}
@@ -0,0 +1,742 @@
+//! Tool calls for models without native function calling.
+//!
+//! When the model cannot be given tools (`supports_tool_calls = false`, or the
+//! provider rejects a request that has them), the tools are described in the
+//! instructions and the model calls one by writing a fenced block:
+//!
+//! ````text
+//! ```tool_call
+//! {"name": "shell", "arguments": {"command": ["ls"]}}
+//! ```
+//! ````
+//!
+//! Free-form tools such as `apply_patch` take their raw input in a block
+//! tagged with the tool name instead, so patches need no JSON escaping.
+//! Completed assistant messages are scanned for these blocks and turned back
+//! into `FunctionCall`/`CustomToolCall` items, so the rest of the tool
+//! pipeline runs unchanged. Past calls and their outputs are replayed to the
+//! model as plain messages.
+//!
+//! Small models get the format slightly wrong often enough that parsing is
+//! lenient: untagged or `json` blocks naming a known tool, a bare JSON
+//! message, `<tool_call>` tags, trailing commas, raw newlines inside strings,
+//! and a reply cut off before the closing fence or braces are all accepted.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use futures::StreamExt;
+use serde_json::Map;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::client_common::tools::ToolSpec;
+
+const TOOL_CALL_TAG: &str = "tool_call";
+
+/// Error fragments providers return when a request carries tools the model
+/// cannot use (Ollama, vLLM without a tool parser, llama.cpp, LM Studio).
+const TOOLS_REJECTED_MESSAGES: &[&str] = &[
+    "does not support tools",
+    "does not support function calling",
+    "tools are not supported",
+    "tool use is not supported",
+    "tool calling is not supported",
+    "\"auto\" tool choice requires",
+];
+
+/// Whether a provider error says the model cannot be given tools.
+pub(crate) fn rejects_native_tools(error_body: &str) -> bool {
+    let error_body = error_body.to_ascii_lowercase();
+    TOOLS_REJECTED_MESSAGES
+        .iter()
+        .any(|message| error_body.contains(message))
+}
+
+/// Rewrites a prompt so that it carries no native tools: the tools are
+/// described in the instructions and past calls become plain messages.
+pub(crate) fn encode_prompt(prompt: &Prompt) -> Prompt {
+    let mut base_instructions = prompt.base_instructions.clone();
+    base_instructions.text.push_str("\n\n");
+    base_instructions
+        .text
+        .push_str(&tool_instructions(&prompt.tools));
+    Prompt {
+        input: encode_history(prompt.get_formatted_input()),
+        tools: Vec::new(),
+        parallel_tool_calls: false,
+        base_instructions,
+        personality: prompt.personality,
+        output_schema: prompt.output_schema.clone(),
+    }
+}
+
+/// Turns tool-call blocks in completed assistant messages into tool call
+/// items. Everything else passes through untouched.
+pub(crate) fn decode_stream(mut stream: ResponseStream, tools: &[ToolSpec]) -> ResponseStream {
+    let decoder = Decoder::new(tools);
+    let (tx_event, rx_event) = mpsc::channel(1600);
+    tokio::spawn(async move {
+        while let Some(event) = stream.next().await {
+            let events = match event {
+                Ok(ResponseEvent::OutputItemDone(item)) => decoder
+                    .decode(item)
+                    .into_iter()
+                    .map(|item| Ok(ResponseEvent::OutputItemDone(item)))
+                    .collect(),
+                event => vec![event],
+            };
+            for event in events {
+                if tx_event.send(event).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    ResponseStream { rx_event }
+}
+
+fn tool_instructions(tools: &[ToolSpec]) -> String {
+    let mut text = String::from(
+        "# Tools\n\n\
+         Tools cannot be called natively in this session. To call a tool, reply with a fenced \
+         block tagged `tool_call` holding one JSON object with the tool `name` and its \
+         `arguments`:\n\n\
+         ```tool_call\n\
+         {\"name\": \"tool_name\", \"arguments\": {\"param\": \"value\"}}\n\
+         ```\n\n\
+         Free-form tools take their raw input instead, in a block tagged with the tool name:\n\n\
+         ```tool_name\n\
+         raw input\n\
+         ```\n\n\
+         You may write a short explanation before the blocks and make several calls in one \
+         reply. Stop after the last block: each result arrives in the next message. Reply \
+         without a block when you are done.\n\nAvailable tools:\n",
+    );
+    for tool in tools {
+        match tool {
+            ToolSpec::Function(tool) => {
+                let parameters =
+                    serde_json::to_string(&tool.parameters).unwrap_or_else(|_| "{}".to_string());
+                text.push_str(&format!(
+                    "\n## {}\n\n{}\n\nArguments (JSON Schema): {parameters}\n",
+                    tool.name, tool.description
+                ));
+            }
+            ToolSpec::Freeform(tool) => {
+                text.push_str(&format!(
+                    "\n## {} (free-form)\n\n{}\n\nInput ({} {}):\n{}\n",
+                    tool.name,
+                    tool.description,
+                    tool.format.syntax,
+                    tool.format.r#type,
+                    tool.format.definition.trim_end()
+                ));
+            }
+            // Hosted and built-in tools have no text form.
+            ToolSpec::LocalShell {}
+            | ToolSpec::WebSearch { .. }
+            | ToolSpec::CodeInterpreter { .. }
+            | ToolSpec::FileSearch { .. } => {}
+        }
+    }
+    text
+}
+
+/// Replays tool calls and their outputs as assistant and user messages.
+fn encode_history(items: Vec<ResponseItem>) -> Vec<ResponseItem> {
+    let mut call_names: HashMap<String, String> = HashMap::new();
+    let mut encoded = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                // Written by hand to keep `name` first whatever the map order.
+                let arguments = if parse_json_values(&arguments).is_empty() {
+                    Value::String(arguments).to_string()
+                } else {
+                    arguments.trim().to_string()
+                };
+                push_text(
+                    &mut encoded,
+                    "assistant",
+                    format!(
+                        "```{TOOL_CALL_TAG}\n{{\"name\": {}, \"arguments\": {arguments}}}\n```",
+                        Value::String(name.clone())
+                    ),
+                );
+                call_names.insert(call_id, name);
+            }
+            ResponseItem::CustomToolCall {
+                name,
+                input,
+                call_id,
+                ..
+            } => {
+                push_text(
+                    &mut encoded,
+                    "assistant",
+                    format!("```{name}\n{}\n```", input.trim_end()),
+                );
+                call_names.insert(call_id, name);
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                let name = call_names.get(&call_id).map_or("tool", String::as_str);
+                push_text(&mut encoded, "user", tool_result(name, &output.content));
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                let name = call_names.get(&call_id).map_or("tool", String::as_str);
+                push_text(&mut encoded, "user", tool_result(name, &output));
+            }
+            item => encoded.push(item),
+        }
+    }
+    encoded
+}
+
+fn tool_result(name: &str, output: &str) -> String {
+    format!("Result of `{name}`:\n{output}")
+}
+
+/// Appends text to the previous message when it has the same role, so chat
+/// templates that require alternating roles still accept the history.
+fn push_text(items: &mut Vec<ResponseItem>, role: &str, text: String) {
+    let content = if role == "assistant" {
+        ContentItem::OutputText { text }
+    } else {
+        ContentItem::InputText { text }
+    };
+    if let Some(ResponseItem::Message {
+        role: last_role,
+        content: last_content,
+        ..
+    }) = items.last_mut()
+        && last_role == role
+    {
+        last_content.push(content);
+        return;
+    }
+    items.push(ResponseItem::Message {
+        id: None,
+        role: role.to_string(),
+        content: vec![content],
+        end_turn: None,
+    });
+}
+
+struct Decoder {
+    function_tools: HashSet<String>,
+    freeform_tools: HashSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ParsedCall {
+    name: String,
+    arguments: Value,
+}
+
+impl Decoder {
+    fn new(tools: &[ToolSpec]) -> Self {
+        let mut function_tools = HashSet::new();
+        let mut freeform_tools = HashSet::new();
+        for tool in tools {
+            match tool {
+                ToolSpec::Function(tool) => {
+                    function_tools.insert(tool.name.clone());
+                }
+                ToolSpec::Freeform(tool) => {
+                    freeform_tools.insert(tool.name.clone());
+                }
+                _ => {}
+            }
+        }
+        Self {
+            function_tools,
+            freeform_tools,
+        }
+    }
+
+    fn decode(&self, item: ResponseItem) -> Vec<ResponseItem> {
+        let ResponseItem::Message {
+            id,
+            role,
+            content,
+            end_turn,
+        } = item
+        else {
+            return vec![item];
+        };
+        let text: String = content
+            .iter()
+            .filter_map(|content| match content {
+                ContentItem::OutputText { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        let (remaining, calls) = if role == "assistant" {
+            self.extract_calls(&text)
+        } else {
+            (String::new(), Vec::new())
+        };
+        if calls.is_empty() {
+            return vec![ResponseItem::Message {
+                id,
+                role,
+                content,
+                end_turn,
+            }];
+        }
+
+        let mut items = Vec::with_capacity(calls.len() + 1);
+        if !remaining.trim().is_empty() {
+            items.push(ResponseItem::Message {
+                id,
+                role,
+                content: vec![ContentItem::OutputText {
+                    text: remaining.trim().to_string(),
+                }],
+                end_turn,
+            });
+        }
+        items.extend(calls.into_iter().map(|call| self.to_item(call)));
+        items
+    }
+
+    fn to_item(&self, call: ParsedCall) -> ResponseItem {
+        let call_id = format!("call_{}", Uuid::new_v4().simple());
+        if self.freeform_tools.contains(&call.name) {
+            let input = match call.arguments {
+                Value::String(input) => input,
+                Value::Object(mut arguments) => match arguments.remove("input") {
+                    Some(Value::String(input)) => input,
+                    _ => Value::Object(arguments).to_string(),
+                },
+                arguments => arguments.to_string(),
+            };
+            return ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id,
+                name: call.name,
+                input,
+            };
+        }
+        let arguments = match call.arguments {
+            Value::Null => "{}".to_string(),
+            Value::String(arguments) => match parse_json_values(&arguments).into_iter().next() {
+                Some(value) => value.to_string(),
+                None => arguments,
+            },
+            arguments => arguments.to_string(),
+        };
+        ResponseItem::FunctionCall {
+            id: None,
+            name: call.name,
+            arguments,
+            call_id,
+        }
+    }
+
+    fn is_known(&self, name: &str) -> bool {
+        self.function_tools.contains(name) || self.freeform_tools.contains(name)
+    }
+
+    /// Splits a message into its prose and the tool calls it contains.
+    fn extract_calls(&self, text: &str) -> (String, Vec<ParsedCall>) {
+        let (remaining, calls) = self.extract_fenced_calls(text);
+        if !calls.is_empty() {
+            return (remaining, calls);
+        }
+        let (remaining, calls) = self.extract_tagged_calls(text);
+        if !calls.is_empty() {
+            return (remaining, calls);
+        }
+        // A message that is nothing but a JSON call.
+        let trimmed = text.trim();
+        if trimmed.starts_with('{') || trimmed.starts_with('[') {
+            let calls = self.calls_from_json(trimmed, false);
+            if !calls.is_empty() {
+                return (String::new(), calls);
+            }
+        }
+        (text.to_string(), Vec::new())
+    }
+
+    fn extract_fenced_calls(&self, text: &str) -> (String, Vec<ParsedCall>) {
+        let mut remaining = String::new();
+        let mut calls = Vec::new();
+        let mut lines = text.split_inclusive('\n');
+        while let Some(line) = lines.next() {
+            let Some(opening) = line.trim_start().strip_prefix("```") else {
+                remaining.push_str(line);
+                continue;
+            };
+            // The JSON sometimes starts on the fence line itself.
+            let opening = opening.trim();
+            let (tag, inline) = match opening.find(['{', '[']) {
+                Some(start) => (opening[..start].trim(), &opening[start..]),
+                None => (opening, ""),
+            };
+            let tag = tag.split_whitespace().next().unwrap_or_default();
+            let freeform = self.freeform_tools.contains(tag);
+            let mut block = line.to_string();
+            let mut body = inline.to_string();
+            if let Some(stripped) = body.trim_end().strip_suffix("```") {
+                body = stripped.to_string();
+            } else {
+                // A reply cut off before the closing fence still counts.
+                for line in lines.by_ref() {
+                    block.push_str(line);
+                    let trimmed = line.trim_end();
+                    // Free-form input (a patch to a Markdown file, say) may
+                    // contain fences of its own; only a bare fence closes it.
+                    if trimmed == "```" {
+                        break;
+                    }
+                    if !freeform && let Some(last) = trimmed.strip_suffix("```") {
+                        body.push_str(last);
+                        break;
+                    }
+                    body.push_str(line);
+                }
+            }
+
+            let block_calls = if freeform {
+                vec![ParsedCall {
+                    name: tag.to_string(),
+                    arguments: Value::String(body.trim_end_matches('\n').to_string()),
+                }]
+            } else {
+                match tag.to_ascii_lowercase().as_str() {
+                    TOOL_CALL_TAG | "tool" | "tool_use" | "function_call" => {
+                        self.calls_from_json(&body, true)
+                    }
+                    "" | "json" | "jsonc" => self.calls_from_json(&body, false),
+                    _ => Vec::new(),
+                }
+            };
+            if block_calls.is_empty() {
+                remaining.push_str(&block);
+            } else {
+                calls.extend(block_calls);
+            }
+        }
+        (remaining, calls)
+    }
+
+    /// `<tool_call>{...}</tool_call>`, the format many open models are
+    /// trained on.
+    fn extract_tagged_calls(&self, text: &str) -> (String, Vec<ParsedCall>) {
+        const OPEN: &str = "<tool_call>";
+        const CLOSE: &str = "</tool_call>";
+        let mut remaining = String::new();
+        let mut calls = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find(OPEN) {
+            remaining.push_str(&rest[..start]);
+            let after = &rest[start + OPEN.len()..];
+            let (body, next) = match after.find(CLOSE) {
+                Some(end) => (&after[..end], &after[end + CLOSE.len()..]),
+                None => (after, ""),
+            };
+            calls.extend(self.calls_from_json(body, true));
+            rest = next;
+        }
+        remaining.push_str(rest);
+        (remaining, calls)
+    }
+
+    /// Reads calls from a JSON block. Blocks that were not explicitly marked
+    /// as tool calls only count when they name a known tool.
+    fn calls_from_json(&self, body: &str, explicit: bool) -> Vec<ParsedCall> {
+        let mut calls = Vec::new();
+        for value in parse_json_values(body) {
+            let values = match value {
+                Value::Array(values) => values,
+                value => vec![value],
+            };
+            calls.extend(
+                values
+                    .into_iter()
+                    .filter_map(call_from_value)
+                    .filter(|call| explicit || self.is_known(&call.name)),
+            );
+        }
+        calls
+    }
+}
+
+fn call_from_value(value: Value) -> Option<ParsedCall> {
+    let Value::Object(mut object) = value else {
+        return None;
+    };
+    // `{"type": "function", "function": {"name": ..., "arguments": ...}}`
+    if let Some(function @ Value::Object(_)) = object.remove("function") {
+        return call_from_value(function);
+    }
+    let name =
+        ["name", "tool", "tool_name"]
+            .into_iter()
+            .find_map(|key| match object.remove(key) {
+                Some(Value::String(name)) if !name.trim().is_empty() => {
+                    Some(name.trim().to_string())
+                }
+                _ => None,
+            })?;
+    let arguments = ["arguments", "parameters", "args", "input"]
+        .into_iter()
+        .find_map(|key| object.remove(key))
+        .unwrap_or(Value::Object(Map::new()));
+    Some(ParsedCall { name, arguments })
+}
+
+/// Parses one or more JSON values, repairing common mistakes when the text
+/// is not valid as written. Text after the last complete value is ignored.
+fn parse_json_values(text: &str) -> Vec<Value> {
+    let text = text.trim();
+    let repaired = repair_json(text);
+    for candidate in [text, repaired.as_str()] {
+        let values: Vec<Value> = serde_json::Deserializer::from_str(candidate)
+            .into_iter::<Value>()
+            .map_while(Result::ok)
+            .collect();
+        if !values.is_empty() {
+            return values;
+        }
+    }
+    Vec::new()
+}
+
+/// Escapes raw control characters inside strings, drops trailing commas and
+/// closes strings, arrays and objects left open at the end.
+fn repair_json(text: &str) -> String {
+    let mut repaired = String::with_capacity(text.len() + 8);
+    let mut open = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for ch in text.chars() {
+        if in_string {
+            match ch {
+                _ if escaped => {
+                    escaped = false;
+                    repaired.push(ch);
+                }
+                '\\' => {
+                    escaped = true;
+                    repaired.push(ch);
+                }
+                '"' => {
+                    in_string = false;
+                    repaired.push(ch);
+                }
+                '\n' => repaired.push_str("\\n"),
+                '\r' => repaired.push_str("\\r"),
+                '\t' => repaired.push_str("\\t"),
+                _ => repaired.push(ch),
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '{' => open.push('}'),
+            '[' => open.push(']'),
+            '}' | ']' => {
+                drop_trailing_comma(&mut repaired);
+                open.pop();
+            }
+            _ => {}
+        }
+        repaired.push(ch);
+    }
+    if in_string {
+        repaired.push('"');
+    }
+    while let Some(close) = open.pop() {
+        drop_trailing_comma(&mut repaired);
+        repaired.push(close);
+    }
+    repaired
+}
+
+fn drop_trailing_comma(text: &mut String) {
+    let trimmed = text.trim_end().len();
+    if text[..trimmed].ends_with(',') {
+        text.truncate(trimmed - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_common::tools::FreeformTool;
+    use crate::client_common::tools::FreeformToolFormat;
+    use crate::client_common::tools::ResponsesApiTool;
+    use crate::tools::spec::JsonSchema;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    fn tools() -> Vec<ToolSpec> {
+        vec![
+            ToolSpec::Function(ResponsesApiTool {
+                name: "shell".to_string(),
+                description: "Runs a command.".to_string(),
+                strict: false,
+                parameters: JsonSchema::Object {
+                    properties: BTreeMap::new(),
+                    required: None,
+                    additional_properties: None,
+                },
+            }),
+            ToolSpec::Freeform(FreeformTool {
+                name: "apply_patch".to_string(),
+                description: "Edits files.".to_string(),
+                format: FreeformToolFormat {
+                    r#type: "grammar".to_string(),
+                    syntax: "lark".to_string(),
+                    definition: "start: patch".to_string(),
+                },
+            }),
+        ]
+    }
+
+    fn call(name: &str, arguments: Value) -> ParsedCall {
+        ParsedCall {
+            name: name.to_string(),
+            arguments,
+        }
+    }
+
+    #[test]
+    fn extracts_fenced_calls_and_keeps_prose() {
+        let decoder = Decoder::new(&tools());
+        let text = "Listing files.\n```tool_call\n{\"name\": \"shell\", \"arguments\": {\"command\": [\"ls\"]}}\n```\n```apply_patch\n*** Begin Patch\n*** End Patch\n```\n";
+
+        let (remaining, calls) = decoder.extract_calls(text);
+
+        assert_eq!(remaining, "Listing files.\n");
+        assert_eq!(
+            calls,
+            vec![
+                call("shell", serde_json::json!({"command": ["ls"]})),
+                call(
+                    "apply_patch",
+                    Value::String("*** Begin Patch\n*** End Patch".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn recovers_malformed_calls() {
+        let decoder = Decoder::new(&tools());
+        let expected = vec![call("shell", serde_json::json!({"command": ["ls",]}))];
+
+        // Trailing comma, missing closing braces and no closing fence.
+        let (_, calls) = decoder
+            .extract_calls("```json\n{\"name\": \"shell\", \"arguments\": {\"command\": [\"ls\",]");
+        assert_eq!(calls, expected);
+        // Bare JSON message.
+        let (remaining, calls) =
+            decoder.extract_calls("{\"name\": \"shell\", \"arguments\": {\"command\": [\"ls\"]}}");
+        assert_eq!((remaining.as_str(), calls), ("", expected.clone()));
+        // Hermes-style tags with a raw newline inside a string.
+        let (_, calls) = decoder.extract_calls(
+            "<tool_call>\n{\"name\": \"apply_patch\", \"arguments\": \"a\nb\"}\n</tool_call>",
+        );
+        assert_eq!(
+            calls,
+            vec![call("apply_patch", Value::String("a\nb".to_string()))]
+        );
+        // Plain JSON examples that do not name a tool are left alone.
+        let text = "```json\n{\"name\": \"example\"}\n```";
+        assert_eq!(decoder.extract_calls(text), (text.to_string(), Vec::new()));
+    }
+
+    #[test]
+    fn decoded_messages_become_tool_calls() {
+        let decoder = Decoder::new(&tools());
+        let items = decoder.decode(ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: "```tool_call\n{\"name\": \"shell\", \"arguments\": \"{\\\"command\\\": [\\\"ls\\\"]}\"}\n```"
+                    .to_string(),
+            }],
+            end_turn: None,
+        });
+
+        let [
+            ResponseItem::FunctionCall {
+                name, arguments, ..
+            },
+        ] = items.as_slice()
+        else {
+            panic!("expected a single function call, got {items:?}");
+        };
+        assert_eq!(
+            (name.as_str(), arguments.as_str()),
+            ("shell", r#"{"command":["ls"]}"#)
+        );
+    }
+
+    #[test]
+    fn history_is_replayed_as_messages() {
+        let history = vec![
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "call-1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "README.md".to_string(),
+                    ..Default::default()
+                },
+            },
+        ];
+
+        assert_eq!(
+            encode_history(history),
+            vec![
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: "```tool_call\n{\"name\": \"shell\", \"arguments\": {\"command\":[\"ls\"]}}\n```"
+                            .to_string(),
+                    }],
+                    end_turn: None,
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "Result of `shell`:\nREADME.md".to_string(),
+                    }],
+                    end_turn: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn recognizes_tool_rejections() {
+        assert!(rejects_native_tools(
+            r#"{"error":{"message":"registry.ollama.ai/library/gemma3:4b does not support tools"}}"#
+        ));
+        assert!(!rejects_native_tools("maximum context length exceeded"));
+    }
+}
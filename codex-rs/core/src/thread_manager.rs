@@ -254,15 +254,8 @@ impl ThreadManager {
         config: Config,
         path: PathBuf,
     ) -> CodexResult<NewThread> {
-        let history = RolloutRecorder::get_rollout_history(&path).await?;
-        let history = truncate_before_nth_user_message(history, nth_user_message);
         self.state
-            .spawn_thread(
-                config,
-                history,
-                Arc::clone(&self.state.auth_manager),
-                self.agent_control(),
-            )
+            .fork_thread(nth_user_message, config, path, self.agent_control())
             .await
     }
 
@@ -323,6 +316,25 @@ impl ThreadManagerState {
         .await
     }
 
+    /// See [`ThreadManager::fork_thread`].
+    pub(crate) async fn fork_thread(
+        &self,
+        nth_user_message: usize,
+        config: Config,
+        path: PathBuf,
+        agent_control: AgentControl,
+    ) -> CodexResult<NewThread> {
+        let history = RolloutRecorder::get_rollout_history(&path).await?;
+        let history = truncate_before_nth_user_message(history, nth_user_message);
+        self.spawn_thread(
+            config,
+            history,
+            Arc::clone(&self.auth_manager),
+            agent_control,
+        )
+        .await
+    }
+
     /// Spawn a new thread with optional history and register it with the manager.
     pub(crate) async fn spawn_thread(
         &self,
@@ -3,6 +3,8 @@ use crate::codex::TurnContext;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
+use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
 use crate::parse_command::parse_command;
 use crate::protocol::EventMsg;
@@ -12,7 +14,8 @@ use crate::protocol::ExecCommandSource;
 use crate::protocol::FileChange;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
-use crate::protocol::TurnDiffEvent;
+use crate::protocol::SandboxDeniedEvent;
+use crate::test_failures;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::sandboxing::ToolError;
 use codex_protocol::parse_command::ParsedCommand;
@@ -260,6 +263,17 @@ impl ToolEmitter {
         output: &ExecToolCallOutput,
         ctx: ToolEventCtx<'_>,
     ) -> String {
+        let digested;
+        let output = match failure_digest(output, ctx) {
+            Some(digest) => {
+                digested = ExecToolCallOutput {
+                    aggregated_output: StreamOutput::new(digest),
+                    ..output.clone()
+                };
+                &digested
+            }
+            None => output,
+        };
         match self {
             Self::Shell { freeform: true, .. } => {
                 super::format_exec_output_for_model_freeform(output, ctx.turn.truncation_policy)
@@ -285,8 +299,32 @@ impl ToolEmitter {
                 };
                 (event, result)
             }
+            Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
+                let response = self.format_exec_output_for_model(&output, ctx);
+                let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
+                self.emit(ctx, event).await;
+                let command = match self {
+                    Self::Shell { command, .. } | Self::UnifiedExec { command, .. } => {
+                        command.clone()
+                    }
+                    Self::ApplyPatch { .. } => vec!["apply_patch".to_string()],
+                };
+                ctx.session
+                    .send_event(
+                        ctx.turn,
+                        EventMsg::SandboxDenied(SandboxDeniedEvent {
+                            call_id: ctx.call_id.to_string(),
+                            turn_id: ctx.turn.sub_id.clone(),
+                            command,
+                        }),
+                    )
+                    .await;
+                return Err(FunctionCallError::RespondToModel(response));
+            }
             Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output })))
-            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
+            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::LimitExceeded {
+                output, ..
+            }))) => {
                 let response = self.format_exec_output_for_model(&output, ctx);
                 let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
                 let result = Err(FunctionCallError::RespondToModel(response));
@@ -321,6 +359,15 @@ impl ToolEmitter {
     }
 }
 
+/// With `test_failure_digest` enabled, the output of a failed command that
+/// ran tests is replaced by a digest of the failures and a shorter raw tail.
+fn failure_digest(output: &ExecToolCallOutput, ctx: ToolEventCtx<'_>) -> Option<String> {
+    if output.exit_code == 0 || !ctx.session.enabled(Feature::TestFailureDigest) {
+        return None;
+    }
+    test_failures::digest_output(&output.aggregated_output.text, ctx.turn.truncation_policy)
+}
+
 struct ExecCommandInput<'a> {
     command: &'a [String],
     cwd: &'a Path,
@@ -454,13 +501,13 @@ async fn emit_patch_end(
         .await;
 
     if let Some(tracker) = ctx.turn_diff_tracker {
-        let unified_diff = {
+        let turn_diff = {
             let mut guard = tracker.lock().await;
-            guard.get_unified_diff()
+            guard.get_turn_diff()
         };
-        if let Ok(Some(unified_diff)) = unified_diff {
+        if let Ok(Some(turn_diff)) = turn_diff {
             ctx.session
-                .send_event(ctx.turn, EventMsg::TurnDiff(TurnDiffEvent { unified_diff }))
+                .send_event(ctx.turn, EventMsg::TurnDiff(turn_diff))
                 .await;
         }
     }
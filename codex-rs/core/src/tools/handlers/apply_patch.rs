@@ -10,7 +10,17 @@ use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
+use crate::patch_preview::PatchBaseline;
+use crate::patch_preview::build_preview;
+use crate::patch_preview::hunk_status;
+use crate::protocol::EventMsg;
+use crate::protocol::PatchFileHunks;
+use crate::protocol::PatchHunkReportEvent;
+use crate::protocol::PatchHunkResult;
+use crate::protocol::PatchPreview;
+use crate::protocol::PatchPreviewEvent;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
@@ -29,6 +39,7 @@ use crate::tools::spec::JsonSchema;
 use async_trait::async_trait;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::ChunkOutcome;
 use codex_utils_absolute_path::AbsolutePathBuf;
 
 pub struct ApplyPatchHandler;
@@ -59,6 +70,105 @@ fn to_abs_path(cwd: &Path, path: &Path) -> Option<AbsolutePathBuf> {
     AbsolutePathBuf::resolve_path_against_base(path, cwd).ok()
 }
 
+/// Emits a [`PatchHunkReportEvent`] for files where some chunk did not apply
+/// as written, and returns a note telling the model which chunks were merged
+/// onto later edits or left out as conflicts.
+async fn report_chunk_placement(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    action: &ApplyPatchAction,
+) -> Option<String> {
+    let mut reports: Vec<_> = action.chunk_reports().iter().collect();
+    if reports.is_empty() {
+        return None;
+    }
+    reports.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut files = Vec::with_capacity(reports.len());
+    let mut notes = Vec::new();
+    for (path, chunks) in reports {
+        let display_path = path
+            .strip_prefix(&action.cwd)
+            .unwrap_or(path)
+            .display()
+            .to_string();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let number = index + 1;
+            let line = chunk.line;
+            match chunk.outcome {
+                ChunkOutcome::Applied | ChunkOutcome::Fuzzy { .. } => {}
+                ChunkOutcome::Merged => notes.push(format!(
+                    "{display_path}: chunk {number} (near line {line}) was merged onto edits made earlier in this turn."
+                )),
+                ChunkOutcome::AlreadyApplied => notes.push(format!(
+                    "{display_path}: chunk {number} (near line {line}) was already present and was skipped."
+                )),
+                ChunkOutcome::Conflict => notes.push(format!(
+                    "{display_path}: chunk {number} (near line {line}) conflicts with edits made earlier in this turn and was NOT applied; re-read the file and retry it."
+                )),
+            }
+        }
+        files.push(PatchFileHunks {
+            path: path.clone(),
+            hunks: chunks
+                .iter()
+                .map(|chunk| PatchHunkResult {
+                    line: chunk.line,
+                    status: hunk_status(chunk.outcome),
+                })
+                .collect(),
+        });
+    }
+
+    session
+        .send_event(
+            turn,
+            EventMsg::PatchHunkReport(PatchHunkReportEvent {
+                call_id: call_id.to_string(),
+                turn_id: turn.sub_id.clone(),
+                files,
+            }),
+        )
+        .await;
+
+    (!notes.is_empty()).then(|| notes.join("\n"))
+}
+
+/// With [`Feature::PatchPreview`] on, emits a [`PatchPreviewEvent`] for the
+/// verified patch and captures the files it touches so the runtime can
+/// refuse to apply it if they change before approval.
+async fn preview_patch(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: &str,
+    action: &ApplyPatchAction,
+) -> Option<(PatchPreview, PatchBaseline)> {
+    if !session.features().enabled(Feature::PatchPreview) {
+        return None;
+    }
+    let preview = build_preview(action);
+    let baseline = PatchBaseline::capture(action);
+    session
+        .send_event(
+            turn,
+            EventMsg::PatchPreview(PatchPreviewEvent {
+                call_id: call_id.to_string(),
+                turn_id: turn.sub_id.clone(),
+                preview: preview.clone(),
+            }),
+        )
+        .await;
+    Some((preview, baseline))
+}
+
+fn with_note(content: String, note: Option<&str>) -> String {
+    match note {
+        Some(note) => format!("{content}\n{note}"),
+        None => content,
+    }
+}
+
 #[async_trait]
 impl ToolHandler for ApplyPatchHandler {
     fn kind(&self) -> ToolKind {
@@ -103,11 +213,24 @@ impl ToolHandler for ApplyPatchHandler {
         // Avoid building temporary ExecParams/command vectors; derive directly from inputs.
         let cwd = turn.cwd.clone();
         let command = vec!["apply_patch".to_string(), patch_input.clone()];
-        match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
+        match apply_patch::verify_apply_patch(session.as_ref(), turn.as_ref(), &command, &cwd).await
+        {
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
-                match apply_patch::apply_patch(turn.as_ref(), changes).await {
+                let note =
+                    report_chunk_placement(session.as_ref(), turn.as_ref(), &call_id, &changes)
+                        .await;
+                if changes.is_empty()
+                    && let Some(note) = note
+                {
+                    return Err(FunctionCallError::RespondToModel(note));
+                }
+                let (preview, baseline) =
+                    preview_patch(session.as_ref(), turn.as_ref(), &call_id, &changes)
+                        .await
+                        .unzip();
+                match apply_patch::apply_patch(session.as_ref(), turn.as_ref(), changes).await {
                     InternalApplyPatchInvocation::Output(item) => {
-                        let content = item?;
+                        let content = with_note(item?, note.as_deref());
                         Ok(ToolOutput::Function {
                             content,
                             content_items: None,
@@ -134,6 +257,8 @@ impl ToolHandler for ApplyPatchHandler {
                             exec_approval_requirement: apply.exec_approval_requirement,
                             timeout_ms: None,
                             codex_exe: turn.codex_linux_sandbox_exe.clone(),
+                            preview,
+                            baseline,
                         };
 
                         let mut orchestrator = ToolOrchestrator::new();
@@ -147,13 +272,17 @@ impl ToolHandler for ApplyPatchHandler {
                         let out = orchestrator
                             .run(&mut runtime, &req, &tool_ctx, &turn, turn.approval_policy)
                             .await;
+                        if out.is_ok() && !apply.scope_expansion.is_empty() {
+                            session.expand_plan_scope(apply.scope_expansion).await;
+                        }
                         let event_ctx = ToolEventCtx::new(
                             session.as_ref(),
                             turn.as_ref(),
                             &call_id,
                             Some(&tracker),
                         );
-                        let content = emitter.finish(event_ctx, out).await?;
+                        let content =
+                            with_note(emitter.finish(event_ctx, out).await?, note.as_deref());
                         Ok(ToolOutput::Function {
                             content,
                             content_items: None,
@@ -193,7 +322,7 @@ pub(crate) async fn intercept_apply_patch(
     call_id: &str,
     tool_name: &str,
 ) -> Result<Option<ToolOutput>, FunctionCallError> {
-    match codex_apply_patch::maybe_parse_apply_patch_verified(command, cwd) {
+    match apply_patch::verify_apply_patch(session, turn, command, cwd).await {
         codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
             session
                 .record_model_warning(
@@ -201,9 +330,18 @@ pub(crate) async fn intercept_apply_patch(
                     turn,
                 )
                 .await;
-            match apply_patch::apply_patch(turn, changes).await {
+            let note = report_chunk_placement(session, turn, call_id, &changes).await;
+            if changes.is_empty()
+                && let Some(note) = note
+            {
+                return Err(FunctionCallError::RespondToModel(note));
+            }
+            let (preview, baseline) = preview_patch(session, turn, call_id, &changes)
+                .await
+                .unzip();
+            match apply_patch::apply_patch(session, turn, changes).await {
                 InternalApplyPatchInvocation::Output(item) => {
-                    let content = item?;
+                    let content = with_note(item?, note.as_deref());
                     Ok(Some(ToolOutput::Function {
                         content,
                         content_items: None,
@@ -225,6 +363,8 @@ pub(crate) async fn intercept_apply_patch(
                         exec_approval_requirement: apply.exec_approval_requirement,
                         timeout_ms,
                         codex_exe: turn.codex_linux_sandbox_exe.clone(),
+                        preview,
+                        baseline,
                     };
 
                     let mut orchestrator = ToolOrchestrator::new();
@@ -238,9 +378,12 @@ pub(crate) async fn intercept_apply_patch(
                     let out = orchestrator
                         .run(&mut runtime, &req, &tool_ctx, turn, turn.approval_policy)
                         .await;
+                    if out.is_ok() && !apply.scope_expansion.is_empty() {
+                        session.expand_plan_scope(apply.scope_expansion).await;
+                    }
                     let event_ctx =
                         ToolEventCtx::new(session, turn, call_id, tracker.as_ref().copied());
-                    let content = emitter.finish(event_ctx, out).await?;
+                    let content = with_note(emitter.finish(event_ctx, out).await?, note.as_deref());
                     Ok(Some(ToolOutput::Function {
                         content,
                         content_items: None,
@@ -0,0 +1,232 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::protocol::BackgroundProcessInfo;
+use crate::protocol::BackgroundProcessStatus;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::unified_exec::BackgroundProcessOutput;
+use crate::unified_exec::UnifiedExecContext;
+
+pub struct BackgroundProcessHandler;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum BackgroundProcessAction {
+    Start,
+    Output,
+    Stop,
+    List,
+}
+
+#[derive(Debug, Deserialize)]
+struct BackgroundProcessArgs {
+    action: BackgroundProcessAction,
+    #[serde(default)]
+    process_id: Option<String>,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    workdir: Option<String>,
+    #[serde(default)]
+    max_output_tokens: Option<usize>,
+}
+
+#[async_trait]
+impl ToolHandler for BackgroundProcessHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    /// Starting and stopping processes locks in plan mode; reading their
+    /// output and listing them does not.
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        let ToolPayload::Function { arguments } = &invocation.payload else {
+            return true;
+        };
+        is_mutating_action(arguments)
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "background_process handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: BackgroundProcessArgs = parse_arguments(&arguments)?;
+        let manager = &session.services.unified_exec_manager;
+        let context = UnifiedExecContext::new(session.clone(), turn.clone(), call_id);
+        let process_id = || {
+            args.process_id.clone().ok_or_else(|| {
+                FunctionCallError::RespondToModel(
+                    "process_id is required for output and stop".to_string(),
+                )
+            })
+        };
+
+        let content = match args.action {
+            BackgroundProcessAction::Start => {
+                let command = args
+                    .command
+                    .as_deref()
+                    .filter(|command| !command.trim().is_empty())
+                    .ok_or_else(|| {
+                        FunctionCallError::RespondToModel(
+                            "command is required for start".to_string(),
+                        )
+                    })?;
+                let workdir = args
+                    .workdir
+                    .clone()
+                    .filter(|dir| !dir.is_empty())
+                    .map(|dir| turn.resolve_path(Some(dir)));
+                let output = manager
+                    .start_background_process(command, workdir, args.max_output_tokens, &context)
+                    .await
+                    .map_err(|err| {
+                        FunctionCallError::RespondToModel(format!(
+                            "failed to start background process: {err}"
+                        ))
+                    })?;
+                format_output(&output)
+            }
+            BackgroundProcessAction::Output => {
+                let output = manager
+                    .background_process_output(&process_id()?, args.max_output_tokens)
+                    .await
+                    .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+                format_output(&output)
+            }
+            BackgroundProcessAction::Stop => {
+                let output = manager
+                    .stop_background_process(&process_id()?, args.max_output_tokens, &context)
+                    .await
+                    .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+                format!(
+                    "Process {} stopped.\n{}",
+                    output.info.process_id,
+                    format_output(&output)
+                )
+            }
+            BackgroundProcessAction::List => {
+                format_list(&manager.list_background_processes().await)
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn is_mutating_action(arguments: &str) -> bool {
+    match serde_json::from_str::<BackgroundProcessArgs>(arguments) {
+        Ok(args) => matches!(
+            args.action,
+            BackgroundProcessAction::Start | BackgroundProcessAction::Stop
+        ),
+        Err(_) => true,
+    }
+}
+
+fn format_status(info: &BackgroundProcessInfo) -> String {
+    match info.status {
+        BackgroundProcessStatus::Running => "running".to_string(),
+        BackgroundProcessStatus::Exited { exit_code } => format!("exited with code {exit_code}"),
+    }
+}
+
+fn format_output(output: &BackgroundProcessOutput) -> String {
+    let info = &output.info;
+    format!(
+        "Process {}: {}\nStatus: {}\nOutput since last read:\n{}",
+        info.process_id,
+        info.command,
+        format_status(info),
+        output.output
+    )
+}
+
+fn format_list(processes: &[BackgroundProcessInfo]) -> String {
+    if processes.is_empty() {
+        return "No background processes.".to_string();
+    }
+    processes
+        .iter()
+        .map(|info| {
+            format!(
+                "{} ({}) in {}: {}",
+                info.process_id,
+                format_status(info),
+                info.cwd.display(),
+                info.command
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn list_shows_status_and_directory() {
+        let processes = vec![
+            BackgroundProcessInfo {
+                process_id: "bg-1".to_string(),
+                command: "npm run dev".to_string(),
+                cwd: PathBuf::from("/repo/web"),
+                status: BackgroundProcessStatus::Running,
+            },
+            BackgroundProcessInfo {
+                process_id: "bg-2".to_string(),
+                command: "cargo watch".to_string(),
+                cwd: PathBuf::from("/repo"),
+                status: BackgroundProcessStatus::Exited { exit_code: 101 },
+            },
+        ];
+        assert_eq!(
+            format_list(&processes),
+            "bg-1 (running) in /repo/web: npm run dev\n\
+             bg-2 (exited with code 101) in /repo: cargo watch"
+        );
+        assert_eq!(format_list(&[]), "No background processes.");
+    }
+
+    #[test]
+    fn only_start_and_stop_are_mutating() {
+        assert!(is_mutating_action(
+            r#"{"action":"start","command":"npm run dev"}"#
+        ));
+        assert!(is_mutating_action(
+            r#"{"action":"stop","process_id":"bg-1"}"#
+        ));
+        assert!(!is_mutating_action(
+            r#"{"action":"output","process_id":"bg-1"}"#
+        ));
+        assert!(!is_mutating_action(r#"{"action":"list"}"#));
+        assert!(is_mutating_action("not json"));
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use regex::Regex;
+use serde::Deserialize;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct BlameContextHandler;
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+const MAX_BODY_CHARS: usize = 600;
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Separates fields and records in the `git show` / `git log` output.
+const FIELD_SEP: char = '\u{1f}';
+const RECORD_SEP: char = '\u{1e}';
+const COMMIT_FORMAT: &str = "--format=%H%x1f%an%x1f%as%x1f%s%x1f%b%x1e";
+
+/// `#123`, `owner/repo#123`, `GH-123`, and Jira-style `ABC-123` references,
+/// plus GitHub pull request / issue URLs.
+#[expect(clippy::expect_used)]
+static REFERENCE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?x)
+        https?://[^\s/]+/([\w.-]+/[\w.-]+)/(?:pull|issues)/(\d+)
+        | ([\w.-]+/[\w.-]+)?\#(\d+)\b
+        | \b(GH-\d+|[A-Z][A-Z0-9]+-\d+)\b
+        ",
+    )
+    .expect("valid reference regex")
+});
+
+/// Uppercase `WORD-123` tokens that are versions or standards, not tickets.
+const NON_TICKET_PREFIXES: &[&str] = &["UTF", "SHA", "ISO", "HTTP", "TLS"];
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct BlameContextArgs {
+    path: String,
+    #[serde(default)]
+    start_line: Option<usize>,
+    #[serde(default)]
+    end_line: Option<usize>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CommitInfo {
+    sha: String,
+    author: String,
+    date: String,
+    subject: String,
+    body: String,
+    /// Lines of the requested region last changed by this commit (blame mode).
+    lines: Option<usize>,
+}
+
+impl CommitInfo {
+    fn references(&self) -> Vec<String> {
+        let mut refs: Vec<String> = Vec::new();
+        for text in [&self.subject, &self.body] {
+            for caps in REFERENCE_RE.captures_iter(text) {
+                let reference = if let (Some(repo), Some(number)) = (caps.get(1), caps.get(2)) {
+                    format!("{}#{}", repo.as_str(), number.as_str())
+                } else if let Some(number) = caps.get(4) {
+                    match caps.get(3) {
+                        Some(repo) => format!("{}#{}", repo.as_str(), number.as_str()),
+                        None => format!("#{}", number.as_str()),
+                    }
+                } else if let Some(key) = caps.get(5) {
+                    let prefix = key.as_str().split('-').next().unwrap_or_default();
+                    if NON_TICKET_PREFIXES.contains(&prefix) {
+                        continue;
+                    }
+                    key.as_str().to_string()
+                } else {
+                    continue;
+                };
+                if !refs.contains(&reference) {
+                    refs.push(reference);
+                }
+            }
+        }
+        refs
+    }
+}
+
+#[async_trait]
+impl ToolHandler for BlameContextHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "blame_context handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: BlameContextArgs = parse_arguments(&arguments)?;
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+        let limit = args.limit.min(MAX_LIMIT);
+        let range = match (args.start_line, args.end_line) {
+            (None, None) => None,
+            (Some(start), end) => {
+                let end = end.unwrap_or(start);
+                if start == 0 || end < start {
+                    return Err(FunctionCallError::RespondToModel(
+                        "start_line must be >= 1 and end_line must be >= start_line".to_string(),
+                    ));
+                }
+                Some((start, end))
+            }
+            (None, Some(_)) => {
+                return Err(FunctionCallError::RespondToModel(
+                    "end_line requires start_line".to_string(),
+                ));
+            }
+        };
+
+        let path = turn.resolve_path(Some(args.path.clone()));
+        if !tokio::fs::metadata(&path)
+            .await
+            .map(|meta| meta.is_file())
+            .unwrap_or(false)
+        {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "`{}` is not a file",
+                path.display()
+            )));
+        }
+        let git_dir = path.parent().unwrap_or(&turn.cwd);
+
+        let mut commits = match range {
+            Some((start, end)) => blame_commits(git_dir, &path, start, end).await?,
+            None => log_commits(git_dir, &path, limit).await?,
+        };
+        commits.truncate(limit);
+
+        let region = match range {
+            Some((start, end)) => format!("{}:{start}-{end}", args.path),
+            None => args.path.clone(),
+        };
+        if commits.is_empty() {
+            return Ok(ToolOutput::Function {
+                content: format!("No committed history for {region}."),
+                content_items: None,
+                success: Some(true),
+            });
+        }
+        Ok(ToolOutput::Function {
+            content: format_commits(&region, &commits),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Commits that last changed each line of the region, most recent first.
+async fn blame_commits(
+    git_dir: &Path,
+    path: &Path,
+    start: usize,
+    end: usize,
+) -> Result<Vec<CommitInfo>, FunctionCallError> {
+    let range = format!("{start},{end}");
+    let blame = run_git(
+        git_dir,
+        &[
+            "blame".as_ref(),
+            "--porcelain".as_ref(),
+            "-L".as_ref(),
+            range.as_ref(),
+            "--".as_ref(),
+            path.as_os_str(),
+        ],
+    )
+    .await?;
+    let line_counts = parse_blame_line_counts(&blame);
+    if line_counts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut args: Vec<&std::ffi::OsStr> =
+        vec!["show".as_ref(), "-s".as_ref(), COMMIT_FORMAT.as_ref()];
+    args.extend(line_counts.keys().map(|sha| sha.as_ref()));
+    let shown = run_git(git_dir, &args).await?;
+    let mut commits = parse_commits(&shown);
+    for commit in &mut commits {
+        commit.lines = line_counts.get(&commit.sha).copied();
+    }
+    // `%as` is YYYY-MM-DD, so string order is date order.
+    commits.sort_by(|a, b| b.date.cmp(&a.date));
+    Ok(commits)
+}
+
+async fn log_commits(
+    git_dir: &Path,
+    path: &Path,
+    limit: usize,
+) -> Result<Vec<CommitInfo>, FunctionCallError> {
+    let max_count = format!("--max-count={limit}");
+    let log = run_git(
+        git_dir,
+        &[
+            "log".as_ref(),
+            "--follow".as_ref(),
+            max_count.as_ref(),
+            COMMIT_FORMAT.as_ref(),
+            "--".as_ref(),
+            path.as_os_str(),
+        ],
+    )
+    .await?;
+    Ok(parse_commits(&log))
+}
+
+async fn run_git(cwd: &Path, args: &[&std::ffi::OsStr]) -> Result<String, FunctionCallError> {
+    let output = timeout(
+        COMMAND_TIMEOUT,
+        Command::new("git").current_dir(cwd).args(args).output(),
+    )
+    .await
+    .map_err(|_| FunctionCallError::RespondToModel("git timed out after 30 seconds".to_string()))?
+    .map_err(|err| FunctionCallError::RespondToModel(format!("failed to launch git: {err}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(FunctionCallError::RespondToModel(format!(
+            "git failed: {}",
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Counts region lines per commit from `git blame --porcelain`, skipping
+/// uncommitted lines.
+fn parse_blame_line_counts(porcelain: &str) -> HashMap<String, usize> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for line in porcelain.lines() {
+        // Header lines: `<40-hex sha> <orig line> <final line> [<group size>]`.
+        let mut fields = line.split(' ');
+        let Some(sha) = fields.next() else {
+            continue;
+        };
+        let is_header =
+            sha.len() == 40 && sha.chars().all(|c| c.is_ascii_hexdigit()) && fields.count() >= 2;
+        if is_header && sha.chars().any(|c| c != '0') {
+            *counts.entry(sha.to_string()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+fn parse_commits(output: &str) -> Vec<CommitInfo> {
+    output
+        .split(RECORD_SEP)
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(5, FIELD_SEP);
+            let sha = fields.next()?.trim();
+            if sha.is_empty() {
+                return None;
+            }
+            Some(CommitInfo {
+                sha: sha.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+                body: fields.next().unwrap_or_default().trim().to_string(),
+                lines: None,
+            })
+        })
+        .collect()
+}
+
+fn format_commits(region: &str, commits: &[CommitInfo]) -> String {
+    let mut out = format!(
+        "{} commit(s) touching {region} (most recent first):\n",
+        commits.len()
+    );
+    for commit in commits {
+        out.push('\n');
+        let short_sha = commit.sha.get(..10).unwrap_or(&commit.sha);
+        out.push_str(&format!("{short_sha} {} {}", commit.date, commit.author));
+        if let Some(lines) = commit.lines {
+            out.push_str(&format!(" ({lines} line(s) in region)"));
+        }
+        out.push('\n');
+        out.push_str(&format!("  {}\n", commit.subject));
+        if !commit.body.is_empty() {
+            let mut body: String = commit.body.chars().take(MAX_BODY_CHARS).collect();
+            if body.len() < commit.body.len() {
+                body.push('…');
+            }
+            for line in body.lines().filter(|line| !line.trim().is_empty()) {
+                out.push_str(&format!("  {line}\n"));
+            }
+        }
+        let refs = commit.references();
+        if !refs.is_empty() {
+            out.push_str(&format!("  refs: {}\n", refs.join(", ")));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command as StdCommand;
+    use tempfile::tempdir;
+
+    fn commit(subject: &str, body: &str) -> CommitInfo {
+        CommitInfo {
+            sha: "a".repeat(40),
+            author: "Dev".to_string(),
+            date: "2026-01-02".to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+            lines: None,
+        }
+    }
+
+    #[test]
+    fn extracts_pr_and_issue_references() {
+        let commit = commit(
+            "Fix overflow in parser (#123)",
+            "Closes acme/widgets#45 and PROJ-9.\nSee https://github.com/acme/widgets/pull/77",
+        );
+        assert_eq!(
+            commit.references(),
+            vec![
+                "#123".to_string(),
+                "acme/widgets#45".to_string(),
+                "PROJ-9".to_string(),
+                "acme/widgets#77".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_commit_records() {
+        let sha = "b".repeat(40);
+        let output =
+            format!("{sha}\u{1f}Dev\u{1f}2026-01-02\u{1f}Subject\u{1f}Body line\n\u{1e}\n");
+        assert_eq!(
+            parse_commits(&output),
+            vec![CommitInfo {
+                sha,
+                author: "Dev".to_string(),
+                date: "2026-01-02".to_string(),
+                subject: "Subject".to_string(),
+                body: "Body line".to_string(),
+                lines: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn counts_blame_lines_and_skips_uncommitted() {
+        let a = "a".repeat(40);
+        let zero = "0".repeat(40);
+        let porcelain =
+            format!("{a} 1 1 2\nauthor Dev\n\tfirst\n{a} 2 2\n\tsecond\n{zero} 3 3 1\n\tthird\n");
+        assert_eq!(parse_blame_line_counts(&porcelain), HashMap::from([(a, 2)]));
+    }
+
+    #[tokio::test]
+    async fn blames_region_in_real_repo() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let git = |args: &[&str]| {
+            StdCommand::new("git")
+                .current_dir(dir.path())
+                .args(args)
+                .env("GIT_AUTHOR_NAME", "Dev")
+                .env("GIT_AUTHOR_EMAIL", "dev@example.com")
+                .env("GIT_COMMITTER_NAME", "Dev")
+                .env("GIT_COMMITTER_EMAIL", "dev@example.com")
+                .output()
+        };
+        if git(&["init", "-q"]).is_err() {
+            return Ok(());
+        }
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "one\ntwo\n")?;
+        git(&["add", "."])?;
+        git(&["commit", "-q", "-m", "Add lib (#12)"])?;
+        std::fs::write(&file, "one\nTWO\nthree\n")?;
+        git(&["commit", "-q", "-am", "Tweak lib for PROJ-3"])?;
+
+        let commits = blame_commits(dir.path(), &file, 2, 3).await?;
+        let subjects: Vec<(&str, Option<usize>)> = commits
+            .iter()
+            .map(|commit| (commit.subject.as_str(), commit.lines))
+            .collect();
+        assert_eq!(subjects, vec![("Tweak lib for PROJ-3", Some(2))]);
+
+        let commits = log_commits(dir.path(), &file, 10).await?;
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[1].references(), vec!["#12".to_string()]);
+        Ok(())
+    }
+}
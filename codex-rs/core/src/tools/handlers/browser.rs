@@ -0,0 +1,210 @@
+use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ReviewDecision;
+use serde::Deserialize;
+use serde::Serialize;
+use url::Url;
+
+use crate::browser::PageInfo;
+use crate::browser::needs_approval;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::BrowserConfig;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::sandboxing::with_cached_approval;
+
+/// Handles `browser_navigate`, `browser_screenshot`, `browser_read_text` and
+/// `browser_click`.
+pub struct BrowserHandler;
+
+#[derive(Deserialize)]
+struct NavigateArgs {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct ScreenshotArgs {
+    #[serde(default)]
+    full_page: bool,
+}
+
+#[derive(Deserialize)]
+struct ReadTextArgs {
+    #[serde(default)]
+    selector: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ClickArgs {
+    selector: String,
+}
+
+/// Approvals are remembered per origin for the rest of the session.
+#[derive(Serialize)]
+struct BrowserApprovalKey {
+    origin: String,
+}
+
+#[async_trait]
+impl ToolHandler for BrowserHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "browser handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let config = turn.client.config();
+        let Some(browser_config) = &config.browser else {
+            return Err(FunctionCallError::RespondToModel(
+                "the browser is not configured".to_string(),
+            ));
+        };
+        let browser = &session.services.browser;
+
+        let content = match tool_name.as_str() {
+            "browser_navigate" => {
+                let args: NavigateArgs = parse_arguments(&arguments)?;
+                let url = Url::parse(&args.url).map_err(|err| {
+                    FunctionCallError::RespondToModel(format!("invalid URL `{}`: {err}", args.url))
+                })?;
+                if !matches!(url.scheme(), "http" | "https" | "file") {
+                    return Err(FunctionCallError::RespondToModel(format!(
+                        "only http, https and file URLs can be opened, not `{}`",
+                        url.scheme()
+                    )));
+                }
+                if let Some(offline) = &config.offline
+                    && url.scheme() != "file"
+                {
+                    offline
+                        .check_url("browser_navigate", url.as_str())
+                        .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+                }
+                approve_navigation(&session, &turn, call_id, &url, browser_config).await?;
+                let page = browser
+                    .navigate(browser_config, &url)
+                    .await
+                    .map_err(FunctionCallError::RespondToModel)?;
+                format_page(&page)
+            }
+            "browser_screenshot" => {
+                let args: ScreenshotArgs = parse_arguments(&arguments)?;
+                let png = browser
+                    .screenshot(browser_config, args.full_page)
+                    .await
+                    .map_err(FunctionCallError::RespondToModel)?;
+                return Ok(ToolOutput::Function {
+                    content: "Screenshot attached.".to_string(),
+                    content_items: Some(vec![FunctionCallOutputContentItem::InputImage {
+                        image_url: format!("data:image/png;base64,{png}"),
+                    }]),
+                    success: Some(true),
+                });
+            }
+            "browser_read_text" => {
+                let args: ReadTextArgs = parse_arguments(&arguments)?;
+                let text = browser
+                    .read_text(browser_config, args.selector.as_deref())
+                    .await
+                    .map_err(FunctionCallError::RespondToModel)?;
+                format!(
+                    "URL: {}\nTitle: {}\n\n{}",
+                    text.url,
+                    text.title,
+                    text.text.trim()
+                )
+            }
+            "browser_click" => {
+                let args: ClickArgs = parse_arguments(&arguments)?;
+                let page = browser
+                    .click(browser_config, &args.selector)
+                    .await
+                    .map_err(FunctionCallError::RespondToModel)?;
+                format_page(&page)
+            }
+            other => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "unsupported browser tool {other}"
+                )));
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Asks before opening an origin that is neither loopback nor listed in
+/// `browser.allowed_origins`. With `approval_policy = "never"` such origins
+/// are refused outright.
+async fn approve_navigation(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: String,
+    url: &Url,
+    config: &BrowserConfig,
+) -> Result<(), FunctionCallError> {
+    if !needs_approval(url, config) {
+        return Ok(());
+    }
+    let origin = url.origin().ascii_serialization();
+    if turn.approval_policy == AskForApproval::Never {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "opening {origin} needs approval, which the approval policy does not allow; the user can add it to browser.allowed_origins"
+        )));
+    }
+    let keys = vec![BrowserApprovalKey {
+        origin: origin.clone(),
+    }];
+    let decision = with_cached_approval(&session.services, "browser_navigate", keys, || async {
+        session
+            .request_command_approval(
+                turn,
+                call_id,
+                vec!["browser_navigate".to_string(), url.to_string()],
+                turn.cwd.clone(),
+                Some(format!("Open {origin} in the browser")),
+                None,
+            )
+            .await
+    })
+    .await;
+    match decision {
+        ReviewDecision::Approved
+        | ReviewDecision::ApprovedForSession
+        | ReviewDecision::ApprovedExecpolicyAmendment { .. } => Ok(()),
+        ReviewDecision::Denied | ReviewDecision::Abort => Err(FunctionCallError::RespondToModel(
+            format!("the user declined opening {url}"),
+        )),
+    }
+}
+
+fn format_page(page: &PageInfo) -> String {
+    format!("URL: {}\nTitle: {}", page.url, page.title)
+}
@@ -0,0 +1,359 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::code_outline::ReferenceMatch;
+use crate::code_outline::Symbol;
+use crate::code_outline::SymbolKind;
+use crate::code_outline::SymbolMatch;
+use crate::code_outline::find_references;
+use crate::code_outline::outline_file;
+use crate::code_outline::with_shared_index;
+use crate::function_tool::FunctionCallError;
+use crate::git_info::get_git_repo_root;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct CodeOutlineHandler;
+
+pub struct FindSymbolHandler;
+
+/// Keeps directory outlines of large trees from flooding the context.
+const MAX_OUTLINE_SYMBOLS: usize = 400;
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct CodeOutlineArgs {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct FindSymbolArgs {
+    name: String,
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    references: bool,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[async_trait]
+impl ToolHandler for CodeOutlineHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "code_outline handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: CodeOutlineArgs = parse_arguments(&arguments)?;
+        let path = turn.resolve_path(Some(args.path.clone()));
+        let metadata = tokio::fs::metadata(&path).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("cannot read `{}`: {err}", args.path))
+        })?;
+
+        let content = if metadata.is_file() {
+            let outline_path = path.clone();
+            let symbols = run_blocking(move || outline_file(&outline_path)).await?;
+            match symbols {
+                Some(symbols) => format_file_outline(&args.path, &symbols),
+                None => {
+                    return Err(FunctionCallError::RespondToModel(format!(
+                        "`{}` is not in a supported language (Rust, Python, JavaScript, TypeScript, Go)",
+                        args.path
+                    )));
+                }
+            }
+        } else {
+            let root = project_root(&turn.cwd);
+            let prefix = relative_to(&root, &path)?;
+            let symbols = run_blocking(move || {
+                with_shared_index(&root, |index| index.symbols_under(Some(&prefix)))
+            })
+            .await?;
+            format_directory_outline(&symbols)
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+#[async_trait]
+impl ToolHandler for FindSymbolHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "find_symbol handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: FindSymbolArgs = parse_arguments(&arguments)?;
+        let name = args.name.trim().to_string();
+        if name.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "name must not be empty".to_string(),
+            ));
+        }
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+        let limit = args.limit.min(MAX_LIMIT);
+        let kind = match args.kind.as_deref() {
+            None => None,
+            Some(kind) => Some(SymbolKind::parse(kind).ok_or_else(|| {
+                FunctionCallError::RespondToModel(format!("unknown symbol kind `{kind}`"))
+            })?),
+        };
+
+        let root = project_root(&turn.cwd);
+        let prefix = match &args.path {
+            Some(path) => Some(relative_to(&root, &turn.resolve_path(Some(path.clone())))?),
+            None => None,
+        };
+        let include_references = args.references;
+        let (definitions, references) = run_blocking(move || {
+            let definitions =
+                with_shared_index(&root, |index| index.find(&name, kind, prefix.as_deref()))?;
+            let references = if include_references {
+                // References are found by identifier, so drop any qualifier.
+                let bare_name = name.rsplit([':', '.']).next().unwrap_or(&name);
+                find_references(&root, bare_name, prefix.as_deref(), limit)?
+            } else {
+                Vec::new()
+            };
+            Ok((definitions, references))
+        })
+        .await?;
+
+        Ok(ToolOutput::Function {
+            content: format_find_results(
+                &args.name,
+                &definitions,
+                include_references.then_some(references.as_slice()),
+                limit,
+            ),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn project_root(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+/// `path` relative to the project root, as used by the symbol index.
+fn relative_to(root: &Path, path: &Path) -> Result<String, FunctionCallError> {
+    path.strip_prefix(root)
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .map_err(|_| {
+            FunctionCallError::RespondToModel(format!(
+                "`{}` is outside the project at {}",
+                path.display(),
+                root.display()
+            ))
+        })
+}
+
+/// Parsing and listing files is blocking work, so keep it off the async
+/// runtime.
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> std::io::Result<T> + Send + 'static,
+) -> Result<T, FunctionCallError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(format!("lookup failed: {err}")))?
+        .map_err(|err| FunctionCallError::RespondToModel(format!("lookup failed: {err}")))
+}
+
+fn qualified_name(symbol: &Symbol) -> String {
+    match &symbol.container {
+        Some(container) => format!("{container}.{}", symbol.name),
+        None => symbol.name.clone(),
+    }
+}
+
+fn format_symbol(symbol: &Symbol) -> String {
+    format!(
+        "{}-{} {} {}: {}",
+        symbol.start_line,
+        symbol.end_line,
+        symbol.kind.as_str(),
+        qualified_name(symbol),
+        symbol.signature
+    )
+}
+
+fn format_file_outline(path: &str, symbols: &[Symbol]) -> String {
+    if symbols.is_empty() {
+        return format!("No definitions found in {path}.");
+    }
+    let mut out = path.to_string();
+    for symbol in symbols {
+        out.push_str(&format!("\n  {}", format_symbol(symbol)));
+    }
+    out
+}
+
+fn format_directory_outline(symbols: &[SymbolMatch]) -> String {
+    if symbols.is_empty() {
+        return "No definitions found.".to_string();
+    }
+    let mut out = String::new();
+    let mut current_path: Option<&str> = None;
+    for found in symbols.iter().take(MAX_OUTLINE_SYMBOLS) {
+        if current_path != Some(found.path.as_str()) {
+            if current_path.is_some() {
+                out.push('\n');
+            }
+            out.push_str(&found.path);
+            current_path = Some(&found.path);
+        }
+        out.push_str(&format!("\n  {}", format_symbol(&found.symbol)));
+    }
+    if symbols.len() > MAX_OUTLINE_SYMBOLS {
+        out.push_str(&format!(
+            "\n\n({} more definitions omitted; outline a narrower path.)",
+            symbols.len() - MAX_OUTLINE_SYMBOLS
+        ));
+    }
+    out
+}
+
+fn format_find_results(
+    name: &str,
+    definitions: &[SymbolMatch],
+    references: Option<&[ReferenceMatch]>,
+    limit: usize,
+) -> String {
+    let mut out = if definitions.is_empty() {
+        format!("No definitions of `{name}` found.")
+    } else {
+        format!("Definitions of `{name}` ({}):", definitions.len())
+    };
+    for found in definitions.iter().take(limit) {
+        out.push_str(&format!(
+            "\n{}:{}",
+            found.path,
+            format_symbol(&found.symbol)
+        ));
+    }
+    if let Some(references) = references {
+        if references.is_empty() {
+            out.push_str(&format!("\nNo references to `{name}` found."));
+        } else {
+            let more = if references.len() >= limit {
+                " (limit reached)"
+            } else {
+                ""
+            };
+            out.push_str(&format!("\nReferences ({}{more}):", references.len()));
+            for found in references {
+                out.push_str(&format!(
+                    "\n{}:{}:{}: {}",
+                    found.path, found.reference.line, found.reference.column, found.reference.text
+                ));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code_outline::Reference;
+    use pretty_assertions::assert_eq;
+
+    fn symbol(name: &str, kind: SymbolKind, container: Option<&str>, line: usize) -> Symbol {
+        Symbol {
+            name: name.to_string(),
+            kind,
+            container: container.map(str::to_string),
+            start_line: line,
+            end_line: line + 2,
+            signature: format!("fn {name}() {{"),
+        }
+    }
+
+    #[test]
+    fn file_outline_lists_qualified_symbols() {
+        assert_eq!(
+            format_file_outline(
+                "src/config.rs",
+                &[
+                    symbol("Config", SymbolKind::Struct, None, 1),
+                    symbol("load", SymbolKind::Method, Some("Config"), 5),
+                ]
+            ),
+            "src/config.rs\n  1-3 struct Config: fn Config() {\n  5-7 method Config.load: fn load() {"
+        );
+        assert_eq!(
+            format_file_outline("a.rs", &[]),
+            "No definitions found in a.rs."
+        );
+    }
+
+    #[test]
+    fn find_results_include_references_when_requested() {
+        let definitions = vec![SymbolMatch {
+            path: "src/lib.rs".to_string(),
+            symbol: symbol("load", SymbolKind::Function, None, 1),
+        }];
+        let references = vec![ReferenceMatch {
+            path: "src/main.rs".to_string(),
+            reference: Reference {
+                line: 4,
+                column: 5,
+                text: "load();".to_string(),
+            },
+        }];
+
+        assert_eq!(
+            format_find_results("load", &definitions, None, 20),
+            "Definitions of `load` (1):\nsrc/lib.rs:1-3 function load: fn load() {"
+        );
+        assert_eq!(
+            format_find_results("load", &[], Some(&references), 20),
+            "No definitions of `load` found.\nReferences (1):\nsrc/main.rs:4:5: load();"
+        );
+    }
+}
@@ -44,6 +44,12 @@ impl ToolHandler for CollabHandler {
         matches!(payload, ToolPayload::Function { .. })
     }
 
+    /// Sub-agents run without plan mode, so starting one or giving it new
+    /// instructions locks in plan mode; waiting for and closing them does not.
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        !matches!(invocation.tool_name.as_str(), "wait" | "close_agent")
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
             session,
@@ -560,7 +566,7 @@ fn collab_agent_error(agent_id: ThreadId, err: CodexErr) -> FunctionCallError {
     }
 }
 
-fn build_agent_spawn_config(
+pub(crate) fn build_agent_spawn_config(
     base_instructions: &BaseInstructions,
     turn: &TurnContext,
 ) -> Result<Config, FunctionCallError> {
@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::project_glossary::Glossary;
+use crate::project_glossary::format_definitions;
+use crate::project_glossary::glossary_paths;
+use crate::project_glossary::lookup;
+use crate::project_glossary::read_glossary;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct DefineHandler;
+
+/// Most known terms listed when nothing matches the query.
+const MAX_SUGGESTED_TERMS: usize = 50;
+
+#[derive(Deserialize)]
+struct DefineArgs {
+    term: String,
+}
+
+#[async_trait]
+impl ToolHandler for DefineHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "define handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: DefineArgs = parse_arguments(&arguments)?;
+        let glossary = read_glossary(&turn.cwd)
+            .await
+            .map_err(FunctionCallError::RespondToModel)?;
+        if glossary.entries.is_empty() {
+            let paths = glossary_paths(&turn.cwd)
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" or ");
+            return Err(FunctionCallError::RespondToModel(format!(
+                "This project has no glossary ({paths})."
+            )));
+        }
+
+        Ok(ToolOutput::Function {
+            content: define(&glossary, &args.term),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn define(glossary: &Glossary, term: &str) -> String {
+    let matches = lookup(&glossary.entries, term);
+    if !matches.is_empty() {
+        return format_definitions(&matches);
+    }
+    let mut known: Vec<&str> = glossary
+        .entries
+        .iter()
+        .take(MAX_SUGGESTED_TERMS)
+        .map(|entry| entry.term.as_str())
+        .collect();
+    if glossary.entries.len() > MAX_SUGGESTED_TERMS {
+        known.push("...");
+    }
+    format!(
+        "No glossary entry matches `{}`. Known terms: {}",
+        term.trim(),
+        known.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project_glossary::GlossaryEntry;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn unknown_terms_list_the_known_ones() {
+        let glossary = Glossary {
+            paths: Vec::new(),
+            entries: vec![
+                GlossaryEntry {
+                    term: "PR".to_string(),
+                    aliases: Vec::new(),
+                    definition: "pull request".to_string(),
+                },
+                GlossaryEntry {
+                    term: "SLO".to_string(),
+                    aliases: vec!["service level objective".to_string()],
+                    definition: "availability target".to_string(),
+                },
+            ],
+        };
+
+        assert_eq!(define(&glossary, "pr"), "PR: pull request");
+        assert_eq!(
+            define(&glossary, " latency "),
+            "No glossary entry matches `latency`. Known terms: PR, SLO"
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::spec::mcp_tool_to_openai_tool;
+
+/// Returns the schema of an MCP tool that `tool_trimming` listed by name
+/// only, and keeps that tool's schema in later requests.
+pub struct DescribeToolHandler;
+
+#[derive(Deserialize)]
+struct DescribeToolArgs {
+    name: String,
+}
+
+#[async_trait]
+impl ToolHandler for DescribeToolHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "describe_tool handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let DescribeToolArgs { name } = parse_arguments(&arguments)?;
+        let tools = session
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .list_all_tools()
+            .await;
+        let Some(info) = tools.get(&name) else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "`{name}` is not a tool listed without parameters; its schema is already in the tool list or it does not exist"
+            )));
+        };
+        let tool = mcp_tool_to_openai_tool(name.clone(), info.tool.clone()).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to read the schema of `{name}`: {err}"
+            ))
+        })?;
+        session.services.tool_trimming.expand(&name).await;
+
+        let content = serde_json::to_string_pretty(&tool).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to serialize the schema of `{name}`: {err}"
+            ))
+        })?;
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::FollowUpSuggestion;
+use codex_protocol::protocol::FollowUpSuggestionsEvent;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Suggestions beyond this many are dropped.
+pub(crate) const MAX_FOLLOW_UPS: usize = 5;
+
+/// Shows the next steps the agent proposes to the user, who can edit one in
+/// the composer or queue it as the next turn.
+pub struct SuggestFollowUpsHandler;
+
+#[derive(Deserialize)]
+struct SuggestFollowUpsArgs {
+    suggestions: Vec<FollowUpSuggestion>,
+}
+
+#[async_trait]
+impl ToolHandler for SuggestFollowUpsHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "suggest_follow_ups handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let SuggestFollowUpsArgs { suggestions } = parse_arguments(&arguments)?;
+        let suggestions = clean_suggestions(suggestions);
+        if suggestions.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "suggest_follow_ups requires at least one suggestion with a prompt".to_string(),
+            ));
+        }
+
+        let count = suggestions.len();
+        session
+            .send_event(
+                turn.as_ref(),
+                EventMsg::FollowUpSuggestions(FollowUpSuggestionsEvent { suggestions }),
+            )
+            .await;
+        Ok(ToolOutput::Function {
+            content: format!(
+                "Showed {count} follow-up suggestion(s) to the user. Do not repeat them in your final message."
+            ),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Trims the suggestions, drops those without a prompt, falls back to the
+/// prompt for a missing title, and keeps at most [`MAX_FOLLOW_UPS`].
+fn clean_suggestions(suggestions: Vec<FollowUpSuggestion>) -> Vec<FollowUpSuggestion> {
+    suggestions
+        .into_iter()
+        .filter_map(|suggestion| {
+            let prompt = suggestion.prompt.trim().to_string();
+            if prompt.is_empty() {
+                return None;
+            }
+            let title = match suggestion.title.trim() {
+                "" => prompt.lines().next().unwrap_or_default().to_string(),
+                title => title.to_string(),
+            };
+            Some(FollowUpSuggestion { title, prompt })
+        })
+        .take(MAX_FOLLOW_UPS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn suggestion(title: &str, prompt: &str) -> FollowUpSuggestion {
+        FollowUpSuggestion {
+            title: title.to_string(),
+            prompt: prompt.to_string(),
+        }
+    }
+
+    #[test]
+    fn clean_suggestions_drops_empty_prompts_and_caps_count() {
+        let mut suggestions = vec![
+            suggestion(" Add tests ", " Add unit tests for the parser.\n"),
+            suggestion("Empty", "  "),
+            suggestion("", "Update the changelog\nwith the new flag."),
+        ];
+        suggestions.extend((0..10).map(|i| suggestion("More", &format!("Step {i}"))));
+
+        let cleaned = clean_suggestions(suggestions);
+        assert_eq!(cleaned.len(), MAX_FOLLOW_UPS);
+        assert_eq!(
+            cleaned[..2],
+            [
+                suggestion("Add tests", "Add unit tests for the parser."),
+                suggestion(
+                    "Update the changelog",
+                    "Update the changelog\nwith the new flag."
+                ),
+            ]
+        );
+    }
+}
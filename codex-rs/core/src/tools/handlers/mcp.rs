@@ -16,6 +16,31 @@ impl ToolHandler for McpHandler {
         ToolKind::Mcp
     }
 
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        let ToolPayload::Mcp { server, tool, .. } = &invocation.payload else {
+            return true;
+        };
+        // Only a server's `readOnlyHint` tells us a tool has no side effects.
+        let tools = invocation
+            .session
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .list_all_tools()
+            .await;
+        !tools.values().any(|info| {
+            info.server_name == *server
+                && info.tool_name == *tool
+                && info
+                    .tool
+                    .annotations
+                    .as_ref()
+                    .and_then(|annotations| annotations.read_only_hint)
+                    == Some(true)
+        })
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
             session,
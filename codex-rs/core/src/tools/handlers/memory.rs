@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::MemoryEntry;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::project_memory::MemoryEdit;
+use crate::project_memory::edit_entries;
+use crate::project_memory::memory_path;
+use crate::project_memory::read_entries;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct MemoryHandler;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum MemoryAction {
+    List,
+    Add,
+    Update,
+    Delete,
+}
+
+#[derive(Deserialize)]
+struct MemoryArgs {
+    action: MemoryAction,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for MemoryHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    /// Adding, updating and deleting entries write `.codex/memory.md`, so
+    /// they lock in plan mode; listing does not.
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        let ToolPayload::Function { arguments } = &invocation.payload else {
+            return true;
+        };
+        is_mutating_action(arguments)
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "memory handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: MemoryArgs = parse_arguments(&arguments)?;
+        let path = memory_path(&turn.cwd);
+        let edit = match args.action {
+            MemoryAction::List => None,
+            MemoryAction::Add => Some(MemoryEdit::Add {
+                text: required(args.text, "text", "add")?,
+            }),
+            MemoryAction::Update => Some(MemoryEdit::Update {
+                id: required(args.id, "id", "update")?,
+                text: required(args.text, "text", "update")?,
+            }),
+            MemoryAction::Delete => Some(MemoryEdit::Delete {
+                id: required(args.id, "id", "delete")?,
+            }),
+        };
+
+        let entries = match edit {
+            Some(edit) => edit_entries(&path, edit)
+                .await
+                .map_err(FunctionCallError::RespondToModel)?,
+            None => read_entries(&path).await.map_err(|err| {
+                FunctionCallError::RespondToModel(format!(
+                    "Failed to read {}: {err}",
+                    path.display()
+                ))
+            })?,
+        };
+
+        Ok(ToolOutput::Function {
+            content: format_entries(&path, &entries),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn is_mutating_action(arguments: &str) -> bool {
+    match serde_json::from_str::<MemoryArgs>(arguments) {
+        Ok(args) => !matches!(args.action, MemoryAction::List),
+        Err(_) => true,
+    }
+}
+
+fn required(value: Option<String>, field: &str, action: &str) -> Result<String, FunctionCallError> {
+    value.ok_or_else(|| {
+        FunctionCallError::RespondToModel(format!("`{field}` is required for `{action}`"))
+    })
+}
+
+fn format_entries(path: &Path, entries: &[MemoryEntry]) -> String {
+    if entries.is_empty() {
+        return format!("Project memory ({}) is empty.", path.display());
+    }
+    let mut out = format!("Project memory ({}):", path.display());
+    for entry in entries {
+        out.push_str(&format!("\n[{}] {}", entry.id, entry.text));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn format_entries_lists_ids() {
+        let path = PathBuf::from("/repo/.codex/memory.md");
+        assert_eq!(
+            format_entries(&path, &[]),
+            "Project memory (/repo/.codex/memory.md) is empty."
+        );
+        assert_eq!(
+            format_entries(
+                &path,
+                &[MemoryEntry {
+                    id: "1".to_string(),
+                    text: "uses pnpm".to_string(),
+                }]
+            ),
+            "Project memory (/repo/.codex/memory.md):\n[1] uses pnpm"
+        );
+    }
+}
@@ -1,35 +1,67 @@
 pub mod apply_patch;
+mod background_process;
+mod blame_context;
+mod browser;
+mod code_navigation;
 pub(crate) mod collab;
+mod define;
+mod describe_tool;
+mod follow_ups;
 mod grep_files;
 mod list_dir;
 mod mcp;
 mod mcp_resource;
+mod memory;
 mod plan;
+mod plugin;
 mod read_file;
 mod request_user_input;
+mod scan_todos;
+mod semantic_search;
 mod shell;
+mod shell_session;
+mod submit_plan;
 mod test_sync;
 mod unified_exec;
 mod view_image;
+mod web_fetch;
+mod web_search;
 
+pub(crate) use follow_ups::MAX_FOLLOW_UPS;
 pub use plan::PLAN_TOOL;
 use serde::Deserialize;
 
 use crate::function_tool::FunctionCallError;
 pub use apply_patch::ApplyPatchHandler;
+pub use background_process::BackgroundProcessHandler;
+pub use blame_context::BlameContextHandler;
+pub use browser::BrowserHandler;
+pub use code_navigation::CodeOutlineHandler;
+pub use code_navigation::FindSymbolHandler;
 pub use collab::CollabHandler;
+pub use define::DefineHandler;
+pub use describe_tool::DescribeToolHandler;
+pub use follow_ups::SuggestFollowUpsHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
+pub use memory::MemoryHandler;
 pub use plan::PlanHandler;
+pub use plugin::PluginToolHandler;
 pub use read_file::ReadFileHandler;
 pub use request_user_input::RequestUserInputHandler;
+pub use scan_todos::ScanTodosHandler;
+pub use semantic_search::SemanticSearchHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
+pub use shell_session::ShellSessionHandler;
+pub use submit_plan::SubmitPlanHandler;
 pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
+pub use web_fetch::WebFetchHandler;
+pub use web_search::WebSearchHandler;
 
 fn parse_arguments<T>(arguments: &str) -> Result<T, FunctionCallError>
 where
@@ -0,0 +1,315 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::function_tool::FunctionCallError;
+use crate::plugin_tools::PluginTool;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::runtimes::plugin::PluginRequest;
+use crate::tools::runtimes::plugin::PluginRuntime;
+use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
+
+/// Runs one tool declared in `.codex/plugins/`.
+pub struct PluginToolHandler {
+    plugin: Arc<PluginTool>,
+}
+
+impl PluginToolHandler {
+    pub(crate) fn new(plugin: Arc<PluginTool>) -> Self {
+        Self { plugin }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for PluginToolHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        self.plugin.is_mutating()
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "{} handler received unsupported payload",
+                    self.plugin.name
+                )));
+            }
+        };
+        let arguments = if arguments.trim().is_empty() {
+            "{}".to_string()
+        } else {
+            arguments
+        };
+
+        // The plugin's command is subject to the exec policy and the
+        // session's approval policy like any command the model runs.
+        let exec_approval_requirement = session
+            .services
+            .exec_policy
+            .create_exec_approval_requirement_for_command(
+                &session.features(),
+                &self.plugin.approval_command(),
+                turn.approval_policy,
+                &turn.sandbox_policy,
+                SandboxPermissions::UseDefault,
+                &turn.cwd,
+            )
+            .await;
+        let req = PluginRequest {
+            plugin: Arc::clone(&self.plugin),
+            arguments,
+            cwd: turn.cwd.clone(),
+            exec_approval_requirement,
+        };
+        let tool_ctx = ToolCtx {
+            session: session.as_ref(),
+            turn: turn.as_ref(),
+            call_id,
+            tool_name,
+        };
+        let content = ToolOrchestrator::new()
+            .run(
+                &mut PluginRuntime::new(),
+                &req,
+                &tool_ctx,
+                &turn,
+                turn.approval_policy,
+            )
+            .await
+            .map_err(|err| match err {
+                ToolError::Rejected(message) => FunctionCallError::RespondToModel(message),
+                ToolError::Codex(err) => FunctionCallError::RespondToModel(err.to_string()),
+            })?;
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codex::Session;
+    use crate::codex::TurnContext;
+    use crate::codex::make_session_and_context_with_rx;
+    use crate::command_rules::CommandRules;
+    use crate::config::types::PoliciesToml;
+    use crate::exec_policy::ExecPolicyManager;
+    use crate::plugin_tools::PluginRunner;
+    use crate::protocol::AskForApproval;
+    use crate::protocol::SandboxPolicy;
+    use crate::state::ActiveTurn;
+    use crate::turn_diff_tracker::TurnDiffTracker;
+    use async_channel::Receiver;
+    use codex_protocol::config_types::SandboxMode;
+    use codex_protocol::protocol::Event;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::ReviewDecision;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tokio::sync::Mutex;
+    use tokio::time::timeout;
+
+    fn plugin(name: &str) -> Arc<PluginTool> {
+        Arc::new(PluginTool {
+            name: name.to_string(),
+            description: format!("{name} plugin"),
+            runner: PluginRunner::Command(vec![format!("codex-test-{name}")]),
+            input_schema: serde_json::json!({ "type": "object" }),
+            output_schema: None,
+            timeout: Duration::from_secs(1),
+            sandbox_mode: SandboxMode::ReadOnly,
+            network_access: false,
+            path: PathBuf::from(format!("/repo/.codex/plugins/{name}.toml")),
+        })
+    }
+
+    /// A session whose turn runs under `approval_policy` and the given
+    /// `[[command_rules]]`, with an active turn so approvals can be answered.
+    async fn session_with(
+        approval_policy: AskForApproval,
+        command_rules: &str,
+    ) -> (Arc<Session>, Arc<TurnContext>, Receiver<Event>) {
+        let (mut session, mut turn, rx) = make_session_and_context_with_rx().await;
+        let policies: PoliciesToml = toml::from_str(command_rules).expect("parse policies");
+        Arc::get_mut(&mut session)
+            .expect("session is not shared yet")
+            .services
+            .exec_policy =
+            ExecPolicyManager::default().with_command_rules(CommandRules::from_toml(
+                policies.command_rules,
+                Path::new("/repo"),
+                Path::new("/repo/.codex/policies.toml"),
+            ));
+        let turn_mut = Arc::get_mut(&mut turn).expect("turn is not shared yet");
+        turn_mut.approval_policy = approval_policy;
+        turn_mut.sandbox_policy = SandboxPolicy::ReadOnly;
+        *session.active_turn.lock().await = Some(ActiveTurn::default());
+        (session, turn, rx)
+    }
+
+    async fn call(
+        session: &Arc<Session>,
+        turn: &Arc<TurnContext>,
+        plugin: Arc<PluginTool>,
+    ) -> Result<ToolOutput, FunctionCallError> {
+        let invocation = ToolInvocation {
+            session: Arc::clone(session),
+            turn: Arc::clone(turn),
+            tracker: Arc::new(Mutex::new(TurnDiffTracker::default())),
+            call_id: "call-1".to_string(),
+            tool_name: plugin.name.clone(),
+            payload: ToolPayload::Function {
+                arguments: "{}".to_string(),
+            },
+        };
+        PluginToolHandler::new(plugin).handle(invocation).await
+    }
+
+    /// Waits for the next approval prompt and answers it with `decision`.
+    async fn answer_prompt(
+        session: &Session,
+        turn: &TurnContext,
+        rx: &Receiver<Event>,
+        decision: ReviewDecision,
+    ) -> Vec<String> {
+        loop {
+            let event = timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .expect("approval prompt")
+                .expect("event channel open");
+            if let EventMsg::ExecApprovalRequest(request) = event.msg {
+                session.notify_approval(&turn.sub_id, decision).await;
+                return request.command;
+            }
+        }
+    }
+
+    fn prompts(rx: &Receiver<Event>) -> usize {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .filter(|event| matches!(event.msg, EventMsg::ExecApprovalRequest(_)))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn auto_deny_rule_refuses_plugin_without_asking() {
+        let (session, turn, rx) = session_with(
+            AskForApproval::UnlessTrusted,
+            r#"
+[[command_rules]]
+name = "no lint plugin"
+program = "codex-test-lint"
+decision = "auto_deny"
+reason = "lint is disabled here"
+"#,
+        )
+        .await;
+
+        let Err(FunctionCallError::RespondToModel(message)) =
+            call(&session, &turn, plugin("lint")).await
+        else {
+            panic!("the command rule should refuse the plugin");
+        };
+
+        assert!(message.contains("lint is disabled here"), "{message}");
+        assert_eq!(prompts(&rx), 0);
+    }
+
+    #[tokio::test]
+    async fn plugins_prompt_before_running() {
+        // unless-trusted asks for any command that is not known to be safe;
+        // on-request asks when a command rule says so.
+        for (approval_policy, command_rules) in [
+            (AskForApproval::UnlessTrusted, ""),
+            (
+                AskForApproval::OnRequest,
+                r#"
+[[command_rules]]
+program = "codex-test-lint"
+decision = "always_ask"
+"#,
+            ),
+        ] {
+            let (session, turn, rx) = session_with(approval_policy, command_rules).await;
+            let plugin = plugin("lint");
+
+            let pending = tokio::spawn({
+                let session = Arc::clone(&session);
+                let turn = Arc::clone(&turn);
+                let plugin = Arc::clone(&plugin);
+                async move { call(&session, &turn, plugin).await }
+            });
+            let command = answer_prompt(&session, &turn, &rx, ReviewDecision::Denied).await;
+
+            assert_eq!(command, plugin.approval_command(), "{approval_policy:?}");
+            let Err(err) = pending.await.expect("call task") else {
+                panic!("a denied plugin call should fail under {approval_policy:?}");
+            };
+            assert_eq!(
+                err,
+                FunctionCallError::RespondToModel("rejected by user".to_string())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn session_approval_covers_later_calls_to_the_same_plugin() {
+        let (session, turn, rx) = session_with(AskForApproval::UnlessTrusted, "").await;
+        let lint = plugin("lint");
+
+        let first = tokio::spawn({
+            let session = Arc::clone(&session);
+            let turn = Arc::clone(&turn);
+            let lint = Arc::clone(&lint);
+            async move { call(&session, &turn, lint).await }
+        });
+        answer_prompt(&session, &turn, &rx, ReviewDecision::ApprovedForSession).await;
+        // The plugin binary does not exist, so the approved call still fails.
+        let _ = first.await.expect("call task");
+
+        let _ = timeout(
+            Duration::from_secs(5),
+            call(&session, &turn, Arc::clone(&lint)),
+        )
+        .await
+        .expect("a plugin approved for the session runs without asking");
+        assert_eq!(prompts(&rx), 0);
+
+        // The approval is keyed by plugin, so another plugin still asks.
+        let format = plugin("format");
+        let other = tokio::spawn({
+            let session = Arc::clone(&session);
+            let turn = Arc::clone(&turn);
+            let format = Arc::clone(&format);
+            async move { call(&session, &turn, format).await }
+        });
+        let command = answer_prompt(&session, &turn, &rx, ReviewDecision::Denied).await;
+        assert_eq!(command, format.approval_command());
+        let _ = other.await.expect("call task");
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use regex::Regex;
+use serde::Deserialize;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::function_tool::FunctionCallError;
+use crate::git_info::get_git_repo_root;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::workspace_files::file_stamp;
+use crate::workspace_files::is_under;
+use crate::workspace_files::list_files;
+use crate::workspace_files::read_text;
+
+pub struct ScanTodosHandler;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+const DEFAULT_TAGS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+/// Larger files are usually generated or vendored.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_TEXT_CHARS: usize = 200;
+const BLAME_TIMEOUT: Duration = Duration::from_secs(30);
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+fn default_blame() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct ScanTodosArgs {
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    min_age_days: Option<u64>,
+    #[serde(default = "default_blame")]
+    blame: bool,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TodoItem {
+    path: String,
+    line: usize,
+    tag: String,
+    /// Name in `TODO(name)`, the conventional owner hint.
+    owner: Option<String>,
+    text: String,
+    blame: Option<BlameInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct BlameInfo {
+    commit: String,
+    author: String,
+    author_mail: String,
+    /// Seconds since the epoch.
+    author_time: i64,
+}
+
+#[async_trait]
+impl ToolHandler for ScanTodosHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "scan_todos handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ScanTodosArgs = parse_arguments(&arguments)?;
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+        let limit = args.limit.min(MAX_LIMIT);
+        let tags: Vec<String> = match args.tags {
+            Some(tags) if !tags.is_empty() => tags.iter().map(|tag| tag.to_uppercase()).collect(),
+            _ => DEFAULT_TAGS.iter().map(ToString::to_string).collect(),
+        };
+        let tag_re = tag_regex(&tags)?;
+        // Author and age filters need blame data even if it is not shown.
+        let needs_blame = args.blame || args.author.is_some() || args.min_age_days.is_some();
+
+        let root = get_git_repo_root(&turn.cwd).unwrap_or_else(|| turn.cwd.clone());
+        let prefix = match &args.path {
+            Some(path) => {
+                let resolved = turn.resolve_path(Some(path.clone()));
+                let rel = resolved.strip_prefix(&root).map_err(|_| {
+                    FunctionCallError::RespondToModel(format!(
+                        "`{path}` is outside the project at {}",
+                        root.display()
+                    ))
+                })?;
+                Some(rel.to_string_lossy().replace('\\', "/"))
+            }
+            None => None,
+        };
+
+        let scan_root = root.clone();
+        let mut items =
+            tokio::task::spawn_blocking(move || scan_files(&scan_root, prefix.as_deref(), &tag_re))
+                .await
+                .map_err(|err| {
+                    FunctionCallError::RespondToModel(format!("scan_todos failed: {err}"))
+                })?
+                .map_err(|err| {
+                    FunctionCallError::RespondToModel(format!(
+                        "failed to list files in {}: {err}",
+                        root.display()
+                    ))
+                })?;
+
+        if needs_blame && root.join(".git").exists() {
+            attach_blame(&root, &mut items).await;
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let author = args.author.map(|author| author.to_lowercase());
+        items.retain(|item| {
+            let matches_author = author.as_ref().is_none_or(|author| {
+                item.owner
+                    .as_ref()
+                    .is_some_and(|owner| owner.to_lowercase().contains(author))
+                    || item.blame.as_ref().is_some_and(|blame| {
+                        blame.author.to_lowercase().contains(author)
+                            || blame.author_mail.to_lowercase().contains(author)
+                    })
+            });
+            let old_enough = args.min_age_days.is_none_or(|min_age| {
+                item.blame
+                    .as_ref()
+                    .is_some_and(|blame| age_days(blame, now) >= min_age)
+            });
+            matches_author && old_enough
+        });
+
+        Ok(ToolOutput::Function {
+            content: format_items(&items, limit, args.blame, now),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+/// Matches `TODO`, `FIXME(alice):`, `HACK - text`, ... for the given tags.
+/// Tags are matched case-sensitively as whole words, so identifiers such as
+/// `todo_list` or `TODOS` do not match.
+fn tag_regex(tags: &[String]) -> Result<Regex, FunctionCallError> {
+    if let Some(tag) = tags
+        .iter()
+        .find(|tag| tag.is_empty() || !tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+    {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "invalid tag `{tag}`: tags must be words such as TODO or FIXME"
+        )));
+    }
+    Regex::new(&format!(
+        r"\b({})\b(?:\(([^)]*)\))?[:\-\s]*(.*)",
+        tags.join("|")
+    ))
+    .map_err(|err| FunctionCallError::RespondToModel(format!("invalid tags: {err}")))
+}
+
+/// Tagged comments in the files under `prefix`, in path and line order.
+fn scan_files(root: &Path, prefix: Option<&str>, tag_re: &Regex) -> std::io::Result<Vec<TodoItem>> {
+    let (files, _) = list_files(root)?;
+    let mut items = Vec::new();
+    for rel in files {
+        if !is_under(&rel, prefix) {
+            continue;
+        }
+        let path = root.join(&rel);
+        if file_stamp(&path).is_none_or(|(size, _)| size > MAX_FILE_BYTES) {
+            continue;
+        }
+        let Some(contents) = read_text(&path) else {
+            continue;
+        };
+        items.extend(scan_contents(&rel, &contents, tag_re));
+    }
+    Ok(items)
+}
+
+fn scan_contents(path: &str, contents: &str, tag_re: &Regex) -> Vec<TodoItem> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let caps = tag_re.captures(line)?;
+            let tag = caps.get(1)?.as_str();
+            let owner = caps
+                .get(2)
+                .map(|owner| owner.as_str().trim().to_string())
+                .filter(|owner| !owner.is_empty());
+            Some(TodoItem {
+                path: path.to_string(),
+                line: idx + 1,
+                tag: tag.to_string(),
+                owner,
+                text: clean_text(caps.get(3).map_or("", |text| text.as_str())),
+                blame: None,
+            })
+        })
+        .collect()
+}
+
+/// Drops trailing comment closers and caps the length.
+fn clean_text(text: &str) -> String {
+    let text = text
+        .trim()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim_end_matches("#}")
+        .trim();
+    let mut cleaned: String = text.chars().take(MAX_TEXT_CHARS).collect();
+    if cleaned.len() < text.len() {
+        cleaned.push('…');
+    }
+    cleaned
+}
+
+/// Blames each file that has items once and attaches the line's commit.
+/// Files that fail to blame (untracked, timed out) keep `blame: None`.
+async fn attach_blame(root: &Path, items: &mut [TodoItem]) {
+    let mut by_path: HashMap<String, Vec<usize>> = HashMap::new();
+    for (idx, item) in items.iter().enumerate() {
+        by_path.entry(item.path.clone()).or_default().push(idx);
+    }
+    for (path, indices) in by_path {
+        let Some(blame) = blame_file(root, &root.join(&path)).await else {
+            continue;
+        };
+        for idx in indices {
+            let item = &mut items[idx];
+            item.blame = blame.get(&item.line).cloned();
+        }
+    }
+}
+
+async fn blame_file(root: &Path, path: &Path) -> Option<HashMap<usize, BlameInfo>> {
+    let output = timeout(
+        BLAME_TIMEOUT,
+        Command::new("git")
+            .current_dir(root)
+            .args(["blame", "--porcelain", "--"])
+            .arg(path)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_blame_porcelain(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Commit info per final line number from `git blame --porcelain`, skipping
+/// uncommitted lines. Author fields are only printed the first time a commit
+/// appears, so they are remembered per commit.
+fn parse_blame_porcelain(porcelain: &str) -> HashMap<usize, BlameInfo> {
+    let mut commits: HashMap<String, BlameInfo> = HashMap::new();
+    let mut lines: HashMap<usize, String> = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in porcelain.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        let mut fields = line.split(' ');
+        let first = fields.next().unwrap_or_default();
+        if first.len() == 40 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+            let final_line = fields.nth(1).and_then(|field| field.parse::<usize>().ok());
+            if let Some(final_line) = final_line {
+                current = Some(first.to_string());
+                lines.insert(final_line, first.to_string());
+                commits
+                    .entry(first.to_string())
+                    .or_insert_with(|| BlameInfo {
+                        commit: first.to_string(),
+                        author: String::new(),
+                        author_mail: String::new(),
+                        author_time: 0,
+                    });
+            }
+            continue;
+        }
+        let Some(info) = current.as_ref().and_then(|sha| commits.get_mut(sha)) else {
+            continue;
+        };
+        if let Some(author) = line.strip_prefix("author ") {
+            info.author = author.to_string();
+        } else if let Some(mail) = line.strip_prefix("author-mail ") {
+            info.author_mail = mail.trim_matches(|c| c == '<' || c == '>').to_string();
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            info.author_time = time.parse().unwrap_or_default();
+        }
+    }
+    lines
+        .into_iter()
+        .filter(|(_, sha)| sha.chars().any(|c| c != '0'))
+        .filter_map(|(line, sha)| commits.get(&sha).map(|info| (line, info.clone())))
+        .collect()
+}
+
+fn age_days(blame: &BlameInfo, now: i64) -> u64 {
+    u64::try_from((now - blame.author_time) / SECONDS_PER_DAY).unwrap_or_default()
+}
+
+fn format_items(items: &[TodoItem], limit: usize, show_blame: bool, now: i64) -> String {
+    if items.is_empty() {
+        return "No matching TODO items found.".to_string();
+    }
+    let mut out = if items.len() > limit {
+        format!("{} item(s); showing the first {limit}:", items.len())
+    } else {
+        format!("{} item(s):", items.len())
+    };
+    for item in items.iter().take(limit) {
+        out.push_str(&format!(
+            "\n{}:{} {} {}",
+            item.path, item.line, item.tag, item.text
+        ));
+        let mut hints = Vec::new();
+        if let Some(owner) = &item.owner {
+            hints.push(format!("owner {owner}"));
+        }
+        if show_blame {
+            match &item.blame {
+                Some(blame) => {
+                    let short_sha = blame.commit.get(..10).unwrap_or(&blame.commit);
+                    hints.push(format!(
+                        "{} <{}>, {} days ago, {short_sha}",
+                        blame.author,
+                        blame.author_mail,
+                        age_days(blame, now)
+                    ));
+                }
+                None => hints.push("not committed".to_string()),
+            }
+        }
+        if !hints.is_empty() {
+            out.push_str(&format!(" ({})", hints.join("; ")));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn default_tag_regex() -> Regex {
+        let tags: Vec<String> = DEFAULT_TAGS.iter().map(ToString::to_string).collect();
+        tag_regex(&tags).expect("valid tags")
+    }
+
+    #[test]
+    fn scan_contents_extracts_tags_owners_and_text() {
+        let contents = "// TODO(alice): drop the retry loop\nlet todo_list = 1;\n/* FIXME handle EOF */\n# NOTE: not scanned by default\n<!-- HACK - inline styles -->\n";
+        let items: Vec<(usize, String, Option<String>, String)> =
+            scan_contents("src/lib.rs", contents, &default_tag_regex())
+                .into_iter()
+                .map(|item| (item.line, item.tag, item.owner, item.text))
+                .collect();
+        assert_eq!(
+            items,
+            vec![
+                (
+                    1,
+                    "TODO".to_string(),
+                    Some("alice".to_string()),
+                    "drop the retry loop".to_string()
+                ),
+                (3, "FIXME".to_string(), None, "handle EOF".to_string()),
+                (5, "HACK".to_string(), None, "inline styles".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tag_regex_rejects_non_words() {
+        assert!(tag_regex(&["TODO".to_string(), "OPTIMIZE".to_string()]).is_ok());
+        assert!(tag_regex(&["TO.*DO".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parse_blame_porcelain_reuses_commit_headers() {
+        let sha = "a".repeat(40);
+        let uncommitted = "0".repeat(40);
+        let porcelain = format!(
+            "{sha} 1 1 2\nauthor Alice\nauthor-mail <alice@example.com>\nauthor-time 1700000000\nsummary first\nfilename src/lib.rs\n\t// TODO one\n{sha} 2 2\n\t// TODO two\n{uncommitted} 3 3 1\nauthor Not Committed Yet\n\t// TODO three\n"
+        );
+        let blame = parse_blame_porcelain(&porcelain);
+
+        let expected = BlameInfo {
+            commit: sha.clone(),
+            author: "Alice".to_string(),
+            author_mail: "alice@example.com".to_string(),
+            author_time: 1_700_000_000,
+        };
+        assert_eq!(blame.get(&1), Some(&expected));
+        assert_eq!(blame.get(&2), Some(&expected));
+        assert_eq!(blame.get(&3), None);
+        assert_eq!(age_days(&expected, 1_700_000_000 + 3 * SECONDS_PER_DAY), 3);
+    }
+
+    #[test]
+    fn format_items_reports_hints_and_truncation() {
+        let item = TodoItem {
+            path: "src/lib.rs".to_string(),
+            line: 4,
+            tag: "TODO".to_string(),
+            owner: Some("bob".to_string()),
+            text: "split this".to_string(),
+            blame: None,
+        };
+        assert_eq!(
+            format_items(&[item.clone(), item], 1, true, 0),
+            "2 item(s); showing the first 1:\nsrc/lib.rs:4 TODO split this (owner bob; not committed)"
+        );
+        assert_eq!(
+            format_items(&[], 10, true, 0),
+            "No matching TODO items found."
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::semantic_index::SearchHit;
+use crate::semantic_index::index_root;
+use crate::semantic_index::search;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct SemanticSearchHandler;
+
+const DEFAULT_LIMIT: usize = 8;
+const MAX_LIMIT: usize = 30;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct SemanticSearchArgs {
+    query: String,
+    #[serde(default = "default_limit")]
+    limit: usize,
+    #[serde(default)]
+    path: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for SemanticSearchHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "semantic_search handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: SemanticSearchArgs = parse_arguments(&arguments)?;
+        let query = args.query.trim().to_string();
+        if query.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "query must not be empty".to_string(),
+            ));
+        }
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+        let limit = args.limit.min(MAX_LIMIT);
+
+        let root = index_root(&turn.cwd);
+        let search_root = root.clone();
+        let path = args.path;
+        let index_config = turn.client.config().semantic_index.clone();
+        // Indexing reads and embeds every changed file, so keep it off the
+        // async runtime.
+        let hits = tokio::task::spawn_blocking(move || {
+            search(&search_root, &query, limit, path.as_deref(), &index_config)
+        })
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(format!("semantic_search failed: {err}")))?
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to update the index in {}: {err}",
+                root.display()
+            ))
+        })?;
+
+        Ok(ToolOutput::Function {
+            content: format_hits(&hits),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn format_hits(hits: &[SearchHit]) -> String {
+    if hits.is_empty() {
+        return "No matching code found.".to_string();
+    }
+    hits.iter()
+        .map(|hit| {
+            format!(
+                "{}:{}-{} (score {:.2})\n{}",
+                hit.path, hit.start_line, hit.end_line, hit.score, hit.snippet
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn format_hits_lists_locations_and_snippets() {
+        assert_eq!(format_hits(&[]), "No matching code found.");
+        assert_eq!(
+            format_hits(&[SearchHit {
+                path: "src/config.rs".to_string(),
+                start_line: 3,
+                end_line: 5,
+                score: 0.4567,
+                snippet: "fn parse_config() {}".to_string(),
+            }]),
+            "src/config.rs:3-5 (score 0.46)\nfn parse_config() {}"
+        );
+    }
+}
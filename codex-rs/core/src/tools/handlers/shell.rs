@@ -7,6 +7,7 @@ use crate::codex::TurnContext;
 use crate::exec::ExecParams;
 use crate::exec_env::create_env;
 use crate::function_tool::FunctionCallError;
+use crate::guardrail::apply_guardrail;
 use crate::is_safe_command::is_known_safe_command;
 use crate::protocol::ExecCommandSource;
 use crate::shell::Shell;
@@ -254,8 +255,17 @@ impl ShellHandler {
                 turn.approval_policy,
                 &turn.sandbox_policy,
                 exec_params.sandbox_permissions,
+                &exec_params.cwd,
             )
             .await;
+        let exec_approval_requirement = apply_guardrail(
+            session.as_ref(),
+            turn.as_ref(),
+            &exec_params.command,
+            &exec_params.cwd,
+            exec_approval_requirement,
+        )
+        .await;
 
         let req = ShellRequest {
             command: exec_params.command.clone(),
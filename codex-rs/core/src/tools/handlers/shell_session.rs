@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::unified_exec::DEFAULT_SHELL_SESSION_TIMEOUT_MS;
+use crate::unified_exec::ShellSessionOutput;
+use crate::unified_exec::UnifiedExecContext;
+
+pub struct ShellSessionHandler;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ShellSessionAction {
+    Open,
+    Run,
+    Close,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShellSessionArgs {
+    action: ShellSessionAction,
+    #[serde(default)]
+    session_id: Option<i32>,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    workdir: Option<String>,
+    #[serde(default = "default_timeout_ms")]
+    timeout_ms: u64,
+    #[serde(default)]
+    max_output_tokens: Option<usize>,
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_SHELL_SESSION_TIMEOUT_MS
+}
+
+#[async_trait]
+impl ToolHandler for ShellSessionHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "shell_session handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: ShellSessionArgs = parse_arguments(&arguments)?;
+        let manager = &session.services.unified_exec_manager;
+        let context = UnifiedExecContext::new(session.clone(), turn.clone(), call_id);
+        let session_id = || {
+            args.session_id.map(|id| id.to_string()).ok_or_else(|| {
+                FunctionCallError::RespondToModel(
+                    "session_id is required for run and close".to_string(),
+                )
+            })
+        };
+
+        let content = match args.action {
+            ShellSessionAction::Open => {
+                let workdir = args
+                    .workdir
+                    .clone()
+                    .filter(|dir| !dir.is_empty())
+                    .map(|dir| turn.resolve_path(Some(dir)));
+                let session_id = manager
+                    .open_shell_session(workdir, &context)
+                    .await
+                    .map_err(|err| {
+                        FunctionCallError::RespondToModel(format!(
+                            "failed to open shell session: {err}"
+                        ))
+                    })?;
+                format!(
+                    "Shell session {session_id} opened. Run commands in it with action \"run\" and close it when done."
+                )
+            }
+            ShellSessionAction::Run => {
+                let session_id = session_id()?;
+                let output = manager
+                    .run_in_shell_session(
+                        &session_id,
+                        args.command.as_deref().unwrap_or_default(),
+                        args.timeout_ms,
+                        args.max_output_tokens,
+                        &context,
+                    )
+                    .await
+                    .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+                format_output(&output)
+            }
+            ShellSessionAction::Close => {
+                let session_id = session_id()?;
+                manager
+                    .close_shell_session(&session_id)
+                    .await
+                    .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+                format!("Shell session {session_id} closed.")
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn format_output(output: &ShellSessionOutput) -> String {
+    let mut sections = Vec::new();
+    let wall_time_seconds = output.wall_time.as_secs_f64();
+    sections.push(format!("Wall time: {wall_time_seconds:.4} seconds"));
+    match output.exit_code {
+        Some(exit_code) => sections.push(format!("Exit code: {exit_code}")),
+        None => sections.push(
+            "Command still running; run with an empty command to keep waiting, or close the session to stop it."
+                .to_string(),
+        ),
+    }
+    if output.session_closed {
+        sections.push(format!(
+            "The shell exited; session {} is closed.",
+            output.session_id
+        ));
+    } else {
+        sections.push(format!("Working directory: {}", output.cwd.display()));
+    }
+    sections.push("Output:".to_string());
+    sections.push(output.output.clone());
+    sections.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    #[test]
+    fn running_commands_report_that_they_can_be_awaited() {
+        let output = ShellSessionOutput {
+            session_id: "1000".to_string(),
+            output: "building".to_string(),
+            exit_code: None,
+            cwd: PathBuf::from("/repo"),
+            wall_time: Duration::from_secs(10),
+            session_closed: false,
+        };
+        assert_eq!(
+            format_output(&output),
+            "Wall time: 10.0000 seconds\n\
+             Command still running; run with an empty command to keep waiting, or close the session to stop it.\n\
+             Working directory: /repo\n\
+             Output:\n\
+             building"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+
+use crate::features::Feature;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::ReviewDecision;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use codex_protocol::plan_tool::ProposedPlan;
+
+pub struct SubmitPlanHandler;
+
+#[async_trait]
+impl ToolHandler for SubmitPlanHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "submit_plan handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let plan: ProposedPlan = parse_arguments(&arguments)?;
+        if plan.steps.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "submit_plan requires at least one step".to_string(),
+            ));
+        }
+        if session.plan_approved().await {
+            return Ok(ToolOutput::Function {
+                content: "A plan is already approved for this turn; continue with it.".to_string(),
+                content_items: None,
+                success: Some(true),
+            });
+        }
+
+        let decision = session
+            .request_plan_approval(turn.as_ref(), call_id, plan)
+            .await;
+        let (content, success) = match decision {
+            ReviewDecision::Approved | ReviewDecision::ApprovedForSession => (
+                "The user approved the plan. Commands and patches are unlocked for the rest of this turn; carry out the plan.",
+                true,
+            ),
+            ReviewDecision::Denied
+            | ReviewDecision::Abort
+            | ReviewDecision::ApprovedExecpolicyAmendment { .. } => (
+                "The user declined the plan. Do not run commands or apply patches; ask what to change or submit a revised plan.",
+                false,
+            ),
+        };
+        let content = if success && session.features().enabled(Feature::PlanScope) {
+            format!(
+                "{content} Patches may only touch the plan's affected_files; editing any other file needs the user's approval."
+            )
+        } else {
+            content.to_string()
+        };
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(success),
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
 use crate::is_safe_command::is_known_safe_command;
 use crate::protocol::EventMsg;
@@ -5,6 +6,7 @@ use crate::protocol::TerminalInteractionEvent;
 use crate::sandboxing::SandboxPermissions;
 use crate::shell::Shell;
 use crate::shell::get_shell_by_model_provided_path;
+use crate::test_failures;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -122,7 +124,7 @@ impl ToolHandler for UnifiedExecHandler {
         let manager: &UnifiedExecProcessManager = &session.services.unified_exec_manager;
         let context = UnifiedExecContext::new(session.clone(), turn.clone(), call_id.clone());
 
-        let response = match tool_name.as_str() {
+        let mut response = match tool_name.as_str() {
             "exec_command" => {
                 let args: ExecCommandArgs = parse_arguments(&arguments)?;
                 let process_id = manager.allocate_process_id().await;
@@ -223,6 +225,16 @@ impl ToolHandler for UnifiedExecHandler {
             }
         };
 
+        if response.exit_code.is_some_and(|code| code != 0)
+            && session.enabled(Feature::TestFailureDigest)
+            && let Some(digest) = test_failures::digest_output(
+                &String::from_utf8_lossy(&response.raw_output),
+                turn.truncation_policy,
+            )
+        {
+            response.output = digest;
+        }
+
         let content = format_response(&response);
 
         Ok(ToolOutput::Function {
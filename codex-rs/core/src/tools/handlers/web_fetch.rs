@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::web_fetch::FetchedPage;
+use crate::web_fetch::fetch;
+
+pub struct WebFetchHandler;
+
+#[derive(Deserialize)]
+struct WebFetchArgs {
+    url: String,
+    #[serde(default)]
+    raw: bool,
+}
+
+#[async_trait]
+impl ToolHandler for WebFetchHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "web_fetch handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: WebFetchArgs = parse_arguments(&arguments)?;
+        let config = turn.client.config();
+        let network_access = turn.sandbox_policy.has_full_network_access();
+        let page = fetch(
+            &args.url,
+            &config.web_fetch,
+            config.offline.as_ref(),
+            network_access,
+            args.raw,
+        )
+        .await
+        .map_err(FunctionCallError::RespondToModel)?;
+
+        Ok(ToolOutput::Function {
+            content: format_page(&page, config.web_fetch.max_bytes),
+            content_items: None,
+            success: Some(page.status.is_success()),
+        })
+    }
+}
+
+/// A header with the response metadata, then the content.
+fn format_page(page: &FetchedPage, max_bytes: usize) -> String {
+    let mut header = vec![
+        format!("URL: {}", page.url),
+        format!("Status: {}", page.status),
+    ];
+    if let Some(content_type) = &page.content_type {
+        header.push(format!("Content-Type: {content_type}"));
+    }
+    if let Some(title) = &page.title {
+        header.push(format!("Title: {title}"));
+    }
+    if page.truncated {
+        header.push(format!(
+            "Truncated: only the first {max_bytes} bytes were read"
+        ));
+    }
+    format!("{}\n\n{}", header.join("\n"), page.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn formats_metadata_before_content() {
+        let page = FetchedPage {
+            url: "https://example.com/docs".to_string(),
+            status: StatusCode::OK,
+            content_type: Some("text/html".to_string()),
+            title: Some("Docs".to_string()),
+            content: "# Docs\n\nHello".to_string(),
+            truncated: true,
+        };
+
+        assert_eq!(
+            format_page(&page, 1024),
+            "URL: https://example.com/docs\n\
+             Status: 200 OK\n\
+             Content-Type: text/html\n\
+             Title: Docs\n\
+             Truncated: only the first 1024 bytes were read\n\n\
+             # Docs\n\nHello"
+        );
+    }
+}
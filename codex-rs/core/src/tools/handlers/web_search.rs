@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::web_search::format_results;
+use crate::web_search::search;
+
+/// Runs `web_search` against `[web_search_provider]`.
+pub struct WebSearchHandler;
+
+#[derive(Deserialize)]
+struct WebSearchArgs {
+    query: String,
+    #[serde(default)]
+    count: Option<usize>,
+}
+
+#[async_trait]
+impl ToolHandler for WebSearchHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "web_search handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: WebSearchArgs = parse_arguments(&arguments)?;
+        let query = args.query.trim();
+        if query.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "query must not be empty".to_string(),
+            ));
+        }
+        let config = turn.client.config();
+        let Some(provider) = &config.web_search_provider else {
+            return Err(FunctionCallError::RespondToModel(
+                "web_search_provider is not configured".to_string(),
+            ));
+        };
+        let results = search(provider, config.offline.as_ref(), query, args.count)
+            .await
+            .map_err(FunctionCallError::RespondToModel)?;
+
+        Ok(ToolOutput::Function {
+            content: format_results(query, &results),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
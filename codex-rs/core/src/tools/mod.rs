@@ -8,6 +8,7 @@ pub mod router;
 pub mod runtimes;
 pub mod sandboxing;
 pub mod spec;
+pub(crate) mod trimming;
 
 use crate::exec::ExecToolCallOutput;
 use crate::truncate::TruncationPolicy;
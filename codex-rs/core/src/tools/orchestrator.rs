@@ -17,7 +17,10 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::default_exec_approval_requirement;
+use crate::tools::sandboxing::is_approved_for_session;
 use codex_otel::ToolDecisionSource;
+use codex_protocol::approvals::ApprovalJournalEntry;
+use codex_protocol::approvals::ApprovalSource;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
 
@@ -56,8 +59,9 @@ impl ToolOrchestrator {
             default_exec_approval_requirement(approval_policy, &turn_ctx.sandbox_policy)
         });
         match requirement {
-            ExecApprovalRequirement::Skip { .. } => {
+            ExecApprovalRequirement::Skip { source, .. } => {
                 otel.tool_decision(otel_tn, otel_ci, &ReviewDecision::Approved, otel_cfg);
+                record_approval(tool_ctx, tool.approval_subject(req), source, true).await;
             }
             ExecApprovalRequirement::Forbidden { reason } => {
                 return Err(ToolError::Rejected(reason));
@@ -69,9 +73,13 @@ impl ToolOrchestrator {
                     call_id: &tool_ctx.call_id,
                     retry_reason: reason,
                 };
+                let cached =
+                    is_approved_for_session(&tool_ctx.session.services, &tool.approval_keys(req))
+                        .await;
                 let decision = tool.start_approval_async(req, approval_ctx).await;
 
                 otel.tool_decision(otel_tn, otel_ci, &decision, otel_user.clone());
+                record_user_decision(tool_ctx, tool.approval_subject(req), cached, &decision).await;
 
                 match decision {
                     ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -101,6 +109,7 @@ impl ToolOrchestrator {
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
             codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
+            exec_limits: turn_ctx.exec_limits,
         };
 
         match tool.run(req, &initial_attempt, tool_ctx).await {
@@ -132,8 +141,15 @@ impl ToolOrchestrator {
                         retry_reason: Some(reason_msg),
                     };
 
+                    let cached = is_approved_for_session(
+                        &tool_ctx.session.services,
+                        &tool.approval_keys(req),
+                    )
+                    .await;
                     let decision = tool.start_approval_async(req, approval_ctx).await;
                     otel.tool_decision(otel_tn, otel_ci, &decision, otel_user);
+                    record_user_decision(tool_ctx, tool.approval_subject(req), cached, &decision)
+                        .await;
 
                     match decision {
                         ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -151,6 +167,7 @@ impl ToolOrchestrator {
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
                     codex_linux_sandbox_exe: None,
+                    exec_limits: turn_ctx.exec_limits,
                 };
 
                 // Second attempt.
@@ -161,6 +178,52 @@ impl ToolOrchestrator {
     }
 }
 
+/// Journals the outcome of an approval prompt, attributing it to the session
+/// allowlist when the prompt was satisfied from the approval cache.
+async fn record_user_decision(
+    tool_ctx: &ToolCtx<'_>,
+    subject: String,
+    cached: bool,
+    decision: &ReviewDecision,
+) {
+    let source = if cached {
+        ApprovalSource::SessionAllowlist
+    } else {
+        ApprovalSource::User {
+            decision: decision.clone(),
+        }
+    };
+    let approved = !matches!(decision, ReviewDecision::Denied | ReviewDecision::Abort);
+    record_approval(tool_ctx, subject, source, approved).await;
+}
+
+async fn record_approval(
+    tool_ctx: &ToolCtx<'_>,
+    subject: String,
+    source: ApprovalSource,
+    approved: bool,
+) {
+    let entry = ApprovalJournalEntry {
+        call_id: tool_ctx.call_id.clone(),
+        turn_id: tool_ctx.turn.sub_id.clone(),
+        tool_name: tool_ctx.tool_name.clone(),
+        subject,
+        source,
+        approved,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Some(audit_log) = tool_ctx.session.services.audit_log.as_ref() {
+        audit_log.record_approval(&entry);
+    }
+    tool_ctx
+        .session
+        .services
+        .approval_journal
+        .lock()
+        .await
+        .record(entry);
+}
+
 fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
     // Keep approval reason terse and stable for UX/tests, but accept the
     // output so we can evolve heuristics later without touching call sites.
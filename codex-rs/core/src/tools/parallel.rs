@@ -12,6 +12,7 @@ use tracing::trace_span;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::error::CodexErr;
+use crate::external_edits::ExternalEdits;
 use crate::function_tool::FunctionCallError;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolPayload;
@@ -70,6 +71,12 @@ impl ToolCallRuntime {
 
         let handle: AbortOnDropHandle<Result<ResponseInputItem, FunctionCallError>> =
             AbortOnDropHandle::new(tokio::spawn(async move {
+                // File changes made while the call runs are the agent's own.
+                let _activity = session
+                    .services
+                    .external_edits
+                    .as_ref()
+                    .map(ExternalEdits::agent_activity);
                 tokio::select! {
                     _ = cancellation_token.cancelled() => {
                         let secs = started.elapsed().as_secs_f32().max(0.1);
@@ -4,6 +4,7 @@ use std::time::Duration;
 
 use crate::client_common::tools::ToolSpec;
 use crate::function_tool::FunctionCallError;
+use crate::plan_mode;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -66,11 +67,24 @@ impl ToolRegistry {
 
     pub async fn dispatch(
         &self,
-        invocation: ToolInvocation,
+        mut invocation: ToolInvocation,
     ) -> Result<ResponseInputItem, FunctionCallError> {
         let tool_name = invocation.tool_name.clone();
         let call_id_owned = invocation.call_id.clone();
         let otel = invocation.turn.client.get_otel_manager();
+        let session = Arc::clone(&invocation.session);
+        let turn = Arc::clone(&invocation.turn);
+        session
+            .services
+            .hooks
+            .before_tool_call(
+                &session,
+                &turn,
+                &call_id_owned,
+                &tool_name,
+                &mut invocation.payload,
+            )
+            .await?;
         let payload_for_response = invocation.payload.clone();
         let log_payload = payload_for_response.log_payload();
 
@@ -104,6 +118,19 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
+        if locked_by_plan_mode(handler.as_ref(), &invocation).await {
+            let message = plan_mode::locked_tool_message(tool_name.as_ref());
+            otel.tool_result(
+                tool_name.as_ref(),
+                &call_id_owned,
+                log_payload.as_ref(),
+                Duration::ZERO,
+                false,
+                &message,
+            );
+            return Err(FunctionCallError::RespondToModel(message));
+        }
+
         let output_cell = tokio::sync::Mutex::new(None);
 
         let result = otel
@@ -135,6 +162,13 @@ impl ToolRegistry {
                 },
             )
             .await;
+        session.services.hooks.after_tool_call(
+            &session,
+            &turn,
+            &call_id_owned,
+            &tool_name,
+            matches!(result, Ok((_, true))),
+        );
 
         match result {
             Ok(_) => {
@@ -149,6 +183,14 @@ impl ToolRegistry {
     }
 }
 
+/// Whether plan mode refuses `invocation`: the tool might change the
+/// workspace and no plan has been approved in this turn yet.
+async fn locked_by_plan_mode(handler: &dyn ToolHandler, invocation: &ToolInvocation) -> bool {
+    invocation.turn.tools_config.plan_mode
+        && handler.is_mutating(invocation).await
+        && !invocation.session.plan_approved().await
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfiguredToolSpec {
     pub spec: ToolSpec,
@@ -190,6 +232,10 @@ impl ToolRegistryBuilder {
             .push(ConfiguredToolSpec::new(spec, supports_parallel_tool_calls));
     }
 
+    pub fn has_handler(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
     pub fn register_handler(&mut self, name: impl Into<String>, handler: Arc<dyn ToolHandler>) {
         let name = name.into();
         if self
@@ -231,3 +277,152 @@ fn unsupported_tool_call_message(payload: &ToolPayload, tool_name: &str) -> Stri
         _ => format!("unsupported call: {tool_name}"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codex::make_session_and_context;
+    use crate::plugin_tools::PluginRunner;
+    use crate::plugin_tools::PluginTool;
+    use crate::tools::handlers::CollabHandler;
+    use crate::tools::handlers::McpHandler;
+    use crate::tools::handlers::MemoryHandler;
+    use crate::tools::handlers::PluginToolHandler;
+    use crate::tools::handlers::ShellCommandHandler;
+    use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::ShellSessionHandler;
+    use crate::tools::handlers::UnifiedExecHandler;
+    use crate::turn_diff_tracker::TurnDiffTracker;
+    use codex_protocol::config_types::SandboxMode;
+    use serde_json::json;
+    use std::path::PathBuf;
+    use tokio::sync::Mutex;
+
+    /// Whether `handler` refuses `payload` in plan mode before any plan is
+    /// approved.
+    async fn locked(handler: &dyn ToolHandler, tool_name: &str, payload: ToolPayload) -> bool {
+        let (session, mut turn) = make_session_and_context().await;
+        turn.tools_config.plan_mode = true;
+        let invocation = ToolInvocation {
+            session: Arc::new(session),
+            turn: Arc::new(turn),
+            tracker: Arc::new(Mutex::new(TurnDiffTracker::default())),
+            call_id: "call-1".to_string(),
+            tool_name: tool_name.to_string(),
+            payload,
+        };
+        locked_by_plan_mode(handler, &invocation).await
+    }
+
+    fn function(arguments: serde_json::Value) -> ToolPayload {
+        ToolPayload::Function {
+            arguments: arguments.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn memory_edits_are_locked_in_plan_mode() {
+        for arguments in [
+            json!({"action": "add", "text": "use pnpm"}),
+            json!({"action": "update", "id": "m1", "text": "use npm"}),
+            json!({"action": "delete", "id": "m1"}),
+        ] {
+            assert!(
+                locked(&MemoryHandler, "memory", function(arguments.clone())).await,
+                "{arguments}"
+            );
+        }
+        assert!(
+            !locked(
+                &MemoryHandler,
+                "memory",
+                function(json!({"action": "list"}))
+            )
+            .await
+        );
+    }
+
+    #[tokio::test]
+    async fn shell_commands_are_locked_unless_known_safe() {
+        let shell = |command: serde_json::Value| function(json!({"command": command}));
+        assert!(!locked(&ShellHandler, "shell", shell(json!(["ls", "-la"]))).await);
+        assert!(
+            locked(
+                &ShellHandler,
+                "shell",
+                shell(json!(["touch", "planned.txt"]))
+            )
+            .await
+        );
+        assert!(
+            !locked(
+                &ShellCommandHandler,
+                "shell_command",
+                shell(json!("git status"))
+            )
+            .await
+        );
+        assert!(
+            locked(
+                &ShellCommandHandler,
+                "shell_command",
+                shell(json!("touch planned.txt && echo planned"))
+            )
+            .await
+        );
+        let exec = function(json!({"cmd": "ls"}));
+        assert!(!locked(&UnifiedExecHandler, "exec_command", exec).await);
+    }
+
+    #[tokio::test]
+    async fn terminal_input_and_shell_sessions_are_locked() {
+        let input = function(json!({"session_id": 1, "chars": "rm -rf build\n"}));
+        assert!(locked(&UnifiedExecHandler, "write_stdin", input).await);
+        let session = function(json!({"action": "run", "session_id": 1, "command": "ls"}));
+        assert!(locked(&ShellSessionHandler, "shell_session", session).await);
+    }
+
+    #[tokio::test]
+    async fn mcp_tools_without_read_only_hint_are_locked() {
+        // No connected server reports the tool as read-only. The case where
+        // one does is covered by the plan mode integration tests.
+        let payload = ToolPayload::Mcp {
+            server: "docs".to_string(),
+            tool: "search".to_string(),
+            raw_arguments: "{}".to_string(),
+        };
+        assert!(locked(&McpHandler, "mcp__docs__search", payload).await);
+    }
+
+    #[tokio::test]
+    async fn only_read_only_plugins_stay_available() {
+        let plugin = |sandbox_mode| {
+            PluginToolHandler::new(Arc::new(PluginTool {
+                name: "check".to_string(),
+                description: "Check things".to_string(),
+                runner: PluginRunner::Command(vec!["./check.sh".to_string()]),
+                input_schema: json!({"type": "object"}),
+                output_schema: None,
+                timeout: Duration::from_secs(1),
+                sandbox_mode,
+                network_access: false,
+                path: PathBuf::from("/repo/.codex/plugins/check.toml"),
+            }))
+        };
+        let write = plugin(SandboxMode::WorkspaceWrite);
+        assert!(locked(&write, "check", function(json!({}))).await);
+        let read = plugin(SandboxMode::ReadOnly);
+        assert!(!locked(&read, "check", function(json!({}))).await);
+    }
+
+    #[tokio::test]
+    async fn spawning_and_instructing_sub_agents_is_locked() {
+        let message = || function(json!({"id": "agent-1", "message": "edit src/lib.rs"}));
+        assert!(locked(&CollabHandler, "spawn_agent", message()).await);
+        assert!(locked(&CollabHandler, "send_input", message()).await);
+        let wait = function(json!({"ids": ["agent-1"]}));
+        assert!(!locked(&CollabHandler, "wait", wait).await);
+        let close = function(json!({"id": "agent-1"}));
+        assert!(!locked(&CollabHandler, "close_agent", close).await);
+    }
+}
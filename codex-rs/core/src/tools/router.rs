@@ -14,6 +14,7 @@ use codex_protocol::models::LocalShellAction;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::ToolTrimmingStats;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::instrument;
@@ -28,6 +29,7 @@ pub struct ToolCall {
 pub struct ToolRouter {
     registry: ToolRegistry,
     specs: Vec<ConfiguredToolSpec>,
+    tool_trimming: Option<ToolTrimmingStats>,
 }
 
 impl ToolRouter {
@@ -38,7 +40,11 @@ impl ToolRouter {
         let builder = build_specs(config, mcp_tools);
         let (specs, registry) = builder.build();
 
-        Self { registry, specs }
+        Self {
+            registry,
+            specs,
+            tool_trimming: None,
+        }
     }
 
     pub fn specs(&self) -> Vec<ToolSpec> {
@@ -48,6 +54,24 @@ impl ToolRouter {
             .collect()
     }
 
+    /// Replace the spec sent for `name`, returning the previous one. The
+    /// handler stays registered, so the tool can still be called.
+    pub(crate) fn replace_spec(&mut self, name: &str, spec: ToolSpec) -> Option<ToolSpec> {
+        self.specs
+            .iter_mut()
+            .find(|config| config.spec.name() == name)
+            .map(|config| std::mem::replace(&mut config.spec, spec))
+    }
+
+    /// What `tool_trimming` left out of this router's specs, if it ran.
+    pub(crate) fn tool_trimming(&self) -> Option<ToolTrimmingStats> {
+        self.tool_trimming
+    }
+
+    pub(crate) fn set_tool_trimming(&mut self, stats: ToolTrimmingStats) {
+        self.tool_trimming = Some(stats);
+    }
+
     pub fn tool_supports_parallel(&self, tool_name: &str) -> bool {
         self.specs
             .iter()
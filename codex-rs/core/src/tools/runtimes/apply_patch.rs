@@ -6,6 +6,7 @@
 //! `SandboxAttempt` with a minimal environment.
 use crate::CODEX_APPLY_PATCH_ARG1;
 use crate::exec::ExecToolCallOutput;
+use crate::patch_preview::PatchBaseline;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxPermissions;
 use crate::sandboxing::execute_env;
@@ -22,6 +23,7 @@ use crate::tools::sandboxing::with_cached_approval;
 use codex_apply_patch::ApplyPatchAction;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::FileChange;
+use codex_protocol::protocol::PatchPreview;
 use codex_protocol::protocol::ReviewDecision;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use futures::future::BoxFuture;
@@ -36,6 +38,10 @@ pub struct ApplyPatchRequest {
     pub exec_approval_requirement: ExecApprovalRequirement,
     pub timeout_ms: Option<u64>,
     pub codex_exe: Option<PathBuf>,
+    /// Dry run shown in the approval dialog (`patch_preview` feature).
+    pub preview: Option<PatchPreview>,
+    /// Files as of `preview`; the patch is not applied if they changed since.
+    pub baseline: Option<PatchBaseline>,
 }
 
 #[derive(Default)]
@@ -92,6 +98,14 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
         req.file_paths.clone()
     }
 
+    fn approval_subject(&self, req: &ApplyPatchRequest) -> String {
+        req.file_paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     fn start_approval_async<'a>(
         &'a mut self,
         req: &'a ApplyPatchRequest,
@@ -103,12 +117,19 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
         let retry_reason = ctx.retry_reason.clone();
         let approval_keys = self.approval_keys(req);
         let changes = req.changes.clone();
+        let preview = req.preview.clone();
         Box::pin(async move {
             if let Some(reason) = retry_reason {
-                let rx_approve = session
-                    .request_patch_approval(turn, call_id, changes.clone(), Some(reason), None)
+                return session
+                    .request_patch_approval(
+                        turn,
+                        call_id,
+                        changes.clone(),
+                        Some(reason),
+                        None,
+                        preview,
+                    )
                     .await;
-                return rx_approve.await.unwrap_or_default();
             }
 
             with_cached_approval(
@@ -116,10 +137,9 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
                 "apply_patch",
                 approval_keys,
                 || async move {
-                    let rx_approve = session
-                        .request_patch_approval(turn, call_id, changes, None, None)
-                        .await;
-                    rx_approve.await.unwrap_or_default()
+                    session
+                        .request_patch_approval(turn, call_id, changes, None, None, preview)
+                        .await
                 },
             )
             .await
@@ -149,8 +169,22 @@ impl ToolRuntime<ApplyPatchRequest, ExecToolCallOutput> for ApplyPatchRuntime {
         attempt: &SandboxAttempt<'_>,
         ctx: &ToolCtx<'_>,
     ) -> Result<ExecToolCallOutput, ToolError> {
+        if let Some(baseline) = &req.baseline {
+            let changed = baseline.changed_paths();
+            if !changed.is_empty() {
+                let paths = changed
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(ToolError::Rejected(format!(
+                    "apply_patch was not applied: {paths} changed since the patch was previewed; re-read the files and retry"
+                )));
+            }
+        }
         let spec = Self::build_command_spec(req)?;
         let env = attempt
+            .for_host_binary()
             .env_for(spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
         let out = execute_env(env, attempt.policy, Self::stdout_stream(ctx))
@@ -5,6 +5,8 @@ Concrete ToolRuntime implementations for specific tools. Each runtime stays
 small and focused and reuses the orchestrator for approvals + sandbox + retry.
 */
 use crate::exec::ExecExpiration;
+use crate::parse_command::extract_shell_command;
+use crate::parse_command::shlex_join;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxPermissions;
 use crate::shell::Shell;
@@ -13,6 +15,7 @@ use std::collections::HashMap;
 use std::path::Path;
 
 pub mod apply_patch;
+pub mod plugin;
 pub mod shell;
 pub mod unified_exec;
 
@@ -40,6 +43,15 @@ pub(crate) fn build_command_spec(
     })
 }
 
+/// Renders a command for the approval journal, unwrapping `bash -lc <script>`
+/// style invocations to the script itself.
+pub(crate) fn command_approval_subject(command: &[String]) -> String {
+    match extract_shell_command(command) {
+        Some((_, script)) => script.to_string(),
+        None => shlex_join(command),
+    }
+}
+
 /// POSIX-only helper: for commands produced by `Shell::derive_exec_args`
 /// for Bash/Zsh/sh of the form `[shell_path, "-lc", "<script>"]`, and
 /// when a snapshot is configured on the session shell, rewrite the argv
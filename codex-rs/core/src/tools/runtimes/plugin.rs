@@ -0,0 +1,118 @@
+/*
+Runtime: plugin
+
+Runs plugin tools under the orchestrator so the exec policy and the session's
+approval policy apply to them like to any other command. The plugin always
+runs in the sandbox its manifest asks for, so a failed call is never retried
+without one.
+*/
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::plugin_tools::PluginTool;
+use crate::tools::sandboxing::Approvable;
+use crate::tools::sandboxing::ApprovalCtx;
+use crate::tools::sandboxing::ExecApprovalRequirement;
+use crate::tools::sandboxing::SandboxAttempt;
+use crate::tools::sandboxing::Sandboxable;
+use crate::tools::sandboxing::SandboxablePreference;
+use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
+use crate::tools::sandboxing::ToolRuntime;
+use crate::tools::sandboxing::with_cached_approval;
+use codex_protocol::protocol::ReviewDecision;
+use futures::future::BoxFuture;
+
+#[derive(Debug)]
+pub struct PluginRequest {
+    pub plugin: Arc<PluginTool>,
+    /// JSON object passed to the plugin on stdin.
+    pub arguments: String,
+    pub cwd: PathBuf,
+    pub exec_approval_requirement: ExecApprovalRequirement,
+}
+
+#[derive(Default)]
+pub struct PluginRuntime;
+
+/// Approving a plugin for the session covers every later call to it.
+#[derive(serde::Serialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct ApprovalKey {
+    plugin: String,
+    manifest: PathBuf,
+}
+
+impl PluginRuntime {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Sandboxable for PluginRuntime {
+    fn sandbox_preference(&self) -> SandboxablePreference {
+        SandboxablePreference::Auto
+    }
+    fn escalate_on_failure(&self) -> bool {
+        false
+    }
+}
+
+impl Approvable<PluginRequest> for PluginRuntime {
+    type ApprovalKey = ApprovalKey;
+
+    fn approval_keys(&self, req: &PluginRequest) -> Vec<Self::ApprovalKey> {
+        vec![ApprovalKey {
+            plugin: req.plugin.name.clone(),
+            manifest: req.plugin.path.clone(),
+        }]
+    }
+
+    fn approval_subject(&self, req: &PluginRequest) -> String {
+        format!("plugin {} {}", req.plugin.name, req.arguments)
+    }
+
+    fn start_approval_async<'a>(
+        &'a mut self,
+        req: &'a PluginRequest,
+        ctx: ApprovalCtx<'a>,
+    ) -> BoxFuture<'a, ReviewDecision> {
+        let keys = self.approval_keys(req);
+        let command = req.plugin.approval_command();
+        let cwd = req.cwd.clone();
+        let reason = ctx
+            .retry_reason
+            .clone()
+            .unwrap_or_else(|| format!("plugin `{}` wants to run", req.plugin.name));
+        let reason = Some(format!("{reason}; arguments: {}", req.arguments));
+        let session = ctx.session;
+        let turn = ctx.turn;
+        let call_id = ctx.call_id.to_string();
+        Box::pin(async move {
+            with_cached_approval(&session.services, "plugin", keys, move || async move {
+                session
+                    .request_command_approval(turn, call_id, command, cwd, reason, None)
+                    .await
+            })
+            .await
+        })
+    }
+
+    fn exec_approval_requirement(&self, req: &PluginRequest) -> Option<ExecApprovalRequirement> {
+        Some(req.exec_approval_requirement.clone())
+    }
+}
+
+impl ToolRuntime<PluginRequest, String> for PluginRuntime {
+    async fn run(
+        &mut self,
+        req: &PluginRequest,
+        _attempt: &SandboxAttempt<'_>,
+        ctx: &ToolCtx<'_>,
+    ) -> Result<String, ToolError> {
+        // The plugin's sandbox comes from its manifest, not from the attempt.
+        req.plugin
+            .run(ctx.turn, &req.arguments)
+            .await
+            .map_err(ToolError::Rejected)
+    }
+}
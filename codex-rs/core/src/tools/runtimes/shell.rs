@@ -11,6 +11,7 @@ use crate::sandboxing::SandboxPermissions;
 use crate::sandboxing::execute_env;
 use crate::shell::ShellType;
 use crate::tools::runtimes::build_command_spec;
+use crate::tools::runtimes::command_approval_subject;
 use crate::tools::runtimes::maybe_wrap_shell_lc_with_snapshot;
 use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
@@ -82,6 +83,10 @@ impl Approvable<ShellRequest> for ShellRuntime {
         }]
     }
 
+    fn approval_subject(&self, req: &ShellRequest) -> String {
+        command_approval_subject(&req.command)
+    }
+
     fn start_approval_async<'a>(
         &'a mut self,
         req: &'a ShellRequest,
@@ -121,7 +126,8 @@ impl Approvable<ShellRequest> for ShellRuntime {
     }
 
     fn sandbox_mode_for_first_attempt(&self, req: &ShellRequest) -> SandboxOverride {
-        if req.sandbox_permissions.requires_escalated_permissions()
+        if (req.sandbox_permissions.requires_escalated_permissions()
+            && !req.exec_approval_requirement.keeps_sandbox())
             || matches!(
                 req.exec_approval_requirement,
                 ExecApprovalRequirement::Skip {
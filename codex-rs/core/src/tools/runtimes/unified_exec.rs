@@ -12,6 +12,7 @@ use crate::powershell::prefix_powershell_script_with_utf8;
 use crate::sandboxing::SandboxPermissions;
 use crate::shell::ShellType;
 use crate::tools::runtimes::build_command_spec;
+use crate::tools::runtimes::command_approval_subject;
 use crate::tools::runtimes::maybe_wrap_shell_lc_with_snapshot;
 use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
@@ -38,6 +39,8 @@ pub struct UnifiedExecRequest {
     pub cwd: PathBuf,
     pub env: HashMap<String, String>,
     pub tty: bool,
+    /// Keep stdin open on pipes when not using a PTY (persistent shell sessions).
+    pub pipe_stdin: bool,
     pub sandbox_permissions: SandboxPermissions,
     pub justification: Option<String>,
     pub exec_approval_requirement: ExecApprovalRequirement,
@@ -56,11 +59,13 @@ pub struct UnifiedExecRuntime<'a> {
 }
 
 impl UnifiedExecRequest {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         command: Vec<String>,
         cwd: PathBuf,
         env: HashMap<String, String>,
         tty: bool,
+        pipe_stdin: bool,
         sandbox_permissions: SandboxPermissions,
         justification: Option<String>,
         exec_approval_requirement: ExecApprovalRequirement,
@@ -70,6 +75,7 @@ impl UnifiedExecRequest {
             cwd,
             env,
             tty,
+            pipe_stdin,
             sandbox_permissions,
             justification,
             exec_approval_requirement,
@@ -105,6 +111,10 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
         }]
     }
 
+    fn approval_subject(&self, req: &UnifiedExecRequest) -> String {
+        command_approval_subject(&req.command)
+    }
+
     fn start_approval_async<'b>(
         &'b mut self,
         req: &'b UnifiedExecRequest,
@@ -147,7 +157,8 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
     }
 
     fn sandbox_mode_for_first_attempt(&self, req: &UnifiedExecRequest) -> SandboxOverride {
-        if req.sandbox_permissions.requires_escalated_permissions()
+        if (req.sandbox_permissions.requires_escalated_permissions()
+            && !req.exec_approval_requirement.keeps_sandbox())
             || matches!(
                 req.exec_approval_requirement,
                 ExecApprovalRequirement::Skip {
@@ -194,7 +205,7 @@ impl<'a> ToolRuntime<UnifiedExecRequest, UnifiedExecProcess> for UnifiedExecRunt
             .env_for(spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
         self.manager
-            .open_session_with_exec_env(&exec_env, req.tty)
+            .open_session_with_exec_env(&exec_env, req.tty, req.pipe_stdin)
             .await
             .map_err(|err| match err {
                 UnifiedExecError::SandboxDenied { output, .. } => {
@@ -1,21 +1,26 @@
 //! Shared approvals and sandboxing traits used by tool runtimes.
 //!
 //! Consolidates the approval flow primitives (`ApprovalDecision`, `ApprovalStore`,
-//! `ApprovalCtx`, `Approvable`) together with the sandbox orchestration traits
-//! and helpers (`Sandboxable`, `ToolRuntime`, `SandboxAttempt`, etc.).
+//! `ApprovalJournal`, `ApprovalCtx`, `Approvable`) together with the sandbox
+//! orchestration traits and helpers (`Sandboxable`, `ToolRuntime`,
+//! `SandboxAttempt`, etc.).
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::config::types::ExecLimits;
 use crate::error::CodexErr;
 use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxManager;
 use crate::sandboxing::SandboxTransformError;
 use crate::state::SessionServices;
+use codex_protocol::approvals::ApprovalJournalEntry;
+use codex_protocol::approvals::ApprovalSource;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::path::Path;
@@ -47,6 +52,62 @@ impl ApprovalStore {
             self.map.insert(s, value);
         }
     }
+
+    /// Serialized keys approved for the rest of the session, sorted.
+    pub(crate) fn session_approvals(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .map
+            .iter()
+            .filter(|(_, decision)| matches!(decision, ReviewDecision::ApprovedForSession))
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Restores a key returned by [`Self::session_approvals`].
+    pub(crate) fn restore_session_approval(&mut self, key: String) {
+        self.map.insert(key, ReviewDecision::ApprovedForSession);
+    }
+}
+
+/// Maximum number of decisions retained by [`ApprovalJournal`].
+const APPROVAL_JOURNAL_CAPACITY: usize = 512;
+
+/// Records which part of the approval configuration authorized each tool call
+/// so users can ask "why was this allowed?" after the fact.
+#[derive(Clone, Default, Debug)]
+pub(crate) struct ApprovalJournal {
+    entries: VecDeque<ApprovalJournalEntry>,
+}
+
+impl ApprovalJournal {
+    pub fn record(&mut self, entry: ApprovalJournalEntry) {
+        if self.entries.len() == APPROVAL_JOURNAL_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Returns the most recent `limit` entries (all when `None`), oldest first.
+    pub fn recent(&self, limit: Option<usize>) -> Vec<ApprovalJournalEntry> {
+        let skip = limit.map_or(0, |limit| self.entries.len().saturating_sub(limit));
+        self.entries.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// Returns true when every key was already approved for the session, i.e. the
+/// next [`with_cached_approval`] call will not prompt the user.
+pub(crate) async fn is_approved_for_session<K>(services: &SessionServices, keys: &[K]) -> bool
+where
+    K: Serialize,
+{
+    if keys.is_empty() {
+        return false;
+    }
+    let store = services.tool_approvals.lock().await;
+    keys.iter()
+        .all(|key| matches!(store.get(key), Some(ReviewDecision::ApprovedForSession)))
 }
 
 /// Takes a vector of approval keys and returns a ReviewDecision.
@@ -72,13 +133,7 @@ where
         return fetch().await;
     }
 
-    let already_approved = {
-        let store = services.tool_approvals.lock().await;
-        keys.iter()
-            .all(|key| matches!(store.get(key), Some(ReviewDecision::ApprovedForSession)))
-    };
-
-    if already_approved {
+    if is_approved_for_session(services, &keys).await {
         return ReviewDecision::ApprovedForSession;
     }
 
@@ -119,6 +174,9 @@ pub(crate) enum ExecApprovalRequirement {
         /// The first attempt should skip sandboxing (e.g., when explicitly
         /// greenlit by policy).
         bypass_sandbox: bool,
+        /// Which part of the approval configuration allowed the call; recorded
+        /// in the session's [`ApprovalJournal`].
+        source: ApprovalSource,
         /// Proposed execpolicy amendment to skip future approvals for similar commands
         /// Only applies if the command fails to run in sandbox and codex prompts the user to run outside the sandbox.
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
@@ -135,6 +193,19 @@ pub(crate) enum ExecApprovalRequirement {
 }
 
 impl ExecApprovalRequirement {
+    /// Whether the call was auto-approved only because it was classified as
+    /// read-only, in which case a request for escalated permissions must not
+    /// take it out of the sandbox.
+    pub fn keeps_sandbox(&self) -> bool {
+        matches!(
+            self,
+            Self::Skip {
+                source: ApprovalSource::ReadOnlyCommand,
+                ..
+            }
+        )
+    }
+
     pub fn proposed_execpolicy_amendment(&self) -> Option<&ExecPolicyAmendment> {
         match self {
             Self::NeedsApproval {
@@ -174,6 +245,9 @@ pub(crate) fn default_exec_approval_requirement(
     } else {
         ExecApprovalRequirement::Skip {
             bypass_sandbox: false,
+            source: ApprovalSource::ModeDefault {
+                approval_policy: policy,
+            },
             proposed_execpolicy_amendment: None,
         }
     }
@@ -197,6 +271,9 @@ pub(crate) trait Approvable<Req> {
     // requests touching a subset can be auto-approved.
     fn approval_keys(&self, req: &Req) -> Vec<Self::ApprovalKey>;
 
+    /// Human-readable summary of the request recorded in the approval journal.
+    fn approval_subject(&self, req: &Req) -> String;
+
     /// Some tools may request to skip the sandbox on the first attempt
     /// (e.g., when the request explicitly asks for escalated permissions).
     /// Defaults to `NoOverride`.
@@ -274,6 +351,8 @@ pub(crate) struct SandboxAttempt<'a> {
     pub(crate) manager: &'a SandboxManager,
     pub(crate) sandbox_cwd: &'a Path,
     pub codex_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
+    /// Resource limits applied to the command regardless of the sandbox.
+    pub exec_limits: ExecLimits,
 }
 
 impl<'a> SandboxAttempt<'a> {
@@ -281,13 +360,35 @@ impl<'a> SandboxAttempt<'a> {
         &self,
         spec: CommandSpec,
     ) -> Result<crate::sandboxing::ExecEnv, SandboxTransformError> {
-        self.manager.transform(
+        let mut env = self.manager.transform(
             spec,
             self.policy,
             self.sandbox,
             self.sandbox_cwd,
             self.codex_linux_sandbox_exe,
-        )
+        )?;
+        env.limits = self.exec_limits;
+        Ok(env)
+    }
+
+    /// The same attempt for commands that run the host's own `codex` binary,
+    /// which is not available inside a container image: a container attempt
+    /// falls back to the OS sandbox.
+    pub fn for_host_binary(&self) -> SandboxAttempt<'a> {
+        let sandbox = match self.sandbox {
+            crate::exec::SandboxType::Container => {
+                crate::safety::get_host_sandbox().unwrap_or(crate::exec::SandboxType::None)
+            }
+            sandbox => sandbox,
+        };
+        SandboxAttempt {
+            sandbox,
+            policy: self.policy,
+            manager: self.manager,
+            sandbox_cwd: self.sandbox_cwd,
+            codex_linux_sandbox_exe: self.codex_linux_sandbox_exe,
+            exec_limits: self.exec_limits,
+        }
     }
 }
 
@@ -308,6 +409,9 @@ mod tests {
             ),
             ExecApprovalRequirement::Skip {
                 bypass_sandbox: false,
+                source: ApprovalSource::ModeDefault {
+                    approval_policy: AskForApproval::OnRequest,
+                },
                 proposed_execpolicy_amendment: None,
             }
         );
@@ -323,4 +427,42 @@ mod tests {
             }
         );
     }
+
+    fn journal_entry(call_id: &str) -> ApprovalJournalEntry {
+        ApprovalJournalEntry {
+            call_id: call_id.to_string(),
+            turn_id: "turn-1".to_string(),
+            tool_name: "shell".to_string(),
+            subject: "ls".to_string(),
+            source: ApprovalSource::KnownSafeCommand,
+            approved: true,
+            timestamp: "2025-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn approval_journal_returns_most_recent_entries_oldest_first() {
+        let mut journal = ApprovalJournal::default();
+        for call_id in ["a", "b", "c"] {
+            journal.record(journal_entry(call_id));
+        }
+
+        assert_eq!(
+            journal.recent(Some(2)),
+            vec![journal_entry("b"), journal_entry("c")]
+        );
+        assert_eq!(journal.recent(None).len(), 3);
+    }
+
+    #[test]
+    fn approval_journal_drops_oldest_entries_beyond_capacity() {
+        let mut journal = ApprovalJournal::default();
+        for idx in 0..=APPROVAL_JOURNAL_CAPACITY {
+            journal.record(journal_entry(&idx.to_string()));
+        }
+
+        let entries = journal.recent(None);
+        assert_eq!(entries.len(), APPROVAL_JOURNAL_CAPACITY);
+        assert_eq!(entries.first(), Some(&journal_entry("1")));
+    }
 }
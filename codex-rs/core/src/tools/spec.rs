@@ -1,14 +1,19 @@
 use crate::agent::AgentRole;
+use crate::client_common::tools::CodeInterpreterContainer;
 use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
+use crate::config::types::HostedToolsToml;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::plugin_tools::PluginTool;
+use crate::tools::handlers::MAX_FOLLOW_UPS;
 use crate::tools::handlers::PLAN_TOOL;
 use crate::tools::handlers::apply_patch::create_apply_patch_freeform_tool;
 use crate::tools::handlers::apply_patch::create_apply_patch_json_tool;
 use crate::tools::handlers::collab::DEFAULT_WAIT_TIMEOUT_MS;
 use crate::tools::handlers::collab::MAX_WAIT_TIMEOUT_MS;
 use crate::tools::registry::ToolRegistryBuilder;
+use crate::web_search::MAX_SEARCH_RESULTS;
 use codex_protocol::config_types::WebSearchMode;
 use codex_protocol::models::VIEW_IMAGE_TOOL_NAME;
 use codex_protocol::openai_models::ApplyPatchToolType;
@@ -20,6 +25,7 @@ use serde_json::Value as JsonValue;
 use serde_json::json;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub(crate) struct ToolsConfig {
@@ -28,6 +34,31 @@ pub(crate) struct ToolsConfig {
     pub web_search_mode: Option<WebSearchMode>,
     pub collab_tools: bool,
     pub collaboration_modes_tools: bool,
+    pub blame_context_tool: bool,
+    pub memory_tool: bool,
+    pub define_tool: bool,
+    pub semantic_search_tool: bool,
+    pub code_navigation_tools: bool,
+    pub scan_todos_tool: bool,
+    pub shell_session_tool: bool,
+    pub background_process_tool: bool,
+    pub web_fetch_tool: bool,
+    /// Exposes `describe_tool` for MCP tools sent without their schema.
+    pub tool_trimming: bool,
+    pub follow_ups_tool: bool,
+    /// Set per turn from the session's plan mode; exposes `submit_plan`.
+    pub plan_mode: bool,
+    /// Set per turn from `[hosted_tools]` for the turn's model.
+    pub hosted_tools: HostedToolsToml,
+    /// Set per turn when `[web_search_provider]` is configured; `web_search`
+    /// then runs locally instead of as the hosted tool.
+    pub local_web_search: bool,
+    /// Set per turn when `[browser]` is configured; exposes the `browser_*`
+    /// tools.
+    pub browser_tools: bool,
+    /// Set per turn from the tools loaded from `.codex/plugins/` at session
+    /// start (`plugin_tools` feature).
+    pub plugin_tools: Vec<Arc<PluginTool>>,
     pub experimental_supported_tools: Vec<String>,
 }
 
@@ -47,6 +78,17 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_collab_tools = features.enabled(Feature::Collab);
         let include_collaboration_modes_tools = features.enabled(Feature::CollaborationModes);
+        let include_blame_context_tool = features.enabled(Feature::BlameContext);
+        let include_memory_tool = features.enabled(Feature::ProjectMemory);
+        let include_define_tool = features.enabled(Feature::ProjectGlossary);
+        let include_semantic_search_tool = features.enabled(Feature::SemanticSearch);
+        let include_code_navigation_tools = features.enabled(Feature::CodeNavigation);
+        let include_scan_todos_tool = features.enabled(Feature::ScanTodos);
+        let include_shell_session_tool = features.enabled(Feature::ShellSession);
+        let include_background_process_tool = features.enabled(Feature::BackgroundProcesses);
+        let include_web_fetch_tool = features.enabled(Feature::WebFetch);
+        let include_tool_trimming = features.enabled(Feature::ToolTrimming);
+        let include_follow_ups_tool = features.enabled(Feature::FollowUps);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -79,6 +121,22 @@ impl ToolsConfig {
             web_search_mode: *web_search_mode,
             collab_tools: include_collab_tools,
             collaboration_modes_tools: include_collaboration_modes_tools,
+            blame_context_tool: include_blame_context_tool,
+            memory_tool: include_memory_tool,
+            define_tool: include_define_tool,
+            semantic_search_tool: include_semantic_search_tool,
+            code_navigation_tools: include_code_navigation_tools,
+            scan_todos_tool: include_scan_todos_tool,
+            shell_session_tool: include_shell_session_tool,
+            background_process_tool: include_background_process_tool,
+            web_fetch_tool: include_web_fetch_tool,
+            tool_trimming: include_tool_trimming,
+            follow_ups_tool: include_follow_ups_tool,
+            plan_mode: false,
+            hosted_tools: HostedToolsToml::default(),
+            local_web_search: false,
+            browser_tools: false,
+            plugin_tools: Vec::new(),
             experimental_supported_tools: model_info.experimental_supported_tools.clone(),
         }
     }
@@ -278,6 +336,127 @@ fn create_write_stdin_tool() -> ToolSpec {
     })
 }
 
+fn create_shell_session_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "action".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "\"open\" starts a shell, \"run\" runs a command in it, \"close\" ends it."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "session_id".to_string(),
+            JsonSchema::Number {
+                description: Some("Session returned by \"open\"; required for run and close.".to_string()),
+            },
+        ),
+        (
+            "command".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Shell command to run. Leave empty to keep waiting for a command that is still running."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "workdir".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Starting directory for \"open\"; defaults to the turn cwd.".to_string(),
+                ),
+            },
+        ),
+        (
+            "timeout_ms".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "How long to wait for the command before returning (default 10000).".to_string(),
+                ),
+            },
+        ),
+        (
+            "max_output_tokens".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Maximum number of tokens to return. Excess output will be truncated."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "shell_session".to_string(),
+        description: "Keeps a shell open across calls so the working directory, environment variables and an activated virtualenv carry over between commands. Open a session, run commands in it one at a time, and close it when done; sessions end with the turn. Commands run without a terminal and with stdin closed, inside the sandbox.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["action".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_background_process_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "action".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "\"start\" launches a command, \"output\" reads what it printed since the last read, \"stop\" kills it, \"list\" shows all processes."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "process_id".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Process returned by \"start\"; required for output and stop.".to_string(),
+                ),
+            },
+        ),
+        (
+            "command".to_string(),
+            JsonSchema::String {
+                description: Some("Shell command to start, e.g. `npm run dev`.".to_string()),
+            },
+        ),
+        (
+            "workdir".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Working directory for \"start\"; defaults to the turn cwd.".to_string(),
+                ),
+            },
+        ),
+        (
+            "max_output_tokens".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Maximum number of tokens to return. Excess output will be truncated."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "background_process".to_string(),
+        description: "Runs long-lived commands such as dev servers, watchers or local databases in the background. Start a process, read its recent output while you work, and stop it when it is no longer needed; processes keep running across turns and are killed when the session ends. Commands run without a terminal and with stdin closed, inside the sandbox.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["action".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_shell_tool() -> ToolSpec {
     let properties = BTreeMap::from([
         (
@@ -617,118 +796,87 @@ fn create_request_user_input_tool() -> ToolSpec {
     })
 }
 
-fn create_close_agent_tool() -> ToolSpec {
-    let mut properties = BTreeMap::new();
-    properties.insert(
-        "id".to_string(),
-        JsonSchema::String {
-            description: Some("Identifier of the agent to close.".to_string()),
-        },
-    );
-
-    ToolSpec::Function(ResponsesApiTool {
-        name: "close_agent".to_string(),
-        description: "Close an agent and return its last known status.".to_string(),
-        strict: false,
-        parameters: JsonSchema::Object {
-            properties,
-            required: Some(vec!["id".to_string()]),
-            additional_properties: Some(false.into()),
-        },
-    })
-}
-
-fn create_test_sync_tool() -> ToolSpec {
-    let barrier_properties = BTreeMap::from([
+fn create_submit_plan_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
         (
-            "id".to_string(),
+            "summary".to_string(),
             JsonSchema::String {
-                description: Some(
-                    "Identifier shared by concurrent calls that should rendezvous".to_string(),
-                ),
+                description: Some("One or two sentences describing the change.".to_string()),
             },
         ),
         (
-            "participants".to_string(),
-            JsonSchema::Number {
-                description: Some(
-                    "Number of tool calls that must arrive before the barrier opens".to_string(),
-                ),
+            "steps".to_string(),
+            JsonSchema::Array {
+                description: Some("Ordered implementation steps.".to_string()),
+                items: Box::new(JsonSchema::String { description: None }),
             },
         ),
         (
-            "timeout_ms".to_string(),
-            JsonSchema::Number {
+            "affected_files".to_string(),
+            JsonSchema::Array {
                 description: Some(
-                    "Maximum time in milliseconds to wait at the barrier".to_string(),
+                    "Files the plan will create, modify, or delete, relative to the working directory."
+                        .to_string(),
                 ),
+                items: Box::new(JsonSchema::String { description: None }),
             },
         ),
-    ]);
-
-    let properties = BTreeMap::from([
         (
-            "sleep_before_ms".to_string(),
-            JsonSchema::Number {
-                description: Some(
-                    "Optional delay in milliseconds before any other action".to_string(),
-                ),
+            "risk".to_string(),
+            JsonSchema::String {
+                description: Some("Risk level of the change: low, medium, or high.".to_string()),
             },
         ),
         (
-            "sleep_after_ms".to_string(),
-            JsonSchema::Number {
+            "risk_notes".to_string(),
+            JsonSchema::String {
                 description: Some(
-                    "Optional delay in milliseconds after completing the barrier".to_string(),
+                    "Optional reason for the risk level (migrations, public API changes, ...)."
+                        .to_string(),
                 ),
             },
         ),
-        (
-            "barrier".to_string(),
-            JsonSchema::Object {
-                properties: barrier_properties,
-                required: Some(vec!["id".to_string(), "participants".to_string()]),
-                additional_properties: Some(false.into()),
-            },
-        ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
-        name: "test_sync_tool".to_string(),
-        description: "Internal synchronization helper used by Codex integration tests.".to_string(),
+        name: "submit_plan".to_string(),
+        description: "Submit an implementation plan for the user to approve. Plan mode is on: commands, patches, and other tools that might change the workspace are rejected until a plan is approved in the current turn. Investigate with read-only tools first, then call this once with a complete plan. If the plan is declined, revise it based on the user's feedback instead of resubmitting it unchanged.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: None,
+            required: Some(vec![
+                "summary".to_string(),
+                "steps".to_string(),
+                "affected_files".to_string(),
+                "risk".to_string(),
+            ]),
             additional_properties: Some(false.into()),
         },
     })
 }
 
-fn create_grep_files_tool() -> ToolSpec {
+fn create_blame_context_tool() -> ToolSpec {
     let properties = BTreeMap::from([
         (
-            "pattern".to_string(),
+            "path".to_string(),
             JsonSchema::String {
-                description: Some("Regular expression pattern to search for.".to_string()),
+                description: Some("Path to the file, relative to the working directory.".to_string()),
             },
         ),
         (
-            "include".to_string(),
-            JsonSchema::String {
+            "start_line".to_string(),
+            JsonSchema::Number {
                 description: Some(
-                    "Optional glob that limits which files are searched (e.g. \"*.rs\" or \
-                     \"*.{ts,tsx}\")."
+                    "First line (1-indexed) of the region. Omit to summarize the whole file's history."
                         .to_string(),
                 ),
             },
         ),
         (
-            "path".to_string(),
-            JsonSchema::String {
+            "end_line".to_string(),
+            JsonSchema::Number {
                 description: Some(
-                    "Directory or file path to search. Defaults to the session's working directory."
-                        .to_string(),
+                    "Last line of the region (inclusive). Defaults to start_line.".to_string(),
                 ),
             },
         ),
@@ -736,191 +884,286 @@ fn create_grep_files_tool() -> ToolSpec {
             "limit".to_string(),
             JsonSchema::Number {
                 description: Some(
-                    "Maximum number of file paths to return (defaults to 100).".to_string(),
+                    "Maximum number of commits to return (default 10, max 50).".to_string(),
                 ),
             },
         ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
-        name: "grep_files".to_string(),
-        description: "Finds files whose contents match the pattern and lists them by modification \
-                      time."
-            .to_string(),
+        name: "blame_context".to_string(),
+        description: "Lists the most recent commits that touched a file region, with author, date, message, and any PR/issue references parsed from the message. Use it to learn why code is written the way it is before changing it; it is much cheaper than reading `git log -p`.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: Some(vec!["pattern".to_string()]),
+            required: Some(vec!["path".to_string()]),
             additional_properties: Some(false.into()),
         },
     })
 }
 
-fn create_read_file_tool() -> ToolSpec {
-    let indentation_properties = BTreeMap::from([
+fn create_memory_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
         (
-            "anchor_line".to_string(),
-            JsonSchema::Number {
-                description: Some(
-                    "Anchor line to center the indentation lookup on (defaults to offset)."
-                        .to_string(),
-                ),
+            "action".to_string(),
+            JsonSchema::String {
+                description: Some("One of `list`, `add`, `update`, or `delete`.".to_string()),
             },
         ),
         (
-            "max_levels".to_string(),
-            JsonSchema::Number {
-                description: Some(
-                    "How many parent indentation levels (smaller indents) to include.".to_string(),
-                ),
+            "id".to_string(),
+            JsonSchema::String {
+                description: Some("Id of the entry to update or delete.".to_string()),
             },
         ),
         (
-            "include_siblings".to_string(),
-            JsonSchema::Boolean {
+            "text".to_string(),
+            JsonSchema::String {
                 description: Some(
-                    "When true, include additional blocks that share the anchor indentation."
+                    "The fact to record, as one short self-contained sentence (add and update)."
                         .to_string(),
                 ),
             },
         ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "memory".to_string(),
+        description: "Reads and edits the project memory in `.codex/memory.md`, which is loaded into every new session in this project. Record durable facts that future sessions would otherwise have to rediscover, such as build and test commands, conventions, or pitfalls; do not record task progress or anything secret. Update or delete entries that turn out to be wrong. Returns the entries with their ids after the change.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["action".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_define_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "term".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "The term, acronym, or identifier pattern to look up, e.g. \"SLO\".".to_string(),
+            ),
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "define".to_string(),
+        description: "Looks up a term in the project glossary (`.codex/glossary.md` or `.codex/glossary.toml`) and returns its full definition, aliases included. Use it before relying on the meaning of a project-specific term, acronym, or naming convention. When nothing matches, lists the terms the glossary defines.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["term".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_semantic_search_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
         (
-            "include_header".to_string(),
-            JsonSchema::Boolean {
+            "query".to_string(),
+            JsonSchema::String {
                 description: Some(
-                    "Include doc comments or attributes directly above the selected block."
+                    "What the code does, in words or identifiers, e.g. \"where retries are scheduled after a failed request\"."
                         .to_string(),
                 ),
             },
         ),
         (
-            "max_lines".to_string(),
+            "limit".to_string(),
             JsonSchema::Number {
+                description: Some("Maximum number of results to return (default 8, max 30).".to_string()),
+            },
+        ),
+        (
+            "path".to_string(),
+            JsonSchema::String {
                 description: Some(
-                    "Hard cap on the number of lines returned when using indentation mode."
+                    "Only search files under this directory or file, relative to the project root."
                         .to_string(),
                 ),
             },
         ),
     ]);
 
-    let properties = BTreeMap::from([
-        (
-            "file_path".to_string(),
-            JsonSchema::String {
-                description: Some("Absolute path to the file".to_string()),
-            },
-        ),
+    ToolSpec::Function(ResponsesApiTool {
+        name: "semantic_search".to_string(),
+        description: "Finds code by meaning using a local index of the project (stored in `.codex/index/` and refreshed for changed files on every call). Returns the best-matching file regions with line ranges and snippets. Use it to locate where something is implemented when you do not know the exact names to grep for; follow up with `read_file` or `rg` for exact matches.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["query".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_code_outline_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "File or directory to outline, relative to the working directory.".to_string(),
+            ),
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "code_outline".to_string(),
+        description: "Lists the functions, methods, types, and other definitions in a file (or every supported file under a directory) with their line ranges and first lines, parsed with tree-sitter. Use it to see the shape of a file before reading specific ranges instead of reading whole files. Supports Rust, Python, JavaScript, TypeScript, and Go.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["path".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_find_symbol_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
         (
-            "offset".to_string(),
-            JsonSchema::Number {
+            "name".to_string(),
+            JsonSchema::String {
                 description: Some(
-                    "The line number to start reading from. Must be 1 or greater.".to_string(),
+                    "Exact name of the symbol, optionally qualified by its container (`Config.load` or `Config::load`)."
+                        .to_string(),
                 ),
             },
         ),
         (
-            "limit".to_string(),
-            JsonSchema::Number {
-                description: Some("The maximum number of lines to return.".to_string()),
+            "kind".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Only return definitions of this kind: function, method, class, struct, enum, interface, trait, type, module, constant, or macro."
+                        .to_string(),
+                ),
             },
         ),
         (
-            "mode".to_string(),
+            "path".to_string(),
             JsonSchema::String {
                 description: Some(
-                    "Optional mode selector: \"slice\" for simple ranges (default) or \"indentation\" \
-                     to expand around an anchor line."
+                    "Only search under this directory or file, relative to the working directory."
                         .to_string(),
                 ),
             },
         ),
         (
-            "indentation".to_string(),
-            JsonSchema::Object {
-                properties: indentation_properties,
-                required: None,
-                additional_properties: Some(false.into()),
+            "references".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Also list the places where the name is used (default false).".to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Maximum number of definitions and of references to return (default 20, max 100)."
+                        .to_string(),
+                ),
             },
         ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
-        name: "read_file".to_string(),
-        description:
-            "Reads a local file with 1-indexed line numbers, supporting slice and indentation-aware block modes."
-                .to_string(),
+        name: "find_symbol".to_string(),
+        description: "Finds where a function, type, or other symbol is defined across the project, and optionally where it is referenced, with file and line locations. Parsed with tree-sitter, so matches in comments and strings are excluded. Supports Rust, Python, JavaScript, TypeScript, and Go.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: Some(vec!["file_path".to_string()]),
+            required: Some(vec!["name".to_string()]),
             additional_properties: Some(false.into()),
         },
     })
 }
 
-fn create_list_dir_tool() -> ToolSpec {
+fn create_scan_todos_tool() -> ToolSpec {
     let properties = BTreeMap::from([
         (
-            "dir_path".to_string(),
+            "path".to_string(),
             JsonSchema::String {
-                description: Some("Absolute path to the directory to list.".to_string()),
+                description: Some(
+                    "Only scan under this directory or file, relative to the working directory."
+                        .to_string(),
+                ),
             },
         ),
         (
-            "offset".to_string(),
-            JsonSchema::Number {
+            "tags".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
                 description: Some(
-                    "The entry number to start listing from. Must be 1 or greater.".to_string(),
+                    "Tags to look for (default TODO, FIXME, HACK, XXX). Matched as uppercase whole words."
+                        .to_string(),
                 ),
             },
         ),
         (
-            "limit".to_string(),
-            JsonSchema::Number {
-                description: Some("The maximum number of entries to return.".to_string()),
+            "author".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Only items whose `TAG(owner)` hint or git blame author name/email contains this text."
+                        .to_string(),
+                ),
             },
         ),
         (
-            "depth".to_string(),
+            "min_age_days".to_string(),
             JsonSchema::Number {
                 description: Some(
-                    "The maximum directory depth to traverse. Must be 1 or greater.".to_string(),
+                    "Only items whose line was last committed at least this many days ago."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "blame".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Include git blame author, age, and commit for each item (default true)."
+                        .to_string(),
                 ),
             },
         ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some("Maximum number of items to return (default 50, max 500).".to_string()),
+            },
+        ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
-        name: "list_dir".to_string(),
-        description:
-            "Lists entries in a local directory with 1-indexed entry numbers and simple type labels."
-                .to_string(),
+        name: "scan_todos".to_string(),
+        description: "Lists TODO/FIXME/HACK comments in the project with their exact file and line, the owner from `TODO(name)`, and the git blame author and age of the line. Use it instead of grepping when asked to review or clean up TODOs, and filter by path, tag, author, or age.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: Some(vec!["dir_path".to_string()]),
+            required: None,
             additional_properties: Some(false.into()),
         },
     })
 }
 
-fn create_list_mcp_resources_tool() -> ToolSpec {
+fn create_web_fetch_tool() -> ToolSpec {
     let properties = BTreeMap::from([
         (
-            "server".to_string(),
+            "url".to_string(),
             JsonSchema::String {
-                description: Some(
-                    "Optional MCP server name. When omitted, lists resources from every configured server."
-                        .to_string(),
-                ),
+                description: Some("The http or https URL to download.".to_string()),
             },
         ),
         (
-            "cursor".to_string(),
-            JsonSchema::String {
+            "raw".to_string(),
+            JsonSchema::Boolean {
                 description: Some(
-                    "Opaque cursor returned by a previous list_mcp_resources call for the same server."
+                    "Return HTML as-is instead of converting it to markdown (default false)."
                         .to_string(),
                 ),
             },
@@ -928,145 +1171,748 @@ fn create_list_mcp_resources_tool() -> ToolSpec {
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
-        name: "list_mcp_resources".to_string(),
-        description: "Lists resources provided by MCP servers. Resources allow servers to share data that provides context to language models, such as files, database schemas, or application-specific information. Prefer resources over web search when possible.".to_string(),
+        name: "web_fetch".to_string(),
+        description: "Downloads a web page or text file and returns its status, content type, title, and content, with HTML converted to markdown. Use it instead of curl in a shell command to read documentation, issues, or API responses. Domains may be restricted by the user's configuration and sandbox.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: None,
+            required: Some(vec!["url".to_string()]),
             additional_properties: Some(false.into()),
         },
     })
 }
 
-fn create_list_mcp_resource_templates_tool() -> ToolSpec {
-    let properties = BTreeMap::from([
+fn create_describe_tool_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "name".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Exact name of the tool, as it appears in the tool list.".to_string(),
+            ),
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "describe_tool".to_string(),
+        description: "Returns the description and input schema of a tool that is listed without its parameters. Call it before using such a tool; afterwards the tool is listed with its full schema.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["name".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_suggest_follow_ups_tool() -> ToolSpec {
+    let suggestion_properties = BTreeMap::from([
         (
-            "server".to_string(),
+            "title".to_string(),
             JsonSchema::String {
-                description: Some(
-                    "Optional MCP server name. When omitted, lists resource templates from all configured servers."
-                        .to_string(),
-                ),
+                description: Some("Short label of a few words, e.g. \"Add tests\".".to_string()),
             },
         ),
         (
-            "cursor".to_string(),
+            "prompt".to_string(),
             JsonSchema::String {
                 description: Some(
-                    "Opaque cursor returned by a previous list_mcp_resource_templates call for the same server."
+                    "Self-contained instruction sent as the user's next message if chosen."
                         .to_string(),
                 ),
             },
         ),
     ]);
+    let properties = BTreeMap::from([(
+        "suggestions".to_string(),
+        JsonSchema::Array {
+            description: Some(format!(
+                "At most {MAX_FOLLOW_UPS} next steps, most useful first."
+            )),
+            items: Box::new(JsonSchema::Object {
+                properties: suggestion_properties,
+                required: Some(vec!["title".to_string(), "prompt".to_string()]),
+                additional_properties: Some(false.into()),
+            }),
+        },
+    )]);
 
     ToolSpec::Function(ResponsesApiTool {
-        name: "list_mcp_resource_templates".to_string(),
-        description: "Lists resource templates provided by MCP servers. Parameterized resource templates allow servers to share data that takes parameters and provides context to language models, such as files, database schemas, or application-specific information. Prefer resource templates over web search when possible.".to_string(),
+        name: "suggest_follow_ups".to_string(),
+        description: "Offers the user concrete next steps as selectable suggestions they can edit or queue as the next turn. Call it at most once, right before your final message, when there are clear follow-up tasks; do not also list them as prose.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: None,
+            required: Some(vec!["suggestions".to_string()]),
             additional_properties: Some(false.into()),
         },
     })
 }
 
-fn create_read_mcp_resource_tool() -> ToolSpec {
+fn create_local_web_search_tool() -> ToolSpec {
     let properties = BTreeMap::from([
         (
-            "server".to_string(),
+            "query".to_string(),
             JsonSchema::String {
-                description: Some(
-                    "MCP server name exactly as configured. Must match the 'server' field returned by list_mcp_resources."
-                        .to_string(),
-                ),
+                description: Some("The search query.".to_string()),
             },
         ),
         (
-            "uri".to_string(),
-            JsonSchema::String {
-                description: Some(
-                    "Resource URI to read. Must be one of the URIs returned by list_mcp_resources."
-                        .to_string(),
-                ),
+            "count".to_string(),
+            JsonSchema::Number {
+                description: Some(format!(
+                    "Number of results to return (default from configuration, at most {MAX_SEARCH_RESULTS})."
+                )),
             },
         ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
-        name: "read_mcp_resource".to_string(),
-        description:
-            "Read a specific resource from an MCP server given the server name and resource URI."
-                .to_string(),
+        name: "web_search".to_string(),
+        description: "Searches the web and returns JSON with the title, URL, and snippet of each result. Use web_fetch to read a result in full.".to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: Some(vec!["server".to_string(), "uri".to_string()]),
+            required: Some(vec!["query".to_string()]),
             additional_properties: Some(false.into()),
         },
     })
 }
-/// TODO(dylan): deprecate once we get rid of json tool
-#[derive(Serialize, Deserialize)]
-pub(crate) struct ApplyPatchToolArgs {
-    pub(crate) input: String,
-}
-
-/// Returns JSON values that are compatible with Function Calling in the
-/// Responses API:
-/// https://platform.openai.com/docs/guides/function-calling?api-mode=responses
-pub fn create_tools_json_for_responses_api(
-    tools: &[ToolSpec],
-) -> crate::error::Result<Vec<serde_json::Value>> {
-    let mut tools_json = Vec::new();
 
-    for tool in tools {
-        let json = serde_json::to_value(tool)?;
-        tools_json.push(json);
-    }
+fn create_browser_navigate_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "url".to_string(),
+        JsonSchema::String {
+            description: Some("The http, https or file URL to open.".to_string()),
+        },
+    )]);
 
-    Ok(tools_json)
+    ToolSpec::Function(ResponsesApiTool {
+        name: "browser_navigate".to_string(),
+        description: "Opens a URL in the headless browser and waits for the page to load. Returns the final URL and title. Use it to check web apps you changed, e.g. a local dev server. Origins other than localhost may need the user's approval.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["url".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
 }
-/// Returns JSON values that are compatible with Function Calling in the
-/// Chat Completions API:
-/// https://platform.openai.com/docs/guides/function-calling?api-mode=chat
-pub(crate) fn create_tools_json_for_chat_completions_api(
-    tools: &[ToolSpec],
-) -> crate::error::Result<Vec<serde_json::Value>> {
-    // We start with the JSON for the Responses API and than rewrite it to match
-    // the chat completions tool call format.
-    let responses_api_tools_json = create_tools_json_for_responses_api(tools)?;
-    let tools_json = responses_api_tools_json
-        .into_iter()
-        .filter_map(|mut tool| {
-            if tool.get("type") != Some(&serde_json::Value::String("function".to_string())) {
-                return None;
-            }
 
-            if let Some(map) = tool.as_object_mut() {
-                let name = map
-                    .get("name")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or_default()
-                    .to_string();
-                // Remove "type" field as it is not needed in chat completions.
-                map.remove("type");
-                Some(json!({
-                    "type": "function",
-                    "name": name,
-                    "function": map,
-                }))
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<serde_json::Value>>();
-    Ok(tools_json)
+fn create_browser_screenshot_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "full_page".to_string(),
+        JsonSchema::Boolean {
+            description: Some(
+                "Capture the whole scrollable page instead of the viewport (default false)."
+                    .to_string(),
+            ),
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "browser_screenshot".to_string(),
+        description:
+            "Takes a PNG screenshot of the current browser page and returns it as an image."
+                .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
 }
 
-pub(crate) fn mcp_tool_to_openai_tool(
+fn create_browser_read_text_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "selector".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "CSS selector of the element to read; the whole page when omitted.".to_string(),
+            ),
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "browser_read_text".to_string(),
+        description: "Returns the visible text of the current browser page, or of the first element matching a CSS selector.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_browser_click_tool() -> ToolSpec {
+    let properties = BTreeMap::from([(
+        "selector".to_string(),
+        JsonSchema::String {
+            description: Some("CSS selector of the element to click.".to_string()),
+        },
+    )]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "browser_click".to_string(),
+        description: "Clicks the first element matching a CSS selector on the current browser page, then returns the page's URL and title.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["selector".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_close_agent_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "id".to_string(),
+        JsonSchema::String {
+            description: Some("Identifier of the agent to close.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "close_agent".to_string(),
+        description: "Close an agent and return its last known status.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_test_sync_tool() -> ToolSpec {
+    let barrier_properties = BTreeMap::from([
+        (
+            "id".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Identifier shared by concurrent calls that should rendezvous".to_string(),
+                ),
+            },
+        ),
+        (
+            "participants".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Number of tool calls that must arrive before the barrier opens".to_string(),
+                ),
+            },
+        ),
+        (
+            "timeout_ms".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Maximum time in milliseconds to wait at the barrier".to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    let properties = BTreeMap::from([
+        (
+            "sleep_before_ms".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Optional delay in milliseconds before any other action".to_string(),
+                ),
+            },
+        ),
+        (
+            "sleep_after_ms".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Optional delay in milliseconds after completing the barrier".to_string(),
+                ),
+            },
+        ),
+        (
+            "barrier".to_string(),
+            JsonSchema::Object {
+                properties: barrier_properties,
+                required: Some(vec!["id".to_string(), "participants".to_string()]),
+                additional_properties: Some(false.into()),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "test_sync_tool".to_string(),
+        description: "Internal synchronization helper used by Codex integration tests.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_grep_files_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "pattern".to_string(),
+            JsonSchema::String {
+                description: Some("Regular expression pattern to search for.".to_string()),
+            },
+        ),
+        (
+            "include".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional glob that limits which files are searched (e.g. \"*.rs\" or \
+                     \"*.{ts,tsx}\")."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Directory or file path to search. Defaults to the session's working directory."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Maximum number of file paths to return (defaults to 100).".to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "grep_files".to_string(),
+        description: "Finds files whose contents match the pattern and lists them by modification \
+                      time."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["pattern".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_read_file_tool() -> ToolSpec {
+    let indentation_properties = BTreeMap::from([
+        (
+            "anchor_line".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Anchor line to center the indentation lookup on (defaults to offset)."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "max_levels".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "How many parent indentation levels (smaller indents) to include.".to_string(),
+                ),
+            },
+        ),
+        (
+            "include_siblings".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "When true, include additional blocks that share the anchor indentation."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "include_header".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Include doc comments or attributes directly above the selected block."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "max_lines".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Hard cap on the number of lines returned when using indentation mode."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    let properties = BTreeMap::from([
+        (
+            "file_path".to_string(),
+            JsonSchema::String {
+                description: Some("Absolute path to the file".to_string()),
+            },
+        ),
+        (
+            "offset".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "The line number to start reading from. Must be 1 or greater.".to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some("The maximum number of lines to return.".to_string()),
+            },
+        ),
+        (
+            "mode".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional mode selector: \"slice\" for simple ranges (default) or \"indentation\" \
+                     to expand around an anchor line."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "indentation".to_string(),
+            JsonSchema::Object {
+                properties: indentation_properties,
+                required: None,
+                additional_properties: Some(false.into()),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "read_file".to_string(),
+        description:
+            "Reads a local file with 1-indexed line numbers, supporting slice and indentation-aware block modes."
+                .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["file_path".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_list_dir_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "dir_path".to_string(),
+            JsonSchema::String {
+                description: Some("Absolute path to the directory to list.".to_string()),
+            },
+        ),
+        (
+            "offset".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "The entry number to start listing from. Must be 1 or greater.".to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some("The maximum number of entries to return.".to_string()),
+            },
+        ),
+        (
+            "depth".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "The maximum directory depth to traverse. Must be 1 or greater.".to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "list_dir".to_string(),
+        description:
+            "Lists entries in a local directory with 1-indexed entry numbers and simple type labels."
+                .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["dir_path".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_list_mcp_resources_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "server".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional MCP server name. When omitted, lists resources from every configured server."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "cursor".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Opaque cursor returned by a previous list_mcp_resources call for the same server."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "list_mcp_resources".to_string(),
+        description: "Lists resources provided by MCP servers. Resources allow servers to share data that provides context to language models, such as files, database schemas, or application-specific information. Prefer resources over web search when possible.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_list_mcp_resource_templates_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "server".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional MCP server name. When omitted, lists resource templates from all configured servers."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "cursor".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Opaque cursor returned by a previous list_mcp_resource_templates call for the same server."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "list_mcp_resource_templates".to_string(),
+        description: "Lists resource templates provided by MCP servers. Parameterized resource templates allow servers to share data that takes parameters and provides context to language models, such as files, database schemas, or application-specific information. Prefer resource templates over web search when possible.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_read_mcp_resource_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "server".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "MCP server name exactly as configured. Must match the 'server' field returned by list_mcp_resources."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "uri".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Resource URI to read. Must be one of the URIs returned by list_mcp_resources."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "read_mcp_resource".to_string(),
+        description:
+            "Read a specific resource from an MCP server given the server name and resource URI."
+                .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["server".to_string(), "uri".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+/// TODO(dylan): deprecate once we get rid of json tool
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ApplyPatchToolArgs {
+    pub(crate) input: String,
+}
+
+/// Returns JSON values that are compatible with Function Calling in the
+/// Responses API:
+/// https://platform.openai.com/docs/guides/function-calling?api-mode=responses
+pub fn create_tools_json_for_responses_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let mut tools_json = Vec::new();
+
+    for tool in tools {
+        let json = serde_json::to_value(tool)?;
+        tools_json.push(json);
+    }
+
+    Ok(tools_json)
+}
+/// Returns JSON values that are compatible with Function Calling in the
+/// Chat Completions API:
+/// https://platform.openai.com/docs/guides/function-calling?api-mode=chat
+pub(crate) fn create_tools_json_for_chat_completions_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    // We start with the JSON for the Responses API and than rewrite it to match
+    // the chat completions tool call format.
+    let responses_api_tools_json = create_tools_json_for_responses_api(tools)?;
+    let tools_json = responses_api_tools_json
+        .into_iter()
+        .filter_map(|mut tool| {
+            if tool.get("type") != Some(&serde_json::Value::String("function".to_string())) {
+                return None;
+            }
+
+            if let Some(map) = tool.as_object_mut() {
+                let name = map
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                // Remove "type" field as it is not needed in chat completions.
+                map.remove("type");
+                Some(json!({
+                    "type": "function",
+                    "name": name,
+                    "function": map,
+                }))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<serde_json::Value>>();
+    Ok(tools_json)
+}
+
+/// Returns JSON tool definitions for the Anthropic Messages API, which takes
+/// `{name, description, input_schema}`. Like Chat Completions, only function
+/// tools are supported.
+pub(crate) fn create_tools_json_for_anthropic_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let responses_api_tools_json = create_tools_json_for_responses_api(tools)?;
+    let tools_json = responses_api_tools_json
+        .into_iter()
+        .filter(|tool| tool.get("type").and_then(serde_json::Value::as_str) == Some("function"))
+        .map(|tool| {
+            let input_schema = tool
+                .get("parameters")
+                .cloned()
+                .unwrap_or_else(|| json!({"type": "object"}));
+            let description = tool
+                .get("description")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or_default();
+            json!({
+                "name": tool["name"],
+                "description": description,
+                "input_schema": input_schema,
+            })
+        })
+        .collect();
+    Ok(tools_json)
+}
+
+/// Returns Gemini `functionDeclarations`. Gemini accepts only an OpenAPI
+/// subset of JSON Schema, so parameters are pruned to the keys it knows and
+/// dropped entirely for tools without properties (it rejects empty objects).
+pub(crate) fn create_tools_json_for_gemini_api(
+    tools: &[ToolSpec],
+) -> crate::error::Result<Vec<serde_json::Value>> {
+    let responses_api_tools_json = create_tools_json_for_responses_api(tools)?;
+    let tools_json = responses_api_tools_json
+        .into_iter()
+        .filter(|tool| tool.get("type").and_then(serde_json::Value::as_str) == Some("function"))
+        .map(|tool| {
+            let mut declaration = json!({
+                "name": tool["name"],
+                "description": tool.get("description").cloned().unwrap_or_default(),
+            });
+            if let Some(parameters) = tool.get("parameters").map(gemini_schema)
+                && parameters
+                    .get("properties")
+                    .and_then(serde_json::Value::as_object)
+                    .is_some_and(|properties| !properties.is_empty())
+                && let Some(obj) = declaration.as_object_mut()
+            {
+                obj.insert("parameters".to_string(), parameters);
+            }
+            declaration
+        })
+        .collect();
+    Ok(tools_json)
+}
+
+fn gemini_schema(schema: &serde_json::Value) -> serde_json::Value {
+    const KEPT: [&str; 6] = [
+        "type",
+        "description",
+        "required",
+        "enum",
+        "format",
+        "nullable",
+    ];
+    let Some(obj) = schema.as_object() else {
+        return schema.clone();
+    };
+    let mut out = serde_json::Map::new();
+    for (key, value) in obj {
+        match key.as_str() {
+            "properties" => {
+                let properties = value
+                    .as_object()
+                    .map(|properties| {
+                        properties
+                            .iter()
+                            .map(|(name, property)| (name.clone(), gemini_schema(property)))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                out.insert(key.clone(), serde_json::Value::Object(properties));
+            }
+            "items" => {
+                out.insert(key.clone(), gemini_schema(value));
+            }
+            _ if KEPT.contains(&key.as_str()) => {
+                out.insert(key.clone(), value.clone());
+            }
+            _ => {}
+        }
+    }
+    serde_json::Value::Object(out)
+}
+
+pub(crate) fn mcp_tool_to_openai_tool(
     fully_qualified_name: String,
     tool: mcp_types::Tool,
 ) -> Result<ResponsesApiTool, serde_json::Error> {
@@ -1076,503 +1922,1029 @@ pub(crate) fn mcp_tool_to_openai_tool(
         ..
     } = tool;
 
-    // OpenAI models mandate the "properties" field in the schema. The Agents
-    // SDK fixed this by inserting an empty object for "properties" if it is not
-    // already present https://github.com/openai/openai-agents-python/issues/449
-    // so here we do the same.
-    if input_schema.properties.is_none() {
-        input_schema.properties = Some(serde_json::Value::Object(serde_json::Map::new()));
+    // OpenAI models mandate the "properties" field in the schema. The Agents
+    // SDK fixed this by inserting an empty object for "properties" if it is not
+    // already present https://github.com/openai/openai-agents-python/issues/449
+    // so here we do the same.
+    if input_schema.properties.is_none() {
+        input_schema.properties = Some(serde_json::Value::Object(serde_json::Map::new()));
+    }
+
+    // Serialize to a raw JSON value so we can sanitize schemas coming from MCP
+    // servers. Some servers omit the top-level or nested `type` in JSON
+    // Schemas (e.g. using enum/anyOf), or use unsupported variants like
+    // `integer`. Our internal JsonSchema is a small subset and requires
+    // `type`, so we coerce/sanitize here for compatibility.
+    let mut serialized_input_schema = serde_json::to_value(input_schema)?;
+    sanitize_json_schema(&mut serialized_input_schema);
+    let input_schema = serde_json::from_value::<JsonSchema>(serialized_input_schema)?;
+
+    Ok(ResponsesApiTool {
+        name: fully_qualified_name,
+        description: description.unwrap_or_default(),
+        strict: false,
+        parameters: input_schema,
+    })
+}
+
+pub(crate) fn plugin_tool_to_openai_tool(
+    tool: &PluginTool,
+) -> Result<ResponsesApiTool, serde_json::Error> {
+    let mut input_schema = tool.input_schema.clone();
+    sanitize_json_schema(&mut input_schema);
+    let parameters = serde_json::from_value::<JsonSchema>(input_schema)?;
+
+    let description = match &tool.output_schema {
+        Some(output_schema) => format!(
+            "{}\n\nReturns JSON matching this schema: {output_schema}",
+            tool.description
+        ),
+        None => tool.description.clone(),
+    };
+    Ok(ResponsesApiTool {
+        name: tool.name.clone(),
+        description,
+        strict: false,
+        parameters,
+    })
+}
+
+/// Sanitize a JSON Schema (as serde_json::Value) so it can fit our limited
+/// JsonSchema enum. This function:
+/// - Ensures every schema object has a "type". If missing, infers it from
+///   common keywords (properties => object, items => array, enum/const/format => string)
+///   and otherwise defaults to "string".
+/// - Fills required child fields (e.g. array items, object properties) with
+///   permissive defaults when absent.
+fn sanitize_json_schema(value: &mut JsonValue) {
+    match value {
+        JsonValue::Bool(_) => {
+            // JSON Schema boolean form: true/false. Coerce to an accept-all string.
+            *value = json!({ "type": "string" });
+        }
+        JsonValue::Array(arr) => {
+            for v in arr.iter_mut() {
+                sanitize_json_schema(v);
+            }
+        }
+        JsonValue::Object(map) => {
+            // First, recursively sanitize known nested schema holders
+            if let Some(props) = map.get_mut("properties")
+                && let Some(props_map) = props.as_object_mut()
+            {
+                for (_k, v) in props_map.iter_mut() {
+                    sanitize_json_schema(v);
+                }
+            }
+            if let Some(items) = map.get_mut("items") {
+                sanitize_json_schema(items);
+            }
+            // Some schemas use oneOf/anyOf/allOf - sanitize their entries
+            for combiner in ["oneOf", "anyOf", "allOf", "prefixItems"] {
+                if let Some(v) = map.get_mut(combiner) {
+                    sanitize_json_schema(v);
+                }
+            }
+
+            // Normalize/ensure type
+            let mut ty = map.get("type").and_then(|v| v.as_str()).map(str::to_string);
+
+            // If type is an array (union), pick first supported; else leave to inference
+            if ty.is_none()
+                && let Some(JsonValue::Array(types)) = map.get("type")
+            {
+                for t in types {
+                    if let Some(tt) = t.as_str()
+                        && matches!(
+                            tt,
+                            "object" | "array" | "string" | "number" | "integer" | "boolean"
+                        )
+                    {
+                        ty = Some(tt.to_string());
+                        break;
+                    }
+                }
+            }
+
+            // Infer type if still missing
+            if ty.is_none() {
+                if map.contains_key("properties")
+                    || map.contains_key("required")
+                    || map.contains_key("additionalProperties")
+                {
+                    ty = Some("object".to_string());
+                } else if map.contains_key("items") || map.contains_key("prefixItems") {
+                    ty = Some("array".to_string());
+                } else if map.contains_key("enum")
+                    || map.contains_key("const")
+                    || map.contains_key("format")
+                {
+                    ty = Some("string".to_string());
+                } else if map.contains_key("minimum")
+                    || map.contains_key("maximum")
+                    || map.contains_key("exclusiveMinimum")
+                    || map.contains_key("exclusiveMaximum")
+                    || map.contains_key("multipleOf")
+                {
+                    ty = Some("number".to_string());
+                }
+            }
+            // If we still couldn't infer, default to string
+            let ty = ty.unwrap_or_else(|| "string".to_string());
+            map.insert("type".to_string(), JsonValue::String(ty.to_string()));
+
+            // Ensure object schemas have properties map
+            if ty == "object" {
+                if !map.contains_key("properties") {
+                    map.insert(
+                        "properties".to_string(),
+                        JsonValue::Object(serde_json::Map::new()),
+                    );
+                }
+                // If additionalProperties is an object schema, sanitize it too.
+                // Leave booleans as-is, since JSON Schema allows boolean here.
+                if let Some(ap) = map.get_mut("additionalProperties") {
+                    let is_bool = matches!(ap, JsonValue::Bool(_));
+                    if !is_bool {
+                        sanitize_json_schema(ap);
+                    }
+                }
+            }
+
+            // Ensure array schemas have items
+            if ty == "array" && !map.contains_key("items") {
+                map.insert("items".to_string(), json!({ "type": "string" }));
+            }
+        }
+        _ => {}
     }
+}
 
-    // Serialize to a raw JSON value so we can sanitize schemas coming from MCP
-    // servers. Some servers omit the top-level or nested `type` in JSON
-    // Schemas (e.g. using enum/anyOf), or use unsupported variants like
-    // `integer`. Our internal JsonSchema is a small subset and requires
-    // `type`, so we coerce/sanitize here for compatibility.
-    let mut serialized_input_schema = serde_json::to_value(input_schema)?;
-    sanitize_json_schema(&mut serialized_input_schema);
-    let input_schema = serde_json::from_value::<JsonSchema>(serialized_input_schema)?;
+/// Builds the tool registry builder while collecting tool specs for later serialization.
+pub(crate) fn build_specs(
+    config: &ToolsConfig,
+    mcp_tools: Option<HashMap<String, mcp_types::Tool>>,
+) -> ToolRegistryBuilder {
+    use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::BackgroundProcessHandler;
+    use crate::tools::handlers::BlameContextHandler;
+    use crate::tools::handlers::BrowserHandler;
+    use crate::tools::handlers::CodeOutlineHandler;
+    use crate::tools::handlers::CollabHandler;
+    use crate::tools::handlers::DefineHandler;
+    use crate::tools::handlers::DescribeToolHandler;
+    use crate::tools::handlers::FindSymbolHandler;
+    use crate::tools::handlers::GrepFilesHandler;
+    use crate::tools::handlers::ListDirHandler;
+    use crate::tools::handlers::McpHandler;
+    use crate::tools::handlers::McpResourceHandler;
+    use crate::tools::handlers::MemoryHandler;
+    use crate::tools::handlers::PlanHandler;
+    use crate::tools::handlers::PluginToolHandler;
+    use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::RequestUserInputHandler;
+    use crate::tools::handlers::ScanTodosHandler;
+    use crate::tools::handlers::SemanticSearchHandler;
+    use crate::tools::handlers::ShellCommandHandler;
+    use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::ShellSessionHandler;
+    use crate::tools::handlers::SubmitPlanHandler;
+    use crate::tools::handlers::SuggestFollowUpsHandler;
+    use crate::tools::handlers::TestSyncHandler;
+    use crate::tools::handlers::UnifiedExecHandler;
+    use crate::tools::handlers::ViewImageHandler;
+    use crate::tools::handlers::WebFetchHandler;
+    use crate::tools::handlers::WebSearchHandler;
 
-    Ok(ResponsesApiTool {
-        name: fully_qualified_name,
-        description: description.unwrap_or_default(),
-        strict: false,
-        parameters: input_schema,
-    })
-}
+    let mut builder = ToolRegistryBuilder::new();
 
-/// Sanitize a JSON Schema (as serde_json::Value) so it can fit our limited
-/// JsonSchema enum. This function:
-/// - Ensures every schema object has a "type". If missing, infers it from
-///   common keywords (properties => object, items => array, enum/const/format => string)
-///   and otherwise defaults to "string".
-/// - Fills required child fields (e.g. array items, object properties) with
-///   permissive defaults when absent.
-fn sanitize_json_schema(value: &mut JsonValue) {
-    match value {
-        JsonValue::Bool(_) => {
-            // JSON Schema boolean form: true/false. Coerce to an accept-all string.
-            *value = json!({ "type": "string" });
+    let shell_handler = Arc::new(ShellHandler);
+    let unified_exec_handler = Arc::new(UnifiedExecHandler);
+    let plan_handler = Arc::new(PlanHandler);
+    let apply_patch_handler = Arc::new(ApplyPatchHandler);
+    let view_image_handler = Arc::new(ViewImageHandler);
+    let mcp_handler = Arc::new(McpHandler);
+    let mcp_resource_handler = Arc::new(McpResourceHandler);
+    let shell_command_handler = Arc::new(ShellCommandHandler);
+    let request_user_input_handler = Arc::new(RequestUserInputHandler);
+
+    match &config.shell_type {
+        ConfigShellToolType::Default => {
+            builder.push_spec(create_shell_tool());
         }
-        JsonValue::Array(arr) => {
-            for v in arr.iter_mut() {
-                sanitize_json_schema(v);
+        ConfigShellToolType::Local => {
+            builder.push_spec(ToolSpec::LocalShell {});
+        }
+        ConfigShellToolType::UnifiedExec => {
+            builder.push_spec(create_exec_command_tool());
+            builder.push_spec(create_write_stdin_tool());
+            builder.register_handler("exec_command", unified_exec_handler.clone());
+            builder.register_handler("write_stdin", unified_exec_handler);
+        }
+        ConfigShellToolType::Disabled => {
+            // Do nothing.
+        }
+        ConfigShellToolType::ShellCommand => {
+            builder.push_spec(create_shell_command_tool());
+        }
+    }
+
+    if config.shell_type != ConfigShellToolType::Disabled {
+        // Always register shell aliases so older prompts remain compatible.
+        builder.register_handler("shell", shell_handler.clone());
+        builder.register_handler("container.exec", shell_handler.clone());
+        builder.register_handler("local_shell", shell_handler);
+        builder.register_handler("shell_command", shell_command_handler);
+
+        if config.shell_session_tool {
+            builder.push_spec(create_shell_session_tool());
+            builder.register_handler("shell_session", Arc::new(ShellSessionHandler));
+        }
+        if config.background_process_tool {
+            builder.push_spec(create_background_process_tool());
+            builder.register_handler("background_process", Arc::new(BackgroundProcessHandler));
+        }
+    }
+
+    builder.push_spec_with_parallel_support(create_list_mcp_resources_tool(), true);
+    builder.push_spec_with_parallel_support(create_list_mcp_resource_templates_tool(), true);
+    builder.push_spec_with_parallel_support(create_read_mcp_resource_tool(), true);
+    builder.register_handler("list_mcp_resources", mcp_resource_handler.clone());
+    builder.register_handler("list_mcp_resource_templates", mcp_resource_handler.clone());
+    builder.register_handler("read_mcp_resource", mcp_resource_handler);
+
+    builder.push_spec(PLAN_TOOL.clone());
+    builder.register_handler("update_plan", plan_handler);
+
+    if config.collaboration_modes_tools {
+        builder.push_spec(create_request_user_input_tool());
+        builder.register_handler("request_user_input", request_user_input_handler);
+    }
+
+    if config.plan_mode {
+        builder.push_spec(create_submit_plan_tool());
+        builder.register_handler("submit_plan", Arc::new(SubmitPlanHandler));
+    }
+
+    if let Some(apply_patch_tool_type) = &config.apply_patch_tool_type {
+        match apply_patch_tool_type {
+            ApplyPatchToolType::Freeform => {
+                builder.push_spec(create_apply_patch_freeform_tool());
+            }
+            ApplyPatchToolType::Function => {
+                builder.push_spec(create_apply_patch_json_tool());
             }
         }
-        JsonValue::Object(map) => {
-            // First, recursively sanitize known nested schema holders
-            if let Some(props) = map.get_mut("properties")
-                && let Some(props_map) = props.as_object_mut()
-            {
-                for (_k, v) in props_map.iter_mut() {
-                    sanitize_json_schema(v);
+        builder.register_handler("apply_patch", apply_patch_handler);
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"grep_files".to_string())
+    {
+        let grep_files_handler = Arc::new(GrepFilesHandler);
+        builder.push_spec_with_parallel_support(create_grep_files_tool(), true);
+        builder.register_handler("grep_files", grep_files_handler);
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"read_file".to_string())
+    {
+        let read_file_handler = Arc::new(ReadFileHandler);
+        builder.push_spec_with_parallel_support(create_read_file_tool(), true);
+        builder.register_handler("read_file", read_file_handler);
+    }
+
+    if config
+        .experimental_supported_tools
+        .iter()
+        .any(|tool| tool == "list_dir")
+    {
+        let list_dir_handler = Arc::new(ListDirHandler);
+        builder.push_spec_with_parallel_support(create_list_dir_tool(), true);
+        builder.register_handler("list_dir", list_dir_handler);
+    }
+
+    if config.blame_context_tool {
+        let blame_context_handler = Arc::new(BlameContextHandler);
+        builder.push_spec_with_parallel_support(create_blame_context_tool(), true);
+        builder.register_handler("blame_context", blame_context_handler);
+    }
+
+    if config.memory_tool {
+        let memory_handler = Arc::new(MemoryHandler);
+        builder.push_spec(create_memory_tool());
+        builder.register_handler("memory", memory_handler);
+    }
+
+    if config.define_tool {
+        let define_handler = Arc::new(DefineHandler);
+        builder.push_spec_with_parallel_support(create_define_tool(), true);
+        builder.register_handler("define", define_handler);
+    }
+
+    if config.semantic_search_tool {
+        let semantic_search_handler = Arc::new(SemanticSearchHandler);
+        builder.push_spec_with_parallel_support(create_semantic_search_tool(), true);
+        builder.register_handler("semantic_search", semantic_search_handler);
+    }
+
+    if config.code_navigation_tools {
+        let code_outline_handler = Arc::new(CodeOutlineHandler);
+        let find_symbol_handler = Arc::new(FindSymbolHandler);
+        builder.push_spec_with_parallel_support(create_code_outline_tool(), true);
+        builder.push_spec_with_parallel_support(create_find_symbol_tool(), true);
+        builder.register_handler("code_outline", code_outline_handler);
+        builder.register_handler("find_symbol", find_symbol_handler);
+    }
+
+    if config.scan_todos_tool {
+        let scan_todos_handler = Arc::new(ScanTodosHandler);
+        builder.push_spec_with_parallel_support(create_scan_todos_tool(), true);
+        builder.register_handler("scan_todos", scan_todos_handler);
+    }
+
+    if config.web_fetch_tool {
+        let web_fetch_handler = Arc::new(WebFetchHandler);
+        builder.push_spec_with_parallel_support(create_web_fetch_tool(), true);
+        builder.register_handler("web_fetch", web_fetch_handler);
+    }
+
+    if config.browser_tools {
+        // All browser tools drive the same page, so they run one at a time.
+        let browser_handler = Arc::new(BrowserHandler);
+        builder.push_spec(create_browser_navigate_tool());
+        builder.push_spec(create_browser_screenshot_tool());
+        builder.push_spec(create_browser_read_text_tool());
+        builder.push_spec(create_browser_click_tool());
+        builder.register_handler("browser_navigate", browser_handler.clone());
+        builder.register_handler("browser_screenshot", browser_handler.clone());
+        builder.register_handler("browser_read_text", browser_handler.clone());
+        builder.register_handler("browser_click", browser_handler);
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"test_sync_tool".to_string())
+    {
+        let test_sync_handler = Arc::new(TestSyncHandler);
+        builder.push_spec_with_parallel_support(create_test_sync_tool(), true);
+        builder.register_handler("test_sync_tool", test_sync_handler);
+    }
+
+    match config.web_search_mode {
+        Some(WebSearchMode::Disabled) => {}
+        _ if config.local_web_search => {
+            builder.push_spec_with_parallel_support(create_local_web_search_tool(), true);
+            builder.register_handler("web_search", Arc::new(WebSearchHandler));
+        }
+        Some(WebSearchMode::Cached) => {
+            builder.push_spec(ToolSpec::WebSearch {
+                external_web_access: Some(false),
+            });
+        }
+        Some(WebSearchMode::Live) => {
+            builder.push_spec(ToolSpec::WebSearch {
+                external_web_access: Some(true),
+            });
+        }
+        None => {}
+    }
+
+    if config.hosted_tools.code_interpreter {
+        builder.push_spec(ToolSpec::CodeInterpreter {
+            container: CodeInterpreterContainer {
+                r#type: "auto".to_string(),
+            },
+        });
+    }
+    if let Some(file_search) = &config.hosted_tools.file_search {
+        builder.push_spec(ToolSpec::FileSearch {
+            vector_store_ids: file_search.vector_store_ids.clone(),
+            max_num_results: file_search.max_num_results,
+        });
+    }
+
+    builder.push_spec_with_parallel_support(create_view_image_tool(), true);
+    builder.register_handler("view_image", view_image_handler);
+
+    if config.collab_tools {
+        let collab_handler = Arc::new(CollabHandler);
+        builder.push_spec(create_spawn_agent_tool());
+        builder.push_spec(create_send_input_tool());
+        builder.push_spec(create_wait_tool());
+        builder.push_spec(create_close_agent_tool());
+        builder.register_handler("spawn_agent", collab_handler.clone());
+        builder.register_handler("send_input", collab_handler.clone());
+        builder.register_handler("wait", collab_handler.clone());
+        builder.register_handler("close_agent", collab_handler);
+    }
+
+    if config.tool_trimming {
+        builder.push_spec_with_parallel_support(create_describe_tool_tool(), true);
+        builder.register_handler("describe_tool", Arc::new(DescribeToolHandler));
+    }
+
+    if config.follow_ups_tool {
+        builder.push_spec(create_suggest_follow_ups_tool());
+        builder.register_handler("suggest_follow_ups", Arc::new(SuggestFollowUpsHandler));
+    }
+
+    for plugin in &config.plugin_tools {
+        if builder.has_handler(&plugin.name) {
+            tracing::warn!("skipping plugin tool {:?}: name is taken", plugin.name);
+            continue;
+        }
+        match plugin_tool_to_openai_tool(plugin) {
+            Ok(tool) => {
+                builder.push_spec_with_parallel_support(
+                    ToolSpec::Function(tool),
+                    !plugin.is_mutating(),
+                );
+                builder.register_handler(
+                    plugin.name.clone(),
+                    Arc::new(PluginToolHandler::new(Arc::clone(plugin))),
+                );
+            }
+            Err(e) => {
+                tracing::error!("Failed to convert {:?} plugin tool: {e:?}", plugin.name);
+            }
+        }
+    }
+
+    if let Some(mcp_tools) = mcp_tools {
+        let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (name, tool) in entries.into_iter() {
+            match mcp_tool_to_openai_tool(name.clone(), tool.clone()) {
+                Ok(converted_tool) => {
+                    builder.push_spec(ToolSpec::Function(converted_tool));
+                    builder.register_handler(name, mcp_handler.clone());
                 }
-            }
-            if let Some(items) = map.get_mut("items") {
-                sanitize_json_schema(items);
-            }
-            // Some schemas use oneOf/anyOf/allOf - sanitize their entries
-            for combiner in ["oneOf", "anyOf", "allOf", "prefixItems"] {
-                if let Some(v) = map.get_mut(combiner) {
-                    sanitize_json_schema(v);
+                Err(e) => {
+                    tracing::error!("Failed to convert {name:?} MCP tool to OpenAI tool: {e:?}");
                 }
             }
+        }
+    }
 
-            // Normalize/ensure type
-            let mut ty = map.get("type").and_then(|v| v.as_str()).map(str::to_string);
+    builder
+}
 
-            // If type is an array (union), pick first supported; else leave to inference
-            if ty.is_none()
-                && let Some(JsonValue::Array(types)) = map.get("type")
-            {
-                for t in types {
-                    if let Some(tt) = t.as_str()
-                        && matches!(
-                            tt,
-                            "object" | "array" | "string" | "number" | "integer" | "boolean"
-                        )
-                    {
-                        ty = Some(tt.to_string());
-                        break;
-                    }
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use crate::client_common::tools::FreeformTool;
+    use crate::config::test_config;
+    use crate::config::types::HostedFileSearchToml;
+    use crate::models_manager::manager::ModelsManager;
+    use crate::tools::registry::ConfiguredToolSpec;
+    use mcp_types::ToolInputSchema;
+    use pretty_assertions::assert_eq;
 
-            // Infer type if still missing
-            if ty.is_none() {
-                if map.contains_key("properties")
-                    || map.contains_key("required")
-                    || map.contains_key("additionalProperties")
-                {
-                    ty = Some("object".to_string());
-                } else if map.contains_key("items") || map.contains_key("prefixItems") {
-                    ty = Some("array".to_string());
-                } else if map.contains_key("enum")
-                    || map.contains_key("const")
-                    || map.contains_key("format")
-                {
-                    ty = Some("string".to_string());
-                } else if map.contains_key("minimum")
-                    || map.contains_key("maximum")
-                    || map.contains_key("exclusiveMinimum")
-                    || map.contains_key("exclusiveMaximum")
-                    || map.contains_key("multipleOf")
-                {
-                    ty = Some("number".to_string());
-                }
+    use super::*;
+
+    fn tool_name(tool: &ToolSpec) -> &str {
+        match tool {
+            ToolSpec::Function(ResponsesApiTool { name, .. }) => name,
+            ToolSpec::LocalShell {} => "local_shell",
+            ToolSpec::WebSearch { .. } => "web_search",
+            ToolSpec::CodeInterpreter { .. } => "code_interpreter",
+            ToolSpec::FileSearch { .. } => "file_search",
+            ToolSpec::Freeform(FreeformTool { name, .. }) => name,
+        }
+    }
+
+    // Avoid order-based assertions; compare via set containment instead.
+    fn assert_contains_tool_names(tools: &[ConfiguredToolSpec], expected_subset: &[&str]) {
+        use std::collections::HashSet;
+        let mut names = HashSet::new();
+        let mut duplicates = Vec::new();
+        for name in tools.iter().map(|t| tool_name(&t.spec)) {
+            if !names.insert(name) {
+                duplicates.push(name);
             }
-            // If we still couldn't infer, default to string
-            let ty = ty.unwrap_or_else(|| "string".to_string());
-            map.insert("type".to_string(), JsonValue::String(ty.to_string()));
+        }
+        assert!(
+            duplicates.is_empty(),
+            "duplicate tool entries detected: {duplicates:?}"
+        );
+        for expected in expected_subset {
+            assert!(
+                names.contains(expected),
+                "expected tool {expected} to be present; had: {names:?}"
+            );
+        }
+    }
 
-            // Ensure object schemas have properties map
-            if ty == "object" {
-                if !map.contains_key("properties") {
-                    map.insert(
-                        "properties".to_string(),
-                        JsonValue::Object(serde_json::Map::new()),
-                    );
+    fn shell_tool_name(config: &ToolsConfig) -> Option<&'static str> {
+        match config.shell_type {
+            ConfigShellToolType::Default => Some("shell"),
+            ConfigShellToolType::Local => Some("local_shell"),
+            ConfigShellToolType::UnifiedExec => None,
+            ConfigShellToolType::Disabled => None,
+            ConfigShellToolType::ShellCommand => Some("shell_command"),
+        }
+    }
+
+    fn find_tool<'a>(
+        tools: &'a [ConfiguredToolSpec],
+        expected_name: &str,
+    ) -> &'a ConfiguredToolSpec {
+        tools
+            .iter()
+            .find(|tool| tool_name(&tool.spec) == expected_name)
+            .unwrap_or_else(|| panic!("expected tool {expected_name}"))
+    }
+
+    fn strip_descriptions_schema(schema: &mut JsonSchema) {
+        match schema {
+            JsonSchema::Boolean { description }
+            | JsonSchema::String { description }
+            | JsonSchema::Number { description } => {
+                *description = None;
+            }
+            JsonSchema::Array { items, description } => {
+                strip_descriptions_schema(items);
+                *description = None;
+            }
+            JsonSchema::Object {
+                properties,
+                required: _,
+                additional_properties,
+            } => {
+                for v in properties.values_mut() {
+                    strip_descriptions_schema(v);
                 }
-                // If additionalProperties is an object schema, sanitize it too.
-                // Leave booleans as-is, since JSON Schema allows boolean here.
-                if let Some(ap) = map.get_mut("additionalProperties") {
-                    let is_bool = matches!(ap, JsonValue::Bool(_));
-                    if !is_bool {
-                        sanitize_json_schema(ap);
-                    }
+                if let Some(AdditionalProperties::Schema(s)) = additional_properties {
+                    strip_descriptions_schema(s);
                 }
             }
+        }
+    }
 
-            // Ensure array schemas have items
-            if ty == "array" && !map.contains_key("items") {
-                map.insert("items".to_string(), json!({ "type": "string" }));
+    fn strip_descriptions_tool(spec: &mut ToolSpec) {
+        match spec {
+            ToolSpec::Function(ResponsesApiTool { parameters, .. }) => {
+                strip_descriptions_schema(parameters);
             }
+            ToolSpec::Freeform(_)
+            | ToolSpec::LocalShell {}
+            | ToolSpec::WebSearch { .. }
+            | ToolSpec::CodeInterpreter { .. }
+            | ToolSpec::FileSearch { .. } => {}
         }
-        _ => {}
     }
-}
-
-/// Builds the tool registry builder while collecting tool specs for later serialization.
-pub(crate) fn build_specs(
-    config: &ToolsConfig,
-    mcp_tools: Option<HashMap<String, mcp_types::Tool>>,
-) -> ToolRegistryBuilder {
-    use crate::tools::handlers::ApplyPatchHandler;
-    use crate::tools::handlers::CollabHandler;
-    use crate::tools::handlers::GrepFilesHandler;
-    use crate::tools::handlers::ListDirHandler;
-    use crate::tools::handlers::McpHandler;
-    use crate::tools::handlers::McpResourceHandler;
-    use crate::tools::handlers::PlanHandler;
-    use crate::tools::handlers::ReadFileHandler;
-    use crate::tools::handlers::RequestUserInputHandler;
-    use crate::tools::handlers::ShellCommandHandler;
-    use crate::tools::handlers::ShellHandler;
-    use crate::tools::handlers::TestSyncHandler;
-    use crate::tools::handlers::UnifiedExecHandler;
-    use crate::tools::handlers::ViewImageHandler;
-    use std::sync::Arc;
-
-    let mut builder = ToolRegistryBuilder::new();
 
-    let shell_handler = Arc::new(ShellHandler);
-    let unified_exec_handler = Arc::new(UnifiedExecHandler);
-    let plan_handler = Arc::new(PlanHandler);
-    let apply_patch_handler = Arc::new(ApplyPatchHandler);
-    let view_image_handler = Arc::new(ViewImageHandler);
-    let mcp_handler = Arc::new(McpHandler);
-    let mcp_resource_handler = Arc::new(McpResourceHandler);
-    let shell_command_handler = Arc::new(ShellCommandHandler);
-    let request_user_input_handler = Arc::new(RequestUserInputHandler);
+    #[test]
+    fn test_full_toolset_specs_for_gpt5_codex_unified_exec_web_search() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        features.enable(Feature::UnifiedExec);
+        features.enable(Feature::CollaborationModes);
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Live),
+        });
+        let (tools, _) = build_specs(&config, None).build();
 
-    match &config.shell_type {
-        ConfigShellToolType::Default => {
-            builder.push_spec(create_shell_tool());
-        }
-        ConfigShellToolType::Local => {
-            builder.push_spec(ToolSpec::LocalShell {});
-        }
-        ConfigShellToolType::UnifiedExec => {
-            builder.push_spec(create_exec_command_tool());
-            builder.push_spec(create_write_stdin_tool());
-            builder.register_handler("exec_command", unified_exec_handler.clone());
-            builder.register_handler("write_stdin", unified_exec_handler);
+        // Build actual map name -> spec
+        use std::collections::BTreeMap;
+        use std::collections::HashSet;
+        let mut actual: BTreeMap<String, ToolSpec> = BTreeMap::from([]);
+        let mut duplicate_names = Vec::new();
+        for t in &tools {
+            let name = tool_name(&t.spec).to_string();
+            if actual.insert(name.clone(), t.spec.clone()).is_some() {
+                duplicate_names.push(name);
+            }
         }
-        ConfigShellToolType::Disabled => {
-            // Do nothing.
+        assert!(
+            duplicate_names.is_empty(),
+            "duplicate tool entries detected: {duplicate_names:?}"
+        );
+
+        // Build expected from the same helpers used by the builder.
+        let mut expected: BTreeMap<String, ToolSpec> = BTreeMap::from([]);
+        for spec in [
+            create_exec_command_tool(),
+            create_write_stdin_tool(),
+            create_list_mcp_resources_tool(),
+            create_list_mcp_resource_templates_tool(),
+            create_read_mcp_resource_tool(),
+            PLAN_TOOL.clone(),
+            create_request_user_input_tool(),
+            create_apply_patch_freeform_tool(),
+            ToolSpec::WebSearch {
+                external_web_access: Some(true),
+            },
+            create_view_image_tool(),
+        ] {
+            expected.insert(tool_name(&spec).to_string(), spec);
         }
-        ConfigShellToolType::ShellCommand => {
-            builder.push_spec(create_shell_command_tool());
+
+        // Exact name set match — this is the only test allowed to fail when tools change.
+        let actual_names: HashSet<_> = actual.keys().cloned().collect();
+        let expected_names: HashSet<_> = expected.keys().cloned().collect();
+        assert_eq!(actual_names, expected_names, "tool name set mismatch");
+
+        // Compare specs ignoring human-readable descriptions.
+        for name in expected.keys() {
+            let mut a = actual.get(name).expect("present").clone();
+            let mut e = expected.get(name).expect("present").clone();
+            strip_descriptions_tool(&mut a);
+            strip_descriptions_tool(&mut e);
+            assert_eq!(a, e, "spec mismatch for {name}");
         }
     }
 
-    if config.shell_type != ConfigShellToolType::Disabled {
-        // Always register shell aliases so older prompts remain compatible.
-        builder.register_handler("shell", shell_handler.clone());
-        builder.register_handler("container.exec", shell_handler.clone());
-        builder.register_handler("local_shell", shell_handler);
-        builder.register_handler("shell_command", shell_command_handler);
+    #[test]
+    fn test_build_specs_collab_tools_enabled() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        features.enable(Feature::Collab);
+        features.enable(Feature::CollaborationModes);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(
+            &tools,
+            &["spawn_agent", "send_input", "wait", "close_agent"],
+        );
     }
 
-    builder.push_spec_with_parallel_support(create_list_mcp_resources_tool(), true);
-    builder.push_spec_with_parallel_support(create_list_mcp_resource_templates_tool(), true);
-    builder.push_spec_with_parallel_support(create_read_mcp_resource_tool(), true);
-    builder.register_handler("list_mcp_resources", mcp_resource_handler.clone());
-    builder.register_handler("list_mcp_resource_templates", mcp_resource_handler.clone());
-    builder.register_handler("read_mcp_resource", mcp_resource_handler);
-
-    builder.push_spec(PLAN_TOOL.clone());
-    builder.register_handler("update_plan", plan_handler);
+    #[test]
+    fn request_user_input_requires_collaboration_modes_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        features.disable(Feature::CollaborationModes);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(
+            !tools.iter().any(|t| t.spec.name() == "request_user_input"),
+            "request_user_input should be disabled when collaboration_modes feature is off"
+        );
 
-    if config.collaboration_modes_tools {
-        builder.push_spec(create_request_user_input_tool());
-        builder.register_handler("request_user_input", request_user_input_handler);
+        features.enable(Feature::CollaborationModes);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(&tools, &["request_user_input"]);
     }
 
-    if let Some(apply_patch_tool_type) = &config.apply_patch_tool_type {
-        match apply_patch_tool_type {
-            ApplyPatchToolType::Freeform => {
-                builder.push_spec(create_apply_patch_freeform_tool());
-            }
-            ApplyPatchToolType::Function => {
-                builder.push_spec(create_apply_patch_json_tool());
-            }
-        }
-        builder.register_handler("apply_patch", apply_patch_handler);
-    }
+    #[test]
+    fn submit_plan_only_in_plan_mode() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let features = Features::with_defaults();
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(
+            !tools.iter().any(|t| t.spec.name() == "submit_plan"),
+            "submit_plan should only be exposed in plan mode"
+        );
 
-    if config
-        .experimental_supported_tools
-        .contains(&"grep_files".to_string())
-    {
-        let grep_files_handler = Arc::new(GrepFilesHandler);
-        builder.push_spec_with_parallel_support(create_grep_files_tool(), true);
-        builder.register_handler("grep_files", grep_files_handler);
+        tools_config.plan_mode = true;
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(&tools, &["submit_plan"]);
     }
 
-    if config
-        .experimental_supported_tools
-        .contains(&"read_file".to_string())
-    {
-        let read_file_handler = Arc::new(ReadFileHandler);
-        builder.push_spec_with_parallel_support(create_read_file_tool(), true);
-        builder.register_handler("read_file", read_file_handler);
-    }
+    #[test]
+    fn blame_context_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(
+            !tools.iter().any(|t| t.spec.name() == "blame_context"),
+            "blame_context should be disabled by default"
+        );
 
-    if config
-        .experimental_supported_tools
-        .iter()
-        .any(|tool| tool == "list_dir")
-    {
-        let list_dir_handler = Arc::new(ListDirHandler);
-        builder.push_spec_with_parallel_support(create_list_dir_tool(), true);
-        builder.register_handler("list_dir", list_dir_handler);
+        features.enable(Feature::BlameContext);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(&tools, &["blame_context"]);
     }
 
-    if config
-        .experimental_supported_tools
-        .contains(&"test_sync_tool".to_string())
-    {
-        let test_sync_handler = Arc::new(TestSyncHandler);
-        builder.push_spec_with_parallel_support(create_test_sync_tool(), true);
-        builder.register_handler("test_sync_tool", test_sync_handler);
-    }
+    #[test]
+    fn memory_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(
+            !tools.iter().any(|t| t.spec.name() == "memory"),
+            "memory should be disabled by default"
+        );
 
-    match config.web_search_mode {
-        Some(WebSearchMode::Cached) => {
-            builder.push_spec(ToolSpec::WebSearch {
-                external_web_access: Some(false),
-            });
-        }
-        Some(WebSearchMode::Live) => {
-            builder.push_spec(ToolSpec::WebSearch {
-                external_web_access: Some(true),
-            });
-        }
-        Some(WebSearchMode::Disabled) | None => {}
+        features.enable(Feature::ProjectMemory);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(&tools, &["memory"]);
     }
 
-    builder.push_spec_with_parallel_support(create_view_image_tool(), true);
-    builder.register_handler("view_image", view_image_handler);
+    #[test]
+    fn define_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(
+            !tools.iter().any(|t| t.spec.name() == "define"),
+            "define should be disabled by default"
+        );
 
-    if config.collab_tools {
-        let collab_handler = Arc::new(CollabHandler);
-        builder.push_spec(create_spawn_agent_tool());
-        builder.push_spec(create_send_input_tool());
-        builder.push_spec(create_wait_tool());
-        builder.push_spec(create_close_agent_tool());
-        builder.register_handler("spawn_agent", collab_handler.clone());
-        builder.register_handler("send_input", collab_handler.clone());
-        builder.register_handler("wait", collab_handler.clone());
-        builder.register_handler("close_agent", collab_handler);
+        features.enable(Feature::ProjectGlossary);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(&tools, &["define"]);
     }
 
-    if let Some(mcp_tools) = mcp_tools {
-        let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
-        entries.sort_by(|a, b| a.0.cmp(&b.0));
+    #[test]
+    fn semantic_search_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(
+            !tools.iter().any(|t| t.spec.name() == "semantic_search"),
+            "semantic_search should be disabled by default"
+        );
 
-        for (name, tool) in entries.into_iter() {
-            match mcp_tool_to_openai_tool(name.clone(), tool.clone()) {
-                Ok(converted_tool) => {
-                    builder.push_spec(ToolSpec::Function(converted_tool));
-                    builder.register_handler(name, mcp_handler.clone());
-                }
-                Err(e) => {
-                    tracing::error!("Failed to convert {name:?} MCP tool to OpenAI tool: {e:?}");
-                }
-            }
-        }
+        features.enable(Feature::SemanticSearch);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(&tools, &["semantic_search"]);
     }
 
-    builder
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::client_common::tools::FreeformTool;
-    use crate::config::test_config;
-    use crate::models_manager::manager::ModelsManager;
-    use crate::tools::registry::ConfiguredToolSpec;
-    use mcp_types::ToolInputSchema;
-    use pretty_assertions::assert_eq;
-
-    use super::*;
+    #[test]
+    fn code_navigation_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(
+            !tools
+                .iter()
+                .any(|t| matches!(t.spec.name(), "code_outline" | "find_symbol")),
+            "code navigation tools should be disabled by default"
+        );
 
-    fn tool_name(tool: &ToolSpec) -> &str {
-        match tool {
-            ToolSpec::Function(ResponsesApiTool { name, .. }) => name,
-            ToolSpec::LocalShell {} => "local_shell",
-            ToolSpec::WebSearch { .. } => "web_search",
-            ToolSpec::Freeform(FreeformTool { name, .. }) => name,
-        }
+        features.enable(Feature::CodeNavigation);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(&tools, &["code_outline", "find_symbol"]);
     }
 
-    // Avoid order-based assertions; compare via set containment instead.
-    fn assert_contains_tool_names(tools: &[ConfiguredToolSpec], expected_subset: &[&str]) {
-        use std::collections::HashSet;
-        let mut names = HashSet::new();
-        let mut duplicates = Vec::new();
-        for name in tools.iter().map(|t| tool_name(&t.spec)) {
-            if !names.insert(name) {
-                duplicates.push(name);
-            }
-        }
+    #[test]
+    fn scan_todos_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
         assert!(
-            duplicates.is_empty(),
-            "duplicate tool entries detected: {duplicates:?}"
+            !tools.iter().any(|t| t.spec.name() == "scan_todos"),
+            "scan_todos should be disabled by default"
         );
-        for expected in expected_subset {
-            assert!(
-                names.contains(expected),
-                "expected tool {expected} to be present; had: {names:?}"
-            );
-        }
-    }
 
-    fn shell_tool_name(config: &ToolsConfig) -> Option<&'static str> {
-        match config.shell_type {
-            ConfigShellToolType::Default => Some("shell"),
-            ConfigShellToolType::Local => Some("local_shell"),
-            ConfigShellToolType::UnifiedExec => None,
-            ConfigShellToolType::Disabled => None,
-            ConfigShellToolType::ShellCommand => Some("shell_command"),
-        }
+        features.enable(Feature::ScanTodos);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(&tools, &["scan_todos"]);
     }
 
-    fn find_tool<'a>(
-        tools: &'a [ConfiguredToolSpec],
-        expected_name: &str,
-    ) -> &'a ConfiguredToolSpec {
-        tools
+    #[test]
+    fn plugin_tools_are_registered_unless_name_is_taken() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let features = Features::with_defaults();
+        let plugin = |name: &str| {
+            Arc::new(PluginTool {
+                name: name.to_string(),
+                description: "Checks migrations".to_string(),
+                runner: crate::plugin_tools::PluginRunner::Command(vec!["check".to_string()]),
+                input_schema: json!({ "type": "object" }),
+                output_schema: Some(json!({ "type": "object" })),
+                timeout: std::time::Duration::from_secs(1),
+                sandbox_mode: codex_protocol::config_types::SandboxMode::ReadOnly,
+                network_access: false,
+                path: std::path::PathBuf::from("check_migration.toml"),
+            })
+        };
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        tools_config.plugin_tools = vec![plugin("check_migration"), plugin("update_plan")];
+        let (tools, _) = build_specs(&tools_config, None).build();
+
+        let check = tools
             .iter()
-            .find(|tool| tool_name(&tool.spec) == expected_name)
-            .unwrap_or_else(|| panic!("expected tool {expected_name}"))
+            .find(|t| t.spec.name() == "check_migration")
+            .expect("plugin tool registered");
+        assert!(check.supports_parallel_tool_calls);
+        assert_eq!(
+            check.spec,
+            ToolSpec::Function(ResponsesApiTool {
+                name: "check_migration".to_string(),
+                description:
+                    "Checks migrations\n\nReturns JSON matching this schema: {\"type\":\"object\"}"
+                        .to_string(),
+                strict: false,
+                parameters: JsonSchema::Object {
+                    properties: BTreeMap::new(),
+                    required: None,
+                    additional_properties: None,
+                },
+            })
+        );
+        // `update_plan` is built in, so the plugin with that name is skipped.
+        assert_contains_tool_names(&tools, &["update_plan"]);
     }
 
-    fn strip_descriptions_schema(schema: &mut JsonSchema) {
-        match schema {
-            JsonSchema::Boolean { description }
-            | JsonSchema::String { description }
-            | JsonSchema::Number { description } => {
-                *description = None;
-            }
-            JsonSchema::Array { items, description } => {
-                strip_descriptions_schema(items);
-                *description = None;
-            }
-            JsonSchema::Object {
-                properties,
-                required: _,
-                additional_properties,
-            } => {
-                for v in properties.values_mut() {
-                    strip_descriptions_schema(v);
-                }
-                if let Some(AdditionalProperties::Schema(s)) = additional_properties {
-                    strip_descriptions_schema(s);
-                }
-            }
-        }
-    }
+    #[test]
+    fn web_fetch_requires_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(
+            !tools.iter().any(|t| t.spec.name() == "web_fetch"),
+            "web_fetch should be disabled by default"
+        );
 
-    fn strip_descriptions_tool(spec: &mut ToolSpec) {
-        match spec {
-            ToolSpec::Function(ResponsesApiTool { parameters, .. }) => {
-                strip_descriptions_schema(parameters);
-            }
-            ToolSpec::Freeform(_) | ToolSpec::LocalShell {} | ToolSpec::WebSearch { .. } => {}
-        }
+        features.enable(Feature::WebFetch);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(&tools, &["web_fetch"]);
     }
 
     #[test]
-    fn test_full_toolset_specs_for_gpt5_codex_unified_exec_web_search() {
+    fn local_web_search_replaces_hosted_tool() {
         let config = test_config();
         let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
-        let mut features = Features::with_defaults();
-        features.enable(Feature::UnifiedExec);
-        features.enable(Feature::CollaborationModes);
-        let config = ToolsConfig::new(&ToolsConfigParams {
+        let features = Features::with_defaults();
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Live),
         });
-        let (tools, _) = build_specs(&config, None).build();
+        tools_config.local_web_search = true;
+        let (tools, _) = build_specs(&tools_config, None).build();
+        let web_search = tools
+            .iter()
+            .find(|tool| tool.spec.name() == "web_search")
+            .expect("web_search tool");
+        assert!(matches!(web_search.spec, ToolSpec::Function(_)));
 
-        // Build actual map name -> spec
-        use std::collections::BTreeMap;
-        use std::collections::HashSet;
-        let mut actual: BTreeMap<String, ToolSpec> = BTreeMap::from([]);
-        let mut duplicate_names = Vec::new();
-        for t in &tools {
-            let name = tool_name(&t.spec).to_string();
-            if actual.insert(name.clone(), t.spec.clone()).is_some() {
-                duplicate_names.push(name);
-            }
-        }
+        tools_config.web_search_mode = Some(WebSearchMode::Disabled);
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(!tools.iter().any(|t| t.spec.name() == "web_search"));
+    }
+
+    #[test]
+    fn browser_tools_require_browser_config() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let features = Features::with_defaults();
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
         assert!(
-            duplicate_names.is_empty(),
-            "duplicate tool entries detected: {duplicate_names:?}"
+            !tools.iter().any(|t| t.spec.name().starts_with("browser_")),
+            "browser tools should be disabled by default"
         );
 
-        // Build expected from the same helpers used by the builder.
-        let mut expected: BTreeMap<String, ToolSpec> = BTreeMap::from([]);
-        for spec in [
-            create_exec_command_tool(),
-            create_write_stdin_tool(),
-            create_list_mcp_resources_tool(),
-            create_list_mcp_resource_templates_tool(),
-            create_read_mcp_resource_tool(),
-            PLAN_TOOL.clone(),
-            create_request_user_input_tool(),
-            create_apply_patch_freeform_tool(),
-            ToolSpec::WebSearch {
-                external_web_access: Some(true),
-            },
-            create_view_image_tool(),
-        ] {
-            expected.insert(tool_name(&spec).to_string(), spec);
-        }
-
-        // Exact name set match — this is the only test allowed to fail when tools change.
-        let actual_names: HashSet<_> = actual.keys().cloned().collect();
-        let expected_names: HashSet<_> = expected.keys().cloned().collect();
-        assert_eq!(actual_names, expected_names, "tool name set mismatch");
-
-        // Compare specs ignoring human-readable descriptions.
-        for name in expected.keys() {
-            let mut a = actual.get(name).expect("present").clone();
-            let mut e = expected.get(name).expect("present").clone();
-            strip_descriptions_tool(&mut a);
-            strip_descriptions_tool(&mut e);
-            assert_eq!(a, e, "spec mismatch for {name}");
-        }
+        tools_config.browser_tools = true;
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(
+            &tools,
+            &[
+                "browser_navigate",
+                "browser_screenshot",
+                "browser_read_text",
+                "browser_click",
+            ],
+        );
     }
 
     #[test]
-    fn test_build_specs_collab_tools_enabled() {
+    fn shell_session_requires_feature() {
         let config = test_config();
         let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
         let mut features = Features::with_defaults();
-        features.enable(Feature::Collab);
-        features.enable(Feature::CollaborationModes);
         let tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
         });
         let (tools, _) = build_specs(&tools_config, None).build();
-        assert_contains_tool_names(
-            &tools,
-            &["spawn_agent", "send_input", "wait", "close_agent"],
+        assert!(
+            !tools.iter().any(|t| t.spec.name() == "shell_session"),
+            "shell_session should be disabled by default"
         );
+
+        features.enable(Feature::ShellSession);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert_contains_tool_names(&tools, &["shell_session"]);
     }
 
     #[test]
-    fn request_user_input_requires_collaboration_modes_feature() {
+    fn background_process_requires_feature() {
         let config = test_config();
         let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
         let mut features = Features::with_defaults();
-        features.disable(Feature::CollaborationModes);
         let tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_info: &model_info,
             features: &features,
@@ -1580,18 +2952,95 @@ mod tests {
         });
         let (tools, _) = build_specs(&tools_config, None).build();
         assert!(
-            !tools.iter().any(|t| t.spec.name() == "request_user_input"),
-            "request_user_input should be disabled when collaboration_modes feature is off"
+            !tools.iter().any(|t| t.spec.name() == "background_process"),
+            "background_process should be disabled by default"
         );
 
-        features.enable(Feature::CollaborationModes);
+        features.enable(Feature::BackgroundProcesses);
         let tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_info: &model_info,
             features: &features,
             web_search_mode: Some(WebSearchMode::Cached),
         });
         let (tools, _) = build_specs(&tools_config, None).build();
-        assert_contains_tool_names(&tools, &["request_user_input"]);
+        assert_contains_tool_names(&tools, &["background_process"]);
+    }
+
+    #[test]
+    fn gemini_schema_keeps_only_supported_keys() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "command": {"type": "array", "items": {"type": "string", "default": "ls"}},
+                "workdir": {"type": "string", "description": "cwd"},
+            },
+            "required": ["command"],
+            "additionalProperties": false,
+        });
+
+        assert_eq!(
+            gemini_schema(&schema),
+            json!({
+                "type": "object",
+                "properties": {
+                    "command": {"type": "array", "items": {"type": "string"}},
+                    "workdir": {"type": "string", "description": "cwd"},
+                },
+                "required": ["command"],
+            })
+        );
+    }
+
+    #[test]
+    fn hosted_tools_are_only_sent_to_responses_api() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let features = Features::with_defaults();
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: None,
+        });
+        tools_config.hosted_tools = HostedToolsToml {
+            code_interpreter: true,
+            file_search: Some(HostedFileSearchToml {
+                vector_store_ids: vec!["vs_1".to_string()],
+                max_num_results: None,
+            }),
+        };
+        let (tools, _) = build_specs(&tools_config, None).build();
+        let specs: Vec<ToolSpec> = tools.into_iter().map(|tool| tool.spec).collect();
+
+        let responses = create_tools_json_for_responses_api(&specs).expect("responses tools");
+        assert!(responses.contains(&json!({
+            "type": "code_interpreter",
+            "container": { "type": "auto" },
+        })));
+        assert!(responses.contains(&json!({
+            "type": "file_search",
+            "vector_store_ids": ["vs_1"],
+        })));
+
+        let chat = create_tools_json_for_chat_completions_api(&specs).expect("chat tools");
+        assert!(
+            chat.iter()
+                .all(|tool| tool["name"] != "code_interpreter" && tool["name"] != "file_search")
+        );
+
+        let gemini = create_tools_json_for_gemini_api(&specs).expect("gemini tools");
+        assert!(
+            gemini
+                .iter()
+                .all(|tool| tool["name"] != "code_interpreter" && tool["name"] != "file_search")
+        );
+
+        let anthropic = create_tools_json_for_anthropic_api(&specs).expect("anthropic tools");
+        assert!(!anthropic.is_empty());
+        assert!(anthropic.iter().all(|tool| {
+            tool["name"] != "code_interpreter"
+                && tool["name"] != "file_search"
+                && tool["input_schema"]["type"] == "object"
+        }));
     }
 
     fn assert_model_tools(
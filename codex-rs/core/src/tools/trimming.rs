@@ -0,0 +1,193 @@
+//! `tool_trimming`: MCP tools that do not look relevant to the current
+//! prompt are sent by name only, and the model fetches a schema with
+//! `describe_tool` when it needs one.
+//!
+//! Relevance is a cheap word match: a tool keeps its schema when the prompt
+//! mentions its server or a word of its name, or shares a few words with its
+//! description. Tools described once keep their schema for the rest of the
+//! session.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use codex_protocol::protocol::ToolTrimmingStats;
+use tokio::sync::Mutex;
+
+use crate::client_common::tools::ResponsesApiTool;
+use crate::client_common::tools::ToolSpec;
+use crate::mcp_connection_manager::ToolInfo;
+use crate::tools::router::ToolRouter;
+use crate::tools::spec::JsonSchema;
+use crate::truncate::approx_token_count;
+
+/// Description words a tool must share with the prompt to keep its schema.
+const MIN_DESCRIPTION_MATCHES: usize = 2;
+
+/// Words too common in prompts and tool descriptions to signal relevance.
+const STOPWORDS: &[&str] = &[
+    "about", "all", "and", "any", "are", "can", "create", "file", "for", "from", "get", "how",
+    "into", "its", "list", "make", "new", "not", "one", "please", "read", "set", "that", "the",
+    "then", "this", "tool", "use", "using", "via", "was", "what", "when", "which", "will", "with",
+    "you", "your",
+];
+
+#[derive(Default)]
+pub(crate) struct ToolTrimming {
+    /// Tools already described via `describe_tool`.
+    expanded: Mutex<HashSet<String>>,
+}
+
+impl ToolTrimming {
+    pub(crate) async fn expand(&self, name: &str) {
+        self.expanded.lock().await.insert(name.to_string());
+    }
+
+    /// Replace the specs of MCP tools that look irrelevant to `prompt` with
+    /// name-only stubs, and record what was saved on `router`.
+    pub(crate) async fn trim(
+        &self,
+        router: &mut ToolRouter,
+        mcp_tools: &HashMap<String, ToolInfo>,
+        prompt: &str,
+    ) {
+        let expanded = self.expanded.lock().await;
+        let prompt_words = words(prompt);
+        let mut stats = ToolTrimmingStats::default();
+        for (name, info) in mcp_tools {
+            let description = info.tool.description.as_deref().unwrap_or_default();
+            if expanded.contains(name)
+                || is_relevant(
+                    &prompt_words,
+                    &info.server_name,
+                    &info.tool_name,
+                    description,
+                )
+            {
+                stats.full_tools += 1;
+                continue;
+            }
+            let stub = stub_spec(name);
+            let stub_tokens = spec_tokens(&stub);
+            if let Some(full) = router.replace_spec(name, stub) {
+                stats.trimmed_tools += 1;
+                stats.saved_tokens += (spec_tokens(&full) - stub_tokens).max(0);
+            }
+        }
+        router.set_tool_trimming(stats);
+    }
+}
+
+fn is_relevant(
+    prompt_words: &HashSet<String>,
+    server_name: &str,
+    tool_name: &str,
+    description: &str,
+) -> bool {
+    let mut name_words = words(server_name);
+    name_words.extend(words(tool_name));
+    if !name_words.is_disjoint(prompt_words) {
+        return true;
+    }
+    words(description).intersection(prompt_words).count() >= MIN_DESCRIPTION_MATCHES
+}
+
+/// Lowercased words of `text`, split at non-alphanumerics, without
+/// stopwords or a plural `s`. A camelCase word also contributes its parts.
+fn words(text: &str) -> HashSet<String> {
+    let mut words = HashSet::new();
+    for run in text.split(|c: char| !c.is_alphanumeric()) {
+        let mut part = String::new();
+        let mut prev_lower = false;
+        let mut is_camel_case = false;
+        for c in run.chars() {
+            if c.is_uppercase() && prev_lower {
+                push_word(&mut words, std::mem::take(&mut part));
+                is_camel_case = true;
+            }
+            part.extend(c.to_lowercase());
+            prev_lower = c.is_lowercase();
+        }
+        if is_camel_case {
+            push_word(&mut words, part);
+            push_word(&mut words, run.to_lowercase());
+        } else {
+            push_word(&mut words, part);
+        }
+    }
+    words
+}
+
+fn push_word(words: &mut HashSet<String>, word: String) {
+    if word.chars().count() < 3 || STOPWORDS.contains(&word.as_str()) {
+        return;
+    }
+    let word = match word.strip_suffix('s') {
+        Some(stem) if stem.chars().count() >= 3 && !stem.ends_with('s') => stem.to_string(),
+        _ => word,
+    };
+    words.insert(word);
+}
+
+fn stub_spec(name: &str) -> ToolSpec {
+    ToolSpec::Function(ResponsesApiTool {
+        name: name.to_string(),
+        description: "Parameters omitted; call `describe_tool` with this name before using it."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties: BTreeMap::new(),
+            required: None,
+            additional_properties: None,
+        },
+    })
+}
+
+fn spec_tokens(spec: &ToolSpec) -> i64 {
+    serde_json::to_string(spec)
+        .map(|json| approx_token_count(&json) as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn sorted(words: HashSet<String>) -> Vec<String> {
+        let mut words: Vec<String> = words.into_iter().collect();
+        words.sort();
+        words
+    }
+
+    #[test]
+    fn words_split_names_and_drop_noise() {
+        assert_eq!(
+            sorted(words("createPullRequest for the github__list_issues tool")),
+            vec!["createpullrequest", "github", "issue", "pull", "request"]
+        );
+        assert_eq!(
+            sorted(words("Check the issues of class")),
+            vec!["check", "class", "issue"]
+        );
+    }
+
+    #[test]
+    fn relevance_matches_server_name_or_description() {
+        let prompt = words("Open a pull request for this branch on GitHub");
+        assert!(is_relevant(&prompt, "github", "merge", ""));
+        assert!(is_relevant(&prompt, "scm", "createPullRequest", ""));
+        assert!(is_relevant(
+            &prompt,
+            "scm",
+            "new_pr",
+            "Opens a pull request from a branch."
+        ));
+        assert!(!is_relevant(
+            &prompt,
+            "slack",
+            "post_message",
+            "Posts a message to a channel, e.g. after a request."
+        ));
+    }
+}
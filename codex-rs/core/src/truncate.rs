@@ -8,6 +8,11 @@ use codex_protocol::openai_models::TruncationPolicyConfig;
 use codex_protocol::protocol::TruncationPolicy as ProtocolTruncationPolicy;
 
 const APPROX_BYTES_PER_TOKEN: usize = 4;
+/// Share of the remaining context window that pending tool outputs may use
+/// under adaptive truncation; the rest is left for the model's reply.
+const ADAPTIVE_OUTPUT_SHARE: f64 = 0.5;
+/// Adaptive truncation never cuts an output below this many tokens.
+const MIN_ADAPTIVE_OUTPUT_TOKENS: usize = 500;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TruncationPolicy {
@@ -73,6 +78,27 @@ impl TruncationPolicy {
     }
 }
 
+/// Shrinks `policy` so the outputs of the `pending_calls` tool calls still
+/// outstanding in this turn split half of `remaining_tokens` evenly. The
+/// result keeps the unit of `policy` and never exceeds it, so early calls
+/// cannot crowd later ones out of the window.
+pub(crate) fn budgeted_truncation_policy(
+    policy: TruncationPolicy,
+    remaining_tokens: i64,
+    pending_calls: usize,
+) -> TruncationPolicy {
+    let remaining = usize::try_from(remaining_tokens).unwrap_or(0);
+    let share = (remaining as f64 * ADAPTIVE_OUTPUT_SHARE) as usize / pending_calls.max(1);
+    let tokens = share.max(MIN_ADAPTIVE_OUTPUT_TOKENS);
+    if tokens >= policy.token_budget() {
+        return policy;
+    }
+    match policy {
+        TruncationPolicy::Bytes(_) => TruncationPolicy::Bytes(approx_bytes_for_tokens(tokens)),
+        TruncationPolicy::Tokens(_) => TruncationPolicy::Tokens(tokens),
+    }
+}
+
 pub(crate) fn formatted_truncate_text(content: &str, policy: TruncationPolicy) -> String {
     if content.len() <= policy.byte_budget() {
         return content.to_string();
@@ -463,6 +489,25 @@ mod tests {
         assert_eq!(out, "😀😀…21 chars truncated…with text\n");
     }
 
+    #[test]
+    fn budgeted_policy_splits_remaining_window_across_pending_calls() {
+        let policy = TruncationPolicy::Tokens(10_000);
+
+        assert_eq!(budgeted_truncation_policy(policy, 200_000, 1), policy);
+        assert_eq!(
+            budgeted_truncation_policy(policy, 40_000, 4),
+            TruncationPolicy::Tokens(5_000)
+        );
+        assert_eq!(
+            budgeted_truncation_policy(TruncationPolicy::Bytes(40_000), 40_000, 4),
+            TruncationPolicy::Bytes(20_000)
+        );
+        assert_eq!(
+            budgeted_truncation_policy(policy, -100, 3),
+            TruncationPolicy::Tokens(MIN_ADAPTIVE_OUTPUT_TOKENS)
+        );
+    }
+
     #[test]
     fn truncates_across_multiple_under_limit_texts_and_reports_omitted() {
         let chunk = "alpha beta gamma delta epsilon zeta eta theta iota kappa lambda mu nu xi omicron pi rho sigma tau upsilon phi chi psi omega.\n";
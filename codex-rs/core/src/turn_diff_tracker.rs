@@ -10,7 +10,11 @@ use anyhow::anyhow;
 use sha1::digest::Output;
 use uuid::Uuid;
 
+use crate::protocol::DiffHunk;
 use crate::protocol::FileChange;
+use crate::protocol::FileDiff;
+use crate::protocol::FileDiffStatus;
+use crate::protocol::TurnDiffEvent;
 
 const ZERO_OID: &str = "0000000000000000000000000000000000000000";
 const DEV_NULL: &str = "/dev/null";
@@ -223,7 +227,15 @@ impl TurnDiffTracker {
     /// collected before the first time they were touched by apply_patch during this turn with
     /// the current repo state.
     pub fn get_unified_diff(&mut self) -> Result<Option<String>> {
+        Ok(self.get_turn_diff()?.map(|event| event.unified_diff))
+    }
+
+    /// Like [`Self::get_unified_diff`], but also returns the per-file status,
+    /// hunk ranges and before/after blob ids so clients do not have to parse
+    /// the diff text.
+    pub fn get_turn_diff(&mut self) -> Result<Option<TurnDiffEvent>> {
         let mut aggregated = String::new();
+        let mut files = Vec::new();
 
         // Compute diffs per tracked internal file in a stable order by external path.
         let mut baseline_file_names: Vec<String> =
@@ -236,20 +248,25 @@ impl TurnDiffTracker {
         });
 
         for internal in baseline_file_names {
-            aggregated.push_str(self.get_file_diff(&internal).as_str());
+            let (file_diff, file) = self.get_file_diff(&internal);
+            aggregated.push_str(file_diff.as_str());
             if !aggregated.ends_with('\n') {
                 aggregated.push('\n');
             }
+            files.extend(file);
         }
 
         if aggregated.trim().is_empty() {
             Ok(None)
         } else {
-            Ok(Some(aggregated))
+            Ok(Some(TurnDiffEvent {
+                unified_diff: aggregated,
+                files,
+            }))
         }
     }
 
-    fn get_file_diff(&mut self, internal_file_name: &str) -> String {
+    fn get_file_diff(&mut self, internal_file_name: &str) -> (String, Option<FileDiff>) {
         let mut aggregated = String::new();
 
         // Snapshot lightweight fields only.
@@ -262,7 +279,7 @@ impl TurnDiffTracker {
         };
         let current_external_path = match self.get_path_for_internal(internal_file_name) {
             Some(p) => p,
-            None => return aggregated,
+            None => return (aggregated, None),
         };
 
         let current_mode = file_mode_for_path(&current_external_path).unwrap_or(FileMode::Regular);
@@ -296,13 +313,31 @@ impl TurnDiffTracker {
 
         // Fast path: identical bytes or both missing.
         if left_bytes == right_bytes.as_deref() {
-            return aggregated;
+            return (aggregated, None);
         }
 
         aggregated.push_str(&format!("diff --git a/{left_display} b/{right_display}\n"));
 
         let is_add = !left_present && right_bytes.is_some();
         let is_delete = left_present && right_bytes.is_none();
+        let status = if is_add {
+            FileDiffStatus::Added
+        } else if is_delete {
+            FileDiffStatus::Deleted
+        } else if left_display != right_display {
+            FileDiffStatus::Renamed
+        } else {
+            FileDiffStatus::Modified
+        };
+        let mut file = FileDiff {
+            path: PathBuf::from(&right_display),
+            old_path: (status == FileDiffStatus::Renamed).then(|| PathBuf::from(&left_display)),
+            status,
+            old_hash: left_present.then(|| left_oid.clone()),
+            new_hash: right_bytes.is_some().then(|| right_oid.clone()),
+            binary: false,
+            hunks: Vec::new(),
+        };
 
         if is_add {
             aggregated.push_str(&format!("new file mode {current_mode}\n"));
@@ -341,6 +376,11 @@ impl TurnDiffTracker {
             };
 
             let diff = similar::TextDiff::from_lines(l, r);
+            file.hunks = diff
+                .grouped_ops(3)
+                .iter()
+                .filter_map(|ops| hunk_range(ops))
+                .collect();
             let unified = diff
                 .unified_diff()
                 .context_radius(3)
@@ -363,11 +403,124 @@ impl TurnDiffTracker {
             aggregated.push_str(&format!("--- {old_header}\n"));
             aggregated.push_str(&format!("+++ {new_header}\n"));
             aggregated.push_str("Binary files differ\n");
+            file.binary = true;
         }
-        aggregated
+        (aggregated, Some(file))
     }
 }
 
+/// Structured view of a proposed patch, built from its `FileChange`s before
+/// it is applied. `new_hash` is left unset for updates because the patched
+/// content is only known once the patch runs.
+pub(crate) fn file_diffs_for_changes(changes: &HashMap<PathBuf, FileChange>) -> Vec<FileDiff> {
+    let blob_oid = |bytes: &[u8]| format!("{:x}", git_blob_sha1_hex_bytes(bytes));
+    let mut files: Vec<FileDiff> = changes
+        .iter()
+        .map(|(path, change)| match change {
+            FileChange::Add { content } => FileDiff {
+                path: path.clone(),
+                old_path: None,
+                status: FileDiffStatus::Added,
+                old_hash: None,
+                new_hash: Some(blob_oid(content.as_bytes())),
+                binary: false,
+                hunks: whole_file_hunk(content, false).into_iter().collect(),
+            },
+            FileChange::Delete { content } => FileDiff {
+                path: path.clone(),
+                old_path: None,
+                status: FileDiffStatus::Deleted,
+                old_hash: Some(blob_oid(content.as_bytes())),
+                new_hash: None,
+                binary: false,
+                hunks: whole_file_hunk(content, true).into_iter().collect(),
+            },
+            FileChange::Update {
+                unified_diff,
+                move_path,
+            } => FileDiff {
+                path: move_path.clone().unwrap_or_else(|| path.clone()),
+                old_path: move_path.as_ref().map(|_| path.clone()),
+                status: if move_path.is_some() {
+                    FileDiffStatus::Renamed
+                } else {
+                    FileDiffStatus::Modified
+                },
+                old_hash: fs::read(path).ok().map(|bytes| blob_oid(&bytes)),
+                new_hash: None,
+                binary: false,
+                hunks: unified_diff.lines().filter_map(parse_hunk_header).collect(),
+            },
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// The single hunk of an added (or, with `deleted`, removed) file.
+fn whole_file_hunk(content: &str, deleted: bool) -> Option<DiffHunk> {
+    let lines = u32::try_from(content.lines().count()).unwrap_or(u32::MAX);
+    if lines == 0 {
+        return None;
+    }
+    let (old_start, old_lines, new_start, new_lines) = if deleted {
+        (1, lines, 0, 0)
+    } else {
+        (0, 0, 1, lines)
+    };
+    Some(DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+    })
+}
+
+/// Parses an `@@ -a,b +c,d @@` header line.
+pub fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let (ranges, _) = line.strip_prefix("@@ -")?.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let parse_range = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = parse_range(old)?;
+    let (new_start, new_lines) = parse_range(new)?;
+    Some(DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+    })
+}
+
+/// Line ranges of one group of diff ops, numbered the way a unified diff
+/// `@@` header numbers them.
+fn hunk_range(ops: &[similar::DiffOp]) -> Option<DiffHunk> {
+    let first = ops.first()?;
+    let last = ops.last()?;
+    let (old_start, old_lines) = header_range(first.old_range().start, last.old_range().end);
+    let (new_start, new_lines) = header_range(first.new_range().start, last.new_range().end);
+    Some(DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+    })
+}
+
+fn header_range(start: usize, end: usize) -> (u32, u32) {
+    let len = end.saturating_sub(start);
+    // Empty ranges begin at the line just before the change.
+    let start = if len == 0 { start } else { start + 1 };
+    (
+        u32::try_from(start).unwrap_or(u32::MAX),
+        u32::try_from(len).unwrap_or(u32::MAX),
+    )
+}
+
 /// Compute the Git SHA-1 blob object ID for the given content (bytes).
 fn git_blob_sha1_hex_bytes(data: &[u8]) -> Output<sha1::Sha1> {
     // Git blob hash is sha1 of: "blob <len>\0<data>"
@@ -893,4 +1046,157 @@ index {ZERO_OID}..{right_oid}
         };
         assert_eq!(combined, expected_combined);
     }
+
+    #[test]
+    fn turn_diff_reports_files_and_hunks() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src.txt");
+        let dest = dir.path().join("dst.txt");
+        let added = dir.path().join("new.txt");
+        let before: String = (1..=20).map(|i| format!("line{i}\n")).collect();
+        fs::write(&src, &before).unwrap();
+
+        let mut acc = TurnDiffTracker::new();
+        acc.on_patch_begin(&HashMap::from([
+            (
+                src.clone(),
+                FileChange::Update {
+                    unified_diff: "".to_owned(),
+                    move_path: Some(dest.clone()),
+                },
+            ),
+            (
+                added.clone(),
+                FileChange::Add {
+                    content: "hello\n".to_string(),
+                },
+            ),
+        ]));
+
+        // Change line 2 and insert a line after line 18: two separate hunks.
+        let after = before
+            .replace("line2\n", "LINE2\n")
+            .replace("line18\n", "line18\ninserted\n");
+        fs::rename(&src, &dest).unwrap();
+        fs::write(&dest, &after).unwrap();
+        fs::write(&added, "hello\n").unwrap();
+
+        let event = acc.get_turn_diff().unwrap().unwrap();
+        assert_eq!(
+            Some(event.unified_diff.clone()),
+            acc.get_unified_diff().unwrap()
+        );
+
+        let mut files = event.files;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(
+            files,
+            vec![
+                FileDiff {
+                    path: dest.clone(),
+                    old_path: Some(src.clone()),
+                    status: FileDiffStatus::Renamed,
+                    old_hash: Some(git_blob_sha1_hex(&before)),
+                    new_hash: Some(git_blob_sha1_hex(&after)),
+                    binary: false,
+                    hunks: vec![
+                        DiffHunk {
+                            old_start: 1,
+                            old_lines: 5,
+                            new_start: 1,
+                            new_lines: 5,
+                        },
+                        DiffHunk {
+                            old_start: 16,
+                            old_lines: 5,
+                            new_start: 16,
+                            new_lines: 6,
+                        },
+                    ],
+                },
+                FileDiff {
+                    path: added.clone(),
+                    old_path: None,
+                    status: FileDiffStatus::Added,
+                    old_hash: None,
+                    new_hash: Some(git_blob_sha1_hex("hello\n")),
+                    binary: false,
+                    hunks: vec![DiffHunk {
+                        old_start: 0,
+                        old_lines: 0,
+                        new_start: 1,
+                        new_lines: 1,
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn file_diffs_for_proposed_changes() {
+        let dir = tempdir().unwrap();
+        let existing = dir.path().join("a.txt");
+        fs::write(&existing, "one\ntwo\n").unwrap();
+        let moved = dir.path().join("b.txt");
+
+        let changes = HashMap::from([
+            (
+                existing.clone(),
+                FileChange::Update {
+                    unified_diff:
+                        "@@ -1,2 +1,3 @@\n one\n+one and a half\n two\n@@ -9 +10,0 @@\n-x\n"
+                            .to_string(),
+                    move_path: Some(moved.clone()),
+                },
+            ),
+            (
+                dir.path().join("gone.txt"),
+                FileChange::Delete {
+                    content: "bye\n".to_string(),
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            file_diffs_for_changes(&changes),
+            vec![
+                FileDiff {
+                    path: moved,
+                    old_path: Some(existing),
+                    status: FileDiffStatus::Renamed,
+                    old_hash: Some(git_blob_sha1_hex("one\ntwo\n")),
+                    new_hash: None,
+                    binary: false,
+                    hunks: vec![
+                        DiffHunk {
+                            old_start: 1,
+                            old_lines: 2,
+                            new_start: 1,
+                            new_lines: 3,
+                        },
+                        DiffHunk {
+                            old_start: 9,
+                            old_lines: 1,
+                            new_start: 10,
+                            new_lines: 0,
+                        },
+                    ],
+                },
+                FileDiff {
+                    path: dir.path().join("gone.txt"),
+                    old_path: None,
+                    status: FileDiffStatus::Deleted,
+                    old_hash: Some(git_blob_sha1_hex("bye\n")),
+                    new_hash: None,
+                    binary: false,
+                    hunks: vec![DiffHunk {
+                        old_start: 1,
+                        old_lines: 1,
+                        new_start: 0,
+                        new_lines: 0,
+                    }],
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,243 @@
+//! Long-running processes started by the `background_process` tool.
+//!
+//! A background process is spawned through the same approval and sandbox flow
+//! as `exec_command`, without a terminal and with stdin closed. Unlike unified
+//! exec processes it is not tied to a turn: it keeps running until the agent
+//! stops it or the session shuts down. Its output is buffered like any unified
+//! exec process and handed out in pieces by `output`.
+//!
+//! Every change to the set of processes (start, exit, stop) is reported with
+//! `EventMsg::BackgroundProcessesUpdated` so clients can show what is alive.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::protocol::BackgroundProcessInfo;
+use crate::protocol::BackgroundProcessStatus;
+use crate::protocol::BackgroundProcessesUpdatedEvent;
+use crate::protocol::EventMsg;
+use crate::sandboxing::SandboxPermissions;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::formatted_truncate_text;
+use crate::unified_exec::UnifiedExecContext;
+use crate::unified_exec::UnifiedExecError;
+use crate::unified_exec::UnifiedExecProcessManager;
+use crate::unified_exec::process::UnifiedExecProcess;
+use crate::unified_exec::resolve_max_tokens;
+
+pub(crate) const MAX_BACKGROUND_PROCESSES: usize = 16;
+
+#[derive(Default)]
+pub(crate) struct BackgroundProcessStore {
+    processes: BTreeMap<u64, BackgroundProcess>,
+    next_id: u64,
+}
+
+struct BackgroundProcess {
+    process: UnifiedExecProcess,
+    command: String,
+    cwd: PathBuf,
+}
+
+impl BackgroundProcess {
+    fn info(&self, id: u64) -> BackgroundProcessInfo {
+        let status = if self.process.has_exited() {
+            BackgroundProcessStatus::Exited {
+                exit_code: self.process.exit_code().unwrap_or(-1),
+            }
+        } else {
+            BackgroundProcessStatus::Running
+        };
+        BackgroundProcessInfo {
+            process_id: format_process_id(id),
+            command: self.command.clone(),
+            cwd: self.cwd.clone(),
+            status,
+        }
+    }
+
+    /// Output printed since the previous call.
+    async fn drain_output(&self, max_output_tokens: Option<usize>) -> String {
+        let chunks = self
+            .process
+            .output_handles()
+            .output_buffer
+            .lock()
+            .await
+            .drain_chunks();
+        let text = String::from_utf8_lossy(&chunks.concat()).to_string();
+        formatted_truncate_text(
+            &text,
+            TruncationPolicy::Tokens(resolve_max_tokens(max_output_tokens)),
+        )
+    }
+}
+
+/// Result of `start`, `output` and `stop` on a background process.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BackgroundProcessOutput {
+    pub info: BackgroundProcessInfo,
+    /// Output printed since the previous `output` call, truncated.
+    pub output: String,
+}
+
+impl UnifiedExecProcessManager {
+    /// Starts `command` in the user's shell and leaves it running.
+    pub(crate) async fn start_background_process(
+        &self,
+        command: &str,
+        workdir: Option<PathBuf>,
+        max_output_tokens: Option<usize>,
+        context: &UnifiedExecContext,
+    ) -> Result<BackgroundProcessOutput, UnifiedExecError> {
+        if self.background_processes.lock().await.processes.len() >= MAX_BACKGROUND_PROCESSES {
+            return Err(UnifiedExecError::BackgroundProcessLimit {
+                max: MAX_BACKGROUND_PROCESSES,
+            });
+        }
+        let cwd = workdir.unwrap_or_else(|| context.turn.cwd.clone());
+        let exec_command = context.session.user_shell().derive_exec_args(command, true);
+        let process = self
+            .open_session_with_sandbox(
+                &exec_command,
+                cwd.clone(),
+                SandboxPermissions::UseDefault,
+                None,
+                false,
+                false,
+                context,
+            )
+            .await?;
+        let process = BackgroundProcess {
+            process,
+            command: command.to_string(),
+            cwd,
+        };
+        let exited = process.process.cancellation_token();
+        let (id, output) = {
+            let mut store = self.background_processes.lock().await;
+            store.next_id += 1;
+            let id = store.next_id;
+            let output = BackgroundProcessOutput {
+                info: process.info(id),
+                output: process.drain_output(max_output_tokens).await,
+            };
+            store.processes.insert(id, process);
+            (id, output)
+        };
+        self.send_background_processes_update(&context.session, &context.turn)
+            .await;
+
+        // Report the exit unless the process was stopped (and reported) first.
+        let session = Arc::clone(&context.session);
+        let turn = Arc::clone(&context.turn);
+        tokio::spawn(async move {
+            exited.cancelled().await;
+            let manager = &session.services.unified_exec_manager;
+            if manager
+                .background_processes
+                .lock()
+                .await
+                .processes
+                .contains_key(&id)
+            {
+                manager
+                    .send_background_processes_update(&session, &turn)
+                    .await;
+            }
+        });
+
+        Ok(output)
+    }
+
+    /// Returns the process's status and the output it printed since the
+    /// previous call.
+    pub(crate) async fn background_process_output(
+        &self,
+        process_id: &str,
+        max_output_tokens: Option<usize>,
+    ) -> Result<BackgroundProcessOutput, UnifiedExecError> {
+        let id = parse_process_id(process_id)?;
+        let store = self.background_processes.lock().await;
+        let process = store
+            .processes
+            .get(&id)
+            .ok_or_else(|| unknown_process_id(process_id))?;
+        Ok(BackgroundProcessOutput {
+            info: process.info(id),
+            output: process.drain_output(max_output_tokens).await,
+        })
+    }
+
+    /// Kills the process (if it is still running) and forgets it, returning
+    /// whatever it printed since the last `output` call.
+    pub(crate) async fn stop_background_process(
+        &self,
+        process_id: &str,
+        max_output_tokens: Option<usize>,
+        context: &UnifiedExecContext,
+    ) -> Result<BackgroundProcessOutput, UnifiedExecError> {
+        let id = parse_process_id(process_id)?;
+        let process = self
+            .background_processes
+            .lock()
+            .await
+            .processes
+            .remove(&id)
+            .ok_or_else(|| unknown_process_id(process_id))?;
+        let output = process.drain_output(max_output_tokens).await;
+        let info = process.info(id);
+        process.process.terminate();
+        self.send_background_processes_update(&context.session, &context.turn)
+            .await;
+        Ok(BackgroundProcessOutput { info, output })
+    }
+
+    pub(crate) async fn list_background_processes(&self) -> Vec<BackgroundProcessInfo> {
+        self.background_processes
+            .lock()
+            .await
+            .processes
+            .iter()
+            .map(|(id, process)| process.info(*id))
+            .collect()
+    }
+
+    /// Kills every background process; called when the session shuts down.
+    pub(crate) async fn terminate_background_processes(&self) {
+        let processes = std::mem::take(&mut self.background_processes.lock().await.processes);
+        for process in processes.into_values() {
+            process.process.terminate();
+        }
+    }
+
+    async fn send_background_processes_update(&self, session: &Session, turn: &TurnContext) {
+        let processes = self.list_background_processes().await;
+        session
+            .send_event(
+                turn,
+                EventMsg::BackgroundProcessesUpdated(BackgroundProcessesUpdatedEvent { processes }),
+            )
+            .await;
+    }
+}
+
+fn format_process_id(id: u64) -> String {
+    format!("bg-{id}")
+}
+
+fn parse_process_id(process_id: &str) -> Result<u64, UnifiedExecError> {
+    process_id
+        .strip_prefix("bg-")
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| unknown_process_id(process_id))
+}
+
+fn unknown_process_id(process_id: &str) -> UnifiedExecError {
+    UnifiedExecError::UnknownProcessId {
+        process_id: process_id.to_string(),
+    }
+}
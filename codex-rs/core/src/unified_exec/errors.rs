@@ -16,6 +16,16 @@ pub(crate) enum UnifiedExecError {
     StdinClosed,
     #[error("missing command line for unified exec request")]
     MissingCommandLine,
+    #[error("shell sessions need bash, zsh or sh, but the user's shell is {shell}")]
+    ShellSessionUnsupported { shell: String },
+    #[error(
+        "the previous command in this shell session is still running; run with an empty command to keep waiting for it, or close the session"
+    )]
+    ShellSessionBusy,
+    #[error("command rejected: {reason}")]
+    ShellSessionRejected { reason: String },
+    #[error("at most {max} background processes can run at once; stop one first")]
+    BackgroundProcessLimit { max: usize },
     #[error("Command denied by sandbox: {message}")]
     SandboxDenied {
         message: String,
@@ -27,6 +27,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
+use codex_utils_pty::TerminalSize;
 use rand::Rng;
 use rand::rng;
 use tokio::sync::Mutex;
@@ -36,13 +37,18 @@ use crate::codex::TurnContext;
 use crate::sandboxing::SandboxPermissions;
 
 mod async_watcher;
+mod background;
 mod errors;
 mod head_tail_buffer;
 mod process;
 mod process_manager;
+mod shell_session;
 
+pub(crate) use background::BackgroundProcessOutput;
 pub(crate) use errors::UnifiedExecError;
 pub(crate) use process::UnifiedExecProcess;
+pub(crate) use shell_session::DEFAULT_SHELL_SESSION_TIMEOUT_MS;
+pub(crate) use shell_session::ShellSessionOutput;
 
 pub(crate) const MIN_YIELD_TIME_MS: u64 = 250;
 // Minimum yield time for an empty `write_stdin`.
@@ -121,12 +127,22 @@ impl ProcessStore {
 
 pub(crate) struct UnifiedExecProcessManager {
     process_store: Mutex<ProcessStore>,
+    /// Size reported by the client via `Op::SetTerminalSize`.
+    terminal_size: std::sync::Mutex<TerminalSize>,
+    /// Shells opened by the `shell_session` tool, keyed by session id.
+    shell_sessions: Mutex<HashMap<String, shell_session::ShellSession>>,
+    /// Processes started by the `background_process` tool. They outlive turns
+    /// and are only terminated when the session shuts down.
+    background_processes: Mutex<background::BackgroundProcessStore>,
 }
 
 impl Default for UnifiedExecProcessManager {
     fn default() -> Self {
         Self {
             process_store: Mutex::new(ProcessStore::default()),
+            terminal_size: std::sync::Mutex::new(TerminalSize::default()),
+            shell_sessions: Mutex::new(HashMap::new()),
+            background_processes: Mutex::new(background::BackgroundProcessStore::default()),
         }
     }
 }
@@ -164,6 +180,7 @@ mod tests {
     use crate::codex::TurnContext;
     use crate::codex::make_session_and_context;
     use crate::protocol::AskForApproval;
+    use crate::protocol::BackgroundProcessStatus;
     use crate::protocol::SandboxPolicy;
     use crate::unified_exec::ExecCommandRequest;
     use crate::unified_exec::WriteStdinRequest;
@@ -477,4 +494,82 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn shell_session_keeps_state_until_terminated() -> anyhow::Result<()> {
+        skip_if_sandbox!(Ok(()));
+
+        let (session, turn) = test_session_and_turn().await;
+        let context =
+            UnifiedExecContext::new(Arc::clone(&session), Arc::clone(&turn), "call".to_string());
+        let manager = &session.services.unified_exec_manager;
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("sub"))?;
+
+        let session_id = manager
+            .open_shell_session(Some(dir.path().to_path_buf()), &context)
+            .await?;
+        let first = manager
+            .run_in_shell_session(
+                &session_id,
+                "cd sub && export CODEX_SHELL_SESSION_VAR=codex",
+                5_000,
+                None,
+                &context,
+            )
+            .await?;
+        assert_eq!(first.exit_code, Some(0));
+        assert!(first.cwd.ends_with("sub"));
+
+        let second = manager
+            .run_in_shell_session(
+                &session_id,
+                "echo \"$CODEX_SHELL_SESSION_VAR\"; exit_code_of_missing_command",
+                5_000,
+                None,
+                &context,
+            )
+            .await?;
+        assert!(second.output.contains("codex"));
+        assert_eq!(second.exit_code, Some(127));
+
+        manager.terminate_all_processes().await;
+        let err = manager
+            .run_in_shell_session(&session_id, "pwd", 1_000, None, &context)
+            .await
+            .expect_err("expected unknown session error");
+        assert!(matches!(err, UnifiedExecError::UnknownProcessId { .. }));
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn background_processes_outlive_turns() -> anyhow::Result<()> {
+        skip_if_sandbox!(Ok(()));
+
+        let (session, turn) = test_session_and_turn().await;
+        let context =
+            UnifiedExecContext::new(Arc::clone(&session), Arc::clone(&turn), "call".to_string());
+        let manager = &session.services.unified_exec_manager;
+
+        let started = manager
+            .start_background_process("echo ready; sleep 30", None, None, &context)
+            .await?;
+        let process_id = started.info.process_id;
+
+        // Ending the turn leaves background processes running.
+        manager.terminate_all_processes().await;
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let output = manager.background_process_output(&process_id, None).await?;
+        assert_eq!(output.info.status, BackgroundProcessStatus::Running);
+        assert!(format!("{}{}", started.output, output.output).contains("ready"));
+
+        let stopped = manager
+            .stop_background_process(&process_id, None, &context)
+            .await?;
+        assert_eq!(stopped.info.process_id, process_id);
+        assert!(manager.list_background_processes().await.is_empty());
+
+        Ok(())
+    }
 }
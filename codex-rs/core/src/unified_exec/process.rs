@@ -17,6 +17,7 @@ use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use codex_utils_pty::ExecCommandSession;
 use codex_utils_pty::SpawnedPty;
+use codex_utils_pty::TerminalSize;
 
 use super::UNIFIED_EXEC_OUTPUT_MAX_TOKENS;
 use super::UnifiedExecError;
@@ -103,6 +104,10 @@ impl UnifiedExecProcess {
         Arc::clone(&self.output_drained)
     }
 
+    pub(super) fn resize(&self, size: TerminalSize) -> anyhow::Result<()> {
+        self.process_handle.resize(size)
+    }
+
     pub(super) fn has_exited(&self) -> bool {
         self.process_handle.has_exited()
     }
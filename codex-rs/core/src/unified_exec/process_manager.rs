@@ -1,3 +1,5 @@
+use codex_protocol::approvals::ApprovalSource;
+use codex_utils_pty::TerminalSize;
 use rand::Rng;
 use std::cmp::Reverse;
 use std::collections::HashMap;
@@ -9,8 +11,11 @@ use tokio::sync::mpsc;
 use tokio::time::Duration;
 use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
+use tracing::debug;
 
 use crate::exec_env::create_env;
+use crate::guardrail::apply_guardrail;
+use crate::protocol::AskForApproval;
 use crate::protocol::ExecCommandSource;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxPermissions;
@@ -20,6 +25,7 @@ use crate::tools::events::ToolEventStage;
 use crate::tools::orchestrator::ToolOrchestrator;
 use crate::tools::runtimes::unified_exec::UnifiedExecRequest as UnifiedExecToolRequest;
 use crate::tools::runtimes::unified_exec::UnifiedExecRuntime;
+use crate::tools::sandboxing::ExecApprovalRequirement;
 use crate::tools::sandboxing::ToolCtx;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::approx_token_count;
@@ -129,6 +135,7 @@ impl UnifiedExecProcessManager {
                 request.sandbox_permissions,
                 request.justification,
                 request.tty,
+                false,
                 context,
             )
             .await;
@@ -390,6 +397,49 @@ impl UnifiedExecProcessManager {
         })
     }
 
+    /// Writes input typed by the user to a running PTY process.
+    pub(crate) async fn write_user_input(
+        &self,
+        process_id: &str,
+        input: &str,
+    ) -> Result<(), UnifiedExecError> {
+        let writer_tx = {
+            let mut store = self.process_store.lock().await;
+            let Some(entry) = store.processes.get_mut(process_id) else {
+                return Err(UnifiedExecError::UnknownProcessId {
+                    process_id: process_id.to_string(),
+                });
+            };
+            if !entry.tty {
+                return Err(UnifiedExecError::StdinClosed);
+            }
+            entry.last_used = Instant::now();
+            entry.process.writer_sender()
+        };
+        Self::send_input(&writer_tx, input.as_bytes()).await
+    }
+
+    /// Stores the size for new PTY processes and resizes the running ones.
+    pub(crate) async fn set_terminal_size(&self, size: TerminalSize) {
+        match self.terminal_size.lock() {
+            Ok(mut guard) => *guard = size,
+            Err(err) => *err.into_inner() = size,
+        }
+        let store = self.process_store.lock().await;
+        for entry in store.processes.values().filter(|entry| entry.tty) {
+            if let Err(err) = entry.process.resize(size) {
+                debug!("failed to resize process {}: {err:#}", entry.process_id);
+            }
+        }
+    }
+
+    fn terminal_size(&self) -> TerminalSize {
+        match self.terminal_size.lock() {
+            Ok(guard) => *guard,
+            Err(err) => *err.into_inner(),
+        }
+    }
+
     async fn send_input(
         writer_tx: &mpsc::Sender<Vec<u8>>,
         data: &[u8],
@@ -450,10 +500,13 @@ impl UnifiedExecProcessManager {
         );
     }
 
+    /// Spawns `env` in a PTY when `tty` is set, otherwise on pipes with stdin
+    /// closed unless `pipe_stdin` keeps it open for writing.
     pub(crate) async fn open_session_with_exec_env(
         &self,
         env: &ExecEnv,
         tty: bool,
+        pipe_stdin: bool,
     ) -> Result<UnifiedExecProcess, UnifiedExecError> {
         let (program, args) = env
             .command
@@ -461,7 +514,17 @@ impl UnifiedExecProcessManager {
             .ok_or(UnifiedExecError::MissingCommandLine)?;
 
         let spawn_result = if tty {
-            codex_utils_pty::pty::spawn_process(
+            codex_utils_pty::pty::spawn_process_with_size(
+                program,
+                args,
+                env.cwd.as_path(),
+                &env.env,
+                &env.arg0,
+                self.terminal_size(),
+            )
+            .await
+        } else if pipe_stdin {
+            codex_utils_pty::pipe::spawn_process(
                 program,
                 args,
                 env.cwd.as_path(),
@@ -484,6 +547,7 @@ impl UnifiedExecProcessManager {
         UnifiedExecProcess::from_spawned(spawned, env.sandbox).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn open_session_with_sandbox(
         &self,
         command: &[String],
@@ -491,6 +555,7 @@ impl UnifiedExecProcessManager {
         sandbox_permissions: SandboxPermissions,
         justification: Option<String>,
         tty: bool,
+        pipe_stdin: bool,
         context: &UnifiedExecContext,
     ) -> Result<UnifiedExecProcess, UnifiedExecError> {
         let env = apply_unified_exec_env(create_env(&context.turn.shell_environment_policy));
@@ -507,13 +572,28 @@ impl UnifiedExecProcessManager {
                 context.turn.approval_policy,
                 &context.turn.sandbox_policy,
                 sandbox_permissions,
+                &cwd,
             )
             .await;
+        let exec_approval_requirement = apply_guardrail(
+            context.session.as_ref(),
+            context.turn.as_ref(),
+            command,
+            &cwd,
+            exec_approval_requirement,
+        )
+        .await;
+        let exec_approval_requirement = if tty {
+            require_approval_for_tty(exec_approval_requirement, context.turn.approval_policy)
+        } else {
+            exec_approval_requirement
+        };
         let req = UnifiedExecToolRequest::new(
             command.to_vec(),
             cwd,
             env,
             tty,
+            pipe_stdin,
             sandbox_permissions,
             justification,
             exec_approval_requirement,
@@ -658,10 +738,20 @@ impl UnifiedExecProcessManager {
             processes.reserved_process_ids.clear();
             entries
         };
+        let shell_sessions: Vec<_> = self
+            .shell_sessions
+            .lock()
+            .await
+            .drain()
+            .map(|(_, session)| session)
+            .collect();
 
         for entry in entries {
             entry.process.terminate();
         }
+        for session in shell_sessions {
+            session.terminate();
+        }
     }
 }
 
@@ -678,6 +768,36 @@ enum ProcessStatus {
     Unknown,
 }
 
+/// A PTY lets the command stop at prompts (passwords, confirmations) that
+/// only the user can answer, so when the approval policy lets the user be
+/// asked, interactive sessions are approved like unsafe commands even if the
+/// command itself is known to be harmless. Explicit execpolicy rules still apply.
+fn require_approval_for_tty(
+    requirement: ExecApprovalRequirement,
+    approval_policy: AskForApproval,
+) -> ExecApprovalRequirement {
+    match requirement {
+        ExecApprovalRequirement::Skip {
+            source:
+                ApprovalSource::KnownSafeCommand
+                | ApprovalSource::ReadOnlyCommand
+                | ApprovalSource::ModeDefault { .. },
+            proposed_execpolicy_amendment,
+            ..
+        } if matches!(
+            approval_policy,
+            AskForApproval::UnlessTrusted | AskForApproval::OnRequest
+        ) =>
+        {
+            ExecApprovalRequirement::NeedsApproval {
+                reason: Some("runs interactively in a terminal".to_string()),
+                proposed_execpolicy_amendment,
+            }
+        }
+        other => other,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -704,6 +824,40 @@ mod tests {
         assert_eq!(env, expected);
     }
 
+    #[test]
+    fn tty_sessions_need_approval_when_the_user_can_be_asked() {
+        let safe = ExecApprovalRequirement::Skip {
+            bypass_sandbox: false,
+            source: ApprovalSource::KnownSafeCommand,
+            proposed_execpolicy_amendment: None,
+        };
+
+        assert_eq!(
+            require_approval_for_tty(safe.clone(), AskForApproval::OnRequest),
+            ExecApprovalRequirement::NeedsApproval {
+                reason: Some("runs interactively in a terminal".to_string()),
+                proposed_execpolicy_amendment: None,
+            }
+        );
+        assert_eq!(
+            require_approval_for_tty(safe.clone(), AskForApproval::Never),
+            safe
+        );
+
+        let rule = ExecApprovalRequirement::Skip {
+            bypass_sandbox: false,
+            source: ApprovalSource::PolicyRule {
+                matched_prefix: vec!["npm".to_string()],
+                justification: None,
+            },
+            proposed_execpolicy_amendment: None,
+        };
+        assert_eq!(
+            require_approval_for_tty(rule.clone(), AskForApproval::UnlessTrusted),
+            rule
+        );
+    }
+
     #[test]
     fn unified_exec_env_overrides_existing_values() {
         let mut base = HashMap::new();
@@ -0,0 +1,529 @@
+//! Persistent shell sessions for the `shell_session` tool.
+//!
+//! A session is one POSIX shell started through the same approval and sandbox
+//! flow as `exec_command`, reading commands from a stdin pipe. Every command
+//! runs in that shell, so `cd`, exported variables and an activated virtualenv
+//! carry over between calls. Each command is followed by a marker line with its
+//! exit status and the shell's working directory, which is how the end of its
+//! output is found without a PTY.
+//!
+//! Sessions are kept next to the unified exec processes and are terminated with
+//! them when the turn ends or is aborted.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rand::Rng;
+use tokio::time::Duration;
+use tokio::time::Instant;
+
+use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
+use crate::guardrail::apply_guardrail;
+use crate::protocol::ExecCommandSource;
+use crate::protocol::ReviewDecision;
+use crate::sandboxing::SandboxPermissions;
+use crate::shell::Shell;
+use crate::shell::ShellType;
+use crate::tools::events::ToolEmitter;
+use crate::tools::events::ToolEventCtx;
+use crate::tools::events::ToolEventFailure;
+use crate::tools::events::ToolEventStage;
+use crate::tools::runtimes::unified_exec::UnifiedExecApprovalKey;
+use crate::tools::sandboxing::ExecApprovalRequirement;
+use crate::tools::sandboxing::with_cached_approval;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::formatted_truncate_text;
+use crate::unified_exec::UnifiedExecContext;
+use crate::unified_exec::UnifiedExecError;
+use crate::unified_exec::UnifiedExecProcessManager;
+use crate::unified_exec::process::UnifiedExecProcess;
+use crate::unified_exec::resolve_max_tokens;
+
+pub(crate) const DEFAULT_SHELL_SESSION_TIMEOUT_MS: u64 = 10_000;
+const MAX_SHELL_SESSION_TIMEOUT_MS: u64 = 300_000;
+
+pub(crate) struct ShellSession {
+    process: Arc<UnifiedExecProcess>,
+    shell: Shell,
+    cwd: PathBuf,
+    /// Command whose marker line has not been seen yet.
+    pending: Option<PendingCommand>,
+}
+
+impl ShellSession {
+    pub(super) fn terminate(&self) {
+        self.process.terminate();
+    }
+}
+
+struct PendingCommand {
+    marker: String,
+    command: Vec<String>,
+    call_id: String,
+    cwd: PathBuf,
+    started_at: Instant,
+    output: Vec<u8>,
+}
+
+/// Result of a `run` call on a shell session.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ShellSessionOutput {
+    pub session_id: String,
+    pub output: String,
+    /// `None` while the command is still running.
+    pub exit_code: Option<i32>,
+    pub cwd: PathBuf,
+    pub wall_time: Duration,
+    /// The shell itself exited (e.g. the command ran `exit`); the session is gone.
+    pub session_closed: bool,
+}
+
+struct MarkerLine {
+    output: String,
+    exit_code: i32,
+    cwd: PathBuf,
+}
+
+impl UnifiedExecProcessManager {
+    /// Starts a shell for a new session and returns its id.
+    pub(crate) async fn open_shell_session(
+        &self,
+        workdir: Option<PathBuf>,
+        context: &UnifiedExecContext,
+    ) -> Result<String, UnifiedExecError> {
+        let shell = context.session.user_shell().as_ref().clone();
+        if !matches!(
+            shell.shell_type,
+            ShellType::Bash | ShellType::Zsh | ShellType::Sh
+        ) {
+            return Err(UnifiedExecError::ShellSessionUnsupported {
+                shell: shell.name().to_string(),
+            });
+        }
+        let cwd = workdir.unwrap_or_else(|| context.turn.cwd.clone());
+        let shell_path = shell.shell_path.to_string_lossy().to_string();
+        let script = format!(
+            "exec {} -s",
+            shlex::try_quote(&shell_path).unwrap_or_else(|_| shell_path.as_str().into())
+        );
+        let command = shell.derive_exec_args(&script, true);
+
+        let session_id = self.allocate_process_id().await;
+        let process = match self
+            .open_session_with_sandbox(
+                &command,
+                cwd.clone(),
+                SandboxPermissions::UseDefault,
+                None,
+                false,
+                true,
+                context,
+            )
+            .await
+        {
+            Ok(process) => process,
+            Err(err) => {
+                self.release_process_id(&session_id).await;
+                return Err(err);
+            }
+        };
+        if process.has_exited() {
+            self.release_process_id(&session_id).await;
+            return Err(UnifiedExecError::create_process(format!(
+                "shell exited with code {}",
+                process.exit_code().unwrap_or(-1)
+            )));
+        }
+
+        self.shell_sessions.lock().await.insert(
+            session_id.clone(),
+            ShellSession {
+                process: Arc::new(process),
+                shell,
+                cwd,
+                pending: None,
+            },
+        );
+        Ok(session_id)
+    }
+
+    /// Runs `command` in the session and waits up to `timeout_ms` for it to
+    /// finish. An empty `command` keeps waiting for the previous one.
+    pub(crate) async fn run_in_shell_session(
+        &self,
+        session_id: &str,
+        command: &str,
+        timeout_ms: u64,
+        max_output_tokens: Option<usize>,
+        context: &UnifiedExecContext,
+    ) -> Result<ShellSessionOutput, UnifiedExecError> {
+        // The session is taken out of the store while the command runs; if the
+        // turn is aborted meanwhile, dropping it terminates the shell.
+        let mut session = self.shell_sessions.lock().await.remove(session_id).ok_or(
+            UnifiedExecError::UnknownProcessId {
+                process_id: session_id.to_string(),
+            },
+        )?;
+
+        let started = match (&session.pending, command.trim().is_empty()) {
+            (Some(_), false) => Err(UnifiedExecError::ShellSessionBusy),
+            (None, true) => Err(UnifiedExecError::MissingCommandLine),
+            (Some(_), true) => Ok(()),
+            (None, false) => start_command(&mut session, command, context).await,
+        };
+        if let Err(err) = started {
+            self.shell_sessions
+                .lock()
+                .await
+                .insert(session_id.to_string(), session);
+            return Err(err);
+        }
+
+        let timeout_ms = timeout_ms.clamp(1, MAX_SHELL_SESSION_TIMEOUT_MS);
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let marker_line = wait_for_marker(&mut session, deadline).await;
+        let max_tokens = resolve_max_tokens(max_output_tokens);
+        let Some(pending) = session.pending.take() else {
+            return Err(UnifiedExecError::MissingCommandLine);
+        };
+        let wall_time = Instant::now().saturating_duration_since(pending.started_at);
+
+        let Some(marker_line) = marker_line else {
+            let text = String::from_utf8_lossy(&pending.output).to_string();
+            if session.process.has_exited() {
+                let exit_code = session.process.exit_code().unwrap_or(-1);
+                emit_command_end(context, &pending, text.clone(), exit_code, wall_time).await;
+                self.release_process_id(session_id).await;
+                return Ok(ShellSessionOutput {
+                    session_id: session_id.to_string(),
+                    output: formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens)),
+                    exit_code: Some(exit_code),
+                    cwd: session.cwd,
+                    wall_time,
+                    session_closed: true,
+                });
+            }
+            let response = ShellSessionOutput {
+                session_id: session_id.to_string(),
+                output: formatted_truncate_text(&text, TruncationPolicy::Tokens(max_tokens)),
+                exit_code: None,
+                cwd: session.cwd.clone(),
+                wall_time,
+                session_closed: false,
+            };
+            // Output already returned to the model is not repeated next time.
+            session.pending = Some(PendingCommand {
+                output: Vec::new(),
+                ..pending
+            });
+            self.shell_sessions
+                .lock()
+                .await
+                .insert(session_id.to_string(), session);
+            return Ok(response);
+        };
+
+        let MarkerLine {
+            output,
+            exit_code,
+            cwd,
+        } = marker_line;
+        emit_command_end(context, &pending, output.clone(), exit_code, wall_time).await;
+        session.cwd = cwd.clone();
+        self.shell_sessions
+            .lock()
+            .await
+            .insert(session_id.to_string(), session);
+        Ok(ShellSessionOutput {
+            session_id: session_id.to_string(),
+            output: formatted_truncate_text(&output, TruncationPolicy::Tokens(max_tokens)),
+            exit_code: Some(exit_code),
+            cwd,
+            wall_time,
+            session_closed: false,
+        })
+    }
+
+    /// Terminates the session's shell and any command still running in it.
+    pub(crate) async fn close_shell_session(
+        &self,
+        session_id: &str,
+    ) -> Result<(), UnifiedExecError> {
+        let session = self.shell_sessions.lock().await.remove(session_id).ok_or(
+            UnifiedExecError::UnknownProcessId {
+                process_id: session_id.to_string(),
+            },
+        )?;
+        session.terminate();
+        self.release_process_id(session_id).await;
+        Ok(())
+    }
+}
+
+/// Asks for approval like `exec_command` would, then writes the command and
+/// its marker line to the shell. The shell already runs in the session's
+/// sandbox, so a policy that would let the command skip the sandbox does not
+/// take it out.
+async fn start_command(
+    session: &mut ShellSession,
+    command: &str,
+    context: &UnifiedExecContext,
+) -> Result<(), UnifiedExecError> {
+    let exec_command = session.shell.derive_exec_args(command, true);
+    let requirement = context
+        .session
+        .services
+        .exec_policy
+        .create_exec_approval_requirement_for_command(
+            &context.session.features(),
+            &exec_command,
+            context.turn.approval_policy,
+            &context.turn.sandbox_policy,
+            SandboxPermissions::UseDefault,
+            &session.cwd,
+        )
+        .await;
+    let requirement = apply_guardrail(
+        context.session.as_ref(),
+        context.turn.as_ref(),
+        &exec_command,
+        &session.cwd,
+        requirement,
+    )
+    .await;
+    match requirement {
+        ExecApprovalRequirement::Skip { .. } => {}
+        ExecApprovalRequirement::Forbidden { reason } => {
+            return Err(UnifiedExecError::ShellSessionRejected { reason });
+        }
+        ExecApprovalRequirement::NeedsApproval {
+            reason,
+            proposed_execpolicy_amendment,
+        } => {
+            let keys = vec![UnifiedExecApprovalKey {
+                command: exec_command.clone(),
+                cwd: session.cwd.clone(),
+                tty: false,
+                sandbox_permissions: SandboxPermissions::UseDefault,
+            }];
+            let approval_command = exec_command.clone();
+            let cwd = session.cwd.clone();
+            let decision = with_cached_approval(
+                &context.session.services,
+                "shell_session",
+                keys,
+                || async move {
+                    context
+                        .session
+                        .request_command_approval(
+                            context.turn.as_ref(),
+                            context.call_id.clone(),
+                            approval_command,
+                            cwd,
+                            reason,
+                            proposed_execpolicy_amendment,
+                        )
+                        .await
+                },
+            )
+            .await;
+            if matches!(decision, ReviewDecision::Denied | ReviewDecision::Abort) {
+                return Err(UnifiedExecError::ShellSessionRejected {
+                    reason: "rejected by user".to_string(),
+                });
+            }
+        }
+    }
+
+    // Drop anything a previous, abandoned command printed after its marker.
+    let _ = session
+        .process
+        .output_handles()
+        .output_buffer
+        .lock()
+        .await
+        .drain_chunks();
+
+    let marker = new_marker();
+    session
+        .process
+        .writer_sender()
+        .send(command_script(command, &marker).into_bytes())
+        .await
+        .map_err(|_| UnifiedExecError::WriteToStdin)?;
+
+    let pending = PendingCommand {
+        marker,
+        command: exec_command,
+        call_id: context.call_id.clone(),
+        cwd: session.cwd.clone(),
+        started_at: Instant::now(),
+        output: Vec::new(),
+    };
+    let event_ctx = ToolEventCtx::new(
+        context.session.as_ref(),
+        context.turn.as_ref(),
+        &pending.call_id,
+        None,
+    );
+    ToolEmitter::shell(
+        pending.command.clone(),
+        pending.cwd.clone(),
+        ExecCommandSource::Agent,
+        false,
+    )
+    .begin(event_ctx)
+    .await;
+    session.pending = Some(pending);
+    Ok(())
+}
+
+/// Collects output for the pending command until its marker line arrives,
+/// the shell exits, or `deadline` passes.
+async fn wait_for_marker(session: &mut ShellSession, deadline: Instant) -> Option<MarkerLine> {
+    let handles = session.process.output_handles();
+    let pending = session.pending.as_mut()?;
+    loop {
+        let notified = handles.output_notify.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        for chunk in handles.output_buffer.lock().await.drain_chunks() {
+            pending.output.extend_from_slice(&chunk);
+        }
+        let text = String::from_utf8_lossy(&pending.output);
+        if let Some(marker_line) = split_marker_line(&text, &pending.marker) {
+            return Some(marker_line);
+        }
+        if handles.cancellation_token.is_cancelled() {
+            return None;
+        }
+        let exited = handles.cancellation_token.cancelled();
+        tokio::select! {
+            _ = &mut notified => {}
+            _ = exited => {}
+            _ = tokio::time::sleep_until(deadline) => return None,
+        }
+    }
+}
+
+async fn emit_command_end(
+    context: &UnifiedExecContext,
+    pending: &PendingCommand,
+    output: String,
+    exit_code: i32,
+    duration: Duration,
+) {
+    let exec_output = ExecToolCallOutput {
+        exit_code,
+        stdout: StreamOutput::new(output.clone()),
+        stderr: StreamOutput::new(String::new()),
+        aggregated_output: StreamOutput::new(output),
+        duration,
+        timed_out: false,
+    };
+    let stage = if exit_code == 0 {
+        ToolEventStage::Success(exec_output)
+    } else {
+        ToolEventStage::Failure(ToolEventFailure::Output(exec_output))
+    };
+    let event_ctx = ToolEventCtx::new(
+        context.session.as_ref(),
+        context.turn.as_ref(),
+        &pending.call_id,
+        None,
+    );
+    ToolEmitter::shell(
+        pending.command.clone(),
+        pending.cwd.clone(),
+        ExecCommandSource::Agent,
+        false,
+    )
+    .emit(event_ctx, stage)
+    .await;
+}
+
+fn new_marker() -> String {
+    let mut rng = rand::rng();
+    let nonce: String = (0..16)
+        .map(|_| format!("{:x}", rng.random_range(0..16)))
+        .collect();
+    format!("__codex_shell_session_{nonce}__")
+}
+
+/// The command runs in a group so `cd` and `export` affect the session, with
+/// stdin detached so it cannot swallow the lines that follow it.
+fn command_script(command: &str, marker: &str) -> String {
+    format!(
+        "{{ {command}\n}} </dev/null 2>&1\nprintf '\\n%s %d %s\\n' '{marker}' \"$?\" \"$PWD\"\n"
+    )
+}
+
+/// Splits the command output from the marker line once the whole line has
+/// arrived.
+fn split_marker_line(text: &str, marker: &str) -> Option<MarkerLine> {
+    let start = text.find(&format!("\n{marker} "))?;
+    let rest = &text[start + marker.len() + 2..];
+    let (line, _) = rest.split_once('\n')?;
+    let (exit_code, cwd) = line.split_once(' ')?;
+    Some(MarkerLine {
+        output: text[..start].to_string(),
+        exit_code: exit_code.parse().ok()?,
+        cwd: PathBuf::from(cwd),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn marker_line_needs_to_be_complete() {
+        let marker = "__codex_shell_session_0__";
+        assert!(split_marker_line("building...\n", marker).is_none());
+        assert!(split_marker_line("done\n\n__codex_shell_session_0__ 0 /tm", marker).is_none());
+
+        let marker_line = split_marker_line(
+            "done\n\n__codex_shell_session_0__ 2 /tmp/my project\nlate",
+            marker,
+        )
+        .expect("complete marker line");
+        assert_eq!(marker_line.output, "done\n");
+        assert_eq!(marker_line.exit_code, 2);
+        assert_eq!(marker_line.cwd, PathBuf::from("/tmp/my project"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn state_carries_over_between_commands() {
+        let first = new_marker();
+        let second = new_marker();
+        let dir = tempfile::tempdir().expect("tempdir");
+        let script = format!(
+            "{}{}",
+            command_script("cd sub && export GREETING=hi && false", &first),
+            command_script(
+                "echo \"$GREETING\"; read -r line; echo \"read:$line\"",
+                &second
+            )
+        );
+        std::fs::create_dir(dir.path().join("sub")).expect("create sub");
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(&script)
+            .current_dir(dir.path())
+            .output()
+            .expect("run sh");
+        let text = String::from_utf8_lossy(&output.stdout).to_string();
+        let sub = dir.path().join("sub").canonicalize().expect("canonicalize");
+
+        let first_line = split_marker_line(&text, &first).expect("first marker");
+        assert_eq!(first_line.exit_code, 1);
+        assert_eq!(first_line.cwd.canonicalize().expect("cwd"), sub);
+
+        let second_line = split_marker_line(&text, &second).expect("second marker");
+        assert_eq!(second_line.exit_code, 0);
+        assert!(second_line.output.ends_with("hi\nread:\n"));
+    }
+}
@@ -59,6 +59,26 @@ pub(crate) enum UserNotification {
         /// The last message sent by the assistant in the turn.
         last_assistant_message: Option<String>,
     },
+
+    /// A scheduled prompt or subagent run started by `codex schedule run` finished.
+    #[serde(rename_all = "kebab-case")]
+    ScheduledRunComplete {
+        schedule_id: String,
+        thread_id: String,
+        cwd: String,
+        last_assistant_message: Option<String>,
+        error: Option<String>,
+    },
+
+    /// A `turn.post_process` hook returned a notification.
+    #[serde(rename_all = "kebab-case")]
+    HookNotification {
+        thread_id: String,
+        turn_id: String,
+        hook_id: Option<String>,
+        title: String,
+        body: Option<String>,
+    },
 }
 
 #[cfg(test)]
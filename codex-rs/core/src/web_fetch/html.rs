@@ -0,0 +1,515 @@
+//! A small HTML to markdown converter for `web_fetch`.
+//!
+//! It is not a full HTML parser: tags are read as they come, without building
+//! a tree, which is enough to keep the headings, paragraphs, lists, links,
+//! code blocks and tables of a typical page while dropping scripts, styles
+//! and markup.
+
+use url::Url;
+
+/// Elements whose content is never shown.
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "head", "title", "script", "style", "noscript", "template", "svg", "iframe", "object", "canvas",
+];
+
+/// `<title>` of the page, if any.
+pub(crate) fn extract_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = collapse_whitespace(&decode_entities(&html[start..end]));
+    (!title.is_empty()).then_some(title)
+}
+
+/// Converts `html` to markdown. Relative links and images are resolved
+/// against `base`.
+pub(crate) fn to_markdown(html: &str, base: &Url) -> String {
+    let lower = html.to_ascii_lowercase();
+    let mut writer = Writer::default();
+    let mut pos = 0;
+
+    while pos < html.len() {
+        let Some(offset) = html[pos..].find('<') else {
+            writer.text(&decode_entities(&html[pos..]));
+            break;
+        };
+        if offset > 0 {
+            writer.text(&decode_entities(&html[pos..pos + offset]));
+        }
+        pos += offset;
+        let rest = &html[pos..];
+
+        if rest.starts_with("<!--") {
+            pos = rest.find("-->").map_or(html.len(), |end| pos + end + 3);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            pos = rest.find('>').map_or(html.len(), |end| pos + end + 1);
+            continue;
+        }
+        let closing = rest.starts_with("</");
+        let name_start = if closing { 2 } else { 1 };
+        if !rest[name_start..].starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+            writer.text("<");
+            pos += 1;
+            continue;
+        }
+
+        let end = tag_end(rest);
+        let tag = &rest[name_start..end.saturating_sub(1).max(name_start)];
+        pos += end;
+        let name_len = tag
+            .find(|ch: char| ch.is_ascii_whitespace() || ch == '/' || ch == '>')
+            .unwrap_or(tag.len());
+        let name = tag[..name_len].to_ascii_lowercase();
+
+        if closing {
+            writer.close(&name);
+        } else if SKIPPED_ELEMENTS.contains(&name.as_str()) && !tag.ends_with('/') {
+            let close = format!("</{name}");
+            pos = lower[pos..].find(&close).map_or(html.len(), |start| {
+                let start = pos + start;
+                html[start..]
+                    .find('>')
+                    .map_or(html.len(), |end| start + end + 1)
+            });
+        } else {
+            writer.open(&name, &tag[name_len..], base);
+        }
+    }
+
+    tidy(&writer.out)
+}
+
+/// Index just past the `>` that ends the tag at the start of `rest`,
+/// skipping `>` inside quoted attribute values.
+fn tag_end(rest: &str) -> usize {
+    let mut quote: Option<char> = None;
+    for (index, ch) in rest.char_indices() {
+        match (quote, ch) {
+            (Some(q), ch) if ch == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '>') => return index + 1,
+            (None, _) => {}
+        }
+    }
+    rest.len()
+}
+
+/// Value of attribute `name` in the attribute text of a tag.
+fn attribute(attrs: &str, name: &str) -> Option<String> {
+    let mut rest = attrs;
+    loop {
+        rest = rest.trim_start_matches(|ch: char| ch.is_ascii_whitespace() || ch == '/');
+        if rest.is_empty() {
+            return None;
+        }
+        let key_len = rest
+            .find(|ch: char| ch.is_ascii_whitespace() || ch == '=' || ch == '/')
+            .unwrap_or(rest.len());
+        let key = &rest[..key_len];
+        rest = rest[key_len..].trim_start();
+        let mut value = None;
+        if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let (raw, remaining) = match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after_eq[1..];
+                    match inner.find(quote) {
+                        Some(end) => (&inner[..end], &inner[end + 1..]),
+                        None => (inner, ""),
+                    }
+                }
+                _ => {
+                    let end = after_eq
+                        .find(|ch: char| ch.is_ascii_whitespace())
+                        .unwrap_or(after_eq.len());
+                    (&after_eq[..end], &after_eq[end..])
+                }
+            };
+            value = Some(raw);
+            rest = remaining;
+        }
+        if key.eq_ignore_ascii_case(name) {
+            return Some(decode_entities(value.unwrap_or_default()));
+        }
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    out: String,
+    /// Line breaks owed before the next content.
+    pending_breaks: usize,
+    pending_space: bool,
+    quote_depth: usize,
+    /// Open lists, innermost last; ordered lists carry their next number.
+    lists: Vec<Option<usize>>,
+    pre_depth: usize,
+    /// Open links; `None` for anchors that are not rendered as links.
+    links: Vec<Option<String>>,
+    first_cell: bool,
+}
+
+impl Writer {
+    fn open(&mut self, name: &str, attrs: &str, base: &Url) {
+        match name {
+            "p" | "blockquote" | "table" | "dl" | "figure" => {
+                self.block(2);
+                if name == "blockquote" {
+                    self.quote_depth += 1;
+                }
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block(2);
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                self.begin(None);
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+            }
+            "ul" | "ol" => {
+                self.block(if self.lists.is_empty() { 2 } else { 1 });
+                self.lists.push((name == "ol").then_some(1));
+            }
+            "li" => {
+                self.block(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.begin(Some(&marker));
+            }
+            "pre" => {
+                self.block(2);
+                self.begin(None);
+                self.out.push_str("```\n");
+                self.pre_depth += 1;
+            }
+            "br" => self.line_break(),
+            "hr" => {
+                self.block(2);
+                self.begin(None);
+                self.out.push_str("---");
+                self.block(2);
+            }
+            "tr" => {
+                self.block(1);
+                self.first_cell = true;
+            }
+            "td" | "th" => {
+                if !self.first_cell {
+                    self.pending_space = false;
+                    self.begin(None);
+                    self.out.push_str(" |");
+                    self.pending_space = true;
+                }
+                self.first_cell = false;
+            }
+            "div" | "section" | "article" | "main" | "header" | "footer" | "nav" | "aside"
+            | "dt" | "dd" | "figcaption" | "caption" => self.block(1),
+            "strong" | "b" => self.inline_marker("**"),
+            "em" | "i" => self.inline_marker("*"),
+            "code" | "kbd" | "samp" => self.inline_marker("`"),
+            "a" => {
+                let href = attribute(attrs, "href")
+                    .filter(|href| !href.starts_with('#') && !href.starts_with("javascript:"))
+                    .and_then(|href| base.join(&href).ok());
+                if href.is_some() && self.pre_depth == 0 {
+                    self.begin(None);
+                    self.out.push('[');
+                }
+                self.links.push(href.map(|url| url.to_string()));
+            }
+            "img" => {
+                let alt = attribute(attrs, "alt").map(|alt| collapse_whitespace(&alt));
+                let src = attribute(attrs, "src").and_then(|src| base.join(&src).ok());
+                if let (Some(alt), Some(src)) = (alt, src)
+                    && !alt.is_empty()
+                {
+                    self.begin(None);
+                    self.out.push_str(&format!("![{alt}]({src})"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "p" | "table" | "dl" | "figure" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block(2);
+            }
+            "blockquote" => {
+                self.block(2);
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+            }
+            "ul" | "ol" => {
+                self.lists.pop();
+                self.block(if self.lists.is_empty() { 2 } else { 1 });
+            }
+            "pre" if self.pre_depth > 0 => {
+                self.pre_depth -= 1;
+                if !self.out.ends_with('\n') {
+                    self.out.push('\n');
+                }
+                self.out.push_str("```");
+                self.block(2);
+            }
+            "li" | "tr" | "div" | "section" | "article" | "main" | "header" | "footer" | "nav"
+            | "aside" | "dt" | "dd" | "figcaption" | "caption" => self.block(1),
+            "strong" | "b" => self.close_marker("**"),
+            "em" | "i" => self.close_marker("*"),
+            "code" | "kbd" | "samp" => self.close_marker("`"),
+            "a" => {
+                if let Some(Some(url)) = self.links.pop()
+                    && self.pre_depth == 0
+                {
+                    if self.out.ends_with('[') {
+                        self.out.pop();
+                    } else {
+                        self.out.push_str(&format!("]({url})"));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.pre_depth > 0 {
+            let text = if self.out.ends_with("```\n") {
+                text.strip_prefix('\n').unwrap_or(text)
+            } else {
+                text
+            };
+            self.out.push_str(text);
+            return;
+        }
+        if text.starts_with(char::is_whitespace) {
+            self.pending_space = true;
+        }
+        for (index, word) in text.split_whitespace().enumerate() {
+            if index > 0 {
+                self.pending_space = true;
+            }
+            self.begin(None);
+            self.out.push_str(word);
+        }
+        if text.ends_with(char::is_whitespace) {
+            self.pending_space = true;
+        }
+    }
+
+    fn inline_marker(&mut self, marker: &str) {
+        if self.pre_depth == 0 {
+            self.begin(None);
+            self.out.push_str(marker);
+        }
+    }
+
+    fn close_marker(&mut self, marker: &str) {
+        if self.pre_depth > 0 {
+            return;
+        }
+        // Drop markers around nothing, e.g. `<b></b>`.
+        if self.out.ends_with(marker) {
+            self.out.truncate(self.out.len() - marker.len());
+        } else {
+            self.out.push_str(marker);
+        }
+    }
+
+    fn block(&mut self, breaks: usize) {
+        self.pending_breaks = self.pending_breaks.max(breaks);
+        self.pending_space = false;
+    }
+
+    fn line_break(&mut self) {
+        self.out.push('\n');
+        self.pending_space = false;
+    }
+
+    /// Writes owed line breaks, then the quote and list prefix at the start
+    /// of a line or a pending space within one.
+    fn begin(&mut self, marker: Option<&str>) {
+        if self.pending_breaks > 0 && !self.out.is_empty() {
+            let existing = self.out.chars().rev().take_while(|ch| *ch == '\n').count();
+            for _ in existing..self.pending_breaks {
+                self.out.push('\n');
+            }
+        }
+        self.pending_breaks = 0;
+        if self.out.is_empty() || self.out.ends_with('\n') {
+            self.out.push_str(&"> ".repeat(self.quote_depth));
+            let depth = self.lists.len();
+            let indent = if marker.is_some() {
+                depth.saturating_sub(1)
+            } else {
+                depth
+            };
+            self.out.push_str(&"  ".repeat(indent));
+        } else if self.pending_space {
+            self.out.push(' ');
+        }
+        self.pending_space = false;
+        if let Some(marker) = marker {
+            self.out.push_str(marker);
+        }
+    }
+}
+
+/// Trims trailing whitespace and collapses runs of blank lines.
+fn tidy(markdown: &str) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in markdown.lines().map(str::trim_end) {
+        let blank = line.is_empty() || line.chars().all(|ch| ch == '>' || ch == ' ');
+        if blank && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(if blank { "" } else { line });
+    }
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| Some((decode_entity(&rest[1..=end])?, end + 2)));
+        match entity {
+            Some((ch, len)) => {
+                decoded.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "middot" => '·',
+        "bull" => '•',
+        "times" => '×',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn converts_common_markup() {
+        let html = r#"<!DOCTYPE html>
+<html><head><title>Guide &amp; Notes</title><style>body { color: red; }</style></head>
+<body>
+<nav><a href="/">Home</a></nav>
+<h1>Getting   started</h1>
+<p>Install the <code>cli</code> with <a href="/docs/install?x=1&amp;y=2">the installer</a>,
+then run it. <b>Do not</b> skip this.</p>
+<script>alert("<p>hidden</p>")</script>
+<ul>
+  <li>First</li>
+  <li>Second
+    <ol><li>Nested one</li><li>Nested two</li></ol>
+  </li>
+</ul>
+<pre><code>fn main() {
+    println!("a &lt; b");
+}
+</code></pre>
+<blockquote><p>Quoted text</p></blockquote>
+<table><tr><th>Name</th><th>Value</th></tr><tr><td>a</td><td>1</td></tr></table>
+<img src="logo.png" alt="Logo"><img src="spacer.gif" alt="">
+</body></html>"#;
+        let base = Url::parse("https://example.com/guide/").expect("valid url");
+
+        assert_eq!(extract_title(html), Some("Guide & Notes".to_string()));
+        assert_eq!(
+            to_markdown(html, &base),
+            "[Home](https://example.com/)
+
+# Getting started
+
+Install the `cli` with [the installer](https://example.com/docs/install?x=1&y=2), then run it. **Do not** skip this.
+
+- First
+- Second
+  1. Nested one
+  2. Nested two
+
+```
+fn main() {
+    println!(\"a < b\");
+}
+```
+
+> Quoted text
+
+Name | Value
+a | 1
+
+![Logo](https://example.com/guide/logo.png)"
+        );
+    }
+
+    #[test]
+    fn decodes_numeric_and_unknown_entities() {
+        assert_eq!(
+            decode_entities("&#169; &#x41; &unknown; a & b"),
+            "© A &unknown; a & b"
+        );
+    }
+}
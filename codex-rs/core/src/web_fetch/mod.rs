@@ -0,0 +1,353 @@
+//! Downloads for the `web_fetch` tool.
+//!
+//! Every URL, including each redirect target, is checked against the
+//! `[web_fetch]` domain lists and the sandbox's network setting before a
+//! request is made: denied domains are always refused, and without network
+//! access in the sandbox only the allowed domains can be reached. HTML pages
+//! are converted to markdown so they read well in the transcript.
+
+pub(crate) mod html;
+
+use std::net::IpAddr;
+
+use reqwest::StatusCode;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::header::LOCATION;
+use reqwest::redirect;
+use url::Host;
+use url::Url;
+
+use crate::config::types::OfflineConfig;
+use crate::config::types::WebFetchConfig;
+use crate::default_client::get_codex_user_agent;
+
+const MAX_REDIRECTS: usize = 5;
+
+/// A downloaded page, ready to be shown to the model.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FetchedPage {
+    /// URL the content was read from, after redirects.
+    pub url: String,
+    pub status: StatusCode,
+    pub content_type: Option<String>,
+    pub title: Option<String>,
+    /// Markdown for HTML pages, the body as text otherwise.
+    pub content: String,
+    /// Whether the body was cut off at `max_bytes`.
+    pub truncated: bool,
+}
+
+/// Checks `url` against the domain lists. `network_access` is whether the
+/// sandbox lets commands reach the network.
+pub(crate) fn check_url(
+    url: &Url,
+    config: &WebFetchConfig,
+    network_access: bool,
+) -> Result<(), String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!(
+            "only http and https URLs can be fetched, not `{}`",
+            url.scheme()
+        ));
+    }
+    let Some(host) = url.host() else {
+        return Err(format!("`{url}` has no host"));
+    };
+    let host_name = match &host {
+        Host::Domain(domain) => domain.trim_end_matches('.').to_ascii_lowercase(),
+        Host::Ipv4(ip) => ip.to_string(),
+        Host::Ipv6(ip) => ip.to_string(),
+    };
+
+    if matches_any(&host_name, &config.denied_domains) {
+        return Err(format!("`{host_name}` is in web_fetch.denied_domains"));
+    }
+    let allowed = matches_any(&host_name, &config.allowed_domains);
+    if !config.allowed_domains.is_empty() && !allowed {
+        return Err(format!("`{host_name}` is not in web_fetch.allowed_domains"));
+    }
+    if !network_access && !allowed {
+        return Err(format!(
+            "network access is disabled in the sandbox; add `{host_name}` to web_fetch.allowed_domains to fetch it"
+        ));
+    }
+    // Local services are only reachable when listed explicitly.
+    if is_local(&host) && !allowed {
+        return Err(format!(
+            "`{host_name}` is a local address; add it to web_fetch.allowed_domains to fetch it"
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `host` is one of `domains` or a subdomain of one.
+fn matches_any(host: &str, domains: &[String]) -> bool {
+    domains.iter().any(|domain| {
+        let domain = domain
+            .trim()
+            .trim_start_matches("*.")
+            .trim_start_matches('.')
+            .trim_end_matches('.')
+            .to_ascii_lowercase();
+        !domain.is_empty()
+            && (host == domain
+                || host
+                    .strip_suffix(&domain)
+                    .is_some_and(|prefix| prefix.ends_with('.')))
+    })
+}
+
+fn is_local(host: &Host<&str>) -> bool {
+    let ip = match host {
+        Host::Domain(domain) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            return domain == "localhost" || domain.ends_with(".localhost");
+        }
+        Host::Ipv4(ip) => IpAddr::V4(*ip),
+        Host::Ipv6(ip) => IpAddr::V6(*ip),
+    };
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+        }
+        IpAddr::V6(ip) => {
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.to_ipv4_mapped().is_some_and(|ip| {
+                    ip.is_loopback() || ip.is_private() || ip.is_link_local()
+                })
+                // Unique local (fc00::/7) and link-local (fe80::/10).
+                || (ip.segments()[0] & 0xfe00) == 0xfc00
+                || (ip.segments()[0] & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// Downloads `url`, following redirects that pass [`check_url`], within the
+/// configured size and time limits. With `raw`, HTML is returned unconverted.
+/// In offline mode every hop must also be a local endpoint.
+pub(crate) async fn fetch(
+    url: &str,
+    config: &WebFetchConfig,
+    offline: Option<&OfflineConfig>,
+    network_access: bool,
+    raw: bool,
+) -> Result<FetchedPage, String> {
+    let mut url = Url::parse(url).map_err(|err| format!("invalid URL `{url}`: {err}"))?;
+    let client = codex_client::apply_network_settings(
+        reqwest::Client::builder()
+            .user_agent(get_codex_user_agent())
+            .redirect(redirect::Policy::none()),
+    )
+    .build()
+    .map_err(|err| format!("failed to create HTTP client: {err}"))?;
+
+    let timeout_error = format!(
+        "timed out after {}s fetching {url}",
+        config.timeout.as_secs()
+    );
+    let download = async {
+        let mut redirects = 0;
+        let mut response = loop {
+            check_url(&url, config, network_access)?;
+            if let Some(offline) = offline {
+                offline
+                    .check_url("web_fetch", url.as_str())
+                    .map_err(|err| err.to_string())?;
+            }
+            let response = client
+                .get(url.clone())
+                .send()
+                .await
+                .map_err(|err| format!("failed to fetch {url}: {err}"))?;
+            if !response.status().is_redirection() {
+                break response;
+            }
+            let Some(location) = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+            else {
+                break response;
+            };
+            redirects += 1;
+            if redirects > MAX_REDIRECTS {
+                return Err(format!("too many redirects fetching {url}"));
+            }
+            url = url
+                .join(location)
+                .map_err(|err| format!("invalid redirect to `{location}`: {err}"))?;
+        };
+
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| format!("failed to read {url}: {err}"))?
+        {
+            let remaining = config.max_bytes.saturating_sub(body.len());
+            if chunk.len() > remaining {
+                body.extend_from_slice(&chunk[..remaining]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok::<_, String>((status, content_type, body, truncated))
+    };
+    let (status, content_type, body, truncated) = tokio::time::timeout(config.timeout, download)
+        .await
+        .map_err(|_| timeout_error)??;
+
+    let (content, title) = page_content(&url, content_type.as_deref(), &body, raw)?;
+    Ok(FetchedPage {
+        url: url.to_string(),
+        status,
+        content_type,
+        title,
+        content,
+        truncated,
+    })
+}
+
+/// The body as text, with HTML converted to markdown unless `raw`.
+fn page_content(
+    url: &Url,
+    content_type: Option<&str>,
+    body: &[u8],
+    raw: bool,
+) -> Result<(String, Option<String>), String> {
+    let mime = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase());
+    let text = String::from_utf8_lossy(body);
+    let is_html = match mime.as_deref() {
+        Some("text/html" | "application/xhtml+xml") => true,
+        Some(mime) if is_text_mime(mime) => false,
+        Some(mime) => {
+            return Err(format!(
+                "{url} returned `{mime}` content; web_fetch only returns text"
+            ));
+        }
+        None if std::str::from_utf8(body).is_err() => {
+            return Err(format!(
+                "{url} returned binary content; web_fetch only returns text"
+            ));
+        }
+        None => text.trim_start().starts_with('<'),
+    };
+    if !is_html {
+        return Ok((text.into_owned(), None));
+    }
+    let title = html::extract_title(&text);
+    if raw {
+        return Ok((text.into_owned(), title));
+    }
+    Ok((html::to_markdown(&text, url), title))
+}
+
+fn is_text_mime(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || mime.ends_with("+json")
+        || mime.ends_with("+xml")
+        || matches!(
+            mime,
+            "application/json"
+                | "application/xml"
+                | "application/javascript"
+                | "application/x-javascript"
+                | "application/x-yaml"
+                | "application/yaml"
+                | "application/toml"
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn config(allowed: &[&str], denied: &[&str]) -> WebFetchConfig {
+        WebFetchConfig {
+            allowed_domains: allowed.iter().map(ToString::to_string).collect(),
+            denied_domains: denied.iter().map(ToString::to_string).collect(),
+            ..WebFetchConfig::default()
+        }
+    }
+
+    fn check(url: &str, config: &WebFetchConfig, network_access: bool) -> Result<(), String> {
+        check_url(&Url::parse(url).expect("valid url"), config, network_access)
+    }
+
+    #[test]
+    fn domain_lists_and_sandbox_network_decide_access() {
+        let open = config(&[], &["tracker.example"]);
+        assert_eq!(check("https://docs.rs/serde", &open, true), Ok(()));
+        assert_eq!(
+            check("https://cdn.tracker.example/a.js", &open, true),
+            Err("`cdn.tracker.example` is in web_fetch.denied_domains".to_string())
+        );
+        assert_eq!(
+            check("https://docs.rs/serde", &open, false),
+            Err("network access is disabled in the sandbox; add `docs.rs` to web_fetch.allowed_domains to fetch it".to_string())
+        );
+        assert_eq!(
+            check("http://127.0.0.1:8080/", &open, true),
+            Err(
+                "`127.0.0.1` is a local address; add it to web_fetch.allowed_domains to fetch it"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            check("file:///etc/passwd", &open, true),
+            Err("only http and https URLs can be fetched, not `file`".to_string())
+        );
+
+        let listed = config(&["*.rust-lang.org", "localhost"], &[]);
+        assert_eq!(
+            check("https://doc.rust-lang.org/std", &listed, false),
+            Ok(())
+        );
+        assert_eq!(check("http://localhost:3000/", &listed, false), Ok(()));
+        assert_eq!(
+            check("https://notrust-lang.org/", &listed, true),
+            Err("`notrust-lang.org` is not in web_fetch.allowed_domains".to_string())
+        );
+    }
+
+    #[test]
+    fn converts_html_and_rejects_binary_content() {
+        let url = Url::parse("https://example.com/").expect("valid url");
+        assert_eq!(
+            page_content(
+                &url,
+                Some("text/html; charset=utf-8"),
+                b"<title>Hi</title><p>Hello <em>there</em></p>",
+                false,
+            ),
+            Ok(("Hello *there*".to_string(), Some("Hi".to_string())))
+        );
+        assert_eq!(
+            page_content(&url, Some("application/json"), b"{\"a\":1}", false),
+            Ok(("{\"a\":1}".to_string(), None))
+        );
+        assert_eq!(
+            page_content(&url, Some("image/png"), b"\x89PNG", false),
+            Err(
+                "https://example.com/ returned `image/png` content; web_fetch only returns text"
+                    .to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Backends for the local `web_search` tool configured under
+//! `[web_search_provider]`.
+//!
+//! Each provider is a plain HTTP GET returning JSON; the results are reduced
+//! to title, URL and snippet so every provider looks the same to the model.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use url::Url;
+
+use crate::config::types::OfflineConfig;
+use crate::config::types::WebSearchProviderKind;
+use crate::config::types::WebSearchProviderToml;
+use crate::default_client::build_reqwest_client;
+use crate::web_fetch::html::decode_entities;
+
+const DEFAULT_MAX_RESULTS: usize = 5;
+pub(crate) const MAX_SEARCH_RESULTS: usize = 20;
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(15);
+const BING_ENDPOINT: &str = "https://api.bing.microsoft.com/v7.0/search";
+const BRAVE_ENDPOINT: &str = "https://api.search.brave.com/res/v1/web/search";
+
+/// Extra request headers, such as the API key.
+type Headers = Vec<(&'static str, String)>;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+}
+
+/// Runs `query` against the configured provider. `count` overrides the
+/// configured number of results, up to [`MAX_SEARCH_RESULTS`]. In offline
+/// mode the provider must be a local endpoint.
+pub(crate) async fn search(
+    provider: &WebSearchProviderToml,
+    offline: Option<&OfflineConfig>,
+    query: &str,
+    count: Option<usize>,
+) -> Result<Vec<SearchResult>, String> {
+    let count = count
+        .or(provider.max_results)
+        .unwrap_or(DEFAULT_MAX_RESULTS)
+        .clamp(1, MAX_SEARCH_RESULTS);
+    let api_key = match api_key_env(provider) {
+        Some(name) => match std::env::var(&name) {
+            Ok(key) if !key.is_empty() => Some(key),
+            _ => return Err(format!("set {name} to use web_search with this provider")),
+        },
+        None => None,
+    };
+    let (url, headers) = build_request(provider, query, count, api_key)?;
+    if let Some(offline) = offline {
+        offline
+            .check_url("web_search", &url.origin().ascii_serialization())
+            .map_err(|err| err.to_string())?;
+    }
+
+    let mut request = build_reqwest_client()
+        .get(url)
+        .header("Accept", "application/json")
+        .timeout(SEARCH_TIMEOUT);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|err| format!("web search request failed: {err}"))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|err| format!("failed to read web search response: {err}"))?;
+    if !status.is_success() {
+        let body: String = body.chars().take(500).collect();
+        return Err(format!("web search provider returned {status}: {body}"));
+    }
+    let body: Value = serde_json::from_str(&body)
+        .map_err(|err| format!("web search provider returned invalid JSON: {err}"))?;
+    Ok(parse_results(provider.provider, &body, count))
+}
+
+/// Results as JSON for the model.
+pub(crate) fn format_results(query: &str, results: &[SearchResult]) -> String {
+    serde_json::json!({
+        "query": query,
+        "results": results,
+    })
+    .to_string()
+}
+
+fn api_key_env(provider: &WebSearchProviderToml) -> Option<String> {
+    provider
+        .api_key_env
+        .clone()
+        .or_else(|| match provider.provider {
+            WebSearchProviderKind::Bing => Some("BING_SEARCH_API_KEY".to_string()),
+            WebSearchProviderKind::Brave => Some("BRAVE_SEARCH_API_KEY".to_string()),
+            WebSearchProviderKind::Searxng | WebSearchProviderKind::Custom => None,
+        })
+}
+
+/// URL and provider-specific headers for a search.
+fn build_request(
+    provider: &WebSearchProviderToml,
+    query: &str,
+    count: usize,
+    api_key: Option<String>,
+) -> Result<(Url, Headers), String> {
+    let endpoint = match (provider.provider, provider.endpoint.as_deref()) {
+        (_, Some(endpoint)) => endpoint,
+        (WebSearchProviderKind::Bing, None) => BING_ENDPOINT,
+        (WebSearchProviderKind::Brave, None) => BRAVE_ENDPOINT,
+        (WebSearchProviderKind::Searxng | WebSearchProviderKind::Custom, None) => {
+            return Err("web_search_provider.endpoint is not set".to_string());
+        }
+    };
+    let mut url = Url::parse(endpoint)
+        .map_err(|err| format!("invalid web_search_provider.endpoint `{endpoint}`: {err}"))?;
+    if provider.provider == WebSearchProviderKind::Searxng && !url.path().ends_with("/search") {
+        let path = format!("{}/search", url.path().trim_end_matches('/'));
+        url.set_path(&path);
+    }
+
+    let count = count.to_string();
+    let mut headers = Vec::new();
+    {
+        let mut params = url.query_pairs_mut();
+        params.append_pair("q", query);
+        match provider.provider {
+            WebSearchProviderKind::Searxng => {
+                params.append_pair("format", "json");
+            }
+            WebSearchProviderKind::Bing
+            | WebSearchProviderKind::Brave
+            | WebSearchProviderKind::Custom => {
+                params.append_pair("count", &count);
+            }
+        }
+    }
+    if let Some(api_key) = api_key {
+        let header = match provider.provider {
+            WebSearchProviderKind::Bing => ("Ocp-Apim-Subscription-Key", api_key),
+            WebSearchProviderKind::Brave => ("X-Subscription-Token", api_key),
+            WebSearchProviderKind::Searxng | WebSearchProviderKind::Custom => {
+                ("Authorization", format!("Bearer {api_key}"))
+            }
+        };
+        headers.push(header);
+    }
+    Ok((url, headers))
+}
+
+/// Pulls up to `count` results out of a provider response.
+fn parse_results(kind: WebSearchProviderKind, body: &Value, count: usize) -> Vec<SearchResult> {
+    // Where the result list lives and what each provider calls the fields.
+    let (list, title, snippet) = match kind {
+        WebSearchProviderKind::Bing => (&body["webPages"]["value"], "name", "snippet"),
+        WebSearchProviderKind::Brave => (&body["web"]["results"], "title", "description"),
+        WebSearchProviderKind::Searxng => (&body["results"], "title", "content"),
+        WebSearchProviderKind::Custom => (&body["results"], "title", "snippet"),
+    };
+    list.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let url = item["url"].as_str()?.to_string();
+            Some(SearchResult {
+                title: clean_text(item[title].as_str().unwrap_or_default()),
+                url,
+                snippet: clean_text(item[snippet].as_str().unwrap_or_default()),
+            })
+        })
+        .take(count)
+        .collect()
+}
+
+/// Drops highlighting markup such as `<strong>` and collapses whitespace.
+fn clean_text(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for ch in text.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => stripped.push(ch),
+            _ => {}
+        }
+    }
+    decode_entities(&stripped)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn provider(kind: WebSearchProviderKind, endpoint: Option<&str>) -> WebSearchProviderToml {
+        WebSearchProviderToml {
+            provider: kind,
+            endpoint: endpoint.map(str::to_string),
+            api_key_env: None,
+            max_results: None,
+        }
+    }
+
+    #[test]
+    fn builds_provider_requests() {
+        let (url, headers) = build_request(
+            &provider(WebSearchProviderKind::Brave, None),
+            "rust async",
+            3,
+            Some("key".to_string()),
+        )
+        .expect("brave request");
+        assert_eq!(
+            url.as_str(),
+            "https://api.search.brave.com/res/v1/web/search?q=rust+async&count=3"
+        );
+        assert_eq!(headers, vec![("X-Subscription-Token", "key".to_string())]);
+
+        let (url, headers) = build_request(
+            &provider(
+                WebSearchProviderKind::Searxng,
+                Some("http://localhost:8888/"),
+            ),
+            "tokio",
+            5,
+            None,
+        )
+        .expect("searxng request");
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8888/search?q=tokio&format=json"
+        );
+        assert_eq!(headers, Vec::new());
+    }
+
+    #[test]
+    fn normalizes_results_across_providers() {
+        let bing = json!({
+            "webPages": { "value": [
+                { "name": "Tokio", "url": "https://tokio.rs/", "snippet": "An <b>async</b> runtime &amp; more" },
+                { "name": "No url" },
+            ]}
+        });
+        assert_eq!(
+            parse_results(WebSearchProviderKind::Bing, &bing, 5),
+            vec![SearchResult {
+                title: "Tokio".to_string(),
+                url: "https://tokio.rs/".to_string(),
+                snippet: "An async runtime & more".to_string(),
+            }]
+        );
+
+        let searxng = json!({
+            "results": [
+                { "title": "a", "url": "https://a.example/", "content": "first" },
+                { "title": "b", "url": "https://b.example/", "content": "second" },
+            ]
+        });
+        assert_eq!(
+            parse_results(WebSearchProviderKind::Searxng, &searxng, 1)
+                .into_iter()
+                .map(|result| result.url)
+                .collect::<Vec<_>>(),
+            vec!["https://a.example/".to_string()]
+        );
+        assert_eq!(
+            parse_results(WebSearchProviderKind::Brave, &json!({}), 5),
+            Vec::new()
+        );
+    }
+}
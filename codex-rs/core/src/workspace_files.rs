@@ -0,0 +1,144 @@
+//! Listing the files of a project for workspace-wide tools.
+//!
+//! Inside a git repository the files are the ones `git ls-files` reports
+//! (tracked plus untracked, minus ignored); elsewhere the directory is
+//! walked, skipping hidden and common build directories.
+
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+/// Stops the listing on huge trees; callers report when it was cut off.
+const MAX_FILES: usize = 50_000;
+
+/// Directories skipped when walking a tree that is not a git repository.
+pub(crate) const SKIPPED_DIRS: &[&str] =
+    &["node_modules", "target", "dist", "build", "__pycache__"];
+
+/// Sorted paths (relative, `/`-separated) of the files in the project at
+/// `root`, and whether the list was cut off at `MAX_FILES`. Codex's own
+/// `.codex/` directory is left out.
+pub(crate) fn list_files(root: &Path) -> io::Result<(Vec<String>, bool)> {
+    let mut files = match git_ls_files(root) {
+        Some(files) => files,
+        None => {
+            let mut files = Vec::new();
+            walk_dir(root, root, &mut files)?;
+            files
+        }
+    };
+    files.retain(|rel| !rel.starts_with(".codex/"));
+    files.sort();
+    files.dedup();
+    let truncated = files.len() > MAX_FILES;
+    files.truncate(MAX_FILES);
+    Ok((files, truncated))
+}
+
+fn git_ls_files(root: &Path) -> Option<Vec<String>> {
+    if !root.join(".git").exists() {
+        return None;
+    }
+    let output = Command::new("git")
+        .args([
+            "ls-files",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+            "-z",
+        ])
+        .current_dir(root)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        output
+            .stdout
+            .split(|byte| *byte == 0)
+            .filter(|path| !path.is_empty())
+            .map(|path| String::from_utf8_lossy(path).into_owned())
+            .collect(),
+    )
+}
+
+fn walk_dir(root: &Path, dir: &Path, files: &mut Vec<String>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        if files.len() > MAX_FILES {
+            return Ok(());
+        }
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                walk_dir(root, &entry.path(), files)?;
+            }
+        } else if file_type.is_file()
+            && let Ok(rel) = entry.path().strip_prefix(root)
+        {
+            files.push(rel.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+/// Whether the relative path `rel` is `prefix` or inside it. `None`, `""`,
+/// and `"."` match every path.
+pub(crate) fn is_under(rel: &str, prefix: Option<&str>) -> bool {
+    let Some(prefix) = prefix
+        .map(|prefix| prefix.trim_start_matches("./").trim_end_matches('/'))
+        .filter(|prefix| !prefix.is_empty() && *prefix != ".")
+    else {
+        return true;
+    };
+    rel == prefix
+        || rel
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Size and modification time (ms since the epoch) of a regular file, used to
+/// tell whether it changed since it was last read.
+pub(crate) fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    let mtime_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_millis() as u64)
+        .unwrap_or_default();
+    Some((metadata.len(), mtime_ms))
+}
+
+/// File contents, or `None` for binary files.
+pub(crate) fn read_text(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    if bytes.iter().take(8 * 1024).any(|byte| *byte == 0) {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_under_matches_whole_components() {
+        assert!(is_under("src/lib.rs", None));
+        assert!(is_under("src/lib.rs", Some("./")));
+        assert!(is_under("src/lib.rs", Some("./src/")));
+        assert!(is_under("src/lib.rs", Some("src/lib.rs")));
+        assert!(!is_under("src2/lib.rs", Some("src")));
+    }
+}
@@ -0,0 +1,403 @@
+//! Runs the agent against its own git worktree (`[worktree_isolation]`).
+//!
+//! The first user turn snapshots the checkout with a ghost commit
+//! (uncommitted edits included) and checks the snapshot out under
+//! `$CODEX_HOME/worktrees/` on a new branch. Every turn then runs with its
+//! working directory mapped into that worktree, so the checkout is left
+//! alone. `Op::ReviewWorktree` shows what changed since the snapshot and
+//! `Op::FinishWorktree` applies it to the checkout or throws it away; the
+//! next user turn starts a fresh worktree. With `scope = "turn"` the review
+//! is also sent at the end of every turn. Worktrees that are never finished
+//! stay on disk with their branch so no work is lost.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use codex_git::CreateGhostCommitOptions;
+use codex_git::add_worktree_on_branch;
+use codex_git::apply_diff_to_worktree;
+use codex_git::create_ghost_commit;
+use codex_git::delete_branch;
+use codex_git::remove_worktree;
+use codex_git::worktree_diff;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::WorktreeCreatedEvent;
+use codex_protocol::protocol::WorktreeFileChange;
+use codex_protocol::protocol::WorktreeFinishAction;
+use codex_protocol::protocol::WorktreeFinishedEvent;
+use codex_protocol::protocol::WorktreeReviewEvent;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::config::types::WorktreeIsolationConfig;
+use crate::config::types::WorktreeScope;
+use crate::git_info::get_git_repo_root;
+use crate::subagents::merge::parse_diff;
+
+#[derive(Debug, Clone)]
+struct IsolatedWorktree {
+    repo_root: PathBuf,
+    path: PathBuf,
+    branch: String,
+    base_commit: String,
+}
+
+impl IsolatedWorktree {
+    /// `cwd` inside the checkout, moved to the same place in the worktree.
+    fn map(&self, cwd: &Path) -> Option<PathBuf> {
+        cwd.strip_prefix(&self.repo_root)
+            .ok()
+            .map(|relative| self.path.join(relative))
+    }
+}
+
+pub(crate) struct WorktreeIsolation {
+    config: WorktreeIsolationConfig,
+    codex_home: PathBuf,
+    thread_id: ThreadId,
+    active: Mutex<Option<IsolatedWorktree>>,
+    next_index: AtomicU64,
+}
+
+impl WorktreeIsolation {
+    pub(crate) fn new(
+        config: WorktreeIsolationConfig,
+        codex_home: &Path,
+        thread_id: ThreadId,
+    ) -> Self {
+        Self {
+            config,
+            codex_home: codex_home.to_path_buf(),
+            thread_id,
+            active: Mutex::new(None),
+            next_index: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn scope(&self) -> WorktreeScope {
+        self.config.scope
+    }
+
+    /// Where a turn in `cwd` runs: inside the active worktree, if any.
+    pub(crate) async fn map_cwd(&self, cwd: &Path) -> Option<PathBuf> {
+        self.active.lock().await.as_ref()?.map(cwd)
+    }
+
+    /// Creates a worktree for the checkout containing `cwd` unless one is
+    /// active. Returns the event announcing a newly created worktree.
+    async fn ensure(&self, cwd: &Path) -> Result<Option<WorktreeCreatedEvent>, String> {
+        let mut active = self.active.lock().await;
+        if active.is_some() {
+            return Ok(None);
+        }
+        let Some(repo_root) = get_git_repo_root(cwd) else {
+            return Err(format!(
+                "worktree_isolation requires a Git repository, but {} is not in one.",
+                cwd.display()
+            ));
+        };
+        let index = self.next_index.fetch_add(1, Ordering::Relaxed) + 1;
+        let name = format!("{}-{index}", self.thread_id);
+        let branch = format!("{}{name}", self.config.branch_prefix);
+        let path = self.codex_home.join("worktrees").join(&name);
+        let result = tokio::task::spawn_blocking(move || {
+            let base = create_ghost_commit(
+                &CreateGhostCommitOptions::new(&repo_root).message("codex worktree base"),
+            )?;
+            add_worktree_on_branch(&repo_root, &path, &branch, base.id())?;
+            Ok::<_, codex_git::GitToolingError>(IsolatedWorktree {
+                repo_root,
+                path,
+                branch,
+                base_commit: base.id().to_string(),
+            })
+        })
+        .await;
+        let worktree = match result {
+            Ok(Ok(worktree)) => worktree,
+            Ok(Err(err)) => return Err(format!("Failed to create an isolated worktree: {err}")),
+            Err(err) => return Err(format!("Worktree task failed: {err}")),
+        };
+        let event = WorktreeCreatedEvent {
+            path: worktree.path.clone(),
+            branch: worktree.branch.clone(),
+            base_commit: worktree.base_commit.clone(),
+        };
+        *active = Some(worktree);
+        Ok(Some(event))
+    }
+
+    async fn review(&self) -> Result<WorktreeReviewEvent, String> {
+        let Some(worktree) = self.active.lock().await.clone() else {
+            return Err("No isolated worktree is active.".to_string());
+        };
+        let unified_diff = collect_diff(&worktree).await?;
+        Ok(WorktreeReviewEvent {
+            files: file_changes(&unified_diff),
+            path: worktree.path,
+            branch: worktree.branch,
+            unified_diff,
+        })
+    }
+
+    /// Merges or discards the active worktree. A diff that no longer applies
+    /// to the checkout leaves the worktree in place.
+    async fn finish(&self, action: WorktreeFinishAction) -> Result<WorktreeFinishedEvent, String> {
+        let mut active = self.active.lock().await;
+        let Some(worktree) = active.clone() else {
+            return Err("No isolated worktree is active.".to_string());
+        };
+        let diff = collect_diff(&worktree).await?;
+        let files = file_changes(&diff)
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+
+        let branch = worktree.branch.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            if action == WorktreeFinishAction::Merge {
+                // Check first so a diff that no longer applies leaves nothing behind.
+                apply_diff_to_worktree(&worktree.repo_root, &diff, true)
+                    .and_then(|()| apply_diff_to_worktree(&worktree.repo_root, &diff, false))
+                    .map_err(|err| {
+                        format!("The worktree changes no longer apply to the checkout: {err}")
+                    })?;
+            }
+            if let Err(err) = remove_worktree(&worktree.repo_root, &worktree.path) {
+                warn!(
+                    "failed to remove worktree {}: {err}",
+                    worktree.path.display()
+                );
+            } else if let Err(err) = delete_branch(&worktree.repo_root, &worktree.branch) {
+                warn!("failed to delete branch {}: {err}", worktree.branch);
+            }
+            Ok::<_, String>(())
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(message)) => return Err(message),
+            Err(err) => return Err(format!("Worktree task failed: {err}")),
+        }
+        *active = None;
+        Ok(WorktreeFinishedEvent {
+            action,
+            branch,
+            files,
+        })
+    }
+}
+
+async fn collect_diff(worktree: &IsolatedWorktree) -> Result<String, String> {
+    let path = worktree.path.clone();
+    let base = worktree.base_commit.clone();
+    match tokio::task::spawn_blocking(move || worktree_diff(&path, &base)).await {
+        Ok(Ok(diff)) => Ok(diff),
+        Ok(Err(err)) => Err(format!("Failed to diff the isolated worktree: {err}")),
+        Err(err) => Err(format!("Worktree task failed: {err}")),
+    }
+}
+
+fn file_changes(diff: &str) -> Vec<WorktreeFileChange> {
+    parse_diff(diff)
+        .into_iter()
+        .map(|file| {
+            let (added, removed) = file.line_counts();
+            WorktreeFileChange {
+                path: file.path,
+                added,
+                removed,
+            }
+        })
+        .collect()
+}
+
+/// Makes sure a user turn has a worktree to run in. Returns `false`, after
+/// reporting the error, when the turn must not start.
+pub(crate) async fn prepare_turn(sess: &Session, sub_id: &str, cwd: &Path) -> bool {
+    let Some(isolation) = sess.services.worktree_isolation.as_ref() else {
+        return true;
+    };
+    let (msg, proceed) = match isolation.ensure(cwd).await {
+        Ok(None) => return true,
+        Ok(Some(created)) => (EventMsg::WorktreeCreated(created), true),
+        Err(message) => (error_msg(message), false),
+    };
+    sess.send_event_raw(Event {
+        id: sub_id.to_string(),
+        msg,
+    })
+    .await;
+    proceed
+}
+
+pub(crate) async fn review_worktree(sess: &Session, sub_id: String) {
+    let msg = match sess.services.worktree_isolation.as_ref() {
+        Some(isolation) => match isolation.review().await {
+            Ok(review) => EventMsg::WorktreeReview(review),
+            Err(message) => error_msg(message),
+        },
+        None => not_enabled(),
+    };
+    sess.send_event_raw(Event { id: sub_id, msg }).await;
+}
+
+/// Sends the review at the end of a turn when `scope = "turn"` and the
+/// worktree has changes.
+pub(crate) async fn review_after_turn(sess: &Session, sub_id: &str) {
+    let Some(isolation) = sess.services.worktree_isolation.as_ref() else {
+        return;
+    };
+    if isolation.scope() != WorktreeScope::Turn || isolation.active.lock().await.is_none() {
+        return;
+    }
+    match isolation.review().await {
+        Ok(review) if review.files.is_empty() => {}
+        Ok(review) => {
+            sess.send_event_raw(Event {
+                id: sub_id.to_string(),
+                msg: EventMsg::WorktreeReview(review),
+            })
+            .await;
+        }
+        Err(message) => warn!("failed to review the isolated worktree: {message}"),
+    }
+}
+
+pub(crate) async fn finish_worktree(sess: &Session, sub_id: String, action: WorktreeFinishAction) {
+    let has_active_turn = { sess.active_turn.lock().await.is_some() };
+    let msg = match sess.services.worktree_isolation.as_ref() {
+        Some(_) if has_active_turn => {
+            error_msg("Cannot finish the worktree while a turn is in progress.".to_string())
+        }
+        Some(isolation) => match isolation.finish(action).await {
+            Ok(finished) => EventMsg::WorktreeFinished(finished),
+            Err(message) => error_msg(message),
+        },
+        None => not_enabled(),
+    };
+    sess.send_event_raw(Event { id: sub_id, msg }).await;
+}
+
+fn not_enabled() -> EventMsg {
+    error_msg(
+        "Worktree isolation is not enabled; add a `[worktree_isolation]` section to config.toml."
+            .to_string(),
+    )
+}
+
+fn error_msg(message: String) -> EventMsg {
+    EventMsg::Error(ErrorEvent {
+        message,
+        codex_error_info: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn run_git(repo: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    fn isolation(codex_home: &Path, scope: WorktreeScope) -> WorktreeIsolation {
+        WorktreeIsolation::new(
+            WorktreeIsolationConfig {
+                scope,
+                branch_prefix: "codex/".to_string(),
+            },
+            codex_home,
+            ThreadId::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn merge_applies_worktree_changes_to_checkout() {
+        let temp = tempdir().expect("tempdir");
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(repo.join("src")).expect("mkdir");
+        run_git(&repo, &["init", "--initial-branch=main"]);
+        std::fs::write(repo.join("src/a.txt"), "one\n").expect("write");
+        let isolation = isolation(&temp.path().join("home"), WorktreeScope::Session);
+
+        let created = isolation
+            .ensure(&repo.join("src"))
+            .await
+            .expect("ensure")
+            .expect("created");
+        assert!(created.branch.starts_with("codex/"));
+        assert_eq!(isolation.ensure(&repo).await, Ok(None));
+        let cwd = isolation
+            .map_cwd(&repo.join("src"))
+            .await
+            .expect("mapped cwd");
+        assert_eq!(cwd, created.path.join("src"));
+
+        std::fs::write(cwd.join("a.txt"), "one\ntwo\n").expect("write");
+        let review = isolation.review().await.expect("review");
+        assert_eq!(
+            review.files,
+            vec![WorktreeFileChange {
+                path: PathBuf::from("src/a.txt"),
+                added: 1,
+                removed: 0,
+            }]
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.join("src/a.txt")).expect("read"),
+            "one\n"
+        );
+
+        let finished = isolation
+            .finish(WorktreeFinishAction::Merge)
+            .await
+            .expect("finish");
+        assert_eq!(finished.files, vec![PathBuf::from("src/a.txt")]);
+        assert_eq!(
+            std::fs::read_to_string(repo.join("src/a.txt")).expect("read"),
+            "one\ntwo\n"
+        );
+        assert!(!created.path.exists());
+        assert_eq!(isolation.map_cwd(&repo).await, None);
+    }
+
+    #[tokio::test]
+    async fn discard_leaves_checkout_untouched() {
+        let temp = tempdir().expect("tempdir");
+        let repo = temp.path().join("repo");
+        std::fs::create_dir(&repo).expect("mkdir");
+        run_git(&repo, &["init", "--initial-branch=main"]);
+        std::fs::write(repo.join("a.txt"), "one\n").expect("write");
+        let isolation = isolation(&temp.path().join("home"), WorktreeScope::Turn);
+
+        let created = isolation
+            .ensure(&repo)
+            .await
+            .expect("ensure")
+            .expect("created");
+        std::fs::write(created.path.join("b.txt"), "new\n").expect("write");
+        let finished = isolation
+            .finish(WorktreeFinishAction::Discard)
+            .await
+            .expect("finish");
+
+        assert_eq!(finished.files, vec![PathBuf::from("b.txt")]);
+        assert!(!repo.join("b.txt").exists());
+        assert!(!created.path.exists());
+    }
+}
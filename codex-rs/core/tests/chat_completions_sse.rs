@@ -58,6 +58,9 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        api_version: None,
+        deployments: None,
+        entra_id: None,
     };
 
     let codex_home = match TempDir::new() {
@@ -56,6 +56,9 @@ async fn responses_stream_includes_subagent_header_on_review() {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        api_version: None,
+        deployments: None,
+        entra_id: None,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -152,6 +155,9 @@ async fn responses_stream_includes_subagent_header_on_other() {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        api_version: None,
+        deployments: None,
+        entra_id: None,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -304,6 +310,9 @@ async fn responses_respects_model_info_overrides_from_config() {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        api_version: None,
+        deployments: None,
+        entra_id: None,
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -2,8 +2,11 @@
 
 use anyhow::Result;
 use codex_core::config::Constrained;
+use codex_core::config::types::ApprovalTimeout;
 use codex_core::features::Feature;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::ApprovalKind;
+use codex_core::protocol::ApprovalTimeoutAction;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
@@ -33,6 +36,7 @@ use serde_json::json;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 use wiremock::Mock;
 use wiremock::MockServer;
 use wiremock::ResponseTemplate;
@@ -1839,3 +1843,84 @@ async fn approving_execpolicy_amendment_persists_policy_and_skips_future_prompts
 
     Ok(())
 }
+
+#[tokio::test(flavor = "current_thread")]
+#[cfg(unix)]
+async fn unanswered_exec_approval_times_out_with_configured_action() -> Result<()> {
+    let server = start_mock_server().await;
+    let approval_policy = AskForApproval::UnlessTrusted;
+    let sandbox_policy = SandboxPolicy::ReadOnly;
+    let sandbox_policy_for_config = sandbox_policy.clone();
+    let mut builder = test_codex().with_config(move |config| {
+        config.approval_policy = Constrained::allow_any(approval_policy);
+        config.sandbox_policy = Constrained::allow_any(sandbox_policy_for_config);
+        config.approval_timeouts.exec = Some(ApprovalTimeout {
+            timeout: Duration::from_millis(200),
+            action: ApprovalTimeoutAction::AllowReadOnly,
+        });
+    });
+    let test = builder.build(&server).await?;
+    let target = test.cwd.path().join("timeout.txt");
+    let _ = fs::remove_file(&target);
+
+    let call_id = "timeout-touch";
+    let (event, expected_command) = ActionKind::RunCommand {
+        command: "touch timeout.txt",
+    }
+    .prepare(&test, &server, call_id, SandboxPermissions::UseDefault)
+    .await?;
+    let expected_command = expected_command.expect("run command should produce a shell command");
+
+    let _ = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-timeout-1"),
+            event,
+            ev_completed("resp-timeout-1"),
+        ]),
+    )
+    .await;
+    let results = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_assistant_message("msg-timeout-1", "done"),
+            ev_completed("resp-timeout-2"),
+        ]),
+    )
+    .await;
+
+    submit_turn(
+        &test,
+        "touch after timeout",
+        approval_policy,
+        sandbox_policy,
+    )
+    .await?;
+
+    let approval = expect_exec_approval(&test, expected_command.as_str()).await;
+    let timed_out = wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::ApprovalTimedOut(_))
+    })
+    .await;
+    let EventMsg::ApprovalTimedOut(timed_out) = timed_out else {
+        unreachable!("filtered above");
+    };
+    assert_eq!(timed_out.call_id, approval.call_id);
+    assert_eq!(timed_out.kind, ApprovalKind::Exec);
+    assert_eq!(timed_out.action, ApprovalTimeoutAction::AllowReadOnly);
+    // `touch` writes, so `allow-read-only` falls back to denying it.
+    assert_eq!(timed_out.decision, ReviewDecision::Denied);
+    wait_for_completion(&test).await;
+
+    let output = results
+        .single_request()
+        .function_call_output_text(call_id)
+        .expect("function call output");
+    assert!(
+        output.contains("exec command rejected by user"),
+        "unexpected output: {output}"
+    );
+    assert!(!target.exists(), "timed-out command must not run");
+
+    Ok(())
+}
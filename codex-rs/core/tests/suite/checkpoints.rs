@@ -0,0 +1,128 @@
+#![cfg(not(target_os = "windows"))]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_core::CodexThread;
+use codex_core::protocol::Checkpoint;
+use codex_core::protocol::CheckpointDiffStat;
+use codex_core::protocol::CheckpointRestoredEvent;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodexHarness;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+
+fn git(path: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .status()
+        .with_context(|| format!("failed to run git {args:?}"))?;
+    if status.success() {
+        return Ok(());
+    }
+    bail!("git {args:?} exited with {status}");
+}
+
+fn init_git_repo(path: &Path) -> Result<()> {
+    git(path, &["init", "--initial-branch=main"])?;
+    git(path, &["config", "core.autocrlf", "false"])?;
+    git(path, &["config", "user.name", "Codex Tests"])?;
+    git(path, &["config", "user.email", "codex-tests@example.com"])?;
+    fs::write(path.join("tracked.txt"), "v0\n")?;
+    git(path, &["add", "tracked.txt"])?;
+    git(path, &["commit", "-m", "initial"])?;
+    Ok(())
+}
+
+async fn create_checkpoint(codex: &Arc<CodexThread>, name: &str) -> Result<Checkpoint> {
+    codex
+        .submit(Op::CreateCheckpoint {
+            name: name.to_string(),
+        })
+        .await?;
+    let checkpoint = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::CheckpointCreated(ev) => Some(ev.checkpoint.clone()),
+        _ => None,
+    })
+    .await;
+    Ok(checkpoint)
+}
+
+async fn list_checkpoints(codex: &Arc<CodexThread>) -> Result<Vec<Checkpoint>> {
+    codex.submit(Op::ListCheckpoints).await?;
+    let checkpoints = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::ListCheckpointsResponse(ev) => Some(ev.checkpoints.clone()),
+        _ => None,
+    })
+    .await;
+    Ok(checkpoints)
+}
+
+async fn restore_checkpoint(codex: &Arc<CodexThread>, id: &str) -> Result<CheckpointRestoredEvent> {
+    codex
+        .submit(Op::RestoreCheckpoint { id: id.to_string() })
+        .await?;
+    let restored = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::CheckpointRestored(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    Ok(restored)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn restore_reverts_past_several_checkpoints() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = TestCodexHarness::with_builder(test_codex()).await?;
+    init_git_repo(harness.cwd())?;
+    let codex = Arc::clone(&harness.test().codex);
+    let tracked = harness.path("tracked.txt");
+    let scratch = harness.path("scratch.txt");
+
+    let before_refactor = create_checkpoint(&codex, "before refactor").await?;
+    assert_eq!(before_refactor.name, "before refactor");
+
+    fs::write(&tracked, "v1\n")?;
+    let second = create_checkpoint(&codex, "").await?;
+    assert_eq!(second.name, "checkpoint 2");
+
+    fs::write(&tracked, "v2\nmore\n")?;
+    fs::write(&scratch, "temp\n")?;
+
+    let listed = list_checkpoints(&codex).await?;
+    let ids: Vec<&str> = listed.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, vec![before_refactor.id.as_str(), second.id.as_str()]);
+    assert_eq!(
+        listed[0].diffstat,
+        Some(CheckpointDiffStat {
+            files_changed: 2,
+            insertions: 3,
+            deletions: 1,
+        })
+    );
+
+    let restored = restore_checkpoint(&codex, &before_refactor.id).await?;
+    assert!(restored.success, "restore failed: {:?}", restored.message);
+    assert_eq!(fs::read_to_string(&tracked)?, "v0\n");
+    assert!(!scratch.exists());
+
+    let remaining = list_checkpoints(&codex).await?;
+    let ids: Vec<&str> = remaining.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, vec![before_refactor.id.as_str()]);
+    assert_eq!(remaining[0].diffstat, Some(CheckpointDiffStat::default()));
+
+    let missing = restore_checkpoint(&codex, &second.id).await?;
+    assert!(!missing.success);
+
+    Ok(())
+}
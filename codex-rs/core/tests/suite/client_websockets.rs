@@ -195,6 +195,9 @@ fn websocket_provider(server: &WebSocketTestServer) -> ModelProviderInfo {
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
         requires_openai_auth: false,
+        api_version: None,
+        deployments: None,
+        entra_id: None,
     }
 }
 
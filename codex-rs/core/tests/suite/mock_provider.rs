@@ -0,0 +1,66 @@
+use codex_core::built_in_model_providers;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use wiremock::MockServer;
+
+const SCRIPT: &str = r#"{
+  "responses": [
+    { "items": [{ "type": "function_call", "name": "update_plan", "arguments": "{\"plan\":[{\"step\":\"say hi\",\"status\":\"in_progress\"}]}", "call_id": "call-1" }] },
+    { "items": [{ "type": "message", "role": "assistant", "content": [{ "type": "output_text", "text": "first turn done" }] }] },
+    { "items": [{ "type": "message", "role": "assistant", "content": [{ "type": "output_text", "text": "second turn done" }] }] }
+  ]
+}"#;
+
+/// The mock provider replays one scripted response per model request,
+/// including tool calls, and picks the next entry from the conversation
+/// history so later turns continue the script.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn mock_provider_replays_scripted_responses() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    let script_dir = tempfile::tempdir()?;
+    let script = script_dir.path().join("mock.json");
+    std::fs::write(&script, SCRIPT)?;
+
+    let test = test_codex()
+        .with_config(move |config| {
+            config.model = Some("mock".to_string());
+            config.model_provider_id = "mock".to_string();
+            config.model_provider = built_in_model_providers()["mock"].clone();
+            config.mock_responses_file = Some(script);
+        })
+        .build(&server)
+        .await?;
+
+    let mut last_agent_messages = Vec::new();
+    for prompt in ["first", "second"] {
+        test.codex
+            .submit(Op::UserInput {
+                items: vec![UserInput::Text {
+                    text: prompt.to_string(),
+                    text_elements: Vec::new(),
+                }],
+                final_output_json_schema: None,
+            })
+            .await?;
+        last_agent_messages.push(
+            wait_for_event_match(&test.codex, |event| match event {
+                EventMsg::TurnComplete(event) => Some(event.last_agent_message.clone()),
+                _ => None,
+            })
+            .await,
+        );
+    }
+
+    assert_eq!(
+        last_agent_messages,
+        vec![
+            Some("first turn done".to_string()),
+            Some("second turn done".to_string()),
+        ]
+    );
+    Ok(())
+}
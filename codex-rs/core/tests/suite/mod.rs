@@ -20,6 +20,7 @@ mod apply_patch_cli;
 #[cfg(not(target_os = "windows"))]
 mod approvals;
 mod auth_refresh;
+mod checkpoints;
 mod cli_stream;
 mod client;
 mod client_websockets;
@@ -40,6 +41,7 @@ mod json_result;
 mod list_dir;
 mod list_models;
 mod live_cli;
+mod mock_provider;
 mod model_info_overrides;
 mod model_overrides;
 mod model_tools;
@@ -49,6 +51,8 @@ mod otel;
 mod pending_input;
 mod permissions_messages;
 mod personality;
+mod pinned_context;
+mod plan_mode;
 mod prompt_caching;
 mod quota_exceeded;
 mod read_file;
@@ -1,5 +1,7 @@
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::QueuedUserInput;
+use codex_core::protocol::QueuedUserInputsEvent;
 use codex_protocol::user_input::UserInput;
 use core_test_support::responses;
 use core_test_support::responses::ev_completed;
@@ -143,3 +145,112 @@ async fn injected_user_input_triggers_follow_up_request_with_deltas() {
 
     server.shutdown().await;
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn queued_user_input_runs_as_next_turn() {
+    let (gate_completed_tx, gate_completed_rx) = oneshot::channel();
+
+    let first_chunks = vec![
+        StreamingSseChunk {
+            gate: None,
+            body: sse_event(ev_response_created("resp-1")),
+        },
+        StreamingSseChunk {
+            gate: None,
+            body: sse_event(ev_message_item_done("msg-1", "first turn")),
+        },
+        StreamingSseChunk {
+            gate: Some(gate_completed_rx),
+            body: sse_event(ev_completed("resp-1")),
+        },
+    ];
+
+    let second_chunks = vec![
+        StreamingSseChunk {
+            gate: None,
+            body: sse_event(ev_response_created("resp-2")),
+        },
+        StreamingSseChunk {
+            gate: None,
+            body: sse_event(ev_completed("resp-2")),
+        },
+    ];
+
+    let (server, _completions) =
+        start_streaming_sse_server(vec![first_chunks, second_chunks]).await;
+
+    let codex = test_codex()
+        .with_model("gpt-5.1")
+        .build_with_streaming_server(&server)
+        .await
+        .unwrap()
+        .codex;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "first prompt".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await
+        .unwrap();
+
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskStarted(_))).await;
+
+    let queued_items = vec![UserInput::Text {
+        text: "second prompt".into(),
+        text_elements: Vec::new(),
+    }];
+    let queued_id = codex
+        .submit(Op::QueueUserInput {
+            items: queued_items.clone(),
+        })
+        .await
+        .unwrap();
+
+    let EventMsg::QueuedUserInputs(queued) = wait_for_event(&codex, |event| {
+        matches!(event, EventMsg::QueuedUserInputs(_))
+    })
+    .await
+    else {
+        unreachable!();
+    };
+    assert_eq!(
+        queued,
+        QueuedUserInputsEvent {
+            inputs: vec![QueuedUserInput {
+                id: queued_id,
+                items: queued_items,
+            }],
+        }
+    );
+
+    let _ = gate_completed_tx.send(());
+
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TurnComplete(_))).await;
+    let EventMsg::QueuedUserInputs(queued) = wait_for_event(&codex, |event| {
+        matches!(event, EventMsg::QueuedUserInputs(_))
+    })
+    .await
+    else {
+        unreachable!();
+    };
+    assert_eq!(queued, QueuedUserInputsEvent { inputs: Vec::new() });
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TurnComplete(_))).await;
+
+    let requests = server.requests().await;
+    assert_eq!(requests.len(), 2);
+
+    let first_body: Value = serde_json::from_slice(&requests[0]).expect("parse first request");
+    let second_body: Value = serde_json::from_slice(&requests[1]).expect("parse second request");
+
+    let first_texts = message_input_texts(&first_body, "user");
+    assert!(!first_texts.iter().any(|text| text == "second prompt"));
+
+    let second_texts = message_input_texts(&second_body, "user");
+    assert!(second_texts.iter().any(|text| text == "second prompt"));
+
+    server.shutdown().await;
+}
@@ -0,0 +1,102 @@
+#![allow(clippy::unwrap_used)]
+
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::PinnedContent;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use std::path::PathBuf;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pinned_context_is_sent_before_and_after_compaction() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let test = test_codex().build(&server).await?;
+    let TestCodex { codex, cwd, .. } = &test;
+    std::fs::write(cwd.path().join("SPEC.md"), "spec v1")?;
+
+    for content in [
+        PinnedContent::Note {
+            text: "Target Python 3.9.".to_string(),
+        },
+        PinnedContent::File {
+            path: PathBuf::from("SPEC.md"),
+        },
+    ] {
+        codex.submit(Op::PinContext { content }).await?;
+        wait_for_event(codex, |event| {
+            matches!(event, EventMsg::PinnedContextUpdated(_))
+        })
+        .await;
+    }
+
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_assistant_message("msg-1", "first reply"),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-2", "summary of the conversation"),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-3", "second reply"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    test.submit_turn("first").await?;
+    codex.submit(Op::Compact).await?;
+    wait_for_event(codex, |event| matches!(event, EventMsg::TurnComplete(_))).await;
+    std::fs::write(cwd.path().join("SPEC.md"), "spec v2")?;
+    test.submit_turn("second").await?;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    let pinned_text = |idx: usize| {
+        requests[idx]
+            .message_input_texts("user")
+            .into_iter()
+            .find(|text| text.starts_with("<pinned_context>"))
+    };
+    let first = pinned_text(0).expect("pinned context in the first turn");
+    assert!(first.contains("Target Python 3.9."), "{first}");
+    assert!(first.contains("spec v1"), "{first}");
+    let after_compaction = pinned_text(2).expect("pinned context after compaction");
+    assert!(after_compaction.contains("Target Python 3.9."));
+    assert!(after_compaction.contains("spec v2"));
+
+    codex
+        .submit(Op::UnpinContext {
+            id: "1".to_string(),
+        })
+        .await?;
+    let remaining = wait_for_event_match(codex, |event| match event {
+        EventMsg::PinnedContextUpdated(ev) => Some(ev.items.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(
+        remaining[0].content,
+        PinnedContent::File {
+            path: cwd.path().join("SPEC.md"),
+        }
+    );
+
+    Ok(())
+}
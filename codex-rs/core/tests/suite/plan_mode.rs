@@ -0,0 +1,249 @@
+#![allow(clippy::unwrap_used)]
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use codex_core::config::types::McpServerConfig;
+use codex_core::config::types::McpServerTransportConfig;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::plan_tool::PlanRisk;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::stdio_server_bin;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn plan_mode_locks_write_tools_until_plan_is_approved() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let TestCodex {
+        codex,
+        cwd,
+        session_configured,
+        ..
+    } = test_codex().build(&server).await?;
+
+    codex.submit(Op::SetPlanMode { enabled: true }).await?;
+    wait_for_event(
+        &codex,
+        |event| matches!(event, EventMsg::PlanModeUpdated(update) if update.enabled),
+    )
+    .await;
+
+    // Known safe commands stay available before approval, so write a file.
+    let shell_args = json!({
+        "command": "touch planned.txt && echo planned",
+        "timeout_ms": 10_000
+    })
+    .to_string();
+    let plan_args = json!({
+        "summary": "Print a greeting.",
+        "steps": ["Run echo"],
+        "affected_files": [],
+        "risk": "low"
+    })
+    .to_string();
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call("early-shell", "shell_command", &shell_args),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_function_call("plan", "submit_plan", &plan_args),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_function_call("approved-shell", "shell_command", &shell_args),
+                ev_completed("resp-3"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+
+    codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "print a greeting".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+            cwd: cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+            collaboration_mode: None,
+            personality: None,
+        })
+        .await?;
+
+    let request = wait_for_event_match(&codex, |event| match event {
+        EventMsg::PlanApprovalRequest(request) => Some(request.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(request.call_id, "plan");
+    assert_eq!(request.plan.steps, vec!["Run echo".to_string()]);
+    assert_eq!(request.plan.risk, PlanRisk::Low);
+
+    codex
+        .submit(Op::PlanApproval {
+            id: request.turn_id,
+            decision: ReviewDecision::Approved,
+        })
+        .await?;
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TurnComplete(_))).await;
+
+    let requests = mock.requests();
+    let tools: Vec<String> = requests[0].body_json()["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|tool| tool.get("name").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+    assert!(tools.contains(&"submit_plan".to_string()), "{tools:?}");
+
+    let early = mock.function_call_output_text("early-shell").unwrap();
+    assert!(early.contains("plan mode is on"), "{early}");
+    let approved = mock.function_call_output_text("approved-shell").unwrap();
+    assert!(approved.contains("planned"), "{approved}");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn plan_mode_allows_only_read_only_mcp_tools() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let server_bin = stdio_server_bin()?;
+    let TestCodex {
+        codex,
+        cwd,
+        session_configured,
+        ..
+    } = test_codex()
+        .with_config(move |config| {
+            let mut servers = config.mcp_servers.get().clone();
+            servers.insert(
+                "rmcp".to_string(),
+                McpServerConfig {
+                    transport: McpServerTransportConfig::Stdio {
+                        command: server_bin,
+                        args: Vec::new(),
+                        env: Some(HashMap::new()),
+                        env_vars: Vec::new(),
+                        cwd: None,
+                    },
+                    enabled: true,
+                    disabled_reason: None,
+                    startup_timeout_sec: Some(Duration::from_secs(10)),
+                    tool_timeout_sec: None,
+                    enabled_tools: None,
+                    disabled_tools: None,
+                    default_tool_approval: None,
+                    tool_approvals: None,
+                },
+            );
+            config
+                .mcp_servers
+                .set(servers)
+                .expect("test mcp servers should accept any configuration");
+        })
+        .build(&server)
+        .await?;
+
+    codex.submit(Op::SetPlanMode { enabled: true }).await?;
+    wait_for_event(
+        &codex,
+        |event| matches!(event, EventMsg::PlanModeUpdated(update) if update.enabled),
+    )
+    .await;
+
+    // `image` is annotated `readOnlyHint: true`; `echo` has no annotations.
+    let mock = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call("echo-call", "mcp__rmcp__echo", r#"{"message":"ping"}"#),
+                ev_function_call("image-call", "mcp__rmcp__image", "{}"),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "call the rmcp tools".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+            cwd: cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::ReadOnly,
+            model: session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+            collaboration_mode: None,
+            personality: None,
+        })
+        .await?;
+
+    let mut started = Vec::new();
+    loop {
+        let event = wait_for_event(&codex, |event| {
+            matches!(
+                event,
+                EventMsg::McpToolCallBegin(_) | EventMsg::TurnComplete(_)
+            )
+        })
+        .await;
+        match event {
+            EventMsg::McpToolCallBegin(begin) => started.push(begin.invocation.tool),
+            _ => break,
+        }
+    }
+    assert_eq!(started, vec!["image".to_string()]);
+
+    let echo = mock.function_call_output_text("echo-call").unwrap();
+    assert!(echo.contains("plan mode is on"), "{echo}");
+
+    Ok(())
+}
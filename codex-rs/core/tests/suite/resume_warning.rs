@@ -33,6 +33,7 @@ fn resume_history(
         developer_instructions: None,
         final_output_json_schema: None,
         truncation_policy: None,
+        tool_trimming: None,
     };
 
     InitialHistory::Resumed(ResumedHistory {
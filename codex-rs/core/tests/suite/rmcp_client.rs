@@ -93,6 +93,8 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    default_tool_approval: None,
+                    tool_approvals: None,
                 },
             );
             config
@@ -233,6 +235,8 @@ async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    default_tool_approval: None,
+                    tool_approvals: None,
                 },
             );
             config
@@ -431,6 +435,8 @@ async fn stdio_image_completions_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    default_tool_approval: None,
+                    tool_approvals: None,
                 },
             );
             config
@@ -577,6 +583,8 @@ async fn stdio_server_propagates_whitelisted_env_vars() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    default_tool_approval: None,
+                    tool_approvals: None,
                 },
             );
             config
@@ -734,6 +742,8 @@ async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    default_tool_approval: None,
+                    tool_approvals: None,
                 },
             );
             config
@@ -923,6 +933,8 @@ async fn streamable_http_with_oauth_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    default_tool_approval: None,
+                    tool_approvals: None,
                 },
             );
             config
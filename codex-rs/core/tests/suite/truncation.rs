@@ -431,6 +431,8 @@ async fn mcp_tool_call_output_exceeds_limit_truncated_for_model() -> Result<()>
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
         config
@@ -523,6 +525,8 @@ async fn mcp_image_output_preserves_image_and_no_text_summary() -> Result<()> {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
         config
@@ -786,6 +790,8 @@ async fn mcp_tool_call_output_not_truncated_with_custom_limit() -> Result<()> {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                default_tool_approval: None,
+                tool_approvals: None,
             },
         );
         config
@@ -10,9 +10,11 @@ use anyhow::Result;
 use anyhow::bail;
 use codex_core::CodexThread;
 use codex_core::features::Feature;
+use codex_core::protocol::DiffHunk;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_core::protocol::UndoCompletedEvent;
+use codex_core::protocol::UndoKind;
 use core_test_support::responses::ev_apply_patch_function_call;
 use core_test_support::responses::ev_assistant_message;
 use core_test_support::responses::ev_completed;
@@ -142,6 +144,17 @@ async fn expect_failed_undo(codex: &Arc<CodexThread>) -> Result<UndoCompletedEve
     Ok(event)
 }
 
+async fn invoke_redo(codex: &Arc<CodexThread>) -> Result<UndoCompletedEvent> {
+    codex.submit(Op::Redo).await?;
+    let event = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::UndoCompleted(done) => Some(done.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(event.kind, UndoKind::Redo);
+    Ok(event)
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn undo_removes_new_file_created_during_turn() -> Result<()> {
     skip_if_no_network!(Ok(()));
@@ -548,3 +561,181 @@ async fn undo_preserves_unrelated_staged_changes() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn redo_restores_state_discarded_by_undo() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let patch = "*** Begin Patch\n*** Add File: redo.txt\n+from turn\n*** End Patch";
+    run_apply_patch_turn(&harness, "create file", "redo-create-file", patch, "ok").await?;
+    let path = harness.path("redo.txt");
+    // Manual edits made after the turn survive the undo/redo round trip too.
+    fs::write(&path, "edited by hand\n")?;
+
+    let codex = Arc::clone(&harness.test().codex);
+    let undone = expect_successful_undo(&codex).await?;
+    assert_eq!(undone.kind, UndoKind::Undo);
+    assert!(undone.redo_commit_id.is_some());
+    assert!(!path.exists());
+
+    let redone = invoke_redo(&codex).await?;
+    assert!(redone.success, "redo failed: {:?}", redone.message);
+    assert_eq!(redone.redo_commit_id, None);
+    assert_eq!(fs::read_to_string(&path)?, "edited by hand\n");
+
+    let nothing = invoke_redo(&codex).await?;
+    assert!(!nothing.success);
+    assert_eq!(nothing.message.as_deref(), Some("Nothing to redo."));
+
+    // The redone turn can be undone again.
+    expect_successful_undo(&codex).await?;
+    assert!(!path.exists());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn new_turn_discards_pending_redo() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let patch = "*** Begin Patch\n*** Add File: first.txt\n+first\n*** End Patch";
+    run_apply_patch_turn(&harness, "create first", "redo-first", patch, "ok").await?;
+
+    let codex = Arc::clone(&harness.test().codex);
+    expect_successful_undo(&codex).await?;
+
+    let patch = "*** Begin Patch\n*** Add File: second.txt\n+second\n*** End Patch";
+    run_apply_patch_turn(&harness, "create second", "redo-second", patch, "ok").await?;
+
+    let redone = invoke_redo(&codex).await?;
+    assert!(!redone.success);
+    assert_eq!(redone.message.as_deref(), Some("Nothing to redo."));
+    assert!(!harness.path("first.txt").exists());
+    assert_eq!(fs::read_to_string(harness.path("second.txt"))?, "second\n");
+
+    Ok(())
+}
+
+async fn invoke_restore_file(
+    codex: &Arc<CodexThread>,
+    turn_id: Option<String>,
+    path: &str,
+) -> Result<UndoCompletedEvent> {
+    codex
+        .submit(Op::RestoreFile {
+            turn_id,
+            path: path.into(),
+        })
+        .await?;
+    let event = wait_for_event_match(codex, |msg| match msg {
+        EventMsg::UndoCompleted(done) => Some(done.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(event.kind, UndoKind::RestoreFile);
+    Ok(event)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn restore_file_reverts_only_that_file() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let keep = harness.path("keep.txt");
+    fs::write(&keep, "keep start\n")?;
+    let revert = harness.path("revert.txt");
+    fs::write(&revert, "revert start\n")?;
+    git(harness.cwd(), &["add", "keep.txt", "revert.txt"])?;
+    git(harness.cwd(), &["commit", "-m", "seed files"])?;
+
+    let patch = "*** Begin Patch\n*** Update File: keep.txt\n@@\n-keep start\n+keep edited\n*** Update File: revert.txt\n@@\n-revert start\n+revert edited\n*** Add File: created.txt\n+new\n*** End Patch";
+    run_apply_patch_turn(&harness, "edit both", "restore-file", patch, "done").await?;
+
+    let codex = Arc::clone(&harness.test().codex);
+    let restored = invoke_restore_file(&codex, None, "revert.txt").await?;
+    assert!(restored.success, "restore failed: {:?}", restored.message);
+    let removed = invoke_restore_file(&codex, None, "created.txt").await?;
+    assert!(removed.success, "restore failed: {:?}", removed.message);
+
+    assert_eq!(fs::read_to_string(&revert)?, "revert start\n");
+    assert_eq!(fs::read_to_string(&keep)?, "keep edited\n");
+    assert!(!harness.path("created.txt").exists());
+
+    // The turn snapshot is still in place, so the whole turn can be undone.
+    expect_successful_undo(&codex).await?;
+    assert_eq!(fs::read_to_string(&keep)?, "keep start\n");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn restore_file_reports_unknown_turn() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let codex = Arc::clone(&harness.test().codex);
+    let event = invoke_restore_file(&codex, Some("missing-turn".to_string()), "a.txt").await?;
+    assert!(!event.success);
+    assert_eq!(
+        event.message.as_deref(),
+        Some("No snapshot recorded for turn missing-turn.")
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn revert_hunks_keeps_the_other_hunks() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let original: String = (1..=12).map(|n| format!("line {n}\n")).collect();
+    let file = harness.path("lines.txt");
+    fs::write(&file, &original)?;
+    git(harness.cwd(), &["add", "lines.txt"])?;
+    git(harness.cwd(), &["commit", "-m", "seed lines"])?;
+
+    let patch = "*** Begin Patch\n*** Update File: lines.txt\n@@\n-line 1\n+first\n@@\n-line 12\n+last\n*** End Patch";
+    run_apply_patch_turn(&harness, "edit both ends", "revert-hunks", patch, "done").await?;
+
+    let codex = Arc::clone(&harness.test().codex);
+    codex
+        .submit(Op::RevertHunks {
+            turn_id: None,
+            path: "lines.txt".into(),
+            // The first of the turn's two hunks, with three lines of context.
+            hunks: vec![DiffHunk {
+                old_start: 1,
+                old_lines: 4,
+                new_start: 1,
+                new_lines: 4,
+            }],
+        })
+        .await?;
+    let event = wait_for_event_match(&codex, |msg| match msg {
+        EventMsg::UndoCompleted(done) => Some(done.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(event.kind, UndoKind::RevertHunks);
+    assert!(event.success, "revert failed: {:?}", event.message);
+
+    assert_eq!(
+        fs::read_to_string(&file)?,
+        original.replace("line 12\n", "last\n")
+    );
+
+    Ok(())
+}
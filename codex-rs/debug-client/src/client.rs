@@ -14,6 +14,7 @@ use std::sync::mpsc::Sender;
 
 use anyhow::Context;
 use anyhow::Result;
+use codex_app_server_protocol::APP_SERVER_PROTOCOL_VERSION;
 use codex_app_server_protocol::AskForApproval;
 use codex_app_server_protocol::ClientInfo;
 use codex_app_server_protocol::ClientNotification;
@@ -99,6 +100,8 @@ impl AppServerClient {
                     title: Some("Debug Client".to_string()),
                     version: env!("CARGO_PKG_VERSION").to_string(),
                 },
+                protocol_version: Some(APP_SERVER_PROTOCOL_VERSION),
+                capabilities: None,
             },
         };
 
@@ -174,6 +177,7 @@ impl AppServerClient {
                 sort_key: None,
                 model_providers: None,
                 archived: None,
+                forked_from_id: None,
             },
         };
         self.send(&request)?;
@@ -0,0 +1,338 @@
+//! `--ci`: `codex exec` for pipelines such as GitHub Actions.
+//!
+//! Nothing waits on a person: approval prompts are declined, questions asked
+//! through `request_user_input` get no answers, and MCP elicitations are
+//! cancelled; any of these marks the run as needing a human. The outcome maps
+//! to a distinct exit status (see [`CiOutcome`]), and errors, sandbox denials
+//! and review findings are printed to stdout as workflow commands
+//! (`::error file=...::...`), which the runner shows as annotations.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::protocol::CodexErrorInfo;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use codex_core::protocol::ReviewFinding;
+use codex_protocol::request_user_input::RequestUserInputResponse;
+
+pub const NEEDS_HUMAN_EXIT_CODE: i32 = 3;
+pub const BUDGET_EXCEEDED_EXIT_CODE: i32 = 4;
+pub const SANDBOX_VIOLATION_EXIT_CODE: i32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CiOutcome {
+    Success,
+    /// An error was reported (exit status 1).
+    Failed,
+    /// The agent asked for an approval or an answer that nobody could give.
+    NeedsHuman,
+    /// A `[budget]` spending limit stopped the run.
+    BudgetExceeded,
+    /// The sandbox blocked a command or patch.
+    SandboxViolation,
+}
+
+impl CiOutcome {
+    pub(crate) fn exit_code(self) -> i32 {
+        match self {
+            Self::Success => 0,
+            Self::Failed => 1,
+            Self::NeedsHuman => NEEDS_HUMAN_EXIT_CODE,
+            Self::BudgetExceeded => BUDGET_EXCEEDED_EXIT_CODE,
+            Self::SandboxViolation => SANDBOX_VIOLATION_EXIT_CODE,
+        }
+    }
+}
+
+pub(crate) struct CiRun {
+    /// Annotation paths are relative to this directory (the repository root).
+    root: PathBuf,
+    error_seen: bool,
+    budget_exceeded: bool,
+    sandbox_denied: bool,
+    needs_human: bool,
+}
+
+impl CiRun {
+    pub(crate) fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            error_seen: false,
+            budget_exceeded: false,
+            sandbox_denied: false,
+            needs_human: false,
+        }
+    }
+
+    /// Updates the outcome with `msg` and returns the workflow commands to
+    /// print for it.
+    pub(crate) fn observe(&mut self, msg: &EventMsg) -> Vec<String> {
+        match msg {
+            EventMsg::Error(ev) => {
+                self.error_seen = true;
+                if matches!(ev.codex_error_info, Some(CodexErrorInfo::BudgetExceeded)) {
+                    self.budget_exceeded = true;
+                    vec![command(
+                        "error",
+                        &[("title", "Budget exceeded")],
+                        &ev.message,
+                    )]
+                } else {
+                    vec![command("error", &[("title", "Codex")], &ev.message)]
+                }
+            }
+            EventMsg::SandboxDenied(ev) => {
+                self.sandbox_denied = true;
+                vec![command(
+                    "error",
+                    &[("title", "Sandbox denied")],
+                    &ev.command.join(" "),
+                )]
+            }
+            EventMsg::ExecApprovalRequest(ev) => {
+                self.needs_human(format!("declined approval for `{}`", ev.command.join(" ")))
+            }
+            EventMsg::ApplyPatchApprovalRequest(ev) => {
+                let mut paths: Vec<String> =
+                    ev.changes.keys().map(|path| self.relative(path)).collect();
+                paths.sort();
+                self.needs_human(format!("declined a patch to {}", paths.join(", ")))
+            }
+            EventMsg::PlanApprovalRequest(_) => {
+                self.needs_human("declined approval for a plan".to_string())
+            }
+            EventMsg::McpToolCallApprovalRequest(ev) => self.needs_human(format!(
+                "declined approval for MCP tool `{}/{}`",
+                ev.invocation.server, ev.invocation.tool
+            )),
+            EventMsg::RequestUserInput(ev) => {
+                let questions: Vec<&str> = ev
+                    .questions
+                    .iter()
+                    .map(|question| question.question.as_str())
+                    .collect();
+                self.needs_human(format!("left unanswered: {}", questions.join(" / ")))
+            }
+            EventMsg::ElicitationRequest(ev) => self.needs_human(format!(
+                "cancelled a request from MCP server `{}`: {}",
+                ev.server_name, ev.message
+            )),
+            EventMsg::ExitedReviewMode(ev) => ev
+                .review_output
+                .iter()
+                .flat_map(|output| &output.findings)
+                .map(|finding| self.finding(finding))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub(crate) fn outcome(&self) -> CiOutcome {
+        if self.budget_exceeded {
+            CiOutcome::BudgetExceeded
+        } else if self.sandbox_denied {
+            CiOutcome::SandboxViolation
+        } else if self.needs_human {
+            CiOutcome::NeedsHuman
+        } else if self.error_seen {
+            CiOutcome::Failed
+        } else {
+            CiOutcome::Success
+        }
+    }
+
+    fn needs_human(&mut self, message: String) -> Vec<String> {
+        self.needs_human = true;
+        vec![command("warning", &[("title", "Needs a human")], &message)]
+    }
+
+    /// P0 and P1 findings are errors, P2 warnings, the rest notices.
+    fn finding(&self, finding: &ReviewFinding) -> String {
+        let level = match finding.priority {
+            i32::MIN..=1 => "error",
+            2 => "warning",
+            _ => "notice",
+        };
+        let location = &finding.code_location;
+        let file = self.relative(&location.absolute_file_path);
+        let line = location.line_range.start.to_string();
+        let end_line = location.line_range.end.to_string();
+        command(
+            level,
+            &[
+                ("file", &file),
+                ("line", &line),
+                ("endLine", &end_line),
+                ("title", &finding.title),
+            ],
+            &finding.body,
+        )
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+/// The reply that declines an approval or question in `event`, if it asks
+/// for one. MCP elicitations are cancelled for every `codex exec` run.
+pub(crate) fn decline(event: &Event) -> Option<Op> {
+    let id = event.id.clone();
+    match &event.msg {
+        EventMsg::ExecApprovalRequest(_) => Some(Op::ExecApproval {
+            id,
+            decision: ReviewDecision::Denied,
+        }),
+        EventMsg::ApplyPatchApprovalRequest(_) => Some(Op::PatchApproval {
+            id,
+            decision: ReviewDecision::Denied,
+        }),
+        EventMsg::PlanApprovalRequest(_) => Some(Op::PlanApproval {
+            id,
+            decision: ReviewDecision::Denied,
+        }),
+        EventMsg::McpToolCallApprovalRequest(_) => Some(Op::McpToolCallApproval {
+            id,
+            decision: ReviewDecision::Denied,
+        }),
+        EventMsg::RequestUserInput(_) => Some(Op::UserInputAnswer {
+            id,
+            response: RequestUserInputResponse {
+                answers: HashMap::new(),
+            },
+        }),
+        _ => None,
+    }
+}
+
+/// Formats a workflow command such as `::error file=a.rs,line=3::message`.
+fn command(level: &str, properties: &[(&str, &str)], message: &str) -> String {
+    let properties: Vec<String> = properties
+        .iter()
+        .map(|(key, value)| format!("{key}={}", escape_property(value)))
+        .collect();
+    let properties = if properties.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", properties.join(","))
+    };
+    format!("::{level}{properties}::{}", escape_data(message))
+}
+
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::ErrorEvent;
+    use codex_core::protocol::ExitedReviewModeEvent;
+    use codex_core::protocol::ReviewCodeLocation;
+    use codex_core::protocol::ReviewLineRange;
+    use codex_core::protocol::ReviewOutputEvent;
+    use codex_core::protocol::SandboxDeniedEvent;
+    use codex_protocol::request_user_input::RequestUserInputEvent;
+    use pretty_assertions::assert_eq;
+
+    fn finding(priority: i32) -> ReviewFinding {
+        ReviewFinding {
+            title: "[P1] Off by one, maybe".to_string(),
+            body: "The loop skips the last item.\nUse `..=`.".to_string(),
+            confidence_score: 0.8,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                line_range: ReviewLineRange { start: 10, end: 12 },
+            },
+        }
+    }
+
+    #[test]
+    fn review_findings_become_annotations() {
+        let mut run = CiRun::new(PathBuf::from("/repo"));
+        let commands = run.observe(&EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+            review_output: Some(ReviewOutputEvent {
+                findings: vec![finding(1), finding(3)],
+                ..Default::default()
+            }),
+        }));
+
+        assert_eq!(
+            commands,
+            vec![
+                "::error file=src/lib.rs,line=10,endLine=12,title=[P1] Off by one%2C maybe::The loop skips the last item.%0AUse `..=`.",
+                "::notice file=src/lib.rs,line=10,endLine=12,title=[P1] Off by one%2C maybe::The loop skips the last item.%0AUse `..=`.",
+            ]
+        );
+        assert_eq!(run.outcome(), CiOutcome::Success);
+    }
+
+    #[test]
+    fn outcome_prefers_the_most_specific_failure() {
+        let mut run = CiRun::new(PathBuf::from("/repo"));
+        run.observe(&EventMsg::Error(ErrorEvent {
+            message: "stream failed".to_string(),
+            codex_error_info: None,
+        }));
+        assert_eq!(run.outcome(), CiOutcome::Failed);
+
+        run.needs_human("declined".to_string());
+        assert_eq!(run.outcome(), CiOutcome::NeedsHuman);
+
+        run.observe(&EventMsg::SandboxDenied(SandboxDeniedEvent {
+            call_id: "call-1".to_string(),
+            turn_id: "1".to_string(),
+            command: vec!["touch".to_string(), "/etc/x".to_string()],
+        }));
+        assert_eq!(run.outcome(), CiOutcome::SandboxViolation);
+
+        let commands = run.observe(&EventMsg::Error(ErrorEvent {
+            message: "Session budget of $1.00 reached".to_string(),
+            codex_error_info: Some(CodexErrorInfo::BudgetExceeded),
+        }));
+        assert_eq!(
+            commands,
+            vec!["::error title=Budget exceeded::Session budget of $1.00 reached"]
+        );
+        assert_eq!(run.outcome(), CiOutcome::BudgetExceeded);
+        assert_eq!(run.outcome().exit_code(), BUDGET_EXCEEDED_EXIT_CODE);
+    }
+
+    #[test]
+    fn declines_requests_that_need_a_person() {
+        let event = Event {
+            id: "7".to_string(),
+            msg: EventMsg::RequestUserInput(RequestUserInputEvent {
+                call_id: "call-1".to_string(),
+                turn_id: "7".to_string(),
+                questions: Vec::new(),
+            }),
+        };
+        assert!(matches!(
+            decline(&event),
+            Some(Op::UserInputAnswer { id, response }) if id == "7" && response.answers.is_empty()
+        ));
+        assert!(
+            decline(&Event {
+                id: "7".to_string(),
+                msg: EventMsg::ShutdownComplete,
+            })
+            .is_none()
+        );
+    }
+}
@@ -2,6 +2,7 @@ use clap::Parser;
 use clap::ValueEnum;
 use codex_common::CliConfigOverrides;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(version)]
@@ -89,6 +90,48 @@ pub struct Cli {
     )]
     pub json: bool,
 
+    /// Format of stdout. `jsonl` prints every protocol event as a JSON line
+    /// with a schema version, then a summary record with the run's status,
+    /// diffstat, token usage, and cost.
+    #[arg(
+        long = "output-format",
+        value_enum,
+        default_value_t = OutputFormat::Human,
+        global = true,
+        conflicts_with = "json"
+    )]
+    pub output_format: OutputFormat,
+
+    /// Stream agent message text to stdout as it is generated instead of
+    /// printing only the final message at the end. With `--json`, agent
+    /// messages are also reported as `item.started`/`item.updated` events.
+    #[arg(long = "stream", default_value_t = false, global = true)]
+    pub stream: bool,
+
+    /// Wall-clock limit for the run, e.g. `30m` or `1h30m`. When it is reached
+    /// the agent is asked to wrap up and summarize, unfinished work is handled
+    /// as configured under `[timebox]`, and the process exits with status 124.
+    #[arg(
+        long = "max-duration",
+        value_name = "DURATION",
+        value_parser = crate::timebox::parse_duration,
+        global = true
+    )]
+    pub max_duration: Option<Duration>,
+
+    /// Run unattended in a CI pipeline: approvals and questions are declined,
+    /// errors, sandbox denials and review findings are printed as GitHub
+    /// Actions annotations, and the exit status tells the outcome apart
+    /// (0 success, 1 failure, 3 needs a human, 4 budget exceeded, 5 sandbox
+    /// violation, 124 `--max-duration` reached).
+    #[arg(
+        long = "ci",
+        default_value_t = false,
+        global = true,
+        conflicts_with_all = ["json", "output_format"]
+    )]
+    pub ci: bool,
+
     /// Specifies file where the last message from the agent should be written.
     #[arg(long = "output-last-message", short = 'o', value_name = "FILE")]
     pub last_message_file: Option<PathBuf>,
@@ -144,7 +187,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "uncommitted",
         default_value_t = false,
-        conflicts_with_all = ["base", "commit", "prompt"]
+        conflicts_with_all = ["base", "commit", "range", "pull_request", "prompt"]
     )]
     pub uncommitted: bool,
 
@@ -152,7 +195,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "base",
         value_name = "BRANCH",
-        conflicts_with_all = ["uncommitted", "commit", "prompt"]
+        conflicts_with_all = ["uncommitted", "commit", "range", "pull_request", "prompt"]
     )]
     pub base: Option<String>,
 
@@ -160,7 +203,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "commit",
         value_name = "SHA",
-        conflicts_with_all = ["uncommitted", "base", "prompt"]
+        conflicts_with_all = ["uncommitted", "base", "range", "pull_request", "prompt"]
     )]
     pub commit: Option<String>,
 
@@ -168,11 +211,59 @@ pub struct ReviewArgs {
     #[arg(long = "title", value_name = "TITLE", requires = "commit")]
     pub commit_title: Option<String>,
 
+    /// Review a revision range as understood by `git diff`, e.g. `main..HEAD`.
+    #[arg(
+        long = "range",
+        value_name = "RANGE",
+        conflicts_with_all = ["uncommitted", "base", "commit", "pull_request", "prompt"]
+    )]
+    pub range: Option<String>,
+
+    /// Review a GitHub pull request, e.g. `https://github.com/owner/repo/pull/123`.
+    /// Its head is fetched into `refs/codex/review/pr-<number>`.
+    #[arg(
+        long = "pr",
+        value_name = "URL",
+        conflicts_with_all = ["uncommitted", "base", "commit", "range", "prompt"]
+    )]
+    pub pull_request: Option<String>,
+
+    /// Review each top-level directory touched by the change with its own
+    /// reviewer, then merge the findings.
+    #[arg(long = "split-by-area", default_value_t = false)]
+    pub split_by_area: bool,
+
+    /// How to print the findings: as text, as a SARIF log, or as the body of
+    /// a GitHub pull request review.
+    #[arg(long = "format", value_enum, default_value_t = ReviewFormat::Text)]
+    pub format: ReviewFormat,
+
     /// Custom review instructions. If `-` is used, read from stdin.
     #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
     pub prompt: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Progress on stderr and the final message on stdout.
+    #[default]
+    Human,
+    /// Every protocol event as JSON lines, then a summary record.
+    Jsonl,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum ReviewFormat {
+    #[default]
+    Text,
+    /// SARIF 2.1.0, e.g. for GitHub code scanning.
+    Sarif,
+    /// Body for `POST /repos/{owner}/{repo}/pulls/{number}/reviews`.
+    Github,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum Color {
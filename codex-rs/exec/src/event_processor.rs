@@ -22,6 +22,10 @@ pub(crate) trait EventProcessor {
     /// Handle a single event emitted by the agent.
     fn process_event(&mut self, event: Event) -> CodexStatus;
 
+    /// Called when `--max-duration` cut the run short, before
+    /// [`EventProcessor::print_final_output`].
+    fn mark_timed_out(&mut self) {}
+
     fn print_final_output(&mut self) {}
 }
 
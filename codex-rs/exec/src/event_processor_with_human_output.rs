@@ -1,6 +1,7 @@
 use codex_common::elapsed::format_duration;
 use codex_common::elapsed::format_elapsed;
 use codex_core::config::Config;
+use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
@@ -10,15 +11,26 @@ use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::ExternalFileChangeKind;
+use codex_core::protocol::ExternalFilesChangedEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::HookEventKind;
+use codex_core::protocol::HookOutcome;
+use codex_core::protocol::HookOutcomeEvent;
+use codex_core::protocol::HostedToolCallEvent;
+use codex_core::protocol::HostedToolKind;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::NetworkActivityEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::ResumeBriefingEvent;
+use codex_core::protocol::SandboxDeniedEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnAnnotationsEvent;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::WarningEvent;
@@ -28,6 +40,7 @@ use owo_colors::OwoColorize;
 use owo_colors::Style;
 use shlex::try_join;
 use std::collections::HashMap;
+use std::io::Write;
 use std::path::PathBuf;
 use std::time::Instant;
 
@@ -62,7 +75,16 @@ pub(crate) struct EventProcessorWithHumanOutput {
     show_raw_agent_reasoning: bool,
     last_message_path: Option<PathBuf>,
     last_total_token_usage: Option<codex_core::protocol::TokenUsageInfo>,
+    /// Estimated session cost from the last `CostUpdate`, in USD.
+    last_session_usd: Option<f64>,
     final_message: Option<String>,
+    /// Whether agent message deltas are written to stdout as they arrive.
+    stream_agent_messages: bool,
+    /// Whether any delta of the in-progress agent message has been written to stdout.
+    agent_message_streamed: bool,
+    /// Whether the last completed agent message was written to stdout as
+    /// deltas. A message that arrives without deltas is not.
+    last_agent_message_streamed: bool,
 }
 
 impl EventProcessorWithHumanOutput {
@@ -70,6 +92,7 @@ impl EventProcessorWithHumanOutput {
         with_ansi: bool,
         config: &Config,
         last_message_path: Option<PathBuf>,
+        stream_agent_messages: bool,
     ) -> Self {
         let call_id_to_patch = HashMap::new();
 
@@ -88,7 +111,11 @@ impl EventProcessorWithHumanOutput {
                 show_raw_agent_reasoning: config.show_raw_agent_reasoning,
                 last_message_path,
                 last_total_token_usage: None,
+                last_session_usd: None,
                 final_message: None,
+                stream_agent_messages,
+                agent_message_streamed: false,
+                last_agent_message_streamed: false,
             }
         } else {
             Self {
@@ -105,7 +132,11 @@ impl EventProcessorWithHumanOutput {
                 show_raw_agent_reasoning: config.show_raw_agent_reasoning,
                 last_message_path,
                 last_total_token_usage: None,
+                last_session_usd: None,
                 final_message: None,
+                stream_agent_messages,
+                agent_message_streamed: false,
+                last_agent_message_streamed: false,
             }
         }
     }
@@ -173,6 +204,121 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "warning:".style(self.yellow).style(self.bold)
                 );
             }
+            EventMsg::HookOutcome(HookOutcomeEvent {
+                hook_id,
+                when,
+                outcome,
+                ..
+            }) => {
+                let hook = hook_id.unwrap_or_else(|| when.to_string());
+                match outcome {
+                    HookOutcome::Blocked { reason } => ts_msg!(
+                        self,
+                        "{} {hook} blocked {when}: {reason}",
+                        "hook".style(self.magenta).style(self.bold)
+                    ),
+                    HookOutcome::Modified => ts_msg!(
+                        self,
+                        "{} {hook} rewrote the tool arguments",
+                        "hook".style(self.magenta).style(self.bold)
+                    ),
+                    HookOutcome::Failed { error } => ts_msg!(
+                        self,
+                        "{} hook {hook} failed: {error}",
+                        "warning:".style(self.yellow).style(self.bold)
+                    ),
+                }
+            }
+            EventMsg::TurnAnnotations(TurnAnnotationsEvent {
+                hook_id,
+                annotations,
+                artifacts,
+                notifications,
+            }) => {
+                let hook = hook_id.unwrap_or_else(|| HookEventKind::TurnPostProcess.to_string());
+                for annotation in annotations {
+                    ts_msg!(
+                        self,
+                        "{} {hook}: {annotation}",
+                        "hook".style(self.magenta).style(self.bold)
+                    );
+                }
+                for artifact in artifacts {
+                    ts_msg!(
+                        self,
+                        "{} {hook} wrote {}",
+                        "hook".style(self.magenta).style(self.bold),
+                        artifact.display()
+                    );
+                }
+                for notification in notifications {
+                    let body = notification
+                        .body
+                        .map(|body| format!(": {body}"))
+                        .unwrap_or_default();
+                    ts_msg!(
+                        self,
+                        "{} {}{body}",
+                        "notification".style(self.magenta).style(self.bold),
+                        notification.title
+                    );
+                }
+            }
+            EventMsg::NetworkActivity(NetworkActivityEvent { connections }) => {
+                ts_msg!(
+                    self,
+                    "{} {} connection(s)",
+                    "network".style(self.magenta).style(self.bold),
+                    connections.len()
+                );
+                for connection in connections {
+                    let error = connection
+                        .error
+                        .map(|error| format!(" (failed: {error})"))
+                        .unwrap_or_default();
+                    ts_msg!(
+                        self,
+                        "  {} {}:{} sent {}B received {}B{error}",
+                        connection.method,
+                        connection.host,
+                        connection.port,
+                        connection.bytes_sent,
+                        connection.bytes_received
+                    );
+                }
+            }
+            EventMsg::ExternalFilesChanged(ExternalFilesChangedEvent { changes }) => {
+                ts_msg!(
+                    self,
+                    "{} {} file(s) changed outside the agent",
+                    "files".style(self.magenta).style(self.bold),
+                    changes.len()
+                );
+                for change in changes {
+                    let kind = match change.kind {
+                        ExternalFileChangeKind::Created => "created",
+                        ExternalFileChangeKind::Modified => "modified",
+                        ExternalFileChangeKind::Deleted => "deleted",
+                    };
+                    ts_msg!(self, "  {kind} {}", change.path.display());
+                }
+            }
+            EventMsg::ResumeBriefing(ResumeBriefingEvent {
+                new_commit_count,
+                changed_files,
+                ci_status,
+                ..
+            }) => {
+                ts_msg!(
+                    self,
+                    "{} {new_commit_count} new commit(s), {} file(s) changed since the last turn",
+                    "resumed".style(self.magenta).style(self.bold),
+                    changed_files.len()
+                );
+                if let Some(ci_status) = ci_status {
+                    ts_msg!(self, "  CI: {ci_status}");
+                }
+            }
             EventMsg::DeprecationNotice(DeprecationNoticeEvent { summary, details }) => {
                 ts_msg!(
                     self,
@@ -219,6 +365,28 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 };
                 ts_msg!(self, "{} {}", "mcp startup:".style(self.cyan), joined);
             }
+            EventMsg::McpServerHealth(health) => {
+                let status_text = match health.status {
+                    codex_core::protocol::McpServerHealthStatus::Degraded {
+                        error,
+                        restart_attempt,
+                        ..
+                    } => format!("unresponsive ({error}); restart attempt {restart_attempt}"),
+                    codex_core::protocol::McpServerHealthStatus::Recovered => {
+                        "recovered".to_string()
+                    }
+                    codex_core::protocol::McpServerHealthStatus::Failed { error } => {
+                        format!("gave up restarting: {error}")
+                    }
+                };
+                ts_msg!(
+                    self,
+                    "{} {} {}",
+                    "mcp:".style(self.cyan),
+                    health.server,
+                    status_text
+                );
+            }
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 ts_msg!(self, "{}", message.style(self.dimmed));
             }
@@ -249,7 +417,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "auto-cancelling (not supported in exec mode)".style(self.dimmed)
                 );
             }
-            EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message }) => {
+            EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message, ..
+            }) => {
                 let last_message = last_agent_message.as_deref();
                 if let Some(output_file) = self.last_message_path.as_deref() {
                     handle_last_message(last_message, output_file);
@@ -262,6 +432,18 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::TokenCount(ev) => {
                 self.last_total_token_usage = ev.info;
             }
+            EventMsg::CostUpdate(ev) => {
+                self.last_session_usd = Some(ev.session_usd);
+            }
+            EventMsg::ProviderRateLimit(ev) => {
+                if let Some(seconds) = ev.resume_in_seconds {
+                    ts_msg!(
+                        self,
+                        "{}",
+                        format!("rate limited, resuming in {seconds}s").style(self.dimmed)
+                    );
+                }
+            }
 
             EventMsg::AgentReasoningSectionBreak(_) => {
                 if !self.show_agent_reasoning {
@@ -279,13 +461,35 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     );
                 }
             }
+            EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta })
+                if self.stream_agent_messages =>
+            {
+                self.agent_message_streamed = true;
+                #[allow(clippy::print_stdout)]
+                {
+                    print!("{delta}");
+                }
+                let _ = std::io::stdout().flush();
+            }
             EventMsg::AgentMessage(AgentMessageEvent { message }) => {
-                ts_msg!(
-                    self,
-                    "{}\n{}",
-                    "codex".style(self.italic).style(self.magenta),
-                    message,
-                );
+                self.last_agent_message_streamed = self.agent_message_streamed;
+                if self.agent_message_streamed {
+                    // Already written to stdout delta by delta; just terminate the line.
+                    self.agent_message_streamed = false;
+                    if !message.ends_with('\n') {
+                        #[allow(clippy::print_stdout)]
+                        {
+                            println!();
+                        }
+                    }
+                } else {
+                    ts_msg!(
+                        self,
+                        "{}\n{}",
+                        "codex".style(self.italic).style(self.magenta),
+                        message,
+                    );
+                }
             }
             EventMsg::ExecCommandBegin(ExecCommandBeginEvent { command, cwd, .. }) => {
                 eprint!(
@@ -295,6 +499,14 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     cwd.to_string_lossy(),
                 );
             }
+            EventMsg::SandboxDenied(SandboxDeniedEvent { command, .. }) => {
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "sandbox denied".style(self.red),
+                    escape_command(&command)
+                );
+            }
             EventMsg::ExecCommandEnd(ExecCommandEndEvent {
                 aggregated_output,
                 duration,
@@ -364,6 +576,42 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::WebSearchEnd(WebSearchEndEvent { call_id: _, query }) => {
                 ts_msg!(self, "🌐 Searched: {query}");
             }
+            EventMsg::HostedToolCall(HostedToolCallEvent {
+                tool,
+                status,
+                input,
+                output,
+                image_urls,
+                ..
+            }) => {
+                let title = match tool {
+                    HostedToolKind::CodeInterpreter => "code_interpreter",
+                    HostedToolKind::FileSearch => "file_search",
+                };
+                let status = status.unwrap_or_else(|| "completed".to_string());
+                let title_style = if status == "completed" {
+                    self.green
+                } else {
+                    self.red
+                };
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "hosted".style(self.magenta),
+                    format!("{title} {status}:").style(title_style),
+                );
+                for line in input.lines().take(MAX_OUTPUT_LINES_FOR_EXEC_TOOL_CALL) {
+                    eprintln!("{}", line.style(self.bold));
+                }
+                for line in output
+                    .lines()
+                    .map(str::to_string)
+                    .chain(image_urls.into_iter().map(|url| format!("image: {url}")))
+                    .take(MAX_OUTPUT_LINES_FOR_EXEC_TOOL_CALL)
+                {
+                    eprintln!("{}", line.style(self.dimmed));
+                }
+            }
             EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
                 call_id,
                 auto_approved,
@@ -481,7 +729,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     eprintln!("{}", line.style(self.dimmed));
                 }
             }
-            EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => {
+            EventMsg::TurnDiff(TurnDiffEvent { unified_diff, .. }) => {
                 ts_msg!(
                     self,
                     "{}",
@@ -549,6 +797,18 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     }
                 }
             }
+            EventMsg::FollowUpSuggestions(ev) => {
+                ts_msg!(self, "{}", "Suggested follow-ups".style(self.magenta));
+                for (index, suggestion) in ev.suggestions.iter().enumerate() {
+                    ts_msg!(
+                        self,
+                        "  {}. {} {}",
+                        index + 1,
+                        suggestion.title,
+                        format!("— {}", suggestion.prompt).style(self.dimmed)
+                    );
+                }
+            }
             EventMsg::ViewImageToolCall(view) => {
                 ts_msg!(
                     self,
@@ -585,12 +845,47 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::WebSearchBegin(_)
             | EventMsg::ExecApprovalRequest(_)
             | EventMsg::ApplyPatchApprovalRequest(_)
+            | EventMsg::PlanApprovalRequest(_)
+            | EventMsg::McpToolsChanged(_)
+            | EventMsg::McpToolCallApprovalRequest(_)
+            | EventMsg::PlanModeUpdated(_)
+            | EventMsg::ConversationForked(_)
+            | EventMsg::CheckpointCreated(_)
+            | EventMsg::ListCheckpointsResponse(_)
+            | EventMsg::CheckpointRestored(_)
+            | EventMsg::WorktreeCreated(_)
+            | EventMsg::WorktreeReview(_)
+            | EventMsg::WorktreeFinished(_)
+            | EventMsg::PinnedContextUpdated(_)
+            | EventMsg::ListPinnedContextResponse(_)
+            | EventMsg::ContextInspection(_)
+            | EventMsg::ApprovalTimedOut(_)
+            | EventMsg::ListMemoryResponse(_)
+            | EventMsg::NextEditSuggestion(_)
+            | EventMsg::PatchHunkReport(_)
+            | EventMsg::PatchPreview(_)
+            | EventMsg::WorkspaceSnapshot(_)
             | EventMsg::TerminalInteraction(_)
             | EventMsg::ExecCommandOutputDelta(_)
             | EventMsg::GetHistoryEntryResponse(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ListMcpPromptsResponse(_)
             | EventMsg::ListSkillsResponse(_)
+            | EventMsg::ApprovalJournalResponse(_)
+            | EventMsg::ListSubAgentsResponse(_)
+            | EventMsg::SubAgentsUpdated(_)
+            | EventMsg::ConfigReloaded(_)
+            | EventMsg::SubAgentRunBegin(_)
+            | EventMsg::SubAgentRunEnd(_)
+            | EventMsg::SubAgentPreview(_)
+            | EventMsg::SubAgentMergePlan(_)
+            | EventMsg::SubAgentMergeApplied(_)
+            | EventMsg::BackgroundTaskBegin(_)
+            | EventMsg::BackgroundTaskEnd(_)
+            | EventMsg::ListBackgroundTasksResponse(_)
+            | EventMsg::BackgroundProcessesUpdated(_)
+            | EventMsg::QueuedUserInputs(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
@@ -620,13 +915,24 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 format_with_separators(usage_info.total_token_usage.blended_total())
             );
         }
+        if let Some(session_usd) = self.last_session_usd {
+            eprintln!(
+                "{}\n${session_usd:.2}",
+                "estimated cost".style(self.magenta).style(self.italic),
+            );
+        }
 
         // If the user has not piped the final message to a file, they will see
         // it twice: once written to stderr as part of the normal event
         // processing, and once here on stdout. We print the token summary above
         // to help break up the output visually in that case.
+        //
+        // When streaming, the message has usually been written to stdout
+        // already; a message that came without deltas has not.
         #[allow(clippy::print_stdout)]
-        if let Some(message) = &self.final_message {
+        if !(self.stream_agent_messages && self.last_agent_message_streamed)
+            && let Some(message) = &self.final_message
+        {
             if message.ends_with('\n') {
                 print!("{message}");
             } else {
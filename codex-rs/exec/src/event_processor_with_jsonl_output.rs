@@ -31,6 +31,7 @@ use crate::exec_events::TodoListItem;
 use crate::exec_events::TurnCompletedEvent;
 use crate::exec_events::TurnFailedEvent;
 use crate::exec_events::TurnStartedEvent;
+use crate::exec_events::TurnStats;
 use crate::exec_events::Usage;
 use crate::exec_events::WebSearchItem;
 use codex_core::config::Config;
@@ -52,6 +53,15 @@ pub struct EventProcessorWithJsonOutput {
     last_total_token_usage: Option<codex_core::protocol::TokenUsage>,
     running_mcp_tool_calls: HashMap<String, RunningMcpToolCall>,
     last_critical_error: Option<ThreadErrorEvent>,
+    /// When set, agent message deltas are reported as `item.started`/`item.updated`.
+    stream_agent_messages: bool,
+    streaming_agent_message: Option<StreamingAgentMessage>,
+}
+
+#[derive(Debug, Clone)]
+struct StreamingAgentMessage {
+    item_id: String,
+    text: String,
 }
 
 #[derive(Debug, Clone)]
@@ -86,12 +96,23 @@ impl EventProcessorWithJsonOutput {
             last_total_token_usage: None,
             running_mcp_tool_calls: HashMap::new(),
             last_critical_error: None,
+            stream_agent_messages: false,
+            streaming_agent_message: None,
         }
     }
 
+    /// Report agent messages incrementally as their deltas arrive.
+    pub fn with_agent_message_streaming(mut self, enabled: bool) -> Self {
+        self.stream_agent_messages = enabled;
+        self
+    }
+
     pub fn collect_thread_events(&mut self, event: &protocol::Event) -> Vec<ThreadEvent> {
         match &event.msg {
             protocol::EventMsg::SessionConfigured(ev) => self.handle_session_configured(ev),
+            protocol::EventMsg::AgentMessageDelta(ev) if self.stream_agent_messages => {
+                self.handle_agent_message_delta(ev)
+            }
             protocol::EventMsg::AgentMessage(ev) => self.handle_agent_message(ev),
             protocol::EventMsg::AgentReasoning(ev) => self.handle_reasoning_event(ev),
             protocol::EventMsg::ExecCommandBegin(ev) => self.handle_exec_command_begin(ev),
@@ -113,7 +134,7 @@ impl EventProcessorWithJsonOutput {
                 Vec::new()
             }
             protocol::EventMsg::TurnStarted(ev) => self.handle_task_started(ev),
-            protocol::EventMsg::TurnComplete(_) => self.handle_task_complete(),
+            protocol::EventMsg::TurnComplete(ev) => self.handle_task_complete(ev),
             protocol::EventMsg::Error(ev) => {
                 let error = ThreadErrorEvent {
                     message: ev.message.clone(),
@@ -185,10 +206,43 @@ impl EventProcessorWithJsonOutput {
         vec![]
     }
 
-    fn handle_agent_message(&self, payload: &protocol::AgentMessageEvent) -> Vec<ThreadEvent> {
+    fn handle_agent_message_delta(
+        &mut self,
+        payload: &protocol::AgentMessageDeltaEvent,
+    ) -> Vec<ThreadEvent> {
+        if let Some(streaming) = self.streaming_agent_message.as_mut() {
+            streaming.text.push_str(&payload.delta);
+            let item = ThreadItem {
+                id: streaming.item_id.clone(),
+                details: ThreadItemDetails::AgentMessage(AgentMessageItem {
+                    text: streaming.text.clone(),
+                }),
+            };
+            return vec![ThreadEvent::ItemUpdated(ItemUpdatedEvent { item })];
+        }
+
+        let streaming = StreamingAgentMessage {
+            item_id: self.get_next_item_id(),
+            text: payload.delta.clone(),
+        };
         let item = ThreadItem {
-            id: self.get_next_item_id(),
+            id: streaming.item_id.clone(),
+            details: ThreadItemDetails::AgentMessage(AgentMessageItem {
+                text: streaming.text.clone(),
+            }),
+        };
+        self.streaming_agent_message = Some(streaming);
+        vec![ThreadEvent::ItemStarted(ItemStartedEvent { item })]
+    }
 
+    fn handle_agent_message(&mut self, payload: &protocol::AgentMessageEvent) -> Vec<ThreadEvent> {
+        // Complete the streamed item, if any, so consumers can correlate it.
+        let id = match self.streaming_agent_message.take() {
+            Some(streaming) => streaming.item_id,
+            None => self.get_next_item_id(),
+        };
+        let item = ThreadItem {
+            id,
             details: ThreadItemDetails::AgentMessage(AgentMessageItem {
                 text: payload.message.clone(),
             }),
@@ -464,7 +518,7 @@ impl EventProcessorWithJsonOutput {
         vec![ThreadEvent::TurnStarted(TurnStartedEvent {})]
     }
 
-    fn handle_task_complete(&mut self) -> Vec<ThreadEvent> {
+    fn handle_task_complete(&mut self, ev: &protocol::TurnCompleteEvent) -> Vec<ThreadEvent> {
         let usage = if let Some(u) = &self.last_total_token_usage {
             Usage {
                 input_tokens: u.input_tokens,
@@ -505,7 +559,21 @@ impl EventProcessorWithJsonOutput {
         if let Some(error) = self.last_critical_error.take() {
             items.push(ThreadEvent::TurnFailed(TurnFailedEvent { error }));
         } else {
-            items.push(ThreadEvent::TurnCompleted(TurnCompletedEvent { usage }));
+            let stats = ev.metadata.as_ref().map(|metadata| TurnStats {
+                model: metadata.model.clone(),
+                reasoning_effort: metadata.reasoning_effort,
+                duration_ms: metadata.duration_ms,
+                retries: metadata.retries,
+                usage: Usage {
+                    input_tokens: metadata.token_usage.input_tokens,
+                    cached_input_tokens: metadata.token_usage.cached_input_tokens,
+                    output_tokens: metadata.token_usage.output_tokens,
+                },
+            });
+            items.push(ThreadEvent::TurnCompleted(TurnCompletedEvent {
+                usage,
+                stats,
+            }));
         }
 
         items
@@ -538,6 +606,7 @@ impl EventProcessor for EventProcessorWithJsonOutput {
 
         if let protocol::EventMsg::TurnComplete(protocol::TurnCompleteEvent {
             last_agent_message,
+            ..
         }) = msg
         {
             if let Some(output_file) = self.last_message_path.as_deref() {
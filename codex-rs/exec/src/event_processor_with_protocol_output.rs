@@ -0,0 +1,233 @@
+//! `codex exec --output-format jsonl`: every protocol event as one JSON line,
+//! followed by a single summary record when the run ends.
+//!
+//! Unlike `--json`, which reports a curated `ThreadEvent` view of the run,
+//! this stream passes the protocol events through unchanged. Each line is an
+//! object with a `record` tag (`event` or `summary`) and `schema_version`.
+//! Event records carry the submission `id` and the protocol `msg` (tagged by
+//! its `type`); consumers should skip `msg.type` values they do not know.
+//! Changes that break existing consumers bump [`SCHEMA_VERSION`].
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use codex_core::config::Config;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::TokenUsage;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::error;
+
+use crate::event_processor::CodexStatus;
+use crate::event_processor::EventProcessor;
+use crate::event_processor::handle_last_message;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+pub enum ProtocolRecord {
+    Event {
+        schema_version: u32,
+        id: String,
+        msg: EventMsg,
+    },
+    Summary {
+        schema_version: u32,
+        #[serde(flatten)]
+        summary: RunSummary,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Completed,
+    /// An error event was reported; `codex exec` exits with status 1.
+    Failed,
+    /// The turn was aborted before it completed.
+    Interrupted,
+    /// `--max-duration` was reached.
+    TimedOut,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub status: RunStatus,
+    pub thread_id: Option<String>,
+    pub last_agent_message: Option<String>,
+    /// Working tree changes reported by the turns' diffs.
+    pub diffstat: DiffStat,
+    /// Running thread total, from the last token count event.
+    pub tokens: Option<TokenUsage>,
+    /// Estimated session cost, when `[budget.prices]` covers the model.
+    pub cost_usd: Option<f64>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStat {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl DiffStat {
+    /// Counts a unified diff as produced by `git diff`.
+    pub fn from_unified_diff(diff: &str) -> Self {
+        let mut stat = Self::default();
+        for line in diff.lines() {
+            if line.starts_with("diff --git ") {
+                stat.files_changed += 1;
+            } else if line.starts_with("+++") || line.starts_with("---") {
+                continue;
+            } else if line.starts_with('+') {
+                stat.insertions += 1;
+            } else if line.starts_with('-') {
+                stat.deletions += 1;
+            }
+        }
+        stat
+    }
+}
+
+pub struct EventProcessorWithProtocolOutput {
+    last_message_path: Option<PathBuf>,
+    started_at: Instant,
+    thread_id: Option<String>,
+    last_agent_message: Option<String>,
+    /// Latest diff of each turn, by submission id; a turn's diff replaces the
+    /// previous one.
+    turn_diffs: BTreeMap<String, String>,
+    tokens: Option<TokenUsage>,
+    cost_usd: Option<f64>,
+    error_seen: bool,
+    aborted: bool,
+    timed_out: bool,
+}
+
+impl EventProcessorWithProtocolOutput {
+    pub fn new(last_message_path: Option<PathBuf>) -> Self {
+        Self {
+            last_message_path,
+            started_at: Instant::now(),
+            thread_id: None,
+            last_agent_message: None,
+            turn_diffs: BTreeMap::new(),
+            tokens: None,
+            cost_usd: None,
+            error_seen: false,
+            aborted: false,
+            timed_out: false,
+        }
+    }
+
+    /// Updates the running summary with `event` and returns its record.
+    pub fn record_event(&mut self, event: &Event) -> ProtocolRecord {
+        match &event.msg {
+            EventMsg::SessionConfigured(ev) => {
+                self.thread_id = Some(ev.session_id.to_string());
+            }
+            EventMsg::TurnComplete(ev) => {
+                self.last_agent_message = ev.last_agent_message.clone();
+                self.aborted = false;
+            }
+            EventMsg::TurnAborted(_) => self.aborted = true,
+            EventMsg::Error(_) => self.error_seen = true,
+            EventMsg::TurnDiff(ev) => {
+                self.turn_diffs
+                    .insert(event.id.clone(), ev.unified_diff.clone());
+            }
+            EventMsg::TokenCount(ev) => {
+                if let Some(info) = &ev.info {
+                    self.tokens = Some(info.total_token_usage.clone());
+                }
+            }
+            EventMsg::CostUpdate(ev) => self.cost_usd = Some(ev.session_usd),
+            _ => {}
+        }
+        ProtocolRecord::Event {
+            schema_version: SCHEMA_VERSION,
+            id: event.id.clone(),
+            msg: event.msg.clone(),
+        }
+    }
+
+    pub fn summary(&self) -> RunSummary {
+        let status = if self.timed_out {
+            RunStatus::TimedOut
+        } else if self.error_seen {
+            RunStatus::Failed
+        } else if self.aborted {
+            RunStatus::Interrupted
+        } else {
+            RunStatus::Completed
+        };
+        let diffstat = self
+            .turn_diffs
+            .values()
+            .fold(DiffStat::default(), |acc, diff| {
+                let stat = DiffStat::from_unified_diff(diff);
+                DiffStat {
+                    files_changed: acc.files_changed + stat.files_changed,
+                    insertions: acc.insertions + stat.insertions,
+                    deletions: acc.deletions + stat.deletions,
+                }
+            });
+        RunSummary {
+            status,
+            thread_id: self.thread_id.clone(),
+            last_agent_message: self.last_agent_message.clone(),
+            diffstat,
+            tokens: self.tokens.clone(),
+            cost_usd: self.cost_usd,
+            duration_ms: u64::try_from(self.started_at.elapsed().as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+
+    #[allow(clippy::print_stdout)]
+    fn print_record(record: &ProtocolRecord) {
+        match serde_json::to_string(record) {
+            Ok(line) => println!("{line}"),
+            Err(e) => error!("Failed to serialize record: {e:?}"),
+        }
+    }
+}
+
+impl EventProcessor for EventProcessorWithProtocolOutput {
+    fn print_config_summary(&mut self, _: &Config, _: &str, ev: &SessionConfiguredEvent) {
+        self.process_event(Event {
+            id: String::new(),
+            msg: EventMsg::SessionConfigured(ev.clone()),
+        });
+    }
+
+    fn process_event(&mut self, event: Event) -> CodexStatus {
+        let record = self.record_event(&event);
+        Self::print_record(&record);
+        match event.msg {
+            EventMsg::TurnComplete(ev) => {
+                if let Some(output_file) = self.last_message_path.as_deref() {
+                    handle_last_message(ev.last_agent_message.as_deref(), output_file);
+                }
+                CodexStatus::InitiateShutdown
+            }
+            EventMsg::ShutdownComplete => CodexStatus::Shutdown,
+            _ => CodexStatus::Running,
+        }
+    }
+
+    fn mark_timed_out(&mut self) {
+        self.timed_out = true;
+    }
+
+    fn print_final_output(&mut self) {
+        Self::print_record(&ProtocolRecord::Summary {
+            schema_version: SCHEMA_VERSION,
+            summary: self.summary(),
+        });
+    }
+}
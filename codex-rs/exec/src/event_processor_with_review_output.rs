@@ -0,0 +1,210 @@
+//! `codex review --format sarif|github`: the review's findings as one JSON
+//! document on stdout once the review finishes.
+//!
+//! `sarif` is a SARIF 2.1.0 log, e.g. for GitHub code scanning. `github` is
+//! the body of GitHub's "create a review for a pull request" API
+//! (`gh api repos/OWNER/REPO/pulls/N/reviews --input -`), with one inline
+//! comment per finding. Paths are relative to the repository root, and a
+//! finding's ```` ```suggestion ```` block becomes a SARIF fix.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::config::Config;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewOutputEvent;
+use codex_core::protocol::SessionConfiguredEvent;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::cli::ReviewFormat;
+use crate::event_processor::CodexStatus;
+use crate::event_processor::EventProcessor;
+use crate::event_processor::handle_last_message;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Severity of a finding, from its review priority (P0 is the highest).
+pub fn severity(priority: i32) -> &'static str {
+    match priority {
+        i32::MIN..=0 => "critical",
+        1 => "high",
+        2 => "medium",
+        _ => "low",
+    }
+}
+
+/// The replacement code of the first ```` ```suggestion ```` block in `body`.
+pub fn suggestion(body: &str) -> Option<String> {
+    let (_, rest) = body.split_once("```suggestion")?;
+    let (_, rest) = rest.split_once('\n')?;
+    let end = rest.find("```")?;
+    Some(rest[..end].to_string())
+}
+
+pub fn render_sarif(output: &ReviewOutputEvent, root: &Path) -> Value {
+    let results: Vec<Value> = output
+        .findings
+        .iter()
+        .map(|finding| {
+            let uri = relative_path(finding, root);
+            let region = json!({
+                "startLine": finding.code_location.line_range.start,
+                "endLine": finding.code_location.line_range.end,
+            });
+            let mut result = json!({
+                "ruleId": format!("codex-review/p{}", finding.priority.max(0)),
+                "level": match finding.priority {
+                    i32::MIN..=1 => "error",
+                    2 => "warning",
+                    _ => "note",
+                },
+                "message": { "text": format!("{}\n\n{}", finding.title, finding.body) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        "region": region,
+                    },
+                }],
+                "properties": {
+                    "severity": severity(finding.priority),
+                    "confidence": finding.confidence_score,
+                },
+            });
+            if let Some(code) = suggestion(&finding.body) {
+                result["fixes"] = json!([{
+                    "description": { "text": finding.title },
+                    "artifactChanges": [{
+                        "artifactLocation": { "uri": uri },
+                        "replacements": [{
+                            "deletedRegion": region,
+                            "insertedContent": { "text": code },
+                        }],
+                    }],
+                }]);
+            }
+            result
+        })
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "codex-review",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/openai/codex",
+                },
+            },
+            "results": results,
+            "properties": {
+                "overallCorrectness": output.overall_correctness,
+                "overallExplanation": output.overall_explanation,
+            },
+        }],
+    })
+}
+
+pub fn render_github_review(output: &ReviewOutputEvent, root: &Path) -> Value {
+    let comments: Vec<Value> = output
+        .findings
+        .iter()
+        .map(|finding| {
+            let range = &finding.code_location.line_range;
+            let mut comment = json!({
+                "path": relative_path(finding, root),
+                "line": range.end,
+                "side": "RIGHT",
+                "body": format!(
+                    "**{}** ({})\n\n{}",
+                    finding.title,
+                    severity(finding.priority),
+                    finding.body
+                ),
+            });
+            if range.start < range.end {
+                comment["start_line"] = json!(range.start);
+                comment["start_side"] = json!("RIGHT");
+            }
+            comment
+        })
+        .collect();
+    let mut body = output.overall_explanation.trim().to_string();
+    if !output.overall_correctness.is_empty() {
+        body = format!("**Verdict:** {}\n\n{body}", output.overall_correctness);
+    }
+    json!({
+        "event": "COMMENT",
+        "body": body.trim_end(),
+        "comments": comments,
+    })
+}
+
+fn relative_path(finding: &ReviewFinding, root: &Path) -> String {
+    let path = &finding.code_location.absolute_file_path;
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+pub struct EventProcessorWithReviewOutput {
+    format: ReviewFormat,
+    /// Finding paths are made relative to this directory.
+    root: PathBuf,
+    last_message_path: Option<PathBuf>,
+    review_output: Option<ReviewOutputEvent>,
+}
+
+impl EventProcessorWithReviewOutput {
+    pub fn new(format: ReviewFormat, root: PathBuf, last_message_path: Option<PathBuf>) -> Self {
+        Self {
+            format,
+            root,
+            last_message_path,
+            review_output: None,
+        }
+    }
+}
+
+impl EventProcessor for EventProcessorWithReviewOutput {
+    fn print_config_summary(&mut self, _: &Config, prompt: &str, _: &SessionConfiguredEvent) {
+        eprintln!("Reviewing {prompt}...");
+    }
+
+    fn process_event(&mut self, event: Event) -> CodexStatus {
+        match event.msg {
+            EventMsg::ExitedReviewMode(ev) => self.review_output = ev.review_output,
+            EventMsg::Error(ev) => eprintln!("ERROR: {}", ev.message),
+            EventMsg::TurnComplete(ev) => {
+                if let Some(output_file) = self.last_message_path.as_deref() {
+                    handle_last_message(ev.last_agent_message.as_deref(), output_file);
+                }
+                return CodexStatus::InitiateShutdown;
+            }
+            EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
+            _ => {}
+        }
+        CodexStatus::Running
+    }
+
+    #[allow(clippy::print_stdout)]
+    fn print_final_output(&mut self) {
+        let Some(output) = &self.review_output else {
+            eprintln!("The review did not finish; no findings to report.");
+            return;
+        };
+        let document = match self.format {
+            ReviewFormat::Sarif => render_sarif(output, &self.root),
+            ReviewFormat::Github => render_github_review(output, &self.root),
+            ReviewFormat::Text => return,
+        };
+        match serde_json::to_string_pretty(&document) {
+            Ok(document) => println!("{document}"),
+            Err(err) => eprintln!("Failed to serialize review findings: {err}"),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use codex_protocol::openai_models::ReasoningEffort;
 use mcp_types::ContentBlock as McpContentBlock;
 use serde::Deserialize;
 use serde::Serialize;
@@ -48,6 +49,25 @@ pub struct TurnStartedEvent {}
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub struct TurnCompletedEvent {
     pub usage: Usage,
+    /// Model, timing and retry stats of this turn, when reported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub stats: Option<TurnStats>,
+}
+
+/// Per-turn stats attached to `turn.completed`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+pub struct TurnStats {
+    /// The model that served the turn.
+    pub model: String,
+    /// The reasoning effort the turn ran with, if any.
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Wall-clock time from the start of the turn until it completed.
+    pub duration_ms: u64,
+    /// Model requests retried after a stream error or a rate limit.
+    pub retries: u32,
+    /// Tokens used by this turn only; `usage` is the running thread total.
+    pub usage: Usage,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
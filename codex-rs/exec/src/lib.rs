@@ -1,18 +1,29 @@
 // - In the default output mode, it is paramount that the only thing written to
-//   stdout is the final message (if any).
-// - In --json mode, stdout must be valid JSONL, one event per line.
+//   stdout is the final message (if any). With --stream, that message is
+//   written incrementally as it is generated.
+// - In --json and --output-format jsonl modes, stdout must be valid JSONL, one
+//   record per line. `review --format sarif|github` prints one JSON document,
+//   and --ci adds workflow commands (`::error ...`) for the CI runner.
 // For both modes, any other output must be written to stderr.
 #![deny(clippy::print_stdout)]
 
+mod ci;
 mod cli;
 mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
+pub mod event_processor_with_protocol_output;
+pub mod event_processor_with_review_output;
 pub mod exec_events;
+mod timebox;
 
+pub use ci::BUDGET_EXCEEDED_EXIT_CODE;
+pub use ci::NEEDS_HUMAN_EXIT_CODE;
+pub use ci::SANDBOX_VIOLATION_EXIT_CODE;
 pub use cli::Cli;
 pub use cli::Command;
 pub use cli::ReviewArgs;
+pub use cli::ReviewFormat;
 use codex_common::oss::ensure_oss_provider_ready;
 use codex_common::oss::get_default_model_for_oss_provider;
 use codex_common::oss::ollama_chat_deprecation_notice;
@@ -43,11 +54,14 @@ use codex_protocol::user_input::UserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
+use event_processor_with_protocol_output::EventProcessorWithProtocolOutput;
+use event_processor_with_review_output::EventProcessorWithReviewOutput;
 use serde_json::Value;
 use std::io::IsTerminal;
 use std::io::Read;
 use std::path::PathBuf;
 use supports_color::Stream;
+pub use timebox::TIMED_OUT_EXIT_CODE;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
@@ -89,13 +103,31 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         add_dir,
         color,
         last_message_file,
+        max_duration,
+        ci: ci_mode,
         json: json_mode,
+        output_format,
+        stream: stream_mode,
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
-        config_overrides,
+        mut config_overrides,
     } = cli;
 
+    let review_format = match command.as_ref() {
+        Some(ExecCommand::Review(args)) => {
+            if args.split_by_area {
+                config_overrides
+                    .raw_overrides
+                    .push("review_split_by_area=true".to_string());
+            }
+            args.format
+        }
+        _ => ReviewFormat::Text,
+    };
+
+    // Workflow commands must reach the runner without escape codes.
+    let color = if ci_mode { cli::Color::Never } else { color };
     let (stdout_with_ansi, stderr_with_ansi) = match color {
         cli::Color::Always => (true, true),
         cli::Color::Never => (false, false),
@@ -258,13 +290,27 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         .with(otel_logger_layer)
         .try_init();
 
-    let mut event_processor: Box<dyn EventProcessor> = match json_mode {
-        true => Box::new(EventProcessorWithJsonOutput::new(last_message_file.clone())),
-        _ => Box::new(EventProcessorWithHumanOutput::create_with_ansi(
-            stdout_with_ansi,
-            &config,
+    let mut event_processor: Box<dyn EventProcessor> = match (json_mode, output_format) {
+        _ if review_format != ReviewFormat::Text => Box::new(EventProcessorWithReviewOutput::new(
+            review_format,
+            get_git_repo_root(&config.cwd).unwrap_or_else(|| config.cwd.clone()),
             last_message_file.clone(),
         )),
+        (true, _) => Box::new(
+            EventProcessorWithJsonOutput::new(last_message_file.clone())
+                .with_agent_message_streaming(stream_mode),
+        ),
+        (false, cli::OutputFormat::Jsonl) => Box::new(EventProcessorWithProtocolOutput::new(
+            last_message_file.clone(),
+        )),
+        (false, cli::OutputFormat::Human) => {
+            Box::new(EventProcessorWithHumanOutput::create_with_ansi(
+                stdout_with_ansi,
+                &config,
+                last_message_file.clone(),
+                stream_mode,
+            ))
+        }
     };
     if let Some(notice) = ollama_chat_support_notice {
         event_processor.process_event(Event {
@@ -318,7 +364,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
 
     // Handle resume subcommand by resolving a rollout path and using explicit resume API.
     let NewThread {
-        thread_id: _,
+        thread_id,
         thread,
         session_configured,
     } = if let Some(ExecCommand::Resume(args)) = command.as_ref() {
@@ -472,7 +518,59 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
-    while let Some(event) = rx.recv().await {
+    // With `--max-duration`: when to ask the agent to wrap up, then when to
+    // interrupt it if it has not finished.
+    let mut wrap_up_at = max_duration.map(|limit| tokio::time::Instant::now() + limit);
+    let mut interrupt_at = None;
+    let mut timed_out = false;
+    let mut shutting_down = false;
+    let mut last_agent_message = None;
+    let mut ci_run = ci_mode.then(|| {
+        ci::CiRun::new(get_git_repo_root(&config.cwd).unwrap_or_else(|| config.cwd.clone()))
+    });
+    loop {
+        let event = tokio::select! {
+            event = rx.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            _ = timebox::sleep_until(wrap_up_at) => {
+                wrap_up_at = None;
+                if shutting_down {
+                    continue;
+                }
+                let Some(limit) = max_duration else {
+                    continue;
+                };
+                let grace = config.timebox.grace;
+                timed_out = true;
+                interrupt_at = Some(tokio::time::Instant::now() + grace);
+                #[allow(clippy::print_stderr)]
+                {
+                    eprintln!(
+                        "Time limit of {} reached; asking the agent to wrap up.",
+                        timebox::format_duration(limit)
+                    );
+                }
+                thread
+                    .submit(Op::UserInput {
+                        items: vec![UserInput::Text {
+                            text: timebox::wrap_up_prompt(limit, grace, config.timebox.persist),
+                            text_elements: Vec::new(),
+                        }],
+                        final_output_json_schema: None,
+                    })
+                    .await?;
+                continue;
+            }
+            _ = timebox::sleep_until(interrupt_at) => {
+                interrupt_at = None;
+                if !shutting_down {
+                    thread.submit(Op::Interrupt).await?;
+                }
+                continue;
+            }
+        };
         if let EventMsg::ElicitationRequest(ev) = &event.msg {
             // Automatically cancel elicitation requests in exec mode.
             thread
@@ -483,13 +581,32 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                 })
                 .await?;
         }
+        if let Some(ci_run) = ci_run.as_mut() {
+            #[allow(clippy::print_stdout)]
+            for command in ci_run.observe(&event.msg) {
+                println!("{command}");
+            }
+            if let Some(op) = ci::decline(&event) {
+                thread.submit(op).await?;
+            }
+        }
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
+        if let EventMsg::TurnComplete(ev) = &event.msg {
+            last_agent_message = ev.last_agent_message.clone();
+        }
+        // A timed-out turn that had to be interrupted does not complete.
+        let interrupted_after_timeout = timed_out && matches!(event.msg, EventMsg::TurnAborted(_));
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
+            CodexStatus::Running if interrupted_after_timeout && !shutting_down => {
+                shutting_down = true;
+                thread.submit(Op::Shutdown).await?;
+            }
             CodexStatus::Running => continue,
             CodexStatus::InitiateShutdown => {
+                shutting_down = true;
                 thread.submit(Op::Shutdown).await?;
             }
             CodexStatus::Shutdown => {
@@ -497,7 +614,34 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             }
         }
     }
+    if timed_out {
+        event_processor.mark_timed_out();
+    }
     event_processor.print_final_output();
+    if timed_out && let Some(limit) = max_duration {
+        let thread_id = thread_id.to_string();
+        let outcome = timebox::persist_progress(
+            &config.cwd,
+            config.timebox.persist,
+            limit,
+            last_agent_message.as_deref(),
+            &thread_id,
+        )
+        .await;
+        #[allow(clippy::print_stderr)]
+        match outcome {
+            Ok(Some(done)) => eprintln!("Time limit reached: {done}."),
+            Ok(None) => {}
+            Err(err) => eprintln!("Time limit reached, but persisting progress failed: {err}"),
+        }
+        std::process::exit(TIMED_OUT_EXIT_CODE);
+    }
+    if let Some(ci_run) = ci_run {
+        let code = ci_run.outcome().exit_code();
+        if code != 0 {
+            std::process::exit(code);
+        }
+    }
     if error_seen {
         std::process::exit(1);
     }
@@ -691,6 +835,11 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
             sha,
             title: args.commit_title,
         }
+    } else if let Some(range) = args.range {
+        ReviewTarget::Range { range }
+    } else if let Some(url) = args.pull_request {
+        codex_core::review_prompts::PullRequestRef::parse(&url)?;
+        ReviewTarget::PullRequest { url }
     } else if let Some(prompt_arg) = args.prompt {
         let prompt = resolve_prompt(Some(prompt_arg)).trim().to_string();
         if prompt.is_empty() {
@@ -701,7 +850,7 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
         }
     } else {
         anyhow::bail!(
-            "Specify --uncommitted, --base, --commit, or provide custom review instructions"
+            "Specify --uncommitted, --base, --commit, --range, --pr, or provide custom review instructions"
         );
     };
 
@@ -723,6 +872,10 @@ mod tests {
             base: None,
             commit: None,
             commit_title: None,
+            range: None,
+            pull_request: None,
+            split_by_area: false,
+            format: ReviewFormat::Text,
             prompt: None,
         })
         .expect("builds uncommitted review request");
@@ -742,6 +895,10 @@ mod tests {
             base: None,
             commit: Some("123456789".to_string()),
             commit_title: Some("Add review command".to_string()),
+            range: None,
+            pull_request: None,
+            split_by_area: false,
+            format: ReviewFormat::Text,
             prompt: None,
         })
         .expect("builds commit review request");
@@ -764,6 +921,10 @@ mod tests {
             base: None,
             commit: None,
             commit_title: None,
+            range: None,
+            pull_request: None,
+            split_by_area: false,
+            format: ReviewFormat::Text,
             prompt: Some("  custom review instructions  ".to_string()),
         })
         .expect("builds custom review request");
@@ -778,6 +939,31 @@ mod tests {
         assert_eq!(request, expected);
     }
 
+    #[test]
+    fn builds_pull_request_review_request() {
+        let args = |url: &str| ReviewArgs {
+            uncommitted: false,
+            base: None,
+            commit: None,
+            commit_title: None,
+            range: None,
+            pull_request: Some(url.to_string()),
+            split_by_area: false,
+            format: ReviewFormat::Text,
+            prompt: None,
+        };
+
+        let request = build_review_request(args("https://github.com/openai/codex/pull/42"))
+            .expect("builds pull request review request");
+        assert_eq!(
+            request.target,
+            ReviewTarget::PullRequest {
+                url: "https://github.com/openai/codex/pull/42".to_string(),
+            }
+        );
+        assert!(build_review_request(args("https://github.com/openai/codex")).is_err());
+    }
+
     #[test]
     fn decode_prompt_bytes_strips_utf8_bom() {
         let input = [0xEF, 0xBB, 0xBF, b'h', b'i', b'\n'];
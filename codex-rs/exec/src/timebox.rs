@@ -0,0 +1,220 @@
+//! `--max-duration`: a wall-clock limit for headless runs.
+//!
+//! When the limit is reached the agent is told to stop starting new work and
+//! to reply with a summary of what is done and what is left. If the turn has
+//! not finished after `[timebox].grace_seconds`, it is interrupted. Progress
+//! is then persisted according to `[timebox].persist` — by this process, not
+//! the agent, because the sandbox keeps `.git` read-only — and `codex exec`
+//! exits with [`TIMED_OUT_EXIT_CODE`].
+
+use std::path::Path;
+use std::time::Duration;
+
+use codex_core::config::types::TimeboxPersist;
+use tokio::process::Command;
+use tokio::time::Instant;
+
+/// Same status as `timeout(1)` uses when the command timed out.
+pub const TIMED_OUT_EXIT_CODE: i32 = 124;
+
+/// Waits until `deadline`, or forever when there is none.
+pub(crate) async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Parses durations such as `90s`, `30m`, `2h` or `1h30m`.
+pub(crate) fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("duration is empty".to_string());
+    }
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for ch in value.chars() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            continue;
+        }
+        let unit = match ch {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            _ => return Err(format!("invalid duration `{value}`: unknown unit `{ch}`")),
+        };
+        let amount: u64 = digits
+            .parse()
+            .map_err(|_| format!("invalid duration `{value}`: expected a number before `{ch}`"))?;
+        total = total.saturating_add(amount.saturating_mul(unit));
+        digits.clear();
+    }
+    if !digits.is_empty() {
+        return Err(format!(
+            "invalid duration `{value}`: add a unit (s, m or h), e.g. `{digits}m`"
+        ));
+    }
+    if total == 0 {
+        return Err("duration must be greater than zero".to_string());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// `1h30m`, `45m`, `90s` — the inverse of [`parse_duration`].
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    let mut text = String::new();
+    if hours > 0 {
+        text.push_str(&format!("{hours}h"));
+    }
+    if minutes > 0 {
+        text.push_str(&format!("{minutes}m"));
+    }
+    if seconds > 0 || text.is_empty() {
+        text.push_str(&format!("{seconds}s"));
+    }
+    text
+}
+
+/// Message sent to the running turn when the limit is reached.
+pub(crate) fn wrap_up_prompt(limit: Duration, grace: Duration, persist: TimeboxPersist) -> String {
+    let persistence = match persist {
+        TimeboxPersist::None => "Your changes will be left in the working tree as they are.",
+        TimeboxPersist::Commit => {
+            "Your changes will be committed to the current branch automatically after you reply; do not commit them yourself."
+        }
+        TimeboxPersist::Branch => {
+            "Your changes will be committed to a new branch automatically after you reply; do not commit them yourself."
+        }
+        TimeboxPersist::Stash => {
+            "Your changes will be stashed automatically after you reply; do not stash them yourself."
+        }
+    };
+    format!(
+        "The time limit of {} for this run has been reached. Do not start new work. \
+Leave the code in a consistent state within the next {}, then reply with a summary \
+in two sections: \"Completed\" (what is done) and \"Remaining\" (what is left, with \
+enough detail for someone to pick it up). {persistence}",
+        format_duration(limit),
+        format_duration(grace),
+    )
+}
+
+/// Persists the work left in `cwd` after a timed-out run. Returns what was
+/// done, or `None` when there was nothing to persist.
+pub(crate) async fn persist_progress(
+    cwd: &Path,
+    persist: TimeboxPersist,
+    limit: Duration,
+    summary: Option<&str>,
+    thread_id: &str,
+) -> Result<Option<String>, String> {
+    if persist == TimeboxPersist::None {
+        return Ok(None);
+    }
+    let status = git(cwd, &["status", "--porcelain"]).await?;
+    if status.trim().is_empty() {
+        return Ok(None);
+    }
+    let message = commit_message(limit, summary);
+    match persist {
+        TimeboxPersist::None => Ok(None),
+        TimeboxPersist::Stash => {
+            git(
+                cwd,
+                &["stash", "push", "--include-untracked", "-m", &message],
+            )
+            .await?;
+            Ok(Some("stashed the remaining changes".to_string()))
+        }
+        TimeboxPersist::Commit => {
+            git(cwd, &["add", "--all"]).await?;
+            git(cwd, &["commit", "--no-verify", "-m", &message]).await?;
+            let branch = git(cwd, &["rev-parse", "--abbrev-ref", "HEAD"]).await?;
+            Ok(Some(format!("committed the changes to {}", branch.trim())))
+        }
+        TimeboxPersist::Branch => {
+            let branch = format!("codex/timebox-{thread_id}");
+            git(cwd, &["switch", "--create", &branch]).await?;
+            git(cwd, &["add", "--all"]).await?;
+            git(cwd, &["commit", "--no-verify", "-m", &message]).await?;
+            Ok(Some(format!(
+                "committed the changes to the new branch {branch}"
+            )))
+        }
+    }
+}
+
+fn commit_message(limit: Duration, summary: Option<&str>) -> String {
+    let subject = format!("WIP: codex exec stopped after {}", format_duration(limit));
+    match summary.map(str::trim).filter(|summary| !summary.is_empty()) {
+        Some(summary) => format!("{subject}\n\n{summary}"),
+        None => subject,
+    }
+}
+
+async fn git(cwd: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .await
+        .map_err(|err| format!("failed to run git {}: {err}", args.join(" ")))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_durations_with_units() {
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(90 * 60)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(
+            parse_duration("30"),
+            Err("invalid duration `30`: add a unit (s, m or h), e.g. `30m`".to_string())
+        );
+        assert_eq!(
+            parse_duration("2d"),
+            Err("invalid duration `2d`: unknown unit `d`".to_string())
+        );
+        assert_eq!(
+            parse_duration("0m"),
+            Err("duration must be greater than zero".to_string())
+        );
+    }
+
+    #[test]
+    fn formats_durations_compactly() {
+        assert_eq!(format_duration(Duration::from_secs(90 * 60)), "1h30m");
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(2 * 3600)), "2h");
+    }
+
+    #[test]
+    fn commit_message_includes_summary() {
+        assert_eq!(
+            commit_message(
+                Duration::from_secs(1800),
+                Some("Completed: a\nRemaining: b\n")
+            ),
+            "WIP: codex exec stopped after 30m\n\nCompleted: a\nRemaining: b"
+        );
+        assert_eq!(
+            commit_message(Duration::from_secs(1800), Some("  ")),
+            "WIP: codex exec stopped after 30m"
+        );
+    }
+}
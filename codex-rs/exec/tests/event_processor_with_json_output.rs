@@ -1,3 +1,4 @@
+use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::AskForApproval;
@@ -42,8 +43,10 @@ use codex_exec::exec_events::TodoListItem as ExecTodoListItem;
 use codex_exec::exec_events::TurnCompletedEvent;
 use codex_exec::exec_events::TurnFailedEvent;
 use codex_exec::exec_events::TurnStartedEvent;
+use codex_exec::exec_events::TurnStats;
 use codex_exec::exec_events::Usage;
 use codex_exec::exec_events::WebSearchItem;
+use codex_protocol::openai_models::ReasoningEffort;
 use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -220,6 +223,7 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
         "p3",
         EventMsg::TurnComplete(codex_core::protocol::TurnCompleteEvent {
             last_agent_message: None,
+            metadata: None,
         }),
     );
     let out_complete = ep.collect_thread_events(&complete);
@@ -245,6 +249,7 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
             }),
             ThreadEvent::TurnCompleted(TurnCompletedEvent {
                 usage: Usage::default(),
+                stats: None,
             }),
         ]
     );
@@ -464,6 +469,7 @@ fn plan_update_after_complete_starts_new_todo_list_with_new_id() {
         "t2",
         EventMsg::TurnComplete(codex_core::protocol::TurnCompleteEvent {
             last_agent_message: None,
+            metadata: None,
         }),
     );
     let _ = ep.collect_thread_events(&complete);
@@ -535,6 +541,60 @@ fn agent_message_produces_item_completed_agent_message() {
     );
 }
 
+#[test]
+fn streamed_agent_message_emits_started_updated_completed() {
+    let mut ep = EventProcessorWithJsonOutput::new(None).with_agent_message_streaming(true);
+    let agent_message = |text: &str| ThreadItem {
+        id: "item_0".to_string(),
+        details: ThreadItemDetails::AgentMessage(AgentMessageItem {
+            text: text.to_string(),
+        }),
+    };
+
+    let mut out = Vec::new();
+    for delta in ["hel", "lo"] {
+        out.extend(ep.collect_thread_events(&event(
+            "e1",
+            EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+                delta: delta.to_string(),
+            }),
+        )));
+    }
+    out.extend(ep.collect_thread_events(&event(
+        "e2",
+        EventMsg::AgentMessage(AgentMessageEvent {
+            message: "hello".to_string(),
+        }),
+    )));
+
+    assert_eq!(
+        out,
+        vec![
+            ThreadEvent::ItemStarted(ItemStartedEvent {
+                item: agent_message("hel"),
+            }),
+            ThreadEvent::ItemUpdated(ItemUpdatedEvent {
+                item: agent_message("hello"),
+            }),
+            ThreadEvent::ItemCompleted(ItemCompletedEvent {
+                item: agent_message("hello"),
+            }),
+        ]
+    );
+}
+
+#[test]
+fn agent_message_deltas_ignored_without_streaming() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+    let out = ep.collect_thread_events(&event(
+        "e1",
+        EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "hel".to_string(),
+        }),
+    ));
+    assert_eq!(out, Vec::new());
+}
+
 #[test]
 fn error_event_produces_error() {
     let mut ep = EventProcessorWithJsonOutput::new(None);
@@ -616,6 +676,7 @@ fn error_followed_by_task_complete_produces_turn_failed() {
         "e2",
         EventMsg::TurnComplete(codex_core::protocol::TurnCompleteEvent {
             last_agent_message: None,
+            metadata: None,
         }),
     );
     assert_eq!(
@@ -1054,6 +1115,8 @@ fn task_complete_produces_turn_completed_with_usage() {
         EventMsg::TokenCount(codex_core::protocol::TokenCountEvent {
             info: Some(info),
             rate_limits: None,
+            tool_usage: Vec::new(),
+            subagent_usage: Vec::new(),
         }),
     );
     assert!(ep.collect_thread_events(&token_count_event).is_empty());
@@ -1063,6 +1126,7 @@ fn task_complete_produces_turn_completed_with_usage() {
         "e2",
         EventMsg::TurnComplete(codex_core::protocol::TurnCompleteEvent {
             last_agent_message: Some("done".to_string()),
+            metadata: None,
         }),
     );
     let out = ep.collect_thread_events(&complete_event);
@@ -1074,6 +1138,50 @@ fn task_complete_produces_turn_completed_with_usage() {
                 cached_input_tokens: 200,
                 output_tokens: 345,
             },
+            stats: None,
+        })]
+    );
+}
+
+#[test]
+fn task_complete_reports_turn_stats() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+    let turn_usage = codex_core::protocol::TokenUsage {
+        input_tokens: 300,
+        cached_input_tokens: 100,
+        output_tokens: 40,
+        reasoning_output_tokens: 10,
+        total_tokens: 340,
+    };
+    let complete_event = event(
+        "e1",
+        EventMsg::TurnComplete(codex_core::protocol::TurnCompleteEvent {
+            last_agent_message: None,
+            metadata: Some(codex_core::protocol::TurnMetadata {
+                model: "gpt-5".to_string(),
+                reasoning_effort: Some(ReasoningEffort::High),
+                duration_ms: 4_200,
+                retries: 2,
+                token_usage: turn_usage,
+            }),
+        }),
+    );
+
+    assert_eq!(
+        ep.collect_thread_events(&complete_event),
+        vec![ThreadEvent::TurnCompleted(TurnCompletedEvent {
+            usage: Usage::default(),
+            stats: Some(TurnStats {
+                model: "gpt-5".to_string(),
+                reasoning_effort: Some(ReasoningEffort::High),
+                duration_ms: 4_200,
+                retries: 2,
+                usage: Usage {
+                    input_tokens: 300,
+                    cached_input_tokens: 100,
+                    output_tokens: 40,
+                },
+            }),
         })]
     );
 }
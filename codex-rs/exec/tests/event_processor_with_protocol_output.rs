@@ -0,0 +1,137 @@
+use codex_core::protocol::CostUpdateEvent;
+use codex_core::protocol::ErrorEvent;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::TokenCountEvent;
+use codex_core::protocol::TokenUsage;
+use codex_core::protocol::TokenUsageInfo;
+use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnAbortedEvent;
+use codex_core::protocol::TurnCompleteEvent;
+use codex_core::protocol::TurnDiffEvent;
+use codex_exec::event_processor_with_protocol_output::DiffStat;
+use codex_exec::event_processor_with_protocol_output::EventProcessorWithProtocolOutput;
+use codex_exec::event_processor_with_protocol_output::ProtocolRecord;
+use codex_exec::event_processor_with_protocol_output::RunStatus;
+use codex_exec::event_processor_with_protocol_output::SCHEMA_VERSION;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+fn event(id: &str, msg: EventMsg) -> Event {
+    Event {
+        id: id.to_string(),
+        msg,
+    }
+}
+
+fn turn_complete(message: &str) -> EventMsg {
+    EventMsg::TurnComplete(TurnCompleteEvent {
+        last_agent_message: Some(message.to_string()),
+        metadata: None,
+    })
+}
+
+#[test]
+fn events_are_passed_through_with_schema_version() {
+    let mut ep = EventProcessorWithProtocolOutput::new(None);
+
+    let record = ep.record_event(&event("1", turn_complete("done")));
+
+    assert_eq!(
+        serde_json::to_value(&record).unwrap(),
+        json!({
+            "record": "event",
+            "schema_version": SCHEMA_VERSION,
+            "id": "1",
+            "msg": { "type": "task_complete", "last_agent_message": "done" },
+        })
+    );
+}
+
+#[test]
+fn summary_collects_diffstat_tokens_and_cost() {
+    let mut ep = EventProcessorWithProtocolOutput::new(None);
+    let diff =
+        "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,2 @@\n-old\n+new\n+more\n";
+    let usage = TokenUsage {
+        input_tokens: 1200,
+        cached_input_tokens: 200,
+        output_tokens: 345,
+        reasoning_output_tokens: 0,
+        total_tokens: 1545,
+    };
+    for msg in [
+        EventMsg::TurnDiff(TurnDiffEvent {
+            unified_diff: "diff --git a/a.rs b/a.rs\n-old\n+first\n".to_string(),
+            files: Vec::new(),
+        }),
+        // A later diff of the same turn replaces the earlier one.
+        EventMsg::TurnDiff(TurnDiffEvent {
+            unified_diff: diff.to_string(),
+            files: Vec::new(),
+        }),
+        EventMsg::TokenCount(TokenCountEvent {
+            info: Some(TokenUsageInfo {
+                total_token_usage: usage.clone(),
+                last_token_usage: usage.clone(),
+                model_context_window: None,
+            }),
+            rate_limits: None,
+            tool_usage: Vec::new(),
+            subagent_usage: Vec::new(),
+        }),
+        EventMsg::CostUpdate(CostUpdateEvent {
+            turn_usd: 0.25,
+            session_usd: 0.5,
+            max_usd_per_turn: None,
+            max_usd_per_session: None,
+        }),
+        turn_complete("done"),
+    ] {
+        ep.record_event(&event("1", msg));
+    }
+
+    let summary = ep.summary();
+    assert_eq!(summary.status, RunStatus::Completed);
+    assert_eq!(summary.last_agent_message.as_deref(), Some("done"));
+    assert_eq!(
+        summary.diffstat,
+        DiffStat {
+            files_changed: 1,
+            insertions: 2,
+            deletions: 1,
+        }
+    );
+    assert_eq!(summary.tokens, Some(usage));
+    assert_eq!(summary.cost_usd, Some(0.5));
+
+    let value = serde_json::to_value(ProtocolRecord::Summary {
+        schema_version: SCHEMA_VERSION,
+        summary,
+    })
+    .unwrap();
+    assert_eq!(value["record"], "summary");
+    assert_eq!(value["status"], "completed");
+    assert_eq!(value["diffstat"]["insertions"], 2);
+}
+
+#[test]
+fn summary_status_reports_errors_and_aborts() {
+    let mut ep = EventProcessorWithProtocolOutput::new(None);
+    ep.record_event(&event(
+        "1",
+        EventMsg::TurnAborted(TurnAbortedEvent {
+            reason: TurnAbortReason::Interrupted,
+        }),
+    ));
+    assert_eq!(ep.summary().status, RunStatus::Interrupted);
+
+    ep.record_event(&event(
+        "1",
+        EventMsg::Error(ErrorEvent {
+            message: "boom".to_string(),
+            codex_error_info: None,
+        }),
+    ));
+    assert_eq!(ep.summary().status, RunStatus::Failed);
+}
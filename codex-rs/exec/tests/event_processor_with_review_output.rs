@@ -0,0 +1,98 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::protocol::ReviewCodeLocation;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewLineRange;
+use codex_core::protocol::ReviewOutputEvent;
+use codex_exec::event_processor_with_review_output::render_github_review;
+use codex_exec::event_processor_with_review_output::render_sarif;
+use codex_exec::event_processor_with_review_output::suggestion;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+fn output() -> ReviewOutputEvent {
+    ReviewOutputEvent {
+        findings: vec![
+            ReviewFinding {
+                title: "[P1] Handle empty input".to_string(),
+                body: "`parse` panics on an empty string.\n\n```suggestion\n    if input.is_empty() {\n        return None;\n    }\n```".to_string(),
+                confidence_score: 0.9,
+                priority: 1,
+                code_location: ReviewCodeLocation {
+                    absolute_file_path: PathBuf::from("/repo/src/parse.rs"),
+                    line_range: ReviewLineRange { start: 3, end: 5 },
+                },
+            },
+            ReviewFinding {
+                title: "[P3] Typo in log message".to_string(),
+                body: "\"recieved\" should be \"received\".".to_string(),
+                confidence_score: 0.5,
+                priority: 3,
+                code_location: ReviewCodeLocation {
+                    absolute_file_path: PathBuf::from("/repo/src/main.rs"),
+                    line_range: ReviewLineRange { start: 9, end: 9 },
+                },
+            },
+        ],
+        overall_correctness: "patch is incorrect".to_string(),
+        overall_explanation: "Empty input crashes the parser.".to_string(),
+        overall_confidence_score: 0.8,
+    }
+}
+
+#[test]
+fn extracts_suggestion_blocks() {
+    assert_eq!(
+        suggestion(&output().findings[0].body).as_deref(),
+        Some("    if input.is_empty() {\n        return None;\n    }\n")
+    );
+    assert_eq!(suggestion("no code here"), None);
+}
+
+#[test]
+fn sarif_reports_levels_locations_and_fixes() {
+    let sarif = render_sarif(&output(), Path::new("/repo"));
+    let results = &sarif["runs"][0]["results"];
+
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(results[0]["level"], "error");
+    assert_eq!(results[0]["ruleId"], "codex-review/p1");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"],
+        json!({
+            "artifactLocation": { "uri": "src/parse.rs" },
+            "region": { "startLine": 3, "endLine": 5 },
+        })
+    );
+    assert_eq!(
+        results[0]["fixes"][0]["artifactChanges"][0]["replacements"][0]["insertedContent"]["text"],
+        "    if input.is_empty() {\n        return None;\n    }\n"
+    );
+    assert_eq!(results[1]["level"], "note");
+    assert_eq!(results[1]["properties"]["severity"], "low");
+    assert!(results[1].get("fixes").is_none());
+}
+
+#[test]
+fn github_review_has_one_comment_per_finding() {
+    let review = render_github_review(&output(), Path::new("/repo"));
+
+    assert_eq!(review["event"], "COMMENT");
+    assert_eq!(
+        review["body"],
+        "**Verdict:** patch is incorrect\n\nEmpty input crashes the parser."
+    );
+    assert_eq!(review["comments"][0]["path"], "src/parse.rs");
+    assert_eq!(review["comments"][0]["start_line"], 3);
+    assert_eq!(review["comments"][0]["line"], 5);
+    assert_eq!(
+        review["comments"][1],
+        json!({
+            "path": "src/main.rs",
+            "line": 9,
+            "side": "RIGHT",
+            "body": "**[P3] Typo in log message** (low)\n\n\"recieved\" should be \"received\".",
+        })
+    );
+}
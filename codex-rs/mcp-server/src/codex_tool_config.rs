@@ -136,7 +136,7 @@ pub(crate) fn create_tool_for_codex_tool_call_param() -> Tool {
     }
 }
 
-fn codex_tool_output_schema() -> ToolOutputSchema {
+pub(crate) fn codex_tool_output_schema() -> ToolOutputSchema {
     ToolOutputSchema {
         properties: Some(serde_json::json!({
             "threadId": { "type": "string" },
@@ -236,6 +236,11 @@ async fn run_codex_tool_session_inner(
                         reason: _,
                         proposed_execpolicy_amendment: _,
                         parsed_cmd,
+                        explanation: _,
+                        sandbox_policy: _,
+                        risk: _,
+                        matched_rule: _,
+                        sandbox: _,
                     }) => {
                         handle_exec_approval_request(
                             command,
@@ -275,6 +280,10 @@ async fn run_codex_tool_session_inner(
                         reason,
                         grant_root,
                         changes,
+                        files: _,
+                        sandbox_policy: _,
+                        risk: _,
+                        preview: _,
                     }) => {
                         handle_patch_approval_request(
                             call_id,
@@ -291,7 +300,9 @@ async fn run_codex_tool_session_inner(
                         .await;
                         continue;
                     }
-                    EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message }) => {
+                    EventMsg::TurnComplete(TurnCompleteEvent {
+                        last_agent_message, ..
+                    }) => {
                         let text = match last_agent_message {
                             Some(msg) => msg,
                             None => "".to_string(),
@@ -314,7 +325,10 @@ async fn run_codex_tool_session_inner(
                     EventMsg::AgentReasoningDelta(_) => {
                         // TODO: think how we want to support this in the MCP
                     }
-                    EventMsg::McpStartupUpdate(_) | EventMsg::McpStartupComplete(_) => {
+                    EventMsg::McpStartupUpdate(_)
+                    | EventMsg::McpStartupComplete(_)
+                    | EventMsg::McpServerHealth(_)
+                    | EventMsg::McpToolsChanged(_) => {
                         // Ignored in MCP tool runner.
                     }
                     EventMsg::AgentMessage(AgentMessageEvent { .. }) => {
@@ -324,13 +338,50 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::AgentReasoningRawContentDelta(_)
                     | EventMsg::TurnStarted(_)
                     | EventMsg::TokenCount(_)
+                    | EventMsg::CostUpdate(_)
+                    | EventMsg::ProviderRateLimit(_)
                     | EventMsg::AgentReasoning(_)
                     | EventMsg::AgentReasoningSectionBreak(_)
                     | EventMsg::McpToolCallBegin(_)
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::McpListToolsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
+                    | EventMsg::ListMcpPromptsResponse(_)
                     | EventMsg::ListSkillsResponse(_)
+                    | EventMsg::ApprovalJournalResponse(_)
+                    | EventMsg::ListSubAgentsResponse(_)
+                    | EventMsg::SubAgentsUpdated(_)
+                    | EventMsg::ConfigReloaded(_)
+                    | EventMsg::SubAgentRunBegin(_)
+                    | EventMsg::SubAgentRunEnd(_)
+                    | EventMsg::SubAgentPreview(_)
+                    | EventMsg::SubAgentMergePlan(_)
+                    | EventMsg::SubAgentMergeApplied(_)
+                    | EventMsg::BackgroundTaskBegin(_)
+                    | EventMsg::BackgroundTaskEnd(_)
+                    | EventMsg::ListBackgroundTasksResponse(_)
+                    | EventMsg::BackgroundProcessesUpdated(_)
+                    | EventMsg::QueuedUserInputs(_)
+                    | EventMsg::HookOutcome(_)
+                    | EventMsg::TurnAnnotations(_)
+                    | EventMsg::NetworkActivity(_)
+                    | EventMsg::PlanApprovalRequest(_)
+                    | EventMsg::McpToolCallApprovalRequest(_)
+                    | EventMsg::PlanModeUpdated(_)
+                    | EventMsg::ConversationForked(_)
+                    | EventMsg::CheckpointCreated(_)
+                    | EventMsg::ListCheckpointsResponse(_)
+                    | EventMsg::CheckpointRestored(_)
+                    | EventMsg::WorktreeCreated(_)
+                    | EventMsg::WorktreeReview(_)
+                    | EventMsg::WorktreeFinished(_)
+                    | EventMsg::PinnedContextUpdated(_)
+                    | EventMsg::ListPinnedContextResponse(_)
+                    | EventMsg::ContextInspection(_)
+                    | EventMsg::ApprovalTimedOut(_)
+                    | EventMsg::ListMemoryResponse(_)
+                    | EventMsg::NextEditSuggestion(_)
+                    | EventMsg::SandboxDenied(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::TerminalInteraction(_)
                     | EventMsg::ExecCommandOutputDelta(_)
@@ -339,11 +390,18 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::StreamError(_)
                     | EventMsg::PatchApplyBegin(_)
                     | EventMsg::PatchApplyEnd(_)
+                    | EventMsg::PatchHunkReport(_)
+                    | EventMsg::PatchPreview(_)
+                    | EventMsg::ExternalFilesChanged(_)
+                    | EventMsg::WorkspaceSnapshot(_)
+                    | EventMsg::ResumeBriefing(_)
                     | EventMsg::TurnDiff(_)
                     | EventMsg::WebSearchBegin(_)
                     | EventMsg::WebSearchEnd(_)
+                    | EventMsg::HostedToolCall(_)
                     | EventMsg::GetHistoryEntryResponse(_)
                     | EventMsg::PlanUpdate(_)
+                    | EventMsg::FollowUpSuggestions(_)
                     | EventMsg::TurnAborted(_)
                     | EventMsg::UserMessage(_)
                     | EventMsg::ShutdownComplete
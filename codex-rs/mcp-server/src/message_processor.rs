@@ -7,6 +7,9 @@ use crate::codex_tool_config::create_tool_for_codex_tool_call_param;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_reply_param;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::subagent_tool::SubAgentToolCallParam;
+use crate::subagent_tool::create_tools_for_subagents;
+use crate::subagent_tool::subagent_name_for_tool;
 use codex_protocol::ThreadId;
 use codex_protocol::protocol::SessionSource;
 
@@ -40,6 +43,7 @@ pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     initialized: bool,
     codex_linux_sandbox_exe: Option<PathBuf>,
+    config: Arc<Config>,
     thread_manager: Arc<ThreadManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ThreadId>>>,
 }
@@ -67,6 +71,7 @@ impl MessageProcessor {
             outgoing,
             initialized: false,
             codex_linux_sandbox_exe,
+            config,
             thread_manager,
             running_requests_id_to_codex_uuid: Arc::new(Mutex::new(HashMap::new())),
         }
@@ -305,11 +310,13 @@ impl MessageProcessor {
         params: <mcp_types::ListToolsRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::trace!("tools/list -> {params:?}");
+        let mut tools = vec![
+            create_tool_for_codex_tool_call_param(),
+            create_tool_for_codex_tool_call_reply_param(),
+        ];
+        tools.extend(create_tools_for_subagents(&self.config));
         let result = ListToolsResult {
-            tools: vec![
-                create_tool_for_codex_tool_call_param(),
-                create_tool_for_codex_tool_call_reply_param(),
-            ],
+            tools,
             next_cursor: None,
         };
 
@@ -325,6 +332,11 @@ impl MessageProcessor {
         tracing::info!("tools/call -> params: {:?}", params);
         let CallToolRequestParams { name, arguments } = params;
 
+        if let Some(subagent) = subagent_name_for_tool(&name) {
+            self.handle_tool_call_subagent(id, subagent, arguments)
+                .await;
+            return;
+        }
         match name.as_str() {
             "codex" => self.handle_tool_call_codex(id, arguments).await,
             "codex-reply" => {
@@ -425,6 +437,75 @@ impl MessageProcessor {
         });
     }
 
+    async fn handle_tool_call_subagent(
+        &self,
+        id: RequestId,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+    ) {
+        let param = match arguments.map(serde_json::from_value::<SubAgentToolCallParam>) {
+            Some(Ok(param)) => param,
+            Some(Err(e)) => {
+                self.send_tool_call_error(
+                    id,
+                    format!("Failed to parse arguments for subagent `{name}`: {e}"),
+                )
+                .await;
+                return;
+            }
+            None => {
+                self.send_tool_call_error(
+                    id,
+                    format!(
+                        "Missing arguments for subagent `{name}` tool-call; the `prompt` field is required."
+                    ),
+                )
+                .await;
+                return;
+            }
+        };
+        let (prompt, config) = match param
+            .into_config(name, self.codex_linux_sandbox_exe.clone())
+            .await
+        {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                self.send_tool_call_error(id, format!("Failed to start subagent `{name}`: {e}"))
+                    .await;
+                return;
+            }
+        };
+
+        let outgoing = self.outgoing.clone();
+        let thread_manager = self.thread_manager.clone();
+        let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
+        task::spawn(async move {
+            crate::codex_tool_runner::run_codex_tool_session(
+                id,
+                prompt,
+                config,
+                outgoing,
+                thread_manager,
+                running_requests_id_to_codex_uuid,
+            )
+            .await;
+        });
+    }
+
+    async fn send_tool_call_error(&self, id: RequestId, text: String) {
+        let result = CallToolResult {
+            content: vec![ContentBlock::TextContent(TextContent {
+                r#type: "text".to_string(),
+                text,
+                annotations: None,
+            })],
+            is_error: Some(true),
+            structured_content: None,
+        };
+        self.send_response::<mcp_types::CallToolRequest>(id, result)
+            .await;
+    }
+
     async fn handle_tool_call_codex_session_reply(
         &self,
         request_id: RequestId,
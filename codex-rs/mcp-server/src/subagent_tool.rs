@@ -0,0 +1,197 @@
+//! Publishes subagent definitions as MCP tools so other clients can run them
+//! directly.
+
+use codex_core::config::Config;
+use codex_core::subagents::SubAgentDefinition;
+use codex_core::subagents::apply_definition;
+use codex_core::subagents::load_subagents;
+use mcp_types::Tool;
+use mcp_types::ToolInputSchema;
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaSettings;
+use serde::Deserialize;
+use serde::Serialize;
+use std::io;
+use std::path::PathBuf;
+
+use crate::codex_tool_config::CodexToolCallParam;
+use crate::codex_tool_config::codex_tool_output_schema;
+
+/// Tool names for subagents are `subagent-<name>`, which keeps them apart
+/// from `codex` and `codex-reply`.
+pub(crate) const SUBAGENT_TOOL_PREFIX: &str = "subagent-";
+
+/// Client-supplied arguments for a `subagent-<name>` tool-call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub struct SubAgentToolCallParam {
+    /// The task to hand to the subagent.
+    pub prompt: String,
+
+    /// Working directory for the session. If relative, it is resolved against
+    /// the server process's current working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+}
+
+impl SubAgentToolCallParam {
+    /// Loads the configuration the `codex` tool would use, then layers the
+    /// `name` subagent's definition on top of it.
+    pub async fn into_config(
+        self,
+        name: &str,
+        codex_linux_sandbox_exe: Option<PathBuf>,
+    ) -> io::Result<(String, Config)> {
+        let (prompt, mut config) = CodexToolCallParam {
+            prompt: self.prompt,
+            cwd: self.cwd,
+            ..Default::default()
+        }
+        .into_config(codex_linux_sandbox_exe)
+        .await?;
+
+        let outcome = load_subagents(&config.cwd, &config.codex_home, &config.subagent_extra_dirs);
+        let definition = outcome.find(name).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("unknown subagent `{name}`"),
+            )
+        })?;
+        apply_definition(&mut config, definition).map_err(io::Error::other)?;
+        Ok((prompt, config))
+    }
+}
+
+/// Returns the subagent name for a `subagent-<name>` tool, if `tool_name` is
+/// one.
+pub(crate) fn subagent_name_for_tool(tool_name: &str) -> Option<&str> {
+    tool_name
+        .strip_prefix(SUBAGENT_TOOL_PREFIX)
+        .filter(|name| !name.is_empty())
+}
+
+/// Builds the `Tool` definitions for every subagent visible from `config`.
+pub(crate) fn create_tools_for_subagents(config: &Config) -> Vec<Tool> {
+    let outcome = load_subagents(&config.cwd, &config.codex_home, &config.subagent_extra_dirs);
+    for error in &outcome.errors {
+        tracing::warn!(
+            "failed to load subagent {}: {}",
+            error.path.display(),
+            error.message
+        );
+    }
+    outcome
+        .subagents
+        .iter()
+        .map(create_tool_for_subagent)
+        .collect()
+}
+
+/// Builds a `Tool` definition for a single subagent.
+pub(crate) fn create_tool_for_subagent(definition: &SubAgentDefinition) -> Tool {
+    let schema = SchemaSettings::draft2019_09()
+        .with(|s| {
+            s.inline_subschemas = true;
+            s.option_add_null_type = false;
+        })
+        .into_generator()
+        .into_root_schema_for::<SubAgentToolCallParam>();
+
+    #[expect(clippy::expect_used)]
+    let schema_value =
+        serde_json::to_value(&schema).expect("subagent tool schema should serialise to JSON");
+
+    let tool_input_schema =
+        serde_json::from_value::<ToolInputSchema>(schema_value).unwrap_or_else(|e| {
+            panic!("failed to create Tool from schema: {e}");
+        });
+
+    let description = if definition.description.is_empty() {
+        format!("Run the `{}` subagent.", definition.name)
+    } else {
+        definition.description.clone()
+    };
+
+    Tool {
+        name: format!("{SUBAGENT_TOOL_PREFIX}{}", definition.name),
+        title: Some(definition.name.clone()),
+        input_schema: tool_input_schema,
+        output_schema: Some(codex_tool_output_schema()),
+        description: Some(description),
+        annotations: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::subagents::SubAgentSource;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn verify_subagent_tool_json_schema() {
+        let definition = SubAgentDefinition {
+            name: "reviewer".to_string(),
+            description: "Reviews diffs".to_string(),
+            color: None,
+            sandbox_mode: None,
+            model: None,
+            model_provider: None,
+            env_allowed: None,
+            env_set: HashMap::new(),
+            instructions: "Only review.".to_string(),
+            path: PathBuf::from("/repo/.codex/agents/reviewer.md"),
+            source: SubAgentSource::Repo,
+        };
+        let tool = create_tool_for_subagent(&definition);
+        let tool_json = serde_json::to_value(&tool).expect("tool serializes");
+        let expected_tool_json = serde_json::json!({
+          "description": "Reviews diffs",
+          "inputSchema": {
+            "properties": {
+              "cwd": {
+                "description": "Working directory for the session. If relative, it is resolved against the server process's current working directory.",
+                "type": "string"
+              },
+              "prompt": {
+                "description": "The task to hand to the subagent.",
+                "type": "string"
+              }
+            },
+            "required": [
+              "prompt"
+            ],
+            "type": "object"
+          },
+          "name": "subagent-reviewer",
+          "outputSchema": {
+            "properties": {
+              "content": {
+                "type": "string"
+              },
+              "threadId": {
+                "type": "string"
+              }
+            },
+            "required": [
+              "threadId",
+              "content"
+            ],
+            "type": "object"
+          },
+          "title": "reviewer"
+        });
+        assert_eq!(expected_tool_json, tool_json);
+    }
+
+    #[test]
+    fn subagent_tool_names_round_trip() {
+        assert_eq!(
+            subagent_name_for_tool("subagent-reviewer"),
+            Some("reviewer")
+        );
+        assert_eq!(subagent_name_for_tool("subagent-"), None);
+        assert_eq!(subagent_name_for_tool("codex"), None);
+    }
+}
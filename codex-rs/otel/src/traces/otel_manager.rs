@@ -147,6 +147,15 @@ impl OtelManager {
         error: Option<&str>,
         duration: Duration,
     ) {
+        let success = if error.is_none() && status.is_none_or(|status| status < 400) {
+            "true"
+        } else {
+            "false"
+        };
+        let status_str = status.map_or_else(|| "none".to_string(), |status| status.to_string());
+        let tags = [("status", status_str.as_str()), ("success", success)];
+        self.counter("codex.api_request", 1, &tags);
+        self.record_duration("codex.api_request.duration_ms", duration, &tags);
         tracing::event!(
             tracing::Level::INFO,
             event.name = "codex.api_request",
@@ -299,6 +308,16 @@ impl OtelManager {
         reasoning_token_count: Option<i64>,
         tool_token_count: i64,
     ) {
+        for (kind, count) in [
+            ("input", Some(input_token_count)),
+            ("output", Some(output_token_count)),
+            ("cached_input", cached_token_count),
+            ("reasoning", reasoning_token_count),
+        ] {
+            if let Some(count) = count.filter(|count| *count > 0) {
+                self.counter("codex.tokens", count, &[("type", kind)]);
+            }
+        }
         tracing::event!(
             tracing::Level::INFO,
             event.name = "codex.sse_event",
@@ -486,6 +505,7 @@ impl OtelManager {
             }
             ResponseEvent::ServerReasoningIncluded(_) => "server_reasoning_included".into(),
             ResponseEvent::RateLimits(_) => "rate_limits".into(),
+            ResponseEvent::ProviderQuota(_) => "provider_quota".into(),
             ResponseEvent::ModelsEtag(_) => "models_etag".into(),
         }
     }
@@ -500,6 +520,8 @@ impl OtelManager {
             ResponseItem::CustomToolCall { .. } => "custom_tool_call".into(),
             ResponseItem::CustomToolCallOutput { .. } => "custom_tool_call_output".into(),
             ResponseItem::WebSearchCall { .. } => "web_search_call".into(),
+            ResponseItem::CodeInterpreterCall { .. } => "code_interpreter_call".into(),
+            ResponseItem::FileSearchCall { .. } => "file_search_call".into(),
             ResponseItem::GhostSnapshot { .. } => "ghost_snapshot".into(),
             ResponseItem::Compaction { .. } => "compaction".into(),
             ResponseItem::Other => "other".into(),
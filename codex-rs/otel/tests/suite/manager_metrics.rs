@@ -11,6 +11,7 @@ use opentelemetry_sdk::metrics::data::AggregatedMetrics;
 use opentelemetry_sdk::metrics::data::MetricData;
 use pretty_assertions::assert_eq;
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 // Ensures OtelManager attaches metadata tags when forwarding metrics.
 #[test]
@@ -102,3 +103,73 @@ fn manager_allows_disabling_metadata_tags() -> Result<()> {
 
     Ok(())
 }
+
+// Ensures API requests and token usage are exported as metrics.
+#[test]
+fn manager_records_api_request_and_token_metrics() -> Result<()> {
+    let (metrics, exporter) = build_metrics_with_defaults(&[])?;
+    let manager = OtelManager::new(
+        ThreadId::new(),
+        "gpt-5.1",
+        "gpt-5.1",
+        Some("account-id".to_string()),
+        None,
+        Some(AuthMode::ApiKey),
+        true,
+        "tty".to_string(),
+        SessionSource::Cli,
+    )
+    .with_metrics_without_metadata_tags(metrics);
+
+    manager.record_api_request(1, Some(200), None, Duration::from_millis(5));
+    manager.sse_event_completed(10, 5, Some(2), None, 0);
+    manager.shutdown_metrics()?;
+
+    let resource_metrics = latest_metrics(&exporter);
+    let metric =
+        find_metric(&resource_metrics, "codex.api_request").expect("api request metric missing");
+    let attrs = match metric.data() {
+        AggregatedMetrics::U64(data) => match data {
+            MetricData::Sum(sum) => {
+                let points: Vec<_> = sum.data_points().collect();
+                assert_eq!(points.len(), 1);
+                attributes_to_map(points[0].attributes())
+            }
+            _ => panic!("unexpected counter aggregation"),
+        },
+        _ => panic!("unexpected counter data type"),
+    };
+    let expected = BTreeMap::from([
+        ("status".to_string(), "200".to_string()),
+        ("success".to_string(), "true".to_string()),
+    ]);
+    assert_eq!(attrs, expected);
+
+    let metric = find_metric(&resource_metrics, "codex.tokens").expect("token metric missing");
+    let mut totals = match metric.data() {
+        AggregatedMetrics::U64(data) => match data {
+            MetricData::Sum(sum) => sum
+                .data_points()
+                .map(|point| {
+                    let kind = attributes_to_map(point.attributes())
+                        .remove("type")
+                        .expect("type tag");
+                    (kind, point.value())
+                })
+                .collect::<Vec<_>>(),
+            _ => panic!("unexpected counter aggregation"),
+        },
+        _ => panic!("unexpected counter data type"),
+    };
+    totals.sort();
+    assert_eq!(
+        totals,
+        vec![
+            ("cached_input".to_string(), 2),
+            ("input".to_string(), 10),
+            ("output".to_string(), 5),
+        ]
+    );
+
+    Ok(())
+}
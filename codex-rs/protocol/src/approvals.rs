@@ -2,7 +2,15 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::parse_command::ParsedCommand;
+use crate::plan_tool::ProposedPlan;
+use crate::protocol::AskForApproval;
 use crate::protocol::FileChange;
+use crate::protocol::FileDiff;
+use crate::protocol::McpInvocation;
+use crate::protocol::PatchPreview;
+use crate::protocol::ReviewDecision;
+use crate::protocol::SandboxCapabilities;
+use crate::protocol::SandboxPolicy;
 use mcp_types::RequestId;
 use schemars::JsonSchema;
 use serde::Deserialize;
@@ -57,6 +65,178 @@ pub struct ExecApprovalRequestEvent {
     #[ts(optional)]
     pub proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     pub parsed_cmd: Vec<ParsedCommand>,
+    /// One-line plain-language description of what the command does and
+    /// which paths it touches, when approval explanations are enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub explanation: Option<String>,
+    /// Sandbox policy the command would run under if approved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sandbox_policy: Option<SandboxPolicy>,
+    /// Coarse risk classification of the command, for approval UIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub risk: Option<ApprovalRisk>,
+    /// Most restrictive `[[command_rules]]` entry matching the command, if
+    /// any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub matched_rule: Option<CommandRuleMatch>,
+    /// What the platform sandbox enforces for `sandbox_policy` on this
+    /// machine. Absent when the command would not run sandboxed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sandbox: Option<SandboxCapabilities>,
+}
+
+/// What a `[[command_rules]]` entry does with the commands it matches.
+/// Ordered from least to most restrictive.
+#[derive(
+    Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, JsonSchema, TS,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandRuleDecision {
+    /// Run without asking, still inside the sandbox.
+    AutoApprove,
+    /// Ask the user every time, whatever the approval policy says.
+    AlwaysAsk,
+    /// Refuse to run the command.
+    AutoDeny,
+}
+
+/// A `[[command_rules]]` entry (from config.toml or `policies.toml`) that
+/// matched a command.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct CommandRuleMatch {
+    /// The rule's `name`, or its program and argument patterns when unnamed.
+    pub rule: String,
+    pub decision: CommandRuleDecision,
+    /// File that defines the rule.
+    pub source: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub reason: Option<String>,
+}
+
+/// Coarse risk level attached to approval requests so clients can highlight
+/// the prompts that deserve a closer look.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRisk {
+    /// Read-only or otherwise known-safe.
+    Low,
+    /// Writes inside the workspace or runs an unclassified command.
+    Medium,
+    /// Deletes files, writes outside the workspace, or matches a known
+    /// destructive command.
+    High,
+}
+
+/// Plan submitted through `submit_plan` while plan mode is on. Approving it
+/// unlocks write tools for the rest of the turn.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct PlanApprovalRequestEvent {
+    /// Identifier for the associated `submit_plan` call.
+    pub call_id: String,
+    /// Turn ID that this plan belongs to.
+    pub turn_id: String,
+    pub plan: ProposedPlan,
+}
+
+/// Call to an MCP tool that its server's config marks `always_ask`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct McpToolCallApprovalRequestEvent {
+    /// Identifier for the associated MCP tool call.
+    pub call_id: String,
+    /// Turn ID that this call belongs to.
+    pub turn_id: String,
+    pub invocation: McpInvocation,
+}
+
+/// Kind of approval prompt, used to pick its `[approval_timeouts]` entry.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalKind {
+    Exec,
+    Patch,
+    Plan,
+    McpTool,
+}
+
+/// What to do with an approval prompt that stays unanswered past its timeout.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApprovalTimeoutAction {
+    /// Deny the request; the model is told and may try something else.
+    #[default]
+    Deny,
+    /// Approve commands classified as read-only and deny everything else.
+    AllowReadOnly,
+    /// Deny the request and abort the turn.
+    AbortTurn,
+}
+
+/// An approval prompt was resolved by its `[approval_timeouts]` entry
+/// because nobody answered it in time.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ApprovalTimedOutEvent {
+    pub call_id: String,
+    pub turn_id: String,
+    pub kind: ApprovalKind,
+    pub timeout_secs: u64,
+    pub action: ApprovalTimeoutAction,
+    /// Decision applied on the user's behalf.
+    pub decision: ReviewDecision,
+}
+
+/// Identifies which part of the approval configuration decided whether a tool
+/// call could run.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type")]
+pub enum ApprovalSource {
+    /// An execpolicy `prefix_rule` from the `.rules` files matched the command.
+    PolicyRule {
+        matched_prefix: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        justification: Option<String>,
+    },
+    /// A `[[command_rules]]` entry auto-approved the command.
+    CommandRule { rule: CommandRuleMatch },
+    /// The command is on the built-in list of known read-only commands.
+    KnownSafeCommand,
+    /// The `read_only_auto_approve` feature classified the command as
+    /// read-only (built-in list or `read_only_commands` config). Such commands
+    /// still run inside the sandbox even when escalation was requested.
+    ReadOnlyCommand,
+    /// An identical request was approved earlier "for this session".
+    SessionAllowlist,
+    /// The active approval policy and sandbox mode allow it without asking.
+    ModeDefault { approval_policy: AskForApproval },
+    /// The user answered an approval prompt.
+    User { decision: ReviewDecision },
+    /// The MCP server's `default_tool_approval` / `tool_approvals` config
+    /// blocks the tool.
+    McpToolBlocked,
+}
+
+/// A single approval decision recorded for a tool call in this session.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ApprovalJournalEntry {
+    pub call_id: String,
+    pub turn_id: String,
+    /// Name of the tool that requested approval (e.g. `shell`, `apply_patch`).
+    pub tool_name: String,
+    /// Human-readable summary of the request: the command line for exec
+    /// tools, the touched paths for patches.
+    pub subject: String,
+    pub source: ApprovalSource,
+    /// Whether the tool call was allowed to run.
+    pub approved: bool,
+    /// RFC 3339 timestamp of the decision.
+    pub timestamp: String,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -92,4 +272,20 @@ pub struct ApplyPatchApprovalRequestEvent {
     /// When set, the agent is asking the user to allow writes under this root for the remainder of the session.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub grant_root: Option<PathBuf>,
+    /// Structured view of `changes`, one entry per file, sorted by path.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileDiff>,
+    /// Sandbox policy the patch would be applied under if approved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sandbox_policy: Option<SandboxPolicy>,
+    /// Coarse risk classification of the patch, for approval UIs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub risk: Option<ApprovalRisk>,
+    /// Dry run of the patch against the working tree (`patch_preview`
+    /// feature).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub preview: Option<PatchPreview>,
 }
@@ -1,3 +1,4 @@
+use crate::config_types::ModeKind;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
@@ -10,6 +11,11 @@ use ts_rs::TS;
 /// - Full slash prefix: `"/{PROMPTS_CMD_PREFIX}:"`
 pub const PROMPTS_CMD_PREFIX: &str = "prompts";
 
+/// Namespace for MCP server prompts: `/{MCP_PROMPTS_CMD_PREFIX}:<server>:<prompt>`.
+/// Unlike custom prompts, these are expanded by the session, which fetches
+/// the prompt from the server with the `KEY=value` arguments that follow.
+pub const MCP_PROMPTS_CMD_PREFIX: &str = "mcp";
+
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema, TS)]
 pub struct CustomPrompt {
     pub name: String,
@@ -18,3 +24,33 @@ pub struct CustomPrompt {
     pub description: Option<String>,
     pub argument_hint: Option<String>,
 }
+
+/// Search root a custom slash command was discovered under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum CustomCommandSource {
+    /// `<git root>/.codex/commands`
+    Repo,
+    /// `$CODEX_HOME/commands`
+    User,
+}
+
+/// A slash command defined by `<name>.md` in `.codex/commands/` or
+/// `$CODEX_HOME/commands/`, invoked as `/<name> [ARGS]`. Like MCP prompts,
+/// it is sent as plain text and expanded by the session.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+pub struct CustomCommand {
+    pub name: String,
+    pub path: PathBuf,
+    /// Body of the file; `$ARGUMENTS` is replaced with the command's arguments.
+    pub content: String,
+    pub description: Option<String>,
+    pub argument_hint: Option<String>,
+    /// Model the command's turn runs with, from the `model` frontmatter key.
+    pub model: Option<String>,
+    /// Collaboration mode the command's turn runs in, from the `mode`
+    /// frontmatter key.
+    pub mode: Option<ModeKind>,
+    pub source: CustomCommandSource,
+}
@@ -13,4 +13,5 @@ pub mod parse_command;
 pub mod plan_tool;
 pub mod protocol;
 pub mod request_user_input;
+pub mod subagents;
 pub mod user_input;
@@ -159,6 +159,38 @@ pub enum ResponseItem {
         status: Option<String>,
         action: WebSearchAction,
     },
+    // Emitted by the Responses API when the model runs code with the hosted
+    // `code_interpreter` tool. `outputs` is only populated when the request
+    // includes `code_interpreter_call.outputs`.
+    CodeInterpreterCall {
+        #[serde(default, skip_serializing)]
+        #[ts(skip)]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        status: Option<String>,
+        #[serde(default)]
+        code: Option<String>,
+        #[serde(default)]
+        container_id: String,
+        #[serde(default)]
+        outputs: Option<Vec<CodeInterpreterOutput>>,
+    },
+    // Emitted by the Responses API for the hosted `file_search` tool.
+    // `results` is only populated when the request includes
+    // `file_search_call.results`.
+    FileSearchCall {
+        #[serde(default, skip_serializing)]
+        #[ts(skip)]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        status: Option<String>,
+        #[serde(default)]
+        queries: Vec<String>,
+        #[serde(default)]
+        results: Option<Vec<FileSearchResult>>,
+    },
     // Generated by the harness but considered exactly as a model response.
     GhostSnapshot {
         ghost_commit: GhostCommit,
@@ -581,6 +613,36 @@ pub enum WebSearchAction {
     Other,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CodeInterpreterOutput {
+    Logs {
+        logs: String,
+    },
+    Image {
+        url: String,
+    },
+
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema, TS)]
+pub struct FileSearchResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub file_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub filename: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub text: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ReasoningItemReasoningSummary {
@@ -617,6 +679,9 @@ impl From<Vec<UserInput>> for ResponseInputItem {
                         local_image_content_items_with_label_number(&path, Some(image_index))
                     }
                     UserInput::Skill { .. } => Vec::new(), // Skill bodies are injected later in core
+                    UserInput::EditorContext { .. } | UserInput::TerminalOutput { .. } => {
+                        Vec::new() // Recorded as its own message in core
+                    }
                 })
                 .collect::<Vec<ContentItem>>(),
         }
@@ -1027,6 +1092,40 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn deserializes_code_interpreter_call_outputs() -> Result<()> {
+        let json = r#"{
+            "id": "ci_1",
+            "type": "code_interpreter_call",
+            "status": "completed",
+            "code": "print(1 + 1)",
+            "container_id": "cntr_1",
+            "outputs": [
+                {"type": "logs", "logs": "2\n"},
+                {"type": "image", "url": "https://example.com/plot.png"}
+            ]
+        }"#;
+
+        let item: ResponseItem = serde_json::from_str(json)?;
+
+        assert_eq!(
+            item,
+            ResponseItem::CodeInterpreterCall {
+                id: Some("ci_1".into()),
+                status: Some("completed".into()),
+                code: Some("print(1 + 1)".into()),
+                container_id: "cntr_1".into(),
+                outputs: Some(vec![
+                    CodeInterpreterOutput::Logs { logs: "2\n".into() },
+                    CodeInterpreterOutput::Image {
+                        url: "https://example.com/plot.png".into(),
+                    },
+                ]),
+            }
+        );
+        Ok(())
+    }
+
     #[test]
     fn roundtrips_web_search_call_actions() -> Result<()> {
         let cases = vec![
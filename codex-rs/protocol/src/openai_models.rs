@@ -170,6 +170,26 @@ const fn default_effective_context_window_percent() -> i64 {
     95
 }
 
+const fn default_supports_tool_calls() -> bool {
+    true
+}
+
+/// Kinds of input a model accepts.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, TS, JsonSchema, EnumIter, Display,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum InputModality {
+    Text,
+    Image,
+}
+
+/// Models are assumed to accept images unless their metadata says otherwise.
+pub fn default_input_modalities() -> Vec<InputModality> {
+    vec![InputModality::Text, InputModality::Image]
+}
+
 /// Model metadata returned by the Codex backend `/models` endpoint.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TS, JsonSchema)]
 pub struct ModelInfo {
@@ -204,9 +224,21 @@ pub struct ModelInfo {
     #[serde(default = "default_effective_context_window_percent")]
     pub effective_context_window_percent: i64,
     pub experimental_supported_tools: Vec<String>,
+    /// Input the model accepts. Image attachments are refused for models
+    /// without [`InputModality::Image`].
+    #[serde(default = "default_input_modalities")]
+    pub input_modalities: Vec<InputModality>,
+    /// Whether the model can be given tools natively. Tools are described in
+    /// the instructions and called through fenced JSON blocks when it cannot.
+    #[serde(default = "default_supports_tool_calls")]
+    pub supports_tool_calls: bool,
 }
 
 impl ModelInfo {
+    pub fn supports_images(&self) -> bool {
+        self.input_modalities.contains(&InputModality::Image)
+    }
+
     pub fn auto_compact_token_limit(&self) -> Option<i64> {
         self.auto_compact_token_limit.or_else(|| {
             self.context_window
@@ -413,6 +445,8 @@ mod tests {
             auto_compact_token_limit: None,
             effective_context_window_percent: 95,
             experimental_supported_tools: vec![],
+            input_modalities: default_input_modalities(),
+            supports_tool_calls: true,
         }
     }
 
@@ -446,4 +480,31 @@ mod tests {
 
         assert_eq!(instructions, "base");
     }
+
+    #[test]
+    fn input_modalities_default_to_text_and_image() {
+        let mut value = serde_json::to_value(test_model(None)).expect("serialize model");
+        value
+            .as_object_mut()
+            .expect("model object")
+            .remove("input_modalities");
+        let model: ModelInfo = serde_json::from_value(value.clone()).expect("deserialize model");
+        assert!(model.supports_images());
+
+        value["input_modalities"] = serde_json::json!(["text"]);
+        let model: ModelInfo = serde_json::from_value(value).expect("deserialize model");
+        assert_eq!(model.input_modalities, vec![InputModality::Text]);
+        assert!(!model.supports_images());
+    }
+
+    #[test]
+    fn tool_calls_are_supported_unless_disabled() {
+        let mut value = serde_json::to_value(test_model(None)).expect("serialize model");
+        value
+            .as_object_mut()
+            .expect("model object")
+            .remove("supports_tool_calls");
+        let model: ModelInfo = serde_json::from_value(value).expect("deserialize model");
+        assert!(model.supports_tool_calls);
+    }
 }
@@ -26,3 +26,29 @@ pub struct UpdatePlanArgs {
     pub explanation: Option<String>,
     pub plan: Vec<PlanItemArg>,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanRisk {
+    Low,
+    Medium,
+    High,
+}
+
+/// Arguments of the `submit_plan` tool used in plan mode. The plan is shown
+/// to the user for approval before write tools are unlocked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(deny_unknown_fields)]
+pub struct ProposedPlan {
+    /// One or two sentences describing the change.
+    pub summary: String,
+    pub steps: Vec<String>,
+    /// Files the plan expects to create, modify, or delete.
+    #[serde(default)]
+    pub affected_files: Vec<String>,
+    pub risk: PlanRisk,
+    /// Why the risk level was chosen (e.g. migrations, public API changes).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub risk_notes: Option<String>,
+}
@@ -16,6 +16,7 @@ use crate::approvals::ElicitationRequestEvent;
 use crate::config_types::CollaborationMode;
 use crate::config_types::Personality;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use crate::custom_prompts::CustomCommand;
 use crate::custom_prompts::CustomPrompt;
 use crate::items::TurnItem;
 use crate::message_history::HistoryEntry;
@@ -27,9 +28,16 @@ use crate::openai_models::ReasoningEffort as ReasoningEffortConfig;
 use crate::parse_command::ParsedCommand;
 use crate::plan_tool::UpdatePlanArgs;
 use crate::request_user_input::RequestUserInputResponse;
+use crate::subagents::SubAgentInfo;
+use crate::subagents::SubAgentLoadError;
+use crate::subagents::SubAgentMergeConflict;
+use crate::subagents::SubAgentMergeResolution;
+use crate::subagents::SubAgentMergeRun;
+use crate::subagents::SubAgentPreview;
 use crate::user_input::UserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use mcp_types::CallToolResult;
+use mcp_types::Prompt as McpPrompt;
 use mcp_types::RequestId;
 use mcp_types::Resource as McpResource;
 use mcp_types::ResourceTemplate as McpResourceTemplate;
@@ -44,9 +52,19 @@ use tracing::error;
 use ts_rs::TS;
 
 pub use crate::approvals::ApplyPatchApprovalRequestEvent;
+pub use crate::approvals::ApprovalJournalEntry;
+pub use crate::approvals::ApprovalKind;
+pub use crate::approvals::ApprovalRisk;
+pub use crate::approvals::ApprovalSource;
+pub use crate::approvals::ApprovalTimedOutEvent;
+pub use crate::approvals::ApprovalTimeoutAction;
+pub use crate::approvals::CommandRuleDecision;
+pub use crate::approvals::CommandRuleMatch;
 pub use crate::approvals::ElicitationAction;
 pub use crate::approvals::ExecApprovalRequestEvent;
 pub use crate::approvals::ExecPolicyAmendment;
+pub use crate::approvals::McpToolCallApprovalRequestEvent;
+pub use crate::approvals::PlanApprovalRequestEvent;
 pub use crate::request_user_input::RequestUserInputEvent;
 
 /// Open/close tags for special user-input blocks. Used across crates to avoid
@@ -60,7 +78,7 @@ pub const COLLABORATION_MODE_CLOSE_TAG: &str = "</collaboration_mode>";
 pub const USER_MESSAGE_BEGIN: &str = "## My request for Codex:";
 
 /// Submission Queue Entry - requests from user
-#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct Submission {
     /// Unique id for this Submission to correlate with Events
     pub id: String,
@@ -69,14 +87,14 @@ pub struct Submission {
 }
 
 /// Config payload for refreshing MCP servers.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
 pub struct McpServerRefreshConfig {
     pub mcp_servers: Value,
     pub mcp_oauth_credentials_store_mode: Value,
 }
 
 /// Submission operation
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[allow(clippy::large_enum_variant)]
 #[non_exhaustive]
@@ -197,6 +215,22 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Approve a plan submitted with `submit_plan` while plan mode is on.
+    PlanApproval {
+        /// The id of the submission we are approving
+        id: String,
+        /// The user's decision in response to the request.
+        decision: ReviewDecision,
+    },
+
+    /// Approve a call to an MCP tool marked `always_ask`.
+    McpToolCallApproval {
+        /// The id of the submission we are approving
+        id: String,
+        /// The user's decision in response to the request.
+        decision: ReviewDecision,
+    },
+
     /// Resolve an MCP elicitation request.
     ResolveElicitation {
         /// Name of the MCP server that issued the request.
@@ -235,9 +269,23 @@ pub enum Op {
     /// Request MCP servers to reinitialize and refresh cached tool lists.
     RefreshMcpServers { config: McpServerRefreshConfig },
 
+    /// Start an MCP server for the rest of the session, replacing a running
+    /// server with the same name. `config` uses the `[mcp_servers.<name>]`
+    /// shape from config.toml. Other servers keep running.
+    /// Reply is delivered via `EventMsg::McpToolsChanged`.
+    AddMcpServer { name: String, config: Value },
+
+    /// Stop a running MCP server and drop its tools for the rest of the
+    /// session. Reply is delivered via `EventMsg::McpToolsChanged`.
+    RemoveMcpServer { name: String },
+
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
+    /// Request the prompts exposed by the running MCP servers.
+    /// Reply is delivered via `EventMsg::ListMcpPromptsResponse`.
+    ListMcpPrompts,
+
     /// Request the list of skills for the provided `cwd` values or the session default.
     ListSkills {
         /// Working directories to scope repo skills discovery.
@@ -256,15 +304,58 @@ pub enum Op {
     /// to generate a summary which will be returned as an AgentMessage event.
     Compact,
 
+    /// Summarize user turns `from_turn..=to_turn` (1-based, oldest first)
+    /// into a single summary message, keeping earlier and later turns
+    /// verbatim. Turns after the range are renumbered accordingly.
+    CompactRange { from_turn: u32, to_turn: u32 },
+
     /// Request Codex to undo a turn (turn are stacked so it is the same effect as CMD + Z).
     Undo,
 
+    /// Restore the working tree as it was before the most recent `Op::Undo`.
+    /// Redo is only possible until the next turn changes the working tree.
+    Redo,
+
+    /// Revert a single file to its state before a turn, keeping every other
+    /// change from that turn. Files the turn created are deleted.
+    RestoreFile {
+        /// Turn whose starting snapshot to restore from; the most recent
+        /// snapshot when omitted.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
+        /// File to restore, absolute or relative to the session cwd.
+        path: PathBuf,
+    },
+
+    /// Revert some hunks of a turn's changes to one file, keeping the rest.
+    /// `hunks` are ranges from that turn's [`TurnDiffEvent`]: each hunk's new
+    /// lines in the file are replaced with its old lines from the snapshot
+    /// taken at the start of the turn.
+    RevertHunks {
+        /// Turn whose starting snapshot holds the old lines; the most recent
+        /// snapshot when omitted.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
+        /// File to revert, absolute or relative to the session cwd.
+        path: PathBuf,
+        hunks: Vec<DiffHunk>,
+    },
+
     /// Request Codex to drop the last N user turns from in-memory context.
     ///
     /// This does not attempt to revert local filesystem changes. Clients are
     /// responsible for undoing any edits on disk.
     ThreadRollback { num_turns: u32 },
 
+    /// Fork this thread into a new thread that starts from a copy of its
+    /// history, leaving this thread untouched. Keeps the first `num_turns`
+    /// user turns, or the whole history when `None`.
+    /// The server replies with [`EventMsg::ConversationForked`].
+    ForkConversation {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        num_turns: Option<u32>,
+    },
+
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
@@ -283,6 +374,176 @@ pub enum Op {
 
     /// Request the list of available models.
     ListModels,
+
+    /// Request the approval journal for this session (oldest entry first).
+    /// Reply is delivered via `EventMsg::ApprovalJournalResponse`.
+    ListApprovalJournal {
+        /// Return at most this many of the most recent entries.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<usize>,
+    },
+
+    /// Request the subagent definitions discovered under `<git root>/.codex/agents`
+    /// and `$CODEX_HOME/agents`. Reply is delivered via `EventMsg::ListSubAgentsResponse`.
+    ListSubAgents,
+
+    /// Run the named subagent in a child thread with the given prompt.
+    /// Progress is reported via `EventMsg::SubAgentRunBegin` / `EventMsg::SubAgentRunEnd`.
+    /// With `dry_run`, nothing is spawned and the resolved invocation is
+    /// reported via `EventMsg::SubAgentPreview` instead.
+    RunSubAgent {
+        name: String,
+        prompt: String,
+        #[serde(default)]
+        dry_run: bool,
+    },
+
+    /// Cancel a running subagent started with `Op::RunSubAgent`.
+    CancelSubAgent { run_id: String },
+
+    /// Combine the changes staged by subagent runs (`subagents.stage_changes`)
+    /// into one merge. When `run_ids` is empty every staged run takes part.
+    /// Edits that do not overlap are merged automatically and overlapping ones
+    /// are reported as conflicts; nothing is applied yet. The plan is
+    /// delivered via `EventMsg::SubAgentMergePlan`.
+    MergeSubAgentChanges {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        run_ids: Vec<String>,
+    },
+
+    /// Apply a merge planned by `Op::MergeSubAgentChanges` to the working
+    /// tree, settling every conflict with one of `resolutions`. The result is
+    /// reported via `EventMsg::SubAgentMergeApplied`.
+    ApplySubAgentMerge {
+        merge_id: String,
+        #[serde(default)]
+        resolutions: Vec<SubAgentMergeResolution>,
+    },
+
+    /// Run `prompt` in a detached child thread that keeps working while the
+    /// user continues the conversation. With `subagent`, the named definition
+    /// is applied to the child first. Progress is reported via
+    /// `EventMsg::BackgroundTaskBegin` / `EventMsg::BackgroundTaskEnd`; the
+    /// child thread's own events stream under the task id.
+    StartBackgroundTask {
+        prompt: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        subagent: Option<String>,
+    },
+
+    /// Request the background tasks started in this session that have not
+    /// finished yet. Reply is delivered via `EventMsg::ListBackgroundTasksResponse`.
+    ListBackgroundTasks,
+
+    /// Cancel a background task started with `Op::StartBackgroundTask`.
+    CancelBackgroundTask { task_id: String },
+
+    /// Queue user input to run as its own turn once the current turn
+    /// completes. When no turn is running the input starts one immediately,
+    /// like [`Op::UserInput`]. Queue changes are reported via
+    /// `EventMsg::QueuedUserInputs`.
+    QueueUserInput {
+        /// User input items, see `InputItem`
+        items: Vec<UserInput>,
+    },
+
+    /// Request the inputs still waiting in the queue. Reply is delivered via
+    /// `EventMsg::QueuedUserInputs`.
+    ListQueuedUserInputs,
+
+    /// Drop every input waiting in the queue without running it.
+    ClearQueuedUserInputs,
+
+    /// Turn plan mode on or off. While it is on, the agent must get a plan
+    /// approved via `submit_plan` before it may run commands or apply patches
+    /// in a turn. Acknowledged with `EventMsg::PlanModeUpdated`.
+    SetPlanMode { enabled: bool },
+
+    /// Snapshot the working tree as a named checkpoint that can be restored
+    /// later with `Op::RestoreCheckpoint`. Acknowledged with
+    /// `EventMsg::CheckpointCreated`.
+    CreateCheckpoint { name: String },
+
+    /// Request the named checkpoints of this session. Reply is delivered via
+    /// `EventMsg::ListCheckpointsResponse`.
+    ListCheckpoints,
+
+    /// Restore the working tree to a checkpoint created with
+    /// `Op::CreateCheckpoint`. Checkpoints created after it are discarded.
+    /// Reports the outcome via `EventMsg::CheckpointRestored`.
+    RestoreCheckpoint { id: String },
+
+    /// Request the changes made so far in the worktree the session runs in
+    /// (`[worktree_isolation]`). Reply is delivered via
+    /// `EventMsg::WorktreeReview`.
+    ReviewWorktree,
+
+    /// Merge the changes of the isolated worktree into the checkout, or
+    /// discard them, then remove the worktree and its branch. Reports the
+    /// outcome via `EventMsg::WorktreeFinished`.
+    FinishWorktree { action: WorktreeFinishAction },
+
+    /// Pin context that is sent with every model request until unpinned, so
+    /// it survives compaction. Acknowledged with
+    /// `EventMsg::PinnedContextUpdated`.
+    PinContext { content: PinnedContent },
+
+    /// Remove a pinned item by id. Acknowledged with
+    /// `EventMsg::PinnedContextUpdated`.
+    UnpinContext { id: String },
+
+    /// Request the pinned items of this session. Reply is delivered via
+    /// `EventMsg::ListPinnedContextResponse`.
+    ListPinnedContext,
+
+    /// Request a breakdown of what the next model request would carry, with
+    /// estimated token counts per item. Reply is delivered via
+    /// `EventMsg::ContextInspection`.
+    InspectContext,
+
+    /// Remove one item reported by `Op::InspectContext` from the context sent
+    /// to the model: a turn, a tool output (replaced by a short placeholder),
+    /// a pinned item, or an instruction message. Not allowed while a turn is
+    /// running. Replies with a fresh `EventMsg::ContextInspection`.
+    DropContextItem { id: String },
+
+    /// Request the entries of the project memory file. Reply is delivered via
+    /// `EventMsg::ListMemoryResponse`.
+    ListMemory,
+
+    /// Replace the text of a project memory entry. Acknowledged with
+    /// `EventMsg::ListMemoryResponse`.
+    UpdateMemory { id: String, text: String },
+
+    /// Delete a project memory entry. Acknowledged with
+    /// `EventMsg::ListMemoryResponse`.
+    DeleteMemory { id: String },
+
+    /// Send input typed by the user to a running `exec_command` process that
+    /// was started with `tty: true`. The input is written as-is (include `\n`
+    /// to submit a line) and is not shown to the model; the model sees its
+    /// effect through the process output.
+    TerminalInput { process_id: String, input: String },
+
+    /// Size of the client's terminal. New PTY processes open at this size and
+    /// running ones are resized to match.
+    SetTerminalSize { rows: u16, cols: u16 },
+
+    /// Ask a side model for the text the user is about to type at the cursor
+    /// of `path`, given the recent messages of this session. The history is
+    /// not changed. Reply is delivered via `EventMsg::NextEditSuggestion`
+    /// with the same `request_id`.
+    SuggestNextEdit {
+        request_id: String,
+        path: PathBuf,
+        /// File contents before the cursor.
+        prefix: String,
+        /// File contents after the cursor.
+        suffix: String,
+        /// Model to ask instead of the session's model, typically a fast one.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+    },
 }
 
 /// Determines the conditions under which the user is consulted to approve
@@ -393,6 +654,40 @@ pub enum SandboxPolicy {
     },
 }
 
+/// Platform mechanism that enforces the sandbox policy for spawned commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum SandboxBackend {
+    /// Commands run without a platform sandbox.
+    None,
+    /// `sandbox-exec` with a generated Seatbelt profile.
+    MacosSeatbelt,
+    /// `codex-linux-sandbox` (Landlock + seccomp).
+    LinuxLandlock,
+    /// Restricted token with capability SIDs, inside a kill-on-close job
+    /// object.
+    WindowsRestrictedToken,
+    /// Restricted token run as a dedicated sandbox user, with firewall rules
+    /// blocking network access.
+    WindowsElevated,
+    /// A Docker or Podman container (`sandbox.backend = "container"`).
+    Container,
+}
+
+/// What the platform sandbox on this machine can actually enforce.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct SandboxCapabilities {
+    pub backend: SandboxBackend,
+    /// Writes outside the policy's writable roots are blocked.
+    pub enforces_writable_roots: bool,
+    /// Outbound network is blocked when the policy disables it.
+    pub enforces_network: bool,
+    /// Gaps in enforcement and how to close them, for display.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
 /// A writable root path accompanied by a list of subpaths that should remain
 /// read‑only even when the root is writable. This is primarily used to ensure
 /// that folders containing files that could be modified to escalate the
@@ -678,6 +973,9 @@ pub enum EventMsg {
     /// Conversation history was rolled back by dropping the last N user turns.
     ThreadRolledBack(ThreadRolledBackEvent),
 
+    /// A branch of this thread was created in response to `Op::ForkConversation`.
+    ConversationForked(ConversationForkedEvent),
+
     /// Agent has started a turn.
     /// v1 wire format uses `task_started`; accept `turn_started` for v2 interop.
     #[serde(rename = "task_started", alias = "turn_started")]
@@ -692,6 +990,14 @@ pub enum EventMsg {
     /// Optional means unknown — UIs should not display when `None`.
     TokenCount(TokenCountEvent),
 
+    /// Estimated cost of the current turn and the session, sent after each
+    /// model response when `[budget.prices]` has a price for the model.
+    CostUpdate(CostUpdateEvent),
+
+    /// Quota reported by the provider's `x-ratelimit-*` headers, and whether
+    /// the turn is waiting out a rate limit before retrying.
+    ProviderRateLimit(ProviderRateLimitEvent),
+
     /// Agent text output message
     AgentMessage(AgentMessageEvent),
 
@@ -718,12 +1024,24 @@ pub enum EventMsg {
     /// Ack the client's configure message.
     SessionConfigured(SessionConfiguredEvent),
 
+    /// The checked-out git branch changed and `[branches]` overrides were
+    /// re-resolved for the session.
+    ConfigReloaded(ConfigReloadedEvent),
+
     /// Incremental MCP startup progress updates.
     McpStartupUpdate(McpStartupUpdateEvent),
 
     /// Aggregate MCP startup completion summary.
     McpStartupComplete(McpStartupCompleteEvent),
 
+    /// A running MCP server stopped answering health checks, was restarted,
+    /// or was given up on.
+    McpServerHealth(McpServerHealthEvent),
+
+    /// An MCP server was added or removed while the session was running; the
+    /// model sees the new tool list from its next request on.
+    McpToolsChanged(McpToolsChangedEvent),
+
     McpToolCallBegin(McpToolCallBeginEvent),
 
     McpToolCallEnd(McpToolCallEndEvent),
@@ -732,6 +1050,9 @@ pub enum EventMsg {
 
     WebSearchEnd(WebSearchEndEvent),
 
+    /// A provider-hosted tool (code interpreter, file search) finished running.
+    HostedToolCall(HostedToolCallEvent),
+
     /// Notification that the server is about to execute a command.
     ExecCommandBegin(ExecCommandBeginEvent),
 
@@ -743,6 +1064,10 @@ pub enum EventMsg {
 
     ExecCommandEnd(ExecCommandEndEvent),
 
+    /// The sandbox blocked a command or patch the agent ran. Sent right
+    /// after the matching `ExecCommandEnd` / `PatchApplyEnd`.
+    SandboxDenied(SandboxDeniedEvent),
+
     /// Notification that the agent attached a local image via the view_image tool.
     ViewImageToolCall(ViewImageToolCallEvent),
 
@@ -754,6 +1079,16 @@ pub enum EventMsg {
 
     ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent),
 
+    /// The agent submitted a plan for approval (plan mode).
+    PlanApprovalRequest(PlanApprovalRequestEvent),
+
+    /// An MCP tool marked `always_ask` is waiting for approval.
+    McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent),
+
+    /// An approval prompt went unanswered and its configured timeout
+    /// action was applied.
+    ApprovalTimedOut(ApprovalTimedOutEvent),
+
     /// Notification advising the user that something they are using has been
     /// deprecated and should be phased out.
     DeprecationNotice(DeprecationNoticeEvent),
@@ -775,6 +1110,15 @@ pub enum EventMsg {
     /// Notification that a patch application has finished.
     PatchApplyEnd(PatchApplyEndEvent),
 
+    /// Some chunks of an `apply_patch` call did not match their file as
+    /// written; reports how each chunk of those files was placed. Sent
+    /// before `PatchApplyBegin`.
+    PatchHunkReport(PatchHunkReportEvent),
+
+    /// Dry run of an `apply_patch` call against the working tree, sent
+    /// before it is approved or applied (`patch_preview` feature).
+    PatchPreview(PatchPreviewEvent),
+
     TurnDiff(TurnDiffEvent),
 
     /// Response to GetHistoryEntryRequest.
@@ -786,14 +1130,129 @@ pub enum EventMsg {
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// Prompts exposed by the running MCP servers.
+    ListMcpPromptsResponse(ListMcpPromptsResponseEvent),
+
     /// List of skills available to the agent.
     ListSkillsResponse(ListSkillsResponseEvent),
 
+    /// Approval decisions recorded in this session, in response to `Op::ListApprovalJournal`.
+    ApprovalJournalResponse(ApprovalJournalResponseEvent),
+
+    /// List of subagents available to the session.
+    ListSubAgentsResponse(ListSubAgentsResponseEvent),
+
+    /// Subagent definitions changed on disk; carries the reloaded list.
+    SubAgentsUpdated(SubAgentsUpdatedEvent),
+
+    /// A subagent run requested via `Op::RunSubAgent` has started.
+    SubAgentRunBegin(SubAgentRunBeginEvent),
+
+    /// A subagent run reached a final status.
+    SubAgentRunEnd(SubAgentRunEndEvent),
+
+    /// Resolved invocation for an `Op::RunSubAgent` with `dry_run` set.
+    SubAgentPreview(SubAgentPreview),
+
+    /// Merge plan for the staged subagent changes, in response to
+    /// `Op::MergeSubAgentChanges`.
+    SubAgentMergePlan(SubAgentMergePlanEvent),
+
+    /// A merge of staged subagent changes was applied to the working tree.
+    SubAgentMergeApplied(SubAgentMergeAppliedEvent),
+
+    /// A background task requested via `Op::StartBackgroundTask` has started.
+    BackgroundTaskBegin(BackgroundTaskBeginEvent),
+
+    /// A background task reached a final status.
+    BackgroundTaskEnd(BackgroundTaskEndEvent),
+
+    /// Background tasks still running in this session, in response to
+    /// `Op::ListBackgroundTasks`.
+    ListBackgroundTasksResponse(ListBackgroundTasksResponseEvent),
+
+    /// Processes started by the `background_process` tool, sent whenever one
+    /// starts, exits or is stopped.
+    BackgroundProcessesUpdated(BackgroundProcessesUpdatedEvent),
+
+    /// Inputs waiting to run as later turns, sent whenever the queue changes
+    /// and in response to `Op::ListQueuedUserInputs`.
+    QueuedUserInputs(QueuedUserInputsEvent),
+
+    /// A blocking `[[hooks]]` entry blocked or rewrote an action, or failed to run.
+    HookOutcome(HookOutcomeEvent),
+
+    /// Annotations, artifacts, and notifications returned by a
+    /// `turn.post_process` hook. Sent right before `TurnComplete`.
+    TurnAnnotations(TurnAnnotationsEvent),
+
+    /// Outbound connections made by agent-run commands during the turn
+    /// (`network_capture` feature). Sent right before `TurnComplete`.
+    NetworkActivity(NetworkActivityEvent),
+
+    /// Workspace files changed outside the agent since the previous model
+    /// request (`external_edits` feature). The model is told in the same request.
+    ExternalFilesChanged(ExternalFilesChangedEvent),
+
+    /// Git state of the workspace at the end of a turn (`resume_briefing`
+    /// feature). Only written to the rollout, never sent to clients.
+    WorkspaceSnapshot(WorkspaceSnapshotEvent),
+
+    /// What changed in the workspace while the session was not running,
+    /// sent on resume (`resume_briefing` feature). The model gets the same
+    /// briefing as the first item after the resumed history.
+    ResumeBriefing(ResumeBriefingEvent),
+
+    /// Plan mode was turned on or off, in response to `Op::SetPlanMode`.
+    PlanModeUpdated(PlanModeUpdatedEvent),
+
+    /// A named checkpoint was created in response to `Op::CreateCheckpoint`.
+    CheckpointCreated(CheckpointCreatedEvent),
+
+    /// Named checkpoints of this session, in response to `Op::ListCheckpoints`.
+    ListCheckpointsResponse(ListCheckpointsResponseEvent),
+
+    /// Outcome of `Op::RestoreCheckpoint`.
+    CheckpointRestored(CheckpointRestoredEvent),
+
+    /// The session started working in a new isolated worktree
+    /// (`[worktree_isolation]`).
+    WorktreeCreated(WorktreeCreatedEvent),
+
+    /// Changes made in the isolated worktree, in response to
+    /// `Op::ReviewWorktree` or at the end of a turn with `scope = "turn"`.
+    WorktreeReview(WorktreeReviewEvent),
+
+    /// Outcome of `Op::FinishWorktree`.
+    WorktreeFinished(WorktreeFinishedEvent),
+
+    /// The pinned items changed, in response to `Op::PinContext` or
+    /// `Op::UnpinContext`.
+    PinnedContextUpdated(PinnedContextUpdatedEvent),
+
+    /// Pinned items of this session, in response to `Op::ListPinnedContext`.
+    ListPinnedContextResponse(ListPinnedContextResponseEvent),
+
+    /// Composition of the next request's context, in response to
+    /// `Op::InspectContext` or `Op::DropContextItem`.
+    ContextInspection(ContextInspectionEvent),
+
+    /// Entries of the project memory file, in response to `Op::ListMemory`,
+    /// `Op::UpdateMemory`, or `Op::DeleteMemory`.
+    ListMemoryResponse(ListMemoryResponseEvent),
+
+    /// Reply to `Op::SuggestNextEdit`.
+    NextEditSuggestion(NextEditSuggestionEvent),
+
     /// Notification that skill data may have been updated and clients may want to reload.
     SkillsUpdateAvailable,
 
     PlanUpdate(UpdatePlanArgs),
 
+    /// Next steps the agent proposed via `suggest_follow_ups`, for the user
+    /// to edit or queue as the next turn.
+    FollowUpSuggestions(FollowUpSuggestionsEvent),
+
     TurnAborted(TurnAbortedEvent),
 
     /// Notification that the agent is shutting down.
@@ -927,6 +1386,8 @@ pub enum CodexErrorInfo {
         http_status_code: Option<u16>,
     },
     ThreadRollbackFailed,
+    /// A `[budget]` spending limit was reached.
+    BudgetExceeded,
     Other,
 }
 
@@ -1069,6 +1530,24 @@ pub struct ContextCompactedEvent;
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnCompleteEvent {
     pub last_agent_message: Option<String>,
+    /// Per-turn stats; absent in events recorded before they were reported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub metadata: Option<TurnMetadata>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct TurnMetadata {
+    /// Model that served the turn.
+    pub model: String,
+    pub reasoning_effort: Option<ReasoningEffortConfig>,
+    /// Wall-clock time from the start of the turn until it completed.
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+    /// Model requests retried after a stream error or a rate limit.
+    pub retries: u32,
+    /// Tokens used by the model requests of this turn only.
+    pub token_usage: TokenUsage,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1159,6 +1638,68 @@ impl TokenUsageInfo {
 pub struct TokenCountEvent {
     pub info: Option<TokenUsageInfo>,
     pub rate_limits: Option<RateLimitSnapshot>,
+    /// Session totals per tool, largest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_usage: Vec<ToolTokenUsage>,
+    /// Session totals per subagent definition, from `Op::RunSubAgent` runs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subagent_usage: Vec<SubAgentTokenUsage>,
+}
+
+/// Tokens attributed to one tool over the session.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ToolTokenUsage {
+    pub tool_name: String,
+    #[ts(type = "number")]
+    pub calls: u64,
+    /// Estimated prompt tokens the tool's outputs added to the context.
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    /// Completion tokens of the responses that followed the outputs, split
+    /// across the calls each response answered.
+    #[ts(type = "number")]
+    pub follow_up_tokens: i64,
+}
+
+/// Tokens spent by the child threads of one subagent definition.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SubAgentTokenUsage {
+    pub name: String,
+    #[ts(type = "number")]
+    pub runs: u64,
+    pub token_usage: TokenUsage,
+}
+
+/// Estimated spending, in USD, from the `[budget.prices]` table.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct CostUpdateEvent {
+    pub turn_usd: f64,
+    pub session_usd: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_usd_per_turn: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_usd_per_session: Option<f64>,
+}
+
+/// Remaining quota from the provider's standard `x-ratelimit-*` headers.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ProviderQuota {
+    pub limit_requests: Option<i64>,
+    pub remaining_requests: Option<i64>,
+    pub limit_tokens: Option<i64>,
+    pub remaining_tokens: Option<i64>,
+    /// Seconds until the request quota is replenished.
+    pub requests_reset_seconds: Option<f64>,
+    /// Seconds until the token quota is replenished.
+    pub tokens_reset_seconds: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ProviderRateLimitEvent {
+    pub quota: Option<ProviderQuota>,
+    /// Seconds until a rate-limited request is retried. `None` when the turn
+    /// is not (or no longer) waiting.
+    pub resume_in_seconds: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
@@ -1392,6 +1933,31 @@ pub struct WebSearchEndEvent {
     pub query: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum HostedToolKind {
+    CodeInterpreter,
+    FileSearch,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct HostedToolCallEvent {
+    pub call_id: String,
+    pub tool: HostedToolKind,
+    /// Final status reported by the provider, e.g. `completed` or `failed`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub status: Option<String>,
+    /// Code that was run, or the search queries one per line.
+    pub input: String,
+    /// Logs printed by the code, or the files the search matched.
+    pub output: String,
+    /// Images produced by the code interpreter.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub image_urls: Vec<String>,
+}
+
 // Conversation kept for backward compatibility.
 /// Response payload for `Op::GetHistory` containing the current session's
 /// in-memory transcript.
@@ -1599,6 +2165,7 @@ pub enum RolloutItem {
     Compacted(CompactedItem),
     TurnContext(TurnContextItem),
     EventMsg(EventMsg),
+    RuntimeState(RuntimeStateItem),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
@@ -1621,6 +2188,43 @@ impl From<CompactedItem> for ResponseItem {
     }
 }
 
+/// Session state kept outside the conversation that a resumed thread restores
+/// (`resume_runtime_state` feature). Each item replaces the previous one.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema, TS)]
+pub struct RuntimeStateItem {
+    /// Approval cache keys the user approved for the rest of the session.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub approved_for_session: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub queued_inputs: Vec<QueuedUserInput>,
+    /// Commands of the running turn that have not finished.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub running_commands: Vec<RunningCommand>,
+    /// Approval prompts of the running turn that are still unanswered.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_approvals: Vec<PendingApproval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, TS)]
+pub struct RunningCommand {
+    pub call_id: String,
+    pub turn_id: String,
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+    /// Unified exec process id, for commands that keep running in the background.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, TS)]
+pub struct PendingApproval {
+    pub call_id: String,
+    pub turn_id: String,
+    pub kind: ApprovalKind,
+    /// The command, the files of the patch, or the summary of the plan.
+    pub summary: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
 pub struct TurnContextItem {
     pub cwd: PathBuf,
@@ -1642,6 +2246,19 @@ pub struct TurnContextItem {
     pub final_output_json_schema: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub truncation_policy: Option<TruncationPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_trimming: Option<ToolTrimmingStats>,
+}
+
+/// How the `tool_trimming` feature shrank the tool list of one request.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ToolTrimmingStats {
+    /// MCP tools sent with their full schema.
+    pub full_tools: u32,
+    /// MCP tools sent by name only.
+    pub trimmed_tools: u32,
+    /// Estimated tokens saved by leaving their schemas out.
+    pub saved_tokens: i64,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
@@ -1699,6 +2316,18 @@ pub enum ReviewTarget {
         title: Option<String>,
     },
 
+    /// Review the changes in a revision range as understood by `git diff`,
+    /// such as `main..HEAD` or `v1.2.0...v1.3.0`.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    Range { range: String },
+
+    /// Review a GitHub pull request given its URL; its head is fetched before
+    /// the review starts.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    PullRequest { url: String },
+
     /// Arbitrary instructions provided by the user.
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -1898,6 +2527,25 @@ pub struct UndoCompletedEvent {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    /// Whether this completes an `Op::Undo`, `Op::Redo`, `Op::RestoreFile`,
+    /// or `Op::RevertHunks`.
+    #[serde(default)]
+    pub kind: UndoKind,
+    /// Snapshot that `Op::Redo` would restore next; `None` when there is
+    /// nothing to redo. Clients can offer a redo shortcut while this is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redo_commit_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum UndoKind {
+    #[default]
+    Undo,
+    Redo,
+    RestoreFile,
+    RevertHunks,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1906,6 +2554,19 @@ pub struct ThreadRolledBackEvent {
     pub num_turns: u32,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ConversationForkedEvent {
+    /// Id of the new thread. Its session metadata records this thread as the
+    /// one it was forked from.
+    pub thread_id: ThreadId,
+    pub rollout_path: PathBuf,
+    /// Number of user turns copied into the branch; `None` when the whole
+    /// history was copied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub num_turns: Option<u32>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct StreamErrorEvent {
     pub message: String,
@@ -1956,9 +2617,136 @@ pub struct PatchApplyEndEvent {
     pub changes: HashMap<PathBuf, FileChange>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PatchHunkReportEvent {
+    /// Identifier of the `apply_patch` call the report belongs to.
+    pub call_id: String,
+    /// Turn ID that this patch belongs to.
+    #[serde(default)]
+    pub turn_id: String,
+    /// Files where some chunk needed fuzz or a three-way merge.
+    pub files: Vec<PatchFileHunks>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PatchFileHunks {
+    pub path: PathBuf,
+    /// One entry per `@@` chunk, in patch order.
+    pub hunks: Vec<PatchHunkResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PatchHunkResult {
+    /// 1-based line in the file before the patch where the chunk starts.
+    pub line: usize,
+    pub status: PatchHunkStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum PatchHunkStatus {
+    /// Matched the file as written.
+    Applied,
+    /// Matched after ignoring a few context lines at its ends.
+    Fuzzy,
+    /// Matched the turn's snapshot of the file and was merged into the
+    /// current contents.
+    Merged,
+    /// The file already contained the chunk's result.
+    AlreadyApplied,
+    /// The file changed in the same lines since the snapshot; not applied.
+    Conflict,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PatchPreviewEvent {
+    /// Identifier of the `apply_patch` call the preview belongs to.
+    pub call_id: String,
+    /// Turn ID that this patch belongs to.
+    #[serde(default)]
+    pub turn_id: String,
+    pub preview: PatchPreview,
+}
+
+/// What applying a patch to the current working tree would do.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PatchPreview {
+    /// One entry per file the patch touches, sorted by path.
+    pub files: Vec<PatchPreviewFile>,
+}
+
+impl PatchPreview {
+    /// Chunks that conflict with the current contents and would be left out.
+    pub fn conflicts(&self) -> usize {
+        self.files
+            .iter()
+            .flat_map(|file| &file.hunks)
+            .filter(|hunk| hunk.status == PatchHunkStatus::Conflict)
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PatchPreviewFile {
+    pub path: PathBuf,
+    /// Destination of a rename.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub move_path: Option<PathBuf>,
+    pub status: FileDiffStatus,
+    /// Placement of each `@@` chunk, in patch order, when some chunk did not
+    /// match the file as written; empty when all of them did.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hunks: Vec<PatchHunkResult>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnDiffEvent {
     pub unified_diff: String,
+    /// Structured view of `unified_diff`: one entry per changed file, in the
+    /// same order as the file sections of the unified diff.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileDiff>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct FileDiff {
+    /// Path of the file at the end of the turn (repo-relative when inside a
+    /// git worktree).
+    pub path: PathBuf,
+    /// Path of the file before the turn, set only for renames.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<PathBuf>,
+    pub status: FileDiffStatus,
+    /// Git blob id of the content before the turn; `None` for added files.
+    pub old_hash: Option<String>,
+    /// Git blob id of the content after the turn; `None` for deleted files.
+    pub new_hash: Option<String>,
+    /// True when either side is not UTF-8 text; `hunks` is empty then.
+    #[serde(default)]
+    pub binary: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum FileDiffStatus {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+}
+
+/// Line ranges of one `@@` hunk, using unified diff conventions: starts are
+/// 1-based, and a zero-length range starts at the line before the change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1978,6 +2766,9 @@ pub struct McpListToolsResponseEvent {
     pub resources: std::collections::HashMap<String, Vec<McpResource>>,
     /// Known resource templates grouped by server name.
     pub resource_templates: std::collections::HashMap<String, Vec<McpResourceTemplate>>,
+    /// Known prompts grouped by server name.
+    #[serde(default)]
+    pub prompts: std::collections::HashMap<String, Vec<McpPrompt>>,
     /// Authentication status for each configured MCP server.
     pub auth_statuses: std::collections::HashMap<String, McpAuthStatus>,
 }
@@ -2013,6 +2804,49 @@ pub struct McpStartupFailure {
     pub error: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpToolsChangedEvent {
+    pub server: String,
+    pub change: McpServerChange,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[ts(rename_all = "snake_case", tag = "type")]
+pub enum McpServerChange {
+    /// The server started; `tools` are the fully qualified names it added.
+    Added { tools: Vec<String> },
+    /// The server was stopped and its tools removed.
+    Removed,
+    /// The server could not be added, or there was no such server to remove.
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpServerHealthEvent {
+    /// Server name being monitored.
+    pub server: String,
+    /// Current health status.
+    pub status: McpServerHealthStatus,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", tag = "state")]
+#[ts(rename_all = "snake_case", tag = "state")]
+pub enum McpServerHealthStatus {
+    /// The server stopped answering; its tools are unavailable until restart
+    /// attempt `restart_attempt` (1-based) succeeds.
+    Degraded {
+        error: String,
+        restart_attempt: u32,
+        retry_in_ms: u64,
+    },
+    /// The server was restarted and answers again.
+    Recovered,
+    /// Every restart attempt failed; the server stays down for the session.
+    Failed { error: String },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -2039,6 +2873,566 @@ impl fmt::Display for McpAuthStatus {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
+    /// Slash commands from `.codex/commands/` and `$CODEX_HOME/commands/`.
+    #[serde(default)]
+    pub custom_commands: Vec<CustomCommand>,
+}
+
+/// Response payload for `Op::ListMcpPrompts`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListMcpPromptsResponseEvent {
+    /// Prompts grouped by server name.
+    pub prompts: std::collections::HashMap<String, Vec<McpPrompt>>,
+}
+
+/// Response payload for `Op::ListApprovalJournal`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ApprovalJournalResponseEvent {
+    pub entries: Vec<ApprovalJournalEntry>,
+}
+
+/// Response payload for `Op::ListSubAgents`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListSubAgentsResponseEvent {
+    pub subagents: Vec<SubAgentInfo>,
+    pub errors: Vec<SubAgentLoadError>,
+}
+
+/// Payload for `EventMsg::SubAgentsUpdated`, sent when a file under the
+/// subagent search roots is created, edited, or removed.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct SubAgentsUpdatedEvent {
+    pub subagents: Vec<SubAgentInfo>,
+    pub errors: Vec<SubAgentLoadError>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct SubAgentRunBeginEvent {
+    /// Identifier for the run; also the thread ID of the child thread.
+    pub run_id: String,
+    pub name: String,
+    pub prompt: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct SubAgentRunEndEvent {
+    pub run_id: String,
+    pub name: String,
+    /// Final status of the child thread.
+    pub status: AgentStatus,
+    /// Files the run changed in its isolated worktree, when
+    /// `subagents.stage_changes` is on. The changes wait for
+    /// `Op::MergeSubAgentChanges` instead of touching the working tree.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub staged_files: Vec<PathBuf>,
+    /// Tokens the child thread used, when it reported any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct SubAgentMergePlanEvent {
+    /// Identifier to pass to `Op::ApplySubAgentMerge`.
+    pub merge_id: String,
+    pub runs: Vec<SubAgentMergeRun>,
+    /// Files whose edits do not overlap and are merged automatically.
+    pub merged_files: Vec<PathBuf>,
+    /// Files that need a resolution before the merge can be applied.
+    pub conflicts: Vec<SubAgentMergeConflict>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct SubAgentMergeAppliedEvent {
+    pub merge_id: String,
+    /// Files written to the working tree.
+    pub applied_files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct BackgroundTaskBeginEvent {
+    /// Identifier for the task; also the thread ID of the child thread whose
+    /// events carry the task's progress.
+    pub task_id: String,
+    /// Subagent definition applied to the task, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub subagent: Option<String>,
+    pub prompt: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct BackgroundTaskEndEvent {
+    pub task_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub subagent: Option<String>,
+    /// Final status of the child thread.
+    pub status: AgentStatus,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct BackgroundTaskInfo {
+    pub task_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub subagent: Option<String>,
+    pub prompt: String,
+    /// Current status of the child thread.
+    pub status: AgentStatus,
+    /// RFC 3339 timestamp of when the task started.
+    pub started_at: String,
+}
+
+/// Response payload for `Op::ListBackgroundTasks`, oldest task first.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListBackgroundTasksResponseEvent {
+    pub tasks: Vec<BackgroundTaskInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackgroundProcessStatus {
+    Running,
+    Exited { exit_code: i32 },
+}
+
+/// A long-running process started by the agent, e.g. a dev server.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct BackgroundProcessInfo {
+    /// Id the agent uses to read the process's output or stop it.
+    pub process_id: String,
+    pub command: String,
+    pub cwd: PathBuf,
+    pub status: BackgroundProcessStatus,
+}
+
+/// All background processes of the session, oldest first. Exited processes
+/// stay listed until the agent stops them.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct BackgroundProcessesUpdatedEvent {
+    pub processes: Vec<BackgroundProcessInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct QueuedUserInput {
+    /// Submission id of the `Op::QueueUserInput`; the queued turn runs under
+    /// this id once it starts.
+    pub id: String,
+    pub items: Vec<UserInput>,
+}
+
+/// Inputs waiting in the queue, next to run first.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct QueuedUserInputsEvent {
+    pub inputs: Vec<QueuedUserInput>,
+}
+
+/// Points in a session where `[[hooks]]` entries can run.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Deserialize, Serialize, JsonSchema, TS,
+)]
+pub enum HookEventKind {
+    #[serde(rename = "prompt.submit")]
+    #[strum(serialize = "prompt.submit")]
+    PromptSubmit,
+    #[serde(rename = "turn.begin")]
+    #[strum(serialize = "turn.begin")]
+    TurnBegin,
+    #[serde(rename = "turn.end")]
+    #[strum(serialize = "turn.end")]
+    TurnEnd,
+    #[serde(rename = "web_search.begin")]
+    #[strum(serialize = "web_search.begin")]
+    WebSearchBegin,
+    #[serde(rename = "web_search.end")]
+    #[strum(serialize = "web_search.end")]
+    WebSearchEnd,
+    #[serde(rename = "tool.call.begin")]
+    #[strum(serialize = "tool.call.begin")]
+    ToolCallBegin,
+    #[serde(rename = "tool.call.end")]
+    #[strum(serialize = "tool.call.end")]
+    ToolCallEnd,
+    #[serde(rename = "tool.exec.begin")]
+    #[strum(serialize = "tool.exec.begin")]
+    ToolExecBegin,
+    #[serde(rename = "tool.exec.end")]
+    #[strum(serialize = "tool.exec.end")]
+    ToolExecEnd,
+    #[serde(rename = "tool.apply_patch.begin")]
+    #[strum(serialize = "tool.apply_patch.begin")]
+    ToolApplyPatchBegin,
+    #[serde(rename = "tool.apply_patch.end")]
+    #[strum(serialize = "tool.apply_patch.end")]
+    ToolApplyPatchEnd,
+    #[serde(rename = "tool.mcp.begin")]
+    #[strum(serialize = "tool.mcp.begin")]
+    ToolMcpBegin,
+    #[serde(rename = "tool.mcp.end")]
+    #[strum(serialize = "tool.mcp.end")]
+    ToolMcpEnd,
+    #[serde(rename = "turn.post_process")]
+    #[strum(serialize = "turn.post_process")]
+    TurnPostProcess,
+}
+
+impl HookEventKind {
+    /// Only these events wait for `blocking = true` hooks and honor their verdict.
+    pub fn supports_blocking(self) -> bool {
+        matches!(
+            self,
+            HookEventKind::PromptSubmit | HookEventKind::ToolCallBegin
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct HookOutcomeEvent {
+    /// `id` of the hook entry, when configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook_id: Option<String>,
+    pub when: HookEventKind,
+    /// Tool call the hook ran for (`tool.call.begin`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub call_id: Option<String>,
+    pub outcome: HookOutcome,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HookOutcome {
+    /// The hook denied the action (exit code 2, or `{"decision": "block"}`).
+    Blocked { reason: String },
+    /// The hook replaced the tool call's arguments.
+    Modified,
+    /// The hook could not run or exited unexpectedly; the action proceeded.
+    Failed { error: String },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct TurnAnnotationsEvent {
+    /// `id` of the hook entry, when configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook_id: Option<String>,
+    /// Notes the hook attached to the turn.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<String>,
+    /// Files the hook had written under `$CODEX_HOME/artifacts/<thread_id>`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notifications: Vec<HookNotification>,
+}
+
+/// A message a `turn.post_process` hook wants shown to the user.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct HookNotification {
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct PlanModeUpdatedEvent {
+    pub enabled: bool,
+}
+
+/// A named snapshot of the working tree taken with `Op::CreateCheckpoint`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct Checkpoint {
+    pub id: String,
+    pub name: String,
+    /// Ghost commit holding the snapshot.
+    pub commit_id: String,
+    /// RFC 3339 timestamp of when the checkpoint was created.
+    pub created_at: String,
+    /// Changes made since the checkpoint, i.e. what restoring it would revert.
+    /// Only filled in by `EventMsg::ListCheckpointsResponse`, and left out when
+    /// the working tree could not be compared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub diffstat: Option<CheckpointDiffStat>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct CheckpointDiffStat {
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct CheckpointCreatedEvent {
+    pub checkpoint: Checkpoint,
+}
+
+/// Response payload for `Op::ListCheckpoints`, oldest checkpoint first.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ListCheckpointsResponseEvent {
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct CheckpointRestoredEvent {
+    pub checkpoint_id: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// What `Op::FinishWorktree` does with the changes of the isolated worktree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum WorktreeFinishAction {
+    /// Apply them to the checked-out tree.
+    Merge,
+    /// Throw them away.
+    Discard,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct WorktreeCreatedEvent {
+    /// Where the worktree is checked out.
+    pub path: PathBuf,
+    pub branch: String,
+    /// Snapshot of the checkout (uncommitted edits included) it started from.
+    pub base_commit: String,
+}
+
+/// Lines added and removed in one file of an isolated worktree.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct WorktreeFileChange {
+    /// Repo-relative path.
+    pub path: PathBuf,
+    pub added: u32,
+    pub removed: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct WorktreeReviewEvent {
+    pub path: PathBuf,
+    pub branch: String,
+    /// Everything changed since the worktree was created, as a git diff.
+    pub unified_diff: String,
+    pub files: Vec<WorktreeFileChange>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct WorktreeFinishedEvent {
+    pub action: WorktreeFinishAction,
+    pub branch: String,
+    /// Files merged into the checkout, or discarded.
+    pub files: Vec<PathBuf>,
+}
+
+/// What a pinned item carries into every model request.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type")]
+pub enum PinnedContent {
+    /// Text of a conversation message, such as an earlier reply.
+    Message { text: String },
+    /// A file whose current contents are read for every request.
+    File { path: PathBuf },
+    /// A note written by the user.
+    Note { text: String },
+}
+
+/// An item pinned with `Op::PinContext`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct PinnedContextItem {
+    pub id: String,
+    pub content: PinnedContent,
+}
+
+/// Every pinned item after the change, oldest first.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct PinnedContextUpdatedEvent {
+    pub items: Vec<PinnedContextItem>,
+}
+
+/// Response payload for `Op::ListPinnedContext`, oldest item first.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ListPinnedContextResponseEvent {
+    pub items: Vec<PinnedContextItem>,
+}
+
+/// What a [`ContextInspectionItem`] is.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextItemKind {
+    /// The model's base instructions (system prompt).
+    BaseInstructions,
+    /// Developer-role instructions.
+    DeveloperInstructions,
+    /// `AGENTS.md` and other user instructions.
+    UserInstructions,
+    /// Instructions of a skill in use.
+    SkillInstructions,
+    /// The `<environment_context>` message.
+    EnvironmentContext,
+    /// An item pinned with `Op::PinContext`.
+    Pinned,
+    /// A user turn: the message and everything the agent did in response.
+    Turn,
+    /// Output of one tool call; already counted in its turn.
+    ToolOutput,
+    /// Any other message, such as a compaction summary.
+    Other,
+}
+
+/// One entry of a [`ContextInspectionEvent`].
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ContextInspectionItem {
+    /// Id to pass to `Op::DropContextItem`; `None` for items that cannot be
+    /// dropped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub kind: ContextItemKind,
+    /// Short description, e.g. the first line of a user message.
+    pub label: String,
+    /// Estimated tokens, using the same byte-based heuristic as the context
+    /// usage indicator.
+    pub tokens: i64,
+    /// 1-based turn of `Turn` and `ToolOutput` items; pass it to
+    /// `Op::CompactRange` to summarize the turn.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn: Option<u32>,
+}
+
+/// Response payload for `Op::InspectContext`, in request order.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ContextInspectionEvent {
+    pub items: Vec<ContextInspectionItem>,
+    /// Sum of the items, not counting `ToolOutput` items twice.
+    pub total_tokens: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<i64>,
+}
+
+/// A durable fact in the project memory file. Ids are 1-based positions in
+/// the file, so they shift when an earlier entry is deleted.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct MemoryEntry {
+    pub id: String,
+    pub text: String,
+}
+
+/// Response payload for `Op::ListMemory` and the memory edit ops.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ListMemoryResponseEvent {
+    /// The project memory file, which may not exist yet.
+    pub path: PathBuf,
+    pub entries: Vec<MemoryEntry>,
+}
+
+/// Response payload for `Op::SuggestNextEdit`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct NextEditSuggestionEvent {
+    pub request_id: String,
+    /// Text to insert at the cursor; `None` when the model had no suggestion
+    /// or the request failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct SandboxDeniedEvent {
+    pub call_id: String,
+    pub turn_id: String,
+    /// The denied command; `["apply_patch"]` for patches.
+    pub command: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct NetworkActivityEvent {
+    pub connections: Vec<NetworkConnection>,
+}
+
+/// One connection relayed by the capture proxy.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct NetworkConnection {
+    pub host: String,
+    pub port: u16,
+    /// Bytes sent by the command, including the request head for plain HTTP.
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// `CONNECT` for tunneled (usually TLS) traffic, otherwise the HTTP method.
+    pub method: String,
+    /// Set when the upstream connection could not be established.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ExternalFilesChangedEvent {
+    pub changes: Vec<ExternalFileChange>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ExternalFileChange {
+    /// Path relative to the turn's working directory.
+    pub path: PathBuf,
+    pub kind: ExternalFileChangeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ExternalFileChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct WorkspaceSnapshotEvent {
+    /// Unix timestamp (seconds) at which the snapshot was taken.
+    pub taken_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Files with uncommitted changes, relative to the repository root.
+    #[serde(default)]
+    pub dirty_files: Vec<WorkspaceFileState>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct WorkspaceFileState {
+    pub path: PathBuf,
+    /// Git blob id of the file's content; `None` when the file is deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ResumeBriefingEvent {
+    /// Unix timestamp (seconds) of the snapshot the workspace was compared with.
+    pub since: i64,
+    /// Branch checked out at the snapshot, set only when it differs from `branch`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Most recent commits made since the snapshot, newest first.
+    pub new_commits: Vec<BriefingCommit>,
+    /// Number of commits made since the snapshot; may exceed `new_commits.len()`.
+    pub new_commit_count: usize,
+    /// Files whose uncommitted changes differ from the snapshot, relative to
+    /// the repository root.
+    pub changed_files: Vec<PathBuf>,
+    /// Latest CI run on the branch, as reported by the GitHub CLI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci_status: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct BriefingCommit {
+    /// Abbreviated commit hash.
+    pub sha: String,
+    pub subject: String,
 }
 
 /// Response payload for `Op::ListSkills`.
@@ -2102,6 +3496,15 @@ pub struct SkillsListEntry {
     pub errors: Vec<SkillErrorInfo>,
 }
 
+/// Payload for `EventMsg::ConfigReloaded`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ConfigReloadedEvent {
+    /// Branch now checked out, or `None` on a detached HEAD.
+    pub branch: Option<String>,
+    pub approval_policy: AskForApproval,
+    pub sandbox_policy: SandboxPolicy,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct SessionConfiguredEvent {
     /// Name left as session_id instead of thread_id for backwards compatibility.
@@ -2208,6 +3611,19 @@ pub struct Chunk {
     pub inserted_lines: Vec<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct FollowUpSuggestion {
+    /// Short label shown on the chip.
+    pub title: String,
+    /// Prompt sent as the user message when the suggestion is chosen.
+    pub prompt: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct FollowUpSuggestionsEvent {
+    pub suggestions: Vec<FollowUpSuggestion>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnAbortedEvent {
     pub reason: TurnAbortReason,
@@ -0,0 +1,105 @@
+use crate::config_types::SandboxMode;
+use crate::protocol::AskForApproval;
+use crate::protocol::SandboxPolicy;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+use ts_rs::TS;
+
+/// Search root a subagent definition was discovered under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum SubAgentSource {
+    /// `<git root>/.codex/agents`
+    Repo,
+    /// `$CODEX_HOME/agents`
+    User,
+    /// A directory listed in `subagents.extra_dirs`.
+    Extra,
+}
+
+/// Client-facing summary of a subagent definition (`<name>.md` with frontmatter).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+pub struct SubAgentInfo {
+    pub name: String,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub color: Option<String>,
+    /// Sandbox mode the subagent runs under; `None` inherits the parent session's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub sandbox_mode: Option<SandboxMode>,
+    /// Model override; `None` inherits the parent session's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub model: Option<String>,
+    pub path: PathBuf,
+    pub source: SubAgentSource,
+}
+
+/// A definition file that was found but could not be loaded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+pub struct SubAgentLoadError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+/// What running a subagent would do, resolved without spawning it
+/// (`Op::RunSubAgent` with `dry_run`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+pub struct SubAgentPreview {
+    pub name: String,
+    pub path: PathBuf,
+    /// Model the child thread would use after the definition's override.
+    pub model: String,
+    pub approval_policy: AskForApproval,
+    /// Effective sandbox policy after the definition's `sandbox_mode`.
+    pub sandbox_policy: SandboxPolicy,
+    /// Built-in tools exposed to the child thread.
+    pub tools: Vec<String>,
+    /// Enabled MCP servers whose tools the child thread would also receive.
+    pub mcp_servers: Vec<String>,
+    /// Parent developer instructions with the definition body appended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub developer_instructions: Option<String>,
+    /// First user message the child thread would receive.
+    pub prompt: String,
+}
+
+/// A subagent run whose staged changes take part in a merge.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+pub struct SubAgentMergeRun {
+    pub run_id: String,
+    pub name: String,
+}
+
+/// A file changed by more than one run where the edits overlap, so one run's
+/// version has to be chosen before the merge is applied.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+pub struct SubAgentMergeConflict {
+    pub path: PathBuf,
+    pub candidates: Vec<SubAgentMergeCandidate>,
+}
+
+/// One run's changes to a conflicted file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+pub struct SubAgentMergeCandidate {
+    pub run_id: String,
+    pub name: String,
+    /// Unified diff of this run's edits to the file.
+    pub diff: String,
+}
+
+/// How to settle a conflicted file when applying a merge.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+pub struct SubAgentMergeResolution {
+    pub path: PathBuf,
+    /// Run whose version of the file is applied; `None` leaves the file as is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub run_id: Option<String>,
+}
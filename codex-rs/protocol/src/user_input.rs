@@ -29,6 +29,38 @@ pub enum UserInput {
         name: String,
         path: std::path::PathBuf,
     },
+
+    /// Editor content attached as context: the selected lines, or the whole
+    /// file when no range is set. `text` is the editor's buffer, so unsaved
+    /// edits are included. Recorded after the prompt as an `<editor_context>`
+    /// message rather than as prompt text.
+    EditorContext {
+        path: std::path::PathBuf,
+        /// First line of the selection (1-based).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        start_line: Option<u32>,
+        /// Last line of the selection (1-based, inclusive).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        end_line: Option<u32>,
+        text: String,
+    },
+
+    /// Output captured from one of the client's terminals, attached as
+    /// context alongside [`UserInput::EditorContext`].
+    TerminalOutput {
+        /// Name of the terminal the output came from.
+        terminal: String,
+        /// Command line that produced the output, when the client knows it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        command: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[ts(optional)]
+        exit_code: Option<i32>,
+        text: String,
+    },
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS, JsonSchema)]
@@ -22,6 +22,7 @@ use rmcp::model::ResourceTemplate;
 use rmcp::model::ServerCapabilities;
 use rmcp::model::ServerInfo;
 use rmcp::model::Tool;
+use rmcp::model::ToolAnnotations;
 use serde::Deserialize;
 use serde_json::json;
 use tokio::task;
@@ -79,11 +80,16 @@ impl TestToolServer {
         }))
         .expect("image tool schema should deserialize");
 
-        Tool::new(
+        let mut tool = Tool::new(
             Cow::Borrowed("image"),
             Cow::Borrowed("Return a single image content block."),
             Arc::new(schema),
-        )
+        );
+        // Lets tests tell read-only tools from ones with side effects.
+        let mut annotations = ToolAnnotations::new();
+        annotations.read_only_hint = Some(true);
+        tool.annotations = Some(annotations);
+        tool
     }
 
     fn memo_resource() -> Resource {
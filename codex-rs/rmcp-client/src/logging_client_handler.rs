@@ -1,4 +1,6 @@
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use rmcp::ClientHandler;
 use rmcp::RoleClient;
@@ -6,6 +8,8 @@ use rmcp::model::CancelledNotificationParam;
 use rmcp::model::ClientInfo;
 use rmcp::model::CreateElicitationRequestParam;
 use rmcp::model::CreateElicitationResult;
+use rmcp::model::CreateMessageRequestParam;
+use rmcp::model::CreateMessageResult;
 use rmcp::model::LoggingLevel;
 use rmcp::model::LoggingMessageNotificationParam;
 use rmcp::model::ProgressNotificationParam;
@@ -19,34 +23,69 @@ use tracing::info;
 use tracing::warn;
 
 use crate::rmcp_client::SendElicitation;
+use crate::rmcp_client::SendSampling;
+use crate::utils::convert_to_mcp;
+use crate::utils::convert_to_rmcp;
 
 #[derive(Clone)]
 pub(crate) struct LoggingClientHandler {
     client_info: ClientInfo,
     send_elicitation: Arc<SendElicitation>,
+    send_sampling: Option<Arc<SendSampling>>,
+    updated_resources: Arc<Mutex<HashSet<String>>>,
 }
 
 impl LoggingClientHandler {
-    pub(crate) fn new(client_info: ClientInfo, send_elicitation: SendElicitation) -> Self {
+    pub(crate) fn new(
+        client_info: ClientInfo,
+        send_elicitation: SendElicitation,
+        send_sampling: Option<SendSampling>,
+        updated_resources: Arc<Mutex<HashSet<String>>>,
+    ) -> Self {
         Self {
             client_info,
             send_elicitation: Arc::new(send_elicitation),
+            send_sampling: send_sampling.map(Arc::new),
+            updated_resources,
         }
     }
 }
 
+fn to_mcp_request_id(id: RequestId) -> mcp_types::RequestId {
+    match id {
+        RequestId::String(id) => mcp_types::RequestId::String(id.to_string()),
+        RequestId::Number(id) => mcp_types::RequestId::Integer(id),
+    }
+}
+
 impl ClientHandler for LoggingClientHandler {
     async fn create_elicitation(
         &self,
         request: CreateElicitationRequestParam,
         context: RequestContext<RoleClient>,
     ) -> Result<CreateElicitationResult, rmcp::ErrorData> {
-        let id = match context.id {
-            RequestId::String(id) => mcp_types::RequestId::String(id.to_string()),
-            RequestId::Number(id) => mcp_types::RequestId::Integer(id),
+        (self.send_elicitation)(to_mcp_request_id(context.id), request)
+            .await
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
+    }
+
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, rmcp::ErrorData> {
+        let Some(send_sampling) = &self.send_sampling else {
+            return Err(rmcp::ErrorData::invalid_request(
+                "sampling is not enabled for this server",
+                None,
+            ));
         };
-        (self.send_elicitation)(id, request)
+        let params = convert_to_mcp(params)
+            .map_err(|err| rmcp::ErrorData::invalid_params(err.to_string(), None))?;
+        let result = send_sampling(to_mcp_request_id(context.id), params)
             .await
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))?;
+        convert_to_rmcp(result)
             .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
     }
 
@@ -78,6 +117,10 @@ impl ClientHandler for LoggingClientHandler {
         _context: NotificationContext<RoleClient>,
     ) {
         info!("MCP server resource updated (uri: {})", params.uri);
+        match self.updated_resources.lock() {
+            Ok(mut updated) => updated.insert(params.uri),
+            Err(err) => err.into_inner().insert(params.uri),
+        };
     }
 
     async fn on_resource_list_changed(&self, _context: NotificationContext<RoleClient>) {
@@ -14,7 +14,8 @@
 //! keystore that always encrypts secrets when they are transferred across the bus. If DBus isn't installed the keystore will fall back to the json
 //! file because we don't use the "vendored" feature.
 //!
-//! If the keyring is not available or fails, we fall back to CODEX_HOME/.credentials.json which is consistent with other coding CLI agents.
+//! If the keyring is not available or fails, we fall back to one file per server under CODEX_HOME/mcp_auth/.
+//! Tokens written by older builds to CODEX_HOME/.credentials.json are still read and move to `mcp_auth/` when next saved.
 
 use anyhow::Context;
 use anyhow::Error;
@@ -36,6 +37,7 @@ use sha2::Sha256;
 use std::collections::BTreeMap;
 use std::fs;
 use std::io::ErrorKind;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -71,8 +73,8 @@ pub enum OAuthCredentialsStoreMode {
     /// Credentials stored in the keyring will only be readable by Codex unless the user explicitly grants access via OS-level keyring access.
     #[default]
     Auto,
-    /// CODEX_HOME/.credentials.json
-    /// This file will be readable to Codex and other applications running as the same user.
+    /// One file per server under CODEX_HOME/mcp_auth/
+    /// These files will be readable to Codex and other applications running as the same user.
     File,
     /// Keyring when available, otherwise fail.
     Keyring,
@@ -103,8 +105,12 @@ pub(crate) fn load_oauth_tokens(
         }
         OAuthCredentialsStoreMode::File => load_oauth_tokens_from_file(server_name, url),
         OAuthCredentialsStoreMode::Keyring => {
-            load_oauth_tokens_from_keyring(&keyring_store, server_name, url)
-                .with_context(|| "failed to read OAuth tokens from keyring".to_string())
+            match load_oauth_tokens_from_keyring(&keyring_store, server_name, url)
+                .with_context(|| "failed to read OAuth tokens from keyring".to_string())?
+            {
+                Some(tokens) => Ok(Some(tokens)),
+                None => migrate_oauth_tokens_from_file(&keyring_store, server_name, url),
+            }
         }
     }
 }
@@ -140,7 +146,13 @@ fn load_oauth_tokens_from_keyring_with_fallback_to_file<K: KeyringStore>(
 ) -> Result<Option<StoredOAuthTokens>> {
     match load_oauth_tokens_from_keyring(keyring_store, server_name, url) {
         Ok(Some(tokens)) => Ok(Some(tokens)),
-        Ok(None) => load_oauth_tokens_from_file(server_name, url),
+        Ok(None) => match migrate_oauth_tokens_from_file(keyring_store, server_name, url) {
+            Ok(tokens) => Ok(tokens),
+            Err(error) => {
+                warn!("failed to move OAuth tokens into keyring: {error}");
+                load_oauth_tokens_from_file(server_name, url)
+            }
+        },
         Err(error) => {
             warn!("failed to read OAuth tokens from keyring: {error}");
             load_oauth_tokens_from_file(server_name, url)
@@ -149,6 +161,21 @@ fn load_oauth_tokens_from_keyring_with_fallback_to_file<K: KeyringStore>(
     }
 }
 
+/// Moves tokens saved to the plaintext fallback file (while the keyring was
+/// unavailable, or under `file` storage) into the keyring, which also removes
+/// them from the file.
+fn migrate_oauth_tokens_from_file<K: KeyringStore>(
+    keyring_store: &K,
+    server_name: &str,
+    url: &str,
+) -> Result<Option<StoredOAuthTokens>> {
+    let Some(tokens) = load_oauth_tokens_from_file(server_name, url)? else {
+        return Ok(None);
+    };
+    save_oauth_tokens_with_keyring(keyring_store, server_name, &tokens)?;
+    Ok(Some(tokens))
+}
+
 fn load_oauth_tokens_from_keyring<K: KeyringStore>(
     keyring_store: &K,
     server_name: &str,
@@ -375,7 +402,12 @@ impl OAuthPersistor {
     }
 }
 
-const FALLBACK_FILENAME: &str = ".credentials.json";
+/// Each server's tokens live in their own file under CODEX_HOME/mcp_auth/.
+const MCP_AUTH_DIR: &str = "mcp_auth";
+/// Older builds kept every server's tokens in a single CODEX_HOME/.credentials.json.
+/// It is still read so existing logins keep working; entries move to
+/// `mcp_auth/` the next time they are saved.
+const LEGACY_FALLBACK_FILENAME: &str = ".credentials.json";
 const MCP_SERVER_TYPE: &str = "http";
 
 type FallbackFile = BTreeMap<String, FallbackTokenEntry>;
@@ -395,51 +427,43 @@ struct FallbackTokenEntry {
 }
 
 fn load_oauth_tokens_from_file(server_name: &str, url: &str) -> Result<Option<StoredOAuthTokens>> {
-    let Some(store) = read_fallback_file()? else {
-        return Ok(None);
-    };
-
     let key = compute_store_key(server_name, url)?;
+    let entry = match read_server_credentials(&key)? {
+        Some(entry) => entry,
+        None => match find_legacy_entry(&key)? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        },
+    };
 
-    for entry in store.values() {
-        let entry_key = compute_store_key(&entry.server_name, &entry.server_url)?;
-        if entry_key != key {
-            continue;
-        }
-
-        let mut token_response = OAuthTokenResponse::new(
-            AccessToken::new(entry.access_token.clone()),
-            BasicTokenType::Bearer,
-            EmptyExtraTokenFields {},
-        );
-
-        if let Some(refresh) = entry.refresh_token.clone() {
-            token_response.set_refresh_token(Some(RefreshToken::new(refresh)));
-        }
-
-        let scopes = entry.scopes.clone();
-        if !scopes.is_empty() {
-            token_response.set_scopes(Some(scopes.into_iter().map(Scope::new).collect()));
-        }
+    let mut token_response = OAuthTokenResponse::new(
+        AccessToken::new(entry.access_token),
+        BasicTokenType::Bearer,
+        EmptyExtraTokenFields {},
+    );
 
-        let mut stored = StoredOAuthTokens {
-            server_name: entry.server_name.clone(),
-            url: entry.server_url.clone(),
-            client_id: entry.client_id.clone(),
-            token_response: WrappedOAuthTokenResponse(token_response),
-            expires_at: entry.expires_at,
-        };
-        refresh_expires_in_from_timestamp(&mut stored);
+    if let Some(refresh) = entry.refresh_token {
+        token_response.set_refresh_token(Some(RefreshToken::new(refresh)));
+    }
 
-        return Ok(Some(stored));
+    if !entry.scopes.is_empty() {
+        token_response.set_scopes(Some(entry.scopes.into_iter().map(Scope::new).collect()));
     }
 
-    Ok(None)
+    let mut stored = StoredOAuthTokens {
+        server_name: entry.server_name,
+        url: entry.server_url,
+        client_id: entry.client_id,
+        token_response: WrappedOAuthTokenResponse(token_response),
+        expires_at: entry.expires_at,
+    };
+    refresh_expires_in_from_timestamp(&mut stored);
+
+    Ok(Some(stored))
 }
 
 fn save_oauth_tokens_to_file(tokens: &StoredOAuthTokens) -> Result<()> {
     let key = compute_store_key(&tokens.server_name, &tokens.url)?;
-    let mut store = read_fallback_file()?.unwrap_or_default();
 
     let token_response = &tokens.token_response.0;
     let expires_at = tokens
@@ -462,23 +486,25 @@ fn save_oauth_tokens_to_file(tokens: &StoredOAuthTokens) -> Result<()> {
         scopes,
     };
 
-    store.insert(key, entry);
-    write_fallback_file(&store)
+    write_server_credentials(&key, &entry)?;
+    remove_legacy_entry(&key)?;
+    Ok(())
 }
 
 fn delete_oauth_tokens_from_file(key: &str) -> Result<bool> {
-    let mut store = match read_fallback_file()? {
-        Some(store) => store,
-        None => return Ok(false),
+    let path = server_credentials_path(key)?;
+    let file_removed = match fs::remove_file(&path) {
+        Ok(()) => true,
+        Err(err) if err.kind() == ErrorKind::NotFound => false,
+        Err(err) => {
+            return Err(err).context(format!(
+                "failed to remove credentials file at {}",
+                path.display()
+            ));
+        }
     };
-
-    let removed = store.remove(key).is_some();
-
-    if removed {
-        write_fallback_file(&store)?;
-    }
-
-    Ok(removed)
+    let legacy_removed = remove_legacy_entry(key)?;
+    Ok(file_removed || legacy_removed)
 }
 
 pub(crate) fn compute_expires_at_millis(response: &OAuthTokenResponse) -> Option<u64> {
@@ -534,14 +560,85 @@ fn compute_store_key(server_name: &str, server_url: &str) -> Result<String> {
     Ok(format!("{server_name}|{truncated}"))
 }
 
-fn fallback_file_path() -> Result<PathBuf> {
+/// CODEX_HOME/mcp_auth/<server>-<hash>.json. Characters that are not safe
+/// in a file name are replaced so any configured server name maps to a file.
+fn server_credentials_path(key: &str) -> Result<PathBuf> {
+    let file_name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let mut path = find_codex_home()?;
+    path.push(MCP_AUTH_DIR);
+    path.push(format!("{file_name}.json"));
+    Ok(path)
+}
+
+fn read_server_credentials(key: &str) -> Result<Option<FallbackTokenEntry>> {
+    let path = server_credentials_path(key)?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).context(format!(
+                "failed to read credentials file at {}",
+                path.display()
+            ));
+        }
+    };
+
+    serde_json::from_str::<FallbackTokenEntry>(&contents)
+        .map(Some)
+        .with_context(|| format!("failed to parse credentials file at {}", path.display()))
+}
+
+fn write_server_credentials(key: &str, entry: &FallbackTokenEntry) -> Result<()> {
+    let path = server_credentials_path(key)?;
+    let serialized = serde_json::to_string(entry)?;
+    write_private_file(&path, &serialized)
+}
+
+fn find_legacy_entry(key: &str) -> Result<Option<FallbackTokenEntry>> {
+    let Some(store) = read_legacy_fallback_file()? else {
+        return Ok(None);
+    };
+
+    for entry in store.into_values() {
+        if compute_store_key(&entry.server_name, &entry.server_url)? == key {
+            return Ok(Some(entry));
+        }
+    }
+    Ok(None)
+}
+
+fn remove_legacy_entry(key: &str) -> Result<bool> {
+    let mut store = match read_legacy_fallback_file()? {
+        Some(store) => store,
+        None => return Ok(false),
+    };
+
+    let removed = store.remove(key).is_some();
+
+    if removed {
+        write_legacy_fallback_file(&store)?;
+    }
+
+    Ok(removed)
+}
+
+fn legacy_fallback_file_path() -> Result<PathBuf> {
     let mut path = find_codex_home()?;
-    path.push(FALLBACK_FILENAME);
+    path.push(LEGACY_FALLBACK_FILENAME);
     Ok(path)
 }
 
-fn read_fallback_file() -> Result<Option<FallbackFile>> {
-    let path = fallback_file_path()?;
+fn read_legacy_fallback_file() -> Result<Option<FallbackFile>> {
+    let path = legacy_fallback_file_path()?;
     let contents = match fs::read_to_string(&path) {
         Ok(contents) => contents,
         Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
@@ -562,8 +659,8 @@ fn read_fallback_file() -> Result<Option<FallbackFile>> {
     }
 }
 
-fn write_fallback_file(store: &FallbackFile) -> Result<()> {
-    let path = fallback_file_path()?;
+fn write_legacy_fallback_file(store: &FallbackFile) -> Result<()> {
+    let path = legacy_fallback_file_path()?;
 
     if store.is_empty() {
         if path.exists() {
@@ -572,18 +669,22 @@ fn write_fallback_file(store: &FallbackFile) -> Result<()> {
         return Ok(());
     }
 
+    let serialized = serde_json::to_string(store)?;
+    write_private_file(&path, &serialized)
+}
+
+fn write_private_file(path: &Path, contents: &str) -> Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    let serialized = serde_json::to_string(store)?;
-    fs::write(&path, serialized)?;
+    fs::write(path, contents)?;
 
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let perms = fs::Permissions::from_mode(0o600);
-        fs::set_permissions(&path, perms)?;
+        fs::set_permissions(path, perms)?;
     }
 
     Ok(())
@@ -681,6 +782,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn load_oauth_tokens_moves_fallback_tokens_into_keyring() -> Result<()> {
+        let _env = TempCodexHome::new();
+        let store = MockKeyringStore::default();
+        let tokens = sample_tokens();
+        let key = super::compute_store_key(&tokens.server_name, &tokens.url)?;
+
+        super::save_oauth_tokens_to_file(&tokens)?;
+
+        super::load_oauth_tokens_from_keyring_with_fallback_to_file(
+            &store,
+            &tokens.server_name,
+            &tokens.url,
+        )?
+        .expect("tokens should load from fallback");
+        let fallback_path = super::server_credentials_path(&key)?;
+        assert!(!fallback_path.exists(), "fallback file should be removed");
+        let stored = store.saved_value(&key).expect("value saved to keyring");
+        assert_tokens_match_without_expiry(
+            &serde_json::from_str::<StoredOAuthTokens>(&stored)?,
+            &tokens,
+        );
+        Ok(())
+    }
+
     #[test]
     fn load_oauth_tokens_falls_back_when_keyring_errors() -> Result<()> {
         let _env = TempCodexHome::new();
@@ -717,7 +843,7 @@ mod tests {
             &tokens,
         )?;
 
-        let fallback_path = super::fallback_file_path()?;
+        let fallback_path = super::server_credentials_path(&key)?;
         assert!(!fallback_path.exists(), "fallback file should be removed");
         let stored = store.saved_value(&key).expect("value saved to keyring");
         assert_eq!(serde_json::from_str::<StoredOAuthTokens>(&stored)?, tokens);
@@ -738,11 +864,9 @@ mod tests {
             &tokens,
         )?;
 
-        let fallback_path = super::fallback_file_path()?;
+        let fallback_path = super::server_credentials_path(&key)?;
         assert!(fallback_path.exists(), "fallback file should be created");
-        let saved = super::read_fallback_file()?.expect("fallback file should load");
-        let key = super::compute_store_key(&tokens.server_name, &tokens.url)?;
-        let entry = saved.get(&key).expect("entry for key");
+        let entry = super::read_server_credentials(&key)?.expect("fallback file should load");
         assert_eq!(entry.server_name, tokens.server_name);
         assert_eq!(entry.server_url, tokens.url);
         assert_eq!(entry.client_id, tokens.client_id);
@@ -772,7 +896,7 @@ mod tests {
         )?;
         assert!(removed);
         assert!(!store.contains(&key));
-        assert!(!super::fallback_file_path()?.exists());
+        assert!(!super::server_credentials_path(&key)?.exists());
         Ok(())
     }
 
@@ -794,7 +918,7 @@ mod tests {
         )?;
         assert!(removed);
         assert!(!store.contains(&key));
-        assert!(!super::fallback_file_path()?.exists());
+        assert!(!super::server_credentials_path(&key)?.exists());
         Ok(())
     }
 
@@ -814,7 +938,50 @@ mod tests {
             &tokens.url,
         );
         assert!(result.is_err());
-        assert!(super::fallback_file_path().unwrap().exists());
+        assert!(super::server_credentials_path(&key)?.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_credentials_file_is_read_and_migrated_on_save() -> Result<()> {
+        let _env = TempCodexHome::new();
+        let tokens = sample_tokens();
+        let key = super::compute_store_key(&tokens.server_name, &tokens.url)?;
+        let legacy_entry = FallbackTokenEntry {
+            server_name: tokens.server_name.clone(),
+            server_url: tokens.url.clone(),
+            client_id: tokens.client_id.clone(),
+            access_token: "access-token".to_string(),
+            expires_at: tokens.expires_at,
+            refresh_token: Some("refresh-token".to_string()),
+            scopes: vec!["scope-a".to_string(), "scope-b".to_string()],
+        };
+        super::write_legacy_fallback_file(&FallbackFile::from([(key.clone(), legacy_entry)]))?;
+
+        let loaded = super::load_oauth_tokens_from_file(&tokens.server_name, &tokens.url)?
+            .expect("tokens should load from the legacy file");
+        assert_tokens_match_without_expiry(&loaded, &tokens);
+
+        super::save_oauth_tokens_to_file(&loaded)?;
+        assert!(!super::legacy_fallback_file_path()?.exists());
+        assert!(super::server_credentials_path(&key)?.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn server_credentials_paths_are_per_server() -> Result<()> {
+        let _env = TempCodexHome::new();
+        let path = super::server_credentials_path("docs/server|0123456789abcdef")?;
+        assert_eq!(
+            path.file_name().and_then(|name| name.to_str()),
+            Some("docs-server-0123456789abcdef.json")
+        );
+        assert_eq!(
+            path.parent()
+                .and_then(Path::file_name)
+                .and_then(|name| name.to_str()),
+            Some(MCP_AUTH_DIR)
+        );
         Ok(())
     }
 
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::ffi::OsString;
 use std::io;
 use std::path::PathBuf;
@@ -8,12 +9,19 @@ use std::time::Duration;
 
 use anyhow::Result;
 use anyhow::anyhow;
+use codex_client::apply_network_settings;
 use futures::FutureExt;
 use futures::future::BoxFuture;
 use mcp_types::CallToolRequestParams;
 use mcp_types::CallToolResult;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::InitializeRequestParams;
 use mcp_types::InitializeResult;
+use mcp_types::ListPromptsRequestParams;
+use mcp_types::ListPromptsResult;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
@@ -23,6 +31,7 @@ use mcp_types::ListToolsResult;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
+use mcp_types::SubscribeRequestParams;
 use reqwest::header::HeaderMap;
 use rmcp::model::CallToolRequestParam;
 use rmcp::model::ClientNotification;
@@ -32,10 +41,13 @@ use rmcp::model::CreateElicitationResult;
 use rmcp::model::CustomNotification;
 use rmcp::model::CustomRequest;
 use rmcp::model::Extensions;
+use rmcp::model::GetPromptRequestParam;
 use rmcp::model::InitializeRequestParam;
 use rmcp::model::PaginatedRequestParam;
+use rmcp::model::PingRequest;
 use rmcp::model::ReadResourceRequestParam;
 use rmcp::model::ServerResult;
+use rmcp::model::SubscribeRequestParam;
 use rmcp::service::RoleClient;
 use rmcp::service::RunningService;
 use rmcp::service::{self};
@@ -43,6 +55,7 @@ use rmcp::transport::StreamableHttpClientTransport;
 use rmcp::transport::auth::AuthClient;
 use rmcp::transport::auth::OAuthState;
 use rmcp::transport::child_process::TokioChildProcess;
+use rmcp::transport::common::client_side_sse::ExponentialBackoff;
 use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::BufReader;
@@ -66,6 +79,13 @@ use crate::utils::convert_to_rmcp;
 use crate::utils::create_env_for_mcp_server;
 use crate::utils::run_with_timeout;
 
+/// How often a dropped SSE stream of a streamable HTTP server is reopened
+/// (resuming from the last event id) before the connection counts as closed.
+const SSE_RECONNECT_MAX_ATTEMPTS: usize = 5;
+
+/// First delay between SSE reconnect attempts; doubles on each attempt.
+const SSE_RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+
 enum PendingTransport {
     ChildProcess(TokioChildProcess),
     StreamableHttp {
@@ -95,10 +115,21 @@ pub type SendElicitation = Box<
     dyn Fn(RequestId, Elicitation) -> BoxFuture<'static, Result<ElicitationResponse>> + Send + Sync,
 >;
 
+/// Interface for serving `sampling/createMessage` requests by running a model
+/// completion on behalf of the server.
+pub type SendSampling = Box<
+    dyn Fn(RequestId, CreateMessageRequestParams) -> BoxFuture<'static, Result<CreateMessageResult>>
+        + Send
+        + Sync,
+>;
+
 /// MCP client implemented on top of the official `rmcp` SDK.
 /// https://github.com/modelcontextprotocol/rust-sdk
 pub struct RmcpClient {
     state: Mutex<ClientState>,
+    /// URIs the server reported through `notifications/resources/updated`
+    /// since the last [`RmcpClient::take_updated_resources`].
+    updated_resources: Arc<std::sync::Mutex<HashSet<String>>>,
 }
 
 impl RmcpClient {
@@ -155,6 +186,7 @@ impl RmcpClient {
             state: Mutex::new(ClientState::Connecting {
                 transport: Some(PendingTransport::ChildProcess(transport)),
             }),
+            updated_resources: Arc::default(),
         })
     }
 
@@ -194,13 +226,16 @@ impl RmcpClient {
                 oauth_persistor,
             }
         } else {
-            let mut http_config = StreamableHttpClientTransportConfig::with_uri(url.to_string());
+            let mut http_config = http_transport_config(url);
             if let Some(bearer_token) = bearer_token.clone() {
                 http_config = http_config.auth_header(bearer_token);
             }
 
-            let http_client =
-                apply_default_headers(reqwest::Client::builder(), &default_headers).build()?;
+            let http_client = apply_default_headers(
+                apply_network_settings(reqwest::Client::builder()),
+                &default_headers,
+            )
+            .build()?;
 
             let transport = StreamableHttpClientTransport::with_client(http_client, http_config);
             PendingTransport::StreamableHttp { transport }
@@ -209,6 +244,7 @@ impl RmcpClient {
             state: Mutex::new(ClientState::Connecting {
                 transport: Some(transport),
             }),
+            updated_resources: Arc::default(),
         })
     }
 
@@ -219,9 +255,28 @@ impl RmcpClient {
         params: InitializeRequestParams,
         timeout: Option<Duration>,
         send_elicitation: SendElicitation,
+    ) -> Result<InitializeResult> {
+        self.initialize_with_sampling(params, timeout, send_elicitation, None)
+            .await
+    }
+
+    /// Like [`RmcpClient::initialize`], but also serves the server's
+    /// `sampling/createMessage` requests with `send_sampling`. Callers should
+    /// advertise the `sampling` capability in `params` when passing a handler.
+    pub async fn initialize_with_sampling(
+        &self,
+        params: InitializeRequestParams,
+        timeout: Option<Duration>,
+        send_elicitation: SendElicitation,
+        send_sampling: Option<SendSampling>,
     ) -> Result<InitializeResult> {
         let rmcp_params: InitializeRequestParam = convert_to_rmcp(params.clone())?;
-        let client_handler = LoggingClientHandler::new(rmcp_params, send_elicitation);
+        let client_handler = LoggingClientHandler::new(
+            rmcp_params,
+            send_elicitation,
+            send_sampling,
+            Arc::clone(&self.updated_resources),
+        );
 
         let (transport, oauth_persistor) = {
             let mut guard = self.state.lock().await;
@@ -350,6 +405,63 @@ impl RmcpClient {
         Ok(converted)
     }
 
+    pub async fn list_prompts(
+        &self,
+        params: Option<ListPromptsRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<ListPromptsResult> {
+        self.refresh_oauth_if_needed().await;
+        let service = self.service().await?;
+        let rmcp_params = params
+            .map(convert_to_rmcp::<_, PaginatedRequestParam>)
+            .transpose()?;
+
+        let fut = service.list_prompts(rmcp_params);
+        let result = run_with_timeout(fut, timeout, "prompts/list").await?;
+        let converted = convert_to_mcp(result)?;
+        self.persist_oauth_tokens().await;
+        Ok(converted)
+    }
+
+    pub async fn get_prompt(
+        &self,
+        params: GetPromptRequestParams,
+        timeout: Option<Duration>,
+    ) -> Result<GetPromptResult> {
+        self.refresh_oauth_if_needed().await;
+        let service = self.service().await?;
+        let rmcp_params: GetPromptRequestParam = convert_to_rmcp(params)?;
+        let fut = service.get_prompt(rmcp_params);
+        let result = run_with_timeout(fut, timeout, "prompts/get").await?;
+        let converted = convert_to_mcp(result)?;
+        self.persist_oauth_tokens().await;
+        Ok(converted)
+    }
+
+    /// Asks the server to send `notifications/resources/updated` for `uri`.
+    pub async fn subscribe_resource(
+        &self,
+        params: SubscribeRequestParams,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        self.refresh_oauth_if_needed().await;
+        let service = self.service().await?;
+        let rmcp_params: SubscribeRequestParam = convert_to_rmcp(params)?;
+        let fut = service.subscribe(rmcp_params);
+        run_with_timeout(fut, timeout, "resources/subscribe").await?;
+        self.persist_oauth_tokens().await;
+        Ok(())
+    }
+
+    /// Returns the resource URIs the server reported as updated since the
+    /// previous call.
+    pub fn take_updated_resources(&self) -> HashSet<String> {
+        match self.updated_resources.lock() {
+            Ok(mut updated) => std::mem::take(&mut *updated),
+            Err(err) => std::mem::take(&mut *err.into_inner()),
+        }
+    }
+
     pub async fn call_tool(
         &self,
         name: String,
@@ -383,6 +495,15 @@ impl RmcpClient {
         Ok(())
     }
 
+    /// Sends `ping` and waits for the server to answer, used to check that a
+    /// running server is still responsive.
+    pub async fn ping(&self, timeout: Option<Duration>) -> Result<()> {
+        let service = self.service().await?;
+        let fut = service.send_request(ClientRequest::PingRequest(PingRequest::default()));
+        run_with_timeout(fut, timeout, "ping").await?;
+        Ok(())
+    }
+
     pub async fn send_custom_request(
         &self,
         method: &str,
@@ -435,6 +556,19 @@ impl RmcpClient {
     }
 }
 
+/// Streamable HTTP settings shared by the plain and OAuth transports. The
+/// session id the server assigns on `initialize` is sent with every request;
+/// when the SSE stream drops, it is reopened with `Last-Event-ID` so events
+/// sent in the meantime are replayed.
+fn http_transport_config(url: &str) -> StreamableHttpClientTransportConfig {
+    let mut config = StreamableHttpClientTransportConfig::with_uri(url.to_string());
+    config.retry_config = Arc::new(ExponentialBackoff {
+        max_times: Some(SSE_RECONNECT_MAX_ATTEMPTS),
+        base_duration: SSE_RECONNECT_BASE_DELAY,
+    });
+    config
+}
+
 async fn create_oauth_transport_and_runtime(
     server_name: &str,
     url: &str,
@@ -445,8 +579,11 @@ async fn create_oauth_transport_and_runtime(
     StreamableHttpClientTransport<AuthClient<reqwest::Client>>,
     OAuthPersistor,
 )> {
-    let http_client =
-        apply_default_headers(reqwest::Client::builder(), &default_headers).build()?;
+    let http_client = apply_default_headers(
+        apply_network_settings(reqwest::Client::builder()),
+        &default_headers,
+    )
+    .build()?;
     let mut oauth_state = OAuthState::new(url.to_string(), Some(http_client.clone())).await?;
 
     oauth_state
@@ -467,10 +604,8 @@ async fn create_oauth_transport_and_runtime(
     let auth_client = AuthClient::new(http_client, manager);
     let auth_manager = auth_client.auth_manager.clone();
 
-    let transport = StreamableHttpClientTransport::with_client(
-        auth_client,
-        StreamableHttpClientTransportConfig::with_uri(url.to_string()),
-    );
+    let transport =
+        StreamableHttpClientTransport::with_client(auth_client, http_transport_config(url));
 
     let runtime = OAuthPersistor::new(
         server_name.to_string(),
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::env;
+use std::fmt;
 use std::time::Duration;
 
 use anyhow::Context;
@@ -27,12 +28,47 @@ where
         let result = time::timeout(duration, fut)
             .await
             .with_context(|| anyhow!("timed out awaiting {label} after {duration:?}"))?;
-        result.map_err(|err| anyhow!("{label} failed: {err}"))
+        result.map_err(|err| service_error(label, err))
     } else {
-        fut.await.map_err(|err| anyhow!("{label} failed: {err}"))
+        fut.await.map_err(|err| service_error(label, err))
     }
 }
 
+/// The connection to the MCP server is gone: the process exited, or the
+/// server ended the HTTP session. Requests on this client will keep failing
+/// until it is replaced.
+#[derive(Debug)]
+struct TransportClosed {
+    label: String,
+}
+
+impl fmt::Display for TransportClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} failed: the connection to the MCP server closed",
+            self.label
+        )
+    }
+}
+
+impl std::error::Error for TransportClosed {}
+
+fn service_error(label: &str, err: ServiceError) -> anyhow::Error {
+    match err {
+        ServiceError::TransportClosed => anyhow::Error::new(TransportClosed {
+            label: label.to_string(),
+        }),
+        err => anyhow!("{label} failed: {err}"),
+    }
+}
+
+/// Whether `err` means the client lost its connection and has to be
+/// restarted before it can serve requests again.
+pub fn is_transport_closed(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<TransportClosed>())
+}
+
 pub(crate) fn convert_call_tool_result(result: RmcpCallToolResult) -> Result<CallToolResult> {
     let mut value = serde_json::to_value(result)?;
     if let Some(obj) = value.as_object_mut()
@@ -211,6 +247,14 @@ mod tests {
     use serial_test::serial;
     use std::ffi::OsString;
 
+    #[test]
+    fn detects_closed_transport_through_context() {
+        let closed = service_error("tools/call", ServiceError::TransportClosed)
+            .context("tool call failed for `docs/search`");
+        assert!(is_transport_closed(&closed));
+        assert!(!is_transport_closed(&anyhow!("tools/call failed: boom")));
+    }
+
     struct EnvVarGuard {
         key: String,
         original: Option<OsString>,
@@ -10,6 +10,7 @@ use crate::bottom_pane::ApprovalRequest;
 use crate::chatwidget::ChatWidget;
 use crate::chatwidget::ExternalEditorState;
 use crate::diff_render::DiffSummary;
+use crate::diff_viewer::parse_git_diff;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::external_editor;
 use crate::file_search::FileSearchManager;
@@ -17,16 +18,30 @@ use crate::history_cell;
 use crate::history_cell::HistoryCell;
 #[cfg(not(debug_assertions))]
 use crate::history_cell::UpdateAvailableHistoryCell;
+use crate::keymap::KeyAction;
+use crate::keymap::keymap;
 use crate::model_migration::ModelMigrationOutcome;
 use crate::model_migration::migration_copy_for_models;
 use crate::model_migration::run_model_migration_prompt;
 use crate::pager_overlay::Overlay;
+use crate::pager_overlay::transcript_plain_text;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::Renderable;
 use crate::resume_picker::SessionSelection;
+use crate::tabs::ParkedTab;
+use crate::tabs::SessionTabs;
+use crate::tabs::TabCommand;
+use crate::tabs::TabId;
+use crate::tabs::TabLabel;
+use crate::tabs::tab_bar_line;
+use crate::tabs::tab_divider_line;
+use crate::tabs::tab_list_lines;
+use crate::theme;
 use crate::tui;
 use crate::tui::TuiEvent;
 use crate::update_action::UpdateAction;
+use crate::voice;
+use crate::voice::VoiceInput;
 use codex_ansi_escape::ansi_escape_line;
 use codex_core::AuthManager;
 use codex_core::CodexAuth;
@@ -36,6 +51,7 @@ use codex_core::config::edit::ConfigEdit;
 use codex_core::config::edit::ConfigEditsBuilder;
 #[cfg(target_os = "windows")]
 use codex_core::features::Feature;
+use codex_core::git_info::get_git_repo_root;
 use codex_core::models_manager::manager::RefreshStrategy;
 use codex_core::models_manager::model_presets::HIDE_GPT_5_1_CODEX_MAX_MIGRATION_PROMPT_CONFIG;
 use codex_core::models_manager::model_presets::HIDE_GPT5_1_MIGRATION_PROMPT_CONFIG;
@@ -58,6 +74,7 @@ use color_eyre::eyre::WrapErr;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use ratatui::layout::Rect;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::widgets::Paragraph;
@@ -374,6 +391,13 @@ pub(crate) struct App {
     external_approval_routes: HashMap<String, (ThreadId, String)>,
     /// Buffered Codex events while external approvals are pending.
     paused_codex_events: VecDeque<Event>,
+
+    /// Push-to-talk recording state (`tui.voice`).
+    voice: VoiceInput,
+
+    /// Session tabs. `chat_widget` and `transcript_cells` belong to the
+    /// active one; the others are parked here.
+    tabs: SessionTabs,
 }
 
 #[derive(Default)]
@@ -392,7 +416,7 @@ impl App {
         crate::chatwidget::ChatWidgetInit {
             config: cfg,
             frame_requester: tui.frame_requester(),
-            app_event_tx: self.app_event_tx.clone(),
+            app_event_tx: self.app_event_tx.for_tab(self.tabs.active()),
             // Fork/resume bootstraps here don't carry any prefilled message content.
             initial_user_message: None,
             enhanced_keys_supported: self.enhanced_keys_supported,
@@ -415,6 +439,202 @@ impl App {
         }
     }
 
+    /// `self.config` with the active tab's working directory, for sessions
+    /// that start in the active tab.
+    fn tab_config(&self) -> Config {
+        let mut config = self.config.clone();
+        config.cwd = self.chat_widget.config_ref().cwd.clone();
+        config
+    }
+
+    fn start_commit_animation(&self) {
+        if self
+            .commit_anim_running
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            let tx = self.app_event_tx.clone();
+            let running = self.commit_anim_running.clone();
+            thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(50));
+                    tx.send(AppEvent::CommitTick);
+                }
+            });
+        }
+    }
+
+    fn tab_labels(&self) -> Vec<TabLabel> {
+        self.tabs
+            .order()
+            .iter()
+            .filter_map(|tab| {
+                if self.tabs.is_active(*tab) {
+                    return Some(TabLabel {
+                        cwd: self.chat_widget.config_ref().cwd.clone(),
+                        active: true,
+                        running: self.chat_widget.is_task_running(),
+                        unread: false,
+                        needs_attention: false,
+                    });
+                }
+                let parked = self.tabs.parked(*tab)?;
+                Some(TabLabel {
+                    cwd: parked.chat_widget.config_ref().cwd.clone(),
+                    active: false,
+                    running: parked.chat_widget.is_task_running(),
+                    unread: parked.unread,
+                    needs_attention: parked.needs_attention,
+                })
+            })
+            .collect()
+    }
+
+    /// Handles an event from a tab that is not on screen. Its thread's events
+    /// and history cells go to the parked widget and transcript; requests to
+    /// open views are dropped since the tab cannot show them.
+    fn handle_parked_tab_event(&mut self, tab: TabId, event: AppEvent) {
+        if matches!(event, AppEvent::StartCommitAnimation) {
+            self.start_commit_animation();
+            return;
+        }
+        // A closed tab's thread may still send events while it shuts down.
+        let Some(parked) = self.tabs.parked_mut(tab) else {
+            return;
+        };
+        match event {
+            AppEvent::CodexEvent(event) => {
+                if matches!(
+                    event.msg,
+                    EventMsg::ExecApprovalRequest(_)
+                        | EventMsg::ApplyPatchApprovalRequest(_)
+                        | EventMsg::ElicitationRequest(_)
+                        | EventMsg::RequestUserInput(_)
+                ) {
+                    parked.needs_attention = true;
+                }
+                parked.chat_widget.handle_codex_event(event);
+            }
+            AppEvent::InsertHistoryCell(cell) => {
+                parked.transcript_cells.push(cell.into());
+                parked.unread = true;
+            }
+            AppEvent::CodexOp(op) => parked.chat_widget.submit_op(op),
+            _ => {}
+        }
+    }
+
+    async fn handle_tab_command(&mut self, tui: &mut tui::Tui, command: TabCommand) {
+        if command != TabCommand::List && !self.external_approval_routes.is_empty() {
+            self.chat_widget.add_error_message(
+                "Answer the pending subagent approval before switching tabs.".to_string(),
+            );
+            return;
+        }
+        match command {
+            TabCommand::List => {
+                let lines = tab_list_lines(&self.tab_labels());
+                self.chat_widget.add_plain_history_lines(lines);
+            }
+            TabCommand::Open(cwd) => self.open_tab(tui, cwd),
+            TabCommand::Select(number) => match self.tabs.by_number(number) {
+                Some(tab) => self.switch_tab(tui, tab),
+                None => self
+                    .chat_widget
+                    .add_error_message(format!("There is no tab {number}.")),
+            },
+            TabCommand::Next => self.switch_tab(tui, self.tabs.neighbor(1)),
+            TabCommand::Previous => self.switch_tab(tui, self.tabs.neighbor(-1)),
+            TabCommand::Close => self.close_tab(tui).await,
+        }
+    }
+
+    /// Opens a tab with a new session and switches to it. A relative `cwd` is
+    /// resolved against the active tab's directory.
+    fn open_tab(&mut self, tui: &mut tui::Tui, cwd: Option<PathBuf>) {
+        let mut config = self.tab_config();
+        if let Some(cwd) = cwd {
+            match dunce::canonicalize(config.cwd.join(&cwd)) {
+                Ok(dir) if dir.is_dir() => config.cwd = dir,
+                _ => {
+                    let cwd = cwd.display();
+                    self.chat_widget
+                        .add_error_message(format!("Not a directory: {cwd}"));
+                    return;
+                }
+            }
+        }
+        let tab = self.tabs.open();
+        let init = crate::chatwidget::ChatWidgetInit {
+            config,
+            frame_requester: tui.frame_requester(),
+            app_event_tx: self.app_event_tx.for_tab(tab),
+            initial_user_message: None,
+            enhanced_keys_supported: self.enhanced_keys_supported,
+            auth_manager: self.auth_manager.clone(),
+            models_manager: self.server.get_models_manager(),
+            feedback: self.feedback.clone(),
+            is_first_run: false,
+            model: Some(self.chat_widget.current_model().to_string()),
+            otel_manager: self.otel_manager.clone(),
+        };
+        let chat_widget = ChatWidget::new(init, self.server.clone());
+        self.tabs.park(tab, ParkedTab::new(chat_widget));
+        self.switch_tab(tui, tab);
+    }
+
+    /// Parks the active tab and shows `target` in its place, reprinting its
+    /// transcript below a divider.
+    fn switch_tab(&mut self, tui: &mut tui::Tui, target: TabId) {
+        let Some(mut tab) = self.tabs.unpark(target) else {
+            return;
+        };
+        self.reset_backtrack_state();
+        let previous = self.tabs.active();
+        std::mem::swap(&mut self.chat_widget, &mut tab.chat_widget);
+        std::mem::swap(&mut self.transcript_cells, &mut tab.transcript_cells);
+        tab.unread = false;
+        tab.needs_attention = false;
+        self.tabs.park(previous, tab);
+        self.tabs.set_active(target);
+
+        let cwd = self.chat_widget.config_ref().cwd.clone();
+        self.file_search = FileSearchManager::new(cwd.clone(), self.app_event_tx.clone());
+        let width = tui.terminal.last_known_screen_size.width;
+        tui.insert_history_lines(vec![
+            Line::from(""),
+            tab_divider_line(self.tabs.active_index() + 1, &cwd),
+        ]);
+        for cell in &self.transcript_cells {
+            let mut display = cell.display_lines(width);
+            if !display.is_empty() && !cell.is_stream_continuation() {
+                display.insert(0, Line::from(""));
+            }
+            tui.insert_history_lines(display);
+        }
+        self.has_emitted_history_lines = true;
+        tui.frame_requester().schedule_frame();
+    }
+
+    /// Closes the active tab, shutting its thread down, and shows the tab
+    /// before it.
+    async fn close_tab(&mut self, tui: &mut tui::Tui) {
+        if self.tabs.len() == 1 {
+            self.chat_widget
+                .add_error_message("This is the only tab; use /quit to exit.".to_string());
+            return;
+        }
+        let closing = self.tabs.active();
+        self.switch_tab(tui, self.tabs.neighbor(-1));
+        let Some(mut tab) = self.tabs.remove(closing) else {
+            return;
+        };
+        if let Some(thread_id) = tab.chat_widget.thread_id() {
+            tab.chat_widget.submit_op(Op::Shutdown);
+            self.server.remove_thread(&thread_id).await;
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn run(
         tui: &mut tui::Tui,
@@ -480,12 +700,13 @@ impl App {
         );
 
         let enhanced_keys_supported = tui.enhanced_keys_supported();
+        let tabs = SessionTabs::new();
         let mut chat_widget = match session_selection {
             SessionSelection::StartFresh | SessionSelection::Exit => {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
+                    app_event_tx: app_event_tx.for_tab(tabs.active()),
                     initial_user_message: crate::chatwidget::create_initial_user_message(
                         initial_prompt.clone(),
                         initial_images.clone(),
@@ -513,7 +734,7 @@ impl App {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
+                    app_event_tx: app_event_tx.for_tab(tabs.active()),
                     initial_user_message: crate::chatwidget::create_initial_user_message(
                         initial_prompt.clone(),
                         initial_images.clone(),
@@ -541,7 +762,7 @@ impl App {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
+                    app_event_tx: app_event_tx.for_tab(tabs.active()),
                     initial_user_message: crate::chatwidget::create_initial_user_message(
                         initial_prompt.clone(),
                         initial_images.clone(),
@@ -589,6 +810,8 @@ impl App {
             windows_sandbox: WindowsSandboxState::default(),
             external_approval_routes: HashMap::new(),
             paused_codex_events: VecDeque::new(),
+            voice: VoiceInput::default(),
+            tabs,
         };
 
         // On startup, if Agent mode (workspace-write) or ReadOnly is active, warn about world-writable dirs on Windows.
@@ -708,21 +931,37 @@ impl App {
                         self.render_transcript_once(tui);
                     }
                     self.chat_widget.maybe_post_pending_notification(tui);
+                    for tab in self.tabs.all_parked_mut() {
+                        tab.chat_widget.maybe_post_pending_notification(tui);
+                    }
                     if self
                         .chat_widget
                         .handle_paste_burst_tick(tui.frame_requester())
                     {
                         return Ok(AppRunControl::Continue);
                     }
-                    tui.draw(
-                        self.chat_widget.desired_height(tui.terminal.size()?.width),
-                        |frame| {
-                            self.chat_widget.render(frame.area(), frame.buffer);
-                            if let Some((x, y)) = self.chat_widget.cursor_pos(frame.area()) {
-                                frame.set_cursor_position((x, y));
-                            }
-                        },
-                    )?;
+                    let size = tui.terminal.size()?;
+                    let tab_bar = (self.tabs.len() > 1).then(|| tab_bar_line(&self.tab_labels()));
+                    let tab_bar_height = u16::from(tab_bar.is_some());
+                    self.chat_widget
+                        .sync_terminal_size(size.height.saturating_sub(tab_bar_height), size.width);
+                    let height = self.chat_widget.desired_height(size.width) + tab_bar_height;
+                    tui.draw(height, |frame| {
+                        let mut area = frame.area();
+                        if let Some(tab_bar) = &tab_bar {
+                            let bar = Rect {
+                                height: tab_bar_height.min(area.height),
+                                ..area
+                            };
+                            tab_bar.render(bar, frame.buffer);
+                            area.y += bar.height;
+                            area.height -= bar.height;
+                        }
+                        self.chat_widget.render(area, frame.buffer);
+                        if let Some((x, y)) = self.chat_widget.cursor_pos(area) {
+                            frame.set_cursor_position((x, y));
+                        }
+                    })?;
                     if self.chat_widget.external_editor_state() == ExternalEditorState::Requested {
                         self.chat_widget
                             .set_external_editor_state(ExternalEditorState::Active);
@@ -735,19 +974,35 @@ impl App {
     }
 
     async fn handle_event(&mut self, tui: &mut tui::Tui, event: AppEvent) -> Result<AppRunControl> {
+        let event = match event {
+            AppEvent::TabEvent { tab, event } if !self.tabs.is_active(tab) => {
+                self.handle_parked_tab_event(tab, *event);
+                return Ok(AppRunControl::Continue);
+            }
+            AppEvent::TabEvent { event, .. } => *event,
+            event => event,
+        };
         match event {
+            // Unwrapped above; senders never tag an event twice.
+            AppEvent::TabEvent { .. } => {}
+            AppEvent::Tab(command) => {
+                self.handle_tab_command(tui, command).await;
+            }
             AppEvent::NewSession => {
                 let model = self.chat_widget.current_model().to_string();
                 let summary =
                     session_summary(self.chat_widget.token_usage(), self.chat_widget.thread_id());
                 self.shutdown_current_thread().await;
-                if let Err(err) = self.server.remove_and_close_all_threads().await {
+                // Threads of other tabs keep running.
+                if self.tabs.len() == 1
+                    && let Err(err) = self.server.remove_and_close_all_threads().await
+                {
                     tracing::warn!(error = %err, "failed to close all threads");
                 }
                 let init = crate::chatwidget::ChatWidgetInit {
-                    config: self.config.clone(),
+                    config: self.tab_config(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: self.app_event_tx.clone(),
+                    app_event_tx: self.app_event_tx.for_tab(self.tabs.active()),
                     // New sessions start without prefilled message content.
                     initial_user_message: None,
                     enhanced_keys_supported: self.enhanced_keys_supported,
@@ -832,20 +1087,24 @@ impl App {
                 // Leaving alt-screen may blank the inline viewport; force a redraw either way.
                 tui.frame_requester().schedule_frame();
             }
-            AppEvent::ForkCurrentSession => {
+            AppEvent::ForkCurrentSession { num_turns } => {
                 let summary =
                     session_summary(self.chat_widget.token_usage(), self.chat_widget.thread_id());
                 if let Some(path) = self.chat_widget.rollout_path() {
                     match self
                         .server
-                        .fork_thread(usize::MAX, self.config.clone(), path.clone())
+                        .fork_thread(
+                            num_turns.map_or(usize::MAX, |n| n as usize),
+                            self.tab_config(),
+                            path.clone(),
+                        )
                         .await
                     {
                         Ok(forked) => {
                             self.shutdown_current_thread().await;
                             let init = self.chatwidget_init_for_forked_or_resumed_thread(
                                 tui,
-                                self.config.clone(),
+                                self.tab_config(),
                             );
                             self.chat_widget = ChatWidget::new_from_existing(
                                 init,
@@ -906,26 +1165,16 @@ impl App {
                 }
             }
             AppEvent::StartCommitAnimation => {
-                if self
-                    .commit_anim_running
-                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
-                    .is_ok()
-                {
-                    let tx = self.app_event_tx.clone();
-                    let running = self.commit_anim_running.clone();
-                    thread::spawn(move || {
-                        while running.load(Ordering::Relaxed) {
-                            thread::sleep(Duration::from_millis(50));
-                            tx.send(AppEvent::CommitTick);
-                        }
-                    });
-                }
+                self.start_commit_animation();
             }
             AppEvent::StopCommitAnimation => {
                 self.commit_anim_running.store(false, Ordering::Release);
             }
             AppEvent::CommitTick => {
                 self.chat_widget.on_commit_tick();
+                for tab in self.tabs.all_parked_mut() {
+                    tab.chat_widget.on_commit_tick();
+                }
             }
             AppEvent::CodexEvent(event) => {
                 if !self.external_approval_routes.is_empty() {
@@ -942,7 +1191,12 @@ impl App {
                 self.handle_external_approval_request(thread_id, event);
             }
             AppEvent::Exit(mode) => match mode {
-                ExitMode::ShutdownFirst => self.chat_widget.submit_op(Op::Shutdown),
+                ExitMode::ShutdownFirst => {
+                    for tab in self.tabs.all_parked_mut() {
+                        tab.chat_widget.submit_op(Op::Shutdown);
+                    }
+                    self.chat_widget.submit_op(Op::Shutdown);
+                }
                 ExitMode::Immediate => {
                     return Ok(AppRunControl::Exit(ExitReason::UserRequested));
                 }
@@ -1016,20 +1270,104 @@ impl App {
                 // Standard path where this is not an external approval response.
                 _ => self.chat_widget.submit_op(op),
             },
+            AppEvent::CopyToClipboard { what, text } => {
+                self.chat_widget.copy_text(&what, text);
+            }
             AppEvent::DiffResult(text) => {
                 // Clear the in-progress state in the bottom pane
                 self.chat_widget.on_diff_complete();
                 // Enter alternate screen using TUI helper and build pager lines
                 let _ = tui.enter_alt_screen();
-                let pager_lines: Vec<ratatui::text::Line<'static>> = if text.trim().is_empty() {
-                    vec!["No changes detected.".italic().into()]
+                let files = parse_git_diff(&text);
+                if files.is_empty() {
+                    // Not a repository, a git error, or nothing changed.
+                    let pager_lines: Vec<ratatui::text::Line<'static>> = if text.trim().is_empty() {
+                        vec!["No changes detected.".italic().into()]
+                    } else {
+                        text.lines().map(ansi_escape_line).collect()
+                    };
+                    self.overlay = Some(Overlay::new_static_with_lines(
+                        pager_lines,
+                        "D I F F".to_string(),
+                    ));
                 } else {
-                    text.lines().map(ansi_escape_line).collect()
-                };
-                self.overlay = Some(Overlay::new_static_with_lines(
-                    pager_lines,
-                    "D I F F".to_string(),
-                ));
+                    let cwd = self.chat_widget.config_ref().cwd.clone();
+                    let repo_root = get_git_repo_root(&cwd).unwrap_or(cwd);
+                    self.overlay = Some(Overlay::new_diff_viewer(
+                        files,
+                        repo_root,
+                        self.app_event_tx.clone(),
+                    ));
+                }
+                tui.frame_requester().schedule_frame();
+            }
+            AppEvent::OpenTranscriptSearch(query) => {
+                if self.overlay.is_none() {
+                    self.open_transcript_search(tui, query);
+                }
+            }
+            AppEvent::OpenTranscriptSelection => {
+                if self.overlay.is_none() {
+                    self.open_transcript_selection(tui);
+                }
+            }
+            AppEvent::SaveTranscript(path) => {
+                let path = self.chat_widget.config_ref().cwd.join(path);
+                let text = transcript_plain_text(&self.transcript_cells);
+                match std::fs::write(&path, format!("{text}\n")) {
+                    Ok(()) => self.chat_widget.add_info_message(
+                        format!("Saved the transcript to {}.", path.display()),
+                        None,
+                    ),
+                    Err(err) => self.chat_widget.add_error_message(format!(
+                        "Failed to save the transcript to {}: {err}",
+                        path.display()
+                    )),
+                }
+            }
+            AppEvent::OpenHunkReview(files) => {
+                if self.overlay.is_some() {
+                    self.chat_widget.add_info_message(
+                        "The patch was applied.".to_string(),
+                        Some("Run /hunks to review its hunks.".to_string()),
+                    );
+                } else {
+                    let _ = tui.enter_alt_screen();
+                    self.overlay = Some(Overlay::new_hunk_review(files, self.app_event_tx.clone()));
+                    tui.frame_requester().schedule_frame();
+                }
+            }
+            AppEvent::HunkReviewFinished(files) => {
+                self.chat_widget.finish_hunk_review(&files);
+            }
+            AppEvent::ReviewHunksAfterApply(call_id) => {
+                self.chat_widget.review_hunks_after_apply(call_id);
+            }
+            AppEvent::SetTheme(name) => match theme::set_theme(&name) {
+                Ok(name) => {
+                    // Cells compare equal before and after a theme change, so
+                    // only a full repaint picks up the new colors.
+                    tui.terminal.clear()?;
+                    self.chat_widget.add_info_message(
+                        format!("Theme set to {name}."),
+                        Some("Set `theme` under [tui] in config.toml to keep it.".to_string()),
+                    );
+                    tui.frame_requester().schedule_frame();
+                }
+                Err(err) => self.chat_widget.add_error_message(err.to_string()),
+            },
+            AppEvent::ContextInspection(inspection) => {
+                match &mut self.overlay {
+                    Some(Overlay::ContextInspector(inspector)) => inspector.update(inspection),
+                    Some(_) => {}
+                    None => {
+                        let _ = tui.enter_alt_screen();
+                        self.overlay = Some(Overlay::new_context_inspector(
+                            inspection,
+                            self.app_event_tx.clone(),
+                        ));
+                    }
+                }
                 tui.frame_requester().schedule_frame();
             }
             AppEvent::StartFileSearch(query) => {
@@ -1040,6 +1378,9 @@ impl App {
             AppEvent::FileSearchResult { query, matches } => {
                 self.chat_widget.apply_file_search_result(query, matches);
             }
+            AppEvent::SymbolSearchResult { query, matches } => {
+                self.chat_widget.apply_symbol_search_result(query, matches);
+            }
             AppEvent::RateLimitSnapshotFetched(snapshot) => {
                 self.chat_widget.on_rate_limit_snapshot(Some(snapshot));
             }
@@ -1094,6 +1435,21 @@ impl App {
                     self.launch_external_editor(tui).await;
                 }
             }
+            AppEvent::VoiceTranscribed(result) => {
+                self.voice = VoiceInput::Idle;
+                self.chat_widget.set_footer_hint_override(None);
+                match result {
+                    Ok(text) if text.is_empty() => {
+                        self.chat_widget
+                            .add_info_message("No speech detected.".to_string(), None);
+                    }
+                    Ok(text) => self.chat_widget.insert_str(&text),
+                    Err(err) => self
+                        .chat_widget
+                        .add_error_message(format!("Voice input failed: {err}")),
+                }
+                tui.frame_requester().schedule_frame();
+            }
             AppEvent::OpenWindowsSandboxEnablePrompt { preset } => {
                 self.chat_widget.open_windows_sandbox_enable_prompt(preset);
             }
@@ -1520,6 +1876,48 @@ impl App {
             AppEvent::OpenReviewCustomPrompt => {
                 self.chat_widget.show_review_custom_prompt();
             }
+            AppEvent::OpenSubAgentPrompt(name) => {
+                self.chat_widget.show_subagent_prompt(name);
+            }
+            AppEvent::ResolveSubAgentMergeConflict { run_id } => {
+                self.chat_widget.resolve_subagent_merge_conflict(run_id);
+            }
+            AppEvent::OpenBackgroundTaskPrompt => {
+                self.chat_widget.show_background_task_prompt();
+            }
+            AppEvent::OpenMemoryEntry(entry) => {
+                self.chat_widget.show_memory_entry_actions(entry);
+            }
+            AppEvent::OpenMemoryEditPrompt(entry) => {
+                self.chat_widget.show_memory_edit_prompt(entry);
+            }
+            AppEvent::ClearQueuedUserMessages => {
+                self.chat_widget.clear_queued_user_messages();
+            }
+            AppEvent::EditQueuedUserMessage(text) => {
+                self.chat_widget.edit_queued_user_message(&text);
+            }
+            AppEvent::CopyLastReplyCode => {
+                self.chat_widget.copy_command("");
+            }
+            AppEvent::ConfirmSuggestedCommand(command) => {
+                self.chat_widget.confirm_suggested_command(command);
+            }
+            AppEvent::OpenFilesInEditor(paths) => {
+                self.open_files_in_editor(tui, &paths).await;
+            }
+            AppEvent::OpenReplyQuotePicker => {
+                self.chat_widget.open_reply_quote_picker();
+            }
+            AppEvent::InsertInComposer(text) => {
+                self.chat_widget.insert_in_composer(&text);
+            }
+            AppEvent::OpenFollowUpActions(suggestion) => {
+                self.chat_widget.open_follow_up_actions(suggestion);
+            }
+            AppEvent::UseFollowUp { prompt, queue } => {
+                self.chat_widget.use_follow_up(prompt, queue);
+            }
             AppEvent::ManageSkillsClosed => {
                 self.chat_widget.handle_manage_skills_closed();
             }
@@ -1558,6 +1956,20 @@ impl App {
                         "E L I C I T A T I O N".to_string(),
                     ));
                 }
+                ApprovalRequest::Plan { plan, .. } => {
+                    let _ = tui.enter_alt_screen();
+                    self.overlay = Some(Overlay::new_static_with_lines(
+                        history_cell::proposed_plan_lines(&plan),
+                        "P L A N".to_string(),
+                    ));
+                }
+                ApprovalRequest::McpToolCall { invocation, .. } => {
+                    let _ = tui.enter_alt_screen();
+                    self.overlay = Some(Overlay::new_static_with_lines(
+                        vec![history_cell::format_mcp_invocation(invocation)],
+                        "M C P".to_string(),
+                    ));
+                }
             },
         }
         Ok(AppRunControl::Continue)
@@ -1573,6 +1985,16 @@ impl App {
             let errors = errors_for_cwd(&cwd, response);
             emit_skill_load_warnings(&self.app_event_tx, &errors);
         }
+        if let Some(Overlay::ContextInspector(inspector)) = &mut self.overlay {
+            match &event.msg {
+                EventMsg::ContextCompacted(_) => {
+                    self.app_event_tx
+                        .send(AppEvent::CodexOp(Op::InspectContext));
+                }
+                EventMsg::Error(err) => inspector.set_status(err.message.clone()),
+                _ => {}
+            }
+        }
         self.handle_backtrack_event(&event.msg);
         self.chat_widget.handle_codex_event(event);
     }
@@ -1683,6 +2105,28 @@ impl App {
         self.chat_widget.set_reasoning_effort(effort);
     }
 
+    async fn open_files_in_editor(&mut self, tui: &mut tui::Tui, paths: &[PathBuf]) {
+        let result = match external_editor::resolve_editor_command() {
+            Ok(editor_cmd) => {
+                tui.with_restored(tui::RestoreMode::KeepRaw, || async {
+                    external_editor::open_files(paths, &editor_cmd).await
+                })
+                .await
+            }
+            Err(external_editor::EditorError::MissingEditor) => {
+                Err(color_eyre::eyre::Report::msg("set $VISUAL or $EDITOR"))
+            }
+            Err(err) => Err(err.into()),
+        };
+        if let Err(err) = result {
+            self.chat_widget
+                .add_to_history(history_cell::new_error_event(format!(
+                    "Failed to open files in the editor: {err}",
+                )));
+        }
+        tui.frame_requester().schedule_frame();
+    }
+
     async fn launch_external_editor(&mut self, tui: &mut tui::Tui) {
         let editor_cmd = match external_editor::resolve_editor_command() {
             Ok(cmd) => cmd,
@@ -1745,8 +2189,55 @@ impl App {
         tui.frame_requester().schedule_frame();
     }
 
+    fn start_voice_input(&mut self, tui: &mut tui::Tui) {
+        let Some(voice_config) = self.config.tui_voice.as_ref() else {
+            self.chat_widget.add_info_message(
+                "Voice input is not configured.".to_string(),
+                Some(
+                    "Add a [tui.voice] section to config.toml to enable push-to-talk.".to_string(),
+                ),
+            );
+            tui.frame_requester().schedule_frame();
+            return;
+        };
+        match self.voice.start(voice_config) {
+            Ok(()) => self.chat_widget.set_footer_hint_override(Some(vec![(
+                voice::RECORDING_HINT.to_string(),
+                String::new(),
+            )])),
+            Err(err) => self
+                .chat_widget
+                .add_error_message(format!("Failed to start recording: {err}")),
+        }
+        tui.frame_requester().schedule_frame();
+    }
+
+    fn stop_voice_input(&mut self, tui: &mut tui::Tui) {
+        let Some(voice_config) = self.config.tui_voice.clone() else {
+            return;
+        };
+        self.voice.stop(voice_config, self.app_event_tx.clone());
+        self.chat_widget.set_footer_hint_override(Some(vec![(
+            voice::TRANSCRIBING_HINT.to_string(),
+            String::new(),
+        )]));
+        tui.frame_requester().schedule_frame();
+    }
+
+    fn cancel_voice_input(&mut self, tui: &mut tui::Tui) {
+        self.voice.cancel();
+        self.chat_widget.set_footer_hint_override(None);
+        tui.frame_requester().schedule_frame();
+    }
+
     async fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) {
         match key_event {
+            _ if self.tabs.len() > 1 && keymap().matches(KeyAction::NextTab, key_event) => {
+                self.handle_tab_command(tui, TabCommand::Next).await;
+            }
+            _ if self.tabs.len() > 1 && keymap().matches(KeyAction::PreviousTab, key_event) => {
+                self.handle_tab_command(tui, TabCommand::Previous).await;
+            }
             KeyEvent {
                 code: KeyCode::Char('t'),
                 modifiers: crossterm::event::KeyModifiers::CONTROL,
@@ -1758,6 +2249,14 @@ impl App {
                 self.overlay = Some(Overlay::new_transcript(self.transcript_cells.clone()));
                 tui.frame_requester().schedule_frame();
             }
+            KeyEvent {
+                code: KeyCode::Char('f'),
+                modifiers: crossterm::event::KeyModifiers::CONTROL,
+                kind: KeyEventKind::Press,
+                ..
+            } => {
+                self.open_transcript_search(tui, String::new());
+            }
             KeyEvent {
                 code: KeyCode::Char('g'),
                 modifiers: crossterm::event::KeyModifiers::CONTROL,
@@ -1773,6 +2272,34 @@ impl App {
                     self.request_external_editor_launch(tui);
                 }
             }
+            // Ctrl+Space is push-to-talk: hold it while speaking, or tap it to
+            // start and again to stop on terminals without key release events.
+            KeyEvent {
+                code: KeyCode::Char(' '),
+                modifiers: crossterm::event::KeyModifiers::CONTROL,
+                kind,
+                ..
+            } => match kind {
+                KeyEventKind::Press if matches!(self.voice, VoiceInput::Recording { .. }) => {
+                    self.stop_voice_input(tui);
+                }
+                KeyEventKind::Press
+                    if matches!(self.voice, VoiceInput::Idle) && self.overlay.is_none() =>
+                {
+                    self.start_voice_input(tui);
+                }
+                KeyEventKind::Release if self.voice.is_held() => {
+                    self.stop_voice_input(tui);
+                }
+                _ => {}
+            },
+            KeyEvent {
+                code: KeyCode::Esc,
+                kind: KeyEventKind::Press,
+                ..
+            } if matches!(self.voice, VoiceInput::Recording { .. }) => {
+                self.cancel_voice_input(tui);
+            }
             // Esc primes/advances backtracking only in normal (not working) mode
             // with the composer focused and empty. In any other state, forward
             // Esc so the active UI (e.g. status indicator, modals, popups)
@@ -1918,6 +2445,8 @@ mod tests {
             windows_sandbox: WindowsSandboxState::default(),
             external_approval_routes: HashMap::new(),
             paused_codex_events: VecDeque::new(),
+            voice: VoiceInput::default(),
+            tabs: SessionTabs::new(),
         }
     }
 
@@ -1962,6 +2491,8 @@ mod tests {
                 windows_sandbox: WindowsSandboxState::default(),
                 external_approval_routes: HashMap::new(),
                 paused_codex_events: VecDeque::new(),
+                voice: VoiceInput::default(),
+                tabs: SessionTabs::new(),
             },
             rx,
             op_rx,
@@ -2304,6 +2835,41 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn events_from_a_parked_tab_stay_in_that_tab() {
+        let mut app = make_test_app().await;
+        let (background, _tx, _rx, mut background_op_rx) =
+            make_chatwidget_manual_with_sender().await;
+        let tab = app.tabs.open();
+        app.tabs.park(tab, ParkedTab::new(background));
+
+        app.handle_parked_tab_event(
+            tab,
+            AppEvent::InsertHistoryCell(Box::new(history_cell::new_info_event(
+                "done".to_string(),
+                None,
+            ))),
+        );
+        app.handle_parked_tab_event(tab, AppEvent::CodexOp(Op::Interrupt));
+
+        assert!(app.transcript_cells.is_empty());
+        let parked = app.tabs.parked(tab).expect("parked tab");
+        assert_eq!(parked.transcript_cells.len(), 1);
+        assert!(parked.unread);
+        match background_op_rx.try_recv() {
+            Ok(Op::Interrupt) => {}
+            other => panic!("expected the op on the parked tab's thread, got {other:?}"),
+        }
+        let labels = app.tab_labels();
+        assert_eq!(
+            labels
+                .iter()
+                .map(|label| (label.active, label.unread))
+                .collect::<Vec<_>>(),
+            vec![(true, false), (false, true)]
+        );
+    }
+
     #[tokio::test]
     async fn session_summary_skip_zero_usage() {
         assert!(session_summary(TokenUsage::default(), None).is_none());
@@ -30,6 +30,7 @@ use crate::app::App;
 use crate::history_cell::SessionInfoCell;
 use crate::history_cell::UserHistoryCell;
 use crate::pager_overlay::Overlay;
+use crate::pager_overlay::TranscriptOverlay;
 use crate::tui;
 use crate::tui::TuiEvent;
 use codex_core::protocol::CodexErrorInfo;
@@ -101,6 +102,16 @@ impl App {
         tui: &mut tui::Tui,
         event: TuiEvent,
     ) -> Result<bool> {
+        // An open transcript search or selection owns the keyboard (Esc
+        // clears it, Enter confirms it), so backtracking keys only apply once
+        // it is closed.
+        if let TuiEvent::Key(_) = &event
+            && let Some(Overlay::Transcript(t)) = &self.overlay
+            && t.captures_keys()
+        {
+            self.overlay_forward_event(tui, event)?;
+            return Ok(true);
+        }
         if self.backtrack.overlay_preview_active {
             match event {
                 TuiEvent::Key(KeyEvent {
@@ -218,6 +229,25 @@ impl App {
         tui.frame_requester().schedule_frame();
     }
 
+    /// Open the transcript overlay with its search prompt (`Ctrl+F`, `/find`).
+    pub(crate) fn open_transcript_search(&mut self, tui: &mut tui::Tui, query: String) {
+        let _ = tui.enter_alt_screen();
+        let mut transcript = TranscriptOverlay::new(self.transcript_cells.clone());
+        transcript.start_search(query);
+        self.overlay = Some(Overlay::Transcript(transcript));
+        tui.frame_requester().schedule_frame();
+    }
+
+    /// Open the transcript overlay with a selection on the last message
+    /// (`/copy select`).
+    pub(crate) fn open_transcript_selection(&mut self, tui: &mut tui::Tui) {
+        let _ = tui.enter_alt_screen();
+        let mut transcript = TranscriptOverlay::new(self.transcript_cells.clone());
+        transcript.start_selection();
+        self.overlay = Some(Overlay::Transcript(transcript));
+        tui.frame_requester().schedule_frame();
+    }
+
     /// Close transcript overlay and restore normal UI.
     pub(crate) fn close_transcript_overlay(&mut self, tui: &mut tui::Tui) {
         let _ = tui.leave_alt_screen();
@@ -11,7 +11,11 @@
 use std::path::PathBuf;
 
 use codex_common::approval_presets::ApprovalPreset;
+use codex_core::code_outline::SymbolMatch;
+use codex_core::protocol::ContextInspectionEvent;
 use codex_core::protocol::Event;
+use codex_core::protocol::FollowUpSuggestion;
+use codex_core::protocol::MemoryEntry;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_file_search::FileMatch;
 use codex_protocol::ThreadId;
@@ -19,6 +23,9 @@ use codex_protocol::openai_models::ModelPreset;
 
 use crate::bottom_pane::ApprovalRequest;
 use crate::history_cell::HistoryCell;
+use crate::hunk_review::ReviewFile;
+use crate::tabs::TabCommand;
+use crate::tabs::TabId;
 
 use codex_core::features::Feature;
 use codex_core::protocol::AskForApproval;
@@ -43,6 +50,18 @@ pub(crate) enum WindowsSandboxFallbackReason {
 #[derive(Debug)]
 pub(crate) enum AppEvent {
     CodexEvent(Event),
+
+    /// An event sent by the widgets of one session tab; see
+    /// [`crate::tabs`]. The app handles it like `event` when the tab is
+    /// active and routes it to the parked tab otherwise.
+    TabEvent {
+        tab: TabId,
+        event: Box<AppEvent>,
+    },
+
+    /// Open, switch or close a session tab (`/tab` and the tab keys).
+    Tab(TabCommand),
+
     ExternalApprovalRequest {
         thread_id: ThreadId,
         event: Event,
@@ -54,8 +73,11 @@ pub(crate) enum AppEvent {
     /// Open the resume picker inside the running TUI session.
     OpenResumePicker,
 
-    /// Fork the current session into a new thread.
-    ForkCurrentSession,
+    /// Fork the current session into a new thread, keeping the first
+    /// `num_turns` user turns (all of them when `None`).
+    ForkCurrentSession {
+        num_turns: Option<u32>,
+    },
 
     /// Request to exit the application.
     ///
@@ -85,12 +107,55 @@ pub(crate) enum AppEvent {
         matches: Vec<FileMatch>,
     },
 
+    /// Result of a symbol search for an `@#name` mention. `query` includes
+    /// the leading `#`.
+    SymbolSearchResult {
+        query: String,
+        matches: Vec<SymbolMatch>,
+    },
+
     /// Result of refreshing rate limits
     RateLimitSnapshotFetched(RateLimitSnapshot),
 
     /// Result of computing a `/diff` command.
     DiffResult(String),
 
+    /// Open the transcript overlay with its search prompt (`/find`); a
+    /// non-empty query is searched right away.
+    OpenTranscriptSearch(String),
+
+    /// Open the transcript overlay with a message selection to copy
+    /// (`/copy select`).
+    OpenTranscriptSelection,
+
+    /// Write the transcript as plain text to a file (`/save`). A relative
+    /// path is resolved against the session's working directory.
+    SaveTranscript(PathBuf),
+
+    /// Switch the color theme (`/theme`) and repaint the screen.
+    SetTheme(String),
+
+    /// Open the hunk review for an applied patch.
+    OpenHunkReview(Vec<ReviewFile>),
+
+    /// Hunks were kept or rejected in the hunk review; revert the rejected
+    /// ones and tell the agent.
+    HunkReviewFinished(Vec<ReviewFile>),
+
+    /// The patch approved with "review each hunk" (by `apply_patch` call
+    /// id); its review opens once it applies.
+    ReviewHunksAfterApply(String),
+
+    /// Context breakdown to show in (or refresh) the `/context` inspector.
+    ContextInspection(ContextInspectionEvent),
+
+    /// Text computed in the background for `/copy` to put on the clipboard.
+    /// `what` names the text in the confirmation message.
+    CopyToClipboard {
+        what: String,
+        text: String,
+    },
+
     InsertHistoryCell(Box<dyn HistoryCell>),
 
     StartCommitAnimation,
@@ -240,6 +305,30 @@ pub(crate) enum AppEvent {
     /// Open the custom prompt option from the review popup.
     OpenReviewCustomPrompt,
 
+    /// Prompt for the input to send to the named subagent.
+    OpenSubAgentPrompt(String),
+
+    /// Settle the conflict currently shown for a `/merge` plan; `None`
+    /// keeps the file as it is.
+    ResolveSubAgentMergeConflict {
+        run_id: Option<String>,
+    },
+
+    /// Prompt for the input of a new background task.
+    OpenBackgroundTaskPrompt,
+
+    /// Offer to edit or delete a project memory entry.
+    OpenMemoryEntry(MemoryEntry),
+
+    /// Prompt for the new text of a project memory entry.
+    OpenMemoryEditPrompt(MemoryEntry),
+
+    /// Drop every message queued behind the running turn.
+    ClearQueuedUserMessages,
+
+    /// Move the queued message with this text back into the composer.
+    EditQueuedUserMessage(String),
+
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
 
@@ -256,6 +345,35 @@ pub(crate) enum AppEvent {
 
     /// Launch the external editor after a normal draw has completed.
     LaunchExternalEditor,
+
+    /// Transcript of a push-to-talk recording to insert into the composer,
+    /// or why it could not be produced.
+    VoiceTranscribed(Result<String, String>),
+
+    /// Copy the last code block of the latest reply (Ctrl+O menu).
+    CopyLastReplyCode,
+
+    /// Ask before running a shell command suggested in the latest reply.
+    ConfirmSuggestedCommand(String),
+
+    /// Open these files in `$VISUAL`/`$EDITOR`.
+    OpenFilesInEditor(Vec<PathBuf>),
+
+    /// Pick a passage of the latest reply to quote in the composer.
+    OpenReplyQuotePicker,
+
+    /// Insert text at the composer's cursor.
+    InsertInComposer(String),
+
+    /// Ask whether to edit or queue this suggested follow-up.
+    OpenFollowUpActions(FollowUpSuggestion),
+
+    /// Use the suggested follow-up with this prompt: queue it as the next
+    /// turn, or put it in the composer to edit first.
+    UseFollowUp {
+        prompt: String,
+        queue: bool,
+    },
 }
 
 /// The exit strategy requested by the UI layer.
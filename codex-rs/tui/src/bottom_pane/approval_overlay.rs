@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 
 use crate::app_event::AppEvent;
@@ -13,6 +14,7 @@ use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::keymap::keymap;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
@@ -21,8 +23,12 @@ use codex_core::features::Features;
 use codex_core::protocol::ElicitationAction;
 use codex_core::protocol::ExecPolicyAmendment;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::McpInvocation;
 use codex_core::protocol::Op;
+use codex_core::protocol::PatchHunkStatus;
+use codex_core::protocol::PatchPreview;
 use codex_core::protocol::ReviewDecision;
+use codex_protocol::plan_tool::ProposedPlan;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
@@ -44,18 +50,47 @@ pub(crate) enum ApprovalRequest {
         command: Vec<String>,
         reason: Option<String>,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
+        /// Plain-language description of the command, when enabled.
+        explanation: Option<String>,
+        /// Gaps in what the platform sandbox enforces for this command.
+        sandbox_warnings: Vec<String>,
     },
     ApplyPatch {
         id: String,
+        /// The `apply_patch` call, to find the patch once it applied.
+        call_id: String,
         reason: Option<String>,
         cwd: PathBuf,
         changes: HashMap<PathBuf, FileChange>,
+        /// Dry run against the working tree (`patch_preview` feature).
+        preview: Option<PatchPreview>,
     },
     McpElicitation {
         server_name: String,
         request_id: RequestId,
         message: String,
     },
+    Plan {
+        id: String,
+        plan: ProposedPlan,
+    },
+    McpToolCall {
+        id: String,
+        invocation: McpInvocation,
+    },
+}
+
+impl ApprovalRequest {
+    /// Id used to answer the request; elicitations are keyed differently.
+    fn id(&self) -> Option<&str> {
+        match self {
+            ApprovalRequest::Exec { id, .. }
+            | ApprovalRequest::ApplyPatch { id, .. }
+            | ApprovalRequest::Plan { id, .. }
+            | ApprovalRequest::McpToolCall { id, .. } => Some(id),
+            ApprovalRequest::McpElicitation { .. } => None,
+        }
+    }
 }
 
 /// Modal overlay asking the user to approve or deny one or more requests.
@@ -107,7 +142,7 @@ impl ApprovalOverlay {
         header: Box<dyn Renderable>,
         features: &Features,
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
-        let (options, title) = match &variant {
+        let (mut options, title) = match &variant {
             ApprovalVariant::Exec {
                 proposed_execpolicy_amendment,
                 ..
@@ -123,8 +158,25 @@ impl ApprovalOverlay {
                 elicitation_options(),
                 format!("{server_name} needs your approval."),
             ),
+            ApprovalVariant::Plan { .. } => (
+                plan_options(),
+                "Would you like Codex to carry out this plan?".to_string(),
+            ),
+            ApprovalVariant::McpToolCall { .. } => (
+                mcp_tool_options(),
+                "Would you like to run the following MCP tool?".to_string(),
+            ),
         };
 
+        let keymap = keymap();
+        for option in &mut options {
+            option.additional_shortcuts = option
+                .additional_shortcuts
+                .iter()
+                .flat_map(|key| keymap.approval_keys(*key))
+                .collect();
+        }
+
         let header = Box::new(ColumnRenderable::with([
             Line::from(title.bold()).into(),
             Line::from("").into(),
@@ -174,6 +226,11 @@ impl ApprovalOverlay {
                 (ApprovalVariant::ApplyPatch { id, .. }, ApprovalDecision::Review(decision)) => {
                     self.handle_patch_decision(id, decision.clone());
                 }
+                (ApprovalVariant::ApplyPatch { id, call_id }, ApprovalDecision::ReviewHunks) => {
+                    self.app_event_tx
+                        .send(AppEvent::ReviewHunksAfterApply(call_id.clone()));
+                    self.handle_patch_decision(id, ReviewDecision::Approved);
+                }
                 (
                     ApprovalVariant::McpElicitation {
                         server_name,
@@ -183,6 +240,12 @@ impl ApprovalOverlay {
                 ) => {
                     self.handle_elicitation_decision(server_name, request_id, *decision);
                 }
+                (ApprovalVariant::Plan { id, plan }, ApprovalDecision::Review(decision)) => {
+                    self.handle_plan_decision(id, plan, decision.clone());
+                }
+                (ApprovalVariant::McpToolCall { id }, ApprovalDecision::Review(decision)) => {
+                    self.handle_mcp_tool_decision(id, decision.clone());
+                }
                 _ => {}
             }
         }
@@ -207,6 +270,28 @@ impl ApprovalOverlay {
         }));
     }
 
+    fn handle_plan_decision(&self, id: &str, plan: &ProposedPlan, decision: ReviewDecision) {
+        let approved = matches!(
+            decision,
+            ReviewDecision::Approved | ReviewDecision::ApprovedForSession
+        );
+        let cell = history_cell::new_plan_decision_cell(plan, approved);
+        self.app_event_tx
+            .send(AppEvent::InsertHistoryCell(Box::new(cell)));
+        self.app_event_tx.send(AppEvent::CodexOp(Op::PlanApproval {
+            id: id.to_string(),
+            decision,
+        }));
+    }
+
+    fn handle_mcp_tool_decision(&self, id: &str, decision: ReviewDecision) {
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::McpToolCallApproval {
+                id: id.to_string(),
+                decision,
+            }));
+    }
+
     fn handle_elicitation_decision(
         &self,
         server_name: &str,
@@ -296,6 +381,12 @@ impl BottomPaneView for ApprovalOverlay {
                         ElicitationAction::Cancel,
                     );
                 }
+                ApprovalVariant::Plan { id, plan } => {
+                    self.handle_plan_decision(id, plan, ReviewDecision::Abort);
+                }
+                ApprovalVariant::McpToolCall { id } => {
+                    self.handle_mcp_tool_decision(id, ReviewDecision::Abort);
+                }
             }
         }
         self.queue.clear();
@@ -314,6 +405,21 @@ impl BottomPaneView for ApprovalOverlay {
         self.enqueue_request(request);
         None
     }
+
+    fn dismiss_approval_request(&mut self, id: &str) -> bool {
+        let queued = self.queue.len();
+        self.queue.retain(|request| request.id() != Some(id));
+        let is_current = !self.current_complete
+            && self
+                .current_request
+                .as_ref()
+                .is_some_and(|request| request.id() == Some(id));
+        if is_current {
+            self.current_complete = true;
+            self.advance_queue();
+        }
+        is_current || self.queue.len() != queued
+    }
 }
 
 impl Renderable for ApprovalOverlay {
@@ -343,12 +449,27 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 command,
                 reason,
                 proposed_execpolicy_amendment,
+                explanation,
+                sandbox_warnings,
             } => {
                 let mut header: Vec<Line<'static>> = Vec::new();
                 if let Some(reason) = reason {
                     header.push(Line::from(vec!["Reason: ".into(), reason.italic()]));
                     header.push(Line::from(""));
                 }
+                if let Some(explanation) = explanation {
+                    header.push(Line::from(vec![
+                        "What it does: ".bold(),
+                        explanation.into(),
+                    ]));
+                    header.push(Line::from(""));
+                }
+                if !sandbox_warnings.is_empty() {
+                    for warning in sandbox_warnings {
+                        header.push(Line::from(vec!["Sandbox: ".bold(), warning.into()]));
+                    }
+                    header.push(Line::from(""));
+                }
                 let full_cmd = strip_bash_lc_and_escape(&command);
                 let mut full_cmd_lines = highlight_bash_to_lines(&full_cmd);
                 if let Some(first) = full_cmd_lines.first_mut() {
@@ -366,9 +487,11 @@ impl From<ApprovalRequest> for ApprovalRequestState {
             }
             ApprovalRequest::ApplyPatch {
                 id,
+                call_id,
                 reason,
                 cwd,
                 changes,
+                preview,
             } => {
                 let mut header: Vec<Box<dyn Renderable>> = Vec::new();
                 if let Some(reason) = reason
@@ -380,9 +503,16 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     ));
                     header.push(Box::new(Line::from("")));
                 }
+                if let Some(preview) = preview {
+                    header.push(Box::new(
+                        Paragraph::new(patch_preview_lines(&preview, &cwd))
+                            .wrap(Wrap { trim: false }),
+                    ));
+                    header.push(Box::new(Line::from("")));
+                }
                 header.push(DiffSummary::new(changes, cwd).into());
                 Self {
-                    variant: ApprovalVariant::ApplyPatch { id },
+                    variant: ApprovalVariant::ApplyPatch { id, call_id },
                     header: Box::new(ColumnRenderable::with(header)),
                 }
             }
@@ -405,6 +535,22 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     header: Box::new(header),
                 }
             }
+            ApprovalRequest::Plan { id, plan } => {
+                let header = Paragraph::new(history_cell::proposed_plan_lines(&plan))
+                    .wrap(Wrap { trim: false });
+                Self {
+                    variant: ApprovalVariant::Plan { id, plan },
+                    header: Box::new(header),
+                }
+            }
+            ApprovalRequest::McpToolCall { id, invocation } => {
+                let header = Paragraph::new(vec![history_cell::format_mcp_invocation(invocation)])
+                    .wrap(Wrap { trim: false });
+                Self {
+                    variant: ApprovalVariant::McpToolCall { id },
+                    header: Box::new(header),
+                }
+            }
         }
     }
 }
@@ -418,17 +564,27 @@ enum ApprovalVariant {
     },
     ApplyPatch {
         id: String,
+        call_id: String,
     },
     McpElicitation {
         server_name: String,
         request_id: RequestId,
     },
+    Plan {
+        id: String,
+        plan: ProposedPlan,
+    },
+    McpToolCall {
+        id: String,
+    },
 }
 
 #[derive(Clone)]
 enum ApprovalDecision {
     Review(ReviewDecision),
     McpElicitation(ElicitationAction),
+    /// Approve a patch, then review its hunks once it applies.
+    ReviewHunks,
 }
 
 #[derive(Clone)]
@@ -490,6 +646,62 @@ fn exec_options(
     .collect()
 }
 
+/// Summary of an `apply_patch` dry run: how many files it touches and every
+/// chunk that did not apply as written.
+fn patch_preview_lines(preview: &PatchPreview, cwd: &Path) -> Vec<Line<'static>> {
+    let files = preview.files.len();
+    let conflicts = preview.conflicts();
+    let summary = match (files, conflicts) {
+        (1, 0) => "1 file, applies cleanly".to_string(),
+        (files, 0) => format!("{files} files, applies cleanly"),
+        (1, conflicts) => format!("1 file, {conflicts} conflicting chunk(s) will be left out"),
+        (files, conflicts) => {
+            format!("{files} files, {conflicts} conflicting chunk(s) will be left out")
+        }
+    };
+    let summary = if conflicts > 0 {
+        summary.red()
+    } else {
+        summary.into()
+    };
+    let mut lines = vec![Line::from(vec!["Dry run: ".bold(), summary])];
+    for file in &preview.files {
+        let chunks: Vec<Span<'static>> = file
+            .hunks
+            .iter()
+            .filter(|hunk| hunk.status != PatchHunkStatus::Applied)
+            .map(|hunk| {
+                let label = match hunk.status {
+                    PatchHunkStatus::Applied => "applies",
+                    PatchHunkStatus::Fuzzy => "fuzzy",
+                    PatchHunkStatus::Merged => "merged",
+                    PatchHunkStatus::AlreadyApplied => "already applied",
+                    PatchHunkStatus::Conflict => "conflict",
+                };
+                let text = format!("line {} {label}", hunk.line);
+                if hunk.status == PatchHunkStatus::Conflict {
+                    text.red()
+                } else {
+                    text.dim()
+                }
+            })
+            .collect();
+        if chunks.is_empty() {
+            continue;
+        }
+        let path = file.path.strip_prefix(cwd).unwrap_or(&file.path);
+        let mut spans = vec![format!("  {}: ", path.display()).into()];
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            if index > 0 {
+                spans.push(", ".dim());
+            }
+            spans.push(chunk);
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
 fn patch_options() -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
@@ -504,6 +716,58 @@ fn patch_options() -> Vec<ApprovalOption> {
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
+        ApprovalOption {
+            label: "Yes, then let me keep or reject each hunk".to_string(),
+            decision: ApprovalDecision::ReviewHunks,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('r'))],
+        },
+        ApprovalOption {
+            label: "No, and tell Codex what to do differently".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Abort),
+            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+        },
+    ]
+}
+
+fn plan_options() -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: "Yes, carry out the plan".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Approved),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        },
+        ApprovalOption {
+            label: "No, keep planning".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Denied),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+        },
+        ApprovalOption {
+            label: "No, and tell Codex what to do differently".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Abort),
+            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+            additional_shortcuts: Vec::new(),
+        },
+    ]
+}
+
+fn mcp_tool_options() -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: "Yes, proceed".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Approved),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        },
+        ApprovalOption {
+            label: "Yes, and don't ask again for this tool".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+        },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
             decision: ApprovalDecision::Review(ReviewDecision::Abort),
@@ -549,6 +813,8 @@ mod tests {
             command: vec!["echo".to_string(), "hi".to_string()],
             reason: Some("reason".to_string()),
             proposed_execpolicy_amendment: None,
+            explanation: None,
+            sandbox_warnings: Vec::new(),
         }
     }
 
@@ -563,6 +829,32 @@ mod tests {
         assert!(view.is_complete());
     }
 
+    #[test]
+    fn dismiss_skips_to_the_next_request_without_answering() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        view.enqueue_request(ApprovalRequest::Exec {
+            id: "next".to_string(),
+            command: vec!["ls".to_string()],
+            reason: None,
+            proposed_execpolicy_amendment: None,
+            explanation: None,
+            sandbox_warnings: Vec::new(),
+        });
+
+        assert!(!view.dismiss_approval_request("unknown"));
+        assert!(view.dismiss_approval_request("test"));
+        assert!(!view.is_complete());
+        assert!(
+            rx.try_recv().is_err(),
+            "dismissing must not send a decision"
+        );
+
+        assert!(view.dismiss_approval_request("next"));
+        assert!(view.is_complete());
+    }
+
     #[test]
     fn shortcut_triggers_selection() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
@@ -593,6 +885,8 @@ mod tests {
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
                     "echo".to_string(),
                 ])),
+                explanation: None,
+                sandbox_warnings: Vec::new(),
             },
             tx,
             Features::with_defaults(),
@@ -619,6 +913,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn patch_review_option_approves_and_asks_for_the_hunk_review() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(
+            ApprovalRequest::ApplyPatch {
+                id: "sub".to_string(),
+                call_id: "call-1".to_string(),
+                reason: None,
+                cwd: PathBuf::from("/repo"),
+                changes: HashMap::new(),
+                preview: None,
+            },
+            tx,
+            Features::with_defaults(),
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+
+        let mut review_call = None;
+        let mut approval = None;
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                AppEvent::ReviewHunksAfterApply(call_id) => review_call = Some(call_id),
+                AppEvent::CodexOp(Op::PatchApproval { id, decision }) => {
+                    approval = Some((id, decision));
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(review_call.as_deref(), Some("call-1"));
+        assert_eq!(
+            approval,
+            Some(("sub".to_string(), ReviewDecision::Approved))
+        );
+    }
+
     #[test]
     fn exec_prefix_option_hidden_when_execpolicy_disabled() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
@@ -631,6 +961,8 @@ mod tests {
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
                     "echo".to_string(),
                 ])),
+                explanation: None,
+                sandbox_warnings: Vec::new(),
             },
             tx,
             {
@@ -655,6 +987,8 @@ mod tests {
             command,
             reason: None,
             proposed_execpolicy_amendment: None,
+            explanation: None,
+            sandbox_warnings: Vec::new(),
         };
 
         let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
@@ -676,6 +1010,89 @@ mod tests {
         );
     }
 
+    #[test]
+    fn header_includes_explanation() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let exec_request = ApprovalRequest::Exec {
+            id: "test".into(),
+            command: vec!["rm".into(), "-rf".into(), "build".into()],
+            reason: None,
+            proposed_execpolicy_amendment: None,
+            explanation: Some("Recursively delete build.".to_string()),
+            sandbox_warnings: Vec::new(),
+        };
+
+        let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, view.desired_height(80)));
+        view.render(Rect::new(0, 0, 80, view.desired_height(80)), &mut buf);
+
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        assert!(
+            rendered
+                .iter()
+                .any(|line| line.contains("What it does: Recursively delete build.")),
+            "expected header to include the explanation, got {rendered:?}"
+        );
+    }
+
+    #[test]
+    fn patch_preview_lists_conflicting_chunks() {
+        use codex_core::protocol::FileDiffStatus;
+        use codex_core::protocol::PatchHunkResult;
+        use codex_core::protocol::PatchPreviewFile;
+
+        let cwd = PathBuf::from("/repo");
+        let preview = PatchPreview {
+            files: vec![
+                PatchPreviewFile {
+                    path: cwd.join("src/lib.rs"),
+                    move_path: None,
+                    status: FileDiffStatus::Modified,
+                    hunks: vec![
+                        PatchHunkResult {
+                            line: 3,
+                            status: PatchHunkStatus::Applied,
+                        },
+                        PatchHunkResult {
+                            line: 40,
+                            status: PatchHunkStatus::Conflict,
+                        },
+                    ],
+                },
+                PatchPreviewFile {
+                    path: cwd.join("README.md"),
+                    move_path: None,
+                    status: FileDiffStatus::Added,
+                    hunks: Vec::new(),
+                },
+            ],
+        };
+
+        let lines: Vec<String> = patch_preview_lines(&preview, &cwd)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Dry run: 2 files, 1 conflicting chunk(s) will be left out".to_string(),
+                "  src/lib.rs: line 40 conflict".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn exec_history_cell_wraps_with_two_space_indent() {
         let command = vec![
@@ -36,6 +36,12 @@ pub(crate) trait BottomPaneView: Renderable {
         Some(request)
     }
 
+    /// Drop a pending approval request that was resolved without the user
+    /// (e.g. by an approval timeout); return true if the view held it.
+    fn dismiss_approval_request(&mut self, _id: &str) -> bool {
+        false
+    }
+
     /// Try to handle request_user_input; return the original value if not
     /// consumed.
     fn try_consume_user_input_request(
@@ -15,6 +15,10 @@
 //! [`ChatComposer::handle_key_event_without_popup`]. After every handled key, we call
 //! [`ChatComposer::sync_popups`] so UI state follows the latest buffer/cursor.
 //!
+//! With `tui.editing_mode = "vim"`, [`Vim`](super::vim::Vim) gets each key first: normal-mode keys
+//! edit the textarea directly and keep popups hidden, while in insert mode only Esc is taken (and
+//! only when no popup would consume it).
+//!
 //! # Submission and Prompt Expansion
 //!
 //! On submit/queue paths, the composer:
@@ -72,6 +76,8 @@
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
 use crate::key_hint::has_ctrl_or_alt;
+use crate::keymap::KeyContext;
+use crate::keymap::keymap;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
@@ -92,6 +98,7 @@ use super::chat_composer_history::ChatComposerHistory;
 use super::command_popup::CommandItem;
 use super::command_popup::CommandPopup;
 use super::command_popup::CommandPopupFlags;
+use super::command_popup::McpPromptCommand;
 use super::file_search_popup::FileSearchPopup;
 use super::footer::CollaborationModeIndicator;
 use super::footer::FooterMode;
@@ -123,6 +130,7 @@ use crate::slash_command::SlashCommand;
 use crate::slash_command::built_in_slash_commands;
 use crate::style::user_message_style;
 use codex_common::fuzzy_match::fuzzy_match;
+use codex_protocol::custom_prompts::CustomCommand;
 use codex_protocol::custom_prompts::CustomPrompt;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use codex_protocol::models::local_image_label_text;
@@ -134,10 +142,15 @@ use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::LocalImageAttachment;
 use crate::bottom_pane::textarea::TextArea;
 use crate::bottom_pane::textarea::TextAreaState;
+use crate::bottom_pane::vim::Vim;
+use crate::bottom_pane::vim::VimKey;
+use crate::bottom_pane::vim::VimMode;
 use crate::clipboard_paste::normalize_pasted_path;
 use crate::clipboard_paste::pasted_image_format;
 use crate::history_cell;
 use crate::ui_consts::LIVE_PREFIX_COLS;
+use codex_core::code_outline::SymbolMatch;
+use codex_core::config::types::EditingMode;
 use codex_core::skills::model::SkillMetadata;
 use codex_file_search::FileMatch;
 use std::cell::RefCell;
@@ -223,6 +236,8 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    custom_commands: Vec<CustomCommand>,
+    mcp_prompts: Vec<McpPromptCommand>,
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     footer_flash: Option<FooterFlash>,
@@ -234,6 +249,8 @@ pub(crate) struct ChatComposer {
     steer_enabled: bool,
     collaboration_modes_enabled: bool,
     collaboration_mode_indicator: Option<CollaborationModeIndicator>,
+    /// Modal editing state when `tui.editing_mode` is `vim`.
+    vim: Option<Vim>,
 }
 
 #[derive(Clone, Debug)]
@@ -285,6 +302,8 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            custom_commands: Vec::new(),
+            mcp_prompts: Vec::new(),
             footer_mode: FooterMode::ShortcutSummary,
             footer_hint_override: None,
             footer_flash: None,
@@ -295,6 +314,7 @@ impl ChatComposer {
             steer_enabled: false,
             collaboration_modes_enabled: false,
             collaboration_mode_indicator: None,
+            vim: None,
         };
         // Apply configuration via the setter to keep side-effects centralized.
         this.set_disable_paste_burst(disable_paste_burst);
@@ -326,6 +346,23 @@ impl ChatComposer {
         self.collaboration_mode_indicator = indicator;
     }
 
+    pub(crate) fn set_editing_mode(&mut self, mode: EditingMode) {
+        self.vim = match mode {
+            EditingMode::Emacs => None,
+            EditingMode::Vim => Some(Vim::new()),
+        };
+    }
+
+    fn vim_mode(&self) -> Option<VimMode> {
+        self.vim.as_ref().map(Vim::mode)
+    }
+
+    /// True when Esc belongs to the composer: in vim insert mode it switches
+    /// to normal mode instead of interrupting or backtracking.
+    pub(crate) fn takes_esc(&self) -> bool {
+        self.vim_mode() == Some(VimMode::Insert)
+    }
+
     fn layout_areas(&self, area: Rect) -> [Rect; 3] {
         let footer_props = self.footer_props();
         let footer_hint_height = self
@@ -727,6 +764,21 @@ impl ChatComposer {
         }
     }
 
+    /// Integrate results from an asynchronous symbol search (`@#name`).
+    pub(crate) fn on_symbol_search_result(&mut self, query: String, matches: Vec<SymbolMatch>) {
+        let Some(current_token) = Self::current_at_token(&self.textarea) else {
+            return;
+        };
+
+        if !current_token.starts_with(&query) {
+            return;
+        }
+
+        if let ActivePopup::File(popup) = &mut self.active_popup {
+            popup.set_symbol_matches(&query, matches);
+        }
+    }
+
     /// Show the transient "press again to quit" hint for `key`.
     ///
     /// The owner (`BottomPane`/`ChatWidget`) is responsible for scheduling a
@@ -780,6 +832,17 @@ impl ChatComposer {
             return (InputResult::None, false);
         }
 
+        // Vim sees keys first, except in insert mode while a popup is open, so
+        // Esc closes the popup there.
+        let vim_first = self.vim_mode() == Some(VimMode::Normal) || !self.popup_active();
+        if vim_first
+            && let Some(vim) = self.vim.as_mut()
+            && vim.handle_key(&mut self.textarea, key_event) == VimKey::Handled
+        {
+            self.sync_popups();
+            return (InputResult::None, true);
+        }
+
         let result = match &mut self.active_popup {
             ActivePopup::Command(_) => self.handle_key_event_with_slash_popup(key_event),
             ActivePopup::File(_) => self.handle_key_event_with_file_popup(key_event),
@@ -790,6 +853,13 @@ impl ChatComposer {
         // Update (or hide/show) popup after processing the key.
         self.sync_popups();
 
+        // A submitted message or command starts the next one in insert mode.
+        if !matches!(result.0, InputResult::None)
+            && let Some(vim) = self.vim.as_mut()
+        {
+            vim.reset();
+        }
+
         result
     }
 
@@ -892,6 +962,29 @@ impl ChatComposer {
                                 }
                             }
                         }
+                        CommandItem::CustomCommand(idx) => {
+                            if let Some(command) = popup.custom_command(idx) {
+                                let typed_command = parse_slash_name(first_line)
+                                    .is_some_and(|(name, _, _)| name == command.name);
+                                if !typed_command {
+                                    let text = format!("/{} ", command.name);
+                                    self.textarea.set_text_clearing_elements(&text);
+                                    cursor_target = Some(text.len());
+                                }
+                            }
+                        }
+                        CommandItem::McpPrompt(idx) => {
+                            if let Some(prompt) = popup.mcp_prompt(idx) {
+                                let starts_with_cmd = first_line
+                                    .trim_start()
+                                    .starts_with(&format!("/{}", prompt.command()));
+                                if !starts_with_cmd {
+                                    let (text, cursor) = mcp_prompt_insertion(prompt);
+                                    self.textarea.set_text_clearing_elements(&text);
+                                    cursor_target = Some(cursor);
+                                }
+                            }
+                        }
                     }
                     if let Some(pos) = cursor_target {
                         self.textarea.set_cursor(pos);
@@ -979,6 +1072,45 @@ impl ChatComposer {
                             }
                             return (InputResult::None, true);
                         }
+                        CommandItem::CustomCommand(idx) => {
+                            // The session expands custom commands too. A command
+                            // that takes arguments waits for them before submitting.
+                            if let Some(command) = popup.custom_command(idx) {
+                                let typed_command = parse_slash_name(first_line)
+                                    .is_some_and(|(name, _, _)| name == command.name);
+                                if !typed_command {
+                                    if command.argument_hint.is_some() {
+                                        let text = format!("/{} ", command.name);
+                                        self.textarea.set_text_clearing_elements(&text);
+                                        self.textarea.set_cursor(text.len());
+                                        return (InputResult::None, true);
+                                    }
+                                    self.textarea
+                                        .set_text_clearing_elements(&format!("/{}", command.name));
+                                }
+                            }
+                        }
+                        CommandItem::McpPrompt(idx) => {
+                            // The session expands MCP prompts, so submit the command
+                            // text as typed once the user has had a chance to fill in
+                            // the arguments.
+                            if let Some(prompt) = popup.mcp_prompt(idx).cloned() {
+                                let command = prompt.command();
+                                let typed_command = parse_slash_name(first_line)
+                                    .is_some_and(|(name, _, _)| name == command);
+                                if !typed_command {
+                                    if prompt.arguments.is_empty() {
+                                        self.textarea
+                                            .set_text_clearing_elements(&format!("/{command}"));
+                                    } else {
+                                        let (text, cursor) = mcp_prompt_insertion(&prompt);
+                                        self.textarea.set_text_clearing_elements(&text);
+                                        self.textarea.set_cursor(cursor);
+                                        return (InputResult::None, true);
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
                 // Fallback to default newline handling if no command selected.
@@ -1639,7 +1771,15 @@ impl ChatComposer {
                             .any(|prompt| prompt.name == prompt_name)
                     })
                     .unwrap_or(false);
-                if !is_builtin && !is_known_prompt {
+                let is_mcp_prompt = self
+                    .mcp_prompts
+                    .iter()
+                    .any(|prompt| prompt.command() == name);
+                let is_custom_command = self
+                    .custom_commands
+                    .iter()
+                    .any(|command| command.name == name);
+                if !is_builtin && !is_known_prompt && !is_mcp_prompt && !is_custom_command {
                     let message = format!(
                         r#"Unrecognized command '/{name}'. Type "/" for a list of supported commands."#
                     );
@@ -1819,7 +1959,23 @@ impl ChatComposer {
                 && let Some((_n, cmd)) =
                     Self::built_in_slash_commands_for_input(self.collaboration_modes_enabled)
                         .find(|(command_name, _)| *command_name == name)
-                && cmd == SlashCommand::Review
+                && matches!(
+                    cmd,
+                    SlashCommand::Review
+                        | SlashCommand::Plan
+                        | SlashCommand::Checkpoint
+                        | SlashCommand::Worktree
+                        | SlashCommand::Pin
+                        | SlashCommand::Fork
+                        | SlashCommand::Compact
+                        | SlashCommand::Copy
+                        | SlashCommand::Save
+                        | SlashCommand::Find
+                        | SlashCommand::Theme
+                        | SlashCommand::Tab
+                        | SlashCommand::Undo
+                        | SlashCommand::Stdin
+                )
             {
                 self.textarea.set_text_clearing_elements("");
                 return Some(InputResult::CommandWithArgs(cmd, rest.to_string()));
@@ -1830,6 +1986,9 @@ impl ChatComposer {
 
     /// Handle key event when no popup is visible.
     fn handle_key_event_without_popup(&mut self, key_event: KeyEvent) -> (InputResult, bool) {
+        let Some(key_event) = keymap().translate(KeyContext::Composer, key_event) else {
+            return (InputResult::None, false);
+        };
         if self.handle_shortcut_overlay_key(&key_event) {
             return (InputResult::None, true);
         }
@@ -2204,6 +2363,11 @@ impl ChatComposer {
     }
 
     fn sync_popups(&mut self) {
+        // Normal-mode keys are commands, not completions.
+        if self.vim_mode() == Some(VimMode::Normal) {
+            self.active_popup = ActivePopup::None;
+            return;
+        }
         let file_token = Self::current_at_token(&self.textarea);
         let browsing_history = self
             .history
@@ -2274,7 +2438,8 @@ impl ChatComposer {
     }
 
     /// Heuristic for whether the typed slash command looks like a valid
-    /// prefix for any known command (built-in or custom prompt).
+    /// prefix for any known command (built-in, custom prompt, custom command,
+    /// or MCP prompt).
     /// Empty names only count when there is no extra content after the '/'.
     fn looks_like_slash_prefix(&self, name: &str, rest_after_name: &str) -> bool {
         if name.is_empty() {
@@ -2293,6 +2458,14 @@ impl ChatComposer {
         self.custom_prompts
             .iter()
             .any(|p| fuzzy_match(&format!("{prompt_prefix}{}", p.name), name).is_some())
+            || self
+                .custom_commands
+                .iter()
+                .any(|c| fuzzy_match(&c.name, name).is_some())
+            || self
+                .mcp_prompts
+                .iter()
+                .any(|p| fuzzy_match(&p.command(), name).is_some())
     }
 
     /// Synchronize `self.command_popup` with the current text in the
@@ -2342,6 +2515,8 @@ impl ChatComposer {
                             collaboration_modes_enabled,
                         },
                     );
+                    command_popup.set_custom_commands(self.custom_commands.clone());
+                    command_popup.set_mcp_prompts(self.mcp_prompts.clone());
                     command_popup.on_composer_text_change(first_line.to_string());
                     self.active_popup = ActivePopup::Command(command_popup);
                 }
@@ -2366,6 +2541,20 @@ impl ChatComposer {
         }
     }
 
+    pub(crate) fn set_custom_commands(&mut self, commands: Vec<CustomCommand>) {
+        self.custom_commands = commands.clone();
+        if let ActivePopup::Command(popup) = &mut self.active_popup {
+            popup.set_custom_commands(commands);
+        }
+    }
+
+    pub(crate) fn set_mcp_prompts(&mut self, prompts: Vec<McpPromptCommand>) {
+        self.mcp_prompts = prompts.clone();
+        if let ActivePopup::Command(popup) = &mut self.active_popup {
+            popup.set_mcp_prompts(prompts);
+        }
+    }
+
     /// Synchronize `self.file_search_popup` with the current text in the textarea.
     /// Note this is only called when self.active_popup is NOT Command.
     fn sync_file_search_popup(&mut self, query: String) {
@@ -2561,6 +2750,24 @@ impl Renderable for ChatComposer {
             );
         }
 
+        if let Some(mode) = self.vim_mode()
+            && composer_rect.height >= 3
+        {
+            let label = match mode {
+                VimMode::Normal => "NORMAL".cyan().bold(),
+                VimMode::Insert => "INSERT".dim(),
+            };
+            let width = label.width() as u16;
+            if composer_rect.width > width + 1 {
+                buf.set_span(
+                    composer_rect.right() - width - 1,
+                    composer_rect.bottom() - 1,
+                    &label,
+                    width,
+                );
+            }
+        }
+
         let mut state = self.textarea_state.borrow_mut();
         StatefulWidgetRef::render_ref(&(&self.textarea), textarea_rect, buf, &mut state);
         if self.textarea.text().is_empty() {
@@ -2578,6 +2785,20 @@ impl Renderable for ChatComposer {
     }
 }
 
+/// Text and cursor for inserting an MCP prompt command, with an empty
+/// `KEY=""` placeholder per argument and the cursor in the first one.
+fn mcp_prompt_insertion(prompt: &McpPromptCommand) -> (String, usize) {
+    let mut text = format!("/{}", prompt.command());
+    let mut cursor = text.len();
+    for (i, arg) in prompt.arguments.iter().enumerate() {
+        text.push_str(&format!(" {arg}=\"\""));
+        if i == 0 {
+            cursor = text.len() - 1;
+        }
+    }
+    (text, cursor)
+}
+
 fn prompt_selection_action(
     prompt: &CustomPrompt,
     first_line: &str,
@@ -3778,7 +3999,11 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "model")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(
+                    CommandItem::UserPrompt(_)
+                    | CommandItem::CustomCommand(_)
+                    | CommandItem::McpPrompt(_),
+                ) => {
                     panic!("unexpected prompt selected for '/mo'")
                 }
                 None => panic!("no selected command for '/mo'"),
@@ -3835,7 +4060,11 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "resume")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(
+                    CommandItem::UserPrompt(_)
+                    | CommandItem::CustomCommand(_)
+                    | CommandItem::McpPrompt(_),
+                ) => {
                     panic!("unexpected prompt selected for '/res'")
                 }
                 None => panic!("no selected command for '/res'"),
@@ -3850,6 +4079,38 @@ mod tests {
     }
 
     // Test helper: simulate human typing with a brief delay and flush the paste-burst buffer
+    #[test]
+    fn vim_mode_esc_closes_popups_first_and_normal_mode_edits() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_editing_mode(EditingMode::Vim);
+        type_chars_humanlike(&mut composer, &['/', 'm', 'o']);
+        assert!(composer.popup_active());
+
+        // The first Esc closes the popup and stays in insert mode.
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        let _ = composer.handle_key_event(esc);
+        assert!(!composer.popup_active());
+        assert!(composer.takes_esc());
+
+        let _ = composer.handle_key_event(esc);
+        assert!(!composer.takes_esc());
+        type_chars_humanlike(&mut composer, &['x']);
+        assert_eq!(composer.textarea.text(), "/m");
+        assert!(!composer.popup_active());
+
+        type_chars_humanlike(&mut composer, &['A', 'o']);
+        assert_eq!(composer.textarea.text(), "/mo");
+        assert!(composer.takes_esc());
+    }
+
     fn type_chars_humanlike(composer: &mut ChatComposer, chars: &[char]) {
         use crossterm::event::KeyCode;
         use crossterm::event::KeyEvent;
@@ -4926,6 +5187,51 @@ mod tests {
         assert!(composer.textarea.is_empty());
     }
 
+    #[test]
+    fn custom_command_completes_and_submits_as_typed() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_steer_enabled(true);
+
+        composer.set_custom_commands(vec![CustomCommand {
+            name: "fixbug".to_string(),
+            path: "/repo/.codex/commands/fixbug.md".into(),
+            content: "Fix $ARGUMENTS".to_string(),
+            description: Some("Fix a bug".to_string()),
+            argument_hint: Some("[ISSUE]".to_string()),
+            model: None,
+            mode: None,
+            source: codex_protocol::custom_prompts::CustomCommandSource::Repo,
+        }]);
+
+        type_chars_humanlike(&mut composer, &['/', 'f', 'i', 'x', 'b']);
+        assert!(matches!(composer.active_popup, ActivePopup::Command(_)));
+
+        // Enter on a command that takes arguments completes it first.
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(result, InputResult::None);
+        assert_eq!(composer.textarea.text(), "/fixbug ");
+
+        type_chars_humanlike(&mut composer, &['4', '2']);
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        // The session expands the command, so it is submitted as typed.
+        assert!(matches!(
+            result,
+            InputResult::Submitted { text, .. } if text == "/fixbug 42"
+        ));
+        assert!(composer.textarea.is_empty());
+    }
+
     #[test]
     fn custom_prompt_submission_expands_arguments() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
@@ -10,7 +10,9 @@ use crate::render::Insets;
 use crate::render::RectExt;
 use crate::slash_command::SlashCommand;
 use crate::slash_command::built_in_slash_commands;
+use codex_protocol::custom_prompts::CustomCommand;
 use codex_protocol::custom_prompts::CustomPrompt;
+use codex_protocol::custom_prompts::MCP_PROMPTS_CMD_PREFIX;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use std::collections::HashSet;
 
@@ -21,18 +23,43 @@ fn windows_degraded_sandbox_active() -> bool {
         && !codex_core::is_windows_elevated_sandbox_enabled()
 }
 
-/// A selectable item in the popup: either a built-in command or a user prompt.
+/// A selectable item in the popup: a built-in command, a user prompt, a
+/// custom command, or an MCP server prompt.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum CommandItem {
     Builtin(SlashCommand),
     // Index into `prompts`
     UserPrompt(usize),
+    // Index into `custom_commands`
+    CustomCommand(usize),
+    // Index into `mcp_prompts`
+    McpPrompt(usize),
+}
+
+/// A prompt exposed by an MCP server, offered as `/mcp:<server>:<name>`.
+/// The session expands it, so the composer only needs its name and
+/// argument names.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct McpPromptCommand {
+    pub(crate) server: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) arguments: Vec<String>,
+}
+
+impl McpPromptCommand {
+    /// The command token after the leading '/'.
+    pub(crate) fn command(&self) -> String {
+        format!("{MCP_PROMPTS_CMD_PREFIX}:{}:{}", self.server, self.name)
+    }
 }
 
 pub(crate) struct CommandPopup {
     command_filter: String,
     builtins: Vec<(&'static str, SlashCommand)>,
     prompts: Vec<CustomPrompt>,
+    custom_commands: Vec<CustomCommand>,
+    mcp_prompts: Vec<McpPromptCommand>,
     state: ScrollState,
 }
 
@@ -57,6 +84,8 @@ impl CommandPopup {
             command_filter: String::new(),
             builtins,
             prompts,
+            custom_commands: Vec::new(),
+            mcp_prompts: Vec::new(),
             state: ScrollState::new(),
         }
     }
@@ -76,6 +105,26 @@ impl CommandPopup {
         self.prompts.get(idx)
     }
 
+    /// Custom commands named like a built-in are hidden; the built-in wins.
+    pub(crate) fn set_custom_commands(&mut self, mut commands: Vec<CustomCommand>) {
+        commands.retain(|command| !self.builtins.iter().any(|(n, _)| *n == command.name));
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        self.custom_commands = commands;
+    }
+
+    pub(crate) fn custom_command(&self, idx: usize) -> Option<&CustomCommand> {
+        self.custom_commands.get(idx)
+    }
+
+    pub(crate) fn set_mcp_prompts(&mut self, mut prompts: Vec<McpPromptCommand>) {
+        prompts.sort_by_key(McpPromptCommand::command);
+        self.mcp_prompts = prompts;
+    }
+
+    pub(crate) fn mcp_prompt(&self, idx: usize) -> Option<&McpPromptCommand> {
+        self.mcp_prompts.get(idx)
+    }
+
     /// Update the filter string based on the current composer text. The text
     /// passed in is expected to start with a leading '/'. Everything after the
     /// *first* '/" on the *first* line becomes the active filter that is used
@@ -131,6 +180,12 @@ impl CommandPopup {
             for idx in 0..self.prompts.len() {
                 out.push((CommandItem::UserPrompt(idx), None));
             }
+            for idx in 0..self.custom_commands.len() {
+                out.push((CommandItem::CustomCommand(idx), None));
+            }
+            for idx in 0..self.mcp_prompts.len() {
+                out.push((CommandItem::McpPrompt(idx), None));
+            }
             return out;
         }
 
@@ -177,6 +232,20 @@ impl CommandPopup {
                 prompt_prefix_len,
             );
         }
+        for (idx, command) in self.custom_commands.iter().enumerate() {
+            push_match(CommandItem::CustomCommand(idx), &command.name, None, 0);
+        }
+        // MCP prompts match on "mcp:server:name", "server:name", or "name".
+        for (idx, p) in self.mcp_prompts.iter().enumerate() {
+            let display = p.command();
+            let name_offset = display.chars().count() - p.name.chars().count();
+            push_match(
+                CommandItem::McpPrompt(idx),
+                &display,
+                Some(&p.name),
+                name_offset,
+            );
+        }
 
         out.extend(exact);
         out.extend(prefix);
@@ -209,6 +278,25 @@ impl CommandPopup {
                             description,
                         )
                     }
+                    CommandItem::CustomCommand(i) => {
+                        let command = &self.custom_commands[i];
+                        let description = command
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| "run custom command".to_string());
+                        (format!("/{}", command.name), description)
+                    }
+                    CommandItem::McpPrompt(i) => {
+                        let prompt = &self.mcp_prompts[i];
+                        let mut description = prompt
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| format!("send prompt from {}", prompt.server));
+                        if !prompt.arguments.is_empty() {
+                            description.push_str(&format!(" ({})", prompt.arguments.join(", ")));
+                        }
+                        (format!("/{}", prompt.command()), description)
+                    }
                 };
                 GenericDisplayRow {
                     name,
@@ -277,7 +365,9 @@ mod tests {
         let matches = popup.filtered_items();
         let has_init = matches.iter().any(|item| match item {
             CommandItem::Builtin(cmd) => cmd.command() == "init",
-            CommandItem::UserPrompt(_) => false,
+            CommandItem::UserPrompt(_)
+            | CommandItem::CustomCommand(_)
+            | CommandItem::McpPrompt(_) => false,
         });
         assert!(
             has_init,
@@ -295,7 +385,13 @@ mod tests {
         let selected = popup.selected_item();
         match selected {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "init"),
-            Some(CommandItem::UserPrompt(_)) => panic!("unexpected prompt selected for '/init'"),
+            Some(
+                CommandItem::UserPrompt(_)
+                | CommandItem::CustomCommand(_)
+                | CommandItem::McpPrompt(_),
+            ) => {
+                panic!("unexpected prompt selected for '/init'")
+            }
             None => panic!("expected a selected command for exact match"),
         }
     }
@@ -307,7 +403,11 @@ mod tests {
         let matches = popup.filtered_items();
         match matches.first() {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "model"),
-            Some(CommandItem::UserPrompt(_)) => {
+            Some(
+                CommandItem::UserPrompt(_)
+                | CommandItem::CustomCommand(_)
+                | CommandItem::McpPrompt(_),
+            ) => {
                 panic!("unexpected prompt ranked before '/model' for '/mo'")
             }
             None => panic!("expected at least one match for '/mo'"),
@@ -324,10 +424,12 @@ mod tests {
             .into_iter()
             .filter_map(|item| match item {
                 CommandItem::Builtin(cmd) => Some(cmd.command()),
-                CommandItem::UserPrompt(_) => None,
+                CommandItem::UserPrompt(_)
+                | CommandItem::CustomCommand(_)
+                | CommandItem::McpPrompt(_) => None,
             })
             .collect();
-        assert_eq!(cmds, vec!["model", "mention", "mcp"]);
+        assert_eq!(cmds, vec!["model", "merge", "mention", "mcp"]);
     }
 
     #[test]
@@ -385,6 +487,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mcp_prompts_match_by_prompt_name() {
+        let mut popup = CommandPopup::new(Vec::new(), CommandPopupFlags::default());
+        popup.set_mcp_prompts(vec![McpPromptCommand {
+            server: "github".to_string(),
+            name: "review_pr".to_string(),
+            description: None,
+            arguments: vec!["pr".to_string()],
+        }]);
+        popup.on_composer_text_change("/review_".to_string());
+
+        assert_eq!(popup.selected_item(), Some(CommandItem::McpPrompt(0)));
+        let rows = popup.rows_from_matches(vec![(CommandItem::McpPrompt(0), None)]);
+        let row = rows.first().expect("row");
+        assert_eq!(row.name, "/mcp:github:review_pr");
+        assert_eq!(
+            row.description.as_deref(),
+            Some("send prompt from github (pr)")
+        );
+    }
+
+    #[test]
+    fn custom_commands_list_by_name_and_yield_to_builtins() {
+        let command = |name: &str, description: Option<&str>| CustomCommand {
+            name: name.to_string(),
+            path: format!("/repo/.codex/commands/{name}.md").into(),
+            content: "body".to_string(),
+            description: description.map(str::to_string),
+            argument_hint: None,
+            model: None,
+            mode: None,
+            source: codex_protocol::custom_prompts::CustomCommandSource::Repo,
+        };
+        let mut popup = CommandPopup::new(Vec::new(), CommandPopupFlags::default());
+        popup.set_custom_commands(vec![
+            command("release-notes", Some("Draft release notes")),
+            command("init", None),
+            command("changelog", None),
+        ]);
+        popup.on_composer_text_change("/rel".to_string());
+
+        assert_eq!(popup.selected_item(), Some(CommandItem::CustomCommand(1)));
+        let rows = popup.rows_from_matches(vec![
+            (CommandItem::CustomCommand(0), None),
+            (CommandItem::CustomCommand(1), None),
+        ]);
+        let rows: Vec<(&str, Option<&str>)> = rows
+            .iter()
+            .map(|row| (row.name.as_str(), row.description.as_deref()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("/changelog", Some("run custom command")),
+                ("/release-notes", Some("Draft release notes")),
+            ]
+        );
+    }
+
     #[test]
     fn prompt_description_uses_frontmatter_metadata() {
         let popup = CommandPopup::new(
@@ -432,7 +593,9 @@ mod tests {
             .into_iter()
             .filter_map(|item| match item {
                 CommandItem::Builtin(cmd) => Some(cmd.command()),
-                CommandItem::UserPrompt(_) => None,
+                CommandItem::UserPrompt(_)
+                | CommandItem::CustomCommand(_)
+                | CommandItem::McpPrompt(_) => None,
             })
             .collect();
         assert!(
@@ -451,7 +614,9 @@ mod tests {
             .into_iter()
             .filter_map(|item| match item {
                 CommandItem::Builtin(cmd) => Some(cmd.command()),
-                CommandItem::UserPrompt(_) => None,
+                CommandItem::UserPrompt(_)
+                | CommandItem::CustomCommand(_)
+                | CommandItem::McpPrompt(_) => None,
             })
             .collect();
         assert!(
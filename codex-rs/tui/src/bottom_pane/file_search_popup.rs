@@ -1,3 +1,4 @@
+use codex_core::code_outline::SymbolMatch;
 use codex_file_search::FileMatch;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
@@ -11,7 +12,49 @@ use super::scroll_state::ScrollState;
 use super::selection_popup_common::GenericDisplayRow;
 use super::selection_popup_common::render_rows;
 
-/// Visual state for the file-search popup.
+/// A row of the popup. `insert` is the text that replaces the `@token` when
+/// the row is selected.
+#[derive(Clone)]
+struct MentionMatch {
+    insert: String,
+    label: String,
+    indices: Option<Vec<u32>>,
+    description: Option<String>,
+}
+
+impl From<FileMatch> for MentionMatch {
+    fn from(file_match: FileMatch) -> Self {
+        Self {
+            insert: file_match.path.clone(),
+            label: file_match.path,
+            indices: file_match.indices,
+            description: None,
+        }
+    }
+}
+
+/// Symbols insert an `@#name` mention, qualified with the container when
+/// there is one, so the session attaches the definition's source.
+impl From<SymbolMatch> for MentionMatch {
+    fn from(symbol_match: SymbolMatch) -> Self {
+        let symbol = symbol_match.symbol;
+        let location = format!("{}:{}", symbol_match.path, symbol.start_line);
+        let label = match &symbol.container {
+            Some(container) => format!("{container}.{}", symbol.name),
+            None => symbol.name.clone(),
+        };
+        Self {
+            insert: format!("@#{label}"),
+            label,
+            indices: None,
+            description: Some(format!("{} · {location}", symbol.kind.as_str())),
+        }
+    }
+}
+
+/// Visual state for the file-search popup. Also lists symbol definitions:
+/// only symbols for `@#name`, and symbols after the files for a plain
+/// `@name`, except that a symbol named exactly `name` comes first.
 pub(crate) struct FileSearchPopup {
     /// Query corresponding to the `matches` currently shown.
     display_query: String,
@@ -20,8 +63,13 @@ pub(crate) struct FileSearchPopup {
     pending_query: String,
     /// When `true` we are still waiting for results for `pending_query`.
     waiting: bool,
-    /// Cached matches; paths relative to the search dir.
-    matches: Vec<FileMatch>,
+    /// Rows shown: `files` and `symbols` merged.
+    matches: Vec<MentionMatch>,
+    /// File matches for `display_query`; paths relative to the search dir.
+    files: Vec<MentionMatch>,
+    /// Symbol matches for `display_query`, and whether each is named exactly
+    /// like the query.
+    symbols: Vec<(bool, MentionMatch)>,
     /// Shared selection/scroll state.
     state: ScrollState,
 }
@@ -33,6 +81,8 @@ impl FileSearchPopup {
             pending_query: String::new(),
             waiting: true,
             matches: Vec::new(),
+            files: Vec::new(),
+            symbols: Vec::new(),
             state: ScrollState::new(),
         }
     }
@@ -53,6 +103,8 @@ impl FileSearchPopup {
 
         if !keep_existing {
             self.matches.clear();
+            self.files.clear();
+            self.symbols.clear();
             self.state.reset();
         }
     }
@@ -64,19 +116,51 @@ impl FileSearchPopup {
         self.pending_query.clear();
         self.waiting = false;
         self.matches.clear();
+        self.files.clear();
+        self.symbols.clear();
         // Reset selection/scroll state when showing the empty prompt.
         self.state.reset();
     }
 
-    /// Replace matches when a `FileSearchResult` arrives.
-    /// Replace matches. Only applied when `query` matches `pending_query`.
+    /// Replace the file matches when a `FileSearchResult` arrives. Symbols
+    /// shown for an earlier query are dropped; the symbol search for this
+    /// query reports right after. Only applied when `query` matches
+    /// `pending_query`.
     pub(crate) fn set_matches(&mut self, query: &str, matches: Vec<FileMatch>) {
         if query != self.pending_query {
             return; // stale
         }
+        self.files = matches.into_iter().map(MentionMatch::from).collect();
+        self.symbols.clear();
+        self.set_rows(query);
+    }
+
+    /// Replace the symbol matches when a `SymbolSearchResult` arrives.
+    pub(crate) fn set_symbol_matches(&mut self, query: &str, matches: Vec<SymbolMatch>) {
+        if query != self.pending_query {
+            return; // stale
+        }
+        let name = query.strip_prefix('#').unwrap_or(query);
+        self.symbols = matches
+            .into_iter()
+            .map(|found| (found.symbol.name.eq_ignore_ascii_case(name), found.into()))
+            .collect();
+        if query.starts_with('#') {
+            self.files.clear();
+        }
+        self.set_rows(query);
+    }
 
+    fn set_rows(&mut self, query: &str) {
+        let (exact, other): (Vec<_>, Vec<_>) = self.symbols.iter().partition(|(exact, _)| *exact);
+        self.matches = exact
+            .into_iter()
+            .map(|(_, row)| row)
+            .chain(&self.files)
+            .chain(other.into_iter().map(|(_, row)| row))
+            .cloned()
+            .collect();
         self.display_query = query.to_string();
-        self.matches = matches;
         self.waiting = false;
         let len = self.matches.len();
         self.state.clamp_selection(len);
@@ -101,7 +185,7 @@ impl FileSearchPopup {
         self.state
             .selected_idx
             .and_then(|idx| self.matches.get(idx))
-            .map(|file_match| file_match.path.as_str())
+            .map(|mention| mention.insert.as_str())
     }
 
     pub(crate) fn calculate_required_height(&self) -> u16 {
@@ -124,13 +208,13 @@ impl WidgetRef for &FileSearchPopup {
             self.matches
                 .iter()
                 .map(|m| GenericDisplayRow {
-                    name: m.path.clone(),
+                    name: m.label.clone(),
                     match_indices: m
                         .indices
                         .as_ref()
                         .map(|v| v.iter().map(|&i| i as usize).collect()),
                     display_shortcut: None,
-                    description: None,
+                    description: m.description.clone(),
                     wrap_indent: None,
                     disabled_reason: None,
                 })
@@ -153,3 +237,80 @@ impl WidgetRef for &FileSearchPopup {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::code_outline::Symbol;
+    use codex_core::code_outline::SymbolKind;
+    use pretty_assertions::assert_eq;
+
+    fn symbol(name: &str, container: Option<&str>, path: &str, line: usize) -> SymbolMatch {
+        SymbolMatch {
+            path: path.to_string(),
+            symbol: Symbol {
+                name: name.to_string(),
+                kind: SymbolKind::Method,
+                container: container.map(str::to_string),
+                start_line: line,
+                end_line: line + 2,
+                signature: format!("pub fn {name}() -> Self {{"),
+            },
+        }
+    }
+
+    fn file(path: &str) -> FileMatch {
+        FileMatch {
+            score: 1,
+            path: path.to_string(),
+            indices: None,
+        }
+    }
+
+    fn inserts(popup: &FileSearchPopup) -> Vec<&str> {
+        popup
+            .matches
+            .iter()
+            .map(|row| row.insert.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn symbol_matches_insert_a_symbol_mention() {
+        let mut popup = FileSearchPopup::new();
+        popup.set_query("#load");
+        popup.set_symbol_matches(
+            "#load",
+            vec![symbol("load", Some("Config"), "src/config.rs", 4)],
+        );
+
+        assert_eq!(popup.selected_match(), Some("@#Config.load"));
+        let row = &popup.matches[0];
+        assert_eq!(
+            (row.label.as_str(), row.description.as_deref()),
+            ("Config.load", Some("method · src/config.rs:4"))
+        );
+    }
+
+    #[test]
+    fn plain_queries_list_files_then_symbols_with_exact_names_first() {
+        let mut popup = FileSearchPopup::new();
+        popup.set_query("load");
+        popup.set_matches("load", vec![file("src/loader.rs")]);
+        assert_eq!(inserts(&popup), vec!["src/loader.rs"]);
+
+        popup.set_symbol_matches(
+            "load",
+            vec![
+                symbol("load", None, "src/lib.rs", 1),
+                symbol("reload", None, "src/lib.rs", 9),
+            ],
+        );
+        assert_eq!(inserts(&popup), vec!["@#load", "src/loader.rs", "@#reload"]);
+
+        // A stale symbol result for an older query is ignored.
+        popup.set_query("loade");
+        popup.set_symbol_matches("load", vec![symbol("unload", None, "src/lib.rs", 5)]);
+        assert_eq!(inserts(&popup), vec!["@#load", "src/loader.rs", "@#reload"]);
+    }
+}
@@ -17,14 +17,20 @@ use std::path::PathBuf;
 
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::queued_user_messages::QueuedUserMessages;
+use crate::bottom_pane::status_line::StatusLine;
 use crate::bottom_pane::unified_exec_footer::UnifiedExecFooter;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::keymap::KeyAction;
+use crate::keymap::keymap;
 use crate::render::renderable::FlexRenderable;
 use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableItem;
 use crate::tui::FrameRequester;
 use bottom_pane_view::BottomPaneView;
+use codex_core::code_outline::SymbolMatch;
+use codex_core::config::types::EditingMode;
+use codex_core::config::types::StatusLineItem;
 use codex_core::features::Features;
 use codex_core::skills::model::SkillMetadata;
 use codex_file_search::FileMatch;
@@ -35,6 +41,7 @@ use crossterm::event::KeyEvent;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use std::time::Duration;
+use std::time::Instant;
 
 mod approval_overlay;
 mod request_user_input;
@@ -72,8 +79,11 @@ pub mod popup_consts;
 mod queued_user_messages;
 mod scroll_state;
 mod selection_popup_common;
+mod status_line;
+pub(crate) use status_line::StatusLineState;
 mod textarea;
 mod unified_exec_footer;
+mod vim;
 pub(crate) use feedback_view::FeedbackNoteView;
 
 /// How long the "press again to quit" hint stays visible.
@@ -105,7 +115,9 @@ pub(crate) enum CancellationEvent {
 
 pub(crate) use chat_composer::ChatComposer;
 pub(crate) use chat_composer::InputResult;
+use codex_protocol::custom_prompts::CustomCommand;
 use codex_protocol::custom_prompts::CustomPrompt;
+use command_popup::McpPromptCommand;
 
 use crate::status_indicator_widget::StatusIndicatorWidget;
 pub(crate) use experimental_features_view::BetaFeatureItem;
@@ -140,6 +152,8 @@ pub(crate) struct BottomPane {
     unified_exec_footer: UnifiedExecFooter,
     /// Queued user messages to show above the composer while a turn is running.
     queued_user_messages: QueuedUserMessages,
+    /// Configured `tui.status_line`, shown below the composer and any view.
+    status_line: StatusLine,
     context_window_percent: Option<i64>,
     context_window_used_tokens: Option<i64>,
 }
@@ -186,6 +200,7 @@ impl BottomPane {
             status: None,
             unified_exec_footer: UnifiedExecFooter::new(),
             queued_user_messages: QueuedUserMessages::new(),
+            status_line: StatusLine::new(Vec::new()),
             esc_backtrack_hint: false,
             animations_enabled,
             context_window_percent: None,
@@ -265,9 +280,10 @@ impl BottomPane {
             // If a task is running and a status line is visible, allow Esc to
             // send an interrupt even while the composer has focus.
             // When a popup is active, prefer dismissing it over interrupting the task.
-            if key_event.code == KeyCode::Esc
+            if keymap().matches(KeyAction::Interrupt, key_event)
                 && self.is_task_running
                 && !self.composer.popup_active()
+                && !(key_event.code == KeyCode::Esc && self.composer.takes_esc())
                 && let Some(status) = &self.status
             {
                 // Send Op::Interrupt
@@ -407,6 +423,21 @@ impl BottomPane {
         }
     }
 
+    /// Like [`Self::update_status`], with a countdown to `resume_at` after
+    /// the header.
+    pub(crate) fn update_status_countdown(
+        &mut self,
+        header: String,
+        details: Option<String>,
+        resume_at: Instant,
+    ) {
+        if let Some(status) = self.status.as_mut() {
+            status.update_header_with_countdown(header, resume_at);
+            status.update_details(details);
+            self.request_redraw();
+        }
+    }
+
     /// Show the transient "press again to quit" hint for `key`.
     ///
     /// `ChatWidget` owns the quit shortcut state machine (it decides when quit is
@@ -544,18 +575,72 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    pub(crate) fn set_editing_mode(&mut self, mode: EditingMode) {
+        self.composer.set_editing_mode(mode);
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_status_line_items(&mut self, items: Vec<StatusLineItem>) {
+        self.status_line = StatusLine::new(items);
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_status_line_state(&mut self, state: StatusLineState) {
+        if self.status_line.set_state(state) {
+            self.request_redraw();
+        }
+    }
+
     pub(crate) fn set_unified_exec_processes(&mut self, processes: Vec<String>) {
         if self.unified_exec_footer.set_processes(processes) {
             self.request_redraw();
         }
     }
 
+    /// Update the running background processes shown under the status line.
+    pub(crate) fn set_background_processes(&mut self, processes: Vec<String>) {
+        if self.unified_exec_footer.set_background_processes(processes) {
+            self.request_redraw();
+        }
+    }
+
     /// Update custom prompts available for the slash popup.
     pub(crate) fn set_custom_prompts(&mut self, prompts: Vec<CustomPrompt>) {
         self.composer.set_custom_prompts(prompts);
         self.request_redraw();
     }
 
+    /// Update the `.codex/commands` slash commands offered as `/<name>`.
+    pub(crate) fn set_custom_commands(&mut self, commands: Vec<CustomCommand>) {
+        self.composer.set_custom_commands(commands);
+        self.request_redraw();
+    }
+
+    /// Update the MCP server prompts offered as `/mcp:<server>:<prompt>`.
+    pub(crate) fn set_mcp_prompts(
+        &mut self,
+        prompts: std::collections::HashMap<String, Vec<mcp_types::Prompt>>,
+    ) {
+        let commands = prompts
+            .into_iter()
+            .flat_map(|(server, prompts)| {
+                prompts.into_iter().map(move |prompt| McpPromptCommand {
+                    server: server.clone(),
+                    name: prompt.name,
+                    description: prompt.description.or(prompt.title),
+                    arguments: prompt
+                        .arguments
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|arg| arg.name)
+                        .collect(),
+                })
+            })
+            .collect();
+        self.composer.set_mcp_prompts(commands);
+        self.request_redraw();
+    }
+
     pub(crate) fn composer_is_empty(&self) -> bool {
         self.composer.is_empty()
     }
@@ -568,7 +653,10 @@ impl BottomPane {
     /// overlays or popups and not running a task. This is the safe context to
     /// use Esc-Esc for backtracking from the main view.
     pub(crate) fn is_normal_backtrack_mode(&self) -> bool {
-        !self.is_task_running && self.view_stack.is_empty() && !self.composer.popup_active()
+        !self.is_task_running
+            && self.view_stack.is_empty()
+            && !self.composer.popup_active()
+            && !self.composer.takes_esc()
     }
 
     /// Return true when no popups or modal views are active, regardless of task state.
@@ -609,6 +697,20 @@ impl BottomPane {
         self.push_view(Box::new(modal));
     }
 
+    /// Closes the approval prompt for `id` once core resolved it without the
+    /// user, e.g. after an approval timeout.
+    pub(crate) fn dismiss_approval_request(&mut self, id: &str) {
+        if let Some(view) = self.view_stack.last_mut()
+            && view.dismiss_approval_request(id)
+        {
+            if view.is_complete() {
+                self.view_stack.pop();
+                self.on_active_view_complete();
+            }
+            self.request_redraw();
+        }
+    }
+
     /// Called when the agent requests user input.
     pub fn push_user_input_request(&mut self, request: RequestUserInputEvent) {
         let request = if let Some(view) = self.view_stack.last_mut() {
@@ -692,6 +794,11 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    pub(crate) fn on_symbol_search_result(&mut self, query: String, matches: Vec<SymbolMatch>) {
+        self.composer.on_symbol_search_result(query, matches);
+        self.request_redraw();
+    }
+
     pub(crate) fn attach_image(&mut self, path: PathBuf) {
         if self.view_stack.is_empty() {
             self.composer.attach_image(path);
@@ -712,6 +819,17 @@ impl BottomPane {
     }
 
     fn as_renderable(&'_ self) -> RenderableItem<'_> {
+        let pane = self.pane_renderable();
+        if self.status_line.is_empty() {
+            return pane;
+        }
+        let mut flex = FlexRenderable::new();
+        flex.push(1, pane);
+        flex.push(0, RenderableItem::Borrowed(&self.status_line));
+        RenderableItem::Owned(Box::new(flex))
+    }
+
+    fn pane_renderable(&'_ self) -> RenderableItem<'_> {
         if let Some(view) = self.active_view() {
             RenderableItem::Borrowed(view)
         } else {
@@ -789,6 +907,8 @@ mod tests {
             command: vec!["echo".into(), "ok".into()],
             reason: None,
             proposed_execpolicy_amendment: None,
+            explanation: None,
+            sandbox_warnings: Vec::new(),
         }
     }
 
@@ -813,6 +933,47 @@ mod tests {
         assert_eq!(CancellationEvent::NotHandled, pane.on_ctrl_c());
     }
 
+    #[test]
+    fn status_line_stays_below_the_composer_and_views() {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let features = Features::with_defaults();
+        let mut pane = BottomPane::new(BottomPaneParams {
+            app_event_tx: tx,
+            frame_requester: FrameRequester::test_dummy(),
+            has_input_focus: true,
+            enhanced_keys_supported: false,
+            placeholder_text: "Ask Codex to do anything".to_string(),
+            disable_paste_burst: false,
+            animations_enabled: true,
+            skills: Some(Vec::new()),
+        });
+        let width = 60;
+        let height_without = pane.desired_height(width);
+
+        pane.set_status_line_items(vec![StatusLineItem::Model, StatusLineItem::Context]);
+        pane.set_status_line_state(StatusLineState {
+            model: "gpt-5.1-codex".to_string(),
+            context_left_percent: Some(80),
+            ..Default::default()
+        });
+        let height = pane.desired_height(width);
+        assert_eq!(height, height_without + 1);
+        let rendered = render_snapshot(&pane, Rect::new(0, 0, width, height));
+        assert_eq!(
+            rendered.lines().last().map(str::trim_end),
+            Some("  gpt-5.1-codex · 80% context left")
+        );
+
+        pane.push_approval_request(exec_request(), &features);
+        let height = pane.desired_height(width);
+        let rendered = render_snapshot(&pane, Rect::new(0, 0, width, height));
+        assert_eq!(
+            rendered.lines().last().map(str::trim_end),
+            Some("  gpt-5.1-codex · 80% context left")
+        );
+    }
+
     // live ring removed; related tests deleted.
 
     #[test]
@@ -1188,4 +1349,34 @@ mod tests {
             "expected Esc to send Op::Interrupt while a task is running"
         );
     }
+
+    #[test]
+    fn esc_in_vim_insert_mode_switches_modes_before_interrupting() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let mut pane = BottomPane::new(BottomPaneParams {
+            app_event_tx: tx,
+            frame_requester: FrameRequester::test_dummy(),
+            has_input_focus: true,
+            enhanced_keys_supported: false,
+            placeholder_text: "Ask Codex to do anything".to_string(),
+            disable_paste_burst: false,
+            animations_enabled: true,
+            skills: Some(Vec::new()),
+        });
+        pane.set_editing_mode(EditingMode::Vim);
+        pane.set_task_running(true);
+
+        pane.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(
+            !matches!(rx.try_recv(), Ok(AppEvent::CodexOp(Op::Interrupt))),
+            "expected the first Esc to leave insert mode"
+        );
+
+        pane.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(
+            matches!(rx.try_recv(), Ok(AppEvent::CodexOp(Op::Interrupt))),
+            "expected Esc in normal mode to interrupt"
+        );
+    }
 }
@@ -0,0 +1,202 @@
+//! Status line below the composer, configured with `tui.status_line`.
+//!
+//! Shows the configured items in order, separated by ` · `. Items without a
+//! value yet (no token usage reported, no known prices, no running tasks) are
+//! left out rather than shown empty.
+
+use codex_core::config::types::StatusLineItem;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+
+use crate::live_wrap::take_prefix_by_width;
+use crate::render::renderable::Renderable;
+use crate::status::format_tokens_compact;
+
+/// Session values the status line can show.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct StatusLineState {
+    pub(crate) model: String,
+    pub(crate) reasoning_effort: Option<String>,
+    pub(crate) context_left_percent: Option<i64>,
+    pub(crate) total_tokens: Option<i64>,
+    pub(crate) session_usd: Option<f64>,
+    pub(crate) sandbox: String,
+    pub(crate) approval: String,
+    /// Background tasks and subagents currently running.
+    pub(crate) running_tasks: usize,
+}
+
+pub(crate) struct StatusLine {
+    items: Vec<StatusLineItem>,
+    state: StatusLineState,
+}
+
+impl StatusLine {
+    pub(crate) fn new(items: Vec<StatusLineItem>) -> Self {
+        Self {
+            items,
+            state: StatusLineState::default(),
+        }
+    }
+
+    pub(crate) fn set_state(&mut self, state: StatusLineState) -> bool {
+        if self.state == state {
+            return false;
+        }
+        self.state = state;
+        true
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn item_text(&self, item: StatusLineItem) -> Option<String> {
+        let state = &self.state;
+        match item {
+            StatusLineItem::Model => {
+                if state.model.is_empty() {
+                    return None;
+                }
+                Some(match &state.reasoning_effort {
+                    Some(effort) => format!("{} {effort}", state.model),
+                    None => state.model.clone(),
+                })
+            }
+            StatusLineItem::Context => state
+                .context_left_percent
+                .map(|percent| format!("{}% context left", percent.clamp(0, 100))),
+            StatusLineItem::Tokens => state
+                .total_tokens
+                .map(|tokens| format!("{} tokens", format_tokens_compact(tokens))),
+            StatusLineItem::Cost => state.session_usd.map(|usd| format!("${usd:.2}")),
+            StatusLineItem::Sandbox => {
+                if state.sandbox.is_empty() {
+                    return None;
+                }
+                Some(format!("{} · {}", state.sandbox, state.approval))
+            }
+            StatusLineItem::Tasks => match state.running_tasks {
+                0 => None,
+                1 => Some("1 task running".to_string()),
+                count => Some(format!("{count} tasks running")),
+            },
+        }
+    }
+
+    fn render_lines(&self, width: u16) -> Vec<Line<'static>> {
+        if self.items.is_empty() || width < 4 {
+            return Vec::new();
+        }
+
+        let parts: Vec<String> = self
+            .items
+            .iter()
+            .filter_map(|item| self.item_text(*item))
+            .collect();
+        let message = format!("  {}", parts.join(" · "));
+        let (truncated, _, _) = take_prefix_by_width(&message, width as usize);
+        vec![Line::from(truncated.dim())]
+    }
+}
+
+impl Renderable for StatusLine {
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        Paragraph::new(self.render_lines(area.width)).render(area, buf);
+    }
+
+    fn desired_height(&self, width: u16) -> u16 {
+        self.render_lines(width).len() as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn state() -> StatusLineState {
+        StatusLineState {
+            model: "gpt-5.1-codex".to_string(),
+            reasoning_effort: Some("high".to_string()),
+            context_left_percent: Some(72),
+            total_tokens: Some(12_345),
+            session_usd: Some(0.4217),
+            sandbox: "workspace-write".to_string(),
+            approval: "on-request".to_string(),
+            running_tasks: 2,
+        }
+    }
+
+    fn text(line: &StatusLine, width: u16) -> String {
+        line.render_lines(width)
+            .iter()
+            .flat_map(|line| line.spans.iter())
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn hidden_without_items() {
+        let mut line = StatusLine::new(Vec::new());
+        line.set_state(state());
+        assert_eq!(line.desired_height(80), 0);
+    }
+
+    #[test]
+    fn items_render_in_the_configured_order() {
+        let mut line = StatusLine::new(vec![
+            StatusLineItem::Context,
+            StatusLineItem::Model,
+            StatusLineItem::Cost,
+            StatusLineItem::Tasks,
+        ]);
+        line.set_state(state());
+        assert_eq!(
+            text(&line, 80),
+            "  72% context left · gpt-5.1-codex high · $0.42 · 2 tasks running"
+        );
+    }
+
+    #[test]
+    fn items_without_a_value_are_skipped() {
+        let mut line = StatusLine::new(vec![
+            StatusLineItem::Model,
+            StatusLineItem::Tokens,
+            StatusLineItem::Cost,
+            StatusLineItem::Tasks,
+        ]);
+        line.set_state(StatusLineState {
+            model: "o3".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(text(&line, 80), "  o3");
+        assert!(!line.set_state(StatusLineState {
+            model: "o3".to_string(),
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn long_lines_are_truncated_to_the_width() {
+        let mut line = StatusLine::new(vec![
+            StatusLineItem::Model,
+            StatusLineItem::Tokens,
+            StatusLineItem::Sandbox,
+        ]);
+        line.set_state(state());
+        assert_eq!(
+            text(&line, 80),
+            "  gpt-5.1-codex high · 12.3K tokens · workspace-write · on-request"
+        );
+        assert_eq!(text(&line, 24), "  gpt-5.1-codex high · 1");
+        assert_eq!(line.desired_height(24), 1);
+    }
+}
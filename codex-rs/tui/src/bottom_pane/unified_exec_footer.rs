@@ -9,12 +9,15 @@ use crate::render::renderable::Renderable;
 
 pub(crate) struct UnifiedExecFooter {
     processes: Vec<String>,
+    /// Commands of the agent's running `background_process` processes.
+    background_processes: Vec<String>,
 }
 
 impl UnifiedExecFooter {
     pub(crate) fn new() -> Self {
         Self {
             processes: Vec::new(),
+            background_processes: Vec::new(),
         }
     }
 
@@ -26,20 +29,46 @@ impl UnifiedExecFooter {
         true
     }
 
+    pub(crate) fn set_background_processes(&mut self, processes: Vec<String>) -> bool {
+        if self.background_processes == processes {
+            return false;
+        }
+        self.background_processes = processes;
+        true
+    }
+
     pub(crate) fn is_empty(&self) -> bool {
-        self.processes.is_empty()
+        self.processes.is_empty() && self.background_processes.is_empty()
     }
 
     fn render_lines(&self, width: u16) -> Vec<Line<'static>> {
-        if self.processes.is_empty() || width < 4 {
+        if width < 4 {
             return Vec::new();
         }
 
-        let count = self.processes.len();
-        let plural = if count == 1 { "" } else { "s" };
-        let message = format!("  {count} background terminal{plural} running · /ps to view");
-        let (truncated, _, _) = take_prefix_by_width(&message, width as usize);
-        vec![Line::from(truncated.dim())]
+        let mut messages = Vec::new();
+        if !self.processes.is_empty() {
+            let count = self.processes.len();
+            let plural = if count == 1 { "" } else { "s" };
+            messages.push(format!(
+                "  {count} background terminal{plural} running · /ps to view"
+            ));
+        }
+        if !self.background_processes.is_empty() {
+            let count = self.background_processes.len();
+            let plural = if count == 1 { "" } else { "es" };
+            messages.push(format!(
+                "  {count} background process{plural} running: {}",
+                self.background_processes.join(", ")
+            ));
+        }
+        messages
+            .into_iter()
+            .map(|message| {
+                let (truncated, _, _) = take_prefix_by_width(&message, width as usize);
+                Line::from(truncated.dim())
+            })
+            .collect()
     }
 }
 
@@ -90,4 +119,26 @@ mod tests {
         footer.render(Rect::new(0, 0, width, height), &mut buf);
         assert_snapshot!("render_many_sessions", format!("{buf:?}"));
     }
+
+    #[test]
+    fn background_processes_get_their_own_line() {
+        let mut footer = UnifiedExecFooter::new();
+        assert!(footer.set_background_processes(vec![
+            "npm run dev".to_string(),
+            "cargo watch -x check".to_string(),
+        ]));
+        assert!(!footer.is_empty());
+        let lines: Vec<String> = footer
+            .render_lines(80)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            lines,
+            vec!["  2 background processes running: npm run dev, cargo watch -x check"]
+        );
+
+        footer.set_processes(vec!["rg foo".to_string()]);
+        assert_eq!(footer.desired_height(80), 2);
+    }
 }
@@ -0,0 +1,838 @@
+//! Vim-style modal editing for the composer, enabled with
+//! `tui.editing_mode = "vim"`.
+//!
+//! Insert mode types as usual; Esc switches to normal mode. There, keys are
+//! motions (`h` `l` `w` `b` `e` `0` `^` `$` `j` `k` `gg` `G`), edits (`x`, `X`,
+//! `p`, `P`, `u`, ...) and the operators `d`, `c` and `y`, which take a motion,
+//! the `iw`/`aw` text objects, or act on the whole line when doubled (`dd`).
+//! Motions and most edits take a count (`3w`, `2dd`).
+//!
+//! Deleted and yanked text goes to the unnamed register, and also to a named
+//! one chosen with `"a`..`"z` (an uppercase name appends). Keys with Ctrl or
+//! Alt, Enter, Tab and the arrow keys keep their usual meaning in both modes,
+//! so submitting, history and the other shortcuts work the same.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use codex_protocol::user_input::TextElement;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+
+use crate::bottom_pane::textarea::TextArea;
+use crate::key_hint::has_ctrl_or_alt;
+
+/// Undo snapshots kept in normal mode.
+const MAX_UNDO: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VimMode {
+    Insert,
+    Normal,
+}
+
+/// Whether [`Vim::handle_key`] consumed a key or left it to the composer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VimKey {
+    Handled,
+    PassThrough,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+impl Operator {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'd' => Some(Self::Delete),
+            'c' => Some(Self::Change),
+            'y' => Some(Self::Yank),
+            _ => None,
+        }
+    }
+
+    fn key(self) -> char {
+        match self {
+            Self::Delete => 'd',
+            Self::Change => 'c',
+            Self::Yank => 'y',
+        }
+    }
+}
+
+/// A multi-key command waiting for its next key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pending {
+    /// `"` was typed; the next key names a register.
+    Register,
+    /// `g` was typed; `gg` goes to the first line.
+    G,
+    /// An operator waiting for its motion. `count` was typed before it.
+    Operator { op: Operator, count: usize },
+    /// `i` or `a` after an operator; `w` completes the text object.
+    TextObject { op: Operator, around: bool },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Register {
+    text: String,
+    /// Whole lines, put above or below the cursor line rather than inline.
+    linewise: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    text: String,
+    elements: Vec<TextElement>,
+    cursor: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct Vim {
+    mode: VimMode,
+    pending: Option<Pending>,
+    /// Count typed so far; 0 when none was given.
+    count: usize,
+    /// Register chosen with `"x` for the next delete, yank or put.
+    register: Option<char>,
+    registers: HashMap<char, Register>,
+    undo: Vec<Snapshot>,
+}
+
+impl Vim {
+    pub(crate) fn new() -> Self {
+        Self {
+            mode: VimMode::Insert,
+            pending: None,
+            count: 0,
+            register: None,
+            registers: HashMap::new(),
+            undo: Vec::new(),
+        }
+    }
+
+    pub(crate) fn mode(&self) -> VimMode {
+        self.mode
+    }
+
+    /// Back to insert mode, e.g. after a message was submitted.
+    pub(crate) fn reset(&mut self) {
+        self.mode = VimMode::Insert;
+        self.clear_pending();
+    }
+
+    pub(crate) fn handle_key(&mut self, textarea: &mut TextArea, key: KeyEvent) -> VimKey {
+        if has_ctrl_or_alt(key.modifiers) {
+            self.clear_pending();
+            return VimKey::PassThrough;
+        }
+        match self.mode {
+            VimMode::Insert => {
+                if key.code != KeyCode::Esc {
+                    return VimKey::PassThrough;
+                }
+                self.mode = VimMode::Normal;
+                let text = textarea.text();
+                let pos = textarea.cursor();
+                if pos > line_start(text, pos) {
+                    textarea.set_cursor(prev_pos(text, pos));
+                }
+                VimKey::Handled
+            }
+            VimMode::Normal => self.handle_normal_key(textarea, key),
+        }
+    }
+
+    fn clear_pending(&mut self) {
+        self.pending = None;
+        self.count = 0;
+        self.register = None;
+    }
+
+    /// The typed count, or 1, and clears it.
+    fn take_count(&mut self) -> usize {
+        std::mem::take(&mut self.count).max(1)
+    }
+
+    fn handle_normal_key(&mut self, textarea: &mut TextArea, key: KeyEvent) -> VimKey {
+        let KeyCode::Char(c) = key.code else {
+            let had_pending = self.pending.is_some() || self.count > 0 || self.register.is_some();
+            self.clear_pending();
+            return match key.code {
+                KeyCode::Esc if had_pending => VimKey::Handled,
+                KeyCode::Backspace => {
+                    let text = textarea.text();
+                    let pos = textarea.cursor();
+                    if pos > line_start(text, pos) {
+                        textarea.set_cursor(prev_pos(text, pos));
+                    }
+                    VimKey::Handled
+                }
+                KeyCode::Delete => {
+                    self.delete_chars(textarea, 1, true);
+                    VimKey::Handled
+                }
+                _ => VimKey::PassThrough,
+            };
+        };
+
+        if let Some(digit) = c.to_digit(10)
+            && (digit > 0 || self.count > 0)
+            && self.pending != Some(Pending::Register)
+        {
+            self.count = self.count.saturating_mul(10).saturating_add(digit as usize);
+            return VimKey::Handled;
+        }
+
+        match self.pending.take() {
+            Some(Pending::Register) => {
+                if c.is_ascii_alphanumeric() || c == '"' {
+                    self.register = Some(c);
+                } else {
+                    self.register = None;
+                }
+            }
+            Some(Pending::G) => {
+                if c == 'g' {
+                    self.count = 0;
+                    textarea.set_cursor(first_non_blank(textarea.text(), 0));
+                }
+            }
+            Some(Pending::Operator { op, count }) => {
+                let count = count.max(1) * self.take_count();
+                if c == op.key() {
+                    self.apply_to_lines(textarea, op, count);
+                } else if c == 'i' || c == 'a' {
+                    self.pending = Some(Pending::TextObject {
+                        op,
+                        around: c == 'a',
+                    });
+                    return VimKey::Handled;
+                } else if let Some(range) =
+                    operator_range(textarea.text(), textarea.cursor(), op, c, count)
+                {
+                    self.apply(textarea, op, range, false);
+                }
+            }
+            Some(Pending::TextObject { op, around }) => {
+                if c == 'w' {
+                    let range = word_object(textarea.text(), textarea.cursor(), around);
+                    self.apply(textarea, op, range, false);
+                }
+            }
+            None => self.handle_command(textarea, c),
+        }
+        if self.pending.is_none() {
+            self.count = 0;
+            if self.mode == VimMode::Normal {
+                clamp_cursor(textarea);
+            }
+        }
+        VimKey::Handled
+    }
+
+    fn handle_command(&mut self, textarea: &mut TextArea, c: char) {
+        if let Some(op) = Operator::from_char(c) {
+            let count = std::mem::take(&mut self.count);
+            self.pending = Some(Pending::Operator { op, count });
+            return;
+        }
+        let count = self.take_count();
+        let text = textarea.text();
+        let pos = textarea.cursor();
+        match c {
+            '"' => {
+                self.count = 0;
+                self.pending = Some(Pending::Register);
+            }
+            'g' => self.pending = Some(Pending::G),
+            'G' => {
+                let last_line = text.rfind('\n').map_or(0, |idx| idx + 1);
+                textarea.set_cursor(first_non_blank(text, last_line));
+            }
+            'j' => {
+                for _ in 0..count {
+                    textarea.move_cursor_down();
+                }
+            }
+            'k' => {
+                for _ in 0..count {
+                    textarea.move_cursor_up();
+                }
+            }
+            'i' => self.enter_insert(textarea, pos),
+            'a' => {
+                let at = if pos < line_end(text, pos) {
+                    next_pos(text, pos)
+                } else {
+                    pos
+                };
+                self.enter_insert(textarea, at);
+            }
+            'I' => self.enter_insert(textarea, first_non_blank(text, pos)),
+            'A' => self.enter_insert(textarea, line_end(text, pos)),
+            'o' | 'O' => {
+                self.save_undo(textarea);
+                let at = if c == 'o' {
+                    line_end(text, pos)
+                } else {
+                    line_start(text, pos)
+                };
+                textarea.insert_str_at(at, "\n");
+                textarea.set_cursor(if c == 'o' { at + 1 } else { at });
+                self.mode = VimMode::Insert;
+            }
+            'x' => self.delete_chars(textarea, count, true),
+            'X' => self.delete_chars(textarea, count, false),
+            's' => {
+                let end = (0..count).fold(pos, |at, _| next_pos(text, at).min(line_end(text, pos)));
+                self.apply(textarea, Operator::Change, pos..end, false);
+            }
+            'D' | 'C' => {
+                let op = if c == 'D' {
+                    Operator::Delete
+                } else {
+                    Operator::Change
+                };
+                self.apply(textarea, op, pos..line_end(text, pos), false);
+            }
+            'S' => self.apply_to_lines(textarea, Operator::Change, count),
+            'Y' => self.apply_to_lines(textarea, Operator::Yank, count),
+            'p' => self.put(textarea, true, count),
+            'P' => self.put(textarea, false, count),
+            'u' => {
+                for _ in 0..count {
+                    let Some(snapshot) = self.undo.pop() else {
+                        break;
+                    };
+                    textarea.set_text_with_elements(&snapshot.text, &snapshot.elements);
+                    textarea.set_cursor(snapshot.cursor);
+                }
+            }
+            _ => {
+                if let Some(target) = motion(text, pos, c, count) {
+                    textarea.set_cursor(target);
+                }
+            }
+        }
+    }
+
+    fn enter_insert(&mut self, textarea: &mut TextArea, at: usize) {
+        // One snapshot per insert, so `u` undoes everything typed in it.
+        self.save_undo(textarea);
+        textarea.set_cursor(at);
+        self.mode = VimMode::Insert;
+    }
+
+    fn save_undo(&mut self, textarea: &TextArea) {
+        if self
+            .undo
+            .last()
+            .is_some_and(|snapshot| snapshot.text == textarea.text())
+        {
+            return;
+        }
+        if self.undo.len() == MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.undo.push(Snapshot {
+            text: textarea.text().to_string(),
+            elements: textarea.text_elements(),
+            cursor: textarea.cursor(),
+        });
+    }
+
+    /// Deletes `count` characters after (`x`) or before (`X`) the cursor,
+    /// without leaving the line.
+    fn delete_chars(&mut self, textarea: &mut TextArea, count: usize, forward: bool) {
+        let text = textarea.text();
+        let pos = textarea.cursor();
+        let range = if forward {
+            let end = line_end(text, pos);
+            pos..(0..count).fold(pos, |at, _| next_pos(text, at).min(end))
+        } else {
+            let start = line_start(text, pos);
+            (0..count).fold(pos, |at, _| prev_pos(text, at).max(start))..pos
+        };
+        if !range.is_empty() {
+            self.apply(textarea, Operator::Delete, range, false);
+        }
+    }
+
+    fn apply(
+        &mut self,
+        textarea: &mut TextArea,
+        op: Operator,
+        range: Range<usize>,
+        linewise: bool,
+    ) {
+        let Some(removed) = textarea.text().get(range.clone()).map(str::to_string) else {
+            return;
+        };
+        self.store(removed, linewise);
+        match op {
+            Operator::Yank => textarea.set_cursor(range.start.min(textarea.cursor())),
+            Operator::Delete | Operator::Change => {
+                self.save_undo(textarea);
+                textarea.replace_range(range.clone(), "");
+                textarea.set_cursor(range.start);
+                if op == Operator::Change {
+                    self.mode = VimMode::Insert;
+                }
+            }
+        }
+    }
+
+    /// `dd`, `cc`, `yy` and friends: the cursor line and the `count - 1`
+    /// lines below it.
+    fn apply_to_lines(&mut self, textarea: &mut TextArea, op: Operator, count: usize) {
+        let text = textarea.text();
+        let pos = textarea.cursor();
+        let start = line_start(text, pos);
+        let end = (1..count).fold(line_end(text, pos), |end, _| {
+            if end < text.len() {
+                line_end(text, end + 1)
+            } else {
+                end
+            }
+        });
+        match op {
+            Operator::Yank => {
+                self.store(text[start..end].to_string(), true);
+            }
+            Operator::Change => self.apply(textarea, op, start..end, true),
+            Operator::Delete => {
+                self.store(text[start..end].to_string(), true);
+                self.save_undo(textarea);
+                // Take a line break with the lines, the one before them when
+                // they are the last.
+                let range = if end < text.len() {
+                    start..end + 1
+                } else {
+                    start.saturating_sub(1)..end
+                };
+                textarea.replace_range(range.clone(), "");
+                let text = textarea.text();
+                let at = if range.start < start {
+                    line_start(text, range.start)
+                } else {
+                    range.start
+                };
+                textarea.set_cursor(first_non_blank(text, at));
+            }
+        }
+    }
+
+    fn store(&mut self, text: String, linewise: bool) {
+        let register = Register { text, linewise };
+        let register = match self.register.take() {
+            Some(name) if name.is_ascii_uppercase() => {
+                let entry = self.registers.entry(name.to_ascii_lowercase()).or_default();
+                if register.linewise && !entry.text.is_empty() {
+                    entry.text.push('\n');
+                }
+                entry.text.push_str(&register.text);
+                entry.linewise |= register.linewise;
+                entry.clone()
+            }
+            Some(name) => {
+                self.registers.insert(name, register.clone());
+                register
+            }
+            None => register,
+        };
+        self.registers.insert('"', register);
+    }
+
+    fn put(&mut self, textarea: &mut TextArea, after: bool, count: usize) {
+        let name = self
+            .register
+            .take()
+            .map_or('"', |name| name.to_ascii_lowercase());
+        let Some(register) = self.registers.get(&name).cloned() else {
+            return;
+        };
+        self.save_undo(textarea);
+        let text = textarea.text();
+        let pos = textarea.cursor();
+        if register.linewise {
+            let lines = vec![register.text.as_str(); count].join("\n");
+            let at = if after {
+                let end = line_end(text, pos);
+                textarea.insert_str_at(end, &format!("\n{lines}"));
+                end + 1
+            } else {
+                let start = line_start(text, pos);
+                textarea.insert_str_at(start, &format!("{lines}\n"));
+                start
+            };
+            textarea.set_cursor(first_non_blank(textarea.text(), at));
+        } else {
+            let inserted = register.text.repeat(count);
+            let at = if after && pos < line_end(text, pos) {
+                next_pos(text, pos)
+            } else {
+                pos
+            };
+            textarea.insert_str_at(at, &inserted);
+            // On the last character put.
+            textarea.set_cursor(prev_pos(textarea.text(), at + inserted.len()));
+        }
+    }
+}
+
+/// Where a motion key moves the cursor, for the keys that are motions.
+fn motion(text: &str, pos: usize, key: char, count: usize) -> Option<usize> {
+    let repeat = |step: fn(&str, usize) -> usize| (0..count).fold(pos, |at, _| step(text, at));
+    Some(match key {
+        'h' => repeat(prev_pos).max(line_start(text, pos)),
+        'l' => repeat(next_pos).min(line_end(text, pos)),
+        'w' => repeat(word_forward),
+        'b' => repeat(word_backward),
+        'e' => repeat(word_end),
+        '0' => line_start(text, pos),
+        '^' => first_non_blank(text, pos),
+        '$' => line_end(text, pos),
+        _ => return None,
+    })
+}
+
+/// The text an operator followed by `key` acts on.
+fn operator_range(
+    text: &str,
+    pos: usize,
+    op: Operator,
+    key: char,
+    count: usize,
+) -> Option<Range<usize>> {
+    let on_word = char_at(text, pos).is_some_and(|c| !c.is_whitespace());
+    // `cw` on a word changes to its end, like `ce`.
+    let key = if key == 'w' && op == Operator::Change && on_word {
+        'e'
+    } else {
+        key
+    };
+    let target = motion(text, pos, key, count)?;
+    Some(match key {
+        // `e` includes the character it lands on.
+        'e' => pos..next_pos(text, target),
+        // `dw` on the last word of a line stops at the line break.
+        'w' => pos..target.min(line_end(text, pos)),
+        _ => pos.min(target)..pos.max(target),
+    })
+}
+
+fn char_at(text: &str, pos: usize) -> Option<char> {
+    text.get(pos..).and_then(|rest| rest.chars().next())
+}
+
+fn next_pos(text: &str, pos: usize) -> usize {
+    char_at(text, pos).map_or(pos, |c| pos + c.len_utf8())
+}
+
+fn prev_pos(text: &str, pos: usize) -> usize {
+    text.get(..pos)
+        .and_then(|before| before.chars().next_back())
+        .map_or(pos, |c| pos - c.len_utf8())
+}
+
+fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |idx| idx + 1)
+}
+
+fn line_end(text: &str, pos: usize) -> usize {
+    text[pos..].find('\n').map_or(text.len(), |idx| pos + idx)
+}
+
+fn first_non_blank(text: &str, pos: usize) -> usize {
+    let start = line_start(text, pos);
+    let end = line_end(text, pos);
+    text[start..end]
+        .find(|c: char| c != ' ' && c != '\t')
+        .map_or(end, |idx| start + idx)
+}
+
+/// Normal mode keeps the cursor on a character, not after the last one.
+fn clamp_cursor(textarea: &mut TextArea) {
+    let text = textarea.text();
+    let pos = textarea.cursor();
+    if pos > line_start(text, pos) && pos == line_end(text, pos) {
+        textarea.set_cursor(prev_pos(text, pos));
+    }
+}
+
+/// Words are runs of letters, digits and `_`, or runs of other
+/// non-blank characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Blank,
+    Word,
+    Punctuation,
+}
+
+fn class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Blank
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// `w`: the start of the next word.
+fn word_forward(text: &str, pos: usize) -> usize {
+    let mut at = pos;
+    if let Some(c) = char_at(text, at)
+        && class(c) != CharClass::Blank
+    {
+        let word = class(c);
+        while char_at(text, at).is_some_and(|c| class(c) == word) {
+            at = next_pos(text, at);
+        }
+    }
+    while char_at(text, at).is_some_and(char::is_whitespace) {
+        at = next_pos(text, at);
+    }
+    at
+}
+
+/// `e`: the last character of this word, or of the next one when already
+/// there.
+fn word_end(text: &str, pos: usize) -> usize {
+    let mut at = next_pos(text, pos);
+    while char_at(text, at).is_some_and(char::is_whitespace) {
+        at = next_pos(text, at);
+    }
+    let Some(word) = char_at(text, at).map(class) else {
+        return pos;
+    };
+    while char_at(text, next_pos(text, at)).is_some_and(|c| class(c) == word) {
+        at = next_pos(text, at);
+    }
+    at
+}
+
+/// `b`: the start of this word, or of the previous one when already there.
+fn word_backward(text: &str, pos: usize) -> usize {
+    let mut at = pos;
+    while at > 0 && char_at(text, prev_pos(text, at)).is_some_and(char::is_whitespace) {
+        at = prev_pos(text, at);
+    }
+    if at == 0 {
+        return 0;
+    }
+    at = prev_pos(text, at);
+    let word = char_at(text, at).map(class);
+    while at > 0 && char_at(text, prev_pos(text, at)).map(class) == word {
+        at = prev_pos(text, at);
+    }
+    at
+}
+
+/// The run of same-class characters around `pos`, within its line.
+fn run_around(text: &str, pos: usize) -> Range<usize> {
+    let Some(c) = char_at(text, pos).filter(|c| *c != '\n') else {
+        return pos..pos;
+    };
+    let same = |at: usize| {
+        char_at(text, at).is_some_and(|other| other != '\n' && class(other) == class(c))
+    };
+    let mut start = pos;
+    while start > 0 && same(prev_pos(text, start)) {
+        start = prev_pos(text, start);
+    }
+    let mut end = pos;
+    while same(end) {
+        end = next_pos(text, end);
+    }
+    start..end
+}
+
+/// `iw` is the word (or run of blanks) under the cursor; `aw` adds the
+/// blanks after it, or before it when there are none after.
+fn word_object(text: &str, pos: usize, around: bool) -> Range<usize> {
+    let word = run_around(text, pos);
+    if !around || word.is_empty() {
+        return word;
+    }
+    let is_blank = |c: char| c == ' ' || c == '\t';
+    if char_at(text, pos).is_some_and(is_blank) {
+        let next = run_around(text, word.end);
+        return word.start..next.end.max(word.end);
+    }
+    let mut end = word.end;
+    while char_at(text, end).is_some_and(is_blank) {
+        end = next_pos(text, end);
+    }
+    if end > word.end {
+        return word.start..end;
+    }
+    let mut start = word.start;
+    while start > 0 && char_at(text, prev_pos(text, start)).is_some_and(is_blank) {
+        start = prev_pos(text, start);
+    }
+    start..word.end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use pretty_assertions::assert_eq;
+
+    /// A textarea holding `text` with the cursor at the `|`, in normal mode.
+    fn normal(text: &str) -> (Vim, TextArea) {
+        let cursor = text.find('|').unwrap_or(text.len());
+        let mut textarea = TextArea::new();
+        textarea.insert_str(&text.replacen('|', "", 1));
+        textarea.set_cursor(cursor);
+        let mut vim = Vim::new();
+        vim.mode = VimMode::Normal;
+        (vim, textarea)
+    }
+
+    /// Feeds `keys` to vim, typing them into the textarea in insert mode.
+    fn keys(vim: &mut Vim, textarea: &mut TextArea, keys: &str) {
+        for c in keys.chars() {
+            let code = if c == '\u{1b}' {
+                KeyCode::Esc
+            } else {
+                KeyCode::Char(c)
+            };
+            if vim.handle_key(textarea, KeyEvent::new(code, KeyModifiers::NONE))
+                == VimKey::PassThrough
+            {
+                textarea.insert_str(&c.to_string());
+            }
+        }
+    }
+
+    /// The text with a `|` at the cursor.
+    fn shown(textarea: &TextArea) -> String {
+        let mut text = textarea.text().to_string();
+        text.insert(textarea.cursor(), '|');
+        text
+    }
+
+    #[test]
+    fn esc_leaves_insert_mode_and_steps_back() {
+        let mut vim = Vim::new();
+        let mut textarea = TextArea::new();
+        textarea.insert_str("hello");
+        assert_eq!(
+            vim.handle_key(
+                &mut textarea,
+                KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE)
+            ),
+            VimKey::PassThrough
+        );
+        keys(&mut vim, &mut textarea, "\u{1b}");
+        assert_eq!(vim.mode(), VimMode::Normal);
+        assert_eq!(shown(&textarea), "hell|o");
+        assert_eq!(
+            vim.handle_key(
+                &mut textarea,
+                KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)
+            ),
+            VimKey::PassThrough
+        );
+        assert_eq!(
+            vim.handle_key(
+                &mut textarea,
+                KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)
+            ),
+            VimKey::PassThrough
+        );
+    }
+
+    #[test]
+    fn word_motions_and_counts() {
+        let (mut vim, mut textarea) = normal("|fn main() { run(x_y); }");
+        keys(&mut vim, &mut textarea, "w");
+        assert_eq!(shown(&textarea), "fn |main() { run(x_y); }");
+        keys(&mut vim, &mut textarea, "w");
+        assert_eq!(shown(&textarea), "fn main|() { run(x_y); }");
+        keys(&mut vim, &mut textarea, "3w");
+        assert_eq!(shown(&textarea), "fn main() { run|(x_y); }");
+        keys(&mut vim, &mut textarea, "e");
+        assert_eq!(shown(&textarea), "fn main() { run(x_|y); }");
+        keys(&mut vim, &mut textarea, "e");
+        assert_eq!(shown(&textarea), "fn main() { run(x_y)|; }");
+        keys(&mut vim, &mut textarea, "2b");
+        assert_eq!(shown(&textarea), "fn main() { run(|x_y); }");
+        keys(&mut vim, &mut textarea, "$");
+        assert_eq!(shown(&textarea), "fn main() { run(x_y); |}");
+        keys(&mut vim, &mut textarea, "0");
+        assert_eq!(shown(&textarea), "|fn main() { run(x_y); }");
+    }
+
+    #[test]
+    fn operators_take_motions_and_text_objects() {
+        let (mut vim, mut textarea) = normal("one |two three");
+        keys(&mut vim, &mut textarea, "dw");
+        assert_eq!(shown(&textarea), "one |three");
+
+        let (mut vim, mut textarea) = normal("one tw|o three");
+        keys(&mut vim, &mut textarea, "ciwTWO\u{1b}");
+        assert_eq!(shown(&textarea), "one TW|O three");
+        assert_eq!(vim.mode(), VimMode::Normal);
+
+        let (mut vim, mut textarea) = normal("one |two three");
+        keys(&mut vim, &mut textarea, "daw");
+        assert_eq!(shown(&textarea), "one |three");
+
+        let (mut vim, mut textarea) = normal("one |two three");
+        keys(&mut vim, &mut textarea, "cwx\u{1b}");
+        assert_eq!(shown(&textarea), "one |x three");
+
+        let (mut vim, mut textarea) = normal("one two| three");
+        keys(&mut vim, &mut textarea, "d$");
+        assert_eq!(shown(&textarea), "one tw|o");
+    }
+
+    #[test]
+    fn dd_deletes_lines_and_p_puts_them_back() {
+        let (mut vim, mut textarea) = normal("first\n  sec|ond\nthird");
+        keys(&mut vim, &mut textarea, "dd");
+        assert_eq!(shown(&textarea), "first\n|third");
+        keys(&mut vim, &mut textarea, "p");
+        assert_eq!(shown(&textarea), "first\nthird\n  |second");
+        keys(&mut vim, &mut textarea, "dd");
+        assert_eq!(shown(&textarea), "first\n|third");
+        keys(&mut vim, &mut textarea, "gg2dd");
+        assert_eq!(textarea.text(), "");
+        keys(&mut vim, &mut textarea, "u");
+        assert_eq!(textarea.text(), "first\nthird");
+    }
+
+    #[test]
+    fn named_registers_keep_yanks_apart() {
+        let (mut vim, mut textarea) = normal("|alpha beta");
+        keys(&mut vim, &mut textarea, "\"ayw");
+        keys(&mut vim, &mut textarea, "w\"Ayw");
+        keys(&mut vim, &mut textarea, "x");
+        assert_eq!(shown(&textarea), "alpha |eta");
+        keys(&mut vim, &mut textarea, "$p");
+        assert_eq!(shown(&textarea), "alpha eta|b");
+        keys(&mut vim, &mut textarea, "0\"aP");
+        assert_eq!(shown(&textarea), "alpha bet|aalpha etab");
+    }
+
+    #[test]
+    fn insert_commands_and_undo() {
+        let (mut vim, mut textarea) = normal("a|b");
+        keys(&mut vim, &mut textarea, "ox\u{1b}");
+        assert_eq!(shown(&textarea), "ab\n|x");
+        keys(&mut vim, &mut textarea, "Ay\u{1b}Iz\u{1b}");
+        assert_eq!(shown(&textarea), "ab\n|zxy");
+        keys(&mut vim, &mut textarea, "u");
+        assert_eq!(textarea.text(), "ab\nxy");
+        keys(&mut vim, &mut textarea, "uu");
+        assert_eq!(textarea.text(), "ab");
+    }
+}
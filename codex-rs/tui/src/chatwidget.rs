@@ -47,7 +47,14 @@ use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::ApprovalJournalResponseEvent;
+use codex_core::protocol::ApprovalKind;
+use codex_core::protocol::ApprovalTimedOutEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::BackgroundProcessStatus;
+use codex_core::protocol::BackgroundProcessesUpdatedEvent;
+use codex_core::protocol::ConfigReloadedEvent;
+use codex_core::protocol::ConversationForkedEvent;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
@@ -59,28 +66,53 @@ use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandOutputDeltaEvent;
 use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::ExternalFilesChangedEvent;
+use codex_core::protocol::FileChange;
+use codex_core::protocol::FollowUpSuggestion;
+use codex_core::protocol::HookEventKind;
+use codex_core::protocol::HookNotification;
+use codex_core::protocol::HookOutcome;
+use codex_core::protocol::HookOutcomeEvent;
+use codex_core::protocol::HostedToolCallEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::ListMcpPromptsResponseEvent;
 use codex_core::protocol::ListSkillsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
+use codex_core::protocol::McpServerHealthEvent;
+use codex_core::protocol::McpServerHealthStatus;
 use codex_core::protocol::McpStartupCompleteEvent;
 use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
+use codex_core::protocol::McpToolCallApprovalRequestEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::NetworkActivityEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::PatchHunkReportEvent;
+use codex_core::protocol::PatchHunkStatus;
+use codex_core::protocol::PlanApprovalRequestEvent;
+use codex_core::protocol::PlanModeUpdatedEvent;
+use codex_core::protocol::ProviderQuota;
+use codex_core::protocol::ProviderRateLimitEvent;
 use codex_core::protocol::RateLimitSnapshot;
+use codex_core::protocol::ResumeBriefingEvent;
+use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SkillMetadata as ProtocolSkillMetadata;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentTokenUsage;
 use codex_core::protocol::TerminalInteractionEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
+use codex_core::protocol::ToolTokenUsage;
 use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnAnnotationsEvent;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::UndoCompletedEvent;
+use codex_core::protocol::UndoKind;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::UserMessageEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
@@ -140,9 +172,9 @@ use crate::bottom_pane::QUIT_SHORTCUT_TIMEOUT;
 use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::StatusLineState;
 use crate::bottom_pane::custom_prompt_view::CustomPromptView;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
-use crate::clipboard_paste::paste_image_to_temp_png;
 use crate::collab;
 use crate::collaboration_modes;
 use crate::diff_render::display_path_for;
@@ -156,8 +188,12 @@ use crate::history_cell::AgentMessageCell;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::McpToolCallCell;
 use crate::history_cell::PlainHistoryCell;
+use crate::hunk_review::ReviewFile;
+use crate::hunk_review::revert_rejected;
+use crate::hunk_review::review_files;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::keymap::keymap;
 use crate::markdown::append_markdown;
 use crate::render::Insets;
 use crate::render::renderable::ColumnRenderable;
@@ -167,7 +203,9 @@ use crate::render::renderable::RenderableExt;
 use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
 use crate::status::RateLimitSnapshotDisplay;
+use crate::tabs::TabCommand;
 use crate::text_formatting::truncate_text;
+use crate::theme;
 use crate::tui::FrameRequester;
 mod interrupts;
 use self::interrupts::InterruptManager;
@@ -178,6 +216,20 @@ mod session_header;
 use self::session_header::SessionHeader;
 mod skills;
 use self::skills::find_skill_mentions;
+mod subagents;
+use self::subagents::RunningSubAgent;
+mod subagent_merge;
+use self::subagent_merge::PendingSubAgentMerge;
+mod background_tasks;
+mod checkpoints;
+mod clipboard;
+mod follow_ups;
+mod mcp_servers;
+mod pinned_context;
+mod project_memory;
+mod queued_messages;
+mod reply_actions;
+mod worktree;
 use crate::streaming::controller::StreamController;
 use std::path::Path;
 
@@ -187,6 +239,7 @@ use codex_common::approval_presets::builtin_approval_presets;
 use codex_core::AuthManager;
 use codex_core::CodexAuth;
 use codex_core::ThreadManager;
+use codex_core::code_outline::SymbolMatch;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 use codex_file_search::FileMatch;
@@ -437,11 +490,25 @@ pub(crate) struct ChatWidget {
     unified_exec_wait_streak: Option<UnifiedExecWaitStreak>,
     task_complete_pending: bool,
     unified_exec_processes: Vec<UnifiedExecProcessSummary>,
+    /// Terminal size (rows, cols) last reported to core for PTY-backed commands.
+    reported_terminal_size: Option<(u16, u16)>,
+    /// Subagent runs started via `/agents` that have not finished yet.
+    running_subagents: Vec<RunningSubAgent>,
+    /// Ids of background tasks that have begun and not ended yet.
+    running_background_tasks: HashSet<String>,
+    /// Session cost from the latest `CostUpdate`, shown in the status line.
+    session_usd: Option<f64>,
+    /// Merge plan from `/merge` waiting for its conflicts to be resolved.
+    pending_subagent_merge: Option<PendingSubAgentMerge>,
+    /// Full text of the latest agent message, copied by `/copy`.
+    last_agent_message: Option<String>,
     /// Tracks whether codex-core currently considers an agent turn to be in progress.
     ///
     /// This is kept separate from `mcp_startup_status` so that MCP startup progress (or completion)
     /// can update the status header without accidentally clearing the spinner for an active turn.
     agent_turn_running: bool,
+    /// When the running turn started, to skip notifications for short turns.
+    turn_started_at: Option<Instant>,
     /// Tracks per-server MCP startup state while startup is in progress.
     ///
     /// The map is `Some(_)` from the first `McpStartupUpdate` until `McpStartupComplete`, and the
@@ -468,6 +535,11 @@ pub(crate) struct ChatWidget {
     suppress_session_configured_redraw: bool,
     // User messages queued while a turn is in progress
     queued_user_messages: VecDeque<UserMessage>,
+    // Next steps from the agent's latest `suggest_follow_ups` call
+    follow_up_suggestions: Vec<FollowUpSuggestion>,
+    // Per-tool and per-subagent totals from the latest token count, for `/tool-usage`
+    tool_token_usage: Vec<ToolTokenUsage>,
+    subagent_token_usage: Vec<SubAgentTokenUsage>,
     // Pending notification to show when unfocused on next Draw
     pending_notification: Option<Notification>,
     /// When `Some`, the user has pressed a quit shortcut and the second press
@@ -493,6 +565,12 @@ pub(crate) struct ChatWidget {
     // This gates rendering of the "Worked for …" separator so purely conversational turns don't
     // show an empty divider. It is reset when the separator is emitted.
     had_work_activity: bool,
+    // Whether plan mode is on, as last reported by `EventMsg::PlanModeUpdated`.
+    plan_mode: bool,
+    // Changes of the last patch that applied, reviewed by `/hunks`.
+    last_applied_patch: Option<HashMap<PathBuf, FileChange>>,
+    // `apply_patch` calls approved with "review each hunk"; the review opens once they apply.
+    hunk_review_calls: HashSet<String>,
     // Status-indicator elapsed seconds captured at the last emitted final-message separator.
     //
     // This lets the separator show per-chunk work time (since the previous separator) rather than
@@ -747,6 +825,8 @@ impl ChatWidget {
         if let Some(messages) = initial_messages {
             self.replay_initial_messages(messages);
         }
+        // A new session starts with the default size; report ours on the next draw.
+        self.reported_terminal_size = None;
         // Ask codex-core to enumerate custom prompts for this session.
         self.submit_op(Op::ListCustomPrompts);
         self.submit_op(Op::ListSkills {
@@ -799,6 +879,7 @@ impl ChatWidget {
     }
 
     fn on_agent_message(&mut self, message: String) {
+        self.last_agent_message = Some(message.clone());
         // If we have a stream_controller, then the final agent message is redundant and will be a
         // duplicate of what has already been streamed.
         if self.stream_controller.is_none() {
@@ -858,6 +939,7 @@ impl ChatWidget {
 
     fn on_task_started(&mut self) {
         self.agent_turn_running = true;
+        self.turn_started_at = Some(Instant::now());
         self.bottom_pane.clear_quit_shortcut_hint();
         self.quit_shortcut_expires_at = None;
         self.quit_shortcut_key = None;
@@ -886,10 +968,18 @@ impl ChatWidget {
 
         // If there is a queued user message, send exactly one now to begin the next turn.
         self.maybe_send_next_queued_input();
-        // Emit a notification when the turn completes (suppressed if focused).
-        self.notify(Notification::AgentTurnComplete {
-            response: last_agent_message.unwrap_or_default(),
-        });
+        // Emit a notification when the turn completes (suppressed if focused),
+        // unless it was shorter than `tui.notification_min_turn_sec`.
+        let min_turn = Duration::from_secs(self.config.tui_notification_min_turn_sec);
+        let long_enough = self
+            .turn_started_at
+            .take()
+            .is_none_or(|started| started.elapsed() >= min_turn);
+        if long_enough {
+            self.notify(Notification::AgentTurnComplete {
+                response: last_agent_message.unwrap_or_default(),
+            });
+        }
 
         self.maybe_show_pending_rate_limit_prompt();
     }
@@ -1084,6 +1174,30 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_mcp_server_health(&mut self, ev: McpServerHealthEvent) {
+        let server = ev.server;
+        match ev.status {
+            McpServerHealthStatus::Degraded {
+                error,
+                restart_attempt,
+                retry_in_ms,
+            } => {
+                let retry_in_sec = retry_in_ms.div_ceil(1000);
+                self.on_warning(format!(
+                    "MCP server `{server}` is not responding ({error}); restarting in {retry_in_sec}s (attempt {restart_attempt})"
+                ));
+            }
+            McpServerHealthStatus::Recovered => {
+                self.add_info_message(format!("MCP server `{server}` was restarted"), None);
+            }
+            McpServerHealthStatus::Failed { error } => {
+                self.on_warning(format!(
+                    "MCP server `{server}` could not be restarted and its tools are unavailable: {error}"
+                ));
+            }
+        }
+    }
+
     fn on_mcp_startup_complete(&mut self, ev: McpStartupCompleteEvent) {
         let mut parts = Vec::new();
         if !ev.failed.is_empty() {
@@ -1101,6 +1215,8 @@ impl ChatWidget {
         }
 
         self.mcp_startup_status = None;
+        // Servers are up now; offer their prompts as slash commands.
+        self.submit_op(Op::ListMcpPrompts);
         self.update_task_running_state();
         self.maybe_send_next_queued_input();
         self.request_redraw();
@@ -1219,6 +1335,48 @@ impl ChatWidget {
         );
     }
 
+    fn on_plan_approval_request(&mut self, ev: PlanApprovalRequestEvent) {
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_plan_approval(ev),
+            |s| s.handle_plan_approval_now(ev2),
+        );
+    }
+
+    fn on_mcp_tool_call_approval_request(&mut self, ev: McpToolCallApprovalRequestEvent) {
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_mcp_tool_call_approval(ev),
+            |s| s.handle_mcp_tool_call_approval_now(ev2),
+        );
+    }
+
+    /// Core answered an approval prompt on its own after `[approval_timeouts]`
+    /// expired; close the prompt and record what was decided.
+    fn on_approval_timed_out(&mut self, ev: ApprovalTimedOutEvent) {
+        self.bottom_pane.dismiss_approval_request(&ev.turn_id);
+        let subject = match ev.kind {
+            ApprovalKind::Exec => "Command",
+            ApprovalKind::Patch => "Patch",
+            ApprovalKind::Plan => "Plan",
+            ApprovalKind::McpTool => "MCP tool call",
+        };
+        let outcome = match ev.decision {
+            ReviewDecision::Approved
+            | ReviewDecision::ApprovedForSession
+            | ReviewDecision::ApprovedExecpolicyAmendment { .. } => "approved as read-only",
+            ReviewDecision::Denied => "denied",
+            ReviewDecision::Abort => "denied and the turn aborted",
+        };
+        self.add_info_message(
+            format!(
+                "{subject} approval timed out after {}s: {outcome}",
+                ev.timeout_secs
+            ),
+            Some("configured by [approval_timeouts] in config.toml".to_string()),
+        );
+    }
+
     fn on_request_user_input(&mut self, ev: RequestUserInputEvent) {
         let ev2 = ev.clone();
         self.defer_or_handle(
@@ -1329,6 +1487,46 @@ impl ChatWidget {
         );
     }
 
+    fn on_patch_hunk_report(&mut self, event: PatchHunkReportEvent) {
+        for file in event.files {
+            let count = |status: PatchHunkStatus| {
+                file.hunks
+                    .iter()
+                    .filter(|hunk| hunk.status == status)
+                    .count()
+            };
+            let conflicts = count(PatchHunkStatus::Conflict);
+            let parts: Vec<String> = [
+                (PatchHunkStatus::Fuzzy, "fuzzy"),
+                (PatchHunkStatus::Merged, "merged"),
+                (PatchHunkStatus::AlreadyApplied, "already applied"),
+                (PatchHunkStatus::Conflict, "conflicting"),
+            ]
+            .into_iter()
+            .filter_map(|(status, label)| {
+                let n = count(status);
+                (n > 0).then(|| format!("{n} {label}"))
+            })
+            .collect();
+            if parts.is_empty() {
+                continue;
+            }
+            let path = file
+                .path
+                .strip_prefix(&self.config.cwd)
+                .unwrap_or(&file.path)
+                .display()
+                .to_string();
+            let hint = (conflicts > 0).then(|| {
+                "conflicting chunks were not applied; the model was asked to retry them".to_string()
+            });
+            self.add_info_message(
+                format!("Patch chunks in {path}: {}", parts.join(", ")),
+                hint,
+            );
+        }
+    }
+
     fn on_exec_command_end(&mut self, ev: ExecCommandEndEvent) {
         if is_unified_exec_source(ev.source) {
             if let Some(process_id) = ev.process_id.as_deref()
@@ -1388,6 +1586,16 @@ impl ChatWidget {
         self.bottom_pane.set_unified_exec_processes(processes);
     }
 
+    fn on_background_processes_updated(&mut self, ev: BackgroundProcessesUpdatedEvent) {
+        let running = ev
+            .processes
+            .into_iter()
+            .filter(|process| process.status == BackgroundProcessStatus::Running)
+            .map(|process| process.command)
+            .collect();
+        self.bottom_pane.set_background_processes(running);
+    }
+
     fn clear_unified_exec_processes(&mut self) {
         if self.unified_exec_processes.is_empty() {
             return;
@@ -1415,6 +1623,11 @@ impl ChatWidget {
         self.add_to_history(history_cell::new_web_search_call(ev.query));
     }
 
+    fn on_hosted_tool_call(&mut self, ev: HostedToolCallEvent) {
+        self.flush_answer_stream_with_separator();
+        self.add_to_history(history_cell::new_hosted_tool_call(ev));
+    }
+
     fn on_collab_event(&mut self, cell: PlainHistoryCell) {
         self.flush_answer_stream_with_separator();
         self.add_to_history(cell);
@@ -1465,17 +1678,32 @@ impl ChatWidget {
     }
 
     fn on_undo_completed(&mut self, event: UndoCompletedEvent) {
-        let UndoCompletedEvent { success, message } = event;
+        let UndoCompletedEvent {
+            success,
+            message,
+            kind,
+            redo_commit_id,
+        } = event;
         self.bottom_pane.hide_status_indicator();
+        let label = match kind {
+            UndoKind::Undo => "Undo",
+            UndoKind::Redo => "Redo",
+            UndoKind::RestoreFile => "File restore",
+            UndoKind::RevertHunks => "Hunk revert",
+        };
         let message = message.unwrap_or_else(|| {
             if success {
-                "Undo completed successfully.".to_string()
+                format!("{label} completed successfully.")
             } else {
-                "Undo failed.".to_string()
+                format!("{label} failed.")
             }
         });
         if success {
-            self.add_info_message(message, None);
+            let hint = redo_commit_id.filter(|_| kind == UndoKind::Undo).map(|id| {
+                let short_id: String = id.chars().take(7).collect();
+                format!("redo available (snapshot {short_id})")
+            });
+            self.add_info_message(message, hint);
         } else {
             self.add_error_message(message);
         }
@@ -1488,6 +1716,24 @@ impl ChatWidget {
         self.set_status(message, additional_details);
     }
 
+    /// While a turn waits out a provider 429, count down to the retry in the
+    /// status header. The previous header comes back with the next event.
+    fn on_provider_rate_limit(&mut self, event: ProviderRateLimitEvent) {
+        let Some(seconds) = event.resume_in_seconds else {
+            return;
+        };
+        if self.retry_status_header.is_none() {
+            self.retry_status_header = Some(self.current_status_header.clone());
+        }
+        let header = String::from("Rate limited");
+        self.current_status_header = header.clone();
+        self.bottom_pane.update_status_countdown(
+            header,
+            event.quota.as_ref().and_then(provider_quota_summary),
+            Instant::now() + Duration::from_secs(seconds),
+        );
+    }
+
     /// Periodic tick to commit at most one queued line to history with a small delay,
     /// animating the output.
     pub(crate) fn on_commit_tick(&mut self) {
@@ -1642,9 +1888,15 @@ impl ChatWidget {
         &mut self,
         event: codex_core::protocol::PatchApplyEndEvent,
     ) {
-        // If the patch was successful, just let the "Edited" block stand.
-        // Otherwise, add a failure block.
-        if !event.success {
+        let review_hunks = self.hunk_review_calls.remove(&event.call_id);
+        // If the patch was successful, just let the "Edited" block stand and
+        // keep the changes for `/hunks`. Otherwise, add a failure block.
+        if event.success {
+            if review_hunks {
+                self.open_hunk_review(&event.changes);
+            }
+            self.last_applied_patch = Some(event.changes);
+        } else {
             self.add_to_history(history_cell::new_patch_apply_failure(event.stderr));
         }
         // Mark that actual work was done (patch applied)
@@ -1662,6 +1914,11 @@ impl ChatWidget {
             command: ev.command,
             reason: ev.reason,
             proposed_execpolicy_amendment: ev.proposed_execpolicy_amendment,
+            explanation: ev.explanation,
+            sandbox_warnings: ev
+                .sandbox
+                .map(|sandbox| sandbox.warnings)
+                .unwrap_or_default(),
         };
         self.bottom_pane
             .push_approval_request(request, &self.config.features);
@@ -1677,9 +1934,11 @@ impl ChatWidget {
 
         let request = ApprovalRequest::ApplyPatch {
             id,
+            call_id: ev.call_id,
             reason: ev.reason,
             changes: ev.changes.clone(),
             cwd: self.config.cwd.clone(),
+            preview: ev.preview,
         };
         self.bottom_pane
             .push_approval_request(request, &self.config.features);
@@ -1707,6 +1966,33 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    pub(crate) fn handle_plan_approval_now(&mut self, ev: PlanApprovalRequestEvent) {
+        self.flush_answer_stream_with_separator();
+
+        let request = ApprovalRequest::Plan {
+            id: ev.turn_id,
+            plan: ev.plan,
+        };
+        self.bottom_pane
+            .push_approval_request(request, &self.config.features);
+        self.request_redraw();
+    }
+
+    pub(crate) fn handle_mcp_tool_call_approval_now(
+        &mut self,
+        ev: McpToolCallApprovalRequestEvent,
+    ) {
+        self.flush_answer_stream_with_separator();
+
+        let request = ApprovalRequest::McpToolCall {
+            id: ev.turn_id,
+            invocation: ev.invocation,
+        };
+        self.bottom_pane
+            .push_approval_request(request, &self.config.features);
+        self.request_redraw();
+    }
+
     pub(crate) fn handle_request_user_input_now(&mut self, ev: RequestUserInputEvent) {
         self.flush_answer_stream_with_separator();
         self.bottom_pane.push_user_input_request(ev);
@@ -1905,7 +2191,14 @@ impl ChatWidget {
             unified_exec_wait_streak: None,
             task_complete_pending: false,
             unified_exec_processes: Vec::new(),
+            reported_terminal_size: None,
+            running_subagents: Vec::new(),
+            running_background_tasks: HashSet::new(),
+            session_usd: None,
+            pending_subagent_merge: None,
+            last_agent_message: None,
             agent_turn_running: false,
+            turn_started_at: None,
             mcp_startup_status: None,
             interrupts: InterruptManager::new(),
             reasoning_buffer: String::new(),
@@ -1915,6 +2208,9 @@ impl ChatWidget {
             thread_id: None,
             forked_from: None,
             queued_user_messages: VecDeque::new(),
+            follow_up_suggestions: Vec::new(),
+            tool_token_usage: Vec::new(),
+            subagent_token_usage: Vec::new(),
             show_welcome_banner: is_first_run,
             suppress_session_configured_redraw: false,
             pending_notification: None,
@@ -1924,6 +2220,9 @@ impl ChatWidget {
             pre_review_token_info: None,
             needs_final_message_separator: false,
             had_work_activity: false,
+            plan_mode: false,
+            last_applied_patch: None,
+            hunk_review_calls: HashSet::new(),
             last_separator_elapsed_secs: None,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
@@ -1938,6 +2237,12 @@ impl ChatWidget {
         widget.bottom_pane.set_collaboration_modes_enabled(
             widget.config.features.enabled(Feature::CollaborationModes),
         );
+        widget
+            .bottom_pane
+            .set_status_line_items(widget.config.tui_status_line.clone());
+        widget
+            .bottom_pane
+            .set_editing_mode(widget.config.tui_editing_mode);
         widget.update_collaboration_mode_indicator();
 
         widget
@@ -2024,7 +2329,14 @@ impl ChatWidget {
             unified_exec_wait_streak: None,
             task_complete_pending: false,
             unified_exec_processes: Vec::new(),
+            reported_terminal_size: None,
+            running_subagents: Vec::new(),
+            running_background_tasks: HashSet::new(),
+            session_usd: None,
+            pending_subagent_merge: None,
+            last_agent_message: None,
             agent_turn_running: false,
+            turn_started_at: None,
             mcp_startup_status: None,
             interrupts: InterruptManager::new(),
             reasoning_buffer: String::new(),
@@ -2034,6 +2346,9 @@ impl ChatWidget {
             thread_id: None,
             forked_from: None,
             queued_user_messages: VecDeque::new(),
+            follow_up_suggestions: Vec::new(),
+            tool_token_usage: Vec::new(),
+            subagent_token_usage: Vec::new(),
             show_welcome_banner: false,
             suppress_session_configured_redraw: true,
             pending_notification: None,
@@ -2043,6 +2358,9 @@ impl ChatWidget {
             pre_review_token_info: None,
             needs_final_message_separator: false,
             had_work_activity: false,
+            plan_mode: false,
+            last_applied_patch: None,
+            hunk_review_calls: HashSet::new(),
             last_separator_elapsed_secs: None,
             last_rendered_width: std::cell::Cell::new(None),
             feedback,
@@ -2057,6 +2375,12 @@ impl ChatWidget {
         widget.bottom_pane.set_collaboration_modes_enabled(
             widget.config.features.enabled(Feature::CollaborationModes),
         );
+        widget
+            .bottom_pane
+            .set_status_line_items(widget.config.tui_status_line.clone());
+        widget
+            .bottom_pane
+            .set_editing_mode(widget.config.tui_editing_mode);
         widget.update_collaboration_mode_indicator();
 
         widget
@@ -2094,23 +2418,7 @@ impl ChatWidget {
             } if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
                 && c.eq_ignore_ascii_case(&'v') =>
             {
-                match paste_image_to_temp_png() {
-                    Ok((path, info)) => {
-                        tracing::debug!(
-                            "pasted image size={}x{} format={}",
-                            info.width,
-                            info.height,
-                            info.encoded_format.label()
-                        );
-                        self.attach_image(path);
-                    }
-                    Err(err) => {
-                        tracing::warn!("failed to paste image: {err}");
-                        self.add_to_history(history_cell::new_error_event(format!(
-                            "Failed to paste image: {err}",
-                        )));
-                    }
-                }
+                self.paste_clipboard_image(true);
                 return;
             }
             other if other.kind == KeyEventKind::Press => {
@@ -2132,6 +2440,14 @@ impl ChatWidget {
             {
                 self.cycle_collaboration_mode();
             }
+            KeyEvent {
+                code: KeyCode::Char('o'),
+                modifiers: KeyModifiers::CONTROL,
+                kind: KeyEventKind::Press,
+                ..
+            } if self.bottom_pane.no_modal_or_popup_active() => {
+                self.open_reply_actions();
+            }
             KeyEvent {
                 code: KeyCode::Up,
                 modifiers: KeyModifiers::ALT,
@@ -2139,20 +2455,7 @@ impl ChatWidget {
                 ..
             } if !self.queued_user_messages.is_empty() => {
                 // Prefer the most recently queued item.
-                if let Some(user_message) = self.queued_user_messages.pop_back() {
-                    let local_image_paths = user_message
-                        .local_images
-                        .iter()
-                        .map(|img| img.path.clone())
-                        .collect();
-                    self.bottom_pane.set_composer_text(
-                        user_message.text,
-                        user_message.text_elements,
-                        local_image_paths,
-                    );
-                    self.refresh_queued_user_messages();
-                    self.request_redraw();
-                }
+                self.restore_queued_user_message(self.queued_user_messages.len() - 1);
             }
             _ => match self.bottom_pane.handle_key_event(key_event) {
                 InputResult::Submitted {
@@ -2263,7 +2566,8 @@ impl ChatWidget {
                 self.app_event_tx.send(AppEvent::OpenResumePicker);
             }
             SlashCommand::Fork => {
-                self.app_event_tx.send(AppEvent::ForkCurrentSession);
+                self.app_event_tx
+                    .send(AppEvent::ForkCurrentSession { num_turns: None });
             }
             SlashCommand::Init => {
                 let init_target = self.config.cwd.join(DEFAULT_PROJECT_DOC_FILENAME);
@@ -2292,6 +2596,26 @@ impl ChatWidget {
                     self.open_collaboration_modes_popup();
                 }
             }
+            SlashCommand::Plan => {
+                self.submit_op(Op::SetPlanMode {
+                    enabled: !self.plan_mode,
+                });
+            }
+            SlashCommand::Checkpoint => {
+                self.checkpoint_command("");
+            }
+            SlashCommand::Worktree => {
+                self.worktree_command("");
+            }
+            SlashCommand::Pin => {
+                self.pin_command("");
+            }
+            SlashCommand::Memory => {
+                self.submit_op(Op::ListMemory);
+            }
+            SlashCommand::Context => {
+                self.submit_op(Op::InspectContext);
+            }
             SlashCommand::Approvals => {
                 self.open_approvals_popup();
             }
@@ -2358,14 +2682,14 @@ impl ChatWidget {
                 }
                 self.request_quit_without_confirmation();
             }
-            // SlashCommand::Undo => {
-            //     self.app_event_tx.send(AppEvent::CodexOp(Op::Undo));
-            // }
+            SlashCommand::Undo => {
+                self.app_event_tx.send(AppEvent::CodexOp(Op::Undo));
+            }
             SlashCommand::Diff => {
                 self.add_diff_in_progress();
                 let tx = self.app_event_tx.clone();
                 tokio::spawn(async move {
-                    let text = match get_git_diff().await {
+                    let text = match get_git_diff(false).await {
                         Ok((is_git_repo, diff_text)) => {
                             if is_git_repo {
                                 diff_text
@@ -2378,6 +2702,31 @@ impl ChatWidget {
                     tx.send(AppEvent::DiffResult(text));
                 });
             }
+            SlashCommand::Copy => {
+                self.copy_command("");
+            }
+            SlashCommand::Save => {
+                self.add_error_message("Usage: /save PATH".to_string());
+            }
+            SlashCommand::Find => {
+                self.app_event_tx
+                    .send(AppEvent::OpenTranscriptSearch(String::new()));
+            }
+            SlashCommand::Hunks => match &self.last_applied_patch {
+                Some(changes) => self.open_hunk_review(changes),
+                None => {
+                    self.add_info_message("No patch has been applied yet.".to_string(), None);
+                }
+            },
+            SlashCommand::Keys => {
+                self.add_plain_history_lines(keymap().display_lines());
+            }
+            SlashCommand::Theme => {
+                self.open_theme_popup();
+            }
+            SlashCommand::Tab => {
+                self.app_event_tx.send(AppEvent::Tab(TabCommand::List));
+            }
             SlashCommand::Mention => {
                 self.insert_str("@");
             }
@@ -2387,9 +2736,38 @@ impl ChatWidget {
             SlashCommand::Status => {
                 self.add_status_output();
             }
+            SlashCommand::Agents => {
+                self.submit_op(Op::ListSubAgents);
+            }
+            SlashCommand::Merge => {
+                self.submit_op(Op::MergeSubAgentChanges {
+                    run_ids: Vec::new(),
+                });
+            }
+            SlashCommand::Tasks => {
+                self.submit_op(Op::ListBackgroundTasks);
+            }
+            SlashCommand::Queue => {
+                self.open_queued_messages_popup();
+            }
+            SlashCommand::FollowUps => {
+                self.open_follow_ups_popup();
+            }
+            SlashCommand::WhyLast => {
+                self.submit_op(Op::ListApprovalJournal { limit: Some(1) });
+            }
+            SlashCommand::ToolUsage => {
+                self.add_to_history(history_cell::new_tool_usage_output(
+                    &self.tool_token_usage,
+                    &self.subagent_token_usage,
+                ));
+            }
             SlashCommand::Ps => {
                 self.add_ps_output();
             }
+            SlashCommand::Stdin => {
+                self.stdin_command("");
+            }
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
@@ -2408,7 +2786,6 @@ impl ChatWidget {
                 use std::collections::HashMap;
 
                 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
-                use codex_core::protocol::FileChange;
 
                 self.app_event_tx.send(AppEvent::CodexEvent(Event {
                     id: "1".to_string(),
@@ -2438,6 +2815,10 @@ impl ChatWidget {
                         ]),
                         reason: None,
                         grant_root: Some(PathBuf::from("/tmp")),
+                        files: Vec::new(),
+                        sandbox_policy: None,
+                        risk: None,
+                        preview: None,
                     }),
                 }));
             }
@@ -2463,6 +2844,71 @@ impl ChatWidget {
                     self.open_collaboration_modes_popup();
                 }
             }
+            SlashCommand::Plan if matches!(trimmed, "on" | "off") => {
+                self.submit_op(Op::SetPlanMode {
+                    enabled: trimmed == "on",
+                });
+            }
+            SlashCommand::Checkpoint => {
+                self.checkpoint_command(trimmed);
+            }
+            SlashCommand::Worktree => {
+                self.worktree_command(trimmed);
+            }
+            SlashCommand::Pin => {
+                self.pin_command(trimmed);
+            }
+            SlashCommand::Mcp if !trimmed.is_empty() => {
+                self.mcp_command(trimmed);
+            }
+            SlashCommand::Stdin => {
+                self.stdin_command(&args);
+            }
+            SlashCommand::Compact if !trimmed.is_empty() => match parse_turn_range(trimmed) {
+                Some((from_turn, to_turn)) => {
+                    self.clear_token_usage();
+                    self.app_event_tx
+                        .send(AppEvent::CodexOp(Op::CompactRange { from_turn, to_turn }));
+                }
+                None => {
+                    self.add_error_message("Usage: /compact [FROM-TO]".to_string());
+                }
+            },
+            SlashCommand::Copy => {
+                self.copy_command(trimmed);
+            }
+            SlashCommand::Save => {
+                self.app_event_tx
+                    .send(AppEvent::SaveTranscript(PathBuf::from(trimmed)));
+            }
+            SlashCommand::Find => {
+                self.app_event_tx
+                    .send(AppEvent::OpenTranscriptSearch(trimmed.to_string()));
+            }
+            SlashCommand::Theme if !trimmed.is_empty() => {
+                self.app_event_tx
+                    .send(AppEvent::SetTheme(trimmed.to_string()));
+            }
+            SlashCommand::Undo if !trimmed.is_empty() => {
+                self.app_event_tx.send(AppEvent::CodexOp(Op::RestoreFile {
+                    turn_id: None,
+                    path: PathBuf::from(trimmed),
+                }));
+            }
+            SlashCommand::Tab => match TabCommand::parse(trimmed) {
+                Some(command) => self.app_event_tx.send(AppEvent::Tab(command)),
+                None => self.add_error_message(TabCommand::USAGE.to_string()),
+            },
+            SlashCommand::Fork if !trimmed.is_empty() => match trimmed.parse::<u32>() {
+                Ok(num_turns) => {
+                    self.app_event_tx.send(AppEvent::ForkCurrentSession {
+                        num_turns: Some(num_turns),
+                    });
+                }
+                Err(_) => {
+                    self.add_error_message("Usage: /fork [number of turns to keep]".to_string());
+                }
+            },
             SlashCommand::Review if !trimmed.is_empty() => {
                 self.submit_op(Op::Review {
                     review_request: ReviewRequest {
@@ -2478,6 +2924,12 @@ impl ChatWidget {
     }
 
     pub(crate) fn handle_paste(&mut self, text: String) {
+        // Several terminals send an empty bracketed paste when the clipboard
+        // only holds an image.
+        if text.is_empty() {
+            self.paste_clipboard_image(false);
+            return;
+        }
         self.bottom_pane.handle_paste(text);
     }
 
@@ -2653,11 +3105,41 @@ impl ChatWidget {
             // `id: None` indicates a synthetic/fake id coming from replay.
             self.dispatch_event_msg(None, msg, true);
         }
+        self.refresh_status_line();
     }
 
     pub(crate) fn handle_codex_event(&mut self, event: Event) {
         let Event { id, msg } = event;
         self.dispatch_event_msg(Some(id), msg, false);
+        self.refresh_status_line();
+    }
+
+    /// Pushes the values shown by `tui.status_line` to the bottom pane.
+    fn refresh_status_line(&mut self) {
+        if self.config.tui_status_line.is_empty() {
+            return;
+        }
+        let context_left_percent = self
+            .token_info
+            .as_ref()
+            .and_then(|info| self.context_remaining_percent(info));
+        let state = StatusLineState {
+            model: self.current_model().to_string(),
+            reasoning_effort: self
+                .stored_collaboration_mode
+                .reasoning_effort()
+                .map(|effort| effort.to_string()),
+            context_left_percent,
+            total_tokens: self
+                .token_info
+                .as_ref()
+                .map(|info| info.total_token_usage.blended_total()),
+            session_usd: self.session_usd,
+            sandbox: sandbox_mode_label(self.config.sandbox_policy.get()).to_string(),
+            approval: self.config.approval_policy.value().to_string(),
+            running_tasks: self.running_background_tasks.len() + self.running_subagents.len(),
+        };
+        self.bottom_pane.set_status_line_state(state);
     }
 
     /// Dispatch a protocol `EventMsg` to the appropriate handler.
@@ -2666,7 +3148,11 @@ impl ChatWidget {
     /// `replay_initial_messages()`. Callers should treat `None` as a "fake" id
     /// that must not be used to correlate follow-up actions.
     fn dispatch_event_msg(&mut self, id: Option<String>, msg: EventMsg, from_replay: bool) {
-        let is_stream_error = matches!(&msg, EventMsg::StreamError(_));
+        let is_stream_error = match &msg {
+            EventMsg::StreamError(_) => true,
+            EventMsg::ProviderRateLimit(ev) => ev.resume_in_seconds.is_some(),
+            _ => false,
+        };
         if !is_stream_error {
             self.restore_retry_status_header_if_present();
         }
@@ -2698,17 +3184,24 @@ impl ChatWidget {
             }
             EventMsg::AgentReasoningSectionBreak(_) => self.on_reasoning_section_break(),
             EventMsg::TurnStarted(_) => self.on_task_started(),
-            EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message }) => {
-                self.on_task_complete(last_agent_message)
-            }
+            EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message, ..
+            }) => self.on_task_complete(last_agent_message),
             EventMsg::TokenCount(ev) => {
                 self.set_token_info(ev.info);
                 self.on_rate_limit_snapshot(ev.rate_limits);
+                self.tool_token_usage = ev.tool_usage;
+                self.subagent_token_usage = ev.subagent_usage;
             }
+            // Budget warnings and limits arrive as Warning and Error events.
+            EventMsg::CostUpdate(ev) => self.session_usd = Some(ev.session_usd),
+            EventMsg::ProviderRateLimit(ev) => self.on_provider_rate_limit(ev),
             EventMsg::Warning(WarningEvent { message }) => self.on_warning(message),
             EventMsg::Error(ErrorEvent { message, .. }) => self.on_error(message),
             EventMsg::McpStartupUpdate(ev) => self.on_mcp_startup_update(ev),
             EventMsg::McpStartupComplete(ev) => self.on_mcp_startup_complete(ev),
+            EventMsg::McpServerHealth(ev) => self.on_mcp_server_health(ev),
+            EventMsg::McpToolsChanged(ev) => self.on_mcp_tools_changed(ev),
             EventMsg::TurnAborted(ev) => match ev.reason {
                 TurnAbortReason::Interrupted => {
                     self.on_interrupted_turn(ev.reason);
@@ -2721,6 +3214,7 @@ impl ChatWidget {
                 }
             },
             EventMsg::PlanUpdate(update) => self.on_plan_update(update),
+            EventMsg::FollowUpSuggestions(ev) => self.on_follow_up_suggestions(ev.suggestions),
             EventMsg::ExecApprovalRequest(ev) => {
                 // For replayed events, synthesize an empty id (these should not occur).
                 self.on_exec_approval_request(id.unwrap_or_default(), ev)
@@ -2731,6 +3225,9 @@ impl ChatWidget {
             EventMsg::ElicitationRequest(ev) => {
                 self.on_elicitation_request(ev);
             }
+            EventMsg::PlanApprovalRequest(ev) => self.on_plan_approval_request(ev),
+            EventMsg::McpToolCallApprovalRequest(ev) => self.on_mcp_tool_call_approval_request(ev),
+            EventMsg::ApprovalTimedOut(ev) => self.on_approval_timed_out(ev),
             EventMsg::RequestUserInput(ev) => {
                 self.on_request_user_input(ev);
             }
@@ -2739,16 +3236,61 @@ impl ChatWidget {
             EventMsg::ExecCommandOutputDelta(delta) => self.on_exec_command_output_delta(delta),
             EventMsg::PatchApplyBegin(ev) => self.on_patch_apply_begin(ev),
             EventMsg::PatchApplyEnd(ev) => self.on_patch_apply_end(ev),
+            EventMsg::PatchHunkReport(ev) => self.on_patch_hunk_report(ev),
+            // Shown in the approval dialog, which carries the same preview.
+            EventMsg::PatchPreview(_) => {}
             EventMsg::ExecCommandEnd(ev) => self.on_exec_command_end(ev),
             EventMsg::ViewImageToolCall(ev) => self.on_view_image_tool_call(ev),
             EventMsg::McpToolCallBegin(ev) => self.on_mcp_tool_call_begin(ev),
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
             EventMsg::WebSearchBegin(ev) => self.on_web_search_begin(ev),
             EventMsg::WebSearchEnd(ev) => self.on_web_search_end(ev),
+            EventMsg::HostedToolCall(ev) => self.on_hosted_tool_call(ev),
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::ListMcpPromptsResponse(ev) => self.on_list_mcp_prompts(ev),
             EventMsg::ListSkillsResponse(ev) => self.on_list_skills(ev),
+            EventMsg::ApprovalJournalResponse(ev) => self.on_approval_journal(ev),
+            EventMsg::ListSubAgentsResponse(ev) => self.on_list_subagents(ev),
+            EventMsg::SubAgentsUpdated(ev) => self.on_subagents_updated(ev),
+            EventMsg::ConfigReloaded(ev) => self.on_config_reloaded(ev),
+            EventMsg::SubAgentRunBegin(ev) => self.on_subagent_run_begin(ev),
+            EventMsg::SubAgentRunEnd(ev) => self.on_subagent_run_end(ev),
+            EventMsg::SubAgentPreview(preview) => self.on_subagent_preview(preview),
+            EventMsg::SubAgentMergePlan(ev) => self.on_subagent_merge_plan(ev),
+            EventMsg::SubAgentMergeApplied(ev) => self.on_subagent_merge_applied(ev),
+            EventMsg::BackgroundTaskBegin(ev) => self.on_background_task_begin(ev),
+            EventMsg::BackgroundTaskEnd(ev) => self.on_background_task_end(ev),
+            EventMsg::ListBackgroundTasksResponse(ev) => self.on_list_background_tasks(ev),
+            EventMsg::BackgroundProcessesUpdated(ev) => self.on_background_processes_updated(ev),
+            // The TUI keeps its own editable queue and never queues in core.
+            EventMsg::QueuedUserInputs(_) => {}
+            EventMsg::HookOutcome(ev) => self.on_hook_outcome(ev),
+            EventMsg::TurnAnnotations(ev) => self.on_turn_annotations(ev),
+            EventMsg::NetworkActivity(ev) => self.on_network_activity(ev),
+            EventMsg::ExternalFilesChanged(ev) => self.on_external_files_changed(ev),
+            EventMsg::ResumeBriefing(ev) => self.on_resume_briefing(ev),
+            // Only written to the rollout; replayed on resume.
+            EventMsg::WorkspaceSnapshot(_) => {}
+            EventMsg::PlanModeUpdated(ev) => self.on_plan_mode_updated(ev),
+            EventMsg::ConversationForked(ev) => self.on_conversation_forked(ev),
+            EventMsg::CheckpointCreated(ev) => self.on_checkpoint_created(ev),
+            EventMsg::ListCheckpointsResponse(ev) => self.on_list_checkpoints(ev),
+            EventMsg::CheckpointRestored(ev) => self.on_checkpoint_restored(ev),
+            EventMsg::WorktreeCreated(ev) => self.on_worktree_created(ev),
+            EventMsg::WorktreeReview(ev) => self.on_worktree_review(ev),
+            EventMsg::WorktreeFinished(ev) => self.on_worktree_finished(ev),
+            EventMsg::PinnedContextUpdated(ev) => self.on_pinned_context_updated(ev),
+            EventMsg::ListPinnedContextResponse(ev) => self.on_list_pinned_context(ev),
+            EventMsg::ContextInspection(ev) => {
+                self.app_event_tx.send(AppEvent::ContextInspection(ev));
+            }
+            EventMsg::ListMemoryResponse(ev) => self.on_list_memory(ev),
+            // Only requested by editor integrations.
+            EventMsg::NextEditSuggestion(_) => {}
+            // The command's output already shows the denial.
+            EventMsg::SandboxDenied(_) => {}
             EventMsg::SkillsUpdateAvailable => {
                 self.submit_op(Op::ListSkills {
                     cwds: Vec::new(),
@@ -2756,7 +3298,9 @@ impl ChatWidget {
                 });
             }
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
-            EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
+            EventMsg::TurnDiff(TurnDiffEvent { unified_diff, .. }) => {
+                self.on_turn_diff(unified_diff)
+            }
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 self.on_background_event(message)
@@ -2981,6 +3525,47 @@ impl ChatWidget {
         self.add_to_history(history_cell::new_unified_exec_processes_output(processes));
     }
 
+    /// Tells core the terminal size so PTY-backed commands lay out their
+    /// output for it; only changes are sent.
+    pub(crate) fn sync_terminal_size(&mut self, rows: u16, cols: u16) {
+        if self.thread_id.is_none() || self.reported_terminal_size == Some((rows, cols)) {
+            return;
+        }
+        self.reported_terminal_size = Some((rows, cols));
+        self.submit_op(Op::SetTerminalSize { rows, cols });
+    }
+
+    /// `/stdin [ID] TEXT`: sends TEXT and a newline to the background terminal
+    /// whose process id is ID, or to the most recently started one.
+    fn stdin_command(&mut self, args: &str) {
+        let args = args.trim_start();
+        let (process_id, text) = match args.split_once(char::is_whitespace) {
+            Some((first, rest))
+                if self
+                    .unified_exec_processes
+                    .iter()
+                    .any(|process| process.key == first) =>
+            {
+                (first.to_string(), rest)
+            }
+            _ => match self.unified_exec_processes.last() {
+                Some(process) => (process.key.clone(), args),
+                None => {
+                    self.add_error_message("No background terminal is running.".to_string());
+                    return;
+                }
+            },
+        };
+        if text.is_empty() {
+            self.add_error_message("Usage: /stdin [ID] TEXT".to_string());
+            return;
+        }
+        self.submit_op(Op::TerminalInput {
+            process_id,
+            input: format!("{text}\n"),
+        });
+    }
+
     fn stop_rate_limit_poller(&mut self) {
         if let Some(handle) = self.rate_limit_poller.take() {
             handle.abort();
@@ -3366,6 +3951,72 @@ impl ChatWidget {
         });
     }
 
+    fn open_hunk_review(&self, changes: &HashMap<PathBuf, FileChange>) {
+        let files = review_files(changes, &self.config.cwd);
+        if !files.is_empty() {
+            self.app_event_tx.send(AppEvent::OpenHunkReview(files));
+        }
+    }
+
+    /// Opens the hunk review of `call_id`'s patch once it applies.
+    pub(crate) fn review_hunks_after_apply(&mut self, call_id: String) {
+        self.hunk_review_calls.insert(call_id);
+    }
+
+    /// Reverts the hunks rejected in the hunk review and tells the agent.
+    pub(crate) fn finish_hunk_review(&mut self, files: &[ReviewFile]) {
+        let outcome = revert_rejected(files);
+        let total: usize = files.iter().map(|file| file.rejected.len()).sum();
+        if outcome.reverted.is_empty() {
+            if outcome.failures.is_empty() {
+                self.add_info_message(format!("Kept all {total} hunks of the patch."), None);
+            }
+        } else {
+            self.add_info_message(
+                format!(
+                    "Reverted {} of {total} hunks of the patch.",
+                    outcome.reverted.len()
+                ),
+                Some("Codex was told which changes you rejected.".to_string()),
+            );
+        }
+        for failure in &outcome.failures {
+            self.add_error_message(failure.clone());
+        }
+        if let Some(feedback) = outcome.feedback_message() {
+            self.queue_user_message(feedback.into());
+        }
+    }
+
+    pub(crate) fn open_theme_popup(&mut self) {
+        let active = theme::active_name();
+        let items: Vec<SelectionItem> = theme::THEMES
+            .iter()
+            .map(|spec| {
+                let name = spec.name;
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::SetTheme(name.to_string()));
+                })];
+                SelectionItem {
+                    name: name.to_string(),
+                    description: Some(spec.description.to_string()),
+                    is_current: name == active,
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Select Theme".to_string()),
+            subtitle: Some("Set `theme` under [tui] in config.toml to keep it.".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
     fn model_selection_actions(
         model_for_action: String,
         effort_for_action: Option<ReasoningEffortConfig>,
@@ -4284,6 +4935,7 @@ impl ChatWidget {
         if let Err(err) = self.config.approval_policy.set(policy) {
             tracing::warn!(%err, "failed to set approval_policy on chat config");
         }
+        self.refresh_status_line();
     }
 
     /// Set the sandbox policy in the widget's config copy.
@@ -4293,6 +4945,7 @@ impl ChatWidget {
             || codex_core::get_platform_sandbox().is_some();
 
         self.config.sandbox_policy.set(policy)?;
+        self.refresh_status_line();
 
         #[cfg(target_os = "windows")]
         if should_clear_downgrade {
@@ -4362,6 +5015,7 @@ impl ChatWidget {
         self.stored_collaboration_mode =
             self.stored_collaboration_mode
                 .with_updates(None, Some(effort), None);
+        self.refresh_status_line();
     }
 
     /// Set the model in the widget's config copy and stored collaboration mode.
@@ -4370,6 +5024,7 @@ impl ChatWidget {
         self.stored_collaboration_mode =
             self.stored_collaboration_mode
                 .with_updates(Some(model.to_string()), None, None);
+        self.refresh_status_line();
     }
 
     pub(crate) fn current_model(&self) -> &str {
@@ -4433,6 +5088,7 @@ impl ChatWidget {
     fn update_collaboration_mode_indicator(&mut self) {
         let indicator = self.collaboration_mode_indicator();
         self.bottom_pane.set_collaboration_mode_indicator(indicator);
+        self.refresh_status_line();
     }
 
     /// Cycle to the next collaboration mode variant (Plan -> PairProgramming -> Execute -> Plan).
@@ -4537,6 +5193,10 @@ impl ChatWidget {
         self.bottom_pane.on_file_search_result(query, matches);
     }
 
+    pub(crate) fn apply_symbol_search_result(&mut self, query: String, matches: Vec<SymbolMatch>) {
+        self.bottom_pane.on_symbol_search_result(query, matches);
+    }
+
     /// Handles a Ctrl+C press at the chat-widget layer.
     ///
     /// The first press arms a time-bounded quit shortcut and shows a footer hint via the bottom
@@ -4648,6 +5308,10 @@ impl ChatWidget {
     /// True when the UI is in the regular composer state with no running task,
     /// no modal overlay (e.g. approvals or status indicator), and no composer popups.
     /// In this state Esc-Esc backtracking is enabled.
+    pub(crate) fn is_task_running(&self) -> bool {
+        self.bottom_pane.is_task_running()
+    }
+
     pub(crate) fn is_normal_backtrack_mode(&self) -> bool {
         self.bottom_pane.is_normal_backtrack_mode()
     }
@@ -4698,15 +5362,188 @@ impl ChatWidget {
 
     fn on_list_custom_prompts(&mut self, ev: ListCustomPromptsResponseEvent) {
         let len = ev.custom_prompts.len();
-        debug!("received {len} custom prompts");
+        let commands_len = ev.custom_commands.len();
+        debug!("received {len} custom prompts and {commands_len} custom commands");
         // Forward to bottom pane so the slash popup can show them now.
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
+        self.bottom_pane.set_custom_commands(ev.custom_commands);
+    }
+
+    fn on_list_mcp_prompts(&mut self, ev: ListMcpPromptsResponseEvent) {
+        self.bottom_pane.set_mcp_prompts(ev.prompts);
     }
 
     fn on_list_skills(&mut self, ev: ListSkillsResponseEvent) {
         self.set_skills_from_response(&ev);
     }
 
+    fn on_approval_journal(&mut self, ev: ApprovalJournalResponseEvent) {
+        self.add_to_history(history_cell::new_approval_journal_output(ev.entries));
+    }
+
+    fn on_config_reloaded(&mut self, ev: ConfigReloadedEvent) {
+        let ConfigReloadedEvent {
+            branch,
+            approval_policy,
+            sandbox_policy,
+        } = ev;
+        self.set_approval_policy(approval_policy);
+        if let Err(err) = self.set_sandbox_policy(sandbox_policy.clone()) {
+            tracing::warn!(%err, "failed to set sandbox_policy on chat config");
+        }
+        let branch = branch.unwrap_or_else(|| "detached HEAD".to_string());
+        self.add_info_message(
+            format!("Branch changed to {branch}; applied branch config overrides"),
+            Some(format!(
+                "approvals: {approval_policy}, sandbox: {sandbox_policy}"
+            )),
+        );
+    }
+
+    fn on_hook_outcome(&mut self, ev: HookOutcomeEvent) {
+        let HookOutcomeEvent {
+            hook_id,
+            when,
+            outcome,
+            ..
+        } = ev;
+        let hook = hook_id.unwrap_or_else(|| when.to_string());
+        match outcome {
+            HookOutcome::Blocked { reason } => {
+                self.on_warning(format!("Hook `{hook}` blocked {when}: {reason}"));
+            }
+            HookOutcome::Modified => {
+                self.add_info_message(format!("Hook `{hook}` rewrote the tool arguments"), None);
+            }
+            HookOutcome::Failed { error } => {
+                self.on_warning(format!("Hook `{hook}` failed: {error}"));
+            }
+        }
+    }
+
+    fn on_turn_annotations(&mut self, ev: TurnAnnotationsEvent) {
+        let TurnAnnotationsEvent {
+            hook_id,
+            annotations,
+            artifacts,
+            notifications,
+        } = ev;
+        let hook = hook_id.unwrap_or_else(|| HookEventKind::TurnPostProcess.to_string());
+        for annotation in annotations {
+            self.add_info_message(annotation, Some(format!("from hook `{hook}`")));
+        }
+        if !artifacts.is_empty() {
+            let paths = artifacts
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.add_info_message(
+                format!("Hook `{hook}` wrote {} artifact(s)", artifacts.len()),
+                Some(paths),
+            );
+        }
+        for HookNotification { title, body } in notifications {
+            self.add_info_message(title, body);
+        }
+    }
+
+    fn on_conversation_forked(&mut self, ev: ConversationForkedEvent) {
+        let ConversationForkedEvent {
+            thread_id,
+            num_turns,
+            ..
+        } = ev;
+        let kept = match num_turns {
+            Some(1) => " (first turn)".to_string(),
+            Some(n) => format!(" (first {n} turns)"),
+            None => String::new(),
+        };
+        self.add_info_message(
+            format!("Forked this chat into {thread_id}{kept}"),
+            Some(format!("continue it with `codex resume {thread_id}`")),
+        );
+    }
+
+    fn on_plan_mode_updated(&mut self, ev: PlanModeUpdatedEvent) {
+        self.plan_mode = ev.enabled;
+        if ev.enabled {
+            self.add_info_message(
+                "Plan mode on".to_string(),
+                Some("Codex will ask you to approve a plan before running commands or editing files.".to_string()),
+            );
+        } else {
+            self.add_info_message("Plan mode off".to_string(), None);
+        }
+    }
+
+    fn on_network_activity(&mut self, ev: NetworkActivityEvent) {
+        let NetworkActivityEvent { connections } = ev;
+        let mut hosts: Vec<String> = Vec::new();
+        let (mut sent, mut received) = (0u64, 0u64);
+        for connection in &connections {
+            let host = format!("{}:{}", connection.host, connection.port);
+            if !hosts.contains(&host) {
+                hosts.push(host);
+            }
+            sent += connection.bytes_sent;
+            received += connection.bytes_received;
+        }
+        self.add_info_message(
+            format!(
+                "Network: {} connection(s), {sent} bytes sent, {received} bytes received",
+                connections.len()
+            ),
+            Some(hosts.join(", ")),
+        );
+    }
+
+    fn on_external_files_changed(&mut self, ev: ExternalFilesChangedEvent) {
+        let ExternalFilesChangedEvent { changes } = ev;
+        let paths = changes
+            .iter()
+            .map(|change| change.path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.add_info_message(
+            format!(
+                "{} file(s) changed outside the agent; Codex was told before its next request",
+                changes.len()
+            ),
+            Some(paths),
+        );
+    }
+
+    fn on_resume_briefing(&mut self, ev: ResumeBriefingEvent) {
+        let ResumeBriefingEvent {
+            previous_branch,
+            branch,
+            new_commits,
+            new_commit_count,
+            changed_files,
+            ci_status,
+            ..
+        } = ev;
+        let mut details = Vec::new();
+        if let Some(previous_branch) = previous_branch {
+            let branch = branch.as_deref().unwrap_or("(detached HEAD)");
+            details.push(format!("branch {previous_branch} → {branch}"));
+        }
+        if let Some(commit) = new_commits.first() {
+            details.push(format!("latest commit {} {}", commit.sha, commit.subject));
+        }
+        if let Some(ci_status) = ci_status {
+            details.push(format!("CI {ci_status}"));
+        }
+        self.add_info_message(
+            format!(
+                "Since the last turn: {new_commit_count} new commit(s), {} file(s) with changed uncommitted edits; Codex was briefed",
+                changed_files.len()
+            ),
+            (!details.is_empty()).then(|| details.join(" · ")),
+        );
+    }
+
     pub(crate) fn open_review_popup(&mut self) {
         let mut items: Vec<SelectionItem> = Vec::new();
 
@@ -5142,5 +5979,41 @@ pub(crate) fn show_review_commit_picker_with_entries(
     });
 }
 
+/// Parses the `/compact` argument: `N-M` for turns N through M, or `N` for a
+/// single turn.
+fn parse_turn_range(arg: &str) -> Option<(u32, u32)> {
+    let (from, to) = arg.split_once('-').unwrap_or((arg, arg));
+    let from_turn = from.trim().parse().ok()?;
+    let to_turn = to.trim().parse().ok()?;
+    Some((from_turn, to_turn))
+}
+
+/// "2 of 500 requests and 0 of 30000 tokens left" for the quotas the
+/// provider reported.
+fn provider_quota_summary(quota: &ProviderQuota) -> Option<String> {
+    let parts: Vec<String> = [
+        (quota.remaining_requests, quota.limit_requests, "requests"),
+        (quota.remaining_tokens, quota.limit_tokens, "tokens"),
+    ]
+    .into_iter()
+    .filter_map(|(remaining, limit, unit)| match (remaining, limit) {
+        (Some(remaining), Some(limit)) => Some(format!("{remaining} of {limit} {unit}")),
+        (Some(remaining), None) => Some(format!("{remaining} {unit}")),
+        _ => None,
+    })
+    .collect();
+    (!parts.is_empty()).then(|| format!("{} left", parts.join(" and ")))
+}
+
+/// Short sandbox mode for the status line; `/status` shows the full policy.
+fn sandbox_mode_label(policy: &SandboxPolicy) -> &'static str {
+    match policy {
+        SandboxPolicy::DangerFullAccess => "danger-full-access",
+        SandboxPolicy::ReadOnly => "read-only",
+        SandboxPolicy::ExternalSandbox { .. } => "external-sandbox",
+        SandboxPolicy::WorkspaceWrite { .. } => "workspace-write",
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests;
@@ -0,0 +1,165 @@
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::custom_prompt_view::CustomPromptView;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::collab;
+use crate::status_indicator_widget::fmt_elapsed_compact;
+use crate::text_formatting::truncate_text;
+use codex_core::protocol::AgentStatus;
+use codex_core::protocol::BackgroundTaskBeginEvent;
+use codex_core::protocol::BackgroundTaskEndEvent;
+use codex_core::protocol::BackgroundTaskInfo;
+use codex_core::protocol::ListBackgroundTasksResponseEvent;
+use codex_core::protocol::Op;
+
+const TASK_PROMPT_PREVIEW_GRAPHEMES: usize = 60;
+
+impl ChatWidget {
+    pub(crate) fn on_list_background_tasks(&mut self, ev: ListBackgroundTasksResponseEvent) {
+        let mut items = vec![SelectionItem {
+            name: "Start a background task".to_string(),
+            description: Some("prefix the prompt with @name to run it as a subagent".to_string()),
+            actions: vec![Box::new(|tx| {
+                tx.send(AppEvent::OpenBackgroundTaskPrompt);
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        }];
+        let now = chrono::Utc::now();
+        items.extend(ev.tasks.into_iter().map(|task| {
+            let task_id = task.task_id.clone();
+            SelectionItem {
+                name: format!("● {}", task_label(&task)),
+                description: Some(task_description(&task, now)),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::CodexOp(Op::CancelBackgroundTask {
+                        task_id: task_id.clone(),
+                    }));
+                })],
+                dismiss_on_select: true,
+                search_value: Some(task.prompt),
+                ..Default::default()
+            }
+        }));
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Background tasks".to_string()),
+            subtitle: Some("Start a task, or cancel a running one".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search tasks".to_string()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn show_background_task_prompt(&mut self) {
+        let tx = self.app_event_tx.clone();
+        let view = CustomPromptView::new(
+            "Start a background task".to_string(),
+            "Type a prompt (optionally `@name prompt`) and press Enter".to_string(),
+            None,
+            Box::new(move |input: String| {
+                let (subagent, prompt) = parse_task_input(&input);
+                if prompt.is_empty() {
+                    return;
+                }
+                tx.send(AppEvent::CodexOp(Op::StartBackgroundTask {
+                    prompt,
+                    subagent,
+                }));
+            }),
+        );
+        self.bottom_pane.show_view(Box::new(view));
+    }
+
+    pub(crate) fn on_background_task_begin(&mut self, ev: BackgroundTaskBeginEvent) {
+        self.running_background_tasks.insert(ev.task_id.clone());
+        self.on_collab_event(collab::background_task_begin(ev));
+    }
+
+    pub(crate) fn on_background_task_end(&mut self, ev: BackgroundTaskEndEvent) {
+        self.running_background_tasks.remove(&ev.task_id);
+        self.on_collab_event(collab::background_task_end(ev));
+    }
+}
+
+/// Splits a leading `@name` off the task input so the task runs under that
+/// subagent definition.
+fn parse_task_input(input: &str) -> (Option<String>, String) {
+    let input = input.trim();
+    if let Some(rest) = input.strip_prefix('@') {
+        let (name, prompt) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if !name.is_empty() {
+            return (Some(name.to_string()), prompt.trim().to_string());
+        }
+    }
+    (None, input.to_string())
+}
+
+fn task_label(task: &BackgroundTaskInfo) -> String {
+    let prompt = truncate_text(
+        &task.prompt.split_whitespace().collect::<Vec<_>>().join(" "),
+        TASK_PROMPT_PREVIEW_GRAPHEMES,
+    );
+    match &task.subagent {
+        Some(name) => format!("@{name} {prompt}"),
+        None => prompt,
+    }
+}
+
+fn task_description(task: &BackgroundTaskInfo, now: chrono::DateTime<chrono::Utc>) -> String {
+    let status = match task.status {
+        AgentStatus::PendingInit => "starting",
+        AgentStatus::Running => "running",
+        AgentStatus::Completed(_) => "completed",
+        AgentStatus::Errored(_) => "errored",
+        AgentStatus::Shutdown => "shut down",
+        AgentStatus::NotFound => "not found",
+    };
+    let elapsed = chrono::DateTime::parse_from_rfc3339(&task.started_at)
+        .ok()
+        .and_then(|started_at| (now - started_at.with_timezone(&chrono::Utc)).to_std().ok())
+        .map(|elapsed| format!(" {}", fmt_elapsed_compact(elapsed.as_secs())))
+        .unwrap_or_default();
+    format!("{status}{elapsed} · enter to cancel")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_task_input_extracts_leading_subagent() {
+        assert_eq!(
+            parse_task_input("  @reviewer check the diff "),
+            (Some("reviewer".to_string()), "check the diff".to_string())
+        );
+        assert_eq!(
+            parse_task_input("refactor the parser"),
+            (None, "refactor the parser".to_string())
+        );
+        assert_eq!(parse_task_input("@ hello"), (None, "@ hello".to_string()));
+    }
+
+    #[test]
+    fn task_description_reports_status_and_elapsed() {
+        let task = BackgroundTaskInfo {
+            task_id: "task".to_string(),
+            subagent: None,
+            prompt: "refactor".to_string(),
+            status: AgentStatus::Running,
+            started_at: "2025-01-01T00:00:00+00:00".to_string(),
+        };
+        let now = chrono::DateTime::parse_from_rfc3339("2025-01-01T00:01:30+00:00")
+            .expect("timestamp")
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            task_description(&task, now),
+            "running 1m 30s · enter to cancel"
+        );
+    }
+}
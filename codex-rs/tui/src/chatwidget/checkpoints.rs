@@ -0,0 +1,168 @@
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use codex_core::protocol::Checkpoint;
+use codex_core::protocol::CheckpointCreatedEvent;
+use codex_core::protocol::CheckpointRestoredEvent;
+use codex_core::protocol::ListCheckpointsResponseEvent;
+use codex_core::protocol::Op;
+
+impl ChatWidget {
+    /// `/checkpoint <name>` creates a checkpoint; a bare `/checkpoint` lists them.
+    pub(crate) fn checkpoint_command(&mut self, args: &str) {
+        let name = parse_checkpoint_name(args);
+        if name.is_empty() {
+            self.submit_op(Op::ListCheckpoints);
+        } else {
+            self.submit_op(Op::CreateCheckpoint { name });
+        }
+    }
+
+    pub(crate) fn on_checkpoint_created(&mut self, ev: CheckpointCreatedEvent) {
+        let Checkpoint { name, id, .. } = ev.checkpoint;
+        self.add_info_message(
+            format!("Created checkpoint \"{name}\""),
+            Some(format!("restore it later from /checkpoint (id {id})")),
+        );
+    }
+
+    pub(crate) fn on_list_checkpoints(&mut self, ev: ListCheckpointsResponseEvent) {
+        if ev.checkpoints.is_empty() {
+            self.add_info_message(
+                "No checkpoints yet".to_string(),
+                Some("create one with /checkpoint <name>".to_string()),
+            );
+            return;
+        }
+
+        // Newest first: that is usually the one to go back to.
+        let items = ev
+            .checkpoints
+            .into_iter()
+            .rev()
+            .map(|checkpoint| {
+                let id = checkpoint.id.clone();
+                SelectionItem {
+                    name: checkpoint.name.clone(),
+                    description: Some(checkpoint_description(&checkpoint)),
+                    actions: vec![Box::new(move |tx| {
+                        tx.send(AppEvent::CodexOp(Op::RestoreCheckpoint { id: id.clone() }));
+                    })],
+                    dismiss_on_select: true,
+                    search_value: Some(checkpoint.name),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Checkpoints".to_string()),
+            subtitle: Some(
+                "Restore a checkpoint; checkpoints created after it are discarded".to_string(),
+            ),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search checkpoints".to_string()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn on_checkpoint_restored(&mut self, ev: CheckpointRestoredEvent) {
+        let CheckpointRestoredEvent {
+            success, message, ..
+        } = ev;
+        if success {
+            self.add_info_message(
+                message.unwrap_or_else(|| "Checkpoint restored.".to_string()),
+                None,
+            );
+        } else {
+            self.add_error_message(
+                message.unwrap_or_else(|| "Failed to restore checkpoint.".to_string()),
+            );
+        }
+    }
+}
+
+/// Strips the optional quotes around `/checkpoint "before refactor"`.
+fn parse_checkpoint_name(args: &str) -> String {
+    let trimmed = args.trim();
+    trimmed
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(trimmed)
+        .trim()
+        .to_string()
+}
+
+fn checkpoint_description(checkpoint: &Checkpoint) -> String {
+    let created = chrono::DateTime::parse_from_rfc3339(&checkpoint.created_at)
+        .map(|created| {
+            created
+                .with_timezone(&chrono::Local)
+                .format("%H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|_| checkpoint.created_at.clone());
+    let changes = match checkpoint.diffstat {
+        Some(stat) if stat.files_changed == 0 => "no changes since".to_string(),
+        Some(stat) => format!(
+            "{} {} changed since (+{} -{})",
+            stat.files_changed,
+            if stat.files_changed == 1 {
+                "file"
+            } else {
+                "files"
+            },
+            stat.insertions,
+            stat.deletions
+        ),
+        None => "changes unknown".to_string(),
+    };
+    format!("{created} · {changes} · enter to restore")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::CheckpointDiffStat;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_checkpoint_name_strips_quotes() {
+        assert_eq!(
+            parse_checkpoint_name(" \"before refactor\" "),
+            "before refactor"
+        );
+        assert_eq!(parse_checkpoint_name("wip"), "wip");
+        assert_eq!(parse_checkpoint_name("   "), "");
+    }
+
+    #[test]
+    fn checkpoint_description_reports_diffstat() {
+        let mut checkpoint = Checkpoint {
+            id: "1".to_string(),
+            name: "before refactor".to_string(),
+            commit_id: "abc".to_string(),
+            created_at: "not a timestamp".to_string(),
+            diffstat: Some(CheckpointDiffStat {
+                files_changed: 2,
+                insertions: 10,
+                deletions: 3,
+            }),
+        };
+        assert_eq!(
+            checkpoint_description(&checkpoint),
+            "not a timestamp · 2 files changed since (+10 -3) · enter to restore"
+        );
+
+        checkpoint.diffstat = None;
+        assert_eq!(
+            checkpoint_description(&checkpoint),
+            "not a timestamp · changes unknown · enter to restore"
+        );
+    }
+}
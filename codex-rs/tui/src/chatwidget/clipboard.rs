@@ -0,0 +1,158 @@
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::clipboard_copy;
+use crate::clipboard_copy::CopyMethod;
+use crate::clipboard_paste::paste_image_to_temp_png;
+use crate::get_git_diff::get_git_diff;
+use crate::history_cell;
+
+impl ChatWidget {
+    /// Handles `/copy` (the last code block of the latest reply, or the whole
+    /// reply when it has none), `/copy reply` (the whole reply), `/copy select`
+    /// (pick messages in the transcript) and `/copy diff` (the working tree
+    /// diff).
+    pub(crate) fn copy_command(&mut self, args: &str) {
+        match args {
+            "" | "reply" => {
+                let Some(message) = self.last_agent_message.clone() else {
+                    self.add_error_message(
+                        "Nothing to copy: Codex has not replied yet.".to_string(),
+                    );
+                    return;
+                };
+                match last_code_block(&message).filter(|_| args.is_empty()) {
+                    Some(code) => self.copy_text("the last code block", code),
+                    None => self.copy_text("the last reply", message),
+                }
+            }
+            "select" => self.app_event_tx.send(AppEvent::OpenTranscriptSelection),
+            "diff" => {
+                let tx = self.app_event_tx.clone();
+                tokio::spawn(async move {
+                    let cell = match get_git_diff(false).await {
+                        Ok((true, diff)) if !diff.trim().is_empty() => {
+                            tx.send(AppEvent::CopyToClipboard {
+                                what: "the diff".to_string(),
+                                text: diff,
+                            });
+                            return;
+                        }
+                        Ok((true, _)) => {
+                            history_cell::new_info_event("No changes to copy.".to_string(), None)
+                        }
+                        Ok((false, _)) => history_cell::new_error_event(
+                            "`/copy diff` — not inside a git repository".to_string(),
+                        ),
+                        Err(err) => {
+                            history_cell::new_error_event(format!("Failed to compute diff: {err}"))
+                        }
+                    };
+                    tx.send(AppEvent::InsertHistoryCell(Box::new(cell)));
+                });
+            }
+            _ => self.add_error_message("Usage: /copy [reply | select | diff]".to_string()),
+        }
+    }
+
+    /// Puts `text` on the clipboard and reports the outcome in the
+    /// transcript. `what` names the text in that message.
+    pub(crate) fn copy_text(&mut self, what: &str, text: String) {
+        match clipboard_copy::copy_to_clipboard(&text) {
+            Ok(CopyMethod::Native) => {
+                self.add_info_message(format!("Copied {what} to the clipboard."), None);
+            }
+            Ok(CopyMethod::Terminal) => self.add_info_message(
+                format!("Sent {what} to the terminal clipboard."),
+                Some("This needs a terminal with OSC 52 support.".to_string()),
+            ),
+            Err(err) => self.add_error_message(format!("Failed to copy {what}: {err}")),
+        }
+    }
+
+    /// Attaches the image on the system clipboard to the composer. With
+    /// `report_errors` unset, a missing image is only logged.
+    pub(crate) fn paste_clipboard_image(&mut self, report_errors: bool) {
+        match paste_image_to_temp_png() {
+            Ok((path, info)) => {
+                tracing::debug!(
+                    "pasted image size={}x{} format={}",
+                    info.width,
+                    info.height,
+                    info.encoded_format.label()
+                );
+                self.attach_image(path);
+            }
+            Err(err) => {
+                tracing::warn!("failed to paste image: {err}");
+                if report_errors {
+                    self.add_to_history(history_cell::new_error_event(format!(
+                        "Failed to paste image: {err}",
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// Returns the body of the last fenced code block in `markdown`. A block
+/// left open at the end of the message still counts.
+fn last_code_block(markdown: &str) -> Option<String> {
+    let mut last = None;
+    let mut open: Option<((char, usize), Vec<&str>)> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        match open.as_mut() {
+            None => {
+                if let Some(fence) = fence_of(trimmed) {
+                    open = Some((fence, Vec::new()));
+                }
+            }
+            Some(((fence_char, fence_len), body)) => {
+                let closes = fence_of(trimmed).is_some_and(|(c, len)| {
+                    c == *fence_char
+                        && len >= *fence_len
+                        && trimmed.trim_end().chars().all(|ch| ch == c)
+                });
+                if closes {
+                    last = open.take().map(|(_, body)| body.join("\n"));
+                } else {
+                    body.push(line);
+                }
+            }
+        }
+    }
+    open.map(|(_, body)| body.join("\n")).or(last)
+}
+
+/// Fence character and length when `line` opens or closes a code block.
+pub(super) fn fence_of(line: &str) -> Option<(char, usize)> {
+    let c = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|ch| *ch == c).count();
+    (len >= 3).then_some((c, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn last_code_block_picks_the_final_block() {
+        let message = "First:\n```sh\ncargo build\n```\nThen:\n```rust\nfn main() {}\n\nlet x = 1;\n```\nDone.";
+        assert_eq!(
+            last_code_block(message),
+            Some("fn main() {}\n\nlet x = 1;".to_string())
+        );
+    }
+
+    #[test]
+    fn last_code_block_handles_nested_fences_and_open_blocks() {
+        let nested = "````md\n```sh\nls\n```\n````";
+        assert_eq!(last_code_block(nested), Some("```sh\nls\n```".to_string()));
+        assert_eq!(
+            last_code_block("```\nunterminated"),
+            Some("unterminated".to_string())
+        );
+        assert_eq!(last_code_block("no code here"), None);
+    }
+}
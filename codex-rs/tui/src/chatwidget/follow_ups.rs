@@ -0,0 +1,130 @@
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::history_cell;
+use crate::text_formatting::truncate_text;
+use codex_core::protocol::FollowUpSuggestion;
+
+const FOLLOW_UP_PREVIEW_GRAPHEMES: usize = 60;
+
+impl ChatWidget {
+    /// Shows the suggestions as chips and keeps them for `/follow-ups`
+    /// until they are used or the agent suggests new ones.
+    pub(super) fn on_follow_up_suggestions(&mut self, suggestions: Vec<FollowUpSuggestion>) {
+        if suggestions.is_empty() {
+            return;
+        }
+        self.add_to_history(history_cell::new_follow_up_suggestions(&suggestions));
+        self.follow_up_suggestions = suggestions;
+        self.request_redraw();
+    }
+
+    pub(crate) fn open_follow_ups_popup(&mut self) {
+        if self.follow_up_suggestions.is_empty() {
+            self.add_info_message(
+                "No suggested follow-ups.".to_string(),
+                Some("Enable the follow_ups feature to let Codex suggest next steps.".to_string()),
+            );
+            return;
+        }
+
+        let items = self
+            .follow_up_suggestions
+            .iter()
+            .enumerate()
+            .map(|(index, suggestion)| {
+                let chosen = suggestion.clone();
+                SelectionItem {
+                    name: format!("{}. {}", index + 1, suggestion.title),
+                    description: Some(truncate_text(
+                        &suggestion.prompt,
+                        FOLLOW_UP_PREVIEW_GRAPHEMES,
+                    )),
+                    actions: vec![Box::new(move |tx| {
+                        tx.send(AppEvent::OpenFollowUpActions(chosen.clone()));
+                    })],
+                    dismiss_on_select: true,
+                    search_value: Some(format!("{} {}", suggestion.title, suggestion.prompt)),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Suggested follow-ups".to_string()),
+            subtitle: Some("Next steps proposed by Codex".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search suggestions".to_string()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_follow_up_actions(&mut self, suggestion: FollowUpSuggestion) {
+        let queue_prompt = suggestion.prompt.clone();
+        let edit_prompt = suggestion.prompt.clone();
+        let queue_description = if self.bottom_pane.is_task_running() {
+            "send it after the running turn"
+        } else {
+            "send it now"
+        };
+        let items = vec![
+            SelectionItem {
+                name: "Queue as next turn".to_string(),
+                description: Some(queue_description.to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::UseFollowUp {
+                        prompt: queue_prompt.clone(),
+                        queue: true,
+                    });
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Edit in composer".to_string(),
+                description: Some("put the prompt in the composer first".to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::UseFollowUp {
+                        prompt: edit_prompt.clone(),
+                        queue: false,
+                    });
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Cancel".to_string(),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(suggestion.title),
+            subtitle: Some(truncate_text(
+                &suggestion.prompt,
+                FOLLOW_UP_PREVIEW_GRAPHEMES,
+            )),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// Queues or pre-fills the suggestion with `prompt`, which then leaves the
+    /// `/follow-ups` list.
+    pub(crate) fn use_follow_up(&mut self, prompt: String, queue: bool) {
+        self.follow_up_suggestions
+            .retain(|suggestion| suggestion.prompt != prompt);
+        if queue {
+            self.queue_user_message(prompt.into());
+        } else {
+            self.bottom_pane
+                .set_composer_text(prompt, Vec::new(), Vec::new());
+        }
+    }
+}
@@ -8,6 +8,8 @@ use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_protocol::approvals::ElicitationRequestEvent;
+use codex_protocol::approvals::McpToolCallApprovalRequestEvent;
+use codex_protocol::approvals::PlanApprovalRequestEvent;
 use codex_protocol::request_user_input::RequestUserInputEvent;
 
 use super::ChatWidget;
@@ -17,6 +19,8 @@ pub(crate) enum QueuedInterrupt {
     ExecApproval(String, ExecApprovalRequestEvent),
     ApplyPatchApproval(String, ApplyPatchApprovalRequestEvent),
     Elicitation(ElicitationRequestEvent),
+    PlanApproval(PlanApprovalRequestEvent),
+    McpToolCallApproval(McpToolCallApprovalRequestEvent),
     RequestUserInput(RequestUserInputEvent),
     ExecBegin(ExecCommandBeginEvent),
     ExecEnd(ExecCommandEndEvent),
@@ -59,6 +63,15 @@ impl InterruptManager {
         self.queue.push_back(QueuedInterrupt::Elicitation(ev));
     }
 
+    pub(crate) fn push_plan_approval(&mut self, ev: PlanApprovalRequestEvent) {
+        self.queue.push_back(QueuedInterrupt::PlanApproval(ev));
+    }
+
+    pub(crate) fn push_mcp_tool_call_approval(&mut self, ev: McpToolCallApprovalRequestEvent) {
+        self.queue
+            .push_back(QueuedInterrupt::McpToolCallApproval(ev));
+    }
+
     pub(crate) fn push_user_input(&mut self, ev: RequestUserInputEvent) {
         self.queue.push_back(QueuedInterrupt::RequestUserInput(ev));
     }
@@ -91,6 +104,10 @@ impl InterruptManager {
                     chat.handle_apply_patch_approval_now(id, ev)
                 }
                 QueuedInterrupt::Elicitation(ev) => chat.handle_elicitation_request_now(ev),
+                QueuedInterrupt::PlanApproval(ev) => chat.handle_plan_approval_now(ev),
+                QueuedInterrupt::McpToolCallApproval(ev) => {
+                    chat.handle_mcp_tool_call_approval_now(ev)
+                }
                 QueuedInterrupt::RequestUserInput(ev) => chat.handle_request_user_input_now(ev),
                 QueuedInterrupt::ExecBegin(ev) => chat.handle_exec_begin_now(ev),
                 QueuedInterrupt::ExecEnd(ev) => chat.handle_exec_end_now(ev),
@@ -0,0 +1,110 @@
+use serde_json::Value;
+use serde_json::json;
+
+use super::ChatWidget;
+use codex_core::protocol::McpServerChange;
+use codex_core::protocol::McpToolsChangedEvent;
+use codex_core::protocol::Op;
+
+const MCP_USAGE: &str =
+    "Usage: /mcp add <name> <command> [args...] | /mcp add <name> <url> | /mcp remove <name>";
+
+impl ChatWidget {
+    /// `/mcp add <name> <command> [args...]` (or `<url>` for a streamable
+    /// HTTP server) and `/mcp remove <name>` change the running servers for
+    /// the rest of the session; config.toml is left alone.
+    pub(crate) fn mcp_command(&mut self, args: &str) {
+        match parse_mcp_args(args) {
+            Ok(McpArgs::Add { name, config }) => {
+                self.submit_op(Op::AddMcpServer { name, config });
+            }
+            Ok(McpArgs::Remove { name }) => {
+                self.submit_op(Op::RemoveMcpServer { name });
+            }
+            Err(message) => self.add_error_message(message),
+        }
+    }
+
+    pub(crate) fn on_mcp_tools_changed(&mut self, ev: McpToolsChangedEvent) {
+        let server = ev.server;
+        match ev.change {
+            McpServerChange::Added { tools } => {
+                let hint = if tools.is_empty() {
+                    "it exposes no tools".to_string()
+                } else {
+                    format!("tools: {}", tools.join(", "))
+                };
+                self.add_info_message(format!("Added MCP server `{server}`"), Some(hint));
+            }
+            McpServerChange::Removed => {
+                self.add_info_message(format!("Removed MCP server `{server}`"), None);
+            }
+            McpServerChange::Failed { error } => {
+                self.add_error_message(error);
+                return;
+            }
+        }
+        // Offer the server's prompts as slash commands, or drop them.
+        self.submit_op(Op::ListMcpPrompts);
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum McpArgs {
+    Add { name: String, config: Value },
+    Remove { name: String },
+}
+
+fn parse_mcp_args(args: &str) -> Result<McpArgs, String> {
+    let Some(words) = shlex::split(args) else {
+        return Err(format!("Unbalanced quotes. {MCP_USAGE}"));
+    };
+    match words.as_slice() {
+        [action, name, target, rest @ ..] if action == "add" => {
+            let config = if rest.is_empty()
+                && (target.starts_with("https://") || target.starts_with("http://"))
+            {
+                json!({ "url": target })
+            } else {
+                json!({ "command": target, "args": rest })
+            };
+            Ok(McpArgs::Add {
+                name: name.clone(),
+                config,
+            })
+        }
+        [action, name] if action == "remove" => Ok(McpArgs::Remove { name: name.clone() }),
+        _ => Err(MCP_USAGE.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_mcp_args_builds_server_config() {
+        assert_eq!(
+            parse_mcp_args("add docs npx -y \"@acme/docs mcp\""),
+            Ok(McpArgs::Add {
+                name: "docs".to_string(),
+                config: json!({ "command": "npx", "args": ["-y", "@acme/docs mcp"] }),
+            })
+        );
+        assert_eq!(
+            parse_mcp_args("add wiki https://mcp.example.com/mcp"),
+            Ok(McpArgs::Add {
+                name: "wiki".to_string(),
+                config: json!({ "url": "https://mcp.example.com/mcp" }),
+            })
+        );
+        assert_eq!(
+            parse_mcp_args("remove docs"),
+            Ok(McpArgs::Remove {
+                name: "docs".to_string()
+            })
+        );
+        assert_eq!(parse_mcp_args("add docs"), Err(MCP_USAGE.to_string()));
+    }
+}
@@ -0,0 +1,166 @@
+use std::path::PathBuf;
+
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use codex_core::protocol::ListPinnedContextResponseEvent;
+use codex_core::protocol::Op;
+use codex_core::protocol::PinnedContent;
+use codex_core::protocol::PinnedContextUpdatedEvent;
+
+/// Longest preview of a pinned message or note shown in the `/pin` list.
+const PREVIEW_CHARS: usize = 60;
+
+impl ChatWidget {
+    /// `/pin <note>`, `/pin file <path>`, and `/pin last` pin context; a bare
+    /// `/pin` lists the pinned items so they can be unpinned.
+    pub(crate) fn pin_command(&mut self, args: &str) {
+        let content = match parse_pin_args(args) {
+            PinArgs::List => {
+                self.submit_op(Op::ListPinnedContext);
+                return;
+            }
+            PinArgs::LastReply => match self.last_agent_message.clone() {
+                Some(text) => PinnedContent::Message { text },
+                None => {
+                    self.add_error_message(
+                        "Nothing to pin: Codex has not replied yet.".to_string(),
+                    );
+                    return;
+                }
+            },
+            PinArgs::Content(content) => content,
+        };
+        self.submit_op(Op::PinContext { content });
+    }
+
+    pub(crate) fn on_pinned_context_updated(&mut self, ev: PinnedContextUpdatedEvent) {
+        let count = ev.items.len();
+        let message = match count {
+            0 => "Nothing is pinned anymore".to_string(),
+            1 => "1 item pinned to the context".to_string(),
+            count => format!("{count} items pinned to the context"),
+        };
+        self.add_info_message(
+            message,
+            Some("pinned items are kept across compaction; /pin to list or unpin".to_string()),
+        );
+    }
+
+    pub(crate) fn on_list_pinned_context(&mut self, ev: ListPinnedContextResponseEvent) {
+        if ev.items.is_empty() {
+            self.add_info_message(
+                "Nothing is pinned".to_string(),
+                Some("pin with /pin <note>, /pin file <path>, or /pin last".to_string()),
+            );
+            return;
+        }
+
+        let items = ev
+            .items
+            .into_iter()
+            .map(|item| {
+                let id = item.id.clone();
+                let (name, description) = describe_pinned(&item.content);
+                SelectionItem {
+                    search_value: Some(name.clone()),
+                    name,
+                    description: Some(format!("{description} · enter to unpin")),
+                    actions: vec![Box::new(move |tx| {
+                        tx.send(AppEvent::CodexOp(Op::UnpinContext { id: id.clone() }));
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Pinned context".to_string()),
+            subtitle: Some("Sent with every request, even after compaction".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search pinned items".to_string()),
+            ..Default::default()
+        });
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum PinArgs {
+    List,
+    LastReply,
+    Content(PinnedContent),
+}
+
+fn parse_pin_args(args: &str) -> PinArgs {
+    let trimmed = args.trim();
+    if trimmed.is_empty() {
+        return PinArgs::List;
+    }
+    if trimmed == "last" {
+        return PinArgs::LastReply;
+    }
+    match trimmed.strip_prefix("file ") {
+        Some(path) if !path.trim().is_empty() => PinArgs::Content(PinnedContent::File {
+            path: PathBuf::from(path.trim()),
+        }),
+        _ => PinArgs::Content(PinnedContent::Note {
+            text: trimmed.to_string(),
+        }),
+    }
+}
+
+/// Popup row name and kind label for a pinned item.
+fn describe_pinned(content: &PinnedContent) -> (String, &'static str) {
+    match content {
+        PinnedContent::File { path } => (path.display().to_string(), "file"),
+        PinnedContent::Message { text } => (preview(text), "message"),
+        PinnedContent::Note { text } => (preview(text), "note"),
+    }
+}
+
+fn preview(text: &str) -> String {
+    let first_line = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+    let mut preview: String = first_line.trim().chars().take(PREVIEW_CHARS).collect();
+    if first_line.trim().chars().count() > PREVIEW_CHARS || text.trim().lines().count() > 1 {
+        preview.push('…');
+    }
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_pin_args_picks_the_kind() {
+        assert_eq!(parse_pin_args("  "), PinArgs::List);
+        assert_eq!(parse_pin_args("last"), PinArgs::LastReply);
+        assert_eq!(
+            parse_pin_args("file docs/SPEC.md "),
+            PinArgs::Content(PinnedContent::File {
+                path: PathBuf::from("docs/SPEC.md")
+            })
+        );
+        assert_eq!(
+            parse_pin_args("keep the public API stable"),
+            PinArgs::Content(PinnedContent::Note {
+                text: "keep the public API stable".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn preview_keeps_the_first_line() {
+        assert_eq!(preview("short note"), "short note");
+        assert_eq!(preview("\nfirst line\nsecond line"), "first line…");
+    }
+}
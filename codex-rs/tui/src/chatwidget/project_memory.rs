@@ -0,0 +1,114 @@
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::custom_prompt_view::CustomPromptView;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::text_formatting::truncate_text;
+use codex_core::protocol::ListMemoryResponseEvent;
+use codex_core::protocol::MemoryEntry;
+use codex_core::protocol::Op;
+
+/// Longest preview of an entry shown in the `/memory` list.
+const ENTRY_PREVIEW_GRAPHEMES: usize = 80;
+
+impl ChatWidget {
+    /// Lists the project memory entries; shown for `/memory` and again after
+    /// each edit so several stale entries can be cleaned up in a row.
+    pub(crate) fn on_list_memory(&mut self, ev: ListMemoryResponseEvent) {
+        let path = ev.path.display().to_string();
+        if ev.entries.is_empty() {
+            self.add_info_message(
+                "Project memory is empty".to_string(),
+                Some(format!(
+                    "with the project_memory feature enabled, Codex records facts in {path}"
+                )),
+            );
+            return;
+        }
+
+        let items = ev
+            .entries
+            .into_iter()
+            .map(|entry| {
+                let name = format!("{}. {}", entry.id, entry_preview(&entry.text));
+                SelectionItem {
+                    search_value: Some(entry.text.clone()),
+                    name,
+                    description: Some("enter to edit or delete".to_string()),
+                    actions: vec![Box::new(move |tx| {
+                        tx.send(AppEvent::OpenMemoryEntry(entry.clone()));
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Project memory".to_string()),
+            subtitle: Some(format!("Loaded into new sessions from {path}")),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search memory".to_string()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn show_memory_entry_actions(&mut self, entry: MemoryEntry) {
+        let edit_entry = entry.clone();
+        let delete_id = entry.id.clone();
+        let items = vec![
+            SelectionItem {
+                name: "Edit".to_string(),
+                description: Some("replace the text of this entry".to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::OpenMemoryEditPrompt(edit_entry.clone()));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Delete".to_string(),
+                description: Some("remove this entry from the memory file".to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::CodexOp(Op::DeleteMemory {
+                        id: delete_id.clone(),
+                    }));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!("Memory entry {}", entry.id)),
+            subtitle: Some(entry_preview(&entry.text)),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn show_memory_edit_prompt(&mut self, entry: MemoryEntry) {
+        let tx = self.app_event_tx.clone();
+        let id = entry.id;
+        let view = CustomPromptView::new(
+            format!("Edit memory entry {id}"),
+            "Type the new text and press Enter".to_string(),
+            Some(entry_preview(&entry.text)),
+            Box::new(move |text: String| {
+                tx.send(AppEvent::CodexOp(Op::UpdateMemory {
+                    id: id.clone(),
+                    text,
+                }));
+            }),
+        );
+        self.bottom_pane.show_view(Box::new(view));
+    }
+}
+
+fn entry_preview(text: &str) -> String {
+    truncate_text(text, ENTRY_PREVIEW_GRAPHEMES)
+}
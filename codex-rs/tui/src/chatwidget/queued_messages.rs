@@ -0,0 +1,123 @@
+use super::ChatWidget;
+use super::UserMessage;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::text_formatting::truncate_text;
+
+const QUEUED_MESSAGE_PREVIEW_GRAPHEMES: usize = 60;
+
+impl ChatWidget {
+    pub(crate) fn open_queued_messages_popup(&mut self) {
+        if self.queued_user_messages.is_empty() {
+            self.add_info_message(
+                "No queued messages.".to_string(),
+                Some("Messages sent while a turn is running run as the next turns.".to_string()),
+            );
+            return;
+        }
+
+        let count = self.queued_user_messages.len();
+        let mut items = vec![SelectionItem {
+            name: "Clear queue".to_string(),
+            description: Some(format!("drop {count} queued message(s) without sending")),
+            actions: vec![Box::new(|tx| {
+                tx.send(AppEvent::ClearQueuedUserMessages);
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        }];
+        items.extend(
+            self.queued_user_messages
+                .iter()
+                .enumerate()
+                .map(|(index, message)| {
+                    let text = message.text.clone();
+                    SelectionItem {
+                        name: format!("{}. {}", index + 1, queued_message_label(message)),
+                        description: Some("enter to move back to the composer".to_string()),
+                        actions: vec![Box::new(move |tx| {
+                            tx.send(AppEvent::EditQueuedUserMessage(text.clone()));
+                        })],
+                        dismiss_on_select: true,
+                        search_value: Some(message.text.clone()),
+                        ..Default::default()
+                    }
+                }),
+        );
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Queued messages".to_string()),
+            subtitle: Some("Sent in order, each as its own turn".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search queued messages".to_string()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn clear_queued_user_messages(&mut self) {
+        self.queued_user_messages.clear();
+        self.refresh_queued_user_messages();
+        self.request_redraw();
+    }
+
+    /// Moves the queued message with `text` back into the composer. Looked up
+    /// by text because the queue may have advanced since the popup opened.
+    pub(crate) fn edit_queued_user_message(&mut self, text: &str) {
+        let Some(index) = self
+            .queued_user_messages
+            .iter()
+            .position(|message| message.text == text)
+        else {
+            return;
+        };
+        self.restore_queued_user_message(index);
+    }
+
+    pub(super) fn restore_queued_user_message(&mut self, index: usize) {
+        let Some(user_message) = self.queued_user_messages.remove(index) else {
+            return;
+        };
+        let local_image_paths = user_message
+            .local_images
+            .iter()
+            .map(|img| img.path.clone())
+            .collect();
+        self.bottom_pane.set_composer_text(
+            user_message.text,
+            user_message.text_elements,
+            local_image_paths,
+        );
+        self.refresh_queued_user_messages();
+        self.request_redraw();
+    }
+}
+
+fn queued_message_label(message: &UserMessage) -> String {
+    let text = message
+        .text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let label = truncate_text(&text, QUEUED_MESSAGE_PREVIEW_GRAPHEMES);
+    match message.local_images.len() {
+        0 => label,
+        1 => format!("{label} (+1 image)"),
+        images => format!("{label} (+{images} images)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn queued_message_label_collapses_whitespace() {
+        let message = UserMessage::from("fix the\n\n  flaky   test".to_string());
+        assert_eq!(queued_message_label(&message), "fix the flaky test");
+    }
+}
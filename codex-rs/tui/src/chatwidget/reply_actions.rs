@@ -0,0 +1,387 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::ChatWidget;
+use super::clipboard::fence_of;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::text_formatting::truncate_text;
+use codex_core::protocol::Op;
+
+/// Longest preview of a command or passage shown in the popups.
+const PREVIEW_GRAPHEMES: usize = 60;
+
+/// At most this many mentioned files are opened at once.
+const MAX_OPENED_FILES: usize = 10;
+
+/// Info strings of fenced blocks whose content is a shell command.
+const SHELL_LANGUAGES: &[&str] = &["sh", "bash", "zsh", "shell", "console", "terminal"];
+
+impl ChatWidget {
+    /// Ctrl+O: actions on the latest reply (copy, run, open, quote).
+    pub(crate) fn open_reply_actions(&mut self) {
+        let Some(message) = self.last_agent_message.clone() else {
+            self.add_info_message("Codex has not replied yet.".to_string(), None);
+            return;
+        };
+        let command = last_shell_command(&message);
+        let files = mentioned_files(&message, &self.config.cwd);
+
+        let mut items = vec![SelectionItem {
+            name: "Copy last code block".to_string(),
+            description: Some("the whole reply when it has no code block".to_string()),
+            actions: vec![Box::new(|tx| {
+                tx.send(AppEvent::CopyLastReplyCode);
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        }];
+        items.push(match command {
+            Some(command) => SelectionItem {
+                name: "Run suggested command".to_string(),
+                description: Some(truncate_text(&command, PREVIEW_GRAPHEMES)),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::ConfirmSuggestedCommand(command.clone()));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            None => SelectionItem {
+                name: "Run suggested command".to_string(),
+                disabled_reason: Some("no shell code block in the reply".to_string()),
+                ..Default::default()
+            },
+        });
+        items.push(if files.is_empty() {
+            SelectionItem {
+                name: "Open mentioned files".to_string(),
+                disabled_reason: Some("the reply names no existing file".to_string()),
+                ..Default::default()
+            }
+        } else {
+            let listed = files
+                .iter()
+                .map(|path| display_path(path, &self.config.cwd))
+                .collect::<Vec<_>>()
+                .join(", ");
+            SelectionItem {
+                name: format!("Open mentioned files ({})", files.len()),
+                description: Some(truncate_text(&listed, PREVIEW_GRAPHEMES)),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::OpenFilesInEditor(files.clone()));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            }
+        });
+        items.push(SelectionItem {
+            name: "Quote in a follow-up…".to_string(),
+            description: Some("pick a passage to quote in the composer".to_string()),
+            actions: vec![Box::new(|tx| {
+                tx.send(AppEvent::OpenReplyQuotePicker);
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Reply actions".to_string()),
+            subtitle: Some("Act on Codex's latest reply".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// Asks before running a command taken from the reply; it runs like a
+    /// `!` command typed by the user.
+    pub(crate) fn confirm_suggested_command(&mut self, command: String) {
+        let run_command = command.clone();
+        let edit_command = command.clone();
+        let items = vec![
+            SelectionItem {
+                name: "Run it".to_string(),
+                description: Some("run in the session's shell and sandbox".to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::CodexOp(Op::RunUserShellCommand {
+                        command: run_command.clone(),
+                    }));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Edit before running".to_string(),
+                description: Some("put it in the composer as a ! command".to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::InsertInComposer(format!("!{edit_command}")));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Cancel".to_string(),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Run this command?".to_string()),
+            subtitle: Some(truncate_text(&command, PREVIEW_GRAPHEMES)),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// Lists the passages of the latest reply; the chosen one is inserted in
+    /// the composer as a Markdown quote followed by an empty line.
+    pub(crate) fn open_reply_quote_picker(&mut self) {
+        let Some(message) = self.last_agent_message.as_deref() else {
+            return;
+        };
+        let items = reply_passages(message)
+            .into_iter()
+            .map(|passage| {
+                let preview = passage.lines().next().unwrap_or_default().to_string();
+                let quote = quote_passage(&passage);
+                SelectionItem {
+                    name: truncate_text(&preview, PREVIEW_GRAPHEMES),
+                    description: (passage.lines().count() > 1)
+                        .then(|| format!("{} lines", passage.lines().count())),
+                    search_value: Some(passage),
+                    actions: vec![Box::new(move |tx| {
+                        tx.send(AppEvent::InsertInComposer(quote.clone()));
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Quote a passage".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to filter passages".to_string()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn insert_in_composer(&mut self, text: &str) {
+        self.bottom_pane.insert_str(text);
+    }
+}
+
+/// The command in the last fenced block tagged as shell. In `console`-style
+/// blocks only the `$ `-prefixed lines are kept, without the prompt.
+fn last_shell_command(markdown: &str) -> Option<String> {
+    let mut last = None;
+    let mut open: Option<((char, usize), bool, Vec<&str>)> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        match open.as_mut() {
+            None => {
+                if let Some(fence) = fence_of(trimmed) {
+                    let info = trimmed.trim_start_matches(fence.0).trim();
+                    let language = info.split_whitespace().next().unwrap_or_default();
+                    let is_shell =
+                        SHELL_LANGUAGES.contains(&language.to_ascii_lowercase().as_str());
+                    open = Some((fence, is_shell, Vec::new()));
+                }
+            }
+            Some(((fence_char, fence_len), is_shell, body)) => {
+                if closes_fence(trimmed, (*fence_char, *fence_len)) {
+                    if *is_shell && let Some(command) = shell_command(body) {
+                        last = Some(command);
+                    }
+                    open = None;
+                } else {
+                    body.push(line);
+                }
+            }
+        }
+    }
+    last
+}
+
+/// Whether `line` (without indentation) closes a block opened by `fence`.
+fn closes_fence(line: &str, (fence_char, fence_len): (char, usize)) -> bool {
+    fence_of(line).is_some_and(|(c, len)| {
+        c == fence_char && len >= fence_len && line.trim_end().chars().all(|ch| ch == c)
+    })
+}
+
+fn shell_command(lines: &[&str]) -> Option<String> {
+    let prompted: Vec<&str> = lines
+        .iter()
+        .filter_map(|line| line.trim_start().strip_prefix("$ "))
+        .collect();
+    let command = if prompted.is_empty() {
+        lines.join("\n")
+    } else {
+        prompted.join("\n")
+    };
+    let command = command.trim();
+    (!command.is_empty()).then(|| command.to_string())
+}
+
+/// Existing files named in inline code spans or link targets, in order of
+/// first mention. `path:line[:col]` and `#L…` suffixes are dropped.
+fn mentioned_files(markdown: &str, cwd: &Path) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if let Some((fence_char, fence_len)) = fence {
+            if closes_fence(trimmed, (fence_char, fence_len)) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(opened) = fence_of(trimmed) {
+            fence = Some(opened);
+            continue;
+        }
+        candidates.extend(line.split('`').skip(1).step_by(2).map(str::trim));
+        let mut rest = line;
+        while let Some(start) = rest.find("](") {
+            rest = &rest[start + 2..];
+            let Some(end) = rest.find(')') else {
+                break;
+            };
+            candidates.push(rest[..end].trim());
+            rest = &rest[end..];
+        }
+    }
+
+    let mut files: Vec<PathBuf> = Vec::new();
+    for candidate in candidates {
+        let candidate = candidate.strip_prefix("file://").unwrap_or(candidate);
+        let candidate = candidate.split('#').next().unwrap_or_default();
+        let candidate = strip_line_suffix(candidate);
+        if candidate.is_empty() || candidate.contains(char::is_whitespace) {
+            continue;
+        }
+        let path = cwd.join(candidate);
+        if path.is_file() && !files.contains(&path) {
+            files.push(path);
+            if files.len() == MAX_OPENED_FILES {
+                break;
+            }
+        }
+    }
+    files
+}
+
+/// `src/lib.rs:12:5` -> `src/lib.rs`.
+fn strip_line_suffix(candidate: &str) -> &str {
+    let mut path = candidate;
+    for _ in 0..2 {
+        match path.rsplit_once(':') {
+            Some((head, tail)) if !tail.is_empty() && tail.chars().all(|c| c.is_ascii_digit()) => {
+                path = head;
+            }
+            _ => break,
+        }
+    }
+    path
+}
+
+fn display_path(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+/// Paragraphs, list blocks, and fenced code blocks of `markdown`, split at
+/// blank lines outside code blocks.
+fn reply_passages(markdown: &str) -> Vec<String> {
+    let mut passages = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        match fence {
+            None if trimmed.is_empty() => {
+                if !current.is_empty() {
+                    passages.push(current.join("\n"));
+                    current.clear();
+                }
+                continue;
+            }
+            None => fence = fence_of(trimmed),
+            Some((fence_char, fence_len)) => {
+                if closes_fence(trimmed, (fence_char, fence_len)) {
+                    fence = None;
+                }
+            }
+        }
+        current.push(line);
+    }
+    if !current.is_empty() {
+        passages.push(current.join("\n"));
+    }
+    passages
+}
+
+fn quote_passage(passage: &str) -> String {
+    let quoted = passage
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                ">".to_string()
+            } else {
+                format!("> {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("{quoted}\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn last_shell_command_skips_other_languages_and_console_output() {
+        let reply = "Run:\n```bash\ncargo test\n```\nor\n```console\n$ npm ci\nadded 12 packages\n$ npm test\n```\n```rust\nfn main() {}\n```";
+        assert_eq!(
+            last_shell_command(reply),
+            Some("npm ci\nnpm test".to_string())
+        );
+        assert_eq!(last_shell_command("```rust\nfn main() {}\n```"), None);
+    }
+
+    #[test]
+    fn mentioned_files_resolve_code_spans_and_links() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join("src")).expect("mkdir");
+        std::fs::write(dir.path().join("src/lib.rs"), "").expect("write");
+        std::fs::write(dir.path().join("README.md"), "").expect("write");
+        let reply = "See `src/lib.rs:12:5`, [the readme](README.md#L3), `missing.rs` and `src/lib.rs` again.";
+
+        assert_eq!(
+            mentioned_files(reply, dir.path()),
+            vec![dir.path().join("src/lib.rs"), dir.path().join("README.md")]
+        );
+    }
+
+    #[test]
+    fn passages_keep_code_blocks_whole() {
+        let reply = "Intro line.\n\n```sh\necho a\n\necho b\n```\n\n- one\n- two";
+        assert_eq!(
+            reply_passages(reply),
+            vec![
+                "Intro line.".to_string(),
+                "```sh\necho a\n\necho b\n```".to_string(),
+                "- one\n- two".to_string(),
+            ]
+        );
+        assert_eq!(quote_passage("a\n\nb"), "> a\n>\n> b\n\n");
+    }
+}
@@ -0,0 +1,189 @@
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::collab;
+use codex_core::protocol::Op;
+use codex_core::protocol::SubAgentMergeAppliedEvent;
+use codex_core::protocol::SubAgentMergePlanEvent;
+use codex_protocol::subagents::SubAgentMergeCandidate;
+use codex_protocol::subagents::SubAgentMergeResolution;
+
+/// Merge plan from `/merge` whose conflicts are being resolved one by one.
+#[derive(Debug)]
+pub(crate) struct PendingSubAgentMerge {
+    plan: SubAgentMergePlanEvent,
+    resolutions: Vec<SubAgentMergeResolution>,
+}
+
+impl ChatWidget {
+    pub(crate) fn on_subagent_merge_plan(&mut self, ev: SubAgentMergePlanEvent) {
+        self.on_collab_event(collab::subagent_merge_plan(&ev));
+        self.pending_subagent_merge = Some(PendingSubAgentMerge {
+            plan: ev,
+            resolutions: Vec::new(),
+        });
+        self.show_next_subagent_merge_step();
+    }
+
+    /// Records the choice for the conflict currently shown and moves on to
+    /// the next one, or to the final confirmation.
+    pub(crate) fn resolve_subagent_merge_conflict(&mut self, run_id: Option<String>) {
+        let Some(pending) = self.pending_subagent_merge.as_mut() else {
+            return;
+        };
+        let Some(conflict) = pending.plan.conflicts.get(pending.resolutions.len()) else {
+            return;
+        };
+        pending.resolutions.push(SubAgentMergeResolution {
+            path: conflict.path.clone(),
+            run_id,
+        });
+        self.show_next_subagent_merge_step();
+    }
+
+    pub(crate) fn on_subagent_merge_applied(&mut self, ev: SubAgentMergeAppliedEvent) {
+        self.on_collab_event(collab::subagent_merge_applied(ev));
+    }
+
+    fn show_next_subagent_merge_step(&mut self) {
+        let Some(pending) = self.pending_subagent_merge.as_ref() else {
+            return;
+        };
+        let total = pending.plan.conflicts.len();
+        let index = pending.resolutions.len();
+        let Some(conflict) = pending.plan.conflicts.get(index) else {
+            if let Some(pending) = self.pending_subagent_merge.take() {
+                self.show_subagent_merge_confirmation(pending);
+            }
+            return;
+        };
+
+        let mut items: Vec<SelectionItem> = conflict
+            .candidates
+            .iter()
+            .map(|candidate| {
+                let run_id = candidate.run_id.clone();
+                SelectionItem {
+                    name: format!("Take @{}'s changes", candidate.name),
+                    description: Some(candidate_description(candidate)),
+                    actions: vec![Box::new(move |tx| {
+                        tx.send(AppEvent::ResolveSubAgentMergeConflict {
+                            run_id: Some(run_id.clone()),
+                        });
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        items.push(SelectionItem {
+            name: "Keep the current file".to_string(),
+            description: Some("drop every run's edits to this file".to_string()),
+            actions: vec![Box::new(|tx| {
+                tx.send(AppEvent::ResolveSubAgentMergeConflict { run_id: None });
+            })],
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!(
+                "Conflict {}/{total}: {}",
+                index + 1,
+                conflict.path.display()
+            )),
+            subtitle: Some("These runs edited the same lines; pick one version".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    fn show_subagent_merge_confirmation(&mut self, pending: PendingSubAgentMerge) {
+        let PendingSubAgentMerge { plan, resolutions } = pending;
+        let files = plan.merged_files.len()
+            + resolutions
+                .iter()
+                .filter(|resolution| resolution.run_id.is_some())
+                .count();
+        if files == 0 {
+            self.add_info_message(
+                "Nothing left to apply from the subagent runs.".to_string(),
+                None,
+            );
+            return;
+        }
+        let runs = plan
+            .runs
+            .iter()
+            .map(|run| format!("@{}", run.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let merge_id = plan.merge_id;
+        let noun = if files == 1 { "file" } else { "files" };
+        let items = vec![
+            SelectionItem {
+                name: format!("Apply {files} {noun}"),
+                description: Some("write the merged changes to the working tree".to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::CodexOp(Op::ApplySubAgentMerge {
+                        merge_id: merge_id.clone(),
+                        resolutions: resolutions.clone(),
+                    }));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Not now".to_string(),
+                description: Some("keep the changes staged; run /merge again later".to_string()),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Apply subagent changes".to_string()),
+            subtitle: Some(format!("From {runs}")),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+}
+
+fn candidate_description(candidate: &SubAgentMergeCandidate) -> String {
+    let (added, removed) = candidate
+        .diff
+        .lines()
+        .fold((0, 0), |(added, removed), line| {
+            if line.starts_with("+++") || line.starts_with("---") {
+                (added, removed)
+            } else if line.starts_with('+') {
+                (added + 1, removed)
+            } else if line.starts_with('-') {
+                (added, removed + 1)
+            } else {
+                (added, removed)
+            }
+        });
+    format!("+{added} -{removed}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn candidate_description_counts_changed_lines() {
+        let candidate = SubAgentMergeCandidate {
+            run_id: "run-1".to_string(),
+            name: "fixer".to_string(),
+            diff: "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,3 @@\n ctx\n-old\n+new\n+more\n"
+                .to_string(),
+        };
+        assert_eq!(candidate_description(&candidate), "+2 -1");
+    }
+}
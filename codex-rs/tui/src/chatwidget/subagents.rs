@@ -0,0 +1,204 @@
+use std::time::Instant;
+
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::custom_prompt_view::CustomPromptView;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::collab;
+use crate::status_indicator_widget::fmt_elapsed_compact;
+use codex_core::protocol::ListSubAgentsResponseEvent;
+use codex_core::protocol::Op;
+use codex_core::protocol::SubAgentRunBeginEvent;
+use codex_core::protocol::SubAgentRunEndEvent;
+use codex_core::protocol::SubAgentsUpdatedEvent;
+use codex_protocol::subagents::SubAgentInfo;
+use codex_protocol::subagents::SubAgentPreview;
+use codex_protocol::subagents::SubAgentSource;
+
+/// A subagent run started from this session that has not finished yet.
+#[derive(Debug, Clone)]
+pub(crate) struct RunningSubAgent {
+    pub(crate) run_id: String,
+    pub(crate) name: String,
+    pub(crate) started_at: Instant,
+}
+
+impl ChatWidget {
+    pub(crate) fn on_list_subagents(&mut self, ev: ListSubAgentsResponseEvent) {
+        for error in &ev.errors {
+            self.add_error_message(format!(
+                "Failed to load subagent {}: {}",
+                error.path.display(),
+                error.message
+            ));
+        }
+        self.open_subagents_popup(ev.subagents);
+    }
+
+    /// Definitions were edited on disk; report the reloaded set so `/agents`
+    /// and `@name` reflect the change without restarting.
+    pub(crate) fn on_subagents_updated(&mut self, ev: SubAgentsUpdatedEvent) {
+        for error in &ev.errors {
+            self.add_error_message(format!(
+                "Failed to load subagent {}: {}",
+                error.path.display(),
+                error.message
+            ));
+        }
+        let names = ev
+            .subagents
+            .iter()
+            .map(|subagent| format!("@{}", subagent.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let hint = if names.is_empty() {
+            "No subagents defined.".to_string()
+        } else {
+            names
+        };
+        self.add_info_message("Subagent definitions reloaded".to_string(), Some(hint));
+    }
+
+    fn open_subagents_popup(&mut self, subagents: Vec<SubAgentInfo>) {
+        if subagents.is_empty() && self.running_subagents.is_empty() {
+            self.add_info_message(
+                "No subagents found.".to_string(),
+                Some(
+                    "Add <git root>/.codex/agents/<name>.md or $CODEX_HOME/agents/<name>.md."
+                        .to_string(),
+                ),
+            );
+            return;
+        }
+
+        let mut items: Vec<SelectionItem> = self
+            .running_subagents
+            .iter()
+            .map(|run| {
+                let run_id = run.run_id.clone();
+                let elapsed = fmt_elapsed_compact(run.started_at.elapsed().as_secs());
+                SelectionItem {
+                    name: format!("● @{}", run.name),
+                    description: Some(format!("running {elapsed} · enter to cancel")),
+                    actions: vec![Box::new(move |tx| {
+                        tx.send(AppEvent::CodexOp(Op::CancelSubAgent {
+                            run_id: run_id.clone(),
+                        }));
+                    })],
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        items.extend(subagents.into_iter().map(|subagent| {
+            let name = subagent.name.clone();
+            SelectionItem {
+                name: format!("@{}", subagent.name),
+                description: Some(subagent_description(&subagent)),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::OpenSubAgentPrompt(name.clone()));
+                })],
+                dismiss_on_select: false,
+                search_value: Some(subagent.name),
+                ..Default::default()
+            }
+        }));
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Subagents".to_string()),
+            subtitle: Some("Run a subagent, or cancel a running one".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search subagents".to_string()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn show_subagent_prompt(&mut self, name: String) {
+        let tx = self.app_event_tx.clone();
+        let view = CustomPromptView::new(
+            format!("Run @{name}"),
+            "Type a prompt for the subagent and press Enter".to_string(),
+            None,
+            Box::new(move |prompt: String| {
+                let prompt = prompt.trim().to_string();
+                if prompt.is_empty() {
+                    return;
+                }
+                tx.send(AppEvent::CodexOp(Op::RunSubAgent {
+                    name: name.clone(),
+                    prompt,
+                    dry_run: false,
+                }));
+            }),
+        );
+        self.bottom_pane.show_view(Box::new(view));
+    }
+
+    pub(crate) fn on_subagent_run_begin(&mut self, ev: SubAgentRunBeginEvent) {
+        self.running_subagents.push(RunningSubAgent {
+            run_id: ev.run_id.clone(),
+            name: ev.name.clone(),
+            started_at: Instant::now(),
+        });
+        self.on_collab_event(collab::subagent_run_begin(ev));
+    }
+
+    pub(crate) fn on_subagent_run_end(&mut self, ev: SubAgentRunEndEvent) {
+        self.running_subagents.retain(|run| run.run_id != ev.run_id);
+        self.on_collab_event(collab::subagent_run_end(ev));
+    }
+
+    pub(crate) fn on_subagent_preview(&mut self, preview: SubAgentPreview) {
+        self.on_collab_event(collab::subagent_preview(preview));
+    }
+}
+
+fn subagent_description(subagent: &SubAgentInfo) -> String {
+    let mode = subagent
+        .sandbox_mode
+        .map(|mode| mode.to_string())
+        .unwrap_or_else(|| "inherit".to_string());
+    let source = match subagent.source {
+        SubAgentSource::Repo => "repo",
+        SubAgentSource::User => "user",
+        SubAgentSource::Extra => "extra",
+    };
+    format!("[{mode}] {} · {source}", subagent.description)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::config_types::SandboxMode;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn subagent_description_includes_mode_badge_and_source() {
+        let mut subagent = SubAgentInfo {
+            name: "reviewer".to_string(),
+            description: "Reviews diffs".to_string(),
+            color: None,
+            sandbox_mode: Some(SandboxMode::ReadOnly),
+            model: None,
+            path: PathBuf::from("/repo/.codex/agents/reviewer.md"),
+            source: SubAgentSource::Repo,
+        };
+        assert_eq!(
+            subagent_description(&subagent),
+            "[read-only] Reviews diffs · repo"
+        );
+
+        subagent.sandbox_mode = None;
+        subagent.source = SubAgentSource::User;
+        assert_eq!(
+            subagent_description(&subagent),
+            "[inherit] Reviews diffs · user"
+        );
+    }
+}
@@ -20,6 +20,7 @@ use codex_core::config::Config;
 use codex_core::config::ConfigBuilder;
 use codex_core::config::Constrained;
 use codex_core::config::ConstraintError;
+use codex_core::config::types::StatusLineItem;
 use codex_core::config_loader::RequirementSource;
 use codex_core::features::Feature;
 use codex_core::models_manager::manager::ModelsManager;
@@ -27,8 +28,12 @@ use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningEvent;
+use codex_core::protocol::AgentStatus;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::BackgroundTaskBeginEvent;
+use codex_core::protocol::BackgroundTaskEndEvent;
+use codex_core::protocol::CostUpdateEvent;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -39,24 +44,29 @@ use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExecPolicyAmendment;
 use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::FollowUpSuggestionsEvent;
 use codex_core::protocol::McpStartupCompleteEvent;
 use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::PlanModeUpdatedEvent;
 use codex_core::protocol::RateLimitWindow;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentMergePlanEvent;
 use codex_core::protocol::TerminalInteractionEvent;
 use codex_core::protocol::TokenCountEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
+use codex_core::protocol::ToolTokenUsage;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnStartedEvent;
 use codex_core::protocol::UndoCompletedEvent;
+use codex_core::protocol::UndoKind;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
@@ -71,6 +81,10 @@ use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
 use codex_protocol::protocol::CodexErrorInfo;
+use codex_protocol::subagents::SubAgentMergeCandidate;
+use codex_protocol::subagents::SubAgentMergeConflict;
+use codex_protocol::subagents::SubAgentMergeResolution;
+use codex_protocol::subagents::SubAgentMergeRun;
 use codex_protocol::user_input::TextElement;
 use codex_protocol::user_input::UserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
@@ -595,6 +609,8 @@ async fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_review_tokens, context_window)),
             rate_limits: None,
+            tool_usage: Vec::new(),
+            subagent_usage: Vec::new(),
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -614,6 +630,8 @@ async fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(review_tokens, context_window)),
             rate_limits: None,
+            tool_usage: Vec::new(),
+            subagent_usage: Vec::new(),
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(97));
@@ -643,6 +661,8 @@ async fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_compact_tokens, context_window)),
             rate_limits: None,
+            tool_usage: Vec::new(),
+            subagent_usage: Vec::new(),
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -652,6 +672,8 @@ async fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: None,
             rate_limits: None,
+            tool_usage: Vec::new(),
+            subagent_usage: Vec::new(),
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), None);
@@ -682,6 +704,8 @@ async fn context_indicator_shows_used_tokens_when_window_unknown() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(token_info),
             rate_limits: None,
+            tool_usage: Vec::new(),
+            subagent_usage: Vec::new(),
         }),
     });
 
@@ -820,7 +844,14 @@ async fn make_chatwidget_manual(
         unified_exec_wait_streak: None,
         task_complete_pending: false,
         unified_exec_processes: Vec::new(),
+        reported_terminal_size: None,
+        running_subagents: Vec::new(),
+        running_background_tasks: HashSet::new(),
+        session_usd: None,
+        pending_subagent_merge: None,
+        last_agent_message: None,
         agent_turn_running: false,
+        turn_started_at: None,
         mcp_startup_status: None,
         interrupts: InterruptManager::new(),
         reasoning_buffer: String::new(),
@@ -832,6 +863,9 @@ async fn make_chatwidget_manual(
         frame_requester: FrameRequester::test_dummy(),
         show_welcome_banner: true,
         queued_user_messages: VecDeque::new(),
+        follow_up_suggestions: Vec::new(),
+        tool_token_usage: Vec::new(),
+        subagent_token_usage: Vec::new(),
         suppress_session_configured_redraw: false,
         pending_notification: None,
         quit_shortcut_expires_at: None,
@@ -840,6 +874,9 @@ async fn make_chatwidget_manual(
         pre_review_token_info: None,
         needs_final_message_separator: false,
         had_work_activity: false,
+        plan_mode: false,
+        last_applied_patch: None,
+        hunk_review_calls: HashSet::new(),
         last_separator_elapsed_secs: None,
         last_rendered_width: std::cell::Cell::new(None),
         feedback: codex_feedback::CodexFeedback::new(),
@@ -1187,6 +1224,11 @@ async fn exec_approval_emits_proposed_command_and_decision_history() {
         ),
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        explanation: None,
+        sandbox_policy: None,
+        risk: None,
+        matched_rule: None,
+        sandbox: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-short".into(),
@@ -1231,6 +1273,11 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         ),
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        explanation: None,
+        sandbox_policy: None,
+        risk: None,
+        matched_rule: None,
+        sandbox: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-multi".into(),
@@ -1281,6 +1328,11 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         reason: None,
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        explanation: None,
+        sandbox_policy: None,
+        risk: None,
+        matched_rule: None,
+        sandbox: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-long".into(),
@@ -1811,6 +1863,7 @@ async fn unified_exec_wait_after_final_agent_message_snapshot() {
         id: "turn-1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: Some("Final response.".into()),
+            metadata: None,
         }),
     });
 
@@ -1850,6 +1903,7 @@ async fn unified_exec_wait_before_streamed_agent_message_snapshot() {
         id: "turn-1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            metadata: None,
         }),
     });
 
@@ -1900,6 +1954,7 @@ async fn unified_exec_waiting_multiple_empty_snapshots() {
         id: "turn-wait-1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            metadata: None,
         }),
     });
 
@@ -1951,6 +2006,7 @@ async fn unified_exec_non_empty_then_empty_snapshots() {
         id: "turn-wait-3".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            metadata: None,
         }),
     });
 
@@ -2156,6 +2212,21 @@ async fn slash_exit_requests_exit() {
     assert_matches!(rx.try_recv(), Ok(AppEvent::Exit(ExitMode::ShutdownFirst)));
 }
 
+#[tokio::test]
+async fn slash_plan_toggles_plan_mode() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Plan);
+    assert_matches!(op_rx.try_recv(), Ok(Op::SetPlanMode { enabled: true }));
+
+    chat.handle_codex_event(Event {
+        id: "plan-mode".into(),
+        msg: EventMsg::PlanModeUpdated(PlanModeUpdatedEvent { enabled: true }),
+    });
+    chat.dispatch_command(SlashCommand::Plan);
+    assert_matches!(op_rx.try_recv(), Ok(Op::SetPlanMode { enabled: false }));
+}
+
 #[tokio::test]
 async fn slash_resume_opens_picker() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -2171,7 +2242,170 @@ async fn slash_fork_requests_current_fork() {
 
     chat.dispatch_command(SlashCommand::Fork);
 
-    assert_matches!(rx.try_recv(), Ok(AppEvent::ForkCurrentSession));
+    assert_matches!(
+        rx.try_recv(),
+        Ok(AppEvent::ForkCurrentSession { num_turns: None })
+    );
+}
+
+#[tokio::test]
+async fn slash_fork_with_turn_count_keeps_that_many_turns() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(SlashCommand::Fork, " 2 ".to_string());
+
+    assert_matches!(
+        rx.try_recv(),
+        Ok(AppEvent::ForkCurrentSession { num_turns: Some(2) })
+    );
+}
+
+#[tokio::test]
+async fn slash_compact_with_turn_range_compacts_only_that_range() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(SlashCommand::Compact, "2-4".to_string());
+
+    assert_matches!(
+        rx.try_recv(),
+        Ok(AppEvent::CodexOp(Op::CompactRange {
+            from_turn: 2,
+            to_turn: 4
+        }))
+    );
+}
+
+#[tokio::test]
+async fn slash_undo_with_a_path_restores_only_that_file() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(SlashCommand::Undo, " src/lib.rs ".to_string());
+
+    assert_matches!(
+        rx.try_recv(),
+        Ok(AppEvent::CodexOp(Op::RestoreFile { turn_id: None, path }))
+            if path == PathBuf::from("src/lib.rs")
+    );
+}
+
+#[tokio::test]
+async fn slash_tab_arguments_open_and_switch_tabs() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(SlashCommand::Tab, "new ../web".to_string());
+    assert_matches!(
+        rx.try_recv(),
+        Ok(AppEvent::Tab(TabCommand::Open(Some(path)))) if path == PathBuf::from("../web")
+    );
+
+    chat.dispatch_command_with_args(SlashCommand::Tab, "2".to_string());
+    assert_matches!(rx.try_recv(), Ok(AppEvent::Tab(TabCommand::Select(2))));
+
+    chat.dispatch_command_with_args(SlashCommand::Tab, "rename".to_string());
+    let cells = drain_insert_history(&mut rx);
+    let message = lines_to_single_string(cells.last().expect("usage error"));
+    assert!(message.contains(TabCommand::USAGE), "message: {message}");
+}
+
+#[tokio::test]
+async fn slash_copy_without_a_reply_reports_nothing_to_copy() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Copy);
+
+    let cells = drain_insert_history(&mut rx);
+    let message = lines_to_single_string(cells.last().expect("copy error"));
+    assert!(
+        message.contains("Nothing to copy"),
+        "unexpected message: {message}"
+    );
+}
+
+#[tokio::test]
+async fn slash_save_and_copy_select_are_handled_by_the_app() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(SlashCommand::Save, "notes/session.md".to_string());
+    assert_matches!(
+        rx.try_recv(),
+        Ok(AppEvent::SaveTranscript(path)) if path == PathBuf::from("notes/session.md")
+    );
+
+    chat.dispatch_command_with_args(SlashCommand::Copy, "select".to_string());
+    assert_matches!(rx.try_recv(), Ok(AppEvent::OpenTranscriptSelection));
+
+    chat.dispatch_command(SlashCommand::Save);
+    let cells = drain_insert_history(&mut rx);
+    let message = lines_to_single_string(cells.last().expect("usage error"));
+    assert!(message.contains("Usage: /save PATH"), "message: {message}");
+}
+
+#[tokio::test]
+async fn slash_merge_resolves_conflicts_before_applying() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Merge);
+    assert_matches!(
+        op_rx.try_recv(),
+        Ok(Op::MergeSubAgentChanges { run_ids }) if run_ids.is_empty()
+    );
+
+    let candidate = |run_id: &str, name: &str| SubAgentMergeCandidate {
+        run_id: run_id.to_string(),
+        name: name.to_string(),
+        diff: "@@ -1 +1 @@\n-old\n+new\n".to_string(),
+    };
+    chat.handle_codex_event(Event {
+        id: "merge".into(),
+        msg: EventMsg::SubAgentMergePlan(SubAgentMergePlanEvent {
+            merge_id: "1".to_string(),
+            runs: vec![
+                SubAgentMergeRun {
+                    run_id: "run-a".to_string(),
+                    name: "fixer".to_string(),
+                },
+                SubAgentMergeRun {
+                    run_id: "run-b".to_string(),
+                    name: "tester".to_string(),
+                },
+            ],
+            merged_files: vec![PathBuf::from("README.md")],
+            conflicts: vec![SubAgentMergeConflict {
+                path: PathBuf::from("src/lib.rs"),
+                candidates: vec![candidate("run-a", "fixer"), candidate("run-b", "tester")],
+            }],
+        }),
+    });
+    let _ = drain_insert_history(&mut rx);
+
+    // Nothing is applied until the conflict is settled: take the second run.
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let run_id = match rx.try_recv() {
+        Ok(AppEvent::ResolveSubAgentMergeConflict { run_id }) => run_id,
+        other => panic!("expected ResolveSubAgentMergeConflict, got {other:?}"),
+    };
+    assert_eq!(run_id.as_deref(), Some("run-b"));
+    assert_matches!(op_rx.try_recv(), Err(TryRecvError::Empty));
+
+    chat.resolve_subagent_merge_conflict(run_id);
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match rx.try_recv() {
+        Ok(AppEvent::CodexOp(Op::ApplySubAgentMerge {
+            merge_id,
+            resolutions,
+        })) => {
+            assert_eq!(merge_id, "1");
+            assert_eq!(
+                resolutions,
+                vec![SubAgentMergeResolution {
+                    path: PathBuf::from("src/lib.rs"),
+                    run_id: Some("run-b".to_string()),
+                }]
+            );
+        }
+        other => panic!("expected ApplySubAgentMerge, got {other:?}"),
+    }
 }
 
 #[tokio::test]
@@ -2230,6 +2464,8 @@ async fn undo_success_events_render_info_messages() {
         msg: EventMsg::UndoCompleted(UndoCompletedEvent {
             success: true,
             message: None,
+            kind: UndoKind::Undo,
+            redo_commit_id: None,
         }),
     });
 
@@ -2265,6 +2501,8 @@ async fn undo_failure_events_render_error_message() {
         msg: EventMsg::UndoCompleted(UndoCompletedEvent {
             success: false,
             message: Some("Failed to restore workspace state.".to_string()),
+            kind: UndoKind::Undo,
+            redo_commit_id: None,
         }),
     });
 
@@ -2282,6 +2520,43 @@ async fn undo_failure_events_render_error_message() {
     );
 }
 
+#[tokio::test]
+async fn undo_completed_mentions_available_redo() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.handle_codex_event(Event {
+        id: "turn-3".to_string(),
+        msg: EventMsg::UndoCompleted(UndoCompletedEvent {
+            success: true,
+            message: None,
+            kind: UndoKind::Undo,
+            redo_commit_id: Some("0123456789abcdef".to_string()),
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn-4".to_string(),
+        msg: EventMsg::UndoCompleted(UndoCompletedEvent {
+            success: true,
+            message: None,
+            kind: UndoKind::Redo,
+            redo_commit_id: None,
+        }),
+    });
+
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 2);
+    let undo = lines_to_single_string(&cells[0]);
+    assert!(
+        undo.contains("redo available (snapshot 0123456)"),
+        "expected redo hint, got {undo:?}"
+    );
+    let redo = lines_to_single_string(&cells[1]);
+    assert!(
+        redo.contains("Redo completed successfully."),
+        "expected redo message, got {redo:?}"
+    );
+}
+
 #[tokio::test]
 async fn undo_started_hides_interrupt_hint() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -3180,6 +3455,11 @@ async fn approval_modal_exec_snapshot() -> anyhow::Result<()> {
             "world".into(),
         ])),
         parsed_cmd: vec![],
+        explanation: None,
+        sandbox_policy: None,
+        risk: None,
+        matched_rule: None,
+        sandbox: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve".into(),
@@ -3233,6 +3513,11 @@ async fn approval_modal_exec_without_reason_snapshot() -> anyhow::Result<()> {
             "world".into(),
         ])),
         parsed_cmd: vec![],
+        explanation: None,
+        sandbox_policy: None,
+        risk: None,
+        matched_rule: None,
+        sandbox: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-noreason".into(),
@@ -3273,6 +3558,11 @@ async fn approval_modal_exec_multiline_prefix_hides_execpolicy_option_snapshot()
         reason: None,
         proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command)),
         parsed_cmd: vec![],
+        explanation: None,
+        sandbox_policy: None,
+        risk: None,
+        matched_rule: None,
+        sandbox: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-multiline-trunc".into(),
@@ -3317,6 +3607,10 @@ async fn approval_modal_patch_snapshot() -> anyhow::Result<()> {
         changes,
         reason: Some("The model wants to apply changes".into()),
         grant_root: Some(PathBuf::from("/tmp")),
+        files: Vec::new(),
+        sandbox_policy: None,
+        risk: None,
+        preview: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-patch".into(),
@@ -3476,6 +3770,7 @@ async fn turn_complete_clears_unified_exec_processes() {
         id: "turn-1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            metadata: None,
         }),
     });
 
@@ -3568,6 +3863,11 @@ async fn status_widget_and_approval_modal_snapshot() {
             "hello world".into(),
         ])),
         parsed_cmd: vec![],
+        explanation: None,
+        sandbox_policy: None,
+        risk: None,
+        matched_rule: None,
+        sandbox: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-exec".into(),
@@ -3697,6 +3997,10 @@ async fn apply_patch_events_emit_history_cells() {
         changes,
         reason: None,
         grant_root: None,
+        files: Vec::new(),
+        sandbox_policy: None,
+        risk: None,
+        preview: None,
     };
     chat.handle_codex_event(Event {
         id: "s1".into(),
@@ -3777,6 +4081,50 @@ async fn apply_patch_events_emit_history_cells() {
     );
 }
 
+#[tokio::test]
+async fn hunk_review_opens_once_the_patch_applies_and_from_slash_hunks() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Hunks);
+    let cells = drain_insert_history(&mut rx);
+    assert!(
+        lines_to_single_string(cells.last().expect("info cell"))
+            .contains("No patch has been applied yet.")
+    );
+
+    chat.review_hunks_after_apply("c1".to_string());
+    let changes = HashMap::from([(
+        PathBuf::from("foo.txt"),
+        FileChange::Add {
+            content: "hello\n".to_string(),
+        },
+    )]);
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::PatchApplyEnd(PatchApplyEndEvent {
+            call_id: "c1".into(),
+            turn_id: "turn-c1".into(),
+            stdout: String::new(),
+            stderr: String::new(),
+            success: true,
+            changes,
+        }),
+    });
+    let opened = std::iter::from_fn(|| rx.try_recv().ok()).find_map(|ev| match ev {
+        AppEvent::OpenHunkReview(files) => Some(files),
+        _ => None,
+    });
+    let files = opened.expect("hunk review should open");
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].diff.path, "foo.txt");
+
+    chat.dispatch_command(SlashCommand::Hunks);
+    assert!(
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .any(|ev| matches!(ev, AppEvent::OpenHunkReview(_)))
+    );
+}
+
 #[tokio::test]
 async fn apply_patch_manual_approval_adjusts_header() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -3796,6 +4144,10 @@ async fn apply_patch_manual_approval_adjusts_header() {
             changes: proposed_changes,
             reason: None,
             grant_root: None,
+            files: Vec::new(),
+            sandbox_policy: None,
+            risk: None,
+            preview: None,
         }),
     });
     drain_insert_history(&mut rx);
@@ -3845,6 +4197,10 @@ async fn apply_patch_manual_flow_snapshot() {
             changes: proposed_changes,
             reason: Some("Manual review required".into()),
             grant_root: None,
+            files: Vec::new(),
+            sandbox_policy: None,
+            risk: None,
+            preview: None,
         }),
     });
     let history_before_apply = drain_insert_history(&mut rx);
@@ -3896,6 +4252,10 @@ async fn apply_patch_approval_sends_op_with_submission_id() {
         changes,
         reason: None,
         grant_root: None,
+        files: Vec::new(),
+        sandbox_policy: None,
+        risk: None,
+        preview: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-123".into(),
@@ -3936,6 +4296,10 @@ async fn apply_patch_full_flow_integration_like() {
             changes,
             reason: None,
             grant_root: None,
+            files: Vec::new(),
+            sandbox_policy: None,
+            risk: None,
+            preview: None,
         }),
     });
 
@@ -4016,6 +4380,10 @@ async fn apply_patch_untrusted_shows_approval_modal() -> anyhow::Result<()> {
             changes,
             reason: None,
             grant_root: None,
+            files: Vec::new(),
+            sandbox_policy: None,
+            risk: None,
+            preview: None,
         }),
     });
 
@@ -4067,6 +4435,10 @@ async fn apply_patch_request_shows_diff_summary() -> anyhow::Result<()> {
             changes,
             reason: None,
             grant_root: None,
+            files: Vec::new(),
+            sandbox_policy: None,
+            risk: None,
+            preview: None,
         }),
     });
 
@@ -4262,6 +4634,7 @@ async fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
         id: "s1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            metadata: None,
         }),
     });
 
@@ -4550,6 +4923,7 @@ printf 'fenced within fenced\n'
         id: "t1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            metadata: None,
         }),
     });
     for lines in drain_insert_history(&mut rx) {
@@ -4616,3 +4990,167 @@ async fn review_queues_user_messages_snapshot() {
     .unwrap();
     assert_snapshot!(term.backend().vt100().screen().contents());
 }
+
+#[tokio::test]
+async fn follow_up_suggestions_show_chips_and_queue_the_chosen_one() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(None).await;
+    chat.thread_id = Some(ThreadId::new());
+    let add_tests = FollowUpSuggestion {
+        title: "Add tests".to_string(),
+        prompt: "Add unit tests for the parser.".to_string(),
+    };
+    let update_docs = FollowUpSuggestion {
+        title: "Update docs".to_string(),
+        prompt: "Document the new flag in the README.".to_string(),
+    };
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::FollowUpSuggestions(FollowUpSuggestionsEvent {
+            suggestions: vec![add_tests.clone(), update_docs.clone()],
+        }),
+    });
+    let history = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        history.contains("[1] Add tests  [2] Update docs"),
+        "expected chips: {history}"
+    );
+
+    chat.dispatch_command(SlashCommand::FollowUps);
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(popup.contains("2. Update docs"), "expected picker: {popup}");
+    chat.handle_key_event(KeyEvent::from(KeyCode::Enter));
+    let chosen = match rx.try_recv() {
+        Ok(AppEvent::OpenFollowUpActions(suggestion)) => suggestion,
+        other => panic!("expected OpenFollowUpActions, got {other:?}"),
+    };
+    assert_eq!(chosen, add_tests);
+
+    chat.open_follow_up_actions(chosen);
+    chat.handle_key_event(KeyEvent::from(KeyCode::Enter));
+    match rx.try_recv() {
+        Ok(AppEvent::UseFollowUp { prompt, queue }) => {
+            assert!(queue);
+            chat.use_follow_up(prompt, queue);
+        }
+        other => panic!("expected UseFollowUp, got {other:?}"),
+    }
+    match next_submit_op(&mut op_rx) {
+        Op::UserTurn { items, .. } => assert_eq!(
+            items,
+            vec![UserInput::Text {
+                text: add_tests.prompt.clone(),
+                text_elements: Vec::new(),
+            }]
+        ),
+        other => panic!("expected Op::UserTurn, got {other:?}"),
+    }
+    assert_eq!(chat.follow_up_suggestions, vec![update_docs]);
+}
+
+#[tokio::test]
+async fn tool_usage_command_lists_tools_from_latest_token_count() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TokenCount(TokenCountEvent {
+            info: None,
+            rate_limits: None,
+            tool_usage: vec![ToolTokenUsage {
+                tool_name: "shell".to_string(),
+                calls: 3,
+                output_tokens: 12_400,
+                follow_up_tokens: 900,
+            }],
+            subagent_usage: Vec::new(),
+        }),
+    });
+
+    chat.dispatch_command(SlashCommand::ToolUsage);
+    let history = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        history.contains("shell (3 calls)  12.4K output · 900 follow-up"),
+        "expected tool line: {history}"
+    );
+}
+
+#[tokio::test]
+async fn status_line_tracks_cost_and_running_tasks() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.config.tui_status_line = vec![StatusLineItem::Cost, StatusLineItem::Tasks];
+    chat.bottom_pane
+        .set_status_line_items(chat.config.tui_status_line.clone());
+
+    chat.handle_codex_event(Event {
+        id: "cost".into(),
+        msg: EventMsg::CostUpdate(CostUpdateEvent {
+            turn_usd: 0.25,
+            session_usd: 1.5,
+            max_usd_per_turn: None,
+            max_usd_per_session: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "task".into(),
+        msg: EventMsg::BackgroundTaskBegin(BackgroundTaskBeginEvent {
+            task_id: "task-1".to_string(),
+            subagent: None,
+            prompt: "run the tests".to_string(),
+        }),
+    });
+    let rendered = render_bottom_popup(&chat, 80);
+    assert_eq!(
+        rendered.lines().last().map(str::trim),
+        Some("$1.50 · 1 task running")
+    );
+
+    chat.handle_codex_event(Event {
+        id: "task".into(),
+        msg: EventMsg::BackgroundTaskEnd(BackgroundTaskEndEvent {
+            task_id: "task-1".to_string(),
+            subagent: None,
+            status: AgentStatus::Completed(None),
+        }),
+    });
+    let rendered = render_bottom_popup(&chat, 80);
+    assert_eq!(rendered.lines().last().map(str::trim), Some("$1.50"));
+}
+
+#[tokio::test]
+async fn short_turns_do_not_notify_below_the_min_turn_length() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.config.tui_notification_min_turn_sec = 60;
+    let complete_turn = |chat: &mut ChatWidget| {
+        chat.handle_codex_event(Event {
+            id: "turn-1".into(),
+            msg: EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message: Some("Done.".into()),
+                metadata: None,
+            }),
+        });
+    };
+
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    complete_turn(&mut chat);
+    assert!(chat.pending_notification.is_none());
+
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    chat.turn_started_at = Instant::now().checked_sub(Duration::from_secs(120));
+    complete_turn(&mut chat);
+    assert!(chat.pending_notification.is_some());
+}
@@ -0,0 +1,168 @@
+use super::ChatWidget;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::SelectionAction;
+use crate::bottom_pane::SelectionItem;
+use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use codex_core::protocol::Op;
+use codex_core::protocol::WorktreeCreatedEvent;
+use codex_core::protocol::WorktreeFileChange;
+use codex_core::protocol::WorktreeFinishAction;
+use codex_core::protocol::WorktreeFinishedEvent;
+use codex_core::protocol::WorktreeReviewEvent;
+
+impl ChatWidget {
+    /// `/worktree` reviews the isolated worktree; `/worktree merge` and
+    /// `/worktree discard` finish it directly.
+    pub(crate) fn worktree_command(&mut self, args: &str) {
+        match args.trim() {
+            "" => self.submit_op(Op::ReviewWorktree),
+            "merge" => self.submit_op(Op::FinishWorktree {
+                action: WorktreeFinishAction::Merge,
+            }),
+            "discard" => self.submit_op(Op::FinishWorktree {
+                action: WorktreeFinishAction::Discard,
+            }),
+            other => self.add_error_message(format!(
+                "Unknown /worktree argument `{other}`; use `merge` or `discard`."
+            )),
+        }
+    }
+
+    pub(crate) fn on_worktree_created(&mut self, ev: WorktreeCreatedEvent) {
+        self.add_info_message(
+            format!("Working in an isolated worktree on branch {}", ev.branch),
+            Some(format!(
+                "{} · review, merge or discard it with /worktree",
+                ev.path.display()
+            )),
+        );
+    }
+
+    pub(crate) fn on_worktree_review(&mut self, ev: WorktreeReviewEvent) {
+        let WorktreeReviewEvent {
+            branch,
+            unified_diff,
+            files,
+            ..
+        } = ev;
+        if files.is_empty() {
+            self.add_info_message(
+                format!("No changes in the worktree on branch {branch} yet"),
+                None,
+            );
+            return;
+        }
+
+        let items = vec![
+            SelectionItem {
+                name: "Merge into checkout".to_string(),
+                description: Some(
+                    "apply the changes to the checked-out tree and remove the worktree".to_string(),
+                ),
+                actions: finish(WorktreeFinishAction::Merge),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Discard".to_string(),
+                description: Some("remove the worktree and its branch".to_string()),
+                actions: finish(WorktreeFinishAction::Discard),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Show diff".to_string(),
+                description: Some("open the full diff".to_string()),
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::DiffResult(unified_diff.clone()));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Keep working".to_string(),
+                description: Some("decide later with /worktree".to_string()),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(format!("Worktree {branch}")),
+            subtitle: Some(worktree_summary(&files)),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn on_worktree_finished(&mut self, ev: WorktreeFinishedEvent) {
+        let count = ev.files.len();
+        let files = if count == 1 { "file" } else { "files" };
+        let message = match ev.action {
+            WorktreeFinishAction::Merge => {
+                format!(
+                    "Merged {count} {files} from {} into the checkout",
+                    ev.branch
+                )
+            }
+            WorktreeFinishAction::Discard => {
+                format!("Discarded {count} {files} changed on {}", ev.branch)
+            }
+        };
+        self.add_info_message(
+            message,
+            Some("the next message starts a fresh worktree".to_string()),
+        );
+    }
+}
+
+fn finish(action: WorktreeFinishAction) -> Vec<SelectionAction> {
+    vec![Box::new(move |tx| {
+        tx.send(AppEvent::CodexOp(Op::FinishWorktree { action }));
+    })]
+}
+
+/// One line such as `2 files changed (+10 -3): src/a.rs, b.txt`.
+fn worktree_summary(files: &[WorktreeFileChange]) -> String {
+    let added: u32 = files.iter().map(|file| file.added).sum();
+    let removed: u32 = files.iter().map(|file| file.removed).sum();
+    let names: Vec<String> = files
+        .iter()
+        .map(|file| file.path.display().to_string())
+        .collect();
+    format!(
+        "{} {} changed (+{added} -{removed}): {}",
+        files.len(),
+        if files.len() == 1 { "file" } else { "files" },
+        names.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn worktree_summary_totals_line_counts() {
+        let files = vec![
+            WorktreeFileChange {
+                path: PathBuf::from("src/a.rs"),
+                added: 8,
+                removed: 3,
+            },
+            WorktreeFileChange {
+                path: PathBuf::from("b.txt"),
+                added: 2,
+                removed: 0,
+            },
+        ];
+        assert_eq!(
+            worktree_summary(&files),
+            "2 files changed (+10 -3): src/a.rs, b.txt"
+        );
+    }
+}
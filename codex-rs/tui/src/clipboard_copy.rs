@@ -0,0 +1,182 @@
+//! Copying text to the system clipboard.
+//!
+//! The native clipboard (via `arboard`) is tried first. When it is
+//! unavailable (headless Linux, WSL, Android) the text is sent to the
+//! terminal with an OSC 52 escape sequence instead, which most modern
+//! terminals turn into a clipboard write. In an SSH session the native
+//! clipboard belongs to the remote machine, so OSC 52 goes first there.
+
+use std::fmt;
+use std::io::stdout;
+
+use base64::Engine;
+use crossterm::Command;
+use ratatui::crossterm::execute;
+use ratatui::text::Line;
+
+/// Terminals commonly cap OSC 52 payloads around 100 KB; larger writes are
+/// silently dropped, so refuse them up front.
+const OSC52_MAX_ENCODED_BYTES: usize = 100_000;
+
+/// How [`copy_to_clipboard`] delivered the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CopyMethod {
+    Native,
+    /// Handed to the terminal; whether it reached the clipboard depends on
+    /// the terminal's OSC 52 support.
+    Terminal,
+}
+
+pub(crate) fn copy_to_clipboard(text: &str) -> Result<CopyMethod, String> {
+    if is_remote_session() {
+        copy_osc52(text)
+            .map(|()| CopyMethod::Terminal)
+            .or_else(|osc_err| {
+                copy_native(text)
+                    .map(|()| CopyMethod::Native)
+                    .map_err(|native_err| format!("{osc_err}; {native_err}"))
+            })
+    } else {
+        copy_native(text)
+            .map(|()| CopyMethod::Native)
+            .or_else(|native_err| {
+                tracing::debug!(
+                    "native clipboard unavailable, falling back to OSC 52: {native_err}"
+                );
+                copy_osc52(text).map(|()| CopyMethod::Terminal)
+            })
+    }
+}
+
+/// Flattens rendered lines into plain text, one line per row.
+pub(crate) fn lines_to_plain_text(lines: &[Line<'_>]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_remote_session() -> bool {
+    std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some()
+}
+
+#[cfg(not(target_os = "android"))]
+fn copy_native(text: &str) -> Result<(), String> {
+    use std::sync::Mutex;
+
+    // On X11/Wayland the copying process serves the clipboard contents, so
+    // the handle is kept alive for the rest of the session.
+    static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+    let mut guard = CLIPBOARD
+        .lock()
+        .map_err(|_| "clipboard lock poisoned".to_string())?;
+    if guard.is_none() {
+        *guard = Some(arboard::Clipboard::new().map_err(|err| err.to_string())?);
+    }
+    match guard.as_mut() {
+        Some(clipboard) => clipboard
+            .set_text(text.to_string())
+            .map_err(|err| err.to_string()),
+        None => Err("clipboard unavailable".to_string()),
+    }
+}
+
+#[cfg(target_os = "android")]
+fn copy_native(_text: &str) -> Result<(), String> {
+    Err("native clipboard is unsupported on Android".to_string())
+}
+
+fn copy_osc52(text: &str) -> Result<(), String> {
+    let in_tmux = std::env::var_os("TMUX").is_some();
+    let sequence = osc52_sequence(text, in_tmux)?;
+    execute!(stdout(), SetClipboard(sequence)).map_err(|err| err.to_string())
+}
+
+fn osc52_sequence(text: &str, in_tmux: bool) -> Result<String, String> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    if encoded.len() > OSC52_MAX_ENCODED_BYTES {
+        return Err(format!(
+            "{} bytes is too large to copy through the terminal",
+            text.len()
+        ));
+    }
+    let sequence = format!("\x1b]52;c;{encoded}\x07");
+    if in_tmux {
+        // tmux only forwards escape sequences wrapped in a DCS passthrough,
+        // with every ESC doubled.
+        Ok(format!(
+            "\x1bPtmux;{}\x1b\\",
+            sequence.replace('\x1b', "\x1b\x1b")
+        ))
+    } else {
+        Ok(sequence)
+    }
+}
+
+/// Command that writes a prepared OSC 52 sequence to the terminal.
+#[derive(Debug, Clone)]
+struct SetClipboard(String);
+
+impl Command for SetClipboard {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Err(std::io::Error::other(
+            "tried to execute SetClipboard using WinAPI; use ANSI instead",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn osc52_sequence_encodes_text() {
+        assert_eq!(
+            osc52_sequence("hi", false),
+            Ok("\x1b]52;c;aGk=\x07".to_string())
+        );
+    }
+
+    #[test]
+    fn osc52_sequence_wraps_for_tmux() {
+        assert_eq!(
+            osc52_sequence("hi", true),
+            Ok("\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\".to_string())
+        );
+    }
+
+    #[test]
+    fn osc52_sequence_rejects_oversized_text() {
+        assert!(osc52_sequence(&"x".repeat(OSC52_MAX_ENCODED_BYTES), false).is_err());
+    }
+
+    #[test]
+    fn lines_to_plain_text_drops_styling_and_trailing_spaces() {
+        let lines = vec![
+            Line::from(vec!["• ".into(), "hello  ".into()]),
+            Line::from(""),
+            Line::from("world"),
+        ];
+        assert_eq!(lines_to_plain_text(&lines), "• hello\n\nworld");
+    }
+}
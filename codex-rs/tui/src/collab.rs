@@ -1,17 +1,26 @@
 use crate::history_cell::PlainHistoryCell;
 use crate::render::line_utils::prefix_lines;
+use crate::status::format_tokens_compact;
 use crate::text_formatting::truncate_text;
 use codex_core::protocol::AgentStatus;
+use codex_core::protocol::BackgroundTaskBeginEvent;
+use codex_core::protocol::BackgroundTaskEndEvent;
 use codex_core::protocol::CollabAgentInteractionEndEvent;
 use codex_core::protocol::CollabAgentSpawnEndEvent;
 use codex_core::protocol::CollabCloseEndEvent;
 use codex_core::protocol::CollabWaitingBeginEvent;
 use codex_core::protocol::CollabWaitingEndEvent;
+use codex_core::protocol::SubAgentMergeAppliedEvent;
+use codex_core::protocol::SubAgentMergePlanEvent;
+use codex_core::protocol::SubAgentRunBeginEvent;
+use codex_core::protocol::SubAgentRunEndEvent;
 use codex_protocol::ThreadId;
+use codex_protocol::subagents::SubAgentPreview;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 const COLLAB_PROMPT_PREVIEW_GRAPHEMES: usize = 160;
 const COLLAB_AGENT_ERROR_PREVIEW_GRAPHEMES: usize = 160;
@@ -97,6 +106,171 @@ pub(crate) fn close_end(ev: CollabCloseEndEvent) -> PlainHistoryCell {
     collab_event("Agent closed", details)
 }
 
+pub(crate) fn subagent_run_begin(ev: SubAgentRunBeginEvent) -> PlainHistoryCell {
+    let SubAgentRunBeginEvent {
+        run_id,
+        name,
+        prompt,
+    } = ev;
+    let mut details = vec![detail_line("run", run_id)];
+    if let Some(line) = prompt_line(&prompt) {
+        details.push(line);
+    }
+    collab_event(format!("Subagent @{name} started"), details)
+}
+
+pub(crate) fn subagent_run_end(ev: SubAgentRunEndEvent) -> PlainHistoryCell {
+    let SubAgentRunEndEvent {
+        run_id,
+        name,
+        status,
+        staged_files,
+        token_usage,
+    } = ev;
+    let mut details = vec![detail_line("run", run_id), status_line(&status)];
+    details.extend(outcome_line(&status));
+    if let Some(token_usage) = token_usage {
+        details.push(detail_line(
+            "tokens",
+            Span::from(format_tokens_compact(token_usage.total_tokens)).dim(),
+        ));
+    }
+    if !staged_files.is_empty() {
+        let count = staged_files.len();
+        let noun = if count == 1 { "file" } else { "files" };
+        details.push(detail_line(
+            "staged",
+            Span::from(format!("{count} {noun} · /merge to review and apply")).cyan(),
+        ));
+    }
+    collab_event(format!("Subagent @{name} finished"), details)
+}
+
+pub(crate) fn background_task_begin(ev: BackgroundTaskBeginEvent) -> PlainHistoryCell {
+    let BackgroundTaskBeginEvent {
+        task_id,
+        subagent,
+        prompt,
+    } = ev;
+    let mut details = vec![detail_line("task", task_id)];
+    if let Some(line) = prompt_line(&prompt) {
+        details.push(line);
+    }
+    collab_event(
+        background_task_title(subagent.as_deref(), "started"),
+        details,
+    )
+}
+
+pub(crate) fn background_task_end(ev: BackgroundTaskEndEvent) -> PlainHistoryCell {
+    let BackgroundTaskEndEvent {
+        task_id,
+        subagent,
+        status,
+    } = ev;
+    let mut details = vec![detail_line("task", task_id), status_line(&status)];
+    details.extend(outcome_line(&status));
+    collab_event(
+        background_task_title(subagent.as_deref(), "finished"),
+        details,
+    )
+}
+
+fn background_task_title(subagent: Option<&str>, verb: &str) -> String {
+    match subagent {
+        Some(name) => format!("Background task (@{name}) {verb}"),
+        None => format!("Background task {verb}"),
+    }
+}
+
+pub(crate) fn subagent_preview(preview: SubAgentPreview) -> PlainHistoryCell {
+    let SubAgentPreview {
+        name,
+        path,
+        model,
+        approval_policy,
+        sandbox_policy,
+        tools,
+        mcp_servers,
+        developer_instructions,
+        prompt,
+    } = preview;
+    let mut details = vec![
+        detail_line("definition", path.display().to_string()),
+        detail_line("model", model),
+        detail_line("approval", approval_policy.to_string()),
+        detail_line("sandbox", sandbox_policy.to_string()),
+        detail_line("tools", tools.join(", ")),
+    ];
+    if !mcp_servers.is_empty() {
+        details.push(detail_line("mcp", mcp_servers.join(", ")));
+    }
+    if let Some(instructions) = developer_instructions {
+        details.push(detail_line(
+            "instructions",
+            Span::from(truncate_text(
+                &instructions
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                COLLAB_AGENT_RESPONSE_PREVIEW_GRAPHEMES,
+            ))
+            .dim(),
+        ));
+    }
+    if let Some(line) = prompt_line(&prompt) {
+        details.push(line);
+    }
+    collab_event(format!("Subagent @{name} dry run (not started)"), details)
+}
+
+pub(crate) fn subagent_merge_plan(ev: &SubAgentMergePlanEvent) -> PlainHistoryCell {
+    let runs = ev
+        .runs
+        .iter()
+        .map(|run| format!("@{}", run.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut details = vec![
+        detail_line("runs", runs),
+        detail_line("merged", format_paths(&ev.merged_files)),
+    ];
+    if !ev.conflicts.is_empty() {
+        let conflicts: Vec<PathBuf> = ev
+            .conflicts
+            .iter()
+            .map(|conflict| conflict.path.clone())
+            .collect();
+        details.push(detail_line(
+            "conflicts",
+            Span::from(format_paths(&conflicts)).red(),
+        ));
+    }
+    collab_event("Subagent changes ready to merge", details)
+}
+
+pub(crate) fn subagent_merge_applied(ev: SubAgentMergeAppliedEvent) -> PlainHistoryCell {
+    let SubAgentMergeAppliedEvent {
+        merge_id: _,
+        applied_files,
+    } = ev;
+    collab_event(
+        "Subagent changes applied",
+        vec![detail_line("files", format_paths(&applied_files))],
+    )
+}
+
+fn format_paths(paths: &[PathBuf]) -> String {
+    if paths.is_empty() {
+        return "none".to_string();
+    }
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn collab_event(title: impl Into<String>, details: Vec<Line<'static>>) -> PlainHistoryCell {
     let title = title.into();
     let mut lines: Vec<Line<'static>> =
@@ -126,6 +300,28 @@ fn status_span(status: &AgentStatus) -> Span<'static> {
     }
 }
 
+/// The final message or error carried by a finished child thread, if any.
+fn outcome_line(status: &AgentStatus) -> Option<Line<'static>> {
+    match status {
+        AgentStatus::Completed(Some(message)) => Some(detail_line(
+            "result",
+            truncate_text(
+                &message.split_whitespace().collect::<Vec<_>>().join(" "),
+                COLLAB_AGENT_RESPONSE_PREVIEW_GRAPHEMES,
+            ),
+        )),
+        AgentStatus::Errored(error) => Some(detail_line(
+            "error",
+            Span::from(truncate_text(
+                &error.split_whitespace().collect::<Vec<_>>().join(" "),
+                COLLAB_AGENT_ERROR_PREVIEW_GRAPHEMES,
+            ))
+            .dim(),
+        )),
+        _ => None,
+    }
+}
+
 fn prompt_line(prompt: &str) -> Option<Line<'static>> {
     let trimmed = prompt.trim();
     if trimmed.is_empty() {
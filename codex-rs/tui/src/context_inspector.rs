@@ -0,0 +1,386 @@
+//! Full-screen context inspector opened by `/context`.
+//!
+//! Lists what the next request carries (base instructions, pinned items,
+//! instruction messages, each turn and its tool outputs) with a heat bar and
+//! an estimated token count per item. Items can be dropped from the context
+//! (`Op::DropContextItem`) or, for turns, replaced by a summary
+//! (`Op::CompactRange`); the list refreshes once core reports the change.
+
+use std::io::Result;
+
+use codex_core::protocol::ContextInspectionEvent;
+use codex_core::protocol::ContextInspectionItem;
+use codex_core::protocol::ContextItemKind;
+use codex_core::protocol::Op;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::key_hint;
+use crate::key_hint::KeyBinding;
+use crate::pager_overlay::render_key_hints;
+use crate::status::format_tokens_compact;
+use crate::tui;
+use crate::tui::TuiEvent;
+
+const KEY_UP: KeyBinding = key_hint::plain(KeyCode::Up);
+const KEY_DOWN: KeyBinding = key_hint::plain(KeyCode::Down);
+const KEY_K: KeyBinding = key_hint::plain(KeyCode::Char('k'));
+const KEY_J: KeyBinding = key_hint::plain(KeyCode::Char('j'));
+const KEY_D: KeyBinding = key_hint::plain(KeyCode::Char('d'));
+const KEY_C: KeyBinding = key_hint::plain(KeyCode::Char('c'));
+const KEY_R: KeyBinding = key_hint::plain(KeyCode::Char('r'));
+const KEY_Q: KeyBinding = key_hint::plain(KeyCode::Char('q'));
+const KEY_CTRL_C: KeyBinding = key_hint::ctrl(KeyCode::Char('c'));
+
+const BAR_WIDTH: usize = 10;
+
+/// Rows above the list (summary and a blank line) and below it (status and
+/// key hints).
+const HEADER_ROWS: u16 = 2;
+const FOOTER_ROWS: u16 = 3;
+
+pub(crate) struct ContextInspectorOverlay {
+    inspection: ContextInspectionEvent,
+    selected: usize,
+    scroll_offset: usize,
+    /// Id of the item waiting for a second `d` to confirm the drop.
+    pending_drop: Option<String>,
+    status: Option<String>,
+    app_event_tx: AppEventSender,
+    is_done: bool,
+}
+
+impl ContextInspectorOverlay {
+    pub(crate) fn new(inspection: ContextInspectionEvent, app_event_tx: AppEventSender) -> Self {
+        Self {
+            inspection,
+            selected: 0,
+            scroll_offset: 0,
+            pending_drop: None,
+            status: None,
+            app_event_tx,
+            is_done: false,
+        }
+    }
+
+    /// Replaces the listed items, keeping the selection on the same item when
+    /// it is still there.
+    pub(crate) fn update(&mut self, inspection: ContextInspectionEvent) {
+        let selected_id = self.selected_item().and_then(|item| item.id.clone());
+        let fallback = self.selected;
+        self.inspection = inspection;
+        self.selected = selected_id
+            .and_then(|id| {
+                self.inspection
+                    .items
+                    .iter()
+                    .position(|item| item.id.as_deref() == Some(id.as_str()))
+            })
+            .unwrap_or(fallback)
+            .min(self.inspection.items.len().saturating_sub(1));
+        self.pending_drop = None;
+        self.status = None;
+    }
+
+    /// Shows `message` (e.g. an error from core) in the status line.
+    pub(crate) fn set_status(&mut self, message: String) {
+        self.status = Some(message);
+    }
+
+    pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
+        match event {
+            TuiEvent::Key(key_event) => {
+                self.handle_key_event(key_event);
+                tui.frame_requester().schedule_frame();
+                Ok(())
+            }
+            TuiEvent::Draw => {
+                tui.draw(u16::MAX, |frame| {
+                    self.render(frame.area(), frame.buffer);
+                })?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn selected_item(&self) -> Option<&ContextInspectionItem> {
+        self.inspection.items.get(self.selected)
+    }
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        let pending_drop = self.pending_drop.take();
+        match key_event {
+            e if KEY_Q.is_press(e) || KEY_CTRL_C.is_press(e) => self.is_done = true,
+            e if KEY_UP.is_press(e) || KEY_K.is_press(e) => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            e if KEY_DOWN.is_press(e) || KEY_J.is_press(e) => {
+                if self.selected + 1 < self.inspection.items.len() {
+                    self.selected += 1;
+                }
+            }
+            e if KEY_R.is_press(e) => {
+                self.app_event_tx
+                    .send(AppEvent::CodexOp(Op::InspectContext));
+            }
+            e if KEY_D.is_press(e) => {
+                let Some(id) = self.selected_item().and_then(|item| item.id.clone()) else {
+                    self.status = Some("This item cannot be dropped.".to_string());
+                    return;
+                };
+                if pending_drop.as_deref() == Some(id.as_str()) {
+                    self.status = Some("Dropping…".to_string());
+                    self.app_event_tx
+                        .send(AppEvent::CodexOp(Op::DropContextItem { id }));
+                } else {
+                    self.status = Some("Press d again to drop this item.".to_string());
+                    self.pending_drop = Some(id);
+                }
+            }
+            e if KEY_C.is_press(e) => {
+                let Some(turn) = self.selected_item().and_then(|item| item.turn) else {
+                    self.status = Some("Only turns can be compacted.".to_string());
+                    return;
+                };
+                self.status = Some(format!("Compacting turn {turn}…"));
+                self.app_event_tx.send(AppEvent::CodexOp(Op::CompactRange {
+                    from_turn: turn,
+                    to_turn: turn,
+                }));
+            }
+            _ => {}
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let list_height = usize::from(area.height.saturating_sub(HEADER_ROWS + FOOTER_ROWS));
+        if self.selected < self.scroll_offset {
+            self.scroll_offset = self.selected;
+        } else if list_height > 0 && self.selected >= self.scroll_offset + list_height {
+            self.scroll_offset = self.selected + 1 - list_height;
+        }
+
+        let mut lines = vec![self.summary_line(), Line::from("")];
+        let basis = self.heat_basis();
+        lines.extend(
+            self.inspection
+                .items
+                .iter()
+                .enumerate()
+                .skip(self.scroll_offset)
+                .take(list_height)
+                .map(|(idx, item)| item_line(item, basis, idx == self.selected)),
+        );
+        let list_area = Rect::new(
+            area.x,
+            area.y,
+            area.width,
+            area.height.saturating_sub(FOOTER_ROWS),
+        );
+        Paragraph::new(lines).render(list_area, buf);
+
+        let footer_y = area.y + area.height.saturating_sub(FOOTER_ROWS);
+        if let Some(status) = &self.status {
+            Paragraph::new(Line::from(format!(" {status}")))
+                .render(Rect::new(area.x, footer_y, area.width, 1), buf);
+        }
+        let hints: Vec<(&[KeyBinding], &str)> = vec![
+            (&[KEY_UP, KEY_DOWN], "to select"),
+            (&[KEY_D], "to drop"),
+            (&[KEY_C], "to compact a turn"),
+            (&[KEY_R], "to refresh"),
+            (&[KEY_Q], "to quit"),
+        ];
+        render_key_hints(
+            Rect::new(area.x, footer_y.saturating_add(1), area.width, 1),
+            buf,
+            &hints,
+        );
+    }
+
+    fn summary_line(&self) -> Line<'static> {
+        let total = self.inspection.total_tokens;
+        let mut spans: Vec<Span<'static>> = vec![
+            " Context".bold(),
+            format!(" · {} tokens", format_tokens_compact(total)).into(),
+        ];
+        if let Some(window) = self.inspection.context_window.filter(|window| *window > 0) {
+            let percent = total.saturating_mul(100) / window;
+            spans.push(format!(" of {} ({percent}%)", format_tokens_compact(window)).dim());
+        }
+        Line::from(spans)
+    }
+
+    /// Token count a full heat bar stands for: the context window, or the
+    /// total when the window is unknown.
+    fn heat_basis(&self) -> i64 {
+        self.inspection
+            .context_window
+            .unwrap_or(self.inspection.total_tokens)
+            .max(1)
+    }
+}
+
+fn item_line(item: &ContextInspectionItem, basis: i64, selected: bool) -> Line<'static> {
+    let marker: Span<'static> = if selected { "› ".cyan() } else { "  ".into() };
+    let tokens = format!(" {:>6}  ", format_tokens_compact(item.tokens));
+    let kind = format!("{:<12}", kind_label(item));
+    let label: Span<'static> = if selected {
+        item.label.clone().cyan()
+    } else {
+        item.label.clone().into()
+    };
+    Line::from(vec![
+        marker,
+        heat_bar(item.tokens, basis),
+        tokens.into(),
+        kind.dim(),
+        label,
+    ])
+}
+
+/// Bar filled in proportion to `tokens / basis`, red for items taking a
+/// fifth of the basis or more and cyan from one in twenty.
+fn heat_bar(tokens: i64, basis: i64) -> Span<'static> {
+    let share = tokens.max(0) as f64 / basis.max(1) as f64;
+    let mut filled = ((share * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+    if tokens > 0 {
+        filled = filled.max(1);
+    }
+    let bar = format!("{}{}", "█".repeat(filled), "·".repeat(BAR_WIDTH - filled));
+    if share >= 0.2 {
+        bar.red()
+    } else if share >= 0.05 {
+        bar.cyan()
+    } else {
+        bar.dim()
+    }
+}
+
+fn kind_label(item: &ContextInspectionItem) -> String {
+    match item.kind {
+        ContextItemKind::BaseInstructions => "system".to_string(),
+        ContextItemKind::DeveloperInstructions => "developer".to_string(),
+        ContextItemKind::UserInstructions => "AGENTS.md".to_string(),
+        ContextItemKind::SkillInstructions => "skill".to_string(),
+        ContextItemKind::EnvironmentContext => "environment".to_string(),
+        ContextItemKind::Pinned => "pinned".to_string(),
+        ContextItemKind::Turn => match item.turn {
+            Some(turn) => format!("turn {turn}"),
+            None => "turn".to_string(),
+        },
+        ContextItemKind::ToolOutput => "  └ output".to_string(),
+        ContextItemKind::Other => "other".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn item(
+        id: &str,
+        kind: ContextItemKind,
+        tokens: i64,
+        turn: Option<u32>,
+    ) -> ContextInspectionItem {
+        ContextInspectionItem {
+            id: Some(id.to_string()),
+            kind,
+            label: id.to_string(),
+            tokens,
+            turn,
+        }
+    }
+
+    fn inspection(items: Vec<ContextInspectionItem>) -> ContextInspectionEvent {
+        ContextInspectionEvent {
+            total_tokens: items.iter().map(|item| item.tokens).sum(),
+            items,
+            context_window: Some(1_000),
+        }
+    }
+
+    fn press(overlay: &mut ContextInspectorOverlay, code: KeyCode) {
+        overlay.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn drop_needs_a_second_press_and_compact_uses_the_turn() {
+        let (tx, mut rx) = unbounded_channel();
+        let mut overlay = ContextInspectorOverlay::new(
+            inspection(vec![
+                item("turn:1", ContextItemKind::Turn, 300, Some(1)),
+                item("output:c1", ContextItemKind::ToolOutput, 200, Some(1)),
+            ]),
+            AppEventSender::new(tx),
+        );
+
+        press(&mut overlay, KeyCode::Down);
+        press(&mut overlay, KeyCode::Char('d'));
+        assert!(rx.try_recv().is_err());
+        press(&mut overlay, KeyCode::Char('d'));
+        match rx.try_recv() {
+            Ok(AppEvent::CodexOp(Op::DropContextItem { id })) => assert_eq!(id, "output:c1"),
+            other => panic!("expected a drop, got {other:?}"),
+        }
+
+        press(&mut overlay, KeyCode::Char('c'));
+        match rx.try_recv() {
+            Ok(AppEvent::CodexOp(Op::CompactRange { from_turn, to_turn })) => {
+                assert_eq!((from_turn, to_turn), (1, 1));
+            }
+            other => panic!("expected a compaction, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn update_keeps_the_selected_item() {
+        let (tx, _rx) = unbounded_channel();
+        let mut overlay = ContextInspectorOverlay::new(
+            inspection(vec![
+                item("item:0", ContextItemKind::DeveloperInstructions, 50, None),
+                item("turn:1", ContextItemKind::Turn, 300, Some(1)),
+                item("turn:2", ContextItemKind::Turn, 100, Some(2)),
+            ]),
+            AppEventSender::new(tx),
+        );
+        press(&mut overlay, KeyCode::Down);
+        press(&mut overlay, KeyCode::Down);
+
+        overlay.update(inspection(vec![
+            item("turn:1", ContextItemKind::Turn, 300, Some(1)),
+            item("turn:2", ContextItemKind::Turn, 100, Some(2)),
+        ]));
+        assert_eq!(
+            overlay.selected_item().and_then(|item| item.id.clone()),
+            Some("turn:2".to_string())
+        );
+    }
+
+    #[test]
+    fn heat_bar_scales_with_the_share_of_the_basis() {
+        assert_eq!(heat_bar(500, 1_000).content, "█████·····");
+        assert_eq!(heat_bar(1, 1_000).content, "█·········");
+        assert_eq!(heat_bar(0, 1_000).content, "··········");
+    }
+}
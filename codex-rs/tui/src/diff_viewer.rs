@@ -0,0 +1,1012 @@
+//! Full-screen diff viewer opened by `/diff`.
+//!
+//! The working tree diff (tracked changes plus untracked files) is split per
+//! file: a file list on the left with per-file line counts, and the selected
+//! file's hunks on the right with line numbers and syntax highlighting. A
+//! file can be opened in `$VISUAL`/`$EDITOR`, and the hunk under the cursor
+//! copied as a patch that `git apply` accepts.
+//!
+//! The same viewer reviews a patch Codex applied (see [`crate::hunk_review`]):
+//! each hunk is kept or rejected, and the decisions go back to the chat.
+
+use std::io::Result;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::protocol::DiffHunk as HunkRange;
+use codex_core::turn_diff_tracker::parse_hunk_header;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Style;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::clipboard_copy::CopyMethod;
+use crate::clipboard_copy::copy_to_clipboard;
+use crate::hunk_review::ReviewFile;
+use crate::key_hint;
+use crate::key_hint::KeyBinding;
+use crate::pager_overlay::render_key_hints;
+use crate::render::code_highlight::CodeLanguage;
+use crate::render::code_highlight::highlight_code_to_lines;
+use crate::tui;
+use crate::tui::TuiEvent;
+
+const KEY_UP: KeyBinding = key_hint::plain(KeyCode::Up);
+const KEY_DOWN: KeyBinding = key_hint::plain(KeyCode::Down);
+const KEY_K: KeyBinding = key_hint::plain(KeyCode::Char('k'));
+const KEY_J: KeyBinding = key_hint::plain(KeyCode::Char('j'));
+const KEY_PAGE_UP: KeyBinding = key_hint::plain(KeyCode::PageUp);
+const KEY_PAGE_DOWN: KeyBinding = key_hint::plain(KeyCode::PageDown);
+const KEY_SPACE: KeyBinding = key_hint::plain(KeyCode::Char(' '));
+const KEY_HOME: KeyBinding = key_hint::plain(KeyCode::Home);
+const KEY_END: KeyBinding = key_hint::plain(KeyCode::End);
+const KEY_LEFT: KeyBinding = key_hint::plain(KeyCode::Left);
+const KEY_RIGHT: KeyBinding = key_hint::plain(KeyCode::Right);
+const KEY_H: KeyBinding = key_hint::plain(KeyCode::Char('h'));
+const KEY_L: KeyBinding = key_hint::plain(KeyCode::Char('l'));
+const KEY_N: KeyBinding = key_hint::plain(KeyCode::Char('n'));
+const KEY_P: KeyBinding = key_hint::plain(KeyCode::Char('p'));
+const KEY_O: KeyBinding = key_hint::plain(KeyCode::Char('o'));
+const KEY_Y: KeyBinding = key_hint::plain(KeyCode::Char('y'));
+const KEY_A: KeyBinding = key_hint::plain(KeyCode::Char('a'));
+const KEY_R: KeyBinding = key_hint::plain(KeyCode::Char('r'));
+const KEY_ENTER: KeyBinding = key_hint::plain(KeyCode::Enter);
+const KEY_Q: KeyBinding = key_hint::plain(KeyCode::Char('q'));
+const KEY_ESC: KeyBinding = key_hint::plain(KeyCode::Esc);
+const KEY_CTRL_C: KeyBinding = key_hint::ctrl(KeyCode::Char('c'));
+
+/// Rows above the panes (summary and a blank line) and below them (status
+/// and key hints).
+const HEADER_ROWS: u16 = 2;
+const FOOTER_ROWS: u16 = 2;
+
+/// The file list is hidden below this width so the diff stays readable.
+const MIN_WIDTH_FOR_FILE_LIST: u16 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiffLineKind {
+    Context,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiffHunk {
+    /// The `@@ -a,b +c,d @@` line, including any section heading.
+    pub(crate) header: String,
+    /// The line ranges `header` describes.
+    pub(crate) range: HunkRange,
+    pub(crate) lines: Vec<(DiffLineKind, String)>,
+}
+
+impl DiffHunk {
+    /// A hunk starting at `line`, or `None` when it is not an `@@` header.
+    fn from_header(line: &str) -> Option<Self> {
+        Some(Self {
+            header: line.to_string(),
+            range: parse_hunk_header(line)?,
+            lines: Vec::new(),
+        })
+    }
+
+    fn old_start(&self) -> usize {
+        self.range.old_start as usize
+    }
+
+    pub(crate) fn new_start(&self) -> usize {
+        self.range.new_start as usize
+    }
+
+    fn push_line(&mut self, line: &str) {
+        let kind = match line.chars().next() {
+            Some('+') => DiffLineKind::Insert,
+            Some('-') => DiffLineKind::Delete,
+            Some(' ') | None => DiffLineKind::Context,
+            // "\ No newline at end of file"
+            _ => return,
+        };
+        self.lines
+            .push((kind, line.get(1..).unwrap_or_default().to_string()));
+    }
+
+    /// The header and signed lines, as in a unified diff.
+    pub(crate) fn patch_text(&self) -> String {
+        let mut text = format!("{}\n", self.header);
+        for (kind, line) in &self.lines {
+            let sign = match kind {
+                DiffLineKind::Context => ' ',
+                DiffLineKind::Insert => '+',
+                DiffLineKind::Delete => '-',
+            };
+            text.push(sign);
+            text.push_str(line);
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FileStatus {
+    Modified,
+    Added,
+    Deleted,
+    Renamed { from: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileDiff {
+    /// Path relative to the repository root (the old path for deletions).
+    pub(crate) path: String,
+    pub(crate) status: FileStatus,
+    pub(crate) binary: bool,
+    pub(crate) hunks: Vec<DiffHunk>,
+}
+
+impl FileDiff {
+    fn counts(&self) -> (usize, usize) {
+        self.hunks.iter().flat_map(|hunk| hunk.lines.iter()).fold(
+            (0, 0),
+            |(added, removed), (kind, _)| match kind {
+                DiffLineKind::Insert => (added + 1, removed),
+                DiffLineKind::Delete => (added, removed + 1),
+                DiffLineKind::Context => (added, removed),
+            },
+        )
+    }
+
+    /// `hunk` as a single-hunk patch.
+    fn hunk_patch(&self, hunk: &DiffHunk) -> String {
+        let old_path = match &self.status {
+            FileStatus::Added => "/dev/null".to_string(),
+            FileStatus::Renamed { from } => format!("a/{from}"),
+            FileStatus::Modified | FileStatus::Deleted => format!("a/{}", self.path),
+        };
+        let new_path = match self.status {
+            FileStatus::Deleted => "/dev/null".to_string(),
+            _ => format!("b/{}", self.path),
+        };
+        format!("--- {old_path}\n+++ {new_path}\n{}", hunk.patch_text())
+    }
+}
+
+/// Splits uncolored `git diff` output into files and hunks.
+pub(crate) fn parse_git_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff {
+                path: path_from_diff_git_line(rest),
+                status: FileStatus::Modified,
+                binary: false,
+                hunks: Vec::new(),
+            });
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+        if let Some(hunk) = DiffHunk::from_header(line) {
+            file.hunks.push(hunk);
+        } else if let Some(hunk) = file.hunks.last_mut() {
+            hunk.push_line(line);
+        } else if line.starts_with("new file mode") {
+            file.status = FileStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.status = FileStatus::Deleted;
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            file.status = FileStatus::Renamed {
+                from: unquote(from),
+            };
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            file.path = unquote(to);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            if path != "/dev/null" {
+                file.path = strip_side_prefix(&unquote(path), "b/");
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            if path != "/dev/null" {
+                file.path = strip_side_prefix(&unquote(path), "a/");
+            }
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        }
+    }
+    files
+}
+
+/// Splits a unified diff without file headers, like the `unified_diff` of a
+/// patch Codex applied, into hunks.
+pub(crate) fn parse_hunks(diff: &str) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    for line in diff.lines() {
+        if let Some(hunk) = DiffHunk::from_header(line) {
+            hunks.push(hunk);
+        } else if let Some(hunk) = hunks.last_mut() {
+            hunk.push_line(line);
+        }
+    }
+    hunks
+}
+
+/// Best guess from `a/<path> b/<path>`; the `---`/`+++` lines, when present,
+/// replace it.
+fn path_from_diff_git_line(rest: &str) -> String {
+    let rest = unquote(rest);
+    match rest.rfind(" b/") {
+        Some(idx) => rest[idx + 3..].to_string(),
+        None => strip_side_prefix(&rest, "a/"),
+    }
+}
+
+fn strip_side_prefix(path: &str, prefix: &str) -> String {
+    path.strip_prefix(prefix).unwrap_or(path).to_string()
+}
+
+/// Drops the quotes git puts around paths with unusual characters.
+fn unquote(path: &str) -> String {
+    path.trim_matches('"').to_string()
+}
+
+/// The selected file's diff, rendered once per selection.
+#[derive(Default)]
+struct RenderedFile {
+    lines: Vec<Line<'static>>,
+    /// Row of each hunk header in `lines`.
+    hunk_rows: Vec<usize>,
+}
+
+pub(crate) struct DiffViewerOverlay {
+    files: Vec<FileDiff>,
+    repo_root: PathBuf,
+    selected: usize,
+    rendered: RenderedFile,
+    scroll: usize,
+    /// Diff rows shown at the last draw, for paging.
+    page_height: usize,
+    list_scroll: usize,
+    status: Option<String>,
+    /// Set when reviewing an applied patch; parallel to `files`.
+    review: Option<Vec<ReviewFile>>,
+    app_event_tx: AppEventSender,
+    is_done: bool,
+}
+
+impl DiffViewerOverlay {
+    /// `files` must not be empty; paths are relative to `repo_root`.
+    pub(crate) fn new(
+        files: Vec<FileDiff>,
+        repo_root: PathBuf,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        let rendered = files
+            .first()
+            .map(|file| render_file(file, None))
+            .unwrap_or_default();
+        Self {
+            files,
+            repo_root,
+            selected: 0,
+            rendered,
+            scroll: 0,
+            page_height: 1,
+            list_scroll: 0,
+            status: None,
+            review: None,
+            app_event_tx,
+            is_done: false,
+        }
+    }
+
+    /// Reviews an applied patch: `a` keeps the current hunk, `r` rejects it,
+    /// and Enter sends the decisions back as
+    /// [`AppEvent::HunkReviewFinished`]. `files` must not be empty.
+    pub(crate) fn new_review(files: Vec<ReviewFile>, app_event_tx: AppEventSender) -> Self {
+        let diffs = files.iter().map(|file| file.diff.clone()).collect();
+        let mut overlay = Self::new(diffs, PathBuf::new(), app_event_tx);
+        overlay.review = Some(files);
+        overlay.rendered = overlay.render_selected();
+        overlay
+    }
+
+    pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
+        match event {
+            TuiEvent::Key(key_event) => {
+                self.handle_key_event(key_event);
+                tui.frame_requester().schedule_frame();
+                Ok(())
+            }
+            TuiEvent::Draw => {
+                tui.draw(u16::MAX, |frame| {
+                    self.render(frame.area(), frame.buffer);
+                })?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        self.status = None;
+        let reviewing = self.review.is_some();
+        match key_event {
+            e if KEY_Q.is_press(e) || KEY_ESC.is_press(e) || KEY_CTRL_C.is_press(e) => {
+                self.is_done = true;
+            }
+            e if reviewing && KEY_A.is_press(e) => self.decide_current_hunk(false),
+            e if reviewing && KEY_R.is_press(e) => self.decide_current_hunk(true),
+            e if reviewing && KEY_ENTER.is_press(e) => self.finish_review(),
+            e if KEY_UP.is_press(e) || KEY_K.is_press(e) => self.scroll_by(-1),
+            e if KEY_DOWN.is_press(e) || KEY_J.is_press(e) => self.scroll_by(1),
+            e if KEY_PAGE_UP.is_press(e) => self.scroll_by(-(self.page_height as isize)),
+            e if KEY_PAGE_DOWN.is_press(e) || KEY_SPACE.is_press(e) => {
+                self.scroll_by(self.page_height as isize);
+            }
+            e if KEY_HOME.is_press(e) => self.scroll = 0,
+            e if KEY_END.is_press(e) => self.scroll = self.max_scroll(),
+            e if KEY_LEFT.is_press(e) || KEY_H.is_press(e) => {
+                self.select_file(self.selected.saturating_sub(1));
+            }
+            e if KEY_RIGHT.is_press(e) || KEY_L.is_press(e) => {
+                self.select_file(self.selected + 1);
+            }
+            e if KEY_N.is_press(e) => self.jump_to_hunk(true),
+            e if KEY_P.is_press(e) => self.jump_to_hunk(false),
+            // Leaving for the editor would drop the review.
+            e if !reviewing && KEY_O.is_press(e) => self.open_selected_file(),
+            e if KEY_Y.is_press(e) => self.copy_current_hunk(),
+            _ => {}
+        }
+    }
+
+    fn selected_file(&self) -> Option<&FileDiff> {
+        self.files.get(self.selected)
+    }
+
+    fn select_file(&mut self, index: usize) {
+        if index >= self.files.len() || index == self.selected {
+            return;
+        }
+        self.selected = index;
+        self.scroll = 0;
+        self.rendered = self.render_selected();
+    }
+
+    fn render_selected(&self) -> RenderedFile {
+        let rejected = self
+            .review
+            .as_ref()
+            .and_then(|review| review.get(self.selected))
+            .map(|file| file.rejected.as_slice());
+        self.selected_file()
+            .map(|file| render_file(file, rejected))
+            .unwrap_or_default()
+    }
+
+    /// The last hunk header can always reach the top row, so every hunk can
+    /// become the current one.
+    fn max_scroll(&self) -> usize {
+        let last_hunk = self.rendered.hunk_rows.last().copied().unwrap_or(0);
+        self.rendered
+            .lines
+            .len()
+            .saturating_sub(self.page_height)
+            .max(last_hunk)
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    /// The hunk at the top of the view, or the first one above the first
+    /// hunk header.
+    fn current_hunk(&self) -> Option<usize> {
+        if self.rendered.hunk_rows.is_empty() {
+            return None;
+        }
+        Some(
+            self.rendered
+                .hunk_rows
+                .iter()
+                .rposition(|row| *row <= self.scroll)
+                .unwrap_or(0),
+        )
+    }
+
+    /// Scrolls to the next hunk, or back to the start of the current one
+    /// (then the previous one).
+    fn jump_to_hunk(&mut self, forward: bool) {
+        let Some(current) = self.current_hunk() else {
+            return;
+        };
+        let rows = &self.rendered.hunk_rows;
+        let target = if forward {
+            rows.get(current + 1)
+        } else if rows.get(current).is_some_and(|row| self.scroll > *row) {
+            rows.get(current)
+        } else {
+            current.checked_sub(1).and_then(|idx| rows.get(idx))
+        };
+        if let Some(row) = target {
+            self.scroll = *row;
+        }
+    }
+
+    /// Marks the current hunk and moves on to the next one, across files.
+    fn decide_current_hunk(&mut self, rejected: bool) {
+        let Some(hunk) = self.current_hunk() else {
+            return;
+        };
+        if let Some(flag) = self
+            .review
+            .as_mut()
+            .and_then(|review| review.get_mut(self.selected))
+            .and_then(|file| file.rejected.get_mut(hunk))
+        {
+            *flag = rejected;
+        }
+        self.rendered = self.render_selected();
+        if let Some(row) = self.rendered.hunk_rows.get(hunk + 1) {
+            self.scroll = *row;
+        } else if self.selected + 1 < self.files.len() {
+            self.select_file(self.selected + 1);
+        } else {
+            self.status = Some("That was the last hunk. Press Enter to finish.".to_string());
+        }
+    }
+
+    fn finish_review(&mut self) {
+        if let Some(review) = self.review.take() {
+            self.app_event_tx.send(AppEvent::HunkReviewFinished(review));
+        }
+        self.is_done = true;
+    }
+
+    fn open_selected_file(&mut self) {
+        let Some(file) = self.selected_file() else {
+            return;
+        };
+        if file.status == FileStatus::Deleted {
+            self.status = Some("This file was deleted.".to_string());
+            return;
+        }
+        let path = self.repo_root.join(&file.path);
+        self.app_event_tx
+            .send(AppEvent::OpenFilesInEditor(vec![path]));
+        self.is_done = true;
+    }
+
+    fn current_hunk_patch(&self) -> Option<String> {
+        let file = self.selected_file()?;
+        let hunk = file.hunks.get(self.current_hunk()?)?;
+        Some(file.hunk_patch(hunk))
+    }
+
+    fn copy_current_hunk(&mut self) {
+        let Some(patch) = self.current_hunk_patch() else {
+            self.status = Some("No hunk to copy.".to_string());
+            return;
+        };
+        self.status = Some(match copy_to_clipboard(&patch) {
+            Ok(CopyMethod::Native) => "Copied hunk to the clipboard.".to_string(),
+            Ok(CopyMethod::Terminal) => "Sent hunk to the terminal clipboard (OSC 52).".to_string(),
+            Err(err) => format!("Failed to copy hunk: {err}"),
+        });
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        Paragraph::new(self.summary_line()).render(Rect::new(area.x, area.y, area.width, 1), buf);
+
+        let body_height = area.height.saturating_sub(HEADER_ROWS + FOOTER_ROWS);
+        let body_y = area.y + HEADER_ROWS.min(area.height);
+        let list_width = if area.width >= MIN_WIDTH_FOR_FILE_LIST {
+            (area.width / 3).clamp(20, 40)
+        } else {
+            0
+        };
+        if list_width > 0 {
+            self.render_file_list(Rect::new(area.x, body_y, list_width, body_height), buf);
+        }
+        let diff_x = area.x + list_width + u16::from(list_width > 0) * 2;
+        let diff_area = Rect::new(
+            diff_x,
+            body_y,
+            area.width.saturating_sub(diff_x - area.x),
+            body_height,
+        );
+        self.page_height = usize::from(body_height).max(1);
+        self.scroll = self.scroll.min(self.max_scroll());
+        self.render_diff(diff_area, buf);
+
+        let footer_y = area.y + area.height.saturating_sub(FOOTER_ROWS);
+        if let Some(status) = &self.status {
+            Paragraph::new(Line::from(format!(" {status}")))
+                .render(Rect::new(area.x, footer_y, area.width, 1), buf);
+        }
+        let hints: Vec<(&[KeyBinding], &str)> = if self.review.is_some() {
+            vec![
+                (&[KEY_A], "to keep"),
+                (&[KEY_R], "to reject"),
+                (&[KEY_N, KEY_P], "for next/previous hunk"),
+                (&[KEY_LEFT, KEY_RIGHT], "to switch file"),
+                (&[KEY_ENTER], "to revert rejected hunks"),
+                (&[KEY_Q], "to keep all"),
+            ]
+        } else {
+            vec![
+                (&[KEY_UP, KEY_DOWN], "to scroll"),
+                (&[KEY_LEFT, KEY_RIGHT], "to switch file"),
+                (&[KEY_N, KEY_P], "for next/previous hunk"),
+                (&[KEY_O], "to open"),
+                (&[KEY_Y], "to copy hunk"),
+                (&[KEY_Q], "to quit"),
+            ]
+        };
+        render_key_hints(
+            Rect::new(area.x, footer_y.saturating_add(1), area.width, 1),
+            buf,
+            &hints,
+        );
+    }
+
+    fn summary_line(&self) -> Line<'static> {
+        let (added, removed) = self
+            .files
+            .iter()
+            .map(FileDiff::counts)
+            .fold((0, 0), |(a, r), (fa, fr)| (a + fa, r + fr));
+        let noun = if self.files.len() == 1 {
+            "file"
+        } else {
+            "files"
+        };
+        let mut spans = vec![
+            if self.review.is_some() {
+                " Review".bold()
+            } else {
+                " Diff".bold()
+            },
+            format!(" · {} {noun} changed ", self.files.len()).into(),
+            format!("+{added}").green(),
+            " ".into(),
+            format!("-{removed}").red(),
+        ];
+        if let Some(review) = &self.review {
+            let hunks: usize = review.iter().map(|file| file.rejected.len()).sum();
+            let rejected: usize = review.iter().map(ReviewFile::rejected_count).sum();
+            let text = format!(" · {rejected} of {hunks} hunks rejected");
+            spans.push(if rejected > 0 { text.red() } else { text.dim() });
+        }
+        Line::from(spans)
+    }
+
+    fn render_file_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let height = usize::from(area.height);
+        if self.selected < self.list_scroll {
+            self.list_scroll = self.selected;
+        } else if height > 0 && self.selected >= self.list_scroll + height {
+            self.list_scroll = self.selected + 1 - height;
+        }
+        let lines: Vec<Line<'static>> = self
+            .files
+            .iter()
+            .enumerate()
+            .skip(self.list_scroll)
+            .take(height)
+            .map(|(idx, file)| {
+                let rejected = self
+                    .review
+                    .as_ref()
+                    .and_then(|review| review.get(idx))
+                    .map_or(0, ReviewFile::rejected_count);
+                file_list_line(file, idx == self.selected, rejected, area.width)
+            })
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    fn render_diff(&self, area: Rect, buf: &mut Buffer) {
+        let current = self
+            .current_hunk()
+            .and_then(|idx| self.rendered.hunk_rows.get(idx).copied());
+        let lines: Vec<Line<'static>> = self
+            .rendered
+            .lines
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(usize::from(area.height))
+            .map(|(row, line)| {
+                if Some(row) == current {
+                    line.clone().cyan()
+                } else {
+                    line.clone()
+                }
+            })
+            .collect();
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+fn file_list_line(file: &FileDiff, selected: bool, rejected: usize, width: u16) -> Line<'static> {
+    let marker: Span<'static> = if selected { "› ".cyan() } else { "  ".into() };
+    let status = match file.status {
+        FileStatus::Modified => "M ".into(),
+        FileStatus::Added => "A ".green(),
+        FileStatus::Deleted => "D ".red(),
+        FileStatus::Renamed { .. } => "R ".into(),
+    };
+    let (added, removed) = file.counts();
+    let counts = format!(" +{added} -{removed}");
+    let rejected = if rejected > 0 {
+        format!(" ✗{rejected}")
+    } else {
+        String::new()
+    };
+    let name_width = usize::from(width)
+        .saturating_sub(4 + counts.len() + rejected.chars().count())
+        .max(1);
+    let name = truncate_start(&file.path, name_width);
+    let name: Span<'static> = if selected { name.cyan() } else { name.into() };
+    Line::from(vec![marker, status, name, counts.dim(), rejected.red()])
+}
+
+/// Keeps the end of `path`, which holds the file name, when it is too long.
+fn truncate_start(path: &str, width: usize) -> String {
+    let count = path.chars().count();
+    if count <= width {
+        return path.to_string();
+    }
+    let keep = width.saturating_sub(1);
+    format!("…{}", path.chars().skip(count - keep).collect::<String>())
+}
+
+/// `rejected` marks each hunk as kept or rejected when reviewing a patch.
+fn render_file(file: &FileDiff, rejected: Option<&[bool]>) -> RenderedFile {
+    let (added, removed) = file.counts();
+    let mut title: Vec<Span<'static>> = vec![file.path.clone().bold()];
+    match &file.status {
+        FileStatus::Modified => {}
+        FileStatus::Added => title.push(" (new file)".green()),
+        FileStatus::Deleted => title.push(" (deleted)".red()),
+        FileStatus::Renamed { from } => title.push(format!(" (renamed from {from})").dim()),
+    }
+    title.push(format!("  +{added} -{removed}").dim());
+    let mut lines = vec![Line::from(title)];
+    let mut hunk_rows = Vec::new();
+
+    if file.binary {
+        lines.push("Binary file not shown.".italic().into());
+    } else if file.hunks.is_empty() {
+        lines.push("No content changes.".italic().into());
+    }
+
+    let language = CodeLanguage::from_path(Path::new(&file.path));
+    let max_line = file
+        .hunks
+        .iter()
+        .map(|hunk| hunk.old_start().max(hunk.new_start()) + hunk.lines.len())
+        .max()
+        .unwrap_or(0);
+    let number_width = max_line.to_string().len();
+    for (idx, hunk) in file.hunks.iter().enumerate() {
+        lines.push(Line::from(""));
+        hunk_rows.push(lines.len());
+        let decision = rejected.and_then(|rejected| rejected.get(idx)).copied();
+        let mut header = vec![hunk.header.clone().dim()];
+        match decision {
+            Some(true) => header.push("  ✗ rejected".red()),
+            Some(false) => header.push("  ✓ kept".green()),
+            None => {}
+        }
+        lines.push(Line::from(header));
+        let body = render_hunk(hunk, language, number_width);
+        if decision == Some(true) {
+            lines.extend(body.into_iter().map(|line| line.dim().crossed_out()));
+        } else {
+            lines.extend(body);
+        }
+    }
+    RenderedFile { lines, hunk_rows }
+}
+
+fn render_hunk(
+    hunk: &DiffHunk,
+    language: Option<CodeLanguage>,
+    number_width: usize,
+) -> Vec<Line<'static>> {
+    let code = hunk
+        .lines
+        .iter()
+        .map(|(_, text)| text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut highlighted = language
+        .map(|language| highlight_code_to_lines(language, &code))
+        .filter(|lines| lines.len() == hunk.lines.len())
+        .unwrap_or_else(|| {
+            hunk.lines
+                .iter()
+                .map(|(_, text)| Line::from(text.clone()))
+                .collect()
+        })
+        .into_iter();
+
+    let mut old_line = hunk.old_start();
+    let mut new_line = hunk.new_start();
+    let mut out = Vec::with_capacity(hunk.lines.len());
+    for (kind, _) in &hunk.lines {
+        let (number, sign, style) = match kind {
+            DiffLineKind::Context => {
+                old_line += 1;
+                new_line += 1;
+                (new_line - 1, ' ', Style::default())
+            }
+            DiffLineKind::Insert => {
+                new_line += 1;
+                (new_line - 1, '+', Style::default().fg(Color::Green))
+            }
+            DiffLineKind::Delete => {
+                old_line += 1;
+                (old_line - 1, '-', Style::default().fg(Color::Red))
+            }
+        };
+        let mut spans = vec![
+            format!("{number:>number_width$} ").dim(),
+            Span::styled(sign.to_string(), style),
+        ];
+        if let Some(code) = highlighted.next() {
+            spans.extend(
+                code.spans
+                    .into_iter()
+                    .map(|span| Span::styled(span.content, style.patch(span.style))),
+            );
+        }
+        out.push(Line::from(spans));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hunk_review::review_files;
+    use codex_core::protocol::FileChange;
+    use crossterm::event::KeyModifiers;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    const DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@ mod a;
+ fn one() {}
+-fn two() {}
++fn two() -> u8 { 2 }
+ fn three() {}
+@@ -10,2 +10,3 @@
+ fn ten() {}
++fn eleven() {}
+ fn twelve() {}
+\\ No newline at end of file
+diff --git a/notes.txt b/notes.txt
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/notes.txt
+@@ -0,0 +1 @@
++hello
+diff --git a/old.py b/old.py
+deleted file mode 100644
+--- a/old.py
++++ /dev/null
+@@ -1 +0,0 @@
+-print('bye')
+diff --git a/logo.png b/logo.png
+Binary files a/logo.png and b/logo.png differ
+";
+
+    fn press(overlay: &mut DiffViewerOverlay, code: KeyCode) {
+        overlay.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn parses_files_statuses_and_hunks() {
+        let files = parse_git_diff(DIFF);
+
+        assert_eq!(
+            files
+                .iter()
+                .map(|file| (file.path.as_str(), file.status.clone(), file.binary))
+                .collect::<Vec<_>>(),
+            vec![
+                ("src/lib.rs", FileStatus::Modified, false),
+                ("notes.txt", FileStatus::Added, false),
+                ("old.py", FileStatus::Deleted, false),
+                ("logo.png", FileStatus::Modified, true),
+            ]
+        );
+        let lib = &files[0];
+        assert_eq!(lib.counts(), (2, 1));
+        assert_eq!(
+            lib.hunks
+                .iter()
+                .map(|hunk| (hunk.old_start(), hunk.new_start(), hunk.lines.len()))
+                .collect::<Vec<_>>(),
+            vec![(1, 1, 4), (10, 10, 3)]
+        );
+        assert_eq!(
+            lib.hunks[0].lines[2],
+            (DiffLineKind::Insert, "fn two() -> u8 { 2 }".to_string())
+        );
+    }
+
+    #[test]
+    fn renames_keep_both_paths() {
+        let files = parse_git_diff(
+            "diff --git a/a.rs b/b.rs\nsimilarity index 90%\nrename from a.rs\nrename to b.rs\n",
+        );
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "b.rs");
+        assert_eq!(
+            files[0].status,
+            FileStatus::Renamed {
+                from: "a.rs".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn hunk_navigation_and_patch_copy() {
+        let (tx, _rx) = unbounded_channel();
+        let mut overlay = DiffViewerOverlay::new(
+            parse_git_diff(DIFF),
+            PathBuf::from("/repo"),
+            AppEventSender::new(tx),
+        );
+        overlay.page_height = 3;
+
+        assert_eq!(overlay.current_hunk(), Some(0));
+        press(&mut overlay, KeyCode::Char('n'));
+        assert_eq!(overlay.current_hunk(), Some(1));
+        assert_eq!(
+            overlay.current_hunk_patch().as_deref(),
+            Some(
+                "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -10,2 +10,3 @@\n fn ten() {}\n+fn eleven() {}\n fn twelve() {}\n"
+            )
+        );
+        press(&mut overlay, KeyCode::Char('p'));
+        assert_eq!(overlay.current_hunk(), Some(0));
+
+        press(&mut overlay, KeyCode::Right);
+        assert_eq!(overlay.scroll, 0);
+        assert_eq!(
+            overlay.current_hunk_patch().as_deref(),
+            Some("--- /dev/null\n+++ b/notes.txt\n@@ -0,0 +1 @@\n+hello\n")
+        );
+    }
+
+    #[test]
+    fn open_sends_the_path_under_the_repo_root() {
+        let (tx, mut rx) = unbounded_channel();
+        let mut overlay = DiffViewerOverlay::new(
+            parse_git_diff(DIFF),
+            PathBuf::from("/repo"),
+            AppEventSender::new(tx),
+        );
+
+        press(&mut overlay, KeyCode::Char('o'));
+        match rx.try_recv() {
+            Ok(AppEvent::OpenFilesInEditor(paths)) => {
+                assert_eq!(paths, vec![PathBuf::from("/repo/src/lib.rs")]);
+            }
+            other => panic!("expected an open request, got {other:?}"),
+        }
+        assert!(overlay.is_done());
+    }
+
+    #[test]
+    fn review_marks_hunks_and_sends_the_decisions() {
+        let (tx, mut rx) = unbounded_channel();
+        let changes = HashMap::from([
+            (
+                PathBuf::from("/repo/src/lib.rs"),
+                FileChange::Update {
+                    unified_diff:
+                        "@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n@@ -10,2 +10,3 @@\n j\n+k\n l\n"
+                            .to_string(),
+                    move_path: None,
+                },
+            ),
+            (
+                PathBuf::from("/repo/notes.txt"),
+                FileChange::Add {
+                    content: "hello\n".to_string(),
+                },
+            ),
+        ]);
+        let mut overlay = DiffViewerOverlay::new_review(
+            review_files(&changes, Path::new("/repo")),
+            AppEventSender::new(tx),
+        );
+        overlay.page_height = 3;
+
+        // Deciding moves on to the next hunk, across files.
+        press(&mut overlay, KeyCode::Char('r'));
+        assert_eq!(
+            overlay.selected_file().map(|file| file.path.as_str()),
+            Some("src/lib.rs")
+        );
+        press(&mut overlay, KeyCode::Char('a'));
+        assert_eq!(overlay.current_hunk(), Some(1));
+        press(&mut overlay, KeyCode::Char('r'));
+        assert!(overlay.status.is_some());
+        assert!(
+            overlay
+                .summary_line()
+                .spans
+                .iter()
+                .any(|span| span.content == " · 2 of 3 hunks rejected")
+        );
+
+        press(&mut overlay, KeyCode::Enter);
+        match rx.try_recv() {
+            Ok(AppEvent::HunkReviewFinished(files)) => assert_eq!(
+                files
+                    .iter()
+                    .map(|file| file.rejected.clone())
+                    .collect::<Vec<_>>(),
+                vec![vec![true], vec![false, true]]
+            ),
+            other => panic!("expected the review decisions, got {other:?}"),
+        }
+        assert!(overlay.is_done());
+    }
+
+    #[test]
+    fn diff_lines_have_numbers_signs_and_colors() {
+        let files = parse_git_diff(DIFF);
+        let rendered = render_file(&files[0], None);
+        let text = |line: &Line<'_>| -> String {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect()
+        };
+
+        let insert = &rendered.lines[rendered.hunk_rows[0] + 3];
+        assert_eq!(text(insert), " 2 +fn two() -> u8 { 2 }");
+        assert!(
+            insert.spans[1..]
+                .iter()
+                .all(|span| span.style.fg == Some(Color::Green))
+        );
+        let delete = &rendered.lines[rendered.hunk_rows[0] + 2];
+        assert_eq!(text(delete), " 2 -fn two() {}");
+        assert_eq!(
+            truncate_start("src/deeply/nested/file.rs", 10),
+            "…d/file.rs"
+        );
+    }
+}
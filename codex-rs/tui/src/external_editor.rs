@@ -90,6 +90,37 @@ pub(crate) async fn run_editor(seed: &str, editor_cmd: &[String]) -> Result<Stri
     Ok(contents)
 }
 
+/// Opens `paths` in the editor and waits for it to exit.
+pub(crate) async fn open_files(paths: &[std::path::PathBuf], editor_cmd: &[String]) -> Result<()> {
+    if editor_cmd.is_empty() {
+        return Err(Report::msg("editor command is empty"));
+    }
+
+    let mut cmd = {
+        #[cfg(windows)]
+        {
+            Command::new(resolve_windows_program(&editor_cmd[0]))
+        }
+        #[cfg(not(windows))]
+        {
+            Command::new(&editor_cmd[0])
+        }
+    };
+    let status = cmd
+        .args(&editor_cmd[1..])
+        .args(paths)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .await?;
+
+    if !status.success() {
+        return Err(Report::msg(format!("editor exited with status {status}")));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,9 +17,18 @@
 //!    recent query.
 //! 4. If there is a in-flight search that is not a prefix of the latest thing
 //!    the user typed, it is cancelled.
+//!
+//! Queries starting with `#` (`@#name`) search symbol definitions instead of
+//! file names and are answered with `AppEvent::SymbolSearchResult`. Other
+//! queries that look like an identifier search both: the file results are
+//! sent first, then a shorter list of symbols.
 
+use codex_core::code_outline::SymbolMatch;
+use codex_core::code_outline::with_shared_index;
+use codex_core::git_info::get_git_repo_root;
 use codex_file_search as file_search;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -34,6 +43,9 @@ use crate::app_event_sender::AppEventSender;
 const MAX_FILE_SEARCH_RESULTS: NonZeroUsize = NonZeroUsize::new(20).unwrap();
 const NUM_FILE_SEARCH_THREADS: NonZeroUsize = NonZeroUsize::new(2).unwrap();
 
+/// Symbols listed after the files for a plain `@name` query.
+const MAX_SYMBOLS_WITH_FILES: usize = 8;
+
 /// How long to wait after a keystroke before firing the first search when none
 /// is currently running. Keeps early queries more meaningful.
 const FILE_SEARCH_DEBOUNCE: Duration = Duration::from_millis(100);
@@ -164,6 +176,16 @@ impl FileSearchManager {
     ) {
         let compute_indices = true;
         std::thread::spawn(move || {
+            if let Some(symbol_query) = query.strip_prefix('#') {
+                let matches =
+                    search_symbols(symbol_query, &search_dir, MAX_FILE_SEARCH_RESULTS.get());
+                if !cancellation_token.load(Ordering::Relaxed) {
+                    tx.send(AppEvent::SymbolSearchResult { query, matches });
+                }
+                FileSearchManager::finish_search(&search_state, &cancellation_token);
+                return;
+            }
+
             let matches = file_search::run(
                 &query,
                 MAX_FILE_SEARCH_RESULTS,
@@ -177,23 +199,88 @@ impl FileSearchManager {
             .map(|res| res.matches)
             .unwrap_or_default();
 
-            let is_cancelled = cancellation_token.load(Ordering::Relaxed);
-            if !is_cancelled {
-                tx.send(AppEvent::FileSearchResult { query, matches });
+            if cancellation_token.load(Ordering::Relaxed) {
+                FileSearchManager::finish_search(&search_state, &cancellation_token);
+                return;
             }
+            tx.send(AppEvent::FileSearchResult {
+                query: query.clone(),
+                matches,
+            });
 
-            // Reset the active search state. Do a pointer comparison to verify
-            // that we are clearing the ActiveSearch that corresponds to the
-            // cancellation token we were given.
-            {
-                #[expect(clippy::unwrap_used)]
-                let mut st = search_state.lock().unwrap();
-                if let Some(active_search) = &st.active_search
-                    && Arc::ptr_eq(&active_search.cancellation_token, &cancellation_token)
-                {
-                    st.active_search = None;
+            if looks_like_identifier(&query) {
+                let matches = search_symbols(&query, &search_dir, MAX_SYMBOLS_WITH_FILES);
+                if !cancellation_token.load(Ordering::Relaxed) {
+                    tx.send(AppEvent::SymbolSearchResult { query, matches });
                 }
             }
+
+            FileSearchManager::finish_search(&search_state, &cancellation_token);
         });
     }
+
+    /// Reset the active search state. Do a pointer comparison to verify
+    /// that we are clearing the ActiveSearch that corresponds to the
+    /// cancellation token we were given.
+    fn finish_search(search_state: &Mutex<SearchState>, cancellation_token: &Arc<AtomicBool>) {
+        #[expect(clippy::unwrap_used)]
+        let mut st = search_state.lock().unwrap();
+        if let Some(active_search) = &st.active_search
+            && Arc::ptr_eq(&active_search.cancellation_token, cancellation_token)
+        {
+            st.active_search = None;
+        }
+    }
+}
+
+/// Definitions matching `query` in the project containing `search_dir`, with
+/// paths made relative to `search_dir` so the inserted location resolves
+/// like a file mention.
+fn search_symbols(query: &str, search_dir: &Path, limit: usize) -> Vec<SymbolMatch> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let root = get_git_repo_root(search_dir).unwrap_or_else(|| search_dir.to_path_buf());
+    let matches = match with_shared_index(&root, |index| index.search(query, limit)) {
+        Ok(matches) => matches,
+        Err(err) => {
+            tracing::warn!("symbol search failed: {err}");
+            return Vec::new();
+        }
+    };
+    matches
+        .into_iter()
+        .map(|mut found| {
+            let absolute = root.join(&found.path);
+            found.path = absolute
+                .strip_prefix(search_dir)
+                .unwrap_or(&absolute)
+                .to_string_lossy()
+                .into_owned();
+            found
+        })
+        .collect()
+}
+
+/// Whether a plain `@` query could name a symbol, so it is worth searching
+/// the symbol index too. Paths (`src/`, `main.rs`) are left to file search.
+fn looks_like_identifier(query: &str) -> bool {
+    query.chars().count() >= 2
+        && query
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_identifier_queries_search_symbols() {
+        assert!(looks_like_identifier("parse_frontmatter"));
+        assert!(looks_like_identifier("Config"));
+        assert!(!looks_like_identifier("p"));
+        assert!(!looks_like_identifier("src/lib"));
+        assert!(!looks_like_identifier("main.rs"));
+    }
 }
@@ -14,15 +14,20 @@ use tokio::process::Command;
 ///
 /// * `bool` – Whether the current working directory is inside a Git repo.
 /// * `String` – The concatenated diff (may be empty).
-pub(crate) async fn get_git_diff() -> io::Result<(bool, String)> {
+///
+/// With `color`, the diff carries ANSI colors for display; without it, the
+/// diff is plain text that can be applied as a patch.
+pub(crate) async fn get_git_diff(color: bool) -> io::Result<(bool, String)> {
     // First check if we are inside a Git repository.
     if !inside_git_repo().await? {
         return Ok((false, String::new()));
     }
 
+    let color_flag = if color { "--color" } else { "--no-color" };
+
     // Run tracked diff and untracked file listing in parallel.
     let (tracked_diff_res, untracked_output_res) = tokio::join!(
-        run_git_capture_diff(&["diff", "--color"]),
+        run_git_capture_diff(&["diff", color_flag]),
         run_git_capture_stdout(&["ls-files", "--others", "--exclude-standard"]),
     );
     let tracked_diff = tracked_diff_res?;
@@ -45,7 +50,7 @@ pub(crate) async fn get_git_diff() -> io::Result<(bool, String)> {
         let null_path = null_path.clone();
         let file = file.to_string();
         join_set.spawn(async move {
-            let args = ["diff", "--color", "--no-index", "--", &null_path, &file];
+            let args = ["diff", color_flag, "--no-index", "--", &null_path, &file];
             run_git_capture_diff(&args).await
         });
     }
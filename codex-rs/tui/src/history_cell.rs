@@ -26,8 +26,10 @@ use crate::render::line_utils::line_to_static;
 use crate::render::line_utils::prefix_lines;
 use crate::render::line_utils::push_owned_lines;
 use crate::render::renderable::Renderable;
+use crate::status::format_tokens_compact;
 use crate::style::user_message_style;
 use crate::text_formatting::format_and_truncate_tool_result;
+use crate::text_formatting::format_json_compact;
 use crate::text_formatting::truncate_text;
 use crate::tooltips;
 use crate::ui_consts::LIVE_PREFIX_COLS;
@@ -41,14 +43,24 @@ use codex_common::format_env_display::format_env_display;
 use codex_core::config::Config;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::FollowUpSuggestion;
+use codex_core::protocol::HostedToolCallEvent;
+use codex_core::protocol::HostedToolKind;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::SubAgentTokenUsage;
+use codex_core::protocol::ToolTokenUsage;
+use codex_protocol::approvals::ApprovalJournalEntry;
+use codex_protocol::approvals::ApprovalSource;
 use codex_protocol::config_types::CollaborationMode;
 use codex_protocol::openai_models::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::plan_tool::PlanItemArg;
+use codex_protocol::plan_tool::PlanRisk;
+use codex_protocol::plan_tool::ProposedPlan;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
+use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::user_input::TextElement;
 use crossterm::event::KeyCode;
 use image::DynamicImage;
@@ -657,6 +669,158 @@ pub(crate) fn new_unified_exec_processes_output(processes: Vec<String>) -> Compo
     CompositeHistoryCell::new(vec![Box::new(command), Box::new(summary)])
 }
 
+/// Render the `/why-last` explanation for recent approval journal entries.
+pub(crate) fn new_approval_journal_output(entries: Vec<ApprovalJournalEntry>) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec!["/why-last".magenta().into()];
+    if entries.is_empty() {
+        lines.push(
+            "  • No commands or patches have been approved in this session yet."
+                .italic()
+                .into(),
+        );
+        return PlainHistoryCell { lines };
+    }
+
+    for entry in entries.into_iter().rev() {
+        let subject = truncate_exec_snippet(&entry.subject);
+        lines.push(
+            vec![
+                "  • ".dim(),
+                subject.cyan(),
+                format!(" ({})", entry.tool_name).dim(),
+            ]
+            .into(),
+        );
+        let (explanation, hint) = approval_source_explanation(&entry.source);
+        lines.push(vec!["    ".into(), explanation.into()].into());
+        if let Some(hint) = hint {
+            lines.push(vec!["    ".into(), hint.dim()].into());
+        }
+    }
+    PlainHistoryCell { lines }
+}
+
+/// Render the `/tool-usage` breakdown of tokens per tool and per subagent.
+pub(crate) fn new_tool_usage_output(
+    tools: &[ToolTokenUsage],
+    subagents: &[SubAgentTokenUsage],
+) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec!["/tool-usage".magenta().into()];
+    if tools.is_empty() && subagents.is_empty() {
+        lines.push("  • No tool calls in this session yet.".italic().into());
+        return PlainHistoryCell { lines };
+    }
+
+    for tool in tools {
+        let calls = if tool.calls == 1 { "call" } else { "calls" };
+        lines.push(
+            vec![
+                "  • ".dim(),
+                tool.tool_name.clone().cyan(),
+                format!(" ({} {calls})", tool.calls).dim(),
+                format!(
+                    "  {} output · {} follow-up",
+                    format_tokens_compact(tool.output_tokens),
+                    format_tokens_compact(tool.follow_up_tokens)
+                )
+                .into(),
+            ]
+            .into(),
+        );
+    }
+    for subagent in subagents {
+        let runs = if subagent.runs == 1 { "run" } else { "runs" };
+        lines.push(
+            vec![
+                "  • ".dim(),
+                format!("@{}", subagent.name).cyan(),
+                format!(" ({} {runs})", subagent.runs).dim(),
+                format!(
+                    "  {} total",
+                    format_tokens_compact(subagent.token_usage.total_tokens)
+                )
+                .into(),
+            ]
+            .into(),
+        );
+    }
+    lines.push(
+        "  Output tokens are estimated; follow-up tokens are the completions that answered them."
+            .dim()
+            .into(),
+    );
+    PlainHistoryCell { lines }
+}
+
+fn approval_source_explanation(source: &ApprovalSource) -> (String, Option<&'static str>) {
+    match source {
+        ApprovalSource::PolicyRule {
+            matched_prefix,
+            justification,
+        } => {
+            let prefix = matched_prefix.join(" ");
+            let explanation = match justification {
+                Some(justification) => {
+                    format!("Auto-approved by execpolicy rule `{prefix}`: {justification}")
+                }
+                None => format!("Auto-approved by execpolicy rule `{prefix}`"),
+            };
+            (
+                explanation,
+                Some("Edit the .rules files under $CODEX_HOME/rules to change this."),
+            )
+        }
+        ApprovalSource::CommandRule { rule } => {
+            let explanation = match &rule.reason {
+                Some(reason) => format!("Auto-approved by command rule `{}`: {reason}", rule.rule),
+                None => format!("Auto-approved by command rule `{}`", rule.rule),
+            };
+            (
+                explanation,
+                Some(
+                    "Edit [[command_rules]] in config.toml or .codex/policies.toml to change this.",
+                ),
+            )
+        }
+        ApprovalSource::KnownSafeCommand => (
+            "Auto-approved: on the built-in list of read-only commands".to_string(),
+            Some("Known-safe commands still run inside the sandbox."),
+        ),
+        ApprovalSource::ReadOnlyCommand => (
+            "Auto-approved: classified as a read-only command".to_string(),
+            Some("Read-only commands always run inside the sandbox."),
+        ),
+        ApprovalSource::SessionAllowlist => (
+            "Auto-approved: you approved it earlier for the rest of this session".to_string(),
+            Some("Start a new session to reset session approvals."),
+        ),
+        ApprovalSource::ModeDefault { approval_policy } => (
+            format!(
+                "Auto-approved by the `{approval_policy}` approval policy in the current sandbox mode"
+            ),
+            Some("Use /approvals to require a prompt for these."),
+        ),
+        ApprovalSource::McpToolBlocked => (
+            "Blocked by the MCP server's `tool_approvals` config".to_string(),
+            Some("Edit the server's entry under [mcp_servers] in config.toml to change this."),
+        ),
+        ApprovalSource::User { decision } => {
+            let explanation = match decision {
+                ReviewDecision::Approved => "You approved it",
+                ReviewDecision::ApprovedExecpolicyAmendment { .. } => {
+                    "You approved it and added an execpolicy rule for similar commands"
+                }
+                ReviewDecision::ApprovedForSession => {
+                    "You approved it for the rest of this session"
+                }
+                ReviewDecision::Denied => "You declined it",
+                ReviewDecision::Abort => "You declined it and stopped the turn",
+            };
+            (explanation.to_string(), None)
+        }
+    }
+}
+
 fn truncate_exec_snippet(full_cmd: &str) -> String {
     let mut snippet = match full_cmd.split_once('\n') {
         Some((first, _)) => format!("{first} ..."),
@@ -1265,10 +1429,16 @@ impl McpToolCallCell {
         self.result = Some(Err("interrupted".to_string()));
     }
 
-    fn render_content_block(block: &mcp_types::ContentBlock, width: usize) -> String {
+    /// Formats a result block; `truncate` limits text to the few lines the
+    /// main view shows, while the transcript keeps it whole.
+    fn render_content_block(
+        block: &mcp_types::ContentBlock,
+        width: usize,
+        truncate: bool,
+    ) -> String {
         match block {
             mcp_types::ContentBlock::TextContent(text) => {
-                format_and_truncate_tool_result(&text.text, TOOL_CALL_MAX_LINES, width)
+                format_tool_result(&text.text, width, truncate)
             }
             mcp_types::ContentBlock::ImageContent(_) => "<image content>".to_string(),
             mcp_types::ContentBlock::AudioContent(_) => "<audio content>".to_string(),
@@ -1288,6 +1458,23 @@ impl McpToolCallCell {
 
 impl HistoryCell for McpToolCallCell {
     fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+        self.lines(width, true)
+    }
+
+    fn transcript_lines(&self, width: u16) -> Vec<Line<'static>> {
+        self.lines(width, false)
+    }
+
+    fn transcript_animation_tick(&self) -> Option<u64> {
+        if !self.animations_enabled || self.result.is_some() {
+            return None;
+        }
+        Some((self.start_time.elapsed().as_millis() / 50) as u64)
+    }
+}
+
+impl McpToolCallCell {
+    fn lines(&self, width: u16, truncate: bool) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = Vec::new();
         let status = self.success();
         let bullet = match status {
@@ -1333,7 +1520,8 @@ impl HistoryCell for McpToolCallCell {
                 Ok(mcp_types::CallToolResult { content, .. }) => {
                     if !content.is_empty() {
                         for block in content {
-                            let text = Self::render_content_block(block, detail_wrap_width);
+                            let text =
+                                Self::render_content_block(block, detail_wrap_width, truncate);
                             for segment in text.split('\n') {
                                 let line = Line::from(segment.to_string().dim());
                                 let wrapped = word_wrap_line(
@@ -1348,11 +1536,8 @@ impl HistoryCell for McpToolCallCell {
                     }
                 }
                 Err(err) => {
-                    let err_text = format_and_truncate_tool_result(
-                        &format!("Error: {err}"),
-                        TOOL_CALL_MAX_LINES,
-                        width as usize,
-                    );
+                    let err_text =
+                        format_tool_result(&format!("Error: {err}"), width as usize, truncate);
                     let err_line = Line::from(err_text.dim());
                     let wrapped = word_wrap_line(
                         &err_line,
@@ -1376,12 +1561,13 @@ impl HistoryCell for McpToolCallCell {
 
         lines
     }
+}
 
-    fn transcript_animation_tick(&self) -> Option<u64> {
-        if !self.animations_enabled || self.result.is_some() {
-            return None;
-        }
-        Some((self.start_time.elapsed().as_millis() / 50) as u64)
+fn format_tool_result(text: &str, width: usize, truncate: bool) -> String {
+    if truncate {
+        format_and_truncate_tool_result(text, TOOL_CALL_MAX_LINES, width)
+    } else {
+        format_json_compact(text).unwrap_or_else(|| text.to_string())
     }
 }
 
@@ -1398,6 +1584,39 @@ pub(crate) fn new_web_search_call(query: String) -> PrefixedWrappedHistoryCell {
     PrefixedWrappedHistoryCell::new(text, "• ".dim(), "  ")
 }
 
+/// Completed provider-hosted tool call: the code or queries it ran and the
+/// first lines of what came back.
+pub(crate) fn new_hosted_tool_call(event: HostedToolCallEvent) -> PlainHistoryCell {
+    let title = match event.tool {
+        HostedToolKind::CodeInterpreter => "Ran code interpreter",
+        HostedToolKind::FileSearch => "Searched files",
+    };
+    let mut header: Vec<Span<'static>> = vec!["• ".dim(), title.bold(), " (hosted)".dim()];
+    if let Some(status) = event.status.filter(|status| status != "completed") {
+        header.push(format!(" {status}").red());
+    }
+    let mut lines = vec![Line::from(header)];
+    for line in event.input.lines().take(TOOL_CALL_MAX_LINES) {
+        lines.push(Line::from(vec!["  │ ".dim(), line.to_string().into()]));
+    }
+    let output = event
+        .output
+        .lines()
+        .map(str::to_string)
+        .chain(
+            event
+                .image_urls
+                .into_iter()
+                .map(|url| format!("image: {url}")),
+        )
+        .take(TOOL_CALL_MAX_LINES);
+    for (index, line) in output.enumerate() {
+        let prefix = if index == 0 { "  └ " } else { "    " };
+        lines.push(Line::from(vec![prefix.dim(), line.dim()]));
+    }
+    PlainHistoryCell::new(lines)
+}
+
 /// If the first content is an image, return a new cell with the image.
 /// TODO(rgwood-dd): Handle images properly even if they're not the first result.
 fn try_new_completed_mcp_tool_call_with_image_output(
@@ -1670,6 +1889,28 @@ pub(crate) fn new_info_event(message: String, hint: Option<String>) -> PlainHist
     PlainHistoryCell { lines }
 }
 
+/// Next steps the agent suggested, as numbered chips on one line.
+pub(crate) fn new_follow_up_suggestions(suggestions: &[FollowUpSuggestion]) -> PlainHistoryCell {
+    let mut chips: Vec<Span<'static>> = vec!["  ".into()];
+    for (idx, suggestion) in suggestions.iter().enumerate() {
+        if idx > 0 {
+            chips.push("  ".into());
+        }
+        chips.push(format!("[{}] {}", idx + 1, suggestion.title).cyan());
+    }
+    let lines: Vec<Line<'static>> = vec![
+        vec![
+            "• ".dim(),
+            "Suggested follow-ups".bold(),
+            " ".into(),
+            "/follow-ups to edit or queue one".dark_gray(),
+        ]
+        .into(),
+        chips.into(),
+    ];
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_error_event(message: String) -> PlainHistoryCell {
     // Use a hair space (U+200A) to create a subtle, near-invisible separation
     // before the text. VS16 is intentionally omitted to keep spacing tighter
@@ -1679,6 +1920,64 @@ pub(crate) fn new_error_event(message: String) -> PlainHistoryCell {
 }
 
 /// Render a user‑friendly plan update styled like a checkbox todo list.
+/// Lines describing a plan submitted in plan mode: summary, numbered steps,
+/// affected files, and risk.
+pub(crate) fn proposed_plan_lines(plan: &ProposedPlan) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line<'static>> = vec![Line::from(plan.summary.clone())];
+    lines.push(Line::from(""));
+    for (idx, step) in plan.steps.iter().enumerate() {
+        lines.push(Line::from(vec![
+            format!("{}. ", idx + 1).dim(),
+            step.clone().into(),
+        ]));
+    }
+    if !plan.affected_files.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            "Files: ".dim(),
+            plan.affected_files.join(", ").into(),
+        ]));
+    }
+    let risk = match plan.risk {
+        PlanRisk::Low => "low".green(),
+        PlanRisk::Medium => "medium".yellow(),
+        PlanRisk::High => "high".red().bold(),
+    };
+    let mut risk_line = vec!["Risk: ".dim(), risk];
+    if let Some(notes) = plan.risk_notes.as_ref().filter(|notes| !notes.is_empty()) {
+        risk_line.push(format!(" — {notes}").dim());
+    }
+    lines.push(Line::from(risk_line));
+    lines
+}
+
+pub(crate) fn new_plan_decision_cell(plan: &ProposedPlan, approved: bool) -> PlainHistoryCell {
+    let header: Line<'static> = if approved {
+        vec![
+            "✔ ".green(),
+            "You ".into(),
+            "approved".bold(),
+            " the plan".into(),
+        ]
+        .into()
+    } else {
+        vec![
+            "✗ ".red(),
+            "You ".into(),
+            "declined".bold(),
+            " the plan".into(),
+        ]
+        .into()
+    };
+    let mut lines = vec![header];
+    lines.extend(prefix_lines(
+        proposed_plan_lines(plan),
+        "  └ ".dim(),
+        "    ".into(),
+    ));
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_plan_update(update: UpdatePlanArgs) -> PlanUpdateCell {
     let UpdatePlanArgs { explanation, plan } = update;
     PlanUpdateCell { explanation, plan }
@@ -1856,7 +2155,7 @@ impl HistoryCell for FinalMessageSeparator {
     }
 }
 
-fn format_mcp_invocation<'a>(invocation: McpInvocation) -> Line<'a> {
+pub(crate) fn format_mcp_invocation<'a>(invocation: McpInvocation) -> Line<'a> {
     let args_str = invocation
         .arguments
         .as_ref()
@@ -1952,6 +2251,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn approval_journal_output_explains_policy_rule() {
+        let cell = new_approval_journal_output(vec![ApprovalJournalEntry {
+            call_id: "call-1".to_string(),
+            turn_id: "turn-1".to_string(),
+            tool_name: "shell".to_string(),
+            subject: "cargo test -p codex-core".to_string(),
+            source: ApprovalSource::PolicyRule {
+                matched_prefix: vec!["cargo".to_string(), "test".to_string()],
+                justification: Some("tests are safe".to_string()),
+            },
+            approved: true,
+            timestamp: "2025-01-01T00:00:00Z".to_string(),
+        }]);
+        let lines = render_transcript(&cell);
+        assert_eq!(
+            lines,
+            vec![
+                "/why-last",
+                "  • cargo test -p codex-core (shell)",
+                "    Auto-approved by execpolicy rule `cargo test`: tests are safe",
+                "    Edit the .rules files under $CODEX_HOME/rules to change this.",
+            ],
+        );
+    }
+
+    #[test]
+    fn approval_journal_output_when_empty() {
+        let cell = new_approval_journal_output(Vec::new());
+        let lines = render_transcript(&cell);
+        assert_eq!(
+            lines,
+            vec![
+                "/why-last",
+                "  • No commands or patches have been approved in this session yet.",
+            ],
+        );
+    }
+
     #[test]
     fn ps_output_empty_snapshot() {
         let cell = new_unified_exec_processes_output(Vec::new());
@@ -2006,6 +2344,8 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            default_tool_approval: None,
+            tool_approvals: None,
         };
         let mut servers = config.mcp_servers.get().clone();
         servers.insert("docs".to_string(), stdio_config);
@@ -2027,6 +2367,8 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            default_tool_approval: None,
+            tool_approvals: None,
         };
         servers.insert("http".to_string(), http_config);
         config
@@ -2339,6 +2681,39 @@ mod tests {
         insta::assert_snapshot!(rendered);
     }
 
+    #[test]
+    fn completed_mcp_tool_call_transcript_keeps_full_output() {
+        let invocation = McpInvocation {
+            server: "logs".into(),
+            tool: "tail".into(),
+            arguments: None,
+        };
+        let text = (1..=100)
+            .map(|n| format!("log line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let result = CallToolResult {
+            content: vec![ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text,
+                r#type: "text".into(),
+            })],
+            is_error: None,
+            structured_content: None,
+        };
+
+        let mut cell = new_active_mcp_tool_call("call-7".into(), invocation, true);
+        assert!(
+            cell.complete(Duration::from_millis(10), Ok(result))
+                .is_none()
+        );
+
+        let display = render_lines(&cell.display_lines(80)).join("\n");
+        let transcript = render_lines(&cell.transcript_lines(80)).join("\n");
+        assert!(!display.contains("log line 100"));
+        assert!(transcript.contains("log line 100"));
+    }
+
     #[test]
     fn session_header_includes_reasoning_level_when_present() {
         let cell = SessionHeaderHistoryCell::new(
@@ -0,0 +1,446 @@
+//! Hunk-by-hunk review of a patch Codex applied (`/hunks`, or the "review
+//! each hunk" option when approving a patch).
+//!
+//! The patch is already on disk when the review opens, so keeping a hunk
+//! needs no work and rejecting one reverse-applies it: the hunk's new lines
+//! are located in the file and replaced with its old lines. Hunks are
+//! reverted bottom-up so the line numbers of earlier hunks stay valid, and a
+//! hunk whose lines moved is found by searching outward from where the patch
+//! put it.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::protocol::DiffHunk as HunkRange;
+use codex_core::protocol::FileChange;
+
+use crate::diff_render::display_path_for;
+use crate::diff_viewer::DiffHunk;
+use crate::diff_viewer::DiffLineKind;
+use crate::diff_viewer::FileDiff;
+use crate::diff_viewer::FileStatus;
+use crate::diff_viewer::parse_hunks;
+
+/// One file of the patch under review.
+#[derive(Debug, Clone)]
+pub(crate) struct ReviewFile {
+    /// The patched file on disk (the destination of a move).
+    pub(crate) path: PathBuf,
+    pub(crate) diff: FileDiff,
+    /// One flag per hunk of `diff`.
+    pub(crate) rejected: Vec<bool>,
+    /// Contents to write back when the deletion of the file is rejected.
+    restore: Option<String>,
+}
+
+impl ReviewFile {
+    pub(crate) fn rejected_count(&self) -> usize {
+        self.rejected.iter().filter(|rejected| **rejected).count()
+    }
+}
+
+/// The files of an applied patch, sorted by path, with every hunk kept.
+pub(crate) fn review_files(changes: &HashMap<PathBuf, FileChange>, cwd: &Path) -> Vec<ReviewFile> {
+    let mut files: Vec<ReviewFile> = changes
+        .iter()
+        .map(|(path, change)| review_file(path, change, cwd))
+        .collect();
+    files.sort_by(|a, b| a.diff.path.cmp(&b.diff.path));
+    files
+}
+
+fn review_file(path: &Path, change: &FileChange, cwd: &Path) -> ReviewFile {
+    let display_path = display_path_for(path, cwd);
+    let (path, diff, restore) = match change {
+        FileChange::Add { content } => (
+            path.to_path_buf(),
+            FileDiff {
+                path: display_path,
+                status: FileStatus::Added,
+                binary: false,
+                hunks: vec![whole_file_hunk(content, DiffLineKind::Insert)],
+            },
+            None,
+        ),
+        FileChange::Delete { content } => (
+            path.to_path_buf(),
+            FileDiff {
+                path: display_path,
+                status: FileStatus::Deleted,
+                binary: false,
+                hunks: vec![whole_file_hunk(content, DiffLineKind::Delete)],
+            },
+            Some(content.clone()),
+        ),
+        FileChange::Update {
+            unified_diff,
+            move_path,
+        } => {
+            let hunks = parse_hunks(unified_diff);
+            match move_path {
+                Some(dest) => (
+                    dest.clone(),
+                    FileDiff {
+                        path: display_path_for(dest, cwd),
+                        status: FileStatus::Renamed { from: display_path },
+                        binary: false,
+                        hunks,
+                    },
+                    None,
+                ),
+                None => (
+                    path.to_path_buf(),
+                    FileDiff {
+                        path: display_path,
+                        status: FileStatus::Modified,
+                        binary: false,
+                        hunks,
+                    },
+                    None,
+                ),
+            }
+        }
+    };
+    ReviewFile {
+        path,
+        rejected: vec![false; diff.hunks.len()],
+        diff,
+        restore,
+    }
+}
+
+/// An added or deleted file as a single hunk.
+fn whole_file_hunk(content: &str, kind: DiffLineKind) -> DiffHunk {
+    let lines: Vec<(DiffLineKind, String)> = content
+        .lines()
+        .map(|line| (kind, line.to_string()))
+        .collect();
+    let count = u32::try_from(lines.len()).unwrap_or(u32::MAX);
+    let range = if kind == DiffLineKind::Delete {
+        HunkRange {
+            old_start: 1,
+            old_lines: count,
+            new_start: 0,
+            new_lines: 0,
+        }
+    } else {
+        HunkRange {
+            old_start: 0,
+            old_lines: 0,
+            new_start: 1,
+            new_lines: count,
+        }
+    };
+    DiffHunk {
+        header: format!(
+            "@@ -{},{} +{},{} @@",
+            range.old_start, range.old_lines, range.new_start, range.new_lines
+        ),
+        range,
+        lines,
+    }
+}
+
+/// What reverting the rejected hunks did.
+#[derive(Debug, Default)]
+pub(crate) struct RevertOutcome {
+    /// One entry per reverted hunk, in the words sent back to the agent.
+    pub(crate) reverted: Vec<String>,
+    /// Hunks that stayed applied, and why.
+    pub(crate) failures: Vec<String>,
+}
+
+impl RevertOutcome {
+    /// Tells the agent which changes were undone so it does not redo them.
+    pub(crate) fn feedback_message(&self) -> Option<String> {
+        if self.reverted.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "I reviewed your last patch hunk by hunk and rejected part of it. \
+             These changes were reverted on disk:\n\n{}\n\n\
+             The rest of the patch is kept. Do not reapply the rejected changes unless I ask for them.",
+            self.reverted.join("\n\n")
+        ))
+    }
+}
+
+/// Reverts every rejected hunk in `files` on disk.
+pub(crate) fn revert_rejected(files: &[ReviewFile]) -> RevertOutcome {
+    let mut outcome = RevertOutcome::default();
+    for file in files {
+        let rejected: Vec<&DiffHunk> = file
+            .diff
+            .hunks
+            .iter()
+            .zip(&file.rejected)
+            .filter(|(_, rejected)| **rejected)
+            .map(|(hunk, _)| hunk)
+            .collect();
+        if rejected.is_empty() {
+            continue;
+        }
+        let display_path = &file.diff.path;
+        match file.diff.status {
+            FileStatus::Added => match fs::remove_file(&file.path) {
+                Ok(()) => outcome
+                    .reverted
+                    .push(format!("- `{display_path}`: the new file was removed")),
+                Err(err) => outcome
+                    .failures
+                    .push(format!("Could not remove {display_path}: {err}")),
+            },
+            FileStatus::Deleted => {
+                let content = file.restore.as_deref().unwrap_or_default();
+                let written = match file.path.parent() {
+                    Some(parent) => fs::create_dir_all(parent),
+                    None => Ok(()),
+                }
+                .and_then(|()| fs::write(&file.path, content));
+                match written {
+                    Ok(()) => outcome
+                        .reverted
+                        .push(format!("- `{display_path}`: the deleted file was restored")),
+                    Err(err) => outcome
+                        .failures
+                        .push(format!("Could not restore {display_path}: {err}")),
+                }
+            }
+            FileStatus::Modified | FileStatus::Renamed { .. } => {
+                let content = match fs::read_to_string(&file.path) {
+                    Ok(content) => content,
+                    Err(err) => {
+                        outcome
+                            .failures
+                            .push(format!("Could not read {display_path}: {err}"));
+                        continue;
+                    }
+                };
+                let (reverted, missed) = revert_hunks(&content, rejected.clone());
+                if let Err(err) = fs::write(&file.path, reverted) {
+                    outcome
+                        .failures
+                        .push(format!("Could not write {display_path}: {err}"));
+                    continue;
+                }
+                for hunk in rejected {
+                    if missed.iter().any(|missed| std::ptr::eq(*missed, hunk)) {
+                        outcome.failures.push(format!(
+                            "Could not find {} in {display_path}; it stays applied",
+                            hunk.header
+                        ));
+                    } else {
+                        outcome.reverted.push(format!(
+                            "- `{display_path}`:\n```diff\n{}```",
+                            hunk.patch_text()
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    outcome
+}
+
+/// Reverse-applies `hunks` to `content`, returning the new contents and the
+/// hunks whose lines could not be found.
+fn revert_hunks<'a>(content: &str, mut hunks: Vec<&'a DiffHunk>) -> (String, Vec<&'a DiffHunk>) {
+    let crlf = content.contains("\r\n");
+    let body = content.strip_suffix('\n').unwrap_or(content);
+    let mut lines: Vec<String> = if content.is_empty() {
+        Vec::new()
+    } else {
+        body.split('\n').map(str::to_string).collect()
+    };
+
+    hunks.sort_by_key(|hunk| Reverse(hunk.new_start()));
+    let mut missed = Vec::new();
+    for hunk in hunks {
+        let side = |skip: DiffLineKind| -> Vec<&str> {
+            hunk.lines
+                .iter()
+                .filter(|(kind, _)| *kind != skip)
+                .map(|(_, text)| text.as_str())
+                .collect()
+        };
+        let new_lines = side(DiffLineKind::Delete);
+        let old_lines = side(DiffLineKind::Insert);
+        let Some(start) = locate(&lines, &new_lines, hunk.new_start()) else {
+            missed.push(hunk);
+            continue;
+        };
+        let line_ending = if crlf { "\r" } else { "" };
+        lines.splice(
+            start..start + new_lines.len(),
+            old_lines.iter().map(|line| format!("{line}{line_ending}")),
+        );
+    }
+
+    let mut reverted = lines.join("\n");
+    if !lines.is_empty() && (content.is_empty() || content.ends_with('\n')) {
+        reverted.push('\n');
+    }
+    (reverted, missed)
+}
+
+/// Where `needle` starts in `lines`, searching outward from the 1-based line
+/// `hint`. Carriage returns at the ends of `lines` are ignored.
+fn locate(lines: &[String], needle: &[&str], hint: usize) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(hint.min(lines.len()));
+    }
+    let last = lines.len().checked_sub(needle.len())?;
+    let matches_at = |start: usize| {
+        lines[start..start + needle.len()]
+            .iter()
+            .zip(needle)
+            .all(|(line, expected)| line.trim_end_matches('\r') == *expected)
+    };
+    let expected = hint.saturating_sub(1).min(last);
+    (0..=last)
+        .flat_map(|distance| [expected.checked_sub(distance), Some(expected + distance)])
+        .flatten()
+        .filter(|start| *start <= last)
+        .find(|start| matches_at(*start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    const UNIFIED_DIFF: &str = "\
+@@ -1,3 +1,3 @@
+ a
+-b
++B
+ c
+@@ -6,3 +6,4 @@
+ f
+ g
++inserted
+ h
+";
+
+    const OLD: &str = "a\nb\nc\nd\ne\nf\ng\nh\n";
+    const NEW: &str = "a\nB\nc\nd\ne\nf\ng\ninserted\nh\n";
+
+    fn update(path: &Path) -> HashMap<PathBuf, FileChange> {
+        HashMap::from([(
+            path.to_path_buf(),
+            FileChange::Update {
+                unified_diff: UNIFIED_DIFF.to_string(),
+                move_path: None,
+            },
+        )])
+    }
+
+    #[test]
+    fn reverting_hunks_restores_their_old_lines() {
+        let hunks = parse_hunks(UNIFIED_DIFF);
+        assert_eq!(revert_hunks(NEW, hunks.iter().collect()).0, OLD);
+        assert_eq!(
+            revert_hunks(NEW, vec![&hunks[1]]).0,
+            "a\nB\nc\nd\ne\nf\ng\nh\n"
+        );
+
+        // Lines that moved since the patch are still found, and CRLF files
+        // keep their line endings.
+        let shifted = format!("x\ny\n{}", NEW.replace('\n', "\r\n"));
+        assert_eq!(
+            revert_hunks(&shifted, vec![&hunks[0]]).0,
+            format!(
+                "x\ny\n{}",
+                "a\nb\nc\nd\ne\nf\ng\ninserted\nh\n".replace('\n', "\r\n")
+            )
+        );
+
+        let (unchanged, missed) = revert_hunks("nothing\nhere\n", vec![&hunks[0]]);
+        assert_eq!(unchanged, "nothing\nhere\n");
+        assert_eq!(missed, vec![&hunks[0]]);
+    }
+
+    #[test]
+    fn rejected_hunks_are_reverted_on_disk_and_reported() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("letters.txt");
+        fs::write(&path, NEW).expect("write");
+
+        let mut files = review_files(&update(&path), dir.path());
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].diff.path, "letters.txt");
+        assert_eq!(files[0].rejected, vec![false, false]);
+        files[0].rejected[1] = true;
+
+        let outcome = revert_rejected(&files);
+        assert_eq!(outcome.failures, Vec::<String>::new());
+        assert_eq!(
+            fs::read_to_string(&path).expect("read"),
+            "a\nB\nc\nd\ne\nf\ng\nh\n"
+        );
+        let message = outcome.feedback_message().expect("feedback");
+        assert!(
+            message
+                .contains("- `letters.txt`:\n```diff\n@@ -6,3 +6,4 @@\n f\n g\n+inserted\n h\n```")
+        );
+        assert!(!message.contains("+B"));
+    }
+
+    #[test]
+    fn rejecting_added_and_deleted_files_undoes_them() {
+        let dir = tempdir().expect("tempdir");
+        let added = dir.path().join("new.txt");
+        let deleted = dir.path().join("gone").join("old.txt");
+        fs::write(&added, "hello\n").expect("write");
+        let changes = HashMap::from([
+            (
+                added.clone(),
+                FileChange::Add {
+                    content: "hello\n".to_string(),
+                },
+            ),
+            (
+                deleted.clone(),
+                FileChange::Delete {
+                    content: "bye\n".to_string(),
+                },
+            ),
+        ]);
+
+        let mut files = review_files(&changes, dir.path());
+        assert_eq!(
+            files
+                .iter()
+                .map(|file| (file.diff.path.as_str(), file.diff.hunks[0].header.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("gone/old.txt", "@@ -1,1 +0,0 @@"),
+                ("new.txt", "@@ -0,0 +1,1 @@"),
+            ]
+        );
+        for file in &mut files {
+            file.rejected = vec![true];
+        }
+
+        let outcome = revert_rejected(&files);
+        assert_eq!(outcome.failures, Vec::<String>::new());
+        assert!(!added.exists());
+        assert_eq!(fs::read_to_string(&deleted).expect("read"), "bye\n");
+        assert_eq!(outcome.reverted.len(), 2);
+    }
+
+    #[test]
+    fn nothing_rejected_means_no_feedback() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("letters.txt");
+        fs::write(&path, NEW).expect("write");
+
+        let outcome = revert_rejected(&review_files(&update(&path), dir.path()));
+        assert_eq!(outcome.feedback_message(), None);
+        assert_eq!(fs::read_to_string(&path).expect("read"), NEW);
+    }
+}
@@ -92,19 +92,14 @@ where
     // fetch/restore the cursor position. insert_history_lines should be cursor-position-neutral :)
     queue!(writer, MoveTo(0, cursor_top))?;
 
+    let theme = crate::theme::colors();
     for line in wrapped {
         queue!(writer, Print("\r\n"))?;
         queue!(
             writer,
             SetColors(Colors::new(
-                line.style
-                    .fg
-                    .map(std::convert::Into::into)
-                    .unwrap_or(CColor::Reset),
-                line.style
-                    .bg
-                    .map(std::convert::Into::into)
-                    .unwrap_or(CColor::Reset)
+                theme.apply(line.style.fg.unwrap_or(Color::Reset)).into(),
+                theme.apply(line.style.bg.unwrap_or(Color::Reset)).into()
             ))
         )?;
         queue!(writer, Clear(ClearType::UntilNewLine))?;
@@ -245,6 +240,7 @@ fn write_spans<'a, I>(mut writer: &mut impl Write, content: I) -> io::Result<()>
 where
     I: IntoIterator<Item = &'a Span<'a>>,
 {
+    let theme = crate::theme::colors();
     let mut fg = Color::Reset;
     let mut bg = Color::Reset;
     let mut last_modifier = Modifier::empty();
@@ -265,7 +261,10 @@ where
         if next_fg != fg || next_bg != bg {
             queue!(
                 writer,
-                SetColors(Colors::new(next_fg.into(), next_bg.into()))
+                SetColors(Colors::new(
+                    theme.apply(next_fg).into(),
+                    theme.apply(next_bg).into()
+                ))
             )?;
             fg = next_fg;
             bg = next_bg;
@@ -26,6 +26,14 @@ impl KeyBinding {
         Self { key, modifiers }
     }
 
+    pub(crate) const fn code(&self) -> KeyCode {
+        self.key
+    }
+
+    pub(crate) const fn modifiers(&self) -> KeyModifiers {
+        self.modifiers
+    }
+
     pub fn is_press(&self, event: KeyEvent) -> bool {
         self.key == event.code
             && self.modifiers == event.modifiers
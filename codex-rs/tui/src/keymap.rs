@@ -0,0 +1,684 @@
+//! User-configurable keys for TUI actions (`[tui.keybindings]`).
+//!
+//! Key handlers keep matching the built-in keys. Before a handler sees an
+//! event, [`Keymap::translate`] rewrites a configured key into the built-in
+//! key of its action and drops built-in keys the user moved to another key.
+//! Approval shortcuts are rebound when the options are built, so the hints
+//! show the configured keys.
+//!
+//! The keymap is parsed and validated once at startup by [`install`] and read
+//! everywhere else through [`keymap`].
+
+use std::sync::LazyLock;
+use std::sync::OnceLock;
+
+use codex_core::config::types::KeyList;
+use codex_core::config::types::KeybindingsToml;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use thiserror::Error;
+
+use crate::key_hint;
+use crate::key_hint::KeyBinding;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyAction {
+    Submit,
+    Newline,
+    Interrupt,
+    NextTab,
+    PreviousTab,
+    ScrollUp,
+    ScrollDown,
+    PageUp,
+    PageDown,
+    Approve,
+    ApproveForSession,
+    Deny,
+}
+
+/// Where an action's keys are read. Two actions in the same context cannot
+/// share a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KeyContext {
+    /// The chat composer, including interrupting a running turn.
+    Composer,
+    /// The transcript and other full-screen pagers.
+    Pager,
+    /// An approval request in the bottom pane.
+    Approval,
+}
+
+impl KeyContext {
+    const ALL: [Self; 3] = [Self::Composer, Self::Pager, Self::Approval];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Composer => "Composer",
+            Self::Pager => "Transcript and pagers",
+            Self::Approval => "Approvals",
+        }
+    }
+
+    /// Keys the context handles itself; actions cannot be bound to them.
+    fn reserved(self) -> &'static [(KeyBinding, &'static str)] {
+        const COMPOSER: &[(KeyBinding, &str)] = &[
+            (key_hint::ctrl(KeyCode::Char('c')), "quits"),
+            (key_hint::ctrl(KeyCode::Char('t')), "opens the transcript"),
+            (
+                key_hint::ctrl(KeyCode::Char('f')),
+                "searches the transcript",
+            ),
+            (
+                key_hint::ctrl(KeyCode::Char('g')),
+                "opens the external editor",
+            ),
+        ];
+        const PAGER: &[(KeyBinding, &str)] = &[
+            (key_hint::plain(KeyCode::Char('q')), "closes the pager"),
+            (key_hint::ctrl(KeyCode::Char('c')), "closes the pager"),
+            (
+                key_hint::plain(KeyCode::Char('/')),
+                "searches the transcript",
+            ),
+            (
+                key_hint::plain(KeyCode::Char('y')),
+                "copies from the transcript",
+            ),
+            (key_hint::plain(KeyCode::Esc), "edits the previous message"),
+        ];
+        const APPROVAL: &[(KeyBinding, &str)] = &[
+            (
+                key_hint::plain(KeyCode::Enter),
+                "confirms the highlighted option",
+            ),
+            (key_hint::plain(KeyCode::Esc), "declines with feedback"),
+            (key_hint::ctrl(KeyCode::Char('c')), "declines with feedback"),
+            (key_hint::ctrl(KeyCode::Char('a')), "opens the full request"),
+            (
+                key_hint::plain(KeyCode::Char('p')),
+                "approves the command prefix",
+            ),
+            (key_hint::plain(KeyCode::Char('c')), "cancels a request"),
+            (
+                key_hint::plain(KeyCode::Char('r')),
+                "reviews each hunk of a patch",
+            ),
+        ];
+        match self {
+            Self::Composer => COMPOSER,
+            Self::Pager => PAGER,
+            Self::Approval => APPROVAL,
+        }
+    }
+}
+
+impl KeyAction {
+    pub(crate) const ALL: [Self; 12] = [
+        Self::Submit,
+        Self::Newline,
+        Self::Interrupt,
+        Self::NextTab,
+        Self::PreviousTab,
+        Self::ScrollUp,
+        Self::ScrollDown,
+        Self::PageUp,
+        Self::PageDown,
+        Self::Approve,
+        Self::ApproveForSession,
+        Self::Deny,
+    ];
+
+    /// Name of the action in `[tui.keybindings]`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Submit => "submit",
+            Self::Newline => "newline",
+            Self::Interrupt => "interrupt",
+            Self::NextTab => "next_tab",
+            Self::PreviousTab => "previous_tab",
+            Self::ScrollUp => "scroll_up",
+            Self::ScrollDown => "scroll_down",
+            Self::PageUp => "page_up",
+            Self::PageDown => "page_down",
+            Self::Approve => "approve",
+            Self::ApproveForSession => "approve_for_session",
+            Self::Deny => "deny",
+        }
+    }
+
+    fn context(self) -> KeyContext {
+        match self {
+            Self::Submit | Self::Newline | Self::Interrupt | Self::NextTab | Self::PreviousTab => {
+                KeyContext::Composer
+            }
+            Self::ScrollUp | Self::ScrollDown | Self::PageUp | Self::PageDown => KeyContext::Pager,
+            Self::Approve | Self::ApproveForSession | Self::Deny => KeyContext::Approval,
+        }
+    }
+
+    /// The key the handlers match for this action.
+    fn builtin(self) -> KeyBinding {
+        match self {
+            Self::Submit => key_hint::plain(KeyCode::Enter),
+            Self::Newline => key_hint::shift(KeyCode::Enter),
+            Self::Interrupt => key_hint::plain(KeyCode::Esc),
+            Self::NextTab => key_hint::ctrl(KeyCode::PageDown),
+            Self::PreviousTab => key_hint::ctrl(KeyCode::PageUp),
+            Self::ScrollUp => key_hint::plain(KeyCode::Up),
+            Self::ScrollDown => key_hint::plain(KeyCode::Down),
+            Self::PageUp => key_hint::plain(KeyCode::PageUp),
+            Self::PageDown => key_hint::plain(KeyCode::PageDown),
+            Self::Approve => key_hint::plain(KeyCode::Char('y')),
+            Self::ApproveForSession => key_hint::plain(KeyCode::Char('a')),
+            Self::Deny => key_hint::plain(KeyCode::Char('n')),
+        }
+    }
+
+    /// Keys bound when `[tui.keybindings]` leaves the action unset.
+    fn defaults(self) -> Vec<KeyBinding> {
+        match self {
+            Self::Newline => vec![self.builtin(), key_hint::ctrl(KeyCode::Char('j'))],
+            Self::ScrollUp => vec![self.builtin(), key_hint::plain(KeyCode::Char('k'))],
+            Self::ScrollDown => vec![self.builtin(), key_hint::plain(KeyCode::Char('j'))],
+            Self::PageUp => vec![
+                self.builtin(),
+                key_hint::shift(KeyCode::Char(' ')),
+                key_hint::ctrl(KeyCode::Char('b')),
+            ],
+            Self::PageDown => vec![
+                self.builtin(),
+                key_hint::plain(KeyCode::Char(' ')),
+                key_hint::ctrl(KeyCode::Char('f')),
+            ],
+            _ => vec![self.builtin()],
+        }
+    }
+
+    fn configured(self, toml: &KeybindingsToml) -> Option<&KeyList> {
+        match self {
+            Self::Submit => toml.submit.as_ref(),
+            Self::Newline => toml.newline.as_ref(),
+            Self::Interrupt => toml.interrupt.as_ref(),
+            Self::NextTab => toml.next_tab.as_ref(),
+            Self::PreviousTab => toml.previous_tab.as_ref(),
+            Self::ScrollUp => toml.scroll_up.as_ref(),
+            Self::ScrollDown => toml.scroll_down.as_ref(),
+            Self::PageUp => toml.page_up.as_ref(),
+            Self::PageDown => toml.page_down.as_ref(),
+            Self::Approve => toml.approve.as_ref(),
+            Self::ApproveForSession => toml.approve_for_session.as_ref(),
+            Self::Deny => toml.deny.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub(crate) enum KeymapError {
+    #[error("`{action}`: invalid key `{key}`: {reason}")]
+    InvalidKey {
+        action: &'static str,
+        key: String,
+        reason: String,
+    },
+    #[error("`{key}` is bound to both `{first}` and `{second}`")]
+    Conflict {
+        key: String,
+        first: &'static str,
+        second: &'static str,
+    },
+    #[error("`{action}`: `{key}` is reserved ({reason})")]
+    Reserved {
+        action: &'static str,
+        key: String,
+        reason: &'static str,
+    },
+}
+
+/// Effective keys for every action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Keymap {
+    bindings: Vec<(KeyAction, Vec<KeyBinding>)>,
+    /// Actions whose keys come from `[tui.keybindings]`.
+    customized: Vec<KeyAction>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: KeyAction::ALL
+                .into_iter()
+                .map(|action| (action, action.defaults()))
+                .collect(),
+            customized: Vec::new(),
+        }
+    }
+}
+
+impl Keymap {
+    pub(crate) fn from_config(toml: &KeybindingsToml) -> Result<Self, KeymapError> {
+        let mut keymap = Self::default();
+        for (action, keys) in &mut keymap.bindings {
+            let Some(configured) = action.configured(toml) else {
+                continue;
+            };
+            *keys = configured
+                .keys()
+                .iter()
+                .map(|key| {
+                    parse_key(key).map_err(|reason| KeymapError::InvalidKey {
+                        action: action.name(),
+                        key: key.clone(),
+                        reason,
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            keymap.customized.push(*action);
+        }
+        keymap.validate()?;
+        Ok(keymap)
+    }
+
+    fn validate(&self) -> Result<(), KeymapError> {
+        for (idx, (action, keys)) in self.bindings.iter().enumerate() {
+            for key in keys {
+                if self.customized.contains(action)
+                    && let Some((_, reason)) = action
+                        .context()
+                        .reserved()
+                        .iter()
+                        .find(|(reserved, _)| reserved == key)
+                {
+                    return Err(KeymapError::Reserved {
+                        action: action.name(),
+                        key: key_name(key),
+                        reason,
+                    });
+                }
+                let clash = self.bindings[..idx]
+                    .iter()
+                    .find(|(other, other_keys)| {
+                        other.context() == action.context() && other_keys.contains(key)
+                    })
+                    .or_else(|| {
+                        keys.iter()
+                            .filter(|other| *other == key)
+                            .nth(1)
+                            .map(|_| &self.bindings[idx])
+                    });
+                if let Some((other, _)) = clash {
+                    return Err(KeymapError::Conflict {
+                        key: key_name(key),
+                        first: other.name(),
+                        second: action.name(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn bindings(&self, action: KeyAction) -> &[KeyBinding] {
+        self.bindings
+            .iter()
+            .find(|(candidate, _)| *candidate == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn is_customized(&self, action: KeyAction) -> bool {
+        self.customized.contains(&action)
+    }
+
+    /// Whether `event` presses one of the keys bound to `action`.
+    pub(crate) fn matches(&self, action: KeyAction, event: KeyEvent) -> bool {
+        self.bindings(action).iter().any(|key| key.is_press(event))
+    }
+
+    /// Rewrites `event` for the handlers of `context`: a key bound to an
+    /// action becomes the action's built-in key, and a built-in key the user
+    /// moved elsewhere is dropped (`None`). Other keys pass through.
+    /// Interrupt is matched with [`Keymap::matches`] instead because Esc has
+    /// other uses in the composer, and so are the tab keys, which the app
+    /// handles before the composer sees them.
+    pub(crate) fn translate(&self, context: KeyContext, event: KeyEvent) -> Option<KeyEvent> {
+        let actions = || {
+            KeyAction::ALL.into_iter().filter(move |action| {
+                action.context() == context
+                    && !matches!(
+                        action,
+                        KeyAction::Interrupt | KeyAction::NextTab | KeyAction::PreviousTab
+                    )
+            })
+        };
+        if let Some(action) = actions().find(|action| self.matches(*action, event)) {
+            let builtin = action.builtin();
+            return Some(KeyEvent {
+                code: builtin.code(),
+                modifiers: builtin.modifiers(),
+                ..event
+            });
+        }
+        let moved = actions().any(|action| {
+            self.is_customized(action) && action.defaults().iter().any(|key| key.is_press(event))
+        });
+        (!moved).then_some(event)
+    }
+
+    /// Keys to use for a built-in approval shortcut: the configured keys of
+    /// the action it belongs to, or the key itself.
+    pub(crate) fn approval_keys(&self, builtin: KeyBinding) -> Vec<KeyBinding> {
+        KeyAction::ALL
+            .into_iter()
+            .find(|action| action.context() == KeyContext::Approval && action.builtin() == builtin)
+            .map_or_else(|| vec![builtin], |action| self.bindings(action).to_vec())
+    }
+
+    /// The `/keys` view: every action with its effective keys.
+    pub(crate) fn display_lines(&self) -> Vec<Line<'static>> {
+        let mut lines: Vec<Line<'static>> = vec!["/keys".magenta().into()];
+        let width = KeyAction::ALL
+            .iter()
+            .map(|action| action.name().len())
+            .max()
+            .unwrap_or_default();
+        for context in KeyContext::ALL {
+            lines.push("".into());
+            lines.push(Line::from(context.title().bold()));
+            for action in KeyAction::ALL
+                .into_iter()
+                .filter(|action| action.context() == context)
+            {
+                let mut spans: Vec<Span<'static>> =
+                    vec![format!("  {:<width$}  ", action.name()).into()];
+                let keys = self.bindings(action);
+                if keys.is_empty() {
+                    spans.push("unbound".dim());
+                }
+                for (idx, key) in keys.iter().enumerate() {
+                    if idx > 0 {
+                        spans.push(", ".dim());
+                    }
+                    spans.push(Span::from(key_name(key)).cyan());
+                }
+                if self.is_customized(action) {
+                    spans.push(" (custom)".dim());
+                }
+                lines.push(Line::from(spans));
+            }
+        }
+        lines.push("".into());
+        lines.push(
+            "Change these in [tui.keybindings] in config.toml."
+                .dim()
+                .into(),
+        );
+        lines
+    }
+}
+
+static INSTALLED: OnceLock<Keymap> = OnceLock::new();
+static DEFAULT: LazyLock<Keymap> = LazyLock::new(Keymap::default);
+
+/// Parses `[tui.keybindings]` and makes it the keymap for this process.
+pub(crate) fn install(toml: &KeybindingsToml) -> Result<(), KeymapError> {
+    let keymap = Keymap::from_config(toml)?;
+    let _ = INSTALLED.set(keymap);
+    Ok(())
+}
+
+/// The installed keymap, or the defaults before [`install`] (and in tests).
+pub(crate) fn keymap() -> &'static Keymap {
+    INSTALLED.get().unwrap_or(&DEFAULT)
+}
+
+fn key_name(key: &KeyBinding) -> String {
+    Span::from(key).content.into_owned()
+}
+
+/// Parses keys like `enter`, `ctrl-j`, `shift-enter`, `alt+x`, `f2` or `y`.
+fn parse_key(spec: &str) -> Result<KeyBinding, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = spec.trim();
+    loop {
+        let lower = rest.to_ascii_lowercase();
+        let Some((modifier, len)) = [
+            ("ctrl", KeyModifiers::CONTROL),
+            ("control", KeyModifiers::CONTROL),
+            ("alt", KeyModifiers::ALT),
+            ("option", KeyModifiers::ALT),
+            ("meta", KeyModifiers::ALT),
+            ("shift", KeyModifiers::SHIFT),
+        ]
+        .into_iter()
+        .find_map(|(name, modifier)| {
+            let after = lower.strip_prefix(name)?;
+            (after.len() > 1 && (after.starts_with('-') || after.starts_with('+')))
+                .then_some((modifier, name.len() + 1))
+        }) else {
+            break;
+        };
+        modifiers |= modifier;
+        rest = &rest[len..];
+    }
+
+    let code = match rest.to_ascii_lowercase().as_str() {
+        "" => return Err("no key given".to_string()),
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        "space" => KeyCode::Char(' '),
+        name => {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => return Err("unknown key name".to_string()),
+                },
+            }
+        }
+    };
+
+    // Terminals report shifted letters as the uppercase character with SHIFT.
+    let code = match code {
+        KeyCode::Char(c) if c.is_ascii_uppercase() => {
+            modifiers |= KeyModifiers::SHIFT;
+            KeyCode::Char(c)
+        }
+        KeyCode::Char(c) if c.is_ascii_lowercase() && modifiers.contains(KeyModifiers::SHIFT) => {
+            KeyCode::Char(c.to_ascii_uppercase())
+        }
+        code => code,
+    };
+    Ok(KeyBinding::new(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn list(keys: &[&str]) -> Option<KeyList> {
+        Some(KeyList::Many(
+            keys.iter().map(ToString::to_string).collect(),
+        ))
+    }
+
+    #[test]
+    fn keys_parse_with_modifiers_and_names() {
+        assert_eq!(parse_key("ctrl-j"), Ok(key_hint::ctrl(KeyCode::Char('j'))));
+        assert_eq!(
+            parse_key("Shift+Enter"),
+            Ok(key_hint::shift(KeyCode::Enter))
+        );
+        assert_eq!(
+            parse_key("ctrl-alt-x"),
+            Ok(key_hint::ctrl_alt(KeyCode::Char('x')))
+        );
+        assert_eq!(parse_key("Y"), Ok(key_hint::shift(KeyCode::Char('Y'))));
+        assert_eq!(
+            parse_key("shift-y"),
+            Ok(key_hint::shift(KeyCode::Char('Y')))
+        );
+        assert_eq!(parse_key("-"), Ok(key_hint::plain(KeyCode::Char('-'))));
+        assert_eq!(parse_key("ctrl--"), Ok(key_hint::ctrl(KeyCode::Char('-'))));
+        assert_eq!(parse_key("f5"), Ok(key_hint::plain(KeyCode::F(5))));
+        assert_eq!(parse_key("pgdn"), Ok(key_hint::plain(KeyCode::PageDown)));
+        assert!(parse_key("ctrl-").is_err());
+        assert!(parse_key("hyper-x").is_err());
+        assert!(parse_key("f99").is_err());
+    }
+
+    #[test]
+    fn swapped_submit_and_newline_translate_to_the_builtin_keys() {
+        let keymap = Keymap::from_config(&KeybindingsToml {
+            submit: list(&["ctrl-enter"]),
+            newline: list(&["enter"]),
+            ..Default::default()
+        })
+        .expect("valid keymap");
+
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        let ctrl_enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::CONTROL);
+        let shift_enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT);
+        let letter = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+
+        let ctrl_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL);
+
+        assert_eq!(
+            keymap.translate(KeyContext::Composer, enter),
+            Some(shift_enter)
+        );
+        assert_eq!(
+            keymap.translate(KeyContext::Composer, ctrl_enter),
+            Some(enter)
+        );
+        // Ctrl+J was a default newline key the user replaced.
+        assert_eq!(keymap.translate(KeyContext::Composer, ctrl_j), None);
+        assert_eq!(keymap.translate(KeyContext::Composer, letter), Some(letter));
+        // Other contexts are unaffected.
+        assert_eq!(keymap.translate(KeyContext::Pager, enter), Some(enter));
+    }
+
+    #[test]
+    fn defaults_pass_keys_through_unchanged() {
+        let keymap = Keymap::default();
+        let space = KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.translate(KeyContext::Pager, space),
+            Some(KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE))
+        );
+        let ctrl_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL);
+        assert_eq!(
+            keymap.translate(KeyContext::Composer, ctrl_j),
+            Some(KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT))
+        );
+        assert!(keymap.matches(
+            KeyAction::Interrupt,
+            KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)
+        ));
+        let ctrl_page_down = KeyEvent::new(KeyCode::PageDown, KeyModifiers::CONTROL);
+        assert!(keymap.matches(KeyAction::NextTab, ctrl_page_down));
+        // The app switches tabs before the composer sees the key.
+        assert_eq!(
+            keymap.translate(KeyContext::Composer, ctrl_page_down),
+            Some(ctrl_page_down)
+        );
+        assert_eq!(
+            keymap.approval_keys(key_hint::plain(KeyCode::Char('y'))),
+            vec![key_hint::plain(KeyCode::Char('y'))]
+        );
+    }
+
+    #[test]
+    fn conflicts_and_reserved_keys_are_rejected() {
+        assert_eq!(
+            Keymap::from_config(&KeybindingsToml {
+                submit: list(&["ctrl-j"]),
+                ..Default::default()
+            }),
+            Err(KeymapError::Conflict {
+                key: "ctrl + j".to_string(),
+                first: "submit",
+                second: "newline",
+            })
+        );
+        assert_eq!(
+            Keymap::from_config(&KeybindingsToml {
+                approve: list(&["a"]),
+                ..Default::default()
+            }),
+            Err(KeymapError::Conflict {
+                key: "a".to_string(),
+                first: "approve",
+                second: "approve_for_session",
+            })
+        );
+        assert!(matches!(
+            Keymap::from_config(&KeybindingsToml {
+                interrupt: list(&["ctrl-c"]),
+                ..Default::default()
+            }),
+            Err(KeymapError::Reserved {
+                action: "interrupt",
+                ..
+            })
+        ));
+        assert!(matches!(
+            Keymap::from_config(&KeybindingsToml {
+                deny: list(&["nope"]),
+                ..Default::default()
+            }),
+            Err(KeymapError::InvalidKey { action: "deny", .. })
+        ));
+        // The same key in different contexts is fine.
+        assert!(
+            Keymap::from_config(&KeybindingsToml {
+                approve: list(&["j"]),
+                ..Default::default()
+            })
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn approval_shortcuts_follow_the_configured_keys() {
+        let keymap = Keymap::from_config(&KeybindingsToml {
+            approve: list(&["ctrl-y", "Y"]),
+            deny: list(&[]),
+            ..Default::default()
+        })
+        .expect("valid keymap");
+        assert_eq!(
+            keymap.approval_keys(key_hint::plain(KeyCode::Char('y'))),
+            vec![
+                key_hint::ctrl(KeyCode::Char('y')),
+                key_hint::shift(KeyCode::Char('Y'))
+            ]
+        );
+        assert_eq!(
+            keymap.approval_keys(key_hint::plain(KeyCode::Char('n'))),
+            Vec::new()
+        );
+        assert_eq!(
+            keymap.approval_keys(key_hint::plain(KeyCode::Char('c'))),
+            vec![key_hint::plain(KeyCode::Char('c'))]
+        );
+    }
+}
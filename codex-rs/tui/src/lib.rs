@@ -23,6 +23,7 @@ use codex_core::config::ConfigOverrides;
 use codex_core::config::find_codex_home;
 use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::resolve_oss_provider;
+use codex_core::find_archived_thread_path_by_id_str;
 use codex_core::find_thread_path_by_id_str;
 use codex_core::get_platform_sandbox;
 use codex_core::protocol::AskForApproval;
@@ -47,12 +48,15 @@ mod ascii_animation;
 mod bottom_pane;
 mod chatwidget;
 mod cli;
+mod clipboard_copy;
 mod clipboard_paste;
 mod collab;
 mod collaboration_modes;
 mod color;
+mod context_inspector;
 pub mod custom_terminal;
 mod diff_render;
+mod diff_viewer;
 mod exec_cell;
 mod exec_command;
 mod external_editor;
@@ -60,8 +64,10 @@ mod file_search;
 mod frames;
 mod get_git_diff;
 mod history_cell;
+mod hunk_review;
 pub mod insert_history;
 mod key_hint;
+mod keymap;
 pub mod live_wrap;
 mod markdown;
 mod markdown_render;
@@ -83,15 +89,19 @@ mod status;
 mod status_indicator_widget;
 mod streaming;
 mod style;
+mod tabs;
 mod terminal_palette;
 mod text_formatting;
+mod theme;
 mod tooltips;
+mod transcript_search;
 mod tui;
 mod ui_consts;
 pub mod update_action;
 mod update_prompt;
 mod updates;
 mod version;
+mod voice;
 
 mod wrapping;
 
@@ -249,6 +259,16 @@ pub async fn run_main(
         std::process::exit(1);
     }
 
+    if let Err(err) = keymap::install(&config.tui_keybindings) {
+        eprintln!("Error in [tui.keybindings]: {err}");
+        std::process::exit(1);
+    }
+
+    if let Err(err) = theme::install(&config) {
+        eprintln!("Error in the [tui] theme settings: {err}");
+        std::process::exit(1);
+    }
+
     let log_dir = codex_core::config::log_dir(&config)?;
     std::fs::create_dir_all(&log_dir)?;
     // Open (or create) your log file, appending to it.
@@ -513,7 +533,13 @@ async fn run_ratatui_app(
             resume_picker::SessionSelection::StartFresh
         }
     } else if let Some(id_str) = cli.resume_session_id.as_deref() {
-        match find_thread_path_by_id_str(&config.codex_home, id_str).await? {
+        let path = match find_thread_path_by_id_str(&config.codex_home, id_str).await? {
+            None if cli.resume_archived => {
+                find_archived_thread_path_by_id_str(&config.codex_home, id_str).await?
+            }
+            path => path,
+        };
+        match path {
             Some(path) => resume_picker::SessionSelection::Resume(path),
             None => return missing_session_exit(id_str, "resume"),
         }
@@ -540,14 +566,24 @@ async fn run_ratatui_app(
             _ => resume_picker::SessionSelection::StartFresh,
         }
     } else if cli.resume_picker {
-        match resume_picker::run_resume_picker(
-            &mut tui,
-            &config.codex_home,
-            &config.model_provider_id,
-            cli.resume_show_all,
-        )
-        .await?
-        {
+        let selection = if cli.resume_archived {
+            resume_picker::run_archived_resume_picker(
+                &mut tui,
+                &config.codex_home,
+                &config.model_provider_id,
+                cli.resume_show_all,
+            )
+            .await?
+        } else {
+            resume_picker::run_resume_picker(
+                &mut tui,
+                &config.codex_home,
+                &config.model_provider_id,
+                cli.resume_show_all,
+            )
+            .await?
+        };
+        match selection {
             resume_picker::SessionSelection::Exit => {
                 restore();
                 session_log::log_session_end();
@@ -600,6 +636,7 @@ async fn run_ratatui_app(
 
     let use_alt_screen = determine_alt_screen_mode(no_alt_screen, config.tui_alternate_screen);
     tui.set_alt_screen_enabled(use_alt_screen);
+    tui.set_notification_method(config.tui_notification_method);
 
     let app_result = App::run(
         &mut tui,
@@ -1,10 +1,13 @@
+mod native;
 mod osc9;
 mod windows_toast;
 
 use std::env;
 use std::io;
 
+use codex_core::config::types::NotificationMethod;
 use codex_core::env::is_wsl;
+use native::NativeBackend;
 use osc9::Osc9Backend;
 use windows_toast::WindowsToastBackend;
 
@@ -12,12 +15,14 @@ use windows_toast::WindowsToastBackend;
 pub enum NotificationBackendKind {
     Osc9,
     WindowsToast,
+    Native,
 }
 
 #[derive(Debug)]
 pub enum DesktopNotificationBackend {
     Osc9(Osc9Backend),
     WindowsToast(WindowsToastBackend),
+    Native(NativeBackend),
 }
 
 impl DesktopNotificationBackend {
@@ -29,10 +34,15 @@ impl DesktopNotificationBackend {
         Self::WindowsToast(WindowsToastBackend::default())
     }
 
+    pub fn native() -> Self {
+        Self::Native(NativeBackend)
+    }
+
     pub fn kind(&self) -> NotificationBackendKind {
         match self {
             DesktopNotificationBackend::Osc9(_) => NotificationBackendKind::Osc9,
             DesktopNotificationBackend::WindowsToast(_) => NotificationBackendKind::WindowsToast,
+            DesktopNotificationBackend::Native(_) => NotificationBackendKind::Native,
         }
     }
 
@@ -40,6 +50,7 @@ impl DesktopNotificationBackend {
         match self {
             DesktopNotificationBackend::Osc9(backend) => backend.notify(message),
             DesktopNotificationBackend::WindowsToast(backend) => backend.notify(message),
+            DesktopNotificationBackend::Native(backend) => backend.notify(message),
         }
     }
 }
@@ -55,6 +66,18 @@ pub fn detect_backend() -> DesktopNotificationBackend {
     }
 }
 
+/// Backend for the configured `tui.notification_method`.
+pub fn backend_for(method: NotificationMethod) -> DesktopNotificationBackend {
+    match method {
+        NotificationMethod::Auto => detect_backend(),
+        NotificationMethod::Osc9 => DesktopNotificationBackend::osc9(),
+        NotificationMethod::Native if cfg!(windows) || is_wsl() => {
+            DesktopNotificationBackend::windows_toast()
+        }
+        NotificationMethod::Native => DesktopNotificationBackend::native(),
+    }
+}
+
 fn should_use_windows_toasts() -> bool {
     is_wsl() && env::var_os("WT_SESSION").is_some()
 }
@@ -62,7 +85,9 @@ fn should_use_windows_toasts() -> bool {
 #[cfg(test)]
 mod tests {
     use super::NotificationBackendKind;
+    use super::backend_for;
     use super::detect_backend;
+    use codex_core::config::types::NotificationMethod;
     use serial_test::serial;
     use std::ffi::OsString;
 
@@ -116,6 +141,28 @@ mod tests {
         assert_eq!(detect_backend().kind(), NotificationBackendKind::Osc9);
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[serial]
+    fn native_method_uses_the_system_notifier() {
+        let _wsl_guard = EnvVarGuard::remove("WSL_DISTRO_NAME");
+        let _wt_guard = EnvVarGuard::set("WT_SESSION", "abc");
+        assert_eq!(
+            backend_for(NotificationMethod::Native).kind(),
+            NotificationBackendKind::Native
+        );
+        assert_eq!(
+            backend_for(NotificationMethod::Osc9).kind(),
+            NotificationBackendKind::Osc9
+        );
+
+        let _wsl_guard = EnvVarGuard::set("WSL_DISTRO_NAME", "Ubuntu");
+        assert_eq!(
+            backend_for(NotificationMethod::Native).kind(),
+            NotificationBackendKind::WindowsToast
+        );
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     #[serial]
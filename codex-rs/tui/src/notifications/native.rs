@@ -0,0 +1,84 @@
+use std::io;
+use std::process::Command;
+use std::process::Stdio;
+
+const APP_NAME: &str = "Codex";
+
+/// Posts notifications through the system notifier: `notify-send` on Linux
+/// and other Unixes, `osascript` on macOS. Windows uses
+/// [`super::windows_toast::WindowsToastBackend`] instead.
+#[derive(Debug, Default)]
+pub struct NativeBackend;
+
+impl NativeBackend {
+    pub fn notify(&mut self, message: &str) -> io::Result<()> {
+        let mut command = notifier_command(message);
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        let status = command.status()?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "{} exited with status {status}",
+                command.get_program().to_string_lossy()
+            )))
+        }
+    }
+}
+
+/// The message goes in as an argument after `--`, so it needs no quoting
+/// and a leading `-` is not taken for an option.
+#[cfg(target_os = "macos")]
+fn notifier_command(message: &str) -> Command {
+    let mut command = Command::new("osascript");
+    command
+        .args(["-e", "on run argv", "-e"])
+        .arg(format!(
+            "display notification (item 1 of argv) with title \"{APP_NAME}\""
+        ))
+        .args(["-e", "end run", "--", message]);
+    command
+}
+
+/// The message goes in as an argument after `--`, so it needs no quoting
+/// and a leading `-` is not taken for an option.
+#[cfg(not(target_os = "macos"))]
+fn notifier_command(message: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command
+        .arg(format!("--app-name={APP_NAME}"))
+        .args(["--", APP_NAME, message]);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::notifier_command;
+    use pretty_assertions::assert_eq;
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn notify_send_gets_the_message_as_the_body() {
+        let command = notifier_command("- done: fixed the build");
+        assert_eq!(command.get_program(), "notify-send");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            vec!["--app-name=Codex", "--", "Codex", "- done: fixed the build"]
+        );
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn osascript_reads_the_message_from_argv() {
+        let command = notifier_command("say \"hi\"");
+        assert_eq!(command.get_program(), "osascript");
+        assert_eq!(
+            command.get_args().last().and_then(|arg| arg.to_str()),
+            Some("say \"hi\"")
+        );
+    }
+}
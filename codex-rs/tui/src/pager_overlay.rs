@@ -16,22 +16,41 @@
 //! mutates in place or when its transcript output is time-dependent.
 
 use std::io::Result;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::app_event_sender::AppEventSender;
 use crate::chatwidget::ActiveCellTranscriptKey;
+use crate::clipboard_copy::CopyMethod;
+use crate::clipboard_copy::copy_to_clipboard;
+use crate::clipboard_copy::lines_to_plain_text;
+use crate::context_inspector::ContextInspectorOverlay;
+use crate::diff_viewer::DiffViewerOverlay;
+use crate::diff_viewer::FileDiff;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::UserHistoryCell;
+use crate::hunk_review::ReviewFile;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::keymap::KeyContext;
+use crate::keymap::keymap;
 use crate::render::Insets;
 use crate::render::renderable::InsetRenderable;
 use crate::render::renderable::Renderable;
 use crate::style::user_message_style;
+use crate::transcript_search::LineHighlight;
+use crate::transcript_search::TranscriptSearch;
+use crate::transcript_search::find_matches;
+use crate::transcript_search::highlight_line;
 use crate::tui;
 use crate::tui::TuiEvent;
+use codex_core::protocol::ContextInspectionEvent;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::buffer::Cell;
 use ratatui::layout::Rect;
@@ -49,6 +68,8 @@ use ratatui::widgets::Wrap;
 pub(crate) enum Overlay {
     Transcript(TranscriptOverlay),
     Static(StaticOverlay),
+    ContextInspector(ContextInspectorOverlay),
+    DiffViewer(DiffViewerOverlay),
 }
 
 impl Overlay {
@@ -67,10 +88,31 @@ impl Overlay {
         Self::Static(StaticOverlay::with_renderables(renderables, title))
     }
 
+    pub(crate) fn new_context_inspector(
+        inspection: ContextInspectionEvent,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        Self::ContextInspector(ContextInspectorOverlay::new(inspection, app_event_tx))
+    }
+
+    pub(crate) fn new_diff_viewer(
+        files: Vec<FileDiff>,
+        repo_root: PathBuf,
+        app_event_tx: AppEventSender,
+    ) -> Self {
+        Self::DiffViewer(DiffViewerOverlay::new(files, repo_root, app_event_tx))
+    }
+
+    pub(crate) fn new_hunk_review(files: Vec<ReviewFile>, app_event_tx: AppEventSender) -> Self {
+        Self::DiffViewer(DiffViewerOverlay::new_review(files, app_event_tx))
+    }
+
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match self {
             Overlay::Transcript(o) => o.handle_event(tui, event),
             Overlay::Static(o) => o.handle_event(tui, event),
+            Overlay::ContextInspector(o) => o.handle_event(tui, event),
+            Overlay::DiffViewer(o) => o.handle_event(tui, event),
         }
     }
 
@@ -78,6 +120,8 @@ impl Overlay {
         match self {
             Overlay::Transcript(o) => o.is_done(),
             Overlay::Static(o) => o.is_done(),
+            Overlay::ContextInspector(o) => o.is_done(),
+            Overlay::DiffViewer(o) => o.is_done(),
         }
     }
 }
@@ -99,10 +143,16 @@ const KEY_CTRL_D: KeyBinding = key_hint::ctrl(KeyCode::Char('d'));
 const KEY_CTRL_B: KeyBinding = key_hint::ctrl(KeyCode::Char('b'));
 const KEY_CTRL_U: KeyBinding = key_hint::ctrl(KeyCode::Char('u'));
 const KEY_Q: KeyBinding = key_hint::plain(KeyCode::Char('q'));
+const KEY_Y: KeyBinding = key_hint::plain(KeyCode::Char('y'));
+const KEY_V: KeyBinding = key_hint::plain(KeyCode::Char('v'));
 const KEY_ESC: KeyBinding = key_hint::plain(KeyCode::Esc);
 const KEY_ENTER: KeyBinding = key_hint::plain(KeyCode::Enter);
 const KEY_CTRL_T: KeyBinding = key_hint::ctrl(KeyCode::Char('t'));
 const KEY_CTRL_C: KeyBinding = key_hint::ctrl(KeyCode::Char('c'));
+const KEY_SLASH: KeyBinding = key_hint::plain(KeyCode::Char('/'));
+const KEY_N: KeyBinding = key_hint::plain(KeyCode::Char('n'));
+const KEY_P: KeyBinding = key_hint::plain(KeyCode::Char('p'));
+const KEY_BACKSPACE: KeyBinding = key_hint::plain(KeyCode::Backspace);
 
 // Common pager navigation hints rendered on the first line
 const PAGER_KEY_HINTS: &[(&[KeyBinding], &str)] = &[
@@ -112,7 +162,7 @@ const PAGER_KEY_HINTS: &[(&[KeyBinding], &str)] = &[
 ];
 
 // Render a single line of key hints from (key(s), description) pairs.
-fn render_key_hints(area: Rect, buf: &mut Buffer, pairs: &[(&[KeyBinding], &str)]) {
+pub(crate) fn render_key_hints(area: Rect, buf: &mut Buffer, pairs: &[(&[KeyBinding], &str)]) {
     let mut spans: Vec<Span<'static>> = vec![" ".into()];
     let mut first = true;
     for (keys, desc) in pairs {
@@ -141,6 +191,8 @@ struct PagerView {
     last_rendered_height: Option<usize>,
     /// If set, on next render ensure this chunk is visible.
     pending_scroll_chunk: Option<usize>,
+    /// If set, on next render ensure this (chunk, row within chunk) is visible.
+    pending_scroll_row: Option<(usize, usize)>,
 }
 
 impl PagerView {
@@ -152,6 +204,7 @@ impl PagerView {
             last_content_height: None,
             last_rendered_height: None,
             pending_scroll_chunk: None,
+            pending_scroll_row: None,
         }
     }
 
@@ -174,6 +227,9 @@ impl PagerView {
         if let Some(idx) = self.pending_scroll_chunk.take() {
             self.ensure_chunk_visible(idx, content_area);
         }
+        if let Some((idx, row)) = self.pending_scroll_row.take() {
+            self.ensure_row_visible(idx, row, content_area);
+        }
         self.scroll_offset = self
             .scroll_offset
             .min(content_height.saturating_sub(content_area.height as usize));
@@ -260,6 +316,9 @@ impl PagerView {
     }
 
     fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) -> Result<()> {
+        let Some(key_event) = keymap().translate(KeyContext::Pager, key_event) else {
+            return Ok(());
+        };
         match key_event {
             e if KEY_UP.is_press(e) || KEY_K.is_press(e) => {
                 self.scroll_offset = self.scroll_offset.saturating_sub(1);
@@ -351,6 +410,30 @@ impl PagerView {
         self.pending_scroll_chunk = Some(chunk_index);
     }
 
+    /// Request that a row of the given chunk be scrolled into view on next render.
+    fn scroll_row_into_view(&mut self, chunk_index: usize, row: usize) {
+        self.pending_scroll_row = Some((chunk_index, row));
+    }
+
+    /// Centers `row` of chunk `idx` in the viewport unless it is already visible.
+    fn ensure_row_visible(&mut self, idx: usize, row: usize, area: Rect) {
+        if area.height == 0 || idx >= self.renderables.len() {
+            return;
+        }
+        let target = self
+            .renderables
+            .iter()
+            .take(idx)
+            .map(|r| r.desired_height(area.width) as usize)
+            .sum::<usize>()
+            + row;
+        let height = area.height as usize;
+        let visible = self.scroll_offset <= target && target < self.scroll_offset + height;
+        if !visible {
+            self.scroll_offset = target.saturating_sub(height / 2);
+        }
+    }
+
     fn ensure_chunk_visible(&mut self, idx: usize, area: Rect) {
         if area.height == 0 || idx >= self.renderables.len() {
             return;
@@ -406,12 +489,25 @@ impl Renderable for CachedRenderable {
 struct CellRenderable {
     cell: Arc<dyn HistoryCell>,
     style: Style,
+    /// Search matches to paint over the cell's lines.
+    highlights: Vec<LineHighlight>,
 }
 
 impl Renderable for CellRenderable {
     fn render(&self, area: Rect, buf: &mut Buffer) {
-        let p =
-            Paragraph::new(Text::from(self.cell.transcript_lines(area.width))).style(self.style);
+        let mut lines = self.cell.transcript_lines(area.width);
+        if !self.highlights.is_empty() {
+            lines = lines
+                .into_iter()
+                .enumerate()
+                .map(|(idx, line)| {
+                    let highlights: Vec<&LineHighlight> =
+                        self.highlights.iter().filter(|h| h.line == idx).collect();
+                    highlight_line(line, &highlights)
+                })
+                .collect();
+        }
+        let p = Paragraph::new(Text::from(lines)).style(self.style);
         p.render(area, buf);
     }
 
@@ -429,11 +525,33 @@ pub(crate) struct TranscriptOverlay {
     /// Committed transcript cells (does not include the live tail).
     cells: Vec<Arc<dyn HistoryCell>>,
     highlight_cell: Option<usize>,
+    /// Messages picked with `v` for `y` to copy, if a selection is open.
+    selection: Option<MessageSelection>,
+    /// Outcome of the last `y` copy, shown below the key hints.
+    copy_status: Option<String>,
+    /// Active `/` search, if any.
+    search: Option<TranscriptSearch>,
+    /// Width of the last render; search matches are computed at this width.
+    last_width: Option<u16>,
     /// Cache key for the render-only live tail appended after committed cells.
     live_tail_key: Option<LiveTailKey>,
     is_done: bool,
 }
 
+/// A run of consecutive messages, from where `v` was pressed to where the
+/// cursor has moved since. Either end may come first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct MessageSelection {
+    anchor: usize,
+    cursor: usize,
+}
+
+impl MessageSelection {
+    fn cells(self) -> RangeInclusive<usize> {
+        self.anchor.min(self.cursor)..=self.anchor.max(self.cursor)
+    }
+}
+
 /// Cache key for the active-cell "live tail" appended to the transcript overlay.
 ///
 /// Changing any field implies a different rendered tail.
@@ -457,12 +575,16 @@ impl TranscriptOverlay {
     pub(crate) fn new(transcript_cells: Vec<Arc<dyn HistoryCell>>) -> Self {
         Self {
             view: PagerView::new(
-                Self::render_cells(&transcript_cells, None),
+                Self::render_cells(&transcript_cells, None, None, None),
                 "T R A N S C R I P T".to_string(),
                 usize::MAX,
             ),
             cells: transcript_cells,
             highlight_cell: None,
+            selection: None,
+            copy_status: None,
+            search: None,
+            last_width: None,
             live_tail_key: None,
             is_done: false,
         }
@@ -471,13 +593,30 @@ impl TranscriptOverlay {
     fn render_cells(
         cells: &[Arc<dyn HistoryCell>],
         highlight_cell: Option<usize>,
+        selected: Option<RangeInclusive<usize>>,
+        search: Option<&TranscriptSearch>,
     ) -> Vec<Box<dyn Renderable>> {
         cells
             .iter()
             .enumerate()
             .flat_map(|(i, c)| {
                 let mut v: Vec<Box<dyn Renderable>> = Vec::new();
-                let mut cell_renderable = if c.as_any().is::<UserHistoryCell>() {
+                let highlights = search
+                    .map(|search| search.highlights_for(i))
+                    .unwrap_or_default();
+                let in_selection = selected.as_ref().is_some_and(|range| range.contains(&i));
+                let mut cell_renderable = if in_selection {
+                    let style = if c.as_any().is::<UserHistoryCell>() {
+                        user_message_style()
+                    } else {
+                        Style::default()
+                    };
+                    Box::new(CachedRenderable::new(CellRenderable {
+                        cell: c.clone(),
+                        style: style.reversed(),
+                        highlights,
+                    })) as Box<dyn Renderable>
+                } else if c.as_any().is::<UserHistoryCell>() {
                     Box::new(CachedRenderable::new(CellRenderable {
                         cell: c.clone(),
                         style: if highlight_cell == Some(i) {
@@ -485,11 +624,13 @@ impl TranscriptOverlay {
                         } else {
                             user_message_style()
                         },
+                        highlights,
                     })) as Box<dyn Renderable>
                 } else {
                     Box::new(CachedRenderable::new(CellRenderable {
                         cell: c.clone(),
                         style: Style::default(),
+                        highlights,
                     })) as Box<dyn Renderable>
                 };
                 if !c.is_stream_continuation() && i > 0 {
@@ -519,7 +660,13 @@ impl TranscriptOverlay {
         let had_prior_cells = !self.cells.is_empty();
         let tail_renderable = self.take_live_tail_renderable();
         self.cells.push(cell);
-        self.view.renderables = Self::render_cells(&self.cells, self.highlight_cell);
+        self.update_search_matches();
+        self.view.renderables = Self::render_cells(
+            &self.cells,
+            self.highlight_cell,
+            self.selection.map(MessageSelection::cells),
+            self.search.as_ref(),
+        );
         if let Some(tail) = tail_renderable {
             let tail = if !had_prior_cells
                 && self
@@ -604,9 +751,179 @@ impl TranscriptOverlay {
         self.view.is_scrolled_to_bottom()
     }
 
+    /// Opens the search prompt; a non-empty `query` is searched right away
+    /// and jumps to its most recent match.
+    pub(crate) fn start_search(&mut self, query: String) {
+        self.search = Some(TranscriptSearch::new(query));
+        self.refresh_search();
+    }
+
+    /// Whether a search or a selection is open. Keys then go to it first, so
+    /// `App` forwards them here instead of treating Esc/Enter as backtracking.
+    pub(crate) fn captures_keys(&self) -> bool {
+        self.search.is_some() || self.selection.is_some()
+    }
+
+    /// Opens a selection on the highlighted message, or on the last one when
+    /// nothing is highlighted (`v`, or `/copy select`).
+    pub(crate) fn start_selection(&mut self) {
+        let Some(last) = self.cells.len().checked_sub(1) else {
+            return;
+        };
+        let start = self.highlight_cell.unwrap_or(last).min(last);
+        self.search = None;
+        self.selection = Some(MessageSelection {
+            anchor: start,
+            cursor: start,
+        });
+        self.rebuild_renderables();
+        self.view.scroll_chunk_into_view(start);
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let Some(selection) = self.selection.as_mut() else {
+            return;
+        };
+        let last = self.cells.len().saturating_sub(1);
+        selection.cursor = selection.cursor.saturating_add_signed(offset).min(last);
+        let cursor = selection.cursor;
+        self.rebuild_renderables();
+        self.view.scroll_chunk_into_view(cursor);
+    }
+
+    fn clear_selection(&mut self) {
+        self.selection = None;
+        self.rebuild_renderables();
+    }
+
+    /// Handles a key for an open selection, or `v` to open one. Returns
+    /// whether the key was consumed; paging keys still scroll the view.
+    fn handle_selection_key(&mut self, key_event: KeyEvent) -> bool {
+        if self.selection.is_none() {
+            if KEY_V.is_press(key_event) && self.search.is_none() {
+                self.start_selection();
+                return true;
+            }
+            return false;
+        }
+        match key_event {
+            e if KEY_UP.is_press(e) || KEY_K.is_press(e) => self.move_selection(-1),
+            e if KEY_DOWN.is_press(e) || KEY_J.is_press(e) => self.move_selection(1),
+            e if KEY_Y.is_press(e) || KEY_ENTER.is_press(e) => {
+                self.copy_selection();
+                self.clear_selection();
+            }
+            e if KEY_ESC.is_press(e) || KEY_V.is_press(e) || KEY_CTRL_C.is_press(e) => {
+                self.clear_selection();
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn clear_search(&mut self) {
+        self.search = None;
+        self.rebuild_renderables();
+    }
+
+    fn update_search_matches(&mut self) {
+        let (Some(search), Some(width)) = (self.search.as_mut(), self.last_width) else {
+            return;
+        };
+        let matches = find_matches(
+            self.cells.iter().map(|cell| cell.transcript_lines(width)),
+            search.query(),
+        );
+        search.set_matches(matches);
+    }
+
+    /// Recomputes the matches, repaints them, and scrolls to the current one.
+    fn refresh_search(&mut self) {
+        self.update_search_matches();
+        self.rebuild_renderables();
+        self.scroll_to_current_match();
+    }
+
+    fn scroll_to_current_match(&mut self) {
+        let Some(found) = self
+            .search
+            .as_ref()
+            .and_then(TranscriptSearch::current_match)
+        else {
+            return;
+        };
+        // Cells after the first are rendered below a blank inset row.
+        let inset = self
+            .cells
+            .get(found.cell)
+            .is_some_and(|cell| found.cell > 0 && !cell.is_stream_continuation());
+        self.view
+            .scroll_row_into_view(found.cell, usize::from(inset) + found.line);
+    }
+
+    /// Handles a key for the search prompt or its results. Returns whether
+    /// the key was consumed.
+    fn handle_search_key(&mut self, key_event: KeyEvent) -> bool {
+        let Some(search) = self.search.as_mut() else {
+            if KEY_SLASH.is_press(key_event) || KEY_CTRL_F.is_press(key_event) {
+                self.start_search(String::new());
+                return true;
+            }
+            return false;
+        };
+        if search.is_editing() {
+            match key_event {
+                e if KEY_ESC.is_press(e) || KEY_CTRL_C.is_press(e) => self.clear_search(),
+                e if KEY_ENTER.is_press(e) => {
+                    if search.query().is_empty() {
+                        self.clear_search();
+                    } else {
+                        search.set_editing(false);
+                    }
+                }
+                e if KEY_BACKSPACE.is_press(e) => {
+                    search.pop_char();
+                    self.refresh_search();
+                }
+                KeyEvent {
+                    code: KeyCode::Char(c),
+                    modifiers,
+                    kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                    ..
+                } if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                    search.push_char(c);
+                    self.refresh_search();
+                }
+                _ => return false,
+            }
+            return true;
+        }
+        match key_event {
+            e if KEY_ESC.is_press(e) => self.clear_search(),
+            e if KEY_N.is_press(e) => {
+                search.select_next();
+                self.rebuild_renderables();
+                self.scroll_to_current_match();
+            }
+            e if KEY_P.is_press(e) => {
+                search.select_previous();
+                self.rebuild_renderables();
+                self.scroll_to_current_match();
+            }
+            e if KEY_SLASH.is_press(e) || KEY_CTRL_F.is_press(e) => search.set_editing(true),
+            _ => return false,
+        }
+        true
+    }
+
     fn rebuild_renderables(&mut self) {
         let tail_renderable = self.take_live_tail_renderable();
-        self.view.renderables = Self::render_cells(&self.cells, self.highlight_cell);
+        self.view.renderables = Self::render_cells(
+            &self.cells,
+            self.highlight_cell,
+            self.selection.map(MessageSelection::cells),
+            self.search.as_ref(),
+        );
         if let Some(tail) = tail_renderable {
             self.view.renderables.push(tail);
         }
@@ -634,23 +951,89 @@ impl TranscriptOverlay {
         renderable
     }
 
+    /// Text copied by `y`: the selected messages, the highlighted message, or
+    /// the whole transcript when nothing is picked.
+    fn selection_text(&self) -> String {
+        let selected = if let Some(selection) = self.selection {
+            self.cells.get(selection.cells()).unwrap_or_default()
+        } else {
+            match self.highlight_cell.and_then(|idx| self.cells.get(idx)) {
+                Some(cell) => std::slice::from_ref(cell),
+                None => self.cells.as_slice(),
+            }
+        };
+        transcript_plain_text(selected)
+    }
+
+    fn copy_selection(&mut self) {
+        let what = match self.selection.map(MessageSelection::cells) {
+            Some(range) if range.start() != range.end() => {
+                format!("{} messages", range.end() - range.start() + 1)
+            }
+            Some(_) => "message".to_string(),
+            None if self.highlight_cell.is_some() => "message".to_string(),
+            None => "transcript".to_string(),
+        };
+        self.copy_status = Some(match copy_to_clipboard(&self.selection_text()) {
+            Ok(CopyMethod::Native) => format!("Copied {what} to the clipboard."),
+            Ok(CopyMethod::Terminal) => format!("Sent {what} to the terminal clipboard (OSC 52)."),
+            Err(err) => format!("Failed to copy {what}: {err}"),
+        });
+    }
+
     fn render_hints(&self, area: Rect, buf: &mut Buffer) {
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
+        let line3 = Rect::new(area.x, area.y.saturating_add(2), area.width, 1);
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
 
-        let mut pairs: Vec<(&[KeyBinding], &str)> = vec![(&[KEY_Q], "to quit")];
-        if self.highlight_cell.is_some() {
-            pairs.push((&[KEY_ESC, KEY_LEFT], "to edit prev"));
-            pairs.push((&[KEY_RIGHT], "to edit next"));
-            pairs.push((&[KEY_ENTER], "to edit message"));
-        } else {
-            pairs.push((&[KEY_ESC], "to edit prev"));
+        let mut pairs: Vec<(&[KeyBinding], &str)> = Vec::new();
+        match &self.search {
+            _ if self.selection.is_some() => {
+                pairs.push((&[KEY_UP, KEY_DOWN], "to extend"));
+                pairs.push((&[KEY_Y, KEY_ENTER], "to copy"));
+                pairs.push((&[KEY_ESC], "to cancel"));
+            }
+            Some(search) if search.is_editing() => {
+                pairs.push((&[KEY_ENTER], "to search"));
+                pairs.push((&[KEY_ESC], "to cancel"));
+            }
+            Some(_) => {
+                pairs.push((&[KEY_N, KEY_P], "to jump to next/prev match"));
+                pairs.push((&[KEY_SLASH], "to edit search"));
+                pairs.push((&[KEY_ESC], "to clear search"));
+                pairs.push((&[KEY_Q], "to quit"));
+            }
+            None => {
+                pairs.push((&[KEY_Q], "to quit"));
+                if self.highlight_cell.is_some() {
+                    pairs.push((&[KEY_ESC, KEY_LEFT], "to edit prev"));
+                    pairs.push((&[KEY_RIGHT], "to edit next"));
+                    pairs.push((&[KEY_ENTER], "to edit message"));
+                } else {
+                    pairs.push((&[KEY_ESC], "to edit prev"));
+                }
+                pairs.push((&[KEY_Y], "to copy"));
+                pairs.push((&[KEY_V], "to select"));
+                pairs.push((&[KEY_SLASH, KEY_CTRL_F], "to search"));
+            }
         }
         render_key_hints(line2, buf, &pairs);
+
+        if let Some(search) = &self.search {
+            Paragraph::new(search.status_line()).render_ref(line3, buf);
+        } else if let Some(status) = &self.copy_status {
+            Paragraph::new(Line::from(format!(" {status}")).dim()).render_ref(line3, buf);
+        }
     }
 
     pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        if self.last_width != Some(area.width) {
+            self.last_width = Some(area.width);
+            if self.search.is_some() {
+                self.refresh_search();
+            }
+        }
         let top_h = area.height.saturating_sub(3);
         let top = Rect::new(area.x, area.y, area.width, top_h);
         let bottom = Rect::new(area.x, area.y + top_h, area.width, 3);
@@ -662,11 +1045,22 @@ impl TranscriptOverlay {
 impl TranscriptOverlay {
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match event {
+            TuiEvent::Key(key_event)
+                if self.handle_selection_key(key_event) || self.handle_search_key(key_event) =>
+            {
+                tui.frame_requester().schedule_frame();
+                Ok(())
+            }
             TuiEvent::Key(key_event) => match key_event {
                 e if KEY_Q.is_press(e) || KEY_CTRL_C.is_press(e) || KEY_CTRL_T.is_press(e) => {
                     self.is_done = true;
                     Ok(())
                 }
+                e if KEY_Y.is_press(e) => {
+                    self.copy_selection();
+                    tui.frame_requester().schedule_frame();
+                    Ok(())
+                }
                 other => self.view.handle_key_event(tui, other),
             },
             TuiEvent::Draw => {
@@ -683,6 +1077,16 @@ impl TranscriptOverlay {
     }
 }
 
+/// The transcript as plain text, for copying and `/save`: each message's
+/// transcript lines unwrapped, with a blank line between messages.
+pub(crate) fn transcript_plain_text(cells: &[Arc<dyn HistoryCell>]) -> String {
+    cells
+        .iter()
+        .map(|cell| lines_to_plain_text(&cell.transcript_lines(u16::MAX)))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 pub(crate) struct StaticOverlay {
     view: PagerView,
     is_done: bool,
@@ -852,6 +1256,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn selection_text_is_highlighted_cell_or_whole_transcript() {
+        let mut overlay = TranscriptOverlay::new(vec![
+            Arc::new(TestCell {
+                lines: vec![Line::from("alpha"), Line::from("beta")],
+            }),
+            Arc::new(TestCell {
+                lines: vec![Line::from("gamma")],
+            }),
+        ]);
+        assert_eq!(overlay.selection_text(), "alpha\nbeta\n\ngamma");
+
+        overlay.set_highlight_cell(Some(1));
+        assert_eq!(overlay.selection_text(), "gamma");
+    }
+
+    #[test]
+    fn selection_extends_over_a_range_of_messages() {
+        let mut overlay = TranscriptOverlay::new(
+            ["alpha", "beta", "gamma"]
+                .into_iter()
+                .map(|text| {
+                    Arc::new(TestCell {
+                        lines: vec![Line::from(text)],
+                    }) as Arc<dyn HistoryCell>
+                })
+                .collect(),
+        );
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        assert!(overlay.handle_selection_key(key(KeyCode::Char('v'))));
+        assert!(overlay.captures_keys());
+        assert_eq!(overlay.selection_text(), "gamma");
+
+        assert!(overlay.handle_selection_key(key(KeyCode::Char('k'))));
+        assert_eq!(overlay.selection_text(), "beta\n\ngamma");
+
+        overlay.handle_selection_key(key(KeyCode::Down));
+        overlay.handle_selection_key(key(KeyCode::Down));
+        assert_eq!(overlay.selection_text(), "gamma");
+
+        assert!(!overlay.handle_selection_key(key(KeyCode::PageUp)));
+        assert!(overlay.handle_selection_key(key(KeyCode::Esc)));
+        assert!(!overlay.captures_keys());
+        assert_eq!(overlay.selection_text(), "alpha\n\nbeta\n\ngamma");
+    }
+
     #[test]
     fn transcript_overlay_snapshot_basic() {
         // Prepare a transcript overlay with a few lines
@@ -1000,6 +1451,39 @@ mod tests {
         assert_snapshot!("transcript_overlay_apply_patch_scroll_vt100", snapshot);
     }
 
+    #[test]
+    fn transcript_search_jumps_between_matches() {
+        let mut overlay = TranscriptOverlay::new(
+            (0..20)
+                .map(|i| {
+                    Arc::new(TestCell {
+                        lines: vec![Line::from(format!("line{i}"))],
+                    }) as Arc<dyn HistoryCell>
+                })
+                .collect(),
+        );
+        overlay.view.scroll_offset = 0;
+        overlay.start_search("line1".to_string());
+        let area = Rect::new(0, 0, 40, 12);
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+        let text = buffer_to_text(&buf, area);
+        assert!(text.contains("line19"), "latest match visible: {text}");
+        assert!(text.contains("/line1  11/11"), "status line: {text}");
+
+        overlay.view.scroll_offset = 0;
+        assert!(overlay.handle_search_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE)));
+        overlay.render(area, &mut buf);
+        let text = buffer_to_text(&buf, area);
+        assert!(text.contains("line18"), "previous match visible: {text}");
+        assert!(text.contains("10/11"), "status line: {text}");
+
+        // Esc clears the search before it can start a backtrack.
+        assert!(overlay.handle_search_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(!overlay.is_searching());
+        assert!(!overlay.handle_search_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+    }
+
     #[test]
     fn transcript_overlay_keeps_scroll_pinned_at_bottom() {
         let mut overlay = TranscriptOverlay::new(
@@ -0,0 +1,266 @@
+//! Syntax highlighting for source snippets, keyed by file extension.
+//!
+//! Uses the same tree-sitter highlighter as [`super::highlight`], with the
+//! grammars Codex already ships for code navigation. Styles only add
+//! modifiers (bold keywords, dim strings and comments, italic types) so they
+//! combine with a foreground color chosen by the caller, such as the green
+//! and red of diff lines.
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use ratatui::style::Style;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use tree_sitter_highlight::Highlight;
+use tree_sitter_highlight::HighlightConfiguration;
+use tree_sitter_highlight::HighlightEvent;
+use tree_sitter_highlight::Highlighter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CodeLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+    Bash,
+}
+
+impl CodeLanguage {
+    const ALL: [Self; 7] = [
+        Self::Rust,
+        Self::Python,
+        Self::JavaScript,
+        Self::TypeScript,
+        Self::Tsx,
+        Self::Go,
+        Self::Bash,
+    ];
+
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            "sh" | "bash" | "zsh" => Some(Self::Bash),
+            _ => None,
+        }
+    }
+
+    fn build_config(self) -> Option<HighlightConfiguration> {
+        let (language, name, query) = match self {
+            Self::Rust => (
+                tree_sitter_rust::LANGUAGE.into(),
+                "rust",
+                tree_sitter_rust::HIGHLIGHTS_QUERY.to_string(),
+            ),
+            Self::Python => (
+                tree_sitter_python::LANGUAGE.into(),
+                "python",
+                tree_sitter_python::HIGHLIGHTS_QUERY.to_string(),
+            ),
+            Self::JavaScript => (
+                tree_sitter_javascript::LANGUAGE.into(),
+                "javascript",
+                format!(
+                    "{}\n{}",
+                    tree_sitter_javascript::JSX_HIGHLIGHT_QUERY,
+                    tree_sitter_javascript::HIGHLIGHT_QUERY
+                ),
+            ),
+            // The TypeScript queries only cover what TypeScript adds to
+            // JavaScript.
+            Self::TypeScript => (
+                tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+                "typescript",
+                format!(
+                    "{}\n{}",
+                    tree_sitter_typescript::HIGHLIGHTS_QUERY,
+                    tree_sitter_javascript::HIGHLIGHT_QUERY
+                ),
+            ),
+            Self::Tsx => (
+                tree_sitter_typescript::LANGUAGE_TSX.into(),
+                "tsx",
+                format!(
+                    "{}\n{}\n{}",
+                    tree_sitter_typescript::HIGHLIGHTS_QUERY,
+                    tree_sitter_javascript::JSX_HIGHLIGHT_QUERY,
+                    tree_sitter_javascript::HIGHLIGHT_QUERY
+                ),
+            ),
+            Self::Go => (
+                tree_sitter_go::LANGUAGE.into(),
+                "go",
+                tree_sitter_go::HIGHLIGHTS_QUERY.to_string(),
+            ),
+            Self::Bash => (
+                tree_sitter_bash::LANGUAGE.into(),
+                "bash",
+                tree_sitter_bash::HIGHLIGHT_QUERY.to_string(),
+            ),
+        };
+        let mut config = HighlightConfiguration::new(language, name, &query, "", "").ok()?;
+        config.configure(&CAPTURES.map(|(capture, _)| capture));
+        Some(config)
+    }
+}
+
+/// Captures that get a style. Capture names match by prefix, so
+/// `comment.documentation` is styled as a comment; everything else stays
+/// unstyled.
+const CAPTURES: [(&str, CaptureStyle); 7] = [
+    ("comment", CaptureStyle::Comment),
+    ("keyword", CaptureStyle::Keyword),
+    ("string", CaptureStyle::String),
+    ("escape", CaptureStyle::String),
+    ("type", CaptureStyle::Type),
+    ("constructor", CaptureStyle::Type),
+    ("attribute", CaptureStyle::String),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum CaptureStyle {
+    Comment,
+    Keyword,
+    String,
+    Type,
+}
+
+impl CaptureStyle {
+    fn style(self) -> Style {
+        match self {
+            Self::Comment => Style::default().dim().italic(),
+            Self::Keyword => Style::default().bold(),
+            Self::String => Style::default().dim(),
+            Self::Type => Style::default().italic(),
+        }
+    }
+}
+
+fn config_for(language: CodeLanguage) -> Option<&'static HighlightConfiguration> {
+    static CONFIGS: [OnceLock<Option<HighlightConfiguration>>; CodeLanguage::ALL.len()] =
+        [const { OnceLock::new() }; CodeLanguage::ALL.len()];
+    CONFIGS[language as usize]
+        .get_or_init(|| language.build_config())
+        .as_ref()
+}
+
+/// Splits `code` into one styled `Line` per source line. Code that fails to
+/// highlight comes back unstyled, so the result always has one entry per
+/// line of `code`.
+pub(crate) fn highlight_code_to_lines(language: CodeLanguage, code: &str) -> Vec<Line<'static>> {
+    highlight(language, code).unwrap_or_else(|| code.split('\n').map(plain_line).collect())
+}
+
+fn highlight(language: CodeLanguage, code: &str) -> Option<Vec<Line<'static>>> {
+    let config = config_for(language)?;
+    let mut highlighter = Highlighter::new();
+    let events = highlighter
+        .highlight(config, code.as_bytes(), None, |_| None)
+        .ok()?;
+
+    let mut lines = vec![Line::from("")];
+    let mut stack: Vec<Highlight> = Vec::new();
+    for event in events {
+        match event.ok()? {
+            HighlightEvent::HighlightStart(highlight) => stack.push(highlight),
+            HighlightEvent::HighlightEnd => {
+                stack.pop();
+            }
+            HighlightEvent::Source { start, end } => {
+                let style = stack
+                    .last()
+                    .and_then(|highlight| CAPTURES.get(highlight.0))
+                    .map(|(_, capture)| capture.style())
+                    .unwrap_or_default();
+                for (i, part) in code.get(start..end)?.split('\n').enumerate() {
+                    if i > 0 {
+                        lines.push(Line::from(""));
+                    }
+                    if !part.is_empty()
+                        && let Some(line) = lines.last_mut()
+                    {
+                        line.spans.push(Span::styled(part.to_string(), style));
+                    }
+                }
+            }
+        }
+    }
+    Some(lines)
+}
+
+fn plain_line(text: &str) -> Line<'static> {
+    Line::from(text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ratatui::style::Modifier;
+
+    fn text(line: &Line<'_>) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    fn style_of(lines: &[Line<'_>], token: &str) -> Option<Style> {
+        lines
+            .iter()
+            .flat_map(|line| line.spans.iter())
+            .find(|span| span.content.as_ref() == token)
+            .map(|span| span.style)
+    }
+
+    #[test]
+    fn every_language_highlights() {
+        for language in CodeLanguage::ALL {
+            assert!(
+                config_for(language).is_some(),
+                "{language:?} highlight query should load"
+            );
+        }
+    }
+
+    #[test]
+    fn rust_keywords_comments_and_strings_are_styled() {
+        let code = "// note\nfn main() {\n    let s = \"hi\";\n}";
+        let lines = highlight_code_to_lines(CodeLanguage::Rust, code);
+
+        assert_eq!(
+            lines.iter().map(text).collect::<Vec<_>>(),
+            code.split('\n').collect::<Vec<_>>()
+        );
+        let keyword = style_of(&lines, "fn").expect("fn span");
+        assert!(keyword.add_modifier.contains(Modifier::BOLD));
+        let string = style_of(&lines, "\"hi\"").expect("string span");
+        assert!(string.add_modifier.contains(Modifier::DIM));
+        let comment = style_of(&lines, "// note").expect("comment span");
+        assert!(comment.add_modifier.contains(Modifier::ITALIC));
+        assert_eq!(keyword.fg, None);
+    }
+
+    #[test]
+    fn languages_are_detected_from_the_extension() {
+        assert_eq!(
+            CodeLanguage::from_path(Path::new("src/lib.rs")),
+            Some(CodeLanguage::Rust)
+        );
+        assert_eq!(
+            CodeLanguage::from_path(Path::new("web/App.TSX")),
+            Some(CodeLanguage::Tsx)
+        );
+        assert_eq!(CodeLanguage::from_path(Path::new("README.md")), None);
+        assert_eq!(CodeLanguage::from_path(Path::new("Makefile")), None);
+    }
+}
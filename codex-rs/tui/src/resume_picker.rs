@@ -51,6 +51,7 @@ pub enum SessionSelection {
 #[derive(Clone, Copy, Debug)]
 pub enum SessionPickerAction {
     Resume,
+    ResumeArchived,
     Fork,
 }
 
@@ -58,20 +59,23 @@ impl SessionPickerAction {
     fn title(self) -> &'static str {
         match self {
             SessionPickerAction::Resume => "Resume a previous session",
+            SessionPickerAction::ResumeArchived => "Resume an archived session",
             SessionPickerAction::Fork => "Fork a previous session",
         }
     }
 
     fn action_label(self) -> &'static str {
         match self {
-            SessionPickerAction::Resume => "resume",
+            SessionPickerAction::Resume | SessionPickerAction::ResumeArchived => "resume",
             SessionPickerAction::Fork => "fork",
         }
     }
 
     fn selection(self, path: PathBuf) -> SessionSelection {
         match self {
-            SessionPickerAction::Resume => SessionSelection::Resume(path),
+            SessionPickerAction::Resume | SessionPickerAction::ResumeArchived => {
+                SessionSelection::Resume(path)
+            }
             SessionPickerAction::Fork => SessionSelection::Fork(path),
         }
     }
@@ -115,6 +119,24 @@ pub async fn run_resume_picker(
     .await
 }
 
+/// Same as [`run_resume_picker`], but lists threads moved to cold storage.
+/// The selected rollout is restored to the sessions directory on resume.
+pub async fn run_archived_resume_picker(
+    tui: &mut Tui,
+    codex_home: &Path,
+    default_provider: &str,
+    show_all: bool,
+) -> Result<SessionSelection> {
+    run_session_picker(
+        tui,
+        codex_home,
+        default_provider,
+        show_all,
+        SessionPickerAction::ResumeArchived,
+    )
+    .await
+}
+
 pub async fn run_fork_picker(
     tui: &mut Tui,
     codex_home: &Path,
@@ -153,16 +175,35 @@ async fn run_session_picker(
         let tx = loader_tx.clone();
         tokio::spawn(async move {
             let provider_filter = vec![request.default_provider.clone()];
-            let page = RolloutRecorder::list_threads(
-                &request.codex_home,
-                PAGE_SIZE,
-                request.cursor.as_ref(),
-                ThreadSortKey::CreatedAt,
-                INTERACTIVE_SESSION_SOURCES,
-                Some(provider_filter.as_slice()),
-                request.default_provider.as_str(),
-            )
-            .await;
+            let cursor = request.cursor.as_ref();
+            let providers = Some(provider_filter.as_slice());
+            let default_provider = request.default_provider.as_str();
+            let page = match action {
+                SessionPickerAction::ResumeArchived => {
+                    RolloutRecorder::list_archived_threads(
+                        &request.codex_home,
+                        PAGE_SIZE,
+                        cursor,
+                        ThreadSortKey::CreatedAt,
+                        INTERACTIVE_SESSION_SOURCES,
+                        providers,
+                        default_provider,
+                    )
+                    .await
+                }
+                SessionPickerAction::Resume | SessionPickerAction::Fork => {
+                    RolloutRecorder::list_threads(
+                        &request.codex_home,
+                        PAGE_SIZE,
+                        cursor,
+                        ThreadSortKey::CreatedAt,
+                        INTERACTIVE_SESSION_SOURCES,
+                        providers,
+                        default_provider,
+                    )
+                    .await
+                }
+            };
             let _ = tx.send(BackgroundEvent::PageLoaded {
                 request_token: request.request_token,
                 search_token: request.search_token,
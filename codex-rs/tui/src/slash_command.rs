@@ -19,17 +19,37 @@ pub enum SlashCommand {
     ElevateSandbox,
     Experimental,
     Skills,
+    Agents,
+    Merge,
+    Tasks,
+    Queue,
+    FollowUps,
     Review,
     New,
     Resume,
     Fork,
+    Tab,
     Init,
     Compact,
+    Context,
     Collab,
-    // Undo,
+    Plan,
+    Checkpoint,
+    Worktree,
+    Pin,
+    Memory,
+    Undo,
     Diff,
+    Hunks,
+    Copy,
+    Save,
+    Find,
+    Keys,
+    Theme,
     Mention,
     Status,
+    ToolUsage,
+    WhyLast,
     Mcp,
     Logout,
     Quit,
@@ -37,6 +57,7 @@ pub enum SlashCommand {
     Feedback,
     Rollout,
     Ps,
+    Stdin,
     TestApproval,
 }
 
@@ -47,24 +68,55 @@ impl SlashCommand {
             SlashCommand::Feedback => "send logs to maintainers",
             SlashCommand::New => "start a new chat during a conversation",
             SlashCommand::Init => "create an AGENTS.md file with instructions for Codex",
-            SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
+            SlashCommand::Compact => {
+                "summarize conversation to free up context (/compact N-M: turns N..M)"
+            }
+            SlashCommand::Context => "inspect what fills the context window; drop or compact items",
             SlashCommand::Review => "review my current changes and find issues",
             SlashCommand::Resume => "resume a saved chat",
-            SlashCommand::Fork => "fork the current chat",
-            // SlashCommand::Undo => "ask Codex to undo a turn",
+            SlashCommand::Fork => "fork the current chat (/fork N keeps the first N turns)",
+            SlashCommand::Tab => "open another session in a tab (/tab new [PATH]), or switch tabs",
+            SlashCommand::Undo => "undo the last turn (/undo FILE: revert just that file)",
             SlashCommand::Quit | SlashCommand::Exit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
+            SlashCommand::Hunks => "keep or reject each hunk of the last applied patch",
+            SlashCommand::Copy => {
+                "copy the last code block to the clipboard (/copy reply, /copy select, /copy diff)"
+            }
+            SlashCommand::Save => "save the transcript as plain text (/save PATH)",
+            SlashCommand::Find => "search the transcript (/find TEXT)",
+            SlashCommand::Keys => "show the effective key bindings",
+            SlashCommand::Theme => "choose a color theme (/theme NAME)",
             SlashCommand::Mention => "mention a file",
+            SlashCommand::Agents => "list subagents, run one, or cancel a running one",
+            SlashCommand::Merge => "review and apply the changes staged by subagent runs",
+            SlashCommand::Tasks => "list background tasks, start one, or cancel a running one",
+            SlashCommand::Queue => {
+                "show messages queued behind the running turn, edit or clear them"
+            }
+            SlashCommand::FollowUps => "edit or queue a next step suggested by Codex",
             SlashCommand::Skills => "use skills to improve how Codex performs specific tasks",
             SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::ToolUsage => "show which tools and subagents used the most tokens",
+            SlashCommand::WhyLast => "explain why the last tool call was allowed to run",
             SlashCommand::Ps => "list background terminals",
+            SlashCommand::Stdin => "type a line into a running terminal (/stdin [ID] TEXT)",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Collab => "change collaboration mode (experimental)",
+            SlashCommand::Plan => {
+                "toggle plan mode: approve a plan before Codex edits or runs commands"
+            }
+            SlashCommand::Checkpoint => "create a named checkpoint, or list and restore one",
+            SlashCommand::Worktree => "review the isolated worktree, then merge or discard it",
+            SlashCommand::Pin => {
+                "pin a note, a file (/pin file PATH), or the last reply (/pin last)"
+            }
+            SlashCommand::Memory => "review, edit, or delete project memory entries",
             SlashCommand::Approvals => "choose what Codex can do without approval",
             SlashCommand::Permissions => "choose what Codex is allowed to do",
             SlashCommand::ElevateSandbox => "set up elevated agent sandbox",
             SlashCommand::Experimental => "toggle beta features",
-            SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Mcp => "list MCP tools, or add/remove a server for this session",
             SlashCommand::Logout => "log out of Codex",
             SlashCommand::Rollout => "print the rollout file path",
             SlashCommand::TestApproval => "test approval request",
@@ -85,19 +137,40 @@ impl SlashCommand {
             | SlashCommand::Fork
             | SlashCommand::Init
             | SlashCommand::Compact
-            // | SlashCommand::Undo
+            | SlashCommand::Undo
             | SlashCommand::Model
             | SlashCommand::Approvals
             | SlashCommand::Permissions
             | SlashCommand::ElevateSandbox
             | SlashCommand::Experimental
+            | SlashCommand::Plan
+            | SlashCommand::Checkpoint
+            | SlashCommand::Worktree
+            | SlashCommand::Merge
             | SlashCommand::Review
             | SlashCommand::Logout => false,
             SlashCommand::Diff
+            | SlashCommand::Hunks
+            | SlashCommand::Copy
+            | SlashCommand::Save
+            | SlashCommand::Find
+            | SlashCommand::Keys
+            | SlashCommand::Theme
+            | SlashCommand::Tab
             | SlashCommand::Mention
+            | SlashCommand::Pin
+            | SlashCommand::Context
+            | SlashCommand::Memory
             | SlashCommand::Skills
+            | SlashCommand::Agents
+            | SlashCommand::Tasks
+            | SlashCommand::Queue
+            | SlashCommand::FollowUps
             | SlashCommand::Status
+            | SlashCommand::ToolUsage
+            | SlashCommand::WhyLast
             | SlashCommand::Ps
+            | SlashCommand::Stdin
             | SlashCommand::Mcp
             | SlashCommand::Feedback
             | SlashCommand::Quit
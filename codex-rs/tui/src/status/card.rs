@@ -28,6 +28,7 @@ use super::format::push_label;
 use super::format::truncate_line_to_width;
 use super::helpers::compose_account_display;
 use super::helpers::compose_agents_summary;
+use super::helpers::compose_managed_policy_summary;
 use super::helpers::compose_model_display;
 use super::helpers::format_directory_display;
 use super::helpers::format_tokens_compact;
@@ -64,6 +65,7 @@ struct StatusHistoryCell {
     directory: PathBuf,
     approval: String,
     sandbox: String,
+    managed_policy: Option<String>,
     agents_summary: String,
     collaboration_mode: Option<String>,
     model_provider: Option<String>,
@@ -163,6 +165,7 @@ impl StatusHistoryCell {
                 }
             }
         };
+        let managed_policy = compose_managed_policy_summary(config);
         let agents_summary = compose_agents_summary(config);
         let model_provider = format_model_provider(config);
         let account = compose_account_display(auth_manager, plan_type);
@@ -193,6 +196,7 @@ impl StatusHistoryCell {
             directory: config.cwd.clone(),
             approval,
             sandbox,
+            managed_policy,
             agents_summary,
             collaboration_mode: collaboration_mode.map(ToString::to_string),
             model_provider,
@@ -381,6 +385,9 @@ impl HistoryCell for StatusHistoryCell {
         if self.model_provider.is_some() {
             push_label(&mut labels, &mut seen, "Model provider");
         }
+        if self.managed_policy.is_some() {
+            push_label(&mut labels, &mut seen, "Managed policy");
+        }
         if account_value.is_some() {
             push_label(&mut labels, &mut seen, "Account");
         }
@@ -436,6 +443,9 @@ impl HistoryCell for StatusHistoryCell {
         lines.push(formatter.line("Directory", vec![Span::from(directory_value)]));
         lines.push(formatter.line("Approval", vec![Span::from(self.approval.clone())]));
         lines.push(formatter.line("Sandbox", vec![Span::from(self.sandbox.clone())]));
+        if let Some(managed_policy) = self.managed_policy.as_ref() {
+            lines.push(formatter.line("Managed policy", vec![Span::from(managed_policy.clone())]));
+        }
         lines.push(formatter.line("Agents.md", vec![Span::from(self.agents_summary.clone())]));
 
         if let Some(account_value) = account_value {
@@ -5,6 +5,7 @@ use chrono::Local;
 use codex_app_server_protocol::AuthMode;
 use codex_core::AuthManager;
 use codex_core::config::Config;
+use codex_core::config_loader::SandboxModeRequirement;
 use codex_core::project_doc::discover_project_doc_paths;
 use codex_protocol::account::PlanType;
 use std::path::Path;
@@ -36,6 +37,44 @@ pub(crate) fn compose_model_display(
     (model_name.to_string(), details)
 }
 
+/// What `requirements.toml` or managed preferences lock, or `None` when
+/// nothing is managed.
+pub(crate) fn compose_managed_policy_summary(config: &Config) -> Option<String> {
+    let requirements = config.config_layer_stack.requirements_toml();
+    let mut parts = Vec::new();
+    if let Some(policies) = &requirements.allowed_approval_policies {
+        let policies: Vec<String> = policies.iter().map(ToString::to_string).collect();
+        parts.push(format!("approval {}", policies.join(", ")));
+    }
+    if let Some(modes) = &requirements.allowed_sandbox_modes {
+        let modes: Vec<&str> = modes
+            .iter()
+            .map(|mode| match mode {
+                SandboxModeRequirement::ReadOnly => "read-only",
+                SandboxModeRequirement::WorkspaceWrite => "workspace-write",
+                SandboxModeRequirement::DangerFullAccess => "danger-full-access",
+                SandboxModeRequirement::ExternalSandbox => "external-sandbox",
+            })
+            .collect();
+        parts.push(format!("sandbox {}", modes.join(", ")));
+    }
+    if let Some(providers) = &requirements.allowed_model_providers {
+        parts.push(format!("providers {}", providers.join(", ")));
+    }
+    if let Some(servers) = &requirements.mcp_servers {
+        if servers.is_empty() {
+            parts.push("no MCP servers".to_string());
+        } else {
+            let names: Vec<&str> = servers.keys().map(String::as_str).collect();
+            parts.push(format!("MCP servers {}", names.join(", ")));
+        }
+    }
+    if requirements.require_audit_log == Some(true) {
+        parts.push("audit log on".to_string());
+    }
+    (!parts.is_empty()).then(|| parts.join("; "))
+}
+
 pub(crate) fn compose_agents_summary(config: &Config) -> String {
     match discover_project_doc_paths(config) {
         Ok(paths) => {
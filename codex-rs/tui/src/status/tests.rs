@@ -7,6 +7,9 @@ use chrono::Utc;
 use codex_core::AuthManager;
 use codex_core::config::Config;
 use codex_core::config::ConfigBuilder;
+use codex_core::config_loader::ConfigLayerStack;
+use codex_core::config_loader::ConfigRequirements;
+use codex_core::config_loader::ConfigRequirementsToml;
 use codex_core::models_manager::manager::ModelsManager;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::RateLimitSnapshot;
@@ -923,3 +926,51 @@ async fn status_context_window_uses_last_usage() {
         "context line should not use total aggregated tokens, got: {context_line}"
     );
 }
+
+#[tokio::test]
+async fn status_card_shows_managed_policy() {
+    let temp_home = TempDir::new().expect("temp home");
+    let mut config = test_config(&temp_home).await;
+    let requirements: ConfigRequirementsToml = toml::from_str(
+        r#"
+allowed_approval_policies = ["on-request"]
+allowed_model_providers = ["openai"]
+require_audit_log = true
+"#,
+    )
+    .expect("requirements");
+    config.config_layer_stack =
+        ConfigLayerStack::new(Vec::new(), ConfigRequirements::default(), requirements)
+            .expect("layer stack");
+
+    let auth_manager = test_auth_manager(&config);
+    let usage = TokenUsage::default();
+    let now = chrono::Local
+        .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+        .single()
+        .expect("timestamp");
+    let model_slug = ModelsManager::get_model_offline(config.model.as_deref());
+    let composite = new_status_output(
+        &config,
+        &auth_manager,
+        None,
+        &usage,
+        &None,
+        None,
+        None,
+        None,
+        now,
+        &model_slug,
+        None,
+        None,
+    );
+    let policy_line = render_lines(&composite.display_lines(120))
+        .into_iter()
+        .find(|line| line.contains("Managed policy"))
+        .expect("managed policy line");
+
+    assert!(
+        policy_line.contains("approval on-request; providers openai; audit log on"),
+        "unexpected managed policy line: {policy_line}"
+    );
+}
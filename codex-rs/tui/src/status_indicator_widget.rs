@@ -34,6 +34,8 @@ pub(crate) struct StatusIndicatorWidget {
     /// Animated header text (defaults to "Working").
     header: String,
     details: Option<String>,
+    /// When set, the header counts down to this instant.
+    resume_at: Option<Instant>,
     show_interrupt_hint: bool,
 
     elapsed_running: Duration,
@@ -70,6 +72,7 @@ impl StatusIndicatorWidget {
         Self {
             header: String::from("Working"),
             details: None,
+            resume_at: None,
             show_interrupt_hint: true,
             elapsed_running: Duration::ZERO,
             last_resume_at: Instant::now(),
@@ -88,6 +91,29 @@ impl StatusIndicatorWidget {
     /// Update the animated header label (left of the brackets).
     pub(crate) fn update_header(&mut self, header: String) {
         self.header = header;
+        self.resume_at = None;
+    }
+
+    /// Update the header and append a countdown to `resume_at`, e.g.
+    /// "Rate limited, resuming in 42s".
+    pub(crate) fn update_header_with_countdown(&mut self, header: String, resume_at: Instant) {
+        self.header = header;
+        self.resume_at = Some(resume_at);
+    }
+
+    fn header_at(&self, now: Instant) -> String {
+        match self.resume_at {
+            Some(resume_at) => {
+                let remaining = resume_at.saturating_duration_since(now);
+                let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+                format!(
+                    "{}, resuming in {}",
+                    self.header,
+                    fmt_elapsed_compact(seconds)
+                )
+            }
+            None => self.header.clone(),
+        }
     }
 
     /// Update the details text shown below the header.
@@ -206,14 +232,15 @@ impl Renderable for StatusIndicatorWidget {
         let now = Instant::now();
         let elapsed_duration = self.elapsed_duration_at(now);
         let pretty_elapsed = fmt_elapsed_compact(elapsed_duration.as_secs());
+        let header = self.header_at(now);
 
         let mut spans = Vec::with_capacity(5);
         spans.push(spinner(Some(self.last_resume_at), self.animations_enabled));
         spans.push(" ".into());
         if self.animations_enabled {
-            spans.extend(shimmer_spans(&self.header));
-        } else if !self.header.is_empty() {
-            spans.push(self.header.clone().into());
+            spans.extend(shimmer_spans(&header));
+        } else if !header.is_empty() {
+            spans.push(header.into());
         }
         spans.push(" ".into());
         if self.show_interrupt_hint {
@@ -266,6 +293,27 @@ mod tests {
         assert_eq!(fmt_elapsed_compact(25 * 3600 + 2 * 60 + 3), "25h 02m 03s");
     }
 
+    #[test]
+    fn countdown_header_rounds_up_and_clears_on_update() {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let mut w = StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), true);
+        let now = Instant::now();
+
+        w.update_header_with_countdown(
+            "Rate limited".to_string(),
+            now + Duration::from_millis(41_200),
+        );
+        assert_eq!(w.header_at(now), "Rate limited, resuming in 42s");
+        assert_eq!(
+            w.header_at(now + Duration::from_secs(60)),
+            "Rate limited, resuming in 0s"
+        );
+
+        w.update_header("Working".to_string());
+        assert_eq!(w.header_at(now), "Working");
+    }
+
     #[test]
     fn renders_with_working_header() {
         let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
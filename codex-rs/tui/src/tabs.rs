@@ -0,0 +1,387 @@
+//! Session tabs: several conversations in one TUI, each bound to its own
+//! thread and working directory.
+//!
+//! The active tab's widget and transcript stay where they always were, in
+//! [`crate::app::App`]. The other tabs are parked in [`SessionTabs`] and keep
+//! consuming their thread's events in the background. Every widget sends its
+//! events through [`crate::app_event_sender::AppEventSender::for_tab`], so the
+//! app can tell which tab an event belongs to.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+use crate::chatwidget::ChatWidget;
+use crate::history_cell::HistoryCell;
+use crate::status::format_directory_display;
+use crate::text_formatting::truncate_text;
+
+/// Longest tab title shown in the tab bar, in graphemes.
+const MAX_TITLE_LEN: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct TabId(u64);
+
+/// What `/tab` (or a tab key) asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TabCommand {
+    /// List the open tabs.
+    List,
+    /// Open a tab with a fresh session, in `cwd` or the current tab's
+    /// directory.
+    Open(Option<PathBuf>),
+    /// Switch to the tab with this 1-based number.
+    Select(usize),
+    Next,
+    Previous,
+    /// Close the active tab and shut its thread down.
+    Close,
+}
+
+impl TabCommand {
+    pub(crate) const USAGE: &'static str = "Usage: /tab [new [PATH] | N | next | prev | close]";
+
+    /// Parses the arguments of `/tab`.
+    pub(crate) fn parse(args: &str) -> Option<Self> {
+        let args = args.trim();
+        let (verb, rest) = args
+            .split_once(char::is_whitespace)
+            .map_or((args, ""), |(verb, rest)| (verb, rest.trim()));
+        match verb {
+            "" | "list" if rest.is_empty() => Some(Self::List),
+            "new" | "open" => Some(Self::Open((!rest.is_empty()).then(|| PathBuf::from(rest)))),
+            "next" if rest.is_empty() => Some(Self::Next),
+            "prev" | "previous" if rest.is_empty() => Some(Self::Previous),
+            "close" if rest.is_empty() => Some(Self::Close),
+            number if rest.is_empty() => match number.parse::<usize>() {
+                Ok(number) if number > 0 => Some(Self::Select(number)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// A tab that is not on screen.
+pub(crate) struct ParkedTab {
+    pub(crate) chat_widget: ChatWidget,
+    pub(crate) transcript_cells: Vec<Arc<dyn HistoryCell>>,
+    /// New transcript output arrived since the tab was last shown.
+    pub(crate) unread: bool,
+    /// An approval or question is waiting for the user.
+    pub(crate) needs_attention: bool,
+}
+
+impl ParkedTab {
+    pub(crate) fn new(chat_widget: ChatWidget) -> Self {
+        Self {
+            chat_widget,
+            transcript_cells: Vec::new(),
+            unread: false,
+            needs_attention: false,
+        }
+    }
+}
+
+/// The open tabs in display order, and the parked state of the inactive ones.
+pub(crate) struct SessionTabs {
+    order: Vec<TabId>,
+    active: TabId,
+    next_id: u64,
+    parked: HashMap<TabId, ParkedTab>,
+}
+
+impl SessionTabs {
+    /// A single tab, which is active.
+    pub(crate) fn new() -> Self {
+        let first = TabId(0);
+        Self {
+            order: vec![first],
+            active: first,
+            next_id: 1,
+            parked: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn active(&self) -> TabId {
+        self.active
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub(crate) fn is_active(&self, tab: TabId) -> bool {
+        self.active == tab
+    }
+
+    /// 0-based position of the active tab.
+    pub(crate) fn active_index(&self) -> usize {
+        self.order
+            .iter()
+            .position(|tab| *tab == self.active)
+            .unwrap_or_default()
+    }
+
+    /// Tab ids in display order.
+    pub(crate) fn order(&self) -> &[TabId] {
+        &self.order
+    }
+
+    /// Tab `number` (1-based), if it exists.
+    pub(crate) fn by_number(&self, number: usize) -> Option<TabId> {
+        number
+            .checked_sub(1)
+            .and_then(|idx| self.order.get(idx))
+            .copied()
+    }
+
+    /// The tab `offset` places from the active one, wrapping around.
+    pub(crate) fn neighbor(&self, offset: isize) -> TabId {
+        let len = self.order.len() as isize;
+        let idx = (self.active_index() as isize + offset).rem_euclid(len);
+        self.order[idx as usize]
+    }
+
+    /// Adds a tab after the last one and returns its id. The caller parks its
+    /// widget with [`SessionTabs::park`].
+    pub(crate) fn open(&mut self) -> TabId {
+        let tab = TabId(self.next_id);
+        self.next_id += 1;
+        self.order.push(tab);
+        tab
+    }
+
+    pub(crate) fn park(&mut self, tab: TabId, parked: ParkedTab) {
+        self.parked.insert(tab, parked);
+    }
+
+    /// Takes `tab` out of the parked set so it can become active.
+    pub(crate) fn unpark(&mut self, tab: TabId) -> Option<ParkedTab> {
+        self.parked.remove(&tab)
+    }
+
+    pub(crate) fn set_active(&mut self, tab: TabId) {
+        self.active = tab;
+    }
+
+    /// Drops a parked tab from the tab list and returns it.
+    pub(crate) fn remove(&mut self, tab: TabId) -> Option<ParkedTab> {
+        self.order.retain(|candidate| *candidate != tab);
+        self.parked.remove(&tab)
+    }
+
+    pub(crate) fn parked(&self, tab: TabId) -> Option<&ParkedTab> {
+        self.parked.get(&tab)
+    }
+
+    pub(crate) fn parked_mut(&mut self, tab: TabId) -> Option<&mut ParkedTab> {
+        self.parked.get_mut(&tab)
+    }
+
+    pub(crate) fn all_parked_mut(&mut self) -> impl Iterator<Item = &mut ParkedTab> {
+        self.parked.values_mut()
+    }
+}
+
+/// What the tab bar and `/tab` show for one tab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TabLabel {
+    pub(crate) cwd: PathBuf,
+    pub(crate) active: bool,
+    pub(crate) running: bool,
+    pub(crate) unread: bool,
+    pub(crate) needs_attention: bool,
+}
+
+impl TabLabel {
+    /// The last component of the tab's directory.
+    fn title(&self) -> String {
+        let title = self.cwd.file_name().map_or_else(
+            || self.cwd.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        truncate_text(&title, MAX_TITLE_LEN)
+    }
+}
+
+/// The tab bar: numbered titles, the active one highlighted. A `!` marks a
+/// tab waiting for an approval, `•` a running turn and `*` unread output.
+pub(crate) fn tab_bar_line(labels: &[TabLabel]) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    for (idx, label) in labels.iter().enumerate() {
+        if idx > 0 {
+            spans.push(" │".dim());
+        }
+        let text = format!(" {} {}", idx + 1, label.title());
+        spans.push(if label.active {
+            text.cyan().bold()
+        } else {
+            text.dim()
+        });
+        if label.needs_attention {
+            spans.push(" !".red().bold());
+        } else if label.running {
+            spans.push(" •".cyan());
+        } else if label.unread {
+            spans.push(" *".magenta());
+        }
+    }
+    Line::from(spans)
+}
+
+/// The `/tab` view: every tab with its directory and state.
+pub(crate) fn tab_list_lines(labels: &[TabLabel]) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line<'static>> = vec!["/tab".magenta().into(), "".into()];
+    for (idx, label) in labels.iter().enumerate() {
+        let mut spans: Vec<Span<'static>> = vec![
+            format!("  {}  ", idx + 1).into(),
+            format_directory_display(&label.cwd, None).into(),
+        ];
+        if label.active {
+            spans.push("  (current)".cyan());
+        }
+        if label.needs_attention {
+            spans.push("  waiting for approval".red());
+        } else if label.running {
+            spans.push("  working".dim());
+        } else if label.unread {
+            spans.push("  new output".dim());
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push("".into());
+    lines.push(
+        "Open a tab with /tab new [PATH], switch with /tab N, close with /tab close."
+            .dim()
+            .into(),
+    );
+    lines
+}
+
+/// Divider printed above a tab's transcript when switching to it.
+pub(crate) fn tab_divider_line(number: usize, cwd: &Path) -> Line<'static> {
+    Line::from(
+        format!(
+            "── tab {number} · {} ──",
+            format_directory_display(cwd, None)
+        )
+        .dim(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn label(dir: &str) -> TabLabel {
+        TabLabel {
+            cwd: PathBuf::from("/work").join(dir),
+            active: false,
+            running: false,
+            unread: false,
+            needs_attention: false,
+        }
+    }
+
+    #[test]
+    fn tab_command_parses_arguments() {
+        assert_eq!(TabCommand::parse(""), Some(TabCommand::List));
+        assert_eq!(TabCommand::parse("new"), Some(TabCommand::Open(None)));
+        assert_eq!(
+            TabCommand::parse("new  ../other repo"),
+            Some(TabCommand::Open(Some(PathBuf::from("../other repo"))))
+        );
+        assert_eq!(TabCommand::parse("2"), Some(TabCommand::Select(2)));
+        assert_eq!(TabCommand::parse("prev"), Some(TabCommand::Previous));
+        assert_eq!(TabCommand::parse("close"), Some(TabCommand::Close));
+        assert_eq!(TabCommand::parse("0"), None);
+        assert_eq!(TabCommand::parse("close 2"), None);
+        assert_eq!(TabCommand::parse("rename x"), None);
+    }
+
+    #[test]
+    fn neighbor_wraps_around() {
+        let mut tabs = SessionTabs::new();
+        let first = tabs.active();
+        let second = tabs.open();
+        let third = tabs.open();
+        assert_eq!(tabs.neighbor(1), second);
+        assert_eq!(tabs.neighbor(-1), third);
+
+        tabs.set_active(third);
+        assert_eq!(tabs.neighbor(1), first);
+        assert_eq!(tabs.by_number(2), Some(second));
+        assert_eq!(tabs.by_number(4), None);
+
+        tabs.remove(second);
+        assert_eq!(tabs.order(), &[first, third]);
+        assert_eq!(tabs.active_index(), 1);
+    }
+
+    #[test]
+    fn tab_bar_marks_the_active_tab_and_activity() {
+        let line = tab_bar_line(&[
+            TabLabel {
+                active: true,
+                ..label("codex")
+            },
+            TabLabel {
+                running: true,
+                ..label("web")
+            },
+            TabLabel {
+                unread: true,
+                needs_attention: true,
+                ..label("a-directory-with-a-very-long-name")
+            },
+        ]);
+        let text: String = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(text, " 1 codex │ 2 web • │ 3 a-directory-with-a-ve... !");
+        assert!(
+            line.spans[0]
+                .style
+                .add_modifier
+                .contains(ratatui::style::Modifier::BOLD)
+        );
+    }
+
+    #[test]
+    fn tab_list_shows_directories_and_state() {
+        let lines = tab_list_lines(&[
+            label("codex"),
+            TabLabel {
+                active: true,
+                running: true,
+                ..label("web")
+            },
+        ]);
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            text[1..4],
+            [
+                "".to_string(),
+                "  1  /work/codex".to_string(),
+                "  2  /work/web  (current)  working".to_string(),
+            ]
+        );
+    }
+}
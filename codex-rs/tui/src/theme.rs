@@ -0,0 +1,431 @@
+//! Color themes (`[tui] theme`, `/theme`).
+//!
+//! Widgets keep styling text with the ANSI colors from `styles.md`. When
+//! cells are written to the terminal (`custom_terminal` and
+//! `insert_history`), the active theme swaps those colors for its own, so a
+//! theme reaches every widget without each one knowing about it. Lines that
+//! are already in the terminal's scrollback keep the colors they were
+//! printed with.
+
+use std::ops::Range;
+use std::sync::LazyLock;
+use std::sync::RwLock;
+
+use codex_core::config::Config;
+use codex_core::config::types::ThemePaletteToml;
+use codex_core::config::types::ThemeVariant;
+use ratatui::style::Color;
+use thiserror::Error;
+
+use crate::color::is_light;
+use crate::terminal_palette::best_color;
+use crate::terminal_palette::default_bg;
+
+type Rgb = (u8, u8, u8);
+
+/// Colors a theme gives each role. `None` keeps the terminal's ANSI color.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Palette {
+    accent: Option<Rgb>,
+    success: Option<Rgb>,
+    error: Option<Rgb>,
+    codex: Option<Rgb>,
+    user_message_bg: Option<Rgb>,
+}
+
+const TERMINAL: Palette = Palette {
+    accent: None,
+    success: None,
+    error: None,
+    codex: None,
+    user_message_bg: None,
+};
+
+pub(crate) struct ThemeSpec {
+    pub(crate) name: &'static str,
+    pub(crate) description: &'static str,
+    dark: Palette,
+    light: Palette,
+}
+
+pub(crate) const THEMES: &[ThemeSpec] = &[
+    ThemeSpec {
+        name: "default",
+        description: "the terminal's own colors",
+        dark: TERMINAL,
+        light: TERMINAL,
+    },
+    ThemeSpec {
+        name: "solarized",
+        description: "Solarized accents",
+        dark: Palette {
+            accent: Some((0x2a, 0xa1, 0x98)),
+            success: Some((0x85, 0x99, 0x00)),
+            error: Some((0xdc, 0x32, 0x2f)),
+            codex: Some((0xd3, 0x36, 0x82)),
+            user_message_bg: Some((0x07, 0x36, 0x42)),
+        },
+        light: Palette {
+            accent: Some((0x1f, 0x7a, 0x73)),
+            success: Some((0x5f, 0x6e, 0x00)),
+            error: Some((0xc0, 0x26, 0x24)),
+            codex: Some((0xb0, 0x2a, 0x6b)),
+            user_message_bg: Some((0xee, 0xe8, 0xd5)),
+        },
+    },
+    ThemeSpec {
+        name: "gruvbox",
+        description: "Gruvbox's warm palette",
+        dark: Palette {
+            accent: Some((0x8e, 0xc0, 0x7c)),
+            success: Some((0xb8, 0xbb, 0x26)),
+            error: Some((0xfb, 0x49, 0x34)),
+            codex: Some((0xd3, 0x86, 0x9b)),
+            user_message_bg: Some((0x3c, 0x38, 0x36)),
+        },
+        light: Palette {
+            accent: Some((0x42, 0x7b, 0x58)),
+            success: Some((0x79, 0x74, 0x0e)),
+            error: Some((0x9d, 0x00, 0x06)),
+            codex: Some((0x8f, 0x3f, 0x71)),
+            user_message_bg: Some((0xeb, 0xdb, 0xb2)),
+        },
+    },
+    ThemeSpec {
+        name: "high-contrast",
+        description: "saturated colors that stand out on any background",
+        dark: Palette {
+            accent: Some((0x00, 0xff, 0xff)),
+            success: Some((0x00, 0xff, 0x5f)),
+            error: Some((0xff, 0x5f, 0x5f)),
+            codex: Some((0xff, 0x5f, 0xff)),
+            user_message_bg: None,
+        },
+        light: Palette {
+            accent: Some((0x00, 0x5f, 0x87)),
+            success: Some((0x00, 0x5f, 0x00)),
+            error: Some((0xaf, 0x00, 0x00)),
+            codex: Some((0x87, 0x00, 0x87)),
+            user_message_bg: None,
+        },
+    },
+];
+
+fn find_theme(name: &str) -> Result<&'static ThemeSpec, ThemeError> {
+    THEMES
+        .iter()
+        .find(|theme| theme.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| ThemeError::UnknownTheme {
+            name: name.to_string(),
+            available: THEMES
+                .iter()
+                .map(|theme| theme.name)
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub(crate) enum ThemeError {
+    #[error("unknown theme `{name}` (available: {available})")]
+    UnknownTheme { name: String, available: String },
+    #[error("`{role}`: invalid color `{value}`; use `#rrggbb` or an ANSI color name")]
+    InvalidColor { role: &'static str, value: String },
+}
+
+/// A color from `[tui.theme_palette]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PaletteColor {
+    Rgb(Rgb),
+    Ansi(Color),
+}
+
+/// `[tui.theme_palette]`, parsed. Each role replaces the theme's color.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct PaletteOverrides {
+    accent: Option<PaletteColor>,
+    success: Option<PaletteColor>,
+    error: Option<PaletteColor>,
+    codex: Option<PaletteColor>,
+    user_message_bg: Option<PaletteColor>,
+}
+
+impl PaletteOverrides {
+    fn parse(toml: &ThemePaletteToml) -> Result<Self, ThemeError> {
+        let parse = |role: &'static str, value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|value| {
+                    parse_color(value).ok_or_else(|| ThemeError::InvalidColor {
+                        role,
+                        value: value.to_string(),
+                    })
+                })
+                .transpose()
+        };
+        Ok(Self {
+            accent: parse("accent", &toml.accent)?,
+            success: parse("success", &toml.success)?,
+            error: parse("error", &toml.error)?,
+            codex: parse("codex", &toml.codex)?,
+            user_message_bg: parse("user_message_bg", &toml.user_message_bg)?,
+        })
+    }
+}
+
+fn parse_color(value: &str) -> Option<PaletteColor> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |range: Range<usize>| u8::from_str_radix(hex.get(range)?, 16).ok();
+        return Some(PaletteColor::Rgb((
+            channel(0..2)?,
+            channel(2..4)?,
+            channel(4..6)?,
+        )));
+    }
+    let name = value.to_ascii_lowercase().replace(['_', ' '], "-");
+    let color = match name.as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "dark-gray" | "dark-grey" => Color::DarkGray,
+        "light-red" => Color::LightRed,
+        "light-green" => Color::LightGreen,
+        "light-yellow" => Color::LightYellow,
+        "light-blue" => Color::LightBlue,
+        "light-magenta" => Color::LightMagenta,
+        "light-cyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    };
+    Some(PaletteColor::Ansi(color))
+}
+
+/// Colors of the active theme, converted to what the terminal can show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ThemeColors {
+    accent: Option<Color>,
+    success: Option<Color>,
+    error: Option<Color>,
+    codex: Option<Color>,
+    user_message_bg: Option<Color>,
+}
+
+impl ThemeColors {
+    fn resolve(
+        palette: Palette,
+        overrides: PaletteOverrides,
+        to_color: impl Fn(Rgb) -> Option<Color>,
+    ) -> Self {
+        let role = |overridden: Option<PaletteColor>, themed: Option<Rgb>| match overridden {
+            Some(PaletteColor::Ansi(color)) => Some(color),
+            Some(PaletteColor::Rgb(rgb)) => to_color(rgb),
+            None => themed.and_then(&to_color),
+        };
+        Self {
+            accent: role(overrides.accent, palette.accent),
+            success: role(overrides.success, palette.success),
+            error: role(overrides.error, palette.error),
+            codex: role(overrides.codex, palette.codex),
+            user_message_bg: role(overrides.user_message_bg, palette.user_message_bg),
+        }
+    }
+
+    /// The color to write for `color`: the theme's color for the ANSI colors
+    /// the style guide assigns a role, otherwise `color` itself.
+    pub(crate) fn apply(&self, color: Color) -> Color {
+        let themed = match color {
+            Color::Cyan => self.accent,
+            Color::Green => self.success,
+            Color::Red => self.error,
+            Color::Magenta => self.codex,
+            _ => None,
+        };
+        themed.unwrap_or(color)
+    }
+
+    pub(crate) fn user_message_bg(&self) -> Option<Color> {
+        self.user_message_bg
+    }
+}
+
+/// Nearest color the terminal can show, or `None` when color support is
+/// unknown and the ANSI color is the safer choice.
+fn terminal_color(rgb: Rgb) -> Option<Color> {
+    let color = best_color(rgb);
+    (color != Color::Reset).then_some(color)
+}
+
+fn resolve_variant(variant: ThemeVariant) -> ThemeVariant {
+    match variant {
+        ThemeVariant::Auto if default_bg().is_some_and(is_light) => ThemeVariant::Light,
+        ThemeVariant::Auto => ThemeVariant::Dark,
+        variant => variant,
+    }
+}
+
+struct ActiveTheme {
+    spec: &'static ThemeSpec,
+    variant: ThemeVariant,
+    overrides: PaletteOverrides,
+    colors: ThemeColors,
+}
+
+impl ActiveTheme {
+    fn new(spec: &'static ThemeSpec, variant: ThemeVariant, overrides: PaletteOverrides) -> Self {
+        let palette = match resolve_variant(variant) {
+            ThemeVariant::Light => spec.light,
+            ThemeVariant::Auto | ThemeVariant::Dark => spec.dark,
+        };
+        Self {
+            spec,
+            variant,
+            overrides,
+            colors: ThemeColors::resolve(palette, overrides, terminal_color),
+        }
+    }
+}
+
+static ACTIVE: LazyLock<RwLock<Option<ActiveTheme>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Applies the theme settings from `config`. Fails on an unknown theme name
+/// or a color that does not parse.
+pub(crate) fn install(config: &Config) -> Result<(), ThemeError> {
+    let spec = find_theme(config.tui_theme.as_deref().unwrap_or("default"))?;
+    let overrides = PaletteOverrides::parse(&config.tui_theme_palette)?;
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = Some(ActiveTheme::new(spec, config.tui_theme_variant, overrides));
+    }
+    Ok(())
+}
+
+/// Switches to the theme called `name`, keeping the configured variant and
+/// palette. The caller must redraw the whole screen afterwards.
+pub(crate) fn set_theme(name: &str) -> Result<&'static str, ThemeError> {
+    let spec = find_theme(name)?;
+    if let Ok(mut active) = ACTIVE.write() {
+        let (variant, overrides) = active
+            .as_ref()
+            .map(|theme| (theme.variant, theme.overrides))
+            .unwrap_or_default();
+        *active = Some(ActiveTheme::new(spec, variant, overrides));
+    }
+    Ok(spec.name)
+}
+
+/// Colors of the active theme. Identity mapping until [`install`] runs.
+pub(crate) fn colors() -> ThemeColors {
+    ACTIVE
+        .read()
+        .ok()
+        .and_then(|active| active.as_ref().map(|theme| theme.colors))
+        .unwrap_or_default()
+}
+
+/// Name of the active theme.
+pub(crate) fn active_name() -> &'static str {
+    ACTIVE
+        .read()
+        .ok()
+        .and_then(|active| active.as_ref().map(|theme| theme.spec.name))
+        .unwrap_or("default")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[allow(clippy::disallowed_methods)]
+    fn rgb((r, g, b): Rgb) -> Option<Color> {
+        Some(Color::Rgb(r, g, b))
+    }
+
+    #[test]
+    fn palette_colors_parse_as_hex_or_ansi_names() {
+        assert_eq!(
+            parse_color("#268BD2"),
+            Some(PaletteColor::Rgb((0x26, 0x8b, 0xd2)))
+        );
+        assert_eq!(
+            parse_color("Light_Red"),
+            Some(PaletteColor::Ansi(Color::LightRed))
+        );
+        assert_eq!(parse_color("grey"), Some(PaletteColor::Ansi(Color::Gray)));
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("#gg0000"), None);
+        assert_eq!(parse_color("teal"), None);
+
+        assert_eq!(
+            PaletteOverrides::parse(&ThemePaletteToml {
+                codex: Some("purple".to_string()),
+                ..Default::default()
+            }),
+            Err(ThemeError::InvalidColor {
+                role: "codex",
+                value: "purple".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn themes_remap_only_the_style_guide_colors() {
+        let solarized = find_theme("Solarized").expect("built-in theme");
+        let colors = ThemeColors::resolve(solarized.dark, PaletteOverrides::default(), rgb);
+        assert_eq!(
+            colors.apply(Color::Cyan),
+            rgb((0x2a, 0xa1, 0x98)).unwrap_or_default()
+        );
+        assert_eq!(colors.apply(Color::Blue), Color::Blue);
+        assert_eq!(colors.apply(Color::Reset), Color::Reset);
+
+        let default = find_theme("default").expect("built-in theme");
+        let colors = ThemeColors::resolve(default.light, PaletteOverrides::default(), rgb);
+        assert_eq!(colors, ThemeColors::default());
+    }
+
+    #[test]
+    fn palette_overrides_win_over_the_theme() {
+        let gruvbox = find_theme("gruvbox").expect("built-in theme");
+        let overrides = PaletteOverrides::parse(&ThemePaletteToml {
+            error: Some("light-red".to_string()),
+            accent: Some("#000080".to_string()),
+            ..Default::default()
+        })
+        .expect("valid palette");
+        let colors = ThemeColors::resolve(gruvbox.light, overrides, rgb);
+        assert_eq!(colors.apply(Color::Red), Color::LightRed);
+        assert_eq!(
+            colors.apply(Color::Cyan),
+            rgb((0, 0, 0x80)).unwrap_or_default()
+        );
+        assert_eq!(
+            colors.apply(Color::Green),
+            rgb((0x79, 0x74, 0x0e)).unwrap_or_default()
+        );
+
+        // Without color support the theme's own colors fall back to ANSI.
+        let colors = ThemeColors::resolve(gruvbox.dark, overrides, |_| None);
+        assert_eq!(colors.apply(Color::Green), Color::Green);
+        assert_eq!(colors.apply(Color::Red), Color::LightRed);
+    }
+
+    #[test]
+    fn unknown_themes_list_the_available_ones() {
+        assert_eq!(
+            find_theme("nord").err(),
+            Some(ThemeError::UnknownTheme {
+                name: "nord".to_string(),
+                available: "default, solarized, gruvbox, high-contrast".to_string(),
+            })
+        );
+    }
+}
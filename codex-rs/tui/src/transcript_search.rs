@@ -0,0 +1,340 @@
+//! Incremental search over the transcript overlay (`Ctrl+F` or `/find`).
+//!
+//! Matches are found in the transcript lines of committed cells, which show
+//! tool output in full even where the main view collapses it. Lines are
+//! searched at the width the overlay renders at, so a match's line index is
+//! also its row within the cell. Queries are smart-case: a query without
+//! uppercase letters ignores case.
+
+use std::ops::Range;
+
+use ratatui::style::Style;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+/// One occurrence of the query in the transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SearchMatch {
+    /// Index into the overlay's committed cells.
+    pub(crate) cell: usize,
+    /// Index into the cell's transcript lines.
+    pub(crate) line: usize,
+    /// Byte range within the line's text.
+    pub(crate) range: Range<usize>,
+}
+
+/// A match to paint in a rendered cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LineHighlight {
+    pub(crate) line: usize,
+    pub(crate) range: Range<usize>,
+    pub(crate) current: bool,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct TranscriptSearch {
+    query: String,
+    /// Keys edit the query while `true`; afterwards they navigate matches.
+    editing: bool,
+    matches: Vec<SearchMatch>,
+    current: Option<usize>,
+}
+
+impl TranscriptSearch {
+    pub(crate) fn new(query: String) -> Self {
+        Self {
+            editing: query.is_empty(),
+            query,
+            matches: Vec::new(),
+            current: None,
+        }
+    }
+
+    pub(crate) fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub(crate) fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    pub(crate) fn set_editing(&mut self, editing: bool) {
+        self.editing = editing;
+    }
+
+    pub(crate) fn push_char(&mut self, c: char) {
+        self.query.push(c);
+    }
+
+    pub(crate) fn pop_char(&mut self) {
+        self.query.pop();
+    }
+
+    /// Replaces the matches, keeping the current one when it still matches
+    /// and otherwise selecting the last (most recent) match.
+    pub(crate) fn set_matches(&mut self, matches: Vec<SearchMatch>) {
+        let previous = self.current_match().cloned();
+        self.current = previous
+            .and_then(|previous| matches.iter().position(|m| *m == previous))
+            .or_else(|| matches.len().checked_sub(1));
+        self.matches = matches;
+    }
+
+    pub(crate) fn current_match(&self) -> Option<&SearchMatch> {
+        self.current.and_then(|idx| self.matches.get(idx))
+    }
+
+    /// Moves to the next match (towards the end of the transcript), wrapping
+    /// around.
+    pub(crate) fn select_next(&mut self) {
+        if let Some(current) = self.current {
+            self.current = Some((current + 1) % self.matches.len());
+        }
+    }
+
+    /// Moves to the previous match, wrapping around.
+    pub(crate) fn select_previous(&mut self) {
+        if let Some(current) = self.current {
+            self.current = Some(current.checked_sub(1).unwrap_or(self.matches.len() - 1));
+        }
+    }
+
+    /// Matches inside `cell`, for rendering.
+    pub(crate) fn highlights_for(&self, cell: usize) -> Vec<LineHighlight> {
+        let current = self.current_match();
+        self.matches
+            .iter()
+            .filter(|m| m.cell == cell)
+            .map(|m| LineHighlight {
+                line: m.line,
+                range: m.range.clone(),
+                current: current == Some(m),
+            })
+            .collect()
+    }
+
+    /// The prompt shown below the transcript, e.g. `/needle  3/12`.
+    pub(crate) fn status_line(&self) -> Line<'static> {
+        let mut spans: Vec<Span<'static>> = vec![" /".cyan(), self.query.clone().into()];
+        if self.editing {
+            spans.push("█".dim());
+        }
+        if !self.query.is_empty() {
+            spans.push("  ".into());
+            spans.push(match self.current {
+                Some(current) => format!("{}/{}", current + 1, self.matches.len()).dim(),
+                None => "no matches".red(),
+            });
+        }
+        Line::from(spans)
+    }
+}
+
+/// Finds every match of `query` in the given cells' transcript lines.
+pub(crate) fn find_matches<'a>(
+    cells: impl IntoIterator<Item = Vec<Line<'a>>>,
+    query: &str,
+) -> Vec<SearchMatch> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches = Vec::new();
+    for (cell, lines) in cells.into_iter().enumerate() {
+        for (line, text) in lines.iter().map(line_text).enumerate() {
+            matches.extend(
+                find_in_text(&text, query)
+                    .into_iter()
+                    .map(|range| SearchMatch { cell, line, range }),
+            );
+        }
+    }
+    matches
+}
+
+fn line_text(line: &Line<'_>) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Non-overlapping byte ranges of `query` in `text`.
+fn find_in_text(text: &str, query: &str) -> Vec<Range<usize>> {
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let query: Vec<char> = query.chars().collect();
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let chars_eq =
+        |a: char, b: char| a == b || (ignore_case && a.to_lowercase().eq(b.to_lowercase()));
+
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while start + query.len() <= chars.len() {
+        let window = &chars[start..start + query.len()];
+        if window
+            .iter()
+            .zip(&query)
+            .all(|((_, a), b)| chars_eq(*a, *b))
+        {
+            let end = chars
+                .get(start + query.len())
+                .map_or(text.len(), |(offset, _)| *offset);
+            ranges.push(chars[start].0..end);
+            start += query.len();
+        } else {
+            start += 1;
+        }
+    }
+    ranges
+}
+
+/// Paints `highlights` (byte ranges into the line's text) over `line`.
+pub(crate) fn highlight_line(line: Line<'static>, highlights: &[&LineHighlight]) -> Line<'static> {
+    if highlights.is_empty() {
+        return line;
+    }
+    let mut spans = Vec::with_capacity(line.spans.len() + highlights.len() * 2);
+    let mut offset = 0;
+    for span in line.spans {
+        let content = span.content.as_ref();
+        let span_range = offset..offset + content.len();
+        let mut cursor = span_range.start;
+        for highlight in highlights {
+            let start = highlight.range.start.clamp(cursor, span_range.end);
+            let end = highlight.range.end.clamp(start, span_range.end);
+            if start == end {
+                continue;
+            }
+            if cursor < start {
+                spans.push(Span::styled(
+                    content[cursor - offset..start - offset].to_string(),
+                    span.style,
+                ));
+            }
+            spans.push(Span::styled(
+                content[start - offset..end - offset].to_string(),
+                span.style.patch(match_style(highlight.current)),
+            ));
+            cursor = end;
+        }
+        if cursor < span_range.end {
+            spans.push(Span::styled(
+                content[cursor - offset..].to_string(),
+                span.style,
+            ));
+        }
+        offset = span_range.end;
+    }
+    Line {
+        spans,
+        style: line.style,
+        alignment: line.alignment,
+    }
+}
+
+fn match_style(current: bool) -> Style {
+    if current {
+        Style::default().cyan().reversed()
+    } else {
+        Style::default().reversed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn matching_is_smart_case_and_non_overlapping() {
+        assert_eq!(find_in_text("Foo foo FOO", "foo"), vec![0..3, 4..7, 8..11]);
+        assert_eq!(find_in_text("Foo foo FOO", "Foo"), vec![0..3]);
+        assert_eq!(find_in_text("aaaa", "aa"), vec![0..2, 2..4]);
+        assert_eq!(find_in_text("héllo wörld", "wö"), vec![7..10]);
+        assert_eq!(
+            find_in_text("short", "longer query"),
+            Vec::<Range<usize>>::new()
+        );
+    }
+
+    #[test]
+    fn matches_are_found_across_cells_and_lines() {
+        let cells = vec![
+            vec![Line::from("no hits here")],
+            vec![
+                Line::from(vec!["$ ".into(), "cargo test".into()]),
+                Line::from("test result: FAILED"),
+            ],
+        ];
+        assert_eq!(
+            find_matches(cells, "test"),
+            vec![
+                SearchMatch {
+                    cell: 1,
+                    line: 0,
+                    range: 8..12,
+                },
+                SearchMatch {
+                    cell: 1,
+                    line: 1,
+                    range: 0..4,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn navigation_starts_at_the_latest_match_and_wraps() {
+        let mut search = TranscriptSearch::new("x".to_string());
+        assert!(!search.is_editing());
+        search.set_matches(
+            (0..3)
+                .map(|cell| SearchMatch {
+                    cell,
+                    line: 0,
+                    range: 0..1,
+                })
+                .collect(),
+        );
+        assert_eq!(search.current_match().map(|m| m.cell), Some(2));
+        search.select_next();
+        assert_eq!(search.current_match().map(|m| m.cell), Some(0));
+        search.select_previous();
+        search.select_previous();
+        assert_eq!(search.current_match().map(|m| m.cell), Some(1));
+
+        // Refreshing keeps the selection when the match survives.
+        let matches = search.matches.clone();
+        search.set_matches(matches);
+        assert_eq!(search.current_match().map(|m| m.cell), Some(1));
+        search.set_matches(Vec::new());
+        assert_eq!(search.current_match(), None);
+        search.select_next();
+        assert_eq!(search.current_match(), None);
+    }
+
+    #[test]
+    fn highlights_split_spans_and_keep_their_styles() {
+        let line = Line::from(vec!["$ ".magenta(), "grep needle".into()]);
+        let current = LineHighlight {
+            line: 0,
+            range: 7..13,
+            current: true,
+        };
+        let other = LineHighlight {
+            line: 0,
+            range: 0..1,
+            current: false,
+        };
+        let highlighted = highlight_line(line, &[&other, &current]);
+        assert_eq!(
+            highlighted.spans,
+            vec![
+                "$".magenta().reversed(),
+                " ".magenta(),
+                "grep ".into(),
+                "needle".cyan().reversed(),
+            ]
+        );
+    }
+}
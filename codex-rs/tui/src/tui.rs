@@ -11,6 +11,7 @@ use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 
+use codex_core::config::types::NotificationMethod;
 use crossterm::Command;
 use crossterm::SynchronizedUpdate;
 use crossterm::event::DisableBracketedPaste;
@@ -40,6 +41,7 @@ use crate::custom_terminal;
 use crate::custom_terminal::Terminal as CustomTerminal;
 use crate::notifications::DesktopNotificationBackend;
 use crate::notifications::NotificationBackendKind;
+use crate::notifications::backend_for;
 use crate::notifications::detect_backend;
 use crate::tui::event_stream::EventBroker;
 use crate::tui::event_stream::TuiEventStream;
@@ -285,6 +287,11 @@ impl Tui {
         self.alt_screen_enabled = enabled;
     }
 
+    /// Switch to the backend for the configured `tui.notification_method`.
+    pub fn set_notification_method(&mut self, method: NotificationMethod) {
+        self.notification_backend = Some(backend_for(method));
+    }
+
     pub fn frame_requester(&self) -> FrameRequester {
         self.frame_requester.clone()
     }
@@ -362,17 +369,19 @@ impl Tui {
         match backend.notify(&message) {
             Ok(()) => true,
             Err(err) => match backend.kind() {
-                NotificationBackendKind::WindowsToast => {
+                kind
+                @ (NotificationBackendKind::WindowsToast | NotificationBackendKind::Native) => {
                     tracing::error!(
                         error = %err,
-                        "Failed to send Windows toast notification; falling back to OSC 9"
+                        backend = ?kind,
+                        "Failed to send system notification; falling back to OSC 9"
                     );
                     self.notification_backend = Some(DesktopNotificationBackend::osc9());
                     if let Some(backend) = self.notification_backend.as_mut() {
                         if let Err(osc_err) = backend.notify(&message) {
                             tracing::warn!(
                                 error = %osc_err,
-                                "Failed to emit OSC 9 notification after toast fallback; \
+                                "Failed to emit OSC 9 notification after fallback; \
                                  disabling future notifications"
                             );
                             self.notification_backend = None;
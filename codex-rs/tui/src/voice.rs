@@ -0,0 +1,355 @@
+//! Push-to-talk voice input for the composer (`[tui.voice]`).
+//!
+//! Audio is captured by an [`AudioCapture`] into a temporary WAV file while
+//! the push-to-talk key is held (or between two presses on terminals that do
+//! not report key releases). The recording is then transcribed by the
+//! configured backend and the text is inserted into the composer, where it
+//! can be edited before submitting.
+
+use std::io;
+use std::path::Path;
+use std::process::Child;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_core::config::types::VoiceBackend;
+use codex_core::config::types::VoiceConfig;
+use tempfile::TempPath;
+use tokio::process::Command;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+
+/// Footer hint shown while recording.
+pub(crate) const RECORDING_HINT: &str =
+    "Recording… release (or press) Ctrl+Space to transcribe, Esc to cancel";
+
+/// Footer hint shown while the recording is transcribed.
+pub(crate) const TRANSCRIBING_HINT: &str = "Transcribing…";
+
+/// Releases sooner than this after the press are treated as a tap, so the
+/// recording keeps going until the key is pressed again.
+const TAP_THRESHOLD: Duration = Duration::from_millis(400);
+
+/// How long the recorder gets to finish the WAV file after being interrupted.
+const RECORDER_STOP_TIMEOUT: Duration = Duration::from_secs(3);
+
+const TRANSCRIBE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Source of microphone audio.
+pub(crate) trait AudioCapture {
+    /// Starts recording into the WAV file at `output`.
+    fn start(&self, output: &Path) -> io::Result<Box<dyn AudioRecording>>;
+}
+
+/// A recording in progress.
+pub(crate) trait AudioRecording: Send {
+    /// Stops recording and waits until the WAV file is complete.
+    fn finish(self: Box<Self>) -> io::Result<()>;
+
+    /// Stops recording without waiting for the file.
+    fn cancel(self: Box<Self>);
+}
+
+/// Records with an external command such as SoX's `rec`, which writes to
+/// `{output}` until it is interrupted.
+pub(crate) struct CommandCapture {
+    command: Vec<String>,
+}
+
+impl CommandCapture {
+    pub(crate) fn new(command: Vec<String>) -> Self {
+        Self { command }
+    }
+}
+
+impl AudioCapture for CommandCapture {
+    fn start(&self, output: &Path) -> io::Result<Box<dyn AudioRecording>> {
+        let argv = substitute(&self.command, "{output}", output);
+        let Some((program, args)) = argv.split_first() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "tui.voice.record_command is empty",
+            ));
+        };
+        let child = std::process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| {
+                io::Error::new(err.kind(), format!("failed to start `{program}`: {err}"))
+            })?;
+        Ok(Box::new(CommandRecording { child }))
+    }
+}
+
+struct CommandRecording {
+    child: Child,
+}
+
+impl AudioRecording for CommandRecording {
+    fn finish(mut self: Box<Self>) -> io::Result<()> {
+        if let Some(status) = self.child.try_wait()? {
+            // The recorder gave up before it was asked to stop.
+            return Err(io::Error::other(format!(
+                "recorder exited early ({status})"
+            )));
+        }
+        interrupt(&mut self.child);
+        let deadline = Instant::now() + RECORDER_STOP_TIMEOUT;
+        while Instant::now() < deadline {
+            if self.child.try_wait()?.is_some() {
+                return Ok(());
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        Ok(())
+    }
+
+    fn cancel(mut self: Box<Self>) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Asks the recorder to stop the way Ctrl+C would, so it can finalize the
+/// WAV header.
+#[cfg(unix)]
+fn interrupt(child: &mut Child) {
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: `pid` belongs to a child we spawned and have not reaped yet.
+        unsafe {
+            libc::kill(pid, libc::SIGINT);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn interrupt(child: &mut Child) {
+    let _ = child.kill();
+}
+
+/// Voice input state owned by the app.
+#[derive(Default)]
+pub(crate) enum VoiceInput {
+    #[default]
+    Idle,
+    Recording {
+        recording: Box<dyn AudioRecording>,
+        file: TempPath,
+        started_at: Instant,
+    },
+    Transcribing,
+}
+
+impl VoiceInput {
+    /// Starts recording with the recorder from `config`.
+    pub(crate) fn start(&mut self, config: &VoiceConfig) -> io::Result<()> {
+        let file = tempfile::Builder::new()
+            .prefix("codex-voice-")
+            .suffix(".wav")
+            .tempfile()?
+            .into_temp_path();
+        let recording = CommandCapture::new(config.record_command.clone()).start(&file)?;
+        *self = VoiceInput::Recording {
+            recording,
+            file,
+            started_at: Instant::now(),
+        };
+        Ok(())
+    }
+
+    /// Whether a key release at this point should end the recording rather
+    /// than be treated as the end of a tap.
+    pub(crate) fn is_held(&self) -> bool {
+        matches!(self, VoiceInput::Recording { started_at, .. } if started_at.elapsed() >= TAP_THRESHOLD)
+    }
+
+    /// Stops recording and transcribes in the background; the result arrives
+    /// as [`AppEvent::VoiceTranscribed`].
+    pub(crate) fn stop(&mut self, config: VoiceConfig, tx: AppEventSender) {
+        let VoiceInput::Recording {
+            recording, file, ..
+        } = std::mem::replace(self, VoiceInput::Transcribing)
+        else {
+            return;
+        };
+        tokio::spawn(async move {
+            let result = match tokio::task::spawn_blocking(move || recording.finish()).await {
+                Ok(Ok(())) => transcribe(&config, &file).await,
+                Ok(Err(err)) => Err(format!("Recording failed: {err}")),
+                Err(err) => Err(format!("Recording failed: {err}")),
+            };
+            tx.send(AppEvent::VoiceTranscribed(result));
+        });
+    }
+
+    /// Drops the current recording.
+    pub(crate) fn cancel(&mut self) {
+        if let VoiceInput::Recording { recording, .. } = std::mem::take(self) {
+            recording.cancel();
+        }
+    }
+}
+
+/// Runs the configured backend on the WAV file at `input`.
+async fn transcribe(config: &VoiceConfig, input: &Path) -> Result<String, String> {
+    let argv = transcribe_argv(config, input)?;
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| "tui.voice.transcribe_command is empty".to_string())?;
+    let output = tokio::time::timeout(
+        TRANSCRIBE_TIMEOUT,
+        Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| {
+        format!(
+            "`{program}` did not finish within {}s",
+            TRANSCRIBE_TIMEOUT.as_secs()
+        )
+    })?
+    .map_err(|err| format!("failed to run `{program}`: {err}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`{program}` failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(clean_transcript(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn transcribe_argv(config: &VoiceConfig, input: &Path) -> Result<Vec<String>, String> {
+    match config.backend {
+        VoiceBackend::WhisperCpp => {
+            let model = config.whisper_cpp_model.as_ref().ok_or_else(|| {
+                "set tui.voice.whisper_cpp_model to a whisper.cpp model file".to_string()
+            })?;
+            let mut argv = vec![
+                config.whisper_cpp_binary.clone(),
+                "--model".to_string(),
+                model.display().to_string(),
+                "--file".to_string(),
+                input.display().to_string(),
+                "--no-timestamps".to_string(),
+                "--no-prints".to_string(),
+            ];
+            if let Some(language) = &config.language {
+                argv.extend(["--language".to_string(), language.clone()]);
+            }
+            Ok(argv)
+        }
+        VoiceBackend::Command => {
+            let mut argv = substitute(&config.transcribe_command, "{input}", input);
+            if argv.is_empty() {
+                return Err(
+                    "set tui.voice.transcribe_command for the `command` backend".to_string()
+                );
+            }
+            if !config
+                .transcribe_command
+                .iter()
+                .any(|arg| arg.contains("{input}"))
+            {
+                argv.push(input.display().to_string());
+            }
+            Ok(argv)
+        }
+    }
+}
+
+fn substitute(command: &[String], placeholder: &str, path: &Path) -> Vec<String> {
+    let path = path.display().to_string();
+    command
+        .iter()
+        .map(|arg| arg.replace(placeholder, &path))
+        .collect()
+}
+
+/// Joins the transcript into one line and drops non-speech markers such as
+/// whisper.cpp's `[BLANK_AUDIO]`.
+fn clean_transcript(raw: &str) -> String {
+    raw.split_whitespace()
+        .filter(|word| !is_marker(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_marker(word: &str) -> bool {
+    word.len() > 2
+        && word.starts_with('[')
+        && word.ends_with(']')
+        && word[1..word.len() - 1]
+            .chars()
+            .all(|ch| ch.is_ascii_uppercase() || ch == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn whisper_cpp_argv_includes_model_and_language() {
+        let config = VoiceConfig {
+            whisper_cpp_model: Some(PathBuf::from("/models/ggml-base.bin")),
+            language: Some("ja".to_string()),
+            ..VoiceConfig::default()
+        };
+        assert_eq!(
+            transcribe_argv(&config, Path::new("/tmp/a.wav")),
+            Ok(vec![
+                "whisper-cli".to_string(),
+                "--model".to_string(),
+                "/models/ggml-base.bin".to_string(),
+                "--file".to_string(),
+                "/tmp/a.wav".to_string(),
+                "--no-timestamps".to_string(),
+                "--no-prints".to_string(),
+                "--language".to_string(),
+                "ja".to_string(),
+            ])
+        );
+        assert_eq!(
+            transcribe_argv(&VoiceConfig::default(), Path::new("/tmp/a.wav")),
+            Err("set tui.voice.whisper_cpp_model to a whisper.cpp model file".to_string())
+        );
+    }
+
+    #[test]
+    fn command_argv_substitutes_or_appends_input() {
+        let mut config = VoiceConfig {
+            backend: VoiceBackend::Command,
+            transcribe_command: vec!["stt".to_string(), "--in={input}".to_string()],
+            ..VoiceConfig::default()
+        };
+        assert_eq!(
+            transcribe_argv(&config, Path::new("/tmp/a.wav")),
+            Ok(vec!["stt".to_string(), "--in=/tmp/a.wav".to_string()])
+        );
+        config.transcribe_command = vec!["stt".to_string()];
+        assert_eq!(
+            transcribe_argv(&config, Path::new("/tmp/a.wav")),
+            Ok(vec!["stt".to_string(), "/tmp/a.wav".to_string()])
+        );
+    }
+
+    #[test]
+    fn clean_transcript_joins_lines_and_drops_markers() {
+        assert_eq!(
+            clean_transcript(" [BLANK_AUDIO]\n Fix the failing\n test in [src] please.\n"),
+            "Fix the failing test in [src] please."
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::ffi::OsString;
+use std::path::Path;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_stdout;
+
+/// Summary of the changes between two commits, as reported by `git diff --numstat`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStat {
+    pub files_changed: u32,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+/// Returns the diffstat between two commits. Binary files count as changed
+/// files without contributing insertions or deletions.
+pub fn diff_stat_between(
+    repo_path: &Path,
+    from: &str,
+    to: &str,
+) -> Result<DiffStat, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    let output = run_git_for_stdout(
+        repo_root.as_path(),
+        vec![
+            OsString::from("diff"),
+            OsString::from("--numstat"),
+            OsString::from("--no-renames"),
+            OsString::from(from),
+            OsString::from(to),
+        ],
+        None,
+    )?;
+    Ok(parse_numstat(&output))
+}
+
+fn parse_numstat(output: &str) -> DiffStat {
+    let mut stat = DiffStat::default();
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        let mut fields = line.split('\t');
+        let insertions = fields.next().and_then(|value| value.parse::<u32>().ok());
+        let deletions = fields.next().and_then(|value| value.parse::<u32>().ok());
+        stat.files_changed += 1;
+        stat.insertions += insertions.unwrap_or(0);
+        stat.deletions += deletions.unwrap_or(0);
+    }
+    stat
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn run_git_stdout(repo_path: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .output()
+            .expect("git command");
+        assert!(output.status.success(), "git command failed: {args:?}");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn commit_all(repo_path: &Path, message: &str) -> String {
+        run_git_stdout(repo_path, &["add", "-A"]);
+        run_git_stdout(
+            repo_path,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                message,
+            ],
+        );
+        run_git_stdout(repo_path, &["rev-parse", "HEAD"])
+    }
+
+    #[test]
+    fn parse_numstat_counts_binary_files_without_lines() {
+        let output = "3\t1\tsrc/lib.rs\n-\t-\tlogo.png\n0\t7\tREADME.md\n";
+        assert_eq!(
+            parse_numstat(output),
+            DiffStat {
+                files_changed: 3,
+                insertions: 3,
+                deletions: 8,
+            }
+        );
+    }
+
+    #[test]
+    fn diff_stat_between_commits() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path();
+        run_git_stdout(repo, &["init", "--initial-branch=main"]);
+        run_git_stdout(repo, &["config", "core.autocrlf", "false"]);
+
+        std::fs::write(repo.join("a.txt"), "one\ntwo\n")?;
+        let first = commit_all(repo, "first");
+        std::fs::write(repo.join("a.txt"), "one\nthree\nfour\n")?;
+        std::fs::write(repo.join("b.txt"), "new\n")?;
+        let second = commit_all(repo, "second");
+
+        assert_eq!(
+            diff_stat_between(repo, &first, &second)?,
+            DiffStat {
+                files_changed: 2,
+                insertions: 3,
+                deletions: 1,
+            }
+        );
+        assert_eq!(
+            diff_stat_between(repo, &second, &second)?,
+            DiffStat::default()
+        );
+        Ok(())
+    }
+}
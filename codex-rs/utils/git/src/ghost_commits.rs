@@ -462,6 +462,149 @@ pub fn restore_to_commit(repo_path: &Path, commit_id: &str) -> Result<(), GitToo
     restore_to_commit_inner(repo_root.as_path(), repo_prefix.as_deref(), commit_id)
 }
 
+/// What [`restore_path_from_ghost_commit`] did to the working tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoredPath {
+    /// The path was rewritten with its contents from the snapshot.
+    Restored,
+    /// The path did not exist when the snapshot was captured and was deleted.
+    Removed,
+}
+
+/// Restore a single path to its state in `commit`, leaving the rest of the
+/// working tree untouched. `path` may be absolute or relative to `repo_path`.
+///
+/// Paths that were created after the snapshot are deleted. Untracked files the
+/// snapshot deliberately skipped (large or ignored ones) are left alone and
+/// reported as [`GitToolingError::PathNotInSnapshot`].
+pub fn restore_path_from_ghost_commit(
+    repo_path: &Path,
+    commit: &GhostCommit,
+    path: &Path,
+) -> Result<RestoredPath, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+
+    let repo_root = resolve_repository_root(repo_path)?;
+    let relative = repo_relative_path(repo_root.as_path(), repo_path, path)?;
+    let listed = run_git_for_stdout(
+        repo_root.as_path(),
+        vec![
+            OsString::from("ls-tree"),
+            OsString::from("--name-only"),
+            OsString::from(commit.id()),
+            OsString::from("--"),
+            relative.as_os_str().to_os_string(),
+        ],
+        None,
+    )?;
+    if !listed.is_empty() {
+        // Same as a full restore: the index is left alone on purpose.
+        run_git_for_status(
+            repo_root.as_path(),
+            vec![
+                OsString::from("restore"),
+                OsString::from("--source"),
+                OsString::from(commit.id()),
+                OsString::from("--worktree"),
+                OsString::from("--"),
+                relative.as_os_str().to_os_string(),
+            ],
+            None,
+        )?;
+        return Ok(RestoredPath::Restored);
+    }
+
+    let preserved_files: HashSet<PathBuf> = commit
+        .preexisting_untracked_files()
+        .iter()
+        .cloned()
+        .collect();
+    let target = repo_root.join(&relative);
+    let skipped_by_snapshot = should_preserve(
+        &relative,
+        &preserved_files,
+        commit.preexisting_untracked_dirs(),
+    );
+    // A new directory may still hold untracked files the snapshot skipped, so
+    // only single files are deleted.
+    if skipped_by_snapshot || target.is_dir() || !target.exists() {
+        return Err(GitToolingError::PathNotInSnapshot { path: relative });
+    }
+    remove_path(&target)?;
+    Ok(RestoredPath::Removed)
+}
+
+/// Contents of `path` as captured in `commit`, or `None` when the snapshot
+/// does not contain it. `path` may be absolute or relative to `repo_path`.
+pub fn read_path_from_ghost_commit(
+    repo_path: &Path,
+    commit: &GhostCommit,
+    path: &Path,
+) -> Result<Option<String>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+
+    let repo_root = resolve_repository_root(repo_path)?;
+    let relative = repo_relative_path(repo_root.as_path(), repo_path, path)?;
+    let listed = run_git_for_stdout(
+        repo_root.as_path(),
+        vec![
+            OsString::from("ls-tree"),
+            OsString::from("--name-only"),
+            OsString::from(commit.id()),
+            OsString::from("--"),
+            relative.as_os_str().to_os_string(),
+        ],
+        None,
+    )?;
+    if listed.is_empty() {
+        return Ok(None);
+    }
+    let object = format!(
+        "{}:{}",
+        commit.id(),
+        relative.to_string_lossy().replace('\\', "/")
+    );
+    run_git_for_stdout_all(
+        repo_root.as_path(),
+        vec![
+            OsString::from("cat-file"),
+            OsString::from("blob"),
+            OsString::from(object),
+        ],
+        None,
+    )
+    .map(Some)
+}
+
+/// Resolves `path` (absolute, or relative to `repo_path`) to a path relative
+/// to the repository root.
+fn repo_relative_path(
+    repo_root: &Path,
+    repo_path: &Path,
+    path: &Path,
+) -> Result<PathBuf, GitToolingError> {
+    if !path.is_absolute() {
+        let joined = match repo_subdir(repo_root, repo_path) {
+            Some(prefix) => prefix.join(path),
+            None => path.to_path_buf(),
+        };
+        return normalize_relative_path(&joined);
+    }
+
+    let stripped = path
+        .strip_prefix(repo_root)
+        .map(Path::to_path_buf)
+        .or_else(|_| {
+            let repo_root_canon = repo_root.canonicalize()?;
+            path.strip_prefix(&repo_root_canon)
+                .map(Path::to_path_buf)
+                .map_err(|_| GitToolingError::PathEscapesRepository {
+                    path: path.to_path_buf(),
+                })
+        })?;
+    normalize_relative_path(&stripped)
+}
+
 /// Restores the working tree and index to the given commit using `git restore`.
 /// The repository root and optional repository-relative prefix limit the restore scope.
 fn restore_to_commit_inner(
@@ -1491,6 +1634,108 @@ mod tests {
         assert_matches!(err, GitToolingError::NotAGitRepository { .. });
     }
 
+    #[test]
+    /// Restoring one path leaves every other change in place.
+    fn restore_path_only_touches_that_path() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        std::fs::create_dir_all(repo.join("src"))?;
+        std::fs::write(repo.join("src/keep.txt"), "keep before\n")?;
+        std::fs::write(repo.join("src/revert.txt"), "revert before\n")?;
+        run_git_in(repo, &["add", "."]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+        std::fs::write(repo.join("notes.txt"), "untracked notes\n")?;
+
+        let ghost = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+
+        std::fs::write(repo.join("src/keep.txt"), "keep after\n")?;
+        std::fs::write(repo.join("src/revert.txt"), "revert after\n")?;
+        std::fs::write(repo.join("src/created.txt"), "created\n")?;
+
+        let workspace = repo.join("src");
+        let restored = restore_path_from_ghost_commit(&workspace, &ghost, Path::new("revert.txt"))?;
+        assert_eq!(restored, RestoredPath::Restored);
+        let removed = restore_path_from_ghost_commit(repo, &ghost, &workspace.join("created.txt"))?;
+        assert_eq!(removed, RestoredPath::Removed);
+
+        assert_eq!(
+            std::fs::read_to_string(repo.join("src/revert.txt"))?,
+            "revert before\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.join("src/keep.txt"))?,
+            "keep after\n"
+        );
+        assert!(!repo.join("src/created.txt").exists());
+        assert_matches!(
+            restore_path_from_ghost_commit(repo, &ghost, Path::new("../outside.txt")),
+            Err(GitToolingError::PathEscapesRepository { .. })
+        );
+        assert_matches!(
+            restore_path_from_ghost_commit(repo, &ghost, Path::new("missing.txt")),
+            Err(GitToolingError::PathNotInSnapshot { .. })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_path_returns_snapshot_contents() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        init_test_repo(repo);
+        std::fs::create_dir_all(repo.join("src"))?;
+        std::fs::write(repo.join("src/lib.rs"), "before\n")?;
+        run_git_in(repo, &["add", "."]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+        std::fs::write(repo.join("src/lib.rs"), "edited\n")?;
+        std::fs::write(repo.join("notes.txt"), "untracked notes\n")?;
+
+        let ghost = create_ghost_commit(&CreateGhostCommitOptions::new(repo))?;
+
+        std::fs::write(repo.join("src/lib.rs"), "after\n")?;
+        std::fs::write(repo.join("src/created.txt"), "created\n")?;
+
+        let workspace = repo.join("src");
+        assert_eq!(
+            read_path_from_ghost_commit(&workspace, &ghost, Path::new("lib.rs"))?,
+            Some("edited\n".to_string())
+        );
+        assert_eq!(
+            read_path_from_ghost_commit(repo, &ghost, Path::new("notes.txt"))?,
+            Some("untracked notes\n".to_string())
+        );
+        assert_eq!(
+            read_path_from_ghost_commit(repo, &ghost, &workspace.join("created.txt"))?,
+            None
+        );
+
+        Ok(())
+    }
+
     #[test]
     /// Restoring from a subdirectory affects only that subdirectory.
     fn restore_from_subdirectory_restores_files_relatively() -> Result<(), GitToolingError> {
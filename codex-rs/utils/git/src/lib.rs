@@ -3,10 +3,12 @@ use std::path::PathBuf;
 
 mod apply;
 mod branch;
+mod diff_stat;
 mod errors;
 mod ghost_commits;
 mod operations;
 mod platform;
+mod worktree;
 
 pub use apply::ApplyGitRequest;
 pub use apply::ApplyGitResult;
@@ -15,6 +17,8 @@ pub use apply::extract_paths_from_patch;
 pub use apply::parse_git_apply_output;
 pub use apply::stage_paths;
 pub use branch::merge_base_with_head;
+pub use diff_stat::DiffStat;
+pub use diff_stat::diff_stat_between;
 pub use errors::GitToolingError;
 pub use ghost_commits::CreateGhostCommitOptions;
 pub use ghost_commits::GhostSnapshotConfig;
@@ -22,17 +26,26 @@ pub use ghost_commits::GhostSnapshotReport;
 pub use ghost_commits::IgnoredUntrackedFile;
 pub use ghost_commits::LargeUntrackedDir;
 pub use ghost_commits::RestoreGhostCommitOptions;
+pub use ghost_commits::RestoredPath;
 pub use ghost_commits::capture_ghost_snapshot_report;
 pub use ghost_commits::create_ghost_commit;
 pub use ghost_commits::create_ghost_commit_with_report;
+pub use ghost_commits::read_path_from_ghost_commit;
 pub use ghost_commits::restore_ghost_commit;
 pub use ghost_commits::restore_ghost_commit_with_options;
+pub use ghost_commits::restore_path_from_ghost_commit;
 pub use ghost_commits::restore_to_commit;
 pub use platform::create_symlink;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use ts_rs::TS;
+pub use worktree::add_detached_worktree;
+pub use worktree::add_worktree_on_branch;
+pub use worktree::apply_diff_to_worktree;
+pub use worktree::delete_branch;
+pub use worktree::remove_worktree;
+pub use worktree::worktree_diff;
 
 type CommitID = String;
 
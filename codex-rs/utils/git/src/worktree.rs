@@ -0,0 +1,234 @@
+use std::ffi::OsString;
+use std::path::Path;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_status;
+use crate::operations::run_git_for_stdout_all;
+
+/// Checks out `commit` into a new detached worktree at `worktree_path`, so an
+/// agent can edit a copy of the repository without touching the checkout.
+pub fn add_detached_worktree(
+    repo_path: &Path,
+    worktree_path: &Path,
+    commit: &str,
+) -> Result<(), GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    run_git_for_status(
+        repo_root.as_path(),
+        vec![
+            OsString::from("worktree"),
+            OsString::from("add"),
+            OsString::from("--detach"),
+            worktree_path.as_os_str().to_os_string(),
+            OsString::from(commit),
+        ],
+        None,
+    )
+}
+
+/// Checks out `commit` into a new worktree at `worktree_path` on a new branch
+/// named `branch`, so the work done in it stays reachable by name.
+pub fn add_worktree_on_branch(
+    repo_path: &Path,
+    worktree_path: &Path,
+    branch: &str,
+    commit: &str,
+) -> Result<(), GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    run_git_for_status(
+        repo_root.as_path(),
+        vec![
+            OsString::from("worktree"),
+            OsString::from("add"),
+            OsString::from("-b"),
+            OsString::from(branch),
+            worktree_path.as_os_str().to_os_string(),
+            OsString::from(commit),
+        ],
+        None,
+    )
+}
+
+/// Force-deletes `branch`, e.g. after its worktree was removed.
+pub fn delete_branch(repo_path: &Path, branch: &str) -> Result<(), GitToolingError> {
+    let repo_root = resolve_repository_root(repo_path)?;
+    run_git_for_status(
+        repo_root.as_path(),
+        vec![
+            OsString::from("branch"),
+            OsString::from("-D"),
+            OsString::from(branch),
+        ],
+        None,
+    )
+}
+
+/// Returns every change made in the worktree since `base`, including new
+/// files, as a binary-safe unified diff relative to the repository root.
+pub fn worktree_diff(worktree_path: &Path, base: &str) -> Result<String, GitToolingError> {
+    run_git_for_status(
+        worktree_path,
+        vec![OsString::from("add"), OsString::from("-A")],
+        None,
+    )?;
+    run_git_for_stdout_all(
+        worktree_path,
+        vec![
+            OsString::from("diff"),
+            OsString::from("--cached"),
+            OsString::from("--binary"),
+            OsString::from(base),
+        ],
+        None,
+    )
+}
+
+/// Deletes a worktree created by [`add_detached_worktree`] or
+/// [`add_worktree_on_branch`], discarding any changes left in it.
+pub fn remove_worktree(repo_path: &Path, worktree_path: &Path) -> Result<(), GitToolingError> {
+    let repo_root = resolve_repository_root(repo_path)?;
+    run_git_for_status(
+        repo_root.as_path(),
+        vec![
+            OsString::from("worktree"),
+            OsString::from("remove"),
+            OsString::from("--force"),
+            worktree_path.as_os_str().to_os_string(),
+        ],
+        None,
+    )
+}
+
+/// Applies `diff` to the working tree of the repository containing
+/// `repo_path` without touching the index. With `check_only`, nothing is
+/// written and the call only reports whether the diff would apply.
+pub fn apply_diff_to_worktree(
+    repo_path: &Path,
+    diff: &str,
+    check_only: bool,
+) -> Result<(), GitToolingError> {
+    if diff.trim().is_empty() {
+        return Ok(());
+    }
+    let repo_root = resolve_repository_root(repo_path)?;
+    let patch_dir = tempfile::tempdir()?;
+    let patch_path = patch_dir.path().join("patch.diff");
+    std::fs::write(&patch_path, diff)?;
+
+    let mut args = vec![
+        OsString::from("apply"),
+        OsString::from("--whitespace=nowarn"),
+    ];
+    if check_only {
+        args.push(OsString::from("--check"));
+    }
+    args.push(patch_path.into_os_string());
+    run_git_for_status(repo_root.as_path(), args, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CreateGhostCommitOptions;
+    use crate::create_ghost_commit;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn run_git(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    #[test]
+    fn worktree_changes_round_trip_onto_the_checkout() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path().join("repo");
+        std::fs::create_dir(&repo)?;
+        run_git(&repo, &["init", "--initial-branch=main"]);
+        run_git(&repo, &["config", "core.autocrlf", "false"]);
+        std::fs::write(repo.join("a.txt"), "one\ntwo\n")?;
+        run_git(&repo, &["add", "-A"]);
+        run_git(
+            &repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+        // Uncommitted edits are part of the snapshot the worktree starts from.
+        std::fs::write(repo.join("a.txt"), "one\ntwo\nthree\n")?;
+        let base = create_ghost_commit(&CreateGhostCommitOptions::new(&repo))?;
+
+        let worktree = temp.path().join("worktree");
+        add_detached_worktree(&repo, &worktree, base.id())?;
+        assert_eq!(
+            std::fs::read_to_string(worktree.join("a.txt"))?,
+            "one\ntwo\nthree\n"
+        );
+        std::fs::write(worktree.join("a.txt"), "zero\none\ntwo\nthree\n")?;
+        std::fs::write(worktree.join("b.txt"), "new\n")?;
+        let diff = worktree_diff(&worktree, base.id())?;
+        remove_worktree(&repo, &worktree)?;
+        assert!(!worktree.exists());
+
+        apply_diff_to_worktree(&repo, &diff, true)?;
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.txt"))?,
+            "one\ntwo\nthree\n"
+        );
+        apply_diff_to_worktree(&repo, &diff, false)?;
+        assert_eq!(
+            (
+                std::fs::read_to_string(repo.join("a.txt"))?,
+                std::fs::read_to_string(repo.join("b.txt"))?,
+            ),
+            ("zero\none\ntwo\nthree\n".to_string(), "new\n".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn branch_worktree_is_removed_with_its_branch() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path().join("repo");
+        std::fs::create_dir(&repo)?;
+        run_git(&repo, &["init", "--initial-branch=main"]);
+        std::fs::write(repo.join("a.txt"), "one\n")?;
+        let base = create_ghost_commit(&CreateGhostCommitOptions::new(&repo))?;
+
+        let worktree = temp.path().join("worktree");
+        add_worktree_on_branch(&repo, &worktree, "codex/test-1", base.id())?;
+        let head = Command::new("git")
+            .current_dir(&worktree)
+            .args(["rev-parse", "--abbrev-ref", "HEAD"])
+            .output()
+            .expect("git rev-parse");
+        assert_eq!(String::from_utf8_lossy(&head.stdout).trim(), "codex/test-1");
+        std::fs::write(worktree.join("a.txt"), "two\n")?;
+        assert!(worktree_diff(&worktree, base.id())?.contains("+two"));
+
+        remove_worktree(&repo, &worktree)?;
+        delete_branch(&repo, "codex/test-1")?;
+        let branches = Command::new("git")
+            .current_dir(&repo)
+            .args(["branch", "--list", "codex/*"])
+            .output()
+            .expect("git branch");
+        assert_eq!(String::from_utf8_lossy(&branches.stdout).trim(), "");
+        Ok(())
+    }
+}
@@ -23,3 +23,5 @@ pub type SpawnedPty = SpawnedProcess;
 pub use pty::conpty_supported;
 /// Spawn a process attached to a PTY for interactive use.
 pub use pty::spawn_process as spawn_pty_process;
+/// Size of a PTY in character cells.
+pub use pty::TerminalSize;
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
 
 use portable_pty::MasterPty;
+use portable_pty::PtySize;
 use portable_pty::SlavePty;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
@@ -12,6 +13,8 @@ use tokio::sync::oneshot;
 use tokio::task::AbortHandle;
 use tokio::task::JoinHandle;
 
+use crate::pty::TerminalSize;
+
 pub(crate) trait ChildTerminator: Send + Sync {
     fn kill(&mut self) -> io::Result<()>;
 }
@@ -101,6 +104,18 @@ impl ProcessHandle {
         self.exit_code.lock().ok().and_then(|guard| *guard)
     }
 
+    /// Resizes the PTY; fails for processes spawned with pipes.
+    pub fn resize(&self, size: TerminalSize) -> anyhow::Result<()> {
+        let handles = self
+            ._pty_handles
+            .lock()
+            .map_err(|_| anyhow::anyhow!("PTY handles lock poisoned"))?;
+        match handles.as_ref() {
+            Some(handles) => handles._master.resize(PtySize::from(size)),
+            None => anyhow::bail!("process is not attached to a terminal"),
+        }
+    }
+
     /// Attempts to kill the child and abort helper tasks.
     pub fn terminate(&self) {
         if let Ok(mut killer_opt) = self.killer.lock() {
@@ -5,7 +5,7 @@ macro_rules! windows_modules {
 }
 
 windows_modules!(
-    acl, allow, audit, cap, dpapi, env, hide_users, identity, logging, policy, process, token,
+    acl, allow, audit, cap, dpapi, env, hide_users, identity, job, logging, policy, process, token,
     winutil
 );
 
@@ -47,6 +47,8 @@ pub use identity::require_logon_sandbox_creds;
 #[cfg(target_os = "windows")]
 pub use identity::sandbox_setup_is_complete;
 #[cfg(target_os = "windows")]
+pub use job::create_job_kill_on_close;
+#[cfg(target_os = "windows")]
 pub use logging::log_note;
 #[cfg(target_os = "windows")]
 pub use logging::LOG_FILE_NAME;
@@ -100,6 +102,7 @@ mod windows_impl {
     use super::env::apply_no_network_to_env;
     use super::env::ensure_non_interactive_pager;
     use super::env::normalize_null_device_env;
+    use super::job::create_job_kill_on_close;
     use super::logging::debug_log;
     use super::logging::log_failure;
     use super::logging::log_start;
@@ -123,6 +126,8 @@ mod windows_impl {
     use windows_sys::Win32::Foundation::SetHandleInformation;
     use windows_sys::Win32::Foundation::HANDLE;
     use windows_sys::Win32::Foundation::HANDLE_FLAG_INHERIT;
+    use windows_sys::Win32::System::JobObjects::AssignProcessToJobObject;
+    use windows_sys::Win32::System::JobObjects::TerminateJobObject;
     use windows_sys::Win32::System::Pipes::CreatePipe;
     use windows_sys::Win32::System::Threading::CreateProcessAsUserW;
     use windows_sys::Win32::System::Threading::GetExitCodeProcess;
@@ -333,6 +338,15 @@ mod windows_impl {
             CloseHandle(err_w);
         }
 
+        // Keep the whole process tree in a kill-on-close job so nothing the
+        // command spawned outlives it.
+        let h_job = unsafe { create_job_kill_on_close().ok() };
+        if let Some(job) = h_job {
+            unsafe {
+                let _ = AssignProcessToJobObject(job, pi.hProcess);
+            }
+        }
+
         let (tx_out, rx_out) = std::sync::mpsc::channel::<Vec<u8>>();
         let (tx_err, rx_err) = std::sync::mpsc::channel::<Vec<u8>>();
         let t_out = std::thread::spawn(move || {
@@ -388,7 +402,14 @@ mod windows_impl {
             }
         } else {
             unsafe {
-                windows_sys::Win32::System::Threading::TerminateProcess(pi.hProcess, 1);
+                match h_job {
+                    Some(job) => {
+                        TerminateJobObject(job, 1);
+                    }
+                    None => {
+                        windows_sys::Win32::System::Threading::TerminateProcess(pi.hProcess, 1);
+                    }
+                }
             }
         }
 
@@ -400,6 +421,9 @@ mod windows_impl {
                 CloseHandle(pi.hProcess);
             }
             CloseHandle(h_token);
+            if let Some(job) = h_job {
+                CloseHandle(job);
+            }
         }
         let _ = t_out.join();
         let _ = t_err.join();
@@ -31,6 +31,22 @@ export type Usage = {
 export type TurnCompletedEvent = {
   type: "turn.completed";
   usage: Usage;
+  /** Model, timing and retry stats of this turn, when reported. */
+  stats?: TurnStats;
+};
+
+/** Per-turn stats attached to `turn.completed`. */
+export type TurnStats = {
+  /** The model that served the turn. */
+  model: string;
+  /** The reasoning effort the turn ran with, if any. */
+  reasoning_effort: string | null;
+  /** Wall-clock time from the start of the turn until it completed. */
+  duration_ms: number;
+  /** Model requests retried after a stream error or a rate limit. */
+  retries: number;
+  /** Tokens used by this turn only; `usage` is the running thread total. */
+  usage: Usage;
 };
 
 /** Indicates that a turn failed with an error. */
@@ -3,6 +3,7 @@ export type {
   ThreadStartedEvent,
   TurnStartedEvent,
   TurnCompletedEvent,
+  TurnStats,
   TurnFailedEvent,
   ItemStartedEvent,
   ItemUpdatedEvent,
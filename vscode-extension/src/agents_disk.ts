@@ -64,13 +64,13 @@ export async function listAgentsFromDisk(cwdFsPath: string): Promise<{
   return { agents, errors, gitRoot };
 }
 
-function resolveCodexHome(): string {
+export function resolveCodexHome(): string {
   const env = process.env["CODEX_HOME"];
   if (env && env.trim()) return env.trim();
   return path.join(os.homedir(), ".codex");
 }
 
-async function findGitRoot(
+export async function findGitRoot(
   start: string,
   errors: string[],
 ): Promise<string | null> {
@@ -96,7 +96,7 @@ async function findGitRoot(
   return null;
 }
 
-async function listMarkdownStems(
+export async function listMarkdownStems(
   dir: string,
   errors: string[],
 ): Promise<string[]> {
@@ -15,6 +15,12 @@ import {
 import type { ThreadResumeParams } from "../generated/v2/ThreadResumeParams";
 import type { ThreadStartParams } from "../generated/v2/ThreadStartParams";
 import type { ThreadCompactParams } from "../generated/v2/ThreadCompactParams";
+import type { ThreadMcpServerAddParams } from "../generated/v2/ThreadMcpServerAddParams";
+import type { ThreadRestoreFileParams } from "../generated/v2/ThreadRestoreFileParams";
+import type { ThreadRevertHunksParams } from "../generated/v2/ThreadRevertHunksParams";
+import type { TurnDiffHunk } from "../generated/v2/TurnDiffHunk";
+import type { PinnedContent } from "../generated/v2/PinnedContent";
+import type { PinnedContextItem } from "../generated/v2/PinnedContextItem";
 import type { TurnStartParams } from "../generated/v2/TurnStartParams";
 import type { UserInput } from "../generated/v2/UserInput";
 import type { ThreadItem } from "../generated/v2/ThreadItem";
@@ -40,7 +46,16 @@ import type { LogoutAccountResponse } from "../generated/v2/LogoutAccountRespons
 import type { SwitchAccountParams } from "../generated/v2/SwitchAccountParams";
 import type { SwitchAccountResponse } from "../generated/v2/SwitchAccountResponse";
 import type { SkillsListEntry } from "../generated/v2/SkillsListEntry";
+import type { SubAgentsListEntry } from "../generated/v2/SubAgentsListEntry";
 import type { Thread } from "../generated/v2/Thread";
+import type { ThreadExportFormat } from "../generated/v2/ThreadExportFormat";
+import type { ThreadSearchResult } from "../generated/v2/ThreadSearchResult";
+import type { ConfigEdit } from "../generated/v2/ConfigEdit";
+import type { JsonValue } from "../generated/serde_json/JsonValue";
+import type { ConfigDiagnostic } from "../generated/v2/ConfigDiagnostic";
+import type { ConfigReadResponse } from "../generated/v2/ConfigReadResponse";
+import type { ConfigRequirements } from "../generated/v2/ConfigRequirements";
+import type { ConfigWriteResponse } from "../generated/v2/ConfigWriteResponse";
 import type { Turn } from "../generated/v2/Turn";
 import type { AnyServerNotification } from "./types";
 import type { FuzzyFileSearchResponse } from "../generated/FuzzyFileSearchResponse";
@@ -83,6 +98,15 @@ export class BackendManager implements vscode.Disposable {
   private readonly latestDiffByThreadId = new Map<string, string>();
   private readonly modelsByBackendKey = new Map<string, Model[]>();
   private readonly itemsByThreadId = new Map<string, Map<string, ThreadItem>>();
+  private readonly pinnedByThreadId = new Map<string, PinnedContextItem[]>();
+  private readonly pinnedWaitersByThreadId = new Map<
+    string,
+    Array<(items: PinnedContextItem[]) => void>
+  >();
+  private readonly nextEditWaitersByRequestId = new Map<
+    string,
+    (text: string | null) => void
+  >();
 
   public onSessionAdded: ((session: Session) => void) | null = null;
   public onAssistantDelta:
@@ -425,15 +449,18 @@ export class BackendManager implements vscode.Disposable {
     const proc = this.processes.get(backendKey);
     if (!proc) throw new Error("Backend process was not started");
 
+    const profile = profileForFolder(folder);
+    const config: { [key: string]: JsonValue } = {};
+    if (modelSettings?.reasoning)
+      config["reasoning_effort"] = modelSettings.reasoning;
+    if (profile) config["profile"] = profile;
     const params: ThreadStartParams = {
       model: modelSettings?.model ?? null,
       modelProvider: modelSettings?.provider ?? null,
       cwd: folder.uri.fsPath,
       approvalPolicy: null,
       sandbox: null,
-      config: modelSettings?.reasoning
-        ? { reasoning_effort: modelSettings.reasoning }
-        : null,
+      config: Object.keys(config).length > 0 ? config : null,
       baseInstructions: null,
       developerInstructions: null,
       experimentalRawEvents: false,
@@ -447,10 +474,11 @@ export class BackendManager implements vscode.Disposable {
       workspaceFolderUri: folder.uri.toString(),
       title: folder.name,
       threadId: res.thread.id,
+      ...(profile ? { profile } : {}),
     };
     this.sessions.add(backendKey, session);
     this.output.appendLine(
-      `[session] created: ${session.title} threadId=${session.threadId}`,
+      `[session] created: ${session.title} threadId=${session.threadId}${profile ? ` profile=${profile}` : ""}`,
     );
     this.onSessionAdded?.(session);
     return session;
@@ -486,6 +514,20 @@ export class BackendManager implements vscode.Disposable {
     return res.data ?? [];
   }
 
+  // Subagents are a codez feature, so this always goes through the codez backend.
+  public async listSubAgentsForWorkspaceFolder(
+    folder: vscode.WorkspaceFolder,
+  ): Promise<SubAgentsListEntry | null> {
+    await this.startForBackendId(folder, "codez");
+    const backendKey = makeBackendInstanceKey(folder.uri.toString(), "codez");
+    const proc = this.processes.get(backendKey);
+    if (!proc)
+      throw new Error("Backend is not running for this workspace folder");
+
+    const res = await proc.subagentsList({ cwds: [folder.uri.fsPath] });
+    return res.data[0] ?? null;
+  }
+
   public async fuzzyFileSearchForSession(
     session: Session,
     query: string,
@@ -583,6 +625,111 @@ export class BackendManager implements vscode.Disposable {
     return { data: res.data ?? [], nextCursor: res.nextCursor ?? null };
   }
 
+  // Thread history requests (search, stats, fork, export, delete) for the
+  // History view. They act on stored threads, so they need an app-server
+  // backend; opencode has no equivalent.
+  private async historyProcess(
+    folder: vscode.WorkspaceFolder,
+    backendId: BackendId,
+  ): Promise<{ proc: BackendProcess; backendKey: string }> {
+    if (backendId === "opencode")
+      throw new Error("Thread history is not supported on opencode");
+    const backendKey = makeBackendInstanceKey(folder.uri.toString(), backendId);
+    await this.startForBackendId(folder, backendId);
+    const proc = this.processes.get(backendKey);
+    if (!proc)
+      throw new Error("Backend is not running for this workspace folder");
+    return { proc, backendKey };
+  }
+
+  public async searchThreads(
+    folder: vscode.WorkspaceFolder,
+    backendId: BackendId,
+    query: string,
+    cursor: string | null,
+  ): Promise<{ data: ThreadSearchResult[]; nextCursor: string | null }> {
+    const { proc } = await this.historyProcess(folder, backendId);
+    const res = await proc.threadSearch({
+      query,
+      cursor,
+      limit: 50,
+      archived: null,
+    });
+    return { data: res.data ?? [], nextCursor: res.nextCursor ?? null };
+  }
+
+  // The thread with `model` and `diffStat` filled in.
+  public async readThreadStats(
+    folder: vscode.WorkspaceFolder,
+    backendId: BackendId,
+    threadId: string,
+  ): Promise<Thread> {
+    const { proc } = await this.historyProcess(folder, backendId);
+    const res = await proc.threadRead({
+      threadId,
+      includeTurns: false,
+      includeStats: true,
+    });
+    return res.thread;
+  }
+
+  public async exportThread(
+    folder: vscode.WorkspaceFolder,
+    backendId: BackendId,
+    threadId: string,
+    format: ThreadExportFormat,
+  ): Promise<string> {
+    const { proc } = await this.historyProcess(folder, backendId);
+    const res = await proc.threadExport({ threadId, format });
+    return res.contents;
+  }
+
+  public async deleteThread(
+    folder: vscode.WorkspaceFolder,
+    backendId: BackendId,
+    threadId: string,
+  ): Promise<void> {
+    const { proc } = await this.historyProcess(folder, backendId);
+    await proc.threadDelete({ threadId });
+  }
+
+  // Forks a stored thread into a new session; the fork is loaded, like a
+  // resumed thread, and its history comes back in `thread`.
+  public async forkThread(
+    folder: vscode.WorkspaceFolder,
+    backendId: BackendId,
+    source: Thread,
+  ): Promise<{ session: Session; thread: Thread }> {
+    const { proc, backendKey } = await this.historyProcess(folder, backendId);
+    const res = await proc.threadFork({
+      threadId: source.id,
+      path: null,
+      model: null,
+      modelProvider: null,
+      cwd: null,
+      approvalPolicy: null,
+      sandbox: null,
+      config: null,
+      baseInstructions: null,
+      developerInstructions: null,
+      numTurns: null,
+    });
+    const session: Session = {
+      id: randomUUID(),
+      backendKey,
+      backendId,
+      workspaceFolderUri: folder.uri.toString(),
+      title: source.name ?? folder.name,
+      threadId: res.thread.id,
+    };
+    this.sessions.add(backendKey, session);
+    this.output.appendLine(
+      `[session] forked: ${session.title} threadId=${session.threadId} from=${source.id}`,
+    );
+    this.onSessionAdded?.(session);
+    return { session, thread: res.thread };
+  }
+
   private async fetchAllModels(proc: BackendProcess): Promise<Model[]> {
     const out: Model[] = [];
     let cursor: string | null = null;
@@ -780,6 +927,64 @@ export class BackendManager implements vscode.Disposable {
     return await proc.accountLoginStart(params);
   }
 
+  // The effective config as seen from `folder` (with its layers), the
+  // schema of `config.toml`, and what the backend found wrong in the files,
+  // for the settings editor.
+  public async readConfig(
+    folder: vscode.WorkspaceFolder,
+    backendId: BackendId,
+  ): Promise<{
+    config: ConfigReadResponse;
+    schema: unknown;
+    diagnostics: ConfigDiagnostic[];
+    requirements: ConfigRequirements | null;
+  }> {
+    const proc = await this.configProcess(folder, backendId);
+    const [config, schema, validation, requirements] = await Promise.all([
+      proc.configRead({ includeLayers: true, cwd: folder.uri.fsPath }),
+      proc.configSchemaRead(),
+      proc.configValidate({ cwd: folder.uri.fsPath }),
+      proc.configRequirementsRead(),
+    ]);
+    return {
+      config,
+      schema: schema.schema,
+      diagnostics: validation.diagnostics,
+      requirements: requirements.requirements,
+    };
+  }
+
+  // Applies `edits` to the user's `config.toml`. The backend rejects edits
+  // that would not parse, and a stale `expectedVersion`.
+  public async writeConfig(
+    folder: vscode.WorkspaceFolder,
+    backendId: BackendId,
+    edits: ConfigEdit[],
+    expectedVersion: string | null,
+  ): Promise<ConfigWriteResponse> {
+    const proc = await this.configProcess(folder, backendId);
+    return await proc.configBatchWrite({
+      edits,
+      filePath: null,
+      expectedVersion,
+    });
+  }
+
+  private async configProcess(
+    folder: vscode.WorkspaceFolder,
+    backendId: BackendId,
+  ): Promise<BackendProcess> {
+    if (backendId === "opencode")
+      throw new Error("Editing config.toml is not supported on opencode");
+    await this.startForBackendId(folder, backendId);
+    const proc = this.processes.get(
+      makeBackendInstanceKey(folder.uri.toString(), backendId),
+    );
+    if (!proc)
+      throw new Error("Backend is not running for this workspace folder");
+    return proc;
+  }
+
   public async listAccounts(session: Session): Promise<ListAccountsResponse> {
     const folder = this.resolveWorkspaceFolder(session.workspaceFolderUri);
     if (!folder) {
@@ -956,6 +1161,8 @@ export class BackendManager implements vscode.Disposable {
       { kind: "imageUrl"; url: string } | { kind: "localImage"; path: string }
     >,
     modelSettings: ModelSettings | null | undefined,
+    // `editorContext` / `terminalOutput` items attached to the prompt.
+    context: UserInput[] = [],
   ): Promise<void> {
     const folder = this.resolveWorkspaceFolder(session.workspaceFolderUri);
     if (!folder) {
@@ -972,6 +1179,11 @@ export class BackendManager implements vscode.Disposable {
       if (images.length > 0) {
         throw new Error("opencode backend does not support image inputs yet.");
       }
+      if (context.length > 0) {
+        throw new Error(
+          "opencode backend does not support context attachments yet.",
+        );
+      }
       const trimmed = text.trim();
       if (!trimmed) {
         throw new Error("Message must include text");
@@ -1081,6 +1293,7 @@ export class BackendManager implements vscode.Disposable {
       const neverImg: never = img;
       throw new Error(`Unexpected image input: ${String(neverImg)}`);
     }
+    input.push(...context);
     if (input.length === 0) {
       throw new Error("Message must include text or images");
     }
@@ -1099,7 +1312,11 @@ export class BackendManager implements vscode.Disposable {
     };
 
     const imageSuffix = images.length > 0 ? ` [images=${images.length}]` : "";
-    this.output.appendLine(`\n>> (${session.title}) ${text}${imageSuffix}`);
+    const contextSuffix =
+      context.length > 0 ? ` [context=${context.length}]` : "";
+    this.output.appendLine(
+      `\n>> (${session.title}) ${text}${imageSuffix}${contextSuffix}`,
+    );
     this.output.append(`<< (${session.title}) `);
     const turn = await this.withTimeout(
       "turn/start",
@@ -1241,6 +1458,273 @@ export class BackendManager implements vscode.Disposable {
     );
   }
 
+  public async threadRestoreFile(
+    session: Session,
+    path: string,
+    turnId: string | null = null,
+  ): Promise<void> {
+    const folder = this.resolveWorkspaceFolder(session.workspaceFolderUri);
+    if (!folder) {
+      throw new Error(
+        `WorkspaceFolder not found for session: ${session.workspaceFolderUri}`,
+      );
+    }
+
+    await this.startForBackendId(folder, session.backendId);
+    if (this.opencode.get(session.backendKey)) {
+      throw new Error("opencode backend does not support restoring files.");
+    }
+    const proc = this.processes.get(session.backendKey);
+    if (!proc)
+      throw new Error("Backend is not running for this workspace folder");
+
+    const params: ThreadRestoreFileParams = {
+      threadId: session.threadId,
+      turnId,
+      path,
+    };
+    this.output.appendLine(`\n>> (${session.title}) /undo ${path}`);
+    await this.withTimeout(
+      "thread/restoreFile",
+      proc.threadRestoreFile(params),
+      10_000,
+    );
+  }
+
+  public async threadRevertHunks(
+    session: Session,
+    turnId: string | null,
+    path: string,
+    hunks: TurnDiffHunk[],
+  ): Promise<void> {
+    const folder = this.resolveWorkspaceFolder(session.workspaceFolderUri);
+    if (!folder) {
+      throw new Error(
+        `WorkspaceFolder not found for session: ${session.workspaceFolderUri}`,
+      );
+    }
+
+    await this.startForBackendId(folder, session.backendId);
+    if (this.opencode.get(session.backendKey)) {
+      throw new Error("opencode backend does not support reverting hunks.");
+    }
+    const proc = this.processes.get(session.backendKey);
+    if (!proc)
+      throw new Error("Backend is not running for this workspace folder");
+
+    const params: ThreadRevertHunksParams = {
+      threadId: session.threadId,
+      turnId,
+      path,
+      hunks,
+    };
+    this.output.appendLine(
+      `\n>> (${session.title}) revert ${hunks.length} hunk(s) in ${path}`,
+    );
+    await this.withTimeout(
+      "thread/revertHunks",
+      proc.threadRevertHunks(params),
+      10_000,
+    );
+  }
+
+  public async threadPinAdd(
+    session: Session,
+    content: PinnedContent,
+  ): Promise<void> {
+    const proc = await this.pinProcess(session);
+    const label = content.type === "file" ? content.path : content.type;
+    this.output.appendLine(`\n>> (${session.title}) /pin ${label}`);
+    await this.withTimeout(
+      "thread/pin/add",
+      proc.threadPinAdd({ threadId: session.threadId, content }),
+      10_000,
+    );
+  }
+
+  public async threadPinRemove(session: Session, id: string): Promise<void> {
+    const proc = await this.pinProcess(session);
+    await this.withTimeout(
+      "thread/pin/remove",
+      proc.threadPinRemove({ threadId: session.threadId, id }),
+      10_000,
+    );
+  }
+
+  /** Starts an MCP server for this session only; config.toml is untouched. */
+  public async threadMcpServerAdd(
+    session: Session,
+    name: string,
+    config: ThreadMcpServerAddParams["config"],
+  ): Promise<void> {
+    const proc = await this.pinProcess(session, "MCP server changes");
+    this.output.appendLine(`\n>> (${session.title}) /mcp add ${name}`);
+    await this.withTimeout(
+      "thread/mcpServer/add",
+      proc.threadMcpServerAdd({ threadId: session.threadId, name, config }),
+      10_000,
+    );
+  }
+
+  public async threadMcpServerRemove(
+    session: Session,
+    name: string,
+  ): Promise<void> {
+    const proc = await this.pinProcess(session, "MCP server changes");
+    this.output.appendLine(`\n>> (${session.title}) /mcp remove ${name}`);
+    await this.withTimeout(
+      "thread/mcpServer/remove",
+      proc.threadMcpServerRemove({ threadId: session.threadId, name }),
+      10_000,
+    );
+  }
+
+  /** Writes `input` to a command the thread is running in a PTY. */
+  public async threadTerminalWrite(
+    session: Session,
+    processId: string,
+    input: string,
+  ): Promise<void> {
+    const folder = this.resolveWorkspaceFolder(session.workspaceFolderUri);
+    if (!folder) {
+      throw new Error(
+        `WorkspaceFolder not found for session: ${session.workspaceFolderUri}`,
+      );
+    }
+
+    await this.startForBackendId(folder, session.backendId);
+    if (this.opencode.get(session.backendKey)) {
+      throw new Error("opencode backend does not support terminal input.");
+    }
+    const proc = this.processes.get(session.backendKey);
+    if (!proc)
+      throw new Error("Backend is not running for this workspace folder");
+
+    await this.withTimeout(
+      "thread/terminal/write",
+      proc.threadTerminalWrite({
+        threadId: session.threadId,
+        processId,
+        input,
+      }),
+      10_000,
+    );
+  }
+
+  /**
+   * Switches the model / reasoning effort of a running thread. Takes effect
+   * from the next turn; null leaves the current value unchanged.
+   */
+  public async updateThreadSettings(
+    session: Session,
+    model: string | null,
+    reasoning: string | null,
+  ): Promise<void> {
+    const folder = this.resolveWorkspaceFolder(session.workspaceFolderUri);
+    if (!folder) {
+      throw new Error(
+        `WorkspaceFolder not found for session: ${session.workspaceFolderUri}`,
+      );
+    }
+
+    await this.startForBackendId(folder, session.backendId);
+    if (this.opencode.get(session.backendKey)) {
+      throw new Error("opencode backend does not support thread settings.");
+    }
+    const proc = this.processes.get(session.backendKey);
+    if (!proc)
+      throw new Error("Backend is not running for this workspace folder");
+
+    await this.withTimeout(
+      "thread/settings/update",
+      proc.threadSettingsUpdate({
+        threadId: session.threadId,
+        model: model ?? undefined,
+        effort: this.toReasoningEffort(reasoning) ?? undefined,
+      }),
+      10_000,
+    );
+  }
+
+  /** Resolves with the items reported by the next `thread/pinned/updated`. */
+  public async threadPinList(session: Session): Promise<PinnedContextItem[]> {
+    const proc = await this.pinProcess(session);
+    const threadId = session.threadId;
+    const items = new Promise<PinnedContextItem[]>((resolve) => {
+      const waiters = this.pinnedWaitersByThreadId.get(threadId) ?? [];
+      waiters.push(resolve);
+      this.pinnedWaitersByThreadId.set(threadId, waiters);
+    });
+    await this.withTimeout(
+      "thread/pin/list",
+      proc.threadPinList({ threadId }),
+      10_000,
+    );
+    return await this.withTimeout("thread/pinned/updated", items, 10_000);
+  }
+
+  /**
+   * Asks for an inline suggestion at the cursor and resolves with the text of
+   * the matching `thread/nextEdit/suggested` (null: no suggestion).
+   */
+  public async suggestNextEdit(
+    session: Session,
+    args: {
+      path: string;
+      prefix: string;
+      suffix: string;
+      model: string | null;
+    },
+  ): Promise<string | null> {
+    const proc = await this.pinProcess(session, "next edit suggestions");
+    const requestId = randomUUID();
+    const text = new Promise<string | null>((resolve) => {
+      this.nextEditWaitersByRequestId.set(requestId, resolve);
+    });
+    try {
+      await this.withTimeout(
+        "thread/nextEdit/suggest",
+        proc.threadNextEditSuggest({
+          threadId: session.threadId,
+          requestId,
+          path: args.path,
+          prefix: args.prefix,
+          suffix: args.suffix,
+          model: args.model ?? undefined,
+        }),
+        10_000,
+      );
+      return await this.withTimeout("thread/nextEdit/suggested", text, 15_000);
+    } finally {
+      this.nextEditWaitersByRequestId.delete(requestId);
+    }
+  }
+
+  public pinnedItems(session: Session): PinnedContextItem[] {
+    return this.pinnedByThreadId.get(session.threadId) ?? [];
+  }
+
+  private async pinProcess(
+    session: Session,
+    feature = "pinned context",
+  ): Promise<BackendProcess> {
+    const folder = this.resolveWorkspaceFolder(session.workspaceFolderUri);
+    if (!folder) {
+      throw new Error(
+        `WorkspaceFolder not found for session: ${session.workspaceFolderUri}`,
+      );
+    }
+
+    await this.startForBackendId(folder, session.backendId);
+    if (this.opencode.get(session.backendKey)) {
+      throw new Error(`opencode backend does not support ${feature}.`);
+    }
+    const proc = this.processes.get(session.backendKey);
+    if (!proc)
+      throw new Error("Backend is not running for this workspace folder");
+    return proc;
+  }
+
   private emitNotification(
     backendKey: string,
     session: Session | null,
@@ -1253,6 +1737,20 @@ export class BackendManager implements vscode.Disposable {
         this.latestDiffByThreadId.set(session.threadId, diff);
       }
     }
+    if (n.method === "thread/pinned/updated" && session) {
+      const items = ((n as any).params?.items ?? []) as PinnedContextItem[];
+      this.pinnedByThreadId.set(session.threadId, items);
+      const waiters = this.pinnedWaitersByThreadId.get(session.threadId) ?? [];
+      this.pinnedWaitersByThreadId.delete(session.threadId);
+      for (const resolve of waiters) resolve(items);
+    }
+    if (n.method === "thread/nextEdit/suggested") {
+      const p = (n as any).params as { requestId?: unknown; text?: unknown };
+      const requestId = typeof p?.requestId === "string" ? p.requestId : "";
+      this.nextEditWaitersByRequestId.get(requestId)?.(
+        typeof p?.text === "string" ? p.text : null,
+      );
+    }
     if (
       (n.method === "item/started" || n.method === "item/completed") &&
       session
@@ -1905,6 +2403,15 @@ export class BackendManager implements vscode.Disposable {
   }
 }
 
+// The config profile new threads in `folder` start with (`codez.profile`,
+// which folder settings can set per root in a multi-root workspace).
+export function profileForFolder(
+  folder: vscode.WorkspaceFolder,
+): string | null {
+  const cfg = vscode.workspace.getConfiguration("codez", folder.uri);
+  return cfg.get<string>("profile")?.trim() || null;
+}
+
 function extractOpencodeText(msg: OpencodeMessageWithParts): string {
   const parts = Array.isArray(msg.parts) ? msg.parts : [];
   const out: string[] = [];
@@ -12,12 +12,34 @@ import type { ThreadResumeParams } from "../generated/v2/ThreadResumeParams";
 import type { ThreadResumeResponse } from "../generated/v2/ThreadResumeResponse";
 import type { ThreadCompactParams } from "../generated/v2/ThreadCompactParams";
 import type { ThreadCompactResponse } from "../generated/v2/ThreadCompactResponse";
+import type { ThreadRestoreFileParams } from "../generated/v2/ThreadRestoreFileParams";
+import type { ThreadRestoreFileResponse } from "../generated/v2/ThreadRestoreFileResponse";
+import type { ThreadRevertHunksParams } from "../generated/v2/ThreadRevertHunksParams";
+import type { ThreadRevertHunksResponse } from "../generated/v2/ThreadRevertHunksResponse";
+import type { ThreadMcpServerAddParams } from "../generated/v2/ThreadMcpServerAddParams";
+import type { ThreadMcpServerAddResponse } from "../generated/v2/ThreadMcpServerAddResponse";
+import type { ThreadMcpServerRemoveParams } from "../generated/v2/ThreadMcpServerRemoveParams";
+import type { ThreadMcpServerRemoveResponse } from "../generated/v2/ThreadMcpServerRemoveResponse";
+import type { ThreadPinAddParams } from "../generated/v2/ThreadPinAddParams";
+import type { ThreadPinAddResponse } from "../generated/v2/ThreadPinAddResponse";
+import type { ThreadPinRemoveParams } from "../generated/v2/ThreadPinRemoveParams";
+import type { ThreadPinRemoveResponse } from "../generated/v2/ThreadPinRemoveResponse";
+import type { ThreadPinListParams } from "../generated/v2/ThreadPinListParams";
+import type { ThreadPinListResponse } from "../generated/v2/ThreadPinListResponse";
+import type { ThreadTerminalWriteParams } from "../generated/v2/ThreadTerminalWriteParams";
+import type { ThreadTerminalWriteResponse } from "../generated/v2/ThreadTerminalWriteResponse";
+import type { ThreadSettingsUpdateParams } from "../generated/v2/ThreadSettingsUpdateParams";
+import type { ThreadSettingsUpdateResponse } from "../generated/v2/ThreadSettingsUpdateResponse";
+import type { ThreadNextEditSuggestParams } from "../generated/v2/ThreadNextEditSuggestParams";
+import type { ThreadNextEditSuggestResponse } from "../generated/v2/ThreadNextEditSuggestResponse";
 import type { TurnStartParams } from "../generated/v2/TurnStartParams";
 import type { TurnStartResponse } from "../generated/v2/TurnStartResponse";
 import type { TurnInterruptParams } from "../generated/v2/TurnInterruptParams";
 import type { TurnInterruptResponse } from "../generated/v2/TurnInterruptResponse";
 import type { SkillsListParams } from "../generated/v2/SkillsListParams";
 import type { SkillsListResponse } from "../generated/v2/SkillsListResponse";
+import type { SubAgentsListParams } from "../generated/v2/SubAgentsListParams";
+import type { SubAgentsListResponse } from "../generated/v2/SubAgentsListResponse";
 import type { ModelListParams } from "../generated/v2/ModelListParams";
 import type { ModelListResponse } from "../generated/v2/ModelListResponse";
 import type { ThreadArchiveParams } from "../generated/v2/ThreadArchiveParams";
@@ -26,6 +48,16 @@ import type { ThreadRollbackParams } from "../generated/v2/ThreadRollbackParams"
 import type { ThreadRollbackResponse } from "../generated/v2/ThreadRollbackResponse";
 import type { ThreadListParams } from "../generated/v2/ThreadListParams";
 import type { ThreadListResponse } from "../generated/v2/ThreadListResponse";
+import type { ThreadSearchParams } from "../generated/v2/ThreadSearchParams";
+import type { ThreadSearchResponse } from "../generated/v2/ThreadSearchResponse";
+import type { ThreadReadParams } from "../generated/v2/ThreadReadParams";
+import type { ThreadReadResponse } from "../generated/v2/ThreadReadResponse";
+import type { ThreadForkParams } from "../generated/v2/ThreadForkParams";
+import type { ThreadForkResponse } from "../generated/v2/ThreadForkResponse";
+import type { ThreadExportParams } from "../generated/v2/ThreadExportParams";
+import type { ThreadExportResponse } from "../generated/v2/ThreadExportResponse";
+import type { ThreadDeleteParams } from "../generated/v2/ThreadDeleteParams";
+import type { ThreadDeleteResponse } from "../generated/v2/ThreadDeleteResponse";
 import type { ListMcpServerStatusParams } from "../generated/v2/ListMcpServerStatusParams";
 import type { ListMcpServerStatusResponse } from "../generated/v2/ListMcpServerStatusResponse";
 import type { GetAccountParams } from "../generated/v2/GetAccountParams";
@@ -37,9 +69,18 @@ import type { ListAccountsResponse } from "../generated/v2/ListAccountsResponse"
 import type { LogoutAccountResponse } from "../generated/v2/LogoutAccountResponse";
 import type { SwitchAccountParams } from "../generated/v2/SwitchAccountParams";
 import type { SwitchAccountResponse } from "../generated/v2/SwitchAccountResponse";
+import type { ConfigReadParams } from "../generated/v2/ConfigReadParams";
+import type { ConfigReadResponse } from "../generated/v2/ConfigReadResponse";
+import type { ConfigRequirementsReadResponse } from "../generated/v2/ConfigRequirementsReadResponse";
+import type { ConfigSchemaReadResponse } from "../generated/v2/ConfigSchemaReadResponse";
+import type { ConfigValidateParams } from "../generated/v2/ConfigValidateParams";
+import type { ConfigValidateResponse } from "../generated/v2/ConfigValidateResponse";
+import type { ConfigBatchWriteParams } from "../generated/v2/ConfigBatchWriteParams";
+import type { ConfigWriteResponse } from "../generated/v2/ConfigWriteResponse";
 import type { ApprovalDecision } from "../generated/v2/ApprovalDecision";
 import type { CommandExecutionRequestApprovalResponse } from "../generated/v2/CommandExecutionRequestApprovalResponse";
 import type { FileChangeRequestApprovalResponse } from "../generated/v2/FileChangeRequestApprovalResponse";
+import type { McpToolCallRequestApprovalResponse } from "../generated/v2/McpToolCallRequestApprovalResponse";
 import type { AskUserQuestionResponse } from "../generated/v2/AskUserQuestionResponse";
 import type { FuzzyFileSearchParams } from "../generated/FuzzyFileSearchParams";
 import type { FuzzyFileSearchResponse } from "../generated/FuzzyFileSearchResponse";
@@ -190,6 +231,96 @@ export class BackendProcess implements vscode.Disposable {
     });
   }
 
+  public async threadRestoreFile(
+    params: ThreadRestoreFileParams,
+  ): Promise<ThreadRestoreFileResponse> {
+    return this.rpc.request<ThreadRestoreFileResponse>({
+      method: "thread/restoreFile",
+      params,
+    });
+  }
+
+  public async threadRevertHunks(
+    params: ThreadRevertHunksParams,
+  ): Promise<ThreadRevertHunksResponse> {
+    return this.rpc.request<ThreadRevertHunksResponse>({
+      method: "thread/revertHunks",
+      params,
+    });
+  }
+
+  public async threadMcpServerAdd(
+    params: ThreadMcpServerAddParams,
+  ): Promise<ThreadMcpServerAddResponse> {
+    return this.rpc.request<ThreadMcpServerAddResponse>({
+      method: "thread/mcpServer/add",
+      params,
+    });
+  }
+
+  public async threadMcpServerRemove(
+    params: ThreadMcpServerRemoveParams,
+  ): Promise<ThreadMcpServerRemoveResponse> {
+    return this.rpc.request<ThreadMcpServerRemoveResponse>({
+      method: "thread/mcpServer/remove",
+      params,
+    });
+  }
+
+  public async threadPinAdd(
+    params: ThreadPinAddParams,
+  ): Promise<ThreadPinAddResponse> {
+    return this.rpc.request<ThreadPinAddResponse>({
+      method: "thread/pin/add",
+      params,
+    });
+  }
+
+  public async threadPinRemove(
+    params: ThreadPinRemoveParams,
+  ): Promise<ThreadPinRemoveResponse> {
+    return this.rpc.request<ThreadPinRemoveResponse>({
+      method: "thread/pin/remove",
+      params,
+    });
+  }
+
+  public async threadPinList(
+    params: ThreadPinListParams,
+  ): Promise<ThreadPinListResponse> {
+    return this.rpc.request<ThreadPinListResponse>({
+      method: "thread/pin/list",
+      params,
+    });
+  }
+
+  public async threadTerminalWrite(
+    params: ThreadTerminalWriteParams,
+  ): Promise<ThreadTerminalWriteResponse> {
+    return this.rpc.request<ThreadTerminalWriteResponse>({
+      method: "thread/terminal/write",
+      params,
+    });
+  }
+
+  public async threadSettingsUpdate(
+    params: ThreadSettingsUpdateParams,
+  ): Promise<ThreadSettingsUpdateResponse> {
+    return this.rpc.request<ThreadSettingsUpdateResponse>({
+      method: "thread/settings/update",
+      params,
+    });
+  }
+
+  public async threadNextEditSuggest(
+    params: ThreadNextEditSuggestParams,
+  ): Promise<ThreadNextEditSuggestResponse> {
+    return this.rpc.request<ThreadNextEditSuggestResponse>({
+      method: "thread/nextEdit/suggest",
+      params,
+    });
+  }
+
   public async threadRollback(
     params: ThreadRollbackParams,
   ): Promise<ThreadRollbackResponse> {
@@ -215,6 +346,51 @@ export class BackendProcess implements vscode.Disposable {
     return this.rpc.request<ThreadListResponse>(request);
   }
 
+  public async threadSearch(
+    params: ThreadSearchParams,
+  ): Promise<ThreadSearchResponse> {
+    return this.rpc.request<ThreadSearchResponse>({
+      method: "thread/search",
+      params,
+    });
+  }
+
+  public async threadRead(
+    params: ThreadReadParams,
+  ): Promise<ThreadReadResponse> {
+    return this.rpc.request<ThreadReadResponse>({
+      method: "thread/read",
+      params,
+    });
+  }
+
+  public async threadFork(
+    params: ThreadForkParams,
+  ): Promise<ThreadForkResponse> {
+    return this.rpc.request<ThreadForkResponse>({
+      method: "thread/fork",
+      params,
+    });
+  }
+
+  public async threadExport(
+    params: ThreadExportParams,
+  ): Promise<ThreadExportResponse> {
+    return this.rpc.request<ThreadExportResponse>({
+      method: "thread/export",
+      params,
+    });
+  }
+
+  public async threadDelete(
+    params: ThreadDeleteParams,
+  ): Promise<ThreadDeleteResponse> {
+    return this.rpc.request<ThreadDeleteResponse>({
+      method: "thread/delete",
+      params,
+    });
+  }
+
   public async mcpServerStatusList(
     params: ListMcpServerStatusParams,
   ): Promise<ListMcpServerStatusResponse> {
@@ -262,6 +438,15 @@ export class BackendProcess implements vscode.Disposable {
     });
   }
 
+  public async subagentsList(
+    params: SubAgentsListParams,
+  ): Promise<SubAgentsListResponse> {
+    return this.rpc.request<SubAgentsListResponse>({
+      method: "subagents/list",
+      params,
+    });
+  }
+
   public async accountRead(
     params: GetAccountParams,
   ): Promise<GetAccountResponse> {
@@ -303,6 +488,47 @@ export class BackendProcess implements vscode.Disposable {
     });
   }
 
+  public async configRead(
+    params: ConfigReadParams,
+  ): Promise<ConfigReadResponse> {
+    return this.rpc.request<ConfigReadResponse>({
+      method: "config/read",
+      params,
+    });
+  }
+
+  public async configRequirementsRead(): Promise<ConfigRequirementsReadResponse> {
+    return this.rpc.request<ConfigRequirementsReadResponse>({
+      method: "configRequirements/read",
+      params: undefined,
+    });
+  }
+
+  public async configSchemaRead(): Promise<ConfigSchemaReadResponse> {
+    return this.rpc.request<ConfigSchemaReadResponse>({
+      method: "config/schema/read",
+      params: undefined,
+    });
+  }
+
+  public async configValidate(
+    params: ConfigValidateParams,
+  ): Promise<ConfigValidateResponse> {
+    return this.rpc.request<ConfigValidateResponse>({
+      method: "config/validate",
+      params,
+    });
+  }
+
+  public async configBatchWrite(
+    params: ConfigBatchWriteParams,
+  ): Promise<ConfigWriteResponse> {
+    return this.rpc.request<ConfigWriteResponse>({
+      method: "config/batchWrite",
+      params,
+    });
+  }
+
   public async accountRateLimitsRead(): Promise<GetAccountRateLimitsResponse> {
     return this.rpc.request<GetAccountRateLimitsResponse>({
       method: "account/rateLimits/read",
@@ -369,7 +595,7 @@ export class BackendProcess implements vscode.Disposable {
     }
 
     if (this.approvalsDefaultDecision !== "prompt") {
-      if (isV2ApprovalRequest(req)) {
+      if (usesV2Decision(req)) {
         const decision =
           this.approvalsDefaultDecision === "decline" ? "decline" : "cancel";
         this.respondV2Approval(req.id, decision);
@@ -403,12 +629,12 @@ export class BackendProcess implements vscode.Disposable {
     );
 
     if (choice === "Accept") {
-      if (isV2ApprovalRequest(req)) this.respondV2Approval(req.id, "accept");
+      if (usesV2Decision(req)) this.respondV2Approval(req.id, "accept");
       else this.respondV1Approval(req.id, "approved");
       return;
     }
     if (choice === "Accept (For Session)") {
-      if (isV2ApprovalRequest(req)) {
+      if (usesV2Decision(req)) {
         this.respondV2Approval(req.id, "acceptForSession");
       } else {
         this.respondV1Approval(req.id, "approved_for_session");
@@ -416,18 +642,19 @@ export class BackendProcess implements vscode.Disposable {
       return;
     }
     if (choice === "Decline") {
-      if (isV2ApprovalRequest(req)) this.respondV2Approval(req.id, "decline");
+      if (usesV2Decision(req)) this.respondV2Approval(req.id, "decline");
       else this.respondV1Approval(req.id, "denied");
       return;
     }
-    if (isV2ApprovalRequest(req)) this.respondV2Approval(req.id, "cancel");
+    if (usesV2Decision(req)) this.respondV2Approval(req.id, "cancel");
     else this.respondV1Approval(req.id, "abort");
   }
 
   public respondV2Approval(id: RequestId, decision: ApprovalDecision): void {
     const result:
       | CommandExecutionRequestApprovalResponse
-      | FileChangeRequestApprovalResponse = { decision };
+      | FileChangeRequestApprovalResponse
+      | McpToolCallRequestApprovalResponse = { decision };
     this.rpc.respond(id, result);
   }
 
@@ -469,6 +696,27 @@ function isV2ApprovalRequest(req: ServerRequest): req is V2ApprovalRequest {
   );
 }
 
+type McpToolApprovalRequest = Extract<
+  ServerRequest,
+  {
+    method: "item/mcpToolCall/requestApproval";
+  }
+>;
+
+function isMcpToolApprovalRequest(
+  req: ServerRequest,
+): req is McpToolApprovalRequest {
+  return req.method === "item/mcpToolCall/requestApproval";
+}
+
+// MCP tool approvals have no dedicated UI yet, but they answer with the same
+// v2 decisions as command and file change approvals.
+function usesV2Decision(
+  req: ServerRequest,
+): req is V2ApprovalRequest | McpToolApprovalRequest {
+  return isV2ApprovalRequest(req) || isMcpToolApprovalRequest(req);
+}
+
 function isAskUserQuestionRequest(
   req: ServerRequest,
 ): req is V2AskUserQuestionRequest {
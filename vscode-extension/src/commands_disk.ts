@@ -0,0 +1,104 @@
+import * as fs from "node:fs/promises";
+import * as path from "node:path";
+
+import {
+  findGitRoot,
+  listMarkdownStems,
+  resolveCodexHome,
+} from "./agents_disk";
+
+export type CustomCommandSummary = {
+  name: string;
+  description: string | null;
+  argumentHint: string | null;
+  path: string;
+  source: "repo" | "home";
+};
+
+// Custom slash commands: <git root>/.codex/commands/<name>.md, then
+// $CODEX_HOME/commands/<name>.md. Same-named commands resolve to the repo
+// one, matching the backend. The backend expands `/<name> ARGS` on send, so
+// only the names and frontmatter are needed here.
+export async function listCustomCommandsFromDisk(
+  cwdFsPath: string,
+): Promise<{
+  commands: CustomCommandSummary[];
+  errors: string[];
+}> {
+  const errors: string[] = [];
+  const commands: CustomCommandSummary[] = [];
+  const seen = new Set<string>();
+
+  const gitRoot = await findGitRoot(cwdFsPath, errors);
+  const roots: Array<{ dir: string; source: "repo" | "home" }> = [];
+  if (gitRoot)
+    roots.push({
+      dir: path.join(gitRoot, ".codex", "commands"),
+      source: "repo",
+    });
+  roots.push({
+    dir: path.join(resolveCodexHome(), "commands"),
+    source: "home",
+  });
+
+  for (const root of roots) {
+    const names = await listMarkdownStems(root.dir, errors);
+    for (const name of names) {
+      if (!/^[A-Za-z0-9_-]+$/.test(name)) {
+        errors.push(`${root.source}: invalid command name: ${name}`);
+        continue;
+      }
+      if (seen.has(name)) continue;
+      const filePath = path.join(root.dir, `${name}.md`);
+      let content: string;
+      try {
+        content = await fs.readFile(filePath, "utf8");
+      } catch (err) {
+        errors.push(
+          `${root.source}: failed to read ${filePath}: ${String((err as Error).message ?? err)}`,
+        );
+        continue;
+      }
+      seen.add(name);
+      const fields = parseFrontmatterFields(content);
+      commands.push({
+        name,
+        description: fields.get("description") || null,
+        argumentHint:
+          fields.get("argument-hint") || fields.get("argument_hint") || null,
+        path: filePath,
+        source: root.source,
+      });
+    }
+  }
+
+  commands.sort((a, b) => a.name.localeCompare(b.name));
+  return { commands, errors };
+}
+
+// Frontmatter is optional for commands; a file without a terminated
+// `---` block has no fields.
+function parseFrontmatterFields(content: string): Map<string, string> {
+  const fields = new Map<string, string>();
+  const lines = content.split(/\r?\n/);
+  if ((lines[0] ?? "").trim() !== "---") return fields;
+
+  for (let i = 1; i < lines.length; i += 1) {
+    const trimmed = (lines[i] ?? "").trim();
+    if (trimmed === "---") return fields;
+    if (!trimmed || trimmed.startsWith("#")) continue;
+    const idx = trimmed.indexOf(":");
+    if (idx <= 0) continue;
+    const key = trimmed.slice(0, idx).trim().toLowerCase();
+    let val = trimmed.slice(idx + 1).trim();
+    if (val.length >= 2) {
+      const first = val[0];
+      const last = val[val.length - 1];
+      if ((first === '"' && last === '"') || (first === "'" && last === "'")) {
+        val = val.slice(1, -1);
+      }
+    }
+    fields.set(key, val.trim());
+  }
+  return new Map();
+}
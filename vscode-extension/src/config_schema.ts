@@ -0,0 +1,244 @@
+// Validation of `config.toml` values against the schema the backend publishes
+// (`config/schema/read`), so the settings editor can point at a bad field
+// before anything is written. Covers the draft-07 keywords the generated
+// schema uses: `$ref` into `definitions`, `allOf` / `anyOf` / `oneOf`, `enum`,
+// `type`, `minimum`, `properties`, `additionalProperties`, `required` and
+// `items`. The backend still validates every write.
+
+type SchemaObject = { [keyword: string]: unknown };
+type Schema = SchemaObject | boolean;
+
+export type SchemaError = {
+  // Dotted key path of the offending value, e.g. `mcp_servers.docs.args`.
+  path: string;
+  message: string;
+};
+
+export class ConfigSchema {
+  public constructor(private readonly root: SchemaObject) {}
+
+  public static from(value: unknown): ConfigSchema | null {
+    if (!isObject(value)) return null;
+    return new ConfigSchema(value);
+  }
+
+  // Errors for `value` as the value of `keyPath` (dotted, from the root).
+  public validate(keyPath: string, value: unknown): SchemaError[] {
+    const schema = this.at(keyPath);
+    if (schema === null) {
+      return [{ path: keyPath, message: "Unknown setting." }];
+    }
+    const errors: SchemaError[] = [];
+    this.check(schema, value, keyPath, errors);
+    return errors;
+  }
+
+  // The allowed values of a string setting, in schema order.
+  public enumValues(keyPath: string): string[] {
+    const schema = this.at(keyPath);
+    return schema === null ? [] : this.collectEnum(schema);
+  }
+
+  public description(keyPath: string): string | null {
+    const schema = this.at(keyPath);
+    if (!isObject(schema)) return null;
+    if (typeof schema["description"] === "string") {
+      return schema["description"];
+    }
+    const target = this.resolve(schema);
+    return isObject(target) && typeof target["description"] === "string"
+      ? target["description"]
+      : null;
+  }
+
+  // The schema of the value at `keyPath`, following `properties` and then
+  // `additionalProperties`; null when the path is not allowed.
+  private at(keyPath: string): Schema | null {
+    let schema: Schema = this.root;
+    for (const segment of keyPath.split(".")) {
+      const next = this.child(schema, segment);
+      if (next === null) return null;
+      schema = next;
+    }
+    return schema;
+  }
+
+  private child(schema: Schema, key: string): Schema | null {
+    const target = this.resolve(schema);
+    if (target === true) return true;
+    if (target === false) return null;
+    const properties = target["properties"];
+    if (isObject(properties) && key in properties) {
+      return properties[key] as Schema;
+    }
+    const additional = target["additionalProperties"];
+    if (additional === undefined || additional === true) return true;
+    if (additional === false) return null;
+    return additional as Schema;
+  }
+
+  // Follows `$ref` and a single-entry `allOf` (how schemars wraps a
+  // reference that carries its own description).
+  private resolve(schema: Schema): Schema {
+    let current = schema;
+    for (let depth = 0; depth < 32 && isObject(current); depth += 1) {
+      const ref = current["$ref"];
+      const allOf = current["allOf"];
+      if (typeof ref === "string") {
+        current = this.definition(ref);
+      } else if (Array.isArray(allOf) && allOf.length === 1) {
+        current = allOf[0] as Schema;
+      } else {
+        break;
+      }
+    }
+    return current;
+  }
+
+  private definition(ref: string): Schema {
+    const prefix = "#/definitions/";
+    const definitions = this.root["definitions"];
+    if (!ref.startsWith(prefix) || !isObject(definitions)) return true;
+    const found = definitions[ref.slice(prefix.length)];
+    return found === undefined ? true : (found as Schema);
+  }
+
+  private collectEnum(schema: Schema): string[] {
+    const target = this.resolve(schema);
+    if (!isObject(target)) return [];
+    const values = Array.isArray(target["enum"])
+      ? target["enum"].filter((v): v is string => typeof v === "string")
+      : [];
+    for (const key of ["anyOf", "oneOf"]) {
+      const branches = target[key];
+      if (!Array.isArray(branches)) continue;
+      for (const branch of branches) {
+        values.push(...this.collectEnum(branch as Schema));
+      }
+    }
+    return values;
+  }
+
+  private check(
+    schema: Schema,
+    value: unknown,
+    path: string,
+    errors: SchemaError[],
+  ): void {
+    const target = this.resolve(schema);
+    if (target === true) return;
+    if (target === false) {
+      errors.push({ path, message: "Not allowed here." });
+      return;
+    }
+
+    const allOf = target["allOf"];
+    if (Array.isArray(allOf)) {
+      for (const branch of allOf) {
+        this.check(branch as Schema, value, path, errors);
+      }
+    }
+    for (const key of ["anyOf", "oneOf"]) {
+      const branches = target[key];
+      if (!Array.isArray(branches)) continue;
+      const matches = branches.some((branch) => {
+        const branchErrors: SchemaError[] = [];
+        this.check(branch as Schema, value, path, branchErrors);
+        return branchErrors.length === 0;
+      });
+      if (!matches) {
+        const allowed = this.collectEnum(target);
+        errors.push({
+          path,
+          message:
+            allowed.length > 0
+              ? `Must be one of: ${allowed.join(", ")}.`
+              : "Does not match any allowed form.",
+        });
+        return;
+      }
+    }
+
+    const allowedValues = target["enum"];
+    if (
+      Array.isArray(allowedValues) &&
+      !allowedValues.some((v) => JSON.stringify(v) === JSON.stringify(value))
+    ) {
+      errors.push({
+        path,
+        message: `Must be one of: ${allowedValues.map(String).join(", ")}.`,
+      });
+      return;
+    }
+
+    const type = target["type"];
+    if (type !== undefined) {
+      const types = Array.isArray(type) ? type : [type];
+      if (!types.some((t) => matchesType(String(t), value))) {
+        errors.push({ path, message: `Must be ${types.join(" or ")}.` });
+        return;
+      }
+    }
+
+    const minimum = target["minimum"];
+    if (typeof minimum === "number" && typeof value === "number") {
+      if (value < minimum) {
+        errors.push({ path, message: `Must be at least ${minimum}.` });
+      }
+    }
+
+    if (Array.isArray(value)) {
+      const items = target["items"];
+      if (items !== undefined && !Array.isArray(items)) {
+        value.forEach((item, index) =>
+          this.check(items as Schema, item, `${path}[${index}]`, errors),
+        );
+      }
+      return;
+    }
+
+    if (isObject(value)) {
+      const required = target["required"];
+      if (Array.isArray(required)) {
+        for (const key of required) {
+          if (!(String(key) in value)) {
+            errors.push({ path: `${path}.${key}`, message: "Required." });
+          }
+        }
+      }
+      for (const [key, child] of Object.entries(value)) {
+        const childSchema = this.child(target, key);
+        if (childSchema === null) {
+          errors.push({ path: `${path}.${key}`, message: "Unknown setting." });
+          continue;
+        }
+        this.check(childSchema, child, `${path}.${key}`, errors);
+      }
+    }
+  }
+}
+
+function matchesType(type: string, value: unknown): boolean {
+  switch (type) {
+    case "string":
+      return typeof value === "string";
+    case "boolean":
+      return typeof value === "boolean";
+    case "integer":
+      return typeof value === "number" && Number.isInteger(value);
+    case "number":
+      return typeof value === "number";
+    case "array":
+      return Array.isArray(value);
+    case "object":
+      return isObject(value);
+    case "null":
+      return value === null;
+    default:
+      return true;
+  }
+}
+
+function isObject(value: unknown): value is SchemaObject {
+  return typeof value === "object" && value !== null && !Array.isArray(value);
+}
@@ -9,13 +9,17 @@ import { parse as shellParse } from "shell-quote";
 import * as vscode from "vscode";
 import { BackendManager } from "./backend/manager";
 import type { BackendTermination } from "./backend/manager";
+import { profileForFolder } from "./backend/manager";
 import { listAgentsFromDisk } from "./agents_disk";
+import { listCustomCommandsFromDisk } from "./commands_disk";
 import type { AnyServerNotification } from "./backend/types";
 import type { ContentBlock } from "./generated/ContentBlock";
 import type { ImageContent } from "./generated/ImageContent";
 import type { AskUserQuestionRequest } from "./generated/AskUserQuestionRequest";
 import type { CommandAction } from "./generated/v2/CommandAction";
 import type { Model } from "./generated/v2/Model";
+import type { PinnedContent } from "./generated/v2/PinnedContent";
+import type { PinnedContextItem } from "./generated/v2/PinnedContextItem";
 import type { RateLimitSnapshot } from "./generated/v2/RateLimitSnapshot";
 import type { RateLimitWindow } from "./generated/v2/RateLimitWindow";
 import type { AskUserQuestionResponse } from "./generated/v2/AskUserQuestionResponse";
@@ -23,6 +27,7 @@ import type { Thread } from "./generated/v2/Thread";
 import type { ThreadItem } from "./generated/v2/ThreadItem";
 import type { ThreadTokenUsage } from "./generated/v2/ThreadTokenUsage";
 import type { Turn } from "./generated/v2/Turn";
+import type { TurnDiffFile } from "./generated/v2/TurnDiffFile";
 import type { BackendId, Session } from "./sessions";
 import { SessionStore } from "./sessions";
 import {
@@ -39,17 +44,81 @@ import {
   type ModelState,
 } from "./ui/chat_view";
 import { DiffDocumentProvider, makeDiffUri } from "./ui/diff_provider";
+import { BASE_SCHEME, DiffReviewController } from "./ui/diff_review";
+import {
+  AskCodexLensProvider,
+  buildAskPrompt,
+  defaultQuestion,
+  targetFromEditor,
+} from "./ui/ask_codex";
+import type { AskTarget } from "./ui/ask_codex";
+import {
+  BACKEND_ITEM_LIMIT_BYTES,
+  EditorContextStore,
+  fileAttachment,
+  formatBytes,
+  linesAttachment,
+  openEditorAttachments,
+  selectionAttachment,
+  terminalAttachment,
+  warnBytesFor,
+  type EditorContextAttachment,
+} from "./ui/editor_context";
+import { TerminalCapture } from "./ui/terminal_capture";
+import {
+  StagedReviewComments,
+  codezCommand,
+  resolveRepository,
+  reviewStagedChanges,
+  setCommitMessage,
+  suggestCommitMessage,
+} from "./ui/scm_assist";
+import {
+  ModelStatusBar,
+  pickModelAndEffort,
+  type ModelStatus,
+} from "./ui/model_status";
+import {
+  FixWithCodexProvider,
+  buildFixPrompt,
+  contextLines,
+} from "./ui/fix_with_codex";
+import { NextEditProvider, nextEditEnabled } from "./ui/next_edit";
 import { SessionPanelManager } from "./ui/session_panel_manager";
+import { ConfigPanel } from "./ui/config_panel";
 import { SessionTreeDataProvider } from "./ui/session_tree";
+import { HistoryTreeDataProvider } from "./ui/history_tree";
+import type { HistoryMoreNode, HistoryThreadNode } from "./ui/history_tree";
+import {
+  SubAgentsTreeDataProvider,
+  subagentRootLabel,
+  type SubAgentTreeNode,
+} from "./ui/subagents_tree";
 
 const REWIND_STEP_TIMEOUT_MS = 120_000;
 const LAST_ACTIVE_SESSION_KEY = "codez.lastActiveSessionId.v1";
 const DEFAULT_PROJECT_DOC_FILENAME = "AGENTS.md";
+// The History view lists threads through codez-only APIs (`thread/search`,
+// `thread/read` stats, `thread/delete`), so it always talks to codez.
+const HISTORY_BACKEND_ID: BackendId = "codez";
+// The settings editor validates against `config/schema/read`, a codez-only
+// API, so it also talks to codez.
+const CONFIG_BACKEND_ID: BackendId = "codez";
 
 let backendManager: BackendManager | null = null;
 let sessions: SessionStore | null = null;
 let sessionTree: SessionTreeDataProvider | null = null;
+let subagentsTree: SubAgentsTreeDataProvider | null = null;
+let historyTree: HistoryTreeDataProvider | null = null;
 let diffProvider: DiffDocumentProvider | null = null;
+let diffReview: DiffReviewController | null = null;
+let editorContext: EditorContextStore | null = null;
+let terminalCapture: TerminalCapture | null = null;
+let stagedReview: StagedReviewComments | null = null;
+let modelStatus: ModelStatusBar | null = null;
+// File of a "Fix with Codex" request whose review opens once the turn
+// changes it, by session id.
+const fixReviewUriBySessionId = new Map<string, string>();
 let chatView: ChatViewProvider | null = null;
 let sessionPanels: SessionPanelManager | null = null;
 let activeSessionId: string | null = null;
@@ -371,6 +440,8 @@ const WORKSPACE_COLOR_PALETTE = [
 ] as const;
 let workspaceColorOverrides: Record<string, number> = {};
 const mcpStatusByBackendKey = new Map<string, Map<string, string>>();
+// sessionId -> processId -> command for running background terminals.
+const runningTerminalsBySessionId = new Map<string, Map<string, string>>();
 const defaultTitleRe = /^(.*)\s+\([0-9a-f]{8}\)$/i;
 type UiImageInput = { name: string; url: string };
 type BackendImageInput =
@@ -391,6 +462,16 @@ type SessionRuntime = {
   statusText: string | null;
   uiHydrationBlockedText: string | null;
   tokenUsage: ThreadTokenUsage | null;
+  // Set while the turn waits out a provider 429; the status bar counts down.
+  rateLimitResumeAtMs: number | null;
+  rateLimitCountdownTimer: NodeJS.Timeout | null;
+  // Last rate limit / auth failure, with the model it happened on. Cleared by
+  // a completed turn or a model switch.
+  modelProblem: {
+    kind: "rateLimit" | "auth";
+    model: string | null;
+    message: string;
+  } | null;
   sending: boolean;
   reloading: boolean;
   compactInFlight: boolean;
@@ -419,6 +500,7 @@ type SessionRuntime = {
       command: string | null;
       cwd: string | null;
       grantRoot: string | null;
+      explanation: string | null;
     }
   >;
   approvalResolvers: Map<
@@ -487,6 +569,7 @@ export function activate(context: vscode.ExtensionContext): void {
   backendManager.onBackendTerminated = (backendKey, info) =>
     handleBackendTerminated(backendKey, info);
   backendManager.onServerEvent = (backendKey, session, n) => {
+    if (n.method === "subagents/updated") subagentsTree?.refresh();
     if (session) applyServerNotification(backendKey, session.id, n);
     else applyGlobalNotification(backendKey, n);
   };
@@ -523,6 +606,10 @@ export function activate(context: vscode.ExtensionContext): void {
       req.method === "item/fileChange/requestApproval"
         ? (req.params.grantRoot ?? null)
         : null;
+    const explanation =
+      req.method === "item/commandExecution/requestApproval"
+        ? (req.params.explanation ?? null)
+        : null;
 
     rt.pendingApprovals.set(requestKey, {
       title,
@@ -534,6 +621,7 @@ export function activate(context: vscode.ExtensionContext): void {
       command: fallbackCommand,
       cwd: fallbackCwd,
       grantRoot: fallbackGrantRoot,
+      explanation,
     });
     chatView?.refresh();
     void showCodezViewContainer();
@@ -637,6 +725,47 @@ export function activate(context: vscode.ExtensionContext): void {
   );
   context.subscriptions.push(diffProvider);
 
+  diffReview = new DiffReviewController(
+    async (sessionId, turnId, filePath, hunks) => {
+      if (!backendManager)
+        throw new Error("backendManager is not initialized");
+      const session = sessions?.getById(sessionId);
+      if (!session) throw new Error("Session not found.");
+      await backendManager.threadRevertHunks(session, turnId, filePath, hunks);
+    },
+  );
+  context.subscriptions.push(diffReview);
+  context.subscriptions.push(
+    vscode.workspace.registerTextDocumentContentProvider(
+      BASE_SCHEME,
+      diffReview,
+    ),
+    vscode.languages.registerCodeLensProvider({ scheme: "file" }, diffReview),
+    vscode.languages.registerCodeActionsProvider(
+      { scheme: "file" },
+      diffReview,
+      { providedCodeActionKinds: [vscode.CodeActionKind.QuickFix] },
+    ),
+    vscode.commands.registerCommand(
+      "codez.diffReview.keep",
+      (key: string, newStart: number | null) => {
+        diffReview?.keep(key, newStart);
+      },
+    ),
+    vscode.commands.registerCommand(
+      "codez.diffReview.revert",
+      async (key: string, newStart: number | null) => {
+        try {
+          await diffReview?.revertHunks(key, newStart);
+        } catch (err) {
+          void vscode.window.showErrorMessage(
+            `Failed to revert the change: ${String(err)}`,
+          );
+        }
+      },
+    ),
+  );
+
   sessionTree = new SessionTreeDataProvider(
     context.extensionUri,
     sessions,
@@ -649,6 +778,68 @@ export function activate(context: vscode.ExtensionContext): void {
     }),
   );
 
+  subagentsTree = new SubAgentsTreeDataProvider(
+    async (folder) => {
+      if (!backendManager) throw new Error("backendManager is not initialized");
+      return await backendManager.listSubAgentsForWorkspaceFolder(folder);
+    },
+    (folder, err) => {
+      output.appendLine(
+        `[subagents] failed to list for ${folder.uri.fsPath}: ${String((err as Error).message ?? err)}`,
+      );
+    },
+  );
+  context.subscriptions.push(subagentsTree);
+  context.subscriptions.push(
+    vscode.window.createTreeView("codez.subagentsView", {
+      treeDataProvider: subagentsTree,
+    }),
+  );
+
+  historyTree = new HistoryTreeDataProvider(
+    {
+      list: async (folder, cursor) => {
+        if (!backendManager)
+          throw new Error("backendManager is not initialized");
+        return await backendManager.listThreadsForWorkspaceFolderAndBackendId(
+          folder,
+          HISTORY_BACKEND_ID,
+          { cursor, limit: 50, modelProviders: null },
+        );
+      },
+      search: async (folder, query, cursor) => {
+        if (!backendManager)
+          throw new Error("backendManager is not initialized");
+        return await backendManager.searchThreads(
+          folder,
+          HISTORY_BACKEND_ID,
+          query,
+          cursor,
+        );
+      },
+      stats: async (folder, threadId) => {
+        if (!backendManager)
+          throw new Error("backendManager is not initialized");
+        return await backendManager.readThreadStats(
+          folder,
+          HISTORY_BACKEND_ID,
+          threadId,
+        );
+      },
+    },
+    (folder, err) => {
+      output.appendLine(
+        `[history] failed to list threads for ${folder.uri.fsPath}: ${String((err as Error).message ?? err)}`,
+      );
+    },
+  );
+  context.subscriptions.push(historyTree);
+  context.subscriptions.push(
+    vscode.window.createTreeView("codez.historyView", {
+      treeDataProvider: historyTree,
+    }),
+  );
+
   chatView = new ChatViewProvider(
     context,
     () => buildChatState(),
@@ -1152,14 +1343,7 @@ export function activate(context: vscode.ExtensionContext): void {
           args !== null &&
           (args as Record<string, unknown>)["forcePickFolder"] === true
             ? null
-            : (() => {
-                if (!sessions) return null;
-                const active = activeSessionId
-                  ? sessions.getById(activeSessionId)
-                  : null;
-                if (!active) return null;
-                return resolveWorkspaceFolderForSession(active);
-              })()) ??
+            : autoDetectedWorkspaceFolder()) ??
           (await pickWorkspaceFolder());
         if (!folder) return;
 
@@ -1375,28 +1559,7 @@ export function activate(context: vscode.ExtensionContext): void {
         }
 
         const thread = (picked as any).thread as Thread;
-        const session: Session = {
-          id: crypto.randomUUID(),
-          backendId,
-          backendKey: makeBackendInstanceKey(folder.uri.toString(), backendId),
-          workspaceFolderUri: folder.uri.toString(),
-          title: normalizeSessionTitle(thread.preview || "Resumed"),
-          threadId: thread.id,
-        };
-
-        sessions.add(session.backendKey, session);
-        saveSessions(extensionContext, sessions);
-        ensureRuntime(session.id);
-        sessionTree?.refresh();
-
-        // Don't override the recorded thread model on resume. Users can still
-        // change the model via the UI for subsequent turns.
-        const resumed = await backendManager.resumeSession(session);
-        void ensureModelsFetched(session);
-        hydrateRuntimeFromThread(session.id, resumed.thread);
-        setActiveSession(session.id);
-        refreshCustomPromptsFromDisk();
-        await showCodezViewContainer();
+        await resumeStoredThread(folder, backendId, thread);
         return;
       }
     }),
@@ -2094,120 +2257,1254 @@ export function activate(context: vscode.ExtensionContext): void {
 
   context.subscriptions.push(
     vscode.commands.registerCommand(
-      "codez.sessionMenu",
+      "codez.showCustomCommands",
       async (args?: unknown) => {
         if (!sessions) throw new Error("sessions is not initialized");
-        const session = parseSessionArg(args, sessions);
+
+        const session =
+          parseSessionArg(args, sessions) ??
+          (activeSessionId ? sessions.getById(activeSessionId) : null);
         if (!session) {
-          void vscode.window.showErrorMessage("Session not found.");
+          void vscode.window.showErrorMessage("No session selected.");
+          return;
+        }
+
+        const folder = resolveWorkspaceFolderForSession(session);
+        if (!folder) {
+          void vscode.window.showErrorMessage(
+            "WorkspaceFolder not found for session.",
+          );
+          return;
+        }
+
+        if (session.backendId !== "codez") {
+          void vscode.window.showInformationMessage(
+            "Custom commands は codez セッションでのみ利用できます。",
+          );
+          return;
+        }
+
+        const { commands, errors } = await listCustomCommandsFromDisk(
+          folder.uri.fsPath,
+        );
+        if (errors.length > 0) {
+          output.appendLine(`[commands] cwd=${folder.uri.fsPath}`);
+          for (const e of errors) output.appendLine(`[commands] ${e}`);
+        }
+
+        if (commands.length === 0) {
+          void vscode.window.showInformationMessage(
+            "No custom commands found. Add <git root>/.codex/commands/<name>.md or $CODEX_HOME/commands/<name>.md.",
+          );
           return;
         }
 
         const picked = await vscode.window.showQuickPick(
-          [
-            { label: "Rename", action: "rename" as const },
-            { label: "Copy Session ID", action: "copySessionId" as const },
-            { label: "Open in Editor Tab", action: "openPanel" as const },
-            { label: "Close Tab (Hide)", action: "hide" as const },
-          ],
-          { title: session.title },
+          commands.map((c) => ({
+            label: `/${c.name}`,
+            description: [c.argumentHint, c.description]
+              .filter(Boolean)
+              .join("  "),
+            detail: `${c.source} • ${c.path}`,
+            command: c,
+          })),
+          {
+            title: "Codex UI: Custom commands",
+            matchOnDescription: true,
+            matchOnDetail: true,
+          },
         );
         if (!picked) return;
+        // The backend expands `/<name> ARGS` when the message is sent.
+        chatView?.insertIntoInput(`/${picked.command.name} `);
+      },
+    ),
+  );
 
-        if (picked.action === "copySessionId") {
-          await vscode.commands.executeCommand("codez.copySessionId", {
-            sessionId: session.id,
-          });
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.showMcpPrompts",
+      async (args?: unknown) => {
+        if (!backendManager)
+          throw new Error("backendManager is not initialized");
+        if (!sessions) throw new Error("sessions is not initialized");
+
+        const session =
+          parseSessionArg(args, sessions) ??
+          (activeSessionId ? sessions.getById(activeSessionId) : null);
+        if (!session) {
+          void vscode.window.showErrorMessage("No session selected.");
+          return;
+        }
+        if (session.backendId !== "codez") {
+          void vscode.window.showInformationMessage(
+            "MCP prompts は codez セッションでのみ利用できます。",
+          );
           return;
         }
 
-        if (picked.action === "rename") {
-          await vscode.commands.executeCommand("codez.renameSession", {
-            sessionId: session.id,
-          });
+        let servers;
+        try {
+          const response = await backendManager.listMcpServerStatus(
+            session.backendKey,
+          );
+          servers = response.data;
+        } catch (err) {
+          output.appendLine(`[mcp] Failed to list MCP prompts: ${String(err)}`);
+          void vscode.window.showErrorMessage("Failed to list MCP prompts.");
           return;
         }
 
-        if (picked.action === "openPanel") {
-          await vscode.commands.executeCommand("codez.openSessionPanel", {
-            sessionId: session.id,
+        const items = servers.flatMap((server) =>
+          (server.prompts ?? []).map((prompt) => ({
+            label: `/mcp:${server.name}:${prompt.name}`,
+            description: prompt.title ?? undefined,
+            detail: prompt.description ?? undefined,
+            server: server.name,
+            prompt,
+          })),
+        );
+        if (items.length === 0) {
+          void vscode.window.showInformationMessage(
+            "No MCP prompts found. None of the configured MCP servers expose prompts.",
+          );
+          return;
+        }
+
+        const picked = await vscode.window.showQuickPick(items, {
+          title: "Codex UI: MCP prompts",
+          matchOnDescription: true,
+          matchOnDetail: true,
+        });
+        if (!picked) return;
+
+        // Arguments are sent as KEY="value"; the backend fetches and expands
+        // the prompt when the message is sent.
+        const args: string[] = [];
+        for (const arg of picked.prompt.arguments ?? []) {
+          const required = arg.required === true;
+          const value = await vscode.window.showInputBox({
+            title: `${picked.label}: ${arg.name}`,
+            prompt: arg.description ?? undefined,
+            placeHolder: required ? "(required)" : "(optional)",
+            validateInput: (v) =>
+              required && !v.trim() ? `${arg.name} is required.` : null,
           });
+          if (value === undefined) return;
+          if (!value && !required) continue;
+          const quoted = value.replace(/\\/g, "\\\\").replace(/"/g, '\\"');
+          args.push(`${arg.name}="${quoted}"`);
+        }
+
+        chatView?.insertIntoInput([picked.label, ...args].join(" ") + " ");
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("codez.subagents.refresh", () => {
+      subagentsTree?.refresh();
+    }),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.subagents.open",
+      async (node?: SubAgentTreeNode) => {
+        if (!node || node.kind !== "subagent") return;
+        const doc = await vscode.workspace.openTextDocument(
+          vscode.Uri.file(node.subagent.path),
+        );
+        await vscode.window.showTextDocument(doc, { preview: false });
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.subagents.invoke",
+      async (node?: SubAgentTreeNode) => {
+        if (!node || node.kind !== "subagent") return;
+        if (!sessions) throw new Error("sessions is not initialized");
+
+        const session = activeSessionId
+          ? sessions.getById(activeSessionId)
+          : null;
+        if (!session) {
+          void vscode.window.showErrorMessage("No session selected.");
+          return;
+        }
+        if (session.backendId !== "codez") {
+          void vscode.window.showInformationMessage(
+            "Agents は codez セッションでのみ利用できます。",
+          );
           return;
         }
 
-        await vscode.commands.executeCommand("codez.hideSessionTab", {
-          sessionId: session.id,
+        chatView?.reveal();
+        chatView?.insertIntoInput(`@${node.subagent.name} `);
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("codez.history.refresh", () => {
+      historyTree?.refresh();
+    }),
+    vscode.commands.registerCommand("codez.history.search", async () => {
+      if (!historyTree) throw new Error("historyTree is not initialized");
+      const query = await vscode.window.showInputBox({
+        title: "Codex UI: Search History",
+        prompt: "Words that must all appear in the thread (empty to clear)",
+        value: historyTree.searchQuery ?? "",
+      });
+      if (query === undefined) return;
+      historyTree.setSearchQuery(query);
+    }),
+    vscode.commands.registerCommand("codez.history.clearSearch", () => {
+      historyTree?.setSearchQuery(null);
+    }),
+    vscode.commands.registerCommand(
+      "codez.history.loadMore",
+      async (node?: HistoryMoreNode) => {
+        if (node?.kind !== "more") return;
+        await historyTree?.loadMore(node);
+      },
+    ),
+    vscode.commands.registerCommand(
+      "codez.history.resume",
+      async (node?: HistoryThreadNode) => {
+        // Only the History view's rows carry a thread.
+        if (node?.kind !== "thread") return;
+        try {
+          await resumeStoredThread(
+            node.folder,
+            HISTORY_BACKEND_ID,
+            node.thread,
+          );
+        } catch (err) {
+          void vscode.window.showErrorMessage(
+            `Failed to resume the thread: ${String(err)}`,
+          );
+        }
+      },
+    ),
+    vscode.commands.registerCommand(
+      "codez.history.fork",
+      async (node?: HistoryThreadNode) => {
+        if (node?.kind !== "thread") return;
+        if (!backendManager)
+          throw new Error("backendManager is not initialized");
+
+        try {
+          // onSessionAdded saves and selects the new session.
+          const { session, thread } = await backendManager.forkThread(
+            node.folder,
+            HISTORY_BACKEND_ID,
+            node.thread,
+          );
+          hydrateRuntimeFromThread(session.id, thread);
+          historyTree?.refresh();
+        } catch (err) {
+          void vscode.window.showErrorMessage(
+            `Failed to fork the thread: ${String(err)}`,
+          );
+        }
+      },
+    ),
+    vscode.commands.registerCommand(
+      "codez.history.export",
+      async (node?: HistoryThreadNode) => {
+        if (node?.kind !== "thread") return;
+        if (!backendManager)
+          throw new Error("backendManager is not initialized");
+
+        const picked = await vscode.window.showQuickPick(
+          [
+            { label: "Markdown", format: "markdown" as const, ext: "md" },
+            { label: "HTML", format: "html" as const, ext: "html" },
+            { label: "JSON", format: "json" as const, ext: "json" },
+          ],
+          { title: "Codex UI: Export Thread" },
+        );
+        if (!picked) return;
+        const target = await vscode.window.showSaveDialog({
+          defaultUri: vscode.Uri.joinPath(
+            node.folder.uri,
+            `codex-${node.thread.id}.${picked.ext}`,
+          ),
+          filters: { [picked.label]: [picked.ext] },
         });
+        if (!target) return;
+
+        try {
+          const contents = await backendManager.exportThread(
+            node.folder,
+            HISTORY_BACKEND_ID,
+            node.thread.id,
+            picked.format,
+          );
+          await vscode.workspace.fs.writeFile(
+            target,
+            Buffer.from(contents, "utf8"),
+          );
+          void vscode.window.showInformationMessage(
+            `Exported the thread to ${vscode.workspace.asRelativePath(target, false)}.`,
+          );
+        } catch (err) {
+          void vscode.window.showErrorMessage(
+            `Failed to export the thread: ${String(err)}`,
+          );
+        }
       },
     ),
+    vscode.commands.registerCommand(
+      "codez.history.delete",
+      async (node?: HistoryThreadNode) => {
+        if (node?.kind !== "thread") return;
+        if (!backendManager)
+          throw new Error("backendManager is not initialized");
+        if (!sessions) throw new Error("sessions is not initialized");
+
+        const title =
+          node.thread.name ?? formatThreadLabel(node.thread.preview);
+        const confirm = await vscode.window.showWarningMessage(
+          `Delete "${title}"? Its rollout, name, and tags are removed permanently.`,
+          { modal: true },
+          "Delete",
+        );
+        if (confirm !== "Delete") return;
+
+        try {
+          await backendManager.deleteThread(
+            node.folder,
+            HISTORY_BACKEND_ID,
+            node.thread.id,
+          );
+        } catch (err) {
+          void vscode.window.showErrorMessage(
+            `Failed to delete the thread: ${String(err)}`,
+          );
+          return;
+        }
+        const backendKey = makeBackendInstanceKey(
+          node.folder.uri.toString(),
+          HISTORY_BACKEND_ID,
+        );
+        const open = sessions.getByThreadId(backendKey, node.thread.id);
+        if (open) {
+          await vscode.commands.executeCommand("codez.closeSession", {
+            sessionId: open.id,
+          });
+        }
+        historyTree?.refresh();
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.subagents.create",
+      async (node?: SubAgentTreeNode) => {
+        if (!subagentsTree) throw new Error("subagentsTree is not initialized");
+
+        let root = node?.kind === "root" ? node.root : null;
+        if (!root) {
+          const folder =
+            node && node.kind !== "error"
+              ? node.folder
+              : await vscode.window.showWorkspaceFolderPick();
+          if (!folder) return;
+          const roots = await subagentsTree.rootsForFolder(folder);
+          if (roots.length === 0) {
+            void vscode.window.showErrorMessage(
+              "Subagent roots are unavailable (is the codez backend running?).",
+            );
+            return;
+          }
+          const picked = await vscode.window.showQuickPick(
+            roots.map((r) => ({
+              label: subagentRootLabel(r),
+              description: r.path,
+              root: r,
+            })),
+            { title: "Codex UI: New Subagent Location" },
+          );
+          if (!picked) return;
+          root = picked.root;
+        }
+
+        const name = await vscode.window.showInputBox({
+          title: "Codex UI: New Subagent",
+          prompt: "Subagent name (file name without .md)",
+          placeHolder: "e.g. reviewer",
+          validateInput: (value) =>
+            /^[A-Za-z0-9_-]{1,64}$/.test(value.trim())
+              ? undefined
+              : "Use 1-64 characters from [A-Za-z0-9_-].",
+        });
+        if (!name) return;
+
+        const dirUri = vscode.Uri.file(root.path);
+        const fileUri = vscode.Uri.joinPath(dirUri, `${name.trim()}.md`);
+        try {
+          await vscode.workspace.fs.stat(fileUri);
+          void vscode.window.showErrorMessage(
+            `Subagent already exists: ${fileUri.fsPath}`,
+          );
+          return;
+        } catch {
+          // Does not exist yet.
+        }
+
+        const scaffold = [
+          "---",
+          "description: Describe when to use this subagent",
+          "# sandbox_mode: read-only",
+          "# model: gpt-5.1",
+          "---",
+          "",
+          "You are a focused subagent. Describe its instructions here.",
+          "",
+        ].join("\n");
+        await vscode.workspace.fs.createDirectory(dirUri);
+        await vscode.workspace.fs.writeFile(
+          fileUri,
+          Buffer.from(scaffold, "utf8"),
+        );
+        subagentsTree.refresh();
+        const doc = await vscode.workspace.openTextDocument(fileUri);
+        await vscode.window.showTextDocument(doc, { preview: false });
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.sessionMenu",
+      async (args?: unknown) => {
+        if (!sessions) throw new Error("sessions is not initialized");
+        const session = parseSessionArg(args, sessions);
+        if (!session) {
+          void vscode.window.showErrorMessage("Session not found.");
+          return;
+        }
+
+        const picked = await vscode.window.showQuickPick(
+          [
+            { label: "Rename", action: "rename" as const },
+            { label: "Copy Session ID", action: "copySessionId" as const },
+            { label: "Open in Editor Tab", action: "openPanel" as const },
+            { label: "Close Tab (Hide)", action: "hide" as const },
+          ],
+          { title: session.title },
+        );
+        if (!picked) return;
+
+        if (picked.action === "copySessionId") {
+          await vscode.commands.executeCommand("codez.copySessionId", {
+            sessionId: session.id,
+          });
+          return;
+        }
+
+        if (picked.action === "rename") {
+          await vscode.commands.executeCommand("codez.renameSession", {
+            sessionId: session.id,
+          });
+          return;
+        }
+
+        if (picked.action === "openPanel") {
+          await vscode.commands.executeCommand("codez.openSessionPanel", {
+            sessionId: session.id,
+          });
+          return;
+        }
+
+        await vscode.commands.executeCommand("codez.hideSessionTab", {
+          sessionId: session.id,
+        });
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.hideSessionTab",
+      async (args?: unknown) => {
+        if (!sessions) throw new Error("sessions is not initialized");
+        const session = parseSessionArg(args, sessions);
+        if (!session) {
+          void vscode.window.showErrorMessage("Session not found.");
+          return;
+        }
+
+        hiddenTabSessionIds.add(session.id);
+        saveHiddenTabSessions(context);
+
+        if (activeSessionId === session.id) {
+          const visible = sessions
+            .listAll()
+            .filter((s) => !hiddenTabSessionIds.has(s.id));
+          const next =
+            visible.find((s) => s.backendKey === session.backendKey) ??
+            visible[0] ??
+            null;
+          if (next) setActiveSession(next.id);
+          else activeSessionId = null;
+        }
+
+        chatView?.refresh();
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.closeSession",
+      async (args?: unknown) => {
+        if (!sessions) throw new Error("sessions is not initialized");
+        if (!extensionContext) throw new Error("extensionContext is not set");
+
+        const session = parseSessionArg(args, sessions);
+        if (!session) {
+          void vscode.window.showErrorMessage("Session not found.");
+          return;
+        }
+
+        sessions.remove(session.id);
+        saveSessions(extensionContext, sessions);
+
+        runtimeBySessionId.delete(session.id);
+        hiddenTabSessionIds.delete(session.id);
+        unreadSessionIds.delete(session.id);
+        saveHiddenTabSessions(extensionContext);
+
+        if (activeSessionId === session.id) {
+          const visible = sessions.listAll();
+          const next =
+            visible.find((s) => s.backendKey === session.backendKey) ??
+            visible[0] ??
+            null;
+          if (next) setActiveSession(next.id);
+          else activeSessionId = null;
+        }
+
+        sessionTree?.refresh();
+        chatView?.refresh();
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.restoreFile",
+      async (resource?: { resourceUri?: vscode.Uri } | vscode.Uri) => {
+        if (!backendManager)
+          throw new Error("backendManager is not initialized");
+        if (!sessions) throw new Error("sessions is not initialized");
+
+        // SCM menus pass a SourceControlResourceState; editor menus pass a Uri.
+        const uri =
+          resource instanceof vscode.Uri
+            ? resource
+            : (resource?.resourceUri ??
+              vscode.window.activeTextEditor?.document.uri);
+        if (!uri || uri.scheme !== "file") {
+          void vscode.window.showErrorMessage("No file selected to restore.");
+          return;
+        }
+
+        const session = activeSessionId
+          ? sessions.getById(activeSessionId)
+          : null;
+        const folder = vscode.workspace.getWorkspaceFolder(uri);
+        if (
+          !session ||
+          !folder ||
+          folder.uri.toString() !== session.workspaceFolderUri
+        ) {
+          void vscode.window.showErrorMessage(
+            "Select a session in this file's workspace folder first.",
+          );
+          return;
+        }
+
+        const relativePath = vscode.workspace.asRelativePath(uri, false);
+        const confirm = await vscode.window.showWarningMessage(
+          `Revert Codex's changes to ${relativePath} from the latest turn?`,
+          { modal: true },
+          "Restore",
+        );
+        if (confirm !== "Restore") return;
+
+        try {
+          await backendManager.threadRestoreFile(session, uri.fsPath);
+        } catch (err) {
+          void vscode.window.showErrorMessage(
+            `Failed to restore ${relativePath}: ${String(err)}`,
+          );
+        }
+      },
+    ),
+  );
+
+  editorContext = new EditorContextStore();
+  context.subscriptions.push(
+    vscode.languages.registerCodeActionsProvider(
+      { scheme: "file" },
+      new FixWithCodexProvider(),
+      {
+        providedCodeActionKinds: FixWithCodexProvider.providedCodeActionKinds,
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.fixDiagnostic",
+      async (uri?: vscode.Uri, diagnostic?: vscode.Diagnostic) => {
+        if (!editorContext) throw new Error("editorContext is not initialized");
+        if (!(uri instanceof vscode.Uri) || !diagnostic) {
+          void vscode.window.showInformationMessage(
+            "Use the \"Fix with Codex\" quick fix on a problem.",
+          );
+          return;
+        }
+        const folder = vscode.workspace.getWorkspaceFolder(uri);
+        if (!folder) {
+          void vscode.window.showErrorMessage(
+            "This file is not in a workspace folder.",
+          );
+          return;
+        }
+        const document = await vscode.workspace.openTextDocument(uri);
+        const session = await sessionForFolder(folder);
+        if (!session) return;
+
+        const relPath = path
+          .relative(folder.uri.fsPath, uri.fsPath)
+          .split(path.sep)
+          .join("/");
+        let prompt = buildFixPrompt(relPath, diagnostic);
+        const { startLine, endLine } = contextLines(document, diagnostic);
+        if (session.backendId === "codez") {
+          editorContext.add(session.id, [
+            linesAttachment(document, startLine, endLine),
+          ]);
+        } else {
+          // Other backends take no attachments; point at the lines instead.
+          prompt += `\n\nContext: @${relPath}#L${startLine + 1}-L${endLine + 1}`;
+        }
+        fixReviewUriBySessionId.set(session.id, uri.toString());
+        await sendUserText(session, prompt);
+      },
+    ),
+  );
+  terminalCapture = new TerminalCapture();
+  context.subscriptions.push(terminalCapture);
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("codez.openSettings", async () => {
+      if (!backendManager) throw new Error("backendManager is not initialized");
+      const folder = await pickWorkspaceFolder();
+      if (!folder) return;
+      const bm = backendManager;
+      ConfigPanel.show(folder, {
+        read: (f) => bm.readConfig(f, CONFIG_BACKEND_ID),
+        write: (f, edits, expectedVersion) =>
+          bm.writeConfig(f, CONFIG_BACKEND_ID, edits, expectedVersion),
+      });
+    }),
+  );
+
+  stagedReview = new StagedReviewComments();
+  context.subscriptions.push(stagedReview);
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.scm.generateCommitMessage",
+      async (sourceControl?: unknown) => {
+        const repo = await resolveRepository(sourceControl);
+        if (!repo) {
+          void vscode.window.showErrorMessage("No Git repository found.");
+          return;
+        }
+        const command = codezCommand(repo.rootUri);
+        try {
+          const message = await vscode.window.withProgress(
+            {
+              location: vscode.ProgressLocation.SourceControl,
+              title: "Generating commit message…",
+            },
+            () => suggestCommitMessage(command, repo.rootUri),
+          );
+          setCommitMessage(repo, message);
+        } catch (err) {
+          void vscode.window.showErrorMessage(String(err));
+        }
+      },
+    ),
+  );
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.scm.reviewStaged",
+      async (sourceControl?: unknown) => {
+        if (!stagedReview) throw new Error("stagedReview is not initialized");
+        const repo = await resolveRepository(sourceControl);
+        if (!repo) {
+          void vscode.window.showErrorMessage("No Git repository found.");
+          return;
+        }
+        const command = codezCommand(repo.rootUri);
+        try {
+          const verdict = await vscode.window.withProgress(
+            {
+              location: vscode.ProgressLocation.SourceControl,
+              title: "Reviewing staged changes…",
+            },
+            () => reviewStagedChanges(command, repo.rootUri),
+          );
+          stagedReview.show(repo.rootUri, verdict);
+          const count = verdict.findings.length;
+          if (count === 0) {
+            void vscode.window.showInformationMessage(
+              "Codex review: no findings in the staged changes.",
+            );
+          } else {
+            const summary = `Codex review: ${count} finding${count === 1 ? "" : "s"}${verdict.block ? " (blocking)" : ""}.`;
+            if (verdict.block) void vscode.window.showWarningMessage(summary);
+            else void vscode.window.showInformationMessage(summary);
+          }
+        } catch (err) {
+          void vscode.window.showErrorMessage(String(err));
+        }
+      },
+    ),
+  );
+  context.subscriptions.push(
+    vscode.commands.registerCommand("codez.scm.clearReview", () => {
+      stagedReview?.clear();
+    }),
+  );
+
+  modelStatus = new ModelStatusBar(() => modelStatusForActiveSession());
+  context.subscriptions.push(modelStatus);
+  if (chatView) chatView.onRefresh = () => modelStatus?.scheduleUpdate();
+  context.subscriptions.push(
+    vscode.commands.registerCommand("codez.switchModel", async () => {
+      if (!backendManager) throw new Error("backendManager is not initialized");
+      if (!sessions) throw new Error("sessions is not initialized");
+      const session = activeSessionId
+        ? sessions.getById(activeSessionId)
+        : null;
+      if (!session) {
+        void vscode.window.showErrorMessage("No session selected.");
+        return;
+      }
+      await ensureModelsFetched(session);
+      const rt = ensureRuntime(session.id);
+      const state = getSessionModelState(session.id);
+      const choice = await pickModelAndEffort(
+        getModelOptionsForSession(session) ?? [],
+        { model: state.model, effort: state.reasoning },
+        rt.modelProblem
+          ? { model: rt.modelProblem.model, text: rt.modelProblem.message }
+          : null,
+      );
+      if (!choice) return;
+      setSessionModelState(session.id, {
+        model: choice.model,
+        provider: choice.model === state.model ? state.provider : null,
+        reasoning: choice.effort,
+      });
+      if (rt.modelProblem && rt.modelProblem.model !== choice.model) {
+        rt.modelProblem = null;
+      }
+      chatView?.refresh();
+
+      // turn/start carries the selection too; codez also applies it to the
+      // thread right away so compaction and review use the new model.
+      if (session.backendId !== "codez") return;
+      if (choice.model === null && choice.effort === null) return;
+      try {
+        await backendManager.updateThreadSettings(
+          session,
+          choice.model,
+          choice.effort,
+        );
+      } catch (err) {
+        void vscode.window.showErrorMessage(
+          `Failed to switch model: ${String((err as Error)?.message ?? err)}`,
+        );
+      }
+    }),
+  );
+
+  const nextEdit = new NextEditProvider(
+    (document) => nextEditSessionFor(document),
+    (session, request) => {
+      if (!backendManager) throw new Error("backendManager is not initialized");
+      return backendManager.suggestNextEdit(session, request);
+    },
+    output,
+  );
+  context.subscriptions.push(
+    vscode.languages.registerInlineCompletionItemProvider(
+      { scheme: "file" },
+      nextEdit,
+    ),
+    vscode.commands.registerCommand("codez.nextEdit.toggle", async () => {
+      const enabled = !nextEditEnabled();
+      await vscode.workspace
+        .getConfiguration("codez")
+        .update("nextEdit.enabled", enabled, vscode.ConfigurationTarget.Global);
+      void vscode.window.showInformationMessage(
+        `Codex next edit suggestions ${enabled ? "enabled" : "disabled"}.`,
+      );
+    }),
+  );
+
+  const askCodexLenses = new AskCodexLensProvider();
+  context.subscriptions.push(
+    askCodexLenses,
+    vscode.languages.registerCodeLensProvider({ scheme: "file" }, askCodexLenses),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.askAboutThis",
+      async (args?: unknown) => {
+        if (!backendManager)
+          throw new Error("backendManager is not initialized");
+        if (!sessions) throw new Error("sessions is not initialized");
+
+        // CodeLenses pass their target; the editor menu passes the file's Uri.
+        let target: AskTarget | null = null;
+        if (args && typeof args === "object" && "startLine" in args) {
+          target = args as AskTarget;
+        } else {
+          const editor = vscode.window.activeTextEditor;
+          if (editor && editor.document.uri.scheme === "file") {
+            target = await targetFromEditor(editor);
+          }
+        }
+        if (!target) {
+          void vscode.window.showErrorMessage("No code selected to ask about.");
+          return;
+        }
+        const folder = vscode.workspace.getWorkspaceFolder(target.uri);
+        if (!folder) {
+          void vscode.window.showErrorMessage(
+            "This file is not in a workspace folder.",
+          );
+          return;
+        }
+
+        const question = await vscode.window.showInputBox({
+          title: "Ask Codex about this",
+          prompt: target.symbol
+            ? `Question about ${target.symbol}`
+            : "Question about the selected lines",
+          value: defaultQuestion(target),
+        });
+        if (!question?.trim()) return;
+
+        const session = await sessionForFolder(folder);
+        if (!session) return;
+
+        const relPath = path
+          .relative(folder.uri.fsPath, target.uri.fsPath)
+          .split(path.sep)
+          .join("/");
+        await sendUserText(session, buildAskPrompt(target, relPath, question));
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.context.attachSelection",
+      async () => {
+        const editor = vscode.window.activeTextEditor;
+        const item = editor ? selectionAttachment(editor) : null;
+        if (!item) {
+          void vscode.window.showErrorMessage("Select some text to attach.");
+          return;
+        }
+        await attachEditorContext([item]);
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.context.attachFile",
+      async (resource?: vscode.Uri) => {
+        // Explorer and editor title menus pass the file's Uri.
+        const document =
+          resource instanceof vscode.Uri
+            ? await vscode.workspace.openTextDocument(resource)
+            : vscode.window.activeTextEditor?.document;
+        if (!document) {
+          void vscode.window.showErrorMessage("No file to attach.");
+          return;
+        }
+        await attachEditorContext([fileAttachment(document)]);
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.context.attachOpenEditors",
+      async () => {
+        const items = await openEditorAttachments();
+        if (items.length === 0) {
+          void vscode.window.showErrorMessage("No open editors to attach.");
+          return;
+        }
+        await attachEditorContext(items);
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.context.remove",
+      async (args?: { sessionId?: unknown; id?: unknown }) => {
+        if (!editorContext) throw new Error("editorContext is not initialized");
+        const sessionId = args?.sessionId ?? activeSessionId;
+        if (typeof sessionId !== "string") return;
+        if (typeof args?.id === "string") {
+          editorContext.remove(sessionId, args.id);
+        } else {
+          editorContext.take(sessionId);
+        }
+        chatView?.refresh();
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.context.attachTerminalOutput",
+      async (arg?: unknown) => {
+        if (!terminalCapture)
+          throw new Error("terminalCapture is not initialized");
+        // The terminal context menu passes the terminal.
+        const terminal =
+          arg &&
+          typeof arg === "object" &&
+          vscode.window.terminals.includes(arg as vscode.Terminal)
+            ? (arg as vscode.Terminal)
+            : vscode.window.activeTerminal;
+        if (!terminal) {
+          void vscode.window.showErrorMessage("No terminal is open.");
+          return;
+        }
+
+        const lineCount = vscode.workspace
+          .getConfiguration("codez")
+          .get<number>("terminalOutput.lines", 200);
+        const last = terminalCapture.lastCommand(terminal);
+        let source: "command" | "lines" = "lines";
+        if (last && last.output) {
+          const status = last.running
+            ? "running"
+            : last.exitCode === null
+              ? "finished"
+              : `exit ${last.exitCode}`;
+          const picked = await vscode.window.showQuickPick(
+            [
+              {
+                label: "Last command output",
+                description: `${last.commandLine ?? "(unknown command)"} (${status})`,
+                source: "command" as const,
+              },
+              {
+                label: `Last ${lineCount} lines`,
+                description: terminal.name,
+                source: "lines" as const,
+              },
+            ],
+            { title: "Send terminal output to Codex" },
+          );
+          if (!picked) return;
+          source = picked.source;
+        }
+
+        const item =
+          source === "command" && last
+            ? terminalAttachment(
+                terminal.name,
+                last.commandLine,
+                last.exitCode,
+                last.output,
+              )
+            : terminalAttachment(
+                terminal.name,
+                null,
+                null,
+                await terminalCapture.lastLines(terminal, lineCount),
+              );
+        if (item.bytes === 0) {
+          void vscode.window.showInformationMessage(
+            "The terminal has no output to send.",
+          );
+          return;
+        }
+        await attachEditorContext([item]);
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.pinContext",
+      async (resource?: vscode.Uri) => {
+        if (!backendManager)
+          throw new Error("backendManager is not initialized");
+        if (!sessions) throw new Error("sessions is not initialized");
+
+        const session = activeSessionId
+          ? sessions.getById(activeSessionId)
+          : null;
+        if (!session) {
+          void vscode.window.showErrorMessage("Select a session first.");
+          return;
+        }
+
+        let content: PinnedContent | null = null;
+        if (resource instanceof vscode.Uri) {
+          content = { type: "file", path: resource.fsPath };
+        } else {
+          const editor = vscode.window.activeTextEditor;
+          const selection =
+            editor && !editor.selection.isEmpty
+              ? editor.document.getText(editor.selection)
+              : "";
+          const picks: Array<vscode.QuickPickItem & { pinKind: string }> = [
+            { label: "Note…", description: "type a note", pinKind: "note" },
+          ];
+          if (editor?.document.uri.scheme === "file") {
+            picks.push({
+              label: "Active file",
+              description: vscode.workspace.asRelativePath(
+                editor.document.uri,
+                false,
+              ),
+              pinKind: "file",
+            });
+          }
+          if (selection.trim()) {
+            picks.push({
+              label: "Selection",
+              description: `${selection.length} characters`,
+              pinKind: "selection",
+            });
+          }
+          const picked = await vscode.window.showQuickPick(picks, {
+            title: "Pin to Codex context",
+            placeHolder: "Pinned items are sent with every request",
+          });
+          if (!picked) return;
+          if (picked.pinKind === "file" && editor) {
+            content = { type: "file", path: editor.document.uri.fsPath };
+          } else if (picked.pinKind === "selection") {
+            content = { type: "message", text: selection };
+          } else {
+            const text = await vscode.window.showInputBox({
+              title: "Pin a note",
+              prompt: "Kept in the context for the rest of the session",
+            });
+            if (!text?.trim()) return;
+            content = { type: "note", text };
+          }
+        }
+
+        try {
+          await backendManager.threadPinAdd(session, content);
+        } catch (err) {
+          void vscode.window.showErrorMessage(
+            `Failed to pin context: ${String(err)}`,
+          );
+        }
+      },
+    ),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("codez.unpinContext", async () => {
+      if (!backendManager) throw new Error("backendManager is not initialized");
+      if (!sessions) throw new Error("sessions is not initialized");
+
+      const session = activeSessionId ? sessions.getById(activeSessionId) : null;
+      if (!session) {
+        void vscode.window.showErrorMessage("Select a session first.");
+        return;
+      }
+
+      let items: PinnedContextItem[];
+      try {
+        items = await backendManager.threadPinList(session);
+      } catch (err) {
+        void vscode.window.showErrorMessage(
+          `Failed to list pinned context: ${String(err)}`,
+        );
+        return;
+      }
+      if (items.length === 0) {
+        void vscode.window.showInformationMessage("Nothing is pinned.");
+        return;
+      }
+
+      const picked = await vscode.window.showQuickPick(
+        items.map((item) => ({
+          label:
+            item.content.type === "file"
+              ? vscode.workspace.asRelativePath(item.content.path, false)
+              : (item.content.text.split("\n")[0] ?? "").slice(0, 60),
+          description: item.content.type,
+          id: item.id,
+        })),
+        { title: "Unpin from Codex context" },
+      );
+      if (!picked) return;
+
+      try {
+        await backendManager.threadPinRemove(session, picked.id);
+      } catch (err) {
+        void vscode.window.showErrorMessage(
+          `Failed to unpin context: ${String(err)}`,
+        );
+      }
+    }),
+  );
+
+  context.subscriptions.push(
+    vscode.commands.registerCommand("codez.addMcpServer", async () => {
+      if (!backendManager) throw new Error("backendManager is not initialized");
+      if (!sessions) throw new Error("sessions is not initialized");
+
+      const session = activeSessionId ? sessions.getById(activeSessionId) : null;
+      if (!session) {
+        void vscode.window.showErrorMessage("Select a session first.");
+        return;
+      }
+
+      const name = await vscode.window.showInputBox({
+        title: "Add MCP server (1/2)",
+        prompt: "Server name; tools are exposed as mcp__<name>__<tool>",
+        validateInput: (v) =>
+          /^[a-zA-Z0-9_-]+$/.test(v.trim())
+            ? null
+            : "Use letters, digits, '-' or '_'.",
+      });
+      if (!name) return;
+      const target = await vscode.window.showInputBox({
+        title: "Add MCP server (2/2)",
+        prompt: "Command with arguments, or a streamable HTTP URL",
+        placeHolder: "npx -y @acme/docs-mcp",
+      });
+      if (!target?.trim()) return;
+
+      // Only for this session; config.toml is left untouched.
+      const trimmed = target.trim();
+      const [command, ...args] = trimmed.split(/\s+/);
+      const config = /^https?:\/\//.test(trimmed)
+        ? { url: trimmed }
+        : { command, args };
+      try {
+        await backendManager.threadMcpServerAdd(session, name.trim(), config);
+      } catch (err) {
+        void vscode.window.showErrorMessage(
+          `Failed to add MCP server: ${String(err)}`,
+        );
+      }
+    }),
   );
 
   context.subscriptions.push(
-    vscode.commands.registerCommand(
-      "codez.hideSessionTab",
-      async (args?: unknown) => {
-        if (!sessions) throw new Error("sessions is not initialized");
-        const session = parseSessionArg(args, sessions);
-        if (!session) {
-          void vscode.window.showErrorMessage("Session not found.");
-          return;
-        }
-
-        hiddenTabSessionIds.add(session.id);
-        saveHiddenTabSessions(context);
+    vscode.commands.registerCommand("codez.removeMcpServer", async () => {
+      if (!backendManager) throw new Error("backendManager is not initialized");
+      if (!sessions) throw new Error("sessions is not initialized");
 
-        if (activeSessionId === session.id) {
-          const visible = sessions
-            .listAll()
-            .filter((s) => !hiddenTabSessionIds.has(s.id));
-          const next =
-            visible.find((s) => s.backendKey === session.backendKey) ??
-            visible[0] ??
-            null;
-          if (next) setActiveSession(next.id);
-          else activeSessionId = null;
-        }
+      const session = activeSessionId ? sessions.getById(activeSessionId) : null;
+      if (!session) {
+        void vscode.window.showErrorMessage("Select a session first.");
+        return;
+      }
 
-        chatView?.refresh();
-      },
-    ),
+      const name = await vscode.window.showInputBox({
+        title: "Remove MCP server",
+        prompt: "Stops the server for this session only",
+      });
+      if (!name?.trim()) return;
+      try {
+        await backendManager.threadMcpServerRemove(session, name.trim());
+      } catch (err) {
+        void vscode.window.showErrorMessage(
+          `Failed to remove MCP server: ${String(err)}`,
+        );
+      }
+    }),
   );
 
   context.subscriptions.push(
-    vscode.commands.registerCommand(
-      "codez.closeSession",
-      async (args?: unknown) => {
-        if (!sessions) throw new Error("sessions is not initialized");
-        if (!extensionContext) throw new Error("extensionContext is not set");
-
-        const session = parseSessionArg(args, sessions);
-        if (!session) {
-          void vscode.window.showErrorMessage("Session not found.");
-          return;
-        }
+    vscode.commands.registerCommand("codez.sendTerminalInput", async () => {
+      if (!backendManager) throw new Error("backendManager is not initialized");
+      if (!sessions) throw new Error("sessions is not initialized");
 
-        sessions.remove(session.id);
-        saveSessions(extensionContext, sessions);
+      const session = activeSessionId ? sessions.getById(activeSessionId) : null;
+      if (!session) {
+        void vscode.window.showErrorMessage("Select a session first.");
+        return;
+      }
 
-        runtimeBySessionId.delete(session.id);
-        hiddenTabSessionIds.delete(session.id);
-        unreadSessionIds.delete(session.id);
-        saveHiddenTabSessions(extensionContext);
+      const terminals = [
+        ...(runningTerminalsBySessionId.get(session.id) ??
+          new Map<string, string>()),
+      ];
+      if (terminals.length === 0) {
+        void vscode.window.showInformationMessage(
+          "No command is running in a terminal.",
+        );
+        return;
+      }
+      let processId = terminals[terminals.length - 1]![0];
+      if (terminals.length > 1) {
+        const picked = await vscode.window.showQuickPick(
+          terminals.map(([id, command]) => ({
+            label: command,
+            description: `process ${id}`,
+            id,
+          })),
+          { title: "Send input to which command?" },
+        );
+        if (!picked) return;
+        processId = picked.id;
+      }
 
-        if (activeSessionId === session.id) {
-          const visible = sessions.listAll();
-          const next =
-            visible.find((s) => s.backendKey === session.backendKey) ??
-            visible[0] ??
-            null;
-          if (next) setActiveSession(next.id);
-          else activeSessionId = null;
-        }
+      const text = await vscode.window.showInputBox({
+        title: "Send input to the running command",
+        prompt: "Sent followed by Enter. Input is not shown in the chat.",
+        password: true,
+      });
+      if (text === undefined) return;
 
-        sessionTree?.refresh();
-        chatView?.refresh();
-      },
-    ),
+      try {
+        await backendManager.threadTerminalWrite(
+          session,
+          processId,
+          `${text}\n`,
+        );
+      } catch (err) {
+        void vscode.window.showErrorMessage(
+          `Failed to send terminal input: ${String(err)}`,
+        );
+      }
+    }),
   );
 
   context.subscriptions.push(
@@ -2344,6 +3641,47 @@ export function activate(context: vscode.ExtensionContext): void {
     ),
   );
 
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "codez.reviewChanges",
+      async (args?: unknown) => {
+        if (!sessions) throw new Error("sessions is not initialized");
+        if (!diffReview) throw new Error("diffReview is not initialized");
+
+        const session =
+          parseSessionArg(args, sessions) ??
+          (activeSessionId ? sessions.getById(activeSessionId) : null);
+        if (!session) {
+          void vscode.window.showErrorMessage("Session not found.");
+          return;
+        }
+
+        const files = diffReview.pendingFiles(session.id);
+        if (files.length === 0) {
+          void vscode.window.showInformationMessage(
+            "No changes to review in the latest turn.",
+          );
+          return;
+        }
+        let picked = files[0];
+        if (files.length > 1) {
+          const item = await vscode.window.showQuickPick(
+            files.map((file) => ({
+              label: file.relPath,
+              description:
+                file.hunks === 1 ? "1 change" : `${file.hunks} changes`,
+              file,
+            })),
+            { title: "Review Codex changes" },
+          );
+          if (!item) return;
+          picked = item.file;
+        }
+        await diffReview.open(session.id, picked.uri);
+      },
+    ),
+  );
+
   context.subscriptions.push(
     vscode.commands.registerCommand(
       "codez.selectSession",
@@ -2569,7 +3907,14 @@ export function deactivate(): void {
   backendManager = null;
   sessions = null;
   sessionTree = null;
+  historyTree = null;
   diffProvider = null;
+  diffReview = null;
+  editorContext = null;
+  terminalCapture = null;
+  stagedReview = null;
+  modelStatus = null;
+  fixReviewUriBySessionId.clear();
   chatView = null;
   sessionPanels = null;
   outputChannel = null;
@@ -2587,17 +3932,57 @@ async function pickWorkspaceFolder(): Promise<vscode.WorkspaceFolder | null> {
   }
   if (folders.length === 1) return folders[0] ?? null;
 
+  // The active editor's folder comes first, so Enter picks it.
+  const fromEditor = activeEditorWorkspaceFolder();
+  const ordered = fromEditor
+    ? [fromEditor, ...folders.filter((f) => f.index !== fromEditor.index)]
+    : folders;
   const picked = await vscode.window.showQuickPick(
-    folders.map((f) => ({
-      label: f.name,
-      description: f.uri.fsPath,
-      folder: f,
-    })),
+    ordered.map((f) => {
+      const profile = profileForFolder(f);
+      const tags = [
+        f.index === fromEditor?.index ? "active editor" : null,
+        profile ? `profile: ${profile}` : null,
+      ].filter(Boolean);
+      return {
+        label: f.name,
+        description: f.uri.fsPath,
+        detail: tags.length > 0 ? tags.join(" • ") : undefined,
+        folder: f,
+      };
+    }),
     { title: "Codex UI: Select a workspace folder" },
   );
   return picked?.folder ?? null;
 }
 
+// The folder a new session binds to without asking: the only folder, or the
+// active editor's folder unless `codez.newSession.workspaceFolder` is "ask".
+function autoDetectedWorkspaceFolder(): vscode.WorkspaceFolder | null {
+  const folders = vscode.workspace.workspaceFolders ?? [];
+  if (folders.length === 1) return folders[0] ?? null;
+  const mode = vscode.workspace
+    .getConfiguration("codez")
+    .get<string>("newSession.workspaceFolder", "activeEditor");
+  return mode === "activeEditor" ? activeEditorWorkspaceFolder() : null;
+}
+
+// The active session, when it belongs to the document's workspace folder.
+function nextEditSessionFor(document: vscode.TextDocument): Session | null {
+  const session = activeSessionId ? sessions?.getById(activeSessionId) : null;
+  if (!session) return null;
+  const folder = vscode.workspace.getWorkspaceFolder(document.uri);
+  if (folder && folder.uri.toString() !== session.workspaceFolderUri) {
+    return null;
+  }
+  return session;
+}
+
+function activeEditorWorkspaceFolder(): vscode.WorkspaceFolder | null {
+  const uri = vscode.window.activeTextEditor?.document.uri;
+  return uri ? (vscode.workspace.getWorkspaceFolder(uri) ?? null) : null;
+}
+
 function parseSessionArg(args: unknown, store: SessionStore): Session | null {
   if (typeof args !== "object" || args === null) return null;
 
@@ -2788,6 +4173,71 @@ function expandCustomPromptIfAny(
   return { kind: "expanded", text: expanded };
 }
 
+// The active session when it is in `folder`; otherwise a new session there,
+// on a backend the user picks. Selects the session and shows the chat.
+async function sessionForFolder(
+  folder: vscode.WorkspaceFolder,
+): Promise<Session | null> {
+  if (!backendManager) throw new Error("backendManager is not initialized");
+  if (!sessions) throw new Error("sessions is not initialized");
+  let session = activeSessionId ? sessions.getById(activeSessionId) : null;
+  if (!session || session.workspaceFolderUri !== folder.uri.toString()) {
+    const backendId = await pickBackendIdForNewSession(folder);
+    if (!backendId) return null;
+    session = await backendManager.newSession(
+      folder,
+      backendId,
+      getSessionModelState(null),
+    );
+  }
+  setActiveSession(session.id);
+  await showCodezViewContainer();
+  return session;
+}
+
+// Queues editor content for the active session's next prompt, after a
+// confirmation when the pending attachments get large.
+async function attachEditorContext(
+  items: EditorContextAttachment[],
+): Promise<void> {
+  if (!sessions) throw new Error("sessions is not initialized");
+  if (!editorContext) throw new Error("editorContext is not initialized");
+  const session = activeSessionId ? sessions.getById(activeSessionId) : null;
+  if (!session) {
+    void vscode.window.showErrorMessage("No session selected.");
+    return;
+  }
+  if (session.backendId !== "codez") {
+    void vscode.window.showInformationMessage(
+      "Editor context can be attached in codez sessions only.",
+    );
+    return;
+  }
+
+  const warnBytes = warnBytesFor(vscode.Uri.parse(session.workspaceFolderUri));
+  const total =
+    editorContext.totalBytes(session.id) +
+    items.reduce((sum, item) => sum + item.bytes, 0);
+  if (warnBytes > 0 && total > warnBytes) {
+    const truncated = items.some(
+      (item) => item.bytes > BACKEND_ITEM_LIMIT_BYTES,
+    );
+    const choice = await vscode.window.showWarningMessage(
+      `The attached context would be ${formatBytes(total)}, over ${formatBytes(warnBytes)}. ` +
+        "Large attachments use up the model's context window." +
+        (truncated
+          ? ` Attachments over ${formatBytes(BACKEND_ITEM_LIMIT_BYTES)} are truncated.`
+          : ""),
+      "Attach",
+    );
+    if (choice !== "Attach") return;
+  }
+
+  editorContext.add(session.id, items);
+  await showCodezViewContainer();
+  chatView?.refresh();
+}
+
 async function sendUserText(session: Session, text: string): Promise<void> {
   await sendUserInput(session, text, [], getSessionModelState(session.id));
 }
@@ -2821,11 +4271,22 @@ async function sendUserInput(
   rt.sending = true;
   rt.pendingInterrupt = false;
   const backendImages: BackendImageInput[] = [];
+  const attached = editorContext?.take(session.id) ?? [];
   const trimmed = text.trim();
   if (trimmed) {
     upsertBlock(session.id, { id: newLocalId("user"), type: "user", text });
     sessionPanels?.addUserMessage(session.id, text);
   }
+  if (attached.length > 0) {
+    upsertBlock(session.id, {
+      id: newLocalId("user-context"),
+      type: "info",
+      title: "Attached context",
+      text: attached
+        .map((item) => `${item.label} (${formatBytes(item.bytes)})`)
+        .join("\n"),
+    });
+  }
   if (images.length > 0) {
     const galleryImages: Array<{
       title: string;
@@ -2910,8 +4371,11 @@ async function sendUserInput(
       text,
       backendImages,
       modelState,
+      attached.map((item) => item.input),
     );
   } catch (err) {
+    // Keep the attachments for the retry.
+    editorContext?.add(session.id, attached);
     const errText = formatUnknownError(err);
     const cause = err instanceof Error ? (err as any).cause : null;
     const causeText = cause ? `\ncaused by: ${formatUnknownError(cause)}` : "";
@@ -3229,6 +4693,12 @@ async function handleSlashCommand(
     });
     return true;
   }
+  if (cmd === "changes") {
+    await vscode.commands.executeCommand("codez.reviewChanges", {
+      sessionId: session.id,
+    });
+    return true;
+  }
   if (cmd === "rename") {
     if (arg) {
       if (!sessions) throw new Error("sessions is not initialized");
@@ -3255,6 +4725,18 @@ async function handleSlashCommand(
     });
     return true;
   }
+  if (cmd === "commands") {
+    await vscode.commands.executeCommand("codez.showCustomCommands", {
+      sessionId: session.id,
+    });
+    return true;
+  }
+  if (cmd === "mcp-prompts") {
+    await vscode.commands.executeCommand("codez.showMcpPrompts", {
+      sessionId: session.id,
+    });
+    return true;
+  }
   if (cmd === "account") {
     const validateAccountName = (name: string): string | null => {
       const trimmedName = name.trim();
@@ -3439,7 +4921,13 @@ async function handleSlashCommand(
         "- /resume: Resume from history",
         "- /status: Show status",
         "- /mcp: List MCP servers",
+        "- /mcp-prompts: Browse MCP server prompts",
+        "- /mcp:<server>:<prompt> KEY=value: Send an MCP server prompt",
+        mineSelected
+          ? "- /commands: Browse custom commands (.codex/commands)"
+          : "- /commands: Browse custom commands (codez 選択時のみ対応)",
         "- /diff: Open Latest Diff",
+        "- /changes: Review the latest turn's changes (keep / revert per hunk)",
         "- /rename <title>: Rename session",
         "- /skills: Browse skills",
         mineSelected
@@ -3465,6 +4953,49 @@ async function handleSlashCommand(
   return false;
 }
 
+// Opens a stored thread as a session, reusing the session that already has it.
+async function resumeStoredThread(
+  folder: vscode.WorkspaceFolder,
+  backendId: BackendId,
+  thread: Thread,
+): Promise<void> {
+  if (!backendManager) throw new Error("backendManager is not initialized");
+  if (!sessions) throw new Error("sessions is not initialized");
+  if (!extensionContext) throw new Error("extensionContext is not set");
+
+  const backendKey = makeBackendInstanceKey(folder.uri.toString(), backendId);
+  const existing = sessions.getByThreadId(backendKey, thread.id);
+  if (existing) {
+    await vscode.commands.executeCommand("codez.openSession", {
+      sessionId: existing.id,
+    });
+    return;
+  }
+
+  const session: Session = {
+    id: crypto.randomUUID(),
+    backendId,
+    backendKey,
+    workspaceFolderUri: folder.uri.toString(),
+    title: normalizeSessionTitle(thread.name ?? (thread.preview || "Resumed")),
+    threadId: thread.id,
+  };
+
+  sessions.add(session.backendKey, session);
+  saveSessions(extensionContext, sessions);
+  ensureRuntime(session.id);
+  sessionTree?.refresh();
+
+  // Don't override the recorded thread model on resume. Users can still
+  // change the model via the UI for subsequent turns.
+  const resumed = await backendManager.resumeSession(session);
+  void ensureModelsFetched(session);
+  hydrateRuntimeFromThread(session.id, resumed.thread);
+  setActiveSession(session.id);
+  refreshCustomPromptsFromDisk();
+  await showCodezViewContainer();
+}
+
 function formatThreadLabel(preview: string): string {
   const v = String(preview || "").trim();
   return v.length > 0 ? v : "(no preview)";
@@ -4008,6 +5539,27 @@ function refreshCustomPromptsFromDisk(): void {
     .catch(() => {});
 }
 
+function setRateLimitResumeAt(
+  rt: SessionRuntime,
+  resumeAtMs: number | null,
+): void {
+  rt.rateLimitResumeAtMs = resumeAtMs;
+  if (rt.rateLimitCountdownTimer) {
+    clearInterval(rt.rateLimitCountdownTimer);
+    rt.rateLimitCountdownTimer = null;
+  }
+  if (resumeAtMs === null) return;
+  rt.rateLimitCountdownTimer = setInterval(() => {
+    if (
+      rt.rateLimitResumeAtMs === null ||
+      Date.now() >= rt.rateLimitResumeAtMs
+    ) {
+      setRateLimitResumeAt(rt, null);
+    }
+    chatView?.refresh();
+  }, 1000);
+}
+
 function ensureRuntime(sessionId: string): SessionRuntime {
   const existing = runtimeBySessionId.get(sessionId);
   if (existing) return existing;
@@ -4017,6 +5569,9 @@ function ensureRuntime(sessionId: string): SessionRuntime {
     statusText: null,
     uiHydrationBlockedText: null,
     tokenUsage: null,
+    rateLimitResumeAtMs: null,
+    rateLimitCountdownTimer: null,
+    modelProblem: null,
     sending: false,
     reloading: false,
     compactInFlight: false,
@@ -4040,6 +5595,27 @@ function ensureRuntime(sessionId: string): SessionRuntime {
   return rt;
 }
 
+function modelStatusForActiveSession(): ModelStatus | null {
+  const session = activeSessionId ? sessions?.getById(activeSessionId) : null;
+  if (!session) return null;
+  const state = getSessionModelState(session.id);
+  const rt = runtimeBySessionId.get(session.id) ?? null;
+  let problem = rt?.modelProblem?.message ?? null;
+  if (rt?.rateLimitResumeAtMs != null) {
+    const seconds = Math.max(
+      0,
+      Math.ceil((rt.rateLimitResumeAtMs - Date.now()) / 1000),
+    );
+    problem = `Rate limited; retrying in ${seconds}s`;
+  }
+  return {
+    model: state.model,
+    effort: state.reasoning,
+    backendId: session.backendId,
+    problem,
+  };
+}
+
 function getModelOptionsForSession(session: Session | null): Model[] | null {
   if (!session || !backendManager) return null;
   return backendManager.getCachedModels(session);
@@ -4138,6 +5714,13 @@ function buildChatState(): ChatViewState {
   if (hydrationBlockedText) core.push("history not loaded");
   if (baseStatusText) core.push(baseStatusText);
   if (globalRateLimitStatusText) core.push(globalRateLimitStatusText);
+  if (rt.rateLimitResumeAtMs !== null) {
+    const secs = Math.max(
+      0,
+      Math.ceil((rt.rateLimitResumeAtMs - Date.now()) / 1000),
+    );
+    core.push(`rate limited, resuming in ${secs}s`);
+  }
   const suffix: string[] = [];
   if (rt.sending) suffix.push("sending…");
   if (rt.reloading) suffix.push("reloading…");
@@ -4180,6 +5763,11 @@ function buildChatState(): ChatViewState {
       detail: v.detail,
       canAcceptForSession: v.canAcceptForSession,
     })),
+    editorContext:
+      editorContext?.chips(
+        activeRaw.id,
+        warnBytesFor(vscode.Uri.parse(activeRaw.workspaceFolderUri)),
+      ) ?? [],
     customPrompts: promptSummaries,
   };
 }
@@ -4226,6 +5814,74 @@ function applyServerNotification(
       chatView?.refresh();
       return;
     }
+    case "thread/nextEdit/suggested":
+      // Answered through BackendManager.suggestNextEdit.
+      return;
+    case "thread/pinned/updated": {
+      const items = ((n as any).params?.items ?? []) as PinnedContextItem[];
+      upsertBlock(sessionId, {
+        id: newLocalId("info"),
+        type: "info",
+        title: "Pinned context",
+        text:
+          items.length === 0
+            ? "Nothing is pinned."
+            : items
+                .map((item) => {
+                  const c = item.content;
+                  return c.type === "file"
+                    ? `- file: ${c.path}`
+                    : `- ${c.type}: ${c.text.split("\n")[0] ?? ""}`;
+                })
+                .join("\n"),
+      });
+      chatView?.refresh();
+      return;
+    }
+    case "thread/mcpTools/changed": {
+      const p = (n as any).params as {
+        server: string;
+        change:
+          | { type: "added"; tools: string[] }
+          | { type: "removed" }
+          | { type: "failed"; error: string };
+      };
+      const change = p.change;
+      upsertBlock(sessionId, {
+        id: newLocalId(change.type === "failed" ? "error" : "info"),
+        type: change.type === "failed" ? "error" : "info",
+        title:
+          change.type === "added"
+            ? `Added MCP server ${p.server}`
+            : change.type === "removed"
+              ? `Removed MCP server ${p.server}`
+              : `MCP server ${p.server} failed`,
+        text:
+          change.type === "added"
+            ? change.tools.length === 0
+              ? "It exposes no tools."
+              : change.tools.map((tool) => `- ${tool}`).join("\n")
+            : change.type === "failed"
+              ? change.error
+              : "Its tools are no longer offered to the model.",
+      });
+      chatView?.refresh();
+      return;
+    }
+    case "thread/fileRestored": {
+      const p = (n as any).params as { success?: unknown; message?: unknown };
+      const success = p?.success === true;
+      const message =
+        typeof p?.message === "string" ? p.message : "File restore finished.";
+      upsertBlock(sessionId, {
+        id: newLocalId(success ? "info" : "error"),
+        type: success ? "info" : "error",
+        title: success ? "File restored" : "File restore failed",
+        text: message,
+      });
+      chatView?.refresh();
+      return;
+    }
     case "thread/compacted": {
       const turnId = String((n as any).params?.turnId ?? "");
       const workedSeconds = computeWorkedSeconds(rt);
@@ -4288,6 +5944,11 @@ function applyServerNotification(
       chatView?.refresh();
       return;
     case "turn/completed":
+      fixReviewUriBySessionId.delete(sessionId);
+      setRateLimitResumeAt(rt, null);
+      if ((n as any).params?.turn?.status === "completed") {
+        rt.modelProblem = null;
+      }
       rt.sending = false;
       rt.lastTurnCompletedAtMs = Date.now();
       rt.activeTurnId = null;
@@ -4312,6 +5973,17 @@ function applyServerNotification(
       rt.statusText = formatTokenUsageStatus(rt.tokenUsage);
       chatView?.refresh();
       return;
+    case "thread/rateLimit/updated": {
+      const resumeInSeconds = (n as any).params.resumeInSeconds as
+        | number
+        | null;
+      setRateLimitResumeAt(
+        rt,
+        resumeInSeconds === null ? null : Date.now() + resumeInSeconds * 1000,
+      );
+      chatView?.refresh();
+      return;
+    }
     case "item/agentMessage/delta": {
       const id = (n as any).params.itemId as string;
       const block = getOrCreateBlock(rt, id, () => ({
@@ -4474,8 +6146,44 @@ function applyServerNotification(
       chatView?.refresh();
       return;
     }
+    case "turn/followUps/suggested": {
+      const p = (n as any).params as {
+        turnId: string;
+        suggestions: Array<{ title: string; prompt: string }>;
+      };
+      upsertBlock(sessionId, {
+        id: `followUps:${p.turnId}`,
+        type: "followUps",
+        suggestions: p.suggestions,
+      });
+      chatView?.refresh();
+      return;
+    }
     case "turn/diff/updated": {
-      rt.latestDiff = (n as any).params.diff as string;
+      const p = (n as any).params as {
+        turnId: string;
+        diff: string;
+        files?: TurnDiffFile[];
+      };
+      rt.latestDiff = p.diff;
+      const session = sessions?.getById(sessionId);
+      if (session) {
+        diffReview?.update(
+          sessionId,
+          vscode.Uri.parse(session.workspaceFolderUri),
+          p.turnId,
+          p.files ?? [],
+          p.diff,
+        );
+        const fixUri = fixReviewUriBySessionId.get(sessionId);
+        const fixed = diffReview
+          ?.pendingFiles(sessionId)
+          .find((file) => file.uri.toString() === fixUri);
+        if (fixed) {
+          fixReviewUriBySessionId.delete(sessionId);
+          void diffReview?.open(sessionId, fixed.uri);
+        }
+      }
       // Mark existing fileChange blocks as having a diff.
       for (const b of rt.blocks) {
         if (b.type === "fileChange") {
@@ -4529,22 +6237,35 @@ function applyServerNotification(
       const willRetry = !!p?.willRetry;
 
       let title = "Error";
+      let problemKind: "rateLimit" | "auth" | null = null;
       if (infoKey === "rateLimited" || infoKey === "rate_limited") {
         title =
           typeof httpStatusCode === "number"
             ? `Rate limited (HTTP ${httpStatusCode})`
             : "Rate limited";
+        problemKind = "rateLimit";
       } else if (
         infoKey === "usageLimitExceeded" ||
         infoKey === "usage_limit_exceeded"
       ) {
         title = "Usage limit exceeded";
+        problemKind = "rateLimit";
+      } else if (infoKey === "unauthorized") {
+        title = "Unauthorized";
+        problemKind = "auth";
       } else if (
         infoKey === "contextWindowExceeded" ||
         infoKey === "context_window_exceeded"
       ) {
         title = "Context window exceeded";
       }
+      if (problemKind) {
+        rt.modelProblem = {
+          kind: problemKind,
+          model: getSessionModelState(sessionId).model,
+          message: title,
+        };
+      }
 
       const lines: string[] = [];
       if (message) lines.push(message);
@@ -4655,6 +6376,14 @@ function applyItemLifecycle(
         if (completed && item.aggregatedOutput)
           block.output = item.aggregatedOutput;
       }
+      if (item.processId) {
+        const terminals =
+          runningTerminalsBySessionId.get(sessionId) ??
+          new Map<string, string>();
+        if (completed) terminals.delete(item.processId);
+        else terminals.set(item.processId, item.command);
+        runningTerminalsBySessionId.set(sessionId, terminals);
+      }
       chatView?.postBlockUpsert(sessionId, block);
       break;
     }
@@ -6347,6 +8076,14 @@ function formatApprovalDetail(
   const lines: string[] = [];
   lines.push(`method: ${method}`);
   if (reason) lines.push(`reason: ${reason}`);
+  if (typeof approvalParams === "object" && approvalParams !== null) {
+    const explanation = (approvalParams as Record<string, unknown>)[
+      "explanation"
+    ];
+    if (typeof explanation === "string" && explanation) {
+      lines.push(`what it does: ${explanation}`);
+    }
+  }
 
   let command: string | null = null;
   let cwd: string | null = null;
@@ -6409,6 +8146,7 @@ function updatePendingApprovalsFromItem(
         command: approval.command,
         cwd: approval.cwd,
         grantRoot: approval.grantRoot,
+        explanation: approval.explanation,
       },
     );
   }
@@ -6436,6 +8174,7 @@ type PersistedSessionV2 = Pick<
   | "title"
   | "threadId"
   | "customTitle"
+  | "profile"
 >;
 
 function readPersistedSessionsV1(
@@ -6491,6 +8230,7 @@ function loadSessions(
     const title = o["title"];
     const customTitle = o["customTitle"];
     const threadId = o["threadId"];
+    const profile = o["profile"];
 
     if (
       typeof id !== "string" ||
@@ -6513,6 +8253,7 @@ function loadSessions(
       title,
       customTitle: typeof customTitle === "boolean" ? customTitle : false,
       threadId,
+      ...(typeof profile === "string" ? { profile } : {}),
     });
   }
 }
@@ -6536,6 +8277,7 @@ function toPersistedSessionV2(session: Session): PersistedSessionV2 {
     title,
     customTitle,
     threadId,
+    profile,
   } = session;
   return {
     id,
@@ -6545,6 +8287,7 @@ function toPersistedSessionV2(session: Session): PersistedSessionV2 {
     title,
     customTitle,
     threadId,
+    profile,
   };
 }
 
@@ -10,6 +10,8 @@ export type Session = {
   title: string;
   customTitle?: boolean;
   threadId: string;
+  // Config profile the thread was started with (`codez.profile`).
+  profile?: string;
 };
 
 export class SessionStore {
@@ -0,0 +1,176 @@
+import * as vscode from "vscode";
+
+// "Ask Codex about this": CodeLenses above functions and an editor context
+// menu action that send a question about a symbol or a range. The prompt only
+// carries a workspace-relative reference (`@path#L10-L20`, as `@selection`
+// expands to), not the code, so the agent reads the file itself and sees the
+// surrounding code too.
+
+export type AskTarget = {
+  uri: vscode.Uri;
+  startLine: number; // 0-based, inclusive
+  endLine: number; // 0-based, inclusive
+  symbol: string | null;
+  kind: string | null;
+};
+
+const LENS_KINDS = new Set([
+  vscode.SymbolKind.Function,
+  vscode.SymbolKind.Method,
+  vscode.SymbolKind.Constructor,
+]);
+
+export class AskCodexLensProvider
+  implements vscode.CodeLensProvider, vscode.Disposable
+{
+  private readonly emitter = new vscode.EventEmitter<void>();
+  private readonly configListener: vscode.Disposable;
+
+  public readonly onDidChangeCodeLenses = this.emitter.event;
+
+  public constructor() {
+    this.configListener = vscode.workspace.onDidChangeConfiguration((e) => {
+      if (e.affectsConfiguration("codez.askCodex.codeLens")) {
+        this.emitter.fire();
+      }
+    });
+  }
+
+  public dispose(): void {
+    this.configListener.dispose();
+    this.emitter.dispose();
+  }
+
+  public async provideCodeLenses(
+    document: vscode.TextDocument,
+  ): Promise<vscode.CodeLens[]> {
+    const cfg = vscode.workspace.getConfiguration("codez", document.uri);
+    if (!cfg.get<boolean>("askCodex.codeLens", true)) return [];
+    if (!vscode.workspace.getWorkspaceFolder(document.uri)) return [];
+
+    const lenses: vscode.CodeLens[] = [];
+    for (const symbol of flatten(await documentSymbols(document.uri))) {
+      if (!LENS_KINDS.has(symbol.kind)) continue;
+      const line = symbol.range.start.line;
+      const target: AskTarget = {
+        uri: document.uri,
+        startLine: line,
+        endLine: symbol.range.end.line,
+        symbol: symbol.name,
+        kind: kindLabel(symbol.kind),
+      };
+      lenses.push(
+        new vscode.CodeLens(new vscode.Range(line, 0, line, 0), {
+          title: "Ask Codex",
+          command: "codez.askAboutThis",
+          arguments: [target],
+        }),
+      );
+    }
+    return lenses;
+  }
+}
+
+// The target of the active editor: the selected lines, or the function the
+// cursor is in when nothing is selected.
+export async function targetFromEditor(
+  editor: vscode.TextEditor,
+): Promise<AskTarget> {
+  const { document, selection } = editor;
+  const symbol = innermost(
+    flatten(await documentSymbols(document.uri)),
+    selection,
+  );
+  if (selection.isEmpty && symbol) {
+    return {
+      uri: document.uri,
+      startLine: symbol.range.start.line,
+      endLine: symbol.range.end.line,
+      symbol: symbol.name,
+      kind: kindLabel(symbol.kind),
+    };
+  }
+  // A selection ending at column 0 does not include that line.
+  const endLine =
+    selection.end.character === 0 && selection.end.line > selection.start.line
+      ? selection.end.line - 1
+      : selection.end.line;
+  return {
+    uri: document.uri,
+    startLine: selection.start.line,
+    endLine,
+    symbol: symbol?.name ?? null,
+    kind: symbol ? kindLabel(symbol.kind) : null,
+  };
+}
+
+// Fills `{symbol}` and `{file}` in the configured question.
+export function defaultQuestion(target: AskTarget): string {
+  const cfg = vscode.workspace.getConfiguration("codez", target.uri);
+  const template = cfg.get<string>(
+    "askCodex.question",
+    "Explain what {symbol} does.",
+  );
+  const relPath = vscode.workspace.asRelativePath(target.uri, false);
+  const symbol = target.symbol ? `\`${target.symbol}\`` : "this code";
+  return template.replaceAll("{symbol}", symbol).replaceAll("{file}", relPath);
+}
+
+// `relPath` is relative to the session's workspace folder.
+export function buildAskPrompt(
+  target: AskTarget,
+  relPath: string,
+  question: string,
+): string {
+  const start = target.startLine + 1;
+  const end = target.endLine + 1;
+  const range = start === end ? `#L${start}` : `#L${start}-L${end}`;
+  const what = target.symbol
+    ? ` (${target.kind ?? "symbol"} \`${target.symbol}\`)`
+    : "";
+  return `${question.trim()}\n\nContext: @${relPath}${range}${what}`;
+}
+
+async function documentSymbols(
+  uri: vscode.Uri,
+): Promise<vscode.DocumentSymbol[]> {
+  try {
+    const symbols = await vscode.commands.executeCommand<
+      Array<vscode.DocumentSymbol | vscode.SymbolInformation> | undefined
+    >("vscode.executeDocumentSymbolProvider", uri);
+    // Providers that return flat SymbolInformation have no ranges to nest by.
+    return (symbols ?? []).filter(
+      (s): s is vscode.DocumentSymbol => "children" in s,
+    );
+  } catch {
+    return [];
+  }
+}
+
+function flatten(symbols: vscode.DocumentSymbol[]): vscode.DocumentSymbol[] {
+  return symbols.flatMap((s) => [s, ...flatten(s.children)]);
+}
+
+function innermost(
+  symbols: vscode.DocumentSymbol[],
+  selection: vscode.Selection,
+): vscode.DocumentSymbol | null {
+  let best: vscode.DocumentSymbol | null = null;
+  for (const symbol of symbols) {
+    if (!LENS_KINDS.has(symbol.kind)) continue;
+    if (!symbol.range.contains(selection)) continue;
+    if (!best || best.range.contains(symbol.range)) best = symbol;
+  }
+  return best;
+}
+
+function kindLabel(kind: vscode.SymbolKind): string {
+  switch (kind) {
+    case vscode.SymbolKind.Method:
+      return "method";
+    case vscode.SymbolKind.Constructor:
+      return "constructor";
+    default:
+      return "function";
+  }
+}
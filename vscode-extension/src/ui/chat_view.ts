@@ -4,6 +4,7 @@ import * as path from "node:path";
 import * as vscode from "vscode";
 
 import type { Session } from "../sessions";
+import type { EditorContextChip } from "./editor_context";
 import type { AskUserQuestionRequest } from "../generated/AskUserQuestionRequest";
 import type { AskUserQuestionResponse } from "../generated/v2/AskUserQuestionResponse";
 
@@ -120,6 +121,11 @@ export type ChatBlock =
       }>;
     }
   | { id: string; type: "plan"; title: string; text: string }
+  | {
+      id: string;
+      type: "followUps";
+      suggestions: Array<{ title: string; prompt: string }>;
+    }
   | { id: string; type: "error"; title: string; text: string }
   | { id: string; type: "system"; title: string; text: string };
 
@@ -164,6 +170,8 @@ export type ChatViewState = {
     detail: string;
     canAcceptForSession: boolean;
   }>;
+  // Editor content attached to the next prompt.
+  editorContext?: EditorContextChip[];
 };
 
 type RewindRequest = {
@@ -235,6 +243,8 @@ export class ChatViewProvider implements vscode.WebviewViewProvider {
       reject: (err: unknown) => void;
     }
   >();
+  // Called on every refresh, even while the view is hidden (status bar).
+  public onRefresh: (() => void) | null = null;
 
   public insertIntoInput(text: string): void {
     this.view?.webview.postMessage({ type: "insertText", text });
@@ -381,6 +391,7 @@ export class ChatViewProvider implements vscode.WebviewViewProvider {
   }
 
   public refresh(): void {
+    this.onRefresh?.();
     // Avoid flooding the Webview with full-state updates (especially during streaming).
     this.statePostDirty = true;
     if (this.view && !this.view.visible) return;
@@ -658,6 +669,17 @@ export class ChatViewProvider implements vscode.WebviewViewProvider {
       return;
     }
 
+    if (type === "removeContext") {
+      const id = anyMsg["id"];
+      const sessionId = this.getState().activeSession?.id;
+      if (typeof id !== "string" || !sessionId) return;
+      await vscode.commands.executeCommand("codez.context.remove", {
+        sessionId,
+        id,
+      });
+      return;
+    }
+
     if (type === "reloadSession") {
       await vscode.commands.executeCommand("codez.reloadSession");
       return;
@@ -691,15 +713,9 @@ export class ChatViewProvider implements vscode.WebviewViewProvider {
     }
 
     if (type === "newSession") {
-      const st = this.getState();
-      const active = st.activeSession;
-      if (active) {
-        await vscode.commands.executeCommand("codez.newSession", {
-          workspaceFolderUri: active.workspaceFolderUri,
-        });
-      } else {
-        await vscode.commands.executeCommand("codez.newSession");
-      }
+      // The command binds the session to the active editor's folder, or asks
+      // in a multi-root workspace.
+      await vscode.commands.executeCommand("codez.newSession");
       return;
     }
 
@@ -1273,11 +1289,17 @@ export class ChatViewProvider implements vscode.WebviewViewProvider {
         paths = [];
       }
 
+      const symbols =
+        active.backendId === "codez"
+          ? await searchWorkspaceSymbols(folder, norm)
+          : [];
+
       this.view?.webview.postMessage({
         type: "fileSearchResult",
         sessionId,
         query,
         paths,
+        symbols,
       });
       return;
     }
@@ -1393,6 +1415,7 @@ export class ChatViewProvider implements vscode.WebviewViewProvider {
       modelState: full.modelState,
       models: full.models,
       approvals: full.approvals,
+      editorContext: full.editorContext,
       customPrompts: full.customPrompts,
     };
     void this.view.webview
@@ -1508,6 +1531,12 @@ export class ChatViewProvider implements vscode.WebviewViewProvider {
       .editBannerText { flex: 1 1 auto; min-width: 0; font-size: 12px; opacity: 0.9; white-space: nowrap; overflow: hidden; text-overflow: ellipsis; }
       .editBanner button { padding: 4px 8px; font-size: 12px; border-radius: 8px; }
       .msg { margin: 10px 0; padding: 10px 12px; border-radius: 10px; border: 1px solid rgba(127,127,127,0.25); }
+      .followUps { display: flex; flex-wrap: wrap; gap: 6px; align-items: center; border-style: dashed; }
+      .followUpsLabel { font-size: 12px; opacity: 0.7; margin-right: 4px; }
+      .followUpChip { display: inline-flex; border: 1px solid rgba(0, 120, 212, 0.45); border-radius: 999px; overflow: hidden; }
+      .followUpChip button { padding: 3px 10px; font-size: 12px; border: none; border-radius: 0; background: transparent; color: inherit; cursor: pointer; }
+      .followUpChip button + button { padding: 3px 8px; border-left: 1px solid rgba(0, 120, 212, 0.45); }
+      .followUpChip button:hover:not(:disabled) { background: rgba(0, 120, 212, 0.15); }
       .note { margin: 8px 2px; font-size: 12px; opacity: 0.7; color: var(--vscode-descriptionForeground, inherit); }
       /* Keep user distinct from webSearch (both were blue-ish in dark themes). */
       .user { background: rgba(255,255,255,0.035); border-color: rgba(0, 120, 212, 0.35); }
@@ -1609,6 +1638,10 @@ export class ChatViewProvider implements vscode.WebviewViewProvider {
       .attachmentThumb { width: 44px; height: 44px; object-fit: cover; border-radius: 8px; border: 1px solid rgba(127,127,127,0.25); background: rgba(0,0,0,0.02); flex: 0 0 auto; }
       .attachmentName { overflow: hidden; text-overflow: ellipsis; white-space: nowrap; opacity: 0.9; }
       .attachmentRemove { cursor: pointer; opacity: 0.7; }
+      .contextChips { display: none; flex-wrap: wrap; gap: 6px; }
+      .contextChip { border: 1px solid rgba(127,127,127,0.35); border-radius: 10px; padding: 2px 8px; font-size: 12px; display: inline-flex; gap: 6px; align-items: center; max-width: 320px; }
+      .contextChip.large { border-color: var(--vscode-editorWarning-foreground, rgba(204,167,0,0.9)); }
+      .contextChip.large::before { content: "⚠"; color: var(--vscode-editorWarning-foreground, rgba(204,167,0,0.9)); }
       .suggestItem { padding: 8px 10px; cursor: pointer; display: flex; justify-content: space-between; gap: 10px; }
       .suggestItem:hover { background: var(--vscode-list-hoverBackground, rgba(255,255,255,0.06)); }
       .suggestItem.active { background: var(--vscode-list-activeSelectionBackground, rgba(0,120,212,0.25)); }
@@ -1707,6 +1740,7 @@ export class ChatViewProvider implements vscode.WebviewViewProvider {
 	      <div id="editBanner" class="editBanner" style="display:none"></div>
 	      <div id="askUserQuestion" class="askUserQuestion"></div>
 	      <div id="attachments" class="attachments"></div>
+	      <div id="contextChips" class="contextChips"></div>
 	      <button id="returnToBottom" class="returnToBottomBtn" title="Scroll to bottom">Return to Bottom</button>
 	      <div id="inputRow" class="inputRow">
         <input id="imageInput" type="file" accept="image/*" multiple style="display:none" />
@@ -1729,6 +1763,57 @@ export class ChatViewProvider implements vscode.WebviewViewProvider {
   }
 }
 
+const MAX_SYMBOL_SUGGESTIONS = 8;
+
+// Definitions offered for `@name`, from the language servers' workspace
+// symbol providers. They are inserted as `@#mention`; the codez backend looks
+// the name up in its own index and attaches the definition, so a container is
+// only kept when it is a plain identifier the backend can match.
+async function searchWorkspaceSymbols(
+  folder: vscode.WorkspaceFolder,
+  query: string,
+): Promise<Array<{ mention: string; kind: string; location: string }>> {
+  if (!/^[\p{L}\p{N}_$]{2,}$/u.test(query)) return [];
+  let found: vscode.SymbolInformation[] | undefined;
+  try {
+    found = await vscode.commands.executeCommand<
+      vscode.SymbolInformation[] | undefined
+    >("vscode.executeWorkspaceSymbolProvider", query);
+  } catch (err) {
+    console.error("[codez] workspace symbol search failed:", err);
+    return [];
+  }
+  const q = query.toLowerCase();
+  const rank = (name: string): number => {
+    const n = name.toLowerCase();
+    return n === q ? 0 : n.startsWith(q) ? 1 : n.includes(q) ? 2 : 3;
+  };
+  const inFolder = (s: vscode.SymbolInformation): boolean => {
+    const owner = vscode.workspace.getWorkspaceFolder(s.location.uri);
+    return owner?.uri.toString() === folder.uri.toString();
+  };
+  return (found ?? [])
+    .filter((s) => rank(s.name) < 3 && inFolder(s))
+    .sort(
+      (a, b) => rank(a.name) - rank(b.name) || a.name.length - b.name.length,
+    )
+    .slice(0, MAX_SYMBOL_SUGGESTIONS)
+    .map((s) => {
+      const container = /^[A-Za-z_$][\w$]*$/.test(s.containerName)
+        ? s.containerName
+        : null;
+      const rel = path
+        .relative(folder.uri.fsPath, s.location.uri.fsPath)
+        .replace(/\\/g, "/");
+      const line = s.location.range.start.line + 1;
+      return {
+        mention: container ? `${container}.${s.name}` : s.name,
+        kind: vscode.SymbolKind[s.kind].toLowerCase(),
+        location: `${rel}:${line}`,
+      };
+    });
+}
+
 function normalizeFileSearchQuery(query: string): string | null {
   const q = query.trim().replace(/\\/g, "/");
   if (!q) return null;
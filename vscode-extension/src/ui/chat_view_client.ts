@@ -22,6 +22,7 @@ type Session = {
   customTitle?: boolean;
   workspaceFolderUri: string;
   backendId?: "codex" | "codez" | "opencode";
+  profile?: string;
 };
 type ModelState = {
   model: string | null;
@@ -142,6 +143,11 @@ type ChatBlock =
       }>;
     }
   | { id: string; type: "plan"; title: string; text: string }
+  | {
+      id: string;
+      type: "followUps";
+      suggestions: Array<{ title: string; prompt: string }>;
+    }
   | { id: string; type: "error"; title: string; text: string }
   | { id: string; type: "system"; title: string; text: string };
 
@@ -180,6 +186,12 @@ type ChatViewState = {
     detail: string;
     canAcceptForSession: boolean;
   }>;
+  editorContext?: Array<{
+    id: string;
+    label: string;
+    title: string;
+    large: boolean;
+  }>;
   customPrompts?: Array<{
     name: string;
     description: string | null;
@@ -218,7 +230,15 @@ type SuggestItem = {
   insert: string;
   label: string;
   detail?: string;
-  kind: "slash" | "at" | "file" | "dir" | "agent" | "skill";
+  kind: "slash" | "at" | "file" | "dir" | "agent" | "skill" | "symbol";
+};
+
+// A definition offered for `@name`, inserted as an `@#mention` that the
+// backend resolves and attaches as context.
+type SymbolSuggestion = {
+  mention: string;
+  kind: string;
+  location: string;
 };
 
 const WORKTREE_COLORS = [
@@ -271,6 +291,7 @@ function main(): void {
   const imageInput = mustGet<HTMLInputElement>("imageInput");
   const attachBtn = mustGet<HTMLButtonElement>("attach");
   const attachmentsEl = mustGet("attachments");
+  const contextChipsEl = mustGet("contextChips");
   const returnToBottomBtn = mustGet<HTMLButtonElement>("returnToBottom");
   const sendBtn = mustGet<HTMLButtonElement>("send");
   const newBtn = mustGet<HTMLButtonElement>("new");
@@ -1116,8 +1137,15 @@ function main(): void {
     idx: number,
   ): { label: string; tooltip: string } => {
     const title = String(sess.title || "").trim() || "Untitled";
-    if (sess.customTitle) return { label: title, tooltip: title };
-    return { label: `${title} #${idx + 1}`, tooltip: title };
+    const binding = [
+      `Folder: ${uriToBasename(sess.workspaceFolderUri)}`,
+      sess.profile ? `Profile: ${sess.profile}` : null,
+    ]
+      .filter(Boolean)
+      .join(" • ");
+    const tooltip = `${title}\n${binding}`;
+    if (sess.customTitle) return { label: title, tooltip };
+    return { label: `${title} #${idx + 1}`, tooltip };
   };
 
   if (typeof markdownit !== "function") {
@@ -1273,6 +1301,7 @@ function main(): void {
   const blockElByKey = new Map<string, HTMLElement>();
   let tabsSig: string | null = null;
   let approvalsSig: string | null = null;
+  let contextChipsSig: string | null = null;
   const tabElBySessionId = new Map<string, HTMLDivElement>();
   let isComposing = false;
 
@@ -1438,7 +1467,14 @@ function main(): void {
       sessionLabel.textContent =
         "No active session. Create or select a session first.";
     } else {
-      sessionLabel.textContent = `Backend: ${settingsSessionBackendId}`;
+      const active = state.activeSession;
+      sessionLabel.textContent = [
+        `Backend: ${settingsSessionBackendId}`,
+        `Folder: ${uriToBasename(active.workspaceFolderUri)}`,
+        active.profile ? `Profile: ${active.profile}` : null,
+      ]
+        .filter(Boolean)
+        .join(" • ");
     }
     sessionRow.appendChild(sessionLabel);
     sectionSession.appendChild(sessionRow);
@@ -2496,8 +2532,12 @@ function main(): void {
 
   let suggestItems: SuggestItem[] = [];
   let suggestIndex = 0;
-  let fileSearch: null | { sessionId: string; query: string; paths: string[] } =
-    null;
+  let fileSearch: null | {
+    sessionId: string;
+    query: string;
+    paths: string[];
+    symbols: SymbolSuggestion[];
+  } = null;
   let fileSearchInFlight: null | { sessionId: string; query: string } = null;
   let fileSearchTimer: number | null = null;
   const FILE_SEARCH_DEBOUNCE_MS = 250;
@@ -3139,7 +3179,7 @@ function main(): void {
         const altLabel = label.startsWith("/prompts:")
           ? "/" + label.slice("/prompts:".length)
           : label.startsWith("@")
-            ? label.slice(1)
+            ? label.replace(/^@#?/, "")
             : label.startsWith("$")
               ? label.slice(1)
               : label;
@@ -3247,8 +3287,14 @@ function main(): void {
               detail: "",
               kind: "file" as const,
             }));
-            // Prefer agents (above), then directories, then files.
-            items = items.concat(dirItems, fileItems);
+            const symbolItems = existing.symbols.map((s) => ({
+              insert: "@#" + s.mention + " ",
+              label: "@#" + s.mention,
+              detail: `${s.kind} · ${s.location}`,
+              kind: "symbol" as const,
+            }));
+            // Prefer agents (above), then directories, then files, then symbols.
+            items = items.concat(dirItems, fileItems, symbolItems);
           } else {
             scheduleFileSearch(state.activeSession.id, query);
             items = items.concat([
@@ -3854,6 +3900,32 @@ function main(): void {
       approvalsEl.style.display = approvalsVisible ? "" : "none";
     }
 
+    const contextChips = s.activeSession ? s.editorContext || [] : [];
+    const nextContextChipsSig = contextChips
+      .map((c) => [c.id, c.label, c.large ? "!" : ""].join("\t"))
+      .join("\n");
+    if (contextChipsSig !== nextContextChipsSig) {
+      contextChipsSig = nextContextChipsSig;
+      contextChipsEl.innerHTML = "";
+      for (const c of contextChips) {
+        const chip = el("span", c.large ? "contextChip large" : "contextChip");
+        chip.title = c.large
+          ? c.title + " — large; uses much of the context window"
+          : c.title;
+        const name = el("span", "attachmentName");
+        name.textContent = c.label;
+        const remove = el("span", "attachmentRemove");
+        remove.textContent = "×";
+        remove.title = "Remove";
+        remove.addEventListener("click", () =>
+          vscode.postMessage({ type: "removeContext", id: c.id }),
+        );
+        chip.append(name, remove);
+        contextChipsEl.appendChild(chip);
+      }
+      contextChipsEl.style.display = contextChips.length > 0 ? "flex" : "none";
+    }
+
     const globalBlocks = s.globalBlocks || [];
     if (globalBlocks.length > 0) {
       for (const block of globalBlocks) {
@@ -4547,6 +4619,45 @@ function main(): void {
         continue;
       }
 
+      if (block.type === "followUps") {
+        const div = ensureDiv("b:" + block.id, "msg followUps");
+        const label = document.createElement("span");
+        label.className = "followUpsLabel";
+        label.textContent = "Suggested follow-ups:";
+        const chips = block.suggestions.map((suggestion) => {
+          const chip = document.createElement("span");
+          chip.className = "followUpChip";
+          const editBtn = document.createElement("button");
+          editBtn.textContent = suggestion.title;
+          editBtn.title = "Edit in the composer: " + suggestion.prompt;
+          editBtn.addEventListener("click", () => {
+            inputEl.value = suggestion.prompt;
+            autosizeInput();
+            updateSuggestions();
+            saveComposerState();
+            inputEl.focus();
+          });
+          const sendBtn = document.createElement("button");
+          sendBtn.textContent = "▶";
+          sendBtn.disabled = Boolean(state.sending);
+          sendBtn.title = state.sending
+            ? "Available after the running turn"
+            : "Send as the next turn";
+          sendBtn.addEventListener("click", () => {
+            if (state.sending) return;
+            vscode.postMessage({
+              type: "send",
+              text: suggestion.prompt,
+              rewind: null,
+            });
+          });
+          chip.append(editBtn, sendBtn);
+          return chip;
+        });
+        div.replaceChildren(label, ...chips);
+        continue;
+      }
+
       if (block.type === "plan") {
         const id = "plan:" + block.id;
         const det = ensureDetails(
@@ -4689,7 +4800,8 @@ function main(): void {
     const canEdit = backendId === "codez" || backendId === "opencode";
     const text = inputEl.value;
     const trimmed = text.trim();
-    if (!trimmed && pendingImages.length === 0) return;
+    const hasContext = (state.editorContext?.length ?? 0) > 0;
+    if (!trimmed && pendingImages.length === 0 && !hasContext) return;
     if (pendingImages.length > 0) {
       vscode.postMessage({
         type: "sendWithImages",
@@ -5071,6 +5183,7 @@ function main(): void {
       delta?: unknown;
       streaming?: unknown;
       paths?: unknown;
+      symbols?: unknown;
       sessionId?: unknown;
       query?: unknown;
       agents?: unknown;
@@ -5363,6 +5476,15 @@ function main(): void {
       const paths = Array.isArray(anyMsg.paths)
         ? anyMsg.paths.filter((p): p is string => typeof p === "string")
         : [];
+      const symbols = Array.isArray(anyMsg.symbols)
+        ? anyMsg.symbols.filter(
+            (s): s is SymbolSuggestion =>
+              !!s &&
+              typeof s.mention === "string" &&
+              typeof s.kind === "string" &&
+              typeof s.location === "string",
+          )
+        : [];
 
       if (!sessionId || !query) return;
       if (!state.activeSession) return;
@@ -5377,7 +5499,7 @@ function main(): void {
       )
         return;
 
-      fileSearch = { sessionId, query, paths };
+      fileSearch = { sessionId, query, paths, symbols };
       fileSearchInFlight = null;
       updateSuggestions();
       return;
@@ -0,0 +1,680 @@
+import * as crypto from "node:crypto";
+import * as vscode from "vscode";
+
+import type { ConfigDiagnostic } from "../generated/v2/ConfigDiagnostic";
+import type { ConfigEdit } from "../generated/v2/ConfigEdit";
+import type { ConfigLayer } from "../generated/v2/ConfigLayer";
+import type { ConfigLayerMetadata } from "../generated/v2/ConfigLayerMetadata";
+import type { ConfigReadResponse } from "../generated/v2/ConfigReadResponse";
+import type { ConfigRequirements } from "../generated/v2/ConfigRequirements";
+import type { ConfigWriteResponse } from "../generated/v2/ConfigWriteResponse";
+import type { JsonValue } from "../generated/serde_json/JsonValue";
+import { ConfigSchema, type SchemaError } from "../config_schema";
+
+// A form over the user's `config.toml` for the settings people change most:
+// model, approval policy, sandbox mode, MCP servers and subagent directories.
+// Values come from the user layer (the file this form writes); a note shows
+// when another layer (project, managed, flags) overrides one. Edits are
+// checked against the backend's schema first, then written in one
+// `config/batchWrite`, which the backend validates again. Problems already in
+// the files (`config/validate`: unknown keys, wrong types, replaced options)
+// are listed above the form and shown inline in the files' editors. Settings
+// locked by the managed policy (`requirements.toml` or MDM) only offer the
+// values it allows.
+
+export type ConfigPanelBackend = {
+  read(folder: vscode.WorkspaceFolder): Promise<{
+    config: ConfigReadResponse;
+    schema: unknown;
+    diagnostics: ConfigDiagnostic[];
+    requirements: ConfigRequirements | null;
+  }>;
+  write(
+    folder: vscode.WorkspaceFolder,
+    edits: ConfigEdit[],
+    expectedVersion: string | null,
+  ): Promise<ConfigWriteResponse>;
+};
+
+type McpServerForm = {
+  name: string;
+  command: string;
+  args: string[];
+  url: string;
+  enabled: boolean;
+};
+
+type ConfigForm = {
+  model: string;
+  approvalPolicy: string;
+  sandboxMode: string;
+  subagentDirs: string[];
+  mcpServers: McpServerForm[];
+};
+
+type Problem = {
+  text: string;
+  filePath: string | null;
+  line: number | null;
+  column: number | null;
+};
+
+type FormState = {
+  folder: string;
+  filePath: string | null;
+  form: ConfigForm;
+  options: { approvalPolicies: string[]; sandboxModes: string[] };
+  descriptions: Record<string, string>;
+  // Form field -> why the effective value differs from the form.
+  overrides: Record<string, string>;
+  // What the managed policy locks beyond the form's fields, or null.
+  managed: string | null;
+  problems: Problem[];
+};
+
+type JsonObject = { [key: string]: unknown };
+
+// Top-level key paths behind the form fields.
+const FIELD_KEYS = {
+  model: "model",
+  approvalPolicy: "approval_policy",
+  sandboxMode: "sandbox_mode",
+  subagentDirs: "subagents.extra_dirs",
+  mcpServers: "mcp_servers",
+} as const;
+
+const MCP_SERVER_NAME = /^[A-Za-z0-9_-]+$/;
+
+export class ConfigPanel implements vscode.Disposable {
+  private static current: ConfigPanel | null = null;
+
+  private readonly panel: vscode.WebviewPanel;
+  private readonly disposables: vscode.Disposable[] = [];
+  private readonly problems =
+    vscode.languages.createDiagnosticCollection("codez-config");
+  private userLayer: JsonObject = {};
+  private version: string | null = null;
+  private schema: ConfigSchema | null = null;
+  private filePath: string | null = null;
+
+  public static show(
+    folder: vscode.WorkspaceFolder,
+    backend: ConfigPanelBackend,
+  ): void {
+    const current = ConfigPanel.current;
+    if (current && current.folder.uri.toString() === folder.uri.toString()) {
+      current.panel.reveal();
+      return;
+    }
+    current?.panel.dispose();
+    ConfigPanel.current = new ConfigPanel(folder, backend);
+  }
+
+  private constructor(
+    private readonly folder: vscode.WorkspaceFolder,
+    private readonly backend: ConfigPanelBackend,
+  ) {
+    this.panel = vscode.window.createWebviewPanel(
+      "codez.config",
+      `Codex Settings: ${folder.name}`,
+      vscode.ViewColumn.Active,
+      { enableScripts: true, retainContextWhenHidden: true },
+    );
+    this.panel.webview.html = renderHtml();
+    this.disposables.push(this.problems);
+    this.panel.onDidDispose(() => this.dispose(), null, this.disposables);
+    this.panel.webview.onDidReceiveMessage(
+      (msg: unknown) => void this.onMessage(msg),
+      null,
+      this.disposables,
+    );
+  }
+
+  public dispose(): void {
+    if (ConfigPanel.current === this) ConfigPanel.current = null;
+    for (const d of this.disposables) d.dispose();
+    this.disposables.length = 0;
+  }
+
+  private async onMessage(msg: unknown): Promise<void> {
+    if (typeof msg !== "object" || msg === null) return;
+    const m = msg as {
+      type?: unknown;
+      form?: unknown;
+      problem?: Problem;
+    };
+    try {
+      if (m.type === "ready" || m.type === "reload") {
+        await this.load();
+      } else if (m.type === "save") {
+        await this.save(m.form as ConfigForm);
+      } else if (m.type === "openFile" && this.filePath) {
+        await vscode.window.showTextDocument(vscode.Uri.file(this.filePath));
+      } else if (m.type === "openProblem" && m.problem?.filePath) {
+        const position = new vscode.Position(
+          Math.max((m.problem.line ?? 1) - 1, 0),
+          Math.max((m.problem.column ?? 1) - 1, 0),
+        );
+        await vscode.window.showTextDocument(
+          vscode.Uri.file(m.problem.filePath),
+          { selection: new vscode.Range(position, position) },
+        );
+      }
+    } catch (err) {
+      void this.post({ type: "status", kind: "error", text: String(err) });
+    }
+  }
+
+  private async load(): Promise<void> {
+    const { config, schema, diagnostics, requirements } =
+      await this.backend.read(this.folder);
+    this.schema = ConfigSchema.from(schema);
+    this.publishProblems(diagnostics);
+    const user = (config.layers ?? []).find((l) => l.name.type === "user");
+    this.userLayer = asObject(user?.config);
+    this.version = user?.version ?? null;
+    this.filePath = user && "file" in user.name ? user.name.file : null;
+    await this.post({
+      type: "state",
+      state: this.state(config, user, diagnostics, requirements),
+    });
+  }
+
+  // Shows the problems in the editors of the files they are in.
+  private publishProblems(diagnostics: ConfigDiagnostic[]): void {
+    this.problems.clear();
+    const byFile = new Map<string, vscode.Diagnostic[]>();
+    for (const diagnostic of diagnostics) {
+      if (!diagnostic.filePath) continue;
+      const line = Math.max((diagnostic.line ?? 1) - 1, 0);
+      const column = Math.max((diagnostic.column ?? 1) - 1, 0);
+      const key = diagnostic.keyPath.replace(/\[\d+\]$/, "").split(".").pop();
+      const range = new vscode.Range(
+        line,
+        column,
+        line,
+        column + Math.max(key?.length ?? 0, 1),
+      );
+      const item = new vscode.Diagnostic(
+        range,
+        problemText(diagnostic),
+        diagnostic.kind === "typeMismatch"
+          ? vscode.DiagnosticSeverity.Error
+          : vscode.DiagnosticSeverity.Warning,
+      );
+      item.source = "codex";
+      if (diagnostic.kind === "deprecated") {
+        item.tags = [vscode.DiagnosticTag.Deprecated];
+      }
+      const items = byFile.get(diagnostic.filePath) ?? [];
+      items.push(item);
+      byFile.set(diagnostic.filePath, items);
+    }
+    for (const [file, items] of byFile) {
+      this.problems.set(vscode.Uri.file(file), items);
+    }
+  }
+
+  private state(
+    config: ConfigReadResponse,
+    user: ConfigLayer | undefined,
+    diagnostics: ConfigDiagnostic[],
+    requirements: ConfigRequirements | null,
+  ): FormState {
+    const descriptions: Record<string, string> = {};
+    for (const [field, key] of Object.entries(FIELD_KEYS)) {
+      const description = this.schema?.description(key);
+      if (description) descriptions[field] = description;
+    }
+    const overrides: Record<string, string> = {};
+    for (const [field, key] of Object.entries(FIELD_KEYS)) {
+      const origin = overridingOrigin(config, key, user);
+      if (origin) overrides[field] = `Overridden by ${describeLayer(origin)}.`;
+    }
+    const locked = (field: string, allowed: string[] | null | undefined) => {
+      if (!allowed) return;
+      const note = `Locked by the managed policy: ${allowed.join(", ")}.`;
+      overrides[field] = overrides[field] ? `${overrides[field]} ${note}` : note;
+    };
+    locked("approvalPolicy", requirements?.allowedApprovalPolicies);
+    locked("sandboxMode", requirements?.allowedSandboxModes);
+    locked("mcpServers", requirements?.allowedMcpServers);
+    const allowedOnly = (values: string[], allowed?: string[] | null) =>
+      allowed ? values.filter((value) => allowed.includes(value)) : values;
+    return {
+      folder: this.folder.name,
+      filePath: this.filePath,
+      form: formFromLayer(this.userLayer),
+      options: {
+        approvalPolicies: allowedOnly(
+          this.schema?.enumValues("approval_policy") ?? [],
+          requirements?.allowedApprovalPolicies,
+        ),
+        sandboxModes: allowedOnly(
+          this.schema?.enumValues("sandbox_mode") ?? [],
+          requirements?.allowedSandboxModes,
+        ),
+      },
+      descriptions,
+      overrides,
+      managed: describeManaged(requirements),
+      problems: diagnostics.map((diagnostic) => ({
+        text: problemLocation(diagnostic) + problemText(diagnostic),
+        filePath: diagnostic.filePath,
+        line: diagnostic.line,
+        column: diagnostic.column,
+      })),
+    };
+  }
+
+  private async save(form: ConfigForm): Promise<void> {
+    const { edits, errors } = buildEdits(this.userLayer, form);
+    if (this.schema) {
+      for (const edit of edits) {
+        if (edit.value === null) continue;
+        errors.push(...this.schema.validate(edit.keyPath, edit.value));
+      }
+    }
+    if (errors.length > 0) {
+      await this.post({ type: "errors", errors });
+      return;
+    }
+    if (edits.length === 0) {
+      await this.post({ type: "status", kind: "info", text: "No changes." });
+      return;
+    }
+    const res = await this.backend.write(this.folder, edits, this.version);
+    await this.load();
+    const text =
+      res.status === "okOverridden" && res.overriddenMetadata
+        ? `Saved to ${res.filePath}. ${res.overriddenMetadata.message}`
+        : `Saved to ${res.filePath}. New sessions use the updated settings.`;
+    await this.post({ type: "status", kind: "info", text });
+  }
+
+  private async post(message: unknown): Promise<void> {
+    await this.panel.webview.postMessage(message);
+  }
+}
+
+function formFromLayer(layer: JsonObject): ConfigForm {
+  const subagents = asObject(layer["subagents"]);
+  const servers = asObject(layer["mcp_servers"]);
+  return {
+    model: asString(layer["model"]),
+    approvalPolicy: asString(layer["approval_policy"]),
+    sandboxMode: asString(layer["sandbox_mode"]),
+    subagentDirs: asStrings(subagents["extra_dirs"]),
+    mcpServers: Object.entries(servers).map(([name, raw]) => {
+      const server = asObject(raw);
+      return {
+        name,
+        command: asString(server["command"]),
+        args: asStrings(server["args"]),
+        url: asString(server["url"]),
+        enabled: server["enabled"] !== false,
+      };
+    }),
+  };
+}
+
+// Edits that turn `layer` into `form`, touching only what changed. MCP
+// server settings the form does not show (env, headers, tool lists) are kept.
+function buildEdits(
+  layer: JsonObject,
+  form: ConfigForm,
+): { edits: ConfigEdit[]; errors: SchemaError[] } {
+  const edits: ConfigEdit[] = [];
+  const errors: SchemaError[] = [];
+  const set = (keyPath: string, current: unknown, next: JsonValue) => {
+    if (next === null && current === undefined) return;
+    if (JSON.stringify(current) === JSON.stringify(next)) return;
+    edits.push({ keyPath, value: next, mergeStrategy: "replace" });
+  };
+
+  set("model", layer["model"], form.model.trim() || null);
+  set("approval_policy", layer["approval_policy"], form.approvalPolicy || null);
+  set("sandbox_mode", layer["sandbox_mode"], form.sandboxMode || null);
+
+  const subagents = asObject(layer["subagents"]);
+  const dirs = form.subagentDirs.map((d) => d.trim()).filter(Boolean);
+  set(
+    "subagents.extra_dirs",
+    subagents["extra_dirs"],
+    dirs.length > 0 ? dirs : null,
+  );
+
+  const servers = asObject(layer["mcp_servers"]);
+  const names = new Set<string>();
+  for (const server of form.mcpServers) {
+    const name = server.name.trim();
+    const path = `mcp_servers.${name || "?"}`;
+    if (!MCP_SERVER_NAME.test(name)) {
+      errors.push({
+        path,
+        message: "Name must use letters, digits, '-' or '_'.",
+      });
+      continue;
+    }
+    if (names.has(name)) {
+      errors.push({ path, message: "Duplicate server name." });
+      continue;
+    }
+    names.add(name);
+    const command = server.command.trim();
+    const url = server.url.trim();
+    if (Boolean(command) === Boolean(url)) {
+      errors.push({ path, message: "Set either a command or a URL." });
+      continue;
+    }
+    const next: JsonObject = { ...asObject(servers[name]) };
+    delete next["command"];
+    delete next["args"];
+    delete next["url"];
+    if (command) {
+      next["command"] = command;
+      const args = server.args.map((a) => a.trim()).filter(Boolean);
+      if (args.length > 0) next["args"] = args;
+    } else {
+      next["url"] = url;
+    }
+    if (server.enabled) delete next["enabled"];
+    else next["enabled"] = false;
+    set(path, servers[name], next as JsonValue);
+  }
+  for (const name of Object.keys(servers)) {
+    if (!names.has(name)) set(`mcp_servers.${name}`, servers[name], null);
+  }
+  return { edits, errors };
+}
+
+// The layer the effective value of `key` comes from, when it is not the user
+// layer and sits above it.
+function overridingOrigin(
+  config: ConfigReadResponse,
+  key: string,
+  user: ConfigLayer | undefined,
+): ConfigLayerMetadata | null {
+  const origin =
+    config.origins[key] ??
+    Object.entries(config.origins).find(([k]) => k.startsWith(`${key}.`))?.[1];
+  if (!origin || origin.name.type === "user") return null;
+  if (!user) return origin;
+  const rank = (layer: ConfigLayerMetadata["name"]) =>
+    (config.layers ?? []).findIndex((l) => l.name.type === layer.type);
+  // Layers are listed from highest to lowest precedence.
+  return rank(origin.name) < rank(user.name) ? origin : null;
+}
+
+function describeManaged(requirements: ConfigRequirements | null): string | null {
+  const parts: string[] = [];
+  if (requirements?.allowedModelProviders) {
+    parts.push(`model providers: ${requirements.allowedModelProviders.join(", ")}`);
+  }
+  if (requirements?.requireAuditLog) parts.push("the audit log is always on");
+  return parts.length > 0 ? `Managed policy — ${parts.join("; ")}.` : null;
+}
+
+function problemText(diagnostic: ConfigDiagnostic): string {
+  if (!diagnostic.suggestion) return diagnostic.message;
+  return diagnostic.kind === "deprecated"
+    ? `${diagnostic.message}; use \`${diagnostic.suggestion}\` instead`
+    : `${diagnostic.message}; did you mean \`${diagnostic.suggestion}\`?`;
+}
+
+function problemLocation(diagnostic: ConfigDiagnostic): string {
+  if (!diagnostic.filePath) return "";
+  return diagnostic.line === null
+    ? `${diagnostic.filePath}: `
+    : `${diagnostic.filePath}:${diagnostic.line}: `;
+}
+
+function describeLayer(layer: ConfigLayerMetadata): string {
+  switch (layer.name.type) {
+    case "project":
+      return `${layer.name.dotCodexFolder}/config.toml`;
+    case "sessionFlags":
+      return "command-line overrides";
+    case "system":
+    case "legacyManagedConfigTomlFromFile":
+      return `managed config (${layer.name.file})`;
+    default:
+      return "managed preferences";
+  }
+}
+
+function asObject(value: unknown): JsonObject {
+  return typeof value === "object" && value !== null && !Array.isArray(value)
+    ? (value as JsonObject)
+    : {};
+}
+
+function asString(value: unknown): string {
+  return typeof value === "string" ? value : "";
+}
+
+function asStrings(value: unknown): string[] {
+  return Array.isArray(value)
+    ? value.filter((v): v is string => typeof v === "string")
+    : [];
+}
+
+function renderHtml(): string {
+  const nonce = crypto.randomBytes(16).toString("base64");
+  return `<!doctype html>
+<html lang="en">
+  <head>
+    <meta charset="UTF-8" />
+    <meta http-equiv="Content-Security-Policy" content="default-src 'none'; style-src 'unsafe-inline'; script-src 'nonce-${nonce}';" />
+    <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+    <title>Codex Settings</title>
+    <style>
+      body { font-family: var(--vscode-font-family); color: var(--vscode-foreground); padding: 12px 20px; max-width: 760px; }
+      h2 { font-size: 1.1em; margin: 20px 0 6px; }
+      label { display: block; font-weight: 600; margin: 14px 0 4px; }
+      .hint { color: var(--vscode-descriptionForeground); font-size: 0.9em; margin: 2px 0 4px; white-space: pre-wrap; }
+      .override { color: var(--vscode-editorWarning-foreground); font-size: 0.9em; }
+      .error { color: var(--vscode-errorForeground); font-size: 0.9em; }
+      input[type=text], select, textarea { width: 100%; box-sizing: border-box; padding: 4px 6px; color: var(--vscode-input-foreground); background: var(--vscode-input-background); border: 1px solid var(--vscode-input-border, transparent); }
+      textarea { min-height: 4em; font-family: var(--vscode-editor-font-family); }
+      .server { border: 1px solid rgba(127,127,127,0.3); border-radius: 6px; padding: 8px 10px; margin: 8px 0; }
+      .server .row { display: flex; gap: 8px; align-items: center; }
+      .server label { font-weight: normal; margin: 6px 0 2px; }
+      .actions { display: flex; gap: 8px; margin: 20px 0; }
+      button { padding: 4px 12px; color: var(--vscode-button-foreground); background: var(--vscode-button-background); border: none; cursor: pointer; }
+      button.secondary { color: var(--vscode-button-secondaryForeground); background: var(--vscode-button-secondaryBackground); }
+      #status.error { color: var(--vscode-errorForeground); }
+      #problems ul { margin: 4px 0; padding-left: 18px; }
+      #problems li { margin: 2px 0; cursor: pointer; font-family: var(--vscode-editor-font-family); font-size: 0.9em; }
+      #problems li:hover { text-decoration: underline; }
+    </style>
+  </head>
+  <body>
+    <div class="hint" id="file"></div>
+    <div class="override" id="managed"></div>
+    <div id="problems" hidden>
+      <h2>Problems in the config files</h2>
+      <ul></ul>
+    </div>
+    <form id="form">
+      <label for="model">Model</label>
+      <div class="hint" data-description="model"></div>
+      <input type="text" id="model" placeholder="(default)" />
+      <div class="override" data-override="model"></div>
+      <div class="error" data-error="model"></div>
+
+      <label for="approvalPolicy">Approval policy</label>
+      <div class="hint" data-description="approvalPolicy"></div>
+      <select id="approvalPolicy"></select>
+      <div class="override" data-override="approvalPolicy"></div>
+      <div class="error" data-error="approval_policy"></div>
+
+      <label for="sandboxMode">Sandbox mode</label>
+      <div class="hint" data-description="sandboxMode"></div>
+      <select id="sandboxMode"></select>
+      <div class="override" data-override="sandboxMode"></div>
+      <div class="error" data-error="sandbox_mode"></div>
+
+      <label for="subagentDirs">Subagent directories (one absolute path per line)</label>
+      <div class="hint" data-description="subagentDirs"></div>
+      <textarea id="subagentDirs"></textarea>
+      <div class="override" data-override="subagentDirs"></div>
+      <div class="error" data-error="subagents.extra_dirs"></div>
+
+      <h2>MCP servers</h2>
+      <div class="hint">A server runs a command or connects to a URL. Other settings of a server (env, headers, tool lists) are kept as they are in the file.</div>
+      <div class="override" data-override="mcpServers"></div>
+      <div id="servers"></div>
+      <button type="button" class="secondary" id="addServer">Add server</button>
+
+      <div class="actions">
+        <button type="submit">Save</button>
+        <button type="button" class="secondary" id="reload">Reload</button>
+        <button type="button" class="secondary" id="openFile">Open config.toml</button>
+      </div>
+      <div id="status"></div>
+    </form>
+
+    <script nonce="${nonce}">
+      const vscode = acquireVsCodeApi();
+      const $ = (id) => document.getElementById(id);
+      const serversEl = $("servers");
+
+      function fillSelect(select, values, current) {
+        select.innerHTML = "";
+        const options = ["", ...values];
+        if (current && !values.includes(current)) options.push(current);
+        for (const value of options) {
+          const option = document.createElement("option");
+          option.value = value;
+          option.textContent = value || "(default)";
+          select.appendChild(option);
+        }
+        select.value = current;
+      }
+
+      function addServer(server) {
+        const el = document.createElement("div");
+        el.className = "server";
+        el.innerHTML =
+          '<div class="row"><input type="text" data-field="name" placeholder="name" />' +
+          '<label><input type="checkbox" data-field="enabled" /> enabled</label>' +
+          '<button type="button" class="secondary" data-remove>Remove</button></div>' +
+          '<label>Command</label><input type="text" data-field="command" />' +
+          '<label>Arguments (one per line)</label><textarea data-field="args"></textarea>' +
+          '<label>URL (instead of a command)</label><input type="text" data-field="url" />' +
+          '<div class="error" data-server-error></div>';
+        el.querySelector('[data-field="name"]').value = server.name;
+        el.querySelector('[data-field="enabled"]').checked = server.enabled;
+        el.querySelector('[data-field="command"]').value = server.command;
+        el.querySelector('[data-field="args"]').value = server.args.join("\\n");
+        el.querySelector('[data-field="url"]').value = server.url;
+        el.querySelector("[data-remove]").addEventListener("click", () => el.remove());
+        serversEl.appendChild(el);
+      }
+
+      function readForm() {
+        const lines = (text) => text.split("\\n").map((l) => l.trim()).filter(Boolean);
+        return {
+          model: $("model").value,
+          approvalPolicy: $("approvalPolicy").value,
+          sandboxMode: $("sandboxMode").value,
+          subagentDirs: lines($("subagentDirs").value),
+          mcpServers: [...serversEl.querySelectorAll(".server")].map((el) => ({
+            name: el.querySelector('[data-field="name"]').value,
+            enabled: el.querySelector('[data-field="enabled"]').checked,
+            command: el.querySelector('[data-field="command"]').value,
+            args: lines(el.querySelector('[data-field="args"]').value),
+            url: el.querySelector('[data-field="url"]').value,
+          })),
+        };
+      }
+
+      function clearErrors() {
+        for (const el of document.querySelectorAll(".error")) el.textContent = "";
+      }
+
+      function setStatus(kind, text) {
+        $("status").className = kind === "error" ? "error" : "";
+        $("status").textContent = text;
+      }
+
+      function showErrors(errors) {
+        clearErrors();
+        const rest = [];
+        for (const error of errors) {
+          const field = [...document.querySelectorAll("[data-error]")].find(
+            (el) => error.path === el.dataset.error || error.path.startsWith(el.dataset.error + "["),
+          );
+          const server = error.path.match(/^mcp_servers\\.([^.\\[]+)/);
+          const serverEl = server && [...serversEl.querySelectorAll(".server")].find(
+            (el) => el.querySelector('[data-field="name"]').value.trim() === server[1],
+          );
+          const target = field ?? serverEl?.querySelector("[data-server-error]");
+          if (target) target.textContent += error.path + ": " + error.message + " ";
+          else rest.push(error.path + ": " + error.message);
+        }
+        setStatus("error", rest.length > 0 ? rest.join("\\n") : "Fix the errors above and save again.");
+      }
+
+      function render(state) {
+        clearErrors();
+        $("file").textContent = state.filePath
+          ? "Editing " + state.filePath + " (workspace: " + state.folder + ")"
+          : "Editing the user config.toml";
+        $("openFile").disabled = !state.filePath;
+        $("managed").textContent = state.managed ?? "";
+        $("model").value = state.form.model;
+        fillSelect($("approvalPolicy"), state.options.approvalPolicies, state.form.approvalPolicy);
+        fillSelect($("sandboxMode"), state.options.sandboxModes, state.form.sandboxMode);
+        $("subagentDirs").value = state.form.subagentDirs.join("\\n");
+        for (const el of document.querySelectorAll("[data-description]")) {
+          el.textContent = state.descriptions[el.dataset.description] ?? "";
+        }
+        for (const el of document.querySelectorAll("[data-override]")) {
+          el.textContent = state.overrides[el.dataset.override] ?? "";
+        }
+        serversEl.innerHTML = "";
+        for (const server of state.form.mcpServers) addServer(server);
+        renderProblems(state.problems);
+      }
+
+      function renderProblems(problems) {
+        const list = $("problems").querySelector("ul");
+        list.innerHTML = "";
+        $("problems").hidden = problems.length === 0;
+        for (const problem of problems) {
+          const item = document.createElement("li");
+          item.textContent = problem.text;
+          if (problem.filePath) {
+            item.title = "Open " + problem.filePath;
+            item.addEventListener("click", () => vscode.postMessage({ type: "openProblem", problem }));
+          }
+          list.appendChild(item);
+        }
+      }
+
+      $("form").addEventListener("submit", (event) => {
+        event.preventDefault();
+        clearErrors();
+        setStatus("info", "Saving…");
+        vscode.postMessage({ type: "save", form: readForm() });
+      });
+      $("reload").addEventListener("click", () => vscode.postMessage({ type: "reload" }));
+      $("openFile").addEventListener("click", () => vscode.postMessage({ type: "openFile" }));
+      $("addServer").addEventListener("click", () =>
+        addServer({ name: "", enabled: true, command: "", args: [], url: "" }),
+      );
+
+      window.addEventListener("message", (event) => {
+        const msg = event.data;
+        if (!msg) return;
+        if (msg.type === "state") render(msg.state);
+        else if (msg.type === "errors") showErrors(msg.errors);
+        else if (msg.type === "status") setStatus(msg.kind, msg.text);
+      });
+      vscode.postMessage({ type: "ready" });
+    </script>
+  </body>
+</html>`;
+}
@@ -0,0 +1,316 @@
+import * as vscode from "vscode";
+import type { TurnDiffFile } from "../generated/v2/TurnDiffFile";
+import type { TurnDiffHunk } from "../generated/v2/TurnDiffHunk";
+
+// Review of the latest turn's changes in VSCode's diff editor. The left side
+// is the file as it was before the turn, rebuilt from the file on disk and the
+// turn's unified diff; the right side is the file itself. Each pending hunk
+// gets Keep / Revert CodeLenses and quick fixes: keeping only drops it from the
+// review, reverting asks the backend (`thread/revertHunks`) to put its old
+// lines back.
+
+export const BASE_SCHEME = "codez-base";
+
+type ReviewHunk = TurnDiffHunk & {
+  // Lines of the hunk before the turn (context and removed lines).
+  oldText: string[];
+};
+
+type ReviewFile = {
+  uri: vscode.Uri;
+  relPath: string;
+  hunks: ReviewHunk[];
+};
+
+type Review = {
+  sessionId: string;
+  turnId: string;
+  files: Map<string, ReviewFile>;
+};
+
+export type RevertHunks = (
+  sessionId: string,
+  turnId: string,
+  path: string,
+  hunks: TurnDiffHunk[],
+) => Promise<void>;
+
+export class DiffReviewController
+  implements
+    vscode.TextDocumentContentProvider,
+    vscode.CodeLensProvider,
+    vscode.CodeActionProvider,
+    vscode.Disposable
+{
+  private readonly baseEmitter = new vscode.EventEmitter<vscode.Uri>();
+  private readonly lensEmitter = new vscode.EventEmitter<void>();
+  private readonly reviews = new Map<string, Review>();
+
+  public readonly onDidChange = this.baseEmitter.event;
+  public readonly onDidChangeCodeLenses = this.lensEmitter.event;
+
+  public constructor(private readonly revert: RevertHunks) {}
+
+  public dispose(): void {
+    this.baseEmitter.dispose();
+    this.lensEmitter.dispose();
+    this.reviews.clear();
+  }
+
+  // Replaces the session's review with the changes of `turnId`. Deleted,
+  // renamed and binary files are left out; `codez.restoreFile` covers them.
+  public update(
+    sessionId: string,
+    folderUri: vscode.Uri,
+    turnId: string,
+    files: TurnDiffFile[],
+    unifiedDiff: string,
+  ): void {
+    const sections = parseUnifiedDiff(unifiedDiff);
+    const review: Review = { sessionId, turnId, files: new Map() };
+    files.forEach((file, idx) => {
+      if (file.binary) return;
+      if (file.status === "deleted" || file.status === "renamed") return;
+      const oldTexts = sections[idx] ?? [];
+      const uri = vscode.Uri.joinPath(folderUri, file.path);
+      review.files.set(uri.toString(), {
+        uri,
+        relPath: file.path,
+        hunks: file.hunks.map((hunk, i) => ({
+          ...hunk,
+          oldText: oldTexts[i] ?? [],
+        })),
+      });
+    });
+    this.reviews.set(sessionId, review);
+    for (const file of review.files.values())
+      this.baseEmitter.fire(baseUri(sessionId, file.relPath));
+    this.lensEmitter.fire();
+  }
+
+  public clear(sessionId: string): void {
+    if (this.reviews.delete(sessionId)) this.lensEmitter.fire();
+  }
+
+  public pendingFiles(
+    sessionId: string,
+  ): Array<{ uri: vscode.Uri; relPath: string; hunks: number }> {
+    const review = this.reviews.get(sessionId);
+    if (!review) return [];
+    return [...review.files.values()]
+      .filter((file) => file.hunks.length > 0)
+      .map((file) => ({
+        uri: file.uri,
+        relPath: file.relPath,
+        hunks: file.hunks.length,
+      }));
+  }
+
+  public async open(sessionId: string, uri: vscode.Uri): Promise<void> {
+    const found = this.find(uri.toString());
+    if (!found || found.review.sessionId !== sessionId) return;
+    const left = baseUri(sessionId, found.file.relPath);
+    await vscode.commands.executeCommand(
+      "vscode.diff",
+      left,
+      uri,
+      `${found.file.relPath} (Codex changes)`,
+    );
+  }
+
+  public async provideTextDocumentContent(uri: vscode.Uri): Promise<string> {
+    const sessionId = decodeURIComponent(uri.query);
+    const review = this.reviews.get(sessionId);
+    const file = [...(review?.files.values() ?? [])].find(
+      (f) => f.relPath === uri.path.replace(/^\//, ""),
+    );
+    if (!file) return "";
+    const current = await readText(file.uri);
+    return revertLines(current, file.hunks);
+  }
+
+  public provideCodeLenses(document: vscode.TextDocument): vscode.CodeLens[] {
+    const found = this.find(document.uri.toString());
+    if (!found || found.file.hunks.length === 0) return [];
+    const { file } = found;
+    const key = document.uri.toString();
+    const lenses: vscode.CodeLens[] = [];
+    const top = new vscode.Range(0, 0, 0, 0);
+    const count = file.hunks.length;
+    const noun = count === 1 ? "change" : "changes";
+    lenses.push(
+      new vscode.CodeLens(top, {
+        title: `Keep all ${count} Codex ${noun}`,
+        command: "codez.diffReview.keep",
+        arguments: [key, null],
+      }),
+      new vscode.CodeLens(top, {
+        title: "Revert file",
+        command: "codez.diffReview.revert",
+        arguments: [key, null],
+      }),
+    );
+    for (const hunk of file.hunks) {
+      const line = Math.min(
+        Math.max(hunk.newStart - 1, 0),
+        Math.max(document.lineCount - 1, 0),
+      );
+      const range = new vscode.Range(line, 0, line, 0);
+      lenses.push(
+        new vscode.CodeLens(range, {
+          title: "✓ Keep",
+          command: "codez.diffReview.keep",
+          arguments: [key, hunk.newStart],
+        }),
+        new vscode.CodeLens(range, {
+          title: "✗ Revert",
+          command: "codez.diffReview.revert",
+          arguments: [key, hunk.newStart],
+        }),
+      );
+    }
+    return lenses;
+  }
+
+  public provideCodeActions(
+    document: vscode.TextDocument,
+    range: vscode.Range | vscode.Selection,
+  ): vscode.CodeAction[] {
+    const found = this.find(document.uri.toString());
+    if (!found) return [];
+    const key = document.uri.toString();
+    const hunk = found.file.hunks.find((h) => {
+      const first = Math.max(h.newStart - 1, 0);
+      const last = first + Math.max(h.newLines, 1) - 1;
+      return range.start.line <= last && range.end.line >= first;
+    });
+    if (!hunk) return [];
+    const action = (title: string, command: string): vscode.CodeAction => {
+      const a = new vscode.CodeAction(title, vscode.CodeActionKind.QuickFix);
+      a.command = { title, command, arguments: [key, hunk.newStart] };
+      return a;
+    };
+    return [
+      action("Keep this Codex change", "codez.diffReview.keep"),
+      action("Revert this Codex change", "codez.diffReview.revert"),
+    ];
+  }
+
+  // Drops a hunk (or, with `newStart` null, every hunk of the file) from the
+  // review and leaves the file as it is.
+  public keep(key: string, newStart: number | null): void {
+    const found = this.find(key);
+    if (!found) return;
+    const { file, review } = found;
+    file.hunks =
+      newStart === null
+        ? []
+        : file.hunks.filter((h) => h.newStart !== newStart);
+    this.baseEmitter.fire(baseUri(review.sessionId, file.relPath));
+    this.lensEmitter.fire();
+  }
+
+  // Asks the backend to revert a hunk (or, with `newStart` null, every pending
+  // hunk of the file). Hunks below a reverted one move by the lines it adds
+  // or removes, so their positions are kept in step with the file.
+  public async revertHunks(
+    key: string,
+    newStart: number | null,
+  ): Promise<void> {
+    const found = this.find(key);
+    if (!found) return;
+    const { file, review } = found;
+    const targets =
+      newStart === null
+        ? file.hunks
+        : file.hunks.filter((h) => h.newStart === newStart);
+    if (targets.length === 0) return;
+
+    await this.revert(
+      review.sessionId,
+      review.turnId,
+      file.uri.fsPath,
+      targets.map(({ oldStart, oldLines, newStart, newLines }) => ({
+        oldStart,
+        oldLines,
+        newStart,
+        newLines,
+      })),
+    );
+
+    file.hunks = file.hunks
+      .filter((h) => !targets.includes(h))
+      .map((h) => {
+        const shift = targets
+          .filter((t) => t.newStart < h.newStart)
+          .reduce((sum, t) => sum + t.oldLines - t.newLines, 0);
+        return { ...h, newStart: h.newStart + shift };
+      });
+    this.baseEmitter.fire(baseUri(review.sessionId, file.relPath));
+    this.lensEmitter.fire();
+  }
+
+  private find(key: string): { review: Review; file: ReviewFile } | null {
+    for (const review of this.reviews.values()) {
+      const file = review.files.get(key);
+      if (file) return { review, file };
+    }
+    return null;
+  }
+}
+
+function baseUri(sessionId: string, relPath: string): vscode.Uri {
+  return vscode.Uri.from({
+    scheme: BASE_SCHEME,
+    path: `/${relPath}`,
+    query: encodeURIComponent(sessionId),
+  });
+}
+
+async function readText(uri: vscode.Uri): Promise<string> {
+  const open = vscode.workspace.textDocuments.find(
+    (doc) => doc.uri.toString() === uri.toString(),
+  );
+  if (open) return open.getText();
+  try {
+    const bytes = await vscode.workspace.fs.readFile(uri);
+    return new TextDecoder().decode(bytes);
+  } catch {
+    return "";
+  }
+}
+
+// Rebuilds the text before the turn: each hunk's new lines are replaced with
+// its old lines, bottom-up so earlier positions stay valid.
+export function revertLines(current: string, hunks: ReviewHunk[]): string {
+  const eol = current.includes("\r\n") ? "\r\n" : "\n";
+  const lines = current.length === 0 ? [] : current.split(/\r?\n/);
+  const trailing = lines.length > 0 && lines[lines.length - 1] === "";
+  if (trailing) lines.pop();
+  const sorted = [...hunks].sort((a, b) => b.newStart - a.newStart);
+  for (const hunk of sorted) {
+    const first = hunk.newLines === 0 ? hunk.newStart : hunk.newStart - 1;
+    lines.splice(first, hunk.newLines, ...hunk.oldText);
+  }
+  return lines.join(eol) + (trailing ? eol : "");
+}
+
+// Old-side lines of every hunk, grouped by file section in diff order.
+export function parseUnifiedDiff(diff: string): string[][][] {
+  const files: string[][][] = [];
+  let hunks: string[][] | null = null;
+  let hunk: string[] | null = null;
+  for (const line of diff.split("\n")) {
+    if (line.startsWith("diff --git ")) {
+      hunks = [];
+      hunk = null;
+      files.push(hunks);
+    } else if (hunks && line.startsWith("@@")) {
+      hunk = [];
+      hunks.push(hunk);
+    } else if (hunk && (line.startsWith(" ") || line.startsWith("-"))) {
+      hunk.push(line.slice(1));
+    }
+  }
+  return files;
+}
@@ -0,0 +1,215 @@
+import * as vscode from "vscode";
+import type { UserInput } from "../generated/v2/UserInput";
+
+// Editor content attached to the next prompt: the selection, the current
+// file, every open editor, or terminal output. Attachments are kept per
+// session until the next send and go to the backend as `editorContext` /
+// `terminalOutput` inputs, not as prompt text. The text is read when
+// attached, so unsaved edits are included.
+
+export type EditorContextAttachment = {
+  id: string;
+  // Attaching the same source again replaces the earlier attachment.
+  key: string;
+  label: string;
+  title: string;
+  bytes: number;
+  input: UserInput;
+};
+
+export type EditorContextChip = {
+  id: string;
+  label: string;
+  title: string;
+  large: boolean;
+};
+
+// The backend truncates each attachment past this size.
+export const BACKEND_ITEM_LIMIT_BYTES = 128 * 1024;
+
+let nextId = 0;
+
+export class EditorContextStore {
+  private readonly bySessionId = new Map<string, EditorContextAttachment[]>();
+
+  public list(sessionId: string): EditorContextAttachment[] {
+    return this.bySessionId.get(sessionId) ?? [];
+  }
+
+  public totalBytes(sessionId: string): number {
+    return this.list(sessionId).reduce((sum, a) => sum + a.bytes, 0);
+  }
+
+  // Adds attachments, replacing any from the same source.
+  public add(sessionId: string, items: EditorContextAttachment[]): void {
+    const keys = new Set(items.map((a) => a.key));
+    const kept = this.list(sessionId).filter((a) => !keys.has(a.key));
+    this.bySessionId.set(sessionId, [...kept, ...items]);
+  }
+
+  public remove(sessionId: string, id: string): void {
+    const next = this.list(sessionId).filter((a) => a.id !== id);
+    if (next.length > 0) this.bySessionId.set(sessionId, next);
+    else this.bySessionId.delete(sessionId);
+  }
+
+  // Removes and returns the session's attachments, for sending.
+  public take(sessionId: string): EditorContextAttachment[] {
+    const items = this.list(sessionId);
+    this.bySessionId.delete(sessionId);
+    return items;
+  }
+
+  public chips(sessionId: string, warnBytes: number): EditorContextChip[] {
+    return this.list(sessionId).map((a) => ({
+      id: a.id,
+      label: a.label,
+      title: `${a.title} (${formatBytes(a.bytes)})`,
+      large: a.bytes > warnBytes,
+    }));
+  }
+}
+
+export function selectionAttachment(
+  editor: vscode.TextEditor,
+): EditorContextAttachment | null {
+  const { document, selection } = editor;
+  if (selection.isEmpty) return null;
+  // A selection ending at column 0 does not include that line.
+  const endLine =
+    selection.end.character === 0 && selection.end.line > selection.start.line
+      ? selection.end.line - 1
+      : selection.end.line;
+  return linesAttachment(document, selection.start.line, endLine);
+}
+
+// Whole lines `startLine..=endLine` (0-based) of the document.
+export function linesAttachment(
+  document: vscode.TextDocument,
+  startLine: number,
+  endLine: number,
+): EditorContextAttachment {
+  const last = Math.min(endLine, document.lineCount - 1);
+  const first = Math.min(startLine, last);
+  const range = new vscode.Range(
+    first,
+    0,
+    last,
+    document.lineAt(last).range.end.character,
+  );
+  const start = first + 1;
+  const end = last + 1;
+  const lines = start === end ? `L${start}` : `L${start}-L${end}`;
+  return editorAttachment(
+    document,
+    `${baseName(document)}#${lines}`,
+    start,
+    end,
+    document.getText(range),
+  );
+}
+
+export function fileAttachment(
+  document: vscode.TextDocument,
+): EditorContextAttachment {
+  return editorAttachment(
+    document,
+    baseName(document),
+    null,
+    null,
+    document.getText(),
+  );
+}
+
+export function terminalAttachment(
+  terminal: string,
+  command: string | null,
+  exitCode: number | null,
+  text: string,
+): EditorContextAttachment {
+  return {
+    id: newId(),
+    key: `terminal:${terminal}`,
+    label: `${terminal}: ${command ?? "output"}`,
+    title: command
+      ? `${command} in terminal ${terminal}`
+      : `terminal ${terminal}`,
+    bytes: Buffer.byteLength(text, "utf8"),
+    input: {
+      type: "terminalOutput",
+      terminal,
+      ...(command !== null ? { command } : {}),
+      ...(exitCode !== null ? { exitCode } : {}),
+      text,
+    },
+  };
+}
+
+// Text files open in any editor tab, in tab order. Tabs whose document is not
+// loaded yet are opened (without showing them).
+export async function openEditorAttachments(): Promise<
+  EditorContextAttachment[]
+> {
+  const seen = new Set<string>();
+  const out: EditorContextAttachment[] = [];
+  for (const group of vscode.window.tabGroups.all) {
+    for (const tab of group.tabs) {
+      if (!(tab.input instanceof vscode.TabInputText)) continue;
+      const uri = tab.input.uri;
+      if (uri.scheme !== "file" && uri.scheme !== "untitled") continue;
+      if (seen.has(uri.toString())) continue;
+      seen.add(uri.toString());
+      try {
+        out.push(fileAttachment(await vscode.workspace.openTextDocument(uri)));
+      } catch {
+        // Binary or unreadable; skip it.
+      }
+    }
+  }
+  return out;
+}
+
+export function warnBytesFor(uri: vscode.Uri | undefined): number {
+  const cfg = vscode.workspace.getConfiguration("codez", uri);
+  return Math.max(0, cfg.get<number>("editorContext.warnBytes", 100_000));
+}
+
+export function formatBytes(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+function editorAttachment(
+  document: vscode.TextDocument,
+  label: string,
+  startLine: number | null,
+  endLine: number | null,
+  text: string,
+): EditorContextAttachment {
+  const path = document.uri.fsPath;
+  return {
+    id: newId(),
+    key: `${path}#${startLine ?? ""}-${endLine ?? ""}`,
+    label,
+    title: path,
+    bytes: Buffer.byteLength(text, "utf8"),
+    input: {
+      type: "editorContext",
+      path,
+      ...(startLine !== null ? { startLine } : {}),
+      ...(endLine !== null ? { endLine } : {}),
+      text,
+    },
+  };
+}
+
+function newId(): string {
+  nextId += 1;
+  return `ctx-${nextId}`;
+}
+
+function baseName(document: vscode.TextDocument): string {
+  const parts = document.uri.path.split("/");
+  return parts[parts.length - 1] || document.uri.path;
+}
@@ -0,0 +1,111 @@
+import * as vscode from "vscode";
+
+// "Fix with Codex" quick fixes for the diagnostics of any file in a workspace
+// folder. The prompt carries the diagnostic and the file path; the code
+// around it goes along as an attachment. The file's review diff opens as soon
+// as the turn changes it (see `DiffReviewController`).
+
+// Quick fixes offered per request; a line with many problems gets one per
+// diagnostic up to this count.
+const MAX_ACTIONS = 3;
+
+export class FixWithCodexProvider implements vscode.CodeActionProvider {
+  public static readonly providedCodeActionKinds = [
+    vscode.CodeActionKind.QuickFix,
+  ];
+
+  public provideCodeActions(
+    document: vscode.TextDocument,
+    _range: vscode.Range | vscode.Selection,
+    context: vscode.CodeActionContext,
+  ): vscode.CodeAction[] {
+    const cfg = vscode.workspace.getConfiguration("codez", document.uri);
+    if (!cfg.get<boolean>("fixWithCodex.enabled", true)) return [];
+    if (!vscode.workspace.getWorkspaceFolder(document.uri)) return [];
+
+    return context.diagnostics.slice(0, MAX_ACTIONS).map((diagnostic) => {
+      const title = `Fix with Codex: ${firstLine(diagnostic.message, 60)}`;
+      const action = new vscode.CodeAction(
+        title,
+        vscode.CodeActionKind.QuickFix,
+      );
+      action.diagnostics = [diagnostic];
+      action.command = {
+        command: "codez.fixDiagnostic",
+        title,
+        arguments: [document.uri, diagnostic],
+      };
+      return action;
+    });
+  }
+}
+
+// Lines (0-based, inclusive) to attach around the diagnostic.
+export function contextLines(
+  document: vscode.TextDocument,
+  diagnostic: vscode.Diagnostic,
+): { startLine: number; endLine: number } {
+  const cfg = vscode.workspace.getConfiguration("codez", document.uri);
+  const radius = Math.max(0, cfg.get<number>("fixWithCodex.contextLines", 20));
+  return {
+    startLine: Math.max(0, diagnostic.range.start.line - radius),
+    endLine: Math.min(
+      document.lineCount - 1,
+      diagnostic.range.end.line + radius,
+    ),
+  };
+}
+
+// `relPath` is relative to the session's workspace folder.
+export function buildFixPrompt(
+  relPath: string,
+  diagnostic: vscode.Diagnostic,
+): string {
+  const start = diagnostic.range.start.line + 1;
+  const end = diagnostic.range.end.line + 1;
+  const where = start === end ? `line ${start}` : `lines ${start}-${end}`;
+  const origin = [
+    severityLabel(diagnostic.severity),
+    diagnostic.source,
+    code(diagnostic),
+  ]
+    .filter(Boolean)
+    .join(" ");
+  const related = (diagnostic.relatedInformation ?? []).map(
+    (info) =>
+      `- ${vscode.workspace.asRelativePath(info.location.uri, false)}:${info.location.range.start.line + 1}: ${info.message}`,
+  );
+  return [
+    `Fix this problem in \`${relPath}\` at ${where} (${origin}):`,
+    "",
+    diagnostic.message.trim(),
+    ...(related.length > 0 ? ["", "Related:", ...related] : []),
+    "",
+    "Make the smallest change that fixes it, then say what you changed.",
+  ].join("\n");
+}
+
+function code(diagnostic: vscode.Diagnostic): string | null {
+  const raw = diagnostic.code;
+  if (raw === undefined || raw === null) return null;
+  if (typeof raw === "object") return String(raw.value);
+  return String(raw);
+}
+
+function severityLabel(severity: vscode.DiagnosticSeverity): string {
+  switch (severity) {
+    case vscode.DiagnosticSeverity.Error:
+      return "error";
+    case vscode.DiagnosticSeverity.Warning:
+      return "warning";
+    case vscode.DiagnosticSeverity.Information:
+      return "info";
+    default:
+      return "hint";
+  }
+}
+
+function firstLine(text: string, max: number): string {
+  const line = text.trim().split("\n")[0] ?? "";
+  return line.length > max ? `${line.slice(0, max - 1)}…` : line;
+}
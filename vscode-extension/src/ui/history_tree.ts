@@ -0,0 +1,322 @@
+import * as vscode from "vscode";
+
+import type { Thread } from "../generated/v2/Thread";
+import type { ThreadSearchResult } from "../generated/v2/ThreadSearchResult";
+
+type Page<T> = { data: T[]; nextCursor: string | null };
+
+export type HistoryLoaders = {
+  list: (
+    folder: vscode.WorkspaceFolder,
+    cursor: string | null,
+  ) => Promise<Page<Thread>>;
+  search: (
+    folder: vscode.WorkspaceFolder,
+    query: string,
+    cursor: string | null,
+  ) => Promise<Page<ThreadSearchResult>>;
+  // The thread with `model` and `diffStat` filled in.
+  stats: (folder: vscode.WorkspaceFolder, threadId: string) => Promise<Thread>;
+};
+
+type FolderState = {
+  threads: HistoryThreadNode[];
+  nextCursor: string | null;
+  loading: Promise<void> | null;
+  loaded: boolean;
+};
+
+type Stats = Pick<Thread, "model" | "diffStat">;
+
+// Past threads of each workspace folder, newest first, from `thread/list`, or
+// from `thread/search` while a query is set. Rows show the date right away;
+// the model and diffstat need a whole-rollout read (`thread/read` with
+// `includeStats`), so they are fetched per row as it is first shown.
+export class HistoryTreeDataProvider
+  implements vscode.TreeDataProvider<HistoryTreeNode>, vscode.Disposable
+{
+  private readonly emitter = new vscode.EventEmitter<HistoryTreeNode | null>();
+  public readonly onDidChangeTreeData = this.emitter.event;
+
+  private readonly stateByFolderUri = new Map<string, FolderState>();
+  // Keyed by thread id and `updatedAt`, so a thread that moved on is re-read.
+  private readonly statsByKey = new Map<string, Stats | "pending">();
+  private query: string | null = null;
+
+  public constructor(
+    private readonly loaders: HistoryLoaders,
+    private readonly onLoadError: (
+      folder: vscode.WorkspaceFolder,
+      err: unknown,
+    ) => void,
+  ) {}
+
+  public dispose(): void {
+    this.emitter.dispose();
+  }
+
+  public refresh(): void {
+    this.stateByFolderUri.clear();
+    this.emitter.fire(null);
+  }
+
+  public get searchQuery(): string | null {
+    return this.query;
+  }
+
+  public setSearchQuery(query: string | null): void {
+    const trimmed = query?.trim() ?? "";
+    this.query = trimmed ? trimmed : null;
+    void vscode.commands.executeCommand(
+      "setContext",
+      "codez.historySearchActive",
+      this.query !== null,
+    );
+    this.refresh();
+  }
+
+  public async loadMore(node: HistoryMoreNode): Promise<void> {
+    await this.fetchPage(node.folder);
+    this.emitter.fire(null);
+  }
+
+  public getTreeItem(element: HistoryTreeNode): vscode.TreeItem {
+    if (element.kind === "folder") {
+      const item = new vscode.TreeItem(
+        element.folder.name,
+        vscode.TreeItemCollapsibleState.Expanded,
+      );
+      item.iconPath = new vscode.ThemeIcon("root-folder");
+      item.description = this.query ? `search: ${this.query}` : undefined;
+      item.contextValue = "codez.historyFolder";
+      return item;
+    }
+
+    if (element.kind === "more") {
+      const item = new vscode.TreeItem(
+        "Load more…",
+        vscode.TreeItemCollapsibleState.None,
+      );
+      item.iconPath = new vscode.ThemeIcon("ellipsis");
+      item.contextValue = "codez.historyMore";
+      item.command = {
+        command: "codez.history.loadMore",
+        title: "Load More",
+        arguments: [element],
+      };
+      return item;
+    }
+
+    if (element.kind === "empty") {
+      const item = new vscode.TreeItem(
+        element.label,
+        vscode.TreeItemCollapsibleState.None,
+      );
+      item.iconPath = new vscode.ThemeIcon("info");
+      item.contextValue = "codez.historyEmpty";
+      return item;
+    }
+
+    const { thread } = element;
+    const title = thread.name ?? threadPreview(thread.preview);
+    const item = new vscode.TreeItem(
+      title,
+      vscode.TreeItemCollapsibleState.None,
+    );
+    const stats = this.statsFor(element);
+    const when = formatWhen(thread.updatedAt || thread.createdAt);
+    item.description = [when, ...statsParts(stats)].join(" · ");
+    item.tooltip = new vscode.MarkdownString(
+      [
+        `**${escapeMarkdown(title)}**`,
+        "",
+        ...(element.snippet
+          ? [`> ${escapeMarkdown(element.snippet)}`, ""]
+          : []),
+        `- created: ${formatWhen(thread.createdAt)}`,
+        `- updated: ${formatWhen(thread.updatedAt)}`,
+        `- model: \`${stats?.model ?? "?"}\``,
+        ...(stats?.diffStat
+          ? [
+              `- changes: ${stats.diffStat.filesChanged} files, +${stats.diffStat.insertions} −${stats.diffStat.deletions}`,
+            ]
+          : []),
+        ...(thread.tags.length > 0
+          ? [`- tags: ${thread.tags.map((t) => `\`${t}\``).join(" ")}`]
+          : []),
+        ...(thread.gitInfo?.branch
+          ? [`- branch: \`${thread.gitInfo.branch}\``]
+          : []),
+        `- id: \`${thread.id}\``,
+      ].join("\n"),
+    );
+    item.iconPath = new vscode.ThemeIcon(
+      thread.forkedFromId ? "repo-forked" : "history",
+    );
+    item.contextValue = "codez.historyThread";
+    item.command = {
+      command: "codez.history.resume",
+      title: "Resume Thread",
+      arguments: [element],
+    };
+    return item;
+  }
+
+  public async getChildren(
+    element?: HistoryTreeNode,
+  ): Promise<HistoryTreeNode[]> {
+    if (!element) {
+      const folders = vscode.workspace.workspaceFolders ?? [];
+      return folders.map((folder) => ({ kind: "folder", folder }));
+    }
+    if (element.kind !== "folder") return [];
+
+    const state = this.stateFor(element.folder);
+    if (!state.loaded) await this.fetchPage(element.folder);
+    const children: HistoryTreeNode[] = [...state.threads];
+    if (state.nextCursor) {
+      children.push({ kind: "more", folder: element.folder });
+    } else if (state.threads.length === 0) {
+      children.push({
+        kind: "empty",
+        label: this.query ? "No matching threads" : "No threads yet",
+      });
+    }
+    return children;
+  }
+
+  private stateFor(folder: vscode.WorkspaceFolder): FolderState {
+    const key = folder.uri.toString();
+    let state = this.stateByFolderUri.get(key);
+    if (!state) {
+      state = { threads: [], nextCursor: null, loading: null, loaded: false };
+      this.stateByFolderUri.set(key, state);
+    }
+    return state;
+  }
+
+  private fetchPage(folder: vscode.WorkspaceFolder): Promise<void> {
+    const state = this.stateFor(folder);
+    if (state.loading) return state.loading;
+    const query = this.query;
+    const cursor = state.loaded ? state.nextCursor : null;
+    state.loading = (async () => {
+      try {
+        const nodes: HistoryThreadNode[] = [];
+        let nextCursor: string | null;
+        if (query) {
+          const page = await this.loaders.search(folder, query, cursor);
+          for (const hit of page.data) {
+            nodes.push(threadNode(folder, hit.thread, hit.snippet));
+          }
+          nextCursor = page.nextCursor;
+        } else {
+          const page = await this.loaders.list(folder, cursor);
+          for (const thread of page.data) {
+            nodes.push(threadNode(folder, thread, null));
+          }
+          nextCursor = page.nextCursor;
+        }
+        // Threads are stored per user, not per workspace; keep this folder's.
+        const wanted = normalizePath(folder.uri.fsPath);
+        state.threads.push(
+          ...nodes.filter((n) => normalizePath(n.thread.cwd) === wanted),
+        );
+        state.nextCursor = nextCursor;
+      } catch (err) {
+        this.onLoadError(folder, err);
+        state.nextCursor = null;
+      } finally {
+        state.loaded = true;
+        state.loading = null;
+      }
+    })();
+    return state.loading;
+  }
+
+  private statsFor(node: HistoryThreadNode): Stats | null {
+    const key = `${node.thread.id}@${node.thread.updatedAt}`;
+    const cached = this.statsByKey.get(key);
+    if (cached === "pending") return null;
+    if (cached) return cached;
+
+    this.statsByKey.set(key, "pending");
+    this.loaders
+      .stats(node.folder, node.thread.id)
+      .then((thread) => {
+        this.statsByKey.set(key, {
+          model: thread.model,
+          diffStat: thread.diffStat,
+        });
+      })
+      .catch(() => {
+        this.statsByKey.set(key, {});
+      })
+      .finally(() => this.emitter.fire(node));
+    return null;
+  }
+}
+
+export type HistoryFolderNode = {
+  kind: "folder";
+  folder: vscode.WorkspaceFolder;
+};
+export type HistoryThreadNode = {
+  kind: "thread";
+  folder: vscode.WorkspaceFolder;
+  thread: Thread;
+  snippet: string | null;
+};
+export type HistoryMoreNode = {
+  kind: "more";
+  folder: vscode.WorkspaceFolder;
+};
+type HistoryEmptyNode = {
+  kind: "empty";
+  label: string;
+};
+export type HistoryTreeNode =
+  | HistoryFolderNode
+  | HistoryThreadNode
+  | HistoryMoreNode
+  | HistoryEmptyNode;
+
+function threadNode(
+  folder: vscode.WorkspaceFolder,
+  thread: Thread,
+  snippet: string | null,
+): HistoryThreadNode {
+  return { kind: "thread", folder, thread, snippet };
+}
+
+function statsParts(stats: Stats | null): string[] {
+  if (!stats) return [];
+  const parts: string[] = [];
+  if (stats.model) parts.push(stats.model);
+  const diff = stats.diffStat;
+  if (diff && diff.filesChanged > 0) {
+    parts.push(`+${diff.insertions} −${diff.deletions}`);
+  }
+  return parts;
+}
+
+function threadPreview(preview: string): string {
+  const line = preview.trim().split("\n")[0] ?? "";
+  if (!line) return "(no preview)";
+  return line.length > 80 ? `${line.slice(0, 79)}…` : line;
+}
+
+function formatWhen(sec: number): string {
+  const d = new Date(Math.max(0, sec) * 1000);
+  const pad2 = (n: number): string => String(n).padStart(2, "0");
+  return `${d.getFullYear()}-${pad2(d.getMonth() + 1)}-${pad2(d.getDate())} ${pad2(d.getHours())}:${pad2(d.getMinutes())}`;
+}
+
+function normalizePath(p: string): string {
+  const trimmed = p.replace(/[\\/]+$/, "");
+  return process.platform === "win32" ? trimmed.toLowerCase() : trimmed;
+}
+
+function escapeMarkdown(text: string): string {
+  return text.replace(/[\\`*_{}[\]()#+\-.!|<>]/g, "\\$&");
+}
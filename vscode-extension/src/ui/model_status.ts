@@ -0,0 +1,136 @@
+import * as vscode from "vscode";
+
+import type { Model } from "../generated/v2/Model";
+import type { ReasoningEffort } from "../generated/ReasoningEffort";
+
+// Status bar item with the active session's model and reasoning effort. A
+// click opens the switcher (`codez.switchModel`). A rate limit or auth
+// failure on the session's model shows as a warning on the item.
+
+export type ModelStatus = {
+  // null: the model from config.toml.
+  model: string | null;
+  effort: string | null;
+  backendId: string;
+  problem: string | null;
+};
+
+export type ModelChoice = {
+  model: string | null;
+  effort: ReasoningEffort | null;
+};
+
+export class ModelStatusBar implements vscode.Disposable {
+  private readonly item = vscode.window.createStatusBarItem(
+    "codez.modelStatus",
+    vscode.StatusBarAlignment.Right,
+    100,
+  );
+  private timer: NodeJS.Timeout | null = null;
+
+  public constructor(private readonly getStatus: () => ModelStatus | null) {
+    this.item.name = "Codex Model";
+    this.item.command = "codez.switchModel";
+    this.update();
+  }
+
+  public dispose(): void {
+    if (this.timer) clearTimeout(this.timer);
+    this.item.dispose();
+  }
+
+  // Coalesces bursts of updates (streaming refreshes the UI often).
+  public scheduleUpdate(): void {
+    if (this.timer) return;
+    this.timer = setTimeout(() => {
+      this.timer = null;
+      this.update();
+    }, 100);
+  }
+
+  private update(): void {
+    const status = this.getStatus();
+    if (!status) {
+      this.item.hide();
+      return;
+    }
+    const icon = status.problem ? "$(warning)" : "$(hubot)";
+    const model = status.model ?? "default model";
+    const effort = status.effort ? ` · ${status.effort}` : "";
+    this.item.text = `${icon} ${model}${effort}`;
+    const tooltip = new vscode.MarkdownString(undefined, true);
+    tooltip.appendMarkdown(
+      `**Model:** ${status.model ?? "default (config)"}  \n`,
+    );
+    tooltip.appendMarkdown(
+      `**Reasoning effort:** ${status.effort ?? "default"}  \n`,
+    );
+    tooltip.appendMarkdown(`**Backend:** ${status.backendId}`);
+    if (status.problem) {
+      tooltip.appendMarkdown(`\n\n$(warning) ${status.problem}`);
+    }
+    tooltip.appendMarkdown("\n\nClick to switch the model or effort.");
+    this.item.tooltip = tooltip;
+    this.item.backgroundColor = status.problem
+      ? new vscode.ThemeColor("statusBarItem.warningBackground")
+      : undefined;
+    this.item.show();
+  }
+}
+
+// Asks for a model, then for one of its reasoning efforts. `problem` marks the
+// model the last failure happened on. Returns null when cancelled.
+export async function pickModelAndEffort(
+  models: Model[],
+  current: { model: string | null; effort: string | null },
+  problem: { model: string | null; text: string } | null,
+): Promise<ModelChoice | null> {
+  type ModelItem = vscode.QuickPickItem & { model: Model | null };
+  const mark = (isCurrent: boolean) => (isCurrent ? "$(check) " : "");
+  const warn = (model: string | null) =>
+    problem && problem.model === model ? ` $(warning) ${problem.text}` : "";
+  const items: ModelItem[] = [
+    {
+      label: `${mark(current.model === null)}Default`,
+      description: `model from config.toml${warn(null)}`,
+      model: null,
+    },
+    ...models.map((m) => ({
+      label: `${mark(current.model === m.model)}${m.displayName}`,
+      description:
+        `${m.model}${m.isDefault ? " (default)" : ""}` + warn(m.model),
+      detail: m.description || undefined,
+      model: m,
+    })),
+  ];
+  const pickedModel = await vscode.window.showQuickPick(items, {
+    title: "Codex UI: Switch Model",
+    placeHolder: "Model for the next turns of this session",
+    matchOnDescription: true,
+  });
+  if (!pickedModel) return null;
+  const model = pickedModel.model;
+  if (!model || model.supportedReasoningEfforts.length === 0) {
+    return { model: model?.model ?? null, effort: null };
+  }
+
+  type EffortItem = vscode.QuickPickItem & { effort: ReasoningEffort | null };
+  const currentEffort = current.model === model.model ? current.effort : null;
+  const effortItems: EffortItem[] = [
+    {
+      label: `${mark(currentEffort === null)}Default`,
+      description: model.defaultReasoningEffort,
+      effort: null,
+    },
+    ...model.supportedReasoningEfforts.map((o) => ({
+      label: mark(currentEffort === o.reasoningEffort) + o.reasoningEffort,
+      description: o.description,
+      effort: o.reasoningEffort,
+    })),
+  ];
+  const pickedEffort = await vscode.window.showQuickPick(effortItems, {
+    title: `Codex UI: Reasoning Effort (${model.displayName})`,
+  });
+  if (!pickedEffort) return null;
+  return { model: model.model, effort: pickedEffort.effort };
+}
@@ -0,0 +1,80 @@
+import * as vscode from "vscode";
+
+import type { Session } from "../sessions";
+
+// Opt-in ghost text (`codez.nextEdit.enabled`): the active session's thread is
+// asked, through a fast model (`codez.nextEdit.model`), what the user is about
+// to type at the cursor. Tab accepts and Escape dismisses, as for any inline
+// completion.
+
+const DEBOUNCE_MS = 300;
+const MAX_PREFIX_CHARS = 8_000;
+const MAX_SUFFIX_CHARS = 3_000;
+
+export type NextEditRequest = {
+  path: string;
+  prefix: string;
+  suffix: string;
+  model: string | null;
+};
+
+export function nextEditEnabled(): boolean {
+  return vscode.workspace
+    .getConfiguration("codez")
+    .get<boolean>("nextEdit.enabled", false);
+}
+
+export class NextEditProvider implements vscode.InlineCompletionItemProvider {
+  public constructor(
+    // Session whose recent messages inform suggestions for `document`.
+    private readonly sessionFor: (
+      document: vscode.TextDocument,
+    ) => Session | null,
+    private readonly suggest: (
+      session: Session,
+      request: NextEditRequest,
+    ) => Promise<string | null>,
+    private readonly output: vscode.OutputChannel,
+  ) {}
+
+  public async provideInlineCompletionItems(
+    document: vscode.TextDocument,
+    position: vscode.Position,
+    _context: vscode.InlineCompletionContext,
+    token: vscode.CancellationToken,
+  ): Promise<vscode.InlineCompletionItem[] | null> {
+    if (!nextEditEnabled() || document.uri.scheme !== "file") return null;
+    const session = this.sessionFor(document);
+    if (!session || session.backendId !== "codez") return null;
+
+    // Typing cancels the token; only ask once the user pauses.
+    await new Promise((resolve) => setTimeout(resolve, DEBOUNCE_MS));
+    if (token.isCancellationRequested) return null;
+
+    const offset = document.offsetAt(position);
+    const text = document.getText();
+    const model = vscode.workspace
+      .getConfiguration("codez", document.uri)
+      .get<string>("nextEdit.model", "")
+      .trim();
+    let suggestion: string | null;
+    try {
+      suggestion = await this.suggest(session, {
+        path: document.uri.fsPath,
+        prefix: text.slice(Math.max(0, offset - MAX_PREFIX_CHARS), offset),
+        suffix: text.slice(offset, offset + MAX_SUFFIX_CHARS),
+        model: model || null,
+      });
+    } catch (err) {
+      this.output.appendLine(`[nextEdit] ${String(err)}`);
+      return null;
+    }
+    if (!suggestion || token.isCancellationRequested) return null;
+    return [
+      new vscode.InlineCompletionItem(
+        suggestion,
+        new vscode.Range(position, position),
+      ),
+    ];
+  }
+}
@@ -0,0 +1,177 @@
+import { execFile } from "node:child_process";
+import * as path from "node:path";
+import * as vscode from "vscode";
+
+// Source Control view actions backed by `codez commit`: a suggested
+// conventional-commit message for the staged changes (`--suggest`), written
+// into the commit input box, and a review of the staged changes
+// (`--review --json`) whose findings are shown as comments on the files.
+
+export type StagedReviewFinding = {
+  path: string;
+  line: number | null;
+  message: string;
+};
+
+export type StagedReviewVerdict = {
+  block: boolean;
+  findings: StagedReviewFinding[];
+};
+
+// The parts of the built-in Git extension's API used here.
+type GitRepository = {
+  rootUri: vscode.Uri;
+  inputBox: { value: string };
+};
+type GitApi = {
+  repositories: GitRepository[];
+  getRepository(uri: vscode.Uri): GitRepository | null;
+};
+
+// Headless runs can take a while on large diffs.
+const RUN_TIMEOUT_MS = 5 * 60_000;
+
+export class StagedReviewComments implements vscode.Disposable {
+  private readonly controller = vscode.comments.createCommentController(
+    "codez.stagedReview",
+    "Codex review",
+  );
+  private threads: vscode.CommentThread[] = [];
+
+  public dispose(): void {
+    this.clear();
+    this.controller.dispose();
+  }
+
+  public clear(): void {
+    for (const thread of this.threads) thread.dispose();
+    this.threads = [];
+  }
+
+  // Replaces the shown findings with those of `verdict`.
+  public show(root: vscode.Uri, verdict: StagedReviewVerdict): void {
+    this.clear();
+    for (const finding of verdict.findings) {
+      const uri = vscode.Uri.joinPath(root, finding.path);
+      const line = Math.max(0, (finding.line ?? 1) - 1);
+      const comment: vscode.Comment = {
+        body: new vscode.MarkdownString(finding.message),
+        mode: vscode.CommentMode.Preview,
+        author: {
+          name: verdict.block ? "Codex review (blocking)" : "Codex review",
+        },
+      };
+      const thread = this.controller.createCommentThread(
+        uri,
+        new vscode.Range(line, 0, line, 0),
+        [comment],
+      );
+      thread.label = finding.line === null ? finding.path : undefined;
+      thread.canReply = false;
+      thread.collapsibleState = vscode.CommentThreadCollapsibleState.Expanded;
+      this.threads.push(thread);
+    }
+  }
+}
+
+// The repository of the Source Control view the action ran in, or of the
+// active editor (or the first repository) when run from the palette.
+export async function resolveRepository(
+  arg: unknown,
+): Promise<GitRepository | null> {
+  const api = await gitApi();
+  if (!api) return null;
+  const rootUri =
+    arg && typeof arg === "object" && "rootUri" in arg
+      ? (arg as { rootUri?: unknown }).rootUri
+      : undefined;
+  if (rootUri instanceof vscode.Uri) {
+    return api.getRepository(rootUri);
+  }
+  const active = vscode.window.activeTextEditor?.document.uri;
+  const fromEditor = active ? api.getRepository(active) : null;
+  return fromEditor ?? api.repositories[0] ?? null;
+}
+
+// The codez CLI configured for the backend of `uri`'s folder.
+export function codezCommand(uri: vscode.Uri): string {
+  const cfg = vscode.workspace.getConfiguration("codez", uri);
+  return (
+    cfg.get<string>("cli.commands.codez") ??
+    cfg.get<string>("cli.commands.mine") ??
+    "codez"
+  );
+}
+
+export function setCommitMessage(repo: GitRepository, message: string): void {
+  repo.inputBox.value = message;
+}
+
+export async function suggestCommitMessage(
+  command: string,
+  root: vscode.Uri,
+): Promise<string> {
+  const { stdout } = await runCommit(command, root, ["--suggest"]);
+  const message = stdout.trim();
+  if (!message) throw new Error("codez commit --suggest printed nothing.");
+  return message;
+}
+
+export async function reviewStagedChanges(
+  command: string,
+  root: vscode.Uri,
+): Promise<StagedReviewVerdict> {
+  // Exit status 1 means a blocking finding; the verdict is still printed.
+  const { stdout } = await runCommit(
+    command,
+    root,
+    ["--review", "--json"],
+    [1],
+  );
+  const parsed = JSON.parse(stdout) as StagedReviewVerdict;
+  if (!Array.isArray(parsed.findings)) {
+    throw new Error("Unexpected review output.");
+  }
+  return parsed;
+}
+
+function runCommit(
+  command: string,
+  root: vscode.Uri,
+  args: string[],
+  okExitCodes: number[] = [],
+): Promise<{ stdout: string }> {
+  return new Promise((resolve, reject) => {
+    execFile(
+      command,
+      ["commit", ...args],
+      {
+        cwd: root.fsPath,
+        timeout: RUN_TIMEOUT_MS,
+        maxBuffer: 16 * 1024 * 1024,
+      },
+      (err, stdout, stderr) => {
+        const code = (err as { code?: unknown } | null)?.code;
+        if (err && !(typeof code === "number" && okExitCodes.includes(code))) {
+          const detail = String(stderr).trim() || err.message;
+          reject(
+            new Error(
+              `${path.basename(command)} commit ${args.join(" ")} failed: ${detail}`,
+            ),
+          );
+          return;
+        }
+        resolve({ stdout: String(stdout) });
+      },
+    );
+  });
+}
+
+async function gitApi(): Promise<GitApi | null> {
+  const ext = vscode.extensions.getExtension<{
+    getAPI(version: 1): GitApi;
+  }>("vscode.git");
+  if (!ext) return null;
+  const exports = ext.isActive ? ext.exports : await ext.activate();
+  return exports.getAPI(1);
+}
@@ -67,6 +67,14 @@ export class SessionTreeDataProvider
     item.iconPath = iconForColorIndex(this.extensionUri, idx);
     // Show full thread id in description for copyability; omit short id in label.
     item.description = element.session.threadId;
+    const { workspaceFolderUri, profile } = element.session;
+    item.tooltip = [
+      label,
+      `Folder: ${vscode.Uri.parse(workspaceFolderUri).fsPath}`,
+      profile ? `Profile: ${profile}` : null,
+    ]
+      .filter(Boolean)
+      .join("\n");
     item.contextValue = "codez.session";
     item.command = {
       command: "codez.openSession",
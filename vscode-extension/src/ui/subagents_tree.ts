@@ -0,0 +1,215 @@
+import * as vscode from "vscode";
+
+import type { SubAgentMetadata } from "../generated/v2/SubAgentMetadata";
+import type { SubAgentRoot } from "../generated/v2/SubAgentRoot";
+import type { SubAgentsListEntry } from "../generated/v2/SubAgentsListEntry";
+
+export class SubAgentsTreeDataProvider
+  implements vscode.TreeDataProvider<SubAgentTreeNode>, vscode.Disposable
+{
+  private readonly emitter =
+    new vscode.EventEmitter<SubAgentTreeNode | null>();
+  public readonly onDidChangeTreeData = this.emitter.event;
+
+  private readonly entriesByFolderUri = new Map<
+    string,
+    Promise<SubAgentsListEntry | null>
+  >();
+
+  public constructor(
+    private readonly listSubAgents: (
+      folder: vscode.WorkspaceFolder,
+    ) => Promise<SubAgentsListEntry | null>,
+    private readonly onLoadError: (
+      folder: vscode.WorkspaceFolder,
+      err: unknown,
+    ) => void,
+  ) {}
+
+  public dispose(): void {
+    this.emitter.dispose();
+  }
+
+  public refresh(): void {
+    this.entriesByFolderUri.clear();
+    this.emitter.fire(null);
+  }
+
+  public async rootsForFolder(
+    folder: vscode.WorkspaceFolder,
+  ): Promise<SubAgentRoot[]> {
+    const entry = await this.entryForFolder(folder);
+    return entry?.roots ?? [];
+  }
+
+  public getTreeItem(element: SubAgentTreeNode): vscode.TreeItem {
+    if (element.kind === "folder") {
+      const item = new vscode.TreeItem(
+        element.folder.name,
+        vscode.TreeItemCollapsibleState.Expanded,
+      );
+      item.iconPath = new vscode.ThemeIcon("root-folder");
+      item.contextValue = "codez.subagentFolder";
+      return item;
+    }
+
+    if (element.kind === "root") {
+      const item = new vscode.TreeItem(
+        subagentRootLabel(element.root),
+        vscode.TreeItemCollapsibleState.Expanded,
+      );
+      item.description = element.root.path;
+      item.tooltip = element.root.path;
+      item.iconPath = new vscode.ThemeIcon(ROOT_ICONS[element.root.source]);
+      item.contextValue = "codez.subagentRoot";
+      return item;
+    }
+
+    if (element.kind === "error") {
+      const item = new vscode.TreeItem(
+        element.label,
+        vscode.TreeItemCollapsibleState.None,
+      );
+      item.description = element.description;
+      item.tooltip = element.tooltip;
+      item.iconPath = new vscode.ThemeIcon("warning");
+      item.contextValue = "codez.subagentError";
+      return item;
+    }
+
+    const { subagent } = element;
+    const item = new vscode.TreeItem(
+      subagent.name,
+      vscode.TreeItemCollapsibleState.None,
+    );
+    item.description = `[${subagent.sandboxMode ?? "inherit"}] ${subagent.description}`;
+    item.tooltip = new vscode.MarkdownString(
+      [
+        `**@${subagent.name}**`,
+        "",
+        subagent.description,
+        "",
+        `- sandbox: \`${subagent.sandboxMode ?? "inherit"}\``,
+        `- model: \`${subagent.model ?? "inherit"}\``,
+        `- path: \`${subagent.path}\``,
+      ].join("\n"),
+    );
+    item.iconPath = new vscode.ThemeIcon("hubot");
+    item.contextValue = "codez.subagent";
+    item.resourceUri = vscode.Uri.file(subagent.path);
+    item.command = {
+      command: "codez.subagents.open",
+      title: "Open Subagent Definition",
+      arguments: [element],
+    };
+    return item;
+  }
+
+  public async getChildren(
+    element?: SubAgentTreeNode,
+  ): Promise<SubAgentTreeNode[]> {
+    if (!element) {
+      const folders = vscode.workspace.workspaceFolders ?? [];
+      return folders.map((folder) => ({ kind: "folder", folder }));
+    }
+
+    if (element.kind === "folder") {
+      const entry = await this.entryForFolder(element.folder);
+      if (!entry) return [];
+      const roots: SubAgentTreeNode[] = entry.roots.map((root) => ({
+        kind: "root",
+        folder: element.folder,
+        root,
+      }));
+      const errors: SubAgentTreeNode[] = entry.errors.map((err) => ({
+        kind: "error",
+        label: baseName(err.path),
+        description: err.message,
+        tooltip: `${err.path}\n${err.message}`,
+      }));
+      return [...roots, ...errors];
+    }
+
+    if (element.kind === "root") {
+      const entry = await this.entryForFolder(element.folder);
+      return (entry?.subagents ?? [])
+        .filter((s) => isUnderRoot(s, element.root))
+        .map((subagent) => ({
+          kind: "subagent",
+          folder: element.folder,
+          subagent,
+        }));
+    }
+
+    return [];
+  }
+
+  private entryForFolder(
+    folder: vscode.WorkspaceFolder,
+  ): Promise<SubAgentsListEntry | null> {
+    const key = folder.uri.toString();
+    const cached = this.entriesByFolderUri.get(key);
+    if (cached) return cached;
+
+    const pending = this.listSubAgents(folder).catch((err: unknown) => {
+      this.onLoadError(folder, err);
+      return null;
+    });
+    this.entriesByFolderUri.set(key, pending);
+    return pending;
+  }
+}
+
+export type SubAgentFolderNode = {
+  kind: "folder";
+  folder: vscode.WorkspaceFolder;
+};
+export type SubAgentRootNode = {
+  kind: "root";
+  folder: vscode.WorkspaceFolder;
+  root: SubAgentRoot;
+};
+export type SubAgentNode = {
+  kind: "subagent";
+  folder: vscode.WorkspaceFolder;
+  subagent: SubAgentMetadata;
+};
+type SubAgentErrorNode = {
+  kind: "error";
+  label: string;
+  description: string;
+  tooltip: string;
+};
+export type SubAgentTreeNode =
+  | SubAgentFolderNode
+  | SubAgentRootNode
+  | SubAgentNode
+  | SubAgentErrorNode;
+
+export function subagentRootLabel(root: SubAgentRoot): string {
+  switch (root.source) {
+    case "repo":
+      return "Repo";
+    case "user":
+      return "Home";
+    case "extra":
+      return "Shared";
+  }
+}
+
+const ROOT_ICONS: Record<SubAgentRoot["source"], string> = {
+  repo: "repo",
+  user: "home",
+  extra: "organization",
+};
+
+function isUnderRoot(subagent: SubAgentMetadata, root: SubAgentRoot): boolean {
+  if (subagent.source !== root.source) return false;
+  const dir = subagent.path.replace(/[\\/][^\\/]*$/, "");
+  return dir === root.path.replace(/[\\/]+$/, "");
+}
+
+function baseName(p: string): string {
+  const parts = p.split(/[\\/]/);
+  return parts[parts.length - 1] || p;
+}
@@ -0,0 +1,131 @@
+import * as vscode from "vscode";
+
+// Output of the integrated terminals, for "Send Terminal Output to Codex".
+// With shell integration each command's output is recorded while it runs, so
+// the last command can be sent with its command line and exit code. The API
+// arrived in VS Code 1.93 and this extension targets 1.90, so it is detected
+// at runtime. Without it, the last lines are read from the terminal's buffer
+// through select all / copy, with the clipboard restored afterwards.
+
+// Shapes of the shell integration API used here (VS Code 1.93+).
+type ShellExecution = {
+  commandLine?: { value: string };
+  read(): AsyncIterable<string>;
+};
+type ShellExecutionEvent = {
+  terminal: vscode.Terminal;
+  execution: ShellExecution;
+  exitCode?: number;
+};
+
+export type CapturedCommand = {
+  commandLine: string | null;
+  output: string;
+  exitCode: number | null;
+  running: boolean;
+};
+
+type Recorded = CapturedCommand & { execution: ShellExecution };
+
+// Output kept per command; older output is dropped from the front.
+const MAX_RECORDED_CHARS = 512 * 1024;
+
+export class TerminalCapture implements vscode.Disposable {
+  private readonly lastByTerminal = new Map<vscode.Terminal, Recorded>();
+  private readonly disposables: vscode.Disposable[] = [];
+
+  public constructor() {
+    const window = vscode.window as unknown as {
+      onDidStartTerminalShellExecution?: vscode.Event<ShellExecutionEvent>;
+      onDidEndTerminalShellExecution?: vscode.Event<ShellExecutionEvent>;
+    };
+    if (window.onDidStartTerminalShellExecution) {
+      this.disposables.push(
+        window.onDidStartTerminalShellExecution((e) => void this.record(e)),
+      );
+    }
+    if (window.onDidEndTerminalShellExecution) {
+      this.disposables.push(
+        window.onDidEndTerminalShellExecution((e) => {
+          const recorded = this.lastByTerminal.get(e.terminal);
+          if (!recorded || recorded.execution !== e.execution) return;
+          recorded.exitCode = e.exitCode ?? null;
+          recorded.running = false;
+        }),
+      );
+    }
+    this.disposables.push(
+      vscode.window.onDidCloseTerminal((t) => this.lastByTerminal.delete(t)),
+    );
+  }
+
+  public dispose(): void {
+    for (const d of this.disposables) d.dispose();
+    this.lastByTerminal.clear();
+  }
+
+  // The terminal's latest command, when shell integration reported one.
+  public lastCommand(terminal: vscode.Terminal): CapturedCommand | null {
+    const recorded = this.lastByTerminal.get(terminal);
+    if (!recorded) return null;
+    const { commandLine, output, exitCode, running } = recorded;
+    return { commandLine, output: cleanOutput(output), exitCode, running };
+  }
+
+  // The last `count` lines of the terminal's buffer.
+  public async lastLines(
+    terminal: vscode.Terminal,
+    count: number,
+  ): Promise<string> {
+    const saved = await vscode.env.clipboard.readText();
+    try {
+      terminal.show(true);
+      await vscode.commands.executeCommand(
+        "workbench.action.terminal.selectAll",
+      );
+      await vscode.commands.executeCommand(
+        "workbench.action.terminal.copySelection",
+      );
+      await vscode.commands.executeCommand(
+        "workbench.action.terminal.clearSelection",
+      );
+      const text = await vscode.env.clipboard.readText();
+      const lines = cleanOutput(text).split("\n");
+      return lines.slice(-Math.max(1, count)).join("\n");
+    } finally {
+      await vscode.env.clipboard.writeText(saved);
+    }
+  }
+
+  private async record(e: ShellExecutionEvent): Promise<void> {
+    const recorded: Recorded = {
+      execution: e.execution,
+      commandLine: e.execution.commandLine?.value.trim() || null,
+      output: "",
+      exitCode: null,
+      running: true,
+    };
+    this.lastByTerminal.set(e.terminal, recorded);
+    try {
+      for await (const chunk of e.execution.read()) {
+        recorded.output += chunk;
+        if (recorded.output.length > MAX_RECORDED_CHARS) {
+          recorded.output = recorded.output.slice(-MAX_RECORDED_CHARS);
+        }
+      }
+    } catch {
+      // The stream ends with an error when the terminal is disposed.
+    }
+  }
+}
+
+// Drops escape sequences and carriage returns, and trailing blank lines.
+export function cleanOutput(raw: string): string {
+  return raw
+    .replace(/\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)/g, "")
+    .replace(/\x1b\[[0-?]*[ -/]*[@-~]/g, "")
+    .replace(/\x1b[@-_]/g, "")
+    .replace(/\r\n/g, "\n")
+    .replace(/[^\n]*\r/g, "")
+    .replace(/\s+$/, "");
+}